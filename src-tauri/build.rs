@@ -1,3 +1,7 @@
 fn main() {
+    // tauri-build 负责声明 `mobile` cfg；不带 desktop 特性时不运行它，需要自行声明
+    println!("cargo:rustc-check-cfg=cfg(mobile)");
+    // 不带 desktop 特性时不依赖 Tauri，无需生成应用上下文
+    #[cfg(feature = "desktop")]
     tauri_build::build()
 }
@@ -0,0 +1,205 @@
+//! `abv-cli` - command line front-end for server deployments.
+//! Reuses `modules::*` directly and never starts the Tauri runtime.
+
+use crate::models::Account;
+use crate::modules;
+use crate::modules::account_service::AccountService;
+use crate::modules::integration::SystemManager;
+
+const USAGE: &str = "\
+Usage: abv-cli <command> [args]
+
+Commands:
+  accounts list [--json]          List all accounts
+  accounts add <refresh_token>    Add (or update) an account from a refresh token
+  accounts delete <email|id> [--yes]
+                                  Delete an account (--yes confirms domain-policy protected accounts)
+  accounts export [file]          Export refresh tokens as JSON (stdout if no file)
+  quota refresh [<email|id>...]   Refresh quotas of the given accounts (all if none given)
+  proxy run                       Run the proxy service in the foreground (same as --headless)
+  service install [options]       Install the proxy as a systemd unit / Windows service and start it
+  service uninstall [--system]    Stop and remove the service
+  service run [--data-dir <dir>]  Service entry point (started by systemd / the Service Control Manager)
+  switch <email|id>               Switch the active Antigravity account
+  wipe --confirm <phrase>         Securely delete all accounts, tokens, config, logs and stats
+                                  (phrase: WIPE ALL DATA; close the desktop app first)
+  help                            Show this message
+
+Service options:
+  --system                        Linux: install a system-wide unit instead of a user unit
+  --data-dir <dir>                Account data directory used by the service (default: current one)";
+
+/// Entry point used by the `abv-cli` binary. Returns the process exit code.
+pub fn run(args: Vec<String>) -> i32 {
+    let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+
+    match args.as_slice() {
+        [] | ["help"] | ["--help"] | ["-h"] => {
+            println!("{}", USAGE);
+            0
+        }
+        ["proxy", "run"] => {
+            modules::logger::init_logger();
+            crate::init_databases();
+            crate::run_headless();
+            0
+        }
+        ["wipe", "--confirm", phrase] => match modules::wipe::wipe_all_data(phrase) {
+            Ok(report) => {
+                println!(
+                    "Wiped {} files ({} bytes overwritten)",
+                    report.files_removed, report.bytes_overwritten
+                );
+                for path in &report.pending {
+                    println!("  - still in use, removed on next start: {}", path);
+                }
+                0
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                1
+            }
+        },
+        ["service", action @ ("install" | "uninstall" | "run"), rest @ ..] => {
+            let result = crate::service::ServiceOptions::parse(rest).and_then(|opts| match *action {
+                "install" => crate::service::install(&opts),
+                "uninstall" => crate::service::uninstall(&opts),
+                _ => crate::service::run(&opts),
+            });
+            match result {
+                Ok(()) => 0,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    1
+                }
+            }
+        }
+        _ => {
+            let rt = match tokio::runtime::Runtime::new() {
+                Ok(rt) => rt,
+                Err(e) => {
+                    eprintln!("Failed to create Tokio runtime: {}", e);
+                    return 1;
+                }
+            };
+            match rt.block_on(dispatch(&args)) {
+                Ok(()) => 0,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    1
+                }
+            }
+        }
+    }
+}
+
+async fn dispatch(args: &[&str]) -> Result<(), String> {
+    let service = AccountService::new(SystemManager::Headless);
+
+    match args {
+        ["accounts", "list"] => print_accounts(&service.list_accounts()?, false),
+        ["accounts", "list", "--json"] => print_accounts(&service.list_accounts()?, true),
+        ["accounts", "add", refresh_token] => {
+            let account = service.add_account(refresh_token).await?;
+            println!("Added {} ({})", account.email, account.id);
+            Ok(())
+        }
+        ["accounts", "delete", target] | ["accounts", "delete", target, "--yes"] => {
+            let account_id = resolve_account_id(target)?;
+            let confirmed = args.last() == Some(&"--yes");
+            service.delete_account(&account_id, confirmed)?;
+            println!("Deleted {}", target);
+            Ok(())
+        }
+        ["accounts", "export"] => {
+            println!("{}", export_all_accounts()?);
+            Ok(())
+        }
+        ["accounts", "export", path] => {
+            std::fs::write(path, export_all_accounts()?)
+                .map_err(|e| format!("failed_to_write_export: {}", e))?;
+            println!("Exported accounts to {}", path);
+            Ok(())
+        }
+        ["quota", "refresh", targets @ ..] => {
+            let stats = if targets.is_empty() {
+                modules::account::refresh_all_quotas_logic().await?
+            } else {
+                let ids = targets
+                    .iter()
+                    .map(|t| resolve_account_id(t))
+                    .collect::<Result<Vec<_>, _>>()?;
+                modules::account::refresh_quota_for_accounts(&ids, None).await?
+            };
+            println!(
+                "Refreshed {} accounts: {} success, {} failed",
+                stats.total, stats.success, stats.failed
+            );
+            for detail in &stats.details {
+                println!("  - {}", detail);
+            }
+            if stats.failed > 0 {
+                return Err(format!("{} accounts failed to refresh", stats.failed));
+            }
+            Ok(())
+        }
+        ["switch", target] => {
+            let account_id = resolve_account_id(target)?;
+            service.switch_account(&account_id).await?;
+            println!("Switched to {}", target);
+            Ok(())
+        }
+        _ => Err(format!("unknown command: {}\n\n{}", args.join(" "), USAGE)),
+    }
+}
+
+/// Accept either an account ID or an email address
+fn resolve_account_id(target: &str) -> Result<String, String> {
+    if let Some(id) = modules::account::find_account_id_by_email(target) {
+        return Ok(id);
+    }
+    let accounts = modules::list_accounts()?;
+    accounts
+        .into_iter()
+        .find(|a| a.id == target)
+        .map(|a| a.id)
+        .ok_or_else(|| format!("account_not_found: {}", target))
+}
+
+fn export_all_accounts() -> Result<String, String> {
+    let ids: Vec<String> = modules::list_accounts()?.into_iter().map(|a| a.id).collect();
+    let export = modules::account::export_accounts_by_ids(&ids)?;
+    serde_json::to_string_pretty(&export).map_err(|e| format!("failed_to_serialize: {}", e))
+}
+
+fn print_accounts(accounts: &[Account], json: bool) -> Result<(), String> {
+    if json {
+        let out = serde_json::to_string_pretty(accounts)
+            .map_err(|e| format!("failed_to_serialize: {}", e))?;
+        println!("{}", out);
+        return Ok(());
+    }
+
+    let current = modules::get_current_account_id().ok().flatten();
+    for account in accounts {
+        let marker = if current.as_deref() == Some(account.id.as_str()) { "*" } else { " " };
+        let status = if account.disabled {
+            "disabled"
+        } else if account.proxy_disabled {
+            "proxy-off"
+        } else {
+            "active"
+        };
+        let min_quota = account
+            .quota
+            .as_ref()
+            .and_then(|q| q.models.iter().map(|m| m.percentage).min())
+            .map(|p| format!("{}%", p))
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "{} {:<36}  {:<40}  {:<9}  {}",
+            marker, account.id, account.email, status, min_quota
+        );
+    }
+    Ok(())
+}
@@ -1,3 +1,4 @@
+#[cfg(feature = "desktop")]
 use tauri::State;
 use crate::modules::cloudflared::{CloudflaredConfig, CloudflaredManager, CloudflaredStatus};
 use std::sync::Arc;
@@ -28,6 +29,7 @@ impl CloudflaredState {
 }
 
 /// 检查cloudflared是否已安装
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn cloudflared_check(
     state: State<'_, CloudflaredState>,
@@ -50,6 +52,7 @@ pub async fn cloudflared_check(
 }
 
 /// 安装cloudflared
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn cloudflared_install(
     state: State<'_, CloudflaredState>,
@@ -65,6 +68,7 @@ pub async fn cloudflared_install(
 }
 
 /// 启动cloudflared隧道
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn cloudflared_start(
     state: State<'_, CloudflaredState>,
@@ -81,6 +85,7 @@ pub async fn cloudflared_start(
 }
 
 /// 停止cloudflared隧道
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn cloudflared_stop(
     state: State<'_, CloudflaredState>,
@@ -96,6 +101,7 @@ pub async fn cloudflared_stop(
 }
 
 /// 获取cloudflared状态
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn cloudflared_get_status(
     state: State<'_, CloudflaredState>,
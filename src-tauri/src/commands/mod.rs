@@ -1,11 +1,12 @@
 use crate::models::{Account, AppConfig, QuotaData};
 use crate::modules;
-use tauri::{Emitter, Manager};
-use tauri_plugin_opener::OpenerExt;
+#[cfg(feature = "desktop")]
+use tauri::Manager;
 
 // 导出 proxy 命令
 pub mod proxy;
 // 导出 autostart 命令
+#[cfg(feature = "desktop")]
 pub mod autostart;
 // 导出 cloudflared 命令
 pub mod cloudflared;
@@ -17,12 +18,31 @@ pub mod proxy_pool;
 pub mod user_token;
 
 /// 列出所有账号
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn list_accounts() -> Result<Vec<Account>, String> {
     modules::list_accounts()
 }
 
+/// 列出账号摘要 (后端排序 / 筛选，不含 Token 等完整数据)
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn list_account_summaries(
+    sort_by: Option<crate::models::AccountSortBy>,
+    order: Option<crate::models::SortOrder>,
+    filter: Option<crate::models::AccountListFilter>,
+) -> Result<Vec<crate::models::AccountListItem>, String> {
+    tokio::task::spawn_blocking(move || {
+        modules::account::list_account_summaries(
+            &sort_by.unwrap_or_default(),
+            order.unwrap_or_default(),
+            &filter.unwrap_or_default(),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
 /// 添加账号
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn add_account(
     app: tauri::AppHandle,
@@ -47,18 +67,52 @@ pub async fn add_account(
     Ok(account)
 }
 
+/// 生成单个账号的加密分享文本 (可做成二维码)，ttl 为有效秒数
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn create_account_share(
+    account_id: String,
+    passphrase: String,
+    ttl: Option<u64>,
+) -> Result<crate::modules::account_share::AccountShare, String> {
+    crate::modules::account_share::create_share(&account_id, &passphrase, ttl)
+}
+
+/// 兑换账号分享文本并添加账号
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub async fn redeem_account_share(
+    app: tauri::AppHandle,
+    blob: String,
+    passphrase: String,
+) -> Result<Account, String> {
+    let service = modules::account_service::AccountService::new(
+        crate::modules::integration::SystemManager::Desktop(app.clone()),
+    );
+    let account =
+        crate::modules::account_share::redeem_share(&service, &blob, &passphrase).await?;
+
+    let _ = crate::commands::proxy::reload_proxy_accounts(
+        app.state::<crate::commands::proxy::ProxyServiceState>(),
+    )
+    .await;
+
+    Ok(account)
+}
+
 /// 删除账号
 /// 删除账号
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn delete_account(
     app: tauri::AppHandle,
     proxy_state: tauri::State<'_, crate::commands::proxy::ProxyServiceState>,
     account_id: String,
+    confirmed: Option<bool>,
 ) -> Result<(), String> {
     let service = modules::account_service::AccountService::new(
         crate::modules::integration::SystemManager::Desktop(app.clone()),
     );
-    service.delete_account(&account_id)?;
+    service.delete_account(&account_id, confirmed.unwrap_or(false))?;
 
     // Reload token pool
     let _ = crate::commands::proxy::reload_proxy_accounts(proxy_state).await;
@@ -67,17 +121,19 @@ pub async fn delete_account(
 }
 
 /// 批量删除账号
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn delete_accounts(
     app: tauri::AppHandle,
     proxy_state: tauri::State<'_, crate::commands::proxy::ProxyServiceState>,
     account_ids: Vec<String>,
+    confirmed: Option<bool>,
 ) -> Result<(), String> {
     modules::logger::log_info(&format!(
         "收到批量删除请求，共 {} 个账号",
         account_ids.len()
     ));
-    modules::account::delete_accounts(&account_ids).map_err(|e| {
+    modules::account::delete_accounts(&account_ids, confirmed.unwrap_or(false)).map_err(|e| {
         modules::logger::log_error(&format!("批量删除失败: {}", e));
         e
     })?;
@@ -93,6 +149,7 @@ pub async fn delete_accounts(
 
 /// 重新排序账号列表
 /// 根据传入的账号ID数组顺序更新账号排列
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn reorder_accounts(
     proxy_state: tauri::State<'_, crate::commands::proxy::ProxyServiceState>,
@@ -113,17 +170,18 @@ pub async fn reorder_accounts(
 }
 
 /// 切换账号
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn switch_account(
     app: tauri::AppHandle,
     proxy_state: tauri::State<'_, crate::commands::proxy::ProxyServiceState>,
     account_id: String,
-) -> Result<(), String> {
+) -> Result<crate::models::SwitchReport, String> {
     let service = modules::account_service::AccountService::new(
         crate::modules::integration::SystemManager::Desktop(app.clone()),
     );
 
-    service.switch_account(&account_id).await?;
+    let report = service.switch_account(&account_id).await?;
 
     // 同步托盘
     crate::modules::tray::update_tray_menus(&app);
@@ -131,11 +189,42 @@ pub async fn switch_account(
     // [FIX #820] Notify proxy to clear stale session bindings and reload accounts
     let _ = crate::commands::proxy::reload_proxy_accounts(proxy_state).await;
 
-    Ok(())
+    Ok(report)
 }
 
-/// 获取当前账号
+/// 切回上一个账号 (切换历史中最近一次切换前所在的账号)
+#[cfg(feature = "desktop")]
 #[tauri::command]
+pub async fn switch_back(
+    app: tauri::AppHandle,
+    proxy_state: tauri::State<'_, crate::commands::proxy::ProxyServiceState>,
+) -> Result<String, String> {
+    let service = modules::account_service::AccountService::new(
+        crate::modules::integration::SystemManager::Desktop(app.clone()),
+    );
+
+    let account_id = service.switch_back().await?;
+
+    crate::modules::tray::update_tray_menus(&app);
+    let _ = crate::commands::proxy::reload_proxy_accounts(proxy_state).await;
+
+    Ok(account_id)
+}
+
+/// 最近的账号切换记录 (最新在前)
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn get_switch_history() -> Result<Vec<crate::models::SwitchHistoryEntry>, String> {
+    modules::account::get_switch_history()
+}
+
+/// 最近一次账号切换的分阶段结果 (失败时可查看失败阶段与是否已回滚)
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn get_last_switch_report() -> Result<Option<crate::models::SwitchReport>, String> {
+    Ok(modules::account::last_switch_report())
+}
+
+/// 获取当前账号
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn get_current_account() -> Result<Option<Account>, String> {
     // println!("🚀 Backend Command: get_current_account called"); // Commented out to reduce noise for frequent calls, relies on frontend log for frequency
     // Actually user WANTS to see it.
@@ -155,12 +244,13 @@ pub async fn get_current_account() -> Result<Option<Account>, String> {
 /// 导出账号（包含 refresh_token）
 use crate::models::AccountExportResponse;
 
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn export_accounts(account_ids: Vec<String>) -> Result<AccountExportResponse, String> {
     modules::account::export_accounts_by_ids(&account_ids)
 }
 
 /// 内部辅助功能：在添加或导入账号后自动刷新一次额度
+#[cfg(feature = "desktop")]
 async fn internal_refresh_account_quota(
     app: &tauri::AppHandle,
     account: &mut Account,
@@ -184,22 +274,21 @@ async fn internal_refresh_account_quota(
 }
 
 /// 查询账号配额
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn fetch_account_quota(
     app: tauri::AppHandle,
     proxy_state: tauri::State<'_, crate::commands::proxy::ProxyServiceState>,
     account_id: String,
-) -> crate::error::AppResult<QuotaData> {
+) -> Result<QuotaData, crate::error::CommandError> {
     modules::logger::log_info(&format!("手动刷新配额请求: {}", account_id));
-    let mut account =
-        modules::load_account(&account_id).map_err(crate::error::AppError::Account)?;
+    let mut account = modules::load_account(&account_id)?;
 
     // 使用带重试的查询 (Shared logic) — Codex 账号也走此路径，会从 OpenAI API 获取模型列表
     let quota = modules::account::fetch_quota_with_retry(&mut account).await?;
 
     // 4. 更新账号配额
-    modules::update_account_quota(&account_id, quota.clone())
-        .map_err(crate::error::AppError::Account)?;
+    modules::update_account_quota(&account_id, quota.clone())?;
 
     crate::modules::tray::update_tray_menus(&app);
 
@@ -217,9 +306,9 @@ pub use modules::account::RefreshStats;
 /// 刷新所有账号配额 (内部实现)
 pub async fn refresh_all_quotas_internal(
     proxy_state: &crate::commands::proxy::ProxyServiceState,
-    app_handle: Option<tauri::AppHandle>,
+    operation_id: Option<String>,
 ) -> Result<RefreshStats, String> {
-    let stats = modules::account::refresh_all_quotas_logic().await?;
+    let stats = modules::account::refresh_all_quotas_cancellable(operation_id).await?;
 
     // 同步到运行中的反代服务（如果已启动）
     let instance_lock = proxy_state.instance.read().await;
@@ -227,25 +316,182 @@ pub async fn refresh_all_quotas_internal(
         let _ = instance.token_manager.reload_all_accounts().await;
     }
 
-    // 发送全局刷新事件给 UI (如果需要)
-    if let Some(handle) = app_handle {
-        use tauri::Emitter;
-        let _ = handle.emit("accounts://refreshed", ());
+    // 发送全局刷新事件给 UI
+    modules::events::accounts_refreshed();
+
+    Ok(stats)
+}
+
+/// 按自适应间隔刷新到期账号配额 (后台定时任务使用)
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub async fn refresh_due_quotas(
+    proxy_state: tauri::State<'_, crate::commands::proxy::ProxyServiceState>,
+    operation_id: Option<String>,
+) -> Result<RefreshStats, String> {
+    let stats = modules::account::refresh_due_quotas_logic(operation_id).await?;
+    if stats.total == 0 {
+        return Ok(stats);
+    }
+
+    let instance_lock = proxy_state.instance.read().await;
+    if let Some(instance) = instance_lock.as_ref() {
+        let _ = instance.token_manager.reload_all_accounts().await;
+    }
+
+    modules::events::accounts_refreshed();
+
+    Ok(stats)
+}
+
+/// 仅刷新选中的账号配额 (同一账号正在刷新时复用其结果)
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub async fn refresh_quota_for_accounts(
+    proxy_state: tauri::State<'_, crate::commands::proxy::ProxyServiceState>,
+    account_ids: Vec<String>,
+    operation_id: Option<String>,
+) -> Result<RefreshStats, String> {
+    let stats = modules::account::refresh_quota_for_accounts(&account_ids, operation_id).await?;
+
+    let instance_lock = proxy_state.instance.read().await;
+    if let Some(instance) = instance_lock.as_ref() {
+        for account_id in &account_ids {
+            let _ = instance.token_manager.reload_account(account_id).await;
+        }
     }
 
+    modules::events::accounts_refreshed();
+
     Ok(stats)
 }
 
 /// 刷新所有账号配额 (Tauri Command)
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn refresh_all_quotas(
     proxy_state: tauri::State<'_, crate::commands::proxy::ProxyServiceState>,
-    app_handle: tauri::AppHandle,
+    operation_id: Option<String>,
 ) -> Result<RefreshStats, String> {
-    refresh_all_quotas_internal(&proxy_state, Some(app_handle)).await
+    refresh_all_quotas_internal(&proxy_state, operation_id).await
 }
-/// 获取设备指纹（当前 storage.json + 账号绑定）
+
+/// 清除全部本地数据 (账号、Token、配置、日志、统计、本地备份)，先停止反代，完成后重启应用回到首次运行状态
+#[cfg(feature = "desktop")]
 #[tauri::command]
+pub async fn wipe_all_data(
+    app: tauri::AppHandle,
+    proxy_state: tauri::State<'_, crate::commands::proxy::ProxyServiceState>,
+    confirm_phrase: String,
+) -> Result<modules::wipe::WipeReport, String> {
+    modules::wipe::verify_phrase(&confirm_phrase)?;
+
+    // 停止反代，避免清除过程中继续写入账号与日志
+    let _ = crate::commands::proxy::stop_proxy_service(proxy_state).await;
+
+    let report = tokio::task::spawn_blocking(move || modules::wipe::wipe_all_data(&confirm_phrase))
+        .await
+        .map_err(|e| format!("wipe_failed: {}", e))??;
+
+    // 留出时间把结果返回前端，然后重启
+    crate::utils::runtime::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+        app.restart();
+    });
+    Ok(report)
+}
+
+/// 应用锁状态 (前端定时轮询，空闲超时在此时生效)
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub fn get_app_lock_status() -> modules::app_lock::AppLockStatus {
+    modules::app_lock::status()
+}
+
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub fn lock_app() -> Result<(), String> {
+    modules::app_lock::lock()
+}
+
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn unlock_app(password: String) -> Result<(), String> {
+    modules::app_lock::unlock(&password).await
+}
+
+/// 前端用户活动心跳，推迟空闲自动锁定
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub fn app_lock_activity() -> modules::app_lock::AppLockStatus {
+    let status = modules::app_lock::status();
+    if !status.locked {
+        modules::app_lock::touch();
+    }
+    status
+}
+
+/// 设置 / 修改主密码；new_password 为空时清除密码并关闭应用锁
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub fn set_app_lock_password(
+    current_password: Option<String>,
+    new_password: Option<String>,
+) -> Result<(), String> {
+    modules::app_lock::set_password(current_password.as_deref(), new_password.as_deref())
+}
+
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub fn set_app_lock_settings(
+    enabled: bool,
+    idle_minutes: u32,
+    password: Option<String>,
+) -> Result<(), String> {
+    modules::app_lock::configure(enabled, idle_minutes, password.as_deref())
+}
+
+/// 远程管理模式：将命令转发到远程实例的管理 API
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn remote_request(
+    method: String,
+    path: String,
+    body: Option<serde_json::Value>,
+) -> Result<serde_json::Value, String> {
+    modules::remote::forward(&method, &path, body).await
+}
+
+/// 测试远程实例连接 (保存配置前调用)
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn test_remote_connection(
+    remote: crate::models::config::RemoteConfig,
+) -> Result<serde_json::Value, String> {
+    modules::remote::test_connection(&remote).await
+}
+
+/// 取消正在进行的批量操作 (配额刷新 / 批量导入)
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn cancel_operation(operation_id: String) -> Result<bool, String> {
+    Ok(modules::operations::cancel_operation(&operation_id))
+}
+
+/// 列出正在进行的批量操作
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn list_operations() -> Result<Vec<modules::operations::OperationInfo>, String> {
+    Ok(modules::operations::list_operations())
+}
+
+/// 获取日志级别配置及实际生效的过滤规则 (RUST_LOG 覆盖配置时会标出)
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn get_log_levels() -> Result<modules::logger::LogLevelsStatus, String> {
+    Ok(modules::logger::get_log_levels())
+}
+
+/// 运行时修改日志级别 (全局默认 + 按模块覆盖)，并写入配置
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn set_log_levels(levels: crate::models::LoggingConfig) -> Result<(), String> {
+    let mut config = modules::load_app_config()?;
+    config.logging.default_level = levels.default_level;
+    config.logging.module_levels = levels.module_levels;
+    modules::logger::set_log_levels(config.logging.clone())?;
+    modules::save_app_config(&config)
+}
+/// 获取设备指纹（当前 storage.json + 账号绑定）
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn get_device_profiles(
     account_id: String,
 ) -> Result<modules::account::DeviceProfiles, String> {
@@ -253,7 +499,7 @@ pub async fn get_device_profiles(
 }
 
 /// 绑定设备指纹（capture: 采集当前；generate: 生成新指纹），并写入 storage.json
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn bind_device_profile(
     account_id: String,
     mode: String,
@@ -262,13 +508,13 @@ pub async fn bind_device_profile(
 }
 
 /// 预览生成一个指纹（不落盘）
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn preview_generate_profile() -> Result<crate::models::DeviceProfile, String> {
     Ok(crate::modules::device::generate_profile())
 }
 
 /// 使用给定指纹直接绑定
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn bind_device_profile_with_profile(
     account_id: String,
     profile: crate::models::DeviceProfile,
@@ -277,7 +523,7 @@ pub async fn bind_device_profile_with_profile(
 }
 
 /// 将账号已绑定的指纹应用到 storage.json
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn apply_device_profile(
     account_id: String,
 ) -> Result<crate::models::DeviceProfile, String> {
@@ -285,13 +531,13 @@ pub async fn apply_device_profile(
 }
 
 /// 恢复最早的 storage.json 备份（近似“原始”状态）
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn restore_original_device() -> Result<String, String> {
     modules::restore_original_device()
 }
 
 /// 列出指纹版本
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn list_device_versions(
     account_id: String,
 ) -> Result<modules::account::DeviceProfiles, String> {
@@ -299,7 +545,7 @@ pub async fn list_device_versions(
 }
 
 /// 按版本恢复指纹
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn restore_device_version(
     account_id: String,
     version_id: String,
@@ -308,41 +554,66 @@ pub async fn restore_device_version(
 }
 
 /// 删除历史指纹（baseline 不可删）
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn delete_device_version(account_id: String, version_id: String) -> Result<(), String> {
     modules::delete_device_version(&account_id, &version_id)
 }
 
 /// 打开设备存储目录
-#[tauri::command]
-pub async fn open_device_folder(app: tauri::AppHandle) -> Result<(), String> {
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn open_device_folder() -> Result<(), String> {
     let dir = modules::device::get_storage_dir()?;
     let dir_str = dir
         .to_str()
         .ok_or("无法解析存储目录路径为字符串")?
         .to_string();
-    app.opener()
-        .open_path(dir_str, None::<&str>)
-        .map_err(|e| format!("打开目录失败: {}", e))
+    crate::utils::command::open_with_system(&dir_str).map_err(|e| format!("打开目录失败: {}", e))
 }
 
 /// 加载配置
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn load_config() -> Result<AppConfig, String> {
     modules::load_app_config()
 }
 
 /// 保存配置
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn save_config(
-    app: tauri::AppHandle,
     proxy_state: tauri::State<'_, crate::commands::proxy::ProxyServiceState>,
-    config: AppConfig,
+    mut config: AppConfig,
 ) -> Result<(), String> {
+    crate::proxy::content_filter::validate(&config.proxy.content_filter)?;
+    config.proxy.validate_listeners()?;
+    modules::dns::validate(&config.http_client.dns)?;
+    let previous = modules::load_app_config().ok();
+    // 应用锁只能通过专用命令修改
+    config.app_lock = previous
+        .as_ref()
+        .map(|p| p.app_lock.clone())
+        .unwrap_or_default();
+    let monitored_changed = modules::model_catalog::prepare_monitored_models(
+        previous
+            .as_ref()
+            .map(|p| p.quota_protection.monitored_models.as_slice()),
+        &mut config.quota_protection.monitored_models,
+    )?;
     modules::save_app_config(&config)?;
+    if monitored_changed {
+        modules::account::reapply_quota_protection(&config.quota_protection)?;
+    }
+
+    // [NEW] 热更新日志级别
+    if let Err(e) = modules::logger::apply_log_levels_if_changed(&config.logging) {
+        modules::logger::log_warn(&format!("应用日志级别失败: {}", e));
+    }
 
-    // 通知托盘配置已更新
-    let _ = app.emit("config://updated", ());
+    // 热更新共享 HTTP 客户端配置 (同时丢弃按旧代理构建的缓存 Client)
+    modules::http::update_settings(config.http_client.clone());
+    modules::offline::update_config(config.offline.clone());
+    crate::proxy::update_quota_staleness_minutes(config.proxy.quota_staleness_minutes);
+    modules::endpoints::update_config(config.upstream_endpoints.clone());
+    crate::constants::update_version_sources(&config.version_sources);
 
     // 热更新正在运行的服务
     let instance_lock = proxy_state.instance.read().await;
@@ -376,6 +647,19 @@ pub async fn save_config(
         crate::proxy::update_global_system_prompt_config(config.proxy.global_system_prompt.clone());
         // [NEW] 更新全局图像思维模式配置
         crate::proxy::update_image_thinking_mode(config.proxy.image_thinking_mode.clone());
+        // [NEW] 更新模型降级链配置
+        crate::proxy::update_fallback_chains(config.proxy.fallback_chains.clone());
+        // [NEW] 更新订阅等级路由配置
+        crate::proxy::update_tier_routing(config.proxy.tier_routing.clone());
+        crate::proxy::update_admission_config(config.proxy.admission.clone());
+        crate::proxy::update_session_pool_config(config.proxy.session_pool.clone());
+        crate::proxy::update_circuit_breaker_config(config.proxy.circuit_breaker.clone());
+        crate::proxy::update_upstream_timeouts(config.proxy.upstream_timeouts.clone());
+        crate::proxy::update_content_filter_config(config.proxy.content_filter.clone());
+        crate::proxy::update_scoped_system_prompts(config.proxy.scoped_system_prompts.clone());
+        crate::proxy::update_fault_injection_config(config.proxy.fault_injection.clone());
+        crate::proxy::update_request_log_config(config.proxy.request_log.clone());
+        crate::proxy::mdns::refresh(&config.proxy);
         // 更新代理池配置
         instance
             .axum_server
@@ -392,8 +676,79 @@ pub async fn save_config(
     Ok(())
 }
 
+/// 导出配置 (默认不包含本机路径等机器相关字段，也不包含密钥/密码等凭据)
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn export_app_config(
+    include_machine_specific: Option<bool>,
+    include_secrets: Option<bool>,
+) -> Result<String, String> {
+    modules::config::export_app_config(
+        include_machine_specific.unwrap_or(false),
+        include_secrets.unwrap_or(false),
+    )
+}
+
+/// 导入配置 (合并到当前配置并立即生效)
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub async fn import_app_config(
+    app: tauri::AppHandle,
+    proxy_state: tauri::State<'_, crate::commands::proxy::ProxyServiceState>,
+    json: String,
+    include_machine_specific: Option<bool>,
+) -> Result<AppConfig, String> {
+    let config =
+        modules::config::import_app_config(&json, include_machine_specific.unwrap_or(false))?;
+    save_config(app, proxy_state, config.clone()).await?;
+    Ok(config)
+}
+
+/// 将某个配置分区恢复为默认值 (例如 quota_protection / proxy)
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub async fn reset_config_section(
+    app: tauri::AppHandle,
+    proxy_state: tauri::State<'_, crate::commands::proxy::ProxyServiceState>,
+    section: String,
+) -> Result<AppConfig, String> {
+    let config = modules::config::reset_config_section(&section)?;
+    save_config(app, proxy_state, config.clone()).await?;
+    Ok(config)
+}
+
+/// 配置历史版本列表 (新的在前，包含每个版本变更的设置)
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn list_config_history(
+) -> Result<Vec<modules::config_history::ConfigVersionInfo>, String> {
+    modules::config_history::list()
+}
+
+/// 对比两个配置版本 (to 为空时与当前配置对比)
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn diff_config_versions(
+    from: u64,
+    to: Option<u64>,
+) -> Result<Vec<modules::config_history::ConfigChange>, String> {
+    modules::config::diff_config_versions(from, to)
+}
+
+/// 回滚到某个配置历史版本并立即生效
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub async fn rollback_config(
+    app: tauri::AppHandle,
+    proxy_state: tauri::State<'_, crate::commands::proxy::ProxyServiceState>,
+    version: u64,
+) -> Result<AppConfig, String> {
+    let config = modules::config::rollback_config(version)?;
+    save_config(app, proxy_state, config.clone()).await?;
+    modules::logger::log_info(&format!("Config rolled back to version {}", version));
+    Ok(config)
+}
+
 // --- OAuth 命令 ---
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn start_oauth_login(app_handle: tauri::AppHandle) -> Result<Account, String> {
     modules::logger::log_info("开始 OAuth 授权流程...");
@@ -416,6 +771,7 @@ pub async fn start_oauth_login(app_handle: tauri::AppHandle) -> Result<Account,
 }
 
 /// 完成 OAuth 授权（不自动打开浏览器）
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn complete_oauth_login(app_handle: tauri::AppHandle) -> Result<Account, String> {
     modules::logger::log_info("完成 OAuth 授权流程 (manual)...");
@@ -438,6 +794,7 @@ pub async fn complete_oauth_login(app_handle: tauri::AppHandle) -> Result<Accoun
 }
 
 /// 预生成 OAuth 授权链接 (不打开浏览器)
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn prepare_oauth_url(app_handle: tauri::AppHandle) -> Result<String, String> {
     let service = modules::account_service::AccountService::new(
@@ -446,14 +803,14 @@ pub async fn prepare_oauth_url(app_handle: tauri::AppHandle) -> Result<String, S
     service.prepare_oauth_url().await
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn cancel_oauth_login() -> Result<(), String> {
     modules::oauth_server::cancel_oauth_flow();
     Ok(())
 }
 
 /// 手动提交 OAuth Code (用于 Docker/远程环境无法自动回调时)
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn submit_oauth_code(code: String, state: Option<String>) -> Result<(), String> {
     modules::logger::log_info("收到手动提交 OAuth Code 请求");
     modules::oauth_server::submit_oauth_code(code, state).await
@@ -462,6 +819,7 @@ pub async fn submit_oauth_code(code: String, state: Option<String>) -> Result<()
 // --- Codex 账号命令 ---
 
 /// Add a Codex account via manual token/API key input
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn add_codex_account_manual(
     _app: tauri::AppHandle,
@@ -507,6 +865,7 @@ pub async fn add_codex_account_manual(
 }
 
 /// Import Codex account from ~/.codex/auth.json
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn import_codex_from_file(
     _app: tauri::AppHandle,
@@ -542,23 +901,46 @@ pub struct BatchImportResult {
     pub success: u32,
     pub failed: u32,
     pub errors: Vec<String>,
+    /// Files left unprocessed because the operation was cancelled
+    pub skipped: u32,
+    pub cancelled: bool,
+    pub operation_id: String,
 }
 
 /// Batch import Codex accounts from selected auth.json files
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn import_codex_from_files(
     _app: tauri::AppHandle,
     proxy_state: tauri::State<'_, crate::commands::proxy::ProxyServiceState>,
     files: Vec<String>,
+    operation_id: Option<String>,
 ) -> Result<BatchImportResult, String> {
     use crate::models::Account;
     use crate::modules::codex_oauth;
 
+    let op = modules::operations::begin_operation("codex_import", operation_id)?;
     let mut success: u32 = 0;
     let mut failed: u32 = 0;
     let mut errors: Vec<String> = Vec::new();
 
-    for file_path_str in &files {
+    for (idx, file_path_str) in files.iter().enumerate() {
+        if op.is_cancelled() {
+            let skipped = (files.len() - idx) as u32;
+            tracing::info!("Codex batch import cancelled, {} files skipped", skipped);
+            if success > 0 {
+                let _ = crate::commands::proxy::reload_proxy_accounts(proxy_state).await;
+            }
+            return Ok(BatchImportResult {
+                success,
+                failed,
+                errors,
+                skipped,
+                cancelled: true,
+                operation_id: op.id().to_string(),
+            });
+        }
+
         let path = std::path::Path::new(file_path_str);
 
         let file_name = path.file_name()
@@ -605,10 +987,109 @@ pub async fn import_codex_from_files(
         let _ = crate::commands::proxy::reload_proxy_accounts(proxy_state).await;
     }
 
-    Ok(BatchImportResult { success, failed, errors })
+    Ok(BatchImportResult {
+        success,
+        failed,
+        errors,
+        skipped: 0,
+        cancelled: false,
+        operation_id: op.id().to_string(),
+    })
+}
+
+/// Detect the format of another tool's token file and list the accounts it contains (no tokens returned)
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn preview_account_import(
+    file_path: String,
+) -> Result<crate::modules::importers::ImportPreview, String> {
+    Ok(modules::importers::parse_file(std::path::Path::new(&file_path))?.preview())
+}
+
+/// Import accounts from other Gemini / Antigravity multi-account tools' token files (format auto-detected)
+/// Refresh tokens that already exist in the pool are skipped
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub async fn import_accounts_from_files(
+    app: tauri::AppHandle,
+    proxy_state: tauri::State<'_, crate::commands::proxy::ProxyServiceState>,
+    files: Vec<String>,
+    operation_id: Option<String>,
+) -> Result<BatchImportResult, String> {
+    let op = modules::operations::begin_operation("account_import", operation_id)?;
+    let service = modules::account_service::AccountService::new(
+        crate::modules::integration::SystemManager::Desktop(app.clone()),
+    );
+    let mut existing: std::collections::HashSet<String> = modules::account::list_accounts()?
+        .into_iter()
+        .map(|a| a.token.refresh_token)
+        .collect();
+    let mut success: u32 = 0;
+    let mut failed: u32 = 0;
+    let mut errors: Vec<String> = Vec::new();
+    let mut skipped: u32 = 0;
+
+    'files: for (index, file_path_str) in files.iter().enumerate() {
+        let path = std::path::Path::new(file_path_str);
+        let file_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+
+        let parsed = match modules::importers::parse_file(path) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                errors.push(format!("{}: {}", file_name, e));
+                failed += 1;
+                continue;
+            }
+        };
+        tracing::info!(
+            "[Import] {}: detected {} format with {} account(s)",
+            file_name,
+            parsed.format,
+            parsed.accounts.len()
+        );
+
+        for imported in parsed.accounts {
+            if op.is_cancelled() {
+                skipped = (files.len() - index) as u32;
+                break 'files;
+            }
+            if !existing.insert(imported.refresh_token.clone()) {
+                tracing::info!(
+                    "[Import] Skipping existing account {}",
+                    imported.email.as_deref().unwrap_or("(unknown)")
+                );
+                continue;
+            }
+            match service.add_account(&imported.refresh_token).await {
+                Ok(_) => success += 1,
+                Err(e) => {
+                    let label = imported.email.unwrap_or_else(|| file_name.clone());
+                    errors.push(format!("{}: {}", label, e));
+                    failed += 1;
+                }
+            }
+        }
+    }
+
+    if success > 0 {
+        let _ = crate::commands::proxy::reload_proxy_accounts(proxy_state).await;
+    }
+
+    Ok(BatchImportResult {
+        success,
+        failed,
+        errors,
+        skipped,
+        cancelled: skipped > 0,
+        operation_id: op.id().to_string(),
+    })
 }
 
 /// Start Codex OAuth login flow (opens browser for OpenAI login)
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn start_codex_oauth_login(
     app_handle: tauri::AppHandle,
@@ -638,10 +1119,7 @@ pub async fn start_codex_oauth_login(
     let (auth_url, code_verifier) = codex_oauth::get_codex_auth_url(&redirect_uri, &state_str);
 
     // Open browser for user to authenticate
-    use tauri_plugin_opener::OpenerExt;
-    app_handle
-        .opener()
-        .open_url(&auth_url, None::<&str>)
+    crate::utils::command::open_url(&app_handle, &auth_url)
         .map_err(|e| format!("Failed to open browser: {}", e))?;
 
     // Wait for the OAuth callback
@@ -713,6 +1191,7 @@ pub async fn start_codex_oauth_login(
 
 // --- 导入命令 ---
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn import_v1_accounts(
     app: tauri::AppHandle,
@@ -731,6 +1210,7 @@ pub async fn import_v1_accounts(
     Ok(accounts)
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn import_from_db(
     app: tauri::AppHandle,
@@ -755,6 +1235,7 @@ pub async fn import_from_db(
     Ok(account)
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 #[allow(dead_code)]
 pub async fn import_custom_db(
@@ -781,6 +1262,7 @@ pub async fn import_custom_db(
     Ok(account)
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn sync_account_from_db(
     app: tauri::AppHandle,
@@ -847,34 +1329,171 @@ fn validate_path(path: &str) -> Result<(), String> {
 }
 
 /// 保存文本文件 (绕过前端 Scope 限制)
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn save_text_file(path: String, content: String) -> Result<(), String> {
     validate_path(&path)?;
     std::fs::write(&path, content).map_err(|e| format!("写入文件失败: {}", e))
 }
 
 /// 读取文本文件 (绕过前端 Scope 限制)
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn read_text_file(path: String) -> Result<String, String> {
     validate_path(&path)?;
     std::fs::read_to_string(&path).map_err(|e| format!("读取文件失败: {}", e))
 }
 
 /// 清理日志缓存
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn clear_log_cache() -> Result<(), String> {
     modules::logger::clear_logs()
 }
 
+/// 生成诊断包 (脱敏配置、账号摘要、近期日志、代理统计、版本与系统信息)，返回 zip 路径
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn generate_diagnostics_bundle(output_path: Option<String>) -> Result<String, String> {
+    if let Some(path) = &output_path {
+        validate_path(path)?;
+    }
+    let output = output_path.map(std::path::PathBuf::from);
+    let path = tokio::task::spawn_blocking(move || {
+        modules::diagnostics::generate_diagnostics_bundle(output)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// 校验账号索引与账号文件的一致性，返回问题列表与修复计划 (只读)
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn verify_data_integrity() -> Result<modules::account::IntegrityReport, String> {
+    tokio::task::spawn_blocking(modules::account::verify_data_integrity)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 应用用户确认后的修复计划，返回修复后的校验结果
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn apply_integrity_repair(
+    plan: Vec<modules::account::RepairAction>,
+) -> Result<modules::account::IntegrityReport, String> {
+    tokio::task::spawn_blocking(move || modules::account::apply_repair_plan(&plan))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 获取网络 / 离线模式状态
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn get_network_status() -> Result<modules::offline::NetworkStatus, String> {
+    Ok(modules::offline::status())
+}
+
+/// 后台定时任务状态 (是否启用、下次检查时间、最近一次运行结果)
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn scheduler_status() -> Result<modules::scheduler::SchedulerStatus, String> {
+    tokio::task::spawn_blocking(modules::scheduler::scheduler_status)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 启用 / 停用单个后台任务 (写入配置)
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn set_scheduler_job_enabled(
+    job_id: String,
+    enabled: bool,
+) -> Result<modules::scheduler::SchedulerStatus, String> {
+    tokio::task::spawn_blocking(move || modules::scheduler::set_job_enabled(&job_id, enabled))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 本机时钟与上游的偏差 (由上游响应的 Date 头估算)
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn get_clock_skew() -> Result<modules::clock_skew::ClockSkewStatus, String> {
+    Ok(modules::clock_skew::status())
+}
+
+/// 手动开启 / 关闭离线模式
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn set_offline_mode(enabled: bool) -> Result<modules::offline::NetworkStatus, String> {
+    tokio::task::spawn_blocking(move || modules::offline::set_manual(enabled))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 首次运行引导状态
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn get_onboarding_state() -> Result<modules::onboarding::OnboardingState, String> {
+    tokio::task::spawn_blocking(modules::onboarding::get_onboarding_state)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 完成 (或跳过) 一个引导步骤
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn complete_onboarding_step(
+    step: crate::models::OnboardingStep,
+    path: Option<String>,
+    port: Option<u16>,
+    skip: Option<bool>,
+) -> Result<modules::onboarding::OnboardingState, String> {
+    let input = modules::onboarding::OnboardingStepInput {
+        path,
+        port,
+        skip: skip.unwrap_or(false),
+    };
+    tokio::task::spawn_blocking(move || modules::onboarding::complete_onboarding_step(step, input))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 可用界面语言 (内置 + 数据目录 locales/ 下的社区翻译)
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn list_available_languages() -> Result<Vec<modules::i18n::LanguageInfo>, String> {
+    tokio::task::spawn_blocking(modules::i18n::list_available_languages)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 获取合并后的语言包 (内置字符串 + 用户覆盖)，供前端加载社区翻译
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn get_locale_messages(language: String) -> Result<serde_json::Value, String> {
+    tokio::task::spawn_blocking(move || modules::i18n::get_locale_messages(&language))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 按级别 / 模块 / 文本 / 账号 / 时间范围分页查询应用日志
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn query_logs(
+    query: modules::log_store::LogQuery,
+) -> Result<modules::log_store::LogQueryResult, String> {
+    tokio::task::spawn_blocking(move || modules::log_store::query_logs(&query))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 导出指定时间范围内的日志文件 (zip)，返回生成的文件路径
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn export_logs(
+    range: modules::logger::LogRange,
+    output_path: Option<String>,
+) -> Result<String, String> {
+    if let Some(path) = &output_path {
+        validate_path(path)?;
+    }
+    let path = modules::logger::export_logs(&range, output_path.map(std::path::PathBuf::from))?;
+    Ok(path.to_string_lossy().to_string())
+}
+
 /// 清理 Antigravity 应用缓存
 /// 用于解决登录失败、版本验证错误等问题
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn clear_antigravity_cache() -> Result<modules::cache::ClearResult, String> {
     modules::cache::clear_antigravity_cache(None)
 }
 
 /// 获取 Antigravity 缓存路径列表（用于预览）
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn get_antigravity_cache_paths() -> Result<Vec<String>, String> {
     Ok(modules::cache::get_existing_cache_paths()
         .into_iter()
@@ -883,53 +1502,114 @@ pub async fn get_antigravity_cache_paths() -> Result<Vec<String>, String> {
 }
 
 /// 打开数据目录
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn open_data_folder() -> Result<(), String> {
     let path = modules::account::get_data_dir()?;
 
-    #[cfg(target_os = "macos")]
-    {
-        std::process::Command::new("open")
-            .arg(path)
-            .spawn()
-            .map_err(|e| format!("打开文件夹失败: {}", e))?;
-    }
+    crate::utils::command::open_with_system(&path.to_string_lossy())
+        .map_err(|e| format!("打开文件夹失败: {}", e))
+}
 
-    #[cfg(target_os = "windows")]
-    {
-        use crate::utils::command::CommandExtWrapper;
-        std::process::Command::new("explorer")
-            .creation_flags_windows()
-            .arg(path)
-            .spawn()
-            .map_err(|e| format!("打开文件夹失败: {}", e))?;
+/// 获取数据目录绝对路径
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn get_data_dir_path() -> Result<String, String> {
+    let path = modules::account::get_data_dir()?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// 迁移数据目录: 停止反代，复制到新位置并校验，切换所有模块到新位置后恢复反代 (原目录保留作备份)
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub async fn move_data_dir(
+    app: tauri::AppHandle,
+    proxy_state: tauri::State<'_, crate::commands::proxy::ProxyServiceState>,
+    cf_state: tauri::State<'_, crate::commands::cloudflared::CloudflaredState>,
+    new_path: String,
+) -> Result<modules::data_dir::MoveReport, String> {
+    let new_dir = std::path::PathBuf::from(new_path.trim());
+
+    // 停止反代，避免复制过程中继续写入账号与统计
+    let running_config = proxy_state
+        .instance
+        .read()
+        .await
+        .as_ref()
+        .map(|instance| instance.config.clone());
+    if running_config.is_some() {
+        crate::commands::proxy::internal_stop_proxy_service(&proxy_state).await?;
     }
 
-    #[cfg(target_os = "linux")]
-    {
-        std::process::Command::new("xdg-open")
-            .arg(path)
-            .spawn()
-            .map_err(|e| format!("打开文件夹失败: {}", e))?;
+    let result = tokio::task::spawn_blocking(move || modules::data_dir::move_to(new_dir))
+        .await
+        .map_err(|e| format!("data_dir_copy_failed: {}", e))
+        .and_then(|r| r);
+
+    // 管理服务器的账号池常驻内存，切换到新目录后重新加载 (失败时仍是原目录)
+    if let Some(admin) = proxy_state.admin_server.read().await.as_ref() {
+        let token_manager = &admin.axum_server.token_manager;
+        token_manager.set_data_dir(modules::account::get_data_dir()?);
+        let _ = token_manager.load_accounts().await;
+    }
+    if let Some(config) = running_config {
+        if let Err(e) = crate::commands::proxy::internal_start_proxy_service(
+            config,
+            &proxy_state,
+            modules::integration::SystemManager::Desktop(app),
+            std::sync::Arc::new(cf_state.inner().clone()),
+        )
+        .await
+        {
+            tracing::warn!("[DataDir] Failed to restart proxy after moving: {}", e);
+        }
     }
 
-    Ok(())
+    let report = result?;
+    modules::events::accounts_refreshed();
+    modules::events::publish(modules::events::AppEvent::ConfigUpdated);
+    Ok(report)
 }
 
-/// 获取数据目录绝对路径
-#[tauri::command]
-pub async fn get_data_dir_path() -> Result<String, String> {
-    let path = modules::account::get_data_dir()?;
-    Ok(path.to_string_lossy().to_string())
+/// 启动自检结果 (数据目录、配置、反代端口、Antigravity、钥匙串)；自检尚未完成时为 null
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn get_startup_report() -> Result<Option<modules::startup_report::StartupReport>, String> {
+    Ok(modules::startup_report::current())
+}
+
+/// 通知中心列表 (新的在前)；unread_only 时只返回未读
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn list_notifications(
+    unread_only: Option<bool>,
+) -> Result<Vec<modules::notify::Notification>, String> {
+    modules::notify::list(unread_only.unwrap_or(false))
+}
+
+/// 标记通知为已读；ids 为空时全部标记，返回剩余未读数
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn mark_notifications_read(ids: Option<Vec<String>>) -> Result<usize, String> {
+    modules::notify::mark_read(ids.as_deref())
+}
+
+/// 调试: 查看内部事件总线上序号大于 since 的最近事件 (默认最多 100 条)
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn tail_events(
+    since: Option<u64>,
+    limit: Option<usize>,
+) -> Result<Vec<modules::events::EventEnvelope>, String> {
+    Ok(modules::events::tail(
+        since,
+        limit.unwrap_or(modules::events::DEFAULT_TAIL_LIMIT),
+    ))
 }
 
 /// 显示主窗口
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn show_main_window(window: tauri::Window) -> Result<(), String> {
     window.show().map_err(|e| e.to_string())
 }
 
 /// 设置窗口主题（用于同步 Windows 标题栏按钮颜色）
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn set_window_theme(window: tauri::Window, theme: String) -> Result<(), String> {
     use tauri::Theme;
@@ -944,7 +1624,7 @@ pub async fn set_window_theme(window: tauri::Window, theme: String) -> Result<()
 }
 
 /// 获取 Antigravity 可执行文件路径
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn get_antigravity_path(bypass_config: Option<bool>) -> Result<String, String> {
     // 1. 优先从配置查询 (除非明确要求绕过)
     if bypass_config != Some(true) {
@@ -964,8 +1644,17 @@ pub async fn get_antigravity_path(bypass_config: Option<bool>) -> Result<String,
     }
 }
 
+/// 探测本机所有 Antigravity 安装 (按可信度排序)，供设置页选择
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn detect_antigravity_installations(
+) -> Result<Vec<crate::modules::process::AntigravityInstallation>, String> {
+    tokio::task::spawn_blocking(crate::modules::process::detect_antigravity_installations)
+        .await
+        .map_err(|e| format!("detect_installations_failed: {}", e))
+}
+
 /// 获取 Antigravity 启动参数
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn get_antigravity_args() -> Result<Vec<String>, String> {
     match crate::modules::process::get_args_from_running_process() {
         Some(args) => Ok(args),
@@ -977,13 +1666,13 @@ pub async fn get_antigravity_args() -> Result<Vec<String>, String> {
 pub use crate::modules::update_checker::UpdateInfo;
 
 /// 检测 GitHub releases 更新
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn check_for_updates() -> Result<UpdateInfo, String> {
     modules::logger::log_info("收到前端触发的更新检查请求");
     crate::modules::update_checker::check_for_updates().await
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn should_check_updates() -> Result<bool, String> {
     let settings = crate::modules::update_checker::load_update_settings()?;
     Ok(crate::modules::update_checker::should_check_for_updates(
@@ -991,20 +1680,20 @@ pub async fn should_check_updates() -> Result<bool, String> {
     ))
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn update_last_check_time() -> Result<(), String> {
     crate::modules::update_checker::update_last_check_time()
 }
 
 
 /// 检测是否通过 Homebrew Cask 安装
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn check_homebrew_installation() -> Result<bool, String> {
     Ok(crate::modules::update_checker::is_homebrew_installed())
 }
 
 /// 通过 Homebrew Cask 升级应用
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn brew_upgrade_cask() -> Result<String, String> {
     modules::logger::log_info("收到前端触发的 Homebrew 升级请求");
     crate::modules::update_checker::brew_upgrade_cask().await
@@ -1012,14 +1701,14 @@ pub async fn brew_upgrade_cask() -> Result<String, String> {
 
 
 /// 获取更新设置
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn get_update_settings() -> Result<crate::modules::update_checker::UpdateSettings, String>
 {
     crate::modules::update_checker::load_update_settings()
 }
 
 /// 保存更新设置
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn save_update_settings(
     settings: crate::modules::update_checker::UpdateSettings,
 ) -> Result<(), String> {
@@ -1027,6 +1716,7 @@ pub async fn save_update_settings(
 }
 
 /// 切换账号的反代禁用状态
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn toggle_proxy_status(
     app: tauri::AppHandle,
@@ -1057,6 +1747,11 @@ pub async fn toggle_proxy_status(
     let mut account_json: serde_json::Value =
         serde_json::from_str(&content).map_err(|e| format!("解析账号文件失败: {}", e))?;
 
+    let was_disabled = account_json
+        .get("proxy_disabled")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
     // 2. 更新 proxy_disabled 字段
     if enable {
         // 启用反代
@@ -1077,6 +1772,11 @@ pub async fn toggle_proxy_status(
         .map_err(|e| format!("序列化账号数据失败: {}", e))?;
     std::fs::write(&account_path, json_str).map_err(|e| format!("写入账号文件失败: {}", e))?;
 
+    // 重新启用后立即预热一次，不必等待下一轮全局预热
+    if enable && was_disabled {
+        modules::scheduler::schedule_warmup_after_reenable(&account_id, "proxy re-enabled");
+    }
+
     modules::logger::log_info(&format!(
         "账号反代状态已更新: {} ({})",
         account_id,
@@ -1116,20 +1816,70 @@ pub async fn toggle_proxy_status(
     Ok(())
 }
 
-/// 预热所有可用账号
+/// 批量切换账号的反代禁用状态 (单次加锁、单次写索引、单次刷新事件)
+#[cfg(feature = "desktop")]
 #[tauri::command]
+pub async fn set_proxy_status_bulk(
+    proxy_state: tauri::State<'_, crate::commands::proxy::ProxyServiceState>,
+    account_ids: Vec<String>,
+    enable: bool,
+    reason: Option<String>,
+) -> Result<modules::account::BulkProxyStatusResult, String> {
+    let ids = account_ids.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        modules::account::set_proxy_status_bulk(&ids, enable, reason.as_deref())
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    if !result.updated.is_empty() {
+        let instance_lock = proxy_state.instance.read().await;
+        if let Some(instance) = instance_lock.as_ref() {
+            // 禁用的账号中包含固定账号时关闭固定模式（内存 + 配置持久化）
+            if !enable {
+                let pref_id = instance.token_manager.get_preferred_account().await;
+                if pref_id.as_ref().is_some_and(|id| result.updated.contains(id)) {
+                    instance.token_manager.set_preferred_account(None).await;
+                    if let Ok(mut cfg) = crate::modules::config::load_app_config() {
+                        if cfg.proxy.preferred_account_id == pref_id {
+                            cfg.proxy.preferred_account_id = None;
+                            let _ = crate::modules::config::save_app_config(&cfg);
+                        }
+                    }
+                }
+            }
+            let _ = instance.token_manager.reload_all_accounts().await;
+        }
+
+        // 托盘订阅了账号刷新事件
+        modules::events::accounts_refreshed();
+    }
+
+    Ok(result)
+}
+
+/// 以只读方式走一遍切换流程 (刷新 Token / 项目 / 配额 / 设备指纹)，用于验证导入的账号
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn verify_account_login(
+    account_id: String,
+) -> Result<modules::account::LoginVerification, String> {
+    modules::account::verify_account_login(&account_id).await
+}
+
+/// 预热所有可用账号
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn warm_up_all_accounts() -> Result<String, String> {
     modules::quota::warm_up_all_accounts().await
 }
 
 /// 预热指定账号
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn warm_up_account(account_id: String) -> Result<String, String> {
     modules::quota::warm_up_account(&account_id).await
 }
 
 /// 更新账号自定义标签
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn update_account_label(account_id: String, label: String) -> Result<(), String> {
     // 验证标签长度（按字符数计算，支持中文）
     if label.chars().count() > 15 {
@@ -1183,18 +1933,108 @@ pub async fn update_account_label(account_id: String, label: String) -> Result<(
     Ok(())
 }
 
+/// 更新账号分组标签 (用于按 API Key 划分账号池)
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn update_account_tags(account_id: String, tags: Vec<String>) -> Result<Vec<String>, String> {
+    let tags = modules::account::set_account_tags(&account_id, &tags)?;
+    modules::logger::log_info(&format!("账号分组已更新: {} -> {:?}", account_id, tags));
+
+    // 通知 TokenManager 重新加载，使分组立即生效
+    crate::modules::events::account_reload(&account_id);
+    Ok(tags)
+}
+
+/// 设置账号的出站地址族 (ipv4 / ipv6)；传 null 恢复为全局设置
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn update_account_ip_family(
+    account_id: String,
+    ip_family: Option<crate::models::IpFamily>,
+) -> Result<(), String> {
+    modules::account::set_account_ip_family(&account_id, ip_family)?;
+    modules::logger::log_info(&format!(
+        "账号出站地址族已更新: {} -> {:?}",
+        account_id, ip_family
+    ));
+    Ok(())
+}
+
+/// 设置账号专属的 Antigravity 启动参数 (切换到该账号时与全局参数合并)
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn update_account_antigravity_args(
+    account_id: String,
+    antigravity_args: Option<Vec<String>>,
+) -> Result<(), String> {
+    modules::account::set_account_antigravity_args(&account_id, antigravity_args)?;
+    modules::logger::log_info(&format!("账号启动参数已更新: {}", account_id));
+    Ok(())
+}
+
+/// 设置账号使用的 Antigravity 安装 (多个 .app 副本时选择其一)
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn update_account_antigravity_executable(
+    account_id: String,
+    antigravity_executable: Option<String>,
+) -> Result<(), String> {
+    modules::account::set_account_antigravity_executable(&account_id, antigravity_executable)?;
+    modules::logger::log_info(&format!("账号目标安装已更新: {}", account_id));
+    Ok(())
+}
+
+/// 暂时停用账号 (snooze)，到期后由调度器自动恢复；返回恢复时间戳
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn snooze_account(account_id: String, duration_secs: u64) -> Result<i64, String> {
+    modules::account::snooze_account(&account_id, duration_secs)
+}
+
+/// 列出磁盘上存在但未被索引引用的账号文件 (如从备份恢复)
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn list_orphan_accounts() -> Result<Vec<crate::models::AccountSummary>, String> {
+    modules::account::list_orphan_accounts()
+}
+
+/// 将孤立账号文件重新加入索引
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn adopt_orphan(account_id: String) -> Result<crate::models::AccountSummary, String> {
+    let summary = modules::account::adopt_orphan(&account_id)?;
+    crate::modules::events::account_reload(&account_id);
+    Ok(summary)
+}
+
+/// 列出因无法解析而被隔离到 accounts/corrupt/ 的账号文件
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn list_corrupt_accounts() -> Result<Vec<modules::account::CorruptAccountFile>, String> {
+    modules::account::list_corrupt_accounts()
+}
+
+/// 将 (手动修复后的) 隔离账号文件恢复到账号目录并加入索引
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn restore_corrupt_account(
+    file_name: String,
+) -> Result<crate::models::AccountSummary, String> {
+    let summary = modules::account::restore_corrupt_account(&file_name)?;
+    crate::modules::events::account_reload(&summary.id);
+    crate::modules::events::accounts_refreshed();
+    Ok(summary)
+}
+
+/// 永久删除隔离的账号文件
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn delete_corrupt_account(file_name: String) -> Result<(), String> {
+    modules::account::delete_corrupt_account(&file_name)
+}
+
 // ============================================================================
 // HTTP API 设置命令
 // ============================================================================
 
 /// 获取 HTTP API 设置
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn get_http_api_settings() -> Result<crate::modules::http_api::HttpApiSettings, String> {
     crate::modules::http_api::load_settings()
 }
 
 /// 保存 HTTP API 设置
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn save_http_api_settings(
     settings: crate::modules::http_api::HttpApiSettings,
 ) -> Result<(), String> {
@@ -1207,60 +2047,226 @@ pub async fn save_http_api_settings(
 
 pub use crate::modules::token_stats::{AccountTokenStats, TokenStatsAggregated, TokenStatsSummary};
 
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn get_token_stats_hourly(hours: i64) -> Result<Vec<TokenStatsAggregated>, String> {
     crate::modules::token_stats::get_hourly_stats(hours)
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn get_token_stats_daily(days: i64) -> Result<Vec<TokenStatsAggregated>, String> {
     crate::modules::token_stats::get_daily_stats(days)
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn get_token_stats_weekly(weeks: i64) -> Result<Vec<TokenStatsAggregated>, String> {
     crate::modules::token_stats::get_weekly_stats(weeks)
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn get_token_stats_by_account(hours: i64) -> Result<Vec<AccountTokenStats>, String> {
     crate::modules::token_stats::get_account_stats(hours)
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn get_token_stats_summary(hours: i64) -> Result<TokenStatsSummary, String> {
     crate::modules::token_stats::get_summary_stats(hours)
 }
 
+/// 已知模型列表 (内置映射 + 上游目录同步)
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn list_known_models() -> Result<Vec<String>, String> {
+    Ok(crate::modules::model_catalog::list_known_models())
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct MonitoredModelOptions {
+    /// 可监控的标准分组 ID
+    pub standard_ids: Vec<String>,
+    /// 当前配置的监控分组
+    pub monitored_models: Vec<String>,
+}
+
+/// Google project_id 绑定历史 (排查与项目切换时间吻合的 403)；不指定账号时返回全部
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn get_project_binding_history(
+    account_id: Option<String>,
+) -> Result<Vec<crate::modules::project_binding::ProjectBindingHistory>, String> {
+    let histories = crate::modules::project_binding::list_histories();
+    Ok(match account_id {
+        Some(id) => histories.into_iter().filter(|h| h.account_id == id).collect(),
+        None => histories,
+    })
+}
+
+/// 账号最近 N 小时的配额快照 (每次配额刷新记录一次)
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn get_quota_history(
+    account_id: String,
+    hours: Option<i64>,
+) -> Result<Vec<crate::modules::token_stats::QuotaSnapshotPoint>, String> {
+    let hours = hours.unwrap_or(24);
+    tokio::task::spawn_blocking(move || {
+        crate::modules::token_stats::get_quota_history(&account_id, hours)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 账号最近 N 小时的上游延迟 / 带宽百分位 (首字节时间最慢的在前)
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn get_account_latency_stats(
+    hours: Option<i64>,
+) -> Result<Vec<crate::modules::token_stats::AccountLatencyStats>, String> {
+    let hours = hours.unwrap_or(24);
+    tokio::task::spawn_blocking(move || {
+        crate::modules::token_stats::get_account_latency_stats(hours)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 按数据保留策略清理历史数据；dry_run 时只报告将被删除的行数
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn run_data_retention(
+    dry_run: bool,
+) -> Result<crate::modules::retention::RetentionReport, String> {
+    tokio::task::spawn_blocking(move || crate::modules::retention::run(dry_run))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 立即执行账号数据维护 (过期冷却 / 指纹历史 / 临时文件 / 索引校验)；dry_run 时只报告
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn run_account_hygiene(
+    dry_run: bool,
+) -> Result<crate::modules::hygiene::HygieneReport, String> {
+    tokio::task::spawn_blocking(move || crate::modules::hygiene::run(dry_run))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 立即生成问题账号日报并投递到已配置的通知渠道
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn run_problem_digest() -> Result<crate::modules::digest::ProblemDigest, String> {
+    crate::modules::digest::send_digest(true).await
+}
+
+/// 立即打包、加密并上传一份备份到远程目标
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn run_remote_backup() -> Result<crate::modules::backup::BackupResult, String> {
+    let config = crate::modules::config::load_app_config()?;
+    crate::modules::backup::run_backup(&config.backup).await
+}
+
+/// 远程目标上的备份归档 (最新的在前)
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn list_remote_backups() -> Result<Vec<String>, String> {
+    let config = crate::modules::config::load_app_config()?;
+    crate::modules::backup::list_remote(&config.backup.target).await
+}
+
+/// 从远程归档恢复账号与配置 (恢复前会在本地保存当前数据)
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn restore_remote_backup(
+    name: String,
+) -> Result<crate::modules::backup::RestoreResult, String> {
+    let config = crate::modules::config::load_app_config()?;
+    crate::modules::backup::restore_backup(&config.backup, &name).await
+}
+
+/// 最近一次远程备份的结果
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn get_backup_status() -> Result<crate::modules::backup::BackupStatus, String> {
+    Ok(crate::modules::backup::load_status())
+}
+
+/// 立即与远程同步账号池 (双向，按账号最后修改时间解决冲突)
+#[cfg(feature = "desktop")]
 #[tauri::command]
+pub async fn sync_accounts_now(
+    proxy_state: tauri::State<'_, crate::commands::proxy::ProxyServiceState>,
+) -> Result<crate::modules::sync::SyncReport, String> {
+    let report = crate::modules::sync::run_sync().await?;
+    if report.routing.as_deref() == Some("pulled") {
+        crate::modules::sync::apply_pulled_mapping(&proxy_state).await;
+    }
+    Ok(report)
+}
+
+/// 账号池同步状态 (本机 ID、最近一次同步结果)
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn get_sync_status() -> Result<crate::modules::sync::SyncStatus, String> {
+    Ok(crate::modules::sync::load_status())
+}
+
+/// 按合成负载推演当前账号池 (轮换 + 配额保护)，预测各账号消耗与耗尽时间
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn simulate_pool_workload(
+    workload: crate::proxy::pool_simulation::SimulationWorkload,
+) -> Result<crate::proxy::pool_simulation::SimulationReport, String> {
+    crate::proxy::pool_simulation::simulate_current_pool(&workload)
+}
+
+/// 配额保护可监控的分组及当前配置
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn get_monitored_model_options() -> Result<MonitoredModelOptions, String> {
+    Ok(MonitoredModelOptions {
+        standard_ids: crate::modules::model_catalog::list_standard_ids(),
+        monitored_models: modules::load_app_config()?.quota_protection.monitored_models,
+    })
+}
+
+/// 更新配额保护的监控分组：校验后保存，并立即对所有账号重新判定 (无需重启)
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn update_monitored_models(models: Vec<String>) -> Result<Vec<String>, String> {
+    let models = crate::modules::model_catalog::validate_monitored_models(&models)?;
+    let mut config = modules::load_app_config()?;
+    config.quota_protection.monitored_models = models.clone();
+    modules::save_app_config(&config)?;
+    let changed = modules::account::reapply_quota_protection(&config.quota_protection)?;
+    modules::logger::log_info(&format!(
+        "[Quota] Monitored models updated: [{}], {} account(s) re-evaluated with changes",
+        models.join(", "),
+        changed
+    ));
+    Ok(models)
+}
+
+/// 立即从上游同步一次模型目录
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn sync_model_catalog() -> Result<crate::modules::model_catalog::CatalogSyncResult, String> {
+    crate::modules::model_catalog::sync_catalog().await
+}
+
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn get_token_stats_by_model(
     hours: i64,
 ) -> Result<Vec<crate::modules::token_stats::ModelTokenStats>, String> {
     crate::modules::token_stats::get_model_stats(hours)
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn get_token_stats_model_trend_hourly(
     hours: i64,
 ) -> Result<Vec<crate::modules::token_stats::ModelTrendPoint>, String> {
     crate::modules::token_stats::get_model_trend_hourly(hours)
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn get_token_stats_model_trend_daily(
     days: i64,
 ) -> Result<Vec<crate::modules::token_stats::ModelTrendPoint>, String> {
     crate::modules::token_stats::get_model_trend_daily(days)
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn get_token_stats_account_trend_hourly(
     hours: i64,
 ) -> Result<Vec<crate::modules::token_stats::AccountTrendPoint>, String> {
     crate::modules::token_stats::get_account_trend_hourly(hours)
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn get_token_stats_account_trend_daily(
     days: i64,
 ) -> Result<Vec<crate::modules::token_stats::AccountTrendPoint>, String> {
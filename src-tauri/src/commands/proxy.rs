@@ -3,6 +3,7 @@ use crate::proxy::{ProxyConfig, ProxyPoolConfig, TokenManager};
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+#[cfg(feature = "desktop")]
 use tauri::State;
 use tokio::sync::RwLock;
 use tokio::time::Duration;
@@ -52,6 +53,7 @@ impl ProxyServiceState {
 }
 
 /// 启动反代服务 (Tauri 命令)
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn start_proxy_service(
     config: ProxyConfig,
@@ -106,13 +108,7 @@ pub async fn internal_start_proxy_service(
     {
         let mut monitor_lock = state.monitor.write().await;
         if monitor_lock.is_none() {
-            let app_handle =
-                if let crate::modules::integration::SystemManager::Desktop(ref h) = integration {
-                    Some(h.clone())
-                } else {
-                    None
-                };
-            *monitor_lock = Some(Arc::new(ProxyMonitor::new(1000, app_handle)));
+            *monitor_lock = Some(Arc::new(ProxyMonitor::new(1000)));
         }
         // Sync enabled state from config
         if let Some(monitor) = monitor_lock.as_ref() {
@@ -196,6 +192,7 @@ pub async fn internal_start_proxy_service(
     axum_server.set_running(true).await;
 
     *instance_lock = Some(instance);
+    crate::proxy::mdns::start(&config);
 
     // 成功启动后，guard 在这里结束并重置 starting 是 OK 的
     // 但其实我们可以直接手动掉，或者相信 guard
@@ -218,18 +215,13 @@ pub async fn ensure_admin_server(
     if admin_lock.is_some() {
         return Ok(());
     }
+    config.validate_listeners()?;
 
     // Ensure monitor exists
     let monitor = {
         let mut monitor_lock = state.monitor.write().await;
         if monitor_lock.is_none() {
-            let app_handle =
-                if let crate::modules::integration::SystemManager::Desktop(ref h) = integration {
-                    Some(h.clone())
-                } else {
-                    None
-                };
-            *monitor_lock = Some(Arc::new(ProxyMonitor::new(1000, app_handle)));
+            *monitor_lock = Some(Arc::new(ProxyMonitor::new(1000)));
         }
         monitor_lock.as_ref().unwrap().clone()
     };
@@ -256,6 +248,7 @@ pub async fn ensure_admin_server(
         integration.clone(),
         cloudflared_state,
         config.proxy_pool.clone(),
+        config.additional_listeners.clone(),
     )
     .await
     {
@@ -274,12 +267,31 @@ pub async fn ensure_admin_server(
     crate::proxy::update_global_system_prompt_config(config.global_system_prompt.clone());
     // [NEW] 初始化全局图像思维模式配置
     crate::proxy::update_image_thinking_mode(config.image_thinking_mode.clone());
+    // [NEW] 初始化模型降级链配置
+    crate::proxy::update_fallback_chains(config.fallback_chains.clone());
+    // [NEW] 初始化订阅等级路由配置
+    crate::proxy::update_tier_routing(config.tier_routing.clone());
+    crate::proxy::update_admission_config(config.admission.clone());
+    crate::proxy::update_session_pool_config(config.session_pool.clone());
+    crate::proxy::update_circuit_breaker_config(config.circuit_breaker.clone());
+    crate::proxy::update_upstream_timeouts(config.upstream_timeouts.clone());
+    crate::proxy::update_quota_staleness_minutes(config.quota_staleness_minutes);
+    crate::proxy::update_content_filter_config(config.content_filter.clone());
+    crate::proxy::update_scoped_system_prompts(config.scoped_system_prompts.clone());
+    crate::proxy::update_fault_injection_config(config.fault_injection.clone());
+    crate::proxy::update_request_log_config(config.request_log.clone());
     Ok(())
 }
 
 /// 停止反代服务
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn stop_proxy_service(state: State<'_, ProxyServiceState>) -> Result<(), String> {
+    internal_stop_proxy_service(&state).await
+}
+
+/// 内部停止反代服务逻辑
+pub async fn internal_stop_proxy_service(state: &ProxyServiceState) -> Result<(), String> {
     let mut instance_lock = state.instance.write().await;
 
     if instance_lock.is_none() {
@@ -292,11 +304,13 @@ pub async fn stop_proxy_service(state: State<'_, ProxyServiceState>) -> Result<(
         instance.axum_server.set_running(false).await;
         // 已移除 instance.axum_server.stop() 调用，防止杀死 Admin Server
     }
+    crate::proxy::mdns::stop();
 
     Ok(())
 }
 
 /// 获取反代服务状态
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn get_proxy_status(state: State<'_, ProxyServiceState>) -> Result<ProxyStatus, String> {
     // 优先检查启动标志，避免被写锁阻塞
@@ -340,6 +354,7 @@ pub async fn get_proxy_status(state: State<'_, ProxyServiceState>) -> Result<Pro
 }
 
 /// 获取反代服务统计
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn get_proxy_stats(state: State<'_, ProxyServiceState>) -> Result<ProxyStats, String> {
     let monitor_lock = state.monitor.read().await;
@@ -350,7 +365,55 @@ pub async fn get_proxy_stats(state: State<'_, ProxyServiceState>) -> Result<Prox
     }
 }
 
+/// 获取准入控制统计 (各优先级放行 / 排队 / 拒绝次数)
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub async fn get_admission_stats(
+    state: State<'_, ProxyServiceState>,
+) -> Result<crate::proxy::admission::AdmissionStats, String> {
+    let instance_lock = state.instance.read().await;
+    let eligible = match instance_lock.as_ref() {
+        Some(instance) => instance.token_manager.eligible_account_count().await,
+        None => 0,
+    };
+    Ok(crate::proxy::admission::stats(eligible))
+}
+
+/// 列出各账号的上游会话 ID
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn list_upstream_sessions() -> Result<Vec<crate::proxy::session_pool::SessionEntry>, String> {
+    Ok(crate::proxy::session_pool::list())
+}
+
+/// 立即轮换上游会话 ID (account_id 为空时轮换全部)，返回轮换的账号数
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn rotate_upstream_sessions(account_id: Option<String>) -> Result<usize, String> {
+    Ok(crate::proxy::session_pool::rotate(account_id.as_deref()))
+}
+
+/// 各上游端点的熔断状态
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn get_upstream_circuits(
+) -> Result<Vec<crate::proxy::upstream::circuit_breaker::CircuitStatus>, String> {
+    Ok(crate::proxy::upstream::circuit_breaker::snapshot())
+}
+
+/// 内容过滤各规则的命中统计
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn get_content_filter_stats(
+) -> Result<Vec<crate::proxy::content_filter::RuleStats>, String> {
+    Ok(crate::proxy::content_filter::stats())
+}
+
+/// 清空内容过滤命中统计
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn reset_content_filter_stats() -> Result<(), String> {
+    crate::proxy::content_filter::reset_stats();
+    Ok(())
+}
+
 /// 获取反代请求日志
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn get_proxy_logs(
     state: State<'_, ProxyServiceState>,
@@ -365,6 +428,7 @@ pub async fn get_proxy_logs(
 }
 
 /// 设置监控开启状态
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn set_proxy_monitor_enabled(
     state: State<'_, ProxyServiceState>,
@@ -378,6 +442,7 @@ pub async fn set_proxy_monitor_enabled(
 }
 
 /// 清除反代请求日志
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn clear_proxy_logs(state: State<'_, ProxyServiceState>) -> Result<(), String> {
     let monitor_lock = state.monitor.read().await;
@@ -387,8 +452,16 @@ pub async fn clear_proxy_logs(state: State<'_, ProxyServiceState>) -> Result<(),
     Ok(())
 }
 
+/// 按保留策略立即清理并压缩 (VACUUM) 请求日志
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn compact_proxy_logs() -> Result<crate::modules::proxy_db::CompactionResult, String> {
+    tokio::task::spawn_blocking(|| crate::modules::proxy_db::compact(true))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
 /// 获取反代请求日志 (分页)
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn get_proxy_logs_paginated(
     limit: Option<usize>,
     offset: Option<usize>,
@@ -397,19 +470,19 @@ pub async fn get_proxy_logs_paginated(
 }
 
 /// 获取单条日志的完整详情
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn get_proxy_log_detail(log_id: String) -> Result<ProxyRequestLog, String> {
     crate::modules::proxy_db::get_log_detail(&log_id)
 }
 
 /// 获取日志总数
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn get_proxy_logs_count() -> Result<u64, String> {
     crate::modules::proxy_db::get_logs_count()
 }
 
 /// 导出所有日志到指定文件
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn export_proxy_logs(file_path: String) -> Result<usize, String> {
     let logs = crate::modules::proxy_db::get_all_logs_for_export()?;
     let count = logs.len();
@@ -423,7 +496,7 @@ pub async fn export_proxy_logs(file_path: String) -> Result<usize, String> {
 }
 
 /// 导出指定的日志JSON到文件
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn export_proxy_logs_json(file_path: String, json_data: String) -> Result<usize, String> {
     // Parse to count items
     let logs: Vec<serde_json::Value> =
@@ -440,7 +513,7 @@ pub async fn export_proxy_logs_json(file_path: String, json_data: String) -> Res
 }
 
 /// 获取带搜索条件的日志数量
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn get_proxy_logs_count_filtered(
     filter: String,
     errors_only: bool,
@@ -449,7 +522,7 @@ pub async fn get_proxy_logs_count_filtered(
 }
 
 /// 获取带搜索条件的分页日志
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn get_proxy_logs_filtered(
     filter: String,
     errors_only: bool,
@@ -460,12 +533,13 @@ pub async fn get_proxy_logs_filtered(
 }
 
 /// 生成 API Key
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub fn generate_api_key() -> String {
     format!("sk-{}", uuid::Uuid::new_v4().simple())
 }
 
 /// 重新加载账号（当主应用添加/删除账号时调用）
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn reload_proxy_accounts(state: State<'_, ProxyServiceState>) -> Result<usize, String> {
     let instance_lock = state.instance.read().await;
@@ -489,6 +563,7 @@ pub async fn reload_proxy_accounts(state: State<'_, ProxyServiceState>) -> Resul
 }
 
 /// 更新模型映射表 (热更新)
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn update_model_mapping(
     config: ProxyConfig,
@@ -504,8 +579,12 @@ pub async fn update_model_mapping(
     }
 
     // 2. 无论是否运行，都保存到全局配置持久化
+    // 降级链属于路由配置，随映射一起热更新
+    crate::proxy::update_fallback_chains(config.fallback_chains.clone());
+
     let mut app_config = crate::modules::config::load_app_config().map_err(|e| e)?;
     app_config.proxy.custom_mapping = config.custom_mapping;
+    app_config.proxy.fallback_chains = config.fallback_chains;
     crate::modules::config::save_app_config(&app_config).map_err(|e| e)?;
 
     Ok(())
@@ -570,7 +649,7 @@ fn extract_model_ids(value: &serde_json::Value) -> Vec<String> {
 }
 
 /// Fetch available models from the configured z.ai Anthropic-compatible API (`/v1/models`).
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn fetch_zai_models(
     zai: crate::proxy::ZaiConfig,
     upstream_proxy: crate::proxy::config::UpstreamProxyConfig,
@@ -631,6 +710,7 @@ pub async fn fetch_zai_models(
 }
 
 /// 获取当前调度配置
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn get_proxy_scheduling_config(
     state: State<'_, ProxyServiceState>,
@@ -644,6 +724,7 @@ pub async fn get_proxy_scheduling_config(
 }
 
 /// 更新调度配置
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn update_proxy_scheduling_config(
     state: State<'_, ProxyServiceState>,
@@ -659,6 +740,7 @@ pub async fn update_proxy_scheduling_config(
 }
 
 /// 清除所有会话粘性绑定
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn clear_proxy_session_bindings(
     state: State<'_, ProxyServiceState>,
@@ -676,6 +758,7 @@ pub async fn clear_proxy_session_bindings(
 
 /// 设置优先使用的账号（固定账号模式）
 /// 传入 account_id 启用固定模式，传入 null/空字符串恢复轮询模式
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn set_preferred_account(
     state: State<'_, ProxyServiceState>,
@@ -715,6 +798,7 @@ pub async fn set_preferred_account(
 }
 
 /// 获取当前优先使用的账号ID
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn get_preferred_account(
     state: State<'_, ProxyServiceState>,
@@ -727,7 +811,83 @@ pub async fn get_preferred_account(
     }
 }
 
+/// 受监控模型的可用性矩阵 (每个模型当前可用的账号数)
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub async fn get_model_availability(
+    state: State<'_, ProxyServiceState>,
+) -> Result<Vec<crate::proxy::model_availability::ModelAvailability>, String> {
+    let instance_lock = state.instance.read().await;
+    match instance_lock.as_ref() {
+        Some(instance) => Ok(instance.token_manager.model_availability().await),
+        None => Err("proxy_not_running: start the proxy service first".to_string()),
+    }
+}
+
+/// TokenManager 内存状态快照 (已加载账号、token 过期时间、冷却、受保护模型、各模型最近选号)
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub async fn get_token_manager_state(
+    state: State<'_, ProxyServiceState>,
+) -> Result<crate::proxy::token_state::TokenManagerState, String> {
+    let instance_lock = state.instance.read().await;
+    match instance_lock.as_ref() {
+        Some(instance) => Ok(instance.token_manager.state_snapshot().await),
+        None => Err("proxy_not_running: start the proxy service first".to_string()),
+    }
+}
+
+/// 各模型容量阈值的当前状态 (capacity_alerts.min_available)
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub async fn get_capacity_status(
+    state: State<'_, ProxyServiceState>,
+) -> Result<Vec<crate::proxy::capacity_planner::CapacityStatus>, String> {
+    let instance_lock = state.instance.read().await;
+    match instance_lock.as_ref() {
+        Some(instance) => Ok(instance.token_manager.capacity_status().await),
+        None => Err("proxy_not_running: start the proxy service first".to_string()),
+    }
+}
+
+/// 单账号试运行: 经完整代理管线发送一个固定的小请求，但锁定到指定账号
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub async fn test_account_request(
+    state: State<'_, ProxyServiceState>,
+    account_id: String,
+    model: String,
+) -> Result<crate::proxy::dry_run::DryRunResult, String> {
+    let (port, api_key) = {
+        let instance_lock = state.instance.read().await;
+        let instance = instance_lock
+            .as_ref()
+            .ok_or_else(|| "proxy_not_running: start the proxy service first".to_string())?;
+        (instance.config.port, instance.config.api_key.clone())
+    };
+    crate::proxy::dry_run::run(port, &api_key, &account_id, &model).await
+}
+
+/// 重放请求日志: 经完整代理管线重新发送已保存的请求体 (可锁定到指定账号 ID / 邮箱)，返回响应差异
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub async fn replay_request(
+    state: State<'_, ProxyServiceState>,
+    log_id: String,
+    target_account: Option<String>,
+) -> Result<crate::proxy::replay::ReplayResult, String> {
+    let (port, api_key) = {
+        let instance_lock = state.instance.read().await;
+        let instance = instance_lock
+            .as_ref()
+            .ok_or_else(|| "proxy_not_running: start the proxy service first".to_string())?;
+        (instance.config.port, instance.config.api_key.clone())
+    };
+    crate::proxy::replay::replay(port, &api_key, &log_id, target_account.as_deref()).await
+}
+
 /// 清除指定账号的限流记录
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn clear_proxy_rate_limit(
     state: State<'_, ProxyServiceState>,
@@ -742,6 +902,7 @@ pub async fn clear_proxy_rate_limit(
 }
 
 /// 清除所有限流记录
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn clear_all_proxy_rate_limits(
     state: State<'_, ProxyServiceState>,
@@ -756,6 +917,7 @@ pub async fn clear_all_proxy_rate_limits(
 }
 
 /// 触发所有代理的健康检查，并返回更新后的配置
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn check_proxy_health(
     state: State<'_, ProxyServiceState>,
@@ -776,6 +938,7 @@ pub async fn check_proxy_health(
 }
 
 /// 获取当前内存中的代理池状态
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn get_proxy_pool_config(
     state: State<'_, ProxyServiceState>,
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use crate::modules::user_token_db::{self, UserToken, TokenIpBinding};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct CreateTokenRequest {
     pub username: String,
     pub expires_type: String,
@@ -10,9 +10,11 @@ pub struct CreateTokenRequest {
     pub curfew_start: Option<String>,
     pub curfew_end: Option<String>,
     pub custom_expires_at: Option<i64>,  // 自定义过期时间戳 (秒)
+    pub account_group: Option<String>,   // 绑定的账号分组标签
+    pub priority: Option<String>,        // "high" | "normal" | "low"，缺省 normal
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct UpdateTokenRequest {
     pub username: Option<String>,
     pub description: Option<String>,
@@ -20,18 +22,20 @@ pub struct UpdateTokenRequest {
     pub max_ips: Option<i32>,
     pub curfew_start: Option<Option<String>>,
     pub curfew_end: Option<Option<String>>,
+    pub account_group: Option<String>,   // 空字符串表示解除绑定
+    pub priority: Option<String>,
 }
 
 // 命令实现
 
 /// 列出所有令牌
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn list_user_tokens() -> Result<Vec<UserToken>, String> {
     user_token_db::list_tokens()
 }
 
 /// 创建新令牌
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn create_user_token(request: CreateTokenRequest) -> Result<UserToken, String> {
     user_token_db::create_token(
         request.username,
@@ -41,11 +45,13 @@ pub async fn create_user_token(request: CreateTokenRequest) -> Result<UserToken,
         request.curfew_start,
         request.curfew_end,
         request.custom_expires_at,
+        request.account_group,
+        request.priority,
     )
 }
 
 /// 更新令牌
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn update_user_token(id: String, request: UpdateTokenRequest) -> Result<(), String> {
     user_token_db::update_token(
         &id,
@@ -55,23 +61,25 @@ pub async fn update_user_token(id: String, request: UpdateTokenRequest) -> Resul
         request.max_ips,
         request.curfew_start,
         request.curfew_end,
+        request.account_group,
+        request.priority,
     )
 }
 
 /// 删除令牌
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn delete_user_token(id: String) -> Result<(), String> {
     user_token_db::delete_token(&id)
 }
 
 /// 续期令牌
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn renew_user_token(id: String, expires_type: String) -> Result<(), String> {
     user_token_db::renew_token(&id, &expires_type)
 }
 
 /// 获取令牌 IP 绑定
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn get_token_ip_bindings(token_id: String) -> Result<Vec<TokenIpBinding>, String> {
     user_token_db::get_token_ips(&token_id)
 }
@@ -85,7 +93,7 @@ pub struct UserTokenStats {
 }
 
 /// 获取简单的统计信息
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub async fn get_user_token_summary() -> Result<UserTokenStats, String> {
     let tokens = user_token_db::list_tokens()?;
     let active_tokens = tokens.iter().filter(|t| t.enabled).count();
@@ -106,3 +114,31 @@ pub async fn get_user_token_summary() -> Result<UserTokenStats, String> {
         today_requests: 0, // TODO: Implement daily stats query
     })
 }
+
+/// 按 API Key / 月份汇总的用量计费报表 (月份格式 YYYY-MM，起止均包含)
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn get_billing_report(
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<crate::modules::billing::BillingReport, String> {
+    tokio::task::spawn_blocking(move || {
+        crate::modules::billing::generate_report(from.as_deref(), to.as_deref())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 导出计费报表 (format: csv / json，默认 csv)
+#[cfg_attr(feature = "desktop", tauri::command)]
+pub async fn export_billing_report(
+    from: Option<String>,
+    to: Option<String>,
+    format: Option<String>,
+) -> Result<String, String> {
+    let format = crate::modules::billing::ExportFormat::parse(format.as_deref())?;
+    tokio::task::spawn_blocking(move || {
+        crate::modules::billing::export_report(from.as_deref(), to.as_deref(), format)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
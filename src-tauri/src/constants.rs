@@ -1,4 +1,5 @@
-use std::sync::LazyLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, RwLock};
 use regex::Regex;
 
 /// URL to fetch the latest Antigravity version
@@ -15,6 +16,13 @@ const KNOWN_STABLE_VERSION: &str = "4.1.28";
 const KNOWN_STABLE_ELECTRON: &str = "39.2.3";
 const KNOWN_STABLE_CHROME: &str = "132.0.6834.160";
 
+/// Antigravity version → (Electron, Chrome) runtime table, ordered by min version.
+/// The row with the highest `min_version` <= the resolved version applies;
+/// rows from `AppConfig.version_sources.runtime_mappings` extend / override it.
+const RUNTIME_TABLE: &[(&str, &str, &str)] = &[
+    ("0.0.0", KNOWN_STABLE_ELECTRON, KNOWN_STABLE_CHROME),
+];
+
 /// Pre-compiled regex for version parsing (X.Y.Z pattern)
 static VERSION_REGEX: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"\d+\.\d+\.\d+").expect("Invalid version regex")
@@ -48,6 +56,7 @@ fn compare_semver(v1: &str, v2: &str) -> std::cmp::Ordering {
 /// Version source for logging
 #[derive(Debug, PartialEq)]
 enum VersionSource {
+    Pinned,
     LocalInstallation,
     KnownStableFallback,
     RemoteAPI,
@@ -64,62 +73,167 @@ struct VersionConfig {
     chrome: String,
 }
 
-/// Try to fetch the latest Antigravity version from the remote update server.
-/// Runs in a dedicated OS thread to avoid blocking Tokio's async runtime.
-/// Returns None on any network/parse failure — always non-fatal, 5s timeout.
+fn version_sources_config() -> crate::models::VersionSourcesConfig {
+    crate::modules::config::load_app_config()
+        .map(|cfg| cfg.version_sources)
+        .unwrap_or_default()
+}
+
+/// Ordered version source URLs: user-configured sources first, then the built-in
+/// updater / changelog endpoints
+fn version_source_urls(sources: &crate::models::VersionSourcesConfig) -> Vec<String> {
+    let mut urls: Vec<String> = sources
+        .source_urls
+        .iter()
+        .map(|u| u.trim().to_string())
+        .filter(|u| !u.is_empty())
+        .collect();
+    for builtin in [VERSION_URL, CHANGELOG_URL] {
+        if !urls.iter().any(|u| u == builtin) {
+            urls.push(builtin.to_string());
+        }
+    }
+    urls
+}
+
+/// Try to fetch the latest Antigravity version from the configured version sources.
+/// Blocking (5s client timeout per source) — only called from background refresh threads.
+/// Returns None on any network/parse failure — always non-fatal.
 fn try_fetch_remote_version() -> Option<String> {
-    // Spawn a dedicated OS thread so that `reqwest::blocking` never touches
-    // the Tokio thread-pool and cannot trigger the "Cannot block the current
-    // thread from within an asynchronous execution context" panic.
-    let (tx, rx) = std::sync::mpsc::channel::<Option<String>>();
-
-    std::thread::spawn(move || {
-        let result = (|| -> Option<String> {
-            let client = reqwest::blocking::Client::builder()
-                .timeout(std::time::Duration::from_secs(5))
-                .build()
-                .ok()?;
-
-            // 1. Try primary update URL
-            if let Ok(resp) = client.get(VERSION_URL).send() {
-                if let Ok(text) = resp.text() {
-                    if let Some(ver) = parse_version(&text) {
-                        tracing::debug!(remote_version = %ver, "Fetched remote version from VERSION_URL");
-                        return Some(ver);
-                    }
+    // 离线模式下跳过远程版本检查
+    if crate::modules::offline::is_offline() {
+        return None;
+    }
+
+    // 走统一的上游代理 / 连接超时配置
+    let client = crate::modules::http::blocking_reqwest_client(5)?;
+
+    for url in version_source_urls(&version_sources_config()) {
+        if let Ok(resp) = client.get(&url).send() {
+            if let Ok(text) = resp.text() {
+                if let Some(ver) = parse_version(&text) {
+                    tracing::debug!(remote_version = %ver, source = %url, "Fetched remote version");
+                    return Some(ver);
                 }
             }
+        }
+        tracing::debug!(source = %url, "Version source unavailable, trying next");
+    }
 
-            // 2. Try changelog page as secondary fallback
-            if let Ok(resp) = client.get(CHANGELOG_URL).send() {
-                if let Ok(text) = resp.text() {
-                    if let Some(ver) = parse_version(&text) {
-                        tracing::debug!(remote_version = %ver, "Fetched remote version from CHANGELOG_URL");
-                        return Some(ver);
-                    }
-                }
+    tracing::debug!("Unable to fetch remote version; will rely on local/stable floor");
+    None
+}
+
+/// On-disk cache of the last successfully fetched remote version
+const REMOTE_VERSION_CACHE_FILE: &str = "remote_version.json";
+/// Cached remote version is refreshed in the background once older than this
+const REMOTE_VERSION_TTL_SECS: i64 = 12 * 3600;
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+struct RemoteVersionCache {
+    version: String,
+    fetched_at: i64,
+}
+
+impl RemoteVersionCache {
+    fn is_fresh(&self, now: i64) -> bool {
+        now - self.fetched_at < REMOTE_VERSION_TTL_SECS
+    }
+}
+
+fn remote_version_cache_path() -> Option<std::path::PathBuf> {
+    crate::modules::account::get_data_dir()
+        .ok()
+        .map(|dir| dir.join(REMOTE_VERSION_CACHE_FILE))
+}
+
+fn load_remote_version_cache_at(path: &std::path::Path) -> Option<RemoteVersionCache> {
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save_remote_version_cache_at(path: &std::path::Path, cache: &RemoteVersionCache) {
+    match serde_json::to_string_pretty(cache) {
+        Ok(content) => {
+            if let Err(e) = std::fs::write(path, content) {
+                tracing::debug!("Failed to write remote version cache: {}", e);
             }
+        }
+        Err(e) => tracing::debug!("Failed to serialize remote version cache: {}", e),
+    }
+}
 
-            tracing::debug!("Unable to fetch remote version; will rely on local/stable floor");
-            None
-        })();
+/// Resolve the (electron, chrome) pair for an Antigravity version
+fn runtime_for(
+    version: &str,
+    overrides: &[crate::models::RuntimeVersionMapping],
+) -> (String, String) {
+    let builtin = RUNTIME_TABLE
+        .iter()
+        .map(|(min, electron, chrome)| (*min, *electron, *chrome));
+    let configured = overrides
+        .iter()
+        .filter(|m| parse_version(&m.min_version).is_some())
+        .map(|m| (m.min_version.as_str(), m.electron.as_str(), m.chrome.as_str()));
+
+    // 配置的行排在内置行之后，min_version 相同时以配置为准
+    let mut best: Option<(&str, &str, &str)> = None;
+    for row in builtin.chain(configured) {
+        if compare_semver(row.0, version) == std::cmp::Ordering::Greater {
+            continue;
+        }
+        if best.is_none_or(|b| compare_semver(row.0, b.0) != std::cmp::Ordering::Less) {
+            best = Some(row);
+        }
+    }
 
-        let _ = tx.send(result);
-    });
+    best.map(|(_, electron, chrome)| (electron.to_string(), chrome.to_string()))
+        .unwrap_or_else(|| (KNOWN_STABLE_ELECTRON.to_string(), KNOWN_STABLE_CHROME.to_string()))
+}
 
-    // Wait up to 6 seconds (slightly over the client timeout) for the thread
-    rx.recv_timeout(std::time::Duration::from_secs(6))
-        .unwrap_or(None)
+/// Platform segment of the User-Agent for the actual host OS
+pub fn platform_info() -> &'static str {
+    match std::env::consts::OS {
+        "macos" => "Macintosh; Intel Mac OS X 10_15_7",
+        "windows" => "Windows NT 10.0; Win64; x64",
+        "linux" => "X11; Linux x86_64",
+        _ => "X11; Linux x86_64",
+    }
 }
 
 /// Smart version resolution strategy:
-///   best = max(Local Installation, Remote Latest, Known Stable Fallback)
+///   best = max(Local Installation, Remote Latest (cached), Known Stable Fallback)
 ///
 /// This guarantees that even when:
 ///   - The local Antigravity install is outdated, OR
 ///   - Local detection fails (Docker / headless / non-standard path),
+///
 /// ...we always report a version >= the current minimum required by Google's API.
-fn resolve_version_config() -> (VersionConfig, VersionSource) {
+/// No network access here: the remote version comes from the on-disk cache.
+fn resolve_version_config(remote_version: Option<&str>) -> (VersionConfig, VersionSource) {
+    let sources = version_sources_config();
+
+    // 0. Manual pin (AppConfig.version_sources.pinned_version) overrides everything
+    if let Some(pinned) = sources.pinned_version.as_deref() {
+        match parse_version(pinned) {
+            Some(v) if v == pinned.trim() => {
+                let (electron, chrome) = runtime_for(&v, &sources.runtime_mappings);
+                return (
+                    VersionConfig {
+                        version: v,
+                        electron,
+                        chrome,
+                    },
+                    VersionSource::Pinned,
+                );
+            }
+            _ => tracing::warn!(
+                pinned_version = %pinned,
+                "Ignoring invalid pinned_version (expected X.Y.Z)"
+            ),
+        }
+    }
+
     // Floor: static known-stable value (updated with each release of this project)
     let mut best_version = KNOWN_STABLE_VERSION.to_string();
     let mut source = VersionSource::KnownStableFallback;
@@ -151,84 +265,217 @@ fn resolve_version_config() -> (VersionConfig, VersionSource) {
         }
     }
 
-    // 2. Try Remote Version (best-effort; failure is silently ignored)
-    if let Some(remote_v) = try_fetch_remote_version() {
-        if compare_semver(&remote_v, &best_version) > std::cmp::Ordering::Equal {
+    // 2. Remote Version (from cache; refreshed in the background)
+    if let Some(remote_v) = remote_version {
+        if compare_semver(remote_v, &best_version) > std::cmp::Ordering::Equal {
             tracing::info!(
                 remote_version = %remote_v,
                 previous_best = %best_version,
                 "Remote version is newer than current best; upgrading fingerprint version"
             );
-            best_version = remote_v;
+            best_version = remote_v.to_string();
             source = VersionSource::RemoteAPI;
         }
     }
 
+    // Electron / Chrome follow the resolved version so the fingerprint stays coherent
+    let (electron, chrome) = runtime_for(&best_version, &sources.runtime_mappings);
+
     (
         VersionConfig {
             version: best_version,
-            electron: KNOWN_STABLE_ELECTRON.to_string(),
-            chrome: KNOWN_STABLE_CHROME.to_string(),
+            electron,
+            chrome,
         },
         source,
     )
 }
 
-/// Current resolved Antigravity version (e.g., "4.1.28")
-/// Always >= KNOWN_STABLE_VERSION, and >= remote latest when reachable.
-pub static CURRENT_VERSION: LazyLock<String> = LazyLock::new(|| {
-    let (config, _) = resolve_version_config();
-    config.version
+/// Version-derived strings served to request paths
+struct ResolvedVersion {
+    version: String,
+    user_agent: String,
+    native_oauth_user_agent: String,
+}
+
+impl ResolvedVersion {
+    fn build(config: VersionConfig) -> Self {
+        Self {
+            user_agent: format!(
+                "Antigravity/{} ({}) Chrome/{} Electron/{}",
+                config.version,
+                platform_info(),
+                config.chrome,
+                config.electron
+            ),
+            native_oauth_user_agent: format!("vscode/1.X.X (Antigravity/{})", config.version),
+            version: config.version,
+        }
+    }
+}
+
+static REFRESH_IN_FLIGHT: AtomicBool = AtomicBool::new(false);
+
+/// Resolved lazily from local installation + cached remote version (no network),
+/// then updated in place whenever the background refresh finds a newer remote version
+static RESOLVED: LazyLock<RwLock<ResolvedVersion>> = LazyLock::new(|| {
+    let cache = remote_version_cache_path().and_then(|p| load_remote_version_cache_at(&p));
+    let (config, source) = resolve_version_config(cache.as_ref().map(|c| c.version.as_str()));
+
+    tracing::info!(
+        version = %config.version,
+        source = ?source,
+        cached_remote = ?cache.as_ref().map(|c| c.version.as_str()),
+        "User-Agent initialized"
+    );
+
+    if !cache.is_some_and(|c| c.is_fresh(chrono::Utc::now().timestamp())) {
+        spawn_remote_version_refresh();
+    }
+
+    RwLock::new(ResolvedVersion::build(config))
 });
 
+/// Fetch the remote version on a dedicated OS thread (so `reqwest::blocking` never
+/// touches the Tokio thread-pool), persist it, and update the in-memory version.
+fn spawn_remote_version_refresh() {
+    if REFRESH_IN_FLIGHT.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    std::thread::spawn(|| {
+        if let Some(remote_v) = try_fetch_remote_version() {
+            if let Some(path) = remote_version_cache_path() {
+                save_remote_version_cache_at(
+                    &path,
+                    &RemoteVersionCache {
+                        version: remote_v.clone(),
+                        fetched_at: chrono::Utc::now().timestamp(),
+                    },
+                );
+            }
+
+            let (config, source) = resolve_version_config(Some(&remote_v));
+            if let Ok(mut resolved) = RESOLVED.write() {
+                if resolved.version != config.version {
+                    tracing::info!(
+                        version = %config.version,
+                        previous = %resolved.version,
+                        source = ?source,
+                        "User-Agent version updated from background refresh"
+                    );
+                    *resolved = ResolvedVersion::build(config);
+                }
+            }
+        }
+        REFRESH_IN_FLIGHT.store(false, Ordering::SeqCst);
+    });
+}
+
+/// Version sources last applied to RESOLVED (used to skip no-op config saves)
+static APPLIED_SOURCES: LazyLock<RwLock<crate::models::VersionSourcesConfig>> =
+    LazyLock::new(|| RwLock::new(version_sources_config()));
+
+/// Called after the config is saved: when the pin / sources changed, re-resolve the
+/// version and refresh the remote version in the background so they take effect immediately
+pub fn update_version_sources(sources: &crate::models::VersionSourcesConfig) {
+    if let Ok(mut applied) = APPLIED_SOURCES.write() {
+        if *applied == *sources {
+            return;
+        }
+        *applied = sources.clone();
+    }
+
+    let cache = remote_version_cache_path().and_then(|p| load_remote_version_cache_at(&p));
+    let (config, source) = resolve_version_config(cache.as_ref().map(|c| c.version.as_str()));
+    if let Ok(mut resolved) = RESOLVED.write() {
+        if resolved.version != config.version {
+            tracing::info!(
+                version = %config.version,
+                previous = %resolved.version,
+                source = ?source,
+                "User-Agent version updated after config change"
+            );
+            *resolved = ResolvedVersion::build(config);
+        }
+    }
+    if source != VersionSource::Pinned {
+        spawn_remote_version_refresh();
+    }
+}
+
+/// Refresh the cached remote version in the background if it is missing or expired
+pub fn refresh_remote_version_if_stale() {
+    let fresh = remote_version_cache_path()
+        .and_then(|p| load_remote_version_cache_at(&p))
+        .is_some_and(|c| c.is_fresh(chrono::Utc::now().timestamp()));
+    if !fresh {
+        spawn_remote_version_refresh();
+    }
+}
+
+/// Periodically re-checks the remote version cache TTL (hourly)
+pub fn start_remote_version_refresh_job() {
+    crate::utils::runtime::spawn(async {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(3600));
+        loop {
+            interval.tick().await;
+            refresh_remote_version_if_stale();
+        }
+    });
+}
+
+/// Current resolved Antigravity version (e.g., "4.1.28")
+/// Always >= KNOWN_STABLE_VERSION, and >= the cached remote latest.
+pub fn current_version() -> String {
+    RESOLVED
+        .read()
+        .map(|r| r.version.clone())
+        .unwrap_or_else(|_| KNOWN_STABLE_VERSION.to_string())
+}
+
 /// Native OAuth Authorization User-Agent
-pub static NATIVE_OAUTH_USER_AGENT: LazyLock<String> = LazyLock::new(|| {
-    format!("vscode/1.X.X (Antigravity/{})", CURRENT_VERSION.as_str())
-});
+pub fn native_oauth_user_agent() -> String {
+    RESOLVED
+        .read()
+        .map(|r| r.native_oauth_user_agent.clone())
+        .unwrap_or_else(|_| format!("vscode/1.X.X (Antigravity/{})", KNOWN_STABLE_VERSION))
+}
 
 /// Current resolved Antigravity version (e.g., "4.1.28")
 pub fn get_current_version() -> String {
     env!("CARGO_PKG_VERSION").to_string()
 }
 
-/// Returns a full User-Agent string for the current version
+/// Returns a full User-Agent string for the current version on the host platform
 /// "Antigravity/4.1.28 (Macintosh; Intel Mac OS X 10_15_7) Chrome/132.0.6834.160 Electron/39.2.3"
 pub fn get_default_user_agent() -> String {
-    format!("Antigravity/{} (Macintosh; Intel Mac OS X 10_15_7) Chrome/132.0.6834.160 Electron/39.2.3", env!("CARGO_PKG_VERSION"))
+    let version = env!("CARGO_PKG_VERSION");
+    let (electron, chrome) = runtime_for(version, &version_sources_config().runtime_mappings);
+    format!(
+        "Antigravity/{} ({}) Chrome/{} Electron/{}",
+        version,
+        platform_info(),
+        chrome,
+        electron
+    )
 }
 
 /// Global Session ID (generated once per app launch)
+/// Used only when per-account upstream sessions are disabled (see proxy::session_pool)
 pub static SESSION_ID: LazyLock<String> = LazyLock::new(|| {
     uuid::Uuid::new_v4().to_string()
 });
 
-/// Returns the best version choice between local and remote
-/// Version selection: max(local installation, remote latest, known stable 4.1.28)
+/// Returns the User-Agent built from the best version choice
+/// Version selection: max(local installation, cached remote latest, known stable 4.1.28)
 /// This prevents model rejection due to outdated client version headers.
-pub static USER_AGENT: LazyLock<String> = LazyLock::new(|| {
-    let (config, source) = resolve_version_config();
-
-    tracing::info!(
-        version = %config.version,
-        source = ?source,
-        "User-Agent initialized"
-    );
-
-    let platform_info = match std::env::consts::OS {
-        "macos" => "Macintosh; Intel Mac OS X 10_15_7",
-        "windows" => "Windows NT 10.0; Win64; x64",
-        "linux" => "X11; Linux x86_64",
-        _ => "X11; Linux x86_64",
-    };
-
-    format!(
-        "Antigravity/{} ({}) Chrome/{} Electron/{}",
-        config.version,
-        platform_info,
-        config.chrome,
-        config.electron
-    )
-});
+pub fn user_agent() -> String {
+    RESOLVED
+        .read()
+        .map(|r| r.user_agent.clone())
+        .unwrap_or_else(|_| get_default_user_agent())
+}
 
 #[cfg(test)]
 mod tests {
@@ -308,4 +555,89 @@ mod tests {
         };
         assert_eq!(best, "4.1.28");
     }
+
+    #[test]
+    fn test_remote_version_cache_roundtrip_and_ttl() {
+        let path = std::env::temp_dir().join(format!("remote_version_{}.json", uuid::Uuid::new_v4()));
+        assert_eq!(load_remote_version_cache_at(&path), None);
+
+        let cache = RemoteVersionCache {
+            version: "4.2.0".to_string(),
+            fetched_at: 1_000,
+        };
+        save_remote_version_cache_at(&path, &cache);
+        assert_eq!(load_remote_version_cache_at(&path), Some(cache.clone()));
+
+        assert!(cache.is_fresh(1_000 + REMOTE_VERSION_TTL_SECS - 1));
+        assert!(!cache.is_fresh(1_000 + REMOTE_VERSION_TTL_SECS));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_resolved_version_strings() {
+        let resolved = ResolvedVersion::build(VersionConfig {
+            version: "4.2.0".to_string(),
+            electron: KNOWN_STABLE_ELECTRON.to_string(),
+            chrome: KNOWN_STABLE_CHROME.to_string(),
+        });
+        assert!(resolved.user_agent.starts_with("Antigravity/4.2.0 ("));
+        assert!(resolved.user_agent.ends_with(&format!("Chrome/{} Electron/{}", KNOWN_STABLE_CHROME, KNOWN_STABLE_ELECTRON)));
+        assert_eq!(resolved.native_oauth_user_agent, "vscode/1.X.X (Antigravity/4.2.0)");
+    }
+
+    #[test]
+    fn test_version_source_urls_order() {
+        let sources = crate::models::VersionSourcesConfig {
+            pinned_version: None,
+            source_urls: vec![
+                " https://example.com/version ".to_string(),
+                String::new(),
+                CHANGELOG_URL.to_string(),
+            ],
+            runtime_mappings: Vec::new(),
+        };
+        assert_eq!(
+            version_source_urls(&sources),
+            vec![
+                "https://example.com/version".to_string(),
+                CHANGELOG_URL.to_string(),
+                VERSION_URL.to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_runtime_for_picks_highest_applicable_row() {
+        let overrides = vec![
+            crate::models::RuntimeVersionMapping {
+                min_version: "4.2.0".to_string(),
+                electron: "40.0.0".to_string(),
+                chrome: "134.0.0.0".to_string(),
+            },
+            crate::models::RuntimeVersionMapping {
+                min_version: "invalid".to_string(),
+                electron: "1".to_string(),
+                chrome: "1".to_string(),
+            },
+        ];
+        assert_eq!(
+            runtime_for("4.1.28", &overrides),
+            (KNOWN_STABLE_ELECTRON.to_string(), KNOWN_STABLE_CHROME.to_string())
+        );
+        assert_eq!(
+            runtime_for("4.2.3", &overrides),
+            ("40.0.0".to_string(), "134.0.0.0".to_string())
+        );
+
+        // 与内置行 min_version 相同时以配置为准
+        let same_min = vec![crate::models::RuntimeVersionMapping {
+            min_version: "0.0.0".to_string(),
+            electron: "38.0.0".to_string(),
+            chrome: "130.0.0.0".to_string(),
+        }];
+        assert_eq!(
+            runtime_for("4.1.28", &same_min),
+            ("38.0.0".to_string(), "130.0.0.0".to_string())
+        );
+    }
 }
@@ -0,0 +1,40 @@
+// 嵌入式 API
+// 供下游自动化工具直接嵌入账号池与轮换引擎，而不是调用桌面应用或 abv-cli。
+// 以 default-features = false 依赖本 crate 时不编译 Tauri、窗口插件、托盘与 GTK；
+// 数据目录与桌面端共用 (可通过 ABV_DATA_DIR 指定)，因此嵌入方与桌面端看到的是同一个账号池。
+
+use std::path::PathBuf;
+
+pub use crate::models::{Account, AppConfig, QuotaData, TokenData};
+pub use crate::proxy::token_manager::TokenManager;
+
+/// 当前使用的数据目录
+pub fn data_dir() -> Result<PathBuf, String> {
+    crate::modules::account::get_data_dir()
+}
+
+pub fn load_config() -> Result<AppConfig, String> {
+    crate::modules::config::load_app_config()
+}
+
+pub fn save_config(config: &AppConfig) -> Result<(), String> {
+    crate::modules::config::save_app_config(config)
+}
+
+pub fn list_accounts() -> Result<Vec<Account>, String> {
+    crate::modules::account::list_accounts()
+}
+
+/// 加载账号池并返回轮换引擎，通过 get_token 按配置的调度策略取账号
+pub async fn token_manager() -> Result<TokenManager, String> {
+    let manager = TokenManager::new(data_dir()?);
+    manager.load_accounts().await?;
+    Ok(manager)
+}
+
+/// 在当前线程运行完整的反代服务 (包括后台任务)，直到 shutdown 完成后返回。
+/// 会创建自己的 Tokio 运行时，不能在异步上下文中调用；启动失败时与 headless 模式一样退出进程。
+pub fn run_proxy_until(shutdown: impl std::future::Future<Output = ()>) {
+    crate::init_databases();
+    crate::run_headless_until(shutdown);
+}
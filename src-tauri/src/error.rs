@@ -1,4 +1,6 @@
 use serde::Serialize;
+use std::collections::BTreeMap;
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -12,6 +14,7 @@ pub enum AppError {
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
+    #[cfg(feature = "desktop")]
     #[error("Tauri error: {0}")]
     Tauri(#[from] tauri::Error),
 
@@ -26,6 +29,163 @@ pub enum AppError {
 
     #[error("Unknown error: {0}")]
     Unknown(String),
+
+    /// 429 rate limit (per-minute / capacity); worth retrying after `retry_after`
+    #[error("Rate limited: {message}")]
+    RateLimited {
+        retry_after: Option<Duration>,
+        message: String,
+    },
+
+    /// 429 QUOTA_EXHAUSTED: the account's quota for `model` is used up until reset
+    #[error("Quota exhausted: {message}")]
+    QuotaExhausted {
+        model: Option<String>,
+        message: String,
+    },
+
+    /// 403: account not allowed (validation required, suspended, region ...)
+    #[error("Forbidden: {reason}")]
+    Forbidden { reason: String },
+
+    /// 5xx / 529: upstream overloaded or down; not the account's fault
+    #[error("Upstream unavailable: {message}")]
+    UpstreamUnavailable { status: u16, message: String },
+
+    /// 401 / invalid_grant: access or refresh token rejected
+    #[error("Token invalid: {0}")]
+    TokenInvalid(String),
+}
+
+/// OAuth refresh failures whose refresh_token was revoked or expired (account must be re-authorized)
+pub fn is_invalid_grant(message: &str) -> bool {
+    message.contains("invalid_grant")
+}
+
+/// `error.details[].reason` of a Google API error body (e.g. "QUOTA_EXHAUSTED")
+pub fn upstream_error_reason(body: &str) -> Option<String> {
+    let json: serde_json::Value = serde_json::from_str(body.trim()).ok()?;
+    json.get("error")?
+        .get("details")?
+        .as_array()?
+        .iter()
+        .find_map(|d| d.get("reason").and_then(|v| v.as_str()))
+        .map(str::to_string)
+}
+
+/// `error.message` of an upstream error body, or the body itself
+fn upstream_error_message(body: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(body.trim())
+        .ok()
+        .and_then(|json| {
+            json.get("error")
+                .and_then(|e| e.get("message").or_else(|| e.get("error_description")))
+                .or_else(|| json.get("error_description"))
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| body.trim().to_string())
+}
+
+impl AppError {
+    /// Map an upstream error response into the taxonomy.
+    /// This is the one place that inspects upstream status codes and bodies; callers match on variants.
+    pub fn from_upstream(status: u16, body: &str, model: Option<&str>) -> Self {
+        let message = format!("{} - {}", status, upstream_error_message(body));
+        let reason = upstream_error_reason(body);
+        if is_invalid_grant(body) || status == 401 {
+            return AppError::TokenInvalid(message);
+        }
+        match status {
+            403 => AppError::Forbidden {
+                reason: reason.unwrap_or(message),
+            },
+            429 => {
+                let lower = body.to_lowercase();
+                let quota = match reason.as_deref() {
+                    Some(r) => r == "QUOTA_EXHAUSTED",
+                    // 无 reason 时按文本判断，分钟级限流优先
+                    None => {
+                        !(lower.contains("per minute") || lower.contains("rate limit"))
+                            && (lower.contains("exhausted") || lower.contains("quota"))
+                    }
+                };
+                if quota {
+                    AppError::QuotaExhausted {
+                        model: model.map(str::to_string),
+                        message,
+                    }
+                } else {
+                    AppError::RateLimited {
+                        retry_after: crate::proxy::upstream::retry::parse_retry_delay(body)
+                            .map(Duration::from_millis),
+                        message,
+                    }
+                }
+            }
+            500..=599 => AppError::UpstreamUnavailable { status, message },
+            _ => AppError::Network(format!("API Error: {}", message), Some(status)),
+        }
+    }
+
+    /// OAuth token endpoint failure (refresh / exchange)
+    pub fn from_oauth(message: String) -> Self {
+        if is_invalid_grant(&message) {
+            AppError::TokenInvalid(message)
+        } else {
+            AppError::OAuth(message)
+        }
+    }
+
+    /// HTTP status the error corresponds to, if any
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            AppError::Network(_, status) => *status,
+            AppError::RateLimited { .. } | AppError::QuotaExhausted { .. } => Some(429),
+            AppError::Forbidden { .. } => Some(403),
+            AppError::UpstreamUnavailable { status, .. } => Some(*status),
+            AppError::TokenInvalid(_) => Some(401),
+            _ => None,
+        }
+    }
+
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            AppError::RateLimited { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+
+    /// Failure tied to the account (switching accounts may succeed), as opposed to an upstream outage
+    pub fn is_account_specific(&self) -> bool {
+        matches!(
+            self,
+            AppError::RateLimited { .. }
+                | AppError::QuotaExhausted { .. }
+                | AppError::Forbidden { .. }
+                | AppError::TokenInvalid(_)
+        )
+    }
+
+    /// Stable machine-readable kind for UI messaging
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AppError::Database(_) => "database",
+            AppError::Network(..) => "network",
+            AppError::Io(_) => "io",
+            #[cfg(feature = "desktop")]
+            AppError::Tauri(_) => "tauri",
+            AppError::OAuth(_) => "oauth",
+            AppError::Config(_) => "config",
+            AppError::Account(_) => "account",
+            AppError::Unknown(_) => "unknown",
+            AppError::RateLimited { .. } => "rate_limited",
+            AppError::QuotaExhausted { .. } => "quota_exhausted",
+            AppError::Forbidden { .. } => "forbidden",
+            AppError::UpstreamUnavailable { .. } => "upstream_unavailable",
+            AppError::TokenInvalid(_) => "token_invalid",
+        }
+    }
 }
 
 impl From<reqwest::Error> for AppError {
@@ -54,3 +214,181 @@ impl Serialize for AppError {
 
 // Implement alias for Result to simplify usage
 pub type AppResult<T> = Result<T, AppError>;
+
+/// Error returned to the frontend: a stable code plus parameters for localized rendering
+/// (`errors.backend.<code>` in the locale files, see `modules::i18n::localize_error`).
+/// `message` keeps the technical text for logs and as the fallback when no translation exists.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CommandError {
+    pub code: String,
+    pub params: BTreeMap<String, String>,
+    pub message: String,
+}
+
+/// Split a `snake_case_code: detail` error string; strings without a code prefix are "unknown"
+pub fn split_error_code(error: &str) -> (&str, &str) {
+    let (head, detail) = match error.split_once(':') {
+        Some((head, detail)) => (head.trim(), detail.trim()),
+        None => (error.trim(), ""),
+    };
+    let is_code = head.len() > 1
+        && head.starts_with(|c: char| c.is_ascii_lowercase())
+        && head
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if is_code {
+        (head, detail)
+    } else {
+        ("unknown", error.trim())
+    }
+}
+
+impl CommandError {
+    pub fn new(code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            code: code.into(),
+            params: BTreeMap::new(),
+            message: message.into(),
+        }
+    }
+
+    pub fn with_param(mut self, key: &str, value: impl ToString) -> Self {
+        self.params.insert(key.to_string(), value.to_string());
+        self
+    }
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(error: String) -> Self {
+        let (code, detail) = split_error_code(&error);
+        CommandError::new(code, error.as_str()).with_param("detail", detail)
+    }
+}
+
+impl From<&str> for CommandError {
+    fn from(error: &str) -> Self {
+        CommandError::from(error.to_string())
+    }
+}
+
+impl From<AppError> for CommandError {
+    fn from(error: AppError) -> Self {
+        let message = error.to_string();
+        let mut out = CommandError::new(error.kind(), message.as_str());
+        match &error {
+            AppError::RateLimited {
+                retry_after,
+                message,
+            } => {
+                out = out.with_param("detail", message);
+                if let Some(after) = retry_after {
+                    out = out.with_param("retry_after", after.as_secs().max(1));
+                }
+            }
+            AppError::QuotaExhausted { model, message } => {
+                out = out
+                    .with_param("detail", message)
+                    .with_param("model", model.as_deref().unwrap_or("-"));
+            }
+            AppError::Forbidden { reason } => out = out.with_param("detail", reason),
+            AppError::UpstreamUnavailable { status, message } => {
+                out = out
+                    .with_param("detail", message)
+                    .with_param("status", status);
+            }
+            AppError::Network(detail, status) => {
+                out = out.with_param("detail", detail);
+                if let Some(status) = status {
+                    out = out.with_param("status", status);
+                }
+            }
+            AppError::Account(detail) | AppError::Unknown(detail) => {
+                // 模块层的 "code: detail" 字符串沿用其错误码
+                let parsed = CommandError::from(detail.clone());
+                if parsed.code != "unknown" {
+                    return CommandError { message, ..parsed };
+                }
+                out = out.with_param("detail", detail);
+            }
+            AppError::TokenInvalid(detail) | AppError::OAuth(detail) | AppError::Config(detail) => {
+                out = out.with_param("detail", detail)
+            }
+            other => out = out.with_param("detail", other.to_string()),
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upstream_taxonomy() {
+        let quota = r#"{"error":{"code":429,"message":"Resource exhausted","details":[{"reason":"QUOTA_EXHAUSTED"}]}}"#;
+        assert!(matches!(
+            AppError::from_upstream(429, quota, Some("gemini-3-pro")),
+            AppError::QuotaExhausted { model: Some(m), .. } if m == "gemini-3-pro"
+        ));
+
+        let rate = r#"{"error":{"message":"Too many requests per minute","details":[
+            {"@type":"type.googleapis.com/google.rpc.RetryInfo","reason":"RATE_LIMIT_EXCEEDED","retryDelay":"1.5s"}]}}"#;
+        let err = AppError::from_upstream(429, rate, None);
+        assert_eq!(err.kind(), "rate_limited");
+        assert_eq!(err.retry_after(), Some(Duration::from_millis(1500)));
+
+        let forbidden = r#"{"error":{"details":[{"reason":"VALIDATION_REQUIRED"}]}}"#;
+        assert!(matches!(
+            AppError::from_upstream(403, forbidden, None),
+            AppError::Forbidden { reason } if reason == "VALIDATION_REQUIRED"
+        ));
+
+        let grant =
+            r#"{"error":"invalid_grant","error_description":"Token has been expired or revoked."}"#;
+        let err = AppError::from_upstream(400, grant, None);
+        assert!(matches!(err, AppError::TokenInvalid(_)) && err.is_account_specific());
+        assert!(matches!(
+            AppError::from_oauth(format!("Refresh failed: {}", grant)),
+            AppError::TokenInvalid(_)
+        ));
+
+        let err = AppError::from_upstream(503, "overloaded", None);
+        assert!(!err.is_account_specific());
+        assert_eq!(err.status(), Some(503));
+        assert_eq!(AppError::from_upstream(404, "", None).status(), Some(404));
+    }
+
+    #[test]
+    fn test_command_error_codes() {
+        let err = CommandError::from("failed_to_read_account_index: permission denied".to_string());
+        assert_eq!(err.code, "failed_to_read_account_index");
+        assert_eq!(err.params["detail"], "permission denied");
+        assert_eq!(
+            err.message,
+            "failed_to_read_account_index: permission denied"
+        );
+
+        assert_eq!(split_error_code("app_locked"), ("app_locked", ""));
+        assert_eq!(
+            split_error_code("Token refresh failed: x"),
+            ("unknown", "Token refresh failed: x")
+        );
+
+        let err = CommandError::from(AppError::QuotaExhausted {
+            model: Some("claude-opus-4".to_string()),
+            message: "429".to_string(),
+        });
+        assert_eq!(err.code, "quota_exhausted");
+        assert_eq!(err.params["model"], "claude-opus-4");
+
+        let err = CommandError::from(AppError::Account("account_not_found: abc".to_string()));
+        assert_eq!(err.code, "account_not_found");
+        assert_eq!(err.message, "Account error: account_not_found: abc");
+    }
+}
@@ -1,3 +1,6 @@
+// 不带 desktop 特性时 Tauri 命令不会注册，只被命令调用的代码在库构建中视为未使用
+#![cfg_attr(not(feature = "desktop"), allow(dead_code, unused_imports))]
+
 mod models;
 mod modules;
 mod commands;
@@ -5,17 +8,23 @@ mod utils;
 mod proxy;  // Proxy service module
 pub mod error;
 pub mod constants;
+pub mod cli;
+pub mod engine;
+mod service;
 
+#[cfg(feature = "desktop")]
 use tauri::Manager;
 use modules::logger;
 use tracing::{info, warn, error};
 use std::sync::Arc;
 
+#[cfg(feature = "desktop")]
 #[derive(Clone, Copy)]
 struct AppRuntimeFlags {
     tray_enabled: bool,
 }
 
+#[cfg(any(feature = "desktop", target_os = "linux"))]
 fn env_flag_enabled(name: &str) -> bool {
     std::env::var(name)
         .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"))
@@ -32,6 +41,7 @@ fn is_wayland_session() -> bool {
             .unwrap_or(false)
 }
 
+#[cfg(feature = "desktop")]
 fn should_enable_tray() -> bool {
     if env_flag_enabled("ANTIGRAVITY_DISABLE_TRAY") {
         info!("Tray disabled by ANTIGRAVITY_DISABLE_TRAY");
@@ -100,26 +110,39 @@ fn increase_nofile_limit() {
 }
 
 // Test command
+#[cfg(feature = "desktop")]
 #[tauri::command]
 fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run() {
-    // Check for headless mode
-    let args: Vec<String> = std::env::args().collect();
-    let is_headless = args.iter().any(|arg| arg == "--headless");
-
-    // Increase file descriptor limit (macOS only)
-    #[cfg(target_os = "macos")]
-    increase_nofile_limit();
+/// Initialize the SQLite databases shared by GUI, headless and CLI modes
+pub(crate) fn init_databases() {
+    // Finish or roll back an account add/delete/reorder interrupted by a crash
+    if let Err(e) = modules::account::recover_account_journal() {
+        error!("Failed to recover account journal: {}", e);
+    }
 
-    // Initialize logger
-    logger::init_logger();
+    // Remove `*.tmp.*` files left behind by writes interrupted by a crash
+    match modules::account::collect_stale_temp_files() {
+        Ok(0) => {}
+        Ok(n) => info!("Collected {} stale temp file(s) from the data directory", n),
+        Err(e) => warn!("Failed to collect stale temp files: {}", e),
+    }
 
-    #[cfg(target_os = "linux")]
-    configure_linux_gdk_backend();
+    // Report (but do not auto-repair) index / account file inconsistencies
+    match modules::account::verify_data_integrity() {
+        Ok(report) if !report.is_clean() => warn!(
+            "Data integrity issues: {} orphan file(s), {} dangling entr(ies), {} unreadable file(s), {} duplicate email(s); {} repair action(s) available",
+            report.orphan_files.len(),
+            report.dangling_entries.len(),
+            report.unreadable_files.len(),
+            report.duplicate_emails.len(),
+            report.repair_plan.len()
+        ),
+        Ok(_) => {}
+        Err(e) => error!("Failed to verify data integrity: {}", e),
+    }
 
     // Initialize token stats database
     if let Err(e) = modules::token_stats::init_db() {
@@ -135,161 +158,186 @@ pub fn run() {
     if let Err(e) = modules::user_token_db::init_db() {
         error!("Failed to initialize user token database: {}", e);
     }
+}
 
-    if is_headless {
-        info!("Starting in HEADLESS mode...");
+/// Run the proxy service without Tauri until Ctrl-C (`--headless` / `abv-cli proxy run`)
+pub(crate) fn run_headless() {
+    run_headless_until(async {
+        tokio::signal::ctrl_c().await.ok();
+    });
+}
 
-        let rt = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
-        rt.block_on(async {
-            // Initialize states manually
-            // [FIX] Initialize log bridge for headless mode
-            // Pass a dummy app handle or None since we don't have a Tauri app handle in headless mode
-            // Actually log_bridge relies on AppHandle to emit events.
-            // In headless mode, we don't emit events, but we still need the buffer.
-            // We need to modify log_bridge to handle missing AppHandle gracefully, which it already does (Option).
-            // But init_log_bridge requires AppHandle.
-            // We'll skip passing AppHandle for now and just leverage the global buffer capability.
-            // Since init_log_bridge takes AppHandle, we might need a separate init for headless or just not call init and rely on lazy init of buffer?
-            // Checking log_bridge code again...
-            // "static LOG_BUFFER: OnceLock<...> = OnceLock::new();" -> lazy init.
-            // So we just need to ensure the tracing layer is added.
-            // And `logger::init_logger()` adds the layer?
-            // Let's check `modules::logger`.
-
-            let proxy_state = commands::proxy::ProxyServiceState::new();
-            let cf_state = Arc::new(commands::cloudflared::CloudflaredState::new());
-
-            // Load config
-            match modules::config::load_app_config() {
-                Ok(mut config) => {
-                    let mut modified = false;
-                    // Headless/docker 默认允许 LAN 访问（绑定 0.0.0.0）
-                    // 若设置 ABV_BIND_LOCAL_ONLY，则仅绑定 127.0.0.1
-                    let bind_local_only = std::env::var("ABV_BIND_LOCAL_ONLY")
-                        .map(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
-                        .unwrap_or(false);
-                    if bind_local_only {
-                        config.proxy.allow_lan_access = false;
-                        modified = true;
-                    } else {
-                        config.proxy.allow_lan_access = true;
-                    }
+/// Run the proxy service without Tauri until `shutdown` resolves (also used by `abv-cli service run`)
+pub(crate) fn run_headless_until(shutdown: impl std::future::Future<Output = ()>) {
+    modules::headless::enable();
+    let rt = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
+    rt.block_on(async {
+        // Initialize states manually
+        // [FIX] Initialize log bridge for headless mode
+        // Pass a dummy app handle or None since we don't have a Tauri app handle in headless mode
+        // Actually log_bridge relies on AppHandle to emit events.
+        // In headless mode, we don't emit events, but we still need the buffer.
+        // We need to modify log_bridge to handle missing AppHandle gracefully, which it already does (Option).
+        // But init_log_bridge requires AppHandle.
+        // We'll skip passing AppHandle for now and just leverage the global buffer capability.
+        // Since init_log_bridge takes AppHandle, we might need a separate init for headless or just not call init and rely on lazy init of buffer?
+        // Checking log_bridge code again...
+        // "static LOG_BUFFER: OnceLock<...> = OnceLock::new();" -> lazy init.
+        // So we just need to ensure the tracing layer is added.
+        // And `logger::init_logger()` adds the layer?
+        // Let's check `modules::logger`.
+
+        let proxy_state = commands::proxy::ProxyServiceState::new();
+        let cf_state = Arc::new(commands::cloudflared::CloudflaredState::new());
+
+        let settings = match modules::headless::HeadlessSettings::from_env() {
+            Ok(settings) => settings,
+            Err(e) => {
+                error!("Invalid headless environment: {}", e);
+                std::process::exit(1);
+            }
+        };
 
-                    // [FIX] Force auth mode to AllExceptHealth in headless mode if it's Off or Auto
-                    // This ensures Web UI login validation works properly
-                    if matches!(config.proxy.auth_mode, crate::proxy::ProxyAuthMode::Off | crate::proxy::ProxyAuthMode::Auto) {
-                        info!("Headless mode: Forcing auth_mode to AllExceptHealth for Web UI security");
-                        config.proxy.auth_mode = crate::proxy::ProxyAuthMode::AllExceptHealth;
-                        modified = true;
-                    }
+        // Without a data directory there are no accounts or config to serve
+        if let Err(e) = modules::data_dir::resolve() {
+            error!("{}. Set ABV_DATA_DIR to a local directory and restart.", e);
+            std::process::exit(1);
+        }
 
-                    // [NEW] 支持通过环境变量注入 API Key
-                    // 优先级：ABV_API_KEY > API_KEY > 配置文件
-                    let env_key = std::env::var("ABV_API_KEY")
-                        .or_else(|_| std::env::var("API_KEY"))
-                        .ok();
-
-                    if let Some(key) = env_key {
-                        if !key.trim().is_empty() {
-                            info!("Using API Key from environment variable");
-                            config.proxy.api_key = key;
-                            modified = true;
-                        }
-                    }
+        // Crash report left by the previous run
+        modules::crash_report::announce_previous();
 
-                    // [NEW] 支持通过环境变量注入 Web UI 密码
-                    // 优先级：ABV_WEB_PASSWORD > WEB_PASSWORD > 配置文件
-                    let env_web_password = std::env::var("ABV_WEB_PASSWORD")
-                        .or_else(|_| std::env::var("WEB_PASSWORD"))
-                        .ok();
-
-                    if let Some(pwd) = env_web_password {
-                        if !pwd.trim().is_empty() {
-                            info!("Using Web UI Password from environment variable");
-                            config.proxy.admin_password = Some(pwd);
-                            modified = true;
-                        }
-                    }
+        // Self-test before the proxy binds its port (failures are logged)
+        modules::startup_report::run();
 
-                    // [NEW] 支持通过环境变量注入鉴权模式
-                    // 优先级：ABV_AUTH_MODE > AUTH_MODE > 配置文件
-                    let env_auth_mode = std::env::var("ABV_AUTH_MODE")
-                        .or_else(|_| std::env::var("AUTH_MODE"))
-                        .ok();
-
-                    if let Some(mode_str) = env_auth_mode {
-                        let mode = match mode_str.to_lowercase().as_str() {
-                            "off" => Some(crate::proxy::ProxyAuthMode::Off),
-                            "strict" => Some(crate::proxy::ProxyAuthMode::Strict),
-                            "all_except_health" => Some(crate::proxy::ProxyAuthMode::AllExceptHealth),
-                            "auto" => Some(crate::proxy::ProxyAuthMode::Auto),
-                            _ => {
-                                warn!("Invalid AUTH_MODE: {}, ignoring", mode_str);
-                                None
-                            }
-                        };
-                        if let Some(m) = mode {
-                            info!("Using Auth Mode from environment variable: {:?}", m);
-                            config.proxy.auth_mode = m;
-                            modified = true;
-                        }
-                    }
+        // Load config
+        match modules::config::load_app_config() {
+            Ok(mut config) => {
+                let modified = settings.apply(&mut config);
 
-                    info!("--------------------------------------------------");
-                    info!("🚀 Headless mode proxy service starting...");
-                    info!("📍 Port: {}", config.proxy.port);
-                    info!("🔑 Current API Key: {}", config.proxy.api_key);
-                    if let Some(ref pwd) = config.proxy.admin_password {
-                        info!("🔐 Web UI Password: {}", pwd);
+                info!("--------------------------------------------------");
+                info!("🚀 Headless mode proxy service starting...");
+                info!("📍 Port: {}", config.proxy.port);
+                info!("🔑 Current API Key: {}", config.proxy.api_key);
+                if let Some(ref pwd) = config.proxy.admin_password {
+                    info!("🔐 Web UI Password: {}", pwd);
+                } else {
+                    info!("🔐 Web UI Password: (Same as API Key)");
+                }
+                info!("💡 Tips: You can use these keys to login to Web UI and access AI APIs.");
+                info!("💡 Search docker logs or grep gui_config.json to find them.");
+                info!("--------------------------------------------------");
+
+                // [FIX #1460] Persist environment overrides to ensure they are visible in Web UI/load_config
+                if modified {
+                    if let Err(e) = modules::config::save_app_config(&config) {
+                        error!("Failed to persist environment overrides: {}", e);
                     } else {
-                        info!("🔐 Web UI Password: (Same as API Key)");
-                    }
-                    info!("💡 Tips: You can use these keys to login to Web UI and access AI APIs.");
-                    info!("💡 Search docker logs or grep gui_config.json to find them.");
-                    info!("--------------------------------------------------");
-
-                    // [FIX #1460] Persist environment overrides to ensure they are visible in Web UI/load_config
-                    if modified {
-                        if let Err(e) = modules::config::save_app_config(&config) {
-                            error!("Failed to persist environment overrides: {}", e);
-                        } else {
-                            info!("Environment overrides persisted to gui_config.json");
-                        }
+                        info!("Environment overrides persisted to gui_config.json");
                     }
+                }
 
-                    // Start proxy service
-                    if let Err(e) = commands::proxy::internal_start_proxy_service(
-                        config.proxy,
-                        &proxy_state,
-                        crate::modules::integration::SystemManager::Headless,
-                        cf_state.clone(),
-                    ).await {
-                        error!("Failed to start proxy service in headless mode: {}", e);
-                        std::process::exit(1);
+                if let Some(path) = settings.accounts_file.as_ref() {
+                    if let Err(e) = modules::headless::import_accounts_file(path).await {
+                        error!("Failed to import accounts file: {}", e);
                     }
-
-                    info!("Headless proxy service is running.");
-
-                    // [DISABLED] Start smart scheduler (Automatic warmup disabled as per user request)
-                    // modules::scheduler::start_scheduler(None, proxy_state.clone());
-                    info!("Smart scheduler (Automatic Warmup) is DISABLED.");
-                    info!("Smart scheduler started in headless mode.");
                 }
-                Err(e) => {
-                    error!("Failed to load config for headless mode: {}", e);
+
+                // Start proxy service
+                if let Err(e) = commands::proxy::internal_start_proxy_service(
+                    config.proxy,
+                    &proxy_state,
+                    crate::modules::integration::SystemManager::Headless,
+                    cf_state.clone(),
+                ).await {
+                    error!("Failed to start proxy service in headless mode: {}", e);
                     std::process::exit(1);
                 }
+
+                info!("Headless proxy service is running.");
+
+                // [DISABLED] Start smart scheduler (Automatic warmup disabled as per user request)
+                // modules::scheduler::start_scheduler(crate::modules::integration::SystemManager::Headless, proxy_state.clone());
+                info!("Smart scheduler (Automatic Warmup) is DISABLED.");
+                info!("Smart scheduler started in headless mode.");
+
+                modules::model_catalog::start_catalog_sync_job();
+                modules::scheduler::start_snooze_scheduler();
+                modules::offline::start_connectivity_monitor();
+                modules::retention::start_nightly_cleanup();
+                modules::hygiene::start_hygiene_scheduler();
+                modules::backup::start_backup_scheduler();
+                modules::sync::start_sync_scheduler(proxy_state.clone());
+                modules::digest::start_digest_scheduler();
+                #[cfg(feature = "mock-upstream")]
+                proxy::mock_upstream::start_from_env();
+                constants::start_remote_version_refresh_job();
+            }
+            Err(e) => {
+                error!("Failed to load config for headless mode: {}", e);
+                std::process::exit(1);
             }
+        }
 
-            // Wait for Ctrl-C
-            tokio::signal::ctrl_c().await.ok();
-            info!("Headless mode shutting down");
-        });
+        // Wait for Ctrl-C / service stop
+        shutdown.await;
+        info!("Headless mode shutting down");
+    });
+}
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    // Check for headless mode
+    let args: Vec<String> = std::env::args().collect();
+    let is_headless = args.iter().any(|arg| arg == "--headless");
+
+    // Increase file descriptor limit (macOS only)
+    #[cfg(target_os = "macos")]
+    increase_nofile_limit();
+
+    // Finish a data wipe that could not remove files still in use last time
+    let pending_wipe = modules::wipe::finish_pending_wipe();
+
+    // Initialize logger
+    logger::init_logger();
+    match pending_wipe {
+        Some(Ok(report)) if report.pending.is_empty() => {
+            info!("Finished pending data wipe ({} files)", report.files_removed)
+        }
+        Some(Ok(report)) => warn!(
+            "Pending data wipe incomplete, {} path(s) still in use",
+            report.pending.len()
+        ),
+        Some(Err(e)) => error!("Pending data wipe failed: {}", e),
+        None => {}
+    }
+    // Write a crash report when any thread panics (background tasks otherwise die silently)
+    modules::crash_report::install();
+
+    #[cfg(target_os = "linux")]
+    configure_linux_gdk_backend();
+
+    init_databases();
+
+    // 不带 desktop 特性的库构建没有窗口，只能以 headless 方式运行
+    if is_headless || !cfg!(feature = "desktop") {
+        info!("Starting in HEADLESS mode...");
+
+        run_headless();
         return;
     }
 
+    #[cfg(feature = "desktop")]
+    run_desktop();
+}
+
+#[cfg(feature = "desktop")]
+fn run_desktop() {
     let tray_enabled = should_enable_tray();
 
+    // App lock: start locked when a master password is enabled
+    modules::app_lock::init();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
@@ -310,6 +358,7 @@ pub fn run() {
                     app.set_activation_policy(tauri::ActivationPolicy::Regular).unwrap_or(());
                 });
         }))
+        .plugin(tauri_plugin_deep_link::init())
         .manage(commands::proxy::ProxyServiceState::new())
         .manage(commands::cloudflared::CloudflaredState::new())
         .manage(AppRuntimeFlags { tray_enabled })
@@ -319,6 +368,19 @@ pub fn run() {
             // Initialize log bridge with app handle for debug console
             modules::log_bridge::init_log_bridge(app.handle().clone());
 
+            // Data directory unreachable (e.g. home on a disconnected network share): let the user pick a local one
+            if let Err(e) = modules::data_dir::resolve() {
+                modules::data_dir::prompt_fallback(app.handle().clone(), e);
+            }
+
+            // abv:// links from other tools (switch / refresh quota)
+            modules::deep_link::init(app.handle());
+
+            // Crash report left by the previous run: offer to open it
+            if let Some(report) = modules::crash_report::announce_previous() {
+                modules::crash_report::prompt_open(app.handle(), report);
+            }
+
             // Linux: Workaround for transparent window crash/freeze
             // The transparent window feature is unstable on Linux with WebKitGTK
             // We disable the visual alpha channel to prevent softbuffer-related crashes
@@ -354,6 +416,15 @@ pub fn run() {
             // 立即启动管理服务器 (8045)，以便 Web 端能访问
             let handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
+                // Self-test before the admin server binds the port
+                let report = tokio::task::spawn_blocking(modules::startup_report::run).await;
+                if let Ok(report) = report {
+                    if report.degraded {
+                        warn!("Startup self-test found failures, running in degraded mode");
+                    }
+                }
+                modules::startup_report::publish(&handle);
+
                 // Load config
                 if let Ok(config) = modules::config::load_app_config() {
                     let state = handle.state::<commands::proxy::ProxyServiceState>();
@@ -368,6 +439,12 @@ pub fn run() {
                         Arc::new(cf_state.inner().clone()),
                     ).await {
                         error!("Failed to start admin server: {}", e);
+                        modules::startup_report::update(
+                            "proxy_port",
+                            modules::startup_report::CheckStatus::Failed,
+                            Some(e),
+                        );
+                        modules::startup_report::publish(&handle);
                     } else {
                         info!("Admin server (port {}) started successfully", config.proxy.port);
                     }
@@ -390,9 +467,34 @@ pub fn run() {
 
             // [DISABLED] Start smart scheduler (Automatic warmup disabled as per user request)
             // let scheduler_state = app.handle().state::<commands::proxy::ProxyServiceState>();
-            // modules::scheduler::start_scheduler(Some(app.handle().clone()), scheduler_state.inner().clone());
+            // modules::scheduler::start_scheduler(crate::modules::integration::SystemManager::Desktop(app.handle().clone()), scheduler_state.inner().clone());
             info!("Smart scheduler (Automatic Warmup) is DISABLED.");
 
+            // 上游模型目录每日同步
+            modules::model_catalog::start_catalog_sync_job();
+            // 暂停 (snooze) 到期的账号自动恢复
+            modules::scheduler::start_snooze_scheduler();
+            modules::offline::start_connectivity_monitor();
+            // 夜间按保留策略清理历史数据
+            modules::retention::start_nightly_cleanup();
+            // 每周清理过期冷却 / 指纹历史 / 残留临时文件并校验索引
+            modules::hygiene::start_hygiene_scheduler();
+            // 按间隔上传加密备份到远程目标 (WebDAV / S3)
+            modules::backup::start_backup_scheduler();
+            // 与其他实例双向同步账号池
+            modules::sync::start_sync_scheduler(
+                app.handle()
+                    .state::<commands::proxy::ProxyServiceState>()
+                    .inner()
+                    .clone(),
+            );
+            // 每日汇总需要关注的账号并推送通知
+            modules::digest::start_digest_scheduler();
+            // 开发构建：ABV_MOCK_UPSTREAM_ADDR 指定时启动内置模拟上游
+            #[cfg(feature = "mock-upstream")]
+            proxy::mock_upstream::start_from_env();
+            constants::start_remote_version_refresh_job();
+
             // [PHASE 1] 已整合至 Axum 端口 (8045)，不再单独启动 19527 端口
             info!("Management API integrated into main proxy server (port 8045)");
 
@@ -420,15 +522,22 @@ pub fn run() {
                 }
             }
         })
-        .invoke_handler(tauri::generate_handler![
+        // App lock is enforced here, before any command runs
+        .invoke_handler(modules::app_lock::guard(tauri::generate_handler![
             greet,
             // Account management commands
             commands::list_accounts,
+            commands::list_account_summaries,
             commands::add_account,
+            commands::create_account_share,
+            commands::redeem_account_share,
             commands::delete_account,
             commands::delete_accounts,
             commands::reorder_accounts,
             commands::switch_account,
+            commands::switch_back,
+            commands::get_switch_history,
+            commands::get_last_switch_report,
             commands::export_accounts,
             // Device fingerprint
             commands::get_device_profiles,
@@ -445,9 +554,31 @@ pub fn run() {
             // Quota commands
             commands::fetch_account_quota,
             commands::refresh_all_quotas,
+            commands::refresh_due_quotas,
+            commands::refresh_quota_for_accounts,
+            commands::wipe_all_data,
+            // App lock
+            commands::get_app_lock_status,
+            commands::lock_app,
+            commands::unlock_app,
+            commands::app_lock_activity,
+            commands::set_app_lock_password,
+            commands::set_app_lock_settings,
+            commands::cancel_operation,
+            commands::list_operations,
+            commands::get_log_levels,
+            commands::set_log_levels,
+            commands::remote_request,
+            commands::test_remote_connection,
             // Config commands
             commands::load_config,
             commands::save_config,
+            commands::export_app_config,
+            commands::import_app_config,
+            commands::reset_config_section,
+            commands::list_config_history,
+            commands::diff_config_versions,
+            commands::rollback_config,
             // Additional commands
             commands::prepare_oauth_url,
             commands::start_oauth_login,
@@ -458,6 +589,8 @@ pub fn run() {
             commands::add_codex_account_manual,
             commands::import_codex_from_file,
             commands::import_codex_from_files,
+            commands::preview_account_import,
+            commands::import_accounts_from_files,
             commands::start_codex_oauth_login,
             commands::import_v1_accounts,
             commands::import_from_db,
@@ -466,13 +599,39 @@ pub fn run() {
             commands::save_text_file,
             commands::read_text_file,
             commands::clear_log_cache,
+            commands::export_logs,
+            commands::query_logs,
+            commands::generate_diagnostics_bundle,
+            commands::verify_data_integrity,
+            commands::apply_integrity_repair,
+            commands::get_network_status,
+            commands::set_offline_mode,
+            commands::get_clock_skew,
+            commands::scheduler_status,
+            commands::set_scheduler_job_enabled,
+            commands::get_onboarding_state,
+            commands::complete_onboarding_step,
+            commands::list_available_languages,
+            commands::get_locale_messages,
+            commands::list_orphan_accounts,
+            commands::snooze_account,
+            commands::adopt_orphan,
+            commands::list_corrupt_accounts,
+            commands::restore_corrupt_account,
+            commands::delete_corrupt_account,
             commands::clear_antigravity_cache,
             commands::get_antigravity_cache_paths,
             commands::open_data_folder,
             commands::get_data_dir_path,
+            commands::move_data_dir,
+            commands::get_startup_report,
+            commands::list_notifications,
+            commands::mark_notifications_read,
+            commands::tail_events,
             commands::show_main_window,
             commands::set_window_theme,
             commands::get_antigravity_path,
+            commands::detect_antigravity_installations,
             commands::get_antigravity_args,
             commands::check_for_updates,
             commands::check_homebrew_installation,
@@ -482,14 +641,23 @@ pub fn run() {
             commands::should_check_updates,
             commands::update_last_check_time,
             commands::toggle_proxy_status,
+            commands::set_proxy_status_bulk,
+            commands::verify_account_login,
             // Proxy service commands
             commands::proxy::start_proxy_service,
             commands::proxy::stop_proxy_service,
             commands::proxy::get_proxy_status,
             commands::proxy::get_proxy_stats,
+            commands::proxy::get_admission_stats,
+            commands::proxy::list_upstream_sessions,
+            commands::proxy::rotate_upstream_sessions,
+            commands::proxy::get_upstream_circuits,
+            commands::proxy::get_content_filter_stats,
+            commands::proxy::reset_content_filter_stats,
             commands::proxy::get_proxy_logs,
             commands::proxy::get_proxy_logs_paginated,
             commands::proxy::get_proxy_log_detail,
+            commands::proxy::replay_request,
             commands::proxy::get_proxy_logs_count,
             commands::proxy::export_proxy_logs,
             commands::proxy::export_proxy_logs_json,
@@ -497,6 +665,7 @@ pub fn run() {
             commands::proxy::get_proxy_logs_filtered,
             commands::proxy::set_proxy_monitor_enabled,
             commands::proxy::clear_proxy_logs,
+            commands::proxy::compact_proxy_logs,
             commands::proxy::generate_api_key,
             commands::proxy::reload_proxy_accounts,
             commands::proxy::update_model_mapping,
@@ -508,6 +677,10 @@ pub fn run() {
             commands::proxy::clear_proxy_session_bindings,
             commands::proxy::set_preferred_account,
             commands::proxy::get_preferred_account,
+            commands::proxy::test_account_request,
+            commands::proxy::get_model_availability,
+            commands::proxy::get_token_manager_state,
+            commands::proxy::get_capacity_status,
             commands::proxy::clear_proxy_rate_limit,
             commands::proxy::clear_all_proxy_rate_limits,
             commands::proxy::check_proxy_health,
@@ -523,6 +696,10 @@ pub fn run() {
             commands::warm_up_all_accounts,
             commands::warm_up_account,
             commands::update_account_label,
+            commands::update_account_tags,
+            commands::update_account_ip_family,
+            commands::update_account_antigravity_args,
+            commands::update_account_antigravity_executable,
             // HTTP API settings commands
             commands::get_http_api_settings,
             commands::save_http_api_settings,
@@ -533,6 +710,23 @@ pub fn run() {
             commands::get_token_stats_by_account,
             commands::get_token_stats_summary,
             commands::get_token_stats_by_model,
+            commands::list_known_models,
+            commands::get_project_binding_history,
+            commands::simulate_pool_workload,
+            commands::get_quota_history,
+            commands::get_account_latency_stats,
+            commands::run_data_retention,
+            commands::run_account_hygiene,
+            commands::run_problem_digest,
+            commands::run_remote_backup,
+            commands::list_remote_backups,
+            commands::restore_remote_backup,
+            commands::get_backup_status,
+            commands::sync_accounts_now,
+            commands::get_sync_status,
+            commands::sync_model_catalog,
+            commands::get_monitored_model_options,
+            commands::update_monitored_models,
             commands::get_token_stats_model_trend_hourly,
             commands::get_token_stats_model_trend_daily,
             commands::get_token_stats_account_trend_hourly,
@@ -587,7 +781,9 @@ pub fn run() {
             commands::user_token::renew_user_token,
             commands::user_token::get_token_ip_bindings,
             commands::user_token::get_user_token_summary,
-        ])
+            commands::user_token::get_billing_report,
+            commands::user_token::export_billing_report,
+        ]))
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
         .run(|app_handle, event| {
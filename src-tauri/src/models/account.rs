@@ -74,6 +74,18 @@ pub struct Account {
     /// 用户自定义标签
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub custom_label: Option<String>,
+    /// 账号分组标签 (API Key 可绑定分组，仅使用带有对应标签的账号)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// 出站地址族覆盖 (None = 使用全局 http_client.ip_family)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ip_family: Option<super::IpFamily>,
+    /// 切换到此账号时追加的 Antigravity 启动参数；同名标志覆盖全局 antigravity_args
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub antigravity_args: Option<Vec<String>>,
+    /// 切换到此账号时使用的 Antigravity 安装 (如 macOS 上的第二个 .app 副本)；None = 全局 antigravity_executable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub antigravity_executable: Option<String>,
     /// 账户服务商类型 (Google/Codex)
     #[serde(default)]
     pub provider: AccountProvider,
@@ -107,6 +119,10 @@ impl Account {
             proxy_id: None,
             proxy_bound_at: None,
             custom_label: None,
+            tags: Vec::new(),
+            ip_family: None,
+            antigravity_args: None,
+            antigravity_executable: None,
         }
     }
 
@@ -137,6 +153,10 @@ impl Account {
             proxy_id: None,
             proxy_bound_at: None,
             custom_label: None,
+            tags: Vec::new(),
+            ip_family: None,
+            antigravity_args: None,
+            antigravity_executable: None,
         }
     }
 
@@ -149,12 +169,134 @@ impl Account {
     }
 }
 
+/// accounts.json 结构版本 (3.0: generation + checksum)
+pub const INDEX_SCHEMA_VERSION: &str = "3.0";
+
 /// 账号索引数据（accounts.json）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccountIndex {
     pub version: String,
     pub accounts: Vec<AccountSummary>,
     pub current_account_id: Option<String>,
+    /// 写入代数，每次保存递增；用于发现多进程互相覆盖的过期写入 (v3)
+    #[serde(default)]
+    pub generation: u64,
+    /// generation / current_account_id / accounts 的 SHA-256，加载时校验 (v3)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
+    /// 最近的账号切换记录 (最新在后)，供“切回上一个账号”使用
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub switch_history: Vec<SwitchHistoryEntry>,
+}
+
+/// 一次账号切换
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwitchHistoryEntry {
+    pub from_id: Option<String>,
+    pub to_id: String,
+    pub timestamp: i64,
+}
+
+/// 账号切换的阶段 (按执行顺序)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SwitchPhase {
+    RefreshToken,
+    DeviceProfile,
+    /// 定位 storage.json / state.vscdb 并备份
+    Snapshot,
+    CloseProcess,
+    WriteProfile,
+    InjectToken,
+    StartProcess,
+    /// 外部切换命令 (config.switch_command)
+    ExternalCommand,
+    UpdateIndex,
+    /// 失败后恢复 storage.json / state.vscdb 并重启上一个账号
+    Rollback,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SwitchPhaseStatus {
+    Done,
+    Skipped,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwitchPhaseResult {
+    pub phase: SwitchPhase,
+    pub status: SwitchPhaseStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// 一次账号切换的结果: 每个阶段是否完成，以及失败时是否已回滚
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwitchReport {
+    pub account_id: String,
+    pub email: String,
+    pub previous_account_id: Option<String>,
+    pub started_at: i64,
+    pub finished_at: Option<i64>,
+    pub success: bool,
+    pub rolled_back: bool,
+    pub phases: Vec<SwitchPhaseResult>,
+}
+
+impl SwitchReport {
+    pub fn new(account_id: &str, email: &str, previous_account_id: Option<String>) -> Self {
+        Self {
+            account_id: account_id.to_string(),
+            email: email.to_string(),
+            previous_account_id,
+            started_at: chrono::Utc::now().timestamp(),
+            finished_at: None,
+            success: false,
+            rolled_back: false,
+            phases: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, phase: SwitchPhase, status: SwitchPhaseStatus, detail: Option<String>) {
+        self.phases.push(SwitchPhaseResult {
+            phase,
+            status,
+            detail,
+        });
+    }
+
+    pub fn skip(&mut self, phase: SwitchPhase, reason: &str) {
+        self.record(phase, SwitchPhaseStatus::Skipped, Some(reason.to_string()));
+    }
+
+    /// 记录阶段结果并原样返回，便于配合 `?` 使用
+    pub fn track<T>(&mut self, phase: SwitchPhase, result: Result<T, String>) -> Result<T, String> {
+        match &result {
+            Ok(_) => self.record(phase, SwitchPhaseStatus::Done, None),
+            Err(e) => self.record(phase, SwitchPhaseStatus::Failed, Some(e.clone())),
+        }
+        result
+    }
+
+    pub fn is_done(&self, phase: SwitchPhase) -> bool {
+        self.phases
+            .iter()
+            .any(|p| p.phase == phase && p.status == SwitchPhaseStatus::Done)
+    }
+
+    /// 第一个失败的阶段
+    pub fn failed_phase(&self) -> Option<&SwitchPhaseResult> {
+        self.phases
+            .iter()
+            .find(|p| p.phase != SwitchPhase::Rollback && p.status == SwitchPhaseStatus::Failed)
+    }
+
+    pub fn finish(&mut self, success: bool) {
+        self.success = success;
+        self.finished_at = Some(chrono::Utc::now().timestamp());
+    }
 }
 
 /// 账号摘要信息
@@ -174,14 +316,20 @@ pub struct AccountSummary {
     pub last_used: i64,
     #[serde(default)]
     pub provider: AccountProvider,
+    /// 订阅等级 (FREE / PRO / ULTRA)，随配额刷新同步，供列表排序
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subscription_tier: Option<String>,
 }
 
 impl AccountIndex {
     pub fn new() -> Self {
         Self {
-            version: "2.0".to_string(),
+            version: INDEX_SCHEMA_VERSION.to_string(),
             accounts: Vec::new(),
             current_account_id: None,
+            generation: 0,
+            checksum: None,
+            switch_history: Vec::new(),
         }
     }
 }
@@ -199,6 +347,15 @@ pub struct DeviceProfile {
     pub mac_machine_id: String,
     pub dev_device_id: String,
     pub sqm_id: String,
+    /// 模拟的系统语言 (如 "de-DE")，同时用于 Accept-Language
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    /// 模拟的时区 (IANA，如 "Europe/Berlin")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// 模拟的系统版本 (User-Agent 平台段，如 "Windows NT 10.0; Win64; x64")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub os_version: Option<String>,
 }
 
 /// 指纹历史版本
@@ -223,4 +380,76 @@ pub struct AccountExportItem {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccountExportResponse {
     pub accounts: Vec<AccountExportItem>,
+    /// 因域名策略 (no_export) 未导出的账号邮箱
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub withheld: Vec<String>,
+}
+
+/// 账号列表排序字段
+/// JSON: `{"field": "quota", "model": "claude"}` / `{"field": "last_used"}`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(tag = "field", rename_all = "snake_case")]
+pub enum AccountSortBy {
+    /// 索引中的自定义顺序 (拖拽排序)
+    #[default]
+    Custom,
+    /// 指定模型的剩余额度百分比 (模型名或归一化后的标准 ID，如 "claude")
+    Quota { model: String },
+    LastUsed,
+    CreatedAt,
+    /// 订阅等级 ULTRA > PRO > FREE > 未知
+    Tier,
+    Email,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// 账号列表筛选条件 (未设置的条件不参与筛选)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AccountListFilter {
+    /// 已禁用 (账号禁用或反代禁用)
+    #[serde(default)]
+    pub disabled: Option<bool>,
+    /// 存在受配额保护的模型
+    #[serde(default)]
+    pub protected: Option<bool>,
+    /// 是否带有任意标签
+    #[serde(default)]
+    pub tagged: Option<bool>,
+    /// 带有其中任一标签
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// 邮箱 / 名称 / 自定义标签包含该文本 (不区分大小写)
+    #[serde(default)]
+    pub search: Option<String>,
+}
+
+/// 账号列表行：列表页所需字段，不含 Token / 设备指纹
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountListItem {
+    pub id: String,
+    pub email: String,
+    pub name: Option<String>,
+    pub custom_label: Option<String>,
+    pub provider: AccountProvider,
+    pub disabled: bool,
+    pub proxy_disabled: bool,
+    pub protected_models: HashSet<String>,
+    pub tags: Vec<String>,
+    pub subscription_tier: Option<String>,
+    pub is_forbidden: bool,
+    pub created_at: i64,
+    pub last_used: i64,
+    /// 配额上次更新时间
+    pub quota_updated_at: Option<i64>,
+    /// 配额数据已超过 quota_staleness_minutes 未刷新
+    pub stale: bool,
+    /// 按额度排序时所选模型的剩余百分比 (无配额数据时为 None)
+    pub quota_percentage: Option<i32>,
 }
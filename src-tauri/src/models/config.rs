@@ -5,6 +5,9 @@ use crate::modules::cloudflared::CloudflaredConfig;
 /// Application configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
+    /// On-disk format version, see `modules::config::CURRENT_CONFIG_VERSION` (missing = legacy 0)
+    #[serde(default)]
+    pub config_version: u32,
     pub language: String,
     pub theme: String,
     pub auto_refresh: bool,
@@ -30,6 +33,50 @@ pub struct AppConfig {
     pub hidden_menu_items: Vec<String>, // Hidden menu item path list
     #[serde(default)]
     pub cloudflared: CloudflaredConfig, // [NEW] Cloudflared configuration
+    #[serde(default)]
+    pub quiet_hours: QuietHoursConfig, // [NEW] Quiet hours for background jobs
+    #[serde(default)]
+    pub remote: RemoteConfig, // [NEW] Remote management mode
+    #[serde(default)]
+    pub logging: LoggingConfig, // [NEW] Log levels (adjustable at runtime)
+    #[serde(default)]
+    pub device_simulation: DeviceSimulationConfig, // [NEW] Locale / timezone / OS pools for generated device profiles
+    #[serde(default)]
+    pub http_client: HttpClientConfig, // [NEW] Shared HTTP client timeouts / pool / retry settings
+    #[serde(default)]
+    pub offline: OfflineConfig, // [NEW] Offline mode (manual toggle + connectivity auto-detection)
+    #[serde(default)]
+    pub version_sources: VersionSourcesConfig, // [NEW] Extra Antigravity version source URLs / pinned version
+    #[serde(default)]
+    pub tray: TrayConfig, // [NEW] Tray menu text truncation
+    #[serde(default)]
+    pub onboarding: OnboardingProgress, // [NEW] First-run guided setup progress
+    #[serde(default)]
+    pub upstream_endpoints: UpstreamEndpointsConfig, // [NEW] Point all upstream calls at another base URL (mock upstream)
+    #[serde(default)]
+    pub data_retention: DataRetentionConfig, // [NEW] How long quota history / usage stats / audit events are kept
+    #[serde(default)]
+    pub backup: BackupConfig, // [NEW] Scheduled encrypted backups to a remote target (WebDAV / S3)
+    #[serde(default)]
+    pub sync: SyncConfig, // [NEW] Two-way account pool sync through the backup target
+    #[serde(default)]
+    pub digest: DigestConfig, // [NEW] Daily digest of accounts needing attention
+    #[serde(default)]
+    pub capacity_alerts: CapacityAlertConfig, // [NEW] Per-model minimum account availability thresholds
+    #[serde(default)]
+    pub switch_command: SwitchCommandConfig, // [NEW] External command replacing the built-in account switch
+    #[serde(default)]
+    pub domain_policies: Vec<DomainPolicy>, // [NEW] Export / rotation / delete rules keyed by email domain
+    #[serde(default)]
+    pub app_lock: AppLockConfig, // [NEW] Master password lock with idle auto-lock (managed by app_lock commands only)
+    #[serde(default)]
+    pub scheduler: SchedulerConfig, // [NEW] Background jobs switched off individually
+    #[serde(default)]
+    pub exclusion_windows: Vec<ExclusionWindow>, // [NEW] Time windows in which accounts / tags are kept out of proxy rotation
+    #[serde(default)]
+    pub hygiene: HygieneConfig, // [NEW] Weekly data directory maintenance (cooldowns, device history, temp files, index check)
+    #[serde(default)]
+    pub deep_links: DeepLinkConfig, // [NEW] abv:// URL scheme for switching / refreshing from other tools
 }
 
 /// Scheduled warmup configuration
@@ -41,6 +88,30 @@ pub struct ScheduledWarmupConfig {
     /// List of models to warmup
     #[serde(default = "default_warmup_models")]
     pub monitored_models: Vec<String>,
+
+    /// Spread each scheduled warmup batch over this window (seconds)
+    #[serde(default = "default_warmup_window_secs")]
+    pub window_secs: u64,
+
+    /// Minimum gap between two warmups on the same account (seconds)
+    #[serde(default = "default_warmup_per_account_interval_secs")]
+    pub per_account_interval_secs: u64,
+
+    /// Global ceiling on warmup requests per minute (0 = unlimited)
+    #[serde(default = "default_warmup_max_per_minute")]
+    pub max_per_minute: u32,
+}
+
+fn default_warmup_window_secs() -> u64 {
+    300
+}
+
+fn default_warmup_per_account_interval_secs() -> u64 {
+    30
+}
+
+fn default_warmup_max_per_minute() -> u32 {
+    6
 }
 
 fn default_warmup_models() -> Vec<String> {
@@ -57,6 +128,9 @@ impl ScheduledWarmupConfig {
         Self {
             enabled: false,
             monitored_models: default_warmup_models(),
+            window_secs: default_warmup_window_secs(),
+            per_account_interval_secs: default_warmup_per_account_interval_secs(),
+            max_per_minute: default_warmup_max_per_minute(),
         }
     }
 }
@@ -168,9 +242,1094 @@ impl Default for CircuitBreakerConfig {
     }
 }
 
+/// Quiet hours configuration
+/// Background jobs (warmup, batch quota refresh) are suppressed inside this window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuietHoursConfig {
+    /// Whether quiet hours are enabled
+    pub enabled: bool,
+
+    /// Window start, local time "HH:MM"
+    #[serde(default = "default_quiet_start")]
+    pub start: String,
+
+    /// Window end, local time "HH:MM" (may be earlier than start to wrap past midnight)
+    #[serde(default = "default_quiet_end")]
+    pub end: String,
+}
+
+fn default_quiet_start() -> String {
+    "09:00".to_string()
+}
+
+fn default_quiet_end() -> String {
+    "18:00".to_string()
+}
+
+impl QuietHoursConfig {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            start: default_quiet_start(),
+            end: default_quiet_end(),
+        }
+    }
+}
+
+impl Default for QuietHoursConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Remote management configuration
+/// When enabled, the GUI sends admin commands to another instance instead of local modules
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RemoteConfig {
+    /// Whether remote mode is enabled
+    pub enabled: bool,
+
+    /// Base URL of the remote instance, e.g. "http://192.168.1.10:8045"
+    #[serde(default)]
+    pub url: String,
+
+    /// Admin API key / Web UI password of the remote instance (encrypted in the config file)
+    #[serde(
+        default,
+        serialize_with = "crate::utils::crypto::serialize_password",
+        deserialize_with = "crate::utils::crypto::deserialize_password"
+    )]
+    pub token: String,
+}
+
+/// Logging configuration
+/// Levels are tracing filter levels: "off", "error", "warn", "info", "debug", "trace"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoggingConfig {
+    /// Level applied to every target without an explicit override
+    #[serde(default = "default_log_level")]
+    pub default_level: String,
+
+    /// Per-module overrides, keyed by tracing target
+    /// e.g. {"antigravity_tools_lib::proxy": "debug", "hyper": "warn"}
+    #[serde(default)]
+    pub module_levels: std::collections::BTreeMap<String, String>,
+
+    /// Time-based rotation of the log file: "hourly" or "daily" (applied on restart)
+    #[serde(default = "default_log_rotation")]
+    pub rotation: String,
+
+    /// Start a new segment once the current file exceeds this size (MB, 0 = no limit)
+    #[serde(default = "default_log_max_file_size_mb")]
+    pub max_file_size_mb: u64,
+
+    /// Delete log files older than this many days
+    #[serde(default = "default_log_retention_days")]
+    pub retention_days: u64,
+
+    /// Delete the oldest files once the log directory exceeds this size (MB)
+    #[serde(default = "default_log_max_total_size_mb")]
+    pub max_total_size_mb: u64,
+
+    /// Mask OAuth tokens / API keys in logs and error messages (disable only for local debugging)
+    #[serde(default = "default_true")]
+    pub redact_secrets: bool,
+
+    /// Persist log events to the structured store behind `query_logs`
+    #[serde(default = "default_true")]
+    pub store_enabled: bool,
+
+    /// Forward log events to the system log (see `modules::log_forward`)
+    #[serde(default)]
+    pub forward: LogForwardConfig,
+}
+
+/// System log sink for forwarded events
+/// - `syslog`: local socket (`/dev/log`) when `address` is empty, otherwise `udp://host:514` / `tcp://host:601` (RFC 5424)
+/// - `journald`: native journal protocol, fields become journal fields (Linux)
+/// - `eventlog`: Windows Event Log (Application log, source = `app_name`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LogForwardConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    #[serde(default = "default_log_forward_sink")]
+    pub sink: String,
+
+    #[serde(default)]
+    pub address: String,
+
+    /// Syslog facility name (e.g. "daemon", "user", "local0")
+    #[serde(default = "default_log_forward_facility")]
+    pub facility: String,
+
+    /// Syslog APP-NAME / journald SYSLOG_IDENTIFIER / event source
+    #[serde(default = "default_log_forward_app_name")]
+    pub app_name: String,
+
+    /// Events below this level are not forwarded
+    #[serde(default = "default_log_level")]
+    pub min_level: String,
+}
+
+fn default_log_forward_sink() -> String {
+    if cfg!(windows) { "eventlog" } else { "syslog" }.to_string()
+}
+
+fn default_log_forward_facility() -> String {
+    "daemon".to_string()
+}
+
+fn default_log_forward_app_name() -> String {
+    "antigravity-tools".to_string()
+}
+
+impl Default for LogForwardConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sink: default_log_forward_sink(),
+            address: String::new(),
+            facility: default_log_forward_facility(),
+            app_name: default_log_forward_app_name(),
+            min_level: default_log_level(),
+        }
+    }
+}
+
+fn default_log_level() -> String {
+    "info".to_string()
+}
+
+fn default_log_rotation() -> String {
+    "daily".to_string()
+}
+
+fn default_log_max_file_size_mb() -> u64 {
+    50
+}
+
+fn default_log_retention_days() -> u64 {
+    7
+}
+
+fn default_log_max_total_size_mb() -> u64 {
+    1024
+}
+
+impl LoggingConfig {
+    pub fn new() -> Self {
+        Self {
+            default_level: default_log_level(),
+            module_levels: std::collections::BTreeMap::new(),
+            rotation: default_log_rotation(),
+            max_file_size_mb: default_log_max_file_size_mb(),
+            retention_days: default_log_retention_days(),
+            max_total_size_mb: default_log_max_total_size_mb(),
+            redact_secrets: true,
+            store_enabled: true,
+            forward: LogForwardConfig::default(),
+        }
+    }
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Weighted region preset used when generating device profiles
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionPreset {
+    pub name: String,
+    /// Relative selection weight (0 = never picked)
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+    /// BCP 47 locales, e.g. "de-DE"
+    pub locales: Vec<String>,
+    /// IANA timezones, e.g. "Europe/Berlin"
+    pub timezones: Vec<String>,
+}
+
+/// Weighted OS version, written as the User-Agent platform token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OsVersionOption {
+    /// e.g. "Windows NT 10.0; Win64; x64"
+    pub value: String,
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+/// Device profile simulation configuration
+/// Newly generated profiles draw locale, timezone and OS version from these pools
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceSimulationConfig {
+    /// Whether generated profiles get locale / timezone / OS version
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    #[serde(default = "default_region_presets")]
+    pub regions: Vec<RegionPreset>,
+
+    #[serde(default = "default_os_versions")]
+    pub os_versions: Vec<OsVersionOption>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_region_presets() -> Vec<RegionPreset> {
+    let preset = |name: &str, weight: u32, locales: &[&str], timezones: &[&str]| RegionPreset {
+        name: name.to_string(),
+        weight,
+        locales: locales.iter().map(|s| s.to_string()).collect(),
+        timezones: timezones.iter().map(|s| s.to_string()).collect(),
+    };
+    vec![
+        preset(
+            "north_america",
+            40,
+            &["en-US", "en-CA"],
+            &["America/New_York", "America/Chicago", "America/Denver", "America/Los_Angeles", "America/Toronto"],
+        ),
+        preset(
+            "europe",
+            30,
+            &["en-GB", "de-DE", "fr-FR", "es-ES", "it-IT", "nl-NL"],
+            &["Europe/London", "Europe/Berlin", "Europe/Paris", "Europe/Madrid", "Europe/Rome", "Europe/Amsterdam"],
+        ),
+        preset(
+            "asia_pacific",
+            20,
+            &["ja-JP", "ko-KR", "en-SG", "en-AU", "zh-TW"],
+            &["Asia/Tokyo", "Asia/Seoul", "Asia/Singapore", "Australia/Sydney", "Asia/Taipei"],
+        ),
+        preset(
+            "latin_america",
+            10,
+            &["es-MX", "pt-BR", "es-AR"],
+            &["America/Mexico_City", "America/Sao_Paulo", "America/Argentina/Buenos_Aires"],
+        ),
+    ]
+}
+
+fn default_os_versions() -> Vec<OsVersionOption> {
+    let os = |value: &str, weight: u32| OsVersionOption {
+        value: value.to_string(),
+        weight,
+    };
+    vec![
+        os("Windows NT 10.0; Win64; x64", 50),
+        os("Macintosh; Intel Mac OS X 10_15_7", 35),
+        os("X11; Linux x86_64", 15),
+    ]
+}
+
+impl Default for DeviceSimulationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            regions: default_region_presets(),
+            os_versions: default_os_versions(),
+        }
+    }
+}
+
+/// Failure classes that the shared retry policy may retry (see `modules::retry`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryClass {
+    /// 401: caller refreshes the token, then retries immediately
+    Unauthorized,
+    /// 429
+    RateLimited,
+    /// 5xx
+    ServerError,
+    /// 408 / connect or read timeouts / connection errors
+    Timeout,
+}
+
+/// Address family used for outbound connections
+/// Pinning resolves the upstream host to that family only (behind a proxy it applies to the proxy hop)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum IpFamily {
+    #[default]
+    Auto,
+    Ipv4,
+    Ipv6,
+}
+
+/// Custom DNS resolution for upstream hosts (see `modules::dns`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct DnsOverrideConfig {
+    /// Static host -> IP mappings; keys are exact hosts or "*.example.com"
+    #[serde(default)]
+    pub hosts: std::collections::HashMap<String, Vec<String>>,
+
+    /// DNS-over-HTTPS JSON endpoint (e.g. https://dns.google/resolve); unset = system resolver
+    #[serde(default)]
+    pub doh_url: Option<String>,
+}
+
+/// Shared HTTP client configuration
+/// Applies to oauth, quota and version fetches (see `modules::http`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HttpClientConfig {
+    /// Total request timeout for short info requests (seconds)
+    #[serde(default = "default_http_timeout_secs")]
+    pub timeout_secs: u64,
+
+    /// Total request timeout for slow requests such as token exchange / warmup (seconds)
+    #[serde(default = "default_http_long_timeout_secs")]
+    pub long_timeout_secs: u64,
+
+    /// TCP/TLS connect timeout (seconds)
+    #[serde(default = "default_http_connect_timeout_secs")]
+    pub connect_timeout_secs: u64,
+
+    /// Idle pooled connections are closed after this many seconds
+    #[serde(default = "default_http_pool_idle_timeout_secs")]
+    pub pool_idle_timeout_secs: u64,
+
+    /// Maximum idle pooled connections kept per host
+    #[serde(default = "default_http_pool_max_idle_per_host")]
+    pub pool_max_idle_per_host: usize,
+
+    /// Attempts per request, including the first one
+    #[serde(default = "default_http_max_attempts")]
+    pub max_attempts: u32,
+
+    /// Delay before the first retry (milliseconds)
+    #[serde(default = "default_http_retry_base_delay_ms")]
+    pub retry_base_delay_ms: u64,
+
+    /// Upper bound for a single backoff delay (milliseconds)
+    #[serde(default = "default_http_retry_max_delay_ms")]
+    pub retry_max_delay_ms: u64,
+
+    /// Failure classes that are retried
+    #[serde(default = "default_http_retry_on")]
+    pub retry_on: Vec<RetryClass>,
+
+    /// Concurrent account fetches in batch quota refresh
+    #[serde(default = "default_http_max_concurrent_fetches")]
+    pub max_concurrent_fetches: usize,
+
+    /// Outbound address family (accounts may override it)
+    #[serde(default)]
+    pub ip_family: IpFamily,
+
+    /// Static host mappings / DoH resolver for upstream hosts
+    #[serde(default)]
+    pub dns: DnsOverrideConfig,
+}
+
+fn default_http_timeout_secs() -> u64 {
+    15
+}
+
+fn default_http_long_timeout_secs() -> u64 {
+    60
+}
+
+fn default_http_connect_timeout_secs() -> u64 {
+    10
+}
+
+fn default_http_pool_idle_timeout_secs() -> u64 {
+    90
+}
+
+fn default_http_pool_max_idle_per_host() -> usize {
+    8
+}
+
+fn default_http_max_attempts() -> u32 {
+    3
+}
+
+fn default_http_retry_base_delay_ms() -> u64 {
+    1000
+}
+
+fn default_http_retry_max_delay_ms() -> u64 {
+    10_000
+}
+
+fn default_http_retry_on() -> Vec<RetryClass> {
+    vec![
+        RetryClass::Unauthorized,
+        RetryClass::RateLimited,
+        RetryClass::ServerError,
+        RetryClass::Timeout,
+    ]
+}
+
+fn default_http_max_concurrent_fetches() -> usize {
+    5
+}
+
+impl Default for HttpClientConfig {
+    fn default() -> Self {
+        Self {
+            timeout_secs: default_http_timeout_secs(),
+            long_timeout_secs: default_http_long_timeout_secs(),
+            connect_timeout_secs: default_http_connect_timeout_secs(),
+            pool_idle_timeout_secs: default_http_pool_idle_timeout_secs(),
+            pool_max_idle_per_host: default_http_pool_max_idle_per_host(),
+            max_attempts: default_http_max_attempts(),
+            retry_base_delay_ms: default_http_retry_base_delay_ms(),
+            retry_max_delay_ms: default_http_retry_max_delay_ms(),
+            retry_on: default_http_retry_on(),
+            max_concurrent_fetches: default_http_max_concurrent_fetches(),
+            ip_family: IpFamily::default(),
+            dns: DnsOverrideConfig::default(),
+        }
+    }
+}
+
+/// Offline mode configuration (see `modules::offline`)
+/// While offline, background network tasks pause and the proxy rejects requests
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OfflineConfig {
+    /// Manually forced offline
+    #[serde(default)]
+    pub manual: bool,
+
+    /// Probe connectivity periodically and go offline automatically when it is lost
+    #[serde(default = "default_offline_auto_detect")]
+    pub auto_detect: bool,
+
+    /// Seconds between connectivity probes
+    #[serde(default = "default_offline_probe_interval_secs")]
+    pub probe_interval_secs: u64,
+
+    /// URL probed for connectivity (any HTTP response counts as online)
+    #[serde(default = "default_offline_probe_url")]
+    pub probe_url: String,
+}
+
+fn default_offline_auto_detect() -> bool {
+    true
+}
+
+fn default_offline_probe_interval_secs() -> u64 {
+    60
+}
+
+fn default_offline_probe_url() -> String {
+    "https://www.gstatic.com/generate_204".to_string()
+}
+
+impl Default for OfflineConfig {
+    fn default() -> Self {
+        Self {
+            manual: false,
+            auto_detect: default_offline_auto_detect(),
+            probe_interval_secs: default_offline_probe_interval_secs(),
+            probe_url: default_offline_probe_url(),
+        }
+    }
+}
+
+/// Upstream endpoint override (see `modules::endpoints`)
+/// When set, quota / OAuth / proxy calls go to this base URL instead of the Google endpoints,
+/// e.g. the embedded mock upstream used by offline integration tests
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UpstreamEndpointsConfig {
+    /// Base URL such as `http://127.0.0.1:18090`; unset or empty uses the official endpoints
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url_override: Option<String>,
+}
+
+/// Data retention (see `modules::retention`), enforced by a nightly cleanup job
+/// Request logs keep their own `proxy.request_log` policy and are pruned in the same pass
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataRetentionConfig {
+    /// Days of per-account quota snapshots to keep (0 = keep forever)
+    #[serde(default = "default_quota_history_days")]
+    pub quota_history_days: u32,
+
+    /// Days of token usage stats (raw + hourly aggregates) to keep (0 = keep forever)
+    #[serde(default = "default_usage_stats_days")]
+    pub usage_stats_days: u32,
+
+    /// Days of audit events (IP access logs, API key usage logs) to keep (0 = keep forever)
+    #[serde(default = "default_audit_events_days")]
+    pub audit_events_days: u32,
+
+    /// Local hour (0-23) at which the nightly cleanup runs
+    #[serde(default = "default_retention_cleanup_hour")]
+    pub cleanup_hour: u8,
+}
+
+fn default_quota_history_days() -> u32 {
+    30
+}
+
+fn default_usage_stats_days() -> u32 {
+    180
+}
+
+fn default_audit_events_days() -> u32 {
+    90
+}
+
+fn default_retention_cleanup_hour() -> u8 {
+    3
+}
+
+impl Default for DataRetentionConfig {
+    fn default() -> Self {
+        Self {
+            quota_history_days: default_quota_history_days(),
+            usage_stats_days: default_usage_stats_days(),
+            audit_events_days: default_audit_events_days(),
+            cleanup_hour: default_retention_cleanup_hour(),
+        }
+    }
+}
+
+/// Remote backup configuration (see `modules::backup`)
+/// Archives of the data directory are encrypted with `passphrase` before upload;
+/// secrets are stored encrypted at rest like other credentials in this file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupConfig {
+    /// Upload a backup automatically every `interval_hours`
+    #[serde(default)]
+    pub enabled: bool,
+
+    #[serde(default = "default_backup_interval_hours")]
+    pub interval_hours: u32,
+
+    /// Remote archives to keep; older ones are deleted after a successful upload (0 = keep all)
+    #[serde(default = "default_backup_keep_last")]
+    pub keep_last: u32,
+
+    /// Passphrase the archive key is derived from (required to restore on another machine)
+    #[serde(
+        default,
+        serialize_with = "crate::utils::crypto::serialize_password",
+        deserialize_with = "crate::utils::crypto::deserialize_password"
+    )]
+    pub passphrase: String,
+
+    #[serde(default)]
+    pub target: RemoteBackupTarget,
+}
+
+fn default_backup_interval_hours() -> u32 {
+    24
+}
+
+fn default_backup_keep_last() -> u32 {
+    7
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_hours: default_backup_interval_hours(),
+            keep_last: default_backup_keep_last(),
+            passphrase: String::new(),
+            target: RemoteBackupTarget::default(),
+        }
+    }
+}
+
+/// Where backup archives are uploaded
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RemoteBackupTarget {
+    #[default]
+    None,
+    /// WebDAV collection, e.g. `https://dav.example.com/remote.php/dav/files/me/antigravity/`
+    Webdav {
+        url: String,
+        #[serde(default)]
+        username: String,
+        #[serde(
+            default,
+            serialize_with = "crate::utils::crypto::serialize_password",
+            deserialize_with = "crate::utils::crypto::deserialize_password"
+        )]
+        password: String,
+    },
+    /// S3-compatible bucket (AWS, MinIO, R2 ...)
+    S3 {
+        /// e.g. `https://s3.us-east-1.amazonaws.com` or `http://127.0.0.1:9000`
+        endpoint: String,
+        #[serde(default = "default_s3_region")]
+        region: String,
+        bucket: String,
+        /// Key prefix inside the bucket, e.g. `antigravity/`
+        #[serde(default)]
+        prefix: String,
+        access_key_id: String,
+        #[serde(
+            default,
+            serialize_with = "crate::utils::crypto::serialize_password",
+            deserialize_with = "crate::utils::crypto::deserialize_password"
+        )]
+        secret_access_key: String,
+        /// `endpoint/bucket/key` instead of `bucket.endpoint/key` (MinIO and most self-hosted servers)
+        #[serde(default = "default_s3_path_style")]
+        path_style: bool,
+    },
+}
+
+fn default_s3_region() -> String {
+    "us-east-1".to_string()
+}
+
+fn default_s3_path_style() -> bool {
+    true
+}
+
+/// Two-way account pool sync between instances (see `modules::sync`)
+/// Uses the remote target and passphrase of `backup`; conflicts are resolved per account (last write wins)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Minutes between automatic syncs
+    #[serde(default = "default_sync_interval_minutes")]
+    pub interval_minutes: u32,
+
+    /// Also sync model mapping, fallback chains and tier routing
+    #[serde(default = "default_sync_routing")]
+    pub include_routing: bool,
+}
+
+fn default_sync_interval_minutes() -> u32 {
+    15
+}
+
+fn default_sync_routing() -> bool {
+    true
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_minutes: default_sync_interval_minutes(),
+            include_routing: default_sync_routing(),
+        }
+    }
+}
+
+/// Daily digest of accounts needing attention (see `modules::digest`)
+/// Delivered as a desktop notification / `digest://problem-accounts` event, and to `webhook_url` when set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigestConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Local hour (0-23) at which the digest is sent
+    #[serde(default = "default_digest_hour")]
+    pub hour: u8,
+
+    /// Accounts whose lowest model quota is below this percentage are reported
+    #[serde(default = "default_digest_quota_threshold")]
+    pub quota_threshold: i32,
+
+    /// Accounts with at least this many 401 responses in the last 24h are reported
+    #[serde(default = "default_digest_unauthorized_threshold")]
+    pub unauthorized_threshold: u64,
+
+    /// Optional webhook receiving the digest as JSON (`text` / `content` fields fit Slack / Discord)
+    #[serde(
+        default,
+        serialize_with = "crate::utils::crypto::serialize_password",
+        deserialize_with = "crate::utils::crypto::deserialize_password"
+    )]
+    pub webhook_url: String,
+
+    /// Also send the digest when nothing needs attention
+    #[serde(default)]
+    pub send_when_empty: bool,
+}
+
+fn default_digest_hour() -> u8 {
+    9
+}
+
+fn default_digest_quota_threshold() -> i32 {
+    20
+}
+
+fn default_digest_unauthorized_threshold() -> u64 {
+    3
+}
+
+impl Default for DigestConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            hour: default_digest_hour(),
+            quota_threshold: default_digest_quota_threshold(),
+            unauthorized_threshold: default_digest_unauthorized_threshold(),
+            webhook_url: String::new(),
+            send_when_empty: false,
+        }
+    }
+}
+
+/// Per-model minimum pool capacity (see `proxy::capacity_planner`)
+/// Notifies when fewer accounts than required can serve a model, before clients start getting errors
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CapacityAlertConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Model -> minimum number of accounts that must be able to serve it (e.g. `claude-sonnet-4-5: 3`)
+    /// Models are evaluated by their quota group, so all Claude models share one count
+    #[serde(default)]
+    pub min_available: std::collections::BTreeMap<String, usize>,
+
+    /// Optional webhook receiving breaches as JSON (`text` / `content` fields fit Slack / Discord)
+    #[serde(
+        default,
+        serialize_with = "crate::utils::crypto::serialize_password",
+        deserialize_with = "crate::utils::crypto::deserialize_password"
+    )]
+    pub webhook_url: String,
+}
+
+/// Weekly account hygiene job (see `modules::hygiene`)
+/// Cleans up what crashed writes and long uptimes leave behind in the data directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HygieneConfig {
+    #[serde(default = "default_hygiene_enabled")]
+    pub enabled: bool,
+
+    /// ISO weekday (1 = Monday .. 7 = Sunday) on which the job runs
+    #[serde(default = "default_hygiene_weekday")]
+    pub weekday: u8,
+
+    /// Local hour (0-23) at which the job runs
+    #[serde(default = "default_hygiene_hour")]
+    pub hour: u8,
+
+    /// Device profile history entries kept per account (the current one is always kept)
+    #[serde(default = "default_hygiene_device_history_keep")]
+    pub device_history_keep: usize,
+
+    /// Leftover `*.tmp.*` files older than this are removed (younger ones may belong to a write in progress)
+    #[serde(default = "default_hygiene_temp_file_min_age_hours")]
+    pub temp_file_min_age_hours: u64,
+}
+
+fn default_hygiene_enabled() -> bool {
+    true
+}
+
+fn default_hygiene_weekday() -> u8 {
+    7
+}
+
+fn default_hygiene_hour() -> u8 {
+    4
+}
+
+fn default_hygiene_device_history_keep() -> usize {
+    10
+}
+
+fn default_hygiene_temp_file_min_age_hours() -> u64 {
+    24
+}
+
+impl Default for HygieneConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_hygiene_enabled(),
+            weekday: default_hygiene_weekday(),
+            hour: default_hygiene_hour(),
+            device_history_keep: default_hygiene_device_history_keep(),
+            temp_file_min_age_hours: default_hygiene_temp_file_min_age_hours(),
+        }
+    }
+}
+
+/// `abv://` deep links (see `modules::deep_link`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeepLinkConfig {
+    #[serde(default = "default_deep_links_enabled")]
+    pub enabled: bool,
+
+    /// Whether links may switch the active account (refresh links are always allowed while enabled)
+    #[serde(default = "default_deep_links_allow_switch")]
+    pub allow_switch: bool,
+}
+
+fn default_deep_links_enabled() -> bool {
+    true
+}
+
+fn default_deep_links_allow_switch() -> bool {
+    true
+}
+
+impl Default for DeepLinkConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_deep_links_enabled(),
+            allow_switch: default_deep_links_allow_switch(),
+        }
+    }
+}
+
+/// Background job switches (see `modules::scheduler::scheduler_status`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SchedulerConfig {
+    /// Job IDs that skip their runs until re-enabled (e.g. `backup`, `digest`)
+    #[serde(default)]
+    pub disabled_jobs: Vec<String>,
+}
+
+/// External switch hook (see `modules::integration::CommandIntegration`)
+/// When enabled, switching accounts runs `command args...` with the account JSON on stdin
+/// instead of closing / patching / restarting the local Antigravity install
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwitchCommandConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Script or binary to run
+    #[serde(default)]
+    pub command: String,
+
+    #[serde(default)]
+    pub args: Vec<String>,
+
+    /// The switch fails if the command has not exited after this many seconds
+    #[serde(default = "default_switch_command_timeout")]
+    pub timeout_secs: u64,
+}
+
+fn default_switch_command_timeout() -> u64 {
+    60
+}
+
+impl Default for SwitchCommandConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            command: String::new(),
+            args: Vec::new(),
+            timeout_secs: default_switch_command_timeout(),
+        }
+    }
+}
+
+/// Rules applied to every account whose email is in `domain` (see `modules::domain_policy`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DomainPolicy {
+    /// `corp.com` or `@corp.com`; matches the exact domain only (case-insensitive)
+    pub domain: String,
+
+    /// Never include these accounts' refresh tokens in exports
+    #[serde(default)]
+    pub no_export: bool,
+
+    /// Keep these accounts out of proxy rotation
+    #[serde(default)]
+    pub exclude_from_proxy: bool,
+
+    /// Deleting these accounts must be explicitly confirmed
+    #[serde(default)]
+    pub confirm_delete: bool,
+}
+
+impl DomainPolicy {
+    pub fn matches(&self, email: &str) -> bool {
+        let domain = self.domain.trim().trim_start_matches('@');
+        !domain.is_empty()
+            && email
+                .rsplit_once('@')
+                .is_some_and(|(_, d)| d.eq_ignore_ascii_case(domain))
+    }
+}
+
+/// Time window in which the matching accounts are not used by the proxy (see `modules::exclusion_windows`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExclusionWindow {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Account IDs or emails
+    #[serde(default)]
+    pub accounts: Vec<String>,
+
+    /// Account tags; an account matches if it has any of them
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// "HH:MM"; windows with end < start wrap past midnight
+    pub start: String,
+    pub end: String,
+
+    /// ISO weekdays (1 = Monday ... 7 = Sunday) the window starts on; empty = every day
+    #[serde(default)]
+    pub days: Vec<u8>,
+
+    /// "local", "UTC" or a fixed offset such as "+08:00"
+    #[serde(default = "default_exclusion_timezone")]
+    pub timezone: String,
+}
+
+fn default_exclusion_timezone() -> String {
+    "local".to_string()
+}
+
+/// App lock (see `modules::app_lock`); changed only through the app lock commands,
+/// `save_config` keeps the stored value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppLockConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Lock after this many minutes without user activity (0 = only lock on demand)
+    #[serde(default = "default_app_lock_idle_minutes")]
+    pub idle_minutes: u32,
+
+    /// Salted master password hash (`argon2id$<salt>$<hex>`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password_hash: Option<String>,
+}
+
+fn default_app_lock_idle_minutes() -> u32 {
+    15
+}
+
+impl Default for AppLockConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            idle_minutes: default_app_lock_idle_minutes(),
+            password_hash: None,
+        }
+    }
+}
+
+/// Antigravity version used in client fingerprints (see `constants::resolve_version_config`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VersionSourcesConfig {
+    /// Hard override (X.Y.Z); skips local / remote / floor resolution entirely
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pinned_version: Option<String>,
+
+    /// Extra URLs whose response body contains the latest version (X.Y.Z),
+    /// tried before the built-in updater / changelog endpoints
+    #[serde(default)]
+    pub source_urls: Vec<String>,
+
+    /// Extra / overriding rows for the version → Electron / Chrome runtime table
+    #[serde(default)]
+    pub runtime_mappings: Vec<RuntimeVersionMapping>,
+}
+
+/// Electron / Chrome runtime shipped from `min_version` onwards
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuntimeVersionMapping {
+    pub min_version: String,
+    pub electron: String,
+    pub chrome: String,
+}
+
+/// Tray menu text layout (see `modules::i18n::truncate_text`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrayConfig {
+    /// Maximum display width of a tray menu line, in columns (CJK / fullwidth characters count as 2)
+    #[serde(default = "default_tray_max_text_width")]
+    pub max_text_width: usize,
+
+    /// Shorten emails in the middle of the local part so the domain stays visible
+    #[serde(default = "default_true")]
+    pub email_middle_ellipsis: bool,
+
+    /// macOS: short label shown next to the menu bar icon
+    #[serde(default)]
+    pub menu_bar_title: MenuBarTitle,
+}
+
+/// What the macOS menu bar title shows next to the tray icon
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MenuBarTitle {
+    #[default]
+    Off,
+    /// Account name, or the local part of the email
+    Account,
+    /// Lowest remaining quota among monitored models
+    LowestQuota,
+    Both,
+}
+
+fn default_tray_max_text_width() -> usize {
+    40
+}
+
+impl Default for TrayConfig {
+    fn default() -> Self {
+        Self {
+            max_text_width: default_tray_max_text_width(),
+            email_middle_ellipsis: true,
+            menu_bar_title: MenuBarTitle::default(),
+        }
+    }
+}
+
+/// First-run onboarding steps, in the order they are presented
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnboardingStep {
+    LocateExecutable,
+    CaptureDeviceBaseline,
+    AddAccount,
+    ConfigureProxyPort,
+}
+
+impl OnboardingStep {
+    pub const ALL: [OnboardingStep; 4] = [
+        OnboardingStep::LocateExecutable,
+        OnboardingStep::CaptureDeviceBaseline,
+        OnboardingStep::AddAccount,
+        OnboardingStep::ConfigureProxyPort,
+    ];
+}
+
+/// Persisted onboarding progress (see `modules::onboarding`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OnboardingProgress {
+    /// Steps the user confirmed in the guided flow
+    #[serde(default)]
+    pub completed_steps: Vec<OnboardingStep>,
+
+    /// Steps the user chose to skip
+    #[serde(default)]
+    pub skipped_steps: Vec<OnboardingStep>,
+
+    /// Unix timestamp of when the last step was resolved
+    #[serde(default)]
+    pub finished_at: Option<i64>,
+}
+
 impl AppConfig {
     pub fn new() -> Self {
         Self {
+            config_version: crate::modules::config::CURRENT_CONFIG_VERSION,
             language: "zh".to_string(),
             theme: "system".to_string(),
             auto_refresh: true,
@@ -188,6 +1347,28 @@ impl AppConfig {
             circuit_breaker: CircuitBreakerConfig::default(),
             hidden_menu_items: Vec::new(),
             cloudflared: CloudflaredConfig::default(),
+            quiet_hours: QuietHoursConfig::default(),
+            remote: RemoteConfig::default(),
+            logging: LoggingConfig::default(),
+            device_simulation: DeviceSimulationConfig::default(),
+            http_client: HttpClientConfig::default(),
+            offline: OfflineConfig::default(),
+            version_sources: VersionSourcesConfig::default(),
+            tray: TrayConfig::default(),
+            onboarding: OnboardingProgress::default(),
+            upstream_endpoints: UpstreamEndpointsConfig::default(),
+            data_retention: DataRetentionConfig::default(),
+            backup: BackupConfig::default(),
+            sync: SyncConfig::default(),
+            digest: DigestConfig::default(),
+            capacity_alerts: CapacityAlertConfig::default(),
+            switch_command: SwitchCommandConfig::default(),
+            domain_policies: Vec::new(),
+            app_lock: AppLockConfig::default(),
+            scheduler: SchedulerConfig::default(),
+            exclusion_windows: Vec::new(),
+            hygiene: HygieneConfig::default(),
+            deep_links: DeepLinkConfig::default(),
         }
     }
 }
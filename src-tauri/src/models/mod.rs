@@ -3,8 +3,8 @@ pub mod token;
 pub mod quota;
 pub mod config;
 
-pub use account::{Account, AccountIndex, AccountSummary, DeviceProfile, DeviceProfileVersion, AccountExportItem, AccountExportResponse, AccountProvider};
+pub use account::{Account, AccountIndex, SwitchHistoryEntry, SwitchPhase, SwitchPhaseStatus, SwitchReport, AccountSummary, DeviceProfile, DeviceProfileVersion, AccountExportItem, AccountExportResponse, AccountProvider, AccountSortBy, SortOrder, AccountListFilter, AccountListItem};
 pub use token::TokenData;
 pub use quota::QuotaData;
-pub use config::{AppConfig, QuotaProtectionConfig, CircuitBreakerConfig};
+pub use config::{AppConfig, ScheduledWarmupConfig, QuotaProtectionConfig, CircuitBreakerConfig, QuietHoursConfig, LoggingConfig, LogForwardConfig, DeviceSimulationConfig, HttpClientConfig, IpFamily, DnsOverrideConfig, RetryClass, OfflineConfig, VersionSourcesConfig, RuntimeVersionMapping, TrayConfig, MenuBarTitle, OnboardingStep, OnboardingProgress, UpstreamEndpointsConfig, DataRetentionConfig, BackupConfig, RemoteBackupTarget, SyncConfig, DigestConfig, CapacityAlertConfig, SwitchCommandConfig, DomainPolicy, AppLockConfig, SchedulerConfig, ExclusionWindow, HygieneConfig, DeepLinkConfig};
 
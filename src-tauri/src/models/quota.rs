@@ -59,6 +59,15 @@ impl QuotaData {
     pub fn add_model(&mut self, model: ModelQuota) {
         self.models.push(model);
     }
+
+    /// 配额数据是否已过期 (超过 staleness_minutes 未刷新，0 表示从不过期)
+    pub fn is_stale(&self, staleness_minutes: u32) -> bool {
+        self.is_stale_at(staleness_minutes, chrono::Utc::now().timestamp())
+    }
+
+    pub fn is_stale_at(&self, staleness_minutes: u32, now: i64) -> bool {
+        staleness_minutes > 0 && now - self.last_updated > i64::from(staleness_minutes) * 60
+    }
 }
 
 impl Default for QuotaData {
@@ -1,13 +1,14 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json;
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 use std::collections::HashSet;
 
 use crate::models::{
-    Account, AccountIndex, AccountSummary, DeviceProfile, DeviceProfileVersion, QuotaData,
+    Account, AccountIndex, AccountListFilter, AccountListItem, AccountSortBy, AccountSummary,
+    DeviceProfile, DeviceProfileVersion, QuotaData, SortOrder, SwitchPhase, SwitchReport,
     TokenData,
 };
 use crate::modules;
@@ -233,6 +234,8 @@ mod tests {
                     protected_models: HashSet::new(),
                     created_at: now,
                     last_used: now,
+                    provider: crate::models::AccountProvider::Google,
+                    subscription_tier: Some("PRO".to_string()),
                 },
                 AccountSummary {
                     id: "acc-2".to_string(),
@@ -243,9 +246,14 @@ mod tests {
                     protected_models: HashSet::new(),
                     created_at: now - 100,
                     last_used: now - 50,
+                    provider: crate::models::AccountProvider::Google,
+                    subscription_tier: None,
                 },
             ],
             current_account_id: Some("acc-1".to_string()),
+            generation: 0,
+            checksum: None,
+            switch_history: Vec::new(),
         };
 
         // Save the index
@@ -317,38 +325,440 @@ mod tests {
 
         println!("Backup creation on parse failure: successfully created backup");
     }
-}
 
-/// Global account write lock to prevent corruption during concurrent operations
-static ACCOUNT_INDEX_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+    fn quota_with(percentages: &[i32], last_updated: i64) -> QuotaData {
+        let mut quota = QuotaData::new();
+        for (i, pct) in percentages.iter().enumerate() {
+            quota.add_model(crate::models::quota::ModelQuota {
+                name: format!("model-{}", i),
+                percentage: *pct,
+                reset_time: String::new(),
+                display_name: None,
+                supports_images: None,
+                supports_thinking: None,
+                thinking_budget: None,
+                recommended: None,
+                max_tokens: None,
+                max_output_tokens: None,
+                supported_mime_types: None,
+            });
+        }
+        quota.last_updated = last_updated;
+        quota
+    }
 
-// ... existing constants ...
-const DATA_DIR: &str = ".antigravity_tools";
-const ACCOUNTS_INDEX: &str = "accounts.json";
-const ACCOUNTS_DIR: &str = "accounts";
+    #[test]
+    fn test_write_file_durable_truncates_existing() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+        let dir = TestDataDir::new();
+        let path = dir.path().join("durable.json");
 
-/// Get data directory path
-pub fn get_data_dir() -> Result<PathBuf, String> {
-    // [NEW] Support custom data directory via environment variable
-    if let Ok(env_path) = std::env::var("ABV_DATA_DIR") {
-        if !env_path.trim().is_empty() {
-            let data_dir = PathBuf::from(env_path);
-            if !data_dir.exists() {
-                fs::create_dir_all(&data_dir).map_err(|e| format!("failed_to_create_custom_data_dir: {}", e))?;
-            }
-            return Ok(data_dir);
+        write_file_durable(&path, b"{\"long\":\"previous content\"}").unwrap();
+        write_file_durable(&path, b"{}").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"{}");
+
+        let target = dir.path().join("target.json");
+        atomic_replace_file(&path, &target).unwrap();
+        assert!(!path.exists());
+        assert_eq!(fs::read(&target).unwrap(), b"{}");
+    }
+
+    #[test]
+    fn test_verify_data_integrity_reports_repair_plan() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+        let dir = TestDataDir::new();
+
+        create_account_file(dir.path(), "acc-a", "a@example.com");
+        create_account_file(dir.path(), "acc-dup", "A@example.com");
+        create_account_file(dir.path(), "acc-gone", "gone@example.com");
+        let index = rebuild_index_from_accounts_in_dir(dir.path()).unwrap();
+        save_account_index_in_dir(dir.path(), &index).unwrap();
+
+        // Dangling entry, orphan file, unreadable file
+        fs::remove_file(dir.path().join(ACCOUNTS_DIR).join("acc-gone.json")).unwrap();
+        create_account_file(dir.path(), "acc-orphan", "orphan@example.com");
+        fs::write(dir.path().join(ACCOUNTS_DIR).join("broken.json"), "{").unwrap();
+
+        let report = verify_data_integrity_in_dir(dir.path()).unwrap();
+        assert!(!report.is_clean());
+        assert_eq!(report.index_entries, 3);
+        assert_eq!(report.account_files, 4);
+        assert_eq!(report.orphan_files, vec!["acc-orphan".to_string()]);
+        assert_eq!(report.dangling_entries, vec!["acc-gone".to_string()]);
+        assert_eq!(report.unreadable_files, vec!["broken.json".to_string()]);
+        assert_eq!(report.duplicate_emails.len(), 1);
+        assert_eq!(report.duplicate_emails[0].account_ids.len(), 2);
+
+        assert!(report.repair_plan.contains(&RepairAction::AdoptOrphan {
+            account_id: "acc-orphan".to_string(),
+            email: "orphan@example.com".to_string(),
+        }));
+        assert!(report.repair_plan.contains(&RepairAction::RemoveDanglingEntry {
+            account_id: "acc-gone".to_string(),
+            email: "gone@example.com".to_string(),
+        }));
+        assert_eq!(
+            report
+                .repair_plan
+                .iter()
+                .filter(|a| matches!(a, RepairAction::RemoveDuplicate { .. }))
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_corrupt_account_quarantine_and_restore() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+        let dir = TestDataDir::new();
+
+        create_account_file(dir.path(), "acc-ok", "ok@example.com");
+        create_account_file(dir.path(), "acc-bad", "bad@example.com");
+        let mut index = rebuild_index_from_accounts_in_dir(dir.path()).unwrap();
+        save_account_index_in_dir(dir.path(), &index).unwrap();
+        let account_path = dir.path().join(ACCOUNTS_DIR).join("acc-bad.json");
+        let original = fs::read(&account_path).unwrap();
+        fs::write(&account_path, "{\"id\": \"acc-bad\", trunc").unwrap();
+
+        // Plain loads and the integrity check leave the file alone
+        assert!(load_account_at_path(&account_path).is_err());
+        assert!(account_path.exists());
+        let report = verify_data_integrity_in_dir(dir.path()).unwrap();
+        assert!(report.repair_plan.contains(&RepairAction::QuarantineUnreadable {
+            file_name: "acc-bad.json".to_string(),
+        }));
+        assert!(account_path.exists());
+
+        // Recovery moves the file out of the way instead of leaving it to be overwritten
+        assert!(load_account_or_quarantine(&account_path).is_err());
+        assert!(!account_path.exists());
+        let corrupt = list_corrupt_accounts_in_dir(dir.path()).unwrap();
+        assert_eq!(corrupt.len(), 1);
+        assert_eq!(corrupt[0].account_id, "acc-bad");
+        assert!(corrupt[0].indexed);
+        assert!(corrupt[0].error.is_some());
+        let file_name = corrupt[0].file_name.clone();
+        assert!(file_name.starts_with("acc-bad.json.corrupt-"));
+
+        // Still unparseable: restore refuses
+        assert!(restore_corrupt_account_in_dir(dir.path(), &mut index, &file_name)
+            .unwrap_err()
+            .starts_with("corrupt_file_unparseable"));
+        assert!(restore_corrupt_account_in_dir(dir.path(), &mut index, "../acc-ok.json").is_err());
+
+        // Fixed by hand: restore moves it back
+        let quarantined = dir.path().join(ACCOUNTS_DIR).join(CORRUPT_DIR).join(&file_name);
+        fs::write(&quarantined, &original).unwrap();
+        let summary = restore_corrupt_account_in_dir(dir.path(), &mut index, &file_name).unwrap();
+        assert_eq!(summary.email, "bad@example.com");
+        assert_eq!(load_account_at_path(&account_path).unwrap().id, "acc-bad");
+        assert!(list_corrupt_accounts_in_dir(dir.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_stale_temp_files_respect_min_age() {
+        let dir = TestDataDir::new();
+        fs::create_dir_all(dir.path().join(ACCOUNTS_DIR)).unwrap();
+        fs::write(dir.path().join(format!("{}.tmp.abc", ACCOUNTS_INDEX)), b"{}").unwrap();
+        fs::write(dir.path().join(ACCOUNTS_DIR).join("acc.tmp.def"), b"{}").unwrap();
+        fs::write(dir.path().join(ACCOUNTS_DIR).join("acc.json"), b"{}").unwrap();
+
+        let later = std::time::SystemTime::now() + std::time::Duration::from_secs(600);
+        assert_eq!(stale_temp_files_in_dir(dir.path(), TEMP_FILE_GC_MIN_AGE, later).len(), 2);
+        // Fresh files may still be in flight
+        assert!(stale_temp_files_in_dir(dir.path(), TEMP_FILE_GC_MIN_AGE, std::time::SystemTime::now()).is_empty());
+    }
+
+    #[test]
+    fn test_adopt_orphan_into_index() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+        let dir = TestDataDir::new();
+
+        create_account_file(dir.path(), "acc-indexed", "indexed@example.com");
+        let index = rebuild_index_from_accounts_in_dir(dir.path()).unwrap();
+        save_account_index_in_dir(dir.path(), &index).unwrap();
+        create_account_file(dir.path(), "acc-restored", "restored@example.com");
+
+        let orphans = list_orphan_accounts_in_dir(dir.path()).unwrap();
+        assert_eq!(orphans.len(), 1);
+        assert_eq!(orphans[0].id, "acc-restored");
+
+        let mut index = load_account_index_in_dir(dir.path()).unwrap();
+        let summary = adopt_orphan_into_index(dir.path(), &mut index, "acc-restored").unwrap();
+        assert_eq!(summary.email, "restored@example.com");
+        assert_eq!(index.accounts.len(), 2);
+        assert!(adopt_orphan_into_index(dir.path(), &mut index, "acc-restored").is_err());
+        assert!(adopt_orphan_into_index(dir.path(), &mut index, "acc-missing").is_err());
+
+        save_account_index_in_dir(dir.path(), &index).unwrap();
+        assert!(list_orphan_accounts_in_dir(dir.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_snoozed_until_parsing() {
+        assert_eq!(snoozed_until(Some("snoozed_until:1760000000")), Some(1760000000));
+        assert_eq!(snoozed_until(Some("snoozed_until:abc")), None);
+        assert_eq!(snoozed_until(Some("quota_protection")), None);
+        assert_eq!(snoozed_until(None), None);
+    }
+
+    #[test]
+    fn test_journal_replays_interrupted_add_and_delete() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+        let dir = TestDataDir::new();
+
+        // Crash after the account file was written but before the index was saved
+        create_account_file(dir.path(), "acc-kept", "kept@example.com");
+        create_account_file(dir.path(), "acc-new", "new@example.com");
+        let mut index = rebuild_index_from_accounts_in_dir(dir.path()).unwrap();
+        index.accounts.retain(|s| s.id == "acc-kept");
+        save_account_index_in_dir(dir.path(), &index).unwrap();
+        let torn_path = dir.path().join(ACCOUNTS_DIR).join("acc-torn.json");
+        fs::write(&torn_path, "{\"id\": \"acc-torn\", trunc").unwrap();
+
+        write_journal_in_dir(
+            dir.path(),
+            &JournalOp::Add {
+                account_ids: vec![
+                    "acc-new".to_string(),
+                    "acc-missing".to_string(),
+                    "acc-torn".to_string(),
+                ],
+            },
+        )
+        .unwrap();
+        assert!(replay_journal_in_dir(dir.path()).unwrap().is_some());
+
+        let index = load_account_index_in_dir(dir.path()).unwrap();
+        let ids: Vec<_> = index.accounts.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["acc-kept", "acc-new"]);
+        assert!(!dir.path().join(ACCOUNTS_JOURNAL).exists());
+        // The unparseable file is rolled back into quarantine, not deleted
+        assert!(!torn_path.exists());
+        assert_eq!(list_corrupt_accounts_in_dir(dir.path()).unwrap().len(), 1);
+
+        // Crash after the index was saved but before the account file was removed
+        let mut index = index;
+        index.accounts.retain(|s| s.id != "acc-new");
+        index.current_account_id = Some("acc-new".to_string());
+        save_account_index_in_dir(dir.path(), &index).unwrap();
+        write_journal_in_dir(
+            dir.path(),
+            &JournalOp::Delete {
+                account_ids: vec!["acc-new".to_string()],
+            },
+        )
+        .unwrap();
+        replay_journal_in_dir(dir.path()).unwrap();
+
+        let index = load_account_index_in_dir(dir.path()).unwrap();
+        assert_eq!(index.accounts.len(), 1);
+        assert_eq!(index.current_account_id.as_deref(), Some("acc-kept"));
+        assert!(!dir.path().join("accounts").join("acc-new.json").exists());
+
+        // Nothing pending
+        assert!(replay_journal_in_dir(dir.path()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_adaptive_refresh_interval() {
+        let base = 900;
+        assert_eq!(adaptive_refresh_interval_secs(None, base), 0);
+        assert_eq!(adaptive_refresh_interval_secs(Some(&quota_with(&[], 0)), base), base);
+        assert_eq!(adaptive_refresh_interval_secs(Some(&quota_with(&[80, 10], 0)), base), base / 2);
+        assert_eq!(adaptive_refresh_interval_secs(Some(&quota_with(&[100, 100], 0)), base), base * 2);
+        assert_eq!(adaptive_refresh_interval_secs(Some(&quota_with(&[100, 60], 0)), base), base);
+        // Never below the floor
+        assert_eq!(adaptive_refresh_interval_secs(Some(&quota_with(&[0], 0)), 60), 60);
+    }
+
+    #[test]
+    fn test_refresh_offset_is_bounded_and_stable() {
+        for id in ["a", "b", "c", "some-long-account-id"] {
+            let offset = account_refresh_offset_secs(id, 1000);
+            assert!((-100..=100).contains(&offset));
+            assert_eq!(offset, account_refresh_offset_secs(id, 1000));
+        }
+        assert_eq!(account_refresh_offset_secs("a", 5), 0);
+    }
+
+    #[test]
+    fn test_index_generation_and_checksum() {
+        let _guard = TEST_MUTEX.lock().unwrap();
+        let dir = TestDataDir::new();
+        let index_path = dir.path().join(ACCOUNTS_INDEX);
+
+        let mut index = AccountIndex::new();
+        index.current_account_id = Some("acc-1".to_string());
+        save_account_index_in_dir(dir.path(), &index).unwrap();
+
+        let loaded = load_account_index_in_dir(dir.path()).unwrap();
+        assert_eq!(loaded.generation, 1);
+        assert_eq!(loaded.version, crate::models::account::INDEX_SCHEMA_VERSION);
+        let content = fs::read_to_string(&index_path).unwrap();
+        assert!(verify_index_checksum(&content, &loaded));
+
+        // 基于新代数保存 -> 2；再用过期的 (代数 1) 索引保存仍会越过磁盘代数 -> 3
+        save_account_index_in_dir(dir.path(), &loaded).unwrap();
+        assert_eq!(read_index_generation(&index_path), Some(2));
+        save_account_index_in_dir(dir.path(), &loaded).unwrap();
+        assert_eq!(read_index_generation(&index_path), Some(3));
+
+        // 外部修改内容后校验失败
+        let tampered = fs::read_to_string(&index_path).unwrap().replace("acc-1", "acc-9");
+        let parsed: AccountIndex = serde_json::from_str(&tampered).unwrap();
+        assert!(!verify_index_checksum(&tampered, &parsed));
+    }
+
+    #[test]
+    fn test_previous_account_from_switch_history() {
+        let summary = |id: &str| AccountSummary {
+            id: id.to_string(),
+            email: format!("{}@example.com", id),
+            name: None,
+            disabled: false,
+            proxy_disabled: false,
+            protected_models: HashSet::new(),
+            created_at: 0,
+            last_used: 0,
+            provider: crate::models::AccountProvider::Google,
+            subscription_tier: None,
+        };
+        let mut index = AccountIndex::new();
+        index.accounts = vec![summary("a"), summary("b")];
+        assert_eq!(previous_account_in(&index), None);
+
+        push_switch_history(&mut index, None, "a", 1);
+        push_switch_history(&mut index, Some("a".to_string()), "b", 2);
+        index.current_account_id = Some("b".to_string());
+        assert_eq!(previous_account_in(&index).as_deref(), Some("a"));
+
+        // 切回后再切回会回到 b
+        push_switch_history(&mut index, Some("b".to_string()), "a", 3);
+        index.current_account_id = Some("a".to_string());
+        assert_eq!(previous_account_in(&index).as_deref(), Some("b"));
+
+        // 已删除的账号被跳过
+        index.accounts.retain(|s| s.id != "b");
+        assert_eq!(previous_account_in(&index), None);
+
+        for i in 0..(MAX_SWITCH_HISTORY as i64 + 5) {
+            push_switch_history(&mut index, None, "a", i);
         }
+        assert_eq!(index.switch_history.len(), MAX_SWITCH_HISTORY);
+    }
+
+    #[test]
+    fn test_device_profile_problems() {
+        let profile = crate::modules::device::generate_profile_with(&Default::default());
+        assert!(device_profile_problems(&profile).is_empty());
+
+        let mut broken = profile.clone();
+        broken.machine_id = "not-a-machine-id".to_string();
+        broken.sqm_id = "{zzz}".to_string();
+        assert_eq!(device_profile_problems(&broken), vec!["machine_id", "sqm_id"]);
+    }
+
+    #[test]
+    fn test_account_list_sort_and_filter() {
+        let token = TokenData::new("at".to_string(), "rt".to_string(), 3600, None, None, None);
+        let make = |id: &str, pcts: Option<&[i32]>, tier: Option<&str>, tags: &[&str], last_used: i64| {
+            let mut account = Account::new(id.to_string(), format!("{}@example.com", id), token.clone());
+            account.quota = pcts.map(|p| {
+                let mut q = quota_with(p, 0);
+                q.subscription_tier = tier.map(str::to_string);
+                q
+            });
+            account.tags = tags.iter().map(|t| t.to_string()).collect();
+            account.last_used = last_used;
+            account
+        };
+        let accounts = [
+            make("a", Some(&[80][..]), Some("FREE"), &[], 3),
+            make("b", None, None, &["team"], 1),
+            make("c", Some(&[20][..]), Some("g1-ultra-tier"), &["team", "vip"], 2),
+        ];
+        let list = |sort_by: AccountSortBy, order: SortOrder, filter: AccountListFilter| -> Vec<String> {
+            let mut items: Vec<AccountListItem> = accounts
+                .iter()
+                .map(|a| list_item_from_account(a, &sort_by))
+                .filter(|i| matches_account_filter(i, &filter))
+                .collect();
+            sort_account_items(&mut items, &sort_by, order);
+            items.into_iter().map(|i| i.id).collect()
+        };
+
+        let by_quota = AccountSortBy::Quota { model: "model-0".to_string() };
+        // 无配额数据的账号始终排在最后
+        assert_eq!(list(by_quota.clone(), SortOrder::Asc, Default::default()), ["c", "a", "b"]);
+        assert_eq!(list(by_quota, SortOrder::Desc, Default::default()), ["a", "c", "b"]);
+        assert_eq!(list(AccountSortBy::LastUsed, SortOrder::Desc, Default::default()), ["a", "c", "b"]);
+        assert_eq!(list(AccountSortBy::Tier, SortOrder::Desc, Default::default()), ["c", "a", "b"]);
+        assert_eq!(list(AccountSortBy::Custom, SortOrder::Desc, Default::default()), ["c", "b", "a"]);
+
+        let tagged = AccountListFilter { tagged: Some(true), ..Default::default() };
+        assert_eq!(list(AccountSortBy::Custom, SortOrder::Asc, tagged), ["b", "c"]);
+        let vip = AccountListFilter { tags: vec!["vip".to_string()], ..Default::default() };
+        assert_eq!(list(AccountSortBy::Custom, SortOrder::Asc, vip), ["c"]);
+        let search = AccountListFilter { search: Some("A@EXAMPLE".to_string()), ..Default::default() };
+        assert_eq!(list(AccountSortBy::Custom, SortOrder::Asc, search), ["a"]);
+    }
+
+    #[test]
+    fn test_stale_quota_detection_and_refresh_cooldown() {
+        let quota = quota_with(&[50], 1_000);
+        assert!(!quota.is_stale_at(0, 1_000_000));
+        assert!(!quota.is_stale_at(60, 1_000 + 3600));
+        assert!(quota.is_stale_at(60, 1_000 + 3601));
+
+        let mut queued = HashMap::new();
+        assert!(claim_stale_refresh(&mut queued, "a", 100));
+        assert!(!claim_stale_refresh(&mut queued, "a", 100 + STALE_REFRESH_COOLDOWN_SECS - 1));
+        assert!(claim_stale_refresh(&mut queued, "b", 150));
+        assert!(claim_stale_refresh(&mut queued, "a", 100 + STALE_REFRESH_COOLDOWN_SECS));
     }
 
-    let home = dirs::home_dir().ok_or("failed_to_get_home_dir")?;
-    let data_dir = home.join(DATA_DIR);
+    #[tokio::test]
+    async fn test_quota_refresh_is_coalesced_per_account() {
+        let QuotaTicket::Leader(leader) = begin_quota_flight("coalesce-a") else {
+            panic!("first request should fetch");
+        };
+        let QuotaTicket::Follower(rx) = begin_quota_flight("coalesce-a") else {
+            panic!("second request should wait for the first");
+        };
+        assert!(matches!(begin_quota_flight("coalesce-b"), QuotaTicket::Leader(_)));
+
+        leader.complete(&Err("boom".to_string()));
+        assert_eq!(wait_quota_flight(rx).await, Some(Err("boom".to_string())));
 
-    // Ensure directory exists
-    if !data_dir.exists() {
-        fs::create_dir_all(&data_dir).map_err(|e| format!("failed_to_create_data_dir: {}", e))?;
+        // 执行方被丢弃时等待者拿到 None，由其接手刷新
+        let QuotaTicket::Leader(leader) = begin_quota_flight("coalesce-a") else {
+            panic!("slot should be free again");
+        };
+        let QuotaTicket::Follower(rx) = begin_quota_flight("coalesce-a") else {
+            panic!("same account should wait");
+        };
+        drop(leader);
+        assert_eq!(wait_quota_flight(rx).await, None);
+        assert!(matches!(begin_quota_flight("coalesce-a"), QuotaTicket::Leader(_)));
     }
+}
 
-    Ok(data_dir)
+/// Global account write lock to prevent corruption during concurrent operations
+pub(crate) static ACCOUNT_INDEX_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+// ... existing constants ...
+const ACCOUNTS_INDEX: &str = "accounts.json";
+const ACCOUNTS_DIR: &str = "accounts";
+/// Quarantined unparseable account files (under ACCOUNTS_DIR)
+const CORRUPT_DIR: &str = "corrupt";
+const ACCOUNTS_JOURNAL: &str = "accounts.journal";
+/// Temp files younger than this may belong to a write still in flight (e.g. from the CLI)
+const TEMP_FILE_GC_MIN_AGE: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// Get data directory path (resolution with timeout and local fallback lives in data_dir)
+pub fn get_data_dir() -> Result<PathBuf, String> {
+    modules::data_dir::resolve()
 }
 
 /// Get accounts directory path
@@ -406,9 +816,11 @@ fn load_account_index_in_dir(data_dir: &PathBuf) -> Result<AccountIndex, String>
     // Try to parse sanitized content
     match serde_json::from_str::<AccountIndex>(&sanitized) {
         Ok(index) => {
+            let _ = verify_index_checksum(&sanitized, &index);
             crate::modules::logger::log_info(&format!(
-                "Successfully loaded index with {} accounts",
-                index.accounts.len()
+                "Successfully loaded index with {} accounts (generation {})",
+                index.accounts.len(),
+                index.generation
             ));
             Ok(index)
         }
@@ -424,18 +836,84 @@ fn load_account_index_in_dir(data_dir: &PathBuf) -> Result<AccountIndex, String>
     }
 }
 
+/// SHA-256 over the fields that matter (generation / current_account_id / accounts),
+/// computed on the JSON value so that key order and whitespace do not affect it
+fn index_checksum(value: &serde_json::Value) -> String {
+    use sha2::{Digest, Sha256};
+    let field = |key: &str| value.get(key).map(|v| v.to_string()).unwrap_or_default();
+    let mut hasher = Sha256::new();
+    hasher.update(field("generation"));
+    hasher.update(b"\n");
+    hasher.update(field("current_account_id"));
+    hasher.update(b"\n");
+    hasher.update(field("accounts"));
+    format!("{:x}", hasher.finalize())
+}
+
+/// Log (but tolerate) a checksum mismatch: the file parsed, so it is still usable,
+/// but it was truncated / edited outside the app. Returns false on mismatch.
+fn verify_index_checksum(content: &str, index: &AccountIndex) -> bool {
+    let Some(expected) = index.checksum.as_deref() else {
+        return true;
+    };
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(content) else {
+        return true;
+    };
+    let actual = index_checksum(&value);
+    if actual != expected {
+        crate::modules::logger::log_error(&format!(
+            "Account index checksum mismatch at generation {} (expected {}, got {}); the file was modified outside the app or partially written",
+            index.generation,
+            &expected[..expected.len().min(12)],
+            &actual[..12]
+        ));
+        return false;
+    }
+    true
+}
+
+/// Generation currently on disk (None if missing / unreadable)
+fn read_index_generation(index_path: &PathBuf) -> Option<u64> {
+    let raw = fs::read(index_path).ok()?;
+    let value: serde_json::Value = serde_json::from_str(&sanitize_index_content(&raw)).ok()?;
+    Some(value.get("generation").and_then(|g| g.as_u64()).unwrap_or(0))
+}
+
 /// Save account index to a specific directory (internal helper)
+/// Bumps the generation past whatever is on disk and logs stale writes (the caller loaded an
+/// older generation than the one it is about to overwrite, i.e. another writer got in between)
 fn save_account_index_in_dir(data_dir: &PathBuf, index: &AccountIndex) -> Result<(), String> {
     let index_path = data_dir.join(ACCOUNTS_INDEX);
     // Use unique temp file name per write to avoid collision
     let temp_filename = format!("{}.tmp.{}", ACCOUNTS_INDEX, Uuid::new_v4());
     let temp_path = data_dir.join(&temp_filename);
 
-    let content = serde_json::to_string_pretty(index)
+    let on_disk = read_index_generation(&index_path).unwrap_or(0);
+    if on_disk > index.generation {
+        crate::modules::logger::log_warn(&format!(
+            "Stale account index write: based on generation {}, on-disk generation is {} (delta {}); overwriting newer changes",
+            index.generation,
+            on_disk,
+            on_disk - index.generation
+        ));
+    }
+
+    let mut to_write = index.clone();
+    to_write.version = crate::models::account::INDEX_SCHEMA_VERSION.to_string();
+    to_write.generation = on_disk.max(index.generation) + 1;
+    to_write.checksum = None;
+    let mut value = serde_json::to_value(&to_write)
+        .map_err(|e| format!("failed_to_serialize_account_index: {}", e))?;
+    let checksum = index_checksum(&value);
+    if let Some(map) = value.as_object_mut() {
+        map.insert("checksum".to_string(), serde_json::Value::String(checksum));
+    }
+
+    let content = serde_json::to_string_pretty(&value)
         .map_err(|e| format!("failed_to_serialize_account_index: {}", e))?;
 
-    // Write to temporary file
-    if let Err(e) = fs::write(&temp_path, content) {
+    // Write to temporary file (fsynced before rename)
+    if let Err(e) = write_file_durable(&temp_path, content.as_bytes()) {
         // Clean up temp file on failure
         let _ = fs::remove_file(&temp_path);
         return Err(format!("failed_to_write_temp_index_file: {}", e));
@@ -462,19 +940,9 @@ fn rebuild_index_from_accounts_in_dir(data_dir: &PathBuf) -> Result<AccountIndex
                 let path = entry.path();
                 if path.extension().map_or(false, |ext| ext == "json") {
                     if let Some(account_id) = path.file_stem().and_then(|s| s.to_str()) {
-                        match load_account_at_path(&path) {
+                        match load_account_or_quarantine(&path) {
                             Ok(account) => {
-                                    summaries.push(AccountSummary {
-                                        id: account.id,
-                                        email: account.email,
-                                        name: account.name,
-                                        disabled: account.disabled,
-                                        proxy_disabled: account.proxy_disabled,
-                                        protected_models: account.protected_models,
-                                        created_at: account.created_at,
-                                        last_used: account.last_used,
-                                        provider: account.provider,
-                                    });
+                                summaries.push(summary_from_account(&account));
                             }
                             Err(e) => {
                                 crate::modules::logger::log_warn(&format!(
@@ -504,17 +972,57 @@ fn rebuild_index_from_accounts_in_dir(data_dir: &PathBuf) -> Result<AccountIndex
     ));
 
     Ok(AccountIndex {
-        version: "2.0".to_string(),
         accounts: summaries,
         current_account_id,
+        ..AccountIndex::new()
     })
 }
 
 /// Load account from a specific path (internal helper)
-fn load_account_at_path(account_path: &PathBuf) -> Result<Account, String> {
-    let content = fs::read_to_string(account_path)
-        .map_err(|e| format!("failed_to_read_account_data: {}", e))?;
-    serde_json::from_str(&content).map_err(|e| format!("failed_to_parse_account_data: {}", e))
+fn load_account_at_path(account_path: &Path) -> Result<Account, String> {
+    let content =
+        fs::read(account_path).map_err(|e| format!("failed_to_read_account_data: {}", e))?;
+    serde_json::from_slice(&content).map_err(|e| format!("failed_to_parse_account_data: {}", e))
+}
+
+/// Recovery variant of `load_account_at_path`: an unparseable file is moved to
+/// `accounts/corrupt/` so the rebuilt index cannot lead to a save that overwrites the only copy
+fn load_account_or_quarantine(account_path: &Path) -> Result<Account, String> {
+    load_account_at_path(account_path).inspect_err(|e| {
+        if e.starts_with("failed_to_parse_account_data") {
+            quarantine_corrupt_file(account_path);
+        }
+    })
+}
+
+/// Move an unparseable account file to `accounts/corrupt/<name>.corrupt-<timestamp>-<uuid>`
+/// (same naming as the corrupt index backups)
+fn quarantine_corrupt_file(account_path: &Path) {
+    let (Some(dir), Some(file_name)) = (
+        account_path.parent(),
+        account_path.file_name().and_then(|n| n.to_str()),
+    ) else {
+        return;
+    };
+    let backup_name = format!(
+        "{}.corrupt-{}-{}",
+        file_name,
+        chrono::Utc::now().timestamp(),
+        Uuid::new_v4()
+    );
+    let corrupt_dir = dir.join(CORRUPT_DIR);
+    match fs::create_dir_all(&corrupt_dir)
+        .and_then(|_| fs::rename(account_path, corrupt_dir.join(&backup_name)))
+    {
+        Ok(()) => crate::modules::logger::log_warn(&format!(
+            "Quarantined unparseable account file {} as {}/{}",
+            file_name, CORRUPT_DIR, backup_name
+        )),
+        Err(e) => crate::modules::logger::log_warn(&format!(
+            "Failed to quarantine unparseable account file {}: {}",
+            file_name, e
+        )),
+    }
 }
 
 /// Load account index with recovery support
@@ -637,10 +1145,272 @@ fn atomic_replace_file(src: &PathBuf, dst: &PathBuf) -> Result<(), String> {
     Ok(())
 }
 
-/// Non-Windows: use standard rename
+/// Non-Windows: use standard rename, then fsync the parent directory so the
+/// rename itself survives power loss
 #[cfg(not(target_os = "windows"))]
 fn atomic_replace_file(src: &PathBuf, dst: &PathBuf) -> Result<(), String> {
-    fs::rename(src, dst).map_err(|e| format!("rename failed: {}", e))
+    fs::rename(src, dst).map_err(|e| format!("rename failed: {}", e))?;
+    if let Some(parent) = dst.parent() {
+        sync_dir(parent);
+    }
+    Ok(())
+}
+
+/// Best-effort fsync of a directory handle (Unix only)
+#[cfg(not(target_os = "windows"))]
+fn sync_dir(dir: &std::path::Path) {
+    if let Ok(handle) = fs::File::open(dir) {
+        if let Err(e) = handle.sync_all() {
+            crate::modules::logger::log_warn(&format!(
+                "Failed to fsync directory {}: {}",
+                dir.display(),
+                e
+            ));
+        }
+    }
+}
+
+/// Write a temp file and flush it to stable storage before it is renamed into place.
+/// Without this a power loss after rename can leave an empty or NUL-filled target.
+fn write_file_durable(path: &PathBuf, content: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::fs::OpenOptionsExt;
+        // FILE_FLAG_WRITE_THROUGH: writes bypass the lazy-writer cache
+        const FILE_FLAG_WRITE_THROUGH: u32 = 0x8000_0000;
+        options.custom_flags(FILE_FLAG_WRITE_THROUGH);
+    }
+
+    let mut file = options.open(path)?;
+    file.write_all(content)?;
+    file.sync_all()
+}
+
+// ============================================================================
+// Write-ahead journal for multi-file index operations
+// An intent record is written before touching account files + index, and
+// removed once both are consistent. A leftover record means the operation
+// was interrupted and is replayed (or rolled back) on the next startup.
+// ============================================================================
+
+/// Pending multi-file operation recorded in the journal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum JournalOp {
+    /// Account files written, then appended to the index
+    Add { account_ids: Vec<String> },
+    /// Accounts removed from the index, then their files deleted
+    Delete { account_ids: Vec<String> },
+    /// Index rewritten in the given order
+    Reorder { account_ids: Vec<String> },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct JournalRecord {
+    created_at: i64,
+    #[serde(flatten)]
+    op: JournalOp,
+}
+
+fn write_journal_in_dir(data_dir: &Path, op: &JournalOp) -> Result<(), String> {
+    let record = JournalRecord {
+        created_at: chrono::Utc::now().timestamp(),
+        op: op.clone(),
+    };
+    let content = serde_json::to_string_pretty(&record)
+        .map_err(|e| format!("failed_to_serialize_journal: {}", e))?;
+
+    let journal_path = data_dir.join(ACCOUNTS_JOURNAL);
+    let temp_path = data_dir.join(format!("{}.tmp.{}", ACCOUNTS_JOURNAL, Uuid::new_v4()));
+    if let Err(e) = write_file_durable(&temp_path, content.as_bytes()) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(format!("failed_to_write_journal: {}", e));
+    }
+    atomic_replace_file(&temp_path, &journal_path)
+        .map_err(|e| format!("failed_to_replace_journal: {}", e))
+}
+
+fn clear_journal_in_dir(data_dir: &Path) -> Result<(), String> {
+    let journal_path = data_dir.join(ACCOUNTS_JOURNAL);
+    match fs::remove_file(&journal_path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("failed_to_clear_journal: {}", e)),
+    }
+}
+
+/// Run a multi-file operation under a journal record.
+/// Caller must hold ACCOUNT_INDEX_LOCK. On failure the record is replayed
+/// immediately so the index and account files are left consistent.
+fn with_journal<T>(op: JournalOp, f: impl FnOnce() -> Result<T, String>) -> Result<T, String> {
+    let data_dir = get_data_dir()?;
+    write_journal_in_dir(&data_dir, &op)?;
+
+    match f() {
+        Ok(value) => {
+            clear_journal_in_dir(&data_dir)?;
+            Ok(value)
+        }
+        Err(e) => {
+            if let Err(replay_err) = replay_journal_in_dir(&data_dir) {
+                crate::modules::logger::log_error(&format!(
+                    "Failed to replay account journal after error: {}",
+                    replay_err
+                ));
+            }
+            Err(e)
+        }
+    }
+}
+
+/// Replay or roll back an interrupted operation. Returns the replayed op, if any.
+fn replay_journal_in_dir(data_dir: &PathBuf) -> Result<Option<JournalOp>, String> {
+    let journal_path = data_dir.join(ACCOUNTS_JOURNAL);
+    let content = match fs::read_to_string(&journal_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("failed_to_read_journal: {}", e)),
+    };
+
+    let record: JournalRecord = match serde_json::from_str(&content) {
+        Ok(record) => record,
+        Err(e) => {
+            // The journal is replaced atomically, so an unreadable record was never acted on
+            crate::modules::logger::log_warn(&format!(
+                "Discarding unreadable account journal: {}",
+                e
+            ));
+            clear_journal_in_dir(data_dir)?;
+            return Ok(None);
+        }
+    };
+
+    let accounts_dir = data_dir.join(ACCOUNTS_DIR);
+    let mut index = load_account_index_in_dir(data_dir)?;
+
+    match &record.op {
+        JournalOp::Add { account_ids } => {
+            for account_id in account_ids {
+                let account_path = accounts_dir.join(format!("{}.json", account_id));
+                match load_account_or_quarantine(&account_path) {
+                    // Roll forward: the account file made it to disk
+                    Ok(account) => {
+                        if !index.accounts.iter().any(|s| &s.id == account_id) {
+                            index.accounts.push(summary_from_account(&account));
+                        }
+                    }
+                    // Roll back: drop the half-written account (an unparseable file was
+                    // quarantined rather than deleted)
+                    Err(_) => {
+                        index.accounts.retain(|s| &s.id != account_id);
+                    }
+                }
+            }
+        }
+        JournalOp::Delete { account_ids } => {
+            index.accounts.retain(|s| !account_ids.contains(&s.id));
+            for account_id in account_ids {
+                let account_path = accounts_dir.join(format!("{}.json", account_id));
+                if account_path.exists() {
+                    fs::remove_file(&account_path)
+                        .map_err(|e| format!("failed_to_delete_account_file: {}", e))?;
+                }
+            }
+        }
+        JournalOp::Reorder { account_ids } => {
+            index.accounts.sort_by_key(|s| {
+                account_ids
+                    .iter()
+                    .position(|id| id == &s.id)
+                    .unwrap_or(usize::MAX)
+            });
+        }
+    }
+
+    let current_valid = index
+        .current_account_id
+        .as_ref()
+        .is_some_and(|id| index.accounts.iter().any(|s| &s.id == id));
+    if !current_valid {
+        index.current_account_id = index.accounts.first().map(|s| s.id.clone());
+    }
+
+    save_account_index_in_dir(data_dir, &index)?;
+    clear_journal_in_dir(data_dir)?;
+
+    crate::modules::logger::log_warn(&format!(
+        "Recovered interrupted account operation from journal: {:?}",
+        record.op
+    ));
+    Ok(Some(record.op))
+}
+
+/// Replay an interrupted account operation left over from a previous run (called at startup)
+pub fn recover_account_journal() -> Result<bool, String> {
+    let _lock = ACCOUNT_INDEX_LOCK
+        .lock()
+        .map_err(|e| format!("failed_to_acquire_lock: {}", e))?;
+    let data_dir = get_data_dir()?;
+    Ok(replay_journal_in_dir(&data_dir)?.is_some())
+}
+
+/// `*.tmp.*` files (left by crashed atomic writes) in the data and accounts dirs older than `min_age`
+pub(crate) fn stale_temp_files_in_dir(
+    data_dir: &Path,
+    min_age: std::time::Duration,
+    now: std::time::SystemTime,
+) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for dir in [data_dir.to_path_buf(), data_dir.join(ACCOUNTS_DIR)] {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_temp = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.contains(".tmp."));
+            if !is_temp || !path.is_file() {
+                continue;
+            }
+            let old_enough = entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|modified| now.duration_since(modified).ok())
+                .is_some_and(|age| age >= min_age);
+            if old_enough {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Delete temp files orphaned by crashed writes (called at startup); returns how many were removed
+pub fn collect_stale_temp_files() -> Result<usize, String> {
+    // Holding the lock keeps this process's own writes out; the age check covers other processes
+    let _lock = lock_accounts()?;
+    let data_dir = get_data_dir()?;
+    let mut removed = 0;
+    for path in stale_temp_files_in_dir(&data_dir, TEMP_FILE_GC_MIN_AGE, std::time::SystemTime::now()) {
+        match fs::remove_file(&path) {
+            Ok(()) => removed += 1,
+            Err(e) => crate::modules::logger::log_warn(&format!(
+                "Failed to remove stale temp file {}: {}",
+                path.display(),
+                e
+            )),
+        }
+    }
+    Ok(removed)
 }
 
 /// Load account data
@@ -661,7 +1431,7 @@ pub fn save_account(account: &Account) -> Result<(), String> {
     let content = serde_json::to_string_pretty(account)
         .map_err(|e| format!("failed_to_serialize_account_data: {}", e))?;
 
-    if let Err(e) = std::fs::write(&temp_path, content) {
+    if let Err(e) = write_file_durable(&temp_path, content.as_bytes()) {
         let _ = std::fs::remove_file(&temp_path);
         return Err(format!("failed_to_write_temp_account_file: {}", e));
     }
@@ -671,9 +1441,160 @@ pub fn save_account(account: &Account) -> Result<(), String> {
         return Err(format!("failed_to_replace_account_file: {}", e));
     }
 
+    crate::modules::device::cache_header_hints(&account.id, account.device_profile.as_ref());
+    Ok(())
+}
+
+// ============================================================================
+// Transactional writes
+// Load-modify-save flows go through with_index_mut / with_account_mut / with_accounts_mut
+// instead of pairing load_* with save_*: the write lock is held for the whole read-modify-write,
+// only what changed is written, the index summary is kept in step with the account file, and
+// one accounts://refreshed is emitted per transaction when list-visible state changed.
+// Journaled operations (add / delete / reorder / sync) keep their own path.
+// The closures run under the lock and must not call back into these helpers.
+// ============================================================================
+
+pub(crate) fn lock_accounts() -> Result<std::sync::MutexGuard<'static, ()>, String> {
+    ACCOUNT_INDEX_LOCK
+        .lock()
+        .map_err(|e| format!("failed_to_acquire_lock: {}", e))
+}
+
+/// Modify the account index in one locked load / save (not written if `f` leaves it unchanged)
+pub fn with_index_mut<T>(
+    f: impl FnOnce(&mut AccountIndex) -> Result<T, String>,
+) -> Result<T, String> {
+    let _lock = lock_accounts()?;
+    let mut index = load_account_index()?;
+    let before = serde_json::to_value(&index).ok();
+    let value = f(&mut index)?;
+    if serde_json::to_value(&index).ok() != before {
+        save_account_index(&index)?;
+        crate::modules::events::accounts_refreshed();
+    }
+    Ok(value)
+}
+
+/// Modify one account in one locked load / save; its index summary is synced in the same pass
+pub fn with_account_mut<T>(
+    account_id: &str,
+    f: impl FnOnce(&mut Account) -> Result<T, String>,
+) -> Result<T, String> {
+    let _lock = lock_accounts()?;
+    let (value, written) = account_mut_locked(account_id, f)?;
+    if let Some(account) = &written {
+        sync_summaries_locked(std::slice::from_ref(account))?;
+    }
+    Ok(value)
+}
+
+/// Per-account outcome of a batched update, in request order
+pub type PerAccountResults<T> = Vec<(String, Result<T, String>)>;
+
+/// Batched `with_account_mut`: all accounts are updated under one lock and the index is saved
+/// at most once. A missing account or a failing closure is reported per account, not propagated.
+pub fn with_accounts_mut<T>(
+    account_ids: &[String],
+    mut f: impl FnMut(&mut Account) -> Result<T, String>,
+) -> Result<PerAccountResults<T>, String> {
+    let _lock = lock_accounts()?;
+    let mut seen = HashSet::new();
+    let mut written = Vec::new();
+    let mut results = Vec::new();
+    for account_id in account_ids.iter().filter(|id| seen.insert(id.as_str())) {
+        let result = account_mut_locked(account_id, &mut f).map(|(value, account)| {
+            written.extend(account);
+            value
+        });
+        results.push((account_id.clone(), result));
+    }
+    sync_summaries_locked(&written)?;
+    Ok(results)
+}
+
+/// Apply `f` to one account (caller holds the lock); returns the account if it was written
+fn account_mut_locked<T>(
+    account_id: &str,
+    f: impl FnOnce(&mut Account) -> Result<T, String>,
+) -> Result<(T, Option<Account>), String> {
+    let mut account = load_account(account_id)?;
+    let before = serde_json::to_value(&account).ok();
+    let value = f(&mut account)?;
+    if serde_json::to_value(&account).ok() == before {
+        return Ok((value, None));
+    }
+    save_account(&account)?;
+    Ok((value, Some(account)))
+}
+
+fn summary_differs(summary: &AccountSummary, updated: &AccountSummary) -> bool {
+    summary.email != updated.email
+        || summary.name != updated.name
+        || summary.disabled != updated.disabled
+        || summary.proxy_disabled != updated.proxy_disabled
+        || summary.protected_models != updated.protected_models
+        || summary.last_used != updated.last_used
+        || summary.subscription_tier != updated.subscription_tier
+}
+
+/// Copy list-visible fields of written accounts into their index summaries (caller holds the lock)
+fn sync_summaries_locked(accounts: &[Account]) -> Result<(), String> {
+    if accounts.is_empty() {
+        return Ok(());
+    }
+    let mut index = load_account_index()?;
+    let mut changed = false;
+    for account in accounts {
+        if let Some(summary) = index.accounts.iter_mut().find(|s| s.id == account.id) {
+            let updated = summary_from_account(account);
+            if summary_differs(summary, &updated) {
+                *summary = AccountSummary {
+                    created_at: summary.created_at,
+                    ..updated
+                };
+                changed = true;
+            }
+        }
+    }
+    if changed {
+        save_account_index(&index)?;
+        crate::modules::events::accounts_refreshed();
+    }
     Ok(())
 }
 
+/// Persist a refreshed token without overwriting fields other writers changed meanwhile
+pub fn save_refreshed_token(account_id: &str, token: &TokenData) -> Result<(), String> {
+    with_account_mut(account_id, |account| {
+        account.token = token.clone();
+        Ok(())
+    })
+}
+
+/// Disable an account whose refresh token was revoked (invalid_grant) and drop it from the proxy pool
+fn disable_for_invalid_grant(account: &mut Account, error: &str) {
+    let now = chrono::Utc::now().timestamp();
+    account.disabled = true;
+    account.disabled_at = Some(now);
+    account.disabled_reason = Some(format!("invalid_grant: {}", error));
+    let reason = account.disabled_reason.clone();
+    let _ = with_account_mut(&account.id, |stored| {
+        stored.disabled = true;
+        stored.disabled_at = Some(now);
+        stored.disabled_reason = reason;
+        Ok(())
+    });
+    crate::modules::events::account_reload(&account.id);
+    crate::modules::notify::push(
+        crate::modules::notify::Severity::Error,
+        "account",
+        format!("Account disabled: {}", account.email),
+        format!("Refresh token was revoked (invalid_grant): {}", error),
+        Some(&format!("account_disabled:{}", account.id)),
+    );
+}
+
 /// List all accounts
 pub fn list_accounts() -> Result<Vec<Account>, String> {
     crate::modules::logger::log_info("Listing accounts...");
@@ -719,28 +1640,25 @@ pub fn add_account(
     let mut account = Account::new(account_id.clone(), email.clone(), token);
     account.name = name.clone();
 
-    // Save account data
-    save_account(&account)?;
+    with_journal(
+        JournalOp::Add {
+            account_ids: vec![account_id.clone()],
+        },
+        || {
+            // Save account data
+            save_account(&account)?;
 
-    // Update index
-    index.accounts.push(AccountSummary {
-        id: account.id.clone(),
-        email: account.email.clone(),
-        name: account.name.clone(),
-        disabled: account.disabled,
-        proxy_disabled: account.proxy_disabled,
-        protected_models: account.protected_models.clone(),
-        created_at: account.created_at,
-        last_used: account.last_used,
-        provider: account.provider.clone(),
-    });
+            // Update index
+            index.accounts.push(summary_from_account(&account));
 
-    // If first account, set as current
-    if index.current_account_id.is_none() {
-        index.current_account_id = Some(account_id);
-    }
+            // If first account, set as current
+            if index.current_account_id.is_none() {
+                index.current_account_id = Some(account_id);
+            }
 
-    save_account_index(&index)?;
+            save_account_index(&index)
+        },
+    )?;
 
     Ok(account)
 }
@@ -753,34 +1671,63 @@ pub fn add_account_raw(account: Account) -> Result<Account, String> {
         .lock()
         .map_err(|e| format!("failed_to_acquire_lock: {}", e))?;
 
-    save_account(&account)?;
+    with_journal(
+        JournalOp::Add {
+            account_ids: vec![account.id.clone()],
+        },
+        || {
+            save_account(&account)?;
 
-    let mut index = load_account_index()?;
+            let mut index = load_account_index()?;
 
-    // Skip if already registered (idempotent)
-    if !index.accounts.iter().any(|s| s.id == account.id) {
-        index.accounts.push(crate::models::AccountSummary {
-            id: account.id.clone(),
-            email: account.email.clone(),
-            name: account.name.clone(),
-            disabled: account.disabled,
-            proxy_disabled: account.proxy_disabled,
-            protected_models: account.protected_models.clone(),
-            created_at: account.created_at,
-            last_used: account.last_used,
-            provider: account.provider.clone(),
-        });
+            // Skip if already registered (idempotent)
+            if !index.accounts.iter().any(|s| s.id == account.id) {
+                index.accounts.push(summary_from_account(&account));
 
-        if index.current_account_id.is_none() {
-            index.current_account_id = Some(account.id.clone());
-        }
+                if index.current_account_id.is_none() {
+                    index.current_account_id = Some(account.id.clone());
+                }
 
-        save_account_index(&index)?;
-    }
+                save_account_index(&index)?;
+            }
+            Ok(())
+        },
+    )?;
 
     Ok(account)
 }
 
+/// Write an account received from another instance (see `modules::sync`):
+/// replaces the file and refreshes its index summary, registering it if it is new
+pub fn apply_synced_account(account: &Account) -> Result<(), String> {
+    let _lock = ACCOUNT_INDEX_LOCK
+        .lock()
+        .map_err(|e| format!("failed_to_acquire_lock: {}", e))?;
+
+    with_journal(
+        JournalOp::Add {
+            account_ids: vec![account.id.clone()],
+        },
+        || {
+            save_account(account)?;
+
+            let mut index = load_account_index()?;
+            let summary = summary_from_account(account);
+            match index.accounts.iter_mut().find(|s| s.id == account.id) {
+                Some(existing) => *existing = summary,
+                None => index.accounts.push(summary),
+            }
+            if index.current_account_id.is_none() {
+                index.current_account_id = Some(account.id.clone());
+            }
+            save_account_index(&index)
+        },
+    )?;
+
+    crate::modules::events::account_reload(&account.id);
+    Ok(())
+}
+
 /// Add or update account
 pub fn upsert_account(
     email: String,
@@ -816,6 +1763,10 @@ pub fn upsert_account(
                     account.disabled = false;
                     account.disabled_reason = None;
                     account.disabled_at = None;
+                    crate::modules::scheduler::schedule_warmup_after_reenable(
+                        &account.id,
+                        "credentials updated",
+                    );
                 }
                 account.update_last_used();
                 save_account(&account)?;
@@ -858,12 +1809,39 @@ pub fn upsert_account(
     add_account(email, name, token)
 }
 
-/// Delete account
-pub fn delete_account(account_id: &str) -> Result<(), String> {
+/// Domain policy: accounts with `confirm_delete` can only be deleted once the caller confirmed
+fn ensure_delete_confirmed(
+    index: &AccountIndex,
+    account_ids: &[String],
+    confirmed: bool,
+) -> Result<(), String> {
+    if confirmed {
+        return Ok(());
+    }
+    let policies = crate::modules::domain_policy::current();
+    let protected: Vec<&str> = index
+        .accounts
+        .iter()
+        .filter(|s| account_ids.contains(&s.id))
+        .filter(|s| crate::modules::domain_policy::rules_in(&policies, &s.email).confirm_delete)
+        .map(|s| s.email.as_str())
+        .collect();
+    if protected.is_empty() {
+        return Ok(());
+    }
+    Err(format!(
+        "delete_requires_confirmation: {} (domain policy)",
+        protected.join(", ")
+    ))
+}
+
+/// Delete account (`confirmed` is required for accounts covered by a confirm_delete domain policy)
+pub fn delete_account(account_id: &str, confirmed: bool) -> Result<(), String> {
     let _lock = ACCOUNT_INDEX_LOCK
         .lock()
         .map_err(|e| format!("failed_to_acquire_lock: {}", e))?;
     let mut index = load_account_index()?;
+    ensure_delete_confirmed(&index, &[account_id.to_string()], confirmed)?;
 
     // Remove from index
     let original_len = index.accounts.len();
@@ -878,29 +1856,39 @@ pub fn delete_account(account_id: &str) -> Result<(), String> {
         index.current_account_id = index.accounts.first().map(|s| s.id.clone());
     }
 
-    save_account_index(&index)?;
+    with_journal(
+        JournalOp::Delete {
+            account_ids: vec![account_id.to_string()],
+        },
+        || {
+            save_account_index(&index)?;
 
-    // Delete account file
-    let accounts_dir = get_accounts_dir()?;
-    let account_path = accounts_dir.join(format!("{}.json", account_id));
+            // Delete account file
+            let accounts_dir = get_accounts_dir()?;
+            let account_path = accounts_dir.join(format!("{}.json", account_id));
 
-    if account_path.exists() {
-        fs::remove_file(&account_path)
-            .map_err(|e| format!("failed_to_delete_account_file: {}", e))?;
-    }
+            if account_path.exists() {
+                fs::remove_file(&account_path)
+                    .map_err(|e| format!("failed_to_delete_account_file: {}", e))?;
+            }
+            Ok(())
+        },
+    )?;
 
     // [FIX #1477] Trigger TokenManager cache cleanup signal
-    crate::proxy::server::trigger_account_delete(account_id);
+    crate::modules::events::account_deleted(account_id);
+    crate::modules::project_binding::remove(account_id);
 
     Ok(())
 }
 
 /// Batch delete accounts (atomic index operation)
-pub fn delete_accounts(account_ids: &[String]) -> Result<(), String> {
+pub fn delete_accounts(account_ids: &[String], confirmed: bool) -> Result<(), String> {
     let _lock = ACCOUNT_INDEX_LOCK
         .lock()
         .map_err(|e| format!("failed_to_acquire_lock: {}", e))?;
     let mut index = load_account_index()?;
+    ensure_delete_confirmed(&index, account_ids, confirmed)?;
 
     let accounts_dir = get_accounts_dir()?;
 
@@ -912,15 +1900,6 @@ pub fn delete_accounts(account_ids: &[String]) -> Result<(), String> {
         if index.current_account_id.as_deref() == Some(account_id) {
             index.current_account_id = None;
         }
-
-        // Delete account file
-        let account_path = accounts_dir.join(format!("{}.json", account_id));
-        if account_path.exists() {
-            let _ = fs::remove_file(&account_path);
-        }
-
-        // [FIX #1477] Trigger TokenManager cache cleanup signal
-        crate::proxy::server::trigger_account_delete(account_id);
     }
 
     // If current account is empty, use first one as default
@@ -928,7 +1907,28 @@ pub fn delete_accounts(account_ids: &[String]) -> Result<(), String> {
         index.current_account_id = index.accounts.first().map(|s| s.id.clone());
     }
 
-    save_account_index(&index)
+    // Index first, then files: an interrupted batch is finished by journal replay
+    with_journal(
+        JournalOp::Delete {
+            account_ids: account_ids.to_vec(),
+        },
+        || {
+            save_account_index(&index)?;
+
+            for account_id in account_ids {
+                // Delete account file
+                let account_path = accounts_dir.join(format!("{}.json", account_id));
+                if account_path.exists() {
+                    let _ = fs::remove_file(&account_path);
+                }
+
+                // [FIX #1477] Trigger TokenManager cache cleanup signal
+                crate::modules::events::account_deleted(account_id);
+                crate::modules::project_binding::remove(account_id);
+            }
+            Ok(())
+        },
+    )
 }
 
 /// Reorder account list
@@ -968,16 +1968,509 @@ pub fn reorder_accounts(account_ids: &[String]) -> Result<(), String> {
         index.accounts.len()
     ));
 
-    save_account_index(&index)
+    let order: Vec<String> = index.accounts.iter().map(|s| s.id.clone()).collect();
+    with_journal(JournalOp::Reorder { account_ids: order }, || {
+        save_account_index(&index)
+    })
+}
+
+// ============================================================================
+// Data integrity check
+// Cross-checks the index against account files and proposes a repair plan.
+// Nothing is changed until the user applies the plan.
+// ============================================================================
+
+/// Accounts sharing the same email
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DuplicateEmail {
+    pub email: String,
+    pub account_ids: Vec<String>,
+}
+
+/// A single repair step proposed by `verify_data_integrity`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum RepairAction {
+    /// Add an account file that is missing from the index
+    AdoptOrphan { account_id: String, email: String },
+    /// Drop an index entry whose account file is missing or unreadable
+    RemoveDanglingEntry { account_id: String, email: String },
+    /// Delete a duplicate account, keeping the most recently used one
+    RemoveDuplicate {
+        account_id: String,
+        email: String,
+        keep_account_id: String,
+    },
+    /// Move an unparseable `accounts/*.json` file to `accounts/corrupt/`
+    QuarantineUnreadable { file_name: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    pub checked_at: i64,
+    pub index_entries: usize,
+    pub account_files: usize,
+    /// Account files on disk that are not in the index
+    pub orphan_files: Vec<String>,
+    /// Index entries without a readable account file
+    pub dangling_entries: Vec<String>,
+    /// `accounts/*.json` files that could not be parsed
+    pub unreadable_files: Vec<String>,
+    pub duplicate_emails: Vec<DuplicateEmail>,
+    pub repair_plan: Vec<RepairAction>,
+}
+
+impl IntegrityReport {
+    pub fn is_clean(&self) -> bool {
+        self.orphan_files.is_empty()
+            && self.dangling_entries.is_empty()
+            && self.unreadable_files.is_empty()
+            && self.duplicate_emails.is_empty()
+    }
+}
+
+/// Scan `accounts/*.json`, returning parsed accounts keyed by file stem and unreadable file names
+fn scan_account_files_in_dir(data_dir: &Path) -> (HashMap<String, Account>, Vec<String>) {
+    let accounts_dir = data_dir.join(ACCOUNTS_DIR);
+    let mut accounts = HashMap::new();
+    let mut unreadable = Vec::new();
+
+    if let Ok(entries) = fs::read_dir(&accounts_dir) {
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            match load_account_at_path(&path) {
+                Ok(account) => {
+                    accounts.insert(stem.to_string(), account);
+                }
+                Err(_) => unreadable.push(format!("{}.json", stem)),
+            }
+        }
+    }
+    unreadable.sort();
+    (accounts, unreadable)
+}
+
+fn verify_data_integrity_in_dir(data_dir: &PathBuf) -> Result<IntegrityReport, String> {
+    let index = load_account_index_in_dir(data_dir)?;
+    let (files, unreadable_files) = scan_account_files_in_dir(data_dir);
+
+    let indexed: HashSet<&str> = index.accounts.iter().map(|s| s.id.as_str()).collect();
+
+    let mut dangling_entries = Vec::new();
+    let mut repair_plan = Vec::new();
+    for summary in &index.accounts {
+        if !files.contains_key(&summary.id) {
+            dangling_entries.push(summary.id.clone());
+            repair_plan.push(RepairAction::RemoveDanglingEntry {
+                account_id: summary.id.clone(),
+                email: summary.email.clone(),
+            });
+        }
+    }
+
+    // Duplicate emails among live (indexed + readable) accounts
+    let mut by_email: HashMap<String, Vec<&Account>> = HashMap::new();
+    for summary in &index.accounts {
+        if let Some(account) = files.get(&summary.id) {
+            by_email
+                .entry(account.email.to_lowercase())
+                .or_default()
+                .push(account);
+        }
+    }
+    let mut duplicate_emails = Vec::new();
+    for (_, mut accounts) in by_email.into_iter().filter(|(_, v)| v.len() > 1) {
+        accounts.sort_by(|a, b| b.last_used.cmp(&a.last_used).then_with(|| a.id.cmp(&b.id)));
+        let keep = accounts[0];
+        for dup in &accounts[1..] {
+            repair_plan.push(RepairAction::RemoveDuplicate {
+                account_id: dup.id.clone(),
+                email: dup.email.clone(),
+                keep_account_id: keep.id.clone(),
+            });
+        }
+        duplicate_emails.push(DuplicateEmail {
+            email: keep.email.clone(),
+            account_ids: accounts.iter().map(|a| a.id.clone()).collect(),
+        });
+    }
+    duplicate_emails.sort_by(|a, b| a.email.cmp(&b.email));
+
+    // Orphans: adopt unless the email is already served by an indexed account
+    let live_emails: HashSet<String> = index
+        .accounts
+        .iter()
+        .filter_map(|s| files.get(&s.id))
+        .map(|a| a.email.to_lowercase())
+        .collect();
+    let mut orphan_files: Vec<String> = files
+        .keys()
+        .filter(|id| !indexed.contains(id.as_str()))
+        .cloned()
+        .collect();
+    orphan_files.sort();
+    for id in &orphan_files {
+        let account = &files[id];
+        if account.id == *id && !live_emails.contains(&account.email.to_lowercase()) {
+            repair_plan.push(RepairAction::AdoptOrphan {
+                account_id: id.clone(),
+                email: account.email.clone(),
+            });
+        }
+    }
+
+    for file_name in &unreadable_files {
+        repair_plan.push(RepairAction::QuarantineUnreadable {
+            file_name: file_name.clone(),
+        });
+    }
+
+    Ok(IntegrityReport {
+        checked_at: chrono::Utc::now().timestamp(),
+        index_entries: index.accounts.len(),
+        account_files: files.len() + unreadable_files.len(),
+        orphan_files,
+        dangling_entries,
+        unreadable_files,
+        duplicate_emails,
+        repair_plan,
+    })
+}
+
+/// Cross-check the account index against account files (read-only)
+pub fn verify_data_integrity() -> Result<IntegrityReport, String> {
+    let _lock = ACCOUNT_INDEX_LOCK
+        .lock()
+        .map_err(|e| format!("failed_to_acquire_lock: {}", e))?;
+    let data_dir = get_data_dir()?;
+    verify_data_integrity_in_dir(&data_dir)
+}
+
+fn summary_from_account(account: &Account) -> AccountSummary {
+    AccountSummary {
+        id: account.id.clone(),
+        email: account.email.clone(),
+        name: account.name.clone(),
+        disabled: account.disabled,
+        proxy_disabled: account.proxy_disabled,
+        protected_models: account.protected_models.clone(),
+        created_at: account.created_at,
+        last_used: account.last_used,
+        provider: account.provider.clone(),
+        subscription_tier: account
+            .quota
+            .as_ref()
+            .and_then(|q| q.subscription_tier.clone()),
+    }
+}
+
+/// Insert the summary of an on-disk account file into `index` (caller holds the lock)
+fn adopt_orphan_into_index(
+    data_dir: &Path,
+    index: &mut AccountIndex,
+    account_id: &str,
+) -> Result<AccountSummary, String> {
+    if index.accounts.iter().any(|s| s.id == account_id) {
+        return Err(format!("account_already_indexed: {}", account_id));
+    }
+    let path = data_dir.join(ACCOUNTS_DIR).join(format!("{}.json", account_id));
+    if !path.exists() {
+        return Err(format!("account_file_not_found: {}", account_id));
+    }
+    let account = load_account_at_path(&path)?;
+    if account.id != account_id {
+        return Err(format!(
+            "account_id_mismatch: file {} contains {}",
+            account_id, account.id
+        ));
+    }
+
+    let summary = summary_from_account(&account);
+    index.accounts.push(summary.clone());
+    if index.current_account_id.is_none() {
+        index.current_account_id = Some(summary.id.clone());
+    }
+    Ok(summary)
+}
+
+fn list_orphan_accounts_in_dir(data_dir: &PathBuf) -> Result<Vec<AccountSummary>, String> {
+    let index = load_account_index_in_dir(data_dir)?;
+    let indexed: HashSet<&str> = index.accounts.iter().map(|s| s.id.as_str()).collect();
+    let (files, _) = scan_account_files_in_dir(data_dir);
+
+    let mut orphans: Vec<AccountSummary> = files
+        .iter()
+        .filter(|(id, _)| !indexed.contains(id.as_str()))
+        .map(|(_, account)| summary_from_account(account))
+        .collect();
+    orphans.sort_by(|a, b| b.last_used.cmp(&a.last_used).then_with(|| a.email.cmp(&b.email)));
+    Ok(orphans)
+}
+
+/// List readable account files that are not referenced by the index
+/// (e.g. restored from a backup)
+pub fn list_orphan_accounts() -> Result<Vec<AccountSummary>, String> {
+    let _lock = ACCOUNT_INDEX_LOCK
+        .lock()
+        .map_err(|e| format!("failed_to_acquire_lock: {}", e))?;
+    let data_dir = get_data_dir()?;
+    list_orphan_accounts_in_dir(&data_dir)
+}
+
+/// Add a single orphan account file back into the index
+pub fn adopt_orphan(account_id: &str) -> Result<AccountSummary, String> {
+    let _lock = ACCOUNT_INDEX_LOCK
+        .lock()
+        .map_err(|e| format!("failed_to_acquire_lock: {}", e))?;
+    let data_dir = get_data_dir()?;
+    let mut index = load_account_index_in_dir(&data_dir)?;
+    let summary = adopt_orphan_into_index(&data_dir, &mut index, account_id)?;
+    save_account_index_in_dir(&data_dir, &index)?;
+
+    crate::modules::logger::log_info(&format!(
+        "Adopted orphan account: {} ({})",
+        summary.email, summary.id
+    ));
+    Ok(summary)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CorruptAccountFile {
+    pub file_name: String,
+    pub account_id: String,
+    pub quarantined_at: i64,
+    pub size: u64,
+    /// Parse error of the current content (None once the file parses again, e.g. after a manual fix)
+    pub error: Option<String>,
+    /// The index still references the account (its entry stays dangling until restored)
+    pub indexed: bool,
+}
+
+/// Split `<id>.json.corrupt-<timestamp>-<uuid>` into (account id, timestamp)
+fn parse_corrupt_file_name(file_name: &str) -> Option<(String, i64)> {
+    if file_name.contains(['/', '\\']) {
+        return None;
+    }
+    let (stem, rest) = file_name.split_once(".json.corrupt-")?;
+    let timestamp = rest.split('-').next()?.parse().ok()?;
+    (!stem.is_empty()).then(|| (stem.to_string(), timestamp))
+}
+
+fn corrupt_file_path(data_dir: &Path, file_name: &str) -> Result<(PathBuf, String), String> {
+    let (account_id, _) = parse_corrupt_file_name(file_name)
+        .ok_or_else(|| format!("invalid_corrupt_file: {}", file_name))?;
+    let path = data_dir.join(ACCOUNTS_DIR).join(CORRUPT_DIR).join(file_name);
+    if !path.is_file() {
+        return Err(format!("corrupt_file_not_found: {}", file_name));
+    }
+    Ok((path, account_id))
+}
+
+fn list_corrupt_accounts_in_dir(data_dir: &PathBuf) -> Result<Vec<CorruptAccountFile>, String> {
+    let index = load_account_index_in_dir(data_dir)?;
+    let mut files = Vec::new();
+    let Ok(entries) = fs::read_dir(data_dir.join(ACCOUNTS_DIR).join(CORRUPT_DIR)) else {
+        return Ok(files);
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        let Some((account_id, quarantined_at)) = parse_corrupt_file_name(&file_name) else {
+            continue;
+        };
+        let error = match fs::read(entry.path()) {
+            Ok(content) => serde_json::from_slice::<Account>(&content)
+                .err()
+                .map(|e| e.to_string()),
+            Err(e) => Some(e.to_string()),
+        };
+        files.push(CorruptAccountFile {
+            size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+            indexed: index.accounts.iter().any(|s| s.id == account_id),
+            file_name,
+            account_id,
+            quarantined_at,
+            error,
+        });
+    }
+    files.sort_by(|a, b| {
+        b.quarantined_at
+            .cmp(&a.quarantined_at)
+            .then_with(|| a.file_name.cmp(&b.file_name))
+    });
+    Ok(files)
+}
+
+/// Move a quarantined file back into `accounts/` once it parses again and add it to the index
+/// if needed (caller holds the lock and saves the index)
+fn restore_corrupt_account_in_dir(
+    data_dir: &Path,
+    index: &mut AccountIndex,
+    file_name: &str,
+) -> Result<AccountSummary, String> {
+    let (path, account_id) = corrupt_file_path(data_dir, file_name)?;
+    let content = fs::read(&path).map_err(|e| format!("failed_to_read_account_data: {}", e))?;
+    let account: Account = serde_json::from_slice(&content)
+        .map_err(|e| format!("corrupt_file_unparseable: {}", e))?;
+    if account.id != account_id {
+        return Err(format!(
+            "account_id_mismatch: file {} contains {}",
+            file_name, account.id
+        ));
+    }
+    let target = data_dir.join(ACCOUNTS_DIR).join(format!("{}.json", account_id));
+    if target.exists() {
+        return Err(format!("account_file_exists: {}", account_id));
+    }
+    atomic_replace_file(&path, &target)?;
+
+    match index.accounts.iter().find(|s| s.id == account_id) {
+        Some(summary) => Ok(summary.clone()),
+        None => adopt_orphan_into_index(data_dir, index, &account_id),
+    }
+}
+
+/// List quarantined unparseable account files
+pub fn list_corrupt_accounts() -> Result<Vec<CorruptAccountFile>, String> {
+    let _lock = lock_accounts()?;
+    let data_dir = get_data_dir()?;
+    list_corrupt_accounts_in_dir(&data_dir)
+}
+
+/// Restore a quarantined account file (after it has been fixed by hand)
+pub fn restore_corrupt_account(file_name: &str) -> Result<AccountSummary, String> {
+    let _lock = lock_accounts()?;
+    let data_dir = get_data_dir()?;
+    let mut index = load_account_index_in_dir(&data_dir)?;
+    let summary = restore_corrupt_account_in_dir(&data_dir, &mut index, file_name)?;
+    save_account_index_in_dir(&data_dir, &index)?;
+
+    crate::modules::logger::log_info(&format!(
+        "Restored quarantined account file {} ({})",
+        file_name, summary.email
+    ));
+    Ok(summary)
+}
+
+/// Permanently delete a quarantined account file
+pub fn delete_corrupt_account(file_name: &str) -> Result<(), String> {
+    let _lock = lock_accounts()?;
+    let data_dir = get_data_dir()?;
+    let (path, _) = corrupt_file_path(&data_dir, file_name)?;
+    fs::remove_file(&path).map_err(|e| format!("failed_to_delete_file: {}", e))?;
+    crate::modules::logger::log_info(&format!("Deleted quarantined account file {}", file_name));
+    Ok(())
+}
+
+/// Apply (a subset of) a repair plan produced by `verify_data_integrity`,
+/// then return a fresh report
+pub fn apply_repair_plan(plan: &[RepairAction]) -> Result<IntegrityReport, String> {
+    let mut duplicate_ids = Vec::new();
+    {
+        let _lock = ACCOUNT_INDEX_LOCK
+            .lock()
+            .map_err(|e| format!("failed_to_acquire_lock: {}", e))?;
+        let data_dir = get_data_dir()?;
+        let mut index = load_account_index_in_dir(&data_dir)?;
+        let mut changed = false;
+
+        for action in plan {
+            match action {
+                RepairAction::AdoptOrphan { account_id, .. } => {
+                    if index.accounts.iter().any(|s| &s.id == account_id) {
+                        continue;
+                    }
+                    adopt_orphan_into_index(&data_dir, &mut index, account_id)?;
+                    changed = true;
+                }
+                RepairAction::RemoveDanglingEntry { account_id, .. } => {
+                    let before = index.accounts.len();
+                    index.accounts.retain(|s| &s.id != account_id);
+                    changed |= index.accounts.len() != before;
+                }
+                RepairAction::RemoveDuplicate { account_id, .. } => {
+                    duplicate_ids.push(account_id.clone());
+                }
+                RepairAction::QuarantineUnreadable { file_name } => {
+                    // The plan may come from the HTTP API: only bare `*.json` names in accounts/
+                    if !file_name.ends_with(".json")
+                        || Path::new(file_name).file_name().and_then(|n| n.to_str())
+                            != Some(file_name.as_str())
+                    {
+                        return Err(format!("invalid_account_file_name: {}", file_name));
+                    }
+                    let path = data_dir.join(ACCOUNTS_DIR).join(file_name);
+                    // Skip files that were fixed (or removed) since the report
+                    if path.exists() && load_account_at_path(&path).is_err() {
+                        quarantine_corrupt_file(&path);
+                    }
+                }
+            }
+        }
+
+        if changed {
+            let current_valid = index
+                .current_account_id
+                .as_ref()
+                .is_some_and(|id| index.accounts.iter().any(|s| &s.id == id));
+            if !current_valid {
+                index.current_account_id = index.accounts.first().map(|s| s.id.clone());
+            }
+            save_account_index_in_dir(&data_dir, &index)?;
+        }
+    }
+
+    if !duplicate_ids.is_empty() {
+        // Only redundant copies are removed, the account itself is kept
+        delete_accounts(&duplicate_ids, true)?;
+    }
+
+    crate::modules::logger::log_info(&format!(
+        "Applied {} integrity repair action(s)",
+        plan.len()
+    ));
+    verify_data_integrity()
+}
+
+/// Report of the most recent switch (successful or not)
+static LAST_SWITCH_REPORT: Mutex<Option<SwitchReport>> = Mutex::new(None);
+
+pub fn last_switch_report() -> Option<SwitchReport> {
+    LAST_SWITCH_REPORT
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
 }
 
 /// Switch current account (Core Logic)
+/// Runs in phases recorded in the returned `SwitchReport`; when the system integration fails
+/// halfway it restores the previous files / process and the current account stays unchanged.
+/// Only one switch runs at a time: a request for the account already being switched to waits for
+/// that switch, any other request fails with `switch_in_progress` (see `switch_queue`).
 pub async fn switch_account(
     account_id: &str,
     integration: &(impl modules::integration::SystemIntegration + ?Sized),
-) -> Result<(), String> {
-    use crate::modules::oauth;
+) -> Result<SwitchReport, String> {
+    use crate::modules::switch_queue::{self, Ticket};
 
+    let guard = match switch_queue::begin(account_id)? {
+        Ticket::Leader(guard) => guard,
+        Ticket::Follower(rx) => return switch_queue::wait(rx).await,
+    };
+    let result = switch_account_exclusive(account_id, integration).await;
+    guard.complete(&result);
+    result
+}
+
+async fn switch_account_exclusive(
+    account_id: &str,
+    integration: &(impl modules::integration::SystemIntegration + ?Sized),
+) -> Result<SwitchReport, String> {
     let index = {
         let _lock = ACCOUNT_INDEX_LOCK
             .lock()
@@ -996,27 +2489,68 @@ pub async fn switch_account(
         account.email, account.id
     ));
 
+    let previous_id = index
+        .current_account_id
+        .clone()
+        .filter(|id| id != account_id);
+    let mut report = SwitchReport::new(account_id, &account.email, previous_id.clone());
+    let result = run_switch_phases(
+        &mut account,
+        previous_id.as_deref(),
+        integration,
+        &mut report,
+    )
+    .await;
+
+    report.finish(result.is_ok());
+    *LAST_SWITCH_REPORT.lock().unwrap_or_else(|e| e.into_inner()) = Some(report.clone());
+    crate::modules::log_bridge::emit_event("account://switch-report", report.clone());
+
+    match result {
+        Ok(()) => {
+            crate::modules::logger::log_info(&format!(
+                "Account switch core logic completed: {}",
+                account.email
+            ));
+            crate::modules::event_feed::record(
+                crate::modules::event_feed::EventKind::Switch,
+                account.email.clone(),
+            );
+            Ok(report)
+        }
+        Err(e) if report.rolled_back => Err(format!("{} (changes rolled back)", e)),
+        Err(e) => Err(e),
+    }
+}
+
+async fn run_switch_phases(
+    account: &mut Account,
+    previous_id: Option<&str>,
+    integration: &(impl modules::integration::SystemIntegration + ?Sized),
+    report: &mut SwitchReport,
+) -> Result<(), String> {
+    use crate::modules::oauth;
+
     // 2. Ensure Token is valid (auto-refresh)
-    let fresh_token = match account.provider {
+    let refreshed = match account.provider {
         crate::models::AccountProvider::Codex => {
-            match crate::modules::codex_oauth::ensure_codex_fresh_token(&account.token).await
-                .map_err(|e| format!("Token refresh failed: {}", e))?
-            {
-                Some(new_token) => new_token,
-                None => account.token.clone(), // still fresh
-            }
+            crate::modules::codex_oauth::ensure_codex_fresh_token(&account.token)
+                .await
+                .map(|new_token| new_token.unwrap_or_else(|| account.token.clone())) // None: still fresh
         }
         crate::models::AccountProvider::Google => {
-            oauth::ensure_fresh_token(&account.token, Some(&account.id))
-                .await
-                .map_err(|e| format!("Token refresh failed: {}", e))?
+            oauth::ensure_fresh_token(&account.token, Some(&account.id)).await
         }
     };
+    let fresh_token = report.track(
+        SwitchPhase::RefreshToken,
+        refreshed.map_err(|e| format!("Token refresh failed: {}", e)),
+    )?;
 
     // If Token updated, save back to account file
     if fresh_token.access_token != account.token.access_token {
-        account.token = fresh_token.clone();
-        save_account(&account)?;
+        account.token = fresh_token;
+        save_refreshed_token(&account.id, &account.token)?;
     }
 
     // [FIX] Ensure account has a device profile for isolation
@@ -1026,40 +2560,357 @@ pub async fn switch_account(
             account.email
         ));
         let new_profile = modules::device::generate_profile();
-        apply_profile_to_account(
-            &mut account,
-            new_profile.clone(),
+        let applied = apply_profile_to_account(
+            account,
+            new_profile,
             Some("auto_generated".to_string()),
             true,
-        )?;
+        );
+        report.track(SwitchPhase::DeviceProfile, applied)?;
     }
 
     // 3. Execute platform-specific system integration (Close proc, Inject DB, Start proc, etc.)
-    integration.on_account_switch(&account).await?;
+    //    The integration rolls back its own changes when a later phase fails
+    let previous = previous_id.and_then(|id| load_account(id).ok());
+    integration
+        .on_account_switch(account, previous.as_ref(), report)
+        .await?;
 
     // 4. Update tool internal state
-    {
-        let _lock = ACCOUNT_INDEX_LOCK
-            .lock()
-            .map_err(|e| format!("failed_to_acquire_lock: {}", e))?;
-        let mut index = load_account_index()?;
-        index.current_account_id = Some(account_id.to_string());
-        save_account_index(&index)?;
-    }
+    let updated = with_index_mut(|index| {
+        let previous = index.current_account_id.replace(account.id.clone());
+        if previous.as_deref() != Some(account.id.as_str()) {
+            push_switch_history(index, previous, &account.id, chrono::Utc::now().timestamp());
+        }
+        Ok(())
+    });
+    report.track(SwitchPhase::UpdateIndex, updated)?;
 
     account.update_last_used();
-    save_account(&account)?;
+    let last_used = account.last_used;
+    with_account_mut(&account.id, |stored| {
+        stored.last_used = last_used;
+        Ok(())
+    })?;
+    Ok(())
+}
 
-    crate::modules::logger::log_info(&format!(
-        "Account switch core logic completed: {}",
-        account.email
-    ));
+const MAX_SWITCH_HISTORY: usize = 20;
+
+fn push_switch_history(
+    index: &mut AccountIndex,
+    from_id: Option<String>,
+    to_id: &str,
+    timestamp: i64,
+) {
+    index.switch_history.push(crate::models::SwitchHistoryEntry {
+        from_id,
+        to_id: to_id.to_string(),
+        timestamp,
+    });
+    if index.switch_history.len() > MAX_SWITCH_HISTORY {
+        let excess = index.switch_history.len() - MAX_SWITCH_HISTORY;
+        index.switch_history.drain(..excess);
+    }
+}
 
-    Ok(())
+/// 上一个账号: 最近一次切换之前所在、且仍存在的账号
+fn previous_account_in(index: &AccountIndex) -> Option<String> {
+    let current = index.current_account_id.as_deref();
+    index
+        .switch_history
+        .iter()
+        .rev()
+        .filter_map(|entry| entry.from_id.as_deref())
+        .find(|id| Some(*id) != current && index.accounts.iter().any(|s| s.id == *id))
+        .map(str::to_string)
 }
 
-/// Get device profile info: current storage.json + account bound profile
-#[derive(Debug, Serialize)]
+/// 最近的账号切换记录 (最新在前)
+pub fn get_switch_history() -> Result<Vec<crate::models::SwitchHistoryEntry>, String> {
+    let mut history = load_account_index()?.switch_history;
+    history.reverse();
+    Ok(history)
+}
+
+/// 切换前所在的账号 ID (没有可切回的账号时为 None)
+pub fn previous_account_id() -> Result<Option<String>, String> {
+    Ok(previous_account_in(&load_account_index()?))
+}
+
+/// 切回上一个账号，返回切换到的账号 ID
+pub async fn switch_back(
+    integration: &(impl modules::integration::SystemIntegration + ?Sized),
+) -> Result<String, String> {
+    let previous = previous_account_id()?.ok_or_else(|| "no_previous_account".to_string())?;
+    switch_account(&previous, integration).await?;
+    Ok(previous)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationStatus {
+    Ok,
+    Warning,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VerificationStep {
+    /// load_account / refresh_token / resolve_project / fetch_quota / device_profile
+    pub name: String,
+    pub status: VerificationStatus,
+    pub message: Option<String>,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LoginVerification {
+    pub account_id: String,
+    pub email: Option<String>,
+    /// No step failed
+    pub passed: bool,
+    pub steps: Vec<VerificationStep>,
+}
+
+impl LoginVerification {
+    fn push(
+        &mut self,
+        name: &str,
+        started: std::time::Instant,
+        status: VerificationStatus,
+        message: Option<String>,
+    ) {
+        if status == VerificationStatus::Failed {
+            self.passed = false;
+        }
+        self.steps.push(VerificationStep {
+            name: name.to_string(),
+            status,
+            message,
+            duration_ms: started.elapsed().as_millis() as u64,
+        });
+    }
+
+    fn skip(&mut self, names: &[&str], reason: &str) {
+        for name in names {
+            self.push(
+                name,
+                std::time::Instant::now(),
+                VerificationStatus::Skipped,
+                Some(reason.to_string()),
+            );
+        }
+    }
+}
+
+/// Problems with a bound device profile that would be written into storage.json on switch
+fn device_profile_problems(profile: &DeviceProfile) -> Vec<&'static str> {
+    let mut problems = Vec::new();
+    if !profile.machine_id.starts_with("auth0|user_") {
+        problems.push("machine_id");
+    }
+    if profile.mac_machine_id.trim().is_empty() {
+        problems.push("mac_machine_id");
+    }
+    if Uuid::parse_str(&profile.dev_device_id).is_err() {
+        problems.push("dev_device_id");
+    }
+    let sqm = profile.sqm_id.trim_start_matches('{').trim_end_matches('}');
+    if Uuid::parse_str(sqm).is_err() {
+        problems.push("sqm_id");
+    }
+    problems
+}
+
+/// Dry-run the switch pipeline for an account: refresh the token, resolve the project,
+/// hit the quota endpoint and validate the device profile. Antigravity, the current
+/// account and quota data are left untouched; only a refreshed access token is saved.
+pub async fn verify_account_login(account_id: &str) -> Result<LoginVerification, String> {
+    use std::time::Instant;
+
+    const NETWORK_STEPS: [&str; 3] = ["refresh_token", "resolve_project", "fetch_quota"];
+
+    let mut report = LoginVerification {
+        account_id: account_id.to_string(),
+        email: None,
+        passed: true,
+        steps: Vec::new(),
+    };
+
+    // 1. Load
+    let started = Instant::now();
+    let mut account = match load_account(account_id) {
+        Ok(account) => account,
+        Err(e) => {
+            report.push("load_account", started, VerificationStatus::Failed, Some(e));
+            report.skip(&NETWORK_STEPS, "account_not_loaded");
+            report.skip(&["device_profile"], "account_not_loaded");
+            return Ok(report);
+        }
+    };
+    report.email = Some(account.email.clone());
+    let state_note = if account.disabled {
+        Some(format!(
+            "account disabled: {}",
+            account.disabled_reason.as_deref().unwrap_or("-")
+        ))
+    } else if account.proxy_disabled {
+        Some(format!(
+            "proxy disabled: {}",
+            account.proxy_disabled_reason.as_deref().unwrap_or("-")
+        ))
+    } else {
+        None
+    };
+    let status = if state_note.is_some() {
+        VerificationStatus::Warning
+    } else {
+        VerificationStatus::Ok
+    };
+    report.push("load_account", started, status, state_note);
+
+    // 2-4. Network steps
+    if let Err(e) = crate::modules::offline::ensure_online() {
+        report.skip(&NETWORK_STEPS, &e);
+    } else {
+        let started = Instant::now();
+        let refreshed = match account.provider {
+            crate::models::AccountProvider::Codex => {
+                crate::modules::codex_oauth::ensure_codex_fresh_token(&account.token)
+                    .await
+                    .map(|t| t.unwrap_or_else(|| account.token.clone()))
+            }
+            // 强制刷新一次，确认 refresh_token 仍然有效
+            crate::models::AccountProvider::Google => {
+                modules::oauth::refresh_access_token(&account.token.refresh_token, Some(&account.id))
+                    .await
+                    .map(|response| {
+                        TokenData::new(
+                            response.access_token,
+                            account.token.refresh_token.clone(),
+                            response.expires_in,
+                            account.token.email.clone(),
+                            account.token.project_id.clone(),
+                            None,
+                        )
+                    })
+            }
+        };
+
+        match refreshed {
+            Err(e) => {
+                report.push("refresh_token", started, VerificationStatus::Failed, Some(e));
+                report.skip(&NETWORK_STEPS[1..], "token_refresh_failed");
+            }
+            Ok(token) => {
+                if token.access_token != account.token.access_token {
+                    account.token = token;
+                    save_refreshed_token(&account.id, &account.token)?;
+                }
+                report.push("refresh_token", started, VerificationStatus::Ok, None);
+
+                if account.provider == crate::models::AccountProvider::Codex {
+                    report.skip(&NETWORK_STEPS[1..], "not_applicable_for_codex");
+                } else {
+                    let started = Instant::now();
+                    let (project_id, tier) = modules::quota::fetch_project_id(
+                        &account.token.access_token,
+                        &account.email,
+                        Some(&account.id),
+                    )
+                    .await;
+                    match &project_id {
+                        Some(pid) => report.push(
+                            "resolve_project",
+                            started,
+                            VerificationStatus::Ok,
+                            Some(match &tier {
+                                Some(tier) => format!("{} ({})", pid, tier),
+                                None => pid.clone(),
+                            }),
+                        ),
+                        None => report.push(
+                            "resolve_project",
+                            started,
+                            VerificationStatus::Warning,
+                            Some("loadCodeAssist returned no project_id".to_string()),
+                        ),
+                    }
+
+                    let started = Instant::now();
+                    match modules::quota::fetch_quota_with_cache(
+                        &account.token.access_token,
+                        &account.email,
+                        project_id.as_deref(),
+                        Some(&account.id),
+                    )
+                    .await
+                    {
+                        Ok((quota, _)) if quota.is_forbidden => report.push(
+                            "fetch_quota",
+                            started,
+                            VerificationStatus::Failed,
+                            Some(format!(
+                                "forbidden: {}",
+                                quota.forbidden_reason.as_deref().unwrap_or("-")
+                            )),
+                        ),
+                        Ok((quota, _)) => {
+                            let lowest = quota.models.iter().map(|m| m.percentage).min();
+                            report.push(
+                                "fetch_quota",
+                                started,
+                                VerificationStatus::Ok,
+                                Some(format!(
+                                    "{} models, lowest {}",
+                                    quota.models.len(),
+                                    lowest.map_or("-".to_string(), |p| format!("{}%", p))
+                                )),
+                            );
+                        }
+                        Err(e) => report.push("fetch_quota", started, VerificationStatus::Failed, Some(e.to_string())),
+                    }
+                }
+            }
+        }
+    }
+
+    // 5. Device profile
+    let started = Instant::now();
+    match &account.device_profile {
+        None => report.push(
+            "device_profile",
+            started,
+            VerificationStatus::Warning,
+            Some("no bound profile; one will be generated on first switch".to_string()),
+        ),
+        Some(profile) => {
+            let problems = device_profile_problems(profile);
+            if problems.is_empty() {
+                report.push("device_profile", started, VerificationStatus::Ok, None);
+            } else {
+                report.push(
+                    "device_profile",
+                    started,
+                    VerificationStatus::Failed,
+                    Some(format!("malformed fields: {}", problems.join(", "))),
+                );
+            }
+        }
+    }
+
+    crate::modules::logger::log_info(&format!(
+        "Login verification for {}: {}",
+        account.email,
+        if report.passed { "passed" } else { "failed" }
+    ));
+    Ok(report)
+}
+
+/// Get device profile info: current storage.json + account bound profile
+#[derive(Debug, Serialize)]
 pub struct DeviceProfiles {
     pub current_storage: Option<DeviceProfile>,
     pub bound_profile: Option<DeviceProfile>,
@@ -1068,7 +2919,7 @@ pub struct DeviceProfiles {
 }
 
 pub fn get_device_profiles(account_id: &str) -> Result<DeviceProfiles, String> {
-    // In headless/Docker mode, storage.json may not exist - handle gracefully
+    // storage.json is unavailable in headless mode (and may be missing on desktop)
     let current = crate::modules::device::get_storage_path()
         .ok()
         .and_then(|path| crate::modules::device::read_profile(&path).ok());
@@ -1091,10 +2942,11 @@ pub fn bind_device_profile(account_id: &str, mode: &str) -> Result<DeviceProfile
         _ => return Err("mode must be 'capture' or 'generate'".to_string()),
     };
 
-    let mut account = load_account(account_id)?;
     let _ = device::save_global_original(&profile);
-    apply_profile_to_account(
-        &mut account, profile.clone(), Some(mode.to_string()), true)?;
+    with_account_mut(account_id, |account| {
+        push_device_profile(account, profile.clone(), Some(mode.to_string()), true);
+        Ok(())
+    })?;
 
     Ok(profile)
 }
@@ -1105,19 +2957,37 @@ pub fn bind_device_profile_with_profile(
     profile: DeviceProfile,
     label: Option<String>,
 ) -> Result<DeviceProfile, String> {
-    let mut account = load_account(account_id)?;
     let _ = crate::modules::device::save_global_original(&profile);
-    apply_profile_to_account(&mut account, profile.clone(), label, true)?;
+    with_account_mut(account_id, |account| {
+        push_device_profile(account, profile.clone(), label, true);
+        Ok(())
+    })?;
 
     Ok(profile)
 }
 
+/// Bind a profile to the stored account and mirror the result into the caller's copy
 fn apply_profile_to_account(
     account: &mut Account,
     profile: DeviceProfile,
     label: Option<String>,
     add_history: bool,
 ) -> Result<(), String> {
+    let (device_profile, device_history) = with_account_mut(&account.id, |stored| {
+        push_device_profile(stored, profile, label, add_history);
+        Ok((stored.device_profile.clone(), stored.device_history.clone()))
+    })?;
+    account.device_profile = device_profile;
+    account.device_history = device_history;
+    Ok(())
+}
+
+fn push_device_profile(
+    account: &mut Account,
+    profile: DeviceProfile,
+    label: Option<String>,
+    add_history: bool,
+) {
     account.device_profile = Some(profile.clone());
     if add_history {
         // Clear 'current' flag
@@ -1132,8 +3002,6 @@ fn apply_profile_to_account(
             is_current: true,
         });
     }
-    save_account(account)?;
-    Ok(())
 }
 
 /// List available device profile versions for an account (including baseline)
@@ -1143,27 +3011,35 @@ pub fn list_device_versions(account_id: &str) -> Result<DeviceProfiles, String>
 
 /// Restore device profile by version ID ("baseline" for global original, "current" for current bound)
 pub fn restore_device_version(account_id: &str, version_id: &str) -> Result<DeviceProfile, String> {
-    let mut account = load_account(account_id)?;
-
-    let target_profile = if version_id == "baseline" {
-        crate::modules::device::load_global_original().ok_or("Global original profile not found")?
-    } else if let Some(v) = account.device_history.iter().find(|v| v.id == version_id) {
-        v.profile.clone()
-    } else if version_id == "current" {
-        account
-            .device_profile
-            .clone()
-            .ok_or("No currently bound profile")?
+    let baseline = if version_id == "baseline" {
+        Some(
+            crate::modules::device::load_global_original()
+                .ok_or("Global original profile not found")?,
+        )
     } else {
-        return Err("Device profile version not found".to_string());
+        None
     };
 
-    account.device_profile = Some(target_profile.clone());
-    for h in account.device_history.iter_mut() {
-        h.is_current = h.id == version_id;
-    }
-    save_account(&account)?;
-    Ok(target_profile)
+    with_account_mut(account_id, |account| {
+        let target_profile = if let Some(baseline) = baseline {
+            baseline
+        } else if let Some(v) = account.device_history.iter().find(|v| v.id == version_id) {
+            v.profile.clone()
+        } else if version_id == "current" {
+            account
+                .device_profile
+                .clone()
+                .ok_or("No currently bound profile")?
+        } else {
+            return Err("Device profile version not found".to_string());
+        };
+
+        account.device_profile = Some(target_profile.clone());
+        for h in account.device_history.iter_mut() {
+            h.is_current = h.id == version_id;
+        }
+        Ok(target_profile)
+    })
 }
 
 /// Delete specific historical device profile (baseline cannot be deleted)
@@ -1171,47 +3047,49 @@ pub fn delete_device_version(account_id: &str, version_id: &str) -> Result<(), S
     if version_id == "baseline" {
         return Err("Original profile cannot be deleted".to_string());
     }
-    let mut account = load_account(account_id)?;
-    if account
-        .device_history
-        .iter()
-        .any(|v| v.id == version_id && v.is_current)
-    {
-        return Err("Currently bound profile cannot be deleted".to_string());
-    }
-    let before = account.device_history.len();
-    account.device_history.retain(|v| v.id != version_id);
-    if account.device_history.len() == before {
-        return Err("Historical device profile not found".to_string());
-    }
-    save_account(&account)?;
-    Ok(())
+    with_account_mut(account_id, |account| {
+        if account
+            .device_history
+            .iter()
+            .any(|v| v.id == version_id && v.is_current)
+        {
+            return Err("Currently bound profile cannot be deleted".to_string());
+        }
+        let before = account.device_history.len();
+        account.device_history.retain(|v| v.id != version_id);
+        if account.device_history.len() == before {
+            return Err("Historical device profile not found".to_string());
+        }
+        Ok(())
+    })
 }
 /// Apply account bound device profile to storage.json
 pub fn apply_device_profile(account_id: &str) -> Result<DeviceProfile, String> {
     use crate::modules::device;
-    let mut account = load_account(account_id)?;
-    let profile = account
+    let profile = load_account(account_id)?
         .device_profile
-        .clone()
         .ok_or("Account has no bound device profile")?;
     let storage_path = device::get_storage_path()?;
     device::write_profile(&storage_path, &profile)?;
-    account.update_last_used();
-    save_account(&account)?;
+    with_account_mut(account_id, |account| {
+        account.update_last_used();
+        Ok(())
+    })?;
     Ok(profile)
 }
 
 /// Restore earliest storage.json backup (approximate "original" state)
 pub fn restore_original_device() -> Result<String, String> {
     if let Some(current_id) = get_current_account_id()? {
-        if let Ok(mut account) = load_account(&current_id) {
-            if let Some(original) = crate::modules::device::load_global_original() {
+        if let Some(original) = crate::modules::device::load_global_original() {
+            let reset = with_account_mut(&current_id, |account| {
                 account.device_profile = Some(original);
                 for h in account.device_history.iter_mut() {
                     h.is_current = false;
                 }
-                save_account(&account)?;
+                Ok(())
+            });
+            if reset.is_ok() {
                 return Ok(
                     "Reset current account bound profile to original (not applied to storage)"
                         .to_string(),
@@ -1239,137 +3117,478 @@ pub fn get_current_account() -> Result<Option<Account>, String> {
 
 /// Set current active account ID
 pub fn set_current_account_id(account_id: &str) -> Result<(), String> {
-    let _lock = ACCOUNT_INDEX_LOCK
-        .lock()
-        .map_err(|e| format!("failed_to_acquire_lock: {}", e))?;
-    let mut index = load_account_index()?;
-    index.current_account_id = Some(account_id.to_string());
-    save_account_index(&index)
+    with_index_mut(|index| {
+        index.current_account_id = Some(account_id.to_string());
+        Ok(())
+    })
 }
 
-/// Update account quota
-pub fn update_account_quota(account_id: &str, quota: QuotaData) -> Result<(), String> {
-    let mut account = load_account(account_id)?;
-    account.update_quota(quota);
+/// Quota protection: protect monitored model groups at or below the threshold,
+/// release recovered ones, and drop groups that are no longer monitored
+fn apply_quota_protection(
+    account: &mut Account,
+    config: &crate::models::QuotaProtectionConfig,
+) -> bool {
+    let before = account.protected_models.clone();
 
-    // --- Quota protection logic start ---
-    if let Ok(config) = crate::modules::config::load_app_config() {
-        if config.quota_protection.enabled {
-            if let Some(ref q) = account.quota {
-                let threshold = config.quota_protection.threshold_percentage as i32;
+    // Groups removed from monitored_models must not stay protected
+    let stale: Vec<String> = account
+        .protected_models
+        .iter()
+        .filter(|id| !config.monitored_models.contains(id))
+        .cloned()
+        .collect();
+    for std_id in stale {
+        crate::modules::logger::log_info(&format!(
+            "[Quota] Model protection released (no longer monitored): {} (Group: {})",
+            account.email, std_id
+        ));
+        account.protected_models.remove(&std_id);
+    }
 
-                let mut group_min_percentage: HashMap<String, i32> = HashMap::new();
+    if config.enabled {
+        if let Some(ref q) = account.quota {
+            let threshold = config.threshold_percentage as i32;
 
-                for model in &q.models {
-                    if let Some(std_id) =
-                        crate::proxy::common::model_mapping::normalize_to_standard_id(&model.name)
-                    {
-                        let entry = group_min_percentage.entry(std_id).or_insert(100);
-                        if model.percentage < *entry {
-                            *entry = model.percentage;
-                        }
+            let mut group_min_percentage: HashMap<String, i32> = HashMap::new();
+
+            for model in &q.models {
+                if let Some(std_id) =
+                    crate::proxy::common::model_mapping::normalize_to_standard_id(&model.name)
+                {
+                    let entry = group_min_percentage.entry(std_id).or_insert(100);
+                    if model.percentage < *entry {
+                        *entry = model.percentage;
                     }
                 }
+            }
 
-                for std_id in &config.quota_protection.monitored_models {
-                    let min_pct = group_min_percentage.get(std_id).cloned().unwrap_or(100);
+            for std_id in &config.monitored_models {
+                let min_pct = group_min_percentage.get(std_id).cloned().unwrap_or(100);
 
-                    if min_pct <= threshold {
-                        if !account.protected_models.contains(std_id) {
-                            crate::modules::logger::log_info(&format!(
-                                "[Quota] Triggering model protection: {} (Group: {} Min: {}% <= Thres: {}%)",
-                                account.email, std_id, min_pct, threshold
-                            ));
-                            account.protected_models.insert(std_id.clone());
-                        }
-                    } else {
-                        if account.protected_models.contains(std_id) {
-                            crate::modules::logger::log_info(&format!(
-                                "[Quota] Model protection recovered: {} (Group: {} Min: {}% > Thres: {}%)",
-                                account.email, std_id, min_pct, threshold
-                            ));
-                            account.protected_models.remove(std_id);
-                        }
+                if min_pct <= threshold {
+                    if !account.protected_models.contains(std_id) {
+                        crate::modules::logger::log_info(&format!(
+                            "[Quota] Triggering model protection: {} (Group: {} Min: {}% <= Thres: {}%)",
+                            account.email, std_id, min_pct, threshold
+                        ));
+                        crate::modules::event_feed::record(
+                            crate::modules::event_feed::EventKind::Protection,
+                            format!("{} {} {}%", account.email, std_id, min_pct),
+                        );
+                        account.protected_models.insert(std_id.clone());
+                    }
+                } else {
+                    if account.protected_models.contains(std_id) {
+                        crate::modules::logger::log_info(&format!(
+                            "[Quota] Model protection recovered: {} (Group: {} Min: {}% > Thres: {}%)",
+                            account.email, std_id, min_pct, threshold
+                        ));
+                        account.protected_models.remove(std_id);
                     }
                 }
+            }
 
-                // [Compatibility] Migrate from account-level to model-level protection if previously disabled for quota
-                if account.proxy_disabled
-                    && account
-                        .proxy_disabled_reason
-                        .as_ref()
-                        .map_or(false, |r| r == "quota_protection")
-                {
-                    crate::modules::logger::log_info(&format!(
-                        "[Quota] Migrating account {} from account-level to model-level protection",
-                        account.email
-                    ));
-                    account.proxy_disabled = false;
-                    account.proxy_disabled_reason = None;
-                    account.proxy_disabled_at = None;
-                }
+            // [Compatibility] Migrate from account-level to model-level protection if previously disabled for quota
+            if account.proxy_disabled
+                && account
+                    .proxy_disabled_reason
+                    .as_ref()
+                    .map_or(false, |r| r == "quota_protection")
+            {
+                crate::modules::logger::log_info(&format!(
+                    "[Quota] Migrating account {} from account-level to model-level protection",
+                    account.email
+                ));
+                account.proxy_disabled = false;
+                account.proxy_disabled_reason = None;
+                account.proxy_disabled_at = None;
             }
         }
     }
-    // --- Quota protection logic end ---
 
-    // Save account first
-    save_account(&account)?;
+    account.protected_models != before
+}
 
-    // [FIX] 同时更新索引文件中的摘要信息，确保列表页图标即时刷新
-    {
-        let _lock = ACCOUNT_INDEX_LOCK
-            .lock()
-            .map_err(|e| format!("failed_to_acquire_lock: {}", e))?;
-        if let Ok(mut index) = load_account_index() {
-            if let Some(summary) = index.accounts.iter_mut().find(|a| a.id == account_id) {
-                summary.protected_models = account.protected_models.clone();
-                let _ = save_account_index(&index);
-            }
+/// Re-evaluate quota protection for all accounts against the given config
+/// (used when monitored_models changes at runtime). Returns the number of accounts changed.
+pub fn reapply_quota_protection(
+    config: &crate::models::QuotaProtectionConfig,
+) -> Result<usize, String> {
+    let account_ids: Vec<String> = load_account_index()?
+        .accounts
+        .into_iter()
+        .map(|s| s.id)
+        .collect();
+    let results = with_accounts_mut(&account_ids, |account| {
+        Ok(apply_quota_protection(account, config))
+    })?;
+
+    let mut changed = 0;
+    for (account_id, result) in results {
+        if let Ok(true) = result {
+            crate::modules::events::account_reload(&account_id);
+            changed += 1;
         }
     }
+    Ok(changed)
+}
+
+/// Update account quota
+pub fn update_account_quota(account_id: &str, quota: QuotaData) -> Result<(), String> {
+    let config = crate::modules::config::load_app_config().ok();
+    // 索引摘要 (受保护模型 / 订阅等级) 在同一事务内同步，确保列表页图标即时刷新
+    let (account, recovered) = with_account_mut(account_id, |account| {
+        account.update_quota(quota);
+
+        let protected_before = account.protected_models.clone();
+        let was_proxy_disabled = account.proxy_disabled;
+        if let Some(config) = &config {
+            apply_quota_protection(account, &config.quota_protection);
+        }
+        let recovered = protected_before
+            .iter()
+            .any(|id| !account.protected_models.contains(id))
+            || (was_proxy_disabled && !account.proxy_disabled);
+        Ok((account.clone(), recovered))
+    })?;
 
     // [FIX] Trigger TokenManager account reload signal
     // This ensures in-memory protected_models are updated
-    crate::proxy::server::trigger_account_reload(account_id);
+    crate::modules::events::account_reload(account_id);
+
+    // 配额历史 (按 data_retention.quota_history_days 清理)
+    if let Some(quota) = &account.quota {
+        if let Err(e) = crate::modules::token_stats::record_quota_snapshot(account_id, quota) {
+            crate::modules::logger::log_warn(&format!(
+                "Failed to record quota snapshot for {}: {}",
+                account.email, e
+            ));
+        }
+    }
+
+    if recovered {
+        crate::modules::scheduler::schedule_warmup_after_reenable(account_id, "quota recovered");
+    }
 
     Ok(())
 }
 
+/// Apply the proxy enabled / disabled fields to an account
+fn apply_proxy_status(account: &mut Account, enable: bool, reason: Option<&str>, now: i64) {
+    account.proxy_disabled = !enable;
+    account.proxy_disabled_reason = if !enable {
+        reason.map(|s| s.to_string())
+    } else {
+        None
+    };
+    account.proxy_disabled_at = if !enable { Some(now) } else { None };
+}
+
 /// Toggle proxy disabled status for an account
 pub fn toggle_proxy_status(
     account_id: &str,
     enable: bool,
     reason: Option<&str>,
 ) -> Result<(), String> {
-    let _lock = ACCOUNT_INDEX_LOCK
-        .lock()
-        .map_err(|e| format!("failed_to_acquire_lock: {}", e))?;
+    let was_disabled = with_account_mut(account_id, |account| {
+        let was_disabled = account.proxy_disabled;
+        apply_proxy_status(account, enable, reason, chrono::Utc::now().timestamp());
+        Ok(was_disabled)
+    })?;
+    if enable && was_disabled {
+        crate::modules::scheduler::schedule_warmup_after_reenable(account_id, "proxy re-enabled");
+    }
+    Ok(())
+}
 
-    let mut account = load_account(account_id)?;
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkFailure {
+    pub account_id: String,
+    pub error: String,
+}
 
-    account.proxy_disabled = !enable;
-    account.proxy_disabled_reason = if !enable {
-        reason.map(|s| s.to_string())
-    } else {
-        None
-    };
-    account.proxy_disabled_at = if !enable {
-        Some(chrono::Utc::now().timestamp())
-    } else {
-        None
-    };
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BulkProxyStatusResult {
+    pub updated: Vec<String>,
+    pub failed: Vec<BulkFailure>,
+}
+
+/// Enable / disable proxying for many accounts in one locked pass with a single index write
+/// (a missing or unreadable account is reported in `failed` and does not abort the rest)
+pub fn set_proxy_status_bulk(
+    account_ids: &[String],
+    enable: bool,
+    reason: Option<&str>,
+) -> Result<BulkProxyStatusResult, String> {
+    let now = chrono::Utc::now().timestamp();
+    let mut result = BulkProxyStatusResult::default();
+
+    let outcomes = with_accounts_mut(account_ids, |account| {
+        let was_disabled = account.proxy_disabled;
+        apply_proxy_status(account, enable, reason, now);
+        Ok(enable && was_disabled)
+    })?;
+    for (account_id, outcome) in outcomes {
+        match outcome {
+            Ok(reenabled) => {
+                if reenabled {
+                    crate::modules::scheduler::schedule_warmup_after_reenable(
+                        &account_id,
+                        "proxy re-enabled",
+                    );
+                }
+                result.updated.push(account_id)
+            }
+            Err(error) => result.failed.push(BulkFailure { account_id, error }),
+        }
+    }
+
+    crate::modules::logger::log_info(&format!(
+        "Bulk proxy {}: {} updated, {} failed",
+        if enable { "enable" } else { "disable" },
+        result.updated.len(),
+        result.failed.len()
+    ));
+    Ok(result)
+}
+
+/// `proxy_disabled_reason` prefix used by snoozed accounts, followed by a unix timestamp
+pub const SNOOZE_REASON_PREFIX: &str = "snoozed_until:";
+
+/// Parse the wake-up time from a `snoozed_until:<ts>` disable reason
+pub fn snoozed_until(reason: Option<&str>) -> Option<i64> {
+    reason?
+        .strip_prefix(SNOOZE_REASON_PREFIX)
+        .and_then(|ts| ts.trim().parse().ok())
+}
+
+/// Temporarily disable an account for proxying; returns the wake-up timestamp
+pub fn snooze_account(account_id: &str, duration_secs: u64) -> Result<i64, String> {
+    if duration_secs == 0 {
+        return Err("invalid_snooze_duration".to_string());
+    }
+    let until = chrono::Utc::now().timestamp() + duration_secs as i64;
+    toggle_proxy_status(
+        account_id,
+        false,
+        Some(&format!("{}{}", SNOOZE_REASON_PREFIX, until)),
+    )?;
+    crate::modules::events::account_reload(account_id);
+
+    crate::modules::logger::log_info(&format!(
+        "Account {} snoozed for {}s (until {})",
+        account_id, duration_secs, until
+    ));
+    Ok(until)
+}
+
+/// Re-enable snoozed accounts whose wake-up time has passed; returns re-enabled ids
+pub fn wake_expired_snoozes(now: i64) -> Result<Vec<String>, String> {
+    let due: Vec<String> = list_accounts()?
+        .into_iter()
+        .filter(|a| a.proxy_disabled)
+        .filter(|a| snoozed_until(a.proxy_disabled_reason.as_deref()).is_some_and(|ts| ts <= now))
+        .map(|a| a.id)
+        .collect();
+
+    for account_id in &due {
+        toggle_proxy_status(account_id, true, None)?;
+        crate::modules::events::account_reload(account_id);
+        crate::modules::logger::log_info(&format!("Snooze expired, account {} re-enabled", account_id));
+    }
+    Ok(due)
+}
+
+/// Normalize account tags: trim, drop empties and duplicates (order preserved)
+pub fn normalize_account_tags(tags: &[String]) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim();
+        if !tag.is_empty() && !result.iter().any(|t| t == tag) {
+            result.push(tag.to_string());
+        }
+    }
+    result
+}
+
+/// Set the group tags of an account (used for per-API-key pool partitioning)
+pub fn set_account_tags(account_id: &str, tags: &[String]) -> Result<Vec<String>, String> {
+    with_account_mut(account_id, |account| {
+        account.tags = normalize_account_tags(tags);
+        Ok(account.tags.clone())
+    })
+}
+
+/// Set the outbound address family override of an account (None = use the global setting)
+pub fn set_account_ip_family(
+    account_id: &str,
+    family: Option<crate::models::IpFamily>,
+) -> Result<(), String> {
+    let ip_family = family.filter(|f| *f != crate::models::IpFamily::Auto);
+    with_account_mut(account_id, |account| {
+        account.ip_family = ip_family;
+        Ok(())
+    })?;
+    crate::modules::http::set_account_ip_family(account_id, ip_family);
+    Ok(())
+}
+
+/// Set the per-account Antigravity launch args (None or empty = only the global args)
+pub fn set_account_antigravity_args(
+    account_id: &str,
+    args: Option<Vec<String>>,
+) -> Result<(), String> {
+    let args = args
+        .map(|args| {
+            args.into_iter()
+                .map(|a| a.trim().to_string())
+                .filter(|a| !a.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .filter(|args| !args.is_empty());
+    with_account_mut(account_id, |account| {
+        account.antigravity_args = args;
+        Ok(())
+    })
+}
+
+/// Select the Antigravity installation an account targets (None = the global executable)
+pub fn set_account_antigravity_executable(
+    account_id: &str,
+    executable: Option<String>,
+) -> Result<(), String> {
+    let executable = executable
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty());
+    if let Some(ref path) = executable {
+        crate::modules::process::validate_antigravity_executable(path)?;
+    }
+
+    with_account_mut(account_id, |account| {
+        account.antigravity_executable = executable;
+        Ok(())
+    })
+}
+
+/// Remaining percentage of `model` (exact name or normalized standard id such as "claude");
+/// the lowest value when several quota entries map to it
+fn model_quota_percentage(quota: &QuotaData, model: &str) -> Option<i32> {
+    let target = model.trim().to_lowercase();
+    quota
+        .models
+        .iter()
+        .filter(|m| {
+            m.name.to_lowercase() == target
+                || crate::proxy::common::model_mapping::normalize_to_standard_id(&m.name)
+                    .is_some_and(|id| id == target)
+        })
+        .map(|m| m.percentage)
+        .min()
+}
+
+fn list_item_from_account(account: &Account, sort_by: &AccountSortBy) -> AccountListItem {
+    let quota = account.quota.as_ref();
+    AccountListItem {
+        id: account.id.clone(),
+        email: account.email.clone(),
+        name: account.name.clone(),
+        custom_label: account.custom_label.clone(),
+        provider: account.provider.clone(),
+        disabled: account.disabled,
+        proxy_disabled: account.proxy_disabled,
+        protected_models: account.protected_models.clone(),
+        tags: account.tags.clone(),
+        subscription_tier: quota.and_then(|q| q.subscription_tier.clone()),
+        is_forbidden: quota.is_some_and(|q| q.is_forbidden),
+        created_at: account.created_at,
+        last_used: account.last_used,
+        quota_updated_at: quota.map(|q| q.last_updated),
+        stale: quota.is_some_and(|q| {
+            q.is_stale(crate::proxy::config::get_quota_staleness_minutes())
+        }),
+        quota_percentage: match sort_by {
+            AccountSortBy::Quota { model } => quota.and_then(|q| model_quota_percentage(q, model)),
+            _ => None,
+        },
+    }
+}
 
-    save_account(&account)?;
+fn matches_account_filter(item: &AccountListItem, filter: &AccountListFilter) -> bool {
+    if let Some(disabled) = filter.disabled {
+        if (item.disabled || item.proxy_disabled) != disabled {
+            return false;
+        }
+    }
+    if let Some(protected) = filter.protected {
+        if item.protected_models.is_empty() == protected {
+            return false;
+        }
+    }
+    if let Some(tagged) = filter.tagged {
+        if item.tags.is_empty() == tagged {
+            return false;
+        }
+    }
+    if !filter.tags.is_empty() && !filter.tags.iter().any(|t| item.tags.contains(t)) {
+        return false;
+    }
+    if let Some(search) = filter.search.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        let needle = search.to_lowercase();
+        let hit = [Some(&item.email), item.name.as_ref(), item.custom_label.as_ref()]
+            .into_iter()
+            .flatten()
+            .any(|field| field.to_lowercase().contains(&needle));
+        if !hit {
+            return false;
+        }
+    }
+    true
+}
 
-    // Also update index summary
-    let mut index = load_account_index()?;
-    if let Some(summary) = index.accounts.iter_mut().find(|a| a.id == account_id) {
-        summary.proxy_disabled = !enable;
-        save_account_index(&index)?;
+/// Stable sort; accounts without quota data for the chosen model always go last
+fn sort_account_items(items: &mut [AccountListItem], sort_by: &AccountSortBy, order: SortOrder) {
+    use std::cmp::Ordering;
+    let directed = |ord: Ordering| match order {
+        SortOrder::Asc => ord,
+        SortOrder::Desc => ord.reverse(),
+    };
+    match sort_by {
+        AccountSortBy::Custom => {
+            if order == SortOrder::Desc {
+                items.reverse();
+            }
+        }
+        AccountSortBy::Quota { .. } => items.sort_by(|a, b| match (a.quota_percentage, b.quota_percentage) {
+            (Some(x), Some(y)) => directed(x.cmp(&y)),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }),
+        AccountSortBy::LastUsed => items.sort_by(|a, b| directed(a.last_used.cmp(&b.last_used))),
+        AccountSortBy::CreatedAt => items.sort_by(|a, b| directed(a.created_at.cmp(&b.created_at))),
+        // tier_rank: ULTRA(0) .. 未知(3)，升序 = 未知 → FREE → PRO → ULTRA
+        AccountSortBy::Tier => items.sort_by(|a, b| {
+            let rank = |i: &AccountListItem| crate::proxy::token_manager::tier_rank(&i.subscription_tier);
+            directed(rank(b).cmp(&rank(a)))
+        }),
+        AccountSortBy::Email => items.sort_by(|a, b| directed(a.email.to_lowercase().cmp(&b.email.to_lowercase()))),
     }
+}
 
-    Ok(())
+/// Lightweight, backend-sorted / filtered account rows for the list page
+pub fn list_account_summaries(
+    sort_by: &AccountSortBy,
+    order: SortOrder,
+    filter: &AccountListFilter,
+) -> Result<Vec<AccountListItem>, String> {
+    let mut items: Vec<AccountListItem> = list_accounts()?
+        .iter()
+        .map(|account| list_item_from_account(account, sort_by))
+        .filter(|item| matches_account_filter(item, filter))
+        .collect();
+    sort_account_items(&mut items, sort_by, order);
+    Ok(items)
 }
 
 /// Find account ID by email (from index)
@@ -1380,44 +3599,37 @@ pub fn find_account_id_by_email(email: &str) -> Option<String> {
 }
 
 pub fn mark_account_forbidden(account_id: &str, reason: &str) -> Result<(), String> {
-    let _lock = ACCOUNT_INDEX_LOCK
-        .lock()
-        .map_err(|e| format!("failed_to_acquire_lock: {}", e))?;
-
-    let mut account = load_account(account_id)?;
-
-    // 1. Update quota status
-    if let Some(ref mut q) = account.quota {
-        q.is_forbidden = true;
-        q.forbidden_reason = Some(reason.to_string());
-    } else {
-        account.quota = Some(crate::models::QuotaData {
-            models: Vec::new(),
-            last_updated: chrono::Utc::now().timestamp(),
-            subscription_tier: None,
-            is_forbidden: true,
-            forbidden_reason: Some(reason.to_string()),
-            model_forwarding_rules: std::collections::HashMap::new(),
-        });
-    }
-
-    // 2. Disable proxy for this account
-    account.proxy_disabled = true;
-    account.proxy_disabled_reason = Some(format!("Forbidden (403): {}", reason));
-    account.proxy_disabled_at = Some(chrono::Utc::now().timestamp());
-
-    save_account(&account)?;
-
-    // 3. Update index summary
-    let mut index = load_account_index()?;
-    if let Some(summary) = index.accounts.iter_mut().find(|a| a.id == account_id) {
-        summary.proxy_disabled = true;
-        save_account_index(&index)?;
-    }
-
-    // 4. Notify frontend to refresh account list
-    crate::modules::log_bridge::emit_accounts_refreshed();
+    let now = chrono::Utc::now().timestamp();
+    // The index summary and accounts://refreshed follow from the proxy_disabled change
+    let email = with_account_mut(account_id, |account| {
+        // 1. Update quota status
+        if let Some(ref mut q) = account.quota {
+            q.is_forbidden = true;
+            q.forbidden_reason = Some(reason.to_string());
+        } else {
+            account.quota = Some(crate::models::QuotaData {
+                models: Vec::new(),
+                last_updated: now,
+                subscription_tier: None,
+                is_forbidden: true,
+                forbidden_reason: Some(reason.to_string()),
+                model_forwarding_rules: std::collections::HashMap::new(),
+            });
+        }
 
+        // 2. Disable proxy for this account
+        account.proxy_disabled = true;
+        account.proxy_disabled_reason = Some(format!("Forbidden (403): {}", reason));
+        account.proxy_disabled_at = Some(now);
+        Ok(account.email.clone())
+    })?;
+    crate::modules::notify::push(
+        crate::modules::notify::Severity::Warning,
+        "account",
+        format!("Account forbidden: {}", email),
+        format!("Proxy disabled after 403: {}", reason),
+        Some(&format!("account_forbidden:{}", account_id)),
+    );
     Ok(())
 }
 
@@ -1426,18 +3638,36 @@ pub fn export_accounts_by_ids(account_ids: &[String]) -> Result<crate::models::A
     use crate::models::{AccountExportItem, AccountExportResponse};
     
     let accounts = list_accounts()?;
-    
+    let policies = crate::modules::domain_policy::current();
+
+    let mut withheld = Vec::new();
     let export_items: Vec<AccountExportItem> = accounts
         .into_iter()
         .filter(|acc| account_ids.contains(&acc.id))
+        .filter(|acc| {
+            // Domain policy: refresh tokens of these accounts never leave the machine
+            if crate::modules::domain_policy::rules_in(&policies, &acc.email).no_export {
+                withheld.push(acc.email.clone());
+                return false;
+            }
+            true
+        })
         .map(|acc| AccountExportItem {
             email: acc.email,
             refresh_token: acc.token.refresh_token,
         })
         .collect();
+    if !withheld.is_empty() {
+        crate::modules::logger::log_warn(&format!(
+            "Export skipped {} account(s) by domain policy: {}",
+            withheld.len(),
+            withheld.join(", ")
+        ));
+    }
 
     Ok(AccountExportResponse {
         accounts: export_items,
+        withheld,
     })
 }
 
@@ -1445,9 +3675,13 @@ pub fn export_accounts_by_ids(account_ids: &[String]) -> Result<crate::models::A
 #[allow(dead_code)]
 pub fn export_accounts() -> Result<Vec<(String, String)>, String> {
     let accounts = list_accounts()?;
+    let policies = crate::modules::domain_policy::current();
     let mut exports = Vec::new();
 
     for account in accounts {
+        if crate::modules::domain_policy::rules_in(&policies, &account.email).no_export {
+            continue;
+        }
         exports.push((account.email, account.token.refresh_token));
     }
 
@@ -1460,6 +3694,11 @@ pub async fn fetch_quota_with_retry(account: &mut Account) -> crate::error::AppR
     use crate::modules::oauth;
     use reqwest::StatusCode;
 
+    // 离线模式下不发起任何请求，界面继续展示缓存的配额
+    if let Err(e) = crate::modules::offline::ensure_online() {
+        return Err(AppError::Network(e, None));
+    }
+
     // Codex accounts: use hardcoded model list (OAuth token lacks api.model.read scope)
     // Model list aligned with OpenCode's allowedModels
     if account.provider == crate::models::AccountProvider::Codex {
@@ -1467,7 +3706,7 @@ pub async fn fetch_quota_with_retry(account: &mut Account) -> crate::error::AppR
         let fresh_token = match crate::modules::codex_oauth::ensure_codex_fresh_token(&account.token).await {
             Ok(Some(new_token)) => {
                 account.token = new_token;
-                if let Err(e) = save_account(account) {
+                if let Err(e) = save_refreshed_token(&account.id, &account.token) {
                     tracing::warn!("Failed to save refreshed Codex token: {}", e);
                 }
                 account.token.access_token.clone()
@@ -1598,18 +3837,14 @@ pub async fn fetch_quota_with_retry(account: &mut Account) -> crate::error::AppR
     } {
         Ok(t) => t,
         Err(e) => {
-            if e.contains("invalid_grant") {
+            if crate::error::is_invalid_grant(&e) {
                 modules::logger::log_error(&format!(
                     "Disabling account {} due to invalid_grant during token refresh (quota check)",
                     account.email
                 ));
-                account.disabled = true;
-                account.disabled_at = Some(chrono::Utc::now().timestamp());
-                account.disabled_reason = Some(format!("invalid_grant: {}", e));
-                let _ = save_account(account);
-                crate::proxy::server::trigger_account_reload(&account.id);
+                disable_for_invalid_grant(account, &e);
             }
-            return Err(AppError::OAuth(e));
+            return Err(AppError::from_oauth(e));
         }
     };
 
@@ -1662,148 +3897,146 @@ pub async fn fetch_quota_with_retry(account: &mut Account) -> crate::error::AppR
     }
 
     // 2. Attempt query
-    let result: crate::error::AppResult<(QuotaData, Option<String>)> =
-        modules::fetch_quota(&account.token.access_token, &account.email, Some(&account.id)).await;
-
-    // Capture potentially updated project_id and save
-    if let Ok((ref _q, ref project_id)) = result {
-        if project_id.is_some() && *project_id != account.token.project_id {
-            modules::logger::log_info(&format!(
-                "Detected project_id update ({}), saving...",
-                account.email
-            ));
-            account.token.project_id = project_id.clone();
-            if let Err(e) = upsert_account(
-                account.email.clone(),
-                account.name.clone(),
-                account.token.clone(),
-            ) {
-                modules::logger::log_warn(&format!("Failed to sync project_id: {}", e));
+    // 401 时强制刷新 Token 后立即重试，次数受统一重试策略 (AppConfig.http_client) 约束；
+    // 429 / 5xx / 超时已在 fetch_quota 内部按同一策略退避重试
+    let mut backoff = crate::modules::retry::RetryPolicy::current().start();
+    loop {
+        let result: crate::error::AppResult<(QuotaData, Option<String>)> =
+            modules::fetch_quota(&account.token.access_token, &account.email, Some(&account.id)).await;
+
+        // Capture potentially updated project_id and save (history / churn tracked by project_binding)
+        if let Ok((_, Some(ref project_id))) = result {
+            let change = modules::project_binding::observe(
+                &account.id,
+                &account.email,
+                project_id,
+                "quota_refresh",
+            );
+            if change != modules::project_binding::BindingChange::Unchanged
+                || account.token.project_id.as_deref() != Some(project_id.as_str())
+            {
+                modules::logger::log_info(&format!(
+                    "Detected project_id update ({}), saving...",
+                    account.email
+                ));
+                account.token.project_id = Some(project_id.clone());
+                if let Err(e) = upsert_account(
+                    account.email.clone(),
+                    account.name.clone(),
+                    account.token.clone(),
+                ) {
+                    modules::logger::log_warn(&format!("Failed to sync project_id: {}", e));
+                }
             }
         }
-    }
 
-    // 3. Handle 401 error
-    if let Err(AppError::Network(_, status)) = result {
-        if let Some(code) = status {
-            if code == 401 {
+        // 3. Handle 401 error
+        if let Err(AppError::TokenInvalid(_)) = result {
+            if backoff
+                .next_delay(Some(crate::models::RetryClass::Unauthorized))
+                .is_some()
+            {
                 modules::logger::log_warn(&format!(
                     "401 Unauthorized for {}, forcing refresh...",
                     account.email
                 ));
+                force_refresh_token(account).await?;
+                continue;
+            }
+        }
 
-                // Force refresh
-                let new_token = match account.provider {
-                    crate::models::AccountProvider::Codex => {
-                        match crate::modules::codex_oauth::refresh_codex_token(&account.token.refresh_token).await {
-                            Ok(resp) => TokenData::new(
-                                resp.access_token,
-                                resp.refresh_token.unwrap_or_else(|| account.token.refresh_token.clone()),
-                                resp.expires_in.unwrap_or(3600),
-                                account.token.email.clone(),
-                                account.token.project_id.clone(),
-                                None,
-                            ),
-                            Err(e) => {
-                                if e.contains("invalid_grant") {
-                                    modules::logger::log_error(&format!(
-                                        "Disabling account {} due to invalid_grant during forced refresh (quota check)",
-                                        account.email
-                                    ));
-                                    account.disabled = true;
-                                    account.disabled_at = Some(chrono::Utc::now().timestamp());
-                                    account.disabled_reason = Some(format!("invalid_grant: {}", e));
-                                    let _ = save_account(account);
-                                    crate::proxy::server::trigger_account_reload(&account.id);
-                                }
-                                return Err(AppError::OAuth(e));
-                            }
-                        }
-                    }
-                    crate::models::AccountProvider::Google => {
-                        let token_res = match oauth::refresh_access_token(&account.token.refresh_token, Some(&account.id))
-                            .await
-                        {
-                            Ok(t) => t,
-                            Err(e) => {
-                                if e.contains("invalid_grant") {
-                                    modules::logger::log_error(&format!(
-                                        "Disabling account {} due to invalid_grant during forced refresh (quota check)",
-                                        account.email
-                                    ));
-                                    account.disabled = true;
-                                    account.disabled_at = Some(chrono::Utc::now().timestamp());
-                                    account.disabled_reason = Some(format!("invalid_grant: {}", e));
-                                    let _ = save_account(account);
-                                    crate::proxy::server::trigger_account_reload(&account.id);
-                                }
-                                return Err(AppError::OAuth(e));
-                            }
-                        };
-                        TokenData::new(
-                            token_res.access_token.clone(),
-                            account.token.refresh_token.clone(),
-                            token_res.expires_in,
-                            account.token.email.clone(),
-                            account.token.project_id.clone(),
-                            None,
-                        )
-                    }
-                };
+        if let Err(AppError::Forbidden { .. }) = result {
+            if let Some((previous, current, at)) =
+                modules::project_binding::recent_switch(&account.id, 3600)
+            {
+                modules::logger::log_warn(&format!(
+                    "403 for {} shortly after project switch {} -> {} (at {})",
+                    account.email, previous, current, at
+                ));
+            }
+            let mut q = QuotaData::new();
+            q.is_forbidden = true;
+            return Ok(q);
+        }
 
-                // Re-fetch display name
-                let name = if account.name.is_none()
-                    || account.name.as_ref().map_or(false, |n| n.trim().is_empty())
-                {
-                    match oauth::get_user_info(&new_token.access_token, Some(&account.id)).await {
-                        Ok(user_info) => user_info.get_display_name(),
-                        Err(_) => None,
-                    }
-                } else {
-                    account.name.clone()
-                };
+        // fetch_quota already handles 403, just return mapping result
+        return result.map(|(q, _)| q);
+    }
+}
 
-                account.token = new_token.clone();
-                account.name = name.clone();
-                upsert_account(account.email.clone(), name, new_token.clone())
-                    .map_err(AppError::Account)?;
-
-                // Retry query
-                let retry_result: crate::error::AppResult<(QuotaData, Option<String>)> =
-                    modules::fetch_quota(&new_token.access_token, &account.email, Some(&account.id)).await;
-
-                // Also handle project_id saving during retry
-                if let Ok((ref _q, ref project_id)) = retry_result {
-                    if project_id.is_some() && *project_id != account.token.project_id {
-                        modules::logger::log_info(&format!(
-                            "Detected update of project_id after retry ({}), saving...",
+/// 401 后强制刷新 Token，并顺带补全显示名称
+async fn force_refresh_token(account: &mut Account) -> crate::error::AppResult<()> {
+    use crate::error::AppError;
+    use crate::modules::oauth;
+
+    // Force refresh
+    let new_token = match account.provider {
+        crate::models::AccountProvider::Codex => {
+            match crate::modules::codex_oauth::refresh_codex_token(&account.token.refresh_token).await {
+                Ok(resp) => TokenData::new(
+                    resp.access_token,
+                    resp.refresh_token.unwrap_or_else(|| account.token.refresh_token.clone()),
+                    resp.expires_in.unwrap_or(3600),
+                    account.token.email.clone(),
+                    account.token.project_id.clone(),
+                    None,
+                ),
+                Err(e) => {
+                    if crate::error::is_invalid_grant(&e) {
+                        modules::logger::log_error(&format!(
+                            "Disabling account {} due to invalid_grant during forced refresh (quota check)",
                             account.email
                         ));
-                        account.token.project_id = project_id.clone();
-                        let _ = upsert_account(
-                            account.email.clone(),
-                            account.name.clone(),
-                            account.token.clone(),
-                        );
+                        disable_for_invalid_grant(account, &e);
                     }
+                    return Err(AppError::from_oauth(e));
                 }
-
-                if let Err(AppError::Network(_, status)) = retry_result {
-                    if let Some(code) = status {
-                        if code == 403 {
-                            let mut q = QuotaData::new();
-                            q.is_forbidden = true;
-                            return Ok(q);
-                        }
+            }
+        }
+        crate::models::AccountProvider::Google => {
+            let token_res = match oauth::refresh_access_token(&account.token.refresh_token, Some(&account.id))
+                .await
+            {
+                Ok(t) => t,
+                Err(e) => {
+                    if crate::error::is_invalid_grant(&e) {
+                        modules::logger::log_error(&format!(
+                            "Disabling account {} due to invalid_grant during forced refresh (quota check)",
+                            account.email
+                        ));
+                        disable_for_invalid_grant(account, &e);
                     }
+                    return Err(AppError::from_oauth(e));
                 }
-                return retry_result.map(|(q, _)| q);
-            }
+            };
+            TokenData::new(
+                token_res.access_token.clone(),
+                account.token.refresh_token.clone(),
+                token_res.expires_in,
+                account.token.email.clone(),
+                account.token.project_id.clone(),
+                None,
+            )
         }
-    }
+    };
 
-    // fetch_quota already handles 403, just return mapping result
-    result.map(|(q, _)| q)
+    // Re-fetch display name
+    let name = if account.name.is_none()
+        || account.name.as_ref().map_or(false, |n| n.trim().is_empty())
+    {
+        match oauth::get_user_info(&new_token.access_token, Some(&account.id)).await {
+            Ok(user_info) => user_info.get_display_name(),
+            Err(_) => None,
+        }
+    } else {
+        account.name.clone()
+    };
+
+    account.token = new_token.clone();
+    account.name = name.clone();
+    upsert_account(account.email.clone(), name, new_token)
+        .map_err(AppError::Account)?;
+    Ok(())
 }
 
 #[derive(Serialize)]
@@ -1812,26 +4045,405 @@ pub struct RefreshStats {
     pub success: usize,
     pub failed: usize,
     pub details: Vec<String>,
+    /// Accounts not processed because the operation was cancelled
+    pub skipped: usize,
+    pub cancelled: bool,
+    pub operation_id: Option<String>,
+}
+
+impl RefreshStats {
+    fn empty() -> Self {
+        Self {
+            total: 0,
+            success: 0,
+            failed: 0,
+            details: Vec::new(),
+            skipped: 0,
+            cancelled: false,
+            operation_id: None,
+        }
+    }
+}
+
+/// Accounts whose lowest model quota is at or below this percentage refresh more often
+const ADAPTIVE_LOW_QUOTA_THRESHOLD: i32 = 20;
+/// Lower bound for the adaptive interval (seconds)
+const ADAPTIVE_MIN_INTERVAL_SECS: i64 = 60;
+/// Upper bound of the random start delay applied to each scheduled refresh (milliseconds)
+const SCHEDULED_REFRESH_MAX_JITTER_MS: u64 = 15_000;
+
+/// Compute the adaptive refresh interval for an account
+/// - never fetched: due immediately
+/// - near exhaustion (any model <= 20%): half the base interval
+/// - idle (every model still at 100%): double the base interval
+pub fn adaptive_refresh_interval_secs(quota: Option<&QuotaData>, base_secs: i64) -> i64 {
+    let Some(quota) = quota else {
+        return 0;
+    };
+    if quota.models.is_empty() {
+        return base_secs;
+    }
+
+    let min_pct = quota.models.iter().map(|m| m.percentage).min().unwrap_or(100);
+    if min_pct <= ADAPTIVE_LOW_QUOTA_THRESHOLD {
+        (base_secs / 2).max(ADAPTIVE_MIN_INTERVAL_SECS)
+    } else if quota.models.iter().all(|m| m.percentage >= 100) {
+        base_secs * 2
+    } else {
+        base_secs
+    }
+}
+
+/// Stable per-account offset within ±10% of the interval, so accounts refreshed
+/// together drift apart instead of staying in lockstep
+fn account_refresh_offset_secs(account_id: &str, interval_secs: i64) -> i64 {
+    use std::hash::{Hash, Hasher};
+
+    let spread = interval_secs / 10;
+    if spread <= 0 {
+        return 0;
+    }
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    account_id.hash(&mut hasher);
+    (hasher.finish() % (2 * spread as u64 + 1)) as i64 - spread
+}
+
+/// Whether the account's quota is due for a scheduled refresh at `now`
+pub fn is_quota_refresh_due(account: &Account, base_secs: i64, now: i64) -> bool {
+    let Some(ref quota) = account.quota else {
+        return true;
+    };
+    if quota.is_forbidden {
+        return false;
+    }
+    let interval = adaptive_refresh_interval_secs(Some(quota), base_secs);
+    let offset = account_refresh_offset_secs(&account.id, interval);
+    now - quota.last_updated >= interval + offset
+}
+
+/// Progress payload for `quota://refresh-progress`
+#[derive(Serialize, Clone)]
+pub struct RefreshProgress {
+    pub operation_id: String,
+    pub done: usize,
+    pub total: usize,
+    pub current_email: Option<String>,
+}
+
+fn emit_refresh_progress(operation_id: &str, done: usize, total: usize, current_email: Option<&str>) {
+    crate::modules::log_bridge::emit_event(
+        "quota://refresh-progress",
+        RefreshProgress {
+            operation_id: operation_id.to_string(),
+            done,
+            total,
+            current_email: current_email.map(|e| e.to_string()),
+        },
+    );
 }
 
 /// Core logic to batch refresh all account quotas (decoupled from Tauri status)
 pub async fn refresh_all_quotas_logic() -> Result<RefreshStats, String> {
+    refresh_all_quotas_cancellable(None).await
+}
+
+/// Batch refresh registered as a cancellable operation (see `modules::operations`)
+pub async fn refresh_all_quotas_cancellable(
+    operation_id: Option<String>,
+) -> Result<RefreshStats, String> {
+    let op = crate::modules::operations::begin_operation("quota_refresh", operation_id)?;
+    let accounts = list_accounts()?;
+    let mut stats = refresh_quotas_for_accounts(accounts, 0, &op).await?;
+    stats.operation_id = Some(op.id().to_string());
+    Ok(stats)
+}
+
+/// Scheduled quota refresh: only accounts whose adaptive interval has elapsed are
+/// refreshed, and each request start is delayed by a random jitter to avoid bursts
+pub async fn refresh_due_quotas_logic(operation_id: Option<String>) -> Result<RefreshStats, String> {
+    let app_config = crate::modules::config::load_app_config()?;
+
+    if crate::modules::scheduler::is_quiet_hours(&app_config.quiet_hours) {
+        crate::modules::logger::log_info("[Refresh] Quiet hours active, skipping scheduled quota refresh");
+        return Ok(RefreshStats::empty());
+    }
+
+    if crate::modules::offline::is_offline() {
+        crate::modules::logger::log_info("[Refresh] Offline mode active, skipping scheduled quota refresh");
+        return Ok(RefreshStats::empty());
+    }
+
+    let base_secs = app_config.refresh_interval.max(1) as i64 * 60;
+    let now = chrono::Utc::now().timestamp();
+    let due: Vec<Account> = list_accounts()?
+        .into_iter()
+        .filter(|account| is_quota_refresh_due(account, base_secs, now))
+        .collect();
+
+    if due.is_empty() {
+        return Ok(RefreshStats::empty());
+    }
+
+    crate::modules::logger::log_info(&format!(
+        "[Refresh] {} accounts due for scheduled quota refresh (base interval: {}s)",
+        due.len(),
+        base_secs
+    ));
+    let op = crate::modules::operations::begin_operation("quota_refresh_scheduled", operation_id)?;
+    let mut stats =
+        refresh_quotas_for_accounts(due, SCHEDULED_REFRESH_MAX_JITTER_MS, &op).await?;
+    stats.operation_id = Some(op.id().to_string());
+    Ok(stats)
+}
+
+/// Refresh only the selected accounts (UI multi-select, CLI). Unknown ids are reported
+/// as failures; requests for accounts already being refreshed are coalesced
+pub async fn refresh_quota_for_accounts(
+    ids: &[String],
+    operation_id: Option<String>,
+) -> Result<RefreshStats, String> {
+    let mut seen = HashSet::new();
+    let mut accounts = Vec::new();
+    let mut missing = Vec::new();
+    for id in ids.iter().filter(|id| seen.insert(id.as_str())) {
+        match load_account(id) {
+            Ok(account) => accounts.push(account),
+            Err(e) => missing.push(format!("Account {}: {}", id, e)),
+        }
+    }
+    if accounts.is_empty() && missing.is_empty() {
+        return Ok(RefreshStats::empty());
+    }
+
+    let op = crate::modules::operations::begin_operation("quota_refresh_selected", operation_id)?;
+    let mut stats = if accounts.is_empty() {
+        RefreshStats::empty()
+    } else {
+        refresh_quotas_for_accounts(accounts, 0, &op).await?
+    };
+    stats.total += missing.len();
+    stats.failed += missing.len();
+    stats.details.extend(missing);
+    stats.operation_id = Some(op.id().to_string());
+    Ok(stats)
+}
+
+/// Minimum gap between stale-quota refreshes queued for the same account
+const STALE_REFRESH_COOLDOWN_SECS: i64 = 300;
+
+/// Stale-quota refreshes queued by the proxy: account_id -> queued at
+static STALE_REFRESH_QUEUED: Lazy<Mutex<HashMap<String, i64>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn claim_stale_refresh(queued: &mut HashMap<String, i64>, account_id: &str, now: i64) -> bool {
+    claim_refresh_slot(queued, account_id, now, STALE_REFRESH_COOLDOWN_SECS)
+}
+
+fn claim_refresh_slot(
+    queued: &mut HashMap<String, i64>,
+    account_id: &str,
+    now: i64,
+    cooldown_secs: i64,
+) -> bool {
+    match queued.get(account_id) {
+        Some(at) if now - at < cooldown_secs => false,
+        _ => {
+            queued.insert(account_id.to_string(), now);
+            true
+        }
+    }
+}
+
+/// Queue a background refresh for an account the proxy is about to use with stale quota
+/// data. Returns false if one was queued recently; the proxy reloads the account afterwards
+pub fn queue_stale_quota_refresh(account_id: &str) -> bool {
+    {
+        let mut queued = STALE_REFRESH_QUEUED
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if !claim_stale_refresh(&mut queued, account_id, chrono::Utc::now().timestamp()) {
+            return false;
+        }
+    }
+    let account_id = account_id.to_string();
+    crate::utils::runtime::spawn(async move {
+        match refresh_quota_for_accounts(std::slice::from_ref(&account_id), None).await {
+            Ok(stats) if stats.success > 0 => {
+                crate::modules::events::account_reload(&account_id);
+            }
+            Ok(stats) => crate::modules::logger::log_warn(&format!(
+                "[Quota-Stale] Refresh of {} failed: {}",
+                account_id,
+                stats.details.join("; ")
+            )),
+            Err(e) => crate::modules::logger::log_warn(&format!(
+                "[Quota-Stale] Refresh of {} failed: {}",
+                account_id, e
+            )),
+        }
+    });
+    true
+}
+
+/// Minimum gap between refreshes triggered by proxy quota errors for the same account
+const RATE_LIMIT_REFRESH_COOLDOWN_SECS: i64 = 30;
+/// Wait before refreshing so a burst of 429s from parallel requests collapses into one fetch
+const RATE_LIMIT_REFRESH_DELAY_MS: u64 = 2_000;
+
+/// Refreshes queued because the proxy hit quota errors: account_id -> queued at
+static RATE_LIMIT_REFRESH_QUEUED: Lazy<Mutex<HashMap<String, i64>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Refresh an account's quota shortly after the proxy got a quota-related 429 for it, so
+/// quota protection flags update within seconds instead of at the next scheduled refresh.
+/// Debounced per account; returns false if a refresh was queued recently
+pub fn queue_rate_limit_quota_refresh(account_id: &str) -> bool {
+    {
+        let mut queued = RATE_LIMIT_REFRESH_QUEUED
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if !claim_refresh_slot(
+            &mut queued,
+            account_id,
+            chrono::Utc::now().timestamp(),
+            RATE_LIMIT_REFRESH_COOLDOWN_SECS,
+        ) {
+            return false;
+        }
+    }
+    let account_id = account_id.to_string();
+    crate::utils::runtime::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(RATE_LIMIT_REFRESH_DELAY_MS)).await;
+        match refresh_quota_for_accounts(std::slice::from_ref(&account_id), None).await {
+            Ok(stats) if stats.success > 0 => {
+                crate::modules::events::account_reload(&account_id);
+            }
+            Ok(stats) => crate::modules::logger::log_warn(&format!(
+                "[Quota-429] Refresh of {} failed: {}",
+                account_id,
+                stats.details.join("; ")
+            )),
+            Err(e) => crate::modules::logger::log_warn(&format!(
+                "[Quota-429] Refresh of {} failed: {}",
+                account_id, e
+            )),
+        }
+    });
+    true
+}
+
+/// Process-wide limit on concurrent quota fetches, shared by every refresh path so that
+/// overlapping batches (scheduler + UI) don't multiply the configured concurrency.
+/// Rebuilt when `max_concurrent_fetches` changes; batches holding the old one finish on it
+static QUOTA_FETCH_SEMAPHORE: Lazy<Mutex<(usize, std::sync::Arc<tokio::sync::Semaphore>)>> =
+    Lazy::new(|| Mutex::new((0, std::sync::Arc::new(tokio::sync::Semaphore::new(0)))));
+
+fn shared_fetch_semaphore(max_concurrent: usize) -> std::sync::Arc<tokio::sync::Semaphore> {
+    let mut slot = QUOTA_FETCH_SEMAPHORE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if slot.0 != max_concurrent {
+        *slot = (
+            max_concurrent,
+            std::sync::Arc::new(tokio::sync::Semaphore::new(max_concurrent)),
+        );
+    }
+    slot.1.clone()
+}
+
+type QuotaOutcome = Option<Result<(), String>>;
+/// (flight id, result channel) of the refresh currently running for an account
+type QuotaFlightSlot = (u64, tokio::sync::watch::Sender<QuotaOutcome>);
+
+/// In-flight quota refreshes keyed by account id
+static QUOTA_IN_FLIGHT: Lazy<Mutex<HashMap<String, QuotaFlightSlot>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+static QUOTA_FLIGHT_ID: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+
+enum QuotaTicket {
+    Leader(QuotaFlight),
+    Follower(tokio::sync::watch::Receiver<QuotaOutcome>),
+}
+
+/// Ownership of an account's refresh; dropping it without `complete` (cancelled, panicked)
+/// releases the slot and lets waiters retry
+struct QuotaFlight {
+    account_id: String,
+    id: u64,
+}
+
+impl QuotaFlight {
+    fn complete(self, result: &Result<(), String>) {
+        if let Some(tx) = self.release() {
+            tx.send_replace(Some(result.clone()));
+        }
+    }
+
+    fn release(&self) -> Option<tokio::sync::watch::Sender<QuotaOutcome>> {
+        let mut flights = QUOTA_IN_FLIGHT.lock().unwrap_or_else(|e| e.into_inner());
+        match flights.get(&self.account_id) {
+            Some((id, _)) if *id == self.id => flights.remove(&self.account_id).map(|(_, tx)| tx),
+            _ => None,
+        }
+    }
+}
+
+impl Drop for QuotaFlight {
+    fn drop(&mut self) {
+        // Dropping the sender closes the channel; waiters see `None` and take over
+        self.release();
+    }
+}
+
+fn begin_quota_flight(account_id: &str) -> QuotaTicket {
+    let mut flights = QUOTA_IN_FLIGHT.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((_, tx)) = flights.get(account_id) {
+        return QuotaTicket::Follower(tx.subscribe());
+    }
+    let id = QUOTA_FLIGHT_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    let (tx, _) = tokio::sync::watch::channel(None);
+    flights.insert(account_id.to_string(), (id, tx));
+    QuotaTicket::Leader(QuotaFlight {
+        account_id: account_id.to_string(),
+        id,
+    })
+}
+
+/// Result of the running refresh, or `None` if it was abandoned
+async fn wait_quota_flight(
+    mut rx: tokio::sync::watch::Receiver<QuotaOutcome>,
+) -> Option<Result<(), String>> {
+    rx.wait_for(|outcome| outcome.is_some())
+        .await
+        .ok()
+        .and_then(|outcome| outcome.clone())
+}
+
+async fn refresh_quotas_for_accounts(
+    accounts: Vec<Account>,
+    max_jitter_ms: u64,
+    op: &crate::modules::operations::OperationGuard,
+) -> Result<RefreshStats, String> {
     use futures::future::join_all;
+    use std::sync::atomic::{AtomicUsize, Ordering};
     use std::sync::Arc;
-    use tokio::sync::Semaphore;
 
-    const MAX_CONCURRENT: usize = 5;
+    crate::modules::offline::ensure_online()?;
+
+    let cancel = op.token();
+
+    let max_concurrent = crate::modules::http::settings().max_concurrent_fetches.max(1);
     let start = std::time::Instant::now();
 
     crate::modules::logger::log_info(&format!(
         "Starting batch refresh of all account quotas (Concurrent mode, max: {})",
-        MAX_CONCURRENT
+        max_concurrent
     ));
-    let accounts = list_accounts()?;
 
-    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT));
+    let semaphore = shared_fetch_semaphore(max_concurrent);
+    let done_counter = Arc::new(AtomicUsize::new(0));
 
-    let tasks: Vec<_> = accounts
+    let accounts: Vec<Account> = accounts
         .into_iter()
         .filter(|account| {
             // [MOD] Now we allow refreshing disabled and proxy_disabled accounts
@@ -1849,56 +4461,110 @@ pub async fn refresh_all_quotas_logic() -> Result<RefreshStats, String> {
             }
             true
         })
+        .collect();
+    let total = accounts.len();
+    let operation_id = op.id().to_string();
+    emit_refresh_progress(&operation_id, 0, total, None);
+
+    let tasks: Vec<_> = accounts
+        .into_iter()
         .map(|mut account| {
             let email = account.email.clone();
             let account_id = account.id.clone();
             let permit = semaphore.clone();
+            let cancel = cancel.clone();
+            let done_counter = done_counter.clone();
+            let operation_id = operation_id.clone();
             async move {
-                let _guard = permit.acquire().await.unwrap();
-                crate::modules::logger::log_info(&format!("  - Processing {}", email));
-                match fetch_quota_with_retry(&mut account).await {
-                    Ok(quota) => {
-                        if let Err(e) = update_account_quota(&account_id, quota) {
-                            let msg = format!("Account {}: Save quota failed - {}", email, e);
+                if max_jitter_ms > 0 {
+                    let delay = {
+                        use rand::Rng;
+                        rand::thread_rng().gen_range(0..=max_jitter_ms)
+                    };
+                    tokio::select! {
+                        _ = tokio::time::sleep(std::time::Duration::from_millis(delay)) => {}
+                        _ = cancel.cancelled() => return None,
+                    }
+                }
+                // A refresh of the same account already running elsewhere (tray, UI, scheduler)
+                // is awaited instead of fetching again; if it is dropped, take over
+                let result = loop {
+                    let flight = match begin_quota_flight(&account_id) {
+                        QuotaTicket::Follower(rx) => {
+                            crate::modules::logger::log_info(&format!(
+                                "  - {} already refreshing, waiting for the running request",
+                                email
+                            ));
+                            tokio::select! {
+                                outcome = wait_quota_flight(rx) => match outcome {
+                                    Some(result) => break result,
+                                    None => continue,
+                                },
+                                _ = cancel.cancelled() => return None,
+                            }
+                        }
+                        QuotaTicket::Leader(flight) => flight,
+                    };
+                    let _guard = tokio::select! {
+                        guard = permit.acquire() => guard.unwrap(),
+                        _ = cancel.cancelled() => return None,
+                    };
+                    // Accounts already in flight finish normally; only queued ones are skipped
+                    if cancel.is_cancelled() {
+                        return None;
+                    }
+                    crate::modules::logger::log_info(&format!("  - Processing {}", email));
+                    let result = match fetch_quota_with_retry(&mut account).await {
+                        Ok(quota) => {
+                            if let Err(e) = update_account_quota(&account_id, quota) {
+                                let msg = format!("Account {}: Save quota failed - {}", email, e);
+                                crate::modules::logger::log_error(&msg);
+                                Err(msg)
+                            } else {
+                                crate::modules::logger::log_info(&format!("    Success {}", email));
+                                Ok(())
+                            }
+                        }
+                        Err(e) => {
+                            let msg = format!("Account {}: Fetch quota failed - {}", email, e);
                             crate::modules::logger::log_error(&msg);
                             Err(msg)
-                        } else {
-                            crate::modules::logger::log_info(&format!("    Success {}", email));
-                            Ok(())
                         }
-                    }
-                    Err(e) => {
-                        let msg = format!("Account {}: Fetch quota failed - {}", email, e);
-                        crate::modules::logger::log_error(&msg);
-                        Err(msg)
-                    }
-                }
+                    };
+                    flight.complete(&result);
+                    break result;
+                };
+                let done = done_counter.fetch_add(1, Ordering::SeqCst) + 1;
+                emit_refresh_progress(&operation_id, done, total, Some(&email));
+                Some(result)
             }
         })
         .collect();
 
-    let total = tasks.len();
     let results = join_all(tasks).await;
 
     let mut success = 0;
     let mut failed = 0;
+    let mut skipped = 0;
     let mut details = Vec::new();
 
     for result in results {
         match result {
-            Ok(()) => success += 1,
-            Err(msg) => {
+            Some(Ok(())) => success += 1,
+            Some(Err(msg)) => {
                 failed += 1;
                 details.push(msg);
             }
+            None => skipped += 1,
         }
     }
 
     let elapsed = start.elapsed();
     crate::modules::logger::log_info(&format!(
-        "Batch refresh completed: {} success, {} failed, took: {}ms",
+        "Batch refresh completed: {} success, {} failed, {} skipped, took: {}ms",
         success,
         failed,
+        skipped,
         elapsed.as_millis()
     ));
 
@@ -1913,6 +4579,9 @@ pub async fn refresh_all_quotas_logic() -> Result<RefreshStats, String> {
         success,
         failed,
         details,
+        skipped,
+        cancelled: cancel.is_cancelled(),
+        operation_id: None,
     })
 }
 
@@ -1934,6 +4603,12 @@ pub async fn check_and_trigger_warmup_for_recovered_models() {
         return;
     }
 
+    if crate::modules::scheduler::is_quiet_hours(&app_config.quiet_hours)
+        || crate::modules::offline::is_offline()
+    {
+        return;
+    }
+
     crate::modules::logger::log_info(&format!(
         "[Warmup] Checking {} accounts for recovered models after quota refresh...",
         accounts.len()
@@ -51,7 +51,12 @@ impl AccountService {
                     account.token.project_id = Some(pid);
                 }
                 // 保存更新后的账号信息
-                if let Err(e) = modules::account::save_account(&account) {
+                let (quota, project_id) = (account.quota.clone(), account.token.project_id.clone());
+                if let Err(e) = modules::account::with_account_mut(&account.id, |stored| {
+                    stored.quota = quota;
+                    stored.token.project_id = project_id;
+                    Ok(())
+                }) {
                     modules::logger::log_warn(&format!(
                         "[Service] Failed to save quota for {}: {}",
                         email_for_log, e
@@ -79,17 +84,22 @@ impl AccountService {
     }
 
     /// 删除账号逻辑
-    pub fn delete_account(&self, account_id: &str) -> Result<(), String> {
-        modules::delete_account(account_id)?;
+    pub fn delete_account(&self, account_id: &str, confirmed: bool) -> Result<(), String> {
+        modules::delete_account(account_id, confirmed)?;
         self.integration.update_tray();
         Ok(())
     }
 
-    /// 切换账号逻辑
-    pub async fn switch_account(&self, account_id: &str) -> Result<(), String> {
+    /// 切换账号逻辑 (返回各阶段的执行结果)
+    pub async fn switch_account(&self, account_id: &str) -> Result<crate::models::SwitchReport, String> {
         modules::account::switch_account(account_id, &self.integration).await
     }
 
+    /// 切回上一个账号，返回切换到的账号 ID
+    pub async fn switch_back(&self) -> Result<String, String> {
+        modules::account::switch_back(&self.integration).await
+    }
+
     /// 列表获取
     pub fn list_accounts(&self) -> Result<Vec<Account>, String> {
         modules::list_accounts()
@@ -103,28 +113,16 @@ impl AccountService {
     // --- OAuth 逻辑 ---
 
     pub async fn prepare_oauth_url(&self) -> Result<String, String> {
-        let handle = match &self.integration {
-            modules::integration::SystemManager::Desktop(h) => Some(h.clone()),
-            modules::integration::SystemManager::Headless => None,
-        };
-        modules::oauth_server::prepare_oauth_url(handle).await
+        modules::oauth_server::prepare_oauth_url(self.integration.clone()).await
     }
 
     pub async fn start_oauth_login(&self) -> Result<Account, String> {
-        let handle = match &self.integration {
-            modules::integration::SystemManager::Desktop(h) => Some(h.clone()),
-            modules::integration::SystemManager::Headless => None,
-        };
-        let token_res = modules::oauth_server::start_oauth_flow(handle).await?;
+        let token_res = modules::oauth_server::start_oauth_flow(self.integration.clone()).await?;
         self.process_oauth_token(token_res).await
     }
 
     pub async fn complete_oauth_login(&self) -> Result<Account, String> {
-        let handle = match &self.integration {
-            modules::integration::SystemManager::Desktop(h) => Some(h.clone()),
-            modules::integration::SystemManager::Headless => None,
-        };
-        let token_res = modules::oauth_server::complete_oauth_flow(handle).await?;
+        let token_res = modules::oauth_server::complete_oauth_flow(self.integration.clone()).await?;
         self.process_oauth_token(token_res).await
     }
 
@@ -0,0 +1,198 @@
+// 账号分享链接
+// 把单个账号 (邮箱 + refresh_token + 设备指纹) 用口令加密为一段紧凑文本 (可直接做成二维码)，
+// 在另一台机器上用同一口令兑换，替代在聊天软件里直接粘贴 refresh_token。
+//
+// 格式: "agshare1." + base64url(AGBK 加密块，见 backup::encrypt)
+// 有效期写在加密内容中，无法在不知道口令的情况下修改；过期后兑换会被拒绝。
+
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{Account, DeviceProfile};
+use crate::modules::{account, backup, logger};
+
+const PREFIX: &str = "agshare1.";
+const PAYLOAD_VERSION: u32 = 1;
+const MIN_PASSPHRASE_LEN: usize = 12;
+/// 拒绝 "aaaaaaaaaaaa" / "121212121212" 这类重复口令
+const MIN_DISTINCT_CHARS: usize = 6;
+const DEFAULT_TTL_SECS: u64 = 3600;
+const MAX_TTL_SECS: u64 = 7 * 86_400;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SharePayload {
+    v: u32,
+    email: String,
+    refresh_token: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    device_profile: Option<DeviceProfile>,
+    created_at: i64,
+    expires_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountShare {
+    /// 分享文本 (同时作为二维码内容)
+    pub blob: String,
+    pub email: String,
+    pub expires_at: i64,
+}
+
+fn validate_passphrase(passphrase: &str) -> Result<(), String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(format!(
+            "share_passphrase_too_short: at least {} characters",
+            MIN_PASSPHRASE_LEN
+        ));
+    }
+    let distinct: std::collections::HashSet<char> = passphrase.chars().collect();
+    if distinct.len() < MIN_DISTINCT_CHARS {
+        return Err(format!(
+            "share_passphrase_too_weak: use at least {} different characters",
+            MIN_DISTINCT_CHARS
+        ));
+    }
+    Ok(())
+}
+
+fn seal(payload: &SharePayload, passphrase: &str) -> Result<String, String> {
+    let json = serde_json::to_vec(payload).map_err(|e| e.to_string())?;
+    let sealed = backup::encrypt(passphrase, &json)?;
+    Ok(format!(
+        "{}{}",
+        PREFIX,
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(sealed)
+    ))
+}
+
+fn open(blob: &str, passphrase: &str, now: i64) -> Result<SharePayload, String> {
+    // 容忍聊天软件插入的换行 / 空格
+    let compact: String = blob.chars().filter(|c| !c.is_whitespace()).collect();
+    let encoded = compact
+        .strip_prefix(PREFIX)
+        .ok_or_else(|| "invalid_share_blob: unknown format".to_string())?;
+    let sealed = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(encoded)
+        .map_err(|e| format!("invalid_share_blob: {}", e))?;
+    let json = backup::decrypt(passphrase, &sealed)
+        .map_err(|_| "share_decrypt_failed: wrong passphrase or corrupted share".to_string())?;
+    let payload: SharePayload =
+        serde_json::from_slice(&json).map_err(|e| format!("invalid_share_blob: {}", e))?;
+    if payload.v > PAYLOAD_VERSION {
+        return Err(format!(
+            "invalid_share_blob: unsupported version {}",
+            payload.v
+        ));
+    }
+    if now > payload.expires_at {
+        return Err("share_expired".to_string());
+    }
+    Ok(payload)
+}
+
+/// 生成账号分享文本；ttl_secs 默认 1 小时，最长 7 天
+pub fn create_share(
+    account_id: &str,
+    passphrase: &str,
+    ttl_secs: Option<u64>,
+) -> Result<AccountShare, String> {
+    validate_passphrase(passphrase)?;
+    let ttl = ttl_secs.unwrap_or(DEFAULT_TTL_SECS);
+    if ttl == 0 || ttl > MAX_TTL_SECS {
+        return Err(format!(
+            "invalid_share_ttl: must be between 1 and {} seconds",
+            MAX_TTL_SECS
+        ));
+    }
+
+    let acc = account::load_account(account_id)?;
+    let now = chrono::Utc::now().timestamp();
+    let payload = SharePayload {
+        v: PAYLOAD_VERSION,
+        email: acc.email.clone(),
+        refresh_token: acc.token.refresh_token.clone(),
+        device_profile: acc.device_profile.clone(),
+        created_at: now,
+        expires_at: now + ttl as i64,
+    };
+    let blob = seal(&payload, passphrase)?;
+    logger::log_info(&format!(
+        "[Share] Created share for {} (expires in {}s)",
+        acc.email, ttl
+    ));
+    Ok(AccountShare {
+        blob,
+        email: acc.email,
+        expires_at: payload.expires_at,
+    })
+}
+
+/// 兑换分享文本: 用其中的 refresh_token 添加账号，并绑定分享方的设备指纹
+pub async fn redeem_share(
+    service: &crate::modules::account_service::AccountService,
+    blob: &str,
+    passphrase: &str,
+) -> Result<Account, String> {
+    let payload = open(blob, passphrase, chrono::Utc::now().timestamp())?;
+    let mut acc = service.add_account(&payload.refresh_token).await?;
+    if !acc.email.eq_ignore_ascii_case(&payload.email) {
+        logger::log_warn(&format!(
+            "[Share] Share was created for {} but the token belongs to {}",
+            payload.email, acc.email
+        ));
+    }
+    if let Some(profile) = payload.device_profile {
+        account::bind_device_profile_with_profile(&acc.id, profile, Some("shared".to_string()))?;
+        acc = account::load_account(&acc.id)?;
+    }
+    logger::log_info(&format!("[Share] Redeemed share for {}", acc.email));
+    Ok(acc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload(expires_at: i64) -> SharePayload {
+        SharePayload {
+            v: PAYLOAD_VERSION,
+            email: "a@example.com".to_string(),
+            refresh_token: "1//refresh".to_string(),
+            device_profile: None,
+            created_at: 0,
+            expires_at,
+        }
+    }
+
+    #[test]
+    fn test_share_roundtrip() {
+        let blob = seal(&payload(1_000), "correct horse").unwrap();
+        assert!(blob.starts_with(PREFIX));
+        assert!(!blob.contains("1//refresh"));
+
+        // 换行 / 空格被忽略
+        let wrapped = format!("{}\n {}", &blob[..20], &blob[20..]);
+        let opened = open(&wrapped, "correct horse", 500).unwrap();
+        assert_eq!(opened.refresh_token, "1//refresh");
+        assert_eq!(opened.email, "a@example.com");
+    }
+
+    #[test]
+    fn test_share_rejects_wrong_passphrase_and_expired() {
+        let blob = seal(&payload(1_000), "correct horse").unwrap();
+        assert!(open(&blob, "wrong passphrase", 500)
+            .unwrap_err()
+            .starts_with("share_decrypt_failed"));
+        assert_eq!(
+            open(&blob, "correct horse", 1_001).unwrap_err(),
+            "share_expired"
+        );
+        assert!(open("1//refresh", "correct horse", 500)
+            .unwrap_err()
+            .starts_with("invalid_share_blob"));
+        assert!(validate_passphrase("short").is_err());
+        assert!(validate_passphrase("correct1").is_err());
+        assert!(validate_passphrase("abababababab").is_err());
+        assert!(validate_passphrase("correct horse battery").is_ok());
+    }
+}
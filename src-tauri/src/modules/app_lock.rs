@@ -0,0 +1,357 @@
+// 应用锁 (主密码 + 空闲自动锁定)
+// 设置主密码并启用后，应用启动即处于锁定状态；解锁后超过 idle_minutes 无用户操作会再次锁定，
+// 也可随时手动锁定。锁定期间在命令层 (lib.rs 的 invoke_handler) 拦截:
+// - 只有白名单中的命令放行 (锁本身的命令，以及不含账号 / Token / 日志内容的状态查询)
+// - 其余命令 (包括读取账号、配置、日志的只读命令) 返回 app_locked
+// 用户活动只由修改类命令与前端心跳 (app_lock_activity) 计入，轮询类只读命令不会阻止自动锁定。
+// 主密码经 Argon2id 派生后保存。
+// 系统生物识别暂不支持 (没有钥匙串集成)，只能输入主密码解锁。
+// Web 管理界面不经过命令层，仍由管理密码保护。
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU32, Ordering};
+use std::time::Duration;
+
+use crate::models::AppLockConfig;
+use crate::modules::config;
+
+const HASH_SCHEME: &str = "argon2id";
+const MIN_PASSWORD_LEN: usize = 6;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static HAS_PASSWORD: AtomicBool = AtomicBool::new(false);
+static IDLE_MINUTES: AtomicU32 = AtomicU32::new(0);
+static LOCKED: AtomicBool = AtomicBool::new(false);
+static LAST_ACTIVITY: AtomicI64 = AtomicI64::new(0);
+
+/// 锁定期间仍可调用的命令 (锁本身的命令)
+const LOCK_COMMANDS: &[&str] = &[
+    "get_app_lock_status",
+    "unlock_app",
+    "lock_app",
+    "app_lock_activity",
+];
+
+/// 锁定期间可调用的状态查询 (不返回账号、Token、配置或日志内容)
+const ALLOWED_WHILE_LOCKED: &[&str] = &[
+    "get_proxy_status",
+    "get_network_status",
+    "get_clock_skew",
+    "get_onboarding_state",
+    "list_available_languages",
+    "get_locale_messages",
+    "get_update_settings",
+    "should_check_updates",
+    "is_auto_launch_enabled",
+    "show_main_window",
+    "set_window_theme",
+];
+
+/// 轮询类只读命令，不计入用户活动
+const READ_ONLY_PREFIXES: &[&str] = &["get_", "list_", "load_", "check_", "is_"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AppLockStatus {
+    pub enabled: bool,
+    pub has_password: bool,
+    pub locked: bool,
+    pub idle_minutes: u32,
+}
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+fn is_active() -> bool {
+    ENABLED.load(Ordering::SeqCst) && HAS_PASSWORD.load(Ordering::SeqCst)
+}
+
+/// 配置保存后刷新缓存
+pub fn update(cfg: &AppLockConfig) {
+    ENABLED.store(cfg.enabled, Ordering::SeqCst);
+    HAS_PASSWORD.store(cfg.password_hash.is_some(), Ordering::SeqCst);
+    IDLE_MINUTES.store(cfg.idle_minutes, Ordering::SeqCst);
+    if !is_active() {
+        LOCKED.store(false, Ordering::SeqCst);
+    }
+}
+
+/// 启动时调用: 已启用时以锁定状态启动
+pub fn init() {
+    let cfg = config::load_app_config()
+        .map(|c| c.app_lock)
+        .unwrap_or_default();
+    update(&cfg);
+    LOCKED.store(is_active(), Ordering::SeqCst);
+    LAST_ACTIVITY.store(now(), Ordering::SeqCst);
+}
+
+/// 记录一次用户活动
+pub fn touch() {
+    LAST_ACTIVITY.store(now(), Ordering::SeqCst);
+}
+
+pub fn lock() -> Result<(), String> {
+    if !is_active() {
+        return Err("app_lock_not_enabled".to_string());
+    }
+    LOCKED.store(true, Ordering::SeqCst);
+    tracing::info!("[AppLock] Locked");
+    Ok(())
+}
+
+/// 超过空闲时长时锁定；返回当前是否锁定
+fn is_locked_at(now: i64) -> bool {
+    if !is_active() {
+        return false;
+    }
+    if LOCKED.load(Ordering::SeqCst) {
+        return true;
+    }
+    let idle_minutes = IDLE_MINUTES.load(Ordering::SeqCst);
+    if idle_minutes > 0 && now - LAST_ACTIVITY.load(Ordering::SeqCst) >= idle_minutes as i64 * 60 {
+        LOCKED.store(true, Ordering::SeqCst);
+        tracing::info!("[AppLock] Locked after {} idle minute(s)", idle_minutes);
+        return true;
+    }
+    false
+}
+
+pub fn status() -> AppLockStatus {
+    AppLockStatus {
+        enabled: ENABLED.load(Ordering::SeqCst),
+        has_password: HAS_PASSWORD.load(Ordering::SeqCst),
+        locked: is_locked_at(now()),
+        idle_minutes: IDLE_MINUTES.load(Ordering::SeqCst),
+    }
+}
+
+fn classify(command: &str) -> CommandAccess {
+    if LOCK_COMMANDS.contains(&command) {
+        return CommandAccess::Lock;
+    }
+    if ALLOWED_WHILE_LOCKED.contains(&command) {
+        return CommandAccess::Allowed;
+    }
+    CommandAccess::Protected
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum CommandAccess {
+    Lock,
+    Allowed,
+    Protected,
+}
+
+fn is_user_activity(command: &str) -> bool {
+    !READ_ONLY_PREFIXES.iter().any(|p| command.starts_with(p))
+}
+
+/// 命令层检查 (每个 invoke 调用前执行)
+pub fn check_command(command: &str) -> Result<(), String> {
+    check_external(command)?;
+    if classify(command) == CommandAccess::Protected && is_user_activity(command) {
+        touch();
+    }
+    Ok(())
+}
+
+/// 外部触发的操作 (深度链接) 使用: 拦截规则与 check_command 相同，但不计入用户活动，
+/// 外部程序不能借此推迟空闲自动锁定
+pub fn check_external(command: &str) -> Result<(), String> {
+    match classify(command) {
+        CommandAccess::Protected if is_locked_at(now()) => {
+            Err(format!("app_locked: unlock the app to run {}", command))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// 包装 invoke_handler: 锁定时拒绝受保护的命令
+#[cfg(feature = "desktop")]
+pub fn guard(
+    handler: impl Fn(tauri::ipc::Invoke) -> bool + Send + Sync + 'static,
+) -> impl Fn(tauri::ipc::Invoke) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        let command = invoke.message.command().to_string();
+        if let Err(e) = check_command(&command) {
+            invoke
+                .resolver
+                .reject(crate::error::CommandError::from(e).with_param("command", command));
+            return true;
+        }
+        handler(invoke)
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn derive(password: &str, salt: &str) -> Result<String, String> {
+    let params = Params::new(19 * 1024, 2, 1, Some(32))
+        .map_err(|e| format!("app_lock_kdf_failed: {}", e))?;
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(password.as_bytes(), salt.as_bytes(), &mut key)
+        .map_err(|e| format!("app_lock_kdf_failed: {}", e))?;
+    Ok(hex(&key))
+}
+
+fn hash_password(password: &str) -> Result<String, String> {
+    let salt = uuid::Uuid::new_v4().simple().to_string();
+    Ok(format!(
+        "{}${}${}",
+        HASH_SCHEME,
+        salt,
+        derive(password, &salt)?
+    ))
+}
+
+fn verify_password(password: &str, stored: &str) -> bool {
+    let parts: Vec<&str> = stored.split('$').collect();
+    let [scheme, salt, expected] = parts.as_slice() else {
+        return false;
+    };
+    if *scheme != HASH_SCHEME {
+        return false;
+    }
+    let Ok(actual) = derive(password, salt) else {
+        return false;
+    };
+    // 定长比较，避免按字节提前返回
+    actual.len() == expected.len()
+        && actual
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+fn check_current(cfg: &AppLockConfig, password: Option<&str>) -> Result<(), String> {
+    let Some(stored) = cfg.password_hash.as_deref() else {
+        return Ok(());
+    };
+    if password.is_some_and(|p| verify_password(p, stored)) {
+        Ok(())
+    } else {
+        Err("app_lock_invalid_password".to_string())
+    }
+}
+
+pub async fn unlock(password: &str) -> Result<(), String> {
+    let cfg = config::load_app_config()?.app_lock;
+    let candidate = password.to_string();
+    let verified = tokio::task::spawn_blocking(move || check_current(&cfg, Some(&candidate)))
+        .await
+        .map_err(|e| e.to_string())?;
+    if verified.is_err() {
+        // 放慢暴力尝试
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        tracing::warn!("[AppLock] Unlock failed: wrong password");
+        return Err("app_lock_invalid_password".to_string());
+    }
+    LOCKED.store(false, Ordering::SeqCst);
+    touch();
+    tracing::info!("[AppLock] Unlocked");
+    Ok(())
+}
+
+/// 设置 / 修改 / 清除主密码；已有密码时必须提供当前密码，清除密码同时关闭应用锁
+pub fn set_password(current: Option<&str>, new_password: Option<&str>) -> Result<(), String> {
+    let mut app_config = config::load_app_config()?;
+    check_current(&app_config.app_lock, current)?;
+    match new_password {
+        Some(password) => {
+            if password.chars().count() < MIN_PASSWORD_LEN {
+                return Err(format!(
+                    "app_lock_password_too_short: at least {} characters",
+                    MIN_PASSWORD_LEN
+                ));
+            }
+            app_config.app_lock.password_hash = Some(hash_password(password)?);
+        }
+        None => {
+            app_config.app_lock.password_hash = None;
+            app_config.app_lock.enabled = false;
+        }
+    }
+    config::save_app_config(&app_config)?;
+    touch();
+    Ok(())
+}
+
+/// 修改启用状态与空闲时长；启用前须先设置密码，关闭时须提供当前密码
+pub fn configure(enabled: bool, idle_minutes: u32, password: Option<&str>) -> Result<(), String> {
+    let mut app_config = config::load_app_config()?;
+    if enabled && app_config.app_lock.password_hash.is_none() {
+        return Err("app_lock_password_required: set a master password first".to_string());
+    }
+    if !enabled && app_config.app_lock.enabled {
+        check_current(&app_config.app_lock, password)?;
+    }
+    app_config.app_lock.enabled = enabled;
+    app_config.app_lock.idle_minutes = idle_minutes;
+    config::save_app_config(&app_config)?;
+    touch();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_password_hash_roundtrip() {
+        let stored = hash_password("correct horse").unwrap();
+        assert!(stored.starts_with("argon2id$"));
+        assert!(verify_password("correct horse", &stored));
+        assert!(!verify_password("correct horse!", &stored));
+        assert!(!verify_password("correct horse", "sha256$0$salt$00"));
+        assert!(!verify_password("correct horse", "garbage"));
+        // 同一密码每次的盐不同
+        assert_ne!(stored, hash_password("correct horse").unwrap());
+    }
+
+    #[test]
+    fn test_command_classification() {
+        assert_eq!(classify("unlock_app"), CommandAccess::Lock);
+        assert_eq!(classify("get_proxy_status"), CommandAccess::Allowed);
+        // 未列入白名单的只读命令同样拦截
+        assert_eq!(classify("list_account_summaries"), CommandAccess::Protected);
+        assert_eq!(classify("get_proxy_logs_paginated"), CommandAccess::Protected);
+        assert_eq!(classify("get_debug_console_logs"), CommandAccess::Protected);
+        assert_eq!(classify("get_proxy_pool_config"), CommandAccess::Protected);
+        assert_eq!(classify("list_accounts"), CommandAccess::Protected);
+        assert_eq!(classify("load_config"), CommandAccess::Protected);
+        assert_eq!(classify("export_accounts"), CommandAccess::Protected);
+        assert_eq!(classify("switch_account"), CommandAccess::Protected);
+        assert_eq!(classify("save_config"), CommandAccess::Protected);
+        assert!(!is_user_activity("get_proxy_stats"));
+        assert!(is_user_activity("switch_account"));
+    }
+
+    #[test]
+    fn test_external_check_does_not_count_as_activity() {
+        update(&AppLockConfig {
+            enabled: true,
+            password_hash: Some("argon2id$salt$00".to_string()),
+            idle_minutes: 5,
+        });
+        LOCKED.store(false, Ordering::SeqCst);
+        let idle_since = now() - 60;
+        LAST_ACTIVITY.store(idle_since, Ordering::SeqCst);
+
+        assert!(check_external("switch_account").is_ok());
+        assert_eq!(LAST_ACTIVITY.load(Ordering::SeqCst), idle_since);
+        assert!(check_command("switch_account").is_ok());
+        assert!(LAST_ACTIVITY.load(Ordering::SeqCst) > idle_since);
+
+        LOCKED.store(true, Ordering::SeqCst);
+        assert!(check_external("switch_account")
+            .unwrap_err()
+            .starts_with("app_locked"));
+        update(&AppLockConfig::default());
+    }
+}
@@ -0,0 +1,1007 @@
+// 远程备份
+// 把数据目录中的账号与配置文件打包为 zip，用备份口令加密后上传到 WebDAV / S3 兼容存储，
+// 支持按间隔自动上传、只保留最近 N 份，以及从远程下载并恢复 (恢复前先在本地保存一份当前数据)。
+// 适用于不方便直接访问本地文件的无头服务器。
+//
+// 归档格式: "AGBK2" + kdf(1) + salt(16) + nonce(12) + AES-256-GCM(zip)，密钥由 口令 + salt 经 Argon2id 派生。
+// 目标凭据保存在系统钥匙串中 (见 keychain.rs)，钥匙串不可用时与备份口令一样加密保存在配置文件中。
+
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Nonce,
+};
+use argon2::{Algorithm, Argon2, Params, Version};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{Cursor, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::models::{BackupConfig, RemoteBackupTarget};
+use crate::modules::{account, config, logger, offline, scheduler};
+
+const MAGIC: &[u8; 5] = b"AGBK2";
+/// Argon2id, m=19 MiB, t=2, p=1 (OWASP 推荐的最低参数)
+const KDF_ARGON2ID: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const ARCHIVE_PREFIX: &str = "antigravity-backup-";
+const ARCHIVE_SUFFIX: &str = ".agbk";
+const STATE_FILE: &str = "backup_state.json";
+const CHECK_INTERVAL_SECS: u64 = 600;
+const JOB_ID: &str = "backup";
+const REQUEST_TIMEOUT_SECS: u64 = 300;
+
+static SCHEDULER_STARTED: AtomicBool = AtomicBool::new(false);
+static BACKUP_RUNNING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BackupStatus {
+    #[serde(default)]
+    pub last_backup_at: Option<i64>,
+    #[serde(default)]
+    pub last_archive: Option<String>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+    #[serde(default)]
+    pub last_attempt_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupResult {
+    pub archive: String,
+    pub files: usize,
+    pub size: usize,
+    /// 按 keep_last 删除的旧归档
+    pub pruned: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreResult {
+    pub archive: String,
+    pub files_restored: usize,
+    /// 恢复前本地数据的快照 (未加密，位于 <data_dir>/backups)
+    pub safety_copy: String,
+    /// 配置文件已被替换，需要重启后才能完全生效
+    pub restart_required: bool,
+}
+
+// ============================================================================
+// 归档
+// ============================================================================
+
+/// 需要备份的文件: 数据目录顶层的 JSON 文件 + accounts/ 下的账号文件
+fn collect_files(data_dir: &Path) -> Result<Vec<String>, String> {
+    let mut files = Vec::new();
+    let is_json = |p: &Path| p.extension().is_some_and(|e| e == "json");
+    for entry in std::fs::read_dir(data_dir).map_err(|e| format!("backup_read_failed: {}", e))? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        if path.is_file() && is_json(&path) {
+            if let Some(name) = path.file_name() {
+                files.push(name.to_string_lossy().to_string());
+            }
+        }
+    }
+    let accounts_dir = data_dir.join("accounts");
+    if accounts_dir.is_dir() {
+        for entry in std::fs::read_dir(&accounts_dir).map_err(|e| e.to_string())? {
+            let path = entry.map_err(|e| e.to_string())?.path();
+            if path.is_file() && is_json(&path) {
+                if let Some(name) = path.file_name() {
+                    files.push(format!("accounts/{}", name.to_string_lossy()));
+                }
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn build_zip(data_dir: &Path, files: &[String]) -> Result<Vec<u8>, String> {
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    for name in files {
+        let content = std::fs::read(data_dir.join(name))
+            .map_err(|e| format!("backup_read_failed: {}: {}", name, e))?;
+        zip.start_file(name.as_str(), options)
+            .map_err(|e| format!("backup_zip_failed: {}", e))?;
+        zip.write_all(&content)
+            .map_err(|e| format!("backup_zip_failed: {}", e))?;
+    }
+    let cursor = zip
+        .finish()
+        .map_err(|e| format!("backup_zip_failed: {}", e))?;
+    Ok(cursor.into_inner())
+}
+
+/// 解压到数据目录，返回写入的文件数
+fn extract_zip(data_dir: &Path, archive: &[u8]) -> Result<usize, String> {
+    let mut zip = zip::ZipArchive::new(Cursor::new(archive))
+        .map_err(|e| format!("backup_archive_invalid: {}", e))?;
+    let mut restored = 0;
+    for i in 0..zip.len() {
+        let mut entry = zip
+            .by_index(i)
+            .map_err(|e| format!("backup_archive_invalid: {}", e))?;
+        // 拒绝绝对路径与 ".." 等越界条目
+        let Some(relative) = entry.enclosed_name() else {
+            return Err(format!(
+                "backup_archive_invalid: unsafe entry {}",
+                entry.name()
+            ));
+        };
+        if entry.is_dir() {
+            continue;
+        }
+        let target = data_dir.join(relative);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let mut content = Vec::new();
+        entry
+            .read_to_end(&mut content)
+            .map_err(|e| format!("backup_archive_invalid: {}", e))?;
+        let temp = target.with_extension("restore.tmp");
+        std::fs::write(&temp, content).map_err(|e| format!("backup_restore_failed: {}", e))?;
+        std::fs::rename(&temp, &target).map_err(|e| format!("backup_restore_failed: {}", e))?;
+        restored += 1;
+    }
+    Ok(restored)
+}
+
+/// 从口令派生 AES-256 密钥；`kdf` 为归档头中的 kdf 标识
+fn derive_key(kdf: u8, passphrase: &str, salt: &[u8]) -> Result<[u8; 32], String> {
+    match kdf {
+        KDF_ARGON2ID => {
+            let params = Params::new(19 * 1024, 2, 1, Some(32))
+                .map_err(|e| format!("backup_kdf_failed: {}", e))?;
+            let mut key = [0u8; 32];
+            Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+                .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+                .map_err(|e| format!("backup_kdf_failed: {}", e))?;
+            Ok(key)
+        }
+        other => Err(format!("backup_archive_invalid: unknown kdf {}", other)),
+    }
+}
+
+pub(crate) fn encrypt(passphrase: &str, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    if passphrase.is_empty() {
+        return Err("backup_passphrase_required".to_string());
+    }
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut salt);
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let cipher = Aes256Gcm::new(&derive_key(KDF_ARGON2ID, passphrase, &salt)?.into());
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), plaintext)
+        .map_err(|e| format!("backup_encrypt_failed: {}", e))?;
+
+    let mut out = Vec::with_capacity(MAGIC.len() + 1 + SALT_LEN + NONCE_LEN + ciphertext.len());
+    out.extend_from_slice(MAGIC);
+    out.push(KDF_ARGON2ID);
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+pub(crate) fn decrypt(passphrase: &str, data: &[u8]) -> Result<Vec<u8>, String> {
+    let invalid = || "backup_archive_invalid: not an encrypted backup".to_string();
+    let magic = data.get(..MAGIC.len()).ok_or_else(invalid)?;
+    if magic != MAGIC {
+        return Err(invalid());
+    }
+    let kdf = *data.get(MAGIC.len()).ok_or_else(invalid)?;
+    let body = &data[MAGIC.len() + 1..];
+    if body.len() < SALT_LEN + NONCE_LEN {
+        return Err(invalid());
+    }
+    let salt = &body[..SALT_LEN];
+    let nonce = &body[SALT_LEN..SALT_LEN + NONCE_LEN];
+    let cipher = Aes256Gcm::new(&derive_key(kdf, passphrase, salt)?.into());
+    cipher
+        .decrypt(Nonce::from_slice(nonce), &body[SALT_LEN + NONCE_LEN..])
+        .map_err(|_| "backup_decrypt_failed: wrong passphrase or corrupted archive".to_string())
+}
+
+fn archive_name(now: chrono::DateTime<chrono::Utc>) -> String {
+    format!(
+        "{}{}{}",
+        ARCHIVE_PREFIX,
+        now.format("%Y%m%d-%H%M%S"),
+        ARCHIVE_SUFFIX
+    )
+}
+
+fn is_archive_name(name: &str) -> bool {
+    name.starts_with(ARCHIVE_PREFIX) && name.ends_with(ARCHIVE_SUFFIX) && !name.contains('/')
+}
+
+// ============================================================================
+// 远程存储
+// ============================================================================
+
+fn http_client() -> Result<reqwest::Client, String> {
+    crate::modules::http::reqwest_client_builder()
+        .timeout(std::time::Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| format!("backup_http_client_failed: {}", e))
+}
+
+async fn check_status(resp: reqwest::Response, action: &str) -> Result<reqwest::Response, String> {
+    let status = resp.status();
+    if status.is_success() {
+        return Ok(resp);
+    }
+    let body = resp.text().await.unwrap_or_default();
+    let snippet: String = body.chars().take(200).collect();
+    Err(format!(
+        "backup_remote_{}_failed: HTTP {} {}",
+        action,
+        status.as_u16(),
+        snippet.trim()
+    ))
+}
+
+/// 远程对象不存在 (HTTP 404)
+pub(crate) fn is_not_found(error: &str) -> bool {
+    error.contains(": HTTP 404")
+}
+
+mod webdav {
+    use super::*;
+
+    fn collection_url(url: &str) -> String {
+        format!("{}/", url.trim().trim_end_matches('/'))
+    }
+
+    fn request(
+        client: &reqwest::Client,
+        method: reqwest::Method,
+        url: &str,
+        username: &str,
+        password: &str,
+    ) -> reqwest::RequestBuilder {
+        let req = client.request(method, url);
+        if username.is_empty() {
+            req
+        } else {
+            req.basic_auth(username, Some(password))
+        }
+    }
+
+    pub async fn upload(
+        url: &str,
+        username: &str,
+        password: &str,
+        name: &str,
+        data: Vec<u8>,
+    ) -> Result<(), String> {
+        let client = http_client()?;
+        let resp = request(
+            &client,
+            reqwest::Method::PUT,
+            &format!("{}{}", collection_url(url), name),
+            username,
+            password,
+        )
+        .header("Content-Type", "application/octet-stream")
+        .body(data)
+        .send()
+        .await
+        .map_err(|e| format!("backup_remote_upload_failed: {}", e))?;
+        check_status(resp, "upload").await.map(|_| ())
+    }
+
+    pub async fn download(
+        url: &str,
+        username: &str,
+        password: &str,
+        name: &str,
+    ) -> Result<Vec<u8>, String> {
+        let client = http_client()?;
+        let resp = request(
+            &client,
+            reqwest::Method::GET,
+            &format!("{}{}", collection_url(url), name),
+            username,
+            password,
+        )
+        .send()
+        .await
+        .map_err(|e| format!("backup_remote_download_failed: {}", e))?;
+        let resp = check_status(resp, "download").await?;
+        resp.bytes()
+            .await
+            .map(|b| b.to_vec())
+            .map_err(|e| format!("backup_remote_download_failed: {}", e))
+    }
+
+    pub async fn delete(
+        url: &str,
+        username: &str,
+        password: &str,
+        name: &str,
+    ) -> Result<(), String> {
+        let client = http_client()?;
+        let resp = request(
+            &client,
+            reqwest::Method::DELETE,
+            &format!("{}{}", collection_url(url), name),
+            username,
+            password,
+        )
+        .send()
+        .await
+        .map_err(|e| format!("backup_remote_delete_failed: {}", e))?;
+        check_status(resp, "delete").await.map(|_| ())
+    }
+
+    /// 从 PROPFIND 响应中取出条目的文件名
+    pub(super) fn parse_propfind(body: &str) -> Vec<String> {
+        let re = regex::Regex::new(r"<(?:[A-Za-z0-9]+:)?href>([^<]+)</(?:[A-Za-z0-9]+:)?href>")
+            .expect("static regex");
+        re.captures_iter(body)
+            .filter_map(|c| {
+                let href = c.get(1)?.as_str().trim().trim_end_matches('/');
+                let last = href.rsplit('/').next()?;
+                urlencoding_decode(last)
+            })
+            .filter(|name| is_archive_name(name))
+            .collect()
+    }
+
+    fn urlencoding_decode(segment: &str) -> Option<String> {
+        url::form_urlencoded::parse(format!("n={}", segment.replace('+', "%2B")).as_bytes())
+            .next()
+            .map(|(_, v)| v.into_owned())
+    }
+
+    pub async fn list(url: &str, username: &str, password: &str) -> Result<Vec<String>, String> {
+        let client = http_client()?;
+        let method = reqwest::Method::from_bytes(b"PROPFIND").expect("valid method");
+        let resp = request(&client, method, &collection_url(url), username, password)
+            .header("Depth", "1")
+            .header("Content-Type", "application/xml")
+            .body(
+                r#"<?xml version="1.0" encoding="utf-8"?><d:propfind xmlns:d="DAV:"><d:prop><d:displayname/></d:prop></d:propfind>"#,
+            )
+            .send()
+            .await
+            .map_err(|e| format!("backup_remote_list_failed: {}", e))?;
+        let resp = check_status(resp, "list").await?;
+        let body = resp
+            .text()
+            .await
+            .map_err(|e| format!("backup_remote_list_failed: {}", e))?;
+        Ok(parse_propfind(&body))
+    }
+}
+
+mod s3 {
+    use super::*;
+
+    pub struct S3Target<'a> {
+        pub endpoint: &'a str,
+        pub region: &'a str,
+        pub bucket: &'a str,
+        pub prefix: &'a str,
+        pub access_key_id: &'a str,
+        pub secret_access_key: &'a str,
+        pub path_style: bool,
+    }
+
+    pub(super) fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+        use hmac::{Hmac, Mac};
+        let mut mac =
+            <Hmac<Sha256> as Mac>::new_from_slice(key).expect("HMAC accepts keys of any length");
+        mac.update(data);
+        mac.finalize().into_bytes().into()
+    }
+
+    pub(super) fn signing_key(secret: &str, date: &str, region: &str, service: &str) -> [u8; 32] {
+        let k_date = hmac_sha256(format!("AWS4{}", secret).as_bytes(), date.as_bytes());
+        let k_region = hmac_sha256(&k_date, region.as_bytes());
+        let k_service = hmac_sha256(&k_region, service.as_bytes());
+        hmac_sha256(&k_service, b"aws4_request")
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// RFC 3986 编码 (S3 规范化 URI 要求)
+    fn uri_encode(input: &str, keep_slash: bool) -> String {
+        let mut out = String::new();
+        for b in input.bytes() {
+            match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                    out.push(b as char)
+                }
+                b'/' if keep_slash => out.push('/'),
+                _ => out.push_str(&format!("%{:02X}", b)),
+            }
+        }
+        out
+    }
+
+    impl S3Target<'_> {
+        fn key(&self, name: &str) -> String {
+            let prefix = self.prefix.trim_matches('/');
+            if prefix.is_empty() {
+                name.to_string()
+            } else {
+                format!("{}/{}", prefix, name)
+            }
+        }
+
+        /// (请求 URL, Host 头, 规范化路径)
+        fn locate(&self, key: &str) -> Result<(String, String, String), String> {
+            let endpoint = url::Url::parse(self.endpoint.trim())
+                .map_err(|e| format!("invalid_s3_endpoint: {}", e))?;
+            let scheme = endpoint.scheme();
+            let mut host = endpoint
+                .host_str()
+                .ok_or_else(|| "invalid_s3_endpoint: missing host".to_string())?
+                .to_string();
+            if let Some(port) = endpoint.port() {
+                host = format!("{}:{}", host, port);
+            }
+            let key_path = uri_encode(key, true);
+            let (host, path) = if self.path_style {
+                let path = if key.is_empty() {
+                    format!("/{}", uri_encode(self.bucket, false))
+                } else {
+                    format!("/{}/{}", uri_encode(self.bucket, false), key_path)
+                };
+                (host, path)
+            } else {
+                (
+                    format!("{}.{}", self.bucket, host),
+                    format!("/{}", key_path),
+                )
+            };
+            Ok((format!("{}://{}{}", scheme, host, path), host, path))
+        }
+
+        /// 生成 SigV4 签名后的请求
+        pub(super) fn signed_headers(
+            &self,
+            method: &str,
+            host: &str,
+            path: &str,
+            query: &[(&str, &str)],
+            payload: &[u8],
+            now: chrono::DateTime<chrono::Utc>,
+        ) -> Vec<(String, String)> {
+            let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+            let date = now.format("%Y%m%d").to_string();
+            let payload_hash = hex(&Sha256::digest(payload));
+
+            let mut query: Vec<(String, String)> = query
+                .iter()
+                .map(|(k, v)| (uri_encode(k, false), uri_encode(v, false)))
+                .collect();
+            query.sort();
+            let canonical_query = query
+                .iter()
+                .map(|(k, v)| format!("{}={}", k, v))
+                .collect::<Vec<_>>()
+                .join("&");
+
+            let canonical_headers = format!(
+                "host:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n",
+                host, payload_hash, amz_date
+            );
+            let signed = "host;x-amz-content-sha256;x-amz-date";
+            let canonical_request = format!(
+                "{}\n{}\n{}\n{}\n{}\n{}",
+                method, path, canonical_query, canonical_headers, signed, payload_hash
+            );
+            let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+            let string_to_sign = format!(
+                "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+                amz_date,
+                scope,
+                hex(&Sha256::digest(canonical_request.as_bytes()))
+            );
+            let signature = hex(&hmac_sha256(
+                &signing_key(self.secret_access_key, &date, self.region, "s3"),
+                string_to_sign.as_bytes(),
+            ));
+            vec![
+                ("x-amz-date".to_string(), amz_date),
+                ("x-amz-content-sha256".to_string(), payload_hash),
+                (
+                    "Authorization".to_string(),
+                    format!(
+                        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+                        self.access_key_id, scope, signed, signature
+                    ),
+                ),
+            ]
+        }
+
+        async fn send(
+            &self,
+            method: reqwest::Method,
+            key: &str,
+            query: &[(&str, &str)],
+            payload: Vec<u8>,
+            action: &str,
+        ) -> Result<reqwest::Response, String> {
+            let (url, host, path) = self.locate(key)?;
+            let headers = self.signed_headers(
+                method.as_str(),
+                &host,
+                &path,
+                query,
+                &payload,
+                chrono::Utc::now(),
+            );
+            let client = http_client()?;
+            let mut req = client.request(method, &url).query(query);
+            for (name, value) in headers {
+                req = req.header(name, value);
+            }
+            if !payload.is_empty() {
+                req = req.body(payload);
+            }
+            let resp = req
+                .send()
+                .await
+                .map_err(|e| format!("backup_remote_{}_failed: {}", action, e))?;
+            check_status(resp, action).await
+        }
+
+        pub async fn upload(&self, name: &str, data: Vec<u8>) -> Result<(), String> {
+            self.send(reqwest::Method::PUT, &self.key(name), &[], data, "upload")
+                .await
+                .map(|_| ())
+        }
+
+        pub async fn download(&self, name: &str) -> Result<Vec<u8>, String> {
+            let resp = self
+                .send(
+                    reqwest::Method::GET,
+                    &self.key(name),
+                    &[],
+                    Vec::new(),
+                    "download",
+                )
+                .await?;
+            resp.bytes()
+                .await
+                .map(|b| b.to_vec())
+                .map_err(|e| format!("backup_remote_download_failed: {}", e))
+        }
+
+        pub async fn delete(&self, name: &str) -> Result<(), String> {
+            self.send(
+                reqwest::Method::DELETE,
+                &self.key(name),
+                &[],
+                Vec::new(),
+                "delete",
+            )
+            .await
+            .map(|_| ())
+        }
+
+        pub async fn list(&self) -> Result<Vec<String>, String> {
+            let prefix = self.key(ARCHIVE_PREFIX);
+            let resp = self
+                .send(
+                    reqwest::Method::GET,
+                    "",
+                    &[("list-type", "2"), ("prefix", &prefix)],
+                    Vec::new(),
+                    "list",
+                )
+                .await?;
+            let body = resp
+                .text()
+                .await
+                .map_err(|e| format!("backup_remote_list_failed: {}", e))?;
+            Ok(parse_list(&body))
+        }
+    }
+
+    /// 从 ListObjectsV2 响应中取出归档文件名
+    pub(super) fn parse_list(body: &str) -> Vec<String> {
+        let re = regex::Regex::new(r"<Key>([^<]+)</Key>").expect("static regex");
+        re.captures_iter(body)
+            .filter_map(|c| c.get(1)?.as_str().rsplit('/').next().map(str::to_string))
+            .filter(|name| is_archive_name(name))
+            .collect()
+    }
+}
+
+fn s3_target(target: &RemoteBackupTarget) -> Option<s3::S3Target<'_>> {
+    match target {
+        RemoteBackupTarget::S3 {
+            endpoint,
+            region,
+            bucket,
+            prefix,
+            access_key_id,
+            secret_access_key,
+            path_style,
+        } => Some(s3::S3Target {
+            endpoint,
+            region,
+            bucket,
+            prefix,
+            access_key_id,
+            secret_access_key,
+            path_style: *path_style,
+        }),
+        _ => None,
+    }
+}
+
+pub(crate) async fn remote_upload(
+    target: &RemoteBackupTarget,
+    name: &str,
+    data: Vec<u8>,
+) -> Result<(), String> {
+    match target {
+        RemoteBackupTarget::None => Err("backup_target_not_configured".to_string()),
+        RemoteBackupTarget::Webdav {
+            url,
+            username,
+            password,
+        } => webdav::upload(url, username, password, name, data).await,
+        RemoteBackupTarget::S3 { .. } => match s3_target(target) {
+            Some(s3) => s3.upload(name, data).await,
+            None => Err("backup_target_not_configured".to_string()),
+        },
+    }
+}
+
+pub(crate) async fn remote_download(
+    target: &RemoteBackupTarget,
+    name: &str,
+) -> Result<Vec<u8>, String> {
+    match target {
+        RemoteBackupTarget::None => Err("backup_target_not_configured".to_string()),
+        RemoteBackupTarget::Webdav {
+            url,
+            username,
+            password,
+        } => webdav::download(url, username, password, name).await,
+        RemoteBackupTarget::S3 { .. } => match s3_target(target) {
+            Some(s3) => s3.download(name).await,
+            None => Err("backup_target_not_configured".to_string()),
+        },
+    }
+}
+
+async fn remote_delete(target: &RemoteBackupTarget, name: &str) -> Result<(), String> {
+    match target {
+        RemoteBackupTarget::None => Err("backup_target_not_configured".to_string()),
+        RemoteBackupTarget::Webdav {
+            url,
+            username,
+            password,
+        } => webdav::delete(url, username, password, name).await,
+        RemoteBackupTarget::S3 { .. } => match s3_target(target) {
+            Some(s3) => s3.delete(name).await,
+            None => Err("backup_target_not_configured".to_string()),
+        },
+    }
+}
+
+/// 远程归档列表 (最新的在前)
+pub async fn list_remote(target: &RemoteBackupTarget) -> Result<Vec<String>, String> {
+    let mut names = match target {
+        RemoteBackupTarget::None => return Err("backup_target_not_configured".to_string()),
+        RemoteBackupTarget::Webdav {
+            url,
+            username,
+            password,
+        } => webdav::list(url, username, password).await?,
+        RemoteBackupTarget::S3 { .. } => match s3_target(target) {
+            Some(s3) => s3.list().await?,
+            None => return Err("backup_target_not_configured".to_string()),
+        },
+    };
+    // 文件名含时间戳，字典序即时间序
+    names.sort();
+    names.dedup();
+    names.reverse();
+    Ok(names)
+}
+
+// ============================================================================
+// 备份 / 恢复
+// ============================================================================
+
+fn state_path() -> Result<PathBuf, String> {
+    Ok(account::get_data_dir()?.join(STATE_FILE))
+}
+
+pub fn load_status() -> BackupStatus {
+    state_path()
+        .ok()
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_status(status: &BackupStatus) {
+    let Ok(path) = state_path() else {
+        return;
+    };
+    match serde_json::to_string_pretty(status) {
+        Ok(content) => {
+            if let Err(e) = std::fs::write(&path, content) {
+                logger::log_warn(&format!("[Backup] Failed to save backup state: {}", e));
+            }
+        }
+        Err(e) => logger::log_warn(&format!("[Backup] Failed to serialize backup state: {}", e)),
+    }
+}
+
+/// 打包、加密并上传一份备份，然后按 keep_last 清理旧归档
+pub async fn run_backup(config: &BackupConfig) -> Result<BackupResult, String> {
+    if BACKUP_RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("backup_already_running".to_string());
+    }
+    let result = run_backup_inner(config).await;
+    BACKUP_RUNNING.store(false, Ordering::SeqCst);
+
+    let now = chrono::Utc::now().timestamp();
+    let mut status = load_status();
+    status.last_attempt_at = Some(now);
+    match &result {
+        Ok(r) => {
+            status.last_backup_at = Some(now);
+            status.last_archive = Some(r.archive.clone());
+            status.last_error = None;
+        }
+        Err(e) => status.last_error = Some(e.clone()),
+    }
+    save_status(&status);
+    result
+}
+
+async fn run_backup_inner(config: &BackupConfig) -> Result<BackupResult, String> {
+    if matches!(config.target, RemoteBackupTarget::None) {
+        return Err("backup_target_not_configured".to_string());
+    }
+    let data_dir = account::get_data_dir()?;
+    let passphrase = config.passphrase.clone();
+    let (files, encrypted) = tokio::task::spawn_blocking(move || {
+        let files = collect_files(&data_dir)?;
+        let archive = build_zip(&data_dir, &files)?;
+        Ok::<_, String>((files.len(), encrypt(&passphrase, &archive)?))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    let name = archive_name(chrono::Utc::now());
+    let size = encrypted.len();
+    remote_upload(&config.target, &name, encrypted).await?;
+    logger::log_info(&format!(
+        "[Backup] Uploaded {} ({} file(s), {} bytes)",
+        name, files, size
+    ));
+
+    let mut pruned = Vec::new();
+    if config.keep_last > 0 {
+        match list_remote(&config.target).await {
+            Ok(names) => {
+                for old in names.into_iter().skip(config.keep_last as usize) {
+                    match remote_delete(&config.target, &old).await {
+                        Ok(()) => pruned.push(old),
+                        Err(e) => {
+                            logger::log_warn(&format!("[Backup] Failed to prune {}: {}", old, e))
+                        }
+                    }
+                }
+            }
+            Err(e) => logger::log_warn(&format!("[Backup] Failed to list remote archives: {}", e)),
+        }
+    }
+
+    Ok(BackupResult {
+        archive: name,
+        files,
+        size,
+        pruned,
+    })
+}
+
+/// 下载并恢复指定归档 (恢复前先把当前数据打包到 <data_dir>/backups)
+pub async fn restore_backup(config: &BackupConfig, name: &str) -> Result<RestoreResult, String> {
+    if !is_archive_name(name) {
+        return Err(format!("invalid_backup_name: {}", name));
+    }
+    let encrypted = remote_download(&config.target, name).await?;
+    let passphrase = config.passphrase.clone();
+    let archive_label = name.to_string();
+
+    let (files_restored, safety_copy, restart_required) = tokio::task::spawn_blocking(move || {
+        let archive = decrypt(&passphrase, &encrypted)?;
+        let data_dir = account::get_data_dir()?;
+        // 恢复期间阻止其他账号读写，避免与 accounts.json / 账号文件的写入交错
+        let _guard = account::lock_accounts()?;
+
+        let safety_dir = data_dir.join("backups");
+        std::fs::create_dir_all(&safety_dir)
+            .map_err(|e| format!("backup_restore_failed: {}", e))?;
+        let safety_path = safety_dir.join(format!(
+            "pre-restore-{}.zip",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        let current = build_zip(&data_dir, &collect_files(&data_dir)?)?;
+        std::fs::write(&safety_path, current)
+            .map_err(|e| format!("backup_restore_failed: {}", e))?;
+
+        let restored = extract_zip(&data_dir, &archive)?;
+        let restart_required = zip::ZipArchive::new(Cursor::new(&archive))
+            .map(|z| z.file_names().any(|n| n == "gui_config.json"))
+            .unwrap_or(false);
+        Ok::<_, String>((
+            restored,
+            safety_path.to_string_lossy().to_string(),
+            restart_required,
+        ))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    // 让反代重新加载恢复后的账号
+    if let Ok(accounts) = account::list_accounts() {
+        for acc in accounts {
+            crate::modules::events::account_reload(&acc.id);
+        }
+    }
+    logger::log_info(&format!(
+        "[Backup] Restored {} file(s) from {} (previous data saved to {})",
+        files_restored, archive_label, safety_copy
+    ));
+
+    Ok(RestoreResult {
+        archive: archive_label,
+        files_restored,
+        safety_copy,
+        restart_required,
+    })
+}
+
+/// 启动定时备份任务 (每个进程一次)；每次检查时读取最新配置
+pub fn start_backup_scheduler() {
+    if SCHEDULER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    scheduler::register_job(JOB_ID, CHECK_INTERVAL_SECS);
+    crate::utils::runtime::spawn(async {
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_secs(CHECK_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            if !scheduler::job_tick(JOB_ID) {
+                continue;
+            }
+
+            let Ok(app_config) = config::load_app_config() else {
+                continue;
+            };
+            let backup = &app_config.backup;
+            if !backup.enabled
+                || backup.interval_hours == 0
+                || matches!(backup.target, RemoteBackupTarget::None)
+            {
+                continue;
+            }
+            if scheduler::is_quiet_hours(&app_config.quiet_hours) || offline::is_offline() {
+                continue;
+            }
+            let status = load_status();
+            let last = status.last_attempt_at.unwrap_or(0);
+            if chrono::Utc::now().timestamp() - last < i64::from(backup.interval_hours) * 3600 {
+                continue;
+            }
+            let started = std::time::Instant::now();
+            let result = run_backup(backup).await;
+            if let Err(e) = &result {
+                logger::log_warn(&format!("[Backup] Scheduled backup failed: {}", e));
+            }
+            scheduler::record_job_run(
+                JOB_ID,
+                started,
+                result.map(|r| format!("{} ({} files)", r.archive, r.files)),
+            );
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_encrypt_roundtrip_and_wrong_passphrase() {
+        let data = b"zip bytes".to_vec();
+        let sealed = encrypt("correct horse", &data).unwrap();
+        assert_eq!(&sealed[..MAGIC.len()], MAGIC);
+        assert_eq!(decrypt("correct horse", &sealed).unwrap(), data);
+        assert!(decrypt("wrong", &sealed).is_err());
+        assert!(decrypt("correct horse", b"garbage").is_err());
+        assert!(encrypt("", &data).is_err());
+    }
+
+    #[test]
+    fn test_archive_roundtrip() {
+        let src = std::env::temp_dir().join(format!("abv-backup-src-{}", uuid::Uuid::new_v4()));
+        let dst = std::env::temp_dir().join(format!("abv-backup-dst-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(src.join("accounts")).unwrap();
+        std::fs::create_dir_all(&dst).unwrap();
+        std::fs::write(src.join("accounts.json"), b"{\"accounts\":[]}").unwrap();
+        std::fs::write(src.join("accounts").join("a.json"), b"{}").unwrap();
+        std::fs::write(src.join("proxy_logs.db"), b"sqlite").unwrap();
+
+        let files = collect_files(&src).unwrap();
+        assert_eq!(files, vec!["accounts.json", "accounts/a.json"]);
+        let archive = build_zip(&src, &files).unwrap();
+        assert_eq!(extract_zip(&dst, &archive).unwrap(), 2);
+        assert_eq!(
+            std::fs::read(dst.join("accounts").join("a.json")).unwrap(),
+            b"{}"
+        );
+
+        let _ = std::fs::remove_dir_all(&src);
+        let _ = std::fs::remove_dir_all(&dst);
+    }
+
+    #[test]
+    fn test_hmac_and_signing_key() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            hex(&s3::hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        // AWS SigV4 signing key example
+        assert_eq!(
+            hex(&s3::signing_key(
+                "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+                "20120215",
+                "us-east-1",
+                "iam"
+            )),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+    }
+
+    #[test]
+    fn test_parse_remote_listings() {
+        let propfind = r#"<d:multistatus xmlns:d="DAV:">
+            <d:response><d:href>/dav/backups/</d:href></d:response>
+            <d:response><d:href>/dav/backups/antigravity-backup-20260101-000000.agbk</d:href></d:response>
+            <d:response><D:href>/dav/backups/notes.txt</D:href></d:response>
+        </d:multistatus>"#;
+        assert_eq!(
+            webdav::parse_propfind(propfind),
+            vec!["antigravity-backup-20260101-000000.agbk"]
+        );
+
+        let list = "<ListBucketResult><Contents><Key>ag/antigravity-backup-20260102-000000.agbk</Key></Contents>\
+                    <Contents><Key>ag/other.bin</Key></Contents></ListBucketResult>";
+        assert_eq!(
+            s3::parse_list(list),
+            vec!["antigravity-backup-20260102-000000.agbk"]
+        );
+        assert!(!is_archive_name("../antigravity-backup-x.agbk"));
+    }
+}
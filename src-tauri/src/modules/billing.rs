@@ -0,0 +1,366 @@
+// 用量计费导出 (多租户分摊)
+// 按 API Key (用户令牌) × 月份汇总请求数与 token 用量，按 proxy.billing 的模型单价表计算费用，
+// 以 JSON / CSV 导出，内部分摊无需再从日志中统计。
+// 数据来源为 user_tokens.db 的 token_usage_logs，月份按 UTC 划分；未使用用户令牌的请求不计入。
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::modules::user_token_db::{self, MonthlyModelUsage};
+use crate::proxy::common::model_mapping::wildcard_match;
+use crate::proxy::config::{BillingConfig, ModelPrice};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn parse(value: Option<&str>) -> Result<Self, String> {
+        match value.map(|v| v.trim().to_ascii_lowercase()).as_deref() {
+            None | Some("") | Some("csv") => Ok(Self::Csv),
+            Some("json") => Ok(Self::Json),
+            Some(other) => Err(format!("unsupported_billing_format: {}", other)),
+        }
+    }
+}
+
+/// 某个 Key 在某月使用某个模型的用量与费用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BillingLine {
+    pub month: String,
+    pub token_id: String,
+    pub username: String,
+    pub model: String,
+    pub requests: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub total_tokens: i64,
+    pub cost: f64,
+    /// 单价表中没有匹配的模型 (按 0 计价)
+    pub unpriced: bool,
+}
+
+/// 某个 Key 在某月的合计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BillingKeyTotal {
+    pub month: String,
+    pub token_id: String,
+    pub username: String,
+    pub requests: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub total_tokens: i64,
+    pub cost: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BillingReport {
+    pub currency: String,
+    pub from_month: Option<String>,
+    pub to_month: Option<String>,
+    pub generated_at: i64,
+    pub lines: Vec<BillingLine>,
+    pub totals: Vec<BillingKeyTotal>,
+    pub total_cost: f64,
+}
+
+/// 校验 "YYYY-MM" 格式的月份
+fn normalize_month(month: Option<&str>) -> Result<Option<String>, String> {
+    let Some(month) = month.map(str::trim).filter(|m| !m.is_empty()) else {
+        return Ok(None);
+    };
+    chrono::NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d")
+        .map(|date| Some(date.format("%Y-%m").to_string()))
+        .map_err(|_| format!("invalid_billing_month: {}", month))
+}
+
+/// 精确匹配优先，其次最长的通配规则
+fn price_for<'a>(config: &'a BillingConfig, model: &str) -> Option<&'a ModelPrice> {
+    if let Some(price) = config.prices.get(model) {
+        return Some(price);
+    }
+    config
+        .prices
+        .iter()
+        .filter(|(pattern, _)| pattern.contains('*') && wildcard_match(pattern, model))
+        .max_by(|(a, _), (b, _)| a.len().cmp(&b.len()).then_with(|| b.cmp(a)))
+        .map(|(_, price)| price)
+}
+
+fn cost_of(price: &ModelPrice, requests: i64, input_tokens: i64, output_tokens: i64) -> f64 {
+    price.per_request * requests as f64
+        + price.input_per_million * input_tokens as f64 / 1_000_000.0
+        + price.output_per_million * output_tokens as f64 / 1_000_000.0
+}
+
+/// 费用保留 6 位小数，避免浮点累加噪声
+fn round_cost(cost: f64) -> f64 {
+    (cost * 1_000_000.0).round() / 1_000_000.0
+}
+
+pub fn build_report(
+    config: &BillingConfig,
+    usage: Vec<MonthlyModelUsage>,
+    from_month: Option<String>,
+    to_month: Option<String>,
+) -> BillingReport {
+    let mut totals: BTreeMap<(String, String), BillingKeyTotal> = BTreeMap::new();
+    let mut lines = Vec::with_capacity(usage.len());
+
+    for row in usage {
+        let price = price_for(config, &row.model);
+        let cost = price
+            .map(|p| {
+                round_cost(cost_of(
+                    p,
+                    row.requests,
+                    row.input_tokens,
+                    row.output_tokens,
+                ))
+            })
+            .unwrap_or(0.0);
+        let username = row.username.unwrap_or_else(|| "(deleted)".to_string());
+
+        let total = totals
+            .entry((row.month.clone(), row.token_id.clone()))
+            .or_insert_with(|| BillingKeyTotal {
+                month: row.month.clone(),
+                token_id: row.token_id.clone(),
+                username: username.clone(),
+                requests: 0,
+                input_tokens: 0,
+                output_tokens: 0,
+                total_tokens: 0,
+                cost: 0.0,
+            });
+        total.requests += row.requests;
+        total.input_tokens += row.input_tokens;
+        total.output_tokens += row.output_tokens;
+        total.total_tokens += row.input_tokens + row.output_tokens;
+        total.cost = round_cost(total.cost + cost);
+
+        lines.push(BillingLine {
+            month: row.month,
+            token_id: row.token_id,
+            username,
+            model: row.model,
+            requests: row.requests,
+            input_tokens: row.input_tokens,
+            output_tokens: row.output_tokens,
+            total_tokens: row.input_tokens + row.output_tokens,
+            cost,
+            unpriced: price.is_none(),
+        });
+    }
+
+    let totals: Vec<BillingKeyTotal> = totals.into_values().collect();
+    let total_cost = round_cost(totals.iter().map(|t| t.cost).sum());
+    BillingReport {
+        currency: config.currency.clone(),
+        from_month,
+        to_month,
+        generated_at: chrono::Utc::now().timestamp(),
+        lines,
+        totals,
+        total_cost,
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// 每行一个 Key × 月份 × 模型
+pub fn to_csv(report: &BillingReport) -> String {
+    let mut out = format!(
+        "month,token_id,username,model,requests,input_tokens,output_tokens,total_tokens,cost_{}\n",
+        report.currency.to_ascii_lowercase()
+    );
+    for line in &report.lines {
+        out.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{:.6}\n",
+            line.month,
+            csv_field(&line.token_id),
+            csv_field(&line.username),
+            csv_field(&line.model),
+            line.requests,
+            line.input_tokens,
+            line.output_tokens,
+            line.total_tokens,
+            line.cost
+        ));
+    }
+    out
+}
+
+/// 按当前配置的单价表生成报表
+pub fn generate_report(
+    from_month: Option<&str>,
+    to_month: Option<&str>,
+) -> Result<BillingReport, String> {
+    let from_month = normalize_month(from_month)?;
+    let to_month = normalize_month(to_month)?;
+    let config = crate::modules::config::load_app_config()?.proxy.billing;
+    let usage = user_token_db::get_monthly_usage(from_month.as_deref(), to_month.as_deref())?;
+    Ok(build_report(&config, usage, from_month, to_month))
+}
+
+/// 导出为 CSV / JSON 文本
+pub fn export_report(
+    from_month: Option<&str>,
+    to_month: Option<&str>,
+    format: ExportFormat,
+) -> Result<String, String> {
+    let report = generate_report(from_month, to_month)?;
+    match format {
+        ExportFormat::Csv => Ok(to_csv(&report)),
+        ExportFormat::Json => serde_json::to_string_pretty(&report).map_err(|e| e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(
+        month: &str,
+        token: &str,
+        model: &str,
+        requests: i64,
+        input: i64,
+        output: i64,
+    ) -> MonthlyModelUsage {
+        MonthlyModelUsage {
+            month: month.to_string(),
+            token_id: token.to_string(),
+            username: Some(format!("user-{}", token)),
+            model: model.to_string(),
+            requests,
+            input_tokens: input,
+            output_tokens: output,
+        }
+    }
+
+    fn config() -> BillingConfig {
+        let mut config = BillingConfig::default();
+        config.prices.insert(
+            "gemini-*".to_string(),
+            ModelPrice {
+                input_per_million: 1.0,
+                output_per_million: 2.0,
+                per_request: 0.0,
+            },
+        );
+        config.prices.insert(
+            "gemini-3-pro-*".to_string(),
+            ModelPrice {
+                input_per_million: 10.0,
+                output_per_million: 20.0,
+                per_request: 0.0,
+            },
+        );
+        config.prices.insert(
+            "claude-sonnet-4-6".to_string(),
+            ModelPrice {
+                input_per_million: 3.0,
+                output_per_million: 15.0,
+                per_request: 0.01,
+            },
+        );
+        config
+    }
+
+    #[test]
+    fn test_price_matching() {
+        let config = config();
+        assert_eq!(
+            price_for(&config, "claude-sonnet-4-6").unwrap().per_request,
+            0.01
+        );
+        // 最长的通配规则优先
+        assert_eq!(
+            price_for(&config, "gemini-3-pro-high")
+                .unwrap()
+                .input_per_million,
+            10.0
+        );
+        assert_eq!(
+            price_for(&config, "gemini-3-flash")
+                .unwrap()
+                .input_per_million,
+            1.0
+        );
+        assert!(price_for(&config, "gpt-4o").is_none());
+    }
+
+    #[test]
+    fn test_build_report_totals() {
+        let report = build_report(
+            &config(),
+            vec![
+                usage("2026-09", "t1", "gemini-3-flash", 10, 1_000_000, 500_000),
+                usage("2026-09", "t1", "claude-sonnet-4-6", 100, 1_000_000, 0),
+                usage("2026-09", "t2", "gpt-4o", 1, 10, 10),
+                usage("2026-10", "t1", "gemini-3-flash", 1, 0, 1_000_000),
+            ],
+            Some("2026-09".to_string()),
+            None,
+        );
+
+        assert_eq!(report.lines.len(), 4);
+        assert_eq!(report.lines[0].cost, 2.0);
+        assert_eq!(report.lines[1].cost, 4.0);
+        assert!(report.lines[2].unpriced);
+
+        assert_eq!(report.totals.len(), 3);
+        let sept_t1 = &report.totals[0];
+        assert_eq!(
+            (sept_t1.month.as_str(), sept_t1.token_id.as_str()),
+            ("2026-09", "t1")
+        );
+        assert_eq!(sept_t1.requests, 110);
+        assert_eq!(sept_t1.total_tokens, 2_500_000);
+        assert_eq!(sept_t1.cost, 6.0);
+        assert_eq!(report.total_cost, 8.0);
+    }
+
+    #[test]
+    fn test_csv_export() {
+        let mut row = usage("2026-09", "t1", "gemini-3-flash", 1, 1_000_000, 0);
+        row.username = Some("Acme, Inc".to_string());
+        let report = build_report(&config(), vec![row], None, None);
+        let csv = to_csv(&report);
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            "month,token_id,username,model,requests,input_tokens,output_tokens,total_tokens,cost_usd"
+        );
+        assert_eq!(
+            lines.next().unwrap(),
+            "2026-09,t1,\"Acme, Inc\",gemini-3-flash,1,1000000,0,1000000,1.000000"
+        );
+    }
+
+    #[test]
+    fn test_month_and_format_validation() {
+        assert_eq!(
+            normalize_month(Some("2026-3")).unwrap().as_deref(),
+            Some("2026-03")
+        );
+        assert!(normalize_month(Some("2026-13")).is_err());
+        assert_eq!(normalize_month(Some(" ")).unwrap(), None);
+        assert_eq!(
+            ExportFormat::parse(Some("JSON")).unwrap(),
+            ExportFormat::Json
+        );
+        assert!(ExportFormat::parse(Some("xlsx")).is_err());
+    }
+}
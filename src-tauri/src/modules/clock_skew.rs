@@ -0,0 +1,173 @@
+// 时钟偏差检测
+// Token 过期时间按本机时钟计算；系统时间不准 (或运行中被 NTP 校正跳变) 时会导致刷新风暴或提前 401。
+// 从上游响应的 Date 头采样 (上游时间 - 本机时间)，取最近样本的中位数作为偏差；
+// Token 的过期时间计算与过期判断统一使用校正后的时间 `now()`。
+// 偏差超过阈值时记录警告并通知前端 (clock://skew)。
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Mutex;
+
+use crate::modules::logger;
+
+const MAX_SAMPLES: usize = 9;
+/// Date 头精度为 1 秒，再加上网络延迟，小于此值的偏差视为噪声不做校正
+const MIN_CORRECTION_SECS: i64 = 2;
+/// 超过此偏差时发出警告
+pub const WARN_THRESHOLD_SECS: i64 = 60;
+
+#[derive(Default)]
+struct SkewState {
+    samples: VecDeque<i64>,
+    offset: i64,
+    warned: bool,
+    last_sample_at: Option<i64>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Transition {
+    Warn,
+    Recovered,
+}
+
+static STATE: Lazy<Mutex<SkewState>> = Lazy::new(|| Mutex::new(SkewState::default()));
+/// 当前生效的偏差 (秒)，供 now() 无锁读取
+static OFFSET: AtomicI64 = AtomicI64::new(0);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClockSkewStatus {
+    /// 上游时间 - 本机时间 (秒)；正数表示本机时钟偏慢
+    pub offset_secs: i64,
+    pub samples: usize,
+    pub last_sample_at: Option<i64>,
+    pub threshold_secs: i64,
+    pub warning: bool,
+}
+
+impl SkewState {
+    fn push(&mut self, sample: i64, local_now: i64) -> Option<Transition> {
+        if self.samples.len() == MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+        self.last_sample_at = Some(local_now);
+
+        let mut sorted: Vec<i64> = self.samples.iter().copied().collect();
+        sorted.sort_unstable();
+        let median = sorted[(sorted.len() - 1) / 2];
+        self.offset = if median.abs() >= MIN_CORRECTION_SECS {
+            median
+        } else {
+            0
+        };
+
+        if !self.warned && self.offset.abs() > WARN_THRESHOLD_SECS {
+            self.warned = true;
+            Some(Transition::Warn)
+        } else if self.warned && self.offset.abs() <= WARN_THRESHOLD_SECS / 2 {
+            self.warned = false;
+            Some(Transition::Recovered)
+        } else {
+            None
+        }
+    }
+}
+
+/// 记录一次上游响应的 Date 头 (HTTP-date，如 "Sun, 06 Nov 1994 08:49:37 GMT")
+pub fn observe_date_header(value: Option<&str>) {
+    let Some(server) = value.and_then(|v| chrono::DateTime::parse_from_rfc2822(v.trim()).ok())
+    else {
+        return;
+    };
+    let local_now = chrono::Utc::now().timestamp();
+    let sample = server.timestamp() - local_now;
+
+    let (transition, offset) = {
+        let mut state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+        let transition = state.push(sample, local_now);
+        OFFSET.store(state.offset, Ordering::Relaxed);
+        (transition, state.offset)
+    };
+
+    match transition {
+        Some(Transition::Warn) => {
+            logger::log_warn(&format!(
+                "[Clock] System clock differs from upstream by {}s; token expiry checks are corrected, but please sync the system time",
+                offset
+            ));
+            crate::modules::log_bridge::emit_event("clock://skew", status());
+        }
+        Some(Transition::Recovered) => {
+            logger::log_info(&format!(
+                "[Clock] Clock skew back within tolerance ({}s)",
+                offset
+            ));
+            crate::modules::log_bridge::emit_event("clock://skew", status());
+        }
+        None => {}
+    }
+}
+
+/// 当前偏差 (秒)
+pub fn offset_secs() -> i64 {
+    OFFSET.load(Ordering::Relaxed)
+}
+
+/// 校正后的当前 Unix 时间 (秒)；Token 过期相关的计算统一使用此值
+pub fn now() -> i64 {
+    chrono::Utc::now().timestamp() + offset_secs()
+}
+
+pub fn status() -> ClockSkewStatus {
+    let state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    ClockSkewStatus {
+        offset_secs: state.offset,
+        samples: state.samples.len(),
+        last_sample_at: state.last_sample_at,
+        threshold_secs: WARN_THRESHOLD_SECS,
+        warning: state.warned,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_median_offset_ignores_outliers_and_noise() {
+        let mut state = SkewState::default();
+        for sample in [1, -1, 1] {
+            assert_eq!(state.push(sample, 0), None);
+        }
+        assert_eq!(state.offset, 0);
+
+        // 单个异常样本 (如响应被缓存) 不影响中位数
+        let mut state = SkewState::default();
+        for sample in [30, 31, 900, 30, 29] {
+            state.push(sample, 0);
+        }
+        assert_eq!(state.offset, 30);
+        assert!(!state.warned);
+    }
+
+    #[test]
+    fn test_warns_once_and_recovers() {
+        let mut state = SkewState::default();
+        assert_eq!(state.push(-120, 0), Some(Transition::Warn));
+        assert_eq!(state.push(-121, 0), None);
+        for _ in 0..MAX_SAMPLES {
+            state.push(0, 0);
+        }
+        assert_eq!(state.offset, 0);
+        assert!(!state.warned);
+        assert_eq!(state.samples.len(), MAX_SAMPLES);
+    }
+
+    #[test]
+    fn test_http_date_parses() {
+        let parsed = chrono::DateTime::parse_from_rfc2822("Sun, 06 Nov 1994 08:49:37 GMT").unwrap();
+        assert_eq!(parsed.timestamp(), 784_111_777);
+    }
+}
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use tracing::info;
 use crate::models::TokenData;
+use crate::modules::redact;
 
 // OpenAI Codex OAuth configuration
 // Client ID verified from opencode (sst/opencode) source: packages/opencode/src/plugin/codex.ts
@@ -101,7 +102,7 @@ pub async fn exchange_codex_code(
     if !resp.status().is_success() {
         let status = resp.status();
         let body = resp.text().await.unwrap_or_default();
-        return Err(format!("Codex token exchange HTTP {}: {}", status, body));
+        return Err(format!("Codex token exchange HTTP {}: {}", status, redact::upstream_body(&body)));
     }
 
     resp.json::<CodexTokenResponse>()
@@ -129,7 +130,7 @@ pub async fn refresh_codex_token(refresh_token: &str) -> Result<CodexTokenRespon
     if !resp.status().is_success() {
         let status = resp.status();
         let body = resp.text().await.unwrap_or_default();
-        return Err(format!("Codex token refresh HTTP {}: {}", status, body));
+        return Err(format!("Codex token refresh HTTP {}: {}", status, redact::upstream_body(&body)));
     }
 
     resp.json::<CodexTokenResponse>()
@@ -151,7 +152,7 @@ pub async fn get_codex_user_info(access_token: &str) -> Result<CodexUserInfo, St
     if !resp.status().is_success() {
         let status = resp.status();
         let body = resp.text().await.unwrap_or_default();
-        return Err(format!("Codex user info HTTP {}: {}", status, body));
+        return Err(format!("Codex user info HTTP {}: {}", status, redact::upstream_body(&body)));
     }
 
     resp.json::<CodexUserInfo>()
@@ -286,7 +287,7 @@ pub async fn fetch_codex_wham_usage(access_token: &str) -> Result<WhamUsageRespo
     if !resp.status().is_success() {
         let status = resp.status();
         let body = resp.text().await.unwrap_or_default();
-        return Err(format!("Codex wham/usage HTTP {}: {}", status, body));
+        return Err(format!("Codex wham/usage HTTP {}: {}", status, redact::upstream_body(&body)));
     }
 
     resp.json::<WhamUsageResponse>()
@@ -308,7 +309,7 @@ pub async fn ensure_codex_fresh_token(token: &TokenData) -> Result<Option<TokenD
     }
 
     // Check if token expires within 5 minutes
-    let now = chrono::Utc::now().timestamp();
+    let now = crate::modules::clock_skew::now();
     if token.expiry_timestamp > now + 300 {
         return Ok(None); // still fresh
     }
@@ -0,0 +1,238 @@
+// 配置变更历史
+// 每次保存 gui_config.json 时记录一个版本 (完整配置 + 变更的设置路径)，只保留最近 MAX_VERSIONS 个，
+// 用于排查“改了什么导致号池异常” (例如配额保护阈值) 以及回滚到之前的版本。
+// 历史保存在数据目录的 config_history.json；第一次记录时会先把保存前的配置作为基线版本。
+// 快照不保存密钥/密码等凭据 (见 config::redact_secrets)，回滚时沿用本机当前的凭据。
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::modules::account::get_data_dir;
+use crate::modules::config::redact_secrets;
+
+const HISTORY_FILE: &str = "config_history.json";
+const MAX_VERSIONS: usize = 30;
+
+static HISTORY_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ConfigVersion {
+    version: u64,
+    saved_at: i64,
+    changed: Vec<String>,
+    config: Value,
+}
+
+/// 历史版本概要 (不含配置内容)
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigVersionInfo {
+    pub version: u64,
+    pub saved_at: i64,
+    /// 相对上一个版本变更的设置路径，如 "quota_protection.threshold_percentage"
+    pub changed: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConfigChange {
+    pub path: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+fn history_path() -> Result<std::path::PathBuf, String> {
+    Ok(get_data_dir()?.join(HISTORY_FILE))
+}
+
+fn load_in(path: &Path) -> Vec<ConfigVersion> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// 对比两份配置，返回变更的设置 (对象逐层展开，数组和标量整体比较)
+pub fn diff(before: &Value, after: &Value) -> Vec<ConfigChange> {
+    let mut changes = Vec::new();
+    diff_into(String::new(), Some(before), Some(after), &mut changes);
+    changes
+}
+
+fn diff_into(
+    path: String,
+    before: Option<&Value>,
+    after: Option<&Value>,
+    out: &mut Vec<ConfigChange>,
+) {
+    if let (Some(Value::Object(b)), Some(Value::Object(a))) = (before, after) {
+        let mut keys: Vec<&String> = b.keys().chain(a.keys()).collect();
+        keys.sort();
+        keys.dedup();
+        for key in keys {
+            let child = if path.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", path, key)
+            };
+            diff_into(child, b.get(key), a.get(key), out);
+        }
+        return;
+    }
+    if before != after {
+        out.push(ConfigChange {
+            path,
+            before: before.cloned(),
+            after: after.cloned(),
+        });
+    }
+}
+
+fn record_in(
+    path: &Path,
+    previous: Option<Value>,
+    mut config: Value,
+    now: i64,
+) -> Result<(), String> {
+    let mut history = load_in(path);
+    // 旧版本的历史可能含明文凭据，写回时一并清除
+    for version in &mut history {
+        redact_secrets(&mut version.config);
+    }
+    let previous = previous.map(|mut v| {
+        redact_secrets(&mut v);
+        v
+    });
+    redact_secrets(&mut config);
+    if history.is_empty() {
+        if let Some(previous) = previous {
+            history.push(ConfigVersion {
+                version: 1,
+                saved_at: now,
+                changed: Vec::new(),
+                config: previous,
+            });
+        }
+    }
+
+    let changed: Vec<String> = match history.last() {
+        Some(last) => diff(&last.config, &config)
+            .into_iter()
+            .map(|c| c.path)
+            .collect(),
+        None => Vec::new(),
+    };
+    if history.last().is_some() && changed.is_empty() {
+        return Ok(());
+    }
+
+    history.push(ConfigVersion {
+        version: history.last().map_or(1, |v| v.version + 1),
+        saved_at: now,
+        changed,
+        config,
+    });
+    if history.len() > MAX_VERSIONS {
+        let excess = history.len() - MAX_VERSIONS;
+        history.drain(..excess);
+    }
+
+    let content = serde_json::to_string_pretty(&history)
+        .map_err(|e| format!("failed_to_serialize_config_history: {}", e))?;
+    fs::write(path, content).map_err(|e| format!("failed_to_save_config_history: {}", e))
+}
+
+/// 保存配置后调用；previous 为保存前磁盘上的配置 (没有变化时不记录)
+pub fn record(previous: Option<Value>, config: Value) {
+    let _lock = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let result = history_path()
+        .and_then(|path| record_in(&path, previous, config, chrono::Utc::now().timestamp()));
+    if let Err(e) = result {
+        tracing::warn!("[ConfigHistory] {}", e);
+    }
+}
+
+/// 历史版本列表 (新的在前)
+pub fn list() -> Result<Vec<ConfigVersionInfo>, String> {
+    let _lock = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    Ok(load_in(&history_path()?)
+        .into_iter()
+        .rev()
+        .map(|v| ConfigVersionInfo {
+            version: v.version,
+            saved_at: v.saved_at,
+            changed: v.changed,
+        })
+        .collect())
+}
+
+/// 某个历史版本的完整配置
+pub fn get(version: u64) -> Result<Value, String> {
+    let _lock = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    load_in(&history_path()?)
+        .into_iter()
+        .find(|v| v.version == version)
+        .map(|v| v.config)
+        .ok_or_else(|| format!("config_version_not_found: {}", version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_record_and_diff() {
+        let path =
+            std::env::temp_dir().join(format!("config_history_test_{}.json", uuid::Uuid::new_v4()));
+        let v1 = json!({ "quota_protection": { "enabled": true, "threshold_percentage": 10 }, "language": "zh" });
+        let mut v2 = v1.clone();
+        v2["quota_protection"]["threshold_percentage"] = json!(60);
+
+        // 第一次保存: 先记录保存前的基线
+        record_in(&path, Some(v1.clone()), v2.clone(), 100).unwrap();
+        // 内容未变化时不新增版本
+        record_in(&path, Some(v2.clone()), v2.clone(), 200).unwrap();
+        let history = load_in(&path);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].version, 2);
+        assert_eq!(
+            history[1].changed,
+            vec!["quota_protection.threshold_percentage".to_string()]
+        );
+
+        let changes = diff(
+            &v2,
+            &json!({ "quota_protection": { "enabled": true }, "language": "en" }),
+        );
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].path, "language");
+        assert_eq!(changes[1].path, "quota_protection.threshold_percentage");
+        assert_eq!(changes[1].after, None);
+
+        for i in 0..MAX_VERSIONS as i64 {
+            record_in(&path, None, json!({ "n": i }), 300 + i).unwrap();
+        }
+        let history = load_in(&path);
+        assert_eq!(history.len(), MAX_VERSIONS);
+        assert_eq!(history.last().unwrap().version, 2 + MAX_VERSIONS as u64);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_snapshots_carry_no_secrets() {
+        let path =
+            std::env::temp_dir().join(format!("config_history_test_{}.json", uuid::Uuid::new_v4()));
+        let v1 = json!({ "proxy": { "api_key": "sk-one", "port": 8045 }, "cloudflared": { "token": "cf-one" } });
+        let v2 = json!({ "proxy": { "api_key": "sk-two", "port": 8046 }, "cloudflared": { "token": "cf-one" } });
+
+        record_in(&path, Some(v1), v2, 100).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("sk-one") && !content.contains("sk-two") && !content.contains("cf-one"));
+        let history = load_in(&path);
+        assert_eq!(history[1].changed, vec!["proxy.port".to_string()]);
+        let _ = fs::remove_file(&path);
+    }
+}
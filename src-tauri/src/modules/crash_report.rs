@@ -0,0 +1,282 @@
+// 崩溃报告
+// 后台任务 panic 时 tokio 只会结束该任务，相关功能就此静默失效。这里安装 panic hook，
+// 把 panic 信息、调用栈、版本、正在运行的批量操作和最近 200 行日志写入数据目录的 crash_reports/，
+// 下次启动时通过通知中心 (桌面端另弹窗) 提示用户打开报告。同一位置短时间内重复 panic 只写一份。
+
+use once_cell::sync::Lazy;
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, Once};
+use std::time::{Duration, Instant};
+
+const REPORT_DIR: &str = "crash_reports";
+const REPORT_PREFIX: &str = "crash-";
+/// 记录已提示过的最新报告文件名
+const SEEN_FILE: &str = ".seen";
+const MAX_REPORTS: usize = 20;
+const LOG_TAIL_LINES: usize = 200;
+/// 只读取日志文件末尾这么多字节，避免大文件拖慢崩溃处理
+const LOG_TAIL_BYTES: u64 = 256 * 1024;
+const REPEAT_WINDOW: Duration = Duration::from_secs(60);
+
+static INSTALL: Once = Once::new();
+/// 最近一次写报告的 panic 位置与时间
+static LAST_WRITTEN: Lazy<Mutex<Option<(String, Instant)>>> = Lazy::new(|| Mutex::new(None));
+
+fn report_dir() -> Result<PathBuf, String> {
+    Ok(crate::modules::account::get_data_dir()?.join(REPORT_DIR))
+}
+
+/// 文本末尾的 max 行
+fn last_lines(content: &str, max: usize) -> Vec<&str> {
+    let lines: Vec<&str> = content.lines().collect();
+    let skip = lines.len().saturating_sub(max);
+    lines[skip..].to_vec()
+}
+
+/// 最近写入的日志文件末尾若干行
+fn recent_log_lines() -> (Option<PathBuf>, Vec<String>) {
+    let Ok(dir) = crate::modules::logger::get_log_dir() else {
+        return (None, Vec::new());
+    };
+    let newest = fs::read_dir(&dir).ok().and_then(|entries| {
+        entries
+            .flatten()
+            .filter(|e| e.file_name().to_string_lossy().starts_with("app.log"))
+            .filter_map(|e| Some((e.metadata().ok()?.modified().ok()?, e.path())))
+            .max_by_key(|(modified, _)| *modified)
+            .map(|(_, path)| path)
+    });
+    let Some(path) = newest else {
+        return (None, Vec::new());
+    };
+    let mut content = Vec::new();
+    if let Ok(mut file) = fs::File::open(&path) {
+        let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+        let _ = file.seek(SeekFrom::Start(len.saturating_sub(LOG_TAIL_BYTES)));
+        let _ = file.read_to_end(&mut content);
+    }
+    let content = String::from_utf8_lossy(&content);
+    let lines = last_lines(&content, LOG_TAIL_LINES)
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    (Some(path), lines)
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "(non-string panic payload)".to_string()
+    }
+}
+
+fn write_report(message: &str, location: String) -> Result<PathBuf, String> {
+    // panic 的线程可能持有锁，这里都不阻塞等待
+    if let Ok(mut last) = LAST_WRITTEN.try_lock() {
+        if let Some((prev, at)) = last.as_ref() {
+            if *prev == location && at.elapsed() < REPEAT_WINDOW {
+                return Err("repeated_panic".to_string());
+            }
+        }
+        *last = Some((location.clone(), Instant::now()));
+    }
+
+    let now = chrono::Utc::now();
+    let thread = std::thread::current();
+    let operations = match crate::modules::operations::try_list_operations() {
+        Some(ops) if ops.is_empty() => "none".to_string(),
+        Some(ops) => ops
+            .iter()
+            .map(|op| format!("{} ({}, started {})", op.kind, op.id, op.started_at))
+            .collect::<Vec<_>>()
+            .join(", "),
+        None => "unknown (registry busy)".to_string(),
+    };
+    let (log_file, log_lines) = recent_log_lines();
+
+    let mut report = String::new();
+    report.push_str("Antigravity Tools crash report\n");
+    report.push_str(&format!("Time: {}\n", now.to_rfc3339()));
+    report.push_str(&format!("Version: {}\n", env!("CARGO_PKG_VERSION")));
+    report.push_str(&format!(
+        "OS: {} {}\n",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    ));
+    report.push_str(&format!(
+        "Thread: {}\n",
+        thread.name().unwrap_or("(unnamed)")
+    ));
+    report.push_str(&format!("Message: {}\n", message));
+    report.push_str(&format!("Location: {}\n", location));
+    report.push_str(&format!("Active operations: {}\n", operations));
+    report.push_str(&format!(
+        "\nBacktrace:\n{}\n",
+        std::backtrace::Backtrace::force_capture()
+    ));
+    report.push_str(&format!(
+        "\nLast {} log lines ({}):\n",
+        log_lines.len(),
+        log_file
+            .as_ref()
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| "no log file".to_string())
+    ));
+    for line in &log_lines {
+        report.push_str(line);
+        report.push('\n');
+    }
+
+    let dir = report_dir()?;
+    fs::create_dir_all(&dir).map_err(|e| format!("failed_to_create_crash_dir: {}", e))?;
+    let path = dir.join(format!(
+        "{}{}-{}.txt",
+        REPORT_PREFIX,
+        now.format("%Y%m%d-%H%M%S"),
+        std::process::id()
+    ));
+    fs::write(&path, report).map_err(|e| format!("failed_to_write_crash_report: {}", e))?;
+    prune_in(&dir, MAX_REPORTS);
+    Ok(path)
+}
+
+/// 按文件名排序的报告 (文件名含 UTC 时间，排序即时间顺序)
+fn reports_in(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .filter(|name| name.starts_with(REPORT_PREFIX) && name.ends_with(".txt"))
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+fn prune_in(dir: &Path, keep: usize) {
+    let names = reports_in(dir);
+    let excess = names.len().saturating_sub(keep);
+    for name in &names[..excess] {
+        let _ = fs::remove_file(dir.join(name));
+    }
+}
+
+/// 尚未提示过的报告 (按时间顺序)
+fn unseen_in(dir: &Path) -> Vec<String> {
+    let seen = fs::read_to_string(dir.join(SEEN_FILE)).unwrap_or_default();
+    let seen = seen.trim();
+    reports_in(dir)
+        .into_iter()
+        .filter(|name| name.as_str() > seen)
+        .collect()
+}
+
+/// 安装 panic hook (保留默认 hook 的输出)，每个进程一次
+pub fn install() {
+    INSTALL.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let location = info
+                .location()
+                .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()))
+                .unwrap_or_else(|| "unknown".to_string());
+            match write_report(&panic_message(info.payload()), location) {
+                Ok(path) => tracing::error!("[Crash] Panic report written to {}", path.display()),
+                Err(e) if e == "repeated_panic" => {}
+                Err(e) => tracing::error!("[Crash] Failed to write panic report: {}", e),
+            }
+            previous(info);
+        }));
+    });
+}
+
+/// 启动时检查上次运行留下的报告: 推送通知并标记为已提示，返回最新一份报告的路径
+pub fn announce_previous() -> Option<PathBuf> {
+    let dir = report_dir().ok()?;
+    let unseen = unseen_in(&dir);
+    let latest = unseen.last()?.clone();
+    let _ = fs::write(dir.join(SEEN_FILE), &latest);
+    let path = dir.join(&latest);
+    tracing::warn!(
+        "[Crash] {} crash report(s) since the last start, latest: {}",
+        unseen.len(),
+        path.display()
+    );
+    crate::modules::notify::push(
+        crate::modules::notify::Severity::Error,
+        "crash",
+        "Antigravity Tools crashed",
+        format!(
+            "A background task stopped unexpectedly. Report: {}",
+            path.display()
+        ),
+        Some("crash_report"),
+    );
+    Some(path)
+}
+
+/// 桌面端: 提示上次崩溃并提供打开报告
+#[cfg(feature = "desktop")]
+pub fn prompt_open(app: &tauri::AppHandle, report: PathBuf) {
+    use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+    let path = report.to_string_lossy().to_string();
+    app.dialog()
+        .message(format!(
+            "Antigravity Tools stopped unexpectedly last time. A crash report was saved to:\n{}",
+            path
+        ))
+        .title("Antigravity Tools")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Open report".to_string(),
+            "Dismiss".to_string(),
+        ))
+        .show(move |open| {
+            if open {
+                if let Err(e) = crate::utils::command::open_with_system(&path) {
+                    tracing::warn!("[Crash] Failed to open crash report: {}", e);
+                }
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reports_unseen_and_pruned() {
+        assert_eq!(last_lines("a\nb\nc\n", 2), vec!["b", "c"]);
+        assert_eq!(last_lines("a", 5), vec!["a"]);
+
+        let dir = std::env::temp_dir().join(format!("crash_test_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        for name in [
+            "crash-20261014-010000-1.txt",
+            "crash-20261015-010000-1.txt",
+            "crash-20261016-010000-1.txt",
+            "notes.txt",
+        ] {
+            fs::write(dir.join(name), "x").unwrap();
+        }
+        assert_eq!(unseen_in(&dir).len(), 3);
+        fs::write(dir.join(SEEN_FILE), "crash-20261015-010000-1.txt").unwrap();
+        assert_eq!(unseen_in(&dir), vec!["crash-20261016-010000-1.txt"]);
+
+        prune_in(&dir, 2);
+        assert_eq!(
+            reports_in(&dir),
+            vec!["crash-20261015-010000-1.txt", "crash-20261016-010000-1.txt"]
+        );
+        assert!(dir.join("notes.txt").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
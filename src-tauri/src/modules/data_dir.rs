@@ -0,0 +1,478 @@
+// 数据目录解析
+// 部分企业环境中用户主目录指向已断开的网络共享，直接访问会长时间阻塞，各模块再各自报出不同的错误。
+// 这里统一解析 (访问文件系统带超时) 并缓存结果，优先级: ABV_DATA_DIR > 用户选择的本地目录 > ~/.antigravity_tools。
+// 解析失败统一返回 data_dir_unavailable 错误；桌面端启动时弹窗让用户选择本地目录，选择结果持久化供以后启动使用。
+// move_to 把当前数据目录复制到新位置 (普通文件校验 SHA-256，SQLite 数据库以快照复制并做完整性检查)，
+// 再把新位置写入同一个记录文件并切换过去，无需设置 ABV_DATA_DIR，也无需重启。
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+const DATA_DIR: &str = ".antigravity_tools";
+/// 记录用户选择的本地目录 (不放在数据目录里，因为数据目录本身可能不可用)
+const FALLBACK_FILE: &str = "antigravity_tools_data_dir";
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+static RESOLVED: Lazy<Mutex<Option<Result<PathBuf, String>>>> = Lazy::new(|| Mutex::new(None));
+
+/// 在独立线程中执行文件系统操作，超时后放弃 (卡住的线程留在后台，不阻塞调用方)
+fn with_timeout<T: Send + 'static>(
+    timeout: Duration,
+    f: impl FnOnce() -> T + Send + 'static,
+) -> Option<T> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(f());
+    });
+    rx.recv_timeout(timeout).ok()
+}
+
+/// 确保目录存在且是目录
+fn probe_dir(path: &Path, timeout: Duration) -> Result<(), String> {
+    let target = path.to_path_buf();
+    let result = with_timeout(timeout, move || {
+        fs::create_dir_all(&target)?;
+        if fs::metadata(&target)?.is_dir() {
+            Ok(())
+        } else {
+            Err(std::io::Error::other("not a directory"))
+        }
+    });
+    match result {
+        Some(Ok(())) => Ok(()),
+        Some(Err(e)) => Err(format!("data_dir_unavailable: {}: {}", path.display(), e)),
+        None => Err(format!(
+            "data_dir_unavailable: {}: no response after {}s",
+            path.display(),
+            timeout.as_secs()
+        )),
+    }
+}
+
+/// 记录文件的候选位置: 本机应用数据目录、程序所在目录
+fn fallback_records() -> Vec<PathBuf> {
+    let mut records = Vec::new();
+    if let Some(dir) = dirs::data_local_dir() {
+        records.push(dir.join(FALLBACK_FILE));
+    }
+    if let Some(dir) = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+    {
+        records.push(dir.join(FALLBACK_FILE));
+    }
+    records
+}
+
+fn read_fallback(records: Vec<PathBuf>, timeout: Duration) -> Option<PathBuf> {
+    with_timeout(timeout, move || {
+        records.iter().find_map(|record| {
+            let content = fs::read_to_string(record).ok()?;
+            let path = content.trim();
+            (!path.is_empty()).then(|| PathBuf::from(path))
+        })
+    })
+    .flatten()
+}
+
+fn resolve_in(
+    fallback: Option<PathBuf>,
+    home: Option<PathBuf>,
+    timeout: Duration,
+) -> Result<PathBuf, String> {
+    if let Some(dir) = fallback {
+        return probe_dir(&dir, timeout).map(|_| dir);
+    }
+    let dir = home
+        .ok_or("data_dir_unavailable: home directory not found")?
+        .join(DATA_DIR);
+    probe_dir(&dir, timeout).map(|_| dir)
+}
+
+/// 解析数据目录 (account::get_data_dir 的实现)
+pub fn resolve() -> Result<PathBuf, String> {
+    // 环境变量每次读取 (测试中会切换)，不进入缓存
+    if let Ok(env_path) = std::env::var("ABV_DATA_DIR") {
+        if !env_path.trim().is_empty() {
+            let data_dir = PathBuf::from(env_path);
+            if !data_dir.exists() {
+                fs::create_dir_all(&data_dir)
+                    .map_err(|e| format!("failed_to_create_custom_data_dir: {}", e))?;
+            }
+            return Ok(data_dir);
+        }
+    }
+
+    let mut resolved = RESOLVED.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(result) = resolved.as_ref() {
+        return result.clone();
+    }
+    let result = resolve_in(
+        read_fallback(fallback_records(), PROBE_TIMEOUT),
+        dirs::home_dir(),
+        PROBE_TIMEOUT,
+    );
+    if let Err(e) = &result {
+        tracing::error!("[DataDir] {}", e);
+    }
+    *resolved = Some(result.clone());
+    result
+}
+
+/// 删除记录文件 (清除全部数据时调用)，下次启动回到默认位置；返回删除的记录文件
+pub fn forget_location() -> Vec<PathBuf> {
+    fallback_records()
+        .into_iter()
+        .filter(|record| record.is_file() && fs::remove_file(record).is_ok())
+        .collect()
+}
+
+/// 使用本地目录作为数据目录 (不存在时创建)，并记录下来供以后启动使用
+pub fn set_fallback(dir: PathBuf) -> Result<(), String> {
+    probe_dir(&dir, PROBE_TIMEOUT)?;
+    let content = dir.to_string_lossy().to_string();
+    let saved = with_timeout(PROBE_TIMEOUT, move || {
+        fallback_records().into_iter().any(|record| {
+            if let Some(parent) = record.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+            fs::write(&record, &content).is_ok()
+        })
+    })
+    .unwrap_or(false);
+    if !saved {
+        return Err("failed_to_save_data_dir_choice".to_string());
+    }
+    tracing::info!("[DataDir] Using local data directory {}", dir.display());
+    *RESOLVED.lock().unwrap_or_else(|e| e.into_inner()) = Some(Ok(dir));
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MoveReport {
+    pub from: String,
+    pub to: String,
+    pub files: usize,
+    pub bytes: u64,
+}
+
+/// SQLite 文件头
+const SQLITE_MAGIC: &[u8; 16] = b"SQLite format 3\0";
+/// 数据库旁的 WAL / 共享内存 / 回滚日志文件，内容已并入快照，不单独复制
+const SQLITE_SIDE_FILES: &[&str] = &["-wal", "-shm", "-journal"];
+
+#[derive(Debug)]
+struct CopiedFile {
+    rel: PathBuf,
+    sqlite: bool,
+}
+
+fn is_sqlite(path: &Path) -> bool {
+    let mut header = [0u8; 16];
+    fs::File::open(path)
+        .and_then(|mut f| std::io::Read::read_exact(&mut f, &mut header))
+        .is_ok()
+        && &header == SQLITE_MAGIC
+}
+
+/// 属于某个数据库的 -wal / -shm / -journal 文件
+fn is_sqlite_side_file(dir: &Path, name: &str) -> bool {
+    SQLITE_SIDE_FILES.iter().any(|suffix| {
+        name.strip_suffix(suffix)
+            .is_some_and(|db| is_sqlite(&dir.join(db)))
+    })
+}
+
+/// 用 VACUUM INTO 生成一致的快照 (包含尚未 checkpoint 的 WAL 内容，即使仍有其他连接在写)
+fn copy_sqlite(src: &Path, dst: &Path) -> Result<u64, String> {
+    let conn = rusqlite::Connection::open_with_flags(
+        src,
+        rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY | rusqlite::OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .map_err(|e| e.to_string())?;
+    conn.busy_timeout(std::time::Duration::from_secs(5))
+        .map_err(|e| e.to_string())?;
+    conn.execute("VACUUM INTO ?1", [dst.to_string_lossy().as_ref()])
+        .map_err(|e| e.to_string())?;
+    fs::metadata(dst)
+        .map(|m| m.len())
+        .map_err(|e| e.to_string())
+}
+
+/// 递归复制目录，返回复制的文件 (相对路径) 与总字节数
+fn copy_tree(
+    from: &Path,
+    to: &Path,
+    rel: &Path,
+    files: &mut Vec<CopiedFile>,
+) -> Result<u64, String> {
+    let io = |e: std::io::Error| e.to_string();
+    fs::create_dir_all(to.join(rel)).map_err(io)?;
+    let mut bytes = 0;
+    for entry in fs::read_dir(from.join(rel)).map_err(io)? {
+        let entry = entry.map_err(io)?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let child = rel.join(&name);
+        let file_type = entry.file_type().map_err(io)?;
+        if file_type.is_dir() {
+            bytes += copy_tree(from, to, &child, files)?;
+        } else if file_type.is_file() {
+            if is_sqlite_side_file(&from.join(rel), &name) {
+                continue;
+            }
+            let sqlite = is_sqlite(&from.join(&child));
+            bytes += if sqlite {
+                copy_sqlite(&from.join(&child), &to.join(&child))
+                    .map_err(|e| format!("{}: {}", child.display(), e))?
+            } else {
+                fs::copy(from.join(&child), to.join(&child)).map_err(io)?
+            };
+            files.push(CopiedFile { rel: child, sqlite });
+        }
+    }
+    Ok(bytes)
+}
+
+fn file_digest(path: &Path) -> std::io::Result<Vec<u8>> {
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut fs::File::open(path)?, &mut hasher)?;
+    Ok(hasher.finalize().to_vec())
+}
+
+/// 数据库快照与原文件字节不同，校验副本本身的完整性
+fn sqlite_intact(path: &Path) -> bool {
+    rusqlite::Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .and_then(|conn| conn.query_row("PRAGMA quick_check", [], |row| row.get::<_, String>(0)))
+        .is_ok_and(|result| result == "ok")
+}
+
+/// 复制并校验；失败时删除已复制的内容 (原目录保持不变)
+fn move_in(from: &Path, to: &Path) -> Result<MoveReport, String> {
+    if !to.is_absolute() {
+        return Err("data_dir_target_invalid: path must be absolute".to_string());
+    }
+    if to == from || to.starts_with(from) {
+        return Err(
+            "data_dir_target_invalid: target is inside the current data directory".to_string(),
+        );
+    }
+    let created = !to.exists();
+    if !created
+        && fs::read_dir(to)
+            .map_err(|e| format!("data_dir_target_invalid: {}", e))?
+            .next()
+            .is_some()
+    {
+        return Err(format!("data_dir_target_not_empty: {}", to.display()));
+    }
+
+    let cleanup = |e: String| {
+        if created {
+            let _ = fs::remove_dir_all(to);
+        } else if let Ok(entries) = fs::read_dir(to) {
+            for entry in entries.flatten() {
+                let _ = fs::remove_dir_all(entry.path()).or_else(|_| fs::remove_file(entry.path()));
+            }
+        }
+        e
+    };
+
+    let mut files = Vec::new();
+    let bytes = copy_tree(from, to, Path::new(""), &mut files)
+        .map_err(|e| cleanup(format!("data_dir_copy_failed: {}", e)))?;
+    for file in &files {
+        // 文本日志由文件日志器持续追加，迁移后才切换到新目录，不做校验
+        if file.rel.starts_with("logs") {
+            continue;
+        }
+        let same = if file.sqlite {
+            sqlite_intact(&to.join(&file.rel))
+        } else {
+            match (
+                file_digest(&from.join(&file.rel)),
+                file_digest(&to.join(&file.rel)),
+            ) {
+                (Ok(a), Ok(b)) => a == b,
+                _ => false,
+            }
+        };
+        if !same {
+            return Err(cleanup(format!(
+                "data_dir_verify_failed: {}",
+                file.rel.display()
+            )));
+        }
+    }
+    Ok(MoveReport {
+        from: from.display().to_string(),
+        to: to.display().to_string(),
+        files: files.len(),
+        bytes,
+    })
+}
+
+/// 把数据目录迁移到 new_dir 并切换过去 (原目录保留作备份)。
+/// 调用方需先停止反代；日志库在复制前写完缓冲并关闭，复制后在新位置重新打开，
+/// 文本日志随后写入新目录。反代的账号池需由调用方切换 (TokenManager::set_data_dir)。
+pub fn move_to(new_dir: PathBuf) -> Result<MoveReport, String> {
+    if std::env::var("ABV_DATA_DIR").is_ok_and(|v| !v.trim().is_empty()) {
+        return Err(
+            "data_dir_env_override: unset ABV_DATA_DIR to choose the data directory here"
+                .to_string(),
+        );
+    }
+    let from = resolve()?;
+    // 复制期间阻止账号写入
+    let _lock = crate::modules::account::ACCOUNT_INDEX_LOCK
+        .lock()
+        .map_err(|e| format!("failed_to_acquire_lock: {}", e))?;
+    crate::modules::log_store::suspend()?;
+    let result = move_in(&from, &new_dir).and_then(|report| set_fallback(new_dir).map(|_| report));
+    // 成功时在新目录、失败时在原目录重新打开
+    crate::modules::log_store::resume();
+    let report = result?;
+    crate::modules::logger::reopen_log_dir();
+    tracing::info!(
+        "[DataDir] Moved {} file(s) ({} bytes) from {} to {}",
+        report.files,
+        report.bytes,
+        report.from,
+        report.to
+    );
+    Ok(report)
+}
+
+/// 桌面端: 数据目录不可用时提示选择本地目录，选择后重启应用；取消则以降级模式继续运行
+#[cfg(feature = "desktop")]
+pub fn prompt_fallback(app: tauri::AppHandle, error: String) {
+    use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+    // 阻塞式对话框不能在主线程调用
+    std::thread::spawn(move || loop {
+        let choose = app
+            .dialog()
+            .message(format!(
+                "The data directory could not be accessed:\n{}\n\nChoose a local folder to store accounts and settings? The choice is remembered for future launches.",
+                error
+            ))
+            .title("Antigravity Tools")
+            .kind(MessageDialogKind::Error)
+            .buttons(MessageDialogButtons::OkCancelCustom(
+                "Choose folder".to_string(),
+                "Continue without data".to_string(),
+            ))
+            .blocking_show();
+        if !choose {
+            tracing::warn!("[DataDir] No local data directory chosen, running in degraded mode");
+            return;
+        }
+        let Some(dir) = app
+            .dialog()
+            .file()
+            .blocking_pick_folder()
+            .and_then(|p| p.into_path().ok())
+        else {
+            continue;
+        };
+        // 数据放在所选文件夹下的 .antigravity_tools 子目录，不与文件夹中已有的内容混在一起
+        // (清除全部数据时整个数据目录会被删除)
+        match set_fallback(dir.join(DATA_DIR)) {
+            Ok(()) => app.restart(),
+            Err(e) => tracing::error!("[DataDir] {}", e),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_with_fallback_and_timeout() {
+        let root = std::env::temp_dir().join(format!("data_dir_test_{}", uuid::Uuid::new_v4()));
+        let timeout = Duration::from_secs(5);
+
+        let home = resolve_in(None, Some(root.clone()), timeout).unwrap();
+        assert_eq!(home, root.join(DATA_DIR));
+
+        // 已选择的本地目录优先于主目录
+        let local = root.join("local");
+        assert_eq!(
+            resolve_in(Some(local.clone()), Some(root.clone()), timeout).unwrap(),
+            local
+        );
+
+        // 路径被文件占用时报统一错误
+        let blocked = root.join("file");
+        fs::write(&blocked, b"x").unwrap();
+        let err = resolve_in(None, Some(blocked), timeout).unwrap_err();
+        assert!(err.starts_with("data_dir_unavailable:"));
+        assert!(resolve_in(None, None, timeout)
+            .unwrap_err()
+            .starts_with("data_dir_unavailable:"));
+
+        let record = root.join(FALLBACK_FILE);
+        fs::write(&record, format!("{}\n", local.display())).unwrap();
+        assert_eq!(read_fallback(vec![record], timeout), Some(local));
+
+        assert_eq!(
+            with_timeout(Duration::from_millis(50), || std::thread::sleep(
+                Duration::from_secs(2)
+            )),
+            None
+        );
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_move_copies_and_verifies() {
+        let root = std::env::temp_dir().join(format!("data_dir_move_{}", uuid::Uuid::new_v4()));
+        let from = root.join("old");
+        fs::create_dir_all(from.join("accounts")).unwrap();
+        fs::write(from.join("accounts.json"), b"{}").unwrap();
+        fs::write(from.join("accounts").join("a.json"), b"account").unwrap();
+
+        let to = root.join("new");
+        let report = move_in(&from, &to).unwrap();
+        assert_eq!((report.files, report.bytes), (2, 9));
+        assert_eq!(
+            fs::read(to.join("accounts").join("a.json")).unwrap(),
+            b"account"
+        );
+        assert!(from.join("accounts.json").exists());
+
+        // 目标非空、位于原目录内时拒绝
+        assert!(move_in(&from, &to)
+            .unwrap_err()
+            .starts_with("data_dir_target_not_empty"));
+        assert!(move_in(&from, &from.join("sub")).is_err());
+
+        // 仍有连接写入 WAL 的数据库: 以快照复制，-wal / -shm 不单独复制
+        let db_from = root.join("db_old");
+        fs::create_dir_all(&db_from).unwrap();
+        let writer = rusqlite::Connection::open(db_from.join("stats.db")).unwrap();
+        writer
+            .execute_batch(
+                "PRAGMA journal_mode=WAL; CREATE TABLE t (v INTEGER); INSERT INTO t VALUES (1), (2);",
+            )
+            .unwrap();
+        assert!(db_from.join("stats.db-wal").exists());
+        let db_to = root.join("db_new");
+        let report = move_in(&db_from, &db_to).unwrap();
+        assert_eq!(report.files, 1);
+        assert!(!db_to.join("stats.db-wal").exists());
+        let copy = rusqlite::Connection::open(db_to.join("stats.db")).unwrap();
+        let count: i64 = copy
+            .query_row("SELECT COUNT(*) FROM t", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 2);
+        drop(writer);
+        let _ = fs::remove_dir_all(&root);
+    }
+}
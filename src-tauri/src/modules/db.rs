@@ -16,6 +16,10 @@ fn get_antigravity_path() -> Option<PathBuf> {
 
 /// Get Antigravity database path (cross-platform)
 pub fn get_db_path() -> Result<PathBuf, String> {
+    if crate::modules::headless::is_headless() {
+        return Err("state_db_not_available_in_headless_mode".to_string());
+    }
+
     // Prefer path specified by --user-data-dir argument
     if let Some(user_data_dir) = crate::modules::process::get_user_data_dir_from_process() {
         let custom_db_path = user_data_dir.join("User").join("globalStorage").join("state.vscdb");
@@ -57,7 +61,16 @@ pub fn get_db_path() -> Result<PathBuf, String> {
     #[cfg(target_os = "linux")]
     {
         let home = dirs::home_dir().ok_or("Failed to get home directory")?;
-        Ok(home.join(".config/Antigravity/User/globalStorage/state.vscdb"))
+        let standard = home.join(".config/Antigravity/User/globalStorage/state.vscdb");
+        if standard.exists() {
+            return Ok(standard);
+        }
+        // Flatpak / snap installs keep their config inside the sandbox home
+        Ok(crate::modules::process::linux_sandbox_config_dirs()
+            .into_iter()
+            .map(|dir| dir.join("User/globalStorage/state.vscdb"))
+            .find(|p| p.exists())
+            .unwrap_or(standard))
     }
 }
 
@@ -0,0 +1,246 @@
+// abv:// 深度链接
+// 让外部工具 (Raycast / Alfred 脚本、浏览器书签等) 不经过管理 API 直接触发切换与刷新:
+//   abv://switch?email=a@example.com   (或 ?id=<账号 ID>)
+//   abv://switch-back
+//   abv://refresh-quota                (当前账号；也可带 email / id)
+//   abv://show
+// 应用已在运行时链接经 single-instance 转发到现有实例。可在配置 deep_links 中整体关闭或禁止切换；
+// 应用锁锁定时与对应命令一样被拒绝。失败写入通知中心。
+
+use tauri::{Emitter, Manager};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use crate::modules;
+
+pub const SCHEME: &str = "abv";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum AccountRef {
+    Id(String),
+    Email(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DeepLinkAction {
+    Switch(AccountRef),
+    SwitchBack,
+    /// None 表示当前账号
+    RefreshQuota(Option<AccountRef>),
+    Show,
+}
+
+/// 账号 id 只含字母、数字、`-` 与 `_` (UUID)，拒绝 `../` 之类会进入文件路径的值
+fn is_valid_account_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= 64
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn account_ref(url: &url::Url) -> Result<Option<AccountRef>, String> {
+    let mut id = None;
+    let mut email = None;
+    for (key, value) in url.query_pairs() {
+        let value = value.trim().to_string();
+        if value.is_empty() {
+            continue;
+        }
+        match key.as_ref() {
+            "id" => id = Some(value),
+            "email" => email = Some(value),
+            _ => {}
+        }
+    }
+    if let Some(id) = &id {
+        if !is_valid_account_id(id) {
+            return Err(format!("invalid_deep_link: invalid account id {}", id));
+        }
+    }
+    Ok(id.map(AccountRef::Id).or(email.map(AccountRef::Email)))
+}
+
+fn parse(link: &str) -> Result<DeepLinkAction, String> {
+    let url = url::Url::parse(link).map_err(|e| format!("invalid_deep_link: {}", e))?;
+    if url.scheme() != SCHEME {
+        return Err(format!(
+            "invalid_deep_link: unsupported scheme {}",
+            url.scheme()
+        ));
+    }
+    // abv://switch 中动作在 host 位置，abv:switch 中在 path
+    let action = url
+        .host_str()
+        .filter(|h| !h.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| url.path().trim_matches('/').to_string())
+        .to_ascii_lowercase();
+    match action.as_str() {
+        "switch" => account_ref(&url)?
+            .map(DeepLinkAction::Switch)
+            .ok_or_else(|| "invalid_deep_link: switch needs email or id".to_string()),
+        "switch-back" => Ok(DeepLinkAction::SwitchBack),
+        "refresh-quota" => Ok(DeepLinkAction::RefreshQuota(account_ref(&url)?)),
+        "show" => Ok(DeepLinkAction::Show),
+        other => Err(format!("invalid_deep_link: unknown action {}", other)),
+    }
+}
+
+fn resolve_account(account: &AccountRef) -> Result<String, String> {
+    match account {
+        // 只接受索引中已有的账号，链接中的 id 不直接拼进文件路径
+        AccountRef::Id(id) => modules::account::load_account_index()?
+            .accounts
+            .into_iter()
+            .find(|s| s.id == *id)
+            .map(|s| s.id)
+            .ok_or_else(|| format!("account_not_found: {}", id)),
+        AccountRef::Email(email) => modules::account::find_account_id_by_email(email)
+            .ok_or_else(|| format!("account_not_found: {}", email)),
+    }
+}
+
+fn show_main_window(app: &tauri::AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+        #[cfg(target_os = "macos")]
+        app.set_activation_policy(tauri::ActivationPolicy::Regular)
+            .unwrap_or(());
+    }
+}
+
+/// 切换后同步托盘、前端与反代 (与 switch_account 命令一致)
+async fn after_switch(app: &tauri::AppHandle, account_id: String) {
+    modules::tray::update_tray_menus(app);
+    let _ = app.emit("tray://account-switched", account_id);
+    let state = app.state::<crate::commands::proxy::ProxyServiceState>();
+    let _ = crate::commands::proxy::reload_proxy_accounts(state).await;
+}
+
+async fn run(app: &tauri::AppHandle, link: &str) -> Result<(), String> {
+    let cfg = modules::config::load_app_config()?.deep_links;
+    if !cfg.enabled {
+        return Err("deep_links_disabled".to_string());
+    }
+    let action = parse(link)?;
+    let service = modules::account_service::AccountService::new(
+        modules::integration::SystemManager::Desktop(app.clone()),
+    );
+    match action {
+        DeepLinkAction::Show => show_main_window(app),
+        DeepLinkAction::Switch(account) => {
+            if !cfg.allow_switch {
+                return Err("deep_link_switch_disabled".to_string());
+            }
+            modules::app_lock::check_external("switch_account")?;
+            let account_id = resolve_account(&account)?;
+            service.switch_account(&account_id).await?;
+            after_switch(app, account_id).await;
+        }
+        DeepLinkAction::SwitchBack => {
+            if !cfg.allow_switch {
+                return Err("deep_link_switch_disabled".to_string());
+            }
+            modules::app_lock::check_external("switch_back")?;
+            let account_id = service.switch_back().await?;
+            after_switch(app, account_id).await;
+        }
+        DeepLinkAction::RefreshQuota(account) => {
+            modules::app_lock::check_external("fetch_account_quota")?;
+            let account_id = match account {
+                Some(account) => resolve_account(&account)?,
+                None => modules::get_current_account_id()?
+                    .ok_or_else(|| "no_current_account".to_string())?,
+            };
+            let mut account = modules::load_account(&account_id)?;
+            let quota = modules::account::fetch_quota_with_retry(&mut account)
+                .await
+                .map_err(|e| e.to_string())?;
+            modules::update_account_quota(&account_id, quota)?;
+            let state = app.state::<crate::commands::proxy::ProxyServiceState>();
+            if let Some(instance) = state.instance.read().await.as_ref() {
+                let _ = instance.token_manager.reload_account(&account_id).await;
+            }
+            modules::tray::update_tray_menus(app);
+            modules::events::accounts_refreshed();
+        }
+    }
+    Ok(())
+}
+
+fn dispatch(app: &tauri::AppHandle, link: String) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match run(&app, &link).await {
+            Ok(()) => tracing::info!("[DeepLink] Handled {}", link),
+            Err(e) => {
+                tracing::warn!("[DeepLink] {} failed: {}", link, e);
+                modules::notify::push(
+                    modules::notify::Severity::Warning,
+                    "deep_link",
+                    "Link could not be handled",
+                    format!("{}: {}", link, e),
+                    Some("deep_link"),
+                );
+            }
+        }
+    });
+}
+
+/// 注册 URL scheme 并处理启动链接与运行期间收到的链接 (setup 中调用)
+pub fn init(app: &tauri::AppHandle) {
+    // Windows / Linux 在运行时写入 scheme 关联 (macOS 由安装包的 Info.plist 声明)
+    #[cfg(any(windows, target_os = "linux"))]
+    if let Err(e) = app.deep_link().register_all() {
+        tracing::warn!("[DeepLink] Failed to register {}:// scheme: {}", SCHEME, e);
+    }
+
+    if let Ok(Some(urls)) = app.deep_link().get_current() {
+        for url in urls {
+            dispatch(app, url.to_string());
+        }
+    }
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            dispatch(&handle, url.to_string());
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_deep_links() {
+        assert_eq!(
+            parse("abv://switch?email=a%40x.com").unwrap(),
+            DeepLinkAction::Switch(AccountRef::Email("a@x.com".to_string()))
+        );
+        // id 优先于 email
+        assert_eq!(
+            parse("abv://switch?email=a@x.com&id=acc-1").unwrap(),
+            DeepLinkAction::Switch(AccountRef::Id("acc-1".to_string()))
+        );
+        assert!(parse("abv://switch").is_err());
+        assert!(parse("abv://switch?id=..%2F..%2Fgui_config").is_err());
+        assert!(parse("abv://refresh-quota?id=a/b").is_err());
+        assert_eq!(
+            parse("abv://refresh-quota").unwrap(),
+            DeepLinkAction::RefreshQuota(None)
+        );
+        assert_eq!(
+            parse("abv:refresh-quota?id=acc-2").unwrap(),
+            DeepLinkAction::RefreshQuota(Some(AccountRef::Id("acc-2".to_string())))
+        );
+        assert_eq!(
+            parse("ABV://Switch-Back/").unwrap(),
+            DeepLinkAction::SwitchBack
+        );
+        assert_eq!(parse("abv://show").unwrap(), DeepLinkAction::Show);
+        assert!(parse("abv://delete?id=x").is_err());
+        assert!(parse("https://switch?email=a@x.com").is_err());
+    }
+}
@@ -1,6 +1,8 @@
-use crate::models::DeviceProfile;
+use crate::models::{DeviceProfile, DeviceSimulationConfig};
 use crate::modules::{logger, process};
 use chrono::Local;
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
 use rand::{distributions::Alphanumeric, Rng};
 use rusqlite::Connection;
 use serde_json::Value;
@@ -8,20 +10,20 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
-const DATA_DIR: &str = ".antigravity_tools";
 const GLOBAL_BASELINE: &str = "device_original.json";
 
+/// Same data directory as accounts (honours ABV_DATA_DIR)
 fn get_data_dir() -> Result<PathBuf, String> {
-    let home = dirs::home_dir().ok_or("failed_to_get_home_dir")?;
-    let data_dir = home.join(DATA_DIR);
-    if !data_dir.exists() {
-        fs::create_dir_all(&data_dir).map_err(|e| format!("failed_to_create_data_dir: {}", e))?;
-    }
-    Ok(data_dir)
+    crate::modules::account::get_data_dir()
 }
 
 /// Find storage.json path (prefer custom/portable paths)
 pub fn get_storage_path() -> Result<PathBuf, String> {
+    // No local Antigravity installation in headless / Docker mode
+    if crate::modules::headless::is_headless() {
+        return Err("storage_not_available_in_headless_mode".to_string());
+    }
+
     // 1) --user-data-dir flag
     if let Some(user_data_dir) = process::get_user_data_dir_from_process() {
         let path = user_data_dir
@@ -76,6 +78,14 @@ pub fn get_storage_path() -> Result<PathBuf, String> {
         if path.exists() {
             return Ok(path);
         }
+        // Flatpak / snap installs keep their config inside the sandbox home
+        if let Some(path) = process::linux_sandbox_config_dirs()
+            .into_iter()
+            .map(|dir| dir.join("User/globalStorage/storage.json"))
+            .find(|p| p.exists())
+        {
+            return Ok(path);
+        }
     }
 
     Err("storage_json_not_found".to_string())
@@ -141,6 +151,9 @@ pub fn read_profile(storage_path: &Path) -> Result<DeviceProfile, String> {
         mac_machine_id: get_field("macMachineId").ok_or("missing_mac_machine_id")?,
         dev_device_id: get_field("devDeviceId").ok_or("missing_dev_device_id")?,
         sqm_id: get_field("sqmId").ok_or("missing_sqm_id")?,
+        locale: get_field("locale"),
+        timezone: get_field("timezone"),
+        os_version: get_field("osVersion"),
     })
 }
 
@@ -178,6 +191,12 @@ pub fn write_profile(storage_path: &Path, profile: &DeviceProfile) -> Result<(),
             Value::String(profile.dev_device_id.clone()),
         );
         telemetry.insert("sqmId".to_string(), Value::String(profile.sqm_id.clone()));
+        for (key, value) in simulation_fields(profile) {
+            match value {
+                Some(v) => telemetry.insert(key.to_string(), Value::String(v.clone())),
+                None => telemetry.remove(key),
+            };
+        }
     } else {
         return Err("telemetry_not_object".to_string());
     }
@@ -200,6 +219,13 @@ pub fn write_profile(storage_path: &Path, profile: &DeviceProfile) -> Result<(),
             "telemetry.sqmId".to_string(),
             Value::String(profile.sqm_id.clone()),
         );
+        for (key, value) in simulation_fields(profile) {
+            let flat_key = format!("telemetry.{key}");
+            match value {
+                Some(v) => map.insert(flat_key, Value::String(v.clone())),
+                None => map.remove(&flat_key),
+            };
+        }
     }
 
     // Sync storage.serviceMachineId (match with devDeviceId), place at root level
@@ -387,13 +413,180 @@ pub fn restore_backup(storage_path: &Path, use_oldest: bool) -> Result<PathBuf,
     Ok(target)
 }
 
+/// Simulated values that also shape upstream request headers
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeviceHeaderHints {
+    pub locale: Option<String>,
+    pub os_version: Option<String>,
+}
+
+// account_id -> header hints (kept in sync by `save_account`, lazily loaded otherwise)
+static HEADER_HINTS: Lazy<DashMap<String, DeviceHeaderHints>> = Lazy::new(DashMap::new);
+
+pub fn cache_header_hints(account_id: &str, profile: Option<&DeviceProfile>) {
+    let hints = profile
+        .map(|p| DeviceHeaderHints {
+            locale: p.locale.clone(),
+            os_version: p.os_version.clone(),
+        })
+        .unwrap_or_default();
+    HEADER_HINTS.insert(account_id.to_string(), hints);
+}
+
+/// Header hints of an account's bound device profile
+pub fn header_hints_for_account(account_id: &str) -> DeviceHeaderHints {
+    if let Some(hints) = HEADER_HINTS.get(account_id) {
+        return hints.clone();
+    }
+    let profile = crate::modules::account::load_account(account_id)
+        .ok()
+        .and_then(|a| a.device_profile);
+    cache_header_hints(account_id, profile.as_ref());
+    HEADER_HINTS
+        .get(account_id)
+        .map(|h| h.clone())
+        .unwrap_or_default()
+}
+
+/// Locale / timezone / OS version keys as stored under `telemetry` in storage.json
+fn simulation_fields(profile: &DeviceProfile) -> [(&'static str, &Option<String>); 3] {
+    [
+        ("locale", &profile.locale),
+        ("timezone", &profile.timezone),
+        ("osVersion", &profile.os_version),
+    ]
+}
+
 /// Generate a new set of device fingerprints (Cursor/VSCode style)
 pub fn generate_profile() -> DeviceProfile {
-    DeviceProfile {
+    let simulation = crate::modules::config::load_app_config()
+        .map(|cfg| cfg.device_simulation)
+        .unwrap_or_default();
+    generate_profile_with(&simulation)
+}
+
+/// Generate fingerprints, drawing locale / timezone / OS version from the weighted pools
+pub fn generate_profile_with(simulation: &DeviceSimulationConfig) -> DeviceProfile {
+    let mut rng = rand::thread_rng();
+    let mut profile = DeviceProfile {
         machine_id: format!("auth0|user_{}", random_hex(32)),
         mac_machine_id: new_standard_machine_id(),
         dev_device_id: Uuid::new_v4().to_string(),
         sqm_id: format!("{{{}}}", Uuid::new_v4().to_string().to_uppercase()),
+        locale: None,
+        timezone: None,
+        os_version: None,
+    };
+
+    if simulation.enabled {
+        // Locale and timezone come from the same region so they stay plausible together
+        if let Some(region) = pick_weighted(&mut rng, &simulation.regions, |r| r.weight) {
+            profile.locale = pick_uniform(&mut rng, &region.locales);
+            profile.timezone = pick_uniform(&mut rng, &region.timezones);
+        }
+        profile.os_version = pick_weighted(&mut rng, &simulation.os_versions, |o| o.weight)
+            .map(|o| o.value.clone());
+    }
+    profile
+}
+
+fn pick_weighted<'a, T>(rng: &mut impl Rng, items: &'a [T], weight: impl Fn(&T) -> u32) -> Option<&'a T> {
+    let total: u64 = items.iter().map(|i| weight(i) as u64).sum();
+    if total == 0 {
+        return None;
+    }
+    let mut roll = rng.gen_range(0..total);
+    for item in items {
+        let w = weight(item) as u64;
+        if roll < w {
+            return Some(item);
+        }
+        roll -= w;
+    }
+    None
+}
+
+fn pick_uniform(rng: &mut impl Rng, items: &[String]) -> Option<String> {
+    if items.is_empty() {
+        return None;
+    }
+    Some(items[rng.gen_range(0..items.len())].clone())
+}
+
+/// `Accept-Language` value for a simulated locale, e.g. "de-DE" -> "de-DE,de;q=0.9,en;q=0.8"
+pub fn accept_language_for(locale: &str) -> String {
+    let lang = locale.split('-').next().unwrap_or(locale);
+    if lang.eq_ignore_ascii_case("en") {
+        format!("{},en;q=0.9", locale)
+    } else {
+        format!("{},{};q=0.9,en;q=0.8", locale, lang)
+    }
+}
+
+/// Replace the platform token of a User-Agent ("... (platform) ...") with the simulated OS version
+pub fn apply_os_version_to_user_agent(user_agent: &str, os_version: &str) -> String {
+    match (user_agent.find('('), user_agent.find(')')) {
+        (Some(start), Some(end)) if start < end => {
+            format!("{}({}){}", &user_agent[..start], os_version, &user_agent[end + 1..])
+        }
+        _ => user_agent.to_string(),
+    }
+}
+
+/// Client platform reported in `loadCodeAssist` metadata for a User-Agent platform segment
+pub fn client_platform_for(platform_segment: &str) -> &'static str {
+    let segment = platform_segment.to_ascii_lowercase();
+    let arm = segment.contains("arm64") || segment.contains("aarch64");
+    let os = if segment.contains("mac") {
+        "darwin"
+    } else if segment.contains("windows") {
+        "windows"
+    } else if segment.contains("linux") || segment.contains("x11") {
+        "linux"
+    } else {
+        return "PLATFORM_UNSPECIFIED";
+    };
+    match (os, arm) {
+        ("darwin", true) => "DARWIN_ARM64",
+        ("darwin", false) => "DARWIN_AMD64",
+        ("windows", true) => "WINDOWS_ARM64",
+        ("windows", false) => "WINDOWS_AMD64",
+        (_, true) => "LINUX_ARM64",
+        (_, false) => "LINUX_AMD64",
+    }
+}
+
+/// Identity presented by background requests (quota polling / project lookup) for an account
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountIdentity {
+    pub user_agent: String,
+    pub platform: &'static str,
+    pub accept_language: Option<String>,
+}
+
+fn identity_from_hints(hints: &DeviceHeaderHints, base_user_agent: &str) -> AccountIdentity {
+    let platform_segment = hints
+        .os_version
+        .as_deref()
+        .unwrap_or(crate::constants::platform_info());
+    AccountIdentity {
+        user_agent: apply_os_version_to_user_agent(base_user_agent, platform_segment),
+        platform: client_platform_for(platform_segment),
+        accept_language: hints.locale.as_deref().map(accept_language_for),
+    }
+}
+
+/// Same User-Agent / platform / language the proxy uses for this account, so background
+/// polling matches the account's bound fingerprint. Without an account the shared
+/// native User-Agent is used.
+pub fn identity_for_account(account_id: Option<&str>) -> AccountIdentity {
+    match account_id {
+        Some(id) => identity_from_hints(&header_hints_for_account(id), &crate::constants::user_agent()),
+        None => AccountIdentity {
+            user_agent: crate::constants::native_oauth_user_agent(),
+            platform: client_platform_for(crate::constants::platform_info()),
+            accept_language: None,
+        },
     }
 }
 
@@ -421,3 +614,57 @@ fn new_standard_machine_id() -> String {
     }
     id
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_profile_with_simulation() {
+        let profile = generate_profile_with(&DeviceSimulationConfig::default());
+        assert!(profile.locale.is_some());
+        assert!(profile.timezone.is_some());
+        assert!(profile.os_version.is_some());
+
+        let disabled = DeviceSimulationConfig {
+            enabled: false,
+            ..Default::default()
+        };
+        let profile = generate_profile_with(&disabled);
+        assert!(profile.locale.is_none() && profile.os_version.is_none());
+    }
+
+    #[test]
+    fn test_simulated_header_values() {
+        assert_eq!(accept_language_for("de-DE"), "de-DE,de;q=0.9,en;q=0.8");
+        assert_eq!(accept_language_for("en-GB"), "en-GB,en;q=0.9");
+        assert_eq!(
+            apply_os_version_to_user_agent(
+                "Antigravity/1.0 (Macintosh; Intel Mac OS X 10_15_7) Chrome/132 Electron/39",
+                "Windows NT 10.0; Win64; x64"
+            ),
+            "Antigravity/1.0 (Windows NT 10.0; Win64; x64) Chrome/132 Electron/39"
+        );
+        assert_eq!(apply_os_version_to_user_agent("antigravity", "X11"), "antigravity");
+    }
+
+    #[test]
+    fn test_identity_follows_bound_profile() {
+        let base = "Antigravity/1.0 (X11; Linux x86_64) Chrome/132 Electron/39";
+        let hints = DeviceHeaderHints {
+            locale: Some("de-DE".to_string()),
+            os_version: Some("Windows NT 10.0; Win64; x64".to_string()),
+        };
+        let identity = identity_from_hints(&hints, base);
+        assert_eq!(
+            identity.user_agent,
+            "Antigravity/1.0 (Windows NT 10.0; Win64; x64) Chrome/132 Electron/39"
+        );
+        assert_eq!(identity.platform, "WINDOWS_AMD64");
+        assert_eq!(identity.accept_language.as_deref(), Some("de-DE,de;q=0.9,en;q=0.8"));
+
+        assert_eq!(client_platform_for("Macintosh; Intel Mac OS X 10_15_7"), "DARWIN_AMD64");
+        assert_eq!(client_platform_for("X11; Linux aarch64"), "LINUX_ARM64");
+        assert_eq!(client_platform_for("Plan 9"), "PLATFORM_UNSPECIFIED");
+    }
+}
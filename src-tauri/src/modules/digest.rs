@@ -0,0 +1,384 @@
+// 问题账号日报
+// 每天在 digest.hour 汇总需要关注的账号: 已禁用、403 禁止访问、配额低于阈值、
+// 近 24 小时内反复 401、设备指纹冲突 (多个账号共用同一 machine_id / devDeviceId)。
+// 投递渠道: 系统通知 (日志)、前端事件 digest://problem-accounts，以及可选的 webhook (JSON POST，
+// text/content 字段可直接用于 Slack / Discord 等)。
+
+use chrono::Timelike;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use crate::models::{Account, DigestConfig};
+use crate::modules::{account, config, logger, scheduler, token_stats};
+
+const CHECK_INTERVAL_SECS: u64 = 600;
+const JOB_ID: &str = "digest";
+const UNAUTHORIZED_WINDOW_HOURS: i64 = 24;
+const WEBHOOK_TIMEOUT_SECS: u64 = 15;
+
+static DIGEST_STARTED: AtomicBool = AtomicBool::new(false);
+/// 最近一次发送日报的本地日期，避免同一小时内重复发送
+static LAST_DIGEST_RUN: Mutex<Option<chrono::NaiveDate>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestAccount {
+    pub account_id: String,
+    pub email: String,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FingerprintCollision {
+    /// machine_id / dev_device_id
+    pub field: String,
+    pub value: String,
+    pub emails: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProblemDigest {
+    pub generated_at: i64,
+    pub total_accounts: usize,
+    pub disabled: Vec<DigestAccount>,
+    pub forbidden: Vec<DigestAccount>,
+    pub low_quota: Vec<DigestAccount>,
+    pub repeated_unauthorized: Vec<DigestAccount>,
+    pub fingerprint_collisions: Vec<FingerprintCollision>,
+}
+
+impl ProblemDigest {
+    pub fn is_empty(&self) -> bool {
+        self.disabled.is_empty()
+            && self.forbidden.is_empty()
+            && self.low_quota.is_empty()
+            && self.repeated_unauthorized.is_empty()
+            && self.fingerprint_collisions.is_empty()
+    }
+
+    /// 纯文本摘要 (通知与 webhook 共用)
+    pub fn to_text(&self) -> String {
+        if self.is_empty() {
+            return format!(
+                "Account digest: all {} account(s) look healthy",
+                self.total_accounts
+            );
+        }
+        let mut lines = vec![format!(
+            "Account digest: {} account(s), issues found",
+            self.total_accounts
+        )];
+        let sections = [
+            ("Disabled", &self.disabled),
+            ("Forbidden (403)", &self.forbidden),
+            ("Low quota", &self.low_quota),
+            ("Repeated 401s", &self.repeated_unauthorized),
+        ];
+        for (title, entries) in sections {
+            if entries.is_empty() {
+                continue;
+            }
+            lines.push(format!("{} ({}):", title, entries.len()));
+            lines.extend(
+                entries
+                    .iter()
+                    .map(|e| format!("  - {}: {}", e.email, e.detail)),
+            );
+        }
+        if !self.fingerprint_collisions.is_empty() {
+            lines.push(format!(
+                "Fingerprint collisions ({}):",
+                self.fingerprint_collisions.len()
+            ));
+            lines.extend(
+                self.fingerprint_collisions
+                    .iter()
+                    .map(|c| format!("  - {} {}: {}", c.field, c.value, c.emails.join(", "))),
+            );
+        }
+        lines.join("\n")
+    }
+}
+
+fn entry(acc: &Account, detail: String) -> DigestAccount {
+    DigestAccount {
+        account_id: acc.id.clone(),
+        email: acc.email.clone(),
+        detail,
+    }
+}
+
+/// 读取设备指纹中的某个字段
+type ProfileField = fn(&crate::models::DeviceProfile) -> &str;
+
+/// 共用同一指纹字段的账号分组 (空值忽略)
+fn find_collisions(accounts: &[Account]) -> Vec<FingerprintCollision> {
+    let fields: [(&str, ProfileField); 2] = [
+        ("machine_id", |p| p.machine_id.as_str()),
+        ("dev_device_id", |p| p.dev_device_id.as_str()),
+    ];
+    let mut collisions = Vec::new();
+    for (field, get) in fields {
+        let mut groups: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for acc in accounts {
+            if let Some(value) = acc
+                .device_profile
+                .as_ref()
+                .map(get)
+                .filter(|v| !v.is_empty())
+            {
+                groups.entry(value).or_default().push(acc.email.clone());
+            }
+        }
+        collisions.extend(
+            groups
+                .into_iter()
+                .filter(|(_, emails)| emails.len() > 1)
+                .map(|(value, emails)| FingerprintCollision {
+                    field: field.to_string(),
+                    value: value.to_string(),
+                    emails,
+                }),
+        );
+    }
+    collisions
+}
+
+fn build(
+    accounts: &[Account],
+    unauthorized: &HashMap<String, u64>,
+    cfg: &DigestConfig,
+) -> ProblemDigest {
+    let mut digest = ProblemDigest {
+        generated_at: chrono::Utc::now().timestamp(),
+        total_accounts: accounts.len(),
+        disabled: Vec::new(),
+        forbidden: Vec::new(),
+        low_quota: Vec::new(),
+        repeated_unauthorized: Vec::new(),
+        fingerprint_collisions: find_collisions(accounts),
+    };
+
+    for acc in accounts {
+        if acc.disabled {
+            let reason = acc.disabled_reason.as_deref().unwrap_or("disabled");
+            digest.disabled.push(entry(acc, reason.to_string()));
+        } else if acc.proxy_disabled {
+            let reason = acc.proxy_disabled_reason.as_deref().unwrap_or("manual");
+            digest
+                .disabled
+                .push(entry(acc, format!("proxy disabled: {}", reason)));
+        }
+
+        if let Some(quota) = acc.quota.as_ref() {
+            if quota.is_forbidden {
+                let reason = quota.forbidden_reason.as_deref().unwrap_or("403 Forbidden");
+                digest.forbidden.push(entry(acc, reason.to_string()));
+            } else if !acc.disabled {
+                // 按剩余最少的模型判断
+                if let Some(lowest) = quota
+                    .models
+                    .iter()
+                    .min_by_key(|m| m.percentage)
+                    .filter(|m| m.percentage < cfg.quota_threshold)
+                {
+                    digest.low_quota.push(entry(
+                        acc,
+                        format!("{} at {}%", lowest.name, lowest.percentage),
+                    ));
+                }
+            }
+        }
+
+        let count = unauthorized.get(&acc.email).copied().unwrap_or(0);
+        if cfg.unauthorized_threshold > 0 && count >= cfg.unauthorized_threshold {
+            digest.repeated_unauthorized.push(entry(
+                acc,
+                format!("{} x 401 in the last {}h", count, UNAUTHORIZED_WINDOW_HOURS),
+            ));
+        }
+    }
+    digest
+}
+
+/// 汇总当前需要关注的账号
+pub fn build_digest() -> Result<ProblemDigest, String> {
+    let cfg = config::load_app_config()?.digest;
+    let accounts = account::list_accounts()?;
+    let unauthorized = token_stats::count_status_by_account(401, UNAUTHORIZED_WINDOW_HOURS)
+        .unwrap_or_else(|e| {
+            logger::log_warn(&format!("[Digest] Failed to read 401 counts: {}", e));
+            HashMap::new()
+        });
+    Ok(build(&accounts, &unauthorized, &cfg))
+}
+
+async fn post_webhook(url: &str, digest: &ProblemDigest) -> Result<(), String> {
+    let text = digest.to_text();
+    let client = crate::modules::http::reqwest_client_builder()
+        .timeout(std::time::Duration::from_secs(WEBHOOK_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| e.to_string())?;
+    client
+        .post(url)
+        .json(&serde_json::json!({
+            "text": text,
+            "content": text,
+            "digest": digest,
+        }))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .error_for_status()
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 生成并投递日报；手动触发时即使没有问题账号也会投递
+pub async fn send_digest(force: bool) -> Result<ProblemDigest, String> {
+    let cfg = config::load_app_config()?.digest;
+    let digest = tokio::task::spawn_blocking(build_digest)
+        .await
+        .map_err(|e| e.to_string())??;
+    if digest.is_empty() && !force && !cfg.send_when_empty {
+        logger::log_info("[Digest] No accounts need attention");
+        return Ok(digest);
+    }
+
+    crate::modules::integration::SystemManager::Headless
+        .show_notification("Account digest", &digest.to_text());
+    crate::modules::log_bridge::emit_event("digest://problem-accounts", digest.clone());
+    crate::modules::notify::push(
+        crate::modules::notify::Severity::Info,
+        "digest",
+        "Account digest",
+        digest.to_text(),
+        Some("digest"),
+    );
+
+    let url = cfg.webhook_url.trim();
+    if !url.is_empty() {
+        post_webhook(url, &digest)
+            .await
+            .map_err(|e| format!("digest_webhook_failed: {}", e))?;
+        logger::log_info("[Digest] Delivered to webhook");
+    }
+    Ok(digest)
+}
+
+/// 启动每日日报任务 (每个进程一次)
+pub fn start_digest_scheduler() {
+    if DIGEST_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    scheduler::register_job(JOB_ID, CHECK_INTERVAL_SECS);
+    crate::utils::runtime::spawn(async {
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_secs(CHECK_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            if !scheduler::job_tick(JOB_ID) {
+                continue;
+            }
+
+            let Ok(app_config) = config::load_app_config() else {
+                continue;
+            };
+            if !app_config.digest.enabled {
+                continue;
+            }
+            let now = chrono::Local::now();
+            if now.hour() != u32::from(app_config.digest.hour) {
+                continue;
+            }
+            {
+                let mut last = LAST_DIGEST_RUN.lock().unwrap_or_else(|e| e.into_inner());
+                if *last == Some(now.date_naive()) {
+                    continue;
+                }
+                *last = Some(now.date_naive());
+            }
+
+            let started = std::time::Instant::now();
+            let result = send_digest(false).await;
+            if let Err(e) = &result {
+                logger::log_warn(&format!("[Digest] Daily digest failed: {}", e));
+            }
+            scheduler::record_job_run(
+                JOB_ID,
+                started,
+                result.map(|d| format!("{} account(s) checked", d.total_accounts)),
+            );
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{DeviceProfile, QuotaData, TokenData};
+
+    fn account(email: &str, machine_id: &str, percentage: i32) -> Account {
+        let token = TokenData::new(
+            "access".to_string(),
+            "refresh".to_string(),
+            3600,
+            Some(email.to_string()),
+            None,
+            None,
+        );
+        let mut acc = Account::new(email.to_string(), email.to_string(), token);
+        acc.device_profile = Some(DeviceProfile {
+            machine_id: machine_id.to_string(),
+            mac_machine_id: String::new(),
+            dev_device_id: format!("dev-{}", email),
+            sqm_id: String::new(),
+            locale: None,
+            timezone: None,
+            os_version: None,
+        });
+        let mut quota = QuotaData::new();
+        quota.add_model(
+            serde_json::from_value(serde_json::json!({
+                "name": "gemini-3-pro-high",
+                "percentage": percentage,
+                "reset_time": ""
+            }))
+            .unwrap(),
+        );
+        acc.quota = Some(quota);
+        acc
+    }
+
+    #[test]
+    fn test_build_digest_sections() {
+        let mut disabled = account("c@example.com", "m-3", 100);
+        disabled.disabled = true;
+        disabled.disabled_reason = Some("invalid_grant".to_string());
+        let accounts = vec![
+            account("a@example.com", "m-1", 5),
+            account("b@example.com", "m-1", 80),
+            disabled,
+        ];
+        let unauthorized = HashMap::from([
+            ("b@example.com".to_string(), 4),
+            ("a@example.com".to_string(), 1),
+        ]);
+        let digest = build(&accounts, &unauthorized, &DigestConfig::default());
+
+        assert_eq!(digest.disabled.len(), 1);
+        assert_eq!(digest.disabled[0].detail, "invalid_grant");
+        assert_eq!(digest.low_quota.len(), 1);
+        assert_eq!(digest.low_quota[0].email, "a@example.com");
+        assert_eq!(digest.repeated_unauthorized.len(), 1);
+        assert_eq!(digest.repeated_unauthorized[0].email, "b@example.com");
+        assert_eq!(digest.fingerprint_collisions.len(), 1);
+        assert_eq!(digest.fingerprint_collisions[0].field, "machine_id");
+        assert_eq!(
+            digest.fingerprint_collisions[0].emails,
+            vec!["a@example.com", "b@example.com"]
+        );
+        assert!(digest.to_text().contains("Fingerprint collisions (1)"));
+    }
+}
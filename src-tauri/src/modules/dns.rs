@@ -0,0 +1,314 @@
+// 上游 DNS 解析
+// 共享 HTTP 客户端与反代上游客户端统一使用此解析器，按以下顺序解析主机名：
+// 1. http_client.dns.hosts 静态映射 (精确主机名或 "*.example.com")
+// 2. http_client.dns.doh_url 配置的 DNS-over-HTTPS (JSON API，如 https://dns.google/resolve)，失败时回退系统解析
+// 3. 系统解析器
+// 结果再按客户端的出站地址族 (ip_family) 过滤。配置在每次解析时读取，保存后立即生效。
+
+use crate::models::{DnsOverrideConfig, IpFamily};
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use std::net::{IpAddr, SocketAddr};
+use std::time::{Duration, Instant};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+const DOH_TIMEOUT_SECS: u64 = 5;
+const DOH_MIN_TTL_SECS: u64 = 30;
+const DOH_MAX_TTL_SECS: u64 = 3600;
+const DOH_CACHE_MAX_ENTRIES: usize = 512;
+const RTYPE_A: u16 = 1;
+const RTYPE_AAAA: u16 = 28;
+
+/// (doh_url, host, rtype)
+type DohCacheKey = (String, String, u16);
+/// (地址, 过期时间)
+type DohCacheEntry = (Vec<IpAddr>, Instant);
+
+static DOH_CACHE: Lazy<DashMap<DohCacheKey, DohCacheEntry>> = Lazy::new(DashMap::new);
+
+/// 校验 DNS 覆盖配置 (保存配置时调用)
+pub fn validate(cfg: &DnsOverrideConfig) -> Result<(), String> {
+    for (host, ips) in &cfg.hosts {
+        if host.trim().is_empty() || ips.is_empty() {
+            return Err(format!(
+                "invalid_dns_config: host mapping '{}' needs at least one address",
+                host
+            ));
+        }
+        for ip in ips {
+            ip.trim().parse::<IpAddr>().map_err(|_| {
+                format!(
+                    "invalid_dns_config: '{}' for {} is not an IP address",
+                    ip, host
+                )
+            })?;
+        }
+    }
+    if let Some(url) = cfg.doh_url.as_deref().filter(|u| !u.trim().is_empty()) {
+        if !url.trim().starts_with("https://") {
+            return Err("invalid_dns_config: doh_url must be an https:// URL".to_string());
+        }
+    }
+    Ok(())
+}
+
+fn static_lookup(cfg: &DnsOverrideConfig, host: &str) -> Option<Vec<IpAddr>> {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    let matches = |pattern: &str| {
+        let pattern = pattern.trim().to_ascii_lowercase();
+        match pattern.strip_prefix("*.") {
+            Some(suffix) => host.ends_with(&format!(".{}", suffix)),
+            None => pattern == host,
+        }
+    };
+    // 精确匹配优先于通配
+    let ips = cfg
+        .hosts
+        .iter()
+        .find(|(pattern, _)| !pattern.starts_with("*.") && matches(pattern))
+        .or_else(|| cfg.hosts.iter().find(|(pattern, _)| matches(pattern)))
+        .map(|(_, ips)| ips)?;
+    Some(
+        ips.iter()
+            .filter_map(|ip| ip.trim().parse::<IpAddr>().ok())
+            .collect(),
+    )
+}
+
+/// 解析 DoH JSON 响应，返回 (地址, 最小 TTL)
+fn parse_doh_answer(body: &serde_json::Value, rtype: u16) -> Result<(Vec<IpAddr>, u64), String> {
+    let status = body.get("Status").and_then(|v| v.as_u64()).unwrap_or(0);
+    if status != 0 {
+        return Err(format!("DoH status {}", status));
+    }
+    let mut ttl = u64::MAX;
+    let ips: Vec<IpAddr> = body
+        .get("Answer")
+        .and_then(|v| v.as_array())
+        .map(|answers| {
+            answers
+                .iter()
+                .filter(|a| a.get("type").and_then(|v| v.as_u64()) == Some(rtype as u64))
+                .filter_map(|a| {
+                    let ip = a.get("data")?.as_str()?.parse::<IpAddr>().ok()?;
+                    ttl = ttl.min(a.get("TTL").and_then(|v| v.as_u64()).unwrap_or(0));
+                    Some(ip)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok((ips, ttl.clamp(DOH_MIN_TTL_SECS, DOH_MAX_TTL_SECS)))
+}
+
+async fn doh_query(
+    cfg: &DnsOverrideConfig,
+    url: &str,
+    host: &str,
+    rtype: u16,
+) -> Result<Vec<IpAddr>, String> {
+    let key = (url.to_string(), host.to_string(), rtype);
+    if let Some(entry) = DOH_CACHE.get(&key) {
+        if entry.1 > Instant::now() {
+            return Ok(entry.0.clone());
+        }
+    }
+
+    let mut builder = reqwest::Client::builder().timeout(Duration::from_secs(DOH_TIMEOUT_SECS));
+    // DoH 服务器自身的地址也可以写在静态映射中，避免依赖失效的系统 DNS
+    if let Some(doh_host) = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+    {
+        if let Some(ips) = static_lookup(cfg, &doh_host).filter(|ips| !ips.is_empty()) {
+            let addrs: Vec<SocketAddr> =
+                ips.into_iter().map(|ip| SocketAddr::new(ip, 443)).collect();
+            builder = builder.resolve_to_addrs(&doh_host, &addrs);
+        }
+    }
+    let client = builder.build().map_err(|e| e.to_string())?;
+    let body: serde_json::Value = client
+        .get(url)
+        .query(&[("name", host), ("type", &rtype.to_string())])
+        .header("Accept", "application/dns-json")
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .error_for_status()
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+    let (ips, ttl) = parse_doh_answer(&body, rtype)?;
+
+    if DOH_CACHE.len() >= DOH_CACHE_MAX_ENTRIES {
+        DOH_CACHE.clear();
+    }
+    DOH_CACHE.insert(
+        key,
+        (ips.clone(), Instant::now() + Duration::from_secs(ttl)),
+    );
+    Ok(ips)
+}
+
+async fn doh_lookup(
+    cfg: &DnsOverrideConfig,
+    url: &str,
+    host: &str,
+    family: IpFamily,
+) -> Result<Vec<IpAddr>, String> {
+    let mut ips = Vec::new();
+    if family != IpFamily::Ipv6 {
+        ips.extend(doh_query(cfg, url, host, RTYPE_A).await?);
+    }
+    if family != IpFamily::Ipv4 {
+        // AAAA 查询失败不影响已拿到的 IPv4 结果
+        match doh_query(cfg, url, host, RTYPE_AAAA).await {
+            Ok(v6) => ips.extend(v6),
+            Err(e) if ips.is_empty() => return Err(e),
+            Err(_) => {}
+        }
+    }
+    Ok(ips)
+}
+
+fn filter_family(addrs: impl Iterator<Item = SocketAddr>, family: IpFamily) -> Vec<SocketAddr> {
+    addrs
+        .filter(|addr| match family {
+            IpFamily::Auto => true,
+            IpFamily::Ipv4 => addr.is_ipv4(),
+            IpFamily::Ipv6 => addr.is_ipv6(),
+        })
+        .collect()
+}
+
+/// 解析上游主机名 (端口由调用方的连接器填充)
+pub async fn resolve(host: &str, family: IpFamily) -> Result<Vec<SocketAddr>, BoxError> {
+    let cfg = crate::modules::http::settings().dns;
+
+    let ips = if let Some(ips) = static_lookup(&cfg, host) {
+        Some(ips)
+    } else if let Some(url) = cfg
+        .doh_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|u| !u.is_empty())
+    {
+        match doh_lookup(&cfg, url, host, family).await {
+            Ok(ips) if !ips.is_empty() => Some(ips),
+            Ok(_) => None,
+            Err(e) => {
+                tracing::warn!(
+                    "[DNS] DoH lookup for {} failed, using system resolver: {}",
+                    host,
+                    e
+                );
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let addrs = match ips {
+        Some(ips) => filter_family(ips.into_iter().map(|ip| SocketAddr::new(ip, 0)), family),
+        None => filter_family(tokio::net::lookup_host((host, 0)).await?, family),
+    };
+    if addrs.is_empty() {
+        return Err(format!("no {:?} address found for {}", family, host).into());
+    }
+    Ok(addrs)
+}
+
+/// 共享客户端使用的 DNS 解析器
+pub struct UpstreamResolver(pub IpFamily);
+
+impl rquest::dns::Resolve for UpstreamResolver {
+    fn resolve(&self, name: rquest::dns::Name) -> rquest::dns::Resolving {
+        let family = self.0;
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let addrs: rquest::dns::Addrs = Box::new(resolve(&host, family).await?.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+impl reqwest::dns::Resolve for UpstreamResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let family = self.0;
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let addrs: reqwest::dns::Addrs = Box::new(resolve(&host, family).await?.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_family() {
+        let addrs: Vec<SocketAddr> = vec![
+            "142.250.0.1:0".parse().unwrap(),
+            "[2607:f8b0::1]:0".parse().unwrap(),
+        ];
+        assert_eq!(
+            filter_family(addrs.clone().into_iter(), IpFamily::Auto).len(),
+            2
+        );
+        assert!(filter_family(addrs.clone().into_iter(), IpFamily::Ipv4)[0].is_ipv4());
+        assert!(filter_family(addrs.into_iter(), IpFamily::Ipv6)[0].is_ipv6());
+    }
+
+    #[test]
+    fn test_static_hosts_and_validation() {
+        let mut cfg = DnsOverrideConfig::default();
+        cfg.hosts.insert(
+            "oauth2.googleapis.com".to_string(),
+            vec!["142.250.0.10".to_string()],
+        );
+        cfg.hosts.insert(
+            "*.googleapis.com".to_string(),
+            vec!["142.250.0.1".to_string()],
+        );
+        assert!(validate(&cfg).is_ok());
+
+        assert_eq!(
+            static_lookup(&cfg, "OAuth2.googleapis.com."),
+            Some(vec!["142.250.0.10".parse().unwrap()])
+        );
+        assert_eq!(
+            static_lookup(&cfg, "cloudcode-pa.googleapis.com"),
+            Some(vec!["142.250.0.1".parse().unwrap()])
+        );
+        assert_eq!(static_lookup(&cfg, "googleapis.com"), None);
+
+        cfg.doh_url = Some("http://dns.example".to_string());
+        assert!(validate(&cfg)
+            .unwrap_err()
+            .starts_with("invalid_dns_config"));
+        cfg.doh_url = None;
+        cfg.hosts
+            .insert("bad.example".to_string(), vec!["not-an-ip".to_string()]);
+        assert!(validate(&cfg).is_err());
+    }
+
+    #[test]
+    fn test_parse_doh_answer() {
+        let body = serde_json::json!({
+            "Status": 0,
+            "Answer": [
+                {"name": "example.com.", "type": 5, "TTL": 300, "data": "alias.example.com."},
+                {"name": "alias.example.com.", "type": 1, "TTL": 120, "data": "93.184.216.34"},
+                {"name": "alias.example.com.", "type": 1, "TTL": 5, "data": "93.184.216.35"}
+            ]
+        });
+        let (ips, ttl) = parse_doh_answer(&body, RTYPE_A).unwrap();
+        assert_eq!(ips.len(), 2);
+        assert_eq!(ttl, DOH_MIN_TTL_SECS);
+        assert!(parse_doh_answer(&serde_json::json!({"Status": 2}), RTYPE_A).is_err());
+    }
+}
@@ -0,0 +1,98 @@
+// 按邮箱域名的账号策略
+// 例如 @corp.com 的账号: 禁止导出 refresh_token、不参与反代轮换、删除前需要确认。
+// 同一邮箱匹配多条策略时取并集。
+// 反代每次选号都会检查，因此策略缓存在内存中，保存配置时通过 update() 刷新。
+
+use once_cell::sync::Lazy;
+use std::sync::RwLock;
+
+use crate::models::DomainPolicy;
+use crate::modules::config;
+
+/// 某个账号生效的策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DomainRules {
+    pub no_export: bool,
+    pub exclude_from_proxy: bool,
+    pub confirm_delete: bool,
+}
+
+static POLICIES: Lazy<RwLock<Option<Vec<DomainPolicy>>>> = Lazy::new(|| RwLock::new(None));
+
+/// 配置保存后刷新缓存
+pub fn update(policies: Vec<DomainPolicy>) {
+    *POLICIES.write().unwrap_or_else(|e| e.into_inner()) = Some(policies);
+}
+
+pub fn current() -> Vec<DomainPolicy> {
+    if let Some(policies) = POLICIES.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        return policies.clone();
+    }
+    let policies = config::load_app_config()
+        .map(|c| c.domain_policies)
+        .unwrap_or_default();
+    update(policies.clone());
+    policies
+}
+
+pub fn rules_in(policies: &[DomainPolicy], email: &str) -> DomainRules {
+    policies
+        .iter()
+        .filter(|p| p.matches(email))
+        .fold(DomainRules::default(), |rules, p| DomainRules {
+            no_export: rules.no_export || p.no_export,
+            exclude_from_proxy: rules.exclude_from_proxy || p.exclude_from_proxy,
+            confirm_delete: rules.confirm_delete || p.confirm_delete,
+        })
+}
+
+pub fn rules_for(email: &str) -> DomainRules {
+    rules_in(&current(), email)
+}
+
+/// 删除被策略保护的账号时返回的错误前缀，调用方确认后带 confirmed 重试
+pub fn is_confirmation_error(error: &str) -> bool {
+    error.starts_with("delete_requires_confirmation")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rules_are_merged_per_domain() {
+        let policies = vec![
+            DomainPolicy {
+                domain: "@Corp.com".to_string(),
+                no_export: true,
+                ..Default::default()
+            },
+            DomainPolicy {
+                domain: "corp.com".to_string(),
+                confirm_delete: true,
+                ..Default::default()
+            },
+            DomainPolicy {
+                domain: "lab.io".to_string(),
+                exclude_from_proxy: true,
+                ..Default::default()
+            },
+        ];
+
+        let corp = rules_in(&policies, "alice@corp.COM");
+        assert!(corp.no_export && corp.confirm_delete && !corp.exclude_from_proxy);
+        assert_eq!(
+            rules_in(&policies, "bob@lab.io"),
+            DomainRules {
+                exclude_from_proxy: true,
+                ..Default::default()
+            }
+        );
+        // 仅精确匹配域名
+        assert_eq!(
+            rules_in(&policies, "carol@mail.corp.com"),
+            DomainRules::default()
+        );
+        assert_eq!(rules_in(&policies, "corp.com"), DomainRules::default());
+    }
+}
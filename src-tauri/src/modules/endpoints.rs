@@ -0,0 +1,93 @@
+// 上游端点地址
+// 默认使用 Google 官方端点；配置 upstream_endpoints.base_url_override 后，
+// 配额查询 / OAuth 刷新 / 反代请求统一改为请求该地址 (仅替换 scheme + host，保留路径)，
+// 用于连接内置的模拟上游 (mock-upstream feature) 做离线集成测试。
+
+use crate::models::UpstreamEndpointsConfig;
+use once_cell::sync::Lazy;
+use std::sync::RwLock;
+
+static CONFIG: Lazy<RwLock<UpstreamEndpointsConfig>> = Lazy::new(|| {
+    RwLock::new(
+        crate::modules::config::load_app_config()
+            .map(|cfg| cfg.upstream_endpoints)
+            .unwrap_or_default(),
+    )
+});
+
+pub fn update_config(config: UpstreamEndpointsConfig) {
+    if let Ok(mut cfg) = CONFIG.write() {
+        if *cfg != config {
+            crate::modules::logger::log_info(&format!(
+                "[Endpoints] Upstream base URL override: {}",
+                config.base_url_override.as_deref().unwrap_or("(none)")
+            ));
+        }
+        *cfg = config;
+    }
+}
+
+fn override_base() -> Option<String> {
+    CONFIG
+        .read()
+        .ok()?
+        .base_url_override
+        .as_deref()
+        .map(|base| base.trim().trim_end_matches('/'))
+        .filter(|base| !base.is_empty())
+        .map(str::to_string)
+}
+
+/// 把官方 URL 的 scheme + host 替换为 base，保留路径与查询串
+fn rebase(url: &str, base: &str) -> String {
+    let after_scheme = url.find("://").map(|i| i + 3).unwrap_or(0);
+    let path = url[after_scheme..]
+        .find('/')
+        .map(|i| &url[after_scheme + i..])
+        .unwrap_or("");
+    format!("{}{}", base, path)
+}
+
+/// 实际请求的地址 (未配置覆盖时原样返回)
+pub fn resolve(url: &str) -> String {
+    match override_base() {
+        Some(base) => rebase(url, &base),
+        None => url.to_string(),
+    }
+}
+
+/// 多个候选端点 (如 v1internal 的 Sandbox / Daily / Prod)，覆盖后合并为一个
+pub fn resolve_all(urls: &[&str]) -> Vec<String> {
+    let mut resolved: Vec<String> = Vec::new();
+    for url in urls.iter().map(|u| resolve(u)) {
+        if !resolved.contains(&url) {
+            resolved.push(url);
+        }
+    }
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rebase_keeps_path_and_query() {
+        let base = "http://127.0.0.1:18090";
+        assert_eq!(
+            rebase("https://oauth2.googleapis.com/token", base),
+            "http://127.0.0.1:18090/token"
+        );
+        assert_eq!(
+            rebase(
+                "https://daily-cloudcode-pa.sandbox.googleapis.com/v1internal:loadCodeAssist",
+                base
+            ),
+            "http://127.0.0.1:18090/v1internal:loadCodeAssist"
+        );
+        assert_eq!(
+            rebase("https://cloudcode-pa.googleapis.com", base),
+            "http://127.0.0.1:18090"
+        );
+    }
+}
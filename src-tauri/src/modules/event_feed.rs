@@ -0,0 +1,114 @@
+// 近期事件
+// 记录少量值得关注的事件 (账号切换、配额保护触发、反代 5xx 错误、托盘操作失败、模型无可用账号 / 低于容量阈值)，供托盘菜单的
+// “最近事件”子菜单展示，不打开主窗口也能知道离开期间发生了什么。
+// 仅保存在内存中；连续的相同事件会合并计数，避免错误突发把其他事件挤出列表。
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+const MAX_EVENTS: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    Switch,
+    Protection,
+    ProxyError,
+    ActionFailed,
+    ModelUnavailable,
+    CapacityLow,
+}
+
+impl EventKind {
+    pub fn icon(&self) -> &'static str {
+        match self {
+            EventKind::Switch => "🔄",
+            EventKind::Protection => "🛡",
+            EventKind::ProxyError => "⚠",
+            EventKind::ActionFailed => "✖",
+            EventKind::ModelUnavailable => "⛔",
+            EventKind::CapacityLow => "📉",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FeedEvent {
+    pub kind: EventKind,
+    pub message: String,
+    /// 最近一次发生的时间 (Unix 秒)
+    pub timestamp: i64,
+    /// 连续合并的次数
+    pub count: u32,
+}
+
+static EVENTS: Lazy<Mutex<VecDeque<FeedEvent>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+
+fn push(events: &mut VecDeque<FeedEvent>, kind: EventKind, message: String, now: i64) {
+    if let Some(last) = events.back_mut() {
+        if last.kind == kind && last.message == message {
+            last.count += 1;
+            last.timestamp = now;
+            return;
+        }
+    }
+    if events.len() == MAX_EVENTS {
+        events.pop_front();
+    }
+    events.push_back(FeedEvent {
+        kind,
+        message,
+        timestamp: now,
+        count: 1,
+    });
+}
+
+pub fn record(kind: EventKind, message: impl Into<String>) {
+    let mut events = EVENTS.lock().unwrap_or_else(|e| e.into_inner());
+    push(
+        &mut events,
+        kind,
+        message.into(),
+        chrono::Utc::now().timestamp(),
+    );
+}
+
+/// 最近的 n 条事件 (最新在前)
+pub fn recent(n: usize) -> Vec<FeedEvent> {
+    let events = EVENTS.lock().unwrap_or_else(|e| e.into_inner());
+    events.iter().rev().take(n).cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeated_events_are_coalesced_and_capped() {
+        let mut events = VecDeque::new();
+        push(
+            &mut events,
+            EventKind::ProxyError,
+            "502 /v1/messages".into(),
+            1,
+        );
+        push(
+            &mut events,
+            EventKind::ProxyError,
+            "502 /v1/messages".into(),
+            2,
+        );
+        push(&mut events, EventKind::Switch, "a@example.com".into(), 3);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].count, 2);
+        assert_eq!(events[0].timestamp, 2);
+
+        for i in 0..MAX_EVENTS {
+            push(&mut events, EventKind::Switch, format!("{}", i), 4);
+        }
+        assert_eq!(events.len(), MAX_EVENTS);
+        assert_eq!(events[0].message, "0");
+    }
+}
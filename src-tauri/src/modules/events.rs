@@ -0,0 +1,140 @@
+// 内部事件总线
+// 账号重载 / 删除、账号列表刷新、配置更新等内部信号统一通过 publish() 发布。
+// 内置消费者 (反代的待处理队列、前端事件) 在 publish 中同步处理，保证调用返回时信号已经生效；
+// 其余订阅者 (托盘、调试面板，以及以后的 webhook / 审计) 通过 subscribe() 拿到 tokio broadcast 接收端。
+// 最近的事件保存在内存中，供 tail_events 命令查看。
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+
+const CHANNEL_CAPACITY: usize = 256;
+const MAX_RECENT: usize = 500;
+pub const DEFAULT_TAIL_LIMIT: usize = 100;
+
+static SEQ: AtomicU64 = AtomicU64::new(0);
+static BUS: Lazy<broadcast::Sender<EventEnvelope>> =
+    Lazy::new(|| broadcast::channel(CHANNEL_CAPACITY).0);
+static RECENT: Lazy<Mutex<VecDeque<EventEnvelope>>> =
+    Lazy::new(|| Mutex::new(VecDeque::with_capacity(MAX_RECENT)));
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AppEvent {
+    /// 账号文件已变化，反代需要重新加载该账号
+    AccountReload { account_id: String },
+    /// 账号已删除，反代需要清理内存缓存与会话
+    AccountDeleted { account_id: String },
+    /// 账号列表可见状态变化，前端需要刷新
+    AccountsRefreshed,
+    /// 配置已保存
+    ConfigUpdated,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EventEnvelope {
+    /// 进程内递增序号，tail_events 以此作为游标
+    pub seq: u64,
+    /// 毫秒时间戳
+    pub at: i64,
+    pub event: AppEvent,
+}
+
+/// 内置消费者
+fn dispatch(event: &AppEvent) {
+    match event {
+        AppEvent::AccountReload { .. } | AppEvent::AccountDeleted { .. } => {
+            crate::proxy::server::on_event(event)
+        }
+        AppEvent::AccountsRefreshed => {
+            crate::modules::log_bridge::emit_event("accounts://refreshed", ())
+        }
+        AppEvent::ConfigUpdated => crate::modules::log_bridge::emit_event("config://updated", ()),
+    }
+}
+
+/// 发布事件
+pub fn publish(event: AppEvent) {
+    dispatch(&event);
+    let envelope = EventEnvelope {
+        seq: SEQ.fetch_add(1, Ordering::SeqCst) + 1,
+        at: chrono::Utc::now().timestamp_millis(),
+        event,
+    };
+    {
+        let mut recent = RECENT.lock().unwrap_or_else(|e| e.into_inner());
+        if recent.len() >= MAX_RECENT {
+            recent.pop_front();
+        }
+        recent.push_back(envelope.clone());
+    }
+    // 没有订阅者时 send 返回错误，忽略即可
+    let _ = BUS.send(envelope);
+}
+
+pub fn account_reload(account_id: &str) {
+    publish(AppEvent::AccountReload {
+        account_id: account_id.to_string(),
+    });
+}
+
+pub fn account_deleted(account_id: &str) {
+    publish(AppEvent::AccountDeleted {
+        account_id: account_id.to_string(),
+    });
+}
+
+pub fn accounts_refreshed() {
+    publish(AppEvent::AccountsRefreshed);
+}
+
+/// 订阅之后发布的事件 (处理过慢时会收到 RecvError::Lagged)
+pub fn subscribe() -> broadcast::Receiver<EventEnvelope> {
+    BUS.subscribe()
+}
+
+/// 序号大于 since 的最近事件 (最多 limit 条，按时间顺序)
+pub fn tail(since: Option<u64>, limit: usize) -> Vec<EventEnvelope> {
+    let recent = RECENT.lock().unwrap_or_else(|e| e.into_inner());
+    // 序号从 1 开始
+    let since = since.unwrap_or(0);
+    let matching: Vec<&EventEnvelope> = recent.iter().filter(|e| e.seq > since).collect();
+    let skip = matching.len().saturating_sub(limit);
+    matching.into_iter().skip(skip).cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_publish_reaches_subscribers_and_tail() {
+        let mut rx = subscribe();
+        let before = SEQ.load(Ordering::SeqCst);
+        publish(AppEvent::AccountsRefreshed);
+        publish(AppEvent::ConfigUpdated);
+
+        // 其他测试可能同时发布事件，只检查本测试发布的部分
+        let mut received = Vec::new();
+        while let Ok(envelope) = rx.try_recv() {
+            received.push(envelope.event);
+        }
+        assert!(received.contains(&AppEvent::AccountsRefreshed));
+        assert!(received.contains(&AppEvent::ConfigUpdated));
+
+        let tailed = tail(Some(before), MAX_RECENT);
+        assert!(tailed.iter().all(|e| e.seq > before));
+        assert!(tailed.iter().any(|e| e.event == AppEvent::ConfigUpdated));
+        assert_eq!(tail(Some(before), 1).len(), 1);
+
+        let json = serde_json::to_value(&AppEvent::AccountDeleted {
+            account_id: "a".to_string(),
+        })
+        .unwrap();
+        assert_eq!(json["type"], "account_deleted");
+        assert_eq!(json["account_id"], "a");
+    }
+}
@@ -0,0 +1,185 @@
+// 账号排除时间窗口
+// 在配置的时间段内，指定账号 (ID / 邮箱) 或带有指定标签的账号不参与反代选号，
+// 例如个人账号工作日 09:00-18:00 留给自己交互使用，不被号池流量消耗。
+// 时区支持 local / UTC / 固定偏移 (+08:00)；跨午夜的窗口按开始当天计算星期。
+// 反代每次选号都会检查，因此窗口缓存在内存中，保存配置时通过 update() 刷新。
+
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDateTime, Offset, Utc};
+use once_cell::sync::Lazy;
+use std::sync::RwLock;
+
+use crate::models::ExclusionWindow;
+use crate::modules::config;
+use crate::modules::scheduler::{is_within_window, parse_hhmm};
+
+static WINDOWS: Lazy<RwLock<Option<Vec<ExclusionWindow>>>> = Lazy::new(|| RwLock::new(None));
+
+/// 配置保存后刷新缓存
+pub fn update(windows: Vec<ExclusionWindow>) {
+    for w in &windows {
+        if let Err(e) = validate(w) {
+            tracing::warn!("[ExclusionWindows] Ignoring invalid window: {}", e);
+        }
+    }
+    *WINDOWS.write().unwrap_or_else(|e| e.into_inner()) = Some(windows);
+}
+
+pub fn current() -> Vec<ExclusionWindow> {
+    if let Some(windows) = WINDOWS.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        return windows.clone();
+    }
+    let windows = config::load_app_config()
+        .map(|c| c.exclusion_windows)
+        .unwrap_or_default();
+    update(windows.clone());
+    windows
+}
+
+pub fn validate(w: &ExclusionWindow) -> Result<(), String> {
+    if parse_hhmm(&w.start).is_none() || parse_hhmm(&w.end).is_none() {
+        return Err(format!("invalid_time_window: {}-{}", w.start, w.end));
+    }
+    if parse_offset(&w.timezone).is_none() {
+        return Err(format!("invalid_timezone: {}", w.timezone));
+    }
+    if let Some(day) = w.days.iter().find(|d| !(1..=7).contains(*d)) {
+        return Err(format!("invalid_weekday: {}", day));
+    }
+    Ok(())
+}
+
+/// "local" (None 偏移) / "UTC" / "+08:00" / "+0800" / "-5"
+fn parse_offset(tz: &str) -> Option<Option<FixedOffset>> {
+    let tz = tz.trim();
+    if tz.is_empty() || tz.eq_ignore_ascii_case("local") {
+        return Some(None);
+    }
+    let rest = tz
+        .strip_prefix("UTC")
+        .or_else(|| tz.strip_prefix("utc"))
+        .or_else(|| tz.strip_prefix("GMT"))
+        .unwrap_or(tz);
+    if rest.is_empty() || rest.eq_ignore_ascii_case("Z") {
+        return Some(FixedOffset::east_opt(0));
+    }
+    let (sign, digits) = match rest.as_bytes()[0] {
+        b'+' => (1, &rest[1..]),
+        b'-' => (-1, &rest[1..]),
+        _ => return None,
+    };
+    let (hours, minutes) = match digits.split_once(':') {
+        Some((h, m)) => (h, m),
+        None if digits.len() == 4 => digits.split_at(2),
+        None => (digits, "0"),
+    };
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    if hours > 14 || minutes > 59 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60)).map(Some)
+}
+
+/// 窗口所在时区的当前时间
+fn local_time(tz: &str, now: DateTime<Utc>) -> Option<NaiveDateTime> {
+    let offset =
+        parse_offset(tz)?.unwrap_or_else(|| now.with_timezone(&chrono::Local).offset().fix());
+    Some(now.with_timezone(&offset).naive_local())
+}
+
+pub fn is_active(w: &ExclusionWindow, now: DateTime<Utc>) -> bool {
+    if !w.enabled {
+        return false;
+    }
+    let (Some(start), Some(end)) = (parse_hhmm(&w.start), parse_hhmm(&w.end)) else {
+        return false;
+    };
+    let Some(local) = local_time(&w.timezone, now) else {
+        return false;
+    };
+    if !is_within_window(local.time(), start, end) {
+        return false;
+    }
+    if w.days.is_empty() {
+        return true;
+    }
+    // 跨午夜窗口的后半段属于前一天开始的窗口
+    let day = if start > end && local.time() < end {
+        local.date().pred_opt().unwrap_or(local.date())
+    } else {
+        local.date()
+    };
+    w.days.contains(&(day.weekday().number_from_monday() as u8))
+}
+
+fn matches(w: &ExclusionWindow, account_id: &str, email: &str, tags: &[String]) -> bool {
+    w.accounts
+        .iter()
+        .any(|a| a == account_id || a.eq_ignore_ascii_case(email))
+        || w.tags.iter().any(|tag| tags.contains(tag))
+}
+
+/// 账号当前是否处于任一排除窗口内
+pub fn excluded_in(
+    windows: &[ExclusionWindow],
+    account_id: &str,
+    email: &str,
+    tags: &[String],
+    now: DateTime<Utc>,
+) -> bool {
+    windows
+        .iter()
+        .any(|w| matches(w, account_id, email, tags) && is_active(w, now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn window(start: &str, end: &str, days: Vec<u8>, timezone: &str) -> ExclusionWindow {
+        ExclusionWindow {
+            enabled: true,
+            accounts: vec!["me@x.com".to_string()],
+            tags: vec!["personal".to_string()],
+            start: start.to_string(),
+            end: end.to_string(),
+            days,
+            timezone: timezone.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_exclusion_windows() {
+        // 2026-10-14 是星期三；UTC 02:30 = 东八区 10:30
+        let now = Utc.with_ymd_and_hms(2026, 10, 14, 2, 30, 0).unwrap();
+        let weekdays = window("09:00", "18:00", vec![1, 2, 3, 4, 5], "+08:00");
+        assert!(is_active(&weekdays, now));
+        assert!(!is_active(
+            &window("09:00", "18:00", vec![6, 7], "+08:00"),
+            now
+        ));
+        assert!(!is_active(&window("09:00", "18:00", vec![], "UTC"), now));
+
+        // 跨午夜: 星期二 22:00 开始的窗口在星期三 02:30 (UTC) 仍然生效
+        assert!(is_active(&window("22:00", "06:00", vec![2], "UTC"), now));
+        assert!(!is_active(&window("22:00", "06:00", vec![3], "UTC"), now));
+
+        let windows = vec![weekdays];
+        assert!(excluded_in(&windows, "id-1", "ME@x.com", &[], now));
+        assert!(excluded_in(
+            &windows,
+            "id-2",
+            "b@x.com",
+            &["personal".to_string()],
+            now
+        ));
+        assert!(!excluded_in(&windows, "id-3", "c@x.com", &[], now));
+
+        assert_eq!(parse_offset("-0530"), Some(FixedOffset::east_opt(-19800)));
+        assert_eq!(parse_offset("UTC+8"), Some(FixedOffset::east_opt(28800)));
+        assert_eq!(parse_offset("Europe/Berlin"), None);
+        assert!(validate(&window("9am", "18:00", vec![], "local")).is_err());
+        assert!(validate(&window("09:00", "18:00", vec![8], "local")).is_err());
+    }
+}
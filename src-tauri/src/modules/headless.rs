@@ -0,0 +1,288 @@
+// Headless / Docker 运行模式
+// 无界面模式下的配置全部来自环境变量与挂载的 secret 文件，不依赖 gui_config.json 的手工编辑:
+// - ABV_<配置键> 覆盖任意配置项 (见 config::with_env_overrides)，变量名加 _FILE 后缀时从文件读取 (docker secrets)
+// - PORT / API_KEY / WEB_PASSWORD / AUTH_MODE 简写 (同样支持 _FILE)，ABV_BIND_LOCAL_ONLY 仅绑定回环地址
+// - ABV_ACCOUNTS_FILE: 启动时导入账号 (`abv-cli accounts export` 的 JSON，或每行一个 refresh_token)
+// - ABV_LOG_FORMAT=json: 标准输出改为 JSON 行日志 (见 logger::init_logger)
+// 运行期间 is_headless() 为真，storage.json / state.vscdb / 进程检测等桌面端路径直接跳过。
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::models::AppConfig;
+use crate::modules::{account, logger};
+use crate::proxy::ProxyAuthMode;
+
+static HEADLESS: AtomicBool = AtomicBool::new(false);
+
+/// 进入无界面模式 (run_headless 启动时调用)
+pub fn enable() {
+    HEADLESS.store(true, Ordering::SeqCst);
+}
+
+pub fn is_headless() -> bool {
+    HEADLESS.load(Ordering::SeqCst)
+}
+
+/// 读取环境变量；未设置时尝试 `<NAME>_FILE` 指向的文件 (首尾空白会被去掉)
+pub fn env_or_file(name: &str) -> Result<Option<String>, String> {
+    if let Some(value) = std::env::var(name).ok().filter(|v| !v.trim().is_empty()) {
+        return Ok(Some(value));
+    }
+    let Some(path) = std::env::var(format!("{}_FILE", name))
+        .ok()
+        .filter(|p| !p.trim().is_empty())
+    else {
+        return Ok(None);
+    };
+    let content = std::fs::read_to_string(path.trim())
+        .map_err(|e| format!("invalid_env: {}_FILE ({}): {}", name, path, e))?;
+    Ok(Some(content.trim().to_string()).filter(|v| !v.is_empty()))
+}
+
+#[derive(Debug, Default)]
+pub struct HeadlessSettings {
+    pub port: Option<u16>,
+    pub api_key: Option<String>,
+    pub web_password: Option<String>,
+    pub auth_mode: Option<ProxyAuthMode>,
+    pub bind_local_only: bool,
+    pub accounts_file: Option<PathBuf>,
+}
+
+impl HeadlessSettings {
+    pub fn from_env() -> Result<Self, String> {
+        let mut values = std::collections::HashMap::new();
+        for name in [
+            "ABV_PORT",
+            "PORT",
+            "ABV_API_KEY",
+            "API_KEY",
+            "ABV_WEB_PASSWORD",
+            "WEB_PASSWORD",
+            "ABV_AUTH_MODE",
+            "AUTH_MODE",
+            "ABV_BIND_LOCAL_ONLY",
+            "ABV_ACCOUNTS_FILE",
+        ] {
+            if let Some(value) = env_or_file(name)? {
+                values.insert(name, value);
+            }
+        }
+        Self::from_lookup(|name| values.get(name).cloned())
+    }
+
+    /// 按优先级 ABV_X > X 解析 (lookup 已处理 _FILE)
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, String> {
+        let first = |names: &[&str]| names.iter().find_map(|name| lookup(name));
+
+        let port = first(&["ABV_PORT", "PORT"])
+            .map(|raw| {
+                raw.trim()
+                    .parse::<u16>()
+                    .ok()
+                    .filter(|p| *p != 0)
+                    .ok_or_else(|| format!("invalid_env: PORT '{}' is not a valid port", raw))
+            })
+            .transpose()?;
+        let auth_mode = first(&["ABV_AUTH_MODE", "AUTH_MODE"])
+            .map(|raw| {
+                serde_json::from_value::<ProxyAuthMode>(serde_json::Value::String(
+                    raw.trim().to_lowercase(),
+                ))
+                .map_err(|_| {
+                    format!(
+                        "invalid_env: AUTH_MODE '{}' (expected off, strict, all_except_health or auto)",
+                        raw
+                    )
+                })
+            })
+            .transpose()?;
+        let bind_local_only = first(&["ABV_BIND_LOCAL_ONLY"])
+            .map(|v| {
+                matches!(
+                    v.trim().to_lowercase().as_str(),
+                    "1" | "true" | "yes" | "on"
+                )
+            })
+            .unwrap_or(false);
+
+        Ok(Self {
+            port,
+            api_key: first(&["ABV_API_KEY", "API_KEY"]),
+            web_password: first(&["ABV_WEB_PASSWORD", "WEB_PASSWORD"]),
+            auth_mode,
+            bind_local_only,
+            accounts_file: first(&["ABV_ACCOUNTS_FILE"]).map(PathBuf::from),
+        })
+    }
+
+    /// 把环境配置写入 config；返回是否有改动需要持久化
+    pub fn apply(&self, config: &mut AppConfig) -> bool {
+        let mut modified = false;
+
+        // Headless/docker 默认允许 LAN 访问（绑定 0.0.0.0）；ABV_BIND_LOCAL_ONLY 时仅绑定 127.0.0.1
+        if self.bind_local_only {
+            config.proxy.allow_lan_access = false;
+            modified = true;
+        } else {
+            config.proxy.allow_lan_access = true;
+        }
+
+        // Web UI 登录需要鉴权: Off / Auto 强制为 AllExceptHealth
+        if matches!(
+            config.proxy.auth_mode,
+            ProxyAuthMode::Off | ProxyAuthMode::Auto
+        ) {
+            logger::log_info(
+                "Headless mode: Forcing auth_mode to AllExceptHealth for Web UI security",
+            );
+            config.proxy.auth_mode = ProxyAuthMode::AllExceptHealth;
+            modified = true;
+        }
+
+        if let Some(port) = self.port {
+            config.proxy.port = port;
+            modified = true;
+        }
+        if let Some(key) = self.api_key.as_ref() {
+            logger::log_info("Using API Key from environment variable");
+            config.proxy.api_key = key.clone();
+            modified = true;
+        }
+        if let Some(pwd) = self.web_password.as_ref() {
+            logger::log_info("Using Web UI Password from environment variable");
+            config.proxy.admin_password = Some(pwd.clone());
+            modified = true;
+        }
+        if let Some(mode) = self.auth_mode.as_ref() {
+            logger::log_info(&format!(
+                "Using Auth Mode from environment variable: {:?}",
+                mode
+            ));
+            config.proxy.auth_mode = mode.clone();
+            modified = true;
+        }
+        modified
+    }
+}
+
+/// 账号导入文件中的 refresh_token: 导出 JSON 或其他工具的格式 (见 modules::importers)、refresh_token 数组，
+/// 否则按行读取 (# 开头为注释)
+fn parse_accounts_file(content: &str) -> Vec<String> {
+    let tokens: Vec<String> =
+        if let Ok(parsed) = crate::modules::importers::parse(content) {
+            parsed
+                .accounts
+                .into_iter()
+                .map(|a| a.refresh_token)
+                .collect()
+        } else if let Ok(items) = serde_json::from_str::<Vec<String>>(content) {
+            items
+        } else {
+            content
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.starts_with('#'))
+                .map(str::to_string)
+                .collect()
+        };
+    let mut seen = HashSet::new();
+    tokens
+        .into_iter()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty() && seen.insert(t.clone()))
+        .collect()
+}
+
+/// 导入挂载的账号文件；已存在相同 refresh_token 的账号会跳过，因此容器重启时不会重复请求
+pub async fn import_accounts_file(path: &std::path::Path) -> Result<(), String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("failed_to_read_accounts_file: {}: {}", path.display(), e))?;
+    let existing: HashSet<String> = account::list_accounts()?
+        .into_iter()
+        .map(|a| a.token.refresh_token)
+        .collect();
+    let pending: Vec<String> = parse_accounts_file(&content)
+        .into_iter()
+        .filter(|t| !existing.contains(t))
+        .collect();
+    if pending.is_empty() {
+        logger::log_info(&format!("[Headless] No new accounts in {}", path.display()));
+        return Ok(());
+    }
+
+    let service = crate::modules::account_service::AccountService::new(
+        crate::modules::integration::SystemManager::Headless,
+    );
+    let (mut imported, mut failed) = (0, 0);
+    for token in &pending {
+        match service.add_account(token).await {
+            Ok(acc) => {
+                imported += 1;
+                logger::log_info(&format!("[Headless] Imported account {}", acc.email));
+            }
+            Err(e) => {
+                failed += 1;
+                logger::log_warn(&format!("[Headless] Failed to import account: {}", e));
+            }
+        }
+    }
+    logger::log_info(&format!(
+        "[Headless] Accounts file {}: {} imported, {} failed",
+        path.display(),
+        imported,
+        failed
+    ));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_settings_from_lookup() {
+        let env = HashMap::from([
+            ("PORT", "9045"),
+            ("API_KEY", "sk-short"),
+            ("ABV_API_KEY", "sk-prefixed"),
+            ("AUTH_MODE", "Strict"),
+            ("ABV_BIND_LOCAL_ONLY", "yes"),
+            ("ABV_ACCOUNTS_FILE", "/run/secrets/accounts"),
+        ]);
+        let settings =
+            HeadlessSettings::from_lookup(|name| env.get(name).map(|v| v.to_string())).unwrap();
+        assert_eq!(settings.port, Some(9045));
+        assert_eq!(settings.api_key.as_deref(), Some("sk-prefixed"));
+        assert!(matches!(settings.auth_mode, Some(ProxyAuthMode::Strict)));
+        assert!(settings.bind_local_only);
+
+        let mut config = AppConfig::new();
+        assert!(settings.apply(&mut config));
+        assert_eq!(config.proxy.port, 9045);
+        assert!(!config.proxy.allow_lan_access);
+        assert!(matches!(config.proxy.auth_mode, ProxyAuthMode::Strict));
+
+        assert!(HeadlessSettings::from_lookup(|n| (n == "PORT").then(|| "http".into())).is_err());
+        assert!(
+            HeadlessSettings::from_lookup(|n| (n == "AUTH_MODE").then(|| "none".into())).is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_accounts_file() {
+        let export = r#"{"accounts": [
+            {"email": "a@example.com", "refresh_token": "1//a"},
+            {"email": "b@example.com", "refresh_token": "1//b"}
+        ]}"#;
+        assert_eq!(parse_accounts_file(export), vec!["1//a", "1//b"]);
+        assert_eq!(parse_accounts_file(r#"["1//a", "1//a"]"#), vec!["1//a"]);
+        assert_eq!(
+            parse_accounts_file("# tokens\n1//a\n\n  1//c  \n"),
+            vec!["1//a", "1//c"]
+        );
+    }
+}
@@ -0,0 +1,267 @@
+// 共享 HTTP 客户端
+// oauth / quota / 版本检查等请求统一从这里取 Client：
+// - 按 (指纹模拟, 超时, 出口路由) 缓存，同一出口复用连接池，不再每次请求重建 Client
+// - 超时、连接池与重试参数来自 AppConfig.http_client，保存配置后重建
+// - 出口优先级: 代理池 (账号绑定 / 自动选择) > 应用上游代理 > 直连
+// - 出站地址族: 账号覆盖 > http_client.ip_family；DNS 解析 (静态映射 / DoH) 见 modules::dns
+
+use crate::models::{HttpClientConfig, IpFamily};
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use rquest::{Client, Proxy};
+use rquest_util::Emulation;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ClientKey {
+    emulated: bool,
+    timeout_secs: u64,
+    /// "direct" / "upstream:<url>" / "pool:<entry_id>"
+    route: String,
+    ip_family: IpFamily,
+}
+
+static CLIENTS: Lazy<DashMap<ClientKey, Client>> = Lazy::new(DashMap::new);
+
+/// 账号级地址族覆盖 (由 TokenManager 加载账号时同步)
+static ACCOUNT_IP_FAMILIES: Lazy<DashMap<String, IpFamily>> = Lazy::new(DashMap::new);
+
+static SETTINGS: Lazy<RwLock<HttpClientConfig>> = Lazy::new(|| {
+    RwLock::new(
+        crate::modules::config::load_app_config()
+            .map(|cfg| cfg.http_client)
+            .unwrap_or_default(),
+    )
+});
+
+/// 出口路由: 缓存键 + 需要设置的代理
+pub struct Route {
+    key: String,
+    proxy: Option<Proxy>,
+    ip_family: IpFamily,
+}
+
+impl Route {
+    pub fn direct() -> Self {
+        Self {
+            key: "direct".to_string(),
+            proxy: None,
+            ip_family: settings().ip_family,
+        }
+    }
+
+    /// 代理池中的节点 (以节点 ID 区分缓存)
+    pub fn pool(entry_id: &str, proxy: Proxy) -> Self {
+        Self {
+            key: format!("pool:{}", entry_id),
+            proxy: Some(proxy),
+            ip_family: settings().ip_family,
+        }
+    }
+
+    /// 使用账号的地址族 (未覆盖时为全局设置)
+    pub fn for_account(mut self, account_id: Option<&str>) -> Self {
+        self.ip_family = ip_family_for(account_id);
+        self
+    }
+
+    /// 应用配置中的上游代理；未启用或地址无效时直连
+    pub fn upstream() -> Self {
+        let Some(url) = upstream_proxy_url() else {
+            return Self::direct();
+        };
+        match Proxy::all(&url) {
+            Ok(proxy) => Self {
+                key: format!("upstream:{}", url),
+                proxy: Some(proxy),
+                ip_family: settings().ip_family,
+            },
+            Err(e) => {
+                tracing::error!("invalid_proxy_url: {}, error: {}", url, e);
+                Self::direct()
+            }
+        }
+    }
+}
+
+/// 当前生效的 HTTP 客户端配置
+pub fn settings() -> HttpClientConfig {
+    SETTINGS
+        .read()
+        .map(|cfg| cfg.clone())
+        .unwrap_or_default()
+}
+
+/// 更新配置并丢弃已缓存的 Client (下次请求按新配置重建)
+pub fn update_settings(config: HttpClientConfig) {
+    if let Ok(mut cfg) = SETTINGS.write() {
+        if *cfg != config {
+            tracing::info!(
+                "[HTTP] Client settings updated: timeout={}s long={}s connect={}s attempts={}",
+                config.timeout_secs,
+                config.long_timeout_secs,
+                config.connect_timeout_secs,
+                config.max_attempts
+            );
+            *cfg = config;
+        }
+    }
+    reset_clients();
+}
+
+/// 丢弃所有缓存的 Client (上游代理 / 代理池变更后调用)
+pub fn reset_clients() {
+    CLIENTS.clear();
+}
+
+/// 应用配置中启用的上游代理地址
+pub fn upstream_proxy_url() -> Option<String> {
+    let config = crate::modules::config::load_app_config().ok()?;
+    let upstream = config.proxy.upstream_proxy;
+    (upstream.enabled && !upstream.url.is_empty()).then_some(upstream.url)
+}
+
+/// 同步账号的地址族覆盖；None 表示使用全局设置
+pub fn set_account_ip_family(account_id: &str, family: Option<IpFamily>) {
+    match family {
+        Some(family) if family != IpFamily::Auto => {
+            ACCOUNT_IP_FAMILIES.insert(account_id.to_string(), family);
+        }
+        _ => {
+            ACCOUNT_IP_FAMILIES.remove(account_id);
+        }
+    }
+}
+
+/// 账号实际生效的地址族
+pub fn ip_family_for(account_id: Option<&str>) -> IpFamily {
+    account_id
+        .and_then(|id| ACCOUNT_IP_FAMILIES.get(id).map(|f| *f))
+        .unwrap_or_else(|| settings().ip_family)
+}
+
+/// 为 rquest ClientBuilder 设置上游 DNS 解析 (静态映射 / DoH / 地址族过滤，见 modules::dns)
+pub fn apply_resolver(builder: rquest::ClientBuilder, family: IpFamily) -> rquest::ClientBuilder {
+    builder.dns_resolver(Arc::new(crate::modules::dns::UpstreamResolver(family)))
+}
+
+fn build_client(
+    emulated: bool,
+    timeout_secs: u64,
+    proxy: Option<Proxy>,
+    ip_family: IpFamily,
+) -> Client {
+    let cfg = settings();
+    let mut builder = Client::builder()
+        .timeout(Duration::from_secs(timeout_secs))
+        .connect_timeout(Duration::from_secs(cfg.connect_timeout_secs))
+        .pool_idle_timeout(Duration::from_secs(cfg.pool_idle_timeout_secs))
+        .pool_max_idle_per_host(cfg.pool_max_idle_per_host);
+    if emulated {
+        builder = builder.emulation(Emulation::Chrome123);
+    }
+    if let Some(proxy) = proxy {
+        builder = builder.proxy(proxy);
+    }
+    apply_resolver(builder, ip_family)
+        .build()
+        .unwrap_or_else(|_| Client::new())
+}
+
+/// 获取 (或创建并缓存) 指定出口的 Client
+pub fn client_for(emulated: bool, timeout_secs: u64, route: Route) -> Client {
+    let key = ClientKey {
+        emulated,
+        timeout_secs,
+        route: route.key,
+        ip_family: route.ip_family,
+    };
+    if let Some(client) = CLIENTS.get(&key) {
+        return client.clone();
+    }
+    tracing::debug!(
+        "[HTTP] Creating pooled client: route={} emulated={} timeout={}s",
+        key.route,
+        emulated,
+        timeout_secs
+    );
+    let client = build_client(emulated, timeout_secs, route.proxy, route.ip_family);
+    CLIENTS.entry(key).or_insert(client).clone()
+}
+
+/// JA3 模拟 (Chrome) Client，短超时
+pub fn emulated_client() -> Client {
+    client_for(true, settings().timeout_secs, Route::upstream())
+}
+
+/// JA3 模拟 (Chrome) Client，长超时
+pub fn long_emulated_client() -> Client {
+    client_for(true, settings().long_timeout_secs, Route::upstream())
+}
+
+/// 原生 TLS Client，短超时
+pub fn standard_client() -> Client {
+    client_for(false, settings().timeout_secs, Route::upstream())
+}
+
+/// 原生 TLS Client，长超时
+pub fn long_standard_client() -> Client {
+    client_for(false, settings().long_timeout_secs, Route::upstream())
+}
+
+/// 为版本检查等使用 reqwest 的场景构建带统一超时 / 上游代理的 blocking Client
+pub fn blocking_reqwest_client(timeout_secs: u64) -> Option<reqwest::blocking::Client> {
+    let cfg = settings();
+    let mut builder = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(timeout_secs))
+        .connect_timeout(Duration::from_secs(cfg.connect_timeout_secs.min(timeout_secs)));
+    if let Some(proxy) = upstream_proxy_url().and_then(|url| reqwest::Proxy::all(url).ok()) {
+        builder = builder.proxy(proxy);
+    }
+    builder = builder.dns_resolver(Arc::new(crate::modules::dns::UpstreamResolver(cfg.ip_family)));
+    builder.build().ok()
+}
+
+/// 为 reqwest 异步场景 (更新检查) 构建带统一超时 / 上游代理的 ClientBuilder
+pub fn reqwest_client_builder() -> reqwest::ClientBuilder {
+    let cfg = settings();
+    let mut builder = reqwest::Client::builder()
+        .timeout(Duration::from_secs(cfg.timeout_secs))
+        .connect_timeout(Duration::from_secs(cfg.connect_timeout_secs))
+        .pool_idle_timeout(Duration::from_secs(cfg.pool_idle_timeout_secs));
+    if let Some(url) = upstream_proxy_url() {
+        match reqwest::Proxy::all(&url) {
+            Ok(proxy) => builder = builder.proxy(proxy),
+            Err(e) => tracing::warn!("Failed to parse proxy URL '{}': {}", url, e),
+        }
+    }
+    builder = builder.dns_resolver(Arc::new(crate::modules::dns::UpstreamResolver(cfg.ip_family)));
+    builder
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clients_are_reused_per_route() {
+        reset_clients();
+        let _a = client_for(false, 7, Route::direct());
+        let _b = client_for(false, 7, Route::direct());
+        let _c = client_for(true, 7, Route::direct());
+        let count = CLIENTS
+            .iter()
+            .filter(|e| e.key().timeout_secs == 7)
+            .count();
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn test_account_ip_family_override() {
+        set_account_ip_family("acc-v4", Some(IpFamily::Ipv4));
+        assert_eq!(ip_family_for(Some("acc-v4")), IpFamily::Ipv4);
+        set_account_ip_family("acc-v4", None);
+        assert_eq!(ip_family_for(Some("acc-v4")), settings().ip_family);
+    }
+}
@@ -11,8 +11,6 @@ use axum::{
 use serde::{Deserialize, Serialize};
 // 预留 HTTP API 模块，当前未在主流程中启用
 
-use std::sync::Arc;
-use tokio::sync::RwLock;
 use tower_http::cors::{Any, CorsLayer};
 
 use crate::modules::{account, logger, proxy_db};
@@ -25,7 +23,7 @@ pub const DEFAULT_PORT: u16 = 19527;
 // ============================================================================
 
 /// HTTP API Settings
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct HttpApiSettings {
     /// Whether to enable HTTP API service
     #[serde(default = "default_enabled")]
@@ -85,15 +83,12 @@ pub fn save_settings(settings: &HttpApiSettings) -> Result<(), String> {
 /// Server State
 #[derive(Clone)]
 pub struct ApiState {
-    /// Whether there is a switch operation currently in progress
-    pub switching: Arc<RwLock<bool>>,
     pub integration: crate::modules::integration::SystemManager,
 }
 
 impl ApiState {
     pub fn new(integration: crate::modules::integration::SystemManager) -> Self {
         Self {
-            switching: Arc::new(RwLock::new(false)),
             integration,
         }
     }
@@ -126,6 +121,7 @@ struct QuotaResponse {
     models: Vec<ModelQuota>,
     updated_at: Option<i64>,
     subscription_tier: Option<String>,
+    stale: bool,
 }
 
 #[derive(Serialize)]
@@ -246,6 +242,7 @@ async fn list_accounts() -> Result<impl IntoResponse, (StatusCode, Json<ErrorRes
         .map(|acc| {
             let is_current = current_id.as_ref().map(|id| id == &acc.id).unwrap_or(false);
             let quota = acc.quota.map(|q| QuotaResponse {
+                stale: q.is_stale(crate::proxy::config::get_quota_staleness_minutes()),
                 models: q.models.into_iter().map(|m| ModelQuota {
                     name: m.name,
                     percentage: m.percentage,
@@ -285,6 +282,7 @@ async fn get_current_account() -> Result<impl IntoResponse, (StatusCode, Json<Er
 
     let response = current.map(|acc| {
         let quota = acc.quota.map(|q| QuotaResponse {
+            stale: q.is_stale(crate::proxy::config::get_quota_staleness_minutes()),
             models: q.models.into_iter().map(|m| ModelQuota {
                 name: m.name,
                 percentage: m.percentage,
@@ -314,25 +312,22 @@ async fn switch_account(
     State(state): State<ApiState>,
     Json(payload): Json<SwitchRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
-    // Check if another switch operation is already in progress
-    {
-        let switching = state.switching.read().await;
-        if *switching {
+    // Reject while a switch to another account is running (see switch_queue);
+    // a request for the same account simply joins the running switch
+    if let Some(running) = crate::modules::switch_queue::in_progress() {
+        if running.account_id != payload.account_id {
             return Err((
                 StatusCode::CONFLICT,
                 Json(ErrorResponse {
-                    error: "Another switch operation is already in progress".to_string(),
+                    error: format!(
+                        "switch_in_progress: already switching to {}",
+                        running.account_id
+                    ),
                 }),
             ));
         }
     }
 
-    // Mark switch started
-    {
-        let mut switching = state.switching.write().await;
-        *switching = true;
-    }
-
     let account_id = payload.account_id.clone();
     let state_clone = state.clone();
 
@@ -341,17 +336,13 @@ async fn switch_account(
         logger::log_info(&format!("[HTTP API] Starting account switch: {}", account_id));
         
         match account::switch_account(&account_id, &state_clone.integration).await {
-            Ok(()) => {
+            Ok(_) => {
                 logger::log_info(&format!("[HTTP API] Account switch successful: {}", account_id));
             }
             Err(e) => {
                 logger::log_error(&format!("[HTTP API] Account switch failed: {}", e));
             }
         }
-
-        // Mark switch ended
-        let mut switching = state_clone.switching.write().await;
-        *switching = false;
     });
 
     // Immediately return 202 Accepted
@@ -481,8 +472,8 @@ pub async fn start_server(port: u16, integration: crate::modules::integration::S
 
 /// Start HTTP API server in background (non-blocking)
 pub fn spawn_server(port: u16, integration: crate::modules::integration::SystemManager) {
-    // Use tauri::async_runtime::spawn to ensure running within Tauri's runtime
-    tauri::async_runtime::spawn(async move {
+    // Spawn on the shared background runtime (Tauri's runtime on desktop)
+    crate::utils::runtime::spawn(async move {
         if let Err(e) = start_server(port, integration).await {
             logger::log_error(&format!("[HTTP API] Failed to start server: {}", e));
         }
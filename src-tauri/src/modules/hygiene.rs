@@ -0,0 +1,294 @@
+// 账号数据维护 (每周)
+// 自动完成以前需要手动在数据目录里做的清理: 清除已过期的验证阻止 (冷却)、压缩设备指纹历史、
+// 删除写入中途崩溃留下的 *.tmp.* 临时文件、校验账号索引完整性 (只报告，不自动修复)，
+// 完成后发送摘要通知。dry_run 只统计将被处理的内容。
+
+use chrono::{Datelike, Timelike};
+use serde::Serialize;
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use crate::models::{Account, HygieneConfig};
+use crate::modules::{account, config, logger, scheduler};
+
+const CHECK_INTERVAL_SECS: u64 = 600;
+const JOB_ID: &str = "account_hygiene";
+
+static HYGIENE_STARTED: AtomicBool = AtomicBool::new(false);
+/// 最近一次执行的本地日期，避免同一小时内重复执行
+static LAST_RUN: Mutex<Option<chrono::NaiveDate>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HygieneReport {
+    pub dry_run: bool,
+    pub ran_at: i64,
+    /// 清除了已过期验证阻止的账号
+    pub expired_cooldowns: Vec<String>,
+    /// 删除的设备指纹历史条数
+    pub device_history_pruned: usize,
+    /// 删除的临时文件 (相对数据目录)
+    pub temp_files: Vec<String>,
+    pub integrity: Option<account::IntegrityReport>,
+    pub errors: Vec<String>,
+}
+
+impl HygieneReport {
+    pub fn summary(&self) -> String {
+        let integrity = match &self.integrity {
+            Some(report) if report.is_clean() => "index OK".to_string(),
+            Some(report) => format!(
+                "index has {} issue(s)",
+                report.orphan_files.len()
+                    + report.dangling_entries.len()
+                    + report.unreadable_files.len()
+                    + report.duplicate_emails.len()
+            ),
+            None => "index not checked".to_string(),
+        };
+        format!(
+            "{} expired cooldown(s) cleared, {} device history entr(ies) pruned, {} temp file(s) removed, {}",
+            self.expired_cooldowns.len(),
+            self.device_history_pruned,
+            self.temp_files.len(),
+            integrity
+        )
+    }
+}
+
+/// 验证阻止已过期时清除相关字段
+fn clear_expired_cooldown(acc: &mut Account, now: i64) -> bool {
+    if !acc.validation_blocked
+        || acc
+            .validation_blocked_until
+            .is_some_and(|until| until > now)
+    {
+        return false;
+    }
+    acc.validation_blocked = false;
+    acc.validation_blocked_until = None;
+    acc.validation_blocked_reason = None;
+    acc.validation_url = None;
+    true
+}
+
+/// 只保留最近 keep 条设备指纹历史 (当前使用的版本始终保留)，返回删除条数
+fn compact_device_history(acc: &mut Account, keep: usize) -> usize {
+    if acc.device_history.len() <= keep {
+        return 0;
+    }
+    let mut by_age: Vec<(i64, String)> = acc
+        .device_history
+        .iter()
+        .filter(|v| !v.is_current)
+        .map(|v| (v.created_at, v.id.clone()))
+        .collect();
+    by_age.sort_by_key(|v| std::cmp::Reverse(v.0));
+    let current = acc.device_history.len() - by_age.len();
+    let kept: Vec<String> = by_age
+        .into_iter()
+        .take(keep.saturating_sub(current))
+        .map(|(_, id)| id)
+        .collect();
+    let before = acc.device_history.len();
+    acc.device_history
+        .retain(|v| v.is_current || kept.contains(&v.id));
+    before - acc.device_history.len()
+}
+
+/// 按当前配置执行一次维护 (dry_run 时只统计)
+pub fn run(dry_run: bool) -> Result<HygieneReport, String> {
+    let cfg = config::load_app_config()?.hygiene;
+    let data_dir = account::get_data_dir()?;
+    let now = chrono::Utc::now().timestamp();
+    let mut report = HygieneReport {
+        dry_run,
+        ran_at: now,
+        expired_cooldowns: Vec::new(),
+        device_history_pruned: 0,
+        temp_files: Vec::new(),
+        integrity: None,
+        errors: Vec::new(),
+    };
+
+    // 1. 过期冷却 / 2. 设备指纹历史：先在副本上统计，再只写入需要变化的账号
+    let mut targets = Vec::new();
+    for mut acc in account::list_accounts()? {
+        let cleared = clear_expired_cooldown(&mut acc, now);
+        let pruned = compact_device_history(&mut acc, cfg.device_history_keep);
+        if cleared {
+            report.expired_cooldowns.push(acc.id.clone());
+        }
+        report.device_history_pruned += pruned;
+        if cleared || pruned > 0 {
+            targets.push(acc.id);
+        }
+    }
+    if !dry_run && !targets.is_empty() {
+        let keep = cfg.device_history_keep;
+        let results = account::with_accounts_mut(&targets, |acc| {
+            let cleared = clear_expired_cooldown(acc, now);
+            compact_device_history(acc, keep);
+            Ok(cleared)
+        })?;
+        for (account_id, result) in results {
+            match result {
+                // 反代内存中的账号同步解除阻止
+                Ok(true) => crate::modules::events::account_reload(&account_id),
+                Ok(false) => {}
+                Err(e) => report.errors.push(format!("{}: {}", account_id, e)),
+            }
+        }
+    }
+
+    // 3. 临时文件
+    let min_age = Duration::from_secs(cfg.temp_file_min_age_hours * 3600);
+    for path in account::stale_temp_files_in_dir(&data_dir, min_age, SystemTime::now()) {
+        let name = path
+            .strip_prefix(&data_dir)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .to_string();
+        if !dry_run {
+            if let Err(e) = fs::remove_file(&path) {
+                report.errors.push(format!("{}: {}", name, e));
+                continue;
+            }
+        }
+        report.temp_files.push(name);
+    }
+
+    // 4. 索引完整性 (修复需要用户确认，见 apply_integrity_repair)
+    match account::verify_data_integrity() {
+        Ok(integrity) => report.integrity = Some(integrity),
+        Err(e) => report.errors.push(format!("integrity: {}", e)),
+    }
+    Ok(report)
+}
+
+fn is_due(cfg: &HygieneConfig, now: chrono::DateTime<chrono::Local>) -> bool {
+    cfg.enabled
+        && now.weekday().number_from_monday() == u32::from(cfg.weekday)
+        && now.hour() == u32::from(cfg.hour)
+}
+
+/// 启动每周维护任务 (每个进程一次)
+pub fn start_hygiene_scheduler() {
+    if HYGIENE_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    scheduler::register_job(JOB_ID, CHECK_INTERVAL_SECS);
+    crate::utils::runtime::spawn(async {
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_secs(CHECK_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            if !scheduler::job_tick(JOB_ID) {
+                continue;
+            }
+
+            let Ok(app_config) = config::load_app_config() else {
+                continue;
+            };
+            let now = chrono::Local::now();
+            if !is_due(&app_config.hygiene, now) {
+                continue;
+            }
+            {
+                let mut last = LAST_RUN.lock().unwrap_or_else(|e| e.into_inner());
+                if *last == Some(now.date_naive()) {
+                    continue;
+                }
+                *last = Some(now.date_naive());
+            }
+
+            let started = std::time::Instant::now();
+            let result = match tokio::task::spawn_blocking(|| run(false)).await {
+                Ok(Ok(report)) => {
+                    let summary = report.summary();
+                    logger::log_info(&format!("[Hygiene] {}", summary));
+                    for e in &report.errors {
+                        logger::log_warn(&format!("[Hygiene] {}", e));
+                    }
+                    crate::modules::integration::SystemManager::Headless
+                        .show_notification("Account maintenance", &summary);
+                    crate::modules::log_bridge::emit_event("hygiene://report", report.clone());
+                    let severity = if report.errors.is_empty() {
+                        crate::modules::notify::Severity::Info
+                    } else {
+                        crate::modules::notify::Severity::Warning
+                    };
+                    crate::modules::notify::push(
+                        severity,
+                        "hygiene",
+                        "Account maintenance",
+                        summary.clone(),
+                        None,
+                    );
+                    match report.errors.first() {
+                        Some(e) => Err(e.clone()),
+                        None => Ok(summary),
+                    }
+                }
+                Ok(Err(e)) => {
+                    logger::log_warn(&format!("[Hygiene] Maintenance failed: {}", e));
+                    Err(e)
+                }
+                Err(e) => {
+                    logger::log_error(&format!("[Hygiene] Maintenance task failed: {}", e));
+                    Err(e.to_string())
+                }
+            };
+            scheduler::record_job_run(JOB_ID, started, result);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{DeviceProfile, DeviceProfileVersion, TokenData};
+
+    fn version(id: &str, created_at: i64, is_current: bool) -> DeviceProfileVersion {
+        DeviceProfileVersion {
+            id: id.to_string(),
+            created_at,
+            label: id.to_string(),
+            profile: DeviceProfile {
+                machine_id: id.to_string(),
+                mac_machine_id: String::new(),
+                dev_device_id: String::new(),
+                sqm_id: String::new(),
+                locale: None,
+                timezone: None,
+                os_version: None,
+            },
+            is_current,
+        }
+    }
+
+    #[test]
+    fn test_hygiene_steps() {
+        let token = TokenData::new("a".to_string(), "r".to_string(), 3600, None, None, None);
+        let mut acc = Account::new("id".to_string(), "a@x.com".to_string(), token);
+
+        acc.validation_blocked = true;
+        acc.validation_blocked_until = Some(200);
+        assert!(!clear_expired_cooldown(&mut acc, 100));
+        assert!(clear_expired_cooldown(&mut acc, 200));
+        assert!(!acc.validation_blocked && acc.validation_blocked_until.is_none());
+
+        // 当前版本 (最旧) 始终保留，其余保留最新的
+        acc.device_history = vec![
+            version("cur", 1, true),
+            version("v2", 2, false),
+            version("v3", 3, false),
+            version("v4", 4, false),
+        ];
+        assert_eq!(compact_device_history(&mut acc, 2), 2);
+        let ids: Vec<&str> = acc.device_history.iter().map(|v| v.id.as_str()).collect();
+        assert_eq!(ids, vec!["cur", "v4"]);
+        assert_eq!(compact_device_history(&mut acc, 2), 0);
+    }
+}
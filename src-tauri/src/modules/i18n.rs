@@ -1,5 +1,6 @@
+use serde::Serialize;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::path::PathBuf;
 
 /// Tray text structure
 #[derive(Debug, Clone)]
@@ -7,51 +8,484 @@ pub struct TrayTexts {
     pub current: String,
     pub quota: String,
     pub switch_next: String,
+    /// "{email}" placeholder
+    pub switch_back: String,
     pub refresh_current: String,
     pub show_window: String,
     pub quit: String,
     pub no_account: String,
     pub unknown_quota: String,
     pub forbidden: String,
+    /// Tooltip templates ({port} / {count} / {model} / {percentage} placeholders)
+    pub proxy_running: String,
+    pub proxy_stopped: String,
+    pub recent_requests: String,
+    pub lowest_quota: String,
+    pub recent_events: String,
+    pub no_recent_events: String,
 }
 
-/// Load translations from JSON
-fn load_translations(lang: &str) -> HashMap<String, String> {
-    let json_content = match lang {
-        "en" | "en-US" => include_str!("../../../src/locales/en.json"),
-        "tr" | "tr-TR" => include_str!("../../../src/locales/tr.json"),
-        _ => include_str!("../../../src/locales/zh.json"),
+/// Locales bundled into the binary (same files as the frontend): (code, display name, JSON)
+const BUILTIN_LOCALES: &[(&str, &str, &str)] = &[
+    ("zh", "简体中文", include_str!("../../../src/locales/zh.json")),
+    ("zh-TW", "繁體中文", include_str!("../../../src/locales/zh-TW.json")),
+    ("en", "English", include_str!("../../../src/locales/en.json")),
+    ("ja", "日本語", include_str!("../../../src/locales/ja.json")),
+    ("tr", "Türkçe", include_str!("../../../src/locales/tr.json")),
+    ("vi", "Tiếng Việt", include_str!("../../../src/locales/vi.json")),
+    ("pt", "Português", include_str!("../../../src/locales/pt.json")),
+    ("ko", "한국어", include_str!("../../../src/locales/ko.json")),
+    ("ru", "Русский", include_str!("../../../src/locales/ru.json")),
+    ("ar", "العربية", include_str!("../../../src/locales/ar.json")),
+    ("es", "Español", include_str!("../../../src/locales/es.json")),
+    ("my", "Bahasa Melayu", include_str!("../../../src/locales/my.json")),
+];
+
+/// Language used when nothing matches (historical default)
+const DEFAULT_LANGUAGE: &str = "zh";
+/// Optional display name key inside user locale files
+const LANGUAGE_NAME_KEY: &str = "_language_name";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LanguageInfo {
+    pub code: String,
+    pub name: String,
+    /// Bundled with the application
+    pub builtin: bool,
+    /// A user file in `<data_dir>/locales/` adds or overrides strings
+    pub user_provided: bool,
+}
+
+/// User locale directory: `<data_dir>/locales/<code>.json`
+/// Files here override bundled strings key by key, or add new (community) languages.
+fn user_locales_dir() -> Option<PathBuf> {
+    crate::modules::account::get_data_dir()
+        .ok()
+        .map(|dir| dir.join("locales"))
+}
+
+fn builtin_locale(code: &str) -> Option<&'static (&'static str, &'static str, &'static str)> {
+    BUILTIN_LOCALES.iter().find(|(c, _, _)| *c == code)
+}
+
+fn user_locale_path(code: &str) -> Option<PathBuf> {
+    // 只接受简单的语言代码，避免路径穿越
+    if code.is_empty() || !code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return None;
+    }
+    let path = user_locales_dir()?.join(format!("{}.json", code));
+    path.is_file().then_some(path)
+}
+
+fn read_user_locale(code: &str) -> Option<Value> {
+    let path = user_locale_path(code)?;
+    let content = std::fs::read_to_string(&path).ok()?;
+    match serde_json::from_str::<Value>(&content) {
+        Ok(v) if v.is_object() => Some(v),
+        _ => {
+            crate::modules::logger::log_warn(&format!(
+                "[i18n] Ignoring invalid locale file: {}",
+                path.display()
+            ));
+            None
+        }
+    }
+}
+
+/// Map a requested language to an available locale code
+/// ("en-US" -> "en", "zh-CN" -> "zh", "ms-MY" -> "my", unknown -> default)
+fn resolve_code(lang: &str) -> String {
+    let lang = lang.trim();
+    if builtin_locale(lang).is_some() || user_locale_path(lang).is_some() {
+        return lang.to_string();
+    }
+    let alias = match lang {
+        "zh-CN" | "zh-Hans" => Some("zh"),
+        "zh-HK" | "zh-Hant" => Some("zh-TW"),
+        "ms" | "ms-MY" => Some("my"),
+        _ => None,
     };
-    
-    let v: Value = serde_json::from_str(json_content)
-        .unwrap_or_else(|_| serde_json::json!({}));
-    
-    let mut map = HashMap::new();
-    
-    if let Some(tray) = v.get("tray").and_then(|t| t.as_object()) {
-        for (key, value) in tray {
-            if let Some(s) = value.as_str() {
-                map.insert(key.clone(), s.to_string());
+    if let Some(code) = alias {
+        return code.to_string();
+    }
+    let base = lang.split(['-', '_']).next().unwrap_or(lang);
+    if builtin_locale(base).is_some() || user_locale_path(base).is_some() {
+        return base.to_string();
+    }
+    DEFAULT_LANGUAGE.to_string()
+}
+
+/// Recursively overlay `overlay` onto `base` (objects merged key by key, other values replaced)
+fn merge_json(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base_map), Value::Object(overlay_map)) => {
+            for (key, value) in overlay_map {
+                match base_map.get_mut(&key) {
+                    Some(existing) => merge_json(existing, value),
+                    None => {
+                        base_map.insert(key, value);
+                    }
+                }
             }
         }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Full message tree for a language: bundled strings overlaid with the user file
+pub fn get_locale_messages(lang: &str) -> Value {
+    let code = resolve_code(lang);
+    let mut messages = builtin_locale(&code)
+        .and_then(|(_, _, json)| serde_json::from_str(json).ok())
+        .unwrap_or_else(|| serde_json::json!({}));
+    if let Some(user) = read_user_locale(&code) {
+        merge_json(&mut messages, user);
+    }
+    messages
+}
+
+fn lookup<'a>(messages: &'a Value, key: &str) -> Option<&'a str> {
+    key.split('.')
+        .try_fold(messages, |node, part| node.get(part))
+        .and_then(|v| v.as_str())
+}
+
+/// Translate a dotted key (e.g. "tray.quit"), falling back to English
+pub fn translate(lang: &str, key: &str) -> Option<String> {
+    let messages = get_locale_messages(lang);
+    if let Some(s) = lookup(&messages, key) {
+        return Some(s.to_string());
+    }
+    let english = get_locale_messages("en");
+    lookup(&english, key).map(str::to_string)
+}
+
+/// Fill i18next-style `{{name}}` placeholders
+fn interpolate(template: &str, params: &std::collections::BTreeMap<String, String>) -> String {
+    params.iter().fold(template.to_string(), |text, (key, value)| {
+        text.replace(&format!("{{{{{}}}}}", key), value)
+    })
+}
+
+/// User-facing message for a command error (`errors.backend.<code>`);
+/// falls back to the technical message when the code has no translation
+pub fn localize_error(lang: &str, error: &crate::error::CommandError) -> String {
+    match translate(lang, &format!("errors.backend.{}", error.code)) {
+        Some(template) => interpolate(&template, &error.params),
+        None => error.message.clone(),
+    }
+}
+
+/// Same as `localize_error` for a raw `code: detail` error string
+pub fn localize_error_str(lang: &str, error: &str) -> String {
+    localize_error(lang, &crate::error::CommandError::from(error))
+}
+
+/// Bundled languages plus community translations dropped into `<data_dir>/locales/`
+pub fn list_available_languages() -> Vec<LanguageInfo> {
+    let mut languages: Vec<LanguageInfo> = BUILTIN_LOCALES
+        .iter()
+        .map(|(code, name, _)| LanguageInfo {
+            code: code.to_string(),
+            name: name.to_string(),
+            builtin: true,
+            user_provided: false,
+        })
+        .collect();
+
+    let Some(dir) = user_locales_dir() else {
+        return languages;
+    };
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return languages;
+    };
+
+    let mut extra: Vec<LanguageInfo> = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let Some(code) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let Some(messages) = read_user_locale(code) else {
+            continue;
+        };
+        if let Some(existing) = languages.iter_mut().find(|l| l.code == code) {
+            existing.user_provided = true;
+            continue;
+        }
+        let name = messages
+            .get(LANGUAGE_NAME_KEY)
+            .and_then(|v| v.as_str())
+            .unwrap_or(code)
+            .to_string();
+        extra.push(LanguageInfo {
+            code: code.to_string(),
+            name,
+            builtin: false,
+            user_provided: true,
+        });
+    }
+    extra.sort_by(|a, b| a.code.cmp(&b.code));
+    languages.extend(extra);
+    languages
+}
+
+const ELLIPSIS: char = '…';
+/// Unicode First Strong Isolate / Pop Directional Isolate
+const FSI: char = '\u{2068}';
+const PDI: char = '\u{2069}';
+
+/// Languages written right-to-left
+pub fn is_rtl(lang: &str) -> bool {
+    let base = lang.split(['-', '_']).next().unwrap_or(lang);
+    matches!(base, "ar" | "he" | "fa" | "ur")
+}
+
+/// Display width of a character in columns: 0 for combining marks / format characters,
+/// 2 for CJK, Hangul, fullwidth forms and emoji, 1 otherwise
+fn char_width(c: char) -> usize {
+    match c as u32 {
+        0x0300..=0x036F | 0x0610..=0x061A | 0x064B..=0x065F | 0x0670 | 0x06D6..=0x06ED
+        | 0x200B..=0x200F | 0x202A..=0x202E | 0x2066..=0x2069 | 0xFE00..=0xFE0F => 0,
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x1F900..=0x1F9FF
+        | 0x20000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
+
+/// Display width of a string in columns
+pub fn display_width(s: &str) -> usize {
+    s.chars().map(char_width).sum()
+}
+
+/// Split into clusters (base character + following zero-width marks) so truncation
+/// never separates Arabic harakat or combining accents from their base
+fn clusters(s: &str) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        if i > start && char_width(c) > 0 {
+            out.push(&s[start..i]);
+            start = i;
+        }
     }
-    
-    map
+    if start < s.len() {
+        out.push(&s[start..]);
+    }
+    out
+}
+
+/// Longest prefix (or suffix when `from_end`) that fits in `width` columns
+fn take_width(s: &str, width: usize, from_end: bool) -> String {
+    let parts = clusters(s);
+    let mut used = 0;
+    let mut taken = Vec::new();
+    let iter: Box<dyn Iterator<Item = &&str>> = if from_end {
+        Box::new(parts.iter().rev())
+    } else {
+        Box::new(parts.iter())
+    };
+    for part in iter {
+        let w = display_width(part);
+        if used + w > width {
+            break;
+        }
+        used += w;
+        taken.push(*part);
+    }
+    if from_end {
+        taken.reverse();
+    }
+    taken.concat()
+}
+
+/// Truncate to at most `max_width` columns, ending with "…"
+pub fn truncate_text(s: &str, max_width: usize) -> String {
+    if display_width(s) <= max_width {
+        return s.to_string();
+    }
+    if max_width == 0 {
+        return String::new();
+    }
+    let mut out = take_width(s, max_width - 1, false).trim_end().to_string();
+    out.push(ELLIPSIS);
+    out
+}
+
+/// Truncate an email in the middle of the local part, keeping "@domain" visible
+/// ("averyveryverylongname@gmail.com" -> "avery…name@gmail.com")
+pub fn truncate_email(email: &str, max_width: usize) -> String {
+    if display_width(email) <= max_width {
+        return email.to_string();
+    }
+    let Some((local, domain)) = email.rsplit_once('@') else {
+        return truncate_text(email, max_width);
+    };
+    let domain_width = display_width(domain) + 1;
+    // 域名本身放不下 (至少保留 "a…b") 时退化为末尾截断
+    if domain_width + 3 > max_width {
+        return truncate_text(email, max_width);
+    }
+    let budget = max_width - domain_width - 1;
+    let head = take_width(local, budget.div_ceil(2), false);
+    let tail = take_width(local, budget - display_width(&head), true);
+    format!("{}{}{}@{}", head, ELLIPSIS, tail, domain)
+}
+
+/// Wrap left-to-right content (emails, model names) in a bidi isolate when shown in an RTL locale,
+/// so punctuation such as ':' / '@' / '%' is not reordered around it
+pub fn isolate_for(lang: &str, s: &str) -> String {
+    if is_rtl(lang) {
+        format!("{}{}{}", FSI, s, PDI)
+    } else {
+        s.to_string()
+    }
+}
+
+/// "<label>: <value>" fitted to `max_width`; the value is shortened first (emails in the middle
+/// when `email_middle_ellipsis` is set), then the whole line if the label alone is too long
+pub fn tray_line(lang: &str, label: &str, value: &str, max_width: usize, email_middle_ellipsis: bool) -> String {
+    let prefix_width = display_width(label) + 2;
+    let value_budget = max_width.saturating_sub(prefix_width);
+    if value_budget < 4 {
+        return truncate_text(&format!("{}: {}", label, value), max_width);
+    }
+    let value = if email_middle_ellipsis && value.contains('@') {
+        truncate_email(value, value_budget)
+    } else {
+        truncate_text(value, value_budget)
+    };
+    format!("{}: {}", label, isolate_for(lang, &value))
 }
 
 /// Get tray texts (based on language)
 pub fn get_tray_texts(lang: &str) -> TrayTexts {
-    let t = load_translations(lang);
-    
+    let messages = get_locale_messages(lang);
+    let english = get_locale_messages("en");
+    let t = |key: &str, fallback: &str| -> String {
+        let path = format!("tray.{}", key);
+        lookup(&messages, &path)
+            .or_else(|| lookup(&english, &path))
+            .unwrap_or(fallback)
+            .to_string()
+    };
+
     TrayTexts {
-        current: t.get("current").cloned().unwrap_or_else(|| "Current".to_string()),
-        quota: t.get("quota").cloned().unwrap_or_else(|| "Quota".to_string()),
-        switch_next: t.get("switch_next").cloned().unwrap_or_else(|| "Switch to Next Account".to_string()),
-        refresh_current: t.get("refresh_current").cloned().unwrap_or_else(|| "Refresh Current Quota".to_string()),
-        show_window: t.get("show_window").cloned().unwrap_or_else(|| "Show Main Window".to_string()),
-        quit: t.get("quit").cloned().unwrap_or_else(|| "Quit Application".to_string()),
-        no_account: t.get("no_account").cloned().unwrap_or_else(|| "No Account".to_string()),
-        unknown_quota: t.get("unknown_quota").cloned().unwrap_or_else(|| "Unknown".to_string()),
-        forbidden: t.get("forbidden").cloned().unwrap_or_else(|| "Account Forbidden".to_string()),
+        current: t("current", "Current"),
+        quota: t("quota", "Quota"),
+        switch_next: t("switch_next", "Switch to Next Account"),
+        switch_back: t("switch_back", "Switch to Previous ({email})"),
+        refresh_current: t("refresh_current", "Refresh Current Quota"),
+        show_window: t("show_window", "Show Main Window"),
+        quit: t("quit", "Quit Application"),
+        no_account: t("no_account", "No Account"),
+        unknown_quota: t("unknown_quota", "Unknown"),
+        forbidden: t("forbidden", "Account Forbidden"),
+        proxy_running: t("proxy_running", "Proxy running on port {port}"),
+        proxy_stopped: t("proxy_stopped", "Proxy stopped"),
+        recent_requests: t("recent_requests", "Requests (last 5 min): {count}"),
+        lowest_quota: t("lowest_quota", "Lowest quota: {model} {percentage}%"),
+        recent_events: t("recent_events", "Recent Events"),
+        no_recent_events: t("no_recent_events", "No recent events"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_locales_parse() {
+        for (code, _, json) in BUILTIN_LOCALES {
+            let v: Value = serde_json::from_str(json).unwrap_or_else(|e| panic!("{}: {}", code, e));
+            assert!(v.get("tray").is_some(), "{} is missing the tray section", code);
+        }
+    }
+
+    #[test]
+    fn test_merge_json_overrides_leaves() {
+        let mut base = serde_json::json!({ "tray": { "quit": "Quit", "current": "Current" } });
+        merge_json(
+            &mut base,
+            serde_json::json!({ "tray": { "quit": "Exit" }, "extra": "x" }),
+        );
+        assert_eq!(lookup(&base, "tray.quit"), Some("Exit"));
+        assert_eq!(lookup(&base, "tray.current"), Some("Current"));
+        assert_eq!(lookup(&base, "extra"), Some("x"));
+        assert_eq!(lookup(&base, "tray.missing"), None);
+    }
+
+    #[test]
+    fn test_localize_error() {
+        let err = crate::error::CommandError::from("failed_to_read_account_index: denied");
+        assert_eq!(
+            localize_error("en", &err),
+            "Could not read the account list (denied)"
+        );
+        // 没有翻译的错误码保留原文
+        assert_eq!(
+            localize_error_str("en", "some_new_code: details"),
+            "some_new_code: details"
+        );
+    }
+
+    #[test]
+    fn test_resolve_code_aliases() {
+        assert_eq!(resolve_code("en-US"), "en");
+        assert_eq!(resolve_code("zh-CN"), "zh");
+        assert_eq!(resolve_code("zh-TW"), "zh-TW");
+        assert_eq!(resolve_code("ms-MY"), "my");
+        assert_eq!(resolve_code("pt-BR"), "pt");
+    }
+
+    #[test]
+    fn test_truncate_text_is_width_aware() {
+        assert_eq!(truncate_text("Quota", 10), "Quota");
+        assert_eq!(truncate_text("Switch to Next Account", 10), "Switch to…");
+        // 中日韩字符占两列
+        assert_eq!(display_width("切换账号"), 8);
+        assert_eq!(truncate_text("切换到下一个账号", 7), "切换到…");
+        // 不拆开阿拉伯文的附加符号
+        let arabic = "مُحَمَّد";
+        assert_eq!(display_width(arabic), 4);
+        assert_eq!(truncate_text(arabic, 3), "مُحَ…");
+    }
+
+    #[test]
+    fn test_truncate_email_keeps_domain() {
+        assert_eq!(truncate_email("a@b.com", 20), "a@b.com");
+        assert_eq!(
+            truncate_email("averyveryverylongname@gmail.com", 20),
+            "avery…name@gmail.com"
+        );
+        assert_eq!(display_width(&truncate_email("averyveryverylongname@gmail.com", 20)), 20);
+        // 域名过长时退化为末尾截断
+        assert_eq!(truncate_email("me@a-very-long-company-domain.example", 12), "me@a-very-l…");
+    }
+
+    #[test]
+    fn test_tray_line_rtl_isolates_value() {
+        let line = tray_line("ar", "الحالي", "user@gmail.com", 40, true);
+        assert!(line.contains(FSI) && line.ends_with(PDI));
+        assert_eq!(tray_line("en", "Current", "user@gmail.com", 40, true), "Current: user@gmail.com");
+        assert_eq!(
+            tray_line("en", "Current", "averyveryverylongname@gmail.com", 29, true),
+            "Current: avery…name@gmail.com"
+        );
     }
 }
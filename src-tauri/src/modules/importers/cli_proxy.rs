@@ -0,0 +1,36 @@
+// CLIProxyAPI / gcli2api 一类代理工具的凭据: 每个账号一个对象 (`type` + `email` + 嵌套 `token`)，
+// 单文件为一个对象，合并导出时为数组
+use serde_json::Value;
+
+use super::{account_of, ImportedAccount, Importer};
+
+pub struct CliProxyImporter;
+
+fn is_entry(obj: &Value) -> bool {
+    obj.get("type").is_some_and(Value::is_string)
+        && obj
+            .get("token")
+            .is_some_and(|t| t.get("refresh_token").is_some_and(Value::is_string))
+}
+
+fn entries(value: &Value) -> Vec<&Value> {
+    match value {
+        Value::Array(items) => items.iter().collect(),
+        obj => vec![obj],
+    }
+}
+
+impl Importer for CliProxyImporter {
+    fn name(&self) -> &'static str {
+        "cli_proxy"
+    }
+
+    fn detect(&self, value: &Value) -> bool {
+        let entries = entries(value);
+        !entries.is_empty() && entries.iter().all(|e| is_entry(e))
+    }
+
+    fn parse(&self, value: &Value) -> Vec<ImportedAccount> {
+        entries(value).into_iter().filter_map(account_of).collect()
+    }
+}
@@ -0,0 +1,46 @@
+// 以邮箱为键的对象 (antigravity-agent 等工具): `{"a@x.com": {...}}` 或 `{"accounts": {"a@x.com": {...}}}`，
+// 值为包含 refresh_token 的对象或直接为 refresh_token 字符串
+use serde_json::{Map, Value};
+
+use super::{refresh_token_of, ImportedAccount, Importer};
+
+pub struct EmailMapImporter;
+
+fn map_of(value: &Value) -> Option<&Map<String, Value>> {
+    let map = value.as_object()?;
+    let map = map
+        .get("accounts")
+        .and_then(Value::as_object)
+        .unwrap_or(map);
+    (!map.is_empty() && map.keys().all(|k| k.contains('@'))).then_some(map)
+}
+
+impl Importer for EmailMapImporter {
+    fn name(&self) -> &'static str {
+        "email_map"
+    }
+
+    fn detect(&self, value: &Value) -> bool {
+        map_of(value).is_some()
+    }
+
+    fn parse(&self, value: &Value) -> Vec<ImportedAccount> {
+        let Some(map) = map_of(value) else {
+            return Vec::new();
+        };
+        map.iter()
+            .filter_map(|(email, entry)| {
+                let refresh_token = match entry {
+                    Value::String(token) => {
+                        Some(token.trim().to_string()).filter(|t| !t.is_empty())
+                    }
+                    obj => refresh_token_of(obj),
+                }?;
+                Some(ImportedAccount {
+                    email: Some(email.trim().to_string()),
+                    refresh_token,
+                })
+            })
+            .collect()
+    }
+}
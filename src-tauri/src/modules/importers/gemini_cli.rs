@@ -0,0 +1,22 @@
+// Gemini CLI 的 oauth_creds.json (~/.gemini/oauth_creds.json): 单个 OAuth 凭据对象，
+// 没有 email 字段，邮箱从 id_token 中读取
+use serde_json::Value;
+
+use super::{account_of, ImportedAccount, Importer};
+
+pub struct GeminiCliImporter;
+
+impl Importer for GeminiCliImporter {
+    fn name(&self) -> &'static str {
+        "gemini_cli"
+    }
+
+    fn detect(&self, value: &Value) -> bool {
+        value.get("refresh_token").is_some_and(Value::is_string)
+            && (value.get("expiry_date").is_some() || value.get("token_type").is_some())
+    }
+
+    fn parse(&self, value: &Value) -> Vec<ImportedAccount> {
+        account_of(value).into_iter().collect()
+    }
+}
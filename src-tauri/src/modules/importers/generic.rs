@@ -0,0 +1,42 @@
+// 兜底格式: 任意对象数组 (根数组，或根对象下 accounts / data / items / credentials 等数组字段)，
+// 每项按常见字段名 (refresh_token / refreshToken / token.refresh_token ...) 提取
+use serde_json::Value;
+
+use super::{account_of, ImportedAccount, Importer};
+
+pub struct GenericImporter;
+
+const LIST_KEYS: &[&str] = &[
+    "accounts",
+    "data",
+    "items",
+    "credentials",
+    "tokens",
+    "users",
+];
+
+fn items(value: &Value) -> Vec<&Value> {
+    match value {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(map) => LIST_KEYS
+            .iter()
+            .find_map(|k| map.get(*k).and_then(Value::as_array))
+            .map(|items| items.iter().collect())
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+impl Importer for GenericImporter {
+    fn name(&self) -> &'static str {
+        "generic"
+    }
+
+    fn detect(&self, value: &Value) -> bool {
+        items(value).iter().any(|item| account_of(item).is_some())
+    }
+
+    fn parse(&self, value: &Value) -> Vec<ImportedAccount> {
+        items(value).into_iter().filter_map(account_of).collect()
+    }
+}
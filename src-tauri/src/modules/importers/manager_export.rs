@@ -0,0 +1,37 @@
+// 本工具自身的导出格式: `{"accounts": [{"email", "refresh_token"}]}` 或直接为数组
+use serde_json::Value;
+
+use super::{ImportedAccount, Importer};
+use crate::models::{AccountExportItem, AccountExportResponse};
+
+pub struct ManagerExportImporter;
+
+fn items(value: &Value) -> Option<Vec<AccountExportItem>> {
+    serde_json::from_value::<AccountExportResponse>(value.clone())
+        .map(|export| export.accounts)
+        .or_else(|_| serde_json::from_value::<Vec<AccountExportItem>>(value.clone()))
+        .ok()
+        .filter(|items| !items.is_empty())
+}
+
+impl Importer for ManagerExportImporter {
+    fn name(&self) -> &'static str {
+        "antigravity_manager"
+    }
+
+    fn detect(&self, value: &Value) -> bool {
+        items(value).is_some()
+    }
+
+    fn parse(&self, value: &Value) -> Vec<ImportedAccount> {
+        items(value)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|item| !item.refresh_token.trim().is_empty())
+            .map(|item| ImportedAccount {
+                email: Some(item.email).filter(|e| e.contains('@')),
+                refresh_token: item.refresh_token.trim().to_string(),
+            })
+            .collect()
+    }
+}
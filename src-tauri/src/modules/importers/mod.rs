@@ -0,0 +1,220 @@
+// 第三方工具账号导入
+// 其他 Gemini / Antigravity 多账号工具的 Token 存储格式 (JSON)，自动识别格式后提取 email + refresh_token，
+// 迁移时无需手工转换文件。每种格式一个文件，实现 Importer；识别按 IMPORTERS 顺序进行，越具体的格式越靠前，
+// generic 作为兜底。导入本身仍走 AccountService::add_account (用 refresh_token 换取 access_token 并校验账号)。
+
+pub mod cli_proxy;
+pub mod email_map;
+pub mod gemini_cli;
+pub mod generic;
+pub mod manager_export;
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// 从第三方文件中提取的账号
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedAccount {
+    pub email: Option<String>,
+    pub refresh_token: String,
+}
+
+/// 单种存储格式
+pub trait Importer: Send + Sync {
+    /// 格式 ID (返回给前端展示)
+    fn name(&self) -> &'static str;
+
+    /// 是否为该格式
+    fn detect(&self, value: &Value) -> bool;
+
+    /// 提取账号 (detect 为 true 时调用)
+    fn parse(&self, value: &Value) -> Vec<ImportedAccount>;
+}
+
+const IMPORTERS: &[&dyn Importer] = &[
+    &manager_export::ManagerExportImporter,
+    &gemini_cli::GeminiCliImporter,
+    &cli_proxy::CliProxyImporter,
+    &email_map::EmailMapImporter,
+    &generic::GenericImporter,
+];
+
+/// 识别结果 (不含 refresh_token，供导入前预览)
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportPreview {
+    pub format: String,
+    pub count: usize,
+    pub emails: Vec<Option<String>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ParsedImport {
+    pub format: &'static str,
+    pub accounts: Vec<ImportedAccount>,
+}
+
+impl ParsedImport {
+    pub fn preview(&self) -> ImportPreview {
+        ImportPreview {
+            format: self.format.to_string(),
+            count: self.accounts.len(),
+            emails: self.accounts.iter().map(|a| a.email.clone()).collect(),
+        }
+    }
+}
+
+/// 自动识别格式并提取账号 (按 refresh_token 去重)
+pub fn parse(content: &str) -> Result<ParsedImport, String> {
+    let value: Value = serde_json::from_str(content.trim_start_matches('\u{feff}'))
+        .map_err(|e| format!("failed_to_parse_import_file: {}", e))?;
+    let importer = IMPORTERS.iter().find(|i| i.detect(&value)).ok_or_else(|| {
+        "unknown_import_format: no email + refresh_token entries found".to_string()
+    })?;
+
+    let mut seen = HashSet::new();
+    let accounts: Vec<ImportedAccount> = importer
+        .parse(&value)
+        .into_iter()
+        .filter(|a| seen.insert(a.refresh_token.clone()))
+        .collect();
+    if accounts.is_empty() {
+        return Err(format!(
+            "unknown_import_format: {} file contains no refresh tokens",
+            importer.name()
+        ));
+    }
+    Ok(ParsedImport {
+        format: importer.name(),
+        accounts,
+    })
+}
+
+pub fn parse_file(path: &std::path::Path) -> Result<ParsedImport, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("failed_to_read_import_file: {}: {}", path.display(), e))?;
+    parse(&content)
+}
+
+// ===== 各格式共用的字段提取 =====
+
+const REFRESH_TOKEN_KEYS: &[&str] = &["refresh_token", "refreshToken", "refresh-token"];
+const EMAIL_KEYS: &[&str] = &[
+    "email",
+    "email_address",
+    "emailAddress",
+    "account",
+    "username",
+];
+
+fn str_field<'a>(obj: &'a Value, keys: &[&str]) -> Option<&'a str> {
+    keys.iter()
+        .find_map(|k| obj.get(*k).and_then(Value::as_str))
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+/// 对象自身或常见的嵌套字段 (token / credentials / tokens / auth) 中的 refresh_token
+pub(crate) fn refresh_token_of(obj: &Value) -> Option<String> {
+    str_field(obj, REFRESH_TOKEN_KEYS)
+        .or_else(|| {
+            ["token", "credentials", "tokens", "auth", "oauth"]
+                .iter()
+                .filter_map(|k| obj.get(*k))
+                .find_map(|nested| str_field(nested, REFRESH_TOKEN_KEYS))
+        })
+        .map(str::to_string)
+}
+
+/// 对象中的邮箱 (需包含 @)，找不到时尝试解析 id_token
+pub(crate) fn email_of(obj: &Value) -> Option<String> {
+    str_field(obj, EMAIL_KEYS)
+        .filter(|s| s.contains('@'))
+        .map(str::to_string)
+        .or_else(|| {
+            obj.get("user")
+                .and_then(|u| str_field(u, EMAIL_KEYS))
+                .filter(|s| s.contains('@'))
+                .map(str::to_string)
+        })
+        .or_else(|| {
+            str_field(obj, &["id_token"])
+                .or_else(|| obj.get("token").and_then(|t| str_field(t, &["id_token"])))
+                .and_then(email_from_id_token)
+        })
+}
+
+/// 从 Google id_token (JWT) 的 payload 中读取 email，不校验签名
+fn email_from_id_token(id_token: &str) -> Option<String> {
+    use base64::Engine as _;
+    let payload = id_token.split('.').nth(1)?;
+    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(payload.trim_end_matches('='))
+        .ok()?;
+    let claims: Value = serde_json::from_slice(&bytes).ok()?;
+    claims
+        .get("email")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+/// 对象本身即可导入时转换为账号
+pub(crate) fn account_of(obj: &Value) -> Option<ImportedAccount> {
+    Some(ImportedAccount {
+        refresh_token: refresh_token_of(obj)?,
+        email: email_of(obj),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(content: &str) -> (&'static str, Vec<ImportedAccount>) {
+        let parsed = parse(content).unwrap();
+        (parsed.format, parsed.accounts)
+    }
+
+    #[test]
+    fn test_format_detection() {
+        let (format, accounts) =
+            detect(r#"{"accounts":[{"email":"a@x.com","refresh_token":"1//a"}],"exported_at":1}"#);
+        assert_eq!(format, "antigravity_manager");
+        assert_eq!(accounts[0].email.as_deref(), Some("a@x.com"));
+
+        // header.{"email":"cli@x.com"}.signature
+        let (format, accounts) = detect(
+            r#"{"access_token":"ya29","refresh_token":"1//cli","token_type":"Bearer","expiry_date":1,
+                "id_token":"e30.eyJlbWFpbCI6ImNsaUB4LmNvbSJ9.sig"}"#,
+        );
+        assert_eq!(format, "gemini_cli");
+        assert_eq!(accounts[0].email.as_deref(), Some("cli@x.com"));
+
+        let (format, accounts) = detect(
+            r#"[{"type":"gemini","email":"p@x.com","project_id":"p1","token":{"refresh_token":"1//p"}}]"#,
+        );
+        assert_eq!(format, "cli_proxy");
+        assert_eq!(accounts[0].refresh_token, "1//p");
+
+        let (format, accounts) = detect(
+            r#"{"accounts":{"m@x.com":{"refreshToken":"1//m"},"n@x.com":{"token":{"refresh_token":"1//n"}}}}"#,
+        );
+        assert_eq!(format, "email_map");
+        assert_eq!(accounts.len(), 2);
+
+        let (format, accounts) = detect(
+            r#"{"data":[{"user":{"email":"g@x.com"},"credentials":{"refreshToken":"1//g"}},
+                        {"account":"h@x.com","refresh-token":"1//g"}]}"#,
+        );
+        assert_eq!(format, "generic");
+        // 按 refresh_token 去重
+        assert_eq!(accounts.len(), 1);
+
+        assert!(parse(r#"{"foo":"bar"}"#)
+            .unwrap_err()
+            .starts_with("unknown_import_format"));
+        assert!(parse("not json")
+            .unwrap_err()
+            .starts_with("failed_to_parse_import_file"));
+    }
+}
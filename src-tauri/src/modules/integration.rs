@@ -1,10 +1,17 @@
 use crate::modules::{process, db, device};
-use crate::models::Account;
+use crate::models::{Account, SwitchCommandConfig, SwitchPhase, SwitchReport};
 use std::fs;
+use std::path::{Path, PathBuf};
 
 pub trait SystemIntegration: Send + Sync {
     /// 当切换账号时执行的系统层操作（如杀进程、写入文件、注入数据库）
-    async fn on_account_switch(&self, account: &crate::models::Account) -> Result<(), String>;
+    /// 每个阶段记录到 report；失败时实现方负责回滚自己已做的修改，`previous` 为切换前的账号
+    async fn on_account_switch(
+        &self,
+        account: &Account,
+        previous: Option<&Account>,
+        report: &mut SwitchReport,
+    ) -> Result<(), String>;
     
     /// 更新系统托盘（如果适用）
     fn update_tray(&self);
@@ -13,47 +20,191 @@ pub trait SystemIntegration: Send + Sync {
     fn show_notification(&self, title: &str, body: &str);
 }
 
+/// 切换前的 storage.json 内容与 state.vscdb 备份，用于失败回滚
+#[cfg(feature = "desktop")]
+struct SwitchSnapshot {
+    storage_path: PathBuf,
+    storage: Option<Vec<u8>>,
+    db_path: PathBuf,
+    db_backup: Option<PathBuf>,
+}
+
+#[cfg(feature = "desktop")]
+impl SwitchSnapshot {
+    fn take(storage_path: PathBuf, db_path: PathBuf) -> Result<Self, String> {
+        let storage = fs::read(&storage_path).ok();
+        let db_backup = if db_path.exists() {
+            let backup_path = db_path.with_extension("vscdb.backup");
+            fs::copy(&db_path, &backup_path)
+                .map_err(|e| format!("failed_to_backup_state_db: {}", e))?;
+            Some(backup_path)
+        } else {
+            None
+        };
+        Ok(Self {
+            storage_path,
+            storage,
+            db_path,
+            db_backup,
+        })
+    }
+
+    fn restore(&self) -> Result<(), String> {
+        if let Some(ref storage) = self.storage {
+            fs::write(&self.storage_path, storage)
+                .map_err(|e| format!("failed_to_restore_storage: {}", e))?;
+        }
+        if let Some(ref backup) = self.db_backup {
+            fs::copy(backup, &self.db_path)
+                .map_err(|e| format!("failed_to_restore_state_db: {}", e))?;
+        }
+        Ok(())
+    }
+}
+
 /// 桌面版实现：包含完整的进程控制和 UI 同步
+#[cfg(feature = "desktop")]
 pub struct DesktopIntegration {
     pub app_handle: tauri::AppHandle,
 }
 
-impl SystemIntegration for DesktopIntegration {
-    async fn on_account_switch(&self, account: &crate::models::Account) -> Result<(), String> {
-        crate::modules::logger::log_info(&format!("[Desktop] Executing system switch for: {}", account.email));
-        
-        // 1. 获取存储路径
-        let storage_path = device::get_storage_path()?;
+#[cfg(feature = "desktop")]
+impl DesktopIntegration {
+    /// 账号对应的安装副本 (账号设置优先，其次全局设置)
+    fn target_executable(account: &Account) -> Option<String> {
+        account.antigravity_executable.clone().or_else(|| {
+            crate::modules::config::load_app_config()
+                .ok()
+                .and_then(|c| c.antigravity_executable)
+        })
+    }
 
-        // 2. 关闭外部进程
-        if process::is_antigravity_running() {
-            process::close_antigravity(20)?;
+    /// 定位 storage.json / state.vscdb (账号参数指定了 --user-data-dir 时优先使用该目录)
+    fn resolve_paths(account: &Account) -> Result<(PathBuf, PathBuf), String> {
+        let account_storage = account
+            .antigravity_args
+            .as_deref()
+            .and_then(process::user_data_dir_from_args)
+            .map(|dir| dir.join("User").join("globalStorage"));
+        let storage_path = match account_storage
+            .as_ref()
+            .map(|dir| dir.join("storage.json"))
+            .filter(|p| p.exists())
+        {
+            Some(path) => path,
+            None => device::get_storage_path()?,
+        };
+        let db_path = match account_storage
+            .as_ref()
+            .map(|dir| dir.join("state.vscdb"))
+            .filter(|p| p.exists())
+        {
+            Some(path) => path,
+            None => db::get_db_path()?,
+        };
+        Ok((storage_path, db_path))
+    }
+
+    /// 关闭进程之后的各阶段；任一阶段失败即返回，由调用方回滚
+    fn apply(
+        account: &Account,
+        snapshot: &SwitchSnapshot,
+        target: Option<&Path>,
+        report: &mut SwitchReport,
+    ) -> Result<(), String> {
+        // 关闭外部进程 (只关闭该账号对应的安装副本，其他副本不受影响)
+        if process::is_antigravity_running_for(target) {
+            report.track(
+                SwitchPhase::CloseProcess,
+                process::close_antigravity_for(20, target),
+            )?;
+        } else {
+            report.skip(SwitchPhase::CloseProcess, "not running");
         }
 
-        // 3. 写入设备 Profile
-        if let Some(ref profile) = account.device_profile {
-            device::write_profile(&storage_path, profile)?;
+        // 写入设备 Profile
+        match account.device_profile {
+            Some(ref profile) => report.track(
+                SwitchPhase::WriteProfile,
+                device::write_profile(&snapshot.storage_path, profile),
+            )?,
+            None => report.skip(SwitchPhase::WriteProfile, "no bound profile"),
         }
 
-        // 4. 数据库处理与 Token 注入
-        let db_path = db::get_db_path()?;
-        if db_path.exists() {
-            let backup_path = db_path.with_extension("vscdb.backup");
-            let _ = fs::copy(&db_path, &backup_path);
+        // Token 注入
+        report.track(
+            SwitchPhase::InjectToken,
+            db::inject_token(
+                &snapshot.db_path,
+                &account.token.access_token,
+                &account.token.refresh_token,
+                account.token.expiry_timestamp,
+                &account.email,
+            ),
+        )?;
+
+        // 重启外部进程
+        report.track(
+            SwitchPhase::StartProcess,
+            process::start_antigravity_with(
+                account.antigravity_args.as_deref(),
+                account.antigravity_executable.as_deref(),
+            ),
+        )?;
+        Ok(())
+    }
+
+    /// 恢复切换前的文件；若进程已被关闭但新账号未能启动，则用上一个账号的启动参数重新拉起
+    fn rollback(snapshot: &SwitchSnapshot, previous: Option<&Account>, report: &mut SwitchReport) {
+        let mut result = snapshot.restore();
+        if result.is_ok()
+            && report.is_done(SwitchPhase::CloseProcess)
+            && !report.is_done(SwitchPhase::StartProcess)
+        {
+            result = process::start_antigravity_with(
+                previous.and_then(|p| p.antigravity_args.as_deref()),
+                previous.and_then(|p| p.antigravity_executable.as_deref()),
+            );
+        }
+        match report.track(SwitchPhase::Rollback, result) {
+            Ok(()) => {
+                report.rolled_back = true;
+                crate::modules::logger::log_warn("[Desktop] Account switch rolled back");
+            }
+            Err(e) => crate::modules::logger::log_error(&format!(
+                "[Desktop] Rollback after failed switch also failed: {}",
+                e
+            )),
         }
+    }
+}
+
+#[cfg(feature = "desktop")]
+impl SystemIntegration for DesktopIntegration {
+    async fn on_account_switch(
+        &self,
+        account: &Account,
+        previous: Option<&Account>,
+        report: &mut SwitchReport,
+    ) -> Result<(), String> {
+        crate::modules::logger::log_info(&format!("[Desktop] Executing system switch for: {}", account.email));
         
-        db::inject_token(
-            &db_path,
-            &account.token.access_token,
-            &account.token.refresh_token,
-            account.token.expiry_timestamp,
-            &account.email,
+        // 1. 定位文件并备份 (此阶段之前失败不需要回滚)
+        let snapshot = report.track(
+            SwitchPhase::Snapshot,
+            Self::resolve_paths(account)
+                .and_then(|(storage_path, db_path)| SwitchSnapshot::take(storage_path, db_path)),
         )?;
 
-        // 5. 重启外部进程
-        process::start_antigravity()?;
+        // 2. 关闭进程 / 写入 Profile / 注入 Token / 重启进程，失败则回滚
+        let executable = Self::target_executable(account);
+        let target = executable.as_deref().map(Path::new);
+        if let Err(e) = Self::apply(account, &snapshot, target, report) {
+            Self::rollback(&snapshot, previous, report);
+            return Err(e);
+        }
         
-        // 6. 更新托盘
+        // 3. 更新托盘
         let _ = crate::modules::tray::update_tray_menus(&self.app_handle);
         
         Ok(())
@@ -73,7 +224,12 @@ impl SystemIntegration for DesktopIntegration {
 pub struct HeadlessIntegration;
 
 impl SystemIntegration for HeadlessIntegration {
-    async fn on_account_switch(&self, account: &crate::models::Account) -> Result<(), String> {
+    async fn on_account_switch(
+        &self,
+        account: &Account,
+        _previous: Option<&Account>,
+        _report: &mut SwitchReport,
+    ) -> Result<(), String> {
         crate::modules::logger::log_info(&format!("[Headless] Account switched in memory: {}", account.email));
         // Docker 模式下通常不直接控制宿主机的 VS Code 进程
         // 如果需要同步配置到某个 volume，可以在此处添加逻辑
@@ -88,28 +244,132 @@ impl SystemIntegration for HeadlessIntegration {
         crate::modules::logger::log_info(&format!("[Log Notification] {}: {}", title, body));
     }
 }
+/// 外部命令实现：切换账号时运行用户配置的脚本 / 程序 (config.switch_command)，
+/// 账号 JSON 通过 stdin 传入，适用于远程 VM、容器内运行的 Antigravity 等自定义场景。
+/// 命令以非零状态退出或超时即视为切换失败 (stderr 会附在错误信息中)。
+pub struct CommandIntegration {
+    pub config: SwitchCommandConfig,
+}
+
+impl CommandIntegration {
+    /// 已启用且配置了命令时返回
+    pub fn from_config() -> Option<Self> {
+        let config = crate::modules::config::load_app_config().ok()?.switch_command;
+        (config.enabled && !config.command.trim().is_empty()).then_some(Self { config })
+    }
+}
+
+impl SystemIntegration for CommandIntegration {
+    async fn on_account_switch(
+        &self,
+        account: &Account,
+        _previous: Option<&Account>,
+        report: &mut SwitchReport,
+    ) -> Result<(), String> {
+        report.track(SwitchPhase::ExternalCommand, self.run(account).await)
+    }
+
+    fn update_tray(&self) {
+        // No-op
+    }
+
+    fn show_notification(&self, title: &str, body: &str) {
+        crate::modules::logger::log_info(&format!("[Notification] {}: {}", title, body));
+    }
+}
+
+impl CommandIntegration {
+    async fn run(&self, account: &Account) -> Result<(), String> {
+        use crate::utils::command::CommandExtWrapper;
+        use tokio::io::AsyncWriteExt;
+
+        crate::modules::logger::log_info(&format!(
+            "[Command] Running switch command for: {}",
+            account.email
+        ));
+        let payload = serde_json::to_vec(account)
+            .map_err(|e| format!("switch_command_failed: {}", e))?;
+
+        let mut cmd = tokio::process::Command::new(self.config.command.trim());
+        cmd.args(&self.config.args)
+            .env("ABV_ACCOUNT_ID", &account.id)
+            .env("ABV_ACCOUNT_EMAIL", &account.email)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true)
+            .creation_flags_windows();
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| format!("switch_command_failed: {}: {}", self.config.command, e))?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            // 命令不读取 stdin 时写入会 BrokenPipe，不算失败
+            let _ = stdin.write_all(&payload).await;
+        }
+
+        let timeout = std::time::Duration::from_secs(self.config.timeout_secs.max(1));
+        let output = tokio::time::timeout(timeout, child.wait_with_output())
+            .await
+            .map_err(|_| {
+                format!(
+                    "switch_command_failed: timed out after {}s",
+                    self.config.timeout_secs
+                )
+            })?
+            .map_err(|e| format!("switch_command_failed: {}", e))?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if !stdout.trim().is_empty() {
+            crate::modules::logger::log_info(&format!("[Command] {}", stdout.trim()));
+        }
+        if !output.status.success() {
+            return Err(format!(
+                "switch_command_failed: {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(())
+    }
+}
+
 /// 系统集成管理器：替代 Arc<dyn SystemIntegration> 以解决 async trait 的 dyn 兼容性问题
 #[derive(Clone)]
 pub enum SystemManager {
+    #[cfg(feature = "desktop")]
     Desktop(tauri::AppHandle),
     Headless,
 }
 
 impl SystemManager {
-    pub async fn on_account_switch(&self, account: &Account) -> Result<(), String> {
+    pub async fn on_account_switch(
+        &self,
+        account: &Account,
+        previous: Option<&Account>,
+        report: &mut SwitchReport,
+    ) -> Result<(), String> {
+        // 配置了外部切换命令时由命令接管 (桌面版仍刷新托盘)
+        if let Some(integration) = CommandIntegration::from_config() {
+            integration.on_account_switch(account, previous, report).await?;
+            self.update_tray();
+            return Ok(());
+        }
         match self {
+            #[cfg(feature = "desktop")]
             SystemManager::Desktop(handle) => {
                 let integration = DesktopIntegration { app_handle: handle.clone() };
-                integration.on_account_switch(account).await
+                integration.on_account_switch(account, previous, report).await
             },
             SystemManager::Headless => {
                 let integration = HeadlessIntegration;
-                integration.on_account_switch(account).await
+                integration.on_account_switch(account, previous, report).await
             }
         }
     }
 
     pub fn update_tray(&self) {
+        #[cfg(feature = "desktop")]
         if let SystemManager::Desktop(handle) = self {
             let integration = DesktopIntegration { app_handle: handle.clone() };
             integration.update_tray();
@@ -118,6 +378,7 @@ impl SystemManager {
 
     pub fn show_notification(&self, title: &str, body: &str) {
         match self {
+            #[cfg(feature = "desktop")]
             SystemManager::Desktop(handle) => {
                 let integration = DesktopIntegration { app_handle: handle.clone() };
                 integration.show_notification(title, body);
@@ -128,24 +389,43 @@ impl SystemManager {
             }
         }
     }
-}
 
-impl SystemIntegration for SystemManager {
-    async fn on_account_switch(&self, account: &crate::models::Account) -> Result<(), String> {
+    pub fn is_desktop(&self) -> bool {
+        match self {
+            #[cfg(feature = "desktop")]
+            SystemManager::Desktop(_) => true,
+            SystemManager::Headless => false,
+        }
+    }
+
+    /// 向桌面端前端发送事件 (headless 为空操作)
+    pub fn emit<S: serde::Serialize + Clone>(&self, event: &str, payload: S) {
         match self {
+            #[cfg(feature = "desktop")]
             SystemManager::Desktop(handle) => {
-                let integration = DesktopIntegration { app_handle: handle.clone() };
-                integration.on_account_switch(account).await
-            },
+                use tauri::Emitter;
+                let _ = handle.emit(event, payload);
+            }
             SystemManager::Headless => {
-                let integration = HeadlessIntegration;
-                integration.on_account_switch(account).await
+                let _ = (event, payload);
             }
         }
     }
+}
+
+impl SystemIntegration for SystemManager {
+    async fn on_account_switch(
+        &self,
+        account: &Account,
+        previous: Option<&Account>,
+        report: &mut SwitchReport,
+    ) -> Result<(), String> {
+        SystemManager::on_account_switch(self, account, previous, report).await
+    }
 
     fn update_tray(&self) {
         match self {
+            #[cfg(feature = "desktop")]
             SystemManager::Desktop(handle) => {
                 let integration = DesktopIntegration { app_handle: handle.clone() };
                 integration.update_tray();
@@ -159,6 +439,7 @@ impl SystemIntegration for SystemManager {
 
     fn show_notification(&self, title: &str, body: &str) {
         match self {
+            #[cfg(feature = "desktop")]
             SystemManager::Desktop(handle) => {
                 let integration = DesktopIntegration { app_handle: handle.clone() };
                 integration.show_notification(title, body);
@@ -170,3 +451,70 @@ impl SystemIntegration for SystemManager {
         }
     }
 }
+
+#[cfg(all(test, unix, feature = "desktop"))]
+mod tests {
+    use super::*;
+    use crate::models::TokenData;
+
+    fn integration(script: &str) -> CommandIntegration {
+        CommandIntegration {
+            config: SwitchCommandConfig {
+                enabled: true,
+                command: "sh".to_string(),
+                args: vec!["-c".to_string(), script.to_string()],
+                timeout_secs: 5,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_command_integration_receives_account_json() {
+        let token = TokenData::new(
+            "access".to_string(),
+            "refresh".to_string(),
+            3600,
+            None,
+            None,
+            None,
+        );
+        let account = Account::new("id-1".to_string(), "a@example.com".to_string(), token);
+
+        let mut report = SwitchReport::new(&account.id, &account.email, None);
+        let ok = integration(
+            r#"grep -q '"email":"a@example.com"' && [ "$ABV_ACCOUNT_ID" = "id-1" ]"#,
+        );
+        assert!(ok.on_account_switch(&account, None, &mut report).await.is_ok());
+        assert!(report.is_done(SwitchPhase::ExternalCommand));
+
+        let err = integration("echo 'vm unreachable' >&2; exit 3")
+            .on_account_switch(&account, None, &mut report)
+            .await
+            .unwrap_err();
+        assert!(err.starts_with("switch_command_failed"));
+        assert!(err.contains("vm unreachable"));
+        assert_eq!(
+            report.failed_phase().map(|p| p.phase),
+            Some(SwitchPhase::ExternalCommand)
+        );
+    }
+
+    #[test]
+    fn test_snapshot_restores_storage_and_db() {
+        let dir = std::env::temp_dir().join(format!("abv-switch-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let storage = dir.join("storage.json");
+        let db = dir.join("state.vscdb");
+        fs::write(&storage, b"{\"old\":true}").unwrap();
+        fs::write(&db, b"old-db").unwrap();
+
+        let snapshot = SwitchSnapshot::take(storage.clone(), db.clone()).unwrap();
+        fs::write(&storage, b"{\"new\":true}").unwrap();
+        fs::write(&db, b"new-db").unwrap();
+        snapshot.restore().unwrap();
+
+        assert_eq!(fs::read(&storage).unwrap(), b"{\"old\":true}");
+        assert_eq!(fs::read(&db).unwrap(), b"old-db");
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
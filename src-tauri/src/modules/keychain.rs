@@ -0,0 +1,139 @@
+// 系统钥匙串
+// 远程备份目标的凭据 (WebDAV 密码、S3 Secret Key) 保存在系统钥匙串 (macOS 钥匙串 / Windows 凭据管理器 / Secret Service) 中，
+// 配置文件中只保留占位符。钥匙串不可用时 (非桌面构建、Linux 无 Secret Service) 由调用方回退为加密写入配置文件。
+// 读取结果缓存在内存中，避免每次加载配置都访问钥匙串。
+
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// 配置文件中表示“值保存在钥匙串中”的占位符
+pub const PLACEHOLDER: &str = "@keychain";
+
+/// 自检只读取该条目，从不写入
+const PROBE_KEY: &str = "startup-probe";
+
+/// key -> 钥匙串中的值 (None = 已确认不存在)
+static CACHE: Lazy<Mutex<HashMap<String, Option<String>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn cached(key: &str) -> Option<Option<String>> {
+    CACHE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(key)
+        .cloned()
+}
+
+fn remember(key: &str, value: Option<String>) {
+    CACHE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(key.to_string(), value);
+}
+
+pub fn get(key: &str) -> Result<Option<String>, String> {
+    if let Some(value) = cached(key) {
+        return Ok(value);
+    }
+    let value = store::get(key)?;
+    remember(key, value.clone());
+    Ok(value)
+}
+
+pub fn set(key: &str, value: &str) -> Result<(), String> {
+    if cached(key).flatten().as_deref() == Some(value) {
+        return Ok(());
+    }
+    store::set(key, value)?;
+    remember(key, Some(value.to_string()));
+    Ok(())
+}
+
+pub fn delete(key: &str) -> Result<(), String> {
+    if cached(key) == Some(None) {
+        return Ok(());
+    }
+    store::delete(key)?;
+    remember(key, None);
+    Ok(())
+}
+
+/// 检查钥匙串是否可访问 (只读取探测条目，不写入)
+pub fn probe() -> Result<(), String> {
+    store::get(PROBE_KEY).map(|_| ())
+}
+
+#[cfg(all(feature = "desktop", not(test)))]
+mod store {
+    const SERVICE: &str = "com.lbjlaq.antigravity-tools";
+
+    fn entry(key: &str) -> Result<keyring::Entry, String> {
+        keyring::Entry::new(SERVICE, key).map_err(|e| format!("keychain_unavailable: {}", e))
+    }
+
+    pub fn get(key: &str) -> Result<Option<String>, String> {
+        match entry(key)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(format!("keychain_unavailable: {}", e)),
+        }
+    }
+
+    pub fn set(key: &str, value: &str) -> Result<(), String> {
+        entry(key)?
+            .set_password(value)
+            .map_err(|e| format!("keychain_write_failed: {}", e))
+    }
+
+    pub fn delete(key: &str) -> Result<(), String> {
+        match entry(key)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(format!("keychain_write_failed: {}", e)),
+        }
+    }
+}
+
+#[cfg(all(not(feature = "desktop"), not(test)))]
+mod store {
+    const UNAVAILABLE: &str = "keychain_unavailable: built without desktop support";
+
+    pub fn get(_key: &str) -> Result<Option<String>, String> {
+        Err(UNAVAILABLE.to_string())
+    }
+
+    pub fn set(_key: &str, _value: &str) -> Result<(), String> {
+        Err(UNAVAILABLE.to_string())
+    }
+
+    pub fn delete(_key: &str) -> Result<(), String> {
+        Err(UNAVAILABLE.to_string())
+    }
+}
+
+/// 测试不访问真实钥匙串
+#[cfg(test)]
+mod store {
+    use once_cell::sync::Lazy;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    static ENTRIES: Lazy<Mutex<HashMap<String, String>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+    pub fn get(key: &str) -> Result<Option<String>, String> {
+        Ok(ENTRIES.lock().unwrap().get(key).cloned())
+    }
+
+    pub fn set(key: &str, value: &str) -> Result<(), String> {
+        ENTRIES
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    pub fn delete(key: &str) -> Result<(), String> {
+        ENTRIES.lock().unwrap().remove(key);
+        Ok(())
+    }
+}
@@ -6,10 +6,13 @@ use serde::Serialize;
 use std::collections::VecDeque;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, OnceLock};
+#[cfg(feature = "desktop")]
 use tauri::Emitter;
 use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
 use tracing::{Event, Level, Subscriber};
 use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
 use tracing_subscriber::Layer;
 
 /// Maximum logs to keep in buffer
@@ -22,6 +25,7 @@ static LOG_BRIDGE_ENABLED: AtomicBool = AtomicBool::new(false);
 static LOG_ID_COUNTER: AtomicU64 = AtomicU64::new(0);
 
 /// Global app handle for emitting events (set once during setup)
+#[cfg(feature = "desktop")]
 static APP_HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
 
 /// Global log buffer for storing logs before UI connects
@@ -44,6 +48,7 @@ pub struct LogEntry {
 }
 
 /// Initialize the log bridge with app handle (call from setup)
+#[cfg(feature = "desktop")]
 pub fn init_log_bridge(app_handle: tauri::AppHandle) {
     let _ = APP_HANDLE.set(app_handle);
     tracing::debug!("[LogBridge] Initialized with app handle");
@@ -54,11 +59,8 @@ pub fn enable_log_bridge() {
     LOG_BRIDGE_ENABLED.store(true, Ordering::SeqCst);
 
     // Emit all buffered logs to frontend
-    if let Some(handle) = APP_HANDLE.get() {
-        let buffer = get_log_buffer().read();
-        for entry in buffer.iter() {
-            let _ = handle.emit("log-event", entry.clone());
-        }
+    for entry in get_log_buffer().read().iter() {
+        emit_event("log-event", entry.clone());
     }
 
     tracing::info!("[LogBridge] Debug console enabled");
@@ -85,13 +87,14 @@ pub fn clear_log_buffer() {
     get_log_buffer().write().clear();
 }
 
-/// Emit accounts://refreshed event to notify the frontend of account state changes
-/// This is used by background tasks (e.g. warmup 403 handling) that cannot access AppHandle directly.
-pub fn emit_accounts_refreshed() {
+/// Emit an arbitrary event with a payload via the global app handle (no-op in headless mode)
+pub fn emit_event<S: Serialize + Clone>(event: &str, payload: S) {
+    #[cfg(feature = "desktop")]
     if let Some(handle) = APP_HANDLE.get() {
-        let _ = handle.emit("accounts://refreshed", ());
-        tracing::debug!("[LogBridge] Emitted accounts://refreshed event to frontend");
+        let _ = handle.emit(event, payload);
     }
+    #[cfg(not(feature = "desktop"))]
+    let _ = (event, payload);
 }
 
 /// Visitor to extract fields from tracing events
@@ -144,6 +147,9 @@ impl Visit for FieldVisitor {
     }
 }
 
+/// Fields recorded on a span (e.g. request_id / account_id), stored in span extensions
+struct SpanFields(std::collections::HashMap<String, String>);
+
 /// Tracing Layer that bridges logs to buffer and optionally to Tauri frontend
 pub struct TauriLogBridgeLayer;
 
@@ -161,15 +167,28 @@ impl Default for TauriLogBridgeLayer {
 
 impl<S> Layer<S> for TauriLogBridgeLayer
 where
-    S: Subscriber,
+    S: Subscriber + for<'a> LookupSpan<'a>,
 {
-    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
-        // [FIX] 如果调试控制台未启用，直接跳过所有处理，避免性能损耗
-        if !LOG_BRIDGE_ENABLED.load(Ordering::Relaxed) {
-            return;
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            let mut visitor = FieldVisitor::new();
+            attrs.record(&mut visitor);
+            span.extensions_mut().insert(SpanFields(visitor.fields));
         }
+    }
 
-        // Extract metadata
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            let mut visitor = FieldVisitor::new();
+            values.record(&mut visitor);
+            let mut extensions = span.extensions_mut();
+            if let Some(fields) = extensions.get_mut::<SpanFields>() {
+                fields.0.extend(visitor.fields);
+            }
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
         let metadata = event.metadata();
         let level = match *metadata.level() {
             Level::ERROR => "ERROR",
@@ -179,6 +198,14 @@ where
             Level::TRACE => "TRACE",
         };
 
+        // [FIX] 在格式化之前判断: 调试控制台、日志存储均未启用且转发不接收该级别时直接跳过，避免性能损耗
+        let bridge_enabled = LOG_BRIDGE_ENABLED.load(Ordering::Relaxed);
+        let store_enabled = crate::modules::log_store::is_enabled();
+        let forward_enabled = crate::modules::log_forward::accepts(level);
+        if !bridge_enabled && !store_enabled && !forward_enabled {
+            return;
+        }
+
         // Visit fields
         let mut visitor = FieldVisitor::new();
         event.record(&mut visitor);
@@ -191,6 +218,23 @@ where
             return;
         }
 
+        // Inherit span context (request_id, account_id, ...); event fields take precedence
+        let mut fields = std::collections::HashMap::new();
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope.from_root() {
+                if let Some(span_fields) = span.extensions().get::<SpanFields>() {
+                    fields.extend(span_fields.0.clone());
+                }
+            }
+        }
+        fields.extend(visitor.fields);
+
+        // Mask tokens / keys before the entry reaches the log store or the frontend
+        let message = crate::modules::redact::redact(&message).into_owned();
+        for value in fields.values_mut() {
+            *value = crate::modules::redact::redact(value).into_owned();
+        }
+
         // Create log entry
         let entry = LogEntry {
             id: LOG_ID_COUNTER.fetch_add(1, Ordering::SeqCst),
@@ -198,9 +242,23 @@ where
             level: level.to_string(),
             target: metadata.target().to_string(),
             message,
-            fields: visitor.fields,
+            fields,
         };
 
+        // Persist to the structured log store (queryable via query_logs)
+        if store_enabled {
+            crate::modules::log_store::push(&entry);
+        }
+
+        // Forward to syslog / journald / Windows Event Log
+        if forward_enabled {
+            crate::modules::log_forward::push(&entry);
+        }
+
+        if !bridge_enabled {
+            return;
+        }
+
         // Add to buffer
         {
             let mut buffer = get_log_buffer().write();
@@ -211,9 +269,7 @@ where
         }
 
         // Emit to frontend
-        if let Some(handle) = APP_HANDLE.get() {
-            let _ = handle.emit("log-event", entry);
-        }
+        emit_event("log-event", entry);
     }
 }
 
@@ -221,27 +277,27 @@ where
 // Tauri Commands
 // ============================================================================
 
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub fn enable_debug_console() {
     enable_log_bridge();
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub fn disable_debug_console() {
     disable_log_bridge();
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub fn is_debug_console_enabled() -> bool {
     is_log_bridge_enabled()
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub fn get_debug_console_logs() -> Vec<LogEntry> {
     get_buffered_logs()
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "desktop", tauri::command)]
 pub fn clear_debug_console_logs() {
     clear_log_buffer();
 }
@@ -0,0 +1,513 @@
+//! Log forwarding - sends application log events (with span context such as request_id /
+//! account_id) to the system log: syslog (local socket or UDP / TCP), journald, or the
+//! Windows Event Log, so headless deployments integrate with existing log collection.
+//! Events are fed by the log bridge layer (already redacted) and delivered on a dedicated thread.
+
+use crate::models::LogForwardConfig;
+use crate::modules::log_bridge::LogEntry;
+use parking_lot::RwLock;
+use std::io::Write;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::mpsc::{self, SyncSender};
+use std::sync::OnceLock;
+
+/// Pending events beyond this are dropped rather than blocking the logging call site
+const CHANNEL_CAPACITY: usize = 10_000;
+/// Private enterprise number used for the RFC 5424 structured data ID
+const SD_ID: &str = "fields@32473";
+#[cfg(unix)]
+const SYSLOG_SOCKETS: &[&str] = &["/dev/log", "/var/run/syslog", "/var/run/log"];
+#[cfg(unix)]
+const JOURNALD_SOCKET: &str = "/run/systemd/journal/socket";
+
+static SENDER: OnceLock<SyncSender<LogEntry>> = OnceLock::new();
+static CONFIG: RwLock<Option<LogForwardConfig>> = parking_lot::const_rwlock(None);
+/// Most verbose level forwarded (0 = forwarding disabled)
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(0);
+
+/// ERROR = 1 ... TRACE = 5
+fn level_rank(level: &str) -> u8 {
+    match level.trim().to_ascii_uppercase().as_str() {
+        "ERROR" => 1,
+        "WARN" | "WARNING" => 2,
+        "INFO" => 3,
+        "DEBUG" => 4,
+        "TRACE" => 5,
+        _ => 0,
+    }
+}
+
+/// Apply the forwarding config (called on startup and whenever the config is saved)
+pub fn update_config(cfg: &LogForwardConfig) {
+    let max_level = if cfg.enabled {
+        level_rank(&cfg.min_level)
+    } else {
+        0
+    };
+    *CONFIG.write() = Some(cfg.clone());
+    MAX_LEVEL.store(max_level, Ordering::Relaxed);
+    if max_level > 0 && SENDER.get().is_none() {
+        let (tx, rx) = mpsc::sync_channel::<LogEntry>(CHANNEL_CAPACITY);
+        let spawned = std::thread::Builder::new()
+            .name("log-forward".to_string())
+            .spawn(move || writer_loop(rx));
+        if spawned.is_ok() {
+            let _ = SENDER.set(tx);
+        }
+    }
+}
+
+/// Whether events at `level` are forwarded (checked before an event is formatted)
+pub fn accepts(level: &str) -> bool {
+    let rank = level_rank(level);
+    rank > 0 && rank <= MAX_LEVEL.load(Ordering::Relaxed)
+}
+
+/// Queue an event for forwarding (never blocks; drops the event if the queue is full)
+pub(crate) fn push(entry: &LogEntry) {
+    if level_rank(&entry.level) > MAX_LEVEL.load(Ordering::Relaxed) {
+        return;
+    }
+    if let Some(tx) = SENDER.get() {
+        let _ = tx.try_send(entry.clone());
+    }
+}
+
+// NOTE: the writer must not log through tracing - its own events would be fed back here.
+fn writer_loop(rx: mpsc::Receiver<LogEntry>) {
+    let mut current: Option<(LogForwardConfig, Sink)> = None;
+    let mut last_error: Option<String> = None;
+
+    for entry in rx {
+        let Some(cfg) = CONFIG.read().clone().filter(|c| c.enabled) else {
+            current = None;
+            continue;
+        };
+        if current.as_ref().is_none_or(|(open, _)| open != &cfg) {
+            current = match Sink::open(&cfg) {
+                Ok(sink) => Some((cfg, sink)),
+                Err(e) => {
+                    report_error(
+                        &mut last_error,
+                        format!("cannot open {} sink: {}", cfg.sink, e),
+                    );
+                    None
+                }
+            };
+        }
+        let Some((cfg, sink)) = current.as_mut() else {
+            continue;
+        };
+        match sink.send(cfg, &entry) {
+            Ok(()) => last_error = None,
+            Err(e) => {
+                report_error(
+                    &mut last_error,
+                    format!("failed to forward log event: {}", e),
+                );
+                // Reconnect on the next event (syslog daemon restarted, TCP peer closed, ...)
+                current = None;
+            }
+        }
+    }
+}
+
+fn report_error(last_error: &mut Option<String>, message: String) {
+    if last_error.as_deref() != Some(message.as_str()) {
+        eprintln!("[LogForward] {}", message);
+        *last_error = Some(message);
+    }
+}
+
+enum Sink {
+    #[cfg(unix)]
+    LocalSyslog(std::os::unix::net::UnixDatagram),
+    Udp(std::net::UdpSocket),
+    Tcp(std::net::TcpStream),
+    #[cfg(unix)]
+    Journald(std::os::unix::net::UnixDatagram),
+    #[cfg(windows)]
+    EventLog(eventlog::EventSource),
+}
+
+impl Sink {
+    fn open(cfg: &LogForwardConfig) -> Result<Self, String> {
+        match cfg.sink.trim().to_ascii_lowercase().as_str() {
+            "syslog" => Self::open_syslog(cfg.address.trim()),
+            #[cfg(unix)]
+            "journald" => {
+                let socket =
+                    std::os::unix::net::UnixDatagram::unbound().map_err(|e| e.to_string())?;
+                socket.connect(JOURNALD_SOCKET).map_err(|e| e.to_string())?;
+                Ok(Self::Journald(socket))
+            }
+            #[cfg(windows)]
+            "eventlog" => eventlog::EventSource::register(&cfg.app_name).map(Self::EventLog),
+            other => Err(format!("unsupported_log_sink: {}", other)),
+        }
+    }
+
+    fn open_syslog(address: &str) -> Result<Self, String> {
+        if let Some(addr) = address.strip_prefix("udp://") {
+            let socket = std::net::UdpSocket::bind(("0.0.0.0", 0)).map_err(|e| e.to_string())?;
+            socket.connect(addr).map_err(|e| e.to_string())?;
+            return Ok(Self::Udp(socket));
+        }
+        if let Some(addr) = address.strip_prefix("tcp://") {
+            let stream = std::net::TcpStream::connect(addr).map_err(|e| e.to_string())?;
+            return Ok(Self::Tcp(stream));
+        }
+        if !address.is_empty() {
+            return Err(format!("invalid_syslog_address: {}", address));
+        }
+        Self::open_local_syslog()
+    }
+
+    #[cfg(unix)]
+    fn open_local_syslog() -> Result<Self, String> {
+        let socket = std::os::unix::net::UnixDatagram::unbound().map_err(|e| e.to_string())?;
+        let connected = SYSLOG_SOCKETS
+            .iter()
+            .any(|path| socket.connect(path).is_ok());
+        if !connected {
+            return Err("no local syslog socket found".to_string());
+        }
+        Ok(Self::LocalSyslog(socket))
+    }
+
+    #[cfg(not(unix))]
+    fn open_local_syslog() -> Result<Self, String> {
+        Err(
+            "local syslog is not available on this platform, set a udp:// or tcp:// address"
+                .to_string(),
+        )
+    }
+
+    fn send(&mut self, cfg: &LogForwardConfig, entry: &LogEntry) -> Result<(), String> {
+        let result = match self {
+            #[cfg(unix)]
+            Self::LocalSyslog(socket) => socket
+                .send(format_rfc3164(cfg, entry).as_bytes())
+                .map(|_| ()),
+            Self::Udp(socket) => socket
+                .send(format_rfc5424(cfg, entry).as_bytes())
+                .map(|_| ()),
+            Self::Tcp(stream) => {
+                // Octet-counting framing (RFC 6587)
+                let line = format_rfc5424(cfg, entry);
+                stream.write_all(format!("{} {}", line.len(), line).as_bytes())
+            }
+            #[cfg(unix)]
+            Self::Journald(socket) => socket.send(&format_journald(cfg, entry)).map(|_| ()),
+            #[cfg(windows)]
+            Self::EventLog(source) => return source.report(entry),
+        };
+        result.map_err(|e| e.to_string())
+    }
+}
+
+/// Syslog severity for a tracing level
+fn severity(level: &str) -> u8 {
+    match level_rank(level) {
+        1 => 3,
+        2 => 4,
+        3 => 6,
+        _ => 7,
+    }
+}
+
+fn facility_code(facility: &str) -> u8 {
+    match facility.trim().to_ascii_lowercase().as_str() {
+        "kern" => 0,
+        "user" => 1,
+        "mail" => 2,
+        "auth" => 4,
+        "syslog" => 5,
+        "lpr" => 6,
+        "news" => 7,
+        "uucp" => 8,
+        "cron" => 9,
+        "authpriv" => 10,
+        "ftp" => 11,
+        "local0" => 16,
+        "local1" => 17,
+        "local2" => 18,
+        "local3" => 19,
+        "local4" => 20,
+        "local5" => 21,
+        "local6" => 22,
+        "local7" => 23,
+        _ => 3, // daemon
+    }
+}
+
+fn priority(cfg: &LogForwardConfig, entry: &LogEntry) -> u8 {
+    facility_code(&cfg.facility) * 8 + severity(&entry.level)
+}
+
+/// Fields in a stable order (the bridge stores them in a HashMap)
+fn sorted_fields(entry: &LogEntry) -> Vec<(&String, &String)> {
+    let mut fields: Vec<_> = entry.fields.iter().collect();
+    fields.sort();
+    fields
+}
+
+fn hostname() -> String {
+    sysinfo::System::host_name()
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn timestamp(entry: &LogEntry) -> chrono::DateTime<chrono::Local> {
+    chrono::DateTime::from_timestamp_millis(entry.timestamp)
+        .unwrap_or_else(chrono::Utc::now)
+        .with_timezone(&chrono::Local)
+}
+
+/// SD-NAME: printable ASCII without '=', ' ', ']', '"', at most 32 characters
+fn sd_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_ascii_graphic() && !matches!(c, '=' | ']' | '"'))
+        .take(32)
+        .collect()
+}
+
+fn sd_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace(']', "\\]")
+}
+
+/// RFC 5424 line (network syslog); fields go into structured data
+fn format_rfc5424(cfg: &LogForwardConfig, entry: &LogEntry) -> String {
+    let mut sd = format!("[{} target=\"{}\"", SD_ID, sd_value(&entry.target));
+    for (key, value) in sorted_fields(entry) {
+        let name = sd_name(key);
+        if !name.is_empty() && name != "target" {
+            sd.push_str(&format!(" {}=\"{}\"", name, sd_value(value)));
+        }
+    }
+    sd.push(']');
+    format!(
+        "<{}>1 {} {} {} {} - {} {}",
+        priority(cfg, entry),
+        timestamp(entry).to_rfc3339_opts(chrono::SecondsFormat::Millis, false),
+        hostname(),
+        cfg.app_name,
+        std::process::id(),
+        sd,
+        entry.message
+    )
+}
+
+/// BSD syslog line for the local socket (widest daemon support); fields appended as key=value
+#[cfg(unix)]
+fn format_rfc3164(cfg: &LogForwardConfig, entry: &LogEntry) -> String {
+    let mut message = entry.message.clone();
+    for (key, value) in sorted_fields(entry) {
+        message.push_str(&format!(" {}={}", key, value));
+    }
+    format!(
+        "<{}>{} {}[{}]: {}",
+        priority(cfg, entry),
+        timestamp(entry).format("%b %e %H:%M:%S"),
+        cfg.app_name,
+        std::process::id(),
+        message
+    )
+}
+
+/// Journal field name: uppercase ASCII letters, digits and '_', not starting with '_' or a digit
+#[cfg(unix)]
+fn journal_field_name(name: &str) -> Option<String> {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let name = name.trim_start_matches(|c: char| c == '_' || c.is_ascii_digit());
+    (!name.is_empty()).then(|| name.chars().take(64).collect())
+}
+
+/// Native journal protocol datagram (values containing newlines use the length-prefixed form)
+#[cfg(unix)]
+fn format_journald(cfg: &LogForwardConfig, entry: &LogEntry) -> Vec<u8> {
+    fn field(buf: &mut Vec<u8>, name: &str, value: &str) {
+        buf.extend_from_slice(name.as_bytes());
+        if value.contains('\n') {
+            buf.push(b'\n');
+            buf.extend_from_slice(&(value.len() as u64).to_le_bytes());
+        } else {
+            buf.push(b'=');
+        }
+        buf.extend_from_slice(value.as_bytes());
+        buf.push(b'\n');
+    }
+
+    let mut buf = Vec::new();
+    field(&mut buf, "MESSAGE", &entry.message);
+    field(&mut buf, "PRIORITY", &severity(&entry.level).to_string());
+    field(
+        &mut buf,
+        "SYSLOG_FACILITY",
+        &facility_code(&cfg.facility).to_string(),
+    );
+    field(&mut buf, "SYSLOG_IDENTIFIER", &cfg.app_name);
+    field(&mut buf, "TARGET", &entry.target);
+    for (key, value) in sorted_fields(entry) {
+        if let Some(name) = journal_field_name(key) {
+            if !matches!(name.as_str(), "MESSAGE" | "PRIORITY" | "TARGET") {
+                field(&mut buf, &name, value);
+            }
+        }
+    }
+    buf
+}
+
+#[cfg(windows)]
+mod eventlog {
+    use crate::modules::log_bridge::LogEntry;
+    use std::ffi::c_void;
+
+    const EVENTLOG_ERROR_TYPE: u16 = 0x0001;
+    const EVENTLOG_WARNING_TYPE: u16 = 0x0002;
+    const EVENTLOG_INFORMATION_TYPE: u16 = 0x0004;
+
+    #[link(name = "advapi32")]
+    extern "system" {
+        fn RegisterEventSourceW(server: *const u16, source: *const u16) -> *mut c_void;
+        fn DeregisterEventSource(handle: *mut c_void) -> i32;
+        fn ReportEventW(
+            handle: *mut c_void,
+            event_type: u16,
+            category: u16,
+            event_id: u32,
+            user_sid: *const c_void,
+            num_strings: u16,
+            data_size: u32,
+            strings: *const *const u16,
+            raw_data: *const c_void,
+        ) -> i32;
+    }
+
+    fn wide(value: &str) -> Vec<u16> {
+        value.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+
+    /// Application log event source (no message file is registered, so Event Viewer shows the
+    /// text as the event's insertion string)
+    pub struct EventSource(*mut c_void);
+
+    // The handle is only used from the forwarding thread
+    unsafe impl Send for EventSource {}
+
+    impl EventSource {
+        pub fn register(app_name: &str) -> Result<Self, String> {
+            let source = wide(app_name);
+            let handle = unsafe { RegisterEventSourceW(std::ptr::null(), source.as_ptr()) };
+            if handle.is_null() {
+                return Err(std::io::Error::last_os_error().to_string());
+            }
+            Ok(Self(handle))
+        }
+
+        pub fn report(&self, entry: &LogEntry) -> Result<(), String> {
+            let event_type = match super::level_rank(&entry.level) {
+                1 => EVENTLOG_ERROR_TYPE,
+                2 => EVENTLOG_WARNING_TYPE,
+                _ => EVENTLOG_INFORMATION_TYPE,
+            };
+            let mut text = format!("{}\r\n\r\ntarget={}", entry.message, entry.target);
+            for (key, value) in super::sorted_fields(entry) {
+                text.push_str(&format!("\r\n{}={}", key, value));
+            }
+            let text = wide(&text);
+            let strings = [text.as_ptr()];
+            let ok = unsafe {
+                ReportEventW(
+                    self.0,
+                    event_type,
+                    0,
+                    0,
+                    std::ptr::null(),
+                    1,
+                    0,
+                    strings.as_ptr(),
+                    std::ptr::null(),
+                )
+            };
+            if ok == 0 {
+                return Err(std::io::Error::last_os_error().to_string());
+            }
+            Ok(())
+        }
+    }
+
+    impl Drop for EventSource {
+        fn drop(&mut self) {
+            unsafe {
+                DeregisterEventSource(self.0);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_formats() {
+        let cfg = LogForwardConfig {
+            enabled: true,
+            facility: "local0".to_string(),
+            app_name: "abv".to_string(),
+            ..Default::default()
+        };
+        let entry = LogEntry {
+            id: 1,
+            timestamp: 0,
+            level: "WARN".to_string(),
+            target: "antigravity_tools_lib::proxy".to_string(),
+            message: "Upstream 429".to_string(),
+            fields: [
+                ("request_id".to_string(), "r\"1]".to_string()),
+                ("account_id".to_string(), "a\nb".to_string()),
+            ]
+            .into_iter()
+            .collect(),
+        };
+
+        assert_eq!(priority(&cfg, &entry), 16 * 8 + 4);
+        let line = format_rfc5424(&cfg, &entry);
+        assert!(line.starts_with("<132>1 "));
+        assert!(line.ends_with(
+            "[fields@32473 target=\"antigravity_tools_lib::proxy\" account_id=\"a\nb\" request_id=\"r\\\"1\\]\"] Upstream 429"
+        ));
+
+        #[cfg(unix)]
+        {
+            let datagram = format_journald(&cfg, &entry);
+            let text = String::from_utf8_lossy(&datagram);
+            assert!(text.starts_with("MESSAGE=Upstream 429\nPRIORITY=4\n"));
+            assert!(text.contains("REQUEST_ID=r\"1]\n"));
+            // Multi-line values use the length-prefixed form
+            let mut multiline = b"ACCOUNT_ID\n".to_vec();
+            multiline.extend_from_slice(&3u64.to_le_bytes());
+            multiline.extend_from_slice(b"a\nb\n");
+            assert!(datagram
+                .windows(multiline.len())
+                .any(|w| w == multiline.as_slice()));
+            assert_eq!(journal_field_name("_x.y-z").as_deref(), Some("X_Y_Z"));
+        }
+
+        assert_eq!(level_rank("warn"), 2);
+        assert!(level_rank("DEBUG") > level_rank("info"));
+    }
+}
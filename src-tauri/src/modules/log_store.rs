@@ -0,0 +1,526 @@
+//! Structured log store - persists application log events (with span context such as
+//! request_id / account_id) to SQLite so they can be filtered with `query_logs`.
+//! Events are fed by the log bridge layer and written in batches on a dedicated thread.
+
+use crate::modules::log_bridge::LogEntry;
+use crate::modules::logger::LogRange;
+use rusqlite::types::Value;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, RecvTimeoutError, SyncSender};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Pending events beyond this are dropped rather than blocking the logging call site
+const CHANNEL_CAPACITY: usize = 10_000;
+const BATCH_SIZE: usize = 500;
+const FLUSH_INTERVAL: Duration = Duration::from_millis(500);
+/// Hard cap on stored rows, enforced periodically by the writer thread
+const MAX_ROWS: usize = 500_000;
+const CLEANUP_EVERY_INSERTS: usize = 20_000;
+const MAX_PAGE_SIZE: u32 = 500;
+
+/// How long `suspend` waits for the writer to flush and close the database
+const SUSPEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+enum WriterMsg {
+    Entry(LogEntry),
+    /// Flush, checkpoint and close the database, then acknowledge
+    Suspend(mpsc::Sender<()>),
+    /// Reopen the database at the current data directory
+    Resume,
+}
+
+static SENDER: OnceLock<SyncSender<WriterMsg>> = OnceLock::new();
+/// `logging.store_enabled`; the writer keeps running while disabled so it can be turned back on
+static STORE_ENABLED: AtomicBool = AtomicBool::new(true);
+
+pub fn get_log_store_path() -> Result<PathBuf, String> {
+    let data_dir = crate::modules::account::get_data_dir()?;
+    Ok(data_dir.join("app_logs.db"))
+}
+
+fn connect_db() -> Result<Connection, String> {
+    let conn = Connection::open(get_log_store_path()?).map_err(|e| e.to_string())?;
+    conn.pragma_update(None, "journal_mode", "WAL").map_err(|e| e.to_string())?;
+    conn.pragma_update(None, "busy_timeout", 5000).map_err(|e| e.to_string())?;
+    conn.pragma_update(None, "synchronous", "NORMAL").map_err(|e| e.to_string())?;
+    Ok(conn)
+}
+
+fn create_schema(conn: &Connection) -> Result<(), String> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS app_logs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            timestamp INTEGER NOT NULL,
+            level TEXT NOT NULL,
+            level_num INTEGER NOT NULL,
+            target TEXT NOT NULL,
+            message TEXT NOT NULL,
+            fields TEXT,
+            request_id TEXT,
+            account_id TEXT
+        )",
+        [],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_app_logs_timestamp ON app_logs (timestamp DESC)",
+        [],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_app_logs_account ON app_logs (account_id)",
+        [],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Create the store and start the writer thread (call once, after the logger is set up)
+pub fn init_store(retention_days: u64) -> Result<(), String> {
+    if SENDER.get().is_some() {
+        return Ok(());
+    }
+
+    let conn = connect_db()?;
+    create_schema(&conn)?;
+    cleanup(&conn, retention_days)?;
+
+    let (tx, rx) = mpsc::sync_channel::<WriterMsg>(CHANNEL_CAPACITY);
+    if SENDER.set(tx).is_err() {
+        return Ok(());
+    }
+
+    std::thread::Builder::new()
+        .name("log-store".to_string())
+        .spawn(move || writer_loop(conn, rx, retention_days))
+        .map_err(|e| format!("failed_to_spawn_log_store_writer: {}", e))?;
+    Ok(())
+}
+
+pub fn set_enabled(enabled: bool) {
+    STORE_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    STORE_ENABLED.load(Ordering::Relaxed) && SENDER.get().is_some()
+}
+
+/// Queue an event for persistence (never blocks; drops the event if the queue is full)
+pub(crate) fn push(entry: &LogEntry) {
+    if let Some(tx) = SENDER.get() {
+        let _ = tx.try_send(WriterMsg::Entry(entry.clone()));
+    }
+}
+
+/// Flush pending events, checkpoint the WAL and close the database so its files can be
+/// copied or removed. Events arriving meanwhile are kept in memory until `resume`.
+pub fn suspend() -> Result<(), String> {
+    let Some(tx) = SENDER.get() else {
+        return Ok(());
+    };
+    let (ack_tx, ack_rx) = mpsc::channel();
+    tx.send(WriterMsg::Suspend(ack_tx))
+        .map_err(|_| "log_store_writer_stopped".to_string())?;
+    ack_rx
+        .recv_timeout(SUSPEND_TIMEOUT)
+        .map_err(|_| "log_store_suspend_timeout".to_string())
+}
+
+/// Reopen the database (at the data directory resolved now) after `suspend`
+pub fn resume() {
+    if let Some(tx) = SENDER.get() {
+        let _ = tx.send(WriterMsg::Resume);
+    }
+}
+
+fn flush(conn: &mut Option<Connection>, batch: &mut Vec<LogEntry>) -> usize {
+    let Some(conn) = conn.as_mut() else {
+        return 0;
+    };
+    if batch.is_empty() {
+        return 0;
+    }
+    let written = batch.len();
+    if let Err(e) = insert_batch(conn, batch) {
+        eprintln!("[LogStore] Failed to write {} log entries: {}", written, e);
+    }
+    batch.clear();
+    written
+}
+
+fn reopen() -> Result<Connection, String> {
+    let conn = connect_db()?;
+    create_schema(&conn)?;
+    Ok(conn)
+}
+
+/// Apply one message; returns the number of entries written
+fn handle(msg: WriterMsg, conn: &mut Option<Connection>, batch: &mut Vec<LogEntry>) -> usize {
+    match msg {
+        // While suspended the queue is held here; beyond the channel size events are dropped
+        WriterMsg::Entry(entry) => {
+            if batch.len() < CHANNEL_CAPACITY {
+                batch.push(entry);
+            }
+            0
+        }
+        WriterMsg::Suspend(ack) => {
+            let written = flush(conn, batch);
+            if let Some(c) = conn.take() {
+                if let Err(e) = c.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);") {
+                    eprintln!("[LogStore] Checkpoint before close failed: {}", e);
+                }
+            }
+            let _ = ack.send(());
+            written
+        }
+        WriterMsg::Resume => {
+            if conn.is_none() {
+                match reopen() {
+                    Ok(c) => *conn = Some(c),
+                    Err(e) => eprintln!("[LogStore] Failed to reopen log store: {}", e),
+                }
+            }
+            0
+        }
+    }
+}
+
+// NOTE: the writer must not log through tracing - its own events would be fed back here.
+fn writer_loop(conn: Connection, rx: mpsc::Receiver<WriterMsg>, retention_days: u64) {
+    let mut conn = Some(conn);
+    let mut batch = Vec::with_capacity(BATCH_SIZE);
+    let mut since_cleanup = 0usize;
+
+    loop {
+        let disconnected = match rx.recv_timeout(FLUSH_INTERVAL) {
+            Ok(msg) => {
+                since_cleanup += handle(msg, &mut conn, &mut batch);
+                while batch.len() < BATCH_SIZE {
+                    match rx.try_recv() {
+                        Ok(msg) => since_cleanup += handle(msg, &mut conn, &mut batch),
+                        Err(_) => break,
+                    }
+                }
+                false
+            }
+            Err(RecvTimeoutError::Timeout) => false,
+            Err(RecvTimeoutError::Disconnected) => true,
+        };
+
+        since_cleanup += flush(&mut conn, &mut batch);
+
+        if since_cleanup >= CLEANUP_EVERY_INSERTS {
+            if let Some(c) = conn.as_ref() {
+                since_cleanup = 0;
+                if let Err(e) = cleanup(c, retention_days) {
+                    eprintln!("[LogStore] Cleanup failed: {}", e);
+                }
+            }
+        }
+
+        if disconnected {
+            break;
+        }
+    }
+}
+
+fn level_num(level: &str) -> i64 {
+    match level.to_ascii_uppercase().as_str() {
+        "ERROR" => 1,
+        "WARN" => 2,
+        "INFO" => 3,
+        "DEBUG" => 4,
+        _ => 5,
+    }
+}
+
+fn insert_batch(conn: &mut Connection, entries: &[LogEntry]) -> Result<(), String> {
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    {
+        let mut stmt = tx
+            .prepare_cached(
+                "INSERT INTO app_logs (timestamp, level, level_num, target, message, fields, request_id, account_id)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )
+            .map_err(|e| e.to_string())?;
+        for entry in entries {
+            let fields = if entry.fields.is_empty() {
+                None
+            } else {
+                serde_json::to_string(&entry.fields).ok()
+            };
+            stmt.execute(params![
+                entry.timestamp,
+                entry.level,
+                level_num(&entry.level),
+                entry.target,
+                entry.message,
+                fields,
+                entry.fields.get("request_id"),
+                entry.fields.get("account_id"),
+            ])
+            .map_err(|e| e.to_string())?;
+        }
+    }
+    tx.commit().map_err(|e| e.to_string())
+}
+
+fn cleanup(conn: &Connection, retention_days: u64) -> Result<usize, String> {
+    let mut deleted = 0;
+    if retention_days > 0 {
+        let cutoff = chrono::Utc::now().timestamp_millis() - (retention_days as i64) * 24 * 3600 * 1000;
+        deleted += conn
+            .execute("DELETE FROM app_logs WHERE timestamp < ?1", [cutoff])
+            .map_err(|e| e.to_string())?;
+    }
+    deleted += conn
+        .execute(
+            "DELETE FROM app_logs WHERE id <= (SELECT id FROM app_logs ORDER BY id DESC LIMIT 1 OFFSET ?1)",
+            [MAX_ROWS as i64],
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(deleted)
+}
+
+/// Filters for `query_logs`; every field is optional
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LogQuery {
+    /// Minimum severity, e.g. "warn" returns WARN and ERROR
+    pub level: Option<String>,
+    /// Substring of the tracing target, e.g. "proxy" or "modules::account"
+    pub module: Option<String>,
+    /// Substring of the message or any field value
+    pub text: Option<String>,
+    pub account_id: Option<String>,
+    pub request_id: Option<String>,
+    pub range: Option<LogRange>,
+    /// 1-based page number
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogQueryResult {
+    pub total: u64,
+    pub page: u32,
+    pub page_size: u32,
+    /// Newest first
+    pub items: Vec<LogEntry>,
+}
+
+fn escape_like(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+fn add_clause(clauses: &mut Vec<String>, values: &mut Vec<Value>, clause: &str, value: Value) {
+    values.push(value);
+    // Every `{}` refers to the value just pushed
+    clauses.push(clause.replace("{}", &format!("?{}", values.len())));
+}
+
+/// Build the WHERE clause and its positional parameters for a query
+fn build_filter(query: &LogQuery) -> (String, Vec<Value>) {
+    let mut clauses: Vec<String> = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+    let non_empty = |s: &Option<String>| s.as_deref().map(str::trim).filter(|s| !s.is_empty()).map(str::to_string);
+
+    if let Some(level) = non_empty(&query.level) {
+        add_clause(&mut clauses, &mut values, "level_num <= {}", Value::Integer(level_num(&level)));
+    }
+    if let Some(module) = non_empty(&query.module) {
+        let pattern = format!("%{}%", escape_like(&module));
+        add_clause(&mut clauses, &mut values, "target LIKE {} ESCAPE '\\'", Value::Text(pattern));
+    }
+    if let Some(text) = non_empty(&query.text) {
+        let pattern = format!("%{}%", escape_like(&text));
+        add_clause(
+            &mut clauses,
+            &mut values,
+            "(message LIKE {} ESCAPE '\\' OR fields LIKE {} ESCAPE '\\')",
+            Value::Text(pattern),
+        );
+    }
+    if let Some(account_id) = non_empty(&query.account_id) {
+        add_clause(&mut clauses, &mut values, "account_id = {}", Value::Text(account_id));
+    }
+    if let Some(request_id) = non_empty(&query.request_id) {
+        add_clause(&mut clauses, &mut values, "request_id = {}", Value::Text(request_id));
+    }
+    if let Some(range) = &query.range {
+        if let Some(start) = range.start {
+            add_clause(&mut clauses, &mut values, "timestamp >= {}", Value::Integer(start));
+        }
+        if let Some(end) = range.end {
+            add_clause(&mut clauses, &mut values, "timestamp <= {}", Value::Integer(end));
+        }
+    }
+
+    let where_sql = if clauses.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", clauses.join(" AND "))
+    };
+    (where_sql, values)
+}
+
+fn query_with(conn: &Connection, query: &LogQuery) -> Result<LogQueryResult, String> {
+    let page = query.page.unwrap_or(1).max(1);
+    let page_size = query.page_size.unwrap_or(100).clamp(1, MAX_PAGE_SIZE);
+    let (where_sql, values) = build_filter(query);
+
+    let total: u64 = conn
+        .query_row(
+            &format!("SELECT COUNT(*) FROM app_logs{}", where_sql),
+            rusqlite::params_from_iter(values.iter()),
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+
+    let mut page_values = values.clone();
+    page_values.push(Value::Integer(page_size as i64));
+    page_values.push(Value::Integer(((page - 1) as i64) * page_size as i64));
+    let sql = format!(
+        "SELECT id, timestamp, level, target, message, fields FROM app_logs{}
+         ORDER BY id DESC LIMIT ?{} OFFSET ?{}",
+        where_sql,
+        values.len() + 1,
+        values.len() + 2
+    );
+
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let items = stmt
+        .query_map(rusqlite::params_from_iter(page_values.iter()), |row| {
+            let fields: Option<String> = row.get(5)?;
+            Ok(LogEntry {
+                id: row.get::<_, i64>(0)? as u64,
+                timestamp: row.get(1)?,
+                level: row.get(2)?,
+                target: row.get(3)?,
+                message: row.get(4)?,
+                fields: fields
+                    .and_then(|f| serde_json::from_str(&f).ok())
+                    .unwrap_or_default(),
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(LogQueryResult {
+        total,
+        page,
+        page_size,
+        items,
+    })
+}
+
+/// Query stored log events, newest first
+pub fn query_logs(query: &LogQuery) -> Result<LogQueryResult, String> {
+    let conn = connect_db()?;
+    create_schema(&conn)?;
+    query_with(&conn, query)
+}
+
+/// Delete all stored log events
+pub fn clear_store() -> Result<(), String> {
+    let conn = connect_db()?;
+    conn.execute("DELETE FROM app_logs", []).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn entry(ts: i64, level: &str, target: &str, message: &str, account: Option<&str>) -> LogEntry {
+        let mut fields = HashMap::new();
+        if let Some(a) = account {
+            fields.insert("account_id".to_string(), a.to_string());
+        }
+        LogEntry {
+            id: 0,
+            timestamp: ts,
+            level: level.to_string(),
+            target: target.to_string(),
+            message: message.to_string(),
+            fields,
+        }
+    }
+
+    fn setup() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        create_schema(&conn).unwrap();
+        insert_batch(
+            &mut conn,
+            &[
+                entry(1_000, "INFO", "antigravity_tools_lib::modules::account", "refreshed", None),
+                entry(2_000, "ERROR", "antigravity_tools_lib::proxy::handlers::claude", "upstream 500", Some("acc-1")),
+                entry(3_000, "WARN", "antigravity_tools_lib::proxy::handlers::openai", "retrying 50%", Some("acc-2")),
+                entry(4_000, "ERROR", "antigravity_tools_lib::proxy::handlers::gemini", "timeout", Some("acc-1")),
+            ],
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_query_filters() {
+        let conn = setup();
+
+        let all = query_with(&conn, &LogQuery::default()).unwrap();
+        assert_eq!(all.total, 4);
+        assert_eq!(all.items[0].message, "timeout"); // newest first
+
+        let q = LogQuery {
+            level: Some("error".to_string()),
+            module: Some("proxy".to_string()),
+            account_id: Some("acc-1".to_string()),
+            range: Some(LogRange { start: Some(2_500), end: None }),
+            ..Default::default()
+        };
+        let res = query_with(&conn, &q).unwrap();
+        assert_eq!(res.total, 1);
+        assert_eq!(res.items[0].message, "timeout");
+        assert_eq!(res.items[0].fields.get("account_id").unwrap(), "acc-1");
+
+        let q = LogQuery {
+            level: Some("warn".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(query_with(&conn, &q).unwrap().total, 3);
+
+        // Text matches message or field values; LIKE wildcards are literal
+        let q = LogQuery {
+            text: Some("acc-2".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(query_with(&conn, &q).unwrap().total, 1);
+        let q = LogQuery {
+            text: Some("%".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(query_with(&conn, &q).unwrap().total, 1);
+    }
+
+    #[test]
+    fn test_query_pagination() {
+        let conn = setup();
+        let q = LogQuery {
+            page: Some(2),
+            page_size: Some(3),
+            ..Default::default()
+        };
+        let res = query_with(&conn, &q).unwrap();
+        assert_eq!(res.total, 4);
+        assert_eq!(res.items.len(), 1);
+        assert_eq!(res.items[0].message, "refreshed");
+    }
+}
@@ -1,8 +1,46 @@
 use tracing::{info, warn, error};
-use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+use tracing_subscriber::{fmt, layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter, Registry};
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use crate::models::LoggingConfig;
 use crate::modules::account::get_data_dir;
+use crate::modules::redact::RedactingWriter;
+
+/// Handle used to swap the active filter at runtime
+static FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// Bumped when the data directory moves; the file writer then reopens under the new log dir
+static LOG_DIR_GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+/// Log file currently held open by the file writer (closed by stop_file_logging)
+static OPEN_LOG_FILE: OnceLock<Arc<Mutex<Option<fs::File>>>> = OnceLock::new();
+
+/// Set before wiping the data directory; the file writer then discards everything
+static FILE_LOGGING_STOPPED: AtomicBool = AtomicBool::new(false);
+
+/// Levels currently applied to the filter
+static CURRENT_LEVELS: RwLock<Option<LoggingConfig>> = parking_lot::const_rwlock(None);
+
+/// RUST_LOG directives applied at startup instead of the configured levels (cleared by set_log_levels)
+static ENV_FILTER_OVERRIDE: RwLock<Option<String>> = parking_lot::const_rwlock(None);
+
+/// Log levels as reported to the UI
+#[derive(Debug, Clone, Serialize)]
+pub struct LogLevelsStatus {
+    /// Levels from the config
+    #[serde(flatten)]
+    pub levels: LoggingConfig,
+    /// Filter actually in effect, e.g. "info,antigravity_tools_lib::proxy=debug"
+    pub effective_filter: String,
+    /// RUST_LOG value when it overrides the configured levels
+    pub env_override: Option<String>,
+}
 
 // Custom local timezone time formatter
 struct LocalTimer;
@@ -38,17 +76,41 @@ pub fn init_logger() {
         }
     };
     
-    // 1. Set up file Appender (using tracing-appender for rolling logs)
-    // Using a daily rolling strategy here
-    let file_appender = tracing_appender::rolling::daily(log_dir, "app.log");
-    let (non_blocking, _guard) = tracing_appender::non_blocking(file_appender);
+    let levels = crate::modules::config::load_app_config()
+        .map(|c| c.logging)
+        .unwrap_or_default();
+
+    crate::modules::redact::set_enabled(levels.redact_secrets);
+    crate::modules::log_forward::update_config(&levels.forward);
+    crate::modules::log_store::set_enabled(levels.store_enabled);
+
+    // 1. Set up file Appender (rotated by period and size, see RotatingFileWriter)
+    // Tokens / keys are masked before anything reaches the file or console
+    let file_appender = RotatingFileWriter::new(log_dir, &levels);
+    let _ = OPEN_LOG_FILE.set(file_appender.file.clone());
+    let (non_blocking, _guard) =
+        tracing_appender::non_blocking(RedactingWriter::new(file_appender));
     
     // 2. Console output layer (using local timezone)
-    let console_layer = fmt::Layer::new()
-        .with_target(false)
-        .with_thread_ids(false)
-        .with_level(true)
-        .with_timer(LocalTimer);
+    // ABV_LOG_FORMAT=json switches stdout to one JSON object per line (Docker / log collectors)
+    let json_console = console_json_enabled();
+    let console_layer = (!json_console).then(|| {
+        fmt::Layer::new()
+            .with_writer(|| RedactingWriter::new(std::io::stdout()))
+            .with_target(false)
+            .with_thread_ids(false)
+            .with_level(true)
+            .with_timer(LocalTimer)
+    });
+    let json_console_layer = json_console.then(|| {
+        fmt::Layer::new()
+            .json()
+            .with_writer(|| RedactingWriter::new(std::io::stdout()))
+            .with_target(true)
+            .with_current_span(false)
+            .with_span_list(false)
+            .with_timer(LocalTimer)
+    });
         
     // 3. File output layer (disable ANSI formatting, use local timezone)
     let file_layer = fmt::Layer::new()
@@ -59,8 +121,15 @@ pub fn init_logger() {
         .with_timer(LocalTimer);
 
     // 4. Set filtering layer (default to INFO level to reduce log size)
-    let filter_layer = EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| EnvFilter::new("info"));
+    // RUST_LOG wins over the configured levels; either can be replaced later via set_log_levels
+    let filter = match EnvFilter::try_from_default_env() {
+        Ok(filter) => {
+            *ENV_FILTER_OVERRIDE.write() = std::env::var(EnvFilter::DEFAULT_ENV).ok();
+            filter
+        }
+        Err(_) => build_filter(&levels).unwrap_or_else(|_| EnvFilter::new("info")),
+    };
+    let (filter_layer, filter_handle) = reload::Layer::new(filter);
 
     // 6. Log bridge layer
     let bridge_layer = crate::modules::log_bridge::TauriLogBridgeLayer::new();
@@ -69,24 +138,381 @@ pub fn init_logger() {
     let _ = tracing_subscriber::registry()
         .with(filter_layer)
         .with(console_layer)
+        .with(json_console_layer)
         .with(file_layer)
         .with(bridge_layer)
         .try_init();
 
+    let _ = FILTER_HANDLE.set(filter_handle);
+    let (retention_days, max_total_size_mb) = (levels.retention_days, levels.max_total_size_mb);
+    *CURRENT_LEVELS.write() = Some(levels);
+
     // Leak _guard to ensure its lifetime lasts until program exit
     // Recommended practice when using tracing_appender::non_blocking (if manual flushing is not needed)
     std::mem::forget(_guard);
     
     info!("Log system initialized (Console + File persistence)");
+
+    // Structured store behind query_logs
+    if let Err(e) = crate::modules::log_store::init_store(retention_days) {
+        warn!("Failed to initialize log store: {}", e);
+    }
     
-    // Auto-cleanup logs older than 7 days
-    if let Err(e) = cleanup_old_logs(7) {
+    // Auto-cleanup logs past the retention window (7 days by default)
+    if let Err(e) = cleanup_old_logs(retention_days, max_total_size_mb) {
         warn!("Failed to cleanup old logs: {}", e);
     }
 }
 
+fn console_json_enabled() -> bool {
+    std::env::var("ABV_LOG_FORMAT")
+        .map(|v| v.trim().eq_ignore_ascii_case("json"))
+        .unwrap_or(false)
+}
+
+/// Build the filter directive string, e.g. "info,antigravity_tools_lib::proxy=debug"
+fn build_directives(levels: &LoggingConfig) -> Result<String, String> {
+    let parse_level = |level: &str| {
+        tracing::level_filters::LevelFilter::from_str(level.trim())
+            .map_err(|_| format!("invalid_log_level: {}", level))
+    };
+
+    let mut directives = vec![parse_level(&levels.default_level)?.to_string().to_lowercase()];
+    for (module, level) in &levels.module_levels {
+        let module = module.trim();
+        if module.is_empty() || module.contains(|c: char| c == ',' || c == '=' || c.is_whitespace()) {
+            return Err(format!("invalid_log_module: {}", module));
+        }
+        directives.push(format!("{}={}", module, parse_level(level)?.to_string().to_lowercase()));
+    }
+    Ok(directives.join(","))
+}
+
+fn build_filter(levels: &LoggingConfig) -> Result<EnvFilter, String> {
+    let directives = build_directives(levels)?;
+    EnvFilter::try_new(&directives).map_err(|e| format!("invalid_log_filter: {}", e))
+}
+
+/// Continue file logging under the current data directory (after it was moved)
+pub fn reopen_log_dir() {
+    LOG_DIR_GENERATION.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Close the log file and stop writing log files for the rest of the process (data wipe).
+/// Console output and the debug console are unaffected.
+pub fn stop_file_logging() {
+    FILE_LOGGING_STOPPED.store(true, Ordering::SeqCst);
+    if let Some(file) = OPEN_LOG_FILE.get() {
+        if let Some(mut f) = file.lock().take() {
+            let _ = f.flush();
+        }
+    }
+}
+
+/// Get the configured log levels together with the filter actually in effect
+pub fn get_log_levels() -> LogLevelsStatus {
+    let levels = CURRENT_LEVELS.read().clone().unwrap_or_default();
+    let effective_filter = FILTER_HANDLE
+        .get()
+        .and_then(|handle| handle.with_current(|filter| filter.to_string()).ok())
+        .unwrap_or_else(|| build_directives(&levels).unwrap_or_default());
+    LogLevelsStatus {
+        levels,
+        effective_filter,
+        env_override: ENV_FILTER_OVERRIDE.read().clone(),
+    }
+}
+
+/// Replace the active log levels without restarting (does not persist)
+pub fn set_log_levels(levels: LoggingConfig) -> Result<(), String> {
+    let filter = build_filter(&levels)?;
+    let handle = FILTER_HANDLE
+        .get()
+        .ok_or_else(|| "logger_not_initialized".to_string())?;
+    handle
+        .reload(filter)
+        .map_err(|e| format!("failed_to_reload_log_filter: {}", e))?;
+
+    info!("Log levels updated: {}", build_directives(&levels)?);
+    *CURRENT_LEVELS.write() = Some(levels);
+    *ENV_FILTER_OVERRIDE.write() = None;
+    Ok(())
+}
+
+/// Apply levels from a saved config, skipping the reload when nothing changed
+/// (keeps a RUST_LOG override in place across unrelated config saves)
+pub fn apply_log_levels_if_changed(levels: &LoggingConfig) -> Result<(), String> {
+    crate::modules::redact::set_enabled(levels.redact_secrets);
+    crate::modules::log_forward::update_config(&levels.forward);
+    crate::modules::log_store::set_enabled(levels.store_enabled);
+    let unchanged = CURRENT_LEVELS.read().as_ref().is_some_and(|current| {
+        current.default_level == levels.default_level && current.module_levels == levels.module_levels
+    });
+    if unchanged {
+        *CURRENT_LEVELS.write() = Some(levels.clone());
+        return Ok(());
+    }
+    set_log_levels(levels.clone())
+}
+
+const LOG_FILE_PREFIX: &str = "app.log";
+
+/// Time granularity of log file rotation
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RotationPeriod {
+    Hourly,
+    Daily,
+}
+
+impl RotationPeriod {
+    fn from_config(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "hourly" => Self::Hourly,
+            _ => Self::Daily,
+        }
+    }
+
+    fn key(&self, now: &chrono::DateTime<chrono::Local>) -> String {
+        match self {
+            Self::Hourly => now.format("%Y-%m-%d-%H").to_string(),
+            Self::Daily => now.format("%Y-%m-%d").to_string(),
+        }
+    }
+}
+
+/// `app.log.<period>` for the first segment, `app.log.<period>.<n>` for size-rolled ones
+fn segment_file_name(key: &str, segment: u32) -> String {
+    if segment == 0 {
+        format!("{}.{}", LOG_FILE_PREFIX, key)
+    } else {
+        format!("{}.{}.{}", LOG_FILE_PREFIX, key, segment)
+    }
+}
+
+/// Start of the period a log file belongs to (local time), parsed from its file name
+fn segment_start(file_name: &str) -> Option<chrono::NaiveDateTime> {
+    let rest = file_name.strip_prefix(LOG_FILE_PREFIX)?.strip_prefix('.')?;
+    let key = rest.split('.').next()?;
+    if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(&format!("{}:00", key), "%Y-%m-%d-%H:%M") {
+        return Some(dt);
+    }
+    chrono::NaiveDate::parse_from_str(key, "%Y-%m-%d")
+        .ok()?
+        .and_hms_opt(0, 0, 0)
+}
+
+/// File writer that starts a new file when the period changes or the current file
+/// grows past `max_file_size_mb`. Runs on the tracing_appender worker thread.
+struct RotatingFileWriter {
+    dir: PathBuf,
+    dir_generation: usize,
+    period: RotationPeriod,
+    max_bytes: u64,
+    retention_days: u64,
+    max_total_size_mb: u64,
+    current_key: String,
+    segment: u32,
+    size: u64,
+    file: Arc<Mutex<Option<fs::File>>>,
+}
+
+impl RotatingFileWriter {
+    fn new(dir: PathBuf, config: &LoggingConfig) -> Self {
+        Self {
+            dir,
+            dir_generation: LOG_DIR_GENERATION.load(Ordering::Relaxed),
+            period: RotationPeriod::from_config(&config.rotation),
+            max_bytes: config.max_file_size_mb.saturating_mul(1024 * 1024),
+            retention_days: config.retention_days,
+            max_total_size_mb: config.max_total_size_mb,
+            current_key: String::new(),
+            segment: 0,
+            size: 0,
+            file: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    fn open_current(&mut self) -> std::io::Result<fs::File> {
+        let path = self.dir.join(segment_file_name(&self.current_key, self.segment));
+        let file = fs::OpenOptions::new().create(true).append(true).open(&path)?;
+        self.size = file.metadata().map(|m| m.len()).unwrap_or(0);
+        Ok(file)
+    }
+
+    /// Switch to a new period, continuing its last segment after a restart
+    fn open_period(&mut self, key: String) -> std::io::Result<fs::File> {
+        let mut segment = 0;
+        while self.dir.join(segment_file_name(&key, segment + 1)).exists() {
+            segment += 1;
+        }
+        let existing = fs::metadata(self.dir.join(segment_file_name(&key, segment)))
+            .map(|m| m.len())
+            .unwrap_or(0);
+        if self.max_bytes > 0 && existing >= self.max_bytes {
+            segment += 1;
+        }
+        self.current_key = key;
+        self.segment = segment;
+        self.open_current()
+    }
+
+    fn spawn_cleanup(&self) {
+        let (days, total_mb) = (self.retention_days, self.max_total_size_mb);
+        std::thread::spawn(move || {
+            let _ = cleanup_old_logs(days, total_mb);
+        });
+    }
+}
+
+impl Write for RotatingFileWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        // Checked under the lock so stop_file_logging never races with a reopen
+        let handle = self.file.clone();
+        let mut file = handle.lock();
+        if FILE_LOGGING_STOPPED.load(Ordering::SeqCst) {
+            return Ok(buf.len());
+        }
+
+        let generation = LOG_DIR_GENERATION.load(Ordering::Relaxed);
+        if generation != self.dir_generation {
+            self.dir_generation = generation;
+            if let Ok(dir) = get_log_dir() {
+                self.dir = dir;
+                *file = None;
+            }
+        }
+
+        let key = self.period.key(&chrono::Local::now());
+        if file.is_none() || key != self.current_key {
+            let rolled = file.is_some();
+            *file = Some(self.open_period(key)?);
+            if rolled {
+                self.spawn_cleanup();
+            }
+        } else if self.max_bytes > 0 && self.size > 0 && self.size + buf.len() as u64 > self.max_bytes {
+            self.segment += 1;
+            *file = Some(self.open_current()?);
+            self.spawn_cleanup();
+        }
+
+        let file = match file.as_mut() {
+            Some(f) => f,
+            None => return Err(std::io::Error::other("log file not open")),
+        };
+        let written = file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self.file.lock().as_mut() {
+            Some(f) => f.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Time range for log export / query, unix milliseconds (open-ended when None)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LogRange {
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+}
+
+/// A segment overlaps the range if it started before `end` and was last written after `start`
+fn segment_in_range(start_ms: Option<i64>, modified_ms: i64, range: &LogRange) -> bool {
+    let starts_before_end = match (start_ms, range.end) {
+        (Some(s), Some(end)) => s <= end,
+        _ => true,
+    };
+    let written_after_start = range.start.is_none_or(|start| modified_ms >= start);
+    starts_before_end && written_after_start
+}
+
+/// Log files overlapping the given range, oldest first
+pub fn list_log_files(range: &LogRange) -> Result<Vec<PathBuf>, String> {
+    use chrono::TimeZone;
+
+    let log_dir = get_log_dir()?;
+    let entries = fs::read_dir(&log_dir).map_err(|e| format!("Failed to read log directory: {}", e))?;
+
+    let mut files = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !path.is_file() || !name.starts_with(LOG_FILE_PREFIX) {
+            continue;
+        }
+        let modified_ms = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .map(|t| chrono::DateTime::<chrono::Utc>::from(t).timestamp_millis())
+            .unwrap_or(0);
+        let start_ms = segment_start(&name).and_then(|dt| {
+            chrono::Local
+                .from_local_datetime(&dt)
+                .earliest()
+                .map(|d| d.timestamp_millis())
+        });
+        if segment_in_range(start_ms, modified_ms, range) {
+            files.push((modified_ms, path));
+        }
+    }
+    files.sort();
+    Ok(files.into_iter().map(|(_, p)| p).collect())
+}
+
+/// Zip the log segments overlapping `range` (for attaching to bug reports).
+/// Writes to `output` or `<data_dir>/exports/logs-<timestamp>.zip`; returns the zip path.
+pub fn export_logs(range: &LogRange, output: Option<PathBuf>) -> Result<PathBuf, String> {
+    let files = list_log_files(range)?;
+    if files.is_empty() {
+        return Err("no_logs_in_range".to_string());
+    }
+
+    let output = match output {
+        Some(p) => p,
+        None => {
+            let dir = get_data_dir()?.join("exports");
+            fs::create_dir_all(&dir).map_err(|e| format!("Failed to create export directory: {}", e))?;
+            dir.join(format!("logs-{}.zip", chrono::Local::now().format("%Y%m%d-%H%M%S")))
+        }
+    };
+
+    let zip_file = fs::File::create(&output).map_err(|e| format!("Failed to create zip file: {}", e))?;
+    let mut zip = zip::ZipWriter::new(zip_file);
+    for path in &files {
+        add_file_to_zip(&mut zip, path, "")?;
+    }
+    zip.finish().map_err(|e| format!("Failed to finalize zip file: {}", e))?;
+
+    info!("Exported {} log files to {:?}", files.len(), output);
+    Ok(output)
+}
+
+/// Copy a file into the zip under `<dir>/<file name>`
+pub(crate) fn add_file_to_zip<W: Write + std::io::Seek>(
+    zip: &mut zip::ZipWriter<W>,
+    path: &Path,
+    dir: &str,
+) -> Result<(), String> {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let entry_name = if dir.is_empty() { name } else { format!("{}/{}", dir, name) };
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    zip.start_file(entry_name, options)
+        .map_err(|e| format!("Failed to add zip entry: {}", e))?;
+    let mut src = fs::File::open(path).map_err(|e| format!("Failed to open {:?}: {}", path, e))?;
+    std::io::copy(&mut src, zip).map_err(|e| format!("Failed to write zip entry: {}", e))?;
+    Ok(())
+}
+
 /// Cleanup log files older than specified days OR if total size exceeds limit
-pub fn cleanup_old_logs(days_to_keep: u64) -> Result<(), String> {
+pub fn cleanup_old_logs(days_to_keep: u64, max_total_size_mb: u64) -> Result<(), String> {
     use std::time::{SystemTime, UNIX_EPOCH};
     
     let log_dir = get_log_dir()?;
@@ -94,9 +520,9 @@ pub fn cleanup_old_logs(days_to_keep: u64) -> Result<(), String> {
         return Ok(());
     }
 
-    // Constants for size-based cleanup
-    const MAX_TOTAL_SIZE_BYTES: u64 = 1024 * 1024 * 1024; // 1GB
-    const TARGET_SIZE_BYTES: u64 = 512 * 1024 * 1024;    // 512MB
+    // Size-based cleanup trims the directory down to half of the limit
+    let max_total_size_bytes = max_total_size_mb.saturating_mul(1024 * 1024);
+    let target_size_bytes = max_total_size_bytes / 2;
     
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -135,7 +561,8 @@ pub fn cleanup_old_logs(days_to_keep: u64) -> Result<(), String> {
     // 1. First pass: Delete files older than cutoff_time
     let mut remaining_entries = Vec::new();
     for (path, size, modified_secs) in entries_info {
-        if modified_secs < cutoff_time {
+        // retention_days = 0 keeps files regardless of age (size limit still applies)
+        if days_to_keep > 0 && modified_secs < cutoff_time {
             if let Err(e) = fs::remove_file(&path) {
                 warn!("Failed to delete old log file {:?}: {}", path, e);
                 remaining_entries.push((path, size, modified_secs));
@@ -152,14 +579,14 @@ pub fn cleanup_old_logs(days_to_keep: u64) -> Result<(), String> {
     // 2. Second pass: If total size still exceeds limit, delete oldest files
     let mut current_total_size: u64 = remaining_entries.iter().map(|(_, size, _)| *size).sum();
     
-    if current_total_size > MAX_TOTAL_SIZE_BYTES {
-        info!("Log directory size ({} MB) exceeds limit (1024 MB), starting size-based cleanup...", current_total_size / 1024 / 1024);
+    if max_total_size_bytes > 0 && current_total_size > max_total_size_bytes {
+        info!("Log directory size ({} MB) exceeds limit ({} MB), starting size-based cleanup...", current_total_size / 1024 / 1024, max_total_size_mb);
         
         // Sort remaining entries by modification time (oldest first)
         remaining_entries.sort_by_key(|(_, _, modified)| *modified);
         
         for (path, size, _) in remaining_entries {
-            if current_total_size <= TARGET_SIZE_BYTES {
+            if current_total_size <= target_size_bytes {
                 break;
             }
             
@@ -188,6 +615,10 @@ pub fn cleanup_old_logs(days_to_keep: u64) -> Result<(), String> {
 
 /// Clear log cache (using truncation mode to keep file handles valid)
 pub fn clear_logs() -> Result<(), String> {
+    if let Err(e) = crate::modules::log_store::clear_store() {
+        warn!("Failed to clear log store: {}", e);
+    }
+
     let log_dir = get_log_dir()?;
     if log_dir.exists() {
         // Iterate through all files in directory and truncate instead of deleting directory
@@ -222,3 +653,61 @@ pub fn log_warn(message: &str) {
 pub fn log_error(message: &str) {
     error!("{}", message);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_directives() {
+        let mut levels = LoggingConfig::default();
+        assert_eq!(build_directives(&levels).unwrap(), "info");
+
+        levels.default_level = "WARN".to_string();
+        levels
+            .module_levels
+            .insert("antigravity_tools_lib::proxy".to_string(), "debug".to_string());
+        levels.module_levels.insert("hyper".to_string(), "off".to_string());
+        assert_eq!(
+            build_directives(&levels).unwrap(),
+            "warn,antigravity_tools_lib::proxy=debug,hyper=off"
+        );
+        assert!(build_filter(&levels).is_ok());
+    }
+
+    #[test]
+    fn test_segment_file_name_and_start() {
+        assert_eq!(segment_file_name("2026-10-16", 0), "app.log.2026-10-16");
+        assert_eq!(segment_file_name("2026-10-16-09", 2), "app.log.2026-10-16-09.2");
+
+        let daily = segment_start("app.log.2026-10-16.3").unwrap();
+        assert_eq!(daily.to_string(), "2026-10-16 00:00:00");
+        let hourly = segment_start("app.log.2026-10-16-09").unwrap();
+        assert_eq!(hourly.to_string(), "2026-10-16 09:00:00");
+        assert!(segment_start("app.log").is_none());
+        assert!(segment_start("other.log.2026-10-16").is_none());
+    }
+
+    #[test]
+    fn test_segment_in_range() {
+        let range = LogRange { start: Some(1_000), end: Some(2_000) };
+        assert!(segment_in_range(Some(500), 1_500, &range));
+        assert!(!segment_in_range(Some(500), 900, &range)); // last written before the range
+        assert!(!segment_in_range(Some(2_500), 3_000, &range)); // started after the range
+        assert!(segment_in_range(None, 1_500, &range));
+        assert!(segment_in_range(Some(0), 0, &LogRange::default()));
+    }
+
+    #[test]
+    fn test_build_directives_rejects_invalid() {
+        let levels = LoggingConfig {
+            default_level: "verbose".to_string(),
+            ..Default::default()
+        };
+        assert!(build_directives(&levels).is_err());
+
+        let mut levels = LoggingConfig::default();
+        levels.module_levels.insert("a=b".to_string(), "debug".to_string());
+        assert!(build_directives(&levels).is_err());
+    }
+}
@@ -1,6 +1,8 @@
 pub mod account;
+pub mod data_dir;
 pub mod quota;
 pub mod config;
+pub mod config_history;
 pub mod logger;
 pub mod db;
 pub mod process;
@@ -8,12 +10,14 @@ pub mod oauth;
 pub mod oauth_server;
 pub mod codex_oauth;
 pub mod migration;
+#[cfg(feature = "desktop")]
 pub mod tray;
 pub mod i18n;
 pub mod proxy_db;
 pub mod device;
 pub mod update_checker;
 pub mod scheduler;
+pub mod warmup_pacer;
 pub mod token_stats;
 pub mod cloudflared;
 pub mod integration;
@@ -22,9 +26,46 @@ pub mod account_service;
 pub mod http_api;
 pub mod cache;
 pub mod log_bridge;
+pub mod events;
+pub mod log_store;
+pub mod log_forward;
+pub mod diagnostics;
+pub mod redact;
 pub mod security_db;
 pub mod user_token_db;
 pub mod version;
+pub mod operations;
+pub mod remote;
+pub mod model_catalog;
+pub mod http;
+pub mod dns;
+pub mod retry;
+pub mod offline;
+pub mod onboarding;
+pub mod billing;
+pub mod project_binding;
+pub mod endpoints;
+pub mod retention;
+pub mod hygiene;
+pub mod backup;
+pub mod sync;
+pub mod account_share;
+pub mod clock_skew;
+pub mod digest;
+pub mod event_feed;
+pub mod notify;
+pub mod crash_report;
+#[cfg(feature = "desktop")]
+pub mod deep_link;
+pub mod headless;
+pub mod importers;
+pub mod domain_policy;
+pub mod exclusion_windows;
+pub mod switch_queue;
+pub mod wipe;
+pub mod app_lock;
+pub mod keychain;
+pub mod startup_report;
 
 use crate::models;
 
@@ -0,0 +1,414 @@
+// 上游模型目录同步
+// 定期通过 fetchAvailableModels 拉取各账号可见的模型 ID，合并进本地目录文件 (model_catalog.json)，
+// 使配额保护 / 路由配置可以提供最新的模型列表，而不依赖内置的静态映射表。
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tokio::time::{self, Duration};
+
+use crate::models::AccountProvider;
+use crate::modules::{account, config, logger, offline, quota, scheduler};
+
+const CATALOG_FILE: &str = "model_catalog.json";
+/// 两次同步之间的最小间隔 (24 小时)
+const SYNC_INTERVAL_SECS: i64 = 86_400;
+/// 后台任务的检查间隔；静默时段内跳过，之后再补同步
+const CHECK_INTERVAL_SECS: u64 = 3_600;
+const JOB_ID: &str = "model_catalog_sync";
+
+static CATALOG_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CatalogEntry {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    pub first_seen: i64,
+    pub last_seen: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModelCatalog {
+    #[serde(default)]
+    pub last_synced_at: Option<i64>,
+    #[serde(default)]
+    pub models: BTreeMap<String, CatalogEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogSyncResult {
+    pub synced_at: i64,
+    pub accounts_checked: usize,
+    pub accounts_failed: usize,
+    pub new_models: Vec<String>,
+    pub total_models: usize,
+}
+
+fn catalog_path() -> Result<PathBuf, String> {
+    Ok(account::get_data_dir()?.join(CATALOG_FILE))
+}
+
+fn load_catalog_at(path: &PathBuf) -> ModelCatalog {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_catalog_at(path: &PathBuf, catalog: &ModelCatalog) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(catalog)
+        .map_err(|e| format!("failed_to_serialize_catalog: {}", e))?;
+    let temp_path = path.with_extension("json.tmp");
+    std::fs::write(&temp_path, content).map_err(|e| format!("failed_to_write_catalog: {}", e))?;
+    std::fs::rename(&temp_path, path).map_err(|e| format!("failed_to_write_catalog: {}", e))
+}
+
+/// 合并一批 (model_id, display_name)，返回新发现的模型 ID
+fn merge_models(
+    catalog: &mut ModelCatalog,
+    seen: impl IntoIterator<Item = (String, Option<String>)>,
+    now: i64,
+) -> Vec<String> {
+    let mut new_models = Vec::new();
+    for (id, display_name) in seen {
+        match catalog.models.get_mut(&id) {
+            Some(entry) => {
+                entry.last_seen = now;
+                if display_name.is_some() {
+                    entry.display_name = display_name;
+                }
+            }
+            None => {
+                catalog.models.insert(
+                    id.clone(),
+                    CatalogEntry {
+                        display_name,
+                        first_seen: now,
+                        last_seen: now,
+                    },
+                );
+                new_models.push(id);
+            }
+        }
+    }
+    new_models.sort();
+    new_models.dedup();
+    new_models
+}
+
+/// 读取本地模型目录
+pub fn load_catalog() -> ModelCatalog {
+    let _lock = CATALOG_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    catalog_path().map(|p| load_catalog_at(&p)).unwrap_or_default()
+}
+
+/// 已知模型 ID 列表：内置映射 + 上游同步的目录，已排序去重
+pub fn list_known_models() -> Vec<String> {
+    let mut ids: HashSet<String> = crate::proxy::common::model_mapping::get_supported_models()
+        .into_iter()
+        .collect();
+    ids.extend(load_catalog().models.into_keys());
+
+    let mut sorted: Vec<String> = ids.into_iter().collect();
+    sorted.sort();
+    sorted
+}
+
+/// 配额保护可用的标准分组 ID (monitored_models 的合法取值)：
+/// 已知模型经 normalize_to_standard_id 归并后的分组，外加默认监控的分组
+pub fn list_standard_ids() -> Vec<String> {
+    standard_ids_from(&list_known_models())
+}
+
+fn standard_ids_from(known: &[String]) -> Vec<String> {
+    let mut ids: BTreeSet<String> = known
+        .iter()
+        .filter_map(|m| crate::proxy::common::model_mapping::normalize_to_standard_id(m))
+        .collect();
+    ids.extend(crate::models::QuotaProtectionConfig::default().monitored_models);
+    ids.into_iter().collect()
+}
+
+/// 不在标准分组中的 monitored_models 条目及建议
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnknownModelId {
+    pub id: String,
+    pub suggestions: Vec<String>,
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+/// 建议：先按归并规则映射到分组，其次取编辑距离足够接近的分组
+fn suggest(id: &str, standard: &[String]) -> Vec<String> {
+    let mut suggestions = Vec::new();
+    if let Some(std_id) = crate::proxy::common::model_mapping::normalize_to_standard_id(id) {
+        if standard.contains(&std_id) {
+            suggestions.push(std_id);
+        }
+    }
+    let lower = id.to_lowercase();
+    let max_distance = (lower.chars().count() / 3).max(2);
+    let mut close: Vec<(usize, &String)> = standard
+        .iter()
+        .map(|s| (edit_distance(&lower, s), s))
+        .filter(|(d, _)| *d <= max_distance)
+        .collect();
+    close.sort();
+    for (_, s) in close {
+        if !suggestions.contains(s) {
+            suggestions.push(s.clone());
+        }
+    }
+    suggestions
+}
+
+fn check_monitored_models(
+    models: &[String],
+    standard: &[String],
+) -> Result<Vec<String>, Vec<UnknownModelId>> {
+    let mut accepted: Vec<String> = Vec::new();
+    let mut unknown = Vec::new();
+    for id in models.iter().map(|m| m.trim()).filter(|m| !m.is_empty()) {
+        if standard.iter().any(|s| s == id) {
+            if !accepted.iter().any(|a| a == id) {
+                accepted.push(id.to_string());
+            }
+        } else if !unknown.iter().any(|u: &UnknownModelId| u.id == id) {
+            unknown.push(UnknownModelId {
+                id: id.to_string(),
+                suggestions: suggest(id, standard),
+            });
+        }
+    }
+    if unknown.is_empty() {
+        Ok(accepted)
+    } else {
+        Err(unknown)
+    }
+}
+
+/// 校验 monitored_models：去除空白与重复；存在未知 ID 时返回带建议的错误
+pub fn validate_monitored_models(models: &[String]) -> Result<Vec<String>, String> {
+    check_monitored_models(models, &list_standard_ids()).map_err(|unknown| {
+        let details: Vec<String> = unknown
+            .iter()
+            .map(|u| {
+                if u.suggestions.is_empty() {
+                    u.id.clone()
+                } else {
+                    format!("{} (did you mean: {})", u.id, u.suggestions.join(", "))
+                }
+            })
+            .collect();
+        format!("unknown_monitored_models: {}", details.join("; "))
+    })
+}
+
+/// 保存配置前调用：monitored_models 有变动时校验并规范化 (未变动时不校验，兼容旧配置)，返回是否变动
+pub fn prepare_monitored_models(
+    previous: Option<&[String]>,
+    next: &mut Vec<String>,
+) -> Result<bool, String> {
+    if previous == Some(next.as_slice()) {
+        return Ok(false);
+    }
+    *next = validate_monitored_models(next)?;
+    Ok(previous != Some(next.as_slice()))
+}
+
+/// 遍历所有可用的 Google 账号拉取模型列表，并合并进目录
+pub async fn sync_catalog() -> Result<CatalogSyncResult, String> {
+    offline::ensure_online()?;
+    let accounts = account::list_accounts()?;
+    let mut seen: Vec<(String, Option<String>)> = Vec::new();
+    let mut accounts_checked = 0;
+    let mut accounts_failed = 0;
+
+    for acc in accounts
+        .iter()
+        .filter(|a| !a.disabled && a.provider == AccountProvider::Google)
+    {
+        let Ok((token, pid)) = quota::get_valid_token_for_warmup(acc).await else {
+            accounts_failed += 1;
+            continue;
+        };
+        match quota::fetch_quota_with_cache(&token, &acc.email, Some(&pid), Some(&acc.id)).await {
+            Ok((quota_data, _)) if !quota_data.is_forbidden => {
+                accounts_checked += 1;
+                seen.extend(
+                    quota_data
+                        .models
+                        .into_iter()
+                        .map(|m| (m.name, m.display_name)),
+                );
+            }
+            Ok(_) => accounts_failed += 1,
+            Err(e) => {
+                accounts_failed += 1;
+                logger::log_warn(&format!(
+                    "[ModelCatalog] Failed to fetch models for {}: {}",
+                    acc.email, e
+                ));
+            }
+        }
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    let _lock = CATALOG_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let path = catalog_path()?;
+    let mut catalog = load_catalog_at(&path);
+    let new_models = merge_models(&mut catalog, seen, now);
+    // 没有任何账号成功时不推进同步时间，下一轮继续尝试
+    if accounts_checked > 0 {
+        catalog.last_synced_at = Some(now);
+    }
+    save_catalog_at(&path, &catalog)?;
+
+    if !new_models.is_empty() {
+        logger::log_info(&format!(
+            "[ModelCatalog] Discovered {} new model(s): {}",
+            new_models.len(),
+            new_models.join(", ")
+        ));
+    }
+
+    Ok(CatalogSyncResult {
+        synced_at: now,
+        accounts_checked,
+        accounts_failed,
+        new_models,
+        total_models: catalog.models.len(),
+    })
+}
+
+/// 启动后台目录同步任务：每小时检查一次，距上次成功同步超过 24 小时且不在静默时段时执行
+pub fn start_catalog_sync_job() {
+    scheduler::register_job(JOB_ID, CHECK_INTERVAL_SECS);
+    crate::utils::runtime::spawn(async move {
+        let mut interval = time::interval(Duration::from_secs(CHECK_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            if !scheduler::job_tick(JOB_ID) {
+                continue;
+            }
+
+            if let Ok(app_config) = config::load_app_config() {
+                if scheduler::is_quiet_hours(&app_config.quiet_hours) {
+                    continue;
+                }
+            }
+            if offline::is_offline() {
+                continue;
+            }
+
+            let last = load_catalog().last_synced_at.unwrap_or(0);
+            if chrono::Utc::now().timestamp() - last < SYNC_INTERVAL_SECS {
+                continue;
+            }
+
+            let started = std::time::Instant::now();
+            let result = sync_catalog().await.map(|result| {
+                format!(
+                    "{} account(s), {} new, {} total",
+                    result.accounts_checked,
+                    result.new_models.len(),
+                    result.total_models
+                )
+            });
+            match &result {
+                Ok(summary) => {
+                    logger::log_info(&format!("[ModelCatalog] Sync finished: {}", summary))
+                }
+                Err(e) => logger::log_warn(&format!("[ModelCatalog] Sync failed: {}", e)),
+            }
+            scheduler::record_job_run(JOB_ID, started, result);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_models_tracks_new_ids() {
+        let mut catalog = ModelCatalog::default();
+        let added = merge_models(
+            &mut catalog,
+            vec![
+                ("gemini-3-flash".to_string(), None),
+                ("claude-sonnet-4-5".to_string(), Some("Claude Sonnet 4.5".to_string())),
+            ],
+            100,
+        );
+        assert_eq!(added, vec!["claude-sonnet-4-5", "gemini-3-flash"]);
+
+        let added = merge_models(
+            &mut catalog,
+            vec![
+                ("gemini-3-flash".to_string(), Some("Gemini 3 Flash".to_string())),
+                ("gemini-3.5-pro".to_string(), None),
+            ],
+            200,
+        );
+        assert_eq!(added, vec!["gemini-3.5-pro"]);
+
+        let flash = &catalog.models["gemini-3-flash"];
+        assert_eq!(flash.first_seen, 100);
+        assert_eq!(flash.last_seen, 200);
+        assert_eq!(flash.display_name.as_deref(), Some("Gemini 3 Flash"));
+        assert_eq!(catalog.models.len(), 3);
+    }
+
+    #[test]
+    fn test_standard_ids_include_defaults() {
+        let ids = standard_ids_from(&["gemini-2.5-flash-lite".to_string(), "gpt-4o".to_string()]);
+        assert_eq!(
+            ids,
+            vec!["claude", "gemini-3-flash", "gemini-3-pro-high", "gemini-3-pro-image"]
+        );
+    }
+
+    #[test]
+    fn test_check_monitored_models() {
+        let standard = standard_ids_from(&[]);
+        let ok = check_monitored_models(
+            &[
+                " claude ".to_string(),
+                "gemini-3-flash".to_string(),
+                "claude".to_string(),
+                "".to_string(),
+            ],
+            &standard,
+        )
+        .unwrap();
+        assert_eq!(ok, vec!["claude", "gemini-3-flash"]);
+
+        let unknown = check_monitored_models(
+            &[
+                "claude-sonnet-4-5".to_string(),
+                "gemini-3-flsh".to_string(),
+                "gpt-4o".to_string(),
+            ],
+            &standard,
+        )
+        .unwrap_err();
+        assert_eq!(unknown.len(), 3);
+        assert_eq!(unknown[0].suggestions, vec!["claude"]);
+        assert_eq!(unknown[1].suggestions, vec!["gemini-3-flash"]);
+        assert!(unknown[2].suggestions.is_empty());
+    }
+}
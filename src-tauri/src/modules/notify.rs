@@ -0,0 +1,229 @@
+// 通知中心
+// 各模块通过 push() 写入需要用户知道的事件 (例如凌晨账号被禁用)，持久化在数据目录的 notifications.json，
+// 窗口关闭期间产生的警告不会随 toast 一起丢失；前端通过 list_notifications / mark_notifications_read 读取。
+// 带 dedupe_key 的通知在未读期间重复推送时只更新内容与计数，不新增条目。最多保留 MAX_NOTIFICATIONS 条。
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::modules::account::get_data_dir;
+
+const NOTIFICATIONS_FILE: &str = "notifications.json";
+const MAX_NOTIFICATIONS: usize = 200;
+pub const NOTIFICATIONS_EVENT: &str = "notifications://changed";
+
+static STORE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notification {
+    pub id: String,
+    pub severity: Severity,
+    /// 产生通知的模块，如 "account" / "proxy" / "digest"
+    pub source: String,
+    pub title: String,
+    pub body: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dedupe_key: Option<String>,
+    pub created_at: i64,
+    /// 最近一次推送的时间 (合并重复通知时更新)
+    pub updated_at: i64,
+    /// 未读期间合并的次数
+    pub count: u32,
+    pub read: bool,
+}
+
+/// notifications://changed 事件内容
+#[derive(Debug, Clone, Serialize)]
+struct NotificationsChanged {
+    unread: usize,
+    latest: Option<Notification>,
+}
+
+fn load_in(path: &Path) -> Vec<Notification> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_in(path: &Path, notifications: &[Notification]) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(notifications)
+        .map_err(|e| format!("failed_to_serialize_notifications: {}", e))?;
+    fs::write(path, content).map_err(|e| format!("failed_to_save_notifications: {}", e))
+}
+
+fn store_path() -> Result<std::path::PathBuf, String> {
+    Ok(get_data_dir()?.join(NOTIFICATIONS_FILE))
+}
+
+/// 写入一条通知 (新的在前)，返回写入或合并后的通知
+fn push_in(
+    notifications: &mut Vec<Notification>,
+    severity: Severity,
+    source: &str,
+    title: String,
+    body: String,
+    dedupe_key: Option<String>,
+    now: i64,
+) -> Notification {
+    if let Some(key) = dedupe_key.as_deref() {
+        if let Some(pos) = notifications
+            .iter()
+            .position(|n| !n.read && n.dedupe_key.as_deref() == Some(key))
+        {
+            let mut existing = notifications.remove(pos);
+            existing.severity = severity;
+            existing.title = title;
+            existing.body = body;
+            existing.updated_at = now;
+            existing.count += 1;
+            notifications.insert(0, existing.clone());
+            return existing;
+        }
+    }
+    let notification = Notification {
+        id: uuid::Uuid::new_v4().to_string(),
+        severity,
+        source: source.to_string(),
+        title,
+        body,
+        dedupe_key,
+        created_at: now,
+        updated_at: now,
+        count: 1,
+        read: false,
+    };
+    notifications.insert(0, notification.clone());
+    notifications.truncate(MAX_NOTIFICATIONS);
+    notification
+}
+
+/// 推送通知；失败只记录日志，不影响调用方
+pub fn push(
+    severity: Severity,
+    source: &str,
+    title: impl Into<String>,
+    body: impl Into<String>,
+    dedupe_key: Option<&str>,
+) {
+    let _lock = STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let result = store_path().and_then(|path| {
+        let mut notifications = load_in(&path);
+        let latest = push_in(
+            &mut notifications,
+            severity,
+            source,
+            title.into(),
+            body.into(),
+            dedupe_key.map(str::to_string),
+            chrono::Utc::now().timestamp(),
+        );
+        save_in(&path, &notifications)?;
+        Ok(NotificationsChanged {
+            unread: notifications.iter().filter(|n| !n.read).count(),
+            latest: Some(latest),
+        })
+    });
+    match result {
+        Ok(changed) => crate::modules::log_bridge::emit_event(NOTIFICATIONS_EVENT, changed),
+        Err(e) => tracing::warn!("[Notify] {}", e),
+    }
+}
+
+/// 通知列表 (新的在前)
+pub fn list(unread_only: bool) -> Result<Vec<Notification>, String> {
+    let _lock = STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    Ok(load_in(&store_path()?)
+        .into_iter()
+        .filter(|n| !unread_only || !n.read)
+        .collect())
+}
+
+/// 标记为已读；ids 为 None 时全部标记，返回剩余未读数
+pub fn mark_read(ids: Option<&[String]>) -> Result<usize, String> {
+    let _lock = STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let path = store_path()?;
+    let mut notifications = load_in(&path);
+    for n in notifications.iter_mut() {
+        let selected = match ids {
+            Some(ids) => ids.contains(&n.id),
+            None => true,
+        };
+        if selected {
+            n.read = true;
+        }
+    }
+    save_in(&path, &notifications)?;
+    let unread = notifications.iter().filter(|n| !n.read).count();
+    crate::modules::log_bridge::emit_event(
+        NOTIFICATIONS_EVENT,
+        NotificationsChanged {
+            unread,
+            latest: None,
+        },
+    );
+    Ok(unread)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_dedupes_unread_and_caps() {
+        let mut list = Vec::new();
+        let first = push_in(
+            &mut list,
+            Severity::Warning,
+            "account",
+            "Account disabled".into(),
+            "a@x.com".into(),
+            Some("disabled:a".into()),
+            1,
+        );
+        push_in(&mut list, Severity::Info, "digest", "Digest".into(), String::new(), None, 2);
+        let merged = push_in(
+            &mut list,
+            Severity::Error,
+            "account",
+            "Account disabled".into(),
+            "a@x.com (again)".into(),
+            Some("disabled:a".into()),
+            3,
+        );
+        assert_eq!(list.len(), 2);
+        assert_eq!(merged.id, first.id);
+        assert_eq!((merged.count, merged.updated_at), (2, 3));
+        assert_eq!(list[0].id, first.id);
+
+        // 已读后再次推送会新建条目
+        list[0].read = true;
+        push_in(
+            &mut list,
+            Severity::Error,
+            "account",
+            "Account disabled".into(),
+            String::new(),
+            Some("disabled:a".into()),
+            4,
+        );
+        assert_eq!(list.len(), 3);
+
+        for i in 0..MAX_NOTIFICATIONS {
+            push_in(&mut list, Severity::Info, "test", format!("{}", i), String::new(), None, 5);
+        }
+        assert_eq!(list.len(), MAX_NOTIFICATIONS);
+        assert_eq!(list[0].title, format!("{}", MAX_NOTIFICATIONS - 1));
+    }
+}
@@ -77,7 +77,7 @@ pub fn get_auth_url(redirect_uri: &str, state: &str) -> String {
 pub async fn exchange_code(code: &str, redirect_uri: &str) -> Result<TokenResponse, String> {
     // [PHASE 2] 对于登录行为，尚未有 account_id，使用全局池阶梯逻辑
     let client = if let Some(pool) = crate::proxy::proxy_pool::get_global_proxy_pool() {
-        pool.get_effective_standard_client(None, 60).await
+        pool.get_effective_standard_client(None, crate::modules::http::settings().long_timeout_secs).await
     } else {
         crate::utils::http::get_long_standard_client()
     };
@@ -92,12 +92,12 @@ pub async fn exchange_code(code: &str, redirect_uri: &str) -> Result<TokenRespon
 
     tracing::debug!(
         "[OAuth] Sending exchange_code request with User-Agent: {}",
-        crate::constants::NATIVE_OAUTH_USER_AGENT.as_str()
+        crate::constants::native_oauth_user_agent()
     );
 
     let response = client
-        .post(TOKEN_URL)
-        .header(rquest::header::USER_AGENT, crate::constants::NATIVE_OAUTH_USER_AGENT.as_str())
+        .post(crate::modules::endpoints::resolve(TOKEN_URL))
+        .header(rquest::header::USER_AGENT, crate::constants::native_oauth_user_agent())
         .form(&params)
         .send()
         .await
@@ -134,7 +134,7 @@ pub async fn exchange_code(code: &str, redirect_uri: &str) -> Result<TokenRespon
         Ok(token_res)
     } else {
         let error_text = response.text().await.unwrap_or_default();
-        Err(format!("Token exchange failed: {}", error_text))
+        Err(format!("Token exchange failed: {}", crate::modules::redact::upstream_body(&error_text)))
     }
 }
 
@@ -142,7 +142,7 @@ pub async fn exchange_code(code: &str, redirect_uri: &str) -> Result<TokenRespon
 pub async fn refresh_access_token(refresh_token: &str, account_id: Option<&str>) -> Result<TokenResponse, String> {
     // [PHASE 2] 根据 account_id 使用对应的代理
     let client = if let Some(pool) = crate::proxy::proxy_pool::get_global_proxy_pool() {
-        pool.get_effective_standard_client(account_id, 60).await
+        pool.get_effective_standard_client(account_id, crate::modules::http::settings().long_timeout_secs).await
     } else {
         crate::utils::http::get_long_standard_client()
     };
@@ -163,12 +163,12 @@ pub async fn refresh_access_token(refresh_token: &str, account_id: Option<&str>)
     
     tracing::debug!(
         "[OAuth] Sending refresh_access_token request with User-Agent: {}",
-        crate::constants::NATIVE_OAUTH_USER_AGENT.as_str()
+        crate::constants::native_oauth_user_agent()
     );
 
     let response = client
-        .post(TOKEN_URL)
-        .header(rquest::header::USER_AGENT, crate::constants::NATIVE_OAUTH_USER_AGENT.as_str())
+        .post(crate::modules::endpoints::resolve(TOKEN_URL))
+        .header(rquest::header::USER_AGENT, crate::constants::native_oauth_user_agent())
         .form(&params)
         .send()
         .await
@@ -180,6 +180,10 @@ pub async fn refresh_access_token(refresh_token: &str, account_id: Option<&str>)
             }
         })?;
 
+    crate::modules::clock_skew::observe_date_header(
+        response.headers().get("date").and_then(|v| v.to_str().ok()),
+    );
+
     if response.status().is_success() {
         let token_data = response
             .json::<TokenResponse>()
@@ -190,33 +194,61 @@ pub async fn refresh_access_token(refresh_token: &str, account_id: Option<&str>)
         Ok(token_data)
     } else {
         let error_text = response.text().await.unwrap_or_default();
-        Err(format!("Refresh failed: {}", error_text))
+        Err(format!("Refresh failed: {}", crate::modules::redact::upstream_body(&error_text)))
     }
 }
 
 /// Get user info
 pub async fn get_user_info(access_token: &str, account_id: Option<&str>) -> Result<UserInfo, String> {
     let client = if let Some(pool) = crate::proxy::proxy_pool::get_global_proxy_pool() {
-        pool.get_effective_client(account_id, 15).await
+        pool.get_effective_client(account_id, crate::modules::http::settings().timeout_secs).await
     } else {
         crate::utils::http::get_client()
     };
     
-    let response = client
-        .get(USERINFO_URL)
-        .bearer_auth(access_token)
-        .send()
-        .await
-        .map_err(|e| format!("User info request failed: {}", e))?;
+    use crate::error::AppError;
+    use crate::modules::retry;
 
-    if response.status().is_success() {
-        response.json::<UserInfo>()
-            .await
-            .map_err(|e| format!("User info parsing failed: {}", e))
-    } else {
-        let error_text = response.text().await.unwrap_or_default();
-        Err(format!("Failed to get user info: {}", error_text))
-    }
+    // 429 / 5xx / 超时按统一策略重试；401 说明 Token 本身无效，这里无法刷新，直接返回
+    let result = retry::retry(
+        &retry::RetryPolicy::current(),
+        "user_info",
+        |e: &AppError| retry::classify_app_error(e).filter(|c| *c != crate::models::RetryClass::Unauthorized),
+        || async {
+            let response = client
+                .get(crate::modules::endpoints::resolve(USERINFO_URL))
+                .bearer_auth(access_token)
+                .send()
+                .await
+                .map_err(|e| {
+                    let status = e.status().map(|s| s.as_u16());
+                    AppError::Network(format!("User info request failed: {}", e), status)
+                })?;
+
+            let status = response.status();
+            if status.is_success() {
+                response
+                    .json::<UserInfo>()
+                    .await
+                    .map_err(|e| AppError::Unknown(format!("User info parsing failed: {}", e)))
+            } else {
+                let error_text = response.text().await.unwrap_or_default();
+                Err(AppError::Network(
+                    format!(
+                        "Failed to get user info: {}",
+                        crate::modules::redact::upstream_body(&error_text)
+                    ),
+                    Some(status.as_u16()),
+                ))
+            }
+        },
+    )
+    .await;
+
+    result.map_err(|e| match e {
+        AppError::Network(msg, _) | AppError::Unknown(msg) => msg,
+        other => other.to_string(),
+    })
 }
 
 /// Check and refresh Token if needed
@@ -225,7 +257,8 @@ pub async fn ensure_fresh_token(
     current_token: &crate::models::TokenData,
     account_id: Option<&str>,
 ) -> Result<crate::models::TokenData, String> {
-    let now = chrono::Local::now().timestamp();
+    // 使用按上游 Date 头校正后的时间，避免本机时钟偏差导致误判
+    let now = crate::modules::clock_skew::now();
     
     // If no expiry or more than 5 minutes valid, return direct
     if current_token.expiry_timestamp > now + 300 {
@@ -3,7 +3,8 @@ use tokio::net::TcpListener;
 use tokio::sync::mpsc;
 use tokio::sync::watch;
 use std::sync::{Mutex, OnceLock};
-use tauri::Url;
+use url::Url;
+use crate::modules::integration::SystemManager;
 use crate::modules::oauth;
 
 struct OAuthFlowState {
@@ -43,7 +44,7 @@ fn oauth_fail_html() -> &'static str {
     </html>"
 }
 
-async fn ensure_oauth_flow_prepared(app_handle: Option<tauri::AppHandle>) -> Result<String, String> {
+async fn ensure_oauth_flow_prepared(integration: SystemManager) -> Result<String, String> {
 
     // Return URL if flow already exists and is still "fresh" (receiver hasn't been taken)
     if let Ok(mut state) = get_oauth_flow_state().lock() {
@@ -130,12 +131,12 @@ async fn ensure_oauth_flow_prepared(app_handle: Option<tauri::AppHandle>) -> Res
 
     // Start listeners immediately: even if the user authorizes before clicking "Start OAuth",
     // the browser can still hit our callback and finish the flow.
-    let app_handle_for_tasks = app_handle.clone();
+    let integration_for_tasks = integration.clone();
 
     if let Some(l4) = ipv4_listener {
         let tx = code_tx.clone();
         let mut rx = cancel_rx.clone();
-        let app_handle = app_handle_for_tasks.clone();
+        let integration = integration_for_tasks.clone();
         tokio::spawn(async move {
             if let Ok((mut stream, _)) = tokio::select! {
                 res = l4.accept() => res.map_err(|e| format!("failed_to_accept_connection: {}", e)),
@@ -209,10 +210,7 @@ async fn ensure_oauth_flow_prepared(app_handle: Option<tauri::AppHandle>) -> Res
                 let _ = stream.write_all(response_html.as_bytes()).await;
                 let _ = stream.flush().await;
 
-                if let Some(h) = app_handle {
-                    use tauri::Emitter;
-                    let _ = h.emit("oauth-callback-received", ());
-                }
+                integration.emit("oauth-callback-received", ());
                 let _ = tx.send(result).await;
             }
         });
@@ -221,7 +219,7 @@ async fn ensure_oauth_flow_prepared(app_handle: Option<tauri::AppHandle>) -> Res
     if let Some(l6) = ipv6_listener {
         let tx = code_tx.clone();
         let mut rx = cancel_rx;
-        let app_handle = app_handle_for_tasks;
+        let integration = integration_for_tasks;
         tokio::spawn(async move {
             if let Ok((mut stream, _)) = tokio::select! {
                 res = l6.accept() => res.map_err(|e| format!("failed_to_accept_connection: {}", e)),
@@ -291,10 +289,7 @@ async fn ensure_oauth_flow_prepared(app_handle: Option<tauri::AppHandle>) -> Res
                 let _ = stream.write_all(response_html.as_bytes()).await;
                 let _ = stream.flush().await;
 
-                if let Some(h) = app_handle {
-                    use tauri::Emitter;
-                    let _ = h.emit("oauth-callback-received", ());
-                }
+                integration.emit("oauth-callback-received", ());
                 let _ = tx.send(result).await;
             }
         });
@@ -313,17 +308,14 @@ async fn ensure_oauth_flow_prepared(app_handle: Option<tauri::AppHandle>) -> Res
     }
 
     // Send event to frontend (for display/copying link)
-    if let Some(h) = app_handle {
-        use tauri::Emitter;
-        let _ = h.emit("oauth-url-generated", &auth_url);
-    }
+    integration.emit("oauth-url-generated", &auth_url);
 
     Ok(auth_url)
 }
 
 /// Pre-generate OAuth URL (does not open browser, does not block waiting for callback)
-pub async fn prepare_oauth_url(app_handle: Option<tauri::AppHandle>) -> Result<String, String> {
-    ensure_oauth_flow_prepared(app_handle).await
+pub async fn prepare_oauth_url(integration: SystemManager) -> Result<String, String> {
+    ensure_oauth_flow_prepared(integration).await
 }
 
 /// Cancel current OAuth flow
@@ -337,17 +329,18 @@ pub fn cancel_oauth_flow() {
 }
 
 /// Start OAuth flow and wait for callback, then exchange token
-pub async fn start_oauth_flow(app_handle: Option<tauri::AppHandle>) -> Result<oauth::TokenResponse, String> {
+pub async fn start_oauth_flow(integration: SystemManager) -> Result<oauth::TokenResponse, String> {
     // Ensure URL + listener are ready (this way if the user authorizes first, it won't get stuck)
-    let auth_url = ensure_oauth_flow_prepared(app_handle.clone()).await?;
+    let auth_url = ensure_oauth_flow_prepared(integration.clone()).await?;
 
-    if let Some(h) = app_handle {
+    #[cfg(feature = "desktop")]
+    if let SystemManager::Desktop(h) = integration {
         // Open default browser
-        use tauri_plugin_opener::OpenerExt;
-        h.opener()
-            .open_url(&auth_url, None::<String>)
+        crate::utils::command::open_url(&h, &auth_url)
             .map_err(|e| format!("failed_to_open_browser: {}", e))?;
     }
+    #[cfg(not(feature = "desktop"))]
+    let _ = auth_url;
 
     // Take code_rx to wait for it
     let (mut code_rx, redirect_uri) = {
@@ -383,9 +376,9 @@ pub async fn start_oauth_flow(app_handle: Option<tauri::AppHandle>) -> Result<oa
 /// Завершить OAuth flow без открытия браузера.
 /// Предполагается, что пользователь открыл ссылку вручную (или ранее была открыта),
 /// а мы только ждём callback и обмениваем code на token.
-pub async fn complete_oauth_flow(app_handle: Option<tauri::AppHandle>) -> Result<oauth::TokenResponse, String> {
+pub async fn complete_oauth_flow(integration: SystemManager) -> Result<oauth::TokenResponse, String> {
     // Ensure URL + listeners exist
-    let _ = ensure_oauth_flow_prepared(app_handle).await?;
+    let _ = ensure_oauth_flow_prepared(integration).await?;
 
     // Take receiver to wait for code
     let (mut code_rx, redirect_uri) = {
@@ -0,0 +1,170 @@
+// 离线模式
+// 两种来源：
+// - 手动开关 (AppConfig.offline.manual)
+// - 自动检测：后台定期探测 probe_url，连续失败后判定为离线，恢复后自动退出
+// 离线期间后台网络任务 (配额刷新 / 预热 / 模型目录同步 / 版本检查) 暂停，
+// 反代直接以 503 "offline" 拒绝请求，界面继续展示缓存的配额数据并标注其时效。
+
+use crate::models::OfflineConfig;
+use crate::modules::logger;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU32, Ordering};
+use std::sync::RwLock;
+use tokio::time::{self, Duration};
+
+/// 连续探测失败多少次后判定为离线，避免偶发抖动
+const FAILURES_BEFORE_OFFLINE: u32 = 2;
+const PROBE_TIMEOUT_SECS: u64 = 5;
+
+static CONFIG: Lazy<RwLock<OfflineConfig>> = Lazy::new(|| {
+    RwLock::new(
+        crate::modules::config::load_app_config()
+            .map(|cfg| cfg.offline)
+            .unwrap_or_default(),
+    )
+});
+
+static AUTO_OFFLINE: AtomicBool = AtomicBool::new(false);
+static CONSECUTIVE_FAILURES: AtomicU32 = AtomicU32::new(0);
+static LAST_CHECKED_AT: AtomicI64 = AtomicI64::new(0);
+static LAST_ONLINE_AT: AtomicI64 = AtomicI64::new(0);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkStatus {
+    pub offline: bool,
+    pub manual: bool,
+    pub auto_detected: bool,
+    pub auto_detect: bool,
+    pub last_checked_at: Option<i64>,
+    pub last_online_at: Option<i64>,
+}
+
+fn config() -> OfflineConfig {
+    CONFIG.read().map(|cfg| cfg.clone()).unwrap_or_default()
+}
+
+fn non_zero(ts: i64) -> Option<i64> {
+    (ts > 0).then_some(ts)
+}
+
+/// 当前是否处于离线模式 (手动或自动检测)
+pub fn is_offline() -> bool {
+    let cfg = config();
+    cfg.manual || (cfg.auto_detect && AUTO_OFFLINE.load(Ordering::Relaxed))
+}
+
+/// 离线时返回统一的错误，供命令 / 刷新逻辑直接 `?` 使用
+pub fn ensure_online() -> Result<(), String> {
+    if is_offline() {
+        Err("offline: network unavailable, using cached data".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+pub fn status() -> NetworkStatus {
+    let cfg = config();
+    let auto_detected = cfg.auto_detect && AUTO_OFFLINE.load(Ordering::Relaxed);
+    NetworkStatus {
+        offline: cfg.manual || auto_detected,
+        manual: cfg.manual,
+        auto_detected,
+        auto_detect: cfg.auto_detect,
+        last_checked_at: non_zero(LAST_CHECKED_AT.load(Ordering::Relaxed)),
+        last_online_at: non_zero(LAST_ONLINE_AT.load(Ordering::Relaxed)),
+    }
+}
+
+/// 保存配置后同步内存中的离线配置
+pub fn update_config(new_config: OfflineConfig) {
+    if let Ok(mut cfg) = CONFIG.write() {
+        if cfg.manual != new_config.manual {
+            logger::log_info(&format!(
+                "[Offline] Manual offline mode {}",
+                if new_config.manual { "enabled" } else { "disabled" }
+            ));
+        }
+        *cfg = new_config;
+    }
+}
+
+/// 手动切换离线模式并持久化
+pub fn set_manual(enabled: bool) -> Result<NetworkStatus, String> {
+    let mut app_config = crate::modules::config::load_app_config()?;
+    app_config.offline.manual = enabled;
+    crate::modules::config::save_app_config(&app_config)?;
+    update_config(app_config.offline);
+    Ok(status())
+}
+
+/// 记录一次探测结果，返回离线状态是否发生变化
+fn record_probe(online: bool, now: i64) -> bool {
+    LAST_CHECKED_AT.store(now, Ordering::Relaxed);
+    let was_offline = AUTO_OFFLINE.load(Ordering::Relaxed);
+    if online {
+        LAST_ONLINE_AT.store(now, Ordering::Relaxed);
+        CONSECUTIVE_FAILURES.store(0, Ordering::Relaxed);
+        AUTO_OFFLINE.store(false, Ordering::Relaxed);
+        return was_offline;
+    }
+    let failures = CONSECUTIVE_FAILURES.fetch_add(1, Ordering::Relaxed) + 1;
+    if failures >= FAILURES_BEFORE_OFFLINE {
+        AUTO_OFFLINE.store(true, Ordering::Relaxed);
+        return !was_offline;
+    }
+    false
+}
+
+/// 探测网络连通性：收到任意 HTTP 响应即视为在线 (遵循上游代理设置)
+async fn probe(url: &str) -> bool {
+    let client = crate::modules::http::client_for(
+        false,
+        PROBE_TIMEOUT_SECS,
+        crate::modules::http::Route::upstream(),
+    );
+    client.get(url).send().await.is_ok()
+}
+
+/// 启动连通性监测任务
+pub fn start_connectivity_monitor() {
+    crate::utils::runtime::spawn(async move {
+        loop {
+            let cfg = config();
+            if cfg.auto_detect && !cfg.manual {
+                let online = probe(&cfg.probe_url).await;
+                if record_probe(online, chrono::Utc::now().timestamp()) {
+                    if online {
+                        logger::log_info("[Offline] Network connectivity restored, resuming background tasks");
+                    } else {
+                        logger::log_warn("[Offline] Network unreachable, entering offline mode (background tasks paused)");
+                    }
+                }
+            }
+            time::sleep(Duration::from_secs(cfg.probe_interval_secs.max(10))).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_probe_hysteresis() {
+        AUTO_OFFLINE.store(false, Ordering::Relaxed);
+        CONSECUTIVE_FAILURES.store(0, Ordering::Relaxed);
+
+        // 单次失败不切换
+        assert!(!record_probe(false, 100));
+        assert!(!AUTO_OFFLINE.load(Ordering::Relaxed));
+        // 连续失败后进入离线
+        assert!(record_probe(false, 160));
+        assert!(AUTO_OFFLINE.load(Ordering::Relaxed));
+        assert!(!record_probe(false, 220));
+        // 一次成功即恢复
+        assert!(record_probe(true, 280));
+        assert!(!AUTO_OFFLINE.load(Ordering::Relaxed));
+        assert_eq!(LAST_ONLINE_AT.load(Ordering::Relaxed), 280);
+    }
+}
@@ -0,0 +1,238 @@
+// 首次运行引导
+// 按顺序引导新用户完成：定位 Antigravity 可执行文件 -> 备份原始设备指纹 -> 添加第一个账号 -> 设置反代端口
+// - 每一步的完成状态优先根据实际环境检测 (已配置路径 / 已有基线 / 已有账号)，老用户升级后不会被重复引导
+// - 步骤必须按顺序完成或跳过；进度保存在 AppConfig.onboarding
+
+use crate::models::{AppConfig, OnboardingStep};
+use crate::modules::{config, device, logger, process};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Done,
+    Skipped,
+    Pending,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OnboardingStepState {
+    pub step: OnboardingStep,
+    pub status: StepStatus,
+    /// 检测到的当前值 (可执行文件路径 / 账号数 / 端口)，供界面预填
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OnboardingState {
+    pub steps: Vec<OnboardingStepState>,
+    /// 下一步需要处理的步骤；全部完成时为 None
+    pub current_step: Option<OnboardingStep>,
+    pub finished: bool,
+}
+
+/// complete_onboarding_step 的输入；未提供的字段使用检测到的值
+#[derive(Debug, Clone, Default, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingStepInput {
+    /// locate_executable: 手动指定的可执行文件路径
+    pub path: Option<String>,
+    /// configure_proxy_port: 反代监听端口
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub skip: bool,
+}
+
+/// 已配置且存在的路径优先，否则自动探测
+fn detected_executable(cfg: &AppConfig) -> Option<String> {
+    cfg.antigravity_executable
+        .as_deref()
+        .filter(|p| Path::new(p).exists())
+        .map(str::to_string)
+        .or_else(|| {
+            process::get_antigravity_executable_path().map(|p| p.to_string_lossy().to_string())
+        })
+}
+
+fn account_count() -> usize {
+    crate::modules::account::list_accounts()
+        .map(|accounts| accounts.len())
+        .unwrap_or(0)
+}
+
+/// 根据实际环境判断步骤是否已满足 (端口没有可检测的状态，只能由用户确认)
+fn detect(step: OnboardingStep, cfg: &AppConfig) -> (bool, Option<String>) {
+    match step {
+        OnboardingStep::LocateExecutable => {
+            let configured = cfg
+                .antigravity_executable
+                .as_deref()
+                .is_some_and(|p| Path::new(p).exists());
+            (configured, detected_executable(cfg))
+        }
+        OnboardingStep::CaptureDeviceBaseline => (device::load_global_original().is_some(), None),
+        OnboardingStep::AddAccount => {
+            let count = account_count();
+            (count > 0, Some(count.to_string()))
+        }
+        OnboardingStep::ConfigureProxyPort => (false, Some(cfg.proxy.port.to_string())),
+    }
+}
+
+fn build_state(cfg: &AppConfig) -> OnboardingState {
+    let steps: Vec<OnboardingStepState> = OnboardingStep::ALL
+        .iter()
+        .map(|&step| {
+            let (detected, detail) = detect(step, cfg);
+            let status = if detected || cfg.onboarding.completed_steps.contains(&step) {
+                StepStatus::Done
+            } else if cfg.onboarding.skipped_steps.contains(&step) {
+                StepStatus::Skipped
+            } else {
+                StepStatus::Pending
+            };
+            OnboardingStepState {
+                step,
+                status,
+                detail,
+            }
+        })
+        .collect();
+    let current_step = steps
+        .iter()
+        .find(|s| s.status == StepStatus::Pending)
+        .map(|s| s.step);
+    OnboardingState {
+        finished: current_step.is_none(),
+        current_step,
+        steps,
+    }
+}
+
+pub fn get_onboarding_state() -> Result<OnboardingState, String> {
+    Ok(build_state(&config::load_app_config()?))
+}
+
+fn port_available(port: u16) -> bool {
+    std::net::TcpListener::bind(("127.0.0.1", port)).is_ok()
+}
+
+/// 执行某一步的动作并写入进度
+fn apply_step(
+    cfg: &mut AppConfig,
+    step: OnboardingStep,
+    input: &OnboardingStepInput,
+) -> Result<(), String> {
+    match step {
+        OnboardingStep::LocateExecutable => {
+            let path = input
+                .path
+                .clone()
+                .filter(|p| !p.trim().is_empty())
+                .or_else(|| detected_executable(cfg))
+                .ok_or("antigravity_executable_not_found")?;
+            if !Path::new(&path).exists() {
+                return Err(format!("antigravity_executable_not_found: {}", path));
+            }
+            cfg.antigravity_executable = Some(path);
+        }
+        OnboardingStep::CaptureDeviceBaseline => {
+            if device::load_global_original().is_none() {
+                let profile = device::read_profile(&device::get_storage_path()?)?;
+                device::save_global_original(&profile)?;
+            }
+        }
+        OnboardingStep::AddAccount => {
+            if account_count() == 0 {
+                return Err("no_accounts_added".to_string());
+            }
+        }
+        OnboardingStep::ConfigureProxyPort => {
+            let port = input.port.unwrap_or(cfg.proxy.port);
+            if port < 1024 {
+                return Err(format!("invalid_port: {}", port));
+            }
+            // 当前端口可能正被运行中的反代占用，仅对新端口做占用检测
+            if port != cfg.proxy.port && !port_available(port) {
+                return Err(format!("port_in_use: {}", port));
+            }
+            cfg.proxy.port = port;
+        }
+    }
+    Ok(())
+}
+
+/// 完成 (或跳过) 一个引导步骤，返回更新后的状态
+pub fn complete_onboarding_step(
+    step: OnboardingStep,
+    input: OnboardingStepInput,
+) -> Result<OnboardingState, String> {
+    let mut cfg = config::load_app_config()?;
+    let state = build_state(&cfg);
+
+    // 之前的步骤需先完成或跳过
+    if let Some(blocking) = state
+        .steps
+        .iter()
+        .take_while(|s| s.step != step)
+        .find(|s| s.status == StepStatus::Pending)
+    {
+        return Err(format!(
+            "onboarding_step_out_of_order: {:?} must be completed first",
+            blocking.step
+        ));
+    }
+
+    cfg.onboarding.completed_steps.retain(|s| *s != step);
+    cfg.onboarding.skipped_steps.retain(|s| *s != step);
+    if input.skip {
+        cfg.onboarding.skipped_steps.push(step);
+    } else {
+        apply_step(&mut cfg, step, &input)?;
+        cfg.onboarding.completed_steps.push(step);
+    }
+
+    let state = build_state(&cfg);
+    if state.finished && cfg.onboarding.finished_at.is_none() {
+        cfg.onboarding.finished_at = Some(chrono::Utc::now().timestamp());
+    }
+    config::save_app_config(&cfg)?;
+    logger::log_info(&format!(
+        "[Onboarding] Step {:?} {}",
+        step,
+        if input.skip { "skipped" } else { "completed" }
+    ));
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_port_step_requires_confirmation_and_order() {
+        let mut cfg = AppConfig::new();
+        cfg.onboarding.skipped_steps = vec![
+            OnboardingStep::LocateExecutable,
+            OnboardingStep::CaptureDeviceBaseline,
+            OnboardingStep::AddAccount,
+        ];
+        let state = build_state(&cfg);
+        let port = state.steps.last().unwrap();
+        assert_eq!(port.status, StepStatus::Pending);
+        assert_eq!(port.detail.as_deref(), Some("8045"));
+        assert_eq!(state.current_step, Some(OnboardingStep::ConfigureProxyPort));
+
+        cfg.onboarding.completed_steps.push(OnboardingStep::ConfigureProxyPort);
+        assert!(build_state(&cfg).finished);
+
+        let input = OnboardingStepInput {
+            port: Some(80),
+            ..Default::default()
+        };
+        assert!(apply_step(&mut cfg, OnboardingStep::ConfigureProxyPort, &input)
+            .unwrap_err()
+            .starts_with("invalid_port"));
+    }
+}
@@ -0,0 +1,150 @@
+//! Registry for long-running batch operations (quota refresh, bulk import).
+//! Each operation gets an ID that the caller can pass to `cancel_operation` to abort it;
+//! batch loops check the token between items and report partial results.
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OperationInfo {
+    pub id: String,
+    pub kind: String,
+    pub started_at: i64,
+}
+
+struct OperationEntry {
+    info: OperationInfo,
+    token: CancellationToken,
+}
+
+static OPERATIONS: Lazy<Mutex<HashMap<String, OperationEntry>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Handle of a running operation. Unregisters itself when dropped.
+pub struct OperationGuard {
+    id: String,
+    token: CancellationToken,
+}
+
+impl OperationGuard {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+}
+
+impl Drop for OperationGuard {
+    fn drop(&mut self) {
+        if let Ok(mut ops) = OPERATIONS.lock() {
+            ops.remove(&self.id);
+        }
+    }
+}
+
+/// Register a new operation. A caller-supplied ID is used when it is non-empty, so the
+/// frontend can cancel an operation before the command returns; it must not be in use by a
+/// running operation, or cancelling and polling would target the wrong one.
+pub fn begin_operation(kind: &str, requested_id: Option<String>) -> Result<OperationGuard, String> {
+    let token = CancellationToken::new();
+    let mut ops = OPERATIONS.lock().unwrap_or_else(|e| e.into_inner());
+
+    let id = match requested_id.filter(|id| !id.trim().is_empty()) {
+        Some(id) if ops.contains_key(&id) => return Err(format!("operation_id_in_use: {}", id)),
+        Some(id) => id,
+        None => uuid::Uuid::new_v4().to_string(),
+    };
+
+    let info = OperationInfo {
+        id: id.clone(),
+        kind: kind.to_string(),
+        started_at: chrono::Utc::now().timestamp(),
+    };
+    ops.insert(
+        id.clone(),
+        OperationEntry {
+            info: info.clone(),
+            token: token.clone(),
+        },
+    );
+    drop(ops);
+
+    crate::modules::log_bridge::emit_event("operation://started", info);
+    Ok(OperationGuard { id, token })
+}
+
+/// Request cancellation of a running operation. Returns false if the ID is unknown.
+pub fn cancel_operation(id: &str) -> bool {
+    let ops = OPERATIONS.lock().unwrap_or_else(|e| e.into_inner());
+    match ops.get(id) {
+        Some(entry) => {
+            entry.token.cancel();
+            crate::modules::logger::log_info(&format!(
+                "[Operations] Cancellation requested for {} ({})",
+                id, entry.info.kind
+            ));
+            true
+        }
+        None => false,
+    }
+}
+
+/// List currently running operations
+pub fn list_operations() -> Vec<OperationInfo> {
+    let ops = OPERATIONS.lock().unwrap_or_else(|e| e.into_inner());
+    let mut list: Vec<OperationInfo> = ops.values().map(|e| e.info.clone()).collect();
+    list.sort_by_key(|info| info.started_at);
+    list
+}
+
+/// Non-blocking variant for the panic hook, where the panicking thread may hold the lock
+pub fn try_list_operations() -> Option<Vec<OperationInfo>> {
+    let ops = match OPERATIONS.try_lock() {
+        Ok(ops) => ops,
+        Err(std::sync::TryLockError::Poisoned(e)) => e.into_inner(),
+        Err(std::sync::TryLockError::WouldBlock) => return None,
+    };
+    let mut list: Vec<OperationInfo> = ops.values().map(|e| e.info.clone()).collect();
+    list.sort_by_key(|info| info.started_at);
+    Some(list)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_and_unregister() {
+        let op = begin_operation("test", Some("op-test-cancel".to_string())).unwrap();
+        assert_eq!(op.id(), "op-test-cancel");
+        assert!(!op.is_cancelled());
+        assert!(cancel_operation("op-test-cancel"));
+        assert!(op.is_cancelled());
+
+        drop(op);
+        assert!(!cancel_operation("op-test-cancel"));
+    }
+
+    #[test]
+    fn test_duplicate_id_is_rejected() {
+        let first = begin_operation("test", Some("op-test-dup".to_string())).unwrap();
+        let err = begin_operation("test", Some("op-test-dup".to_string())).err().unwrap();
+        assert!(err.starts_with("operation_id_in_use"));
+
+        // Free again once the first operation finishes
+        drop(first);
+        assert!(begin_operation("test", Some("op-test-dup".to_string())).is_ok());
+
+        let blank = begin_operation("test", Some("  ".to_string())).unwrap();
+        assert!(!blank.id().trim().is_empty());
+    }
+}
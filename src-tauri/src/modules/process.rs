@@ -110,7 +110,7 @@ pub fn is_antigravity_running() -> bool {
 
         #[cfg(target_os = "macos")]
         {
-            if exe_path.contains("antigravity.app") && !is_helper {
+            if is_antigravity_bundle(&exe_path) && !is_helper {
                 return true;
             }
         }
@@ -124,9 +124,10 @@ pub fn is_antigravity_running() -> bool {
 
         #[cfg(target_os = "linux")]
         {
-            if (name.contains("antigravity") || exe_path.contains("/antigravity"))
+            if !is_helper
+                && (name.contains("antigravity")
+                    || classify_linux_process(&name, &exe_path, &args_str).is_some())
                 && !name.contains("tools")
-                && !is_helper
             {
                 return true;
             }
@@ -136,6 +137,92 @@ pub fn is_antigravity_running() -> bool {
     false
 }
 
+/// Whether the Antigravity copy at `target` is running (`None` = any copy)
+pub fn is_antigravity_running_for(target: Option<&std::path::Path>) -> bool {
+    match target {
+        None => is_antigravity_running(),
+        Some(_) => !get_antigravity_pids_for(target).is_empty(),
+    }
+}
+
+/// `/Applications/Antigravity 2.app/Contents/MacOS/Electron` -> `/Applications/Antigravity 2.app`
+fn app_bundle_root(path: &str) -> Option<&str> {
+    let mut offset = 0;
+    while let Some(idx) = path[offset..].find(".app") {
+        let end = offset + idx + 4;
+        if end == path.len() || path[end..].starts_with('/') {
+            return Some(&path[..end]);
+        }
+        offset = end;
+    }
+    None
+}
+
+/// Any Antigravity bundle ("Antigravity.app", "Antigravity 2.app", ...), but not this manager
+/// ("Antigravity Tools.app"). Expects a lower-cased path.
+fn is_antigravity_bundle(path: &str) -> bool {
+    app_bundle_root(path)
+        .and_then(|root| root.rsplit('/').next())
+        .is_some_and(|bundle| bundle.starts_with("antigravity") && !bundle.contains("tools"))
+}
+
+/// Whether `path` names an Antigravity program: an Antigravity .app bundle (or a file inside it),
+/// `Antigravity.exe`, the `antigravity` binary / launcher, an Antigravity AppImage or a Flatpak
+/// wrapper. This manager's own executable does not count.
+fn is_antigravity_program_name(path: &str) -> bool {
+    let lower = path.replace('\\', "/").to_lowercase();
+    if app_bundle_root(&lower).is_some() {
+        return is_antigravity_bundle(&lower);
+    }
+    let file_name = lower.rsplit('/').next().unwrap_or_default();
+    let stem = file_name
+        .strip_suffix(".exe")
+        .or_else(|| file_name.strip_suffix(".appimage"))
+        .unwrap_or(file_name);
+    stem.contains("antigravity") && !stem.contains("tools") && !stem.contains("manager")
+}
+
+/// Check a user-supplied Antigravity executable path before it is stored and later launched.
+/// Both the path and its resolved target must name Antigravity, so a link called `antigravity`
+/// pointing at another program is rejected (snap's `/snap/bin/*` launchers resolve to `snap`).
+pub fn validate_antigravity_executable(path: &str) -> Result<(), String> {
+    let target = std::path::Path::new(path)
+        .canonicalize()
+        .map_err(|_| format!("antigravity_executable_not_found: {}", path))?;
+    let is_bundle = app_bundle_root(&target.to_string_lossy().to_lowercase()).is_some();
+    if !(target.is_file() || is_bundle && target.is_dir()) {
+        return Err(format!("antigravity_executable_invalid: {} is not a program", path));
+    }
+    let target_str = target.to_string_lossy();
+    let snap_launcher = path.starts_with("/snap/bin/") && target_str == "/usr/bin/snap";
+    if !is_antigravity_program_name(path)
+        || !(snap_launcher || is_antigravity_program_name(&target_str))
+    {
+        return Err(format!(
+            "antigravity_executable_invalid: {} is not an Antigravity executable",
+            path
+        ));
+    }
+    Ok(())
+}
+
+/// Whether `exe` belongs to the installation at `target`: the same .app bundle on macOS,
+/// otherwise the same program directory (Electron helpers live next to the main executable)
+fn installation_contains(target: &str, exe: &str) -> bool {
+    if let (Some(target_root), Some(exe_root)) = (app_bundle_root(target), app_bundle_root(exe)) {
+        return target_root == exe_root;
+    }
+    let target = std::path::Path::new(target);
+    let exe = std::path::Path::new(exe);
+    if exe == target {
+        return true;
+    }
+    match (target.parent(), exe.parent()) {
+        (Some(t), Some(e)) => e.starts_with(t),
+        _ => false,
+    }
+}
+
 #[cfg(target_os = "linux")]
 /// Get PID set of current process and all direct relatives (ancestors + descendants)
 fn get_self_family_pids(system: &sysinfo::System) -> std::collections::HashSet<u32> {
@@ -192,6 +279,12 @@ fn get_self_family_pids(system: &sysinfo::System) -> std::collections::HashSet<u
 
 /// Get PIDs of all Antigravity processes (including main and helper processes)
 fn get_antigravity_pids() -> Vec<u32> {
+    get_antigravity_pids_for(None)
+}
+
+/// Get PIDs of Antigravity processes, limited to one installation when `target` is given
+/// (a macOS .app bundle or an executable path), so other copies are left alone
+fn get_antigravity_pids_for(target: Option<&std::path::Path>) -> Vec<u32> {
     let mut system = System::new();
     system.refresh_processes(sysinfo::ProcessesToUpdate::All);
 
@@ -316,7 +409,7 @@ fn get_antigravity_pids() -> Vec<u32> {
         #[cfg(target_os = "macos")]
         {
             // Match processes within Antigravity main app bundle, excluding Helper/Plugin/Renderer etc.
-            if exe_path.contains("antigravity.app") && !is_helper {
+            if is_antigravity_bundle(&exe_path) && !is_helper {
                 pids.push(pid_u32);
             }
         }
@@ -332,15 +425,27 @@ fn get_antigravity_pids() -> Vec<u32> {
         #[cfg(target_os = "linux")]
         {
             let name = process.name().to_string_lossy().to_lowercase();
-            if (name == "antigravity" || exe_path.contains("/antigravity"))
-                && !name.contains("tools")
-                && !is_helper
-            {
+            if !is_helper && classify_linux_process(&name, &exe_path, &args_str).is_some() {
                 pids.push(pid_u32);
             }
         }
     }
 
+    if let Some(target) = target {
+        let target = target
+            .canonicalize()
+            .unwrap_or_else(|_| target.to_path_buf());
+        pids.retain(|pid| {
+            system
+                .process(sysinfo::Pid::from_u32(*pid))
+                .and_then(|p| p.exe())
+                .map(|exe| exe.canonicalize().unwrap_or_else(|_| exe.to_path_buf()))
+                .is_some_and(|exe| {
+                    installation_contains(&target.to_string_lossy(), &exe.to_string_lossy())
+                })
+        });
+    }
+
     if !pids.is_empty() {
         crate::modules::logger::log_info(&format!(
             "Found {} Antigravity processes: {:?}",
@@ -353,13 +458,22 @@ fn get_antigravity_pids() -> Vec<u32> {
 }
 
 /// Close Antigravity processes
-pub fn close_antigravity(#[allow(unused_variables)] timeout_secs: u64) -> Result<(), String> {
+pub fn close_antigravity(timeout_secs: u64) -> Result<(), String> {
+    close_antigravity_for(timeout_secs, None)
+}
+
+/// Close the Antigravity copy at `target` (bundle / executable path); other running copies are
+/// never signalled. `None` closes every detected Antigravity process.
+pub fn close_antigravity_for(
+    #[allow(unused_variables)] timeout_secs: u64,
+    target: Option<&std::path::Path>,
+) -> Result<(), String> {
     crate::modules::logger::log_info("Closing Antigravity...");
 
     #[cfg(target_os = "windows")]
     {
         // Windows: Precise kill by PID to support multiple versions or custom filenames
-        let pids = get_antigravity_pids();
+        let pids = get_antigravity_pids_for(target);
         if !pids.is_empty() {
             crate::modules::logger::log_info(&format!(
                 "Precisely closing {} identified processes on Windows...",
@@ -381,7 +495,7 @@ pub fn close_antigravity(#[allow(unused_variables)] timeout_secs: u64) -> Result
         // macOS: Optimize closing strategy to avoid "Window terminated unexpectedly" popups
         // Strategy: SEND SIGTERM to main process only, let it coordinate closing children
 
-        let pids = get_antigravity_pids();
+        let pids = get_antigravity_pids_for(target);
         if !pids.is_empty() {
             // 1. Identify main process (PID)
             // Strategy: Principal processes of Electron/Tauri do not have the `--type` parameter, while Helper processes have `--type=renderer/gpu/utility`, etc.
@@ -390,10 +504,14 @@ pub fn close_antigravity(#[allow(unused_variables)] timeout_secs: u64) -> Result
 
             let mut main_pid = None;
 
-            // Load manual configuration path as highest priority reference
-            let manual_path = crate::modules::config::load_app_config()
-                .ok()
-                .and_then(|c| c.antigravity_executable)
+            // Load manual configuration path as highest priority reference (the target copy if given)
+            let manual_path = target
+                .map(|t| t.to_string_lossy().to_string())
+                .or_else(|| {
+                    crate::modules::config::load_app_config()
+                        .ok()
+                        .and_then(|c| c.antigravity_executable)
+                })
                 .and_then(|p| std::path::PathBuf::from(p).canonicalize().ok());
 
             crate::modules::logger::log_info("Analyzing process list to identify main process:");
@@ -507,7 +625,7 @@ pub fn close_antigravity(#[allow(unused_variables)] timeout_secs: u64) -> Result
             let graceful_timeout = (timeout_secs * 7) / 10;
             let start = std::time::Instant::now();
             while start.elapsed() < Duration::from_secs(graceful_timeout) {
-                if !is_antigravity_running() {
+                if !is_antigravity_running_for(target) {
                     crate::modules::logger::log_info("All Antigravity processes gracefully closed");
                     return Ok(());
                 }
@@ -515,8 +633,8 @@ pub fn close_antigravity(#[allow(unused_variables)] timeout_secs: u64) -> Result
             }
 
             // Phase 2: Force kill (SIGKILL) - targeting all remaining processes (Helpers)
-            if is_antigravity_running() {
-                let remaining_pids = get_antigravity_pids();
+            if is_antigravity_running_for(target) {
+                let remaining_pids = get_antigravity_pids_for(target);
                 if !remaining_pids.is_empty() {
                     crate::modules::logger::log_warn(&format!(
                         "Graceful exit timeout, force killing {} remaining processes (SIGKILL)",
@@ -542,7 +660,7 @@ pub fn close_antigravity(#[allow(unused_variables)] timeout_secs: u64) -> Result
                 }
 
                 // Final check
-                if !is_antigravity_running() {
+                if !is_antigravity_running_for(target) {
                     crate::modules::logger::log_info("All processes exited after forced cleanup");
                     return Ok(());
                 }
@@ -560,17 +678,21 @@ pub fn close_antigravity(#[allow(unused_variables)] timeout_secs: u64) -> Result
     #[cfg(target_os = "linux")]
     {
         // Linux: Also attempt to identify main process and delegate exit
-        let pids = get_antigravity_pids();
+        let pids = get_antigravity_pids_for(target);
         if !pids.is_empty() {
             let mut system = System::new();
             system.refresh_processes(sysinfo::ProcessesToUpdate::All);
 
             let mut main_pid = None;
 
-            // Load manual configuration path as highest priority reference
-            let manual_path = crate::modules::config::load_app_config()
-                .ok()
-                .and_then(|c| c.antigravity_executable)
+            // Load manual configuration path as highest priority reference (the target copy if given)
+            let manual_path = target
+                .map(|t| t.to_string_lossy().to_string())
+                .or_else(|| {
+                    crate::modules::config::load_app_config()
+                        .ok()
+                        .and_then(|c| c.antigravity_executable)
+                })
                 .and_then(|p| std::path::PathBuf::from(p).canonicalize().ok());
 
             crate::modules::logger::log_info("Analyzing Linux process list to identify main process:");
@@ -662,7 +784,7 @@ pub fn close_antigravity(#[allow(unused_variables)] timeout_secs: u64) -> Result
             let graceful_timeout = (timeout_secs * 7) / 10;
             let start = std::time::Instant::now();
             while start.elapsed() < Duration::from_secs(graceful_timeout) {
-                if !is_antigravity_running() {
+                if !is_antigravity_running_for(target) {
                     crate::modules::logger::log_info("Antigravity gracefully closed");
                     return Ok(());
                 }
@@ -670,8 +792,8 @@ pub fn close_antigravity(#[allow(unused_variables)] timeout_secs: u64) -> Result
             }
 
             // Phase 2: Force kill (SIGKILL) - targeting all remaining processes
-            if is_antigravity_running() {
-                let remaining_pids = get_antigravity_pids();
+            if is_antigravity_running_for(target) {
+                let remaining_pids = get_antigravity_pids_for(target);
                 if !remaining_pids.is_empty() {
                     crate::modules::logger::log_warn(&format!(
                         "Graceful exit timeout, force killing {} remaining processes (SIGKILL)",
@@ -692,7 +814,7 @@ pub fn close_antigravity(#[allow(unused_variables)] timeout_secs: u64) -> Result
     }
 
     // Final check
-    if is_antigravity_running() {
+    if is_antigravity_running_for(target) {
         return Err("Unable to close Antigravity process, please close manually and retry".to_string());
     }
 
@@ -703,14 +825,27 @@ pub fn close_antigravity(#[allow(unused_variables)] timeout_secs: u64) -> Result
 /// Start Antigravity
 #[allow(unused_mut)]
 pub fn start_antigravity() -> Result<(), String> {
+    start_antigravity_with(None, None)
+}
+
+/// Start Antigravity with an account's launch args merged over the global ones
+/// (see `merge_launch_args` for precedence). `executable` selects the installation
+/// (e.g. a second .app bundle) instead of the global `antigravity_executable`.
+pub fn start_antigravity_with(
+    account_args: Option<&[String]>,
+    executable: Option<&str>,
+) -> Result<(), String> {
     crate::modules::logger::log_info("Starting Antigravity...");
 
     // Prefer manually specified path and args from configuration
     let config = crate::modules::config::load_app_config().ok();
-    let manual_path = config
-        .as_ref()
-        .and_then(|c| c.antigravity_executable.clone());
-    let args = config.and_then(|c| c.antigravity_args.clone());
+    let manual_path = executable
+        .map(str::to_string)
+        .or_else(|| config.as_ref().and_then(|c| c.antigravity_executable.clone()));
+    let args = merge_launch_args(
+        config.as_ref().and_then(|c| c.antigravity_args.as_deref()),
+        account_args,
+    );
 
     if let Some(mut path_str) = manual_path {
         let mut path = std::path::PathBuf::from(&path_str);
@@ -939,7 +1074,7 @@ fn get_process_info() -> (Option<std::path::PathBuf>, Option<Vec<String>>) {
             #[cfg(target_os = "macos")]
             {
                 // macOS: Exclude helper processes, match main app only, and check Frameworks
-                if exe_path.contains("antigravity.app")
+                if is_antigravity_bundle(&exe_path)
                     && !is_helper
                     && !exe_path.contains("frameworks")
                 {
@@ -963,12 +1098,26 @@ fn get_process_info() -> (Option<std::path::PathBuf>, Option<Vec<String>>) {
 
             #[cfg(target_os = "linux")]
             {
-                // Linux: Check process name or path for antigravity, excluding helpers and manager
-                if (name == "antigravity" || exe_path.contains("/antigravity"))
-                    && !name.contains("tools")
-                    && !is_helper
-                {
-                    return (path, args);
+                // Linux: Check process name or path for antigravity, excluding helpers and manager.
+                // Sandboxed installs report paths that only exist inside the sandbox / mount,
+                // so map them back to a launchable host path.
+                if !is_helper {
+                    match classify_linux_process(&name, &exe_path, &args_str) {
+                        Some(LinuxPackaging::Native) | Some(LinuxPackaging::Snap) => {
+                            return (path, args);
+                        }
+                        Some(packaging) => {
+                            let environ: Vec<String> = process
+                                .environ()
+                                .iter()
+                                .map(|v| v.to_string_lossy().to_string())
+                                .collect();
+                            if let Some(host_path) = sandboxed_launch_path(packaging, &environ) {
+                                return (Some(host_path), args);
+                            }
+                        }
+                        None => {}
+                    }
                 }
             }
         }
@@ -992,58 +1141,105 @@ pub fn get_args_from_running_process() -> Option<Vec<String>> {
 
 /// Get --user-data-dir argument value (if exists)
 pub fn get_user_data_dir_from_process() -> Option<std::path::PathBuf> {
+    if crate::modules::headless::is_headless() {
+        return None;
+    }
+
     // Prefer getting startup arguments from config
     if let Ok(config) = crate::modules::config::load_app_config() {
-        if let Some(args) = config.antigravity_args {
-            // Check arguments in config
-            for i in 0..args.len() {
-                if args[i] == "--user-data-dir" && i + 1 < args.len() {
-                    // Next argument is the path
-                    let path = std::path::PathBuf::from(&args[i + 1]);
-                    if path.exists() {
-                        return Some(path);
-                    }
-                } else if args[i].starts_with("--user-data-dir=") {
-                    // Argument and value in same string, e.g. --user-data-dir=/path/to/data
-                    let parts: Vec<&str> = args[i].splitn(2, '=').collect();
-                    if parts.len() == 2 {
-                        let path_str = parts[1];
-                        let path = std::path::PathBuf::from(path_str);
-                        if path.exists() {
-                            return Some(path);
-                        }
-                    }
-                }
-            }
+        if let Some(dir) = config
+            .antigravity_args
+            .as_deref()
+            .and_then(user_data_dir_from_args)
+        {
+            return Some(dir);
         }
     }
 
     // If not in config, get arguments from running process
-    if let Some(args) = get_args_from_running_process() {
-        for i in 0..args.len() {
-            if args[i] == "--user-data-dir" && i + 1 < args.len() {
-                // Next argument is the path
-                let path = std::path::PathBuf::from(&args[i + 1]);
-                if path.exists() {
-                    return Some(path);
-                }
-            } else if args[i].starts_with("--user-data-dir=") {
-                // Argument and value in same string, e.g. --user-data-dir=/path/to/data
-                let parts: Vec<&str> = args[i].splitn(2, '=').collect();
-                if parts.len() == 2 {
-                    let path_str = parts[1];
-                    let path = std::path::PathBuf::from(path_str);
-                    if path.exists() {
-                        return Some(path);
-                    }
-                }
-            }
+    get_args_from_running_process()
+        .as_deref()
+        .and_then(user_data_dir_from_args)
+}
+
+/// Existing directory given by `--user-data-dir <path>` or `--user-data-dir=<path>`
+pub fn user_data_dir_from_args(args: &[String]) -> Option<std::path::PathBuf> {
+    for i in 0..args.len() {
+        let value = if args[i] == "--user-data-dir" && i + 1 < args.len() {
+            // Next argument is the path
+            &args[i + 1]
+        } else if let Some(value) = args[i].strip_prefix("--user-data-dir=") {
+            // Argument and value in same string, e.g. --user-data-dir=/path/to/data
+            value
+        } else {
+            continue;
+        };
+        let path = std::path::PathBuf::from(value);
+        if path.exists() {
+            return Some(path);
         }
     }
-
     None
 }
 
+/// Split launch args into units keyed by flag name: `--flag=value`, `--flag value`, `--flag`,
+/// or a positional argument (no key)
+fn launch_arg_units(args: &[String]) -> Vec<(Option<String>, Vec<String>)> {
+    let mut units = Vec::new();
+    let mut i = 0;
+    while i < args.len() {
+        let arg = &args[i];
+        if !arg.starts_with('-') {
+            units.push((None, vec![arg.clone()]));
+            i += 1;
+            continue;
+        }
+        if let Some((flag, _)) = arg.split_once('=') {
+            units.push((Some(flag.to_string()), vec![arg.clone()]));
+            i += 1;
+            continue;
+        }
+        let mut unit = vec![arg.clone()];
+        if let Some(value) = args.get(i + 1).filter(|next| !next.starts_with('-')) {
+            unit.push(value.clone());
+            i += 1;
+        }
+        units.push((Some(arg.clone()), unit));
+        i += 1;
+    }
+    units
+}
+
+/// Merge an account's launch args over the global `antigravity_args`.
+///
+/// Precedence: a flag set by the account (in any form) replaces every occurrence of that flag
+/// in the global args; the remaining global args come first, followed by the account args.
+/// Positional arguments are never replaced.
+pub fn merge_launch_args(
+    global: Option<&[String]>,
+    account: Option<&[String]>,
+) -> Option<Vec<String>> {
+    let Some(account) = account.filter(|a| !a.is_empty()) else {
+        return global.map(<[String]>::to_vec);
+    };
+    let account_units = launch_arg_units(account);
+    let overridden: std::collections::HashSet<&str> = account_units
+        .iter()
+        .filter_map(|(flag, _)| flag.as_deref())
+        .collect();
+
+    let mut merged: Vec<String> = launch_arg_units(global.unwrap_or_default())
+        .into_iter()
+        .filter(|(flag, _)| {
+            flag.as_deref()
+                .is_none_or(|flag| !overridden.contains(flag))
+        })
+        .flat_map(|(_, unit)| unit)
+        .collect();
+    merged.extend(account_units.into_iter().flat_map(|(_, unit)| unit));
+    Some(merged)
+}
+
 /// Get Antigravity executable path (cross-platform)
 ///
 /// Search strategy (highest to lowest priority):
@@ -1051,6 +1247,11 @@ pub fn get_user_data_dir_from_process() -> Option<std::path::PathBuf> {
 /// 2. Iterate standard installation locations
 /// 3. Return None
 pub fn get_antigravity_executable_path() -> Option<std::path::PathBuf> {
+    // Headless / Docker: never scan processes or install locations
+    if crate::modules::headless::is_headless() {
+        return None;
+    }
+
     // Strategy 1: Get from running process (supports any location)
     if let Some(path) = get_path_from_running_process() {
         return Some(path);
@@ -1062,11 +1263,36 @@ pub fn get_antigravity_executable_path() -> Option<std::path::PathBuf> {
 
 /// Check standard installation locations
 fn check_standard_locations() -> Option<std::path::PathBuf> {
+    standard_location_candidates()
+        .into_iter()
+        .find(|path| path.exists())
+}
+
+/// Standard installation locations, in preference order (existence not checked)
+fn standard_location_candidates() -> Vec<std::path::PathBuf> {
+    #[allow(unused_mut)]
+    let mut possible_paths: Vec<std::path::PathBuf> = Vec::new();
+
     #[cfg(target_os = "macos")]
     {
-        let path = std::path::PathBuf::from("/Applications/Antigravity.app");
-        if path.exists() {
-            return Some(path);
+        possible_paths.push(std::path::PathBuf::from("/Applications/Antigravity.app"));
+        // Per-user Applications folder
+        if let Some(home) = dirs::home_dir() {
+            possible_paths.push(home.join("Applications/Antigravity.app"));
+        }
+        // Additional copies such as "Antigravity 2.app"
+        let mut app_dirs = vec![std::path::PathBuf::from("/Applications")];
+        if let Some(home) = dirs::home_dir() {
+            app_dirs.push(home.join("Applications"));
+        }
+        for dir in app_dirs {
+            for bundle in antigravity_entries(&dir, ".app") {
+                if !possible_paths.contains(&bundle)
+                    && is_antigravity_bundle(&bundle.to_string_lossy().to_lowercase())
+                {
+                    possible_paths.push(bundle);
+                }
+            }
         }
     }
 
@@ -1081,8 +1307,6 @@ fn check_standard_locations() -> Option<std::path::PathBuf> {
         let program_files_x86 =
             env::var("ProgramFiles(x86)").unwrap_or_else(|_| "C:\\Program Files (x86)".to_string());
 
-        let mut possible_paths = Vec::new();
-
         // User installation location (preferred)
         if let Some(local) = local_appdata {
             possible_paths.push(
@@ -1106,37 +1330,554 @@ fn check_standard_locations() -> Option<std::path::PathBuf> {
                 .join("Antigravity")
                 .join("Antigravity.exe"),
         );
+    }
 
-        // Return the first existing path
-        for path in possible_paths {
-            if path.exists() {
-                return Some(path);
+    #[cfg(target_os = "linux")]
+    {
+        // User local installation
+        if let Some(home) = dirs::home_dir() {
+            possible_paths.push(home.join(".local/bin/antigravity"));
+        }
+
+        possible_paths.push(std::path::PathBuf::from("/usr/bin/antigravity"));
+        possible_paths.push(std::path::PathBuf::from("/opt/Antigravity/antigravity"));
+        possible_paths.push(std::path::PathBuf::from("/usr/share/antigravity/antigravity"));
+
+        // Snap, Flatpak (exported `flatpak run` wrappers) and AppImage files
+        possible_paths.push(std::path::PathBuf::from("/snap/bin/antigravity"));
+        for dir in flatpak_export_dirs() {
+            possible_paths.extend(antigravity_entries(&dir, ""));
+        }
+        if let Some(home) = dirs::home_dir() {
+            for dir in [home.join("Applications"), home.join(".local/bin")] {
+                possible_paths.extend(antigravity_entries(&dir, ".appimage"));
             }
         }
     }
 
-    #[cfg(target_os = "linux")]
+    possible_paths
+}
+
+/// How a Linux Antigravity installation is packaged
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+enum LinuxPackaging {
+    Native,
+    /// Runs inside a bubblewrap sandbox; executable paths are under /app
+    Flatpak,
+    /// Executable under /snap/<name>/
+    Snap,
+    /// Executable inside the AppImage's FUSE mount (/tmp/.mount_<Prefix><random>)
+    AppImage,
+}
+
+/// Decide whether a process is Antigravity (arguments are lower-cased).
+/// Besides native installs this recognises Flatpak (the `bwrap` sandbox or `/app/...` executables),
+/// snap (`/snap/...`) and AppImage (`/tmp/.mount_Antigr*`) processes; the manager itself is excluded.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn classify_linux_process(name: &str, exe_path: &str, args_str: &str) -> Option<LinuxPackaging> {
+    if name.contains("tools") {
+        return None;
+    }
+    if let Some(mount) = exe_path.split("/.mount_").nth(1) {
+        // The runtime names the mount after the first 6 characters of the AppImage file name
+        if mount.starts_with("antigr") || exe_path.contains("antigravity") {
+            return Some(LinuxPackaging::AppImage);
+        }
+    }
+    if exe_path.starts_with("/snap/") && exe_path.contains("antigravity") {
+        return Some(LinuxPackaging::Snap);
+    }
+    if (name == "bwrap" && args_str.contains("antigravity"))
+        || (exe_path.starts_with("/app/")
+            && (exe_path.contains("antigravity") || name == "antigravity"))
     {
-        let possible_paths = vec![
-            std::path::PathBuf::from("/usr/bin/antigravity"),
-            std::path::PathBuf::from("/opt/Antigravity/antigravity"),
-            std::path::PathBuf::from("/usr/share/antigravity/antigravity"),
-        ];
+        return Some(LinuxPackaging::Flatpak);
+    }
+    if name == "antigravity" || exe_path.contains("/antigravity") {
+        return Some(LinuxPackaging::Native);
+    }
+    None
+}
 
-        // User local installation
-        if let Some(home) = dirs::home_dir() {
-            let user_local = home.join(".local/bin/antigravity");
-            if user_local.exists() {
-                return Some(user_local);
+/// Host path that relaunches a sandboxed install: the `.AppImage` file for AppImage
+/// (`APPIMAGE` env), or the Flatpak export wrapper for the app id (`FLATPAK_ID` env)
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn sandboxed_launch_path(packaging: LinuxPackaging, environ: &[String]) -> Option<std::path::PathBuf> {
+    let env = |key: &str| {
+        environ
+            .iter()
+            .find_map(|kv| kv.strip_prefix(key)?.strip_prefix('='))
+            .filter(|v| !v.is_empty())
+    };
+    match packaging {
+        LinuxPackaging::AppImage => env("APPIMAGE").map(std::path::PathBuf::from),
+        LinuxPackaging::Flatpak => {
+            let app_id = env("FLATPAK_ID")?;
+            flatpak_export_dirs()
+                .into_iter()
+                .map(|dir| dir.join(app_id))
+                .find(|path| path.exists())
+        }
+        _ => None,
+    }
+}
+
+/// Directories holding the `flatpak run` wrappers of installed apps (system and user installs)
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn flatpak_export_dirs() -> Vec<std::path::PathBuf> {
+    let mut dirs_list = vec![std::path::PathBuf::from("/var/lib/flatpak/exports/bin")];
+    if let Some(home) = dirs::home_dir() {
+        dirs_list.push(home.join(".local/share/flatpak/exports/bin"));
+    }
+    dirs_list
+}
+
+/// Entries of `dir` whose file name contains "antigravity" and (optionally) ends with `suffix`
+#[cfg_attr(windows, allow(dead_code))]
+fn antigravity_entries(dir: &std::path::Path, suffix: &str) -> Vec<std::path::PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut found: Vec<std::path::PathBuf> = entries
+        .flatten()
+        .filter(|entry| {
+            let file_name = entry.file_name().to_string_lossy().to_lowercase();
+            file_name.contains("antigravity") && file_name.ends_with(suffix)
+        })
+        .map(|entry| entry.path())
+        .collect();
+    found.sort();
+    found
+}
+
+/// Antigravity config directories of sandboxed installs (the `Antigravity` folder that holds
+/// `User/globalStorage`): Flatpak keeps it under ~/.var/app/<app-id>/config, snap under
+/// ~/snap/<name>/current/.config. Used when the standard ~/.config/Antigravity does not exist.
+#[cfg(target_os = "linux")]
+pub fn linux_sandbox_config_dirs() -> Vec<std::path::PathBuf> {
+    let Some(home) = dirs::home_dir() else {
+        return Vec::new();
+    };
+    let mut found: Vec<std::path::PathBuf> = antigravity_entries(&home.join(".var/app"), "")
+        .into_iter()
+        .map(|app_dir| app_dir.join("config/Antigravity"))
+        .collect();
+    found.extend(
+        antigravity_entries(&home.join("snap"), "")
+            .into_iter()
+            .map(|snap_dir| snap_dir.join("current/.config/Antigravity")),
+    );
+    found.retain(|dir| dir.exists());
+    found
+}
+
+/// Where an installation candidate was discovered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallationSource {
+    Configured,
+    RunningProcess,
+    StandardLocation,
+    PathLookup,
+    DesktopEntry,
+    Registry,
+}
+
+impl InstallationSource {
+    /// Ranking weight; a candidate's score is its best source plus a bonus for readable version info
+    fn weight(self) -> u32 {
+        match self {
+            Self::RunningProcess => 100,
+            Self::Configured => 80,
+            Self::Registry => 40,
+            Self::StandardLocation | Self::DesktopEntry => 30,
+            Self::PathLookup => 20,
+        }
+    }
+}
+
+/// A detected Antigravity installation
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AntigravityInstallation {
+    pub path: String,
+    pub sources: Vec<InstallationSource>,
+    pub version: Option<String>,
+    pub running: bool,
+    pub configured: bool,
+    pub score: u32,
+}
+
+/// Executable file names to look for on PATH
+fn executable_names() -> &'static [&'static str] {
+    #[cfg(target_os = "windows")]
+    {
+        &["Antigravity.exe", "antigravity.exe"]
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        &["antigravity", "Antigravity"]
+    }
+}
+
+/// Look up the executable on PATH
+fn path_lookup_candidates() -> Vec<std::path::PathBuf> {
+    let Some(path_var) = std::env::var_os("PATH") else {
+        return Vec::new();
+    };
+    std::env::split_paths(&path_var)
+        .flat_map(|dir| executable_names().iter().map(move |name| dir.join(name)))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// Extract the program path from a .desktop `Exec=` line
+/// (strips quotes and field codes such as %U / %F)
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_desktop_exec(content: &str) -> Option<std::path::PathBuf> {
+    let exec = content
+        .lines()
+        .map(str::trim)
+        .find_map(|line| line.strip_prefix("Exec="))?
+        .trim();
+    let program = if let Some(rest) = exec.strip_prefix('"') {
+        rest.split('"').next()?
+    } else {
+        exec.split_whitespace().next()?
+    };
+    if program.is_empty() || program.starts_with('%') {
+        return None;
+    }
+    Some(std::path::PathBuf::from(program))
+}
+
+/// Scan .desktop launchers (system, user, flatpak, snap) for Antigravity entries
+#[cfg(target_os = "linux")]
+fn desktop_entry_candidates() -> Vec<std::path::PathBuf> {
+    let mut dirs_to_scan = vec![
+        std::path::PathBuf::from("/usr/share/applications"),
+        std::path::PathBuf::from("/usr/local/share/applications"),
+        std::path::PathBuf::from("/var/lib/flatpak/exports/share/applications"),
+        std::path::PathBuf::from("/var/lib/snapd/desktop/applications"),
+    ];
+    if let Some(home) = dirs::home_dir() {
+        dirs_to_scan.push(home.join(".local/share/applications"));
+        dirs_to_scan.push(home.join(".local/share/flatpak/exports/share/applications"));
+    }
+
+    let mut found = Vec::new();
+    for dir in dirs_to_scan {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_lowercase();
+            if !file_name.ends_with(".desktop") || !file_name.contains("antigravity") {
+                continue;
+            }
+            let Ok(content) = std::fs::read_to_string(entry.path()) else {
+                continue;
+            };
+            let Some(program) = parse_desktop_exec(&content) else {
+                continue;
+            };
+            // Bare program names are resolved via PATH
+            let resolved = if program.is_absolute() {
+                Some(program)
+            } else {
+                std::env::var_os("PATH").and_then(|path_var| {
+                    std::env::split_paths(&path_var)
+                        .map(|dir| dir.join(&program))
+                        .find(|path| path.is_file())
+                })
+            };
+            if let Some(path) = resolved.filter(|p| p.exists()) {
+                found.push(path);
             }
         }
+    }
+    found
+}
 
-        for path in possible_paths {
-            if path.exists() {
-                return Some(path);
+/// Read InstallLocation / DisplayIcon from the uninstall registry keys via `reg query`
+#[cfg(target_os = "windows")]
+fn registry_candidates() -> Vec<std::path::PathBuf> {
+    let roots = [
+        r"HKCU\Software\Microsoft\Windows\CurrentVersion\Uninstall",
+        r"HKLM\Software\Microsoft\Windows\CurrentVersion\Uninstall",
+        r"HKLM\Software\WOW6432Node\Microsoft\Windows\CurrentVersion\Uninstall",
+    ];
+    let mut found = Vec::new();
+    for root in roots {
+        let output = Command::new("reg")
+            .args(["query", root, "/s", "/f", "Antigravity", "/d"])
+            .creation_flags(0x08000000) // CREATE_NO_WINDOW
+            .output();
+        let Ok(output) = output else {
+            continue;
+        };
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        for line in stdout.lines() {
+            let line = line.trim();
+            let value = ["InstallLocation", "DisplayIcon"].iter().find_map(|name| {
+                line.strip_prefix(name)
+                    .and_then(|rest| rest.trim_start().strip_prefix("REG_SZ"))
+                    .map(|v| v.trim())
+            });
+            let Some(value) = value.filter(|v| !v.is_empty()) else {
+                continue;
+            };
+            // DisplayIcon may carry an icon index suffix ("...\Antigravity.exe,0")
+            let value = value.trim_matches('"');
+            let value = value.rsplit_once(',').map_or(value, |(path, _)| path);
+            let path = std::path::PathBuf::from(value);
+            let exe = if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("exe")) {
+                path
+            } else {
+                path.join("Antigravity.exe")
+            };
+            if exe.exists() {
+                found.push(exe);
             }
         }
     }
+    found
+}
 
-    None
+/// Merge candidates that point to the same file, keeping discovery order
+fn merge_candidates(
+    candidates: Vec<(std::path::PathBuf, InstallationSource)>,
+) -> Vec<(std::path::PathBuf, Vec<InstallationSource>)> {
+    let mut merged: Vec<(std::path::PathBuf, std::path::PathBuf, Vec<InstallationSource>)> =
+        Vec::new();
+    for (path, source) in candidates {
+        let key = std::fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+        match merged.iter_mut().find(|(k, _, _)| *k == key) {
+            Some((_, _, sources)) => {
+                if !sources.contains(&source) {
+                    sources.push(source);
+                }
+            }
+            None => merged.push((key, path, vec![source])),
+        }
+    }
+    merged
+        .into_iter()
+        .map(|(_, path, sources)| (path, sources))
+        .collect()
+}
+
+fn installation_score(sources: &[InstallationSource], has_version: bool) -> u32 {
+    let base = sources.iter().map(|s| s.weight()).max().unwrap_or(0);
+    base + if has_version { 10 } else { 0 }
+}
+
+/// Detect all Antigravity installations on this machine, best candidate first
+///
+/// Sources: configured path, running process, standard locations, PATH lookup,
+/// .desktop launchers (Linux) and uninstall registry keys (Windows).
+pub fn detect_antigravity_installations() -> Vec<AntigravityInstallation> {
+    let mut candidates: Vec<(std::path::PathBuf, InstallationSource)> = Vec::new();
+
+    let configured = crate::modules::config::load_app_config()
+        .ok()
+        .and_then(|config| config.antigravity_executable)
+        .map(std::path::PathBuf::from)
+        .filter(|path| path.exists());
+    if let Some(path) = &configured {
+        candidates.push((path.clone(), InstallationSource::Configured));
+    }
+
+    let running = get_path_from_running_process();
+    if let Some(path) = &running {
+        candidates.push((path.clone(), InstallationSource::RunningProcess));
+    }
+
+    #[cfg(target_os = "windows")]
+    candidates.extend(
+        registry_candidates()
+            .into_iter()
+            .map(|path| (path, InstallationSource::Registry)),
+    );
+
+    candidates.extend(
+        standard_location_candidates()
+            .into_iter()
+            .filter(|path| path.exists())
+            .map(|path| (path, InstallationSource::StandardLocation)),
+    );
+
+    #[cfg(target_os = "linux")]
+    candidates.extend(
+        desktop_entry_candidates()
+            .into_iter()
+            .map(|path| (path, InstallationSource::DesktopEntry)),
+    );
+
+    candidates.extend(
+        path_lookup_candidates()
+            .into_iter()
+            .map(|path| (path, InstallationSource::PathLookup)),
+    );
+
+    let mut installations: Vec<AntigravityInstallation> = merge_candidates(candidates)
+        .into_iter()
+        .map(|(path, sources)| {
+            let version = crate::modules::version::get_version_for_path(&path)
+                .ok()
+                .map(|v| v.short_version);
+            AntigravityInstallation {
+                path: path.to_string_lossy().to_string(),
+                running: sources.contains(&InstallationSource::RunningProcess),
+                configured: sources.contains(&InstallationSource::Configured),
+                score: installation_score(&sources, version.is_some()),
+                sources,
+                version,
+            }
+        })
+        .collect();
+
+    // Stable sort keeps discovery order among equal scores
+    installations.sort_by_key(|i| std::cmp::Reverse(i.score));
+    installations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_app_bundles_are_told_apart() {
+        assert_eq!(
+            app_bundle_root("/applications/antigravity 2.app/contents/macos/electron"),
+            Some("/applications/antigravity 2.app")
+        );
+        assert_eq!(
+            app_bundle_root("/Applications/Antigravity.app"),
+            Some("/Applications/Antigravity.app")
+        );
+        assert_eq!(app_bundle_root("/opt/apps/antigravity"), None);
+        assert!(is_antigravity_bundle("/applications/antigravity 2.app/contents/macos/electron"));
+        assert!(!is_antigravity_bundle("/applications/antigravity tools.app/contents/macos/app"));
+
+        assert!(is_antigravity_program_name("/Applications/Antigravity 2.app"));
+        assert!(is_antigravity_program_name(
+            "C:\\Users\\me\\AppData\\Local\\Programs\\Antigravity\\Antigravity.exe"
+        ));
+        assert!(is_antigravity_program_name("/usr/share/antigravity/antigravity"));
+        assert!(is_antigravity_program_name("/home/me/Applications/Antigravity-x86_64.AppImage"));
+        assert!(is_antigravity_program_name("/var/lib/flatpak/exports/bin/com.google.Antigravity"));
+        assert!(!is_antigravity_program_name("/Applications/Antigravity Tools.app"));
+        assert!(!is_antigravity_program_name("/usr/bin/antigravity-tools"));
+        assert!(!is_antigravity_program_name("/bin/sh"));
+        assert!(!is_antigravity_program_name("/opt/antigravity/run.sh"));
+
+        let target = "/Applications/Antigravity 2.app";
+        assert!(installation_contains(
+            target,
+            "/Applications/Antigravity 2.app/Contents/Frameworks/Antigravity Helper.app/Contents/MacOS/Antigravity Helper"
+        ));
+        assert!(!installation_contains(
+            target,
+            "/Applications/Antigravity.app/Contents/MacOS/Electron"
+        ));
+        assert!(installation_contains("/opt/ag/antigravity", "/opt/ag/chrome_crashpad_handler"));
+        assert!(!installation_contains("/opt/ag/antigravity", "/opt/ag2/antigravity"));
+    }
+
+    #[test]
+    fn test_classify_linux_process() {
+        use LinuxPackaging::*;
+        let classify = |name: &str, exe: &str, args: &str| classify_linux_process(name, exe, args);
+
+        assert_eq!(
+            classify("antigravity", "/usr/share/antigravity/antigravity", ""),
+            Some(Native)
+        );
+        assert_eq!(
+            classify("antigravity", "/tmp/.mount_antigrxy12ab/usr/share/antigravity/antigravity", ""),
+            Some(AppImage)
+        );
+        assert_eq!(
+            classify("antigravity", "/snap/antigravity/12/usr/share/antigravity/antigravity", ""),
+            Some(Snap)
+        );
+        assert_eq!(classify("antigravity", "/app/extra/antigravity/antigravity", ""), Some(Flatpak));
+        assert_eq!(
+            classify("bwrap", "/usr/bin/bwrap", "--args 42 com.example.antigravity"),
+            Some(Flatpak)
+        );
+        assert_eq!(classify("bwrap", "/usr/bin/bwrap", "--args 42 org.gnome.calculator"), None);
+        assert_eq!(classify("antigravity_tools", "/usr/bin/antigravity_tools", ""), None);
+        assert_eq!(classify("code", "/tmp/.mount_codexy12ab/code", ""), None);
+    }
+
+    #[test]
+    fn test_sandboxed_launch_path() {
+        let environ = vec![
+            "HOME=/home/u".to_string(),
+            "APPIMAGE=/home/u/Applications/Antigravity-1.0.AppImage".to_string(),
+        ];
+        assert_eq!(
+            sandboxed_launch_path(LinuxPackaging::AppImage, &environ),
+            Some(std::path::PathBuf::from("/home/u/Applications/Antigravity-1.0.AppImage"))
+        );
+        assert_eq!(sandboxed_launch_path(LinuxPackaging::AppImage, &[]), None);
+        assert_eq!(sandboxed_launch_path(LinuxPackaging::Native, &environ), None);
+    }
+
+    #[test]
+    fn test_merge_launch_args_precedence() {
+        let v = |args: &[&str]| args.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let global = v(&["--user-data-dir", "/global", "--disable-gpu", "--proxy-server=http://a:1"]);
+
+        assert_eq!(merge_launch_args(Some(&global), None), Some(global.clone()));
+        assert_eq!(merge_launch_args(Some(&global), Some(&[])), Some(global.clone()));
+        assert_eq!(merge_launch_args(None, None), None);
+
+        // 账号参数覆盖同名标志 (无论写法)，其余全局参数保留在前
+        let account = v(&["--user-data-dir=/acc", "--proxy-server", "http://b:2", "--new-window"]);
+        assert_eq!(
+            merge_launch_args(Some(&global), Some(&account)),
+            Some(v(&[
+                "--disable-gpu",
+                "--user-data-dir=/acc",
+                "--proxy-server",
+                "http://b:2",
+                "--new-window"
+            ]))
+        );
+        assert_eq!(merge_launch_args(None, Some(&account)), Some(account.clone()));
+    }
+
+    #[test]
+    fn test_parse_desktop_exec() {
+        let entry = "[Desktop Entry]\nName=Antigravity\nExec=/usr/share/antigravity/antigravity %F\n";
+        assert_eq!(
+            parse_desktop_exec(entry),
+            Some(std::path::PathBuf::from("/usr/share/antigravity/antigravity"))
+        );
+        let quoted = "Exec=\"/opt/Anti Gravity/antigravity\" --new-window %U";
+        assert_eq!(
+            parse_desktop_exec(quoted),
+            Some(std::path::PathBuf::from("/opt/Anti Gravity/antigravity"))
+        );
+        assert_eq!(parse_desktop_exec("Exec=%U"), None);
+    }
+
+    #[test]
+    fn test_candidates_merge_and_rank() {
+        let merged = merge_candidates(vec![
+            ("/nonexistent/antigravity".into(), InstallationSource::StandardLocation),
+            ("/nonexistent/other".into(), InstallationSource::PathLookup),
+            ("/nonexistent/antigravity".into(), InstallationSource::RunningProcess),
+        ]);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(
+            merged[0].1,
+            vec![InstallationSource::StandardLocation, InstallationSource::RunningProcess]
+        );
+        assert_eq!(installation_score(&merged[0].1, true), 110);
+        assert_eq!(installation_score(&merged[1].1, false), 20);
+    }
 }
@@ -0,0 +1,297 @@
+// Google project_id 绑定管理
+// loadCodeAssist 返回的 project_id 可能变化 (账号被迁移到新项目)。这里统一记录每个账号用过的 project_id，
+// 检测短时间内的反复切换 (churn)，切换时让反代侧失效与旧项目相关的缓存，
+// 并保留切换历史，便于排查与项目切换时间相吻合的 403。
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::modules::{account, logger};
+
+const BINDINGS_FILE: &str = "project_bindings.json";
+/// 每个账号最多保留的历史 project_id 条数
+const MAX_HISTORY: usize = 20;
+/// 统计切换次数的时间窗口 (24 小时)
+const CHURN_WINDOW_SECS: i64 = 86_400;
+/// 窗口内切换达到该次数视为 churn
+const CHURN_THRESHOLD: usize = 3;
+
+static BINDINGS_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProjectBindingEntry {
+    pub project_id: String,
+    pub first_seen: i64,
+    pub last_seen: i64,
+    /// 最近一次观察到该 project_id 的来源 (quota_refresh / token_manager ...)
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProjectBindingHistory {
+    pub account_id: String,
+    #[serde(default)]
+    pub email: String,
+    #[serde(default)]
+    pub current: Option<String>,
+    /// 按首次出现时间排序，最新的在末尾
+    #[serde(default)]
+    pub entries: Vec<ProjectBindingEntry>,
+    /// 每次切换的时间戳 (仅保留窗口内的记录)
+    #[serde(default)]
+    pub switches: Vec<i64>,
+    #[serde(default)]
+    pub total_switches: u32,
+    /// 读取时计算，不持久化
+    #[serde(default, skip_deserializing)]
+    pub churning: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BindingChange {
+    Unchanged,
+    /// 首次记录该账号的 project_id
+    Initial,
+    Switched {
+        previous: String,
+    },
+}
+
+fn bindings_path() -> Result<PathBuf, String> {
+    Ok(account::get_data_dir()?.join(BINDINGS_FILE))
+}
+
+fn load_at(path: &PathBuf) -> BTreeMap<String, ProjectBindingHistory> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_at(
+    path: &PathBuf,
+    bindings: &BTreeMap<String, ProjectBindingHistory>,
+) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(bindings)
+        .map_err(|e| format!("failed_to_serialize_project_bindings: {}", e))?;
+    let temp_path = path.with_extension("json.tmp");
+    std::fs::write(&temp_path, content)
+        .map_err(|e| format!("failed_to_write_project_bindings: {}", e))?;
+    std::fs::rename(&temp_path, path)
+        .map_err(|e| format!("failed_to_write_project_bindings: {}", e))
+}
+
+fn is_churning(history: &ProjectBindingHistory, now: i64) -> bool {
+    history
+        .switches
+        .iter()
+        .filter(|ts| now - **ts < CHURN_WINDOW_SECS)
+        .count()
+        >= CHURN_THRESHOLD
+}
+
+/// 记录一次观察到的 project_id
+fn record(
+    history: &mut ProjectBindingHistory,
+    project_id: &str,
+    source: &str,
+    now: i64,
+) -> BindingChange {
+    let change = match history.current.as_deref() {
+        None => BindingChange::Initial,
+        Some(current) if current == project_id => BindingChange::Unchanged,
+        Some(current) => BindingChange::Switched {
+            previous: current.to_string(),
+        },
+    };
+
+    match history
+        .entries
+        .iter_mut()
+        .find(|e| e.project_id == project_id)
+    {
+        Some(entry) => {
+            entry.last_seen = now;
+            entry.source = source.to_string();
+        }
+        None => history.entries.push(ProjectBindingEntry {
+            project_id: project_id.to_string(),
+            first_seen: now,
+            last_seen: now,
+            source: source.to_string(),
+        }),
+    }
+    if history.entries.len() > MAX_HISTORY {
+        // 丢弃最久未出现的记录 (当前绑定除外)
+        if let Some(oldest) = history
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.project_id != project_id)
+            .min_by_key(|(_, e)| e.last_seen)
+            .map(|(i, _)| i)
+        {
+            history.entries.remove(oldest);
+        }
+    }
+
+    if let BindingChange::Switched { .. } = change {
+        history.switches.push(now);
+        history.total_switches += 1;
+    }
+    history.switches.retain(|ts| now - *ts < CHURN_WINDOW_SECS);
+    history.current = Some(project_id.to_string());
+    change
+}
+
+/// 上报账号当前解析到的 project_id；切换时记录历史并失效反代侧的相关缓存
+pub fn observe(account_id: &str, email: &str, project_id: &str, source: &str) -> BindingChange {
+    let project_id = project_id.trim();
+    if project_id.is_empty() {
+        return BindingChange::Unchanged;
+    }
+    let now = chrono::Utc::now().timestamp();
+
+    let (change, churning) = {
+        let _lock = BINDINGS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let Ok(path) = bindings_path() else {
+            return BindingChange::Unchanged;
+        };
+        let mut bindings = load_at(&path);
+        let history =
+            bindings
+                .entry(account_id.to_string())
+                .or_insert_with(|| ProjectBindingHistory {
+                    account_id: account_id.to_string(),
+                    ..Default::default()
+                });
+        history.email = email.to_string();
+        let change = record(history, project_id, source, now);
+        let churning = is_churning(history, now);
+        if change != BindingChange::Unchanged {
+            if let Err(e) = save_at(&path, &bindings) {
+                logger::log_warn(&format!("[ProjectBinding] {}", e));
+            }
+        }
+        (change, churning)
+    };
+
+    if let BindingChange::Switched { previous } = &change {
+        logger::log_warn(&format!(
+            "[ProjectBinding] project_id changed for {}: {} -> {} (via {})",
+            email, previous, project_id, source
+        ));
+        if churning {
+            logger::log_warn(&format!(
+                "[ProjectBinding] {} switched projects {}+ times within 24h, upstream binding is unstable",
+                email, CHURN_THRESHOLD
+            ));
+        }
+        // 上游会话 ID 与粘性会话都绑定在旧项目上
+        crate::proxy::session_pool::remove(account_id);
+        crate::proxy::server::trigger_project_rebind(account_id);
+    }
+    change
+}
+
+/// 最近一次切换发生在 `within_secs` 秒内时返回 (旧, 新, 时间)，用于 403 排查日志
+pub fn recent_switch(account_id: &str, within_secs: i64) -> Option<(String, String, i64)> {
+    let now = chrono::Utc::now().timestamp();
+    let history = get_history(account_id)?;
+    let at = *history.switches.last()?;
+    if now - at >= within_secs {
+        return None;
+    }
+    let current = history.current.clone()?;
+    let previous = history
+        .entries
+        .iter()
+        .filter(|e| e.project_id != current)
+        .max_by_key(|e| e.last_seen)?
+        .project_id
+        .clone();
+    Some((previous, current, at))
+}
+
+/// 所有账号的绑定历史
+pub fn list_histories() -> Vec<ProjectBindingHistory> {
+    let _lock = BINDINGS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let now = chrono::Utc::now().timestamp();
+    bindings_path()
+        .map(|p| load_at(&p))
+        .unwrap_or_default()
+        .into_values()
+        .map(|mut h| {
+            h.churning = is_churning(&h, now);
+            h
+        })
+        .collect()
+}
+
+pub fn get_history(account_id: &str) -> Option<ProjectBindingHistory> {
+    list_histories()
+        .into_iter()
+        .find(|h| h.account_id == account_id)
+}
+
+/// 删除账号时清理其绑定历史
+pub fn remove(account_id: &str) {
+    let _lock = BINDINGS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let Ok(path) = bindings_path() else {
+        return;
+    };
+    let mut bindings = load_at(&path);
+    if bindings.remove(account_id).is_some() {
+        let _ = save_at(&path, &bindings);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_detects_switches() {
+        let mut history = ProjectBindingHistory::default();
+        assert_eq!(
+            record(&mut history, "p1", "quota_refresh", 100),
+            BindingChange::Initial
+        );
+        assert_eq!(
+            record(&mut history, "p1", "quota_refresh", 200),
+            BindingChange::Unchanged
+        );
+        assert_eq!(
+            record(&mut history, "p2", "token_manager", 300),
+            BindingChange::Switched {
+                previous: "p1".to_string()
+            }
+        );
+        assert_eq!(history.current.as_deref(), Some("p2"));
+        assert_eq!(history.entries.len(), 2);
+        assert_eq!(history.entries[0].last_seen, 200);
+        assert_eq!(history.total_switches, 1);
+        assert!(!is_churning(&history, 300));
+
+        record(&mut history, "p1", "quota_refresh", 400);
+        record(&mut history, "p2", "quota_refresh", 500);
+        assert!(is_churning(&history, 500));
+        assert!(!is_churning(&history, 500 + CHURN_WINDOW_SECS));
+        assert_eq!(history.entries.len(), 2);
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut history = ProjectBindingHistory::default();
+        for i in 0..(MAX_HISTORY as i64 + 5) {
+            record(&mut history, &format!("p{}", i), "quota_refresh", i);
+        }
+        assert_eq!(history.entries.len(), MAX_HISTORY);
+        assert_eq!(history.entries.first().unwrap().project_id, "p5");
+        // 窗口外的切换时间不保留
+        record(&mut history, "p0", "quota_refresh", 10 * CHURN_WINDOW_SECS);
+        assert_eq!(history.switches, vec![10 * CHURN_WINDOW_SECS]);
+    }
+}
@@ -1,13 +1,22 @@
 use rusqlite::{params, Connection};
+use serde::Serialize;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
 use crate::proxy::monitor::ProxyRequestLog;
 
+/// Interval of the background retention / compaction pass
+const COMPACTION_INTERVAL_SECS: u64 = 3600;
+
+static MAINTENANCE_STARTED: AtomicBool = AtomicBool::new(false);
+/// Unix seconds of the last VACUUM (0 = not yet in this process)
+static LAST_VACUUM: AtomicI64 = AtomicI64::new(0);
+
 pub fn get_proxy_db_path() -> Result<PathBuf, String> {
     let data_dir = crate::modules::account::get_data_dir()?;
     Ok(data_dir.join("proxy_logs.db"))
 }
 
-fn connect_db() -> Result<Connection, String> {
+pub(crate) fn connect_db() -> Result<Connection, String> {
     let db_path = get_proxy_db_path()?;
     let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
     
@@ -64,6 +73,16 @@ pub fn init_db() -> Result<(), String> {
         [],
     ).map_err(|e| e.to_string())?;
 
+    // Per-account / per-model lookups
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_account_time ON request_logs (account_email, timestamp DESC)",
+        [],
+    ).map_err(|e| e.to_string())?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_model_time ON request_logs (model, timestamp DESC)",
+        [],
+    ).map_err(|e| e.to_string())?;
+
     Ok(())
 }
 
@@ -205,38 +224,112 @@ pub fn get_log_detail(log_id: &str) -> Result<ProxyRequestLog, String> {
     }).map_err(|e| e.to_string())
 }
 
-/// Cleanup old logs (keep last N days)
-pub fn cleanup_old_logs(days: i64) -> Result<usize, String> {
-    let conn = connect_db()?;
-    
-    let cutoff_timestamp = chrono::Utc::now().timestamp() - (days * 24 * 3600);
-    
-    let deleted = conn.execute(
-        "DELETE FROM request_logs WHERE timestamp < ?1",
-        [cutoff_timestamp],
-    ).map_err(|e| e.to_string())?;
-    
-    // Execute VACUUM to reclaim disk space
-    conn.execute("VACUUM", []).map_err(|e| e.to_string())?;
-    
-    Ok(deleted)
+/// Result of one retention pass
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CompactionResult {
+    pub deleted_by_age: usize,
+    pub deleted_by_count: usize,
+    pub vacuumed: bool,
+    pub remaining: u64,
 }
 
-/// Limit maximum log count (keep newest N records)
-#[allow(dead_code)]
-pub fn limit_max_logs(max_count: usize) -> Result<usize, String> {
-    let conn = connect_db()?;
-    
-    let deleted = conn.execute(
-        "DELETE FROM request_logs WHERE id NOT IN (
-            SELECT id FROM request_logs ORDER BY timestamp DESC LIMIT ?1
+/// Delete logs older than `days` (timestamps are stored in milliseconds)
+fn delete_older_than(conn: &Connection, days: u32, now_ms: i64) -> Result<usize, String> {
+    if days == 0 {
+        return Ok(0);
+    }
+    let cutoff_ms = now_ms - i64::from(days) * 24 * 3600 * 1000;
+    conn.execute("DELETE FROM request_logs WHERE timestamp < ?1", [cutoff_ms])
+        .map_err(|e| e.to_string())
+}
+
+/// Keep only the newest `max_rows` logs
+fn trim_to_max_rows(conn: &Connection, max_rows: u64) -> Result<usize, String> {
+    if max_rows == 0 {
+        return Ok(0);
+    }
+    conn.execute(
+        "DELETE FROM request_logs WHERE timestamp < (
+            SELECT timestamp FROM request_logs ORDER BY timestamp DESC LIMIT 1 OFFSET ?1
         )",
-        [max_count],
-    ).map_err(|e| e.to_string())?;
-    
-    conn.execute("VACUUM", []).map_err(|e| e.to_string())?;
-    
-    Ok(deleted)
+        [max_rows - 1],
+    )
+    .map_err(|e| e.to_string())
+}
+
+fn compact_conn(
+    conn: &Connection,
+    config: &crate::proxy::config::RequestLogConfig,
+    now_ms: i64,
+    vacuum_due: bool,
+) -> Result<CompactionResult, String> {
+    let deleted_by_age = delete_older_than(conn, config.retention_days, now_ms)?;
+    let deleted_by_count = trim_to_max_rows(conn, config.max_rows)?;
+
+    // VACUUM rewrites the whole file; only run it when there are free pages to reclaim
+    let free_pages: i64 = conn
+        .query_row("PRAGMA freelist_count", [], |row| row.get(0))
+        .unwrap_or(0);
+    let vacuumed = vacuum_due && free_pages > 0;
+    if vacuumed {
+        conn.execute("VACUUM", []).map_err(|e| e.to_string())?;
+    }
+
+    let remaining: u64 = conn
+        .query_row("SELECT COUNT(*) FROM request_logs", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    Ok(CompactionResult {
+        deleted_by_age,
+        deleted_by_count,
+        vacuumed,
+        remaining,
+    })
+}
+
+/// Apply the configured retention (age / max rows); `force_vacuum` ignores the vacuum interval
+pub fn compact(force_vacuum: bool) -> Result<CompactionResult, String> {
+    let config = crate::proxy::config::get_request_log_config();
+    let now = chrono::Utc::now();
+    let vacuum_due = force_vacuum
+        || (config.vacuum_interval_hours > 0
+            && now.timestamp() - LAST_VACUUM.load(Ordering::Relaxed)
+                >= i64::from(config.vacuum_interval_hours) * 3600);
+
+    let conn = connect_db()?;
+    let result = compact_conn(&conn, &config, now.timestamp_millis(), vacuum_due)?;
+    if result.vacuumed {
+        LAST_VACUUM.store(now.timestamp(), Ordering::Relaxed);
+    }
+    Ok(result)
+}
+
+/// Start the hourly retention / compaction task (once per process)
+pub fn start_maintenance() {
+    if MAINTENANCE_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    tokio::spawn(async {
+        let mut ticker =
+            tokio::time::interval(std::time::Duration::from_secs(COMPACTION_INTERVAL_SECS));
+        loop {
+            ticker.tick().await;
+            match tokio::task::spawn_blocking(|| compact(false)).await {
+                Ok(Ok(result)) => {
+                    if result.deleted_by_age + result.deleted_by_count > 0 || result.vacuumed {
+                        tracing::info!(
+                            "Request log compaction: removed {} expired + {} over limit, vacuumed={}, {} remaining",
+                            result.deleted_by_age,
+                            result.deleted_by_count,
+                            result.vacuumed,
+                            result.remaining
+                        );
+                    }
+                }
+                Ok(Err(e)) => tracing::error!("Failed to compact request logs: {}", e),
+                Err(e) => tracing::error!("Request log compaction task failed: {}", e),
+            }
+        }
+    });
 }
 
 pub fn clear_logs() -> Result<(), String> {
@@ -511,3 +604,58 @@ pub fn get_token_usage_by_ip(limit: usize, hours: i64) -> Result<Vec<IpTokenStat
 
     Ok(stats)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proxy::config::RequestLogConfig;
+
+    #[test]
+    fn test_compaction_by_age_and_count() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute(
+            "CREATE TABLE request_logs (id TEXT PRIMARY KEY, timestamp INTEGER)",
+            [],
+        )
+        .unwrap();
+        let now_ms = 1_800_000_000_000i64;
+        let day_ms = 24 * 3600 * 1000;
+        // 2 expired rows, 5 recent rows
+        for (i, age_days) in [40i64, 31, 3, 2, 1, 0, 0].iter().enumerate() {
+            conn.execute(
+                "INSERT INTO request_logs (id, timestamp) VALUES (?1, ?2)",
+                params![i.to_string(), now_ms - age_days * day_ms - i as i64],
+            )
+            .unwrap();
+        }
+
+        let config = RequestLogConfig {
+            retention_days: 30,
+            max_rows: 3,
+            vacuum_interval_hours: 0,
+        };
+        let result = compact_conn(&conn, &config, now_ms, false).unwrap();
+        assert_eq!(result.deleted_by_age, 2);
+        assert_eq!(result.deleted_by_count, 2);
+        assert_eq!(result.remaining, 3);
+        assert!(!result.vacuumed);
+
+        let oldest_kept: String = conn
+            .query_row(
+                "SELECT id FROM request_logs ORDER BY timestamp ASC LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(oldest_kept, "4");
+
+        // 0 disables both limits
+        let unlimited = RequestLogConfig {
+            retention_days: 0,
+            max_rows: 0,
+            vacuum_interval_hours: 0,
+        };
+        let result = compact_conn(&conn, &unlimited, now_ms + 365 * day_ms, false).unwrap();
+        assert_eq!(result.deleted_by_age + result.deleted_by_count, 0);
+    }
+}
@@ -1,8 +1,9 @@
 use rquest;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use crate::models::QuotaData;
+use crate::models::{QuotaData, RetryClass};
 use crate::modules::config;
+use crate::modules::redact;
 
 const QUOTA_API_URL: &str = "https://cloudcode-pa.googleapis.com/v1internal:fetchAvailableModels";
 
@@ -11,6 +12,53 @@ const NEAR_READY_THRESHOLD: i32 = 95;
 const MAX_RETRIES: u32 = 3;
 const RETRY_DELAY_SECS: u64 = 30;
 
+/// Last quota response per account + project, revalidated with If-None-Match.
+/// Entries are only kept while the server sends an ETag, so servers that ignore validators cost nothing.
+struct QuotaEtagEntry {
+    etag: String,
+    quota: QuotaData,
+}
+
+static QUOTA_ETAGS: once_cell::sync::Lazy<std::sync::Mutex<std::collections::HashMap<String, QuotaEtagEntry>>> =
+    once_cell::sync::Lazy::new(|| std::sync::Mutex::new(std::collections::HashMap::new()));
+
+fn quota_etag_key(email: &str, account_id: Option<&str>, project_id: Option<&str>) -> String {
+    format!("{}|{}", account_id.unwrap_or(email), project_id.unwrap_or(""))
+}
+
+fn cached_quota_etag(key: &str) -> Option<String> {
+    QUOTA_ETAGS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(key)
+        .map(|entry| entry.etag.clone())
+}
+
+/// Cached quota for a 304 response (timestamp refreshed, since the data was just revalidated)
+fn quota_for_not_modified(key: &str) -> Option<QuotaData> {
+    let etags = QUOTA_ETAGS.lock().unwrap_or_else(|e| e.into_inner());
+    let mut quota = etags.get(key)?.quota.clone();
+    quota.last_updated = chrono::Utc::now().timestamp();
+    Some(quota)
+}
+
+/// Remember a 200 response; a response without ETag drops any stale entry
+fn remember_quota_etag(key: &str, etag: Option<String>, quota: &QuotaData) {
+    let mut etags = QUOTA_ETAGS.lock().unwrap_or_else(|e| e.into_inner());
+    match etag.filter(|e| !e.is_empty()) {
+        Some(etag) => {
+            etags.insert(key.to_string(), QuotaEtagEntry { etag, quota: quota.clone() });
+        }
+        None => {
+            etags.remove(key);
+        }
+    }
+}
+
+fn forget_quota_etag(key: &str) {
+    QUOTA_ETAGS.lock().unwrap_or_else(|e| e.into_inner()).remove(key);
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct QuotaResponse {
     models: std::collections::HashMap<String, ModelInfo>,
@@ -87,37 +135,53 @@ struct Tier {
     slug: Option<String>,
 }
 
-/// Get shared HTTP Client (15s timeout) for pure info fetching (No JA3)
+/// Get shared HTTP Client (short timeout) for pure info fetching (No JA3)
 async fn create_standard_client(account_id: Option<&str>) -> rquest::Client {
     if let Some(pool) = crate::proxy::proxy_pool::get_global_proxy_pool() {
-        pool.get_effective_standard_client(account_id, 15).await
+        pool.get_effective_standard_client(account_id, crate::modules::http::settings().timeout_secs).await
     } else {
         crate::utils::http::get_standard_client()
     }
 }
 
-/// Get shared HTTP Client (60s timeout) for pure info fetching (No JA3)
+/// Get shared HTTP Client (long timeout) for pure info fetching (No JA3)
 #[allow(dead_code)] // 预留给预热/后台任务调用
 async fn create_long_standard_client(account_id: Option<&str>) -> rquest::Client {
     if let Some(pool) = crate::proxy::proxy_pool::get_global_proxy_pool() {
-        pool.get_effective_standard_client(account_id, 60).await
+        pool.get_effective_standard_client(account_id, crate::modules::http::settings().long_timeout_secs).await
     } else {
         crate::utils::http::get_long_standard_client()
     }
 }
 
+/// Apply an account's User-Agent / Accept-Language to a background request
+fn with_identity(
+    req: rquest::RequestBuilder,
+    identity: &crate::modules::device::AccountIdentity,
+) -> rquest::RequestBuilder {
+    let req = req.header(rquest::header::USER_AGENT, identity.user_agent.as_str());
+    match identity.accept_language.as_deref() {
+        Some(lang) => req.header(rquest::header::ACCEPT_LANGUAGE, lang),
+        None => req,
+    }
+}
+
 const CLOUD_CODE_BASE_URL: &str = "https://daily-cloudcode-pa.sandbox.googleapis.com";
 
 /// Fetch project ID and subscription tier
-async fn fetch_project_id(access_token: &str, email: &str, account_id: Option<&str>) -> (Option<String>, Option<String>) {
+pub(crate) async fn fetch_project_id(access_token: &str, email: &str, account_id: Option<&str>) -> (Option<String>, Option<String>) {
     let client = create_standard_client(account_id).await;
-    let meta = json!({"metadata": {"ideType": "ANTIGRAVITY"}});
-
-    let res = client
-        .post(format!("{}/v1internal:loadCodeAssist", CLOUD_CODE_BASE_URL))
+    let identity = crate::modules::device::identity_for_account(account_id);
+    let meta = json!({"metadata": {"ideType": "ANTIGRAVITY", "platform": identity.platform}});
+
+    let req = client
+        .post(crate::modules::endpoints::resolve(&format!(
+            "{}/v1internal:loadCodeAssist",
+            CLOUD_CODE_BASE_URL
+        )))
         .header(rquest::header::AUTHORIZATION, format!("Bearer {}", access_token))
-        .header(rquest::header::CONTENT_TYPE, "application/json")
-        .header(rquest::header::USER_AGENT, crate::constants::NATIVE_OAUTH_USER_AGENT.as_str())
+        .header(rquest::header::CONTENT_TYPE, "application/json");
+    let res = with_identity(req, &identity)
         .json(&meta)
         .send()
         .await;
@@ -207,19 +271,34 @@ pub async fn fetch_quota_with_cache(
         json!({}) // Empty payload fallback
     };
     
-    let url = QUOTA_API_URL;
-    let mut last_error: Option<AppError> = None;
-
-    for attempt in 1..=MAX_RETRIES {
-        match client
-            .post(url)
-            .bearer_auth(access_token)
-            .header(rquest::header::USER_AGENT, crate::constants::NATIVE_OAUTH_USER_AGENT.as_str())
-            .json(&json!(payload))
-            .send()
-            .await
-        {
+    let url = crate::modules::endpoints::resolve(QUOTA_API_URL);
+    // 429 / 5xx / 超时按统一退避策略重试；401 交给调用方刷新 Token 后重试
+    let mut backoff = crate::modules::retry::RetryPolicy::current().start();
+
+    // 与该账号在反代中呈现的指纹一致 (绑定设备的系统版本 / 语言)
+    let identity = crate::modules::device::identity_for_account(account_id);
+    let etag_key = quota_etag_key(email, account_id, project_id.as_deref());
+
+    loop {
+        let mut request = with_identity(client.post(&url).bearer_auth(access_token), &identity)
+            .json(&json!(payload));
+        if let Some(etag) = cached_quota_etag(&etag_key) {
+            request = request.header(rquest::header::IF_NONE_MATCH, etag);
+        }
+        match request.send().await {
             Ok(response) => {
+                // Unchanged since the last fetch: reuse the cached parse instead of downloading the model list again
+                if response.status() == rquest::StatusCode::NOT_MODIFIED {
+                    if let Some(mut quota_data) = quota_for_not_modified(&etag_key) {
+                        tracing::debug!("Quota for {} not modified (ETag)", email);
+                        quota_data.subscription_tier = subscription_tier.clone();
+                        return Ok((quota_data, project_id.clone()));
+                    }
+                    // Entry evicted concurrently: ask again without a validator
+                    forget_quota_etag(&etag_key);
+                    continue;
+                }
+
                 // Convert HTTP error status to AppError
                 if let Err(_) = response.error_for_status_ref() {
                     let status = response.status();
@@ -235,19 +314,24 @@ pub async fn fetch_quota_with_cache(
                         return Ok((q, project_id.clone()));
                     }
                     
-                    // Continue retry logic for other errors
-                    if attempt < MAX_RETRIES {
-                         let text = response.text().await.unwrap_or_default();
-                         crate::modules::logger::log_warn(&format!("API Error: {} - {} (Attempt {}/{})", status, text, attempt, MAX_RETRIES));
-                         last_error = Some(AppError::Unknown(format!("HTTP {} - {}", status, text)));
-                         tokio::time::sleep(std::time::Duration::from_secs(1)).await;
-                         continue;
-                    } else {
-                         let text = response.text().await.unwrap_or_default();
-                         return Err(AppError::Unknown(format!("API Error: {} - {}", status, text)));
+                    let text = response.text().await.unwrap_or_default();
+                    let class = crate::modules::retry::classify_status(status.as_u16());
+                    let attempt = backoff.attempt();
+                    if class != Some(RetryClass::Unauthorized) {
+                        if let Some(delay) = backoff.next_delay(class) {
+                            crate::modules::logger::log_warn(&format!("API Error: {} - {} (Attempt {}/{})", status, redact::upstream_body(&text), attempt, backoff.max_attempts()));
+                            tokio::time::sleep(delay).await;
+                            continue;
+                        }
                     }
+                    return Err(AppError::from_upstream(status.as_u16(), &redact::upstream_body(&text), None));
                 }
 
+                let etag = response
+                    .headers()
+                    .get(rquest::header::ETAG)
+                    .and_then(|v| v.to_str().ok())
+                    .map(str::to_string);
                 let quota_response: QuotaResponse = response
                     .json()
                     .await
@@ -293,22 +377,23 @@ pub async fn fetch_quota_with_cache(
                     }
                 }
                 
+                remember_quota_etag(&etag_key, etag, &quota_data);
+
                 // Set subscription tier
                 quota_data.subscription_tier = subscription_tier.clone();
                 
                 return Ok((quota_data, project_id.clone()));
             },
             Err(e) => {
-                crate::modules::logger::log_warn(&format!("Request failed: {} (Attempt {}/{})", e, attempt, MAX_RETRIES));
-                last_error = Some(AppError::from(e));
-                if attempt < MAX_RETRIES {
-                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                let attempt = backoff.attempt();
+                crate::modules::logger::log_warn(&format!("Request failed: {} (Attempt {}/{})", e, attempt, backoff.max_attempts()));
+                match backoff.next_delay(crate::modules::retry::classify_transport_error(&e)) {
+                    Some(delay) => tokio::time::sleep(delay).await,
+                    None => return Err(AppError::from(e)),
                 }
             }
         }
     }
-    
-    Err(last_error.unwrap_or_else(|| AppError::Unknown("Quota fetch failed".to_string())))
 }
 
 /// Internal fetch quota logic
@@ -347,7 +432,7 @@ pub async fn get_valid_token_for_warmup(account: &crate::models::account::Accoun
     // If token changed (meant refreshed), save it
     if new_token.access_token != account.token.access_token {
         account.token = new_token;
-        if let Err(e) = crate::modules::account::save_account(&account) {
+        if let Err(e) = crate::modules::account::save_refreshed_token(&account.id, &account.token) {
             crate::modules::logger::log_warn(&format!("[Warmup] Failed to save refreshed token: {}", e));
         } else {
             crate::modules::logger::log_info(&format!("[Warmup] Successfully refreshed and saved new token for {}", account.email));
@@ -405,7 +490,7 @@ pub async fn warmup_model_directly(
                 true
             } else {
                 let text = response.text().await.unwrap_or_default();
-                crate::modules::logger::log_warn(&format!("[Warmup] ✗ {} for {} (was {}%): HTTP {} - {}", model_name, email, percentage, status, text));
+                crate::modules::logger::log_warn(&format!("[Warmup] ✗ {} for {} (was {}%): HTTP {} - {}", model_name, email, percentage, status, redact::upstream_body(&text)));
                 false
             }
         }
@@ -451,7 +536,7 @@ async fn warmup_codex_directly(access_token: &str, email: &str) -> Result<String
         Ok(format!("HTTP {} for {}", status, email))
     } else {
         let text = resp.text().await.unwrap_or_default();
-        Err(format!("HTTP {} - {}", status, text))
+        Err(format!("HTTP {} - {}", status, redact::upstream_body(&text)))
     }
 }
 
@@ -495,7 +580,7 @@ pub async fn warm_up_all_accounts() -> Result<String, String> {
                             Ok(Some(new_token)) => {
                                 let mut updated = account.clone();
                                 updated.token = new_token;
-                                let _ = crate::modules::account::save_account(&updated);
+                                let _ = crate::modules::account::save_refreshed_token(&updated.id, &updated.token);
                                 updated.token.access_token
                             }
                             Ok(None) => account.token.access_token.clone(),
@@ -702,7 +787,7 @@ pub async fn warm_up_account(account_id: &str) -> Result<String, String> {
             Ok(Some(new_token)) => {
                 let mut updated = account_owned.clone();
                 updated.token = new_token;
-                if let Err(e) = crate::modules::account::save_account(&updated) {
+                if let Err(e) = crate::modules::account::save_refreshed_token(&updated.id, &updated.token) {
                     crate::modules::logger::log_warn(&format!("[Warmup] Failed to save refreshed Codex token: {}", e));
                 }
                 updated.token.access_token
@@ -784,3 +869,25 @@ pub async fn warm_up_account(account_id: &str) -> Result<String, String> {
 
     Ok(format!("Successfully triggered warmup for {} model series", warmed_count))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quota_etag_cache() {
+        let key = quota_etag_key("a@x.com", Some("etag-test"), Some("proj"));
+        assert_eq!(key, "etag-test|proj");
+        let mut quota = QuotaData::new();
+        quota.last_updated = 1;
+
+        remember_quota_etag(&key, Some("\"v1\"".to_string()), &quota);
+        assert_eq!(cached_quota_etag(&key).as_deref(), Some("\"v1\""));
+        assert!(quota_for_not_modified(&key).unwrap().last_updated > 1);
+
+        // Server stopped sending validators: fall back to plain requests
+        remember_quota_etag(&key, None, &quota);
+        assert_eq!(cached_quota_etag(&key), None);
+        assert!(quota_for_not_modified(&key).is_none());
+    }
+}
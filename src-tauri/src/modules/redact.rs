@@ -0,0 +1,155 @@
+//! Central secret redaction for anything that leaves the process (log files, console,
+//! debug console / log store, diagnostics, error strings shown to the user).
+//! OAuth access / refresh / id tokens, API keys and credentials are masked by default;
+//! `logging.redact_secrets = false` turns it off for local debugging (diagnostics bundles
+//! are always redacted).
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::borrow::Cow;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Upstream error bodies embedded in error strings are cut to this many characters
+const MAX_UPSTREAM_BODY_CHARS: usize = 512;
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// (pattern, replacement)
+static TOKEN_PATTERNS: Lazy<Vec<(Regex, String)>> = Lazy::new(|| {
+    [
+        (r"ya29\.[A-Za-z0-9_\-\.]+", REDACTED.to_string()), // Google access token
+        (r"1//[A-Za-z0-9_\-]+", REDACTED.to_string()),      // Google refresh token
+        // JWTs (id tokens, Codex access tokens)
+        (r"eyJ[A-Za-z0-9_\-]+\.[A-Za-z0-9_\-]+\.[A-Za-z0-9_\-]*", REDACTED.to_string()),
+        (r"\bsk-[A-Za-z0-9_\-]{8,}", REDACTED.to_string()), // API keys
+        (r"(?i)bearer\s+[A-Za-z0-9_\-\.=]+", REDACTED.to_string()), // Authorization headers
+        // key=value / "key": "value" pairs (JSON bodies, form bodies, query strings)
+        (
+            r"(?i)(access_token|refresh_token|id_token|client_secret|x-api-key|api_key|password)([\x22']?\s*[:=]\s*[\x22']?)[^\s\x22',}&]+",
+            format!("$1$2{}", REDACTED),
+        ),
+        (r"://[^/\s:@]+:[^/\s@]+@", format!("://{}@", REDACTED)), // credentials in URLs
+    ]
+    .into_iter()
+    .map(|(pattern, replacement)| (Regex::new(pattern).unwrap(), replacement))
+    .collect()
+});
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Applied from `logging.redact_secrets` on startup and config save
+pub fn set_enabled(enabled: bool) {
+    if ENABLED.swap(enabled, Ordering::Relaxed) != enabled && !enabled {
+        tracing::warn!("Secret redaction disabled; logs may contain tokens");
+    }
+}
+
+/// Mask tokens and credentials regardless of the config flag
+pub fn redact_always(text: &str) -> Cow<'_, str> {
+    let mut out = Cow::Borrowed(text);
+    for (re, replacement) in TOKEN_PATTERNS.iter() {
+        let replaced = match re.replace_all(&out, replacement.as_str()) {
+            Cow::Owned(replaced) => Some(replaced),
+            Cow::Borrowed(_) => None,
+        };
+        if let Some(replaced) = replaced {
+            out = Cow::Owned(replaced);
+        }
+    }
+    out
+}
+
+/// Mask tokens and credentials unless redaction is disabled in config
+pub fn redact(text: &str) -> Cow<'_, str> {
+    if is_enabled() {
+        redact_always(text)
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Redacted and truncated upstream response body, for embedding in error messages
+pub fn upstream_body(body: &str) -> String {
+    let body = redact(body.trim());
+    match body.char_indices().nth(MAX_UPSTREAM_BODY_CHARS) {
+        Some((idx, _)) => format!("{}...", &body[..idx]),
+        None => body.into_owned(),
+    }
+}
+
+/// Writer wrapper that redacts each formatted log record before it is written
+/// (tracing's fmt layer writes one complete record per call)
+pub struct RedactingWriter<W: Write> {
+    inner: W,
+}
+
+impl<W: Write> RedactingWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self { inner }
+    }
+}
+
+impl<W: Write> Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if !is_enabled() {
+            return self.inner.write(buf);
+        }
+        match std::str::from_utf8(buf) {
+            Ok(text) => {
+                self.inner.write_all(redact_always(text).as_bytes())?;
+                Ok(buf.len())
+            }
+            Err(_) => self.inner.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redacts_oauth_tokens() {
+        let body = r#"{"access_token": "ya29.a0AfH6SM-abc", "refresh_token":"1//0gAbC-d", "id_token": "eyJhbGciOi.eyJzdWIiOi.c2lnbmF0dXJl", "expires_in": 3599}"#;
+        let out = redact_always(body);
+        assert!(!out.contains("ya29."));
+        assert!(!out.contains("1//0g"));
+        assert!(!out.contains("eyJzdWIiOi"));
+        assert!(out.contains(r#""expires_in": 3599"#));
+
+        let form = "grant_type=refresh_token&refresh_token=rt_abc123&client_id=app";
+        let out = redact_always(form);
+        assert!(!out.contains("rt_abc123"));
+        assert!(out.contains("client_id=app"));
+
+        // Nothing secret: borrowed, untouched
+        assert!(matches!(redact_always("quota 42%"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_upstream_body_truncates() {
+        let body = "x".repeat(MAX_UPSTREAM_BODY_CHARS + 10);
+        let out = upstream_body(&body);
+        assert_eq!(out.len(), MAX_UPSTREAM_BODY_CHARS + 3);
+        assert!(out.ends_with("..."));
+    }
+
+    #[test]
+    fn test_redacting_writer() {
+        let mut writer = RedactingWriter::new(Vec::new());
+        let line = b"Refresh failed: {\"error\":\"invalid_grant\",\"refresh_token\":\"1//0gSecret\"}\n";
+        assert_eq!(writer.write(line).unwrap(), line.len());
+        let written = String::from_utf8(writer.inner).unwrap();
+        assert!(!written.contains("1//0gSecret"));
+        assert!(written.contains("invalid_grant"));
+    }
+}
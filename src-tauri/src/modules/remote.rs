@@ -0,0 +1,118 @@
+//! Remote management mode: forwards GUI commands to the admin API of another
+//! (typically headless) instance instead of executing them against local modules.
+
+use crate::models::config::RemoteConfig;
+use once_cell::sync::Lazy;
+use serde_json::Value;
+
+/// Batch operations (quota refresh, warmup) can take minutes on large pools
+static REMOTE_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(300))
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new())
+});
+
+fn build_url(base: &str, path: &str) -> Result<String, String> {
+    let base = base.trim().trim_end_matches('/');
+    if !(base.starts_with("http://") || base.starts_with("https://")) {
+        return Err(format!("invalid_remote_url: {}", base));
+    }
+    if !path.starts_with('/') {
+        return Err(format!("invalid_remote_path: {}", path));
+    }
+    Ok(format!("{}{}", base, path))
+}
+
+async fn send(
+    remote: &RemoteConfig,
+    method: &str,
+    path: &str,
+    body: Option<Value>,
+) -> Result<Value, String> {
+    let url = build_url(&remote.url, path)?;
+    let method = reqwest::Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+        .map_err(|_| format!("invalid_http_method: {}", method))?;
+
+    let mut req = REMOTE_CLIENT.request(method, &url);
+    if !remote.token.is_empty() {
+        req = req
+            .header("Authorization", format!("Bearer {}", remote.token))
+            .header("x-api-key", &remote.token);
+    }
+    if let Some(body) = body {
+        req = req.json(&body);
+    }
+
+    let resp = req
+        .send()
+        .await
+        .map_err(|e| format!("remote_request_failed: {}", e))?;
+    let status = resp.status();
+    let text = resp
+        .text()
+        .await
+        .map_err(|e| format!("remote_response_read_failed: {}", e))?;
+
+    if !status.is_success() {
+        // Admin API errors are `{ "error": "..." }`
+        let message = serde_json::from_str::<Value>(&text)
+            .ok()
+            .and_then(|v| v.get("error").and_then(|e| e.as_str()).map(|s| s.to_string()))
+            .unwrap_or_else(|| format!("HTTP {}", status.as_u16()));
+        return Err(message);
+    }
+
+    if text.trim().is_empty() {
+        return Ok(Value::Null);
+    }
+    Ok(serde_json::from_str(&text).unwrap_or(Value::String(text)))
+}
+
+/// Forward a request to the configured remote instance
+pub async fn forward(method: &str, path: &str, body: Option<Value>) -> Result<Value, String> {
+    let config = crate::modules::config::load_app_config()?;
+    if !config.remote.enabled {
+        return Err("remote_mode_disabled".to_string());
+    }
+    send(&config.remote, method, path, body).await
+}
+
+/// Check that a remote instance is reachable and the token is accepted.
+/// The settings form passes the token as loaded (encrypted); deserializing decrypts it.
+pub async fn test_connection(remote: &RemoteConfig) -> Result<Value, String> {
+    // Use an authenticated endpoint so a wrong token is reported as a failure
+    send(remote, "GET", "/api/accounts/current", None).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_url() {
+        assert_eq!(
+            build_url("http://home.lan:8045/", "/api/accounts").unwrap(),
+            "http://home.lan:8045/api/accounts"
+        );
+        assert!(build_url("home.lan:8045", "/api/accounts").is_err());
+        assert!(build_url("https://home.lan", "api/accounts").is_err());
+    }
+
+    #[test]
+    fn test_token_encrypted_on_disk() {
+        let remote = RemoteConfig {
+            enabled: true,
+            url: "http://home.lan:8045".to_string(),
+            token: "admin-secret".to_string(),
+        };
+        let saved = serde_json::to_value(&remote).unwrap();
+        assert_ne!(saved["token"], "admin-secret");
+
+        // Loading, and the form sending the loaded value back, both yield the plain token
+        let loaded: RemoteConfig = serde_json::from_value(saved).unwrap();
+        assert_eq!(loaded.token, "admin-secret");
+        let resent: RemoteConfig = serde_json::from_value(serde_json::to_value(&loaded).unwrap()).unwrap();
+        assert_eq!(resent.token, "admin-secret");
+    }
+}
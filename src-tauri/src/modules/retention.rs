@@ -0,0 +1,330 @@
+// 数据保留策略
+// 长期运行的 (无头) 实例会不断累积配额历史、用量统计、请求日志与审计记录。
+// 每晚在 data_retention.cleanup_hour 执行一次清理；dry_run 仅统计将被删除的行数。
+// 请求日志沿用 proxy.request_log.retention_days (其行数上限与 VACUUM 由 proxy_db 的每小时任务负责)。
+
+use chrono::Timelike;
+use rusqlite::Connection;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use crate::models::DataRetentionConfig;
+use crate::modules::{config, logger, proxy_db, scheduler, security_db, token_stats, user_token_db};
+
+const CHECK_INTERVAL_SECS: u64 = 600;
+const JOB_ID: &str = "retention_cleanup";
+
+static NIGHTLY_STARTED: AtomicBool = AtomicBool::new(false);
+/// 最近一次夜间清理的本地日期，避免同一小时内重复执行
+static LAST_NIGHTLY_RUN: Mutex<Option<chrono::NaiveDate>> = Mutex::new(None);
+
+/// 单个表的清理结果
+#[derive(Debug, Clone, Serialize)]
+pub struct RetentionTargetResult {
+    /// quota_history / usage_stats / request_logs / audit_events
+    pub category: String,
+    pub table: String,
+    pub retention_days: u32,
+    /// 早于该时间 (Unix 秒) 的记录会被删除；保留天数为 0 时为 None
+    pub cutoff: Option<i64>,
+    /// dry_run 时为将被删除的行数，否则为实际删除的行数
+    pub rows: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RetentionReport {
+    pub dry_run: bool,
+    pub ran_at: i64,
+    pub targets: Vec<RetentionTargetResult>,
+    pub total_rows: u64,
+    /// 无法打开的数据库或清理失败的表 (不影响其他表)
+    pub errors: Vec<String>,
+}
+
+/// 表内时间列的存储方式
+#[derive(Clone, Copy)]
+enum TimeColumn {
+    Seconds(&'static str),
+    Millis(&'static str),
+    /// 本地时间的小时桶 ("%Y-%m-%d %H:00")
+    HourBucket(&'static str),
+}
+
+struct Target {
+    category: &'static str,
+    table: &'static str,
+    column: TimeColumn,
+    days: u32,
+}
+
+/// 打开某个数据库的连接
+type Connector = fn() -> Result<Connection, String>;
+
+fn prune(
+    conn: &Connection,
+    target: &Target,
+    now: chrono::DateTime<chrono::Utc>,
+    dry_run: bool,
+) -> Result<RetentionTargetResult, String> {
+    let mut result = RetentionTargetResult {
+        category: target.category.to_string(),
+        table: target.table.to_string(),
+        retention_days: target.days,
+        cutoff: None,
+        rows: 0,
+    };
+    if target.days == 0 {
+        return Ok(result);
+    }
+    let cutoff = now - chrono::Duration::days(i64::from(target.days));
+    result.cutoff = Some(cutoff.timestamp());
+
+    let (column, bound): (&str, rusqlite::types::Value) = match target.column {
+        TimeColumn::Seconds(c) => (c, cutoff.timestamp().into()),
+        TimeColumn::Millis(c) => (c, cutoff.timestamp_millis().into()),
+        TimeColumn::HourBucket(c) => (
+            c,
+            cutoff
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:00")
+                .to_string()
+                .into(),
+        ),
+    };
+    let condition = format!("FROM {} WHERE {} < ?1", target.table, column);
+    let rows = if dry_run {
+        conn.query_row(&format!("SELECT COUNT(*) {}", condition), [&bound], |row| {
+            row.get::<_, u64>(0)
+        })
+    } else {
+        conn.execute(&format!("DELETE {}", condition), [&bound])
+            .map(|n| n as u64)
+    }
+    .map_err(|e| format!("{}: {}", target.table, e))?;
+    result.rows = rows;
+    Ok(result)
+}
+
+/// 按当前配置执行一次清理 (dry_run 时只统计)
+pub fn run(dry_run: bool) -> Result<RetentionReport, String> {
+    let app_config = config::load_app_config()?;
+    Ok(run_with(
+        &app_config.data_retention,
+        app_config.proxy.request_log.retention_days,
+        chrono::Utc::now(),
+        dry_run,
+    ))
+}
+
+fn run_with(
+    retention: &DataRetentionConfig,
+    request_log_days: u32,
+    now: chrono::DateTime<chrono::Utc>,
+    dry_run: bool,
+) -> RetentionReport {
+    let databases: [(&str, Connector, Vec<Target>); 4] = [
+        (
+            "token_stats.db",
+            token_stats::connect_db,
+            vec![
+                Target {
+                    category: "quota_history",
+                    table: "quota_snapshots",
+                    column: TimeColumn::Seconds("timestamp"),
+                    days: retention.quota_history_days,
+                },
+                Target {
+                    category: "usage_stats",
+                    table: "token_usage",
+                    column: TimeColumn::Seconds("timestamp"),
+                    days: retention.usage_stats_days,
+                },
+                Target {
+                    category: "usage_stats",
+                    table: "token_stats_hourly",
+                    column: TimeColumn::HourBucket("hour_bucket"),
+                    days: retention.usage_stats_days,
+                },
+                Target {
+                    category: "usage_stats",
+                    table: "request_latency",
+                    column: TimeColumn::Seconds("timestamp"),
+                    days: retention.usage_stats_days,
+                },
+            ],
+        ),
+        (
+            "proxy_logs.db",
+            proxy_db::connect_db,
+            vec![Target {
+                category: "request_logs",
+                table: "request_logs",
+                column: TimeColumn::Millis("timestamp"),
+                days: request_log_days,
+            }],
+        ),
+        (
+            "security.db",
+            security_db::connect_db,
+            vec![Target {
+                category: "audit_events",
+                table: "ip_access_logs",
+                column: TimeColumn::Seconds("timestamp"),
+                days: retention.audit_events_days,
+            }],
+        ),
+        (
+            "user_tokens.db",
+            user_token_db::connect_db,
+            vec![Target {
+                category: "audit_events",
+                table: "token_usage_logs",
+                column: TimeColumn::Seconds("request_time"),
+                days: retention.audit_events_days,
+            }],
+        ),
+    ];
+
+    let mut report = RetentionReport {
+        dry_run,
+        ran_at: now.timestamp(),
+        targets: Vec::new(),
+        total_rows: 0,
+        errors: Vec::new(),
+    };
+    for (name, connect, targets) in databases {
+        let conn = match connect() {
+            Ok(conn) => conn,
+            Err(e) => {
+                report.errors.push(format!("{}: {}", name, e));
+                continue;
+            }
+        };
+        for target in &targets {
+            match prune(&conn, target, now, dry_run) {
+                Ok(result) => {
+                    report.total_rows += result.rows;
+                    report.targets.push(result);
+                }
+                Err(e) => report.errors.push(format!("{}: {}", name, e)),
+            }
+        }
+    }
+    report
+}
+
+/// 启动夜间清理任务 (每个进程一次)
+pub fn start_nightly_cleanup() {
+    if NIGHTLY_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    scheduler::register_job(JOB_ID, CHECK_INTERVAL_SECS);
+    crate::utils::runtime::spawn(async {
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_secs(CHECK_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            if !scheduler::job_tick(JOB_ID) {
+                continue;
+            }
+
+            let Ok(app_config) = config::load_app_config() else {
+                continue;
+            };
+            let now = chrono::Local::now();
+            if now.hour() != u32::from(app_config.data_retention.cleanup_hour) {
+                continue;
+            }
+            {
+                let mut last = LAST_NIGHTLY_RUN.lock().unwrap_or_else(|e| e.into_inner());
+                if *last == Some(now.date_naive()) {
+                    continue;
+                }
+                *last = Some(now.date_naive());
+            }
+
+            let started = std::time::Instant::now();
+            let result = match tokio::task::spawn_blocking(|| run(false)).await {
+                Ok(Ok(report)) => {
+                    logger::log_info(&format!(
+                        "[Retention] Nightly cleanup removed {} row(s) across {} table(s)",
+                        report.total_rows,
+                        report.targets.len()
+                    ));
+                    for e in &report.errors {
+                        logger::log_warn(&format!("[Retention] {}", e));
+                    }
+                    match report.errors.first() {
+                        Some(e) => Err(e.clone()),
+                        None => Ok(format!("{} row(s) removed", report.total_rows)),
+                    }
+                }
+                Ok(Err(e)) => {
+                    logger::log_warn(&format!("[Retention] Cleanup failed: {}", e));
+                    Err(e)
+                }
+                Err(e) => {
+                    logger::log_error(&format!("[Retention] Cleanup task failed: {}", e));
+                    Err(e.to_string())
+                }
+            };
+            scheduler::record_job_run(JOB_ID, started, result);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prune_dry_run_then_delete() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE quota_snapshots (timestamp INTEGER, account_id TEXT);
+             CREATE TABLE token_stats_hourly (hour_bucket TEXT);",
+        )
+        .unwrap();
+        let now = chrono::Utc::now();
+        let day = 86_400;
+        for age_days in [40i64, 31, 10, 0] {
+            conn.execute(
+                "INSERT INTO quota_snapshots VALUES (?1, 'a')",
+                [now.timestamp() - age_days * day],
+            )
+            .unwrap();
+            let bucket = (now - chrono::Duration::days(age_days))
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:00")
+                .to_string();
+            conn.execute("INSERT INTO token_stats_hourly VALUES (?1)", [bucket])
+                .unwrap();
+        }
+
+        let snapshots = Target {
+            category: "quota_history",
+            table: "quota_snapshots",
+            column: TimeColumn::Seconds("timestamp"),
+            days: 30,
+        };
+        let dry = prune(&conn, &snapshots, now, true).unwrap();
+        assert_eq!(dry.rows, 2);
+        assert_eq!(dry.cutoff, Some(now.timestamp() - 30 * day));
+        assert_eq!(prune(&conn, &snapshots, now, false).unwrap().rows, 2);
+        assert_eq!(prune(&conn, &snapshots, now, true).unwrap().rows, 0);
+
+        let hourly = Target {
+            category: "usage_stats",
+            table: "token_stats_hourly",
+            column: TimeColumn::HourBucket("hour_bucket"),
+            days: 20,
+        };
+        assert_eq!(prune(&conn, &hourly, now, false).unwrap().rows, 2);
+
+        // 0 天表示永久保留
+        let keep = Target { days: 0, ..hourly };
+        let result = prune(&conn, &keep, now, false).unwrap();
+        assert_eq!((result.rows, result.cutoff), (0, None));
+    }
+}
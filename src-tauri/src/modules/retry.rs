@@ -0,0 +1,209 @@
+// 通用重试 / 退避策略
+// 配额拉取、用户信息、反代上游请求共用同一套规则：
+// - 失败按 RetryClass 分类 (401 / 429 / 5xx / 超时)，只有配置允许的类别才重试
+// - 指数退避 + 抖动 (equal jitter)，单次等待不超过 retry_max_delay_ms
+// - 401 不等待：调用方刷新 Token 后立即重试
+//
+// 简单场景直接用 `retry()`；重试前需要改动外部状态 (如刷新 Token) 的场景用 `Backoff` 手动驱动循环。
+
+use crate::error::AppError;
+use crate::models::{HttpClientConfig, RetryClass};
+use rand::Rng;
+use std::future::Future;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// 总尝试次数 (含首次)
+    pub max_attempts: u32,
+    pub base_delay_ms: u64,
+    pub max_delay_ms: u64,
+    pub retry_on: Vec<RetryClass>,
+}
+
+impl RetryPolicy {
+    pub fn from_config(cfg: &HttpClientConfig) -> Self {
+        Self {
+            max_attempts: cfg.max_attempts.max(1),
+            base_delay_ms: cfg.retry_base_delay_ms,
+            max_delay_ms: cfg.retry_max_delay_ms.max(cfg.retry_base_delay_ms),
+            retry_on: cfg.retry_on.clone(),
+        }
+    }
+
+    /// 当前生效的全局策略 (AppConfig.http_client)
+    pub fn current() -> Self {
+        Self::from_config(&crate::modules::http::settings())
+    }
+
+    pub fn retries(&self, class: RetryClass) -> bool {
+        self.retry_on.contains(&class)
+    }
+
+    /// 第 `attempt` 次失败后的等待时长；`jitter` 取值 [0, 1]
+    /// 等待 = 退避值的一半 + 抖动 × 另一半，避免多个账号同时重试
+    fn delay_with_jitter(&self, class: RetryClass, attempt: u32, jitter: f64) -> Duration {
+        if class == RetryClass::Unauthorized {
+            return Duration::ZERO;
+        }
+        let exp = self
+            .base_delay_ms
+            .saturating_mul(1u64 << attempt.saturating_sub(1).min(20))
+            .min(self.max_delay_ms);
+        let half = exp / 2;
+        Duration::from_millis(half + ((exp - half) as f64 * jitter.clamp(0.0, 1.0)) as u64)
+    }
+
+    pub fn start(&self) -> Backoff {
+        Backoff {
+            policy: self.clone(),
+            attempt: 1,
+        }
+    }
+}
+
+/// 手动驱动的重试计数器
+pub struct Backoff {
+    policy: RetryPolicy,
+    attempt: u32,
+}
+
+impl Backoff {
+    /// 当前是第几次尝试 (从 1 开始)
+    pub fn attempt(&self) -> u32 {
+        self.attempt
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.policy.max_attempts
+    }
+
+    /// 一次失败之后调用：可以重试时返回需要等待的时长并推进计数，否则返回 None
+    pub fn next_delay(&mut self, class: Option<RetryClass>) -> Option<Duration> {
+        let class = class?;
+        if !self.policy.retries(class) || self.attempt >= self.policy.max_attempts {
+            return None;
+        }
+        let delay = self
+            .policy
+            .delay_with_jitter(class, self.attempt, rand::thread_rng().gen());
+        self.attempt += 1;
+        Some(delay)
+    }
+}
+
+/// HTTP 状态码分类；其余状态码 (400 / 403 / 404 ...) 不重试
+pub fn classify_status(status: u16) -> Option<RetryClass> {
+    match status {
+        401 => Some(RetryClass::Unauthorized),
+        429 => Some(RetryClass::RateLimited),
+        408 => Some(RetryClass::Timeout),
+        500..=599 => Some(RetryClass::ServerError),
+        _ => None,
+    }
+}
+
+/// 传输层错误分类：超时 / 连接失败视为 Timeout
+pub fn classify_transport_error(err: &rquest::Error) -> Option<RetryClass> {
+    if let Some(status) = err.status() {
+        return classify_status(status.as_u16());
+    }
+    (err.is_timeout() || err.is_connect()).then_some(RetryClass::Timeout)
+}
+
+pub fn classify_app_error(err: &AppError) -> Option<RetryClass> {
+    match err {
+        // 配额耗尽在重置前重试无意义
+        AppError::QuotaExhausted { .. } => None,
+        AppError::Network(_, None) => Some(RetryClass::Timeout),
+        other => other.status().and_then(classify_status),
+    }
+}
+
+/// 按策略重试异步操作
+/// `classify` 返回 None 表示不可重试，立即返回错误
+pub async fn retry<T, E, F, Fut, C>(
+    policy: &RetryPolicy,
+    label: &str,
+    classify: C,
+    mut op: F,
+) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    C: Fn(&E) -> Option<RetryClass>,
+    E: std::fmt::Display,
+{
+    let mut backoff = policy.start();
+    loop {
+        let err = match op().await {
+            Ok(value) => return Ok(value),
+            Err(e) => e,
+        };
+        let attempt = backoff.attempt();
+        let class = classify(&err);
+        match backoff.next_delay(class) {
+            Some(delay) => {
+                tracing::warn!(
+                    "[Retry] {} failed ({:?}, attempt {}/{}), retrying in {}ms: {}",
+                    label,
+                    class,
+                    attempt,
+                    backoff.max_attempts(),
+                    delay.as_millis(),
+                    err
+                );
+                tokio::time::sleep(delay).await;
+            }
+            None => return Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay_ms: 1000,
+            max_delay_ms: 3000,
+            retry_on: vec![
+                RetryClass::Unauthorized,
+                RetryClass::RateLimited,
+                RetryClass::ServerError,
+            ],
+        }
+    }
+
+    #[test]
+    fn test_backoff_delays_and_limits() {
+        let p = policy();
+        assert_eq!(p.delay_with_jitter(RetryClass::ServerError, 1, 0.0), Duration::from_millis(500));
+        assert_eq!(p.delay_with_jitter(RetryClass::ServerError, 1, 1.0), Duration::from_millis(1000));
+        assert_eq!(p.delay_with_jitter(RetryClass::RateLimited, 2, 1.0), Duration::from_millis(2000));
+        // 封顶 max_delay_ms
+        assert_eq!(p.delay_with_jitter(RetryClass::RateLimited, 5, 1.0), Duration::from_millis(3000));
+        assert_eq!(p.delay_with_jitter(RetryClass::Unauthorized, 1, 1.0), Duration::ZERO);
+
+        let mut b = p.start();
+        assert!(b.next_delay(None).is_none());
+        // Timeout 未在 retry_on 中
+        assert!(b.next_delay(Some(RetryClass::Timeout)).is_none());
+        assert!(b.next_delay(Some(RetryClass::ServerError)).is_some());
+        assert!(b.next_delay(Some(RetryClass::ServerError)).is_some());
+        assert_eq!(b.attempt(), 3);
+        assert!(b.next_delay(Some(RetryClass::ServerError)).is_none());
+    }
+
+    #[test]
+    fn test_classify_status() {
+        assert_eq!(classify_status(401), Some(RetryClass::Unauthorized));
+        assert_eq!(classify_status(429), Some(RetryClass::RateLimited));
+        assert_eq!(classify_status(503), Some(RetryClass::ServerError));
+        assert_eq!(classify_status(408), Some(RetryClass::Timeout));
+        assert_eq!(classify_status(403), None);
+        assert_eq!(classify_status(400), None);
+    }
+}
@@ -1,15 +1,26 @@
 use chrono::Utc;
 use once_cell::sync::Lazy;
-use std::collections::HashMap;
-use std::sync::Mutex;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, RwLock};
 use tokio::time::{self, Duration};
-use crate::modules::{config, logger, quota, account};
-use crate::models::Account;
+use crate::modules::{config, logger, quota, account, warmup_pacer};
+use crate::models::{Account, QuietHoursConfig, SchedulerConfig};
 use std::path::PathBuf;
 
 // Warmup history: key = "email:model_name:100", value = warmup timestamp
 static WARMUP_HISTORY: Lazy<Mutex<HashMap<String, i64>>> = Lazy::new(|| Mutex::new(load_warmup_history()));
 
+// Accounts with a re-enable warmup already scheduled (at most one pending per account)
+static PENDING_REENABLE_WARMUPS: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+const SNOOZE_JOB: &str = "snooze_wake";
+const SNOOZE_INTERVAL_SECS: u64 = 60;
+
+/// Delay before the re-enable warmup, so several transitions in a row collapse into one
+const REENABLE_WARMUP_DELAY_SECS: u64 = 5;
+
 fn get_warmup_history_path() -> Result<PathBuf, String> {
     let data_dir = account::get_data_dir()?;
     Ok(data_dir.join("warmup_history.json"))
@@ -51,8 +62,194 @@ pub fn check_cooldown(key: &str, cooldown_seconds: i64) -> bool {
     }
 }
 
-pub fn start_scheduler(app_handle: Option<tauri::AppHandle>, proxy_state: crate::commands::proxy::ProxyServiceState) {
-    tauri::async_runtime::spawn(async move {
+pub(crate) fn parse_hhmm(value: &str) -> Option<chrono::NaiveTime> {
+    chrono::NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+/// Whether `now` falls inside [start, end). Windows with end < start wrap past midnight.
+pub(crate) fn is_within_window(now: chrono::NaiveTime, start: chrono::NaiveTime, end: chrono::NaiveTime) -> bool {
+    if start == end {
+        return false;
+    }
+    if start < end {
+        now >= start && now < end
+    } else {
+        now >= start || now < end
+    }
+}
+
+/// Check whether background jobs should be suppressed right now (local time)
+pub fn is_quiet_hours(cfg: &QuietHoursConfig) -> bool {
+    if !cfg.enabled {
+        return false;
+    }
+    match (parse_hhmm(&cfg.start), parse_hhmm(&cfg.end)) {
+        (Some(start), Some(end)) => is_within_window(chrono::Local::now().time(), start, end),
+        _ => {
+            logger::log_warn(&format!(
+                "[Scheduler] Invalid quiet hours window {}-{}, ignoring",
+                cfg.start, cfg.end
+            ));
+            false
+        }
+    }
+}
+
+// ===== Background job registry =====
+// Periodic jobs (catalog sync, backup, digest, ...) register here when started and report each
+// check and run, so their state can be inspected and individual jobs switched off remotely.
+
+/// Outcome of the most recent run of a job
+#[derive(Debug, Clone, Serialize)]
+pub struct JobRun {
+    pub started_at: i64,
+    pub duration_ms: u64,
+    pub success: bool,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JobStatus {
+    pub id: String,
+    pub enabled: bool,
+    /// How often the job wakes up to check whether it is due
+    pub interval_secs: u64,
+    pub last_check_at: Option<i64>,
+    pub next_check_at: Option<i64>,
+    pub last_run: Option<JobRun>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SchedulerStatus {
+    /// Jobs that honour quiet hours / offline mode skip their runs while these are set
+    pub quiet_hours_active: bool,
+    pub offline: bool,
+    pub jobs: Vec<JobStatus>,
+}
+
+#[derive(Debug, Clone)]
+struct JobState {
+    interval_secs: u64,
+    last_check_at: Option<i64>,
+    last_run: Option<JobRun>,
+}
+
+static JOBS: Lazy<Mutex<BTreeMap<&'static str, JobState>>> = Lazy::new(|| Mutex::new(BTreeMap::new()));
+
+static DISABLED_JOBS: Lazy<RwLock<HashSet<String>>> = Lazy::new(|| {
+    let disabled = config::load_app_config()
+        .map(|c| c.scheduler.disabled_jobs.into_iter().collect())
+        .unwrap_or_default();
+    RwLock::new(disabled)
+});
+
+/// Apply saved job switches (called by config::save_app_config)
+pub fn update_config(cfg: &SchedulerConfig) {
+    if let Ok(mut disabled) = DISABLED_JOBS.write() {
+        *disabled = cfg.disabled_jobs.iter().cloned().collect();
+    }
+}
+
+/// Register a periodic job (called once by its start_* function)
+pub fn register_job(id: &'static str, interval_secs: u64) {
+    JOBS.lock().unwrap_or_else(|e| e.into_inner()).insert(
+        id,
+        JobState {
+            interval_secs,
+            last_check_at: None,
+            last_run: None,
+        },
+    );
+}
+
+/// Set once before a data wipe; no job runs again until the app restarts
+static JOBS_HALTED: AtomicBool = AtomicBool::new(false);
+
+/// Stop all background jobs for the rest of the process (runs already in progress finish)
+pub fn halt_all_jobs() {
+    JOBS_HALTED.store(true, Ordering::SeqCst);
+}
+
+fn jobs_halted() -> bool {
+    JOBS_HALTED.load(Ordering::SeqCst)
+}
+
+pub fn is_job_enabled(id: &str) -> bool {
+    DISABLED_JOBS.read().map_or(true, |d| !d.contains(id))
+}
+
+/// Called on every wake-up of a job; returns false when the job is switched off
+pub fn job_tick(id: &str) -> bool {
+    if let Some(job) = JOBS.lock().unwrap_or_else(|e| e.into_inner()).get_mut(id) {
+        job.last_check_at = Some(Utc::now().timestamp());
+    }
+    !jobs_halted() && is_job_enabled(id)
+}
+
+/// Record the outcome of a run; `Ok` carries a short summary
+pub fn record_job_run(id: &str, started: std::time::Instant, result: Result<String, String>) {
+    let duration_ms = started.elapsed().as_millis() as u64;
+    let started_at = Utc::now().timestamp() - (duration_ms / 1000) as i64;
+    let (success, message) = match result {
+        Ok(summary) => (true, summary),
+        Err(e) => (false, e),
+    };
+    if let Some(job) = JOBS.lock().unwrap_or_else(|e| e.into_inner()).get_mut(id) {
+        job.last_run = Some(JobRun {
+            started_at,
+            duration_ms,
+            success,
+            message,
+        });
+    }
+}
+
+pub fn scheduler_status() -> SchedulerStatus {
+    let quiet_hours_active = config::load_app_config()
+        .map(|c| is_quiet_hours(&c.quiet_hours))
+        .unwrap_or(false);
+    let jobs = JOBS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .map(|(id, job)| JobStatus {
+            id: id.to_string(),
+            enabled: is_job_enabled(id),
+            interval_secs: job.interval_secs,
+            last_check_at: job.last_check_at,
+            next_check_at: job.last_check_at.map(|t| t + job.interval_secs as i64),
+            last_run: job.last_run.clone(),
+        })
+        .collect();
+    SchedulerStatus {
+        quiet_hours_active,
+        offline: crate::modules::offline::is_offline(),
+        jobs,
+    }
+}
+
+/// Switch a registered job on or off (persisted in the config)
+pub fn set_job_enabled(id: &str, enabled: bool) -> Result<SchedulerStatus, String> {
+    if !JOBS.lock().unwrap_or_else(|e| e.into_inner()).contains_key(id) {
+        return Err(format!("unknown_job: {}", id));
+    }
+    let mut app_config = config::load_app_config()?;
+    let disabled = &mut app_config.scheduler.disabled_jobs;
+    disabled.retain(|j| j != id);
+    if !enabled {
+        disabled.push(id.to_string());
+    }
+    config::save_app_config(&app_config)?;
+    logger::log_info(&format!(
+        "[Scheduler] Job {} {}",
+        id,
+        if enabled { "enabled" } else { "disabled" }
+    ));
+    Ok(scheduler_status())
+}
+
+pub fn start_scheduler(integration: crate::modules::integration::SystemManager, proxy_state: crate::commands::proxy::ProxyServiceState) {
+    crate::utils::runtime::spawn(async move {
         logger::log_info("Smart Warmup Scheduler started. Monitoring quota at 100%...");
         
         // Scan every 10 minutes
@@ -60,6 +257,9 @@ pub fn start_scheduler(app_handle: Option<tauri::AppHandle>, proxy_state: crate:
 
         loop {
             interval.tick().await;
+            if jobs_halted() {
+                continue;
+            }
 
             // Load configuration
             let Ok(app_config) = config::load_app_config() else {
@@ -69,7 +269,20 @@ pub fn start_scheduler(app_handle: Option<tauri::AppHandle>, proxy_state: crate:
             if !app_config.auto_refresh {
                 continue;
             }
-            
+
+            if is_quiet_hours(&app_config.quiet_hours) {
+                logger::log_info(&format!(
+                    "[Scheduler] Quiet hours active ({}-{}), skipping background scan",
+                    app_config.quiet_hours.start, app_config.quiet_hours.end
+                ));
+                continue;
+            }
+
+            if crate::modules::offline::is_offline() {
+                logger::log_info("[Scheduler] Offline mode active, skipping background scan");
+                continue;
+            }
+
             // Get all accounts (no longer filtering by level)
             let Ok(accounts) = account::list_accounts() else {
                 continue;
@@ -182,51 +395,46 @@ pub fn start_scheduler(app_handle: Option<tauri::AppHandle>, proxy_state: crate:
                     total
                 ));
 
-                let handle_for_warmup = app_handle.clone();
                 let state_for_warmup = proxy_state.clone();
+                let pacing = app_config.scheduled_warmup.clone();
 
                 tokio::spawn(async move {
                     let mut success = 0;
-                    let batch_size = 3;
                     let now_ts = chrono::Utc::now().timestamp();
-                    
-                    for (batch_idx, batch) in warmup_tasks.chunks(batch_size).enumerate() {
-                        let mut handles = Vec::new();
-                        
-                        for (task_idx, (id, email, model, token, pid, pct, history_key)) in batch.iter().enumerate() {
-                            let global_idx = batch_idx * batch_size + task_idx + 1;
-                            let id = id.clone();
-                            let email = email.clone();
-                            let model = model.clone();
-                            let token = token.clone();
-                            let pid = pid.clone();
-                            let pct = *pct;
-                            let history_key = history_key.clone();
-                            
-                            logger::log_info(&format!(
-                                "[Warmup {}/{}] {} @ {} ({}%)",
-                                global_idx, total, model, email, pct
-                            ));
-                            
-                            let handle = tokio::spawn(async move {
-                                let result = quota::warmup_model_directly(&token, &model, &pid, &email, pct, Some(&id)).await;
-                                (result, history_key)
-                            });
-                            handles.push(handle);
-                        }
-                        
-                        for handle in handles {
-                            match handle.await {
-                                Ok((true, history_key)) => {
-                                    success += 1;
-                                    record_warmup_history(&history_key, now_ts);
-                                }
-                                _ => {}
-                            }
-                        }
-                        
-                        if batch_idx < (warmup_tasks.len() + batch_size - 1) / batch_size - 1 {
-                            tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+
+                    // Spread the batch over the configured window instead of firing it all at once
+                    let account_ids: Vec<&str> = warmup_tasks.iter().map(|t| t.0.as_str()).collect();
+                    let schedule = warmup_pacer::plan(&account_ids, &pacing);
+                    if let Some((_, last)) = schedule.last() {
+                        logger::log_info(&format!(
+                            "[Scheduler] Pacing {} warmup tasks over {}s",
+                            total,
+                            last.as_secs()
+                        ));
+                    }
+                    let started = time::Instant::now();
+                    let mut handles = Vec::new();
+
+                    for (seq, (task_idx, offset)) in schedule.into_iter().enumerate() {
+                        time::sleep_until(started + offset).await;
+                        warmup_pacer::acquire(&pacing).await;
+
+                        let (id, email, model, token, pid, pct, history_key) = warmup_tasks[task_idx].clone();
+                        logger::log_info(&format!(
+                            "[Warmup {}/{}] {} @ {} ({}%)",
+                            seq + 1, total, model, email, pct
+                        ));
+
+                        handles.push(tokio::spawn(async move {
+                            let result = quota::warmup_model_directly(&token, &model, &pid, &email, pct, Some(&id)).await;
+                            (result, history_key)
+                        }));
+                    }
+
+                    for handle in handles {
+                        if let Ok((true, history_key)) = handle.await {
+                            success += 1;
+                            record_warmup_history(&history_key, now_ts);
                         }
                     }
 
@@ -237,7 +445,7 @@ pub fn start_scheduler(app_handle: Option<tauri::AppHandle>, proxy_state: crate:
 
                     // Refresh quota
                     tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
-                    let _ = crate::commands::refresh_all_quotas_internal(&state_for_warmup, handle_for_warmup).await;
+                    let _ = crate::commands::refresh_all_quotas_internal(&state_for_warmup, None).await;
                 });
             } else if skipped_cooldown > 0 {
                 logger::log_info(&format!(
@@ -249,12 +457,11 @@ pub fn start_scheduler(app_handle: Option<tauri::AppHandle>, proxy_state: crate:
             }
 
             // Sync to frontend if handle exists
-            if let Some(handle) = app_handle.as_ref() {
-                let handle_inner = handle.clone();
+            if integration.is_desktop() {
                 let state_inner = proxy_state.clone();
                 tokio::spawn(async move {
                     tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-                    let _ = crate::commands::refresh_all_quotas_internal(&state_inner, Some(handle_inner)).await;
+                    let _ = crate::commands::refresh_all_quotas_internal(&state_inner, None).await;
                     logger::log_info("[Scheduler] Quota data synced to frontend");
                 });
             }
@@ -270,6 +477,72 @@ pub fn start_scheduler(app_handle: Option<tauri::AppHandle>, proxy_state: crate:
     });
 }
 
+/// Periodically re-enable snoozed accounts (checked every minute, and once at startup)
+pub fn start_snooze_scheduler() {
+    register_job(SNOOZE_JOB, SNOOZE_INTERVAL_SECS);
+    crate::utils::runtime::spawn(async move {
+        let mut interval = time::interval(Duration::from_secs(SNOOZE_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            if !job_tick(SNOOZE_JOB) {
+                continue;
+            }
+            let started = std::time::Instant::now();
+            let now = Utc::now().timestamp();
+            let result = tokio::task::spawn_blocking(move || account::wake_expired_snoozes(now))
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|r| r.map(|woken| format!("{} account(s) woken", woken.len())));
+            if let Err(e) = &result {
+                logger::log_warn(&format!("[Scheduler] Failed to wake snoozed accounts: {}", e));
+            }
+            record_job_run(SNOOZE_JOB, started, result);
+        }
+    });
+}
+
+/// Schedule a single warmup for an account that just went from disabled / protected back to
+/// enabled (manual re-enable, quota protection recovery, snooze expiry), instead of waiting
+/// for the next global warmup cycle. Skipped when scheduled warmup is off or in quiet hours.
+pub fn schedule_warmup_after_reenable(account_id: &str, trigger: &str) {
+    if !PENDING_REENABLE_WARMUPS.lock().unwrap().insert(account_id.to_string()) {
+        return;
+    }
+    let account_id = account_id.to_string();
+    let trigger = trigger.to_string();
+
+    crate::utils::runtime::spawn(async move {
+        time::sleep(Duration::from_secs(REENABLE_WARMUP_DELAY_SECS)).await;
+        PENDING_REENABLE_WARMUPS.lock().unwrap().remove(&account_id);
+
+        let Ok(app_config) = config::load_app_config() else {
+            return;
+        };
+        if !app_config.scheduled_warmup.enabled {
+            return;
+        }
+        if is_quiet_hours(&app_config.quiet_hours) {
+            logger::log_info(&format!(
+                "[Scheduler] Skipping re-enable warmup for {} during quiet hours",
+                account_id
+            ));
+            return;
+        }
+        let Ok(acc) = account::load_account(&account_id) else {
+            return;
+        };
+        if acc.disabled || acc.proxy_disabled {
+            return;
+        }
+
+        logger::log_info(&format!(
+            "[Scheduler] Account {} back in rotation ({}), running warmup",
+            acc.email, trigger
+        ));
+        trigger_warmup_for_account(&acc).await;
+    });
+}
+
 /// Trigger immediate smart warmup check for a single account
 pub async fn trigger_warmup_for_account(account: &Account) {
 
@@ -299,6 +572,10 @@ pub async fn trigger_warmup_for_account(account: &Account) {
         return;
     };
 
+    if is_quiet_hours(&app_config.quiet_hours) || crate::modules::offline::is_offline() {
+        return;
+    }
+
     let now_ts = Utc::now().timestamp();
     let mut tasks_to_run = Vec::new();
 
@@ -344,7 +621,14 @@ pub async fn trigger_warmup_for_account(account: &Account) {
             tasks_to_run.len(), account.email
         ));
 
-        for (model, pct, history_key) in tasks_to_run {
+        let account_ids = vec![account.id.as_str(); tasks_to_run.len()];
+        let schedule = warmup_pacer::plan(&account_ids, &app_config.scheduled_warmup);
+        let started = time::Instant::now();
+
+        for (task_idx, offset) in schedule {
+            let (model, pct, history_key) = tasks_to_run[task_idx].clone();
+            time::sleep_until(started + offset).await;
+            warmup_pacer::acquire(&app_config.scheduled_warmup).await;
             logger::log_info(&format!(
                 "[Scheduler] 🔥 Triggering individual warmup: {} @ {} (Sync)",
                 model, account.email
@@ -359,3 +643,47 @@ pub async fn trigger_warmup_for_account(account: &Account) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveTime;
+
+    fn t(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn test_daytime_window() {
+        assert!(is_within_window(t(9, 0), t(9, 0), t(18, 0)));
+        assert!(is_within_window(t(12, 30), t(9, 0), t(18, 0)));
+        assert!(!is_within_window(t(18, 0), t(9, 0), t(18, 0)));
+        assert!(!is_within_window(t(8, 59), t(9, 0), t(18, 0)));
+    }
+
+    #[test]
+    fn test_window_wraps_midnight() {
+        assert!(is_within_window(t(23, 0), t(22, 0), t(6, 0)));
+        assert!(is_within_window(t(2, 0), t(22, 0), t(6, 0)));
+        assert!(!is_within_window(t(6, 0), t(22, 0), t(6, 0)));
+        assert!(!is_within_window(t(12, 0), t(22, 0), t(6, 0)));
+    }
+
+    #[test]
+    fn test_empty_window_and_parsing() {
+        assert!(!is_within_window(t(9, 0), t(9, 0), t(9, 0)));
+        assert_eq!(parse_hhmm(" 07:45 "), Some(t(7, 45)));
+        assert_eq!(parse_hhmm("25:00"), None);
+        assert_eq!(parse_hhmm("abc"), None);
+    }
+
+    #[test]
+    fn test_disabled_quiet_hours() {
+        let cfg = QuietHoursConfig {
+            enabled: false,
+            start: "00:00".to_string(),
+            end: "23:59".to_string(),
+        };
+        assert!(!is_quiet_hours(&cfg));
+    }
+}
@@ -71,7 +71,7 @@ pub fn get_security_db_path() -> Result<PathBuf, String> {
 }
 
 /// 连接数据库
-fn connect_db() -> Result<Connection, String> {
+pub(crate) fn connect_db() -> Result<Connection, String> {
     let db_path = get_security_db_path()?;
     let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
 
@@ -0,0 +1,233 @@
+// 启动自检
+// 应用初始化时检查各子系统 (数据目录可写、配置可解析、反代端口可绑定、Antigravity 可找到、系统钥匙串)，
+// 结果保存在内存中并通过 app://startup-report 推送给前端，让“应用能打开但什么都不工作”的情况可以被定位。
+// 某项失败不会中断启动，报告中 degraded = true。
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::sync::RwLock;
+
+use crate::modules;
+
+pub const STARTUP_REPORT_EVENT: &str = "app://startup-report";
+
+static REPORT: Lazy<RwLock<Option<StartupReport>>> = Lazy::new(|| RwLock::new(None));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    /// 可以运行，但部分功能不可用
+    Warning,
+    Failed,
+    /// 当前平台 / 模式下不适用
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupCheck {
+    pub id: String,
+    pub status: CheckStatus,
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupReport {
+    pub generated_at: i64,
+    /// 存在失败项
+    pub degraded: bool,
+    pub checks: Vec<StartupCheck>,
+}
+
+impl StartupReport {
+    fn new(checks: Vec<StartupCheck>) -> Self {
+        let mut report = Self {
+            generated_at: chrono::Utc::now().timestamp(),
+            degraded: false,
+            checks,
+        };
+        report.refresh_degraded();
+        report
+    }
+
+    fn refresh_degraded(&mut self) {
+        self.degraded = self.checks.iter().any(|c| c.status == CheckStatus::Failed);
+    }
+}
+
+fn check(id: &str, status: CheckStatus, detail: Option<String>) -> StartupCheck {
+    StartupCheck {
+        id: id.to_string(),
+        status,
+        detail,
+    }
+}
+
+fn check_data_dir() -> StartupCheck {
+    let dir = match modules::account::get_data_dir() {
+        Ok(dir) => dir,
+        Err(e) => return check("data_dir", CheckStatus::Failed, Some(e)),
+    };
+    let probe = dir.join(format!(".write-probe-{}", std::process::id()));
+    let result = std::fs::write(&probe, b"ok").and_then(|_| std::fs::remove_file(&probe));
+    match result {
+        Ok(()) => check("data_dir", CheckStatus::Ok, Some(dir.display().to_string())),
+        Err(e) => check(
+            "data_dir",
+            CheckStatus::Failed,
+            Some(format!("data_dir_not_writable: {}: {}", dir.display(), e)),
+        ),
+    }
+}
+
+fn check_proxy_port(config: &crate::models::AppConfig) -> StartupCheck {
+    let address = config.proxy.get_bind_address();
+    let port = config.proxy.port;
+    match std::net::TcpListener::bind((address, port)) {
+        Ok(_) => check(
+            "proxy_port",
+            CheckStatus::Ok,
+            Some(format!("{}:{}", address, port)),
+        ),
+        Err(e) => check(
+            "proxy_port",
+            CheckStatus::Failed,
+            Some(format!("port_unavailable: {}:{}: {}", address, port, e)),
+        ),
+    }
+}
+
+fn check_antigravity(config: &crate::models::AppConfig) -> StartupCheck {
+    if modules::headless::is_headless() {
+        return check("antigravity", CheckStatus::Skipped, None);
+    }
+    let configured = config
+        .antigravity_executable
+        .as_deref()
+        .filter(|p| !p.trim().is_empty());
+    if let Some(path) = configured {
+        return if std::path::Path::new(path).exists() {
+            check("antigravity", CheckStatus::Ok, Some(path.to_string()))
+        } else {
+            check(
+                "antigravity",
+                CheckStatus::Warning,
+                Some(format!("antigravity_executable_not_found: {}", path)),
+            )
+        };
+    }
+    match modules::process::get_antigravity_executable_path() {
+        Some(path) => check(
+            "antigravity",
+            CheckStatus::Ok,
+            Some(path.display().to_string()),
+        ),
+        // 仅影响账号切换，反代照常工作
+        None => check(
+            "antigravity",
+            CheckStatus::Warning,
+            Some(
+                "antigravity_not_detected: account switching needs the Antigravity path"
+                    .to_string(),
+            ),
+        ),
+    }
+}
+
+/// 远程备份凭据保存在钥匙串中；不可用时回退为加密写入配置文件，因此只是警告
+fn check_keychain() -> StartupCheck {
+    if !cfg!(feature = "desktop") {
+        return check("keychain", CheckStatus::Skipped, None);
+    }
+    match modules::keychain::probe() {
+        Ok(()) => check("keychain", CheckStatus::Ok, None),
+        Err(e) => check("keychain", CheckStatus::Warning, Some(e)),
+    }
+}
+
+/// 运行自检并保存结果 (必须在管理服务器绑定端口之前调用)
+pub fn run() -> StartupReport {
+    let mut checks = vec![check_data_dir()];
+    let config = match modules::config::load_app_config() {
+        Ok(config) => {
+            checks.push(check("config", CheckStatus::Ok, None));
+            config
+        }
+        Err(e) => {
+            checks.push(check("config", CheckStatus::Failed, Some(e)));
+            crate::models::AppConfig::new()
+        }
+    };
+    checks.push(check_proxy_port(&config));
+    checks.push(check_antigravity(&config));
+    checks.push(check_keychain());
+
+    for c in checks
+        .iter()
+        .filter(|c| c.status == CheckStatus::Failed || c.status == CheckStatus::Warning)
+    {
+        tracing::warn!(
+            "[StartupReport] {} {:?}: {}",
+            c.id,
+            c.status,
+            c.detail.as_deref().unwrap_or("")
+        );
+    }
+
+    let report = StartupReport::new(checks);
+    *REPORT.write().unwrap_or_else(|e| e.into_inner()) = Some(report.clone());
+    report
+}
+
+/// 后续初始化步骤的结果覆盖对应检查项 (例如管理服务器启动失败)
+pub fn update(id: &str, status: CheckStatus, detail: Option<String>) -> Option<StartupReport> {
+    let mut guard = REPORT.write().unwrap_or_else(|e| e.into_inner());
+    let report = guard.as_mut()?;
+    match report.checks.iter_mut().find(|c| c.id == id) {
+        Some(existing) => {
+            existing.status = status;
+            existing.detail = detail;
+        }
+        None => report.checks.push(check(id, status, detail)),
+    }
+    report.refresh_degraded();
+    Some(report.clone())
+}
+
+pub fn current() -> Option<StartupReport> {
+    REPORT.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// 推送给前端 (前端启动较晚时通过 get_startup_report 命令补取)
+#[cfg(feature = "desktop")]
+pub fn publish(app: &tauri::AppHandle) {
+    use tauri::Emitter;
+    if let Some(report) = current() {
+        let _ = app.emit(STARTUP_REPORT_EVENT, report);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proxy_port_check_detects_bound_port() {
+        let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let mut config = crate::models::AppConfig::new();
+        config.proxy.allow_lan_access = false;
+        config.proxy.port = listener.local_addr().unwrap().port();
+        assert_eq!(check_proxy_port(&config).status, CheckStatus::Failed);
+
+        drop(listener);
+        assert_eq!(check_proxy_port(&config).status, CheckStatus::Ok);
+
+        let mut report = StartupReport::new(vec![check("config", CheckStatus::Ok, None)]);
+        assert!(!report.degraded);
+        report
+            .checks
+            .push(check("proxy_port", CheckStatus::Failed, None));
+        report.refresh_degraded();
+        assert!(report.degraded);
+    }
+}
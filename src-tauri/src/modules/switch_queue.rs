@@ -0,0 +1,148 @@
+// 账号切换串行化
+// 托盘“切换下一个”、前端、HTTP API、调度器都可能同时调用 switch_account，交错执行会导致
+// 进程关闭与数据库写入互相覆盖。这里保证同一时间只有一个切换在执行:
+// - 目标相同的请求合并: 等待正在进行的切换并共享其结果
+// - 目标不同的请求直接拒绝 (switch_in_progress)，由调用方提示稍后重试
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tokio::sync::watch;
+
+use crate::models::SwitchReport;
+
+type SwitchOutcome = Option<Result<SwitchReport, String>>;
+
+struct InFlight {
+    id: u64,
+    account_id: String,
+    started_at: i64,
+    tx: watch::Sender<SwitchOutcome>,
+}
+
+static IN_FLIGHT: Mutex<Option<InFlight>> = Mutex::new(None);
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// 正在进行的切换
+#[derive(Debug, Clone, Serialize)]
+pub struct SwitchStatus {
+    pub account_id: String,
+    pub started_at: i64,
+}
+
+pub fn in_progress() -> Option<SwitchStatus> {
+    IN_FLIGHT
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .map(|f| SwitchStatus {
+            account_id: f.account_id.clone(),
+            started_at: f.started_at,
+        })
+}
+
+/// 是否为“已有切换在进行”被拒绝的错误
+pub fn is_in_progress_error(error: &str) -> bool {
+    error.starts_with("switch_in_progress")
+}
+
+pub enum Ticket {
+    /// 由调用方执行切换，完成后调用 complete
+    Leader(LeaderGuard),
+    /// 已有相同目标的切换在进行，等待其结果即可
+    Follower(watch::Receiver<SwitchOutcome>),
+}
+
+/// 持有执行权；未调用 complete 就被丢弃 (任务取消 / panic) 时释放并通知等待者
+pub struct LeaderGuard {
+    id: u64,
+}
+
+impl LeaderGuard {
+    pub fn complete(self, result: &Result<SwitchReport, String>) {
+        release(self.id, result.clone());
+        std::mem::forget(self);
+    }
+}
+
+impl Drop for LeaderGuard {
+    fn drop(&mut self) {
+        release(self.id, Err("switch_cancelled".to_string()));
+    }
+}
+
+fn release(id: u64, result: Result<SwitchReport, String>) {
+    let mut slot = IN_FLIGHT.lock().unwrap_or_else(|e| e.into_inner());
+    if slot.as_ref().map(|f| f.id) == Some(id) {
+        if let Some(flight) = slot.take() {
+            flight.tx.send_replace(Some(result));
+        }
+    }
+}
+
+/// 申请执行切换
+pub fn begin(account_id: &str) -> Result<Ticket, String> {
+    let mut slot = IN_FLIGHT.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(flight) = slot.as_ref() {
+        if flight.account_id == account_id {
+            return Ok(Ticket::Follower(flight.tx.subscribe()));
+        }
+        return Err(format!(
+            "switch_in_progress: already switching to {}",
+            flight.account_id
+        ));
+    }
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    let (tx, _) = watch::channel(None);
+    *slot = Some(InFlight {
+        id,
+        account_id: account_id.to_string(),
+        started_at: chrono::Utc::now().timestamp(),
+        tx,
+    });
+    Ok(Ticket::Leader(LeaderGuard { id }))
+}
+
+/// 等待正在进行的相同切换完成
+pub async fn wait(mut rx: watch::Receiver<SwitchOutcome>) -> Result<SwitchReport, String> {
+    match rx.wait_for(|outcome| outcome.is_some()).await {
+        Ok(outcome) => outcome
+            .clone()
+            .unwrap_or_else(|| Err("switch_cancelled".to_string())),
+        Err(_) => Err("switch_cancelled".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_same_target_is_merged_and_other_targets_rejected() {
+        let Ok(Ticket::Leader(leader)) = begin("queue-a") else {
+            panic!("first request should run the switch");
+        };
+        let Ok(Ticket::Follower(rx)) = begin("queue-a") else {
+            panic!("same target should wait for the running switch");
+        };
+        let err = begin("queue-b").err().unwrap();
+        assert!(is_in_progress_error(&err));
+        assert_eq!(in_progress().unwrap().account_id, "queue-a");
+
+        let report = SwitchReport::new("queue-a", "a@example.com", None);
+        leader.complete(&Ok(report));
+        assert_eq!(wait(rx).await.unwrap().account_id, "queue-a");
+        assert!(in_progress().is_none());
+
+        // 执行方被丢弃时释放，等待者收到取消
+        let Ok(Ticket::Leader(leader)) = begin("queue-b") else {
+            panic!("slot should be free again");
+        };
+        let Ok(Ticket::Follower(rx)) = begin("queue-b") else {
+            panic!("same target should wait");
+        };
+        drop(leader);
+        assert_eq!(wait(rx).await.unwrap_err(), "switch_cancelled");
+        assert!(in_progress().is_none());
+    }
+}
@@ -0,0 +1,678 @@
+// 账号池双向同步
+// 两个 (或多个) 实例通过备份使用的远程目标 (WebDAV / S3) 共享一份加密的同步文档，
+// 其中包含账号 (不含配额等运行时状态)、标签，以及可选的路由配置 (模型映射 / 降级链 / 等级路由)。
+//
+// 冲突解决: 每个账号独立比较最后修改时间，新者胜出 (last-write-wins)；删除以墓碑记录传播。
+// 本地修改时间由指纹变化推断: 每次同步记录账号内容的指纹，下次同步时指纹变化即视为“此刻修改”。
+// 首次同步 (本机从未同步过) 时本地已有的内容视为最旧，与远程冲突时以远程为准。
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::models::{Account, AppConfig};
+use crate::modules::{account, backup, config, logger, offline, scheduler};
+
+const REMOTE_NAME: &str = "antigravity-sync.agsync";
+const STATE_FILE: &str = "sync_state.json";
+const DOCUMENT_VERSION: u32 = 1;
+const CHECK_INTERVAL_SECS: u64 = 60;
+const JOB_ID: &str = "sync";
+
+static SCHEDULER_STARTED: AtomicBool = AtomicBool::new(false);
+static SYNC_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// 同步文档中的一条记录；value 为 None 表示已删除
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SyncEntry<T> {
+    changed_at: i64,
+    hash: String,
+    value: Option<T>,
+}
+
+/// 参与同步的路由配置 (使用有序 Map，保证指纹稳定)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SyncedRouting {
+    custom_mapping: BTreeMap<String, String>,
+    fallback_chains: BTreeMap<String, Vec<String>>,
+    premium_models: Vec<String>,
+    model_tiers: BTreeMap<String, Vec<String>>,
+}
+
+impl SyncedRouting {
+    fn from_config(cfg: &AppConfig) -> Self {
+        Self {
+            custom_mapping: cfg.proxy.custom_mapping.clone().into_iter().collect(),
+            fallback_chains: cfg.proxy.fallback_chains.clone().into_iter().collect(),
+            premium_models: cfg.proxy.tier_routing.premium_models.clone(),
+            model_tiers: cfg
+                .proxy
+                .tier_routing
+                .model_tiers
+                .clone()
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    fn apply_to(&self, cfg: &mut AppConfig) {
+        cfg.proxy.custom_mapping = self.custom_mapping.clone().into_iter().collect();
+        cfg.proxy.fallback_chains = self.fallback_chains.clone().into_iter().collect();
+        cfg.proxy.tier_routing.premium_models = self.premium_models.clone();
+        cfg.proxy.tier_routing.model_tiers = self.model_tiers.clone().into_iter().collect();
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SyncDocument {
+    version: u32,
+    updated_at: i64,
+    updated_by: String,
+    #[serde(default)]
+    accounts: BTreeMap<String, SyncEntry<Account>>,
+    #[serde(default)]
+    routing: Option<SyncEntry<SyncedRouting>>,
+}
+
+/// 上次同步时记录的指纹 (hash 为空表示已删除)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Fingerprint {
+    hash: String,
+    changed_at: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct LocalState {
+    device_id: String,
+    #[serde(default)]
+    accounts: HashMap<String, Fingerprint>,
+    #[serde(default)]
+    routing: Option<Fingerprint>,
+    #[serde(default)]
+    status: SyncStatus,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncReport {
+    pub synced_at: i64,
+    /// 从远程拉取 (新增或更新) 的账号邮箱
+    pub pulled: Vec<String>,
+    /// 推送到远程的账号邮箱
+    pub pushed: Vec<String>,
+    /// 按远程墓碑在本地删除的账号 ID
+    pub deleted_locally: Vec<String>,
+    /// 本地删除并传播到远程的账号 ID
+    pub deleted_remotely: Vec<String>,
+    /// 未能应用的远程账号 (如本地已有同邮箱的其他账号)
+    pub conflicts: Vec<String>,
+    /// "pulled" / "pushed"；路由配置未变化时为 None
+    pub routing: Option<String>,
+    /// 本次是否写回了远程同步文档
+    pub uploaded: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncStatus {
+    #[serde(default)]
+    pub device_id: String,
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub last_sync_at: Option<i64>,
+    #[serde(default)]
+    pub last_attempt_at: Option<i64>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+    #[serde(default)]
+    pub last_report: Option<SyncReport>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Winner {
+    Same,
+    Local,
+    Remote,
+}
+
+fn fingerprint<T: Serialize>(value: &T) -> String {
+    let bytes = serde_json::to_vec(value).unwrap_or_default();
+    Sha256::digest(&bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// 参与同步的账号内容: 去掉配额、最近使用时间与验证阻止等本机运行时状态
+fn sync_view(account: &Account) -> Account {
+    let mut view = account.clone();
+    view.quota = None;
+    view.last_used = 0;
+    view.protected_models.clear();
+    view.validation_blocked = false;
+    view.validation_blocked_until = None;
+    view.validation_blocked_reason = None;
+    view.validation_url = None;
+    view
+}
+
+/// 把远程账号与本地运行时状态合并为要写入的账号
+fn merge_runtime(remote: &Account, local: Option<&Account>) -> Account {
+    let mut merged = remote.clone();
+    if let Some(local) = local {
+        merged.quota = local.quota.clone();
+        merged.last_used = local.last_used;
+        merged.protected_models = local.protected_models.clone();
+        merged.validation_blocked = local.validation_blocked;
+        merged.validation_blocked_until = local.validation_blocked_until;
+        merged.validation_blocked_reason = local.validation_blocked_reason.clone();
+        merged.validation_url = local.validation_url.clone();
+    }
+    merged
+}
+
+/// 由上次指纹推断的本地修改时间
+fn local_changed_at(previous: Option<&Fingerprint>, hash: &str, first_sync: bool, now: i64) -> i64 {
+    match previous {
+        Some(fp) if fp.hash == hash => fp.changed_at,
+        _ if first_sync => 0,
+        _ => now,
+    }
+}
+
+fn local_entries(
+    accounts: &[Account],
+    state: &LocalState,
+    now: i64,
+) -> BTreeMap<String, SyncEntry<Account>> {
+    let first_sync = state.status.last_sync_at.is_none();
+    let mut entries = BTreeMap::new();
+    for acc in accounts {
+        let view = sync_view(acc);
+        let hash = fingerprint(&view);
+        entries.insert(
+            acc.id.clone(),
+            SyncEntry {
+                changed_at: local_changed_at(state.accounts.get(&acc.id), &hash, first_sync, now),
+                hash,
+                value: Some(view),
+            },
+        );
+    }
+    // 上次同步后在本地删除的账号生成墓碑
+    for (id, fp) in &state.accounts {
+        if entries.contains_key(id) {
+            continue;
+        }
+        entries.insert(
+            id.clone(),
+            SyncEntry {
+                changed_at: if fp.hash.is_empty() {
+                    fp.changed_at
+                } else {
+                    now
+                },
+                hash: String::new(),
+                value: None,
+            },
+        );
+    }
+    entries
+}
+
+fn pick<T>(local: Option<&SyncEntry<T>>, remote: Option<&SyncEntry<T>>) -> Winner {
+    match (local, remote) {
+        (Some(l), Some(r)) if l.hash == r.hash => Winner::Same,
+        (Some(l), Some(r)) => {
+            // 时间相同时按指纹排序，保证各实例得出一致的结果
+            if (l.changed_at, &l.hash) > (r.changed_at, &r.hash) {
+                Winner::Local
+            } else {
+                Winner::Remote
+            }
+        }
+        (Some(_), None) => Winner::Local,
+        (None, Some(_)) => Winner::Remote,
+        (None, None) => Winner::Same,
+    }
+}
+
+/// 合并后的账号记录，以及需要在本地应用的远程记录
+struct AccountMerge {
+    merged: BTreeMap<String, SyncEntry<Account>>,
+    apply_locally: Vec<String>,
+    push_remote: Vec<String>,
+}
+
+fn merge_accounts(
+    local: &BTreeMap<String, SyncEntry<Account>>,
+    remote: &BTreeMap<String, SyncEntry<Account>>,
+) -> AccountMerge {
+    let mut result = AccountMerge {
+        merged: BTreeMap::new(),
+        apply_locally: Vec::new(),
+        push_remote: Vec::new(),
+    };
+    let ids: std::collections::BTreeSet<&String> = local.keys().chain(remote.keys()).collect();
+    for id in ids {
+        let (l, r) = (local.get(id), remote.get(id));
+        let winner = match pick(l, r) {
+            Winner::Same => l.or(r),
+            Winner::Local => {
+                result.push_remote.push(id.clone());
+                l
+            }
+            Winner::Remote => {
+                result.apply_locally.push(id.clone());
+                r
+            }
+        };
+        if let Some(entry) = winner {
+            result.merged.insert(id.clone(), entry.clone());
+        }
+    }
+    result
+}
+
+fn state_path() -> Result<std::path::PathBuf, String> {
+    Ok(account::get_data_dir()?.join(STATE_FILE))
+}
+
+fn load_state() -> LocalState {
+    let mut state: LocalState = state_path()
+        .ok()
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    if state.device_id.is_empty() {
+        state.device_id = uuid::Uuid::new_v4().to_string();
+    }
+    state
+}
+
+fn save_state(state: &LocalState) {
+    let Ok(path) = state_path() else {
+        return;
+    };
+    match serde_json::to_string_pretty(state) {
+        Ok(content) => {
+            if let Err(e) = std::fs::write(&path, content) {
+                logger::log_warn(&format!("[Sync] Failed to save sync state: {}", e));
+            }
+        }
+        Err(e) => logger::log_warn(&format!("[Sync] Failed to serialize sync state: {}", e)),
+    }
+}
+
+pub fn load_status() -> SyncStatus {
+    let state = load_state();
+    let mut status = state.status;
+    status.device_id = state.device_id;
+    status.enabled = config::load_app_config()
+        .map(|c| c.sync.enabled)
+        .unwrap_or(false);
+    status
+}
+
+async fn download_document(cfg: &AppConfig) -> Result<SyncDocument, String> {
+    let encrypted = match backup::remote_download(&cfg.backup.target, REMOTE_NAME).await {
+        Ok(data) => data,
+        // 远程还没有同步文档 (第一台实例首次同步)
+        Err(e) if backup::is_not_found(&e) => return Ok(SyncDocument::default()),
+        Err(e) => return Err(e),
+    };
+    let plain = backup::decrypt(&cfg.backup.passphrase, &encrypted)?;
+    let doc: SyncDocument =
+        serde_json::from_slice(&plain).map_err(|e| format!("sync_document_invalid: {}", e))?;
+    if doc.version > DOCUMENT_VERSION {
+        return Err(format!(
+            "sync_document_unsupported: version {} (this build supports {})",
+            doc.version, DOCUMENT_VERSION
+        ));
+    }
+    Ok(doc)
+}
+
+/// 执行一次双向同步；拉取到的路由配置写入配置文件并热更新降级链 / 等级路由，
+/// 模型映射由调用方 (持有反代实例) 热更新
+pub async fn run_sync() -> Result<SyncReport, String> {
+    if SYNC_RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("sync_already_running".to_string());
+    }
+    let mut state = load_state();
+    let result = run_sync_inner(&mut state).await;
+    SYNC_RUNNING.store(false, Ordering::SeqCst);
+
+    let now = chrono::Utc::now().timestamp();
+    state.status.last_attempt_at = Some(now);
+    match &result {
+        Ok(report) => {
+            state.status.last_sync_at = Some(now);
+            state.status.last_error = None;
+            state.status.last_report = Some(report.clone());
+        }
+        Err(e) => state.status.last_error = Some(e.clone()),
+    }
+    save_state(&state);
+    result
+}
+
+async fn run_sync_inner(state: &mut LocalState) -> Result<SyncReport, String> {
+    let cfg = config::load_app_config()?;
+    if matches!(cfg.backup.target, crate::models::RemoteBackupTarget::None) {
+        return Err("backup_target_not_configured".to_string());
+    }
+    if cfg.backup.passphrase.is_empty() {
+        return Err("backup_passphrase_required".to_string());
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    let remote = download_document(&cfg).await?;
+    let local_accounts = account::list_accounts()?;
+    let local = local_entries(&local_accounts, state, now);
+    let merge = merge_accounts(&local, &remote.accounts);
+
+    let mut report = SyncReport {
+        synced_at: now,
+        ..Default::default()
+    };
+    let by_id: HashMap<&str, &Account> =
+        local_accounts.iter().map(|a| (a.id.as_str(), a)).collect();
+    let merged = merge.merged;
+    let mut conflicted = std::collections::HashSet::new();
+
+    for id in &merge.apply_locally {
+        let Some(entry) = merged.get(id) else {
+            continue;
+        };
+        match &entry.value {
+            Some(remote_acc) => {
+                let existing = by_id.get(id.as_str()).copied();
+                if existing.is_none()
+                    && local_accounts
+                        .iter()
+                        .any(|a| a.email == remote_acc.email && a.id != *id)
+                {
+                    // 两台实例各自添加了同一邮箱: 保留本地账号，远程记录原样保留
+                    report.conflicts.push(remote_acc.email.clone());
+                    conflicted.insert(id.clone());
+                    continue;
+                }
+                account::apply_synced_account(&merge_runtime(remote_acc, existing))?;
+                report.pulled.push(remote_acc.email.clone());
+            }
+            None => {
+                if by_id.contains_key(id.as_str()) {
+                    // Deleted on another instance, where any domain policy confirmation already happened
+                    account::delete_account(id, true)?;
+                    report.deleted_locally.push(id.clone());
+                }
+            }
+        }
+    }
+    for id in &merge.push_remote {
+        match merged.get(id).and_then(|e| e.value.as_ref()) {
+            Some(acc) => report.pushed.push(acc.email.clone()),
+            None => report.deleted_remotely.push(id.clone()),
+        }
+    }
+
+    // 路由配置
+    let mut routing_entry = remote.routing.clone();
+    let mut routing_changed_remote = false;
+    if cfg.sync.include_routing {
+        let local_routing = SyncedRouting::from_config(&cfg);
+        let hash = fingerprint(&local_routing);
+        let local_entry = SyncEntry {
+            changed_at: local_changed_at(
+                state.routing.as_ref(),
+                &hash,
+                state.status.last_sync_at.is_none(),
+                now,
+            ),
+            hash,
+            value: Some(local_routing),
+        };
+        match pick(Some(&local_entry), remote.routing.as_ref()) {
+            Winner::Same => {}
+            Winner::Local => {
+                routing_entry = Some(local_entry);
+                routing_changed_remote = true;
+                report.routing = Some("pushed".to_string());
+            }
+            Winner::Remote => {
+                if let Some(value) = remote.routing.as_ref().and_then(|e| e.value.as_ref()) {
+                    let mut new_cfg = config::load_app_config()?;
+                    value.apply_to(&mut new_cfg);
+                    config::save_app_config(&new_cfg)?;
+                    crate::proxy::update_fallback_chains(new_cfg.proxy.fallback_chains.clone());
+                    crate::proxy::update_tier_routing(new_cfg.proxy.tier_routing.clone());
+                    report.routing = Some("pulled".to_string());
+                }
+            }
+        }
+        state.routing = routing_entry.as_ref().map(|e| Fingerprint {
+            hash: e.hash.clone(),
+            changed_at: e.changed_at,
+        });
+    }
+
+    // 远程缺少或落后于合并结果时写回
+    let remote_stale = routing_changed_remote
+        || merged.len() != remote.accounts.len()
+        || merged
+            .iter()
+            .any(|(id, e)| remote.accounts.get(id).map(|r| &r.hash) != Some(&e.hash));
+    if remote_stale {
+        let doc = SyncDocument {
+            version: DOCUMENT_VERSION,
+            updated_at: now,
+            updated_by: state.device_id.clone(),
+            accounts: merged.clone(),
+            routing: routing_entry,
+        };
+        let plain =
+            serde_json::to_vec(&doc).map_err(|e| format!("sync_document_invalid: {}", e))?;
+        let encrypted = backup::encrypt(&cfg.backup.passphrase, &plain)?;
+        backup::remote_upload(&cfg.backup.target, REMOTE_NAME, encrypted).await?;
+        report.uploaded = true;
+    }
+
+    // 冲突的记录不计入本地指纹，否则下次同步会被当作本地删除
+    state.accounts = merged
+        .iter()
+        .filter(|(id, _)| !conflicted.contains(*id))
+        .map(|(id, e)| {
+            (
+                id.clone(),
+                Fingerprint {
+                    hash: e.hash.clone(),
+                    changed_at: e.changed_at,
+                },
+            )
+        })
+        .collect();
+
+    logger::log_info(&format!(
+        "[Sync] pulled {}, pushed {}, deleted locally {}, deleted remotely {}, conflicts {}",
+        report.pulled.len(),
+        report.pushed.len(),
+        report.deleted_locally.len(),
+        report.deleted_remotely.len(),
+        report.conflicts.len()
+    ));
+    Ok(report)
+}
+
+/// 同步拉取到路由配置后，热更新正在运行的反代实例的模型映射
+pub async fn apply_pulled_mapping(proxy_state: &crate::commands::proxy::ProxyServiceState) {
+    let Ok(cfg) = config::load_app_config() else {
+        return;
+    };
+    let instance_lock = proxy_state.instance.read().await;
+    if let Some(instance) = instance_lock.as_ref() {
+        instance.axum_server.update_mapping(&cfg.proxy).await;
+    }
+}
+
+/// 启动定时同步任务 (每个进程一次)；每次检查时读取最新配置
+pub fn start_sync_scheduler(proxy_state: crate::commands::proxy::ProxyServiceState) {
+    if SCHEDULER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    scheduler::register_job(JOB_ID, CHECK_INTERVAL_SECS);
+    crate::utils::runtime::spawn(async move {
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_secs(CHECK_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            if !scheduler::job_tick(JOB_ID) {
+                continue;
+            }
+
+            let Ok(app_config) = config::load_app_config() else {
+                continue;
+            };
+            if !app_config.sync.enabled || app_config.sync.interval_minutes == 0 {
+                continue;
+            }
+            if scheduler::is_quiet_hours(&app_config.quiet_hours) || offline::is_offline() {
+                continue;
+            }
+            let last = load_state().status.last_attempt_at.unwrap_or(0);
+            let due = i64::from(app_config.sync.interval_minutes) * 60;
+            if chrono::Utc::now().timestamp() - last < due {
+                continue;
+            }
+            let started = std::time::Instant::now();
+            let result = run_sync().await;
+            match &result {
+                Ok(report) if report.routing.as_deref() == Some("pulled") => {
+                    apply_pulled_mapping(&proxy_state).await;
+                }
+                Ok(_) => {}
+                Err(e) => logger::log_warn(&format!("[Sync] Scheduled sync failed: {}", e)),
+            }
+            scheduler::record_job_run(
+                JOB_ID,
+                started,
+                result.map(|r| format!("{} pulled, {} pushed", r.pulled.len(), r.pushed.len())),
+            );
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TokenData;
+
+    fn acc(id: &str, email: &str) -> Account {
+        Account::new(
+            id.to_string(),
+            email.to_string(),
+            TokenData::new(
+                "at".into(),
+                "rt".into(),
+                3600,
+                Some(email.into()),
+                None,
+                None,
+            ),
+        )
+    }
+
+    fn entry(acc: &Account, changed_at: i64) -> SyncEntry<Account> {
+        let view = sync_view(acc);
+        SyncEntry {
+            changed_at,
+            hash: fingerprint(&view),
+            value: Some(view),
+        }
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_runtime_state() {
+        let a = acc("a", "a@example.com");
+        let mut b = a.clone();
+        b.last_used += 100;
+        b.validation_blocked = true;
+        b.protected_models.insert("claude".to_string());
+        assert_eq!(fingerprint(&sync_view(&a)), fingerprint(&sync_view(&b)));
+
+        b.tags.push("vip".to_string());
+        assert_ne!(fingerprint(&sync_view(&a)), fingerprint(&sync_view(&b)));
+    }
+
+    #[test]
+    fn test_last_write_wins_per_account() {
+        let a = acc("a", "a@example.com");
+        let mut a_tagged = a.clone();
+        a_tagged.tags.push("team".to_string());
+        let b = acc("b", "b@example.com");
+        let mut b_renamed = b.clone();
+        b_renamed.name = Some("Bee".to_string());
+        let c = acc("c", "c@example.com");
+
+        let local = BTreeMap::from([
+            ("a".to_string(), entry(&a_tagged, 200)),
+            ("b".to_string(), entry(&b, 100)),
+            ("c".to_string(), entry(&c, 50)),
+        ]);
+        let remote = BTreeMap::from([
+            ("a".to_string(), entry(&a, 150)),
+            ("b".to_string(), entry(&b_renamed, 180)),
+            ("d".to_string(), entry(&acc("d", "d@example.com"), 10)),
+        ]);
+        let merge = merge_accounts(&local, &remote);
+        assert_eq!(merge.push_remote, vec!["a", "c"]);
+        assert_eq!(merge.apply_locally, vec!["b", "d"]);
+        let merged_b = merge.merged["b"].value.as_ref().unwrap();
+        assert_eq!(merged_b.name.as_deref(), Some("Bee"));
+        assert_eq!(merge.merged["a"].value.as_ref().unwrap().tags, vec!["team"]);
+    }
+
+    #[test]
+    fn test_tombstones_and_first_sync() {
+        let a = acc("a", "a@example.com");
+        let hash = fingerprint(&sync_view(&a));
+
+        // 上次同步后本地删除 → 生成墓碑并胜过远程旧记录
+        let mut state = LocalState::default();
+        state.status.last_sync_at = Some(100);
+        state.accounts.insert(
+            "a".to_string(),
+            Fingerprint {
+                hash: hash.clone(),
+                changed_at: 100,
+            },
+        );
+        let local = local_entries(&[], &state, 500);
+        assert!(local["a"].value.is_none());
+        let remote = BTreeMap::from([("a".to_string(), entry(&a, 100))]);
+        let merge = merge_accounts(&local, &remote);
+        assert_eq!(merge.push_remote, vec!["a"]);
+        assert!(merge.merged["a"].value.is_none());
+
+        // 首次同步: 本地已有的内容视为最旧，冲突时以远程为准
+        let mut renamed = a.clone();
+        renamed.name = Some("Local".to_string());
+        let local = local_entries(&[renamed], &LocalState::default(), 500);
+        assert_eq!(local["a"].changed_at, 0);
+        let merge = merge_accounts(&local, &remote);
+        assert_eq!(merge.apply_locally, vec!["a"]);
+    }
+
+    #[test]
+    fn test_merge_runtime_keeps_local_quota_state() {
+        let mut local = acc("a", "a@example.com");
+        local.last_used = 42;
+        local.protected_models.insert("gemini".to_string());
+        let mut remote = sync_view(&local);
+        remote.tags.push("synced".to_string());
+        let merged = merge_runtime(&remote, Some(&local));
+        assert_eq!(merged.last_used, 42);
+        assert!(merged.protected_models.contains("gemini"));
+        assert_eq!(merged.tags, vec!["synced"]);
+    }
+}
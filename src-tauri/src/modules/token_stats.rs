@@ -55,12 +55,38 @@ pub struct AccountTrendPoint {
     pub account_data: std::collections::HashMap<String, u64>,
 }
 
+/// Quota percentage of one model at a point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuotaSnapshotPoint {
+    pub timestamp: i64,
+    pub model: String,
+    pub percentage: i32,
+}
+
+/// Per-account upstream latency / bandwidth percentiles (successful requests only)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountLatencyStats {
+    pub account_email: String,
+    pub request_count: u64,
+    pub error_count: u64,
+    pub ttfb_p50_ms: u64,
+    pub ttfb_p90_ms: u64,
+    pub ttfb_p99_ms: u64,
+    pub duration_p50_ms: u64,
+    pub duration_p90_ms: u64,
+    pub duration_p99_ms: u64,
+    pub total_bytes: u64,
+    pub avg_bytes: u64,
+    /// Median transfer rate after the first byte (bytes/s)
+    pub throughput_p50_bps: u64,
+}
+
 pub(crate) fn get_db_path() -> Result<PathBuf, String> {
     let data_dir = crate::modules::account::get_data_dir()?;
     Ok(data_dir.join("token_stats.db"))
 }
 
-fn connect_db() -> Result<Connection, String> {
+pub(crate) fn connect_db() -> Result<Connection, String> {
     let db_path = get_db_path()?;
     let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
 
@@ -122,6 +148,45 @@ pub fn init_db() -> Result<(), String> {
     )
     .map_err(|e| e.to_string())?;
 
+    // Quota history (one row per model per quota refresh)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS quota_snapshots (
+            timestamp INTEGER NOT NULL,
+            account_id TEXT NOT NULL,
+            model TEXT NOT NULL,
+            percentage INTEGER NOT NULL
+        )",
+        [],
+    )
+    .map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_quota_snapshot_account ON quota_snapshots (account_id, timestamp DESC)",
+        [],
+    )
+    .map_err(|e| e.to_string())?;
+
+    // Upstream latency / response size per request
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS request_latency (
+            timestamp INTEGER NOT NULL,
+            account_email TEXT NOT NULL,
+            model TEXT NOT NULL,
+            status INTEGER NOT NULL,
+            ttfb_ms INTEGER NOT NULL,
+            duration_ms INTEGER NOT NULL,
+            response_bytes INTEGER NOT NULL DEFAULT 0
+        )",
+        [],
+    )
+    .map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_request_latency_time ON request_latency (timestamp DESC)",
+        [],
+    )
+    .map_err(|e| e.to_string())?;
+
     Ok(())
 }
 
@@ -553,6 +618,214 @@ pub fn get_account_trend_daily(days: i64) -> Result<Vec<AccountTrendPoint>, Stri
         .collect())
 }
 
+/// Record the quota of every model after a refresh
+pub fn record_quota_snapshot(
+    account_id: &str,
+    quota: &crate::models::QuotaData,
+) -> Result<(), String> {
+    if quota.models.is_empty() {
+        return Ok(());
+    }
+    let mut conn = connect_db()?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    {
+        let mut stmt = tx
+            .prepare(
+                "INSERT INTO quota_snapshots (timestamp, account_id, model, percentage)
+                 VALUES (?1, ?2, ?3, ?4)",
+            )
+            .map_err(|e| e.to_string())?;
+        for model in &quota.models {
+            stmt.execute(params![
+                quota.last_updated,
+                account_id,
+                model.name,
+                model.percentage
+            ])
+            .map_err(|e| e.to_string())?;
+        }
+    }
+    tx.commit().map_err(|e| e.to_string())
+}
+
+/// Quota history of an account over the last N hours (oldest first)
+pub fn get_quota_history(account_id: &str, hours: i64) -> Result<Vec<QuotaSnapshotPoint>, String> {
+    let conn = connect_db()?;
+    let cutoff = chrono::Utc::now().timestamp() - (hours * 3600);
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT timestamp, model, percentage FROM quota_snapshots
+             WHERE account_id = ?1 AND timestamp >= ?2
+             ORDER BY timestamp ASC, model ASC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let points = stmt
+        .query_map(params![account_id, cutoff], |row| {
+            Ok(QuotaSnapshotPoint {
+                timestamp: row.get(0)?,
+                model: row.get(1)?,
+                percentage: row.get(2)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(points)
+}
+
+/// Record upstream time-to-first-byte, total duration and response size of a request
+pub fn record_latency(
+    account_email: &str,
+    model: &str,
+    status: u16,
+    ttfb_ms: u64,
+    duration_ms: u64,
+    response_bytes: u64,
+) -> Result<(), String> {
+    let conn = connect_db()?;
+    conn.execute(
+        "INSERT INTO request_latency (timestamp, account_email, model, status, ttfb_ms, duration_ms, response_bytes)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            chrono::Utc::now().timestamp(),
+            account_email,
+            model,
+            status,
+            ttfb_ms as i64,
+            duration_ms as i64,
+            response_bytes as i64
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Nearest-rank percentile of a sorted slice
+fn percentile(sorted: &[u64], p: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[derive(Default)]
+struct LatencySamples {
+    errors: u64,
+    ttfb: Vec<u64>,
+    duration: Vec<u64>,
+    bytes: Vec<u64>,
+    throughput: Vec<u64>,
+}
+
+impl LatencySamples {
+    fn push(&mut self, status: u16, ttfb_ms: u64, duration_ms: u64, bytes: u64) {
+        if status >= 400 {
+            self.errors += 1;
+            return;
+        }
+        self.ttfb.push(ttfb_ms);
+        self.duration.push(duration_ms);
+        self.bytes.push(bytes);
+        let transfer_ms = duration_ms.saturating_sub(ttfb_ms);
+        if transfer_ms > 0 && bytes > 0 {
+            self.throughput.push(bytes * 1000 / transfer_ms);
+        }
+    }
+
+    fn finish(mut self, account_email: String) -> AccountLatencyStats {
+        self.ttfb.sort_unstable();
+        self.duration.sort_unstable();
+        self.throughput.sort_unstable();
+        let count = self.ttfb.len() as u64;
+        let total_bytes: u64 = self.bytes.iter().sum();
+        AccountLatencyStats {
+            account_email,
+            request_count: count,
+            error_count: self.errors,
+            ttfb_p50_ms: percentile(&self.ttfb, 50.0),
+            ttfb_p90_ms: percentile(&self.ttfb, 90.0),
+            ttfb_p99_ms: percentile(&self.ttfb, 99.0),
+            duration_p50_ms: percentile(&self.duration, 50.0),
+            duration_p90_ms: percentile(&self.duration, 90.0),
+            duration_p99_ms: percentile(&self.duration, 99.0),
+            total_bytes,
+            avg_bytes: total_bytes.checked_div(count).unwrap_or(0),
+            throughput_p50_bps: percentile(&self.throughput, 50.0),
+        }
+    }
+}
+
+/// Latency / bandwidth percentiles per account over the last N hours (slowest TTFB first)
+pub fn get_account_latency_stats(hours: i64) -> Result<Vec<AccountLatencyStats>, String> {
+    let conn = connect_db()?;
+    let cutoff = chrono::Utc::now().timestamp() - (hours * 3600);
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT account_email, status, ttfb_ms, duration_ms, response_bytes
+             FROM request_latency WHERE timestamp >= ?1",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([cutoff], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, u16>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, i64>(3)?,
+                row.get::<_, i64>(4)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut samples: std::collections::BTreeMap<String, LatencySamples> = Default::default();
+    for row in rows {
+        let (account, status, ttfb, duration, bytes) = row.map_err(|e| e.to_string())?;
+        samples.entry(account).or_default().push(
+            status,
+            ttfb.max(0) as u64,
+            duration.max(0) as u64,
+            bytes.max(0) as u64,
+        );
+    }
+
+    let mut result: Vec<AccountLatencyStats> = samples
+        .into_iter()
+        .map(|(account, s)| s.finish(account))
+        .collect();
+    result.sort_by_key(|s| std::cmp::Reverse(s.ttfb_p50_ms));
+    Ok(result)
+}
+
+/// Number of upstream responses with the given status per account over the last N hours
+pub fn count_status_by_account(
+    status: u16,
+    hours: i64,
+) -> Result<std::collections::HashMap<String, u64>, String> {
+    let conn = connect_db()?;
+    let cutoff = chrono::Utc::now().timestamp() - (hours * 3600);
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT account_email, COUNT(*) FROM request_latency
+             WHERE timestamp >= ?1 AND status = ?2
+             GROUP BY account_email",
+        )
+        .map_err(|e| e.to_string())?;
+    let counts = stmt
+        .query_map(params![cutoff, status], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?.max(0) as u64))
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(counts)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -563,4 +836,25 @@ mod tests {
         // For now, just verify the module compiles
         assert!(true);
     }
+
+    #[test]
+    fn test_latency_percentiles() {
+        let sorted: Vec<u64> = (1..=100).collect();
+        assert_eq!(percentile(&sorted, 50.0), 50);
+        assert_eq!(percentile(&sorted, 99.0), 99);
+        assert_eq!(percentile(&[7], 90.0), 7);
+        assert_eq!(percentile(&[], 50.0), 0);
+
+        let mut samples = LatencySamples::default();
+        samples.push(200, 100, 1100, 10_000);
+        samples.push(200, 300, 1300, 20_000);
+        samples.push(429, 20, 20, 100);
+        let stats = samples.finish("a@example.com".to_string());
+        assert_eq!(stats.request_count, 2);
+        assert_eq!(stats.error_count, 1);
+        assert_eq!(stats.ttfb_p50_ms, 100);
+        assert_eq!(stats.ttfb_p99_ms, 300);
+        assert_eq!(stats.avg_bytes, 15_000);
+        assert_eq!(stats.throughput_p50_bps, 10_000);
+    }
 }
@@ -1,8 +1,8 @@
 use tauri::{
     image::Image,
-    menu::{Menu, MenuItem, PredefinedMenuItem},
+    menu::{Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::{MouseButton, TrayIconBuilder, TrayIconEvent},
-    Manager, Emitter, Listener,
+    Manager, Emitter,
 };
 use crate::modules;
 
@@ -100,14 +100,34 @@ pub fn create_tray(app: &tauri::AppHandle) -> tauri::Result<()> {
                                          update_tray_menus(&app_handle);
                                      },
                                      Err(e) => {
-                                         // Error handling, log only
+                                         // 日志保留原始错误，托盘“最近事件”显示本地化提示
                                           modules::logger::log_error(&format!("Tray refresh failed: {}", e));
+                                          record_action_failure(crate::error::CommandError::from(e));
+                                          update_tray_menus(&app_handle);
                                      }
                                  }
                              }
                         }
                     });
                 }
+                "switch_back" => {
+                    tauri::async_runtime::spawn(async move {
+                        let integration = crate::modules::integration::DesktopIntegration {
+                            app_handle: app_handle.clone(),
+                        };
+                        match modules::account::switch_back(&integration).await {
+                            Ok(account_id) => {
+                                let _ = app_handle.emit("tray://account-switched", account_id);
+                                update_tray_menus(&app_handle);
+                            }
+                            Err(e) => {
+                                modules::logger::log_error(&format!("Tray switch back failed: {}", e));
+                                record_action_failure(crate::error::CommandError::from(e));
+                                update_tray_menus(&app_handle);
+                            }
+                        }
+                    });
+                }
                 "switch_next" => {
                     tauri::async_runtime::spawn(async move {
                          // 1. Get all accounts
@@ -127,11 +147,18 @@ pub fn create_tray(app: &tauri::AppHandle) -> tauri::Result<()> {
                              let integration = crate::modules::integration::DesktopIntegration {
                                  app_handle: app_handle.clone(),
                              };
-                             if let Ok(_) = modules::switch_account(&next_account.id, &integration).await {
-                                 // 3. Notify frontend
-                                 let _ = app_handle.emit("tray://account-switched", next_account.id.clone());
-                                 // 4. Update tray
-                                 update_tray_menus(&app_handle);
+                             match modules::switch_account(&next_account.id, &integration).await {
+                                 Ok(_) => {
+                                     // 3. Notify frontend
+                                     let _ = app_handle.emit("tray://account-switched", next_account.id.clone());
+                                     // 4. Update tray
+                                     update_tray_menus(&app_handle);
+                                 }
+                                 Err(e) => {
+                                     modules::logger::log_error(&format!("Tray switch failed: {}", e));
+                                     record_action_failure(crate::error::CommandError::from(e));
+                                     update_tray_menus(&app_handle);
+                                 }
                              }
                          }
                     });
@@ -162,108 +189,400 @@ pub fn create_tray(app: &tauri::AppHandle) -> tauri::Result<()> {
         update_tray_menus(&handle);
     });
 
-    // Listen for config update events
+    // Keep the tooltip's request count current between menu rebuilds
     let handle = app.clone();
-    app.listen("config://updated", move |_event| {
-        modules::logger::log_info("Configuration updated, refreshing tray menu");
-        update_tray_menus(&handle);
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(30));
+        loop {
+            interval.tick().await;
+            refresh_tray_tooltip(&handle).await;
+        }
+    });
+
+    // Rebuild the menu when the config or the account list changes
+    let handle = app.clone();
+    let mut events = modules::events::subscribe();
+    tauri::async_runtime::spawn(async move {
+        use modules::events::AppEvent;
+        use tokio::sync::broadcast::error::RecvError;
+        loop {
+            match events.recv().await {
+                Ok(envelope) => match envelope.event {
+                    AppEvent::ConfigUpdated => {
+                        modules::logger::log_info("Configuration updated, refreshing tray menu");
+                        update_tray_menus(&handle);
+                    }
+                    AppEvent::AccountsRefreshed => update_tray_menus(&handle),
+                    _ => {}
+                },
+                // Missed events: refresh once to be safe
+                Err(RecvError::Lagged(_)) => update_tray_menus(&handle),
+                Err(RecvError::Closed) => break,
+            }
+        }
     });
 
     Ok(())
 }
 
+/// Rendered tray menu texts; the menu is only rebuilt when these change
+#[derive(Debug, Clone, PartialEq)]
+struct TrayMenuState {
+    user_text: String,
+    quota_lines: Vec<String>,
+    recent_events_title: String,
+    /// (menu id, text)
+    events: Vec<(String, String)>,
+    switch_next: String,
+    switch_back: Option<String>,
+    refresh_current: String,
+    show_window: String,
+    quit: String,
+}
+
+/// Calls arriving within this window are coalesced into one rebuild
+const TRAY_REFRESH_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(250);
+
+static TRAY_REFRESH_SCHEDULED: std::sync::atomic::AtomicBool =
+    std::sync::atomic::AtomicBool::new(false);
+static LAST_MENU_STATE: std::sync::Mutex<Option<TrayMenuState>> = std::sync::Mutex::new(None);
+static LAST_TOOLTIP: std::sync::Mutex<Option<String>> = std::sync::Mutex::new(None);
+
 /// Helper function to update tray menu
+/// Debounced: bursts of calls (switch + quota refresh + config event) produce a single rebuild,
+/// and the menu is left alone when the computed texts did not change (avoids flicker)
 pub fn update_tray_menus(app: &tauri::AppHandle) {
+    use std::sync::atomic::Ordering;
+    if TRAY_REFRESH_SCHEDULED
+        .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+        .is_err()
+    {
+        return;
+    }
     let app_clone = app.clone();
     tauri::async_runtime::spawn(async move {
-         // Read config to get language
-         let config = modules::load_app_config().unwrap_or_default();
-         let texts = modules::i18n::get_tray_texts(&config.language);
-         
-         // Get current account info
-         let current = modules::get_current_account_id().unwrap_or(None);
-         
-         let mut menu_lines = Vec::new();
-         let mut user_text = format!("{}: {}", texts.current, texts.no_account);
-
-         if let Some(id) = current {
-             if let Ok(account) = modules::load_account(&id) {
-                 user_text = format!("{}: {}", texts.current, account.email);
-                 
-                 if let Some(q) = account.quota {
-                     if q.is_forbidden {
-                         menu_lines.push(format!("🚫 {}", texts.forbidden));
-                     } else {
-                         // Extract the 3 specified models
-                         let mut gemini_high = 0;
-                         let mut gemini_image = 0;
-                         let mut claude = 0;
-                         
-                         // Use strict matching, consistent with frontend
-                         for m in q.models {
-                             let name = m.name.to_lowercase();
-                             if name == "gemini-3.1-pro-high" || name == "gemini-3-pro-high" { gemini_high = m.percentage; }
-                             if name == "gemini-3-pro-image" { gemini_image = m.percentage; }
-                             if name == "claude-sonnet-4-6" || name == "claude-sonnet-4-5" { claude = m.percentage; }
-                         }
-                         
-                         menu_lines.push(format!("Gemini High: {}%", gemini_high));
-                         menu_lines.push(format!("Gemini Image: {}%", gemini_image));
-                         menu_lines.push(format!("Claude 4.5: {}%", claude));
-                     }
-                 } else {
-                     menu_lines.push(texts.unknown_quota.clone());
-                 }
-             } else {
-                 user_text = format!("{}: Error", texts.current);
-                 menu_lines.push(format!("{}: --", texts.quota));
-             }
-         } else {
-             menu_lines.push(texts.unknown_quota.clone());
-         };
-
-         // Rebuild menu items
-         let info_user = MenuItem::with_id(&app_clone, "info_user", &user_text, false, None::<&str>);
-         
-         // Dynamically create quota items
-         let mut quota_items = Vec::new();
-         for (i, line) in menu_lines.iter().enumerate() {
-             let item = MenuItem::with_id(&app_clone, format!("info_quota_{}", i), line, false, None::<&str>);
-             if let Ok(item) = item {
-                 quota_items.push(item);
-             }
-         }
-         
-         let switch_next = MenuItem::with_id(&app_clone, "switch_next", &texts.switch_next, true, None::<&str>);
-         let refresh_curr = MenuItem::with_id(&app_clone, "refresh_curr", &texts.refresh_current, true, None::<&str>);
-         
-         let show_i = MenuItem::with_id(&app_clone, "show", &texts.show_window, true, None::<&str>);
-         let quit_i = MenuItem::with_id(&app_clone, "quit", &texts.quit, true, None::<&str>);
-         
-         if let (Ok(i_u), Ok(s_n), Ok(r_c), Ok(s), Ok(q)) = (info_user, switch_next, refresh_curr, show_i, quit_i) {
-             let sep1 = PredefinedMenuItem::separator(&app_clone).ok();
-             let sep2 = PredefinedMenuItem::separator(&app_clone).ok();
-             let sep3 = PredefinedMenuItem::separator(&app_clone).ok();
-             
-             let mut items: Vec<&dyn tauri::menu::IsMenuItem<tauri::Wry>> = vec![&i_u];
-             // Add dynamic quota items
-             for item in &quota_items {
-                 items.push(item);
-             }
-             
-             if let Some(ref s) = sep1 { items.push(s); }
-             items.push(&s_n);
-             items.push(&r_c);
-             if let Some(ref s) = sep2 { items.push(s); }
-             items.push(&s);
-             if let Some(ref s) = sep3 { items.push(s); }
-             items.push(&q);
-             
-             if let Ok(menu) = Menu::with_items(&app_clone, &items) {
-                 if let Some(tray) = app_clone.tray_by_id("main") {
-                     let _ = tray.set_menu(Some(menu));
-                 }
-             }
-         }
+        tokio::time::sleep(TRAY_REFRESH_DEBOUNCE).await;
+        // Cleared before reading state so changes made during the rebuild schedule another one
+        TRAY_REFRESH_SCHEDULED.store(false, Ordering::Release);
+
+        let state = compute_menu_state();
+        let unchanged = {
+            let mut last = LAST_MENU_STATE.lock().unwrap_or_else(|e| e.into_inner());
+            let unchanged = last.as_ref() == Some(&state);
+            if !unchanged {
+                *last = Some(state.clone());
+            }
+            unchanged
+        };
+        if !unchanged {
+            match build_menu(&app_clone, &state) {
+                Some(menu) => {
+                    if let Some(tray) = app_clone.tray_by_id("main") {
+                        let _ = tray.set_menu(Some(menu));
+                    }
+                }
+                // Not applied: rebuild on the next call
+                None => *LAST_MENU_STATE.lock().unwrap_or_else(|e| e.into_inner()) = None,
+            }
+        }
+
+        refresh_tray_tooltip(&app_clone).await;
+    });
+}
+
+fn compute_menu_state() -> TrayMenuState {
+    // Read config to get language
+    let config = modules::load_app_config().unwrap_or_default();
+    let texts = modules::i18n::get_tray_texts(&config.language);
+    let lang = config.language.as_str();
+    let max_width = config.tray.max_text_width;
+    let fit = |s: &str| modules::i18n::truncate_text(s, max_width);
+
+    // Get current account info
+    let current = modules::get_current_account_id().unwrap_or(None);
+
+    let mut menu_lines = Vec::new();
+    let mut user_text = modules::i18n::tray_line(lang, &texts.current, &texts.no_account, max_width, false);
+
+    if let Some(id) = current {
+        if let Ok(account) = modules::load_account(&id) {
+            user_text = modules::i18n::tray_line(
+                lang,
+                &texts.current,
+                &account.email,
+                max_width,
+                config.tray.email_middle_ellipsis,
+            );
+
+            if let Some(q) = account.quota {
+                if q.is_forbidden {
+                    menu_lines.push(format!("🚫 {}", texts.forbidden));
+                } else {
+                    // Extract the 3 specified models
+                    let mut gemini_high = 0;
+                    let mut gemini_image = 0;
+                    let mut claude = 0;
+
+                    // Use strict matching, consistent with frontend
+                    for m in q.models {
+                        let name = m.name.to_lowercase();
+                        if name == "gemini-3.1-pro-high" || name == "gemini-3-pro-high" { gemini_high = m.percentage; }
+                        if name == "gemini-3-pro-image" { gemini_image = m.percentage; }
+                        if name == "claude-sonnet-4-6" || name == "claude-sonnet-4-5" { claude = m.percentage; }
+                    }
+
+                    menu_lines.push(format!("Gemini High: {}%", gemini_high));
+                    menu_lines.push(format!("Gemini Image: {}%", gemini_image));
+                    menu_lines.push(format!("Claude 4.5: {}%", claude));
+                }
+            } else {
+                menu_lines.push(texts.unknown_quota.clone());
+            }
+        } else {
+            user_text = modules::i18n::tray_line(lang, &texts.current, "Error", max_width, false);
+            menu_lines.push(format!("{}: --", texts.quota));
+        }
+    } else {
+        menu_lines.push(texts.unknown_quota.clone());
+    };
+
+    // Recent events (switches / protections / proxy errors), newest first
+    let recent = modules::event_feed::recent(RECENT_EVENTS_IN_MENU);
+    let events = if recent.is_empty() {
+        vec![("recent_event_none".to_string(), fit(&texts.no_recent_events))]
+    } else {
+        recent
+            .iter()
+            .enumerate()
+            .map(|(i, event)| (format!("recent_event_{}", i), fit(&format_event(event))))
+            .collect()
+    };
+
+    let switch_back = modules::account::previous_account_id()
+        .ok()
+        .flatten()
+        .and_then(|id| modules::load_account(&id).ok())
+        .map(|acc| fit(&texts.switch_back.replace("{email}", &acc.email)));
+
+    TrayMenuState {
+        user_text,
+        quota_lines: menu_lines.iter().map(|line| fit(line)).collect(),
+        recent_events_title: fit(&texts.recent_events),
+        events,
+        switch_next: fit(&texts.switch_next),
+        switch_back,
+        refresh_current: fit(&texts.refresh_current),
+        show_window: fit(&texts.show_window),
+        quit: fit(&texts.quit),
+    }
+}
+
+fn build_menu(app: &tauri::AppHandle, state: &TrayMenuState) -> Option<Menu<tauri::Wry>> {
+    // Rebuild menu items
+    let info_user = MenuItem::with_id(app, "info_user", &state.user_text, false, None::<&str>);
+
+    // Dynamically create quota items
+    let quota_items: Vec<_> = state
+        .quota_lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| MenuItem::with_id(app, format!("info_quota_{}", i), line, false, None::<&str>).ok())
+        .collect();
+
+    let event_items: Vec<_> = state
+        .events
+        .iter()
+        .filter_map(|(id, line)| MenuItem::with_id(app, id, line, false, None::<&str>).ok())
+        .collect();
+    let event_refs: Vec<&dyn tauri::menu::IsMenuItem<tauri::Wry>> = event_items
+        .iter()
+        .map(|i| i as &dyn tauri::menu::IsMenuItem<tauri::Wry>)
+        .collect();
+    let recent_events = Submenu::with_id_and_items(app, "recent_events", &state.recent_events_title, true, &event_refs).ok();
+
+    let switch_next = MenuItem::with_id(app, "switch_next", &state.switch_next, true, None::<&str>);
+    let switch_back = state
+        .switch_back
+        .as_ref()
+        .and_then(|text| MenuItem::with_id(app, "switch_back", text, true, None::<&str>).ok());
+    let refresh_curr = MenuItem::with_id(app, "refresh_curr", &state.refresh_current, true, None::<&str>);
+
+    let show_i = MenuItem::with_id(app, "show", &state.show_window, true, None::<&str>);
+    let quit_i = MenuItem::with_id(app, "quit", &state.quit, true, None::<&str>);
+
+    let (Ok(i_u), Ok(s_n), Ok(r_c), Ok(s), Ok(q)) = (info_user, switch_next, refresh_curr, show_i, quit_i) else {
+        return None;
+    };
+    let sep1 = PredefinedMenuItem::separator(app).ok();
+    let sep2 = PredefinedMenuItem::separator(app).ok();
+    let sep3 = PredefinedMenuItem::separator(app).ok();
+
+    let mut items: Vec<&dyn tauri::menu::IsMenuItem<tauri::Wry>> = vec![&i_u];
+    // Add dynamic quota items
+    for item in &quota_items {
+        items.push(item);
+    }
+    if let Some(ref m) = recent_events { items.push(m); }
+
+    if let Some(ref s) = sep1 { items.push(s); }
+    items.push(&s_n);
+    if let Some(ref b) = switch_back { items.push(b); }
+    items.push(&r_c);
+    if let Some(ref s) = sep2 { items.push(s); }
+    items.push(&s);
+    if let Some(ref s) = sep3 { items.push(s); }
+    items.push(&q);
+
+    Menu::with_items(app, &items).ok()
+}
+
+const RECENT_EVENTS_IN_MENU: usize = 5;
+
+/// "14:05 🔄 a@example.com (x3)" (local time)
+/// 托盘操作失败: 日志保留技术细节，“最近事件”中显示本地化的提示
+fn record_action_failure(error: crate::error::CommandError) {
+    let language = modules::config::load_app_config()
+        .map(|c| c.language)
+        .unwrap_or_default();
+    modules::event_feed::record(
+        modules::event_feed::EventKind::ActionFailed,
+        modules::i18n::localize_error(&language, &error),
+    );
+}
+
+fn format_event(event: &modules::event_feed::FeedEvent) -> String {
+    let time = chrono::DateTime::from_timestamp(event.timestamp, 0)
+        .map(|t| t.with_timezone(&chrono::Local).format("%H:%M").to_string())
+        .unwrap_or_default();
+    let mut line = format!("{} {} {}", time, event.kind.icon(), event.message);
+    if event.count > 1 {
+        line.push_str(&format!(" (x{})", event.count));
+    }
+    line
+}
+
+/// 当前账号在受监控模型 (配额保护的 monitored_models) 中的最低剩余额度
+fn lowest_monitored_quota(quota: &crate::models::QuotaData, monitored: &[String]) -> Option<(String, i32)> {
+    quota
+        .models
+        .iter()
+        .filter(|m| {
+            crate::proxy::common::model_mapping::normalize_to_standard_id(&m.name)
+                .is_some_and(|id| monitored.contains(&id))
+        })
+        .min_by_key(|m| m.percentage)
+        .map(|m| (m.display_name.clone().unwrap_or_else(|| m.name.clone()), m.percentage))
+}
+
+/// 反代正在运行时的端口 (try_read，避免启动过程中持有写锁时阻塞)
+fn running_proxy_port(app: &tauri::AppHandle) -> Option<u16> {
+    let state = app.try_state::<crate::commands::proxy::ProxyServiceState>()?;
+    let instance = state.instance.try_read().ok()?;
+    instance.as_ref().map(|i| i.config.port)
+}
+
+/// Widest menu bar label (columns) before truncation
+const MENU_BAR_TITLE_WIDTH: usize = 16;
+
+/// macOS 菜单栏图标旁的短标题：当前账号名 (无名称时取邮箱 @ 前部分) 和 / 或最低额度
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn menu_bar_title(
+    mode: crate::models::MenuBarTitle,
+    account: Option<&crate::models::Account>,
+    lowest_percentage: Option<i32>,
+) -> Option<String> {
+    use crate::models::MenuBarTitle;
+    let label = account.map(|a| {
+        let name = a
+            .name
+            .as_deref()
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| a.email.split('@').next().unwrap_or(&a.email));
+        modules::i18n::truncate_text(name, MENU_BAR_TITLE_WIDTH)
     });
+    let quota = lowest_percentage.map(|p| format!("{}%", p));
+    match mode {
+        MenuBarTitle::Off => None,
+        MenuBarTitle::Account => label,
+        MenuBarTitle::LowestQuota => quota,
+        MenuBarTitle::Both => match (label, quota) {
+            (Some(label), Some(quota)) => Some(format!("{} · {}", label, quota)),
+            (label, quota) => label.or(quota),
+        },
+    }
+}
+
+#[cfg(target_os = "macos")]
+static LAST_MENU_BAR_TITLE: std::sync::Mutex<Option<Option<String>>> = std::sync::Mutex::new(None);
+
+#[cfg(target_os = "macos")]
+fn refresh_menu_bar_title(
+    app: &tauri::AppHandle,
+    tray_config: &crate::models::TrayConfig,
+    account: Option<&crate::models::Account>,
+    lowest_percentage: Option<i32>,
+) {
+    let title = menu_bar_title(tray_config.menu_bar_title, account, lowest_percentage);
+    {
+        let mut last = LAST_MENU_BAR_TITLE.lock().unwrap_or_else(|e| e.into_inner());
+        if last.as_ref() == Some(&title) {
+            return;
+        }
+        *last = Some(title.clone());
+    }
+    if let Some(tray) = app.tray_by_id("main") {
+        let _ = tray.set_title(title);
+    }
+}
+
+/// 托盘图标提示：反代状态、近 5 分钟请求数、当前账号与受监控模型的最低额度
+async fn refresh_tray_tooltip(app: &tauri::AppHandle) {
+    let config = modules::load_app_config().unwrap_or_default();
+    let texts = modules::i18n::get_tray_texts(&config.language);
+    let account = modules::get_current_account_id()
+        .ok()
+        .flatten()
+        .and_then(|id| modules::load_account(&id).ok());
+
+    let mut lines = vec![match running_proxy_port(app) {
+        Some(port) => texts.proxy_running.replace("{port}", &port.to_string()),
+        None => texts.proxy_stopped.clone(),
+    }];
+    lines.push(texts.recent_requests.replace(
+        "{count}",
+        &crate::proxy::monitor::recent_request_count(crate::proxy::monitor::RECENT_WINDOW_SECS).to_string(),
+    ));
+    lines.push(format!(
+        "{}: {}",
+        texts.current,
+        account.as_ref().map(|a| a.email.as_str()).unwrap_or(&texts.no_account)
+    ));
+    let lowest = account
+        .as_ref()
+        .and_then(|a| a.quota.as_ref())
+        .filter(|q| !q.is_forbidden)
+        .and_then(|q| lowest_monitored_quota(q, &config.quota_protection.monitored_models));
+    if let Some((model, percentage)) = lowest {
+        lines.push(
+            texts
+                .lowest_quota
+                .replace("{model}", &model)
+                .replace("{percentage}", &percentage.to_string()),
+        );
+    }
+    #[cfg(target_os = "macos")]
+    refresh_menu_bar_title(app, &config.tray, account.as_ref(), lowest.as_ref().map(|(_, p)| *p));
+
+    let tooltip = lines.join("\n");
+    {
+        let mut last = LAST_TOOLTIP.lock().unwrap_or_else(|e| e.into_inner());
+        if last.as_deref() == Some(tooltip.as_str()) {
+            return;
+        }
+        *last = Some(tooltip.clone());
+    }
+    if let Some(tray) = app.tray_by_id("main") {
+        let _ = tray.set_tooltip(Some(tooltip));
+    }
 }
@@ -57,6 +57,9 @@ const UPDATER_JSON_URL: &str = "https://github.com/lbjlaq/Antigravity-Manager/re
 /// 1. Check updater.json (Source of Truth for Auto-Update)
 /// 2. Fallback to GitHub API (Informational)
 pub async fn check_for_updates() -> Result<UpdateInfo, String> {
+    // 离线时直接返回，避免三个更新源依次超时
+    crate::modules::offline::ensure_online()?;
+
     // 1. Try updater.json first (Critical for functional Auto-Update)
     match check_updater_json().await {
         Ok(info) => return Ok(info),
@@ -157,26 +160,16 @@ async fn check_updater_json() -> Result<UpdateInfo, String> {
 }
 
 async fn create_client() -> Result<reqwest::Client, String> {
-    let mut builder = reqwest::Client::builder()
-        .user_agent("Antigravity-Manager")
-        .timeout(std::time::Duration::from_secs(10));
-
-    // Load config to check for upstream proxy
-    if let Ok(config) = crate::modules::config::load_app_config() {
-        if config.proxy.upstream_proxy.enabled && !config.proxy.upstream_proxy.url.is_empty() {
-            logger::log_info(&format!("Update checker using upstream proxy: {}", config.proxy.upstream_proxy.url));
-            match reqwest::Proxy::all(&config.proxy.upstream_proxy.url) {
-                Ok(proxy) => {
-                    builder = builder.proxy(proxy);
-                },
-                Err(e) => {
-                    logger::log_warn(&format!("Failed to parse proxy URL '{}': {}", config.proxy.upstream_proxy.url, e));
-                }
-            }
-        }
+    // 统一的连接超时 / 连接池 / 上游代理配置来自 modules::http
+    if let Some(url) = crate::modules::http::upstream_proxy_url() {
+        logger::log_info(&format!("Update checker using upstream proxy: {}", url));
     }
 
-    builder.build().map_err(|e| format!("Failed to create HTTP client: {}", e))
+    crate::modules::http::reqwest_client_builder()
+        .user_agent("Antigravity-Manager")
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
 }
 
 async fn check_github_api() -> Result<UpdateInfo, String> {
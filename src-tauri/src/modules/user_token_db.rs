@@ -28,6 +28,14 @@ pub struct UserToken {
     pub last_used_at: Option<i64>,
     pub total_requests: i64,
     pub total_tokens_used: i64,
+    #[serde(default)]
+    pub account_group: Option<String>, // 绑定的账号分组标签 (None = 使用全部账号)
+    #[serde(default = "default_priority")]
+    pub priority: String, // 准入优先级: "high" | "normal" | "low" (账号池紧张时 low 最先被排队/拒绝)
+}
+
+fn default_priority() -> String {
+    "normal".to_string()
 }
 
 /// 令牌 IP 绑定结构体
@@ -55,6 +63,18 @@ pub struct TokenUsageLog {
     pub status: u16,
 }
 
+/// 按 API Key / 月份 / 模型汇总的用量
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonthlyModelUsage {
+    pub month: String, // "YYYY-MM" (UTC)
+    pub token_id: String,
+    pub username: Option<String>, // 令牌已删除时为 None
+    pub model: String,
+    pub requests: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+}
+
 /// 获取数据库路径
 pub fn get_db_path() -> Result<PathBuf, String> {
     let mut path = crate::modules::account::get_data_dir()?;
@@ -105,6 +125,8 @@ pub fn init_db() -> Result<(), String> {
     let _ = conn.execute("ALTER TABLE user_tokens ADD COLUMN last_used_at INTEGER", []);
     let _ = conn.execute("ALTER TABLE user_tokens ADD COLUMN curfew_start TEXT", []);
     let _ = conn.execute("ALTER TABLE user_tokens ADD COLUMN curfew_end TEXT", []);
+    let _ = conn.execute("ALTER TABLE user_tokens ADD COLUMN account_group TEXT", []);
+    let _ = conn.execute("ALTER TABLE user_tokens ADD COLUMN priority TEXT DEFAULT 'normal'", []);
 
     // 创建 token_ip_bindings 表
     conn.execute(
@@ -149,10 +171,27 @@ pub fn init_db() -> Result<(), String> {
     let _ = conn.execute("UPDATE user_tokens SET total_requests = 0 WHERE total_requests IS NULL", []);
     let _ = conn.execute("UPDATE user_tokens SET total_tokens_used = 0 WHERE total_tokens_used IS NULL", []);
     let _ = conn.execute("UPDATE user_tokens SET enabled = 1 WHERE enabled IS NULL", []);
+    let _ = conn.execute("UPDATE user_tokens SET priority = 'normal' WHERE priority IS NULL OR priority = ''", []);
 
     Ok(())
 }
 
+/// 空白分组视为未绑定
+fn normalize_account_group(group: Option<String>) -> Option<String> {
+    group
+        .map(|g| g.trim().to_string())
+        .filter(|g| !g.is_empty())
+}
+
+/// 未知值回退为 normal
+fn normalize_priority(priority: Option<String>) -> String {
+    match priority.as_deref().map(|p| p.trim().to_lowercase()).as_deref() {
+        Some("high") => "high".to_string(),
+        Some("low") => "low".to_string(),
+        _ => default_priority(),
+    }
+}
+
 /// 创建新令牌
 pub fn create_token(
     username: String,
@@ -161,7 +200,9 @@ pub fn create_token(
     max_ips: i32,
     curfew_start: Option<String>,
     curfew_end: Option<String>,
-    custom_expires_at: Option<i64>  // 自定义过期时间戳 (秒)
+    custom_expires_at: Option<i64>, // 自定义过期时间戳 (秒)
+    account_group: Option<String>,
+    priority: Option<String>,
 ) -> Result<UserToken, String> {
     let conn = connect_db()?;
     let id = Uuid::new_v4().to_string();
@@ -192,14 +233,16 @@ pub fn create_token(
         last_used_at: None,
         total_requests: 0,
         total_tokens_used: 0,
+        account_group: normalize_account_group(account_group),
+        priority: normalize_priority(priority),
     };
 
     conn.execute(
         "INSERT INTO user_tokens (
             id, token, username, description, enabled, expires_type, expires_at, max_ips,
             curfew_start, curfew_end,
-            created_at, updated_at, total_requests, total_tokens_used
-        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+            created_at, updated_at, total_requests, total_tokens_used, account_group, priority
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
         params![
             user_token.id,
             user_token.token,
@@ -215,6 +258,8 @@ pub fn create_token(
             user_token.updated_at,
             user_token.total_requests,
             user_token.total_tokens_used,
+            user_token.account_group,
+            user_token.priority,
         ],
     ).map_err(|e| format!("Failed to insert user token: {}", e))?;
 
@@ -244,6 +289,11 @@ pub fn list_tokens() -> Result<Vec<UserToken>, String> {
             last_used_at: row.get("last_used_at").unwrap_or(None),
             total_requests: row.get("total_requests").unwrap_or(0),
             total_tokens_used: row.get("total_tokens_used").unwrap_or(0),
+            account_group: row.get("account_group").unwrap_or(None),
+            priority: row
+                .get::<_, Option<String>>("priority")
+                .unwrap_or(None)
+                .unwrap_or_else(default_priority),
         })
     }).map_err(|e| format!("Failed to query tokens: {}", e))?;
 
@@ -278,6 +328,11 @@ pub fn get_token_by_id(id: &str) -> Result<Option<UserToken>, String> {
             last_used_at: row.get("last_used_at")?,
             total_requests: row.get("total_requests")?,
             total_tokens_used: row.get("total_tokens_used")?,
+            account_group: row.get("account_group").unwrap_or(None),
+            priority: row
+                .get::<_, Option<String>>("priority")
+                .unwrap_or(None)
+                .unwrap_or_else(default_priority),
         })
     }).optional().map_err(|e| format!("Failed to query token: {}", e))?;
     
@@ -307,6 +362,11 @@ pub fn get_token_by_value(token: &str) -> Result<Option<UserToken>, String> {
             last_used_at: row.get("last_used_at")?,
             total_requests: row.get("total_requests")?,
             total_tokens_used: row.get("total_tokens_used")?,
+            account_group: row.get("account_group").unwrap_or(None),
+            priority: row
+                .get::<_, Option<String>>("priority")
+                .unwrap_or(None)
+                .unwrap_or_else(default_priority),
         })
     }).optional().map_err(|e| format!("Failed to query token: {}", e))?;
     
@@ -321,7 +381,9 @@ pub fn update_token(
     enabled: Option<bool>,
     max_ips: Option<i32>,
     curfew_start: Option<Option<String>>,
-    curfew_end: Option<Option<String>>
+    curfew_end: Option<Option<String>>,
+    account_group: Option<String>, // Some("") 清除分组绑定
+    priority: Option<String>,
 ) -> Result<(), String> {
     let conn = connect_db()?;
     let now = Utc::now().timestamp();
@@ -366,6 +428,18 @@ pub fn update_token(
         param_idx += 1;
     }
 
+    if let Some(group) = account_group {
+        query.push_str(&format!(", account_group = ?{}", param_idx));
+        params_vec.push(Box::new(normalize_account_group(Some(group))));
+        param_idx += 1;
+    }
+
+    if let Some(priority) = priority {
+        query.push_str(&format!(", priority = ?{}", param_idx));
+        params_vec.push(Box::new(normalize_priority(Some(priority))));
+        param_idx += 1;
+    }
+
     query.push_str(&format!(" WHERE id = ?{}", param_idx));
     params_vec.push(Box::new(id.to_string()));
 
@@ -498,6 +572,46 @@ pub fn record_token_usage_and_ip(
     Ok(())
 }
 
+/// 按月汇总各令牌的用量 (月份为 UTC "YYYY-MM"，起止均包含)
+pub fn get_monthly_usage(
+    from_month: Option<&str>,
+    to_month: Option<&str>,
+) -> Result<Vec<MonthlyModelUsage>, String> {
+    let conn = connect_db()?;
+    let mut stmt = conn.prepare(
+        "SELECT strftime('%Y-%m', l.request_time, 'unixepoch') AS month,
+                l.token_id,
+                t.username,
+                COALESCE(l.model, '') AS model_name,
+                COUNT(*),
+                COALESCE(SUM(l.input_tokens), 0),
+                COALESCE(SUM(l.output_tokens), 0)
+         FROM token_usage_logs l
+         LEFT JOIN user_tokens t ON t.id = l.token_id
+         WHERE (?1 IS NULL OR month >= ?1) AND (?2 IS NULL OR month <= ?2)
+         GROUP BY month, l.token_id, model_name
+         ORDER BY month, t.username, l.token_id, model_name"
+    ).map_err(|e| format!("Failed to prepare monthly usage query: {}", e))?;
+
+    let rows = stmt.query_map(params![from_month, to_month], |row| {
+        Ok(MonthlyModelUsage {
+            month: row.get(0)?,
+            token_id: row.get(1)?,
+            username: row.get(2)?,
+            model: row.get(3)?,
+            requests: row.get(4)?,
+            input_tokens: row.get(5)?,
+            output_tokens: row.get(6)?,
+        })
+    }).map_err(|e| format!("Failed to query monthly usage: {}", e))?;
+
+    let mut usage = Vec::new();
+    for row in rows {
+        usage.push(row.map_err(|e| format!("Failed to read monthly usage: {}", e))?);
+    }
+    Ok(usage)
+}
+
 /// 检查 Token 是否有效 (包含过期时间检查和 IP 限制检查)
 /// 返回: (是否有效, 拒绝原因)
 pub fn validate_token(token_str: &str, ip: &str) -> Result<(bool, Option<String>), String> {
@@ -595,12 +709,13 @@ mod tests {
         
         // Use a random username to avoid collisions in existing DB runs during dev
         let username = format!("TestUser_{}", Uuid::new_v4());
-        let token_res = create_token(username.clone(), "day".to_string(), Some("Test token".to_string()), 0, None, None, None);
+        let token_res = create_token(username.clone(), "day".to_string(), Some("Test token".to_string()), 0, None, None, None, None, Some("LOW".to_string()));
         assert!(token_res.is_ok());
 
         let token = token_res.unwrap();
         assert_eq!(token.username, username);
         assert!(token.token.starts_with("sk-"));
+        assert_eq!(token.priority, "low");
         
         let fetched = get_token_by_id(&token.id);
         assert!(fetched.is_ok());
@@ -41,19 +41,24 @@ pub fn get_antigravity_version() -> Result<AntigravityVersion, String> {
         .ok_or("Unable to locate Antigravity executable")?;
     
     // 2. 根据平台读取版本信息
+    get_version_for_path(&exe_path)
+}
+
+/// 读取指定安装路径的版本信息（跨平台）
+pub fn get_version_for_path(exe_path: &PathBuf) -> Result<AntigravityVersion, String> {
     #[cfg(target_os = "macos")]
     {
-        get_version_macos(&exe_path)
+        get_version_macos(exe_path)
     }
-    
+
     #[cfg(target_os = "windows")]
     {
-        get_version_windows(&exe_path)
+        get_version_windows(exe_path)
     }
-    
+
     #[cfg(target_os = "linux")]
     {
-        get_version_linux(&exe_path)
+        get_version_linux(exe_path)
     }
 }
 
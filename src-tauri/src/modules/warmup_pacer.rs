@@ -0,0 +1,116 @@
+// 预热节流 (warmup pacing)
+// 配额恢复时大量账号 / 模型会同时达到 100%，一次性发出全部预热请求会让同一 IP 出现突发调用，容易被判定为滥用。
+// plan() 把一批预热任务铺开到配置的时间窗口内，并保证同一账号两次预热之间的最小间隔；
+// acquire() 是所有预热路径共用的全局每分钟上限 (定时扫描、账号重新启用后的单账号预热)，窗口与上限冲突时以上限为准。
+
+use crate::models::ScheduledWarmupConfig;
+use once_cell::sync::Lazy;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use tokio::time::{Duration, Instant};
+
+// 下一个可用的全局发送时刻
+static NEXT_SLOT: Lazy<Mutex<Option<Instant>>> = Lazy::new(|| Mutex::new(None));
+
+/// 全局上限对应的最小发送间隔 (max_per_minute 为 0 时不限制)
+fn global_spacing(cfg: &ScheduledWarmupConfig) -> Duration {
+    if cfg.max_per_minute == 0 {
+        Duration::ZERO
+    } else {
+        Duration::from_millis(60_000 / cfg.max_per_minute as u64)
+    }
+}
+
+/// 为一批预热任务计算相对开始时间的发送偏移，返回 (任务下标, 偏移)，按偏移升序。
+/// 账号之间轮流排列，使同一账号的任务自然错开。
+pub fn plan(account_ids: &[&str], cfg: &ScheduledWarmupConfig) -> Vec<(usize, Duration)> {
+    let n = account_ids.len();
+    if n == 0 {
+        return Vec::new();
+    }
+    let spread = Duration::from_secs(cfg.window_secs) / n as u32;
+    let spacing = spread.max(global_spacing(cfg));
+    let per_account = Duration::from_secs(cfg.per_account_interval_secs);
+
+    // 按账号分组 (保持首次出现顺序)，再轮流取出
+    let mut order: Vec<&str> = Vec::new();
+    let mut queues: HashMap<&str, VecDeque<usize>> = HashMap::new();
+    for (idx, &id) in account_ids.iter().enumerate() {
+        queues
+            .entry(id)
+            .or_insert_with(|| {
+                order.push(id);
+                VecDeque::new()
+            })
+            .push_back(idx);
+    }
+
+    let mut last_for_account: HashMap<&str, Duration> = HashMap::new();
+    let mut previous: Option<Duration> = None;
+    let mut schedule = Vec::with_capacity(n);
+    while schedule.len() < n {
+        for &id in &order {
+            let Some(idx) = queues.get_mut(id).and_then(VecDeque::pop_front) else {
+                continue;
+            };
+            let mut at = previous.map_or(Duration::ZERO, |p| p + spacing);
+            if let Some(last) = last_for_account.get(id) {
+                at = at.max(*last + per_account);
+            }
+            previous = Some(at);
+            last_for_account.insert(id, at);
+            schedule.push((idx, at));
+        }
+    }
+    schedule
+}
+
+/// 占用一个全局发送名额，必要时等待到下一个可用时刻
+pub async fn acquire(cfg: &ScheduledWarmupConfig) {
+    let spacing = global_spacing(cfg);
+    if spacing.is_zero() {
+        return;
+    }
+    let slot = {
+        let mut next = NEXT_SLOT.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let slot = next.map_or(now, |n| n.max(now));
+        *next = Some(slot + spacing);
+        slot
+    };
+    tokio::time::sleep_until(slot).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cfg(window_secs: u64, per_account: u64, max_per_minute: u32) -> ScheduledWarmupConfig {
+        ScheduledWarmupConfig {
+            window_secs,
+            per_account_interval_secs: per_account,
+            max_per_minute,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_plan() {
+        // 窗口 300s 内 4 个任务平均铺开，账号轮流
+        let schedule = plan(&["a", "a", "b", "b"], &cfg(300, 30, 0));
+        let secs: Vec<(usize, u64)> = schedule.iter().map(|(i, d)| (*i, d.as_secs())).collect();
+        assert_eq!(secs, vec![(0, 0), (2, 75), (1, 150), (3, 225)]);
+
+        // 全局上限 (6/分钟 = 10s) 比窗口间隔更严格
+        let schedule = plan(&["a", "b", "c"], &cfg(0, 0, 6));
+        let secs: Vec<u64> = schedule.iter().map(|(_, d)| d.as_secs()).collect();
+        assert_eq!(secs, vec![0, 10, 20]);
+
+        // 同一账号的最小间隔
+        let schedule = plan(&["a", "a", "a"], &cfg(0, 30, 6));
+        let secs: Vec<u64> = schedule.iter().map(|(_, d)| d.as_secs()).collect();
+        assert_eq!(secs, vec![0, 30, 60]);
+
+        assert!(plan(&[], &cfg(300, 30, 6)).is_empty());
+    }
+}
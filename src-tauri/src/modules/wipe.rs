@@ -0,0 +1,224 @@
+// 全部数据清除 (归还设备前使用)
+// 删除数据目录中的所有内容: 账号与 Token、配置、日志、统计数据库、本地备份、调试日志等。
+// 调试日志目录配置在数据目录之外时，只删除其中由调试日志写入的文件 (按文件名格式识别)，
+// 目录本身与其他文件保持不动。
+// 每个文件在删除前先用 0 覆盖并落盘 (尽力而为: SSD / 写时复制文件系统上不保证物理擦除)。
+// 仍被占用而无法删除的文件 (如 Windows 上已打开的数据库) 会在数据目录留下标记，
+// 下次启动时在打开日志与数据库之前由 finish_pending_wipe 完成清除。
+// 清除前先停止后台任务、日志文件与日志数据库的写入 (反代由调用方先停止，统计数据库随之不再写入)，
+// 完成后删除数据目录记录文件，下次启动回到默认位置。
+// 远程备份目标 (WebDAV / S3) 上的内容不受影响。
+
+use serde::Serialize;
+use std::fs;
+use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use crate::modules::{account, config, data_dir, log_store, logger, scheduler};
+
+/// 调用方必须原样传入的确认短语
+pub const CONFIRM_PHRASE: &str = "WIPE ALL DATA";
+
+const PENDING_MARKER: &str = ".wipe_pending";
+const OVERWRITE_CHUNK: usize = 1024 * 1024;
+
+#[derive(Debug, Default, Serialize)]
+pub struct WipeReport {
+    pub files_removed: usize,
+    pub bytes_overwritten: u64,
+    /// 未能删除的路径 (下次启动时重试)
+    pub pending: Vec<String>,
+    /// 删除的数据目录记录文件
+    pub location_records_removed: Vec<String>,
+}
+
+/// 用 0 覆盖文件内容并落盘
+fn overwrite_file(path: &Path) -> std::io::Result<u64> {
+    let mut file = fs::OpenOptions::new().write(true).open(path)?;
+    let len = file.metadata()?.len();
+    let zeros = vec![0u8; OVERWRITE_CHUNK];
+    file.seek(SeekFrom::Start(0))?;
+    let mut remaining = len;
+    while remaining > 0 {
+        let n = remaining.min(OVERWRITE_CHUNK as u64) as usize;
+        file.write_all(&zeros[..n])?;
+        remaining -= n as u64;
+    }
+    file.sync_all()?;
+    Ok(len)
+}
+
+fn wipe_dir(dir: &Path, report: &mut WipeReport) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.file_name().is_some_and(|n| n == PENDING_MARKER) {
+            continue;
+        }
+        // 不跟随符号链接，只删除链接本身
+        let Ok(file_type) = entry.file_type() else {
+            report.pending.push(path.display().to_string());
+            continue;
+        };
+        if file_type.is_dir() {
+            wipe_dir(&path, report);
+            if fs::remove_dir(&path).is_err() && path.exists() {
+                report.pending.push(path.display().to_string());
+            }
+            continue;
+        }
+        if file_type.is_file() {
+            if let Ok(bytes) = overwrite_file(&path) {
+                report.bytes_overwritten += bytes;
+            }
+        }
+        match fs::remove_file(&path) {
+            Ok(()) => report.files_removed += 1,
+            Err(_) => report.pending.push(path.display().to_string()),
+        }
+    }
+}
+
+/// 只清除调试日志目录顶层的调试日志文件 (目录由用户指定，可能还存放着别的东西)
+fn wipe_debug_logs(dir: &Path, report: &mut WipeReport) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let is_debug_log = entry
+            .file_name()
+            .to_str()
+            .is_some_and(crate::proxy::debug_logger::is_debug_log_file_name);
+        if !is_debug_log || !entry.file_type().is_ok_and(|t| t.is_file()) {
+            continue;
+        }
+        let path = entry.path();
+        if let Ok(bytes) = overwrite_file(&path) {
+            report.bytes_overwritten += bytes;
+        }
+        match fs::remove_file(&path) {
+            Ok(()) => report.files_removed += 1,
+            Err(_) => report.pending.push(path.display().to_string()),
+        }
+    }
+}
+
+/// 需要清除的位置: 数据目录，以及配置在数据目录之外的调试日志目录
+fn wipe_roots() -> Result<(PathBuf, Option<PathBuf>), String> {
+    let data_dir = account::get_data_dir()?;
+    let debug_log_dir = config::load_app_config()
+        .ok()
+        .and_then(|c| c.proxy.debug_logging.output_dir)
+        .map(PathBuf::from)
+        .filter(|d| d.is_dir() && !d.starts_with(&data_dir));
+    Ok((data_dir, debug_log_dir))
+}
+
+fn wipe_roots_now(data_dir: &Path, debug_log_dir: Option<&Path>) -> Result<WipeReport, String> {
+    let mut report = WipeReport::default();
+    wipe_dir(data_dir, &mut report);
+    if let Some(dir) = debug_log_dir {
+        wipe_debug_logs(dir, &mut report);
+    }
+    let marker = data_dir.join(PENDING_MARKER);
+    if report.pending.is_empty() {
+        let _ = fs::remove_file(&marker);
+    } else {
+        fs::write(&marker, report.pending.join("\n"))
+            .map_err(|e| format!("failed_to_write_wipe_marker: {}", e))?;
+    }
+    Ok(report)
+}
+
+pub fn verify_phrase(confirm_phrase: &str) -> Result<(), String> {
+    if confirm_phrase.trim() != CONFIRM_PHRASE {
+        return Err(format!(
+            "wipe_confirmation_mismatch: type '{}' to confirm",
+            CONFIRM_PHRASE
+        ));
+    }
+    Ok(())
+}
+
+/// 停止之后仍会写入数据目录的组件；此后本进程不再写日志文件，须重启应用
+fn stop_writers() {
+    scheduler::halt_all_jobs();
+    logger::stop_file_logging();
+    // 超时也继续: 仍被占用的数据库文件会记入 pending，下次启动时删除
+    if let Err(e) = log_store::suspend() {
+        tracing::warn!("[Wipe] Log store did not close: {}", e);
+    }
+}
+
+/// 清除全部数据；调用前应先停止反代服务，完成后应用需要重启以回到首次运行状态
+pub fn wipe_all_data(confirm_phrase: &str) -> Result<WipeReport, String> {
+    verify_phrase(confirm_phrase)?;
+    let (data_dir, debug_log_dir) = wipe_roots()?;
+    // 最后一条写入日志文件的记录 (随后被清除)
+    tracing::warn!(
+        "[Wipe] Wiping all application data in {:?} (debug logs in {:?})",
+        data_dir,
+        debug_log_dir
+    );
+    stop_writers();
+    let mut report = wipe_roots_now(&data_dir, debug_log_dir.as_deref())?;
+    report.location_records_removed = data_dir::forget_location()
+        .iter()
+        .map(|p| p.display().to_string())
+        .collect();
+    Ok(report)
+}
+
+/// 启动时完成上次未能删除的文件 (须在初始化日志与数据库之前调用，结果由调用方在日志初始化后记录)；
+/// 没有待完成的清除时返回 None
+pub fn finish_pending_wipe() -> Option<Result<WipeReport, String>> {
+    let data_dir = account::get_data_dir().ok()?;
+    if !data_dir.join(PENDING_MARKER).exists() {
+        return None;
+    }
+    Some(wipe_roots_now(&data_dir, None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wipe_removes_nested_files_and_requires_phrase() {
+        let root = std::env::temp_dir().join(format!("abv-wipe-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("accounts")).unwrap();
+        fs::write(root.join("accounts").join("a.json"), b"refresh-token").unwrap();
+        fs::write(root.join("gui_config.json"), b"{}").unwrap();
+
+        assert!(wipe_all_data("wipe").is_err());
+
+        let report = wipe_roots_now(&root, None).unwrap();
+        assert_eq!(report.files_removed, 2);
+        assert_eq!(report.bytes_overwritten, 15);
+        assert!(report.pending.is_empty());
+        assert_eq!(fs::read_dir(&root).unwrap().count(), 0);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_external_debug_log_dir_only_loses_debug_logs() {
+        let root = std::env::temp_dir().join(format!("abv-wipe-{}", uuid::Uuid::new_v4()));
+        let data_dir = root.join("data");
+        let debug_dir = root.join("shared");
+        fs::create_dir_all(&data_dir).unwrap();
+        fs::create_dir_all(debug_dir.join("nested")).unwrap();
+        let debug_log = debug_dir.join("20260101_120000.123_trace-1_openai_request.json");
+        fs::write(&debug_log, b"{}").unwrap();
+        fs::write(debug_dir.join("notes.json"), b"{}").unwrap();
+        fs::write(debug_dir.join("nested").join("20260101_120000.123_t_x.json"), b"{}").unwrap();
+
+        let report = wipe_roots_now(&data_dir, Some(&debug_dir)).unwrap();
+        assert_eq!(report.files_removed, 1);
+        assert!(!debug_log.exists());
+        assert!(debug_dir.join("notes.json").exists());
+        assert!(debug_dir.join("nested").join("20260101_120000.123_t_x.json").exists());
+        let _ = fs::remove_dir_all(&root);
+    }
+}
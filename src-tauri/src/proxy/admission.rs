@@ -0,0 +1,228 @@
+// 准入控制 (按 API Key 优先级)
+// 账号池紧张时优先保证高优先级 Key 的请求：
+// - 容量 = 可用账号数 × max_concurrent_per_account
+// - high 始终放行；normal / low 仅在进行中请求低于容量的对应份额时放行
+// - 超出份额的请求排队等待其他请求结束，queue_timeout_ms 内仍未放行则被拒绝 (shed)
+// 各优先级的放行 / 排队 / 拒绝次数记录在内存计数器中，供管理接口查看
+
+use crate::proxy::config::AdmissionConfig;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use tokio::sync::Notify;
+use tokio::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ClientPriority {
+    High,
+    #[default]
+    Normal,
+    Low,
+}
+
+impl ClientPriority {
+    /// 解析 UserToken 中保存的优先级，未知值视为 normal
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "high" => Self::High,
+            "low" => Self::Low,
+            _ => Self::Normal,
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Self::High => 0,
+            Self::Normal => 1,
+            Self::Low => 2,
+        }
+    }
+}
+
+/// 进行中的 (已放行) 请求数
+static IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+/// 有请求结束时唤醒排队者
+static RELEASED: Lazy<Notify> = Lazy::new(Notify::new);
+
+#[derive(Default)]
+struct Counters {
+    admitted: [AtomicU64; 3],
+    queued: [AtomicU64; 3],
+    shed: [AtomicU64; 3],
+}
+
+static COUNTERS: Lazy<Counters> = Lazy::new(Counters::default);
+
+/// 放行凭证，drop 时释放占用的容量
+pub struct AdmissionPermit {
+    _private: (),
+}
+
+impl Drop for AdmissionPermit {
+    fn drop(&mut self) {
+        IN_FLIGHT.fetch_sub(1, Ordering::AcqRel);
+        RELEASED.notify_waiters();
+    }
+}
+
+/// 请求被拒绝的原因 (用于响应体和日志)
+#[derive(Debug, Clone)]
+pub struct Shed {
+    pub priority: ClientPriority,
+    pub in_flight: usize,
+    pub capacity: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PriorityCounters {
+    pub admitted: u64,
+    pub queued: u64,
+    pub shed: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AdmissionStats {
+    pub enabled: bool,
+    pub in_flight: usize,
+    pub eligible_accounts: usize,
+    pub capacity: usize,
+    pub high: PriorityCounters,
+    pub normal: PriorityCounters,
+    pub low: PriorityCounters,
+}
+
+/// 该优先级允许的最大进行中请求数；None 表示不限
+fn admit_limit(priority: ClientPriority, capacity: usize, cfg: &AdmissionConfig) -> Option<usize> {
+    let share = match priority {
+        ClientPriority::High => return None,
+        ClientPriority::Normal => cfg.normal_priority_share,
+        ClientPriority::Low => cfg.low_priority_share,
+    };
+    // 至少放行 1 个，保证账号池为空时请求仍能拿到真实的上游错误
+    Some(((capacity as f32 * share.clamp(0.0, 1.0)).floor() as usize).max(1))
+}
+
+fn try_admit(limit: Option<usize>) -> bool {
+    let mut current = IN_FLIGHT.load(Ordering::Acquire);
+    loop {
+        if limit.is_some_and(|limit| current >= limit) {
+            return false;
+        }
+        match IN_FLIGHT.compare_exchange_weak(current, current + 1, Ordering::AcqRel, Ordering::Acquire) {
+            Ok(_) => return true,
+            Err(actual) => current = actual,
+        }
+    }
+}
+
+fn bump(counters: &[AtomicU64; 3], priority: ClientPriority) {
+    counters[priority.index()].fetch_add(1, Ordering::Relaxed);
+}
+
+/// 申请放行；超出份额时排队，超时后返回 Shed
+pub async fn acquire(
+    priority: ClientPriority,
+    eligible_accounts: usize,
+) -> Result<AdmissionPermit, Shed> {
+    let cfg = crate::proxy::config::get_admission_config();
+    let capacity = eligible_accounts.saturating_mul(cfg.max_concurrent_per_account.max(1));
+    let limit = if cfg.enabled {
+        admit_limit(priority, capacity, &cfg)
+    } else {
+        None
+    };
+
+    if try_admit(limit) {
+        bump(&COUNTERS.admitted, priority);
+        return Ok(AdmissionPermit { _private: () });
+    }
+
+    bump(&COUNTERS.queued, priority);
+    let deadline = Instant::now() + Duration::from_millis(cfg.queue_timeout_ms);
+    loop {
+        // 先注册唤醒再检查，避免错过检查与等待之间的释放
+        let released = RELEASED.notified();
+        tokio::pin!(released);
+        released.as_mut().enable();
+
+        if try_admit(limit) {
+            bump(&COUNTERS.admitted, priority);
+            return Ok(AdmissionPermit { _private: () });
+        }
+        if tokio::time::timeout_at(deadline, released).await.is_err() {
+            bump(&COUNTERS.shed, priority);
+            return Err(Shed {
+                priority,
+                in_flight: IN_FLIGHT.load(Ordering::Acquire),
+                capacity,
+            });
+        }
+    }
+}
+
+fn snapshot(priority: ClientPriority) -> PriorityCounters {
+    let i = priority.index();
+    PriorityCounters {
+        admitted: COUNTERS.admitted[i].load(Ordering::Relaxed),
+        queued: COUNTERS.queued[i].load(Ordering::Relaxed),
+        shed: COUNTERS.shed[i].load(Ordering::Relaxed),
+    }
+}
+
+pub fn stats(eligible_accounts: usize) -> AdmissionStats {
+    let cfg = crate::proxy::config::get_admission_config();
+    AdmissionStats {
+        enabled: cfg.enabled,
+        in_flight: IN_FLIGHT.load(Ordering::Acquire),
+        eligible_accounts,
+        capacity: eligible_accounts.saturating_mul(cfg.max_concurrent_per_account.max(1)),
+        high: snapshot(ClientPriority::High),
+        normal: snapshot(ClientPriority::Normal),
+        low: snapshot(ClientPriority::Low),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_admit_limits_by_priority() {
+        let cfg = AdmissionConfig::default();
+        // 2 个账号 × 4 = 8
+        assert_eq!(admit_limit(ClientPriority::High, 8, &cfg), None);
+        assert_eq!(admit_limit(ClientPriority::Normal, 8, &cfg), Some(6));
+        assert_eq!(admit_limit(ClientPriority::Low, 8, &cfg), Some(4));
+        // 账号池为空时仍放行 1 个
+        assert_eq!(admit_limit(ClientPriority::Low, 0, &cfg), Some(1));
+
+        assert_eq!(ClientPriority::parse(" HIGH "), ClientPriority::High);
+        assert_eq!(ClientPriority::parse("bogus"), ClientPriority::Normal);
+    }
+
+    #[tokio::test]
+    async fn test_low_priority_queues_then_sheds() {
+        crate::proxy::config::update_admission_config(AdmissionConfig {
+            queue_timeout_ms: 50,
+            ..AdmissionConfig::default()
+        });
+        let shed_before = snapshot(ClientPriority::Low).shed;
+
+        // 1 个账号: 容量 4，low 份额 2
+        let mut held = vec![
+            acquire(ClientPriority::High, 1).await.unwrap(),
+            acquire(ClientPriority::High, 1).await.unwrap(),
+        ];
+        assert!(acquire(ClientPriority::Low, 1).await.is_err());
+        assert_eq!(snapshot(ClientPriority::Low).shed, shed_before + 1);
+        // high 不受份额限制
+        held.push(acquire(ClientPriority::High, 1).await.unwrap());
+
+        // 释放后排队中的 low 请求被放行
+        let waiter = tokio::spawn(acquire(ClientPriority::Low, 1));
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        held.clear();
+        assert!(waiter.await.unwrap().is_ok());
+    }
+}
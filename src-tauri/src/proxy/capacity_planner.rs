@@ -0,0 +1,183 @@
+// 账号池容量规划
+// 按 capacity_alerts.min_available 为模型设定最低可用账号数 (如 claude-sonnet-4-5 至少 3 个)，
+// 后台清理任务每轮将模型可用性矩阵与阈值比较，低于阈值时在用户遇到错误前发出通知。
+// 模型按配额分组统计 (见 normalize_to_standard_id)，同一次低于阈值只通知一次，恢复后再次低于阈值才会重新通知。
+// 投递渠道: 系统通知 (日志)、最近事件、前端事件 models://capacity-alert，以及可选的 webhook。
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use crate::models::CapacityAlertConfig;
+use crate::proxy::model_availability::ModelAvailability;
+
+pub const ALERT_EVENT: &str = "models://capacity-alert";
+const WEBHOOK_TIMEOUT_SECS: u64 = 15;
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct CapacityStatus {
+    /// 配置中的模型名
+    pub model: String,
+    /// 统计所用的配额分组
+    pub group: String,
+    pub required: usize,
+    pub available: usize,
+    pub breached: bool,
+}
+
+/// 当前低于阈值的模型 (避免重复通知)
+static BREACHED: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// 阈值模型对应的配额分组
+pub fn group_of(model: &str) -> String {
+    crate::proxy::common::model_mapping::normalize_to_standard_id(model)
+        .unwrap_or_else(|| model.to_string())
+}
+
+/// 需要统计可用性的配额分组
+pub fn groups(cfg: &CapacityAlertConfig) -> Vec<String> {
+    let mut groups: Vec<String> = cfg.min_available.keys().map(|m| group_of(m)).collect();
+    groups.sort();
+    groups.dedup();
+    groups
+}
+
+/// 将可用性矩阵与阈值比较
+pub fn evaluate(report: &[ModelAvailability], cfg: &CapacityAlertConfig) -> Vec<CapacityStatus> {
+    cfg.min_available
+        .iter()
+        .map(|(model, &required)| {
+            let group = group_of(model);
+            let available = report
+                .iter()
+                .find(|row| row.model == group)
+                .map_or(0, |row| row.available);
+            CapacityStatus {
+                model: model.clone(),
+                group,
+                required,
+                available,
+                breached: available < required,
+            }
+        })
+        .collect()
+}
+
+/// 本轮新低于阈值的模型；恢复的模型从记录中移除
+fn newly_breached(statuses: &[CapacityStatus]) -> Vec<CapacityStatus> {
+    let mut breached = BREACHED.lock().unwrap_or_else(|e| e.into_inner());
+    breached.retain(|model| statuses.iter().any(|s| &s.model == model && s.breached));
+    statuses
+        .iter()
+        .filter(|s| s.breached && breached.insert(s.model.clone()))
+        .cloned()
+        .collect()
+}
+
+fn alert_text(status: &CapacityStatus) -> String {
+    format!(
+        "Pool capacity low for {}: {} of {} required accounts available",
+        status.model, status.available, status.required
+    )
+}
+
+async fn post_webhook(url: String, status: CapacityStatus) -> Result<(), String> {
+    let text = alert_text(&status);
+    let client = crate::modules::http::reqwest_client_builder()
+        .timeout(std::time::Duration::from_secs(WEBHOOK_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| e.to_string())?;
+    client
+        .post(&url)
+        .json(&serde_json::json!({
+            "text": text,
+            "content": text,
+            "capacity": status,
+        }))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .error_for_status()
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 后台任务每轮调用：新低于阈值的模型发出通知
+pub fn observe(report: &[ModelAvailability], cfg: &CapacityAlertConfig) {
+    if !cfg.enabled {
+        return;
+    }
+    for status in newly_breached(&evaluate(report, cfg)) {
+        let text = alert_text(&status);
+        tracing::warn!("[Capacity] {}", text);
+        crate::modules::event_feed::record(
+            crate::modules::event_feed::EventKind::CapacityLow,
+            format!("{}: {}/{}", status.model, status.available, status.required),
+        );
+        crate::modules::integration::SystemManager::Headless
+            .show_notification("Account pool capacity", &text);
+        crate::modules::log_bridge::emit_event(ALERT_EVENT, status.clone());
+
+        let url = cfg.webhook_url.trim().to_string();
+        if !url.is_empty() {
+            tokio::spawn(async move {
+                if let Err(e) = post_webhook(url, status).await {
+                    tracing::warn!("[Capacity] Webhook delivery failed: {}", e);
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(model: &str, available: usize) -> ModelAvailability {
+        ModelAvailability {
+            model: model.to_string(),
+            available,
+            total: 5,
+            protected: 0,
+            cooling: 0,
+            exhausted: 5 - available,
+        }
+    }
+
+    #[test]
+    fn test_evaluate_and_notify_once() {
+        let cfg = CapacityAlertConfig {
+            enabled: true,
+            min_available: [
+                ("claude-sonnet-4-5".to_string(), 3),
+                ("gemini-3-flash".to_string(), 1),
+            ]
+            .into_iter()
+            .collect(),
+            webhook_url: String::new(),
+        };
+        assert_eq!(groups(&cfg), vec!["claude", "gemini-3-flash"]);
+
+        let report = vec![row("claude", 2), row("gemini-3-flash", 1)];
+        let statuses = evaluate(&report, &cfg);
+        assert_eq!(
+            statuses[0],
+            CapacityStatus {
+                model: "claude-sonnet-4-5".to_string(),
+                group: "claude".to_string(),
+                required: 3,
+                available: 2,
+                breached: true,
+            }
+        );
+        assert!(!statuses[1].breached);
+
+        assert_eq!(newly_breached(&statuses).len(), 1);
+        assert!(newly_breached(&statuses).is_empty());
+        // 恢复后再次低于阈值会重新通知
+        let recovered = vec![row("claude", 3), row("gemini-3-flash", 1)];
+        assert!(newly_breached(&evaluate(&recovered, &cfg)).is_empty());
+        assert_eq!(newly_breached(&statuses).len(), 1);
+    }
+}
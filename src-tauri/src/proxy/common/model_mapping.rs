@@ -181,6 +181,11 @@ pub async fn get_all_dynamic_models(
         }
     }
 
+    // 4. 上游目录同步发现的模型 (model_catalog.json)
+    for catalog_model in crate::modules::model_catalog::load_catalog().models.into_keys() {
+        model_ids.insert(catalog_model);
+    }
+
     // 5. 确保包含常用的 Gemini/画画模型 ID
     model_ids.insert("gemini-3.1-pro-low".to_string());
     
@@ -220,7 +225,7 @@ pub async fn get_all_dynamic_models(
 /// - `claude-*-sonnet-*` matches `claude-3-5-sonnet-20241022` ✓
 /// - `*-thinking` matches `claude-opus-4-5-thinking` ✓
 /// - `a*b*c` matches `a123b456c` ✓
-fn wildcard_match(pattern: &str, text: &str) -> bool {
+pub(crate) fn wildcard_match(pattern: &str, text: &str) -> bool {
     let parts: Vec<&str> = pattern.split('*').collect();
 
     // No wildcard - exact match
@@ -379,6 +384,51 @@ pub fn preferred_provider_for_model(model: &str) -> Option<AccountProvider> {
     None
 }
 
+/// Resolve the fallback models to try when `mapped_model` has no usable account.
+/// Chains are looked up by the requested model, then the mapped model, then its
+/// standard protection ID. The mapped model itself and duplicates are skipped.
+pub fn resolve_fallback_chain(
+    original_model: &str,
+    mapped_model: &str,
+    chains: &std::collections::HashMap<String, Vec<String>>,
+) -> Vec<String> {
+    let standard_id = normalize_to_standard_id(mapped_model);
+    let chain = chains
+        .get(original_model)
+        .or_else(|| chains.get(mapped_model))
+        .or_else(|| standard_id.as_deref().and_then(|id| chains.get(id)));
+
+    let mut result: Vec<String> = Vec::new();
+    for model in chain.into_iter().flatten() {
+        let model = model.trim();
+        if model.is_empty() || model == mapped_model || result.iter().any(|m| m == model) {
+            continue;
+        }
+        result.push(model.to_string());
+    }
+    result
+}
+
+/// Upstream model used for OpenAI-style image requests (dall-e-3, gpt-image-1, ...)
+pub const DEFAULT_IMAGE_MODEL: &str = "gemini-3-pro-image";
+
+/// Resolve the upstream model for an image generation / edit request.
+/// An exact custom mapping wins; Gemini image models (including size / aspect-ratio
+/// suffixes) pass through; anything else maps to `DEFAULT_IMAGE_MODEL`.
+pub fn resolve_image_model(
+    requested: &str,
+    custom_mapping: &std::collections::HashMap<String, String>,
+) -> String {
+    if let Some(target) = custom_mapping.get(requested) {
+        return target.clone();
+    }
+    let lower = requested.to_lowercase();
+    if lower.starts_with("gemini-") && lower.contains("image") {
+        return requested.to_string();
+    }
+    DEFAULT_IMAGE_MODEL.to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -527,4 +577,57 @@ mod tests {
         // Multi-wildcard: "a*b*c" (3)
         assert_eq!(resolve_model_route("a-test-b-foo-c", &custom), "multi-wild");
     }
+
+    #[test]
+    fn test_resolve_fallback_chain() {
+        let mut chains = HashMap::new();
+        chains.insert(
+            "claude-sonnet-4-5".to_string(),
+            vec![
+                "gemini-3-pro-high".to_string(),
+                "gemini-3-flash".to_string(),
+                "gemini-3-flash".to_string(),
+            ],
+        );
+        chains.insert(
+            "gemini-3-pro-high".to_string(),
+            vec!["gemini-3-pro-high".to_string(), "gemini-3-flash".to_string()],
+        );
+
+        // Requested model key wins
+        assert_eq!(
+            resolve_fallback_chain("claude-sonnet-4-5", "claude-sonnet-4-6", &chains),
+            vec!["gemini-3-pro-high", "gemini-3-flash"]
+        );
+        // Mapped model key, skipping itself
+        assert_eq!(
+            resolve_fallback_chain("gemini-3-pro-high", "gemini-3-pro-high", &chains),
+            vec!["gemini-3-flash"]
+        );
+        // Standard protection ID of the mapped model
+        assert_eq!(
+            resolve_fallback_chain("my-alias", "gemini-2.5-pro", &chains),
+            vec!["gemini-3-pro-high", "gemini-3-flash"]
+        );
+        assert!(resolve_fallback_chain("gpt-4o", "gpt-4o", &chains).is_empty());
+    }
+
+    #[test]
+    fn test_resolve_image_model() {
+        let mut mapping = std::collections::HashMap::new();
+        assert_eq!(resolve_image_model("dall-e-3", &mapping), DEFAULT_IMAGE_MODEL);
+        assert_eq!(resolve_image_model("gpt-image-1", &mapping), DEFAULT_IMAGE_MODEL);
+        assert_eq!(
+            resolve_image_model("gemini-3-pro-image-16x9-4k", &mapping),
+            "gemini-3-pro-image-16x9-4k"
+        );
+        // Image-quota protection groups all of them under the same standard ID
+        assert_eq!(
+            normalize_to_standard_id(&resolve_image_model("dall-e-2", &mapping)).as_deref(),
+            Some("gemini-3-pro-image")
+        );
+
+        mapping.insert("dall-e-3".to_string(), "gemini-3.1-flash-image".to_string());
+        assert_eq!(resolve_image_model("dall-e-3", &mapping), "gemini-3.1-flash-image");
+    }
 }
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 // use std::path::PathBuf;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::{OnceLock, RwLock};
 
 // ============================================================================
@@ -121,6 +122,725 @@ pub fn update_image_thinking_mode(mode: Option<String>) {
     }
 }
 
+// ============================================================================
+// 全局模型降级链配置存储
+// 当请求模型在所有账号上均被保护/耗尽时，按链路顺序尝试替代模型
+// ============================================================================
+static GLOBAL_FALLBACK_CHAINS: OnceLock<RwLock<HashMap<String, Vec<String>>>> = OnceLock::new();
+
+/// 获取当前模型降级链配置
+pub fn get_fallback_chains() -> HashMap<String, Vec<String>> {
+    GLOBAL_FALLBACK_CHAINS
+        .get()
+        .and_then(|lock| lock.read().ok())
+        .map(|chains| chains.clone())
+        .unwrap_or_default()
+}
+
+/// 更新全局模型降级链配置
+pub fn update_fallback_chains(chains: HashMap<String, Vec<String>>) {
+    let count = chains.len();
+    if let Some(lock) = GLOBAL_FALLBACK_CHAINS.get() {
+        if let Ok(mut cfg) = lock.write() {
+            *cfg = chains;
+            tracing::info!("[Fallback-Chain] Global config updated: {} chain(s)", count);
+        }
+    } else {
+        let _ = GLOBAL_FALLBACK_CHAINS.set(RwLock::new(chains));
+        tracing::info!("[Fallback-Chain] Global config initialized: {} chain(s)", count);
+    }
+}
+
+// ============================================================================
+// 全局订阅等级路由配置存储
+// ============================================================================
+static GLOBAL_TIER_ROUTING: OnceLock<RwLock<TierRoutingConfig>> = OnceLock::new();
+
+/// 获取当前订阅等级路由配置
+pub fn get_tier_routing() -> TierRoutingConfig {
+    GLOBAL_TIER_ROUTING
+        .get()
+        .and_then(|lock| lock.read().ok())
+        .map(|cfg| cfg.clone())
+        .unwrap_or_default()
+}
+
+/// 更新全局订阅等级路由配置
+pub fn update_tier_routing(config: TierRoutingConfig) {
+    if let Some(lock) = GLOBAL_TIER_ROUTING.get() {
+        if let Ok(mut cfg) = lock.write() {
+            *cfg = config;
+            tracing::info!(
+                "[Tier-Routing] Global config updated: {} premium pattern(s), {} pinned model(s)",
+                cfg.premium_models.len(),
+                cfg.model_tiers.len()
+            );
+        }
+    } else {
+        let _ = GLOBAL_TIER_ROUTING.set(RwLock::new(config));
+    }
+}
+
+// ============================================================================
+// 全局配额过期阈值
+// ============================================================================
+const DEFAULT_QUOTA_STALENESS_MINUTES: u32 = 60;
+static GLOBAL_QUOTA_STALENESS_MINUTES: AtomicU32 = AtomicU32::new(DEFAULT_QUOTA_STALENESS_MINUTES);
+
+/// 获取配额过期阈值 (分钟，0 = 不过期)
+pub fn get_quota_staleness_minutes() -> u32 {
+    GLOBAL_QUOTA_STALENESS_MINUTES.load(Ordering::Relaxed)
+}
+
+/// 更新配额过期阈值
+pub fn update_quota_staleness_minutes(minutes: u32) {
+    if GLOBAL_QUOTA_STALENESS_MINUTES.swap(minutes, Ordering::Relaxed) != minutes {
+        tracing::info!("[Quota] Staleness threshold updated: {} min", minutes);
+    }
+}
+
+// ============================================================================
+// 全局上游会话池配置存储
+// ============================================================================
+static GLOBAL_SESSION_POOL: OnceLock<RwLock<SessionPoolConfig>> = OnceLock::new();
+
+/// 获取当前上游会话池配置
+pub fn get_session_pool_config() -> SessionPoolConfig {
+    GLOBAL_SESSION_POOL
+        .get()
+        .and_then(|lock| lock.read().ok())
+        .map(|cfg| cfg.clone())
+        .unwrap_or_default()
+}
+
+/// 更新全局上游会话池配置
+pub fn update_session_pool_config(config: SessionPoolConfig) {
+    if let Some(lock) = GLOBAL_SESSION_POOL.get() {
+        if let Ok(mut cfg) = lock.write() {
+            *cfg = config;
+            tracing::info!(
+                "[SessionPool] Global config updated: per_account={}, rotation={}h",
+                cfg.per_account,
+                cfg.rotation_hours
+            );
+        }
+    } else {
+        let _ = GLOBAL_SESSION_POOL.set(RwLock::new(config));
+    }
+}
+
+/// 上游会话 ID (x-vscode-sessionid) 配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionPoolConfig {
+    /// 每个账号使用独立且持久化的会话 ID；关闭时所有账号共用每次启动生成的一个 ID
+    #[serde(default = "default_true")]
+    pub per_account: bool,
+
+    /// 会话 ID 轮换周期 (小时)，0 表示不轮换
+    #[serde(default = "default_session_rotation_hours")]
+    pub rotation_hours: u64,
+}
+
+impl Default for SessionPoolConfig {
+    fn default() -> Self {
+        Self {
+            per_account: true,
+            rotation_hours: default_session_rotation_hours(),
+        }
+    }
+}
+
+fn default_session_rotation_hours() -> u64 {
+    24
+}
+
+// ============================================================================
+// 全局上游熔断配置存储
+// ============================================================================
+static GLOBAL_CIRCUIT_BREAKER: OnceLock<RwLock<UpstreamCircuitBreakerConfig>> = OnceLock::new();
+
+/// 获取当前上游熔断配置
+pub fn get_circuit_breaker_config() -> UpstreamCircuitBreakerConfig {
+    GLOBAL_CIRCUIT_BREAKER
+        .get()
+        .and_then(|lock| lock.read().ok())
+        .map(|cfg| cfg.clone())
+        .unwrap_or_default()
+}
+
+/// 更新全局上游熔断配置
+pub fn update_circuit_breaker_config(config: UpstreamCircuitBreakerConfig) {
+    if let Some(lock) = GLOBAL_CIRCUIT_BREAKER.get() {
+        if let Ok(mut cfg) = lock.write() {
+            *cfg = config;
+            tracing::info!(
+                "[CircuitBreaker] Global config updated: enabled={}, threshold={}, open={}s",
+                cfg.enabled,
+                cfg.failure_threshold,
+                cfg.open_secs
+            );
+        }
+    } else {
+        let _ = GLOBAL_CIRCUIT_BREAKER.set(RwLock::new(config));
+    }
+}
+
+/// 上游端点熔断配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpstreamCircuitBreakerConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// 连续失败 (网络错误 / 5xx) 多少次后熔断该端点
+    #[serde(default = "default_circuit_failure_threshold")]
+    pub failure_threshold: u32,
+
+    /// 熔断持续时间 (秒)，到期后放行一个探测请求 (half-open)
+    #[serde(default = "default_circuit_open_secs")]
+    pub open_secs: u64,
+}
+
+impl Default for UpstreamCircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            failure_threshold: default_circuit_failure_threshold(),
+            open_secs: default_circuit_open_secs(),
+        }
+    }
+}
+
+fn default_circuit_failure_threshold() -> u32 {
+    5
+}
+
+fn default_circuit_open_secs() -> u64 {
+    30
+}
+
+// ============================================================================
+// 全局内容过滤配置存储
+// ============================================================================
+static GLOBAL_CONTENT_FILTER: OnceLock<RwLock<ContentFilterConfig>> = OnceLock::new();
+
+/// 获取当前内容过滤配置
+pub fn get_content_filter_config() -> ContentFilterConfig {
+    GLOBAL_CONTENT_FILTER
+        .get()
+        .and_then(|lock| lock.read().ok())
+        .map(|cfg| cfg.clone())
+        .unwrap_or_default()
+}
+
+/// 更新全局内容过滤配置
+pub fn update_content_filter_config(config: ContentFilterConfig) {
+    if let Some(lock) = GLOBAL_CONTENT_FILTER.get() {
+        if let Ok(mut cfg) = lock.write() {
+            *cfg = config;
+            tracing::info!(
+                "[ContentFilter] Global config updated: enabled={}, {} rule(s)",
+                cfg.enabled,
+                cfg.rules.len()
+            );
+        }
+    } else {
+        let _ = GLOBAL_CONTENT_FILTER.set(RwLock::new(config));
+    }
+}
+
+/// 命中规则后的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentFilterAction {
+    /// 拒绝整个请求 / 响应
+    Block,
+    /// 将命中的片段替换为 replacement
+    #[default]
+    Redact,
+    /// 不修改内容，仅记录命中 (日志 / 统计 / 响应头)
+    Annotate,
+}
+
+/// 规则作用方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentFilterDirection {
+    /// 发往上游的提示词
+    #[default]
+    Request,
+    /// 上游返回的响应
+    Response,
+    Both,
+}
+
+/// 内容过滤规则
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContentFilterRule {
+    /// 规则名 (统计与错误信息中使用，需唯一)
+    pub name: String,
+    /// 关键词 (默认，不区分大小写) 或正则表达式
+    pub pattern: String,
+    #[serde(default)]
+    pub is_regex: bool,
+    #[serde(default)]
+    pub action: ContentFilterAction,
+    #[serde(default)]
+    pub direction: ContentFilterDirection,
+    /// redact 时的替换文本，默认 "[FILTERED]"
+    #[serde(default)]
+    pub replacement: Option<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+/// 请求 / 响应内容过滤
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct ContentFilterConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 按顺序应用；命中 block 的规则后不再继续
+    #[serde(default)]
+    pub rules: Vec<ContentFilterRule>,
+}
+
+// ============================================================================
+// 按 API Key / 账号标签注入的系统提示词
+// ============================================================================
+static GLOBAL_SCOPED_SYSTEM_PROMPTS: OnceLock<RwLock<ScopedSystemPromptConfig>> = OnceLock::new();
+
+/// 获取当前按 Key / 账号标签注入的系统提示词配置
+pub fn get_scoped_system_prompts() -> ScopedSystemPromptConfig {
+    GLOBAL_SCOPED_SYSTEM_PROMPTS
+        .get()
+        .and_then(|lock| lock.read().ok())
+        .map(|cfg| cfg.clone())
+        .unwrap_or_default()
+}
+
+/// 更新按 Key / 账号标签注入的系统提示词配置
+pub fn update_scoped_system_prompts(config: ScopedSystemPromptConfig) {
+    if let Some(lock) = GLOBAL_SCOPED_SYSTEM_PROMPTS.get() {
+        if let Ok(mut cfg) = lock.write() {
+            *cfg = config;
+            tracing::info!(
+                "[Scoped-System-Prompt] Config updated: enabled={}, {} rule(s)",
+                cfg.enabled,
+                cfg.rules.len()
+            );
+        }
+    } else {
+        let _ = GLOBAL_SCOPED_SYSTEM_PROMPTS.set(RwLock::new(config));
+    }
+}
+
+/// 规则匹配对象
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ScopedPromptTarget {
+    /// 用户令牌 (API Key)，按令牌 ID 或用户名匹配
+    #[default]
+    ApiKey,
+    /// 本次请求选中账号的分组标签
+    AccountTag,
+}
+
+/// 按 API Key / 账号标签注入的提示词规则
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScopedSystemPromptRule {
+    pub name: String,
+    #[serde(default)]
+    pub target: ScopedPromptTarget,
+    /// 令牌 ID / 用户名，或账号标签
+    pub value: String,
+    /// 插入到系统指令开头 (Antigravity 身份之后)
+    #[serde(default)]
+    pub prefix: String,
+    /// 追加到系统指令末尾
+    #[serde(default)]
+    pub suffix: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+/// 网关层按 Key / 账号标签注入的系统提示词 (客户端可通过请求头 X-Skip-Prompt-Injection 跳过)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct ScopedSystemPromptConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 多条规则同时命中时按顺序拼接
+    #[serde(default)]
+    pub rules: Vec<ScopedSystemPromptRule>,
+}
+
+// ============================================================================
+// 开发者故障注入 (模拟上游故障，仅用于测试)
+// ============================================================================
+static GLOBAL_FAULT_INJECTION: OnceLock<RwLock<FaultInjectionConfig>> = OnceLock::new();
+
+/// 获取当前故障注入配置
+pub fn get_fault_injection_config() -> FaultInjectionConfig {
+    GLOBAL_FAULT_INJECTION
+        .get()
+        .and_then(|lock| lock.read().ok())
+        .map(|cfg| cfg.clone())
+        .unwrap_or_default()
+}
+
+/// 更新故障注入配置
+pub fn update_fault_injection_config(config: FaultInjectionConfig) {
+    if let Some(lock) = GLOBAL_FAULT_INJECTION.get() {
+        if let Ok(mut cfg) = lock.write() {
+            *cfg = config;
+            tracing::info!("[FaultInjection] Config updated: enabled={}", cfg.enabled);
+        }
+    } else {
+        let _ = GLOBAL_FAULT_INJECTION.set(RwLock::new(config));
+    }
+}
+
+/// 开发者模式：允许客户端通过 X-Antigravity-Fault 请求头注入模拟的上游故障
+/// (也可通过环境变量 ABV_FAULT_INJECTION=1 开启)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct FaultInjectionConfig {
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+// ============================================================================
+// 全局请求日志存储配置 (保留策略 / 压缩)
+// ============================================================================
+static GLOBAL_REQUEST_LOG: OnceLock<RwLock<RequestLogConfig>> = OnceLock::new();
+
+/// 获取当前请求日志保留配置
+pub fn get_request_log_config() -> RequestLogConfig {
+    GLOBAL_REQUEST_LOG
+        .get()
+        .and_then(|lock| lock.read().ok())
+        .map(|cfg| cfg.clone())
+        .unwrap_or_default()
+}
+
+/// 更新全局请求日志保留配置
+pub fn update_request_log_config(config: RequestLogConfig) {
+    if let Some(lock) = GLOBAL_REQUEST_LOG.get() {
+        if let Ok(mut cfg) = lock.write() {
+            *cfg = config;
+            tracing::info!(
+                "[RequestLog] Retention updated: {} day(s), max {} row(s), vacuum every {}h",
+                cfg.retention_days,
+                cfg.max_rows,
+                cfg.vacuum_interval_hours
+            );
+        }
+    } else {
+        let _ = GLOBAL_REQUEST_LOG.set(RwLock::new(config));
+    }
+}
+
+/// 请求日志 (SQLite: proxy_logs.db) 的保留与压缩策略，后台每小时执行一次
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RequestLogConfig {
+    /// 删除早于 N 天的日志 (0 = 不按时间清理)
+    #[serde(default = "default_log_retention_days")]
+    pub retention_days: u32,
+    /// 最多保留的行数，超出时删除最旧的 (0 = 不限)
+    #[serde(default = "default_log_max_rows")]
+    pub max_rows: u64,
+    /// 两次 VACUUM 之间的最短间隔 (小时)，仅在有可回收空间时执行 (0 = 从不)
+    #[serde(default = "default_log_vacuum_interval_hours")]
+    pub vacuum_interval_hours: u32,
+}
+
+impl Default for RequestLogConfig {
+    fn default() -> Self {
+        Self {
+            retention_days: default_log_retention_days(),
+            max_rows: default_log_max_rows(),
+            vacuum_interval_hours: default_log_vacuum_interval_hours(),
+        }
+    }
+}
+
+fn default_log_retention_days() -> u32 {
+    30
+}
+
+fn default_log_max_rows() -> u64 {
+    100_000
+}
+
+fn default_log_vacuum_interval_hours() -> u32 {
+    24
+}
+
+/// 模型计费单价
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct ModelPrice {
+    /// 每百万输入 token
+    #[serde(default)]
+    pub input_per_million: f64,
+    /// 每百万输出 token
+    #[serde(default)]
+    pub output_per_million: f64,
+    /// 每次请求的固定费用
+    #[serde(default)]
+    pub per_request: f64,
+}
+
+/// 用量计费导出 (按 API Key / 月份汇总，用于内部分摊)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BillingConfig {
+    #[serde(default = "default_billing_currency")]
+    pub currency: String,
+    /// 键为模型名，支持 * 通配 (精确匹配优先，其次最长的通配规则)；未命中的模型按 0 计价
+    #[serde(default)]
+    pub prices: HashMap<String, ModelPrice>,
+}
+
+impl Default for BillingConfig {
+    fn default() -> Self {
+        Self {
+            currency: default_billing_currency(),
+            prices: HashMap::new(),
+        }
+    }
+}
+
+fn default_billing_currency() -> String {
+    "USD".to_string()
+}
+
+// ============================================================================
+// 全局上游超时配置存储
+// ============================================================================
+static GLOBAL_UPSTREAM_TIMEOUTS: OnceLock<RwLock<UpstreamTimeoutsConfig>> = OnceLock::new();
+
+/// 获取当前上游超时配置
+pub fn get_upstream_timeouts() -> UpstreamTimeoutsConfig {
+    GLOBAL_UPSTREAM_TIMEOUTS
+        .get()
+        .and_then(|lock| lock.read().ok())
+        .map(|cfg| cfg.clone())
+        .unwrap_or_default()
+}
+
+/// 更新全局上游超时配置
+pub fn update_upstream_timeouts(config: UpstreamTimeoutsConfig) {
+    if let Some(lock) = GLOBAL_UPSTREAM_TIMEOUTS.get() {
+        if let Ok(mut cfg) = lock.write() {
+            *cfg = config;
+            tracing::info!(
+                "[Timeouts] Global config updated: connect={}s, read={}s, stream_idle={}s, {} model override(s)",
+                cfg.connect_secs,
+                cfg.read_secs,
+                cfg.stream_idle_secs,
+                cfg.per_model.len()
+            );
+        }
+    } else {
+        let _ = GLOBAL_UPSTREAM_TIMEOUTS.set(RwLock::new(config));
+    }
+}
+
+/// 上游请求超时 (秒，0 表示不限)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpstreamTimeoutsConfig {
+    /// 建立连接超时
+    #[serde(default = "default_connect_timeout_secs")]
+    pub connect_secs: u64,
+
+    /// 非流式请求的总时长上限
+    #[serde(default = "default_read_timeout_secs")]
+    pub read_secs: u64,
+
+    /// 流式响应两次数据之间的最大间隔 (流式请求不受 read_secs 限制)
+    #[serde(default = "default_stream_idle_timeout_secs")]
+    pub stream_idle_secs: u64,
+
+    /// 按模型覆盖，key 为精确模型名或标准 ID
+    /// (claude / gemini-3-flash / gemini-3-pro-high / gemini-3-pro-image)
+    #[serde(default)]
+    pub per_model: std::collections::HashMap<String, ModelTimeoutOverride>,
+}
+
+/// 单个模型的超时覆盖，未设置的字段沿用全局值
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModelTimeoutOverride {
+    #[serde(default)]
+    pub connect_secs: Option<u64>,
+    #[serde(default)]
+    pub read_secs: Option<u64>,
+    #[serde(default)]
+    pub stream_idle_secs: Option<u64>,
+}
+
+/// 某个模型最终生效的超时
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResolvedTimeouts {
+    pub connect_secs: u64,
+    pub read_secs: u64,
+    pub stream_idle_secs: u64,
+}
+
+impl UpstreamTimeoutsConfig {
+    /// 精确模型名优先，其次按 model_mapping 标准 ID 匹配
+    pub fn resolve(&self, model: &str) -> ResolvedTimeouts {
+        let lower = model.to_lowercase();
+        let entry = self
+            .per_model
+            .iter()
+            .find(|(k, _)| k.to_lowercase() == lower)
+            .map(|(_, v)| v)
+            .or_else(|| {
+                crate::proxy::common::model_mapping::normalize_to_standard_id(model)
+                    .and_then(|id| self.per_model.get(&id))
+            });
+        let entry = entry.cloned().unwrap_or_default();
+        ResolvedTimeouts {
+            connect_secs: entry.connect_secs.unwrap_or(self.connect_secs),
+            read_secs: entry.read_secs.unwrap_or(self.read_secs),
+            stream_idle_secs: entry.stream_idle_secs.unwrap_or(self.stream_idle_secs),
+        }
+    }
+}
+
+impl Default for UpstreamTimeoutsConfig {
+    fn default() -> Self {
+        Self {
+            connect_secs: default_connect_timeout_secs(),
+            read_secs: default_read_timeout_secs(),
+            stream_idle_secs: default_stream_idle_timeout_secs(),
+            per_model: std::collections::HashMap::new(),
+        }
+    }
+}
+
+fn default_connect_timeout_secs() -> u64 {
+    20
+}
+
+fn default_read_timeout_secs() -> u64 {
+    600
+}
+
+fn default_stream_idle_timeout_secs() -> u64 {
+    120
+}
+
+// ============================================================================
+// 全局准入控制配置存储
+// ============================================================================
+static GLOBAL_ADMISSION: OnceLock<RwLock<AdmissionConfig>> = OnceLock::new();
+
+/// 获取当前准入控制配置
+pub fn get_admission_config() -> AdmissionConfig {
+    GLOBAL_ADMISSION
+        .get()
+        .and_then(|lock| lock.read().ok())
+        .map(|cfg| cfg.clone())
+        .unwrap_or_default()
+}
+
+/// 更新全局准入控制配置
+pub fn update_admission_config(config: AdmissionConfig) {
+    if let Some(lock) = GLOBAL_ADMISSION.get() {
+        if let Ok(mut cfg) = lock.write() {
+            *cfg = config;
+            tracing::info!(
+                "[Admission] Global config updated: enabled={}, {} req/account, low<{:.0}%, normal<{:.0}%",
+                cfg.enabled,
+                cfg.max_concurrent_per_account,
+                cfg.low_priority_share * 100.0,
+                cfg.normal_priority_share * 100.0
+            );
+        }
+    } else {
+        let _ = GLOBAL_ADMISSION.set(RwLock::new(config));
+    }
+}
+
+/// 按 API Key 优先级的准入控制
+/// 容量 = 可用账号数 × 单账号并发上限；负载超过对应份额时低优先级请求先排队，超时后被拒绝
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdmissionConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// 单个可用账号可同时承载的请求数
+    #[serde(default = "default_admission_per_account")]
+    pub max_concurrent_per_account: usize,
+
+    /// low 优先级仅在进行中请求低于容量的该比例时放行
+    #[serde(default = "default_low_priority_share")]
+    pub low_priority_share: f32,
+
+    /// normal 优先级的放行比例 (high 始终放行)
+    #[serde(default = "default_normal_priority_share")]
+    pub normal_priority_share: f32,
+
+    /// 排队等待的最长时间 (毫秒)，0 表示不排队直接拒绝
+    #[serde(default = "default_admission_queue_timeout_ms")]
+    pub queue_timeout_ms: u64,
+}
+
+impl Default for AdmissionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_concurrent_per_account: default_admission_per_account(),
+            low_priority_share: default_low_priority_share(),
+            normal_priority_share: default_normal_priority_share(),
+            queue_timeout_ms: default_admission_queue_timeout_ms(),
+        }
+    }
+}
+
+fn default_admission_per_account() -> usize {
+    4
+}
+
+fn default_low_priority_share() -> f32 {
+    0.5
+}
+
+fn default_normal_priority_share() -> f32 {
+    0.85
+}
+
+fn default_admission_queue_timeout_ms() -> u64 {
+    10_000
+}
+
+/// 订阅等级路由配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TierRoutingConfig {
+    /// 高端模型关键字 (大小写不敏感的子串匹配)
+    /// 命中时账号按 ULTRA > PRO > FREE 优先，其余模型按配额优先、等级仅作为次级排序
+    #[serde(default = "default_premium_models")]
+    pub premium_models: Vec<String>,
+
+    /// 模型固定到指定订阅等级 (key: 模型名，支持 `*` 通配; value: 允许的等级，如 ["ULTRA"])
+    #[serde(default)]
+    pub model_tiers: HashMap<String, Vec<String>>,
+}
+
+impl Default for TierRoutingConfig {
+    fn default() -> Self {
+        Self {
+            premium_models: default_premium_models(),
+            model_tiers: HashMap::new(),
+        }
+    }
+}
+
+fn default_premium_models() -> Vec<String> {
+    vec![
+        "claude-opus-4-6".to_string(),
+        "claude-opus-4-5".to_string(),
+        "opus".to_string(),
+    ]
+}
+
 /// 全局系统提示词配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GlobalSystemPromptConfig {
@@ -156,6 +876,93 @@ impl Default for ProxyAuthMode {
     }
 }
 
+/// 管理接口角色 (按权限从低到高排序)
+/// - viewer: 只读 (统计 / 配额 / 日志)
+/// - operator: 日常操作 (切换账号 / 刷新配额 / 启停服务)
+/// - admin: 全部权限 (删除账号 / 导出 / 配置与密钥)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AdminRole {
+    #[default]
+    Viewer,
+    Operator,
+    Admin,
+}
+
+/// 附加的管理接口令牌 (admin_password / api_key 始终拥有 admin 角色)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminTokenConfig {
+    /// 备注名 (例如成员名称)，仅用于日志
+    #[serde(default)]
+    pub name: String,
+    pub token: String,
+    #[serde(default)]
+    pub role: AdminRole,
+}
+
+/// 附加监听地址 (与主端口同时启停)
+/// 例如主端口 127.0.0.1:8045 供本机使用，另开 0.0.0.0:8046 并强制鉴权供局域网使用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListenerConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    /// 该监听地址的鉴权策略；未设置时沿用全局 auth_mode
+    /// auto 按监听地址判断: 回环地址 => off，其他 => all_except_health
+    #[serde(default)]
+    pub auth_mode: Option<ProxyAuthMode>,
+    /// 提供 PEM 证书与私钥时以 HTTPS 提供服务
+    #[serde(default)]
+    pub tls: Option<ListenerTlsConfig>,
+}
+
+/// mDNS 广播配置 (服务类型 `_abvproxy._tcp`)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct MdnsConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 广播的实例名称，未设置时使用本机主机名
+    #[serde(default)]
+    pub instance_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListenerTlsConfig {
+    pub cert_path: String,
+    pub key_path: String,
+}
+
+impl ListenerConfig {
+    pub fn bind_address(&self) -> String {
+        if self.host.contains(':') {
+            format!("[{}]:{}", self.host, self.port)
+        } else {
+            format!("{}:{}", self.host, self.port)
+        }
+    }
+
+    /// 该监听地址实际生效的鉴权策略；None 表示沿用全局策略
+    pub fn resolved_auth_mode(&self) -> Option<ProxyAuthMode> {
+        match self.auth_mode {
+            Some(ProxyAuthMode::Auto) => {
+                let loopback = self.host == "localhost"
+                    || self
+                        .host
+                        .parse::<std::net::IpAddr>()
+                        .map(|ip| ip.is_loopback())
+                        .unwrap_or(false);
+                Some(if loopback {
+                    ProxyAuthMode::Off
+                } else {
+                    ProxyAuthMode::AllExceptHealth
+                })
+            }
+            ref other => other.clone(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum ZaiDispatchMode {
@@ -466,6 +1273,10 @@ pub struct ProxyConfig {
     #[serde(default)]
     pub allow_lan_access: bool,
 
+    /// 局域网访问时监听 IPv6 双栈地址 `::` (同时接受 IPv4 连接)
+    #[serde(default)]
+    pub listen_ipv6: bool,
+
     /// Authorization policy for the proxy.
     /// - off: no auth required
     /// - strict: auth required for all routes
@@ -483,6 +1294,18 @@ pub struct ProxyConfig {
     /// Web UI 管理后台密码 (可选，如未设置则使用 api_key)
     pub admin_password: Option<String>,
 
+    /// 按角色授权的附加管理令牌
+    #[serde(default)]
+    pub admin_tokens: Vec<AdminTokenConfig>,
+
+    /// 附加监听地址 (各自独立的鉴权 / TLS 设置)
+    #[serde(default)]
+    pub additional_listeners: Vec<ListenerConfig>,
+
+    /// 局域网 mDNS 广播 (供其他设备自动发现网关)
+    #[serde(default)]
+    pub mdns: MdnsConfig,
+
     /// 是否自动启动
     pub auto_start: bool,
 
@@ -490,6 +1313,52 @@ pub struct ProxyConfig {
     #[serde(default)]
     pub custom_mapping: std::collections::HashMap<String, String>,
 
+    /// 模型降级链 (key: 请求模型或映射后模型, value: 按顺序尝试的替代模型)
+    /// 例如 `claude-sonnet-4-5 -> [gemini-3-pro-high, gemini-3-flash]`
+    /// 仅在该模型于所有账号上都被配额保护或耗尽时生效
+    #[serde(default)]
+    pub fallback_chains: std::collections::HashMap<String, Vec<String>>,
+
+    /// 订阅等级路由 (高端模型优先高等级账号 / 模型固定等级)
+    #[serde(default)]
+    pub tier_routing: TierRoutingConfig,
+
+    /// 按 API Key 优先级的准入控制
+    #[serde(default)]
+    pub admission: AdmissionConfig,
+
+    /// 上游会话 ID 池 (按账号隔离 / 定期轮换)
+    #[serde(default)]
+    pub session_pool: SessionPoolConfig,
+
+    /// 上游端点熔断 (连续失败后快速失败)
+    #[serde(default)]
+    pub circuit_breaker: UpstreamCircuitBreakerConfig,
+
+    /// 上游超时 (连接 / 非流式总时长 / 流式空闲)，可按模型覆盖
+    #[serde(default)]
+    pub upstream_timeouts: UpstreamTimeoutsConfig,
+
+    /// 提示词 / 响应内容过滤规则 (拦截 / 脱敏 / 标注)
+    #[serde(default)]
+    pub content_filter: ContentFilterConfig,
+
+    /// 按 API Key / 账号标签注入的系统提示词前缀 / 后缀
+    #[serde(default)]
+    pub scoped_system_prompts: ScopedSystemPromptConfig,
+
+    /// 用量计费导出的模型单价表
+    #[serde(default)]
+    pub billing: BillingConfig,
+
+    /// 开发者故障注入 (测试客户端重试 / 账号轮换)
+    #[serde(default)]
+    pub fault_injection: FaultInjectionConfig,
+
+    /// 配额数据超过该分钟数未刷新即视为未知: 仍允许调度但会被标记，并在选中时排队刷新 (0 = 不过期)
+    #[serde(default = "default_quota_staleness_minutes")]
+    pub quota_staleness_minutes: u32,
+
     /// API 请求超时时间(秒)
     #[serde(default = "default_request_timeout")]
     pub request_timeout: u64,
@@ -498,6 +1367,10 @@ pub struct ProxyConfig {
     #[serde(default)]
     pub enable_logging: bool,
 
+    /// 请求日志的保留 / 压缩策略
+    #[serde(default)]
+    pub request_log: RequestLogConfig,
+
     /// 调试日志配置 (保存完整链路)
     #[serde(default)]
     pub debug_logging: DebugLoggingConfig,
@@ -571,14 +1444,30 @@ impl Default for ProxyConfig {
         Self {
             enabled: false,
             allow_lan_access: false, // 默认仅本机访问，隐私优先
+            listen_ipv6: false,
             auth_mode: ProxyAuthMode::default(),
             port: 8045,
             api_key: format!("sk-{}", uuid::Uuid::new_v4().simple()),
             admin_password: None,
+            admin_tokens: Vec::new(),
+            additional_listeners: Vec::new(),
+            mdns: MdnsConfig::default(),
             auto_start: false,
             custom_mapping: std::collections::HashMap::new(),
+            fallback_chains: std::collections::HashMap::new(),
+            tier_routing: TierRoutingConfig::default(),
+            admission: AdmissionConfig::default(),
+            session_pool: SessionPoolConfig::default(),
+            circuit_breaker: UpstreamCircuitBreakerConfig::default(),
+            upstream_timeouts: UpstreamTimeoutsConfig::default(),
+            content_filter: ContentFilterConfig::default(),
+            scoped_system_prompts: ScopedSystemPromptConfig::default(),
+            billing: BillingConfig::default(),
+            fault_injection: FaultInjectionConfig::default(),
+            quota_staleness_minutes: default_quota_staleness_minutes(),
             request_timeout: default_request_timeout(),
             enable_logging: true, // 默认开启，支持 token 统计功能
+            request_log: RequestLogConfig::default(),
             debug_logging: DebugLoggingConfig::default(),
             upstream_proxy: UpstreamProxyConfig::default(),
             zai: ZaiConfig::default(),
@@ -596,6 +1485,10 @@ impl Default for ProxyConfig {
     }
 }
 
+fn default_quota_staleness_minutes() -> u32 {
+    DEFAULT_QUOTA_STALENESS_MINUTES
+}
+
 fn default_request_timeout() -> u64 {
     120 // 默认 120 秒,原来 60 秒太短
 }
@@ -619,14 +1512,44 @@ fn default_zai_haiku_model() -> String {
 impl ProxyConfig {
     /// 获取实际的监听地址
     /// - allow_lan_access = false: 返回 "127.0.0.1"（默认，隐私优先）
-    /// - allow_lan_access = true: 返回 "0.0.0.0"（允许局域网访问）
+    /// - allow_lan_access = true: 返回 "0.0.0.0"（允许局域网访问），listen_ipv6 时返回双栈 "::"
     pub fn get_bind_address(&self) -> &str {
-        if self.allow_lan_access {
+        if self.allow_lan_access && self.listen_ipv6 {
+            "::"
+        } else if self.allow_lan_access {
             "0.0.0.0"
         } else {
             "127.0.0.1"
         }
     }
+
+    /// 校验附加监听地址: 端口不能与主端口或彼此重复
+    pub fn validate_listeners(&self) -> Result<(), String> {
+        let mut ports = std::collections::HashSet::from([self.port]);
+        for listener in self.additional_listeners.iter().filter(|l| l.enabled) {
+            if listener.host.trim().is_empty() || listener.port == 0 {
+                return Err(format!(
+                    "invalid_listener_config: host and port are required ({})",
+                    listener.bind_address()
+                ));
+            }
+            if !ports.insert(listener.port) {
+                return Err(format!(
+                    "invalid_listener_config: port {} is already in use by another listener",
+                    listener.port
+                ));
+            }
+            if let Some(tls) = &listener.tls {
+                if tls.cert_path.trim().is_empty() || tls.key_path.trim().is_empty() {
+                    return Err(format!(
+                        "invalid_listener_config: TLS on {} needs both cert_path and key_path",
+                        listener.bind_address()
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 /// 代理认证信息
@@ -721,4 +1644,75 @@ mod tests {
         assert_eq!(normalize_proxy_url(""), "");
         assert_eq!(normalize_proxy_url("   "), "");
     }
+
+    #[test]
+    fn test_resolve_upstream_timeouts() {
+        let mut cfg = UpstreamTimeoutsConfig::default();
+        cfg.per_model.insert(
+            "claude".to_string(),
+            ModelTimeoutOverride {
+                read_secs: Some(1800),
+                ..Default::default()
+            },
+        );
+        cfg.per_model.insert(
+            "gemini-3-flash".to_string(),
+            ModelTimeoutOverride {
+                read_secs: Some(60),
+                stream_idle_secs: Some(30),
+                ..Default::default()
+            },
+        );
+        cfg.per_model.insert(
+            "Claude-Opus-4-6-Thinking".to_string(),
+            ModelTimeoutOverride {
+                read_secs: Some(3600),
+                ..Default::default()
+            },
+        );
+
+        // 按标准 ID 匹配，未覆盖的字段沿用全局值
+        let sonnet = cfg.resolve("claude-sonnet-4-5");
+        assert_eq!(sonnet.read_secs, 1800);
+        assert_eq!(sonnet.connect_secs, 20);
+        assert_eq!(cfg.resolve("gemini-2.5-flash").stream_idle_secs, 30);
+        // 精确模型名优先 (忽略大小写)
+        assert_eq!(cfg.resolve("claude-opus-4-6-thinking").read_secs, 3600);
+        // 未匹配的模型使用全局值
+        assert_eq!(cfg.resolve("unknown-model").read_secs, 600);
+    }
+
+    #[test]
+    fn test_additional_listeners() {
+        let listener = |host: &str, port: u16, auth_mode: Option<ProxyAuthMode>| ListenerConfig {
+            enabled: true,
+            host: host.to_string(),
+            port,
+            auth_mode,
+            tls: None,
+        };
+
+        // auto 按监听地址是否为回环地址决定
+        assert!(matches!(
+            listener("127.0.0.1", 8046, Some(ProxyAuthMode::Auto)).resolved_auth_mode(),
+            Some(ProxyAuthMode::Off)
+        ));
+        assert!(matches!(
+            listener("0.0.0.0", 8046, Some(ProxyAuthMode::Auto)).resolved_auth_mode(),
+            Some(ProxyAuthMode::AllExceptHealth)
+        ));
+        assert!(listener("0.0.0.0", 8046, None).resolved_auth_mode().is_none());
+        assert_eq!(listener("::1", 8046, None).bind_address(), "[::1]:8046");
+
+        let mut cfg = ProxyConfig {
+            additional_listeners: vec![listener("0.0.0.0", 8046, Some(ProxyAuthMode::Strict))],
+            ..Default::default()
+        };
+        assert!(cfg.validate_listeners().is_ok());
+        cfg.additional_listeners.push(listener("0.0.0.0", 8045, None));
+        assert!(cfg
+            .validate_listeners()
+            .unwrap_err()
+            .starts_with("invalid_listener_config"));
+    }
 }
@@ -0,0 +1,457 @@
+// 提示词 / 响应内容过滤
+// 按用户配置的关键词 / 正则规则检查发往上游的请求和上游返回的响应：
+// - block: 拒绝请求 (400) / 拦截响应
+// - redact: 将命中的片段替换为 replacement
+// - annotate: 不修改内容，仅记录命中 (日志 / 统计 / X-Content-Filter 响应头)
+// 只检查 JSON 中的字符串值 (跳过 model / role / 签名 / base64 等字段)。
+// 流式响应逐个 SSE 事件检查：被拆分到多个事件中的文本无法匹配，block 只能截断尚未发出的部分。
+
+use crate::proxy::config::{
+    ContentFilterAction, ContentFilterConfig, ContentFilterDirection, ContentFilterRule,
+};
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use regex::{NoExpand, Regex};
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+pub const DEFAULT_REPLACEMENT: &str = "[FILTERED]";
+
+/// 命中 annotate 规则时返回的响应头 (逗号分隔的规则名)
+pub const ANNOTATION_HEADER: &str = "x-content-filter";
+
+/// 不参与过滤的字段 (标识符 / 签名 / 内联的二进制数据)
+const SKIPPED_KEYS: &[&str] = &[
+    "model",
+    "role",
+    "type",
+    "id",
+    "tool_call_id",
+    "tool_use_id",
+    "signature",
+    "thoughtSignature",
+    "thought_signature",
+    "data",
+    "mimeType",
+    "media_type",
+    "url",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Request,
+    Response,
+}
+
+struct CompiledRule {
+    name: String,
+    regex: Regex,
+    action: ContentFilterAction,
+    direction: ContentFilterDirection,
+    replacement: String,
+}
+
+impl CompiledRule {
+    fn applies_to(&self, stage: Stage) -> bool {
+        matches!(
+            (self.direction, stage),
+            (ContentFilterDirection::Both, _)
+                | (ContentFilterDirection::Request, Stage::Request)
+                | (ContentFilterDirection::Response, Stage::Response)
+        )
+    }
+}
+
+fn compile_rule(rule: &ContentFilterRule) -> Result<CompiledRule, String> {
+    if rule.name.trim().is_empty() {
+        return Err("content_filter_rule_name_empty".to_string());
+    }
+    if rule.pattern.is_empty() {
+        return Err(format!("content_filter_pattern_empty: {}", rule.name));
+    }
+    // 关键词按字面量、不区分大小写匹配
+    let pattern = if rule.is_regex {
+        rule.pattern.clone()
+    } else {
+        format!("(?i){}", regex::escape(&rule.pattern))
+    };
+    let regex = Regex::new(&pattern)
+        .map_err(|e| format!("content_filter_invalid_pattern: {}: {}", rule.name, e))?;
+    Ok(CompiledRule {
+        name: rule.name.clone(),
+        regex,
+        action: rule.action,
+        direction: rule.direction,
+        replacement: rule
+            .replacement
+            .clone()
+            .unwrap_or_else(|| DEFAULT_REPLACEMENT.to_string()),
+    })
+}
+
+/// 保存配置前校验：规则名唯一且模式可编译
+pub fn validate(config: &ContentFilterConfig) -> Result<(), String> {
+    let mut names = std::collections::HashSet::new();
+    for rule in &config.rules {
+        compile_rule(rule)?;
+        if !names.insert(rule.name.as_str()) {
+            return Err(format!("content_filter_duplicate_rule: {}", rule.name));
+        }
+    }
+    Ok(())
+}
+
+/// (编译时的配置, 已编译规则)
+type CompiledCache = Option<(ContentFilterConfig, Arc<Vec<CompiledRule>>)>;
+
+/// 按配置缓存的已编译规则，配置变化时重新编译
+static COMPILED: Lazy<RwLock<CompiledCache>> = Lazy::new(|| RwLock::new(None));
+
+fn compiled_rules(config: ContentFilterConfig) -> Arc<Vec<CompiledRule>> {
+    if let Ok(cache) = COMPILED.read() {
+        if let Some((source, rules)) = cache.as_ref() {
+            if *source == config {
+                return rules.clone();
+            }
+        }
+    }
+    let rules: Vec<CompiledRule> = config
+        .rules
+        .iter()
+        .filter(|rule| rule.enabled)
+        .filter_map(|rule| match compile_rule(rule) {
+            Ok(compiled) => Some(compiled),
+            Err(e) => {
+                tracing::warn!("[ContentFilter] Skipping rule: {}", e);
+                None
+            }
+        })
+        .collect();
+    let rules = Arc::new(rules);
+    if let Ok(mut cache) = COMPILED.write() {
+        *cache = Some((config, rules.clone()));
+    }
+    rules
+}
+
+/// 某一方向上生效的规则集
+pub struct Filter {
+    stage: Stage,
+    rules: Arc<Vec<CompiledRule>>,
+}
+
+/// 当前配置下该方向的过滤器；未启用或没有适用规则时为 None
+pub fn active(stage: Stage) -> Option<Filter> {
+    let config = crate::proxy::config::get_content_filter_config();
+    if !config.enabled {
+        return None;
+    }
+    let rules = compiled_rules(config);
+    if !rules.iter().any(|rule| rule.applies_to(stage)) {
+        return None;
+    }
+    Some(Filter { stage, rules })
+}
+
+/// 一次请求 (或一个响应) 的过滤结果
+#[derive(Debug, Default)]
+pub struct FilterOutcome {
+    /// 命中的 block 规则
+    pub blocked_by: Option<String>,
+    /// 命中的 annotate 规则
+    pub annotations: Vec<String>,
+    /// 内容是否被 redact 修改
+    pub modified: bool,
+    /// 命中的规则 (去重)，用于统计
+    hits: Vec<(String, ContentFilterAction)>,
+}
+
+impl FilterOutcome {
+    fn hit(&mut self, rule: &CompiledRule) {
+        if !self.hits.iter().any(|(name, _)| *name == rule.name) {
+            self.hits.push((rule.name.clone(), rule.action));
+        }
+    }
+}
+
+impl Filter {
+    /// 过滤单段文本，返回修改后的文本 (未修改时为 None)
+    fn apply_text(&self, text: &str, outcome: &mut FilterOutcome) -> Option<String> {
+        let mut current: Option<String> = None;
+        for rule in self.rules.iter().filter(|rule| rule.applies_to(self.stage)) {
+            let haystack = current.as_deref().unwrap_or(text);
+            if !rule.regex.is_match(haystack) {
+                continue;
+            }
+            outcome.hit(rule);
+            match rule.action {
+                ContentFilterAction::Block => {
+                    outcome.blocked_by = Some(rule.name.clone());
+                    return None;
+                }
+                ContentFilterAction::Redact => {
+                    let replaced = rule
+                        .regex
+                        .replace_all(haystack, NoExpand(&rule.replacement))
+                        .into_owned();
+                    current = Some(replaced);
+                    outcome.modified = true;
+                }
+                ContentFilterAction::Annotate => {
+                    if !outcome.annotations.contains(&rule.name) {
+                        outcome.annotations.push(rule.name.clone());
+                    }
+                }
+            }
+        }
+        current
+    }
+
+    /// 递归过滤 JSON 中的字符串值；命中 block 后立即停止
+    pub fn apply_json(&self, value: &mut Value, outcome: &mut FilterOutcome) {
+        if outcome.blocked_by.is_some() {
+            return;
+        }
+        match value {
+            Value::String(text) => {
+                if let Some(replaced) = self.apply_text(text, outcome) {
+                    *text = replaced;
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    self.apply_json(item, outcome);
+                }
+            }
+            Value::Object(map) => {
+                for (key, item) in map.iter_mut() {
+                    if !SKIPPED_KEYS.contains(&key.as_str()) {
+                        self.apply_json(item, outcome);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// 过滤一行 SSE (含换行符)；只处理 JSON 格式的 data 行
+    pub fn apply_sse_line(&self, line: &[u8], outcome: &mut FilterOutcome) -> Vec<u8> {
+        let Some(payload) = line.strip_prefix(b"data:") else {
+            return line.to_vec();
+        };
+        let Ok(mut json) = serde_json::from_slice::<Value>(payload) else {
+            return line.to_vec();
+        };
+        let modified_before = outcome.modified;
+        outcome.modified = false;
+        self.apply_json(&mut json, outcome);
+        let changed = outcome.modified;
+        outcome.modified |= modified_before;
+        if !changed || outcome.blocked_by.is_some() {
+            return line.to_vec();
+        }
+        let ending: &[u8] = if line.ends_with(b"\r\n") {
+            b"\r\n"
+        } else if line.ends_with(b"\n") {
+            b"\n"
+        } else {
+            b""
+        };
+        let mut out = b"data: ".to_vec();
+        out.extend_from_slice(json.to_string().as_bytes());
+        out.extend_from_slice(ending);
+        out
+    }
+}
+
+#[derive(Default)]
+struct RuleCounters {
+    request_hits: AtomicU64,
+    response_hits: AtomicU64,
+    blocked: AtomicU64,
+    redacted: AtomicU64,
+    annotated: AtomicU64,
+    last_hit_at: AtomicI64,
+}
+
+static METRICS: Lazy<DashMap<String, RuleCounters>> = Lazy::new(DashMap::new);
+
+/// 记录一次过滤结果中命中的规则
+pub fn record(stage: Stage, outcome: &FilterOutcome) {
+    if outcome.hits.is_empty() {
+        return;
+    }
+    let now = chrono::Utc::now().timestamp();
+    for (name, action) in &outcome.hits {
+        let counters = METRICS.entry(name.clone()).or_default();
+        let stage_counter = match stage {
+            Stage::Request => &counters.request_hits,
+            Stage::Response => &counters.response_hits,
+        };
+        stage_counter.fetch_add(1, Ordering::Relaxed);
+        let action_counter = match action {
+            ContentFilterAction::Block => &counters.blocked,
+            ContentFilterAction::Redact => &counters.redacted,
+            ContentFilterAction::Annotate => &counters.annotated,
+        };
+        action_counter.fetch_add(1, Ordering::Relaxed);
+        counters.last_hit_at.store(now, Ordering::Relaxed);
+    }
+    let names: Vec<&str> = outcome.hits.iter().map(|(name, _)| name.as_str()).collect();
+    tracing::info!(
+        "[ContentFilter] {:?} matched rule(s): {}{}",
+        stage,
+        names.join(", "),
+        if outcome.blocked_by.is_some() { " (blocked)" } else { "" }
+    );
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleStats {
+    pub name: String,
+    pub request_hits: u64,
+    pub response_hits: u64,
+    pub blocked: u64,
+    pub redacted: u64,
+    pub annotated: u64,
+    pub last_hit_at: Option<i64>,
+}
+
+/// 各规则的命中统计 (包含尚未命中的已配置规则)
+pub fn stats() -> Vec<RuleStats> {
+    let config = crate::proxy::config::get_content_filter_config();
+    let mut names: Vec<String> = config.rules.iter().map(|rule| rule.name.clone()).collect();
+    for entry in METRICS.iter() {
+        if !names.contains(entry.key()) {
+            names.push(entry.key().clone());
+        }
+    }
+    names
+        .into_iter()
+        .map(|name| match METRICS.get(&name) {
+            Some(c) => RuleStats {
+                request_hits: c.request_hits.load(Ordering::Relaxed),
+                response_hits: c.response_hits.load(Ordering::Relaxed),
+                blocked: c.blocked.load(Ordering::Relaxed),
+                redacted: c.redacted.load(Ordering::Relaxed),
+                annotated: c.annotated.load(Ordering::Relaxed),
+                last_hit_at: Some(c.last_hit_at.load(Ordering::Relaxed)).filter(|ts| *ts > 0),
+                name,
+            },
+            None => RuleStats {
+                name,
+                request_hits: 0,
+                response_hits: 0,
+                blocked: 0,
+                redacted: 0,
+                annotated: 0,
+                last_hit_at: None,
+            },
+        })
+        .collect()
+}
+
+pub fn reset_stats() {
+    METRICS.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rule(name: &str, pattern: &str, action: ContentFilterAction) -> ContentFilterRule {
+        ContentFilterRule {
+            name: name.to_string(),
+            pattern: pattern.to_string(),
+            is_regex: false,
+            action,
+            direction: ContentFilterDirection::Both,
+            replacement: None,
+            enabled: true,
+        }
+    }
+
+    fn filter(stage: Stage, rules: Vec<ContentFilterRule>) -> Filter {
+        Filter {
+            stage,
+            rules: Arc::new(rules.iter().map(|r| compile_rule(r).unwrap()).collect()),
+        }
+    }
+
+    #[test]
+    fn test_redact_and_annotate_request() {
+        let mut ticket = rule("ticket", r"PROJ-\d+", ContentFilterAction::Redact);
+        ticket.is_regex = true;
+        let f = filter(
+            Stage::Request,
+            vec![
+                rule("codename", "Bluebird", ContentFilterAction::Redact),
+                ticket,
+                rule("legal", "confidential", ContentFilterAction::Annotate),
+            ],
+        );
+        let mut body = json!({
+            "model": "bluebird-pro",
+            "messages": [{"role": "user", "content": "Project BLUEBIRD (PROJ-42) is Confidential"}]
+        });
+        let mut outcome = FilterOutcome::default();
+        f.apply_json(&mut body, &mut outcome);
+
+        assert!(outcome.modified);
+        assert!(outcome.blocked_by.is_none());
+        assert_eq!(outcome.annotations, vec!["legal".to_string()]);
+        assert_eq!(
+            body["messages"][0]["content"],
+            "Project [FILTERED] ([FILTERED]) is Confidential"
+        );
+        // 模型名等标识字段不参与过滤
+        assert_eq!(body["model"], "bluebird-pro");
+    }
+
+    #[test]
+    fn test_block_and_sse_lines() {
+        let mut response_only = rule("secret", "s3cret", ContentFilterAction::Redact);
+        response_only.direction = ContentFilterDirection::Response;
+        let f = filter(
+            Stage::Request,
+            vec![response_only.clone(), rule("deny", "drop table", ContentFilterAction::Block)],
+        );
+        let mut body = json!({"prompt": "please DROP TABLE users; s3cret"});
+        let mut outcome = FilterOutcome::default();
+        f.apply_json(&mut body, &mut outcome);
+        assert_eq!(outcome.blocked_by.as_deref(), Some("deny"));
+
+        let f = filter(Stage::Response, vec![response_only]);
+        let mut outcome = FilterOutcome::default();
+        let line = f.apply_sse_line(b"data: {\"delta\":\"the s3cret is\"}\n", &mut outcome);
+        assert_eq!(line, b"data: {\"delta\":\"the [FILTERED] is\"}\n".to_vec());
+        let done = f.apply_sse_line(b"data: [DONE]\n", &mut outcome);
+        assert_eq!(done, b"data: [DONE]\n".to_vec());
+        assert!(outcome.modified);
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_rules() {
+        let mut bad = rule("bad", "(unclosed", ContentFilterAction::Block);
+        bad.is_regex = true;
+        let config = ContentFilterConfig {
+            enabled: true,
+            rules: vec![bad],
+        };
+        assert!(validate(&config)
+            .unwrap_err()
+            .starts_with("content_filter_invalid_pattern"));
+
+        let config = ContentFilterConfig {
+            enabled: true,
+            rules: vec![
+                rule("dup", "a", ContentFilterAction::Redact),
+                rule("dup", "b", ContentFilterAction::Redact),
+            ],
+        };
+        assert!(validate(&config).is_err());
+    }
+}
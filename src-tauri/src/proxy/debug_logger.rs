@@ -11,6 +11,24 @@ fn build_filename(prefix: &str, trace_id: Option<&str>) -> String {
     format!("{}_{}_{}.json", ts, tid, prefix)
 }
 
+/// Whether `name` was produced by `build_filename` (`YYYYMMDD_HHMMSS.mmm_<trace>_<prefix>.json`)
+pub fn is_debug_log_file_name(name: &str) -> bool {
+    let Some(stem) = name.strip_suffix(".json") else {
+        return false;
+    };
+    let bytes = stem.as_bytes();
+    let digits = |range: std::ops::Range<usize>| {
+        bytes.get(range).is_some_and(|b| b.iter().all(u8::is_ascii_digit))
+    };
+    digits(0..8)
+        && bytes.get(8) == Some(&b'_')
+        && digits(9..15)
+        && bytes.get(15) == Some(&b'.')
+        && digits(16..19)
+        && bytes.get(19) == Some(&b'_')
+        && bytes.len() > 20
+}
+
 fn resolve_output_dir(cfg: &DebugLoggingConfig) -> Option<PathBuf> {
     if let Some(dir) = cfg.output_dir.as_ref() {
         return Some(PathBuf::from(dir));
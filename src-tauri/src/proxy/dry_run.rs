@@ -0,0 +1,245 @@
+// 单账号试运行 (dry-run tester)
+// 向本地代理的 /v1/messages 发送一个固定的极小请求，走完整代理管线 (鉴权 / 准入 / 模型映射 / 重试)，
+// 但账号选择被锁定到指定账号，返回耗时、响应片段与上游的真实状态码，用于在依赖某账号之前验证其可用性。
+// 锁定通过 X-Antigravity-Dry-Run 请求头传递，值为 `<进程内随机密钥>:<account_id>`，外部客户端无法伪造；
+// 上游每次调用的状态码通过 X-Antigravity-Upstream-Status 响应头带回 (逗号分隔，按调用顺序)。
+// 请求重放 (replay) 锁定账号时复用同一请求头。
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub const DRY_RUN_HEADER: &str = "x-antigravity-dry-run";
+pub const UPSTREAM_STATUS_HEADER: &str = "x-antigravity-upstream-status";
+
+const PROMPT: &str = "Reply with the single word: pong";
+const MAX_TOKENS: u32 = 16;
+const SNIPPET_CHARS: usize = 200;
+const TIMEOUT_SECS: u64 = 90;
+
+static SECRET: Lazy<String> = Lazy::new(|| uuid::Uuid::new_v4().simple().to_string());
+
+/// 单个试运行请求的作用域 (跨多次上游调用共享)
+#[derive(Debug)]
+pub struct DryRunScope {
+    account_id: String,
+    upstream_statuses: Mutex<Vec<u16>>,
+}
+
+impl DryRunScope {
+    pub fn new(account_id: String) -> Self {
+        Self {
+            account_id,
+            upstream_statuses: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn upstream_statuses(&self) -> Vec<u16> {
+        self.upstream_statuses
+            .lock()
+            .map(|s| s.clone())
+            .unwrap_or_default()
+    }
+}
+
+tokio::task_local! {
+    static SCOPE: Arc<DryRunScope>;
+}
+
+/// 在试运行作用域内执行 future，期间 get_token 只会选择被锁定的账号
+pub async fn scope<F: std::future::Future>(scope: Arc<DryRunScope>, f: F) -> F::Output {
+    SCOPE.scope(scope, f).await
+}
+
+/// 当前请求锁定的账号 (不在试运行作用域内时为 None)
+pub fn pinned_account() -> Option<String> {
+    SCOPE.try_with(|s| s.account_id.clone()).ok()
+}
+
+/// 记录一次上游调用的状态码 (不在作用域内时忽略)
+pub fn record_upstream_status(status: u16) {
+    let _ = SCOPE.try_with(|s| {
+        if let Ok(mut statuses) = s.upstream_statuses.lock() {
+            statuses.push(status);
+        }
+    });
+}
+
+/// 构造请求头的值
+pub(crate) fn header_value(account_id: &str) -> String {
+    format!("{}:{}", SECRET.as_str(), account_id)
+}
+
+/// 解析请求头，密钥不匹配时返回 None
+pub fn parse_header(value: &str) -> Option<String> {
+    let (secret, account_id) = value.split_once(':')?;
+    let account_id = account_id.trim();
+    (secret == SECRET.as_str() && !account_id.is_empty()).then(|| account_id.to_string())
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DryRunResult {
+    pub account_id: String,
+    pub email: String,
+    pub model: String,
+    pub success: bool,
+    /// 代理返回给客户端的状态码
+    pub proxy_status: u16,
+    /// 最后一次上游调用的状态码 (未到达上游时为 None)
+    pub upstream_status: Option<u16>,
+    /// 所有上游调用的状态码 (含重试 / 端点降级)
+    pub upstream_statuses: Vec<u16>,
+    pub latency_ms: u64,
+    /// 实际处理请求的账号 (X-Account-Email)
+    pub served_by: Option<String>,
+    pub snippet: String,
+    pub error: Option<String>,
+}
+
+fn truncate(text: &str) -> String {
+    let text = text.trim();
+    if text.chars().count() <= SNIPPET_CHARS {
+        return text.to_string();
+    }
+    let head: String = text.chars().take(SNIPPET_CHARS).collect();
+    format!("{}…", head)
+}
+
+/// 从 Claude 协议响应中提取文本片段或错误信息
+fn extract_snippet(body: &str) -> (String, Option<String>) {
+    let Ok(value) = serde_json::from_str::<Value>(body) else {
+        return (truncate(body), None);
+    };
+    if let Some(error) = value.get("error") {
+        let message = error
+            .get("message")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| error.to_string());
+        return (String::new(), Some(message));
+    }
+    let text: String = value
+        .get("content")
+        .and_then(Value::as_array)
+        .map(|blocks| {
+            blocks
+                .iter()
+                .filter_map(|b| b.get("text").and_then(Value::as_str))
+                .collect::<Vec<_>>()
+                .join("")
+        })
+        .unwrap_or_default();
+    (truncate(&text), None)
+}
+
+pub(crate) fn parse_statuses(value: Option<&str>) -> Vec<u16> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|s| s.trim().parse().ok())
+        .collect()
+}
+
+/// 通过本地代理发送试运行请求
+pub async fn run(
+    port: u16,
+    api_key: &str,
+    account_id: &str,
+    model: &str,
+) -> Result<DryRunResult, String> {
+    let account = crate::modules::account::load_account(account_id)?;
+    let body = json!({
+        "model": model,
+        "max_tokens": MAX_TOKENS,
+        "stream": false,
+        "messages": [{"role": "user", "content": PROMPT}],
+    });
+
+    // 本地回环请求不走系统代理
+    let client = rquest::Client::builder()
+        .timeout(Duration::from_secs(TIMEOUT_SECS))
+        .no_proxy()
+        .build()
+        .unwrap_or_else(|_| rquest::Client::new());
+
+    tracing::info!("[DryRun] Testing {} with model {}", account.email, model);
+    let start = Instant::now();
+    let response = client
+        .post(format!("http://127.0.0.1:{}/v1/messages", port))
+        .header("Content-Type", "application/json")
+        .header("anthropic-version", "2023-06-01")
+        .header("Authorization", format!("Bearer {}", api_key))
+        .header(DRY_RUN_HEADER, header_value(account_id))
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("proxy_request_failed: {}", e))?;
+
+    let proxy_status = response.status().as_u16();
+    let header = |name: &str| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    let upstream_statuses = parse_statuses(header(UPSTREAM_STATUS_HEADER).as_deref());
+    let served_by = header("x-account-email");
+    let text = response.text().await.unwrap_or_default();
+    let latency_ms = start.elapsed().as_millis() as u64;
+
+    let (snippet, error) = extract_snippet(&text);
+    let success = (200..300).contains(&proxy_status) && error.is_none();
+    tracing::info!(
+        "[DryRun] {} / {}: HTTP {} (upstream {:?}) in {}ms",
+        account.email,
+        model,
+        proxy_status,
+        upstream_statuses,
+        latency_ms
+    );
+
+    Ok(DryRunResult {
+        account_id: account_id.to_string(),
+        email: account.email,
+        model: model.to_string(),
+        success,
+        proxy_status,
+        upstream_status: upstream_statuses.last().copied(),
+        upstream_statuses,
+        latency_ms,
+        served_by,
+        snippet,
+        error,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_header_and_scope() {
+        assert_eq!(parse_header(&header_value("acc1")).as_deref(), Some("acc1"));
+        assert!(parse_header("wrong:acc1").is_none());
+        assert!(parse_header(&header_value(" ")).is_none());
+
+        assert!(pinned_account().is_none());
+        let dry_run = Arc::new(DryRunScope::new("acc1".to_string()));
+        let pinned = scope(dry_run.clone(), async {
+            record_upstream_status(429);
+            record_upstream_status(200);
+            pinned_account()
+        })
+        .await;
+        assert_eq!(pinned.as_deref(), Some("acc1"));
+        assert_eq!(dry_run.upstream_statuses(), vec![429, 200]);
+
+        let (snippet, error) = extract_snippet(r#"{"content":[{"type":"text","text":"pong"}]}"#);
+        assert_eq!((snippet.as_str(), error), ("pong", None));
+        let (_, error) = extract_snippet(r#"{"error":{"message":"quota exhausted"}}"#);
+        assert_eq!(error.as_deref(), Some("quota exhausted"));
+    }
+}
@@ -0,0 +1,279 @@
+// 开发者模式：按请求注入模拟的上游故障
+// 客户端通过 X-Antigravity-Fault 请求头指定故障，用于在集成测试中验证账号轮换 / 排队，
+// 或排查客户端自身的重试逻辑。仅在 proxy.fault_injection.enabled 或环境变量 ABV_FAULT_INJECTION=1 时生效。
+//
+// 请求头格式：逗号分隔多个故障，每个故障为 `类型[;键=值]*`
+//   429;retry-after=5      上游返回指定状态码 (任意 4xx / 5xx)，可带 Retry-After
+//   disconnect;after=2048  转发 N 字节后中断响应流
+//   slow;ms=3000           延迟首字节
+// 通用参数 times=N|all：作用于本请求的前 N 次上游调用 (默认 1，便于验证轮换后成功)
+
+use bytes::Bytes;
+use futures::StreamExt;
+use rquest::Response;
+use std::sync::{Arc, Mutex};
+
+pub const FAULT_HEADER: &str = "x-antigravity-fault";
+pub const ENV_FLAG: &str = "ABV_FAULT_INJECTION";
+
+const DEFAULT_SLOW_MS: u64 = 5_000;
+/// 防止误用导致请求长时间挂起
+const MAX_SLOW_MS: u64 = 120_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FaultKind {
+    /// 直接返回指定状态码，不请求上游
+    Status { code: u16, retry_after: Option<u64> },
+    /// 响应体转发 after_bytes 字节后断开
+    Disconnect { after_bytes: usize },
+    /// 延迟返回响应
+    SlowFirstByte { delay_ms: u64 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FaultSpec {
+    pub kind: FaultKind,
+    /// 作用于前 N 次上游调用；None 表示每次
+    pub times: Option<u32>,
+}
+
+/// 单个请求内的故障计划 (跨多次上游调用共享剩余次数)
+#[derive(Debug, Default)]
+pub struct FaultPlan {
+    faults: Mutex<Vec<FaultSpec>>,
+}
+
+impl FaultPlan {
+    pub fn new(faults: Vec<FaultSpec>) -> Self {
+        Self {
+            faults: Mutex::new(faults),
+        }
+    }
+
+    /// 本次上游调用要注入的故障，并扣减剩余次数
+    fn next(&self) -> Vec<FaultKind> {
+        let Ok(mut faults) = self.faults.lock() else {
+            return Vec::new();
+        };
+        let mut active = Vec::new();
+        for fault in faults.iter_mut() {
+            match fault.times {
+                Some(0) => continue,
+                Some(ref mut n) => *n -= 1,
+                None => {}
+            }
+            active.push(fault.kind.clone());
+        }
+        active
+    }
+}
+
+tokio::task_local! {
+    static PLAN: Arc<FaultPlan>;
+}
+
+/// 在指定故障计划的作用域内执行 future
+pub async fn scope_plan<F: std::future::Future>(plan: FaultPlan, f: F) -> F::Output {
+    PLAN.scope(Arc::new(plan), f).await
+}
+
+/// 配置或环境变量开启时生效
+pub fn is_enabled() -> bool {
+    let env_enabled = std::env::var(ENV_FLAG)
+        .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false);
+    env_enabled || crate::proxy::config::get_fault_injection_config().enabled
+}
+
+fn parse_one(spec: &str) -> Result<FaultSpec, String> {
+    let mut parts = spec.split(';').map(str::trim);
+    let kind_name = parts.next().unwrap_or_default().to_ascii_lowercase();
+    let mut params = Vec::new();
+    for part in parts.filter(|p| !p.is_empty()) {
+        let (key, value) = part
+            .split_once('=')
+            .ok_or_else(|| format!("invalid_fault_param: {}", part))?;
+        params.push((key.trim().to_ascii_lowercase(), value.trim().to_string()));
+    }
+    let param = |name: &str| {
+        params
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    };
+    let number = |name: &str| -> Result<Option<u64>, String> {
+        param(name)
+            .map(|v| {
+                v.parse::<u64>()
+                    .map_err(|_| format!("invalid_fault_param: {}={}", name, v))
+            })
+            .transpose()
+    };
+
+    let kind = match kind_name.as_str() {
+        "disconnect" => FaultKind::Disconnect {
+            after_bytes: number("after")?.unwrap_or(0) as usize,
+        },
+        "slow" => FaultKind::SlowFirstByte {
+            delay_ms: number("ms")?.unwrap_or(DEFAULT_SLOW_MS).min(MAX_SLOW_MS),
+        },
+        code => match code.parse::<u16>() {
+            Ok(code) if (400..600).contains(&code) => FaultKind::Status {
+                code,
+                retry_after: number("retry-after")?,
+            },
+            _ => return Err(format!("unknown_fault: {}", kind_name)),
+        },
+    };
+    let times = match param("times") {
+        None => Some(1),
+        Some(v) if v.eq_ignore_ascii_case("all") => None,
+        Some(v) => Some(
+            v.parse::<u32>()
+                .map_err(|_| format!("invalid_fault_param: times={}", v))?,
+        ),
+    };
+    Ok(FaultSpec { kind, times })
+}
+
+/// 解析故障请求头
+pub fn parse(value: &str) -> Result<Vec<FaultSpec>, String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(parse_one)
+        .collect()
+}
+
+/// 上游调用前：取出本次要注入的故障 (不在作用域内时为空)
+pub fn take() -> Vec<FaultKind> {
+    PLAN.try_with(|plan| plan.next()).unwrap_or_default()
+}
+
+/// 构造模拟的上游错误响应
+pub fn synthetic_response(code: u16, retry_after: Option<u64>) -> Response {
+    let status =
+        axum::http::StatusCode::from_u16(code).unwrap_or(axum::http::StatusCode::TOO_MANY_REQUESTS);
+    let body = serde_json::json!({
+        "error": {
+            "code": code,
+            "message": format!("Injected fault: upstream returned {}", code),
+            "status": if code == 429 { "RESOURCE_EXHAUSTED" } else { "UNAVAILABLE" },
+        }
+    });
+    let mut builder = axum::http::Response::builder()
+        .status(status)
+        .header("content-type", "application/json")
+        .header("x-antigravity-fault-injected", "true");
+    if let Some(secs) = retry_after {
+        builder = builder.header("retry-after", secs.to_string());
+    }
+    let response = builder
+        .body(rquest::Body::from(body.to_string()))
+        .expect("static response parts are valid");
+    Response::from(response)
+}
+
+/// 包装上游响应：转发 after_bytes 字节后以错误结束响应流
+pub fn disconnect_after(response: Response, after_bytes: usize) -> Response {
+    let mut builder = axum::http::Response::builder().status(response.status());
+    for (name, value) in response.headers() {
+        if name != "content-length" {
+            builder = builder.header(name, value);
+        }
+    }
+
+    let mut remaining = after_bytes;
+    let stream = response
+        .bytes_stream()
+        .map(move |chunk| -> Result<Bytes, std::io::Error> {
+            let chunk = chunk.map_err(std::io::Error::other)?;
+            if remaining == 0 {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::ConnectionReset,
+                    "injected fault: upstream disconnected",
+                ));
+            }
+            if chunk.len() >= remaining {
+                let head = chunk.slice(..remaining);
+                remaining = 0;
+                return Ok(head);
+            }
+            remaining -= chunk.len();
+            Ok(chunk)
+        })
+        // 截断后的下一块以错误结束；上游提前结束时补一个错误
+        .chain(futures::stream::once(async {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::ConnectionReset,
+                "injected fault: upstream disconnected",
+            ))
+        }))
+        .scan(false, |failed, item| {
+            if *failed {
+                return futures::future::ready(None);
+            }
+            *failed = item.is_err();
+            futures::future::ready(Some(item))
+        });
+
+    let response = builder
+        .body(rquest::Body::wrap_stream(stream))
+        .expect("upstream response parts are valid");
+    Response::from(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_faults() {
+        let faults =
+            parse("429;retry-after=5, slow;ms=300;times=all, disconnect;after=10;times=2").unwrap();
+        assert_eq!(
+            faults,
+            vec![
+                FaultSpec {
+                    kind: FaultKind::Status {
+                        code: 429,
+                        retry_after: Some(5)
+                    },
+                    times: Some(1),
+                },
+                FaultSpec {
+                    kind: FaultKind::SlowFirstByte { delay_ms: 300 },
+                    times: None,
+                },
+                FaultSpec {
+                    kind: FaultKind::Disconnect { after_bytes: 10 },
+                    times: Some(2),
+                },
+            ]
+        );
+        assert_eq!(
+            parse("slow;ms=999999").unwrap()[0].kind,
+            FaultKind::SlowFirstByte {
+                delay_ms: MAX_SLOW_MS
+            }
+        );
+        assert!(parse("200").is_err());
+        assert!(parse("explode").is_err());
+        assert!(parse("429;retry-after=soon").is_err());
+        assert!(parse("503;bogus").is_err());
+    }
+
+    #[test]
+    fn test_plan_counts_down_per_call() {
+        let plan = FaultPlan::new(parse("503;times=2, slow;ms=1;times=all").unwrap());
+        let status = FaultKind::Status {
+            code: 503,
+            retry_after: None,
+        };
+        let slow = FaultKind::SlowFirstByte { delay_ms: 1 };
+        assert_eq!(plan.next(), vec![status.clone(), slow.clone()]);
+        assert_eq!(plan.next(), vec![status, slow.clone()]);
+        assert_eq!(plan.next(), vec![slow]);
+    }
+}
@@ -11,6 +11,13 @@ use uuid::Uuid;
 use crate::proxy::{audio::AudioProcessor, server::AppState};
 
 /// 处理音频转录请求 (OpenAI Whisper API 兼容)
+#[utoipa::path(
+    post,
+    path = "/v1/audio/transcriptions",
+    tag = "OpenAI",
+    summary = "Audio transcription",
+    request_body(content_type = "multipart/form-data", description = "Multipart form")
+)]
 pub async fn handle_audio_transcription(
     State(state): State<AppState>,
     mut multipart: Multipart,
@@ -101,6 +108,7 @@ pub async fn handle_audio_transcription(
         .get_token("text", false, None, &model)
         .await
         .map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))?;
+    tracing::Span::current().record("account_id", account_id.as_str());
 
     info!("使用账号: {}", email);
 
@@ -23,6 +23,7 @@ use crate::proxy::mappers::context_manager::ContextManager;
 use crate::proxy::mappers::estimation_calibrator::get_calibrator;
 use crate::proxy::debug_logger;
 use crate::proxy::upstream::client::mask_email;
+use crate::proxy::upstream::timeouts;
 use crate::proxy::common::client_adapter::CLIENT_ADAPTERS; // [NEW] Import Adapter Registry
 use axum::http::HeaderMap;
 use std::sync::{atomic::Ordering, Arc};
@@ -242,6 +243,13 @@ use super::common::{determine_retry_strategy, apply_retry_strategy, should_rotat
 /// 处理 Claude messages 请求
 /// 
 /// 处理 Chat 消息请求流程
+#[utoipa::path(
+    post,
+    path = "/v1/messages",
+    tag = "Claude",
+    summary = "Messages",
+    request_body = crate::proxy::mappers::claude::ClaudeRequest
+)]
 pub async fn handle_messages(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -542,7 +550,7 @@ pub async fn handle_messages(
             list.iter().map(|t| serde_json::to_value(t).unwrap_or(json!({}))).collect()
         });
 
-        let config = crate::proxy::mappers::common_utils::resolve_request_config(
+        let mut config = crate::proxy::mappers::common_utils::resolve_request_config(
             &request_for_body.model,
             &mapped_model,
             &tools_val,
@@ -557,11 +565,34 @@ pub async fn handle_messages(
         let session_id_str = crate::proxy::session_manager::SessionManager::extract_session_id(&request_for_body);
         let session_id = Some(session_id_str.as_str());
 
+        // [NEW] 模型降级链: 目标模型在所有账号上被保护/耗尽时尝试替代模型
+        let fallbacks = crate::proxy::common::model_mapping::resolve_fallback_chain(
+            &request_for_body.model,
+            &mapped_model,
+            &crate::proxy::config::get_fallback_chains(),
+        );
+
         let force_rotate_token = attempt > 0;
-        let (access_token, project_id, email, account_id, _wait_ms) = match token_manager.get_token(&config.request_type, force_rotate_token, session_id, &config.final_model).await {
-            Ok(t) => t,
+        let (access_token, project_id, email, account_id, _wait_ms) = match token_manager.get_token_with_fallback(&config.request_type, force_rotate_token, session_id, &config.final_model, &fallbacks).await {
+            Ok((t, fallback_model)) => {
+                if let Some(fallback_model) = fallback_model {
+                    crate::proxy::middleware::request_context::record_model_fallback(&mapped_model, &fallback_model);
+                    mapped_model = fallback_model;
+                    last_mapped_model = Some(mapped_model.clone());
+                    config = crate::proxy::mappers::common_utils::resolve_request_config(
+                        &request_for_body.model,
+                        &mapped_model,
+                        &tools_val,
+                        request.size.as_deref(),
+                        request.quality.as_deref(),
+                        None,
+                        None,
+                    );
+                }
+                t
+            }
             Err(e) => {
-                let safe_message = if e.contains("invalid_grant") {
+                let safe_message = if crate::error::is_invalid_grant(&e) {
                     "OAuth refresh failed (invalid_grant): refresh_token likely revoked/expired; reauthorize account(s) to restore service.".to_string()
                 } else {
                     e
@@ -582,6 +613,7 @@ pub async fn handle_messages(
                 ).into_response();
             }
         };
+        tracing::Span::current().record("account_id", account_id.as_str());
 
         last_email = Some(email.clone());
         info!("✓ Using account: {} (type: {})", email, config.request_type);
@@ -876,6 +908,11 @@ pub async fn handle_messages(
             Err(e) => {
                 last_error = e.clone();
                 debug!("Request failed on attempt {}/{}: {}", attempt + 1, max_attempts, e);
+                // 上游端点熔断中，换账号也无济于事，直接快速失败
+                if e.starts_with(crate::proxy::upstream::circuit_breaker::CIRCUIT_OPEN_ERROR) {
+                    last_status = StatusCode::SERVICE_UNAVAILABLE;
+                    break;
+                }
                 continue;
             }
         };
@@ -929,7 +966,10 @@ pub async fn handle_messages(
                     "upstream_url": upstream_url,
                 });
                 let gemini_stream = debug_logger::wrap_stream_with_debug(
-                    Box::pin(response.bytes_stream()),
+                    Box::pin(timeouts::idle_guarded(
+                        response.bytes_stream(),
+                        &request_with_mapped.model,
+                    )),
                     debug_cfg.clone(),
                     trace_id.clone(),
                     "upstream_response",
@@ -1408,6 +1448,12 @@ pub async fn handle_messages(
 }
 
 /// 列出可用模型
+#[utoipa::path(
+    get,
+    path = "/v1/models/claude",
+    tag = "Claude",
+    summary = "List models"
+)]
 pub async fn handle_list_models(State(state): State<AppState>) -> impl IntoResponse {
     use crate::proxy::common::model_mapping::get_all_dynamic_models;
 
@@ -1432,6 +1478,13 @@ pub async fn handle_list_models(State(state): State<AppState>) -> impl IntoRespo
 }
 
 /// 计算 tokens (占位符)
+#[utoipa::path(
+    post,
+    path = "/v1/messages/count_tokens",
+    tag = "Claude",
+    summary = "Count tokens",
+    request_body = crate::proxy::mappers::claude::ClaudeRequest
+)]
 pub async fn handle_count_tokens(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -1864,6 +1917,7 @@ async fn try_compress_with_summary(
         max_tokens: Some(8000),
         temperature: Some(0.3),
         tools: None,
+        tool_choice: None,
         thinking: None,
         metadata: None,
         top_p: None,
@@ -1928,6 +1982,7 @@ async fn try_compress_with_summary(
         max_tokens: original_request.max_tokens,
         temperature: original_request.temperature,
         tools: original_request.tools.clone(),
+        tool_choice: original_request.tool_choice.clone(),
         thinking: original_request.thinking.clone(),
         metadata: original_request.metadata.clone(),
         top_p: original_request.top_p,
@@ -39,8 +39,8 @@ pub fn determine_retry_strategy(
         // 429 限流错误
         429 => {
             // 优先使用服务端返回的 Retry-After
-            if let Some(delay_ms) = crate::proxy::upstream::retry::parse_retry_delay(error_text) {
-                let actual_delay = delay_ms.saturating_add(200).min(30_000); // 上限上调至 30s
+            if let Some(delay) = crate::error::AppError::from_upstream(429, error_text, None).retry_after() {
+                let actual_delay = (delay.as_millis() as u64).saturating_add(200).min(30_000); // 上限上调至 30s
                 RetryStrategy::FixedDelay(Duration::from_millis(actual_delay))
             } else {
                 // 否则使用线性退避：起始 5s，逐步增加
@@ -147,6 +147,13 @@ pub fn should_rotate_account(status_code: u16) -> bool {
 
 /// Detects model capabilities and configuration
 /// POST /v1/models/detect
+#[utoipa::path(
+    post,
+    path = "/v1/models/detect",
+    tag = "System",
+    summary = "Detect model",
+    request_body = serde_json::Value
+)]
 pub async fn handle_detect_model(
     State(state): State<AppState>,
     Json(body): Json<Value>,
@@ -464,7 +464,30 @@ fn convert_tools(tools: Option<Vec<ClaudeTool>>) -> Option<Vec<Value>> {
     }
 }
 
+/// Anthropic tool_choice -> OpenAI tool_choice / parallel_tool_calls
+fn convert_tool_choice(tool_choice: Option<&Value>) -> (Option<Value>, Option<bool>) {
+    let Some(choice) = tool_choice else {
+        return (None, None);
+    };
+    let parallel = choice
+        .get("disable_parallel_tool_use")
+        .and_then(|v| v.as_bool())
+        .map(|disabled| !disabled);
+    let converted = match choice.get("type").and_then(|v| v.as_str()) {
+        Some("auto") => Some(json!("auto")),
+        Some("any") => Some(json!("required")),
+        Some("none") => Some(json!("none")),
+        Some("tool") => choice
+            .get("name")
+            .and_then(|v| v.as_str())
+            .map(|name| json!({"type": "function", "function": {"name": name}})),
+        _ => None,
+    };
+    (converted, parallel)
+}
+
 pub fn anthropic_to_openai_request(req: ClaudeRequest) -> OpenAIRequest {
+    let (tool_choice, parallel_tool_calls) = convert_tool_choice(req.tool_choice.as_ref());
     let mut messages: Vec<OpenAIMessage> = Vec::new();
     if let Some(system) = system_prompt_to_openai_message(req.system) {
         messages.push(system);
@@ -510,8 +533,8 @@ pub fn anthropic_to_openai_request(req: ClaudeRequest) -> OpenAIRequest {
         stop: None,
         response_format: None,
         tools: convert_tools(req.tools),
-        tool_choice: None,
-        parallel_tool_calls: None,
+        tool_choice,
+        parallel_tool_calls,
         instructions: None,
         input: None,
         size: req.size,
@@ -849,6 +872,13 @@ async fn sanitize_cursor_openai_output(
     Response::from_parts(parts, Body::from(body_out))
 }
 
+#[utoipa::path(
+    post,
+    path = "/cursor/chat/completions",
+    tag = "OpenAI",
+    summary = "Cursor chat completions",
+    request_body = crate::proxy::mappers::openai::OpenAIRequest
+)]
 pub async fn handle_cursor_chat_completions(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -944,6 +974,7 @@ mod tests {
                 description: Some("run shell".to_string()),
                 input_schema: Some(json!({"type":"object","properties":{"command":{"type":"string"}}})),
             }]),
+            tool_choice: None,
             stream: false,
             max_tokens: Some(256),
             temperature: Some(0.2),
@@ -969,6 +1000,26 @@ mod tests {
             .any(|m| m.role == "tool" && m.tool_call_id.as_deref() == Some("tool_1")));
     }
 
+    #[test]
+    fn test_convert_tool_choice() {
+        assert_eq!(convert_tool_choice(None), (None, None));
+        assert_eq!(
+            convert_tool_choice(Some(&json!({"type": "any"}))),
+            (Some(json!("required")), None)
+        );
+        assert_eq!(
+            convert_tool_choice(Some(&json!({
+                "type": "tool",
+                "name": "Shell",
+                "disable_parallel_tool_use": true
+            }))),
+            (
+                Some(json!({"type": "function", "function": {"name": "Shell"}})),
+                Some(false)
+            )
+        );
+    }
+
     #[test]
     fn test_sanitize_sse_line_removes_reasoning_content() {
         let line = "data: {\"object\":\"chat.completion.chunk\",\"choices\":[{\"delta\":{\"reasoning_content\":\"hidden\",\"content\":\"ok\"}}]}\n";
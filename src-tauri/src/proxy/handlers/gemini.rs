@@ -17,12 +17,21 @@ use crate::proxy::mappers::gemini::{unwrap_response, wrap_request};
 use crate::proxy::server::AppState;
 use crate::proxy::session_manager::SessionManager;
 use crate::proxy::upstream::client::mask_email;
+use crate::proxy::upstream::timeouts;
 use axum::http::HeaderMap;
 
 const MAX_RETRY_ATTEMPTS: usize = 3;
 
 /// 处理 generateContent 和 streamGenerateContent
 /// 路径参数: model_name, method (e.g. "gemini-pro", "generateContent")
+#[utoipa::path(
+    post,
+    path = "/v1beta/models/{model}",
+    tag = "Gemini",
+    summary = "Generate",
+    params(("model" = String, Path, description = "Model name, optionally with `:action` (e.g. `gemini-2.5-pro:generateContent`)")),
+    request_body = serde_json::Value
+)]
 pub async fn handle_generate(
     State(state): State<AppState>,
     Path(model_action): Path<String>,
@@ -96,7 +105,7 @@ pub async fn handle_generate(
 
     for attempt in 0..max_attempts {
         // 3. 模型路由解析
-        let mapped_model = crate::proxy::common::model_mapping::resolve_model_route(
+        let mut mapped_model = crate::proxy::common::model_mapping::resolve_model_route(
             &model_name,
             &*state.custom_mapping.read().await,
         );
@@ -117,7 +126,7 @@ pub async fn handle_generate(
                 flattened
             });
 
-        let config = crate::proxy::mappers::common_utils::resolve_request_config(
+        let mut config = crate::proxy::mappers::common_utils::resolve_request_config(
             &model_name,
             &mapped_model,
             &tools_val,
@@ -131,17 +140,40 @@ pub async fn handle_generate(
         // 提取 SessionId (粘性指纹)
         let session_id = SessionManager::extract_gemini_session_id(&body, &model_name);
 
+        // [NEW] 模型降级链: 目标模型在所有账号上被保护/耗尽时尝试替代模型
+        let fallbacks = crate::proxy::common::model_mapping::resolve_fallback_chain(
+            &model_name,
+            &mapped_model,
+            &crate::proxy::config::get_fallback_chains(),
+        );
+
         // 关键：在重试尝试 (attempt > 0) 时强制轮换账号
         let (access_token, project_id, email, account_id, _wait_ms) = match token_manager
-            .get_token(
+            .get_token_with_fallback(
                 &config.request_type,
                 attempt > 0,
                 Some(&session_id),
                 &config.final_model,
+                &fallbacks,
             )
             .await
         {
-            Ok(t) => t,
+            Ok((t, fallback_model)) => {
+                if let Some(fallback_model) = fallback_model {
+                    crate::proxy::middleware::request_context::record_model_fallback(&mapped_model, &fallback_model);
+                    mapped_model = fallback_model;
+                    config = crate::proxy::mappers::common_utils::resolve_request_config(
+                        &model_name,
+                        &mapped_model,
+                        &tools_val,
+                        None,
+                        None,
+                        None,
+                        Some(&body),
+                    );
+                }
+                t
+            }
             Err(e) => {
                 return Err((
                     StatusCode::SERVICE_UNAVAILABLE,
@@ -149,6 +181,7 @@ pub async fn handle_generate(
                 ));
             }
         };
+        tracing::Span::current().record("account_id", account_id.as_str());
 
         last_email = Some(email.clone());
         info!("✓ Using account: {} (type: {})", email, config.request_type);
@@ -217,6 +250,10 @@ pub async fn handle_generate(
                     max_attempts,
                     e
                 );
+                // 上游端点熔断中，换账号也无济于事，直接快速失败
+                if e.starts_with(crate::proxy::upstream::circuit_breaker::CIRCUIT_OPEN_ERROR) {
+                    break;
+                }
                 continue;
             }
         };
@@ -276,7 +313,7 @@ pub async fn handle_generate(
                     "upstream_url": upstream_url,
                 });
                 let mut response_stream = debug_logger::wrap_stream_with_debug(
-                    Box::pin(response.bytes_stream()),
+                    Box::pin(timeouts::idle_guarded(response.bytes_stream(), &mapped_model)),
                     debug_cfg.clone(),
                     trace_id.clone(),
                     "upstream_response",
@@ -638,6 +675,12 @@ pub async fn handle_generate(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/v1beta/models",
+    tag = "Gemini",
+    summary = "List models"
+)]
 pub async fn handle_list_models(
     State(state): State<AppState>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
@@ -668,6 +711,13 @@ pub async fn handle_list_models(
     Ok(Json(json!({ "models": models })))
 }
 
+#[utoipa::path(
+    get,
+    path = "/v1beta/models/{model}",
+    tag = "Gemini",
+    summary = "Get model",
+    params(("model" = String, Path, description = "Model name, optionally with `:action` (e.g. `gemini-2.5-pro:generateContent`)"))
+)]
 pub async fn handle_get_model(Path(model_name): Path<String>) -> impl IntoResponse {
     Json(json!({
         "name": format!("models/{}", model_name),
@@ -675,6 +725,14 @@ pub async fn handle_get_model(Path(model_name): Path<String>) -> impl IntoRespon
     }))
 }
 
+#[utoipa::path(
+    post,
+    path = "/v1beta/models/{model}/countTokens",
+    tag = "Gemini",
+    summary = "Count tokens",
+    params(("model" = String, Path, description = "Model name, optionally with `:action` (e.g. `gemini-2.5-pro:generateContent`)")),
+    request_body = serde_json::Value
+)]
 pub async fn handle_count_tokens(
     State(state): State<AppState>,
     Path(_model_name): Path<String>,
@@ -113,6 +113,12 @@ async fn forward_mcp(
     })
 }
 
+#[utoipa::path(
+    post,
+    path = "/mcp/web_search_prime/mcp",
+    tag = "MCP",
+    summary = "Web search prime"
+)]
 pub async fn handle_web_search_prime(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -135,6 +141,12 @@ pub async fn handle_web_search_prime(
     .await
 }
 
+#[utoipa::path(
+    post,
+    path = "/mcp/web_reader/mcp",
+    tag = "MCP",
+    summary = "Web reader"
+)]
 pub async fn handle_web_reader(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -374,6 +386,12 @@ async fn handle_vision_post(state: AppState, headers: HeaderMap, body: Body) ->
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/mcp/zai-mcp-server/mcp",
+    tag = "MCP",
+    summary = "Zai mcp server"
+)]
 pub async fn handle_zai_mcp_server(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -14,6 +14,7 @@ use crate::proxy::mappers::openai::{
 use crate::proxy::debug_logger;
 use crate::proxy::server::AppState;
 use crate::proxy::upstream::client::mask_email;
+use crate::proxy::upstream::timeouts;
 
 const MAX_RETRY_ATTEMPTS: usize = 3;
 use super::common::{
@@ -24,7 +25,15 @@ use crate::proxy::session_manager::SessionManager;
 use axum::http::HeaderMap;
 use tokio::time::Duration;
 use crate::modules::account;
-
+use crate::error::AppError;
+
+#[utoipa::path(
+    post,
+    path = "/v1/chat/completions",
+    tag = "OpenAI",
+    summary = "Chat completions",
+    request_body = crate::proxy::mappers::openai::OpenAIRequest
+)]
 pub async fn handle_chat_completions(
     State(state): State<AppState>,
     headers: HeaderMap, // [CHANGED] Extract headers
@@ -272,7 +281,7 @@ pub async fn handle_chat_completions(
     let mut last_email: Option<String> = None;
 
     // 2. 模型路由解析 (移到循环外以支持在所有路径返回 X-Mapped-Model)
-    let mapped_model = crate::proxy::common::model_mapping::resolve_model_route(
+    let mut mapped_model = crate::proxy::common::model_mapping::resolve_model_route(
         &openai_req.model,
         &*state.custom_mapping.read().await,
     );
@@ -283,7 +292,7 @@ pub async fn handle_chat_completions(
             .tools
             .as_ref()
             .map(|list| list.iter().cloned().collect());
-        let config = crate::proxy::mappers::common_utils::resolve_request_config(
+        let mut config = crate::proxy::mappers::common_utils::resolve_request_config(
             &openai_req.model,
             &mapped_model,
             &tools_val,
@@ -309,16 +318,38 @@ pub async fn handle_chat_completions(
         } else {
             mapped_model.clone()
         };
+        // [NEW] 模型降级链: 目标模型在所有账号上被保护/耗尽时尝试替代模型
+        let fallbacks = crate::proxy::common::model_mapping::resolve_fallback_chain(
+            &openai_req.model,
+            &mapped_model,
+            &crate::proxy::config::get_fallback_chains(),
+        );
         let (access_token, project_id, email, account_id, _wait_ms) = match token_manager
-            .get_token(
+            .get_token_with_fallback(
                 &config.request_type,
                 attempt > 0,
                 Some(&session_id),
                 &token_target_model,
+                &fallbacks,
             )
             .await
         {
-            Ok(t) => t,
+            Ok((t, fallback_model)) => {
+                if let Some(fallback_model) = fallback_model {
+                    crate::proxy::middleware::request_context::record_model_fallback(&mapped_model, &fallback_model);
+                    mapped_model = fallback_model;
+                    config = crate::proxy::mappers::common_utils::resolve_request_config(
+                        &openai_req.model,
+                        &mapped_model,
+                        &tools_val,
+                        None,
+                        None,
+                        None,
+                        None,
+                    );
+                }
+                t
+            }
             Err(e) => {
                 // [FIX] Attach headers to error response for logging visibility
                 let headers = [("X-Mapped-Model", mapped_model.as_str())];
@@ -330,6 +361,7 @@ pub async fn handle_chat_completions(
                     .into_response());
             }
         };
+        tracing::Span::current().record("account_id", account_id.as_str());
 
         // [NEW v4.1.28] 获取完整 Token 对象用于动态规格查询
         let proxy_token = token_manager.get_token_by_id(&account_id);
@@ -489,7 +521,10 @@ pub async fn handle_chat_completions(
                 use futures::StreamExt;
                 use crate::proxy::mappers::openai::streaming::create_codex_responses_to_chat_stream;
 
-                let codex_raw_stream = Box::pin(codex_response.bytes_stream());
+                let codex_raw_stream = Box::pin(timeouts::idle_guarded(
+                    codex_response.bytes_stream(),
+                    &openai_req.model,
+                ));
                 let mut chat_stream = create_codex_responses_to_chat_stream(
                     codex_raw_stream,
                     openai_req.model.clone(),
@@ -594,6 +629,10 @@ pub async fn handle_chat_completions(
                             max_attempts,
                             e
                         );
+                        // 上游端点熔断中，换账号也无济于事，直接快速失败
+                        if e.starts_with(crate::proxy::upstream::circuit_breaker::CIRCUIT_OPEN_ERROR) {
+                            break;
+                        }
                         continue;
                     }
                 }
@@ -654,7 +693,7 @@ pub async fn handle_chat_completions(
                     "upstream_url": upstream_url,
                 });
                 let gemini_stream = debug_logger::wrap_stream_with_debug(
-                    Box::pin(response.bytes_stream()),
+                    Box::pin(timeouts::idle_guarded(response.bytes_stream(), &mapped_model)),
                     debug_cfg.clone(),
                     trace_id.clone(),
                     "upstream_response",
@@ -1148,6 +1187,13 @@ fn convert_to_codex_responses_format(body: &serde_json::Value) -> serde_json::Va
 
 /// 处理 Legacy Completions API (/v1/completions)
 /// 将 Prompt 转换为 Chat Message 格式，复用 handle_chat_completions
+#[utoipa::path(
+    post,
+    path = "/v1/completions",
+    tag = "OpenAI",
+    summary = "Completions",
+    request_body = serde_json::Value
+)]
 pub async fn handle_completions(
     State(state): State<AppState>,
     Json(mut body): Json<Value>,
@@ -1541,7 +1587,7 @@ pub async fn handle_completions(
     let mut last_email: Option<String> = None;
 
     // 2. 模型路由解析 (移到循环外以支持在所有路径返回 X-Mapped-Model)
-    let mapped_model = crate::proxy::common::model_mapping::resolve_model_route(
+    let mut mapped_model = crate::proxy::common::model_mapping::resolve_model_route(
         &openai_req.model,
         &*state.custom_mapping.read().await,
     );
@@ -1554,7 +1600,7 @@ pub async fn handle_completions(
             .tools
             .as_ref()
             .map(|list| list.iter().cloned().collect());
-        let config = crate::proxy::mappers::common_utils::resolve_request_config(
+        let mut config = crate::proxy::mappers::common_utils::resolve_request_config(
             &openai_req.model,
             &mapped_model,
             &tools_val,
@@ -1583,16 +1629,38 @@ pub async fn handle_completions(
         } else {
             mapped_model.clone()
         };
+        // [NEW] 模型降级链: 目标模型在所有账号上被保护/耗尽时尝试替代模型
+        let fallbacks = crate::proxy::common::model_mapping::resolve_fallback_chain(
+            &openai_req.model,
+            &mapped_model,
+            &crate::proxy::config::get_fallback_chains(),
+        );
         let (access_token, project_id, email, account_id, _wait_ms) = match token_manager
-            .get_token(
+            .get_token_with_fallback(
                 &config.request_type,
                 force_rotate,
                 session_id,
                 &token_target_model,
+                &fallbacks,
             )
             .await
         {
-            Ok(t) => t,
+            Ok((t, fallback_model)) => {
+                if let Some(fallback_model) = fallback_model {
+                    crate::proxy::middleware::request_context::record_model_fallback(&mapped_model, &fallback_model);
+                    mapped_model = fallback_model;
+                    config = crate::proxy::mappers::common_utils::resolve_request_config(
+                        &openai_req.model,
+                        &mapped_model,
+                        &tools_val,
+                        None,
+                        None,
+                        None,
+                        None,
+                    );
+                }
+                t
+            }
             Err(e) => {
                 return (
                     StatusCode::SERVICE_UNAVAILABLE,
@@ -1602,6 +1670,7 @@ pub async fn handle_completions(
                     .into_response()
             }
         };
+        tracing::Span::current().record("account_id", account_id.as_str());
 
         last_email = Some(email.clone());
 
@@ -1658,6 +1727,10 @@ pub async fn handle_completions(
                     max_attempts,
                     e
                 );
+                // 上游端点熔断中，换账号也无济于事，直接快速失败
+                if e.starts_with(crate::proxy::upstream::circuit_breaker::CIRCUIT_OPEN_ERROR) {
+                    break;
+                }
                 continue;
             }
         };
@@ -1673,7 +1746,7 @@ pub async fn handle_completions(
                 use axum::response::Response;
                 use futures::StreamExt;
 
-                let gemini_stream = response.bytes_stream();
+                let gemini_stream = timeouts::idle_guarded(response.bytes_stream(), &mapped_model);
 
                 // DECISION: Which stream to create?
                 // If client wants stream: give them what they asked (Legacy/Codex SSE).
@@ -1995,6 +2068,12 @@ pub async fn handle_completions(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/v1/models",
+    tag = "OpenAI",
+    summary = "List models"
+)]
 pub async fn handle_list_models(State(state): State<AppState>) -> impl IntoResponse {
     use crate::proxy::common::model_mapping::get_all_dynamic_models;
 
@@ -2068,7 +2147,7 @@ async fn intercept_chat_to_image(
     });
 
     match handle_images_generations_internal(state, img_req).await {
-        Ok((email, img_res)) => {
+        Ok((email, _mapped_model, img_res)) => {
             // Extract URL
             let mut img_markdown = String::new();
             if let Some(data) = img_res.get("data").and_then(|v| v.as_array()) {
@@ -2153,15 +2232,22 @@ async fn intercept_chat_to_image(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/v1/images/generations",
+    tag = "OpenAI",
+    summary = "Images generations",
+    request_body = serde_json::Value
+)]
 pub async fn handle_images_generations(
     State(state): State<AppState>,
     Json(body): Json<Value>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
     match handle_images_generations_internal(state, body).await {
-        Ok((email_header, openai_response)) => Ok((
+        Ok((email_header, mapped_model, openai_response)) => Ok((
             StatusCode::OK,
             [
-                ("X-Mapped-Model", "dall-e-3"),
+                ("X-Mapped-Model", mapped_model.as_str()),
                 ("X-Account-Email", email_header.as_str()),
             ],
             Json(openai_response),
@@ -2171,20 +2257,70 @@ pub async fn handle_images_generations(
     }
 }
 
+/// [FIX] Map upstream status codes correctly instead of forcing 502
+/// 按错误分类 (状态码 + error.details[].reason) 映射，上游 4xx 请求错误原样返回
+fn image_upstream_status(status: u16, body: &str, model: &str) -> StatusCode {
+    match AppError::from_upstream(status, body, Some(model)) {
+        AppError::RateLimited { .. } | AppError::QuotaExhausted { .. } => {
+            StatusCode::TOO_MANY_REQUESTS
+        }
+        AppError::UpstreamUnavailable { status: 503, .. } => StatusCode::SERVICE_UNAVAILABLE,
+        AppError::Network(_, Some(code)) if (400..500).contains(&code) => {
+            StatusCode::from_u16(code).unwrap_or(StatusCode::BAD_GATEWAY)
+        }
+        _ => StatusCode::BAD_GATEWAY,
+    }
+}
+
+/// 取不到账号: 号池非空说明所有账号的图像配额都被保护 / 耗尽，返回 429
+fn image_token_error_status(pool_size: usize) -> StatusCode {
+    if pool_size > 0 {
+        StatusCode::TOO_MANY_REQUESTS
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+}
+
+fn join_image_errors(errors: &[(StatusCode, String)]) -> String {
+    errors
+        .iter()
+        .map(|(_, e)| e.as_str())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// 全部失败时的状态码: 任一请求被限流则 429，否则取第一个失败的状态码
+fn image_error_status(errors: &[(StatusCode, String)]) -> StatusCode {
+    errors
+        .iter()
+        .map(|(status, _)| *status)
+        .find(|status| *status == StatusCode::TOO_MANY_REQUESTS)
+        .or_else(|| errors.first().map(|(status, _)| *status))
+        .unwrap_or(StatusCode::BAD_GATEWAY)
+}
+
+/// 返回 (账号邮箱, 实际使用的上游模型, OpenAI 格式响应)
 pub async fn handle_images_generations_internal(
     state: AppState,
     body: Value,
-) -> Result<(String, Value), (StatusCode, String)> {
+) -> Result<(String, String, Value), (StatusCode, String)> {
     // 1. 解析请求参数
     let prompt = body.get("prompt").and_then(|v| v.as_str()).ok_or((
         StatusCode::BAD_REQUEST,
         "Missing 'prompt' field".to_string(),
     ))?;
 
-    let model = body
+    let requested_model = body
         .get("model")
         .and_then(|v| v.as_str())
-        .unwrap_or("gemini-3-pro-image");
+        .unwrap_or(crate::proxy::common::model_mapping::DEFAULT_IMAGE_MODEL);
+    // dall-e-3 / gpt-image-1 等 OpenAI 模型名映射到上游图像模型，
+    // 同时保证配额保护按 gemini-3-pro-image 分组生效
+    let mapped_image_model = crate::proxy::common::model_mapping::resolve_image_model(
+        requested_model,
+        &*state.custom_mapping.read().await,
+    );
+    let model = mapped_image_model.as_str();
 
     let n = body.get("n").and_then(|v| v.as_u64()).unwrap_or(1) as usize;
 
@@ -2212,7 +2348,8 @@ pub async fn handle_images_generations_internal(
         .unwrap_or("vivid");
 
     info!(
-        "[Images] Received request: model={}, prompt={:.50}..., n={}, size={}, quality={}, style={}",
+        "[Images] Received request: model={} (mapped: {}), prompt={:.50}..., n={}, size={}, quality={}, style={}",
+        requested_model,
         model,
         prompt,
         n,
@@ -2262,6 +2399,7 @@ pub async fn handle_images_generations_internal(
 
         tasks.push(tokio::spawn(async move {
             let mut last_error = String::new();
+            let mut last_status = StatusCode::BAD_GATEWAY;
 
             for attempt in 0..max_attempts {
                 // 4.1 获取 Token
@@ -2272,6 +2410,7 @@ pub async fn handle_images_generations_internal(
                     Ok(t) => t,
                     Err(e) => {
                         last_error = format!("Token error: {}", e);
+                        last_status = image_token_error_status(max_pool_size);
                         if attempt < max_attempts - 1 {
                             tokio::time::sleep(Duration::from_millis(500)).await;
                             continue;
@@ -2322,6 +2461,7 @@ pub async fn handle_images_generations_internal(
                             let err_text = response.text().await.unwrap_or_default();
                             let status_code = status.as_u16();
                             last_error = format!("Upstream error {}: {}", status, err_text);
+                            last_status = image_upstream_status(status_code, &err_text, &model_to_use);
 
                             // 429/500/503 等错误进行标记和重试
                             if status_code == 429 || status_code == 503 || status_code == 500 {
@@ -2336,35 +2476,44 @@ pub async fn handle_images_generations_internal(
                                         status_code,
                                         None,
                                         &err_text,
-                                        Some("dall-e-3"),
+                                        Some(model_to_use.as_str()),
                                     )
                                     .await;
                                 continue; // Retry loop
                             }
 
                             // 其他错误直接返回
-                            return Err(last_error);
+                            return Err((last_status, last_error));
                         }
                         match response.json::<Value>().await {
                             Ok(json) => return Ok((json, email)),
-                            Err(e) => return Err(format!("Parse error: {}", e)),
+                            Err(e) => {
+                                return Err((
+                                    StatusCode::BAD_GATEWAY,
+                                    format!("Parse error: {}", e),
+                                ))
+                            }
                         }
                     }
                     Err(e) => {
                         last_error = format!("Network error: {}", e);
+                        last_status = StatusCode::BAD_GATEWAY;
                         continue;
                     }
                 }
             }
 
             // All attempts failed
-            Err(format!("Max retries exhausted. Last error: {}", last_error))
+            Err((
+                last_status,
+                format!("Max retries exhausted. Last error: {}", last_error),
+            ))
         }));
     }
 
     // 5. 收集结果
     let mut images: Vec<Value> = Vec::new();
-    let mut errors: Vec<String> = Vec::new();
+    let mut errors: Vec<(StatusCode, String)> = Vec::new();
     let mut used_email: Option<String> = None;
 
     for (idx, task) in tasks.into_iter().enumerate() {
@@ -2406,37 +2555,28 @@ pub async fn handle_images_generations_internal(
                         }
                     }
                 }
-                Err(e) => {
+                Err((status, e)) => {
                     tracing::error!("[Images] Task {} failed: {}", idx, e);
-                    errors.push(e);
+                    errors.push((status, e));
                 }
             },
             Err(e) => {
                 let err_msg = format!("Task join error: {}", e);
                 tracing::error!("[Images] Task {} join error: {}", idx, e);
-                errors.push(err_msg);
+                errors.push((StatusCode::BAD_GATEWAY, err_msg));
             }
         }
     }
 
     if images.is_empty() {
         let error_msg = if !errors.is_empty() {
-            errors.join("; ")
+            join_image_errors(&errors)
         } else {
             "No images generated".to_string()
         };
         tracing::error!("[Images] All {} requests failed. Errors: {}", n, error_msg);
 
-        // [FIX] Map upstream status codes correctly instead of forcing 502
-        let status = if error_msg.contains("429") || error_msg.contains("Quota exhausted") {
-            StatusCode::TOO_MANY_REQUESTS
-        } else if error_msg.contains("503") || error_msg.contains("Service Unavailable") {
-            StatusCode::SERVICE_UNAVAILABLE
-        } else {
-            StatusCode::BAD_GATEWAY
-        };
-
-        return Err((status, error_msg));
+        return Err((image_error_status(&errors), error_msg));
     }
 
     // 部分成功时记录警告
@@ -2445,7 +2585,7 @@ pub async fn handle_images_generations_internal(
             "[Images] Partial success: {} out of {} requests succeeded. Errors: {}",
             images.len(),
             n,
-            errors.join("; ")
+            join_image_errors(&errors)
         );
     }
 
@@ -2467,9 +2607,16 @@ pub async fn handle_images_generations_internal(
     });
 
     let email_header = used_email.unwrap_or_default();
-    Ok((email_header, openai_response))
+    Ok((email_header, clean_model_name, openai_response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/v1/images/edits",
+    tag = "OpenAI",
+    summary = "Images edits",
+    request_body(content_type = "multipart/form-data", description = "Multipart form")
+)]
 pub async fn handle_images_edits(
     State(state): State<AppState>,
     mut multipart: axum::extract::Multipart,
@@ -2588,8 +2735,12 @@ pub async fn handle_images_edits(
         _ => None, // Fallback to standard
     };
 
-    let (image_config, _) = crate::proxy::mappers::common_utils::parse_image_config_with_params(
+    let mapped_image_model = crate::proxy::common::model_mapping::resolve_image_model(
         &model,
+        &*state.custom_mapping.read().await,
+    );
+    let (image_config, model) = crate::proxy::mappers::common_utils::parse_image_config_with_params(
+        &mapped_image_model,
         size_input,
         quality_input,
         image_size_param.as_deref(), // [NEW] Pass direct image_size param
@@ -2657,16 +2808,18 @@ pub async fn handle_images_edits(
 
         tasks.push(tokio::spawn(async move {
             let mut last_error = String::new();
+            let mut last_status = StatusCode::BAD_GATEWAY;
 
             for attempt in 0..max_attempts {
                 // 4.1 获取 Token
                 let (access_token, project_id, email, account_id, _wait_ms) = match token_manager
-                    .get_token("image_gen", attempt > 0, None, "gemini-3-pro-image")
+                    .get_token("image_gen", attempt > 0, None, &model)
                     .await
                 {
                     Ok(t) => t,
                     Err(e) => {
                         last_error = format!("Token error: {}", e);
+                        last_status = image_token_error_status(max_pool_size);
                         if attempt < max_attempts - 1 {
                             tokio::time::sleep(Duration::from_millis(500)).await;
                             continue;
@@ -2723,6 +2876,7 @@ pub async fn handle_images_edits(
                             let err_text = response.text().await.unwrap_or_default();
                             let status_code = status.as_u16();
                             last_error = format!("Upstream error {}: {}", status, err_text);
+                            last_status = image_upstream_status(status_code, &err_text, &model);
 
                             // 429/500/503 等错误进行标记和重试
                             if status_code == 429 || status_code == 503 || status_code == 500 {
@@ -2737,31 +2891,40 @@ pub async fn handle_images_edits(
                                         status_code,
                                         None,
                                         &err_text,
-                                        Some("dall-e-3"),
+                                        Some(model.as_str()),
                                     )
                                     .await;
                                 continue; // Retry loop
                             }
-                            return Err(last_error);
+                            return Err((last_status, last_error));
                         }
                         match response.json::<Value>().await {
                             Ok(json) => return Ok((json, response_format.clone(), email)),
-                            Err(e) => return Err(format!("Parse error: {}", e)),
+                            Err(e) => {
+                                return Err((
+                                    StatusCode::BAD_GATEWAY,
+                                    format!("Parse error: {}", e),
+                                ))
+                            }
                         }
                     }
                     Err(e) => {
                         last_error = format!("Network error: {}", e);
+                        last_status = StatusCode::BAD_GATEWAY;
                         continue;
                     }
                 }
             }
-            Err(format!("Max retries exhausted. Last error: {}", last_error))
+            Err((
+                last_status,
+                format!("Max retries exhausted. Last error: {}", last_error),
+            ))
         }));
     }
 
     // 5. Collect Results
     let mut images: Vec<Value> = Vec::new();
-    let mut errors: Vec<String> = Vec::new();
+    let mut errors: Vec<(StatusCode, String)> = Vec::new();
     let mut used_email: Option<String> = None;
 
     for (idx, task) in tasks.into_iter().enumerate() {
@@ -2802,22 +2965,22 @@ pub async fn handle_images_edits(
                         }
                     }
                 }
-                Err(e) => {
+                Err((status, e)) => {
                     tracing::error!("[Images] Task {} failed: {}", idx, e);
-                    errors.push(e);
+                    errors.push((status, e));
                 }
             },
             Err(e) => {
                 let err_msg = format!("Task join error: {}", e);
                 tracing::error!("[Images] Task {} join error: {}", idx, e);
-                errors.push(err_msg);
+                errors.push((StatusCode::BAD_GATEWAY, err_msg));
             }
         }
     }
 
     if images.is_empty() {
         let error_msg = if !errors.is_empty() {
-            errors.join("; ")
+            join_image_errors(&errors)
         } else {
             "No images generated".to_string()
         };
@@ -2826,7 +2989,7 @@ pub async fn handle_images_edits(
             n,
             error_msg
         );
-        return Err((StatusCode::BAD_GATEWAY, error_msg));
+        return Err((image_error_status(&errors), error_msg));
     }
 
     if !errors.is_empty() {
@@ -2834,7 +2997,7 @@ pub async fn handle_images_edits(
             "[Images] Partial success: {} out of {} requests succeeded. Errors: {}",
             images.len(),
             n,
-            errors.join("; ")
+            join_image_errors(&errors)
         );
     }
 
@@ -2853,7 +3016,7 @@ pub async fn handle_images_edits(
     Ok((
         StatusCode::OK,
         [
-            ("X-Mapped-Model", "dall-e-3"),
+            ("X-Mapped-Model", model.as_str()),
             ("X-Account-Email", email_header.as_str()),
         ],
         Json(openai_response),
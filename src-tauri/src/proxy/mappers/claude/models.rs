@@ -4,7 +4,7 @@
 use serde::{Deserialize, Serialize};
 
 /// Claude API 请求
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct ClaudeRequest {
     pub model: String,
     pub messages: Vec<Message>,
@@ -12,6 +12,9 @@ pub struct ClaudeRequest {
     pub system: Option<SystemPrompt>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tools: Option<Vec<Tool>>,
+    /// auto / any / tool (指定工具) / none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<serde_json::Value>,
     #[serde(default)]
     pub stream: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -37,7 +40,7 @@ pub struct ClaudeRequest {
 }
 
 /// Thinking 配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct ThinkingConfig {
     #[serde(rename = "type")]
     pub type_: String, // "enabled" or "adaptive"
@@ -50,14 +53,14 @@ pub struct ThinkingConfig {
 
 
 /// System Prompt
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(untagged)]
 pub enum SystemPrompt {
     String(String),
     Array(Vec<SystemBlock>),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct SystemBlock {
     #[serde(rename = "type")]
     pub block_type: String,
@@ -65,13 +68,13 @@ pub struct SystemBlock {
 }
 
 /// Message
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct Message {
     pub role: String,
     pub content: MessageContent,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(untagged)]
 pub enum MessageContent {
     String(String),
@@ -79,7 +82,7 @@ pub enum MessageContent {
 }
 
 /// Content Block (Claude)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(tag = "type")]
 pub enum ContentBlock {
     #[serde(rename = "text")]
@@ -144,7 +147,7 @@ pub enum ContentBlock {
     },
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct ImageSource {
     #[serde(rename = "type")]
     pub source_type: String,
@@ -152,7 +155,7 @@ pub struct ImageSource {
     pub data: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct DocumentSource {
     #[serde(rename = "type")]
     pub source_type: String, // "base64"
@@ -161,7 +164,7 @@ pub struct DocumentSource {
 }
 
 /// Tool - supports both client tools (with input_schema) and server tools (like web_search)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct Tool {
     /// Tool type - for server tools like "web_search_20250305"
     #[serde(rename = "type")]
@@ -211,7 +214,7 @@ impl Tool {
 }
 
 /// Metadata
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct Metadata {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub user_id: Option<String>,
@@ -219,7 +222,7 @@ pub struct Metadata {
 
 /// Output Configuration (Claude API v2.0.67+)
 /// Controls effort level for model reasoning
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct OutputConfig {
     /// Effort level: "high", "medium", "low"
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -227,7 +230,7 @@ pub struct OutputConfig {
 }
 
 /// Claude API 响应
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct ClaudeResponse {
     pub id: String,
     #[serde(rename = "type")]
@@ -242,7 +245,7 @@ pub struct ClaudeResponse {
 }
 
 /// Usage
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct Usage {
     pub input_tokens: u32,
     pub output_tokens: u32,
@@ -257,14 +260,14 @@ pub struct Usage {
 // ========== Gemini 数据模型 ==========
 
 /// Gemini Content
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct GeminiContent {
     pub role: String,
     pub parts: Vec<GeminiPart>,
 }
 
 /// Gemini Part
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct GeminiPart {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub text: Option<String>,
@@ -289,7 +292,7 @@ pub struct GeminiPart {
     pub inline_data: Option<InlineData>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct FunctionCall {
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -298,7 +301,7 @@ pub struct FunctionCall {
     pub args: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct FunctionResponse {
     pub name: String,
     pub response: serde_json::Value,
@@ -306,7 +309,7 @@ pub struct FunctionResponse {
     pub id: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct InlineData {
     #[serde(rename = "mimeType")]
     pub mime_type: String,
@@ -314,7 +317,7 @@ pub struct InlineData {
 }
 
 /// Gemini 完整响应
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct GeminiResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub candidates: Option<Vec<Candidate>>,
@@ -329,7 +332,7 @@ pub struct GeminiResponse {
     pub response_id: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct Candidate {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub content: Option<GeminiContent>,
@@ -343,7 +346,7 @@ pub struct Candidate {
     pub grounding_metadata: Option<GroundingMetadata>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct UsageMetadata {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "promptTokenCount")]
@@ -362,7 +365,7 @@ pub struct UsageMetadata {
 // ========== Grounding Metadata (for googleSearch results) ==========
 
 /// Gemini Grounding Metadata - contains search results from googleSearch tool
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct GroundingMetadata {
     #[serde(rename = "webSearchQueries")]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -381,13 +384,13 @@ pub struct GroundingMetadata {
     pub search_entry_point: Option<SearchEntryPoint>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct GroundingChunk {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub web: Option<WebSource>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct WebSource {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub uri: Option<String>,
@@ -395,7 +398,7 @@ pub struct WebSource {
     pub title: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct GroundingSupport {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub segment: Option<TextSegment>,
@@ -407,7 +410,7 @@ pub struct GroundingSupport {
     pub confidence_scores: Option<Vec<f64>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct TextSegment {
     #[serde(rename = "startIndex")]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -419,7 +422,7 @@ pub struct TextSegment {
     pub text: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct SearchEntryPoint {
     #[serde(rename = "renderedContent")]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -592,6 +592,8 @@ pub fn transform_claude_request_in(
     if let Some(sys_inst) = system_instruction {
         inner_request["systemInstruction"] = sys_inst;
     }
+    // 按 API Key / 账号标签注入的提示词
+    crate::proxy::scoped_prompt::apply(&mut inner_request, token);
 
     if !generation_config.is_null() {
         println!("DEBUG: Assigning generation_config: {}", generation_config);
@@ -600,12 +602,10 @@ pub fn transform_claude_request_in(
 
     if let Some(tools_val) = tools {
         inner_request["tools"] = tools_val;
-        // 显式设置工具配置模式为 VALIDATED
-        inner_request["toolConfig"] = json!({
-            "functionCallingConfig": {
-                "mode": "VALIDATED"
-            }
-        });
+        // 默认 VALIDATED；tool_choice (any / tool / none) 映射为对应的 functionCallingConfig
+        inner_request["toolConfig"] = crate::proxy::mappers::common_utils::build_tool_config(
+            claude_req.tool_choice.as_ref(),
+        );
     }
 
 
@@ -2103,6 +2103,7 @@ mod tests {
             }],
             system: None,
             tools: None,
+            tool_choice: None,
             stream: false,
             max_tokens: None,
             temperature: None,
@@ -2123,6 +2124,94 @@ mod tests {
         assert!(body["requestId"].as_str().unwrap().starts_with("agent/"));
     }
 
+    #[test]
+    fn test_tool_choice_and_nested_tool_args() {
+        let req: ClaudeRequest = serde_json::from_value(json!({
+            "model": "claude-sonnet-4-6",
+            "max_tokens": 1024,
+            "tools": [{
+                "name": "create_issue",
+                "description": "Create an issue",
+                "input_schema": {
+                    "type": "object",
+                    "properties": {
+                        "title": {"type": "string"},
+                        "labels": {"type": "array", "items": {"type": "string"}},
+                        "meta": {
+                            "type": "object",
+                            "properties": {
+                                "assignee": {
+                                    "type": "object",
+                                    "properties": {"login": {"type": "string"}}
+                                }
+                            }
+                        }
+                    },
+                    "required": ["title"]
+                }
+            }],
+            "tool_choice": {"type": "tool", "name": "create_issue"},
+            "messages": [
+                {"role": "user", "content": "File a bug"},
+                {"role": "assistant", "content": [{
+                    "type": "tool_use",
+                    "id": "toolu_1",
+                    "name": "create_issue",
+                    "input": {
+                        "title": "Crash",
+                        "description": "stack overflow",
+                        "labels": ["bug", "p1"],
+                        "meta": {"assignee": {"login": "octo"}, "type": "internal"}
+                    }
+                }]},
+                {"role": "user", "content": [{
+                    "type": "tool_result",
+                    "tool_use_id": "toolu_1",
+                    "content": "created #42"
+                }]}
+            ]
+        }))
+        .unwrap();
+
+        let body =
+            transform_claude_request_in(&req, "test-project", false, None, "test_session", None)
+                .unwrap();
+        let request = &body["request"];
+
+        assert_eq!(
+            request["toolConfig"]["functionCallingConfig"],
+            json!({"mode": "ANY", "allowedFunctionNames": ["create_issue"]})
+        );
+
+        let decl = request["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find_map(|t| t.get("functionDeclarations"))
+            .and_then(|d| d.get(0))
+            .unwrap();
+        let login = &decl["parameters"]["properties"]["meta"]["properties"]["assignee"]["properties"]["login"];
+        assert!(login["type"].as_str().unwrap().eq_ignore_ascii_case("string"));
+
+        // 嵌套参数 (包括与 Schema 关键字同名的 title / description / type) 原样透传
+        let call = request["contents"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .flat_map(|c| c["parts"].as_array().cloned().unwrap_or_default())
+            .find_map(|p| p.get("functionCall").cloned())
+            .unwrap();
+        assert_eq!(
+            call["args"],
+            json!({
+                "title": "Crash",
+                "description": "stack overflow",
+                "labels": ["bug", "p1"],
+                "meta": {"assignee": {"login": "octo"}, "type": "internal"}
+            })
+        );
+    }
+
     #[test]
     fn test_clean_json_schema() {
         let mut schema = json!({
@@ -2200,6 +2289,7 @@ mod tests {
             ],
             system: None,
             tools: None,
+            tool_choice: None,
             stream: false,
             max_tokens: None,
             temperature: None,
@@ -2270,6 +2360,7 @@ mod tests {
             ],
             system: None,
             tools: None,
+            tool_choice: None,
             stream: false,
             max_tokens: None,
             temperature: None,
@@ -2340,6 +2431,7 @@ mod tests {
                 type_: None,
                 // cache_control: None, // removed
             }]),
+            tool_choice: None,
             stream: false,
             max_tokens: None,
             temperature: None,
@@ -2394,6 +2486,7 @@ mod tests {
             ],
             system: None,
             tools: None,
+            tool_choice: None,
             stream: false,
             max_tokens: None,
             temperature: None,
@@ -2446,6 +2539,7 @@ mod tests {
             }],
             system: None,
             tools: None,
+            tool_choice: None,
             stream: false,
             max_tokens: None,
             temperature: None,
@@ -2498,6 +2592,7 @@ mod tests {
             }],
             system: None,
             tools: None,
+            tool_choice: None,
             stream: false,
             max_tokens: None,
             temperature: None,
@@ -2689,6 +2784,7 @@ mod tests {
             }],
             system: None,
             tools: None,
+            tool_choice: None,
             stream: false,
             max_tokens: None,
             temperature: None,
@@ -2732,6 +2828,7 @@ mod tests {
             stream: false,
             system: None,
             tools: None,
+            tool_choice: None,
             metadata: None,
             output_config: None,
             size: None,
@@ -2760,6 +2857,7 @@ mod tests {
             stream: false,
             system: None,
             tools: None,
+            tool_choice: None,
             metadata: None,
             output_config: None,
             size: None,
@@ -2792,6 +2890,7 @@ mod tests {
             stream: false,
             system: None,
             tools: None,
+            tool_choice: None,
             metadata: None,
             output_config: None,
             size: None,
@@ -2832,6 +2931,7 @@ mod tests {
             stream: false,
             system: None,
             tools: None,
+            tool_choice: None,
             metadata: None,
             output_config: None,
             size: None,
@@ -2869,6 +2969,7 @@ mod tests {
             stream: false,
             system: None,
             tools: None,
+            tool_choice: None,
             metadata: None,
             output_config: None,
             size: Some("1024x1024".to_string()),
@@ -2913,6 +3014,7 @@ mod tests {
             top_k: None,
             system: None,
             tools: None,
+            tool_choice: None,
             metadata: None,
             output_config: None,
             size: None,
@@ -2961,6 +3063,7 @@ mod tests {
                     }
                 })),
             }]),
+            tool_choice: None,
             stream: false,
             max_tokens: None,
             temperature: None,
@@ -3012,6 +3115,7 @@ mod tests {
                     }
                 })),
             }]),
+            tool_choice: None,
             stream: false,
             max_tokens: None,
             temperature: None,
@@ -335,6 +335,48 @@ fn calculate_aspect_ratio_from_size(size: &str) -> &'static str {
     "1:1" // 默认回退
 }
 
+/// 将客户端的 tool_choice (OpenAI / Anthropic 格式) 转换为 Gemini toolConfig
+/// - 未指定 / auto -> VALIDATED (与原生客户端一致)
+/// - none -> NONE
+/// - required (OpenAI) / any (Anthropic) -> ANY
+/// - 指定工具 -> ANY + allowedFunctionNames
+pub fn build_tool_config(tool_choice: Option<&Value>) -> Value {
+    let (mode, forced_name) = match tool_choice {
+        Some(Value::String(choice)) => match choice.as_str() {
+            "none" => ("NONE", None),
+            "required" | "any" => ("ANY", None),
+            _ => ("VALIDATED", None),
+        },
+        Some(Value::Object(choice)) => match choice.get("type").and_then(|v| v.as_str()) {
+            Some("none") => ("NONE", None),
+            Some("any") => ("ANY", None),
+            // Anthropic: {"type":"tool","name":..}
+            // OpenAI Chat: {"type":"function","function":{"name":..}}; Responses API: {"type":"function","name":..}
+            Some("tool") | Some("function") => {
+                let name = choice
+                    .get("function")
+                    .and_then(|f| f.get("name"))
+                    .or_else(|| choice.get("name"))
+                    .and_then(|v| v.as_str());
+                match name {
+                    Some(name) => ("ANY", Some(name)),
+                    None => ("ANY", None),
+                }
+            }
+            _ => ("VALIDATED", None),
+        },
+        _ => ("VALIDATED", None),
+    };
+
+    let mut config = json!({ "mode": mode });
+    if let Some(name) = forced_name {
+        // 与工具声明中的改名保持一致
+        let name = if name == "local_shell_call" { "shell" } else { name };
+        config["allowedFunctionNames"] = json!([name]);
+    }
+    json!({ "functionCallingConfig": config })
+}
+
 /// Inject current googleSearch tool and ensure no duplicate legacy search tools
 pub fn inject_google_search_tool(body: &mut Value, mapped_model: Option<&str>) {
     if let Some(obj) = body.as_object_mut() {
@@ -547,6 +589,31 @@ mod tests {
         assert!(!config.inject_google_search);
     }
 
+    #[test]
+    fn test_build_tool_config() {
+        let mode = |choice: Option<Value>| build_tool_config(choice.as_ref())["functionCallingConfig"].clone();
+
+        assert_eq!(mode(None), json!({"mode": "VALIDATED"}));
+        assert_eq!(mode(Some(json!("auto"))), json!({"mode": "VALIDATED"}));
+        assert_eq!(mode(Some(json!("none"))), json!({"mode": "NONE"}));
+        assert_eq!(mode(Some(json!("required"))), json!({"mode": "ANY"}));
+        // Anthropic
+        assert_eq!(mode(Some(json!({"type": "any"}))), json!({"mode": "ANY"}));
+        assert_eq!(
+            mode(Some(json!({"type": "tool", "name": "get_weather"}))),
+            json!({"mode": "ANY", "allowedFunctionNames": ["get_weather"]})
+        );
+        // OpenAI Chat / Responses
+        assert_eq!(
+            mode(Some(json!({"type": "function", "function": {"name": "search"}}))),
+            json!({"mode": "ANY", "allowedFunctionNames": ["search"]})
+        );
+        assert_eq!(
+            mode(Some(json!({"type": "function", "name": "local_shell_call"}))),
+            json!({"mode": "ANY", "allowedFunctionNames": ["shell"]})
+        );
+    }
+
     #[test]
     fn test_gemini_native_tool_detection() {
         let tools = Some(vec![json!({
@@ -449,6 +449,7 @@ mod tests {
             messages: vec![],
             system: None,
             tools: None,
+            tool_choice: None,
             stream: false,
             max_tokens: None,
             temperature: None,
@@ -499,6 +499,9 @@ pub fn wrap_request(
                 "parts": parts
             });
         }
+
+        // 按 API Key / 账号标签注入的提示词
+        crate::proxy::scoped_prompt::apply(&mut inner_request, token);
     }
 
     // [ADDED v4.1.24] 扩展 toolConfig 到 VALIDATED 模式
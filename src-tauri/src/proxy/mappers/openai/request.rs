@@ -329,6 +329,7 @@ pub fn transform_openai_request(
                         crate::proxy::common::json_schema::fix_tool_call_args(&mut args, original_schema);
                     }
 
+                    // 参数是调用值而非 Schema，不能做 Schema 清洗 (否则 title / description 等同名参数会被误删)
                     let mut func_call_part = json!({
                         "functionCall": {
                             "name": if tc.function.name == "local_shell_call" { "shell" } else { &tc.function.name },
@@ -337,9 +338,6 @@ pub fn transform_openai_request(
                         }
                     });
 
-                    // [New] 递归清理参数中可能存在的非法校验字段
-                    crate::proxy::common::json_schema::clean_json_schema(&mut func_call_part);
-
                     if let Some(ref sig) = thought_sig {
                         func_call_part["thoughtSignature"] = json!(sig);
                     } else if is_thinking_model || is_gemini_flash_thinking {
@@ -664,9 +662,10 @@ pub fn transform_openai_request(
         if !function_declarations.is_empty() {
             inner_request["tools"] = json!([{ "functionDeclarations": function_declarations }]);
             // [ADDED v4.1.24] toolConfig VALIDATED - aligns with native behavior
-            inner_request["toolConfig"] = json!({
-                "functionCallingConfig": { "mode": "VALIDATED" }
-            });
+            // tool_choice (none / required / 指定函数) 映射为对应的 functionCallingConfig
+            inner_request["toolConfig"] = crate::proxy::mappers::common_utils::build_tool_config(
+                request.tool_choice.as_ref(),
+            );
         }
     }
 
@@ -703,6 +702,8 @@ pub fn transform_openai_request(
         "role": "user",
         "parts": parts
     });
+    // 4. 按 API Key / 账号标签注入的提示词
+    crate::proxy::scoped_prompt::apply(&mut inner_request, token);
 
     if config.inject_google_search {
         crate::proxy::mappers::common_utils::inject_google_search_tool(&mut inner_request, Some(mapped_model));
@@ -1159,6 +1160,97 @@ mod tests {
         crate::proxy::config::update_image_thinking_mode(Some("enabled".to_string()));
     }
 
+    #[test]
+    fn test_tool_choice_and_nested_tool_call_args() {
+        let args = json!({
+            "title": "Crash",
+            "description": "stack overflow",
+            "items": [{"type": "bug", "severity": 1}],
+            "meta": {"assignee": {"login": "octo"}}
+        });
+        let req = OpenAIRequest {
+            model: "gpt-4o".to_string(),
+            messages: vec![
+                OpenAIMessage {
+                    role: "user".to_string(),
+                    content: Some(OpenAIContent::String("File a bug".to_string())),
+                    reasoning_content: None,
+                    tool_calls: None,
+                    tool_call_id: None,
+                    name: None,
+                },
+                OpenAIMessage {
+                    role: "assistant".to_string(),
+                    content: None,
+                    reasoning_content: None,
+                    tool_calls: Some(vec![ToolCall {
+                        id: "call_1".to_string(),
+                        r#type: "function".to_string(),
+                        function: ToolFunction {
+                            name: "create_issue".to_string(),
+                            arguments: args.to_string(),
+                        },
+                    }]),
+                    tool_call_id: None,
+                    name: None,
+                },
+            ],
+            tools: Some(vec![json!({
+                "type": "function",
+                "function": {
+                    "name": "create_issue",
+                    "parameters": {
+                        "type": "object",
+                        "properties": {
+                            "title": {"type": "string"},
+                            "description": {"type": "string"},
+                            "items": {
+                                "type": "array",
+                                "items": {
+                                    "type": "object",
+                                    "properties": {
+                                        "type": {"type": "string"},
+                                        "severity": {"type": "integer"}
+                                    }
+                                }
+                            },
+                            "meta": {
+                                "type": "object",
+                                "properties": {
+                                    "assignee": {"type": "object", "properties": {"login": {"type": "string"}}}
+                                }
+                            }
+                        },
+                        "required": ["title"]
+                    }
+                }
+            })]),
+            tool_choice: Some(json!({"type": "function", "function": {"name": "create_issue"}})),
+            ..Default::default()
+        };
+
+        let (result, _, _) = transform_openai_request(&req, "proj", "gemini-2.5-flash", None);
+        let request = &result["request"];
+
+        assert_eq!(
+            request["toolConfig"]["functionCallingConfig"],
+            json!({"mode": "ANY", "allowedFunctionNames": ["create_issue"]})
+        );
+
+        let params = &request["tools"][0]["functionDeclarations"][0]["parameters"];
+        assert_eq!(params["properties"]["meta"]["properties"]["assignee"]["properties"]["login"]["type"], "STRING");
+        assert_eq!(params["properties"]["items"]["type"], "ARRAY");
+
+        let call = request["contents"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .flat_map(|c| c["parts"].as_array().cloned().unwrap_or_default())
+            .find_map(|p| p.get("functionCall").cloned())
+            .expect("Should have functionCall");
+        assert_eq!(call["args"], args, "Nested tool call arguments must pass through untouched");
+    }
+
     #[test]
     fn test_mixed_tools_injection_openai() {
         // 验证 OpenAI 协议在 Gemini 2.0+ 下支持混合工具
@@ -243,6 +243,30 @@ mod tests {
         assert_eq!(result.choices[0].finish_reason, Some("stop".to_string()));
     }
 
+    #[test]
+    fn test_function_call_nested_args_roundtrip() {
+        let args = json!({
+            "title": "Crash",
+            "items": [{"type": "bug", "severity": 1}],
+            "meta": {"assignee": {"login": "octo"}}
+        });
+        let gemini_resp = json!({
+            "candidates": [{
+                "content": {
+                    "parts": [{"functionCall": {"name": "create_issue", "args": args, "id": "call_1"}}]
+                },
+                "finishReason": "STOP"
+            }]
+        });
+
+        let result = transform_openai_response(&gemini_resp, None, 1);
+        let calls = result.choices[0].message.tool_calls.as_ref().unwrap();
+        assert_eq!(calls[0].id, "call_1");
+        assert_eq!(calls[0].function.name, "create_issue");
+        let parsed: Value = serde_json::from_str(&calls[0].function.arguments).unwrap();
+        assert_eq!(parsed, args);
+    }
+
     #[test]
     fn test_usage_metadata_mapping() {
         let gemini_resp = json!({
@@ -0,0 +1,235 @@
+// 局域网 mDNS 广播
+// 反代服务运行期间以 `_abvproxy._tcp` 广播网关地址，供局域网内的客户端自动发现，
+// 避免手动填写会随 DHCP 变化的 IP。
+// 仅广播局域网可达的端口: allow_lan_access 时为主端口，否则为第一个非回环的附加监听地址。
+
+use mdns_sd::{ServiceDaemon, ServiceInfo};
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+
+use crate::modules::logger;
+use crate::proxy::config::{ProxyAuthMode, ProxyConfig};
+
+pub const SERVICE_TYPE: &str = "_abvproxy._tcp.local.";
+
+#[derive(Default)]
+struct MdnsState {
+    /// 反代服务是否在运行 (只有运行期间才广播)
+    running: bool,
+    daemon: Option<ServiceDaemon>,
+    fullname: Option<String>,
+}
+
+static STATE: Lazy<Mutex<MdnsState>> = Lazy::new(|| Mutex::new(MdnsState::default()));
+
+#[derive(Debug, PartialEq)]
+struct Endpoint {
+    port: u16,
+    tls: bool,
+    auth_required: bool,
+}
+
+fn is_loopback_host(host: &str) -> bool {
+    host == "localhost"
+        || host
+            .parse::<std::net::IpAddr>()
+            .map(|ip| ip.is_loopback())
+            .unwrap_or(false)
+}
+
+/// 选择要广播的端点；没有局域网可达的监听地址时返回 None
+fn lan_endpoint(config: &ProxyConfig) -> Option<Endpoint> {
+    let global_auth = !matches!(
+        crate::proxy::ProxySecurityConfig::from_proxy_config(config).effective_auth_mode(),
+        ProxyAuthMode::Off
+    );
+    if config.allow_lan_access {
+        return Some(Endpoint {
+            port: config.port,
+            tls: false,
+            auth_required: global_auth,
+        });
+    }
+    config
+        .additional_listeners
+        .iter()
+        .find(|l| l.enabled && !is_loopback_host(&l.host))
+        .map(|l| Endpoint {
+            port: l.port,
+            tls: l.tls.is_some(),
+            auth_required: l
+                .resolved_auth_mode()
+                .map(|m| !matches!(m, ProxyAuthMode::Off))
+                .unwrap_or(global_auth),
+        })
+}
+
+fn instance_name(config: &ProxyConfig) -> String {
+    config
+        .mdns
+        .instance_name
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .map(str::to_string)
+        .or_else(sysinfo::System::host_name)
+        .unwrap_or_else(|| "Antigravity Tools".to_string())
+}
+
+fn unregister(state: &mut MdnsState) {
+    if let (Some(daemon), Some(fullname)) = (state.daemon.as_ref(), state.fullname.take()) {
+        if let Err(e) = daemon.unregister(&fullname) {
+            logger::log_warn(&format!("[mDNS] Failed to unregister {}: {}", fullname, e));
+        }
+    }
+}
+
+fn apply(state: &mut MdnsState, config: &ProxyConfig) {
+    unregister(state);
+    if !state.running || !config.mdns.enabled {
+        return;
+    }
+    let Some(endpoint) = lan_endpoint(config) else {
+        logger::log_warn(
+            "[mDNS] Advertising skipped: enable LAN access or add a non-loopback listener",
+        );
+        return;
+    };
+
+    if state.daemon.is_none() {
+        match ServiceDaemon::new() {
+            Ok(daemon) => state.daemon = Some(daemon),
+            Err(e) => {
+                logger::log_warn(&format!("[mDNS] Failed to start responder: {}", e));
+                return;
+            }
+        }
+    }
+    let Some(daemon) = state.daemon.as_ref() else {
+        return;
+    };
+
+    let name = instance_name(config);
+    // mDNS 主机名只允许字母数字与连字符
+    let host_label: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let host_label = host_label.trim_matches('-').to_lowercase();
+    let host_name = if host_label.is_empty() {
+        "antigravity-tools.local.".to_string()
+    } else {
+        format!("{}.local.", host_label)
+    };
+    let version = env!("CARGO_PKG_VERSION");
+    let properties = [
+        ("name", name.as_str()),
+        ("version", version),
+        ("scheme", if endpoint.tls { "https" } else { "http" }),
+        (
+            "auth",
+            if endpoint.auth_required {
+                "required"
+            } else {
+                "none"
+            },
+        ),
+        ("path", "/v1"),
+    ];
+
+    let info = match ServiceInfo::new(
+        SERVICE_TYPE,
+        &name,
+        &host_name,
+        "",
+        endpoint.port,
+        &properties[..],
+    ) {
+        Ok(info) => info.enable_addr_auto(),
+        Err(e) => {
+            logger::log_warn(&format!("[mDNS] Invalid service info: {}", e));
+            return;
+        }
+    };
+    let fullname = info.get_fullname().to_string();
+    match daemon.register(info) {
+        Ok(()) => {
+            logger::log_info(&format!(
+                "[mDNS] Advertising {} on port {}",
+                fullname, endpoint.port
+            ));
+            state.fullname = Some(fullname);
+        }
+        Err(e) => logger::log_warn(&format!("[mDNS] Failed to register service: {}", e)),
+    }
+}
+
+/// 反代服务启动后调用
+pub fn start(config: &ProxyConfig) {
+    let mut state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    state.running = true;
+    apply(&mut state, config);
+}
+
+/// 配置变更后调用；服务未运行时不做任何事
+pub fn refresh(config: &ProxyConfig) {
+    let mut state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    if state.running {
+        apply(&mut state, config);
+    }
+}
+
+/// 反代服务停止后调用
+pub fn stop() {
+    let mut state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    state.running = false;
+    unregister(&mut state);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proxy::config::ListenerConfig;
+
+    #[test]
+    fn test_lan_endpoint_selection() {
+        let mut cfg = ProxyConfig::default();
+        assert_eq!(lan_endpoint(&cfg), None);
+
+        cfg.additional_listeners = vec![
+            ListenerConfig {
+                enabled: true,
+                host: "127.0.0.1".to_string(),
+                port: 8046,
+                auth_mode: None,
+                tls: None,
+            },
+            ListenerConfig {
+                enabled: true,
+                host: "0.0.0.0".to_string(),
+                port: 8047,
+                auth_mode: Some(ProxyAuthMode::Auto),
+                tls: None,
+            },
+        ];
+        assert_eq!(
+            lan_endpoint(&cfg),
+            Some(Endpoint {
+                port: 8047,
+                tls: false,
+                auth_required: true,
+            })
+        );
+
+        // 开启局域网访问时广播主端口，鉴权沿用全局策略 (auto => all_except_health)
+        cfg.allow_lan_access = true;
+        assert_eq!(
+            lan_endpoint(&cfg),
+            Some(Endpoint {
+                port: cfg.port,
+                tls: false,
+                auth_required: true,
+            })
+        );
+    }
+}
@@ -0,0 +1,77 @@
+// 准入控制中间件：按 API Key 优先级排队 / 拒绝 (需位于 auth 之内以读取 UserTokenIdentity)
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{header, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use futures::StreamExt;
+
+use crate::proxy::admission::{self, ClientPriority};
+use crate::proxy::middleware::auth::UserTokenIdentity;
+use crate::proxy::server::AppState;
+
+pub async fn admission_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path();
+    // 仅对生成类请求计数；模型列表、计数、埋点等轻量请求不占容量
+    if request.method() != Method::POST
+        || path.contains("event_logging")
+        || path.ends_with("count_tokens")
+        || path.ends_with(":countTokens")
+        || path.starts_with("/internal/")
+    {
+        return next.run(request).await;
+    }
+
+    let priority = request
+        .extensions()
+        .get::<UserTokenIdentity>()
+        .map(|identity| identity.priority)
+        .unwrap_or_default();
+    // 在鉴权中间件建立的账号分组作用域内统计可用账号
+    let eligible = state.token_manager.eligible_account_count().await;
+
+    match admission::acquire(priority, eligible).await {
+        Ok(permit) => {
+            // 流式响应在 body 结束前仍占用容量：permit 随 body 一起释放
+            let (parts, body) = next.run(request).await.into_parts();
+            let stream = body.into_data_stream().map(move |chunk| {
+                let _held = &permit;
+                chunk
+            });
+            Response::from_parts(parts, Body::from_stream(stream))
+        }
+        Err(shed) => {
+            tracing::warn!(
+                "[Admission] Shed {:?} priority request to {}: {} in flight, capacity {} ({} eligible accounts)",
+                shed.priority,
+                path,
+                shed.in_flight,
+                shed.capacity,
+                eligible
+            );
+            let message = match shed.priority {
+                ClientPriority::Low => "Account pool is saturated; low-priority requests are being shed, retry later",
+                _ => "Account pool is saturated, retry later",
+            };
+            (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, "5")],
+                Json(serde_json::json!({
+                    "error": {
+                        "message": message,
+                        "type": "overloaded_error",
+                        "code": "priority_shed"
+                    }
+                })),
+            )
+                .into_response()
+        }
+    }
+}
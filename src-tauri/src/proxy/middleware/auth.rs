@@ -9,7 +9,7 @@ use axum::{
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
-use crate::proxy::{ProxyAuthMode, ProxySecurityConfig};
+use crate::proxy::{AdminRole, ProxyAuthMode, ProxySecurityConfig};
 
 /// API Key 认证中间件 (代理接口使用，遵循 auth_mode)
 pub async fn auth_middleware(
@@ -54,7 +54,11 @@ async fn auth_middleware_internal(
     }
 
     let security = security.read().await.clone();
-    let effective_mode = security.effective_auth_mode();
+    let effective_mode = request
+        .extensions()
+        .get::<crate::proxy::security::ListenerAuthMode>()
+        .map(|m| m.0.clone())
+        .unwrap_or_else(|| security.effective_auth_mode());
 
     // 权限检查逻辑
     if !force_strict {
@@ -77,16 +81,24 @@ async fn auth_middleware_internal(
             if let Some(token) = api_key {
                 // 尝试验证是否为 User Token（不阻止请求，只记录）
                 if let Ok(Some(user_token)) = crate::modules::user_token_db::get_token_by_value(token) {
+                    let account_group = user_token.account_group.clone();
                     let identity = UserTokenIdentity {
                         token_id: user_token.id,
                         token: user_token.token,
                         username: user_token.username,
+                        account_group: user_token.account_group,
+                        priority: crate::proxy::admission::ClientPriority::parse(&user_token.priority),
                     };
                     // 注入 identity 到请求
                     let (mut parts, body) = request.into_parts();
                     parts.extensions.insert(identity);
                     let request = Request::from_parts(parts, body);
-                    return Ok(next.run(request).await);
+                    // [NEW] 账号池分区: 在该 Key 绑定的分组作用域内处理请求
+                    return Ok(crate::proxy::token_manager::scope_account_group(
+                        account_group,
+                        next.run(request),
+                    )
+                    .await);
                 }
             }
             
@@ -135,7 +147,9 @@ async fn auth_middleware_internal(
         });
 
     if security.api_key.is_empty() && (security.admin_password.is_none() || security.admin_password.as_ref().unwrap().is_empty()) {
-        if force_strict {
+        if force_strict && !security.admin_tokens.is_empty() {
+            // 仅配置了附加管理令牌，继续按角色鉴权
+        } else if force_strict {
              tracing::error!("Admin auth is required but both api_key and admin_password are empty; denying request");
              return Err(StatusCode::UNAUTHORIZED);
         }
@@ -143,26 +157,41 @@ async fn auth_middleware_internal(
         return Err(StatusCode::UNAUTHORIZED);
     }
 
-    // 认证逻辑
-    let authorized = if force_strict {
-        // 管理接口：优先使用独立的 admin_password，如果没有则回退使用 api_key
-        match &security.admin_password {
-            Some(pwd) if !pwd.is_empty() => {
-                api_key.map(|k| k == pwd).unwrap_or(false)
-            }
-            _ => {
-                // 回退使用 api_key
-                api_key.map(|k| k == security.api_key).unwrap_or(false)
-            }
+    // 管理接口：按凭据对应的角色鉴权
+    // admin_password (未设置时回退 api_key) 为 admin，admin_tokens 中的令牌按配置的角色
+    if force_strict {
+        let Some(role) = api_key.and_then(|k| security.admin_role_for(k)) else {
+            return Err(StatusCode::UNAUTHORIZED);
+        };
+        let required = required_admin_role(&method, &path);
+        if role < required {
+            tracing::warn!(
+                "Admin request denied: {} {} requires {:?}, caller has {:?}",
+                method,
+                path,
+                required,
+                role
+            );
+            let body = serde_json::json!({
+                "error": format!("forbidden: requires {} role", role_name(required)),
+            });
+            return Ok(axum::response::Response::builder()
+                .status(StatusCode::FORBIDDEN)
+                .header("Content-Type", "application/json")
+                .body(axum::body::Body::from(body.to_string()))
+                .unwrap());
         }
-    } else {
-        // AI 代理接口：仅允许使用 api_key
-        api_key.map(|k| k == security.api_key).unwrap_or(false)
-    };
+        let (mut parts, body) = request.into_parts();
+        parts.extensions.insert(role);
+        return Ok(next.run(Request::from_parts(parts, body)).await);
+    }
+
+    // AI 代理接口：仅允许使用 api_key
+    let authorized = api_key.map(|k| k == security.api_key).unwrap_or(false);
 
     if authorized {
         Ok(next.run(request).await)
-    } else if !force_strict && api_key.is_some() {
+    } else if api_key.is_some() {
         // 尝试验证 UserToken
         let token = api_key.unwrap();
         
@@ -186,10 +215,13 @@ async fn auth_middleware_internal(
             Ok((true, _)) => {
                 // Token 有效，查询信息以便传递
                 if let Ok(Some(user_token)) = crate::modules::user_token_db::get_token_by_value(token) {
+                     let account_group = user_token.account_group.clone();
                      let identity = UserTokenIdentity {
                         token_id: user_token.id,
                         token: user_token.token,
                         username: user_token.username,
+                        account_group: user_token.account_group,
+                        priority: crate::proxy::admission::ClientPriority::parse(&user_token.priority),
                     };
                     
                     // [FIX] 将身份信息注入到请求 extensions 中，而不是响应
@@ -201,8 +233,12 @@ async fn auth_middleware_internal(
                     parts.extensions.insert(identity);
                     let request = Request::from_parts(parts, body);
                     
-                    // 执行请求
-                    let response = next.run(request).await;
+                    // 执行请求 (限定在该 Key 绑定的账号分组内)
+                    let response = crate::proxy::token_manager::scope_account_group(
+                        account_group,
+                        next.run(request),
+                    )
+                    .await;
                     
                     Ok(response)
                 } else {
@@ -236,6 +272,267 @@ async fn auth_middleware_internal(
     }
 }
 
+fn role_name(role: AdminRole) -> &'static str {
+    match role {
+        AdminRole::Viewer => "viewer",
+        AdminRole::Operator => "operator",
+        AdminRole::Admin => "admin",
+    }
+}
+
+/// 管理接口的最低角色: (方法, 路由模板, 角色)
+/// 路由模板与 server.rs 中注册的一致 (`:param` 匹配单个路径段)，GET 同时适用于 HEAD。
+/// 未列出的接口一律要求 admin；新增路由时必须在这里写明角色 (由测试检查)。
+/// - viewer: 只读查询 (包括把查询参数放在 body 中的 POST)
+/// - operator: 日常运维写操作 (切换账号、刷新配额、启停服务等)
+/// - admin: 删除、导出、配置与密钥、安全策略，以及会改变本机启动命令的接口
+const ROUTE_ROLES: &[(&str, &str, AdminRole)] = &[
+    ("GET", "/health", AdminRole::Viewer),
+    ("GET", "/accounts", AdminRole::Viewer),
+    ("POST", "/accounts", AdminRole::Operator),
+    ("POST", "/accounts/summaries", AdminRole::Viewer),
+    ("POST", "/accounts/share", AdminRole::Admin),
+    ("POST", "/accounts/share/redeem", AdminRole::Admin),
+    ("POST", "/accounts/bulk-toggle-proxy", AdminRole::Operator),
+    ("GET", "/accounts/current", AdminRole::Viewer),
+    ("GET", "/accounts/project-bindings", AdminRole::Viewer),
+    ("POST", "/accounts/simulate", AdminRole::Operator),
+    ("GET", "/accounts/quota-history", AdminRole::Viewer),
+    ("POST", "/accounts/switch", AdminRole::Operator),
+    ("POST", "/accounts/switch-back", AdminRole::Operator),
+    ("GET", "/accounts/switch-history", AdminRole::Viewer),
+    ("GET", "/accounts/switch-report", AdminRole::Viewer),
+    ("POST", "/accounts/refresh", AdminRole::Operator),
+    ("POST", "/accounts/refresh-due", AdminRole::Operator),
+    ("POST", "/accounts/refresh-selected", AdminRole::Operator),
+    ("GET", "/operations", AdminRole::Viewer),
+    ("POST", "/operations/:operationId/cancel", AdminRole::Operator),
+    ("DELETE", "/accounts/:accountId", AdminRole::Admin),
+    ("POST", "/accounts/:accountId/bind-device", AdminRole::Operator),
+    ("POST", "/accounts/:accountId/verify-login", AdminRole::Operator),
+    ("GET", "/accounts/:accountId/device-profiles", AdminRole::Viewer),
+    ("GET", "/accounts/:accountId/device-versions", AdminRole::Viewer),
+    ("POST", "/accounts/device-preview", AdminRole::Operator),
+    ("POST", "/accounts/:accountId/bind-device-profile", AdminRole::Operator),
+    ("POST", "/accounts/restore-original", AdminRole::Operator),
+    ("POST", "/accounts/:accountId/device-versions/:versionId/restore", AdminRole::Operator),
+    ("DELETE", "/accounts/:accountId/device-versions/:versionId", AdminRole::Admin),
+    ("POST", "/accounts/import/v1", AdminRole::Admin),
+    ("POST", "/accounts/import/db", AdminRole::Admin),
+    ("POST", "/accounts/import/db-custom", AdminRole::Admin),
+    ("POST", "/accounts/sync/db", AdminRole::Admin),
+    ("GET", "/stats/summary", AdminRole::Viewer),
+    ("GET", "/stats/hourly", AdminRole::Viewer),
+    ("GET", "/stats/daily", AdminRole::Viewer),
+    ("GET", "/stats/weekly", AdminRole::Viewer),
+    ("GET", "/stats/accounts", AdminRole::Viewer),
+    ("GET", "/stats/models", AdminRole::Viewer),
+    ("GET", "/config", AdminRole::Admin),
+    ("POST", "/config", AdminRole::Admin),
+    ("GET", "/config/export", AdminRole::Admin),
+    ("POST", "/config/import", AdminRole::Admin),
+    ("POST", "/config/reset-section", AdminRole::Admin),
+    ("GET", "/config/history", AdminRole::Admin),
+    ("GET", "/config/history/diff", AdminRole::Admin),
+    ("POST", "/config/history/:version/rollback", AdminRole::Admin),
+    ("POST", "/proxy/cli/status", AdminRole::Viewer),
+    ("POST", "/proxy/cli/sync", AdminRole::Operator),
+    ("POST", "/proxy/cli/restore", AdminRole::Operator),
+    ("POST", "/proxy/cli/config", AdminRole::Admin),
+    ("POST", "/proxy/opencode/status", AdminRole::Viewer),
+    ("POST", "/proxy/opencode/sync", AdminRole::Operator),
+    ("POST", "/proxy/opencode/restore", AdminRole::Operator),
+    ("POST", "/proxy/opencode/clear", AdminRole::Operator),
+    ("POST", "/proxy/opencode/config", AdminRole::Admin),
+    ("POST", "/proxy/droid/status", AdminRole::Viewer),
+    ("POST", "/proxy/droid/sync", AdminRole::Operator),
+    ("POST", "/proxy/droid/restore", AdminRole::Operator),
+    ("POST", "/proxy/droid/config", AdminRole::Admin),
+    ("GET", "/proxy/status", AdminRole::Viewer),
+    ("GET", "/proxy/pool/config", AdminRole::Admin),
+    ("GET", "/proxy/pool/bindings", AdminRole::Viewer),
+    ("POST", "/proxy/pool/bind", AdminRole::Operator),
+    ("POST", "/proxy/pool/unbind", AdminRole::Operator),
+    ("GET", "/proxy/pool/binding/:accountId", AdminRole::Viewer),
+    ("POST", "/proxy/health-check/trigger", AdminRole::Operator),
+    ("POST", "/proxy/start", AdminRole::Operator),
+    ("POST", "/proxy/stop", AdminRole::Operator),
+    ("POST", "/proxy/mapping", AdminRole::Admin),
+    ("POST", "/proxy/api-key/generate", AdminRole::Admin),
+    ("POST", "/proxy/session-bindings/clear", AdminRole::Operator),
+    ("DELETE", "/proxy/rate-limits", AdminRole::Admin),
+    ("DELETE", "/proxy/rate-limits/:accountId", AdminRole::Admin),
+    ("GET", "/proxy/preferred-account", AdminRole::Viewer),
+    ("POST", "/proxy/preferred-account", AdminRole::Operator),
+    ("GET", "/proxy/model-availability", AdminRole::Viewer),
+    ("GET", "/proxy/token-manager", AdminRole::Viewer),
+    ("GET", "/proxy/capacity", AdminRole::Viewer),
+    ("POST", "/accounts/oauth/prepare", AdminRole::Operator),
+    ("POST", "/accounts/oauth/start", AdminRole::Operator),
+    ("POST", "/accounts/oauth/complete", AdminRole::Operator),
+    ("POST", "/accounts/oauth/cancel", AdminRole::Operator),
+    ("POST", "/accounts/oauth/submit-code", AdminRole::Operator),
+    ("POST", "/zai/models/fetch", AdminRole::Operator),
+    ("GET", "/models/known", AdminRole::Viewer),
+    ("POST", "/models/catalog/sync", AdminRole::Operator),
+    ("GET", "/quota-protection/monitored-models", AdminRole::Viewer),
+    ("POST", "/quota-protection/monitored-models", AdminRole::Operator),
+    ("POST", "/proxy/monitor/toggle", AdminRole::Operator),
+    ("GET", "/proxy/cloudflared/status", AdminRole::Viewer),
+    ("POST", "/proxy/cloudflared/install", AdminRole::Admin),
+    ("POST", "/proxy/cloudflared/start", AdminRole::Operator),
+    ("POST", "/proxy/cloudflared/stop", AdminRole::Operator),
+    ("POST", "/system/open-folder", AdminRole::Operator),
+    ("GET", "/proxy/stats", AdminRole::Viewer),
+    ("GET", "/proxy/admission", AdminRole::Viewer),
+    ("GET", "/proxy/sessions", AdminRole::Viewer),
+    ("POST", "/proxy/sessions/rotate", AdminRole::Operator),
+    ("GET", "/proxy/circuits", AdminRole::Viewer),
+    ("GET", "/proxy/content-filter/stats", AdminRole::Viewer),
+    ("POST", "/proxy/content-filter/stats/reset", AdminRole::Operator),
+    ("GET", "/logs", AdminRole::Viewer),
+    ("GET", "/logs/count", AdminRole::Viewer),
+    ("POST", "/logs/clear", AdminRole::Operator),
+    ("POST", "/logs/compact", AdminRole::Operator),
+    ("GET", "/logs/:logId", AdminRole::Viewer),
+    ("POST", "/logs/:logId/replay", AdminRole::Operator),
+    ("POST", "/debug/enable", AdminRole::Admin),
+    ("POST", "/debug/disable", AdminRole::Admin),
+    ("GET", "/debug/enabled", AdminRole::Admin),
+    ("GET", "/debug/logs", AdminRole::Admin),
+    ("POST", "/debug/logs/clear", AdminRole::Admin),
+    ("GET", "/debug/events", AdminRole::Admin),
+    ("POST", "/stats/token/clear", AdminRole::Operator),
+    ("POST", "/stats/retention", AdminRole::Operator),
+    ("POST", "/system/hygiene", AdminRole::Operator),
+    ("POST", "/digest/run", AdminRole::Operator),
+    ("POST", "/backup/run", AdminRole::Admin),
+    ("GET", "/backup/remote", AdminRole::Viewer),
+    ("POST", "/backup/restore", AdminRole::Admin),
+    ("GET", "/backup/status", AdminRole::Viewer),
+    ("POST", "/sync/run", AdminRole::Operator),
+    ("GET", "/sync/status", AdminRole::Viewer),
+    ("GET", "/stats/token/hourly", AdminRole::Viewer),
+    ("GET", "/stats/token/daily", AdminRole::Viewer),
+    ("GET", "/stats/token/weekly", AdminRole::Viewer),
+    ("GET", "/stats/token/by-account", AdminRole::Viewer),
+    ("GET", "/stats/token/summary", AdminRole::Viewer),
+    ("GET", "/stats/token/by-model", AdminRole::Viewer),
+    ("GET", "/stats/latency/by-account", AdminRole::Viewer),
+    ("GET", "/stats/token/model-trend/hourly", AdminRole::Viewer),
+    ("GET", "/stats/token/model-trend/daily", AdminRole::Viewer),
+    ("GET", "/stats/token/account-trend/hourly", AdminRole::Viewer),
+    ("GET", "/stats/token/account-trend/daily", AdminRole::Viewer),
+    ("POST", "/accounts/bulk-delete", AdminRole::Admin),
+    ("POST", "/accounts/export", AdminRole::Admin),
+    ("POST", "/accounts/reorder", AdminRole::Operator),
+    ("GET", "/accounts/:accountId/quota", AdminRole::Viewer),
+    ("POST", "/accounts/:accountId/toggle-proxy", AdminRole::Operator),
+    ("POST", "/accounts/warmup", AdminRole::Operator),
+    ("POST", "/accounts/:accountId/warmup", AdminRole::Operator),
+    ("POST", "/accounts/:accountId/test-request", AdminRole::Operator),
+    ("POST", "/accounts/:accountId/tags", AdminRole::Operator),
+    ("POST", "/accounts/:accountId/ip-family", AdminRole::Operator),
+    ("POST", "/accounts/:accountId/antigravity-args", AdminRole::Admin),
+    ("POST", "/accounts/:accountId/antigravity-executable", AdminRole::Admin),
+    ("GET", "/accounts/orphans", AdminRole::Viewer),
+    ("POST", "/accounts/:accountId/snooze", AdminRole::Operator),
+    ("POST", "/accounts/orphans/:accountId/adopt", AdminRole::Operator),
+    ("GET", "/accounts/corrupt", AdminRole::Viewer),
+    ("POST", "/accounts/corrupt/:fileName/restore", AdminRole::Operator),
+    ("DELETE", "/accounts/corrupt/:fileName", AdminRole::Admin),
+    ("GET", "/system/data-dir", AdminRole::Viewer),
+    ("GET", "/system/startup-report", AdminRole::Viewer),
+    ("GET", "/notifications", AdminRole::Viewer),
+    ("POST", "/notifications/read", AdminRole::Operator),
+    ("GET", "/system/updates/settings", AdminRole::Viewer),
+    ("GET", "/system/updates/check-status", AdminRole::Viewer),
+    ("POST", "/system/updates/check", AdminRole::Operator),
+    ("POST", "/system/updates/touch", AdminRole::Operator),
+    ("POST", "/system/updates/save", AdminRole::Operator),
+    ("GET", "/system/autostart/status", AdminRole::Viewer),
+    ("POST", "/system/autostart/toggle", AdminRole::Operator),
+    ("GET", "/system/http-api/settings", AdminRole::Admin),
+    ("POST", "/system/http-api/settings", AdminRole::Admin),
+    ("GET", "/system/antigravity/path", AdminRole::Viewer),
+    ("GET", "/system/antigravity/installations", AdminRole::Viewer),
+    ("GET", "/system/antigravity/args", AdminRole::Viewer),
+    ("POST", "/system/cache/clear", AdminRole::Operator),
+    ("GET", "/system/cache/paths", AdminRole::Viewer),
+    ("POST", "/system/logs/clear-cache", AdminRole::Operator),
+    ("POST", "/system/logs/export", AdminRole::Admin),
+    ("POST", "/system/logs/query", AdminRole::Viewer),
+    ("POST", "/system/diagnostics", AdminRole::Admin),
+    ("GET", "/system/integrity", AdminRole::Viewer),
+    ("POST", "/system/integrity/repair", AdminRole::Admin),
+    ("GET", "/system/network", AdminRole::Viewer),
+    ("POST", "/system/offline", AdminRole::Operator),
+    ("GET", "/system/clock-skew", AdminRole::Viewer),
+    ("GET", "/system/scheduler", AdminRole::Viewer),
+    ("POST", "/system/scheduler/jobs/:jobId", AdminRole::Operator),
+    ("GET", "/onboarding", AdminRole::Viewer),
+    ("POST", "/onboarding/steps/:step", AdminRole::Operator),
+    ("GET", "/system/languages", AdminRole::Viewer),
+    ("GET", "/system/languages/:language", AdminRole::Viewer),
+    ("GET", "/system/log-levels", AdminRole::Viewer),
+    ("POST", "/system/log-levels", AdminRole::Admin),
+    ("GET", "/security/logs", AdminRole::Admin),
+    ("POST", "/security/logs/clear", AdminRole::Admin),
+    ("GET", "/security/stats", AdminRole::Admin),
+    ("GET", "/security/token-stats", AdminRole::Admin),
+    ("GET", "/security/blacklist", AdminRole::Admin),
+    ("POST", "/security/blacklist", AdminRole::Admin),
+    ("DELETE", "/security/blacklist", AdminRole::Admin),
+    ("POST", "/security/blacklist/clear", AdminRole::Admin),
+    ("GET", "/security/blacklist/check", AdminRole::Admin),
+    ("GET", "/security/whitelist", AdminRole::Admin),
+    ("POST", "/security/whitelist", AdminRole::Admin),
+    ("DELETE", "/security/whitelist", AdminRole::Admin),
+    ("POST", "/security/whitelist/clear", AdminRole::Admin),
+    ("GET", "/security/whitelist/check", AdminRole::Admin),
+    ("GET", "/security/config", AdminRole::Admin),
+    ("POST", "/security/config", AdminRole::Admin),
+    ("GET", "/user-tokens", AdminRole::Admin),
+    ("POST", "/user-tokens", AdminRole::Admin),
+    ("GET", "/user-tokens/summary", AdminRole::Admin),
+    ("GET", "/user-tokens/billing", AdminRole::Admin),
+    ("GET", "/user-tokens/billing/export", AdminRole::Admin),
+    ("POST", "/user-tokens/:id/renew", AdminRole::Admin),
+    ("DELETE", "/user-tokens/:id", AdminRole::Admin),
+    ("PATCH", "/user-tokens/:id", AdminRole::Admin),
+    ("GET", "/auth/url", AdminRole::Viewer),
+];
+
+fn route_matches(template: &str, path: &str) -> bool {
+    let mut template_segments = template.split('/');
+    let mut path_segments = path.split('/');
+    loop {
+        match (template_segments.next(), path_segments.next()) {
+            (None, None) => return true,
+            (Some(t), Some(p)) if t.starts_with(':') && !p.is_empty() => {}
+            (Some(t), Some(p)) if t == p => {}
+            _ => return false,
+        }
+    }
+}
+
+/// 管理接口所需的最低角色 (未列出的接口为 admin)
+fn required_admin_role(method: &axum::http::Method, path: &str) -> AdminRole {
+    // 嵌套路由内看到的路径不含 /api 前缀，两种形式都兼容
+    let path = path.strip_prefix("/api").unwrap_or(path);
+    let method = if *method == axum::http::Method::HEAD {
+        "GET"
+    } else {
+        method.as_str()
+    };
+    // 与 axum 一致: 字面路径段优先于 `:param`
+    ROUTE_ROLES
+        .iter()
+        .filter(|(m, template, _)| *m == method && route_matches(template, path))
+        .min_by_key(|(_, template, _)| template.matches(':').count())
+        .map_or(AdminRole::Admin, |(_, _, role)| *role)
+}
+
 /// 用户令牌身份信息 (传递给 Monitor 使用)
 #[derive(Clone, Debug)]
 pub struct UserTokenIdentity {
@@ -243,6 +540,8 @@ pub struct UserTokenIdentity {
     #[allow(dead_code)] // 保留原始 token 便于审计/调试
     pub token: String,
     pub username: String,
+    pub account_group: Option<String>, // [NEW] 绑定的账号分组
+    pub priority: crate::proxy::admission::ClientPriority, // 准入优先级
 }
 
 #[cfg(test)]
@@ -256,6 +555,7 @@ mod tests {
             auth_mode: ProxyAuthMode::Strict,
             api_key: "sk-api".to_string(),
             admin_password: Some("admin123".to_string()),
+            admin_tokens: Vec::new(),
             allow_lan_access: true,
             port: 8045,
             security_monitor: crate::proxy::config::SecurityMonitorConfig::default(),
@@ -272,6 +572,94 @@ mod tests {
         // 我们在 auth_middleware_internal 基础上做了逻辑校验即可
     }
 
+    #[test]
+    fn test_required_admin_role() {
+        use axum::http::Method;
+        assert_eq!(required_admin_role(&Method::GET, "/stats/summary"), AdminRole::Viewer);
+        assert_eq!(required_admin_role(&Method::GET, "/api/accounts/a1/quota"), AdminRole::Viewer);
+        assert_eq!(required_admin_role(&Method::POST, "/accounts/summaries"), AdminRole::Viewer);
+        assert_eq!(required_admin_role(&Method::POST, "/accounts/switch"), AdminRole::Operator);
+        assert_eq!(required_admin_role(&Method::POST, "/accounts/refresh"), AdminRole::Operator);
+        assert_eq!(required_admin_role(&Method::DELETE, "/accounts/a1"), AdminRole::Admin);
+        assert_eq!(required_admin_role(&Method::POST, "/accounts/export"), AdminRole::Admin);
+        // 配置中包含 api_key，读取也需要 admin
+        assert_eq!(required_admin_role(&Method::GET, "/config"), AdminRole::Admin);
+        assert_eq!(required_admin_role(&Method::GET, "/user-tokens/summary"), AdminRole::Admin);
+        // 分享链接包含 refresh_token
+        assert_eq!(required_admin_role(&Method::POST, "/accounts/share"), AdminRole::Admin);
+        // 修复会移动 / 改写账号文件
+        assert_eq!(required_admin_role(&Method::POST, "/system/integrity/repair"), AdminRole::Admin);
+        assert_eq!(required_admin_role(&Method::GET, "/system/integrity"), AdminRole::Viewer);
+        // 恢复会覆盖账号与配置
+        assert_eq!(required_admin_role(&Method::POST, "/backup/restore"), AdminRole::Admin);
+        assert_eq!(required_admin_role(&Method::GET, "/backup/status"), AdminRole::Viewer);
+        // 模型映射会改写所有调用方的路由
+        assert_eq!(required_admin_role(&Method::POST, "/proxy/mapping"), AdminRole::Admin);
+        // 启动参数与可执行文件路径决定本机启动什么
+        assert_eq!(
+            required_admin_role(&Method::POST, "/accounts/a1/antigravity-args"),
+            AdminRole::Admin
+        );
+        assert_eq!(
+            required_admin_role(&Method::POST, "/accounts/a1/antigravity-executable"),
+            AdminRole::Admin
+        );
+        assert_eq!(required_admin_role(&Method::HEAD, "/stats/summary"), AdminRole::Viewer);
+        // 未列出的路径与方法默认 admin
+        assert_eq!(required_admin_role(&Method::GET, "/configuration"), AdminRole::Admin);
+        assert_eq!(required_admin_role(&Method::PUT, "/accounts/switch"), AdminRole::Admin);
+        assert_eq!(required_admin_role(&Method::GET, "/accounts/a1/quota/extra"), AdminRole::Admin);
+    }
+
+    /// server.rs 管理路由中注册的 (方法, 路径)
+    fn registered_admin_routes() -> Vec<(String, String)> {
+        let source = include_str!("../server.rs");
+        let start = source.find("let admin_routes = Router").unwrap();
+        let end = start + source[start..].find("let app = Router").unwrap();
+        let block = &source[start..end];
+        let block = &block[..block.rfind(".layer(").unwrap_or(block.len())];
+
+        let mut routes = Vec::new();
+        for chunk in block.split(".route(").skip(1) {
+            let path = chunk.split('"').nth(1).unwrap().to_string();
+            for method in ["get", "post", "put", "patch", "delete"] {
+                let call = format!("{}(", method);
+                let registered = chunk.match_indices(&call).any(|(i, _)| {
+                    !chunk[..i]
+                        .chars()
+                        .next_back()
+                        .is_some_and(|c| c.is_alphanumeric() || c == '_')
+                });
+                if registered {
+                    routes.push((method.to_uppercase(), path.clone()));
+                }
+            }
+        }
+        routes
+    }
+
+    #[test]
+    fn test_every_admin_route_has_explicit_role() {
+        let routes = registered_admin_routes();
+        assert!(routes.len() > 100);
+        for (method, path) in &routes {
+            assert!(
+                ROUTE_ROLES.iter().any(|(m, t, _)| m == method && t == path),
+                "{} {} has no entry in ROUTE_ROLES",
+                method,
+                path
+            );
+        }
+        for (method, template, _) in ROUTE_ROLES {
+            assert!(
+                routes.iter().any(|(m, p)| m == method && p == template),
+                "ROUTE_ROLES entry {} {} is not registered",
+                method,
+                template
+            );
+        }
+    }
+
     #[test]
     fn test_auth_placeholder() {
         assert!(true);
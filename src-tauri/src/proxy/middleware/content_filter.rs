@@ -0,0 +1,213 @@
+// 内容过滤中间件：在请求到达 handler 前过滤提示词，在响应返回客户端前过滤内容
+// 位于 monitor 之外，监控日志中记录的是过滤后的内容
+use axum::{
+    body::{Body, Bytes},
+    extract::Request,
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use futures::StreamExt;
+use serde_json::Value;
+
+use crate::proxy::content_filter::{self, Filter, FilterOutcome, Stage, ANNOTATION_HEADER};
+
+fn is_content_type(headers: &axum::http::HeaderMap, needle: &str) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains(needle))
+}
+
+fn blocked_body(stage: Stage, rule: &str) -> Value {
+    let message = match stage {
+        Stage::Request => format!("Request blocked by content filter rule '{}'", rule),
+        Stage::Response => format!("Response blocked by content filter rule '{}'", rule),
+    };
+    serde_json::json!({
+        "error": {
+            "message": message,
+            "type": "content_filter",
+            "code": "content_filter_blocked"
+        }
+    })
+}
+
+fn blocked_response(stage: Stage, rule: &str) -> Response {
+    let status = match stage {
+        Stage::Request => StatusCode::BAD_REQUEST,
+        Stage::Response => StatusCode::BAD_GATEWAY,
+    };
+    (status, Json(blocked_body(stage, rule))).into_response()
+}
+
+/// 过滤 JSON 请求体；命中 block 时返回拒绝响应
+async fn filter_request(
+    filter: &Filter,
+    request: Request,
+    annotations: &mut Vec<String>,
+) -> Result<Request, Response> {
+    if !is_content_type(request.headers(), "json") {
+        return Ok(request);
+    }
+    let (mut parts, body) = request.into_parts();
+    let bytes = axum::body::to_bytes(body, usize::MAX).await.map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Failed to read request body: {}", e),
+        )
+            .into_response()
+    })?;
+    let Ok(mut json) = serde_json::from_slice::<Value>(&bytes) else {
+        return Ok(Request::from_parts(parts, Body::from(bytes)));
+    };
+
+    let mut outcome = FilterOutcome::default();
+    filter.apply_json(&mut json, &mut outcome);
+    content_filter::record(Stage::Request, &outcome);
+    if let Some(rule) = &outcome.blocked_by {
+        tracing::warn!(
+            "[ContentFilter] Blocked request to {} (rule '{}')",
+            parts.uri.path(),
+            rule
+        );
+        return Err(blocked_response(Stage::Request, rule));
+    }
+    annotations.extend(outcome.annotations);
+
+    let body = if outcome.modified {
+        parts.headers.remove(header::CONTENT_LENGTH);
+        Bytes::from(serde_json::to_vec(&json).unwrap_or_else(|_| bytes.to_vec()))
+    } else {
+        bytes
+    };
+    Ok(Request::from_parts(parts, Body::from(body)))
+}
+
+/// 过滤 JSON 响应或 SSE 流
+async fn filter_response(
+    filter: Filter,
+    response: Response,
+    annotations: &mut Vec<String>,
+) -> Response {
+    if is_content_type(response.headers(), "text/event-stream") {
+        return filter_sse_response(filter, response);
+    }
+    if !is_content_type(response.headers(), "json") {
+        return response;
+    }
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            return (
+                StatusCode::BAD_GATEWAY,
+                format!("Failed to read upstream response: {}", e),
+            )
+                .into_response()
+        }
+    };
+    let Ok(mut json) = serde_json::from_slice::<Value>(&bytes) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+
+    let mut outcome = FilterOutcome::default();
+    filter.apply_json(&mut json, &mut outcome);
+    content_filter::record(Stage::Response, &outcome);
+    if let Some(rule) = &outcome.blocked_by {
+        return blocked_response(Stage::Response, rule);
+    }
+    annotations.extend(outcome.annotations);
+
+    if !outcome.modified {
+        return Response::from_parts(parts, Body::from(bytes));
+    }
+    parts.headers.remove(header::CONTENT_LENGTH);
+    let body = serde_json::to_vec(&json).unwrap_or_else(|_| bytes.to_vec());
+    Response::from_parts(parts, Body::from(body))
+}
+
+/// 逐行过滤 SSE；命中 block 时发送错误事件并结束流 (响应头已发出，annotate 仅计入统计)
+fn filter_sse_response(filter: Filter, response: Response) -> Response {
+    let (mut parts, body) = response.into_parts();
+    parts.headers.remove(header::CONTENT_LENGTH);
+    let mut upstream = body.into_data_stream();
+
+    let stream = async_stream::stream! {
+        let mut pending: Vec<u8> = Vec::new();
+        let mut outcome = FilterOutcome::default();
+        while let Some(chunk) = upstream.next().await {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    yield Err(e);
+                    break;
+                }
+            };
+            pending.extend_from_slice(&chunk);
+            let mut out = Vec::with_capacity(pending.len());
+            while let Some(pos) = pending.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = pending.drain(..=pos).collect();
+                let filtered = filter.apply_sse_line(&line, &mut outcome);
+                // 命中 block 的数据行本身不再发出
+                if outcome.blocked_by.is_some() {
+                    break;
+                }
+                out.extend(filtered);
+            }
+            if let Some(rule) = outcome.blocked_by.clone() {
+                let error_event = format!(
+                    "event: error\ndata: {}\n\n",
+                    blocked_body(Stage::Response, &rule)
+                );
+                out.extend_from_slice(error_event.as_bytes());
+                yield Ok(Bytes::from(out));
+                break;
+            }
+            if !out.is_empty() {
+                yield Ok(Bytes::from(out));
+            }
+        }
+        if outcome.blocked_by.is_none() && !pending.is_empty() {
+            yield Ok(Bytes::from(filter.apply_sse_line(&pending, &mut outcome)));
+        }
+        content_filter::record(Stage::Response, &outcome);
+    };
+    Response::from_parts(parts, Body::from_stream(stream))
+}
+
+pub async fn content_filter_middleware(request: Request, next: Next) -> Response {
+    if request.method() != Method::POST {
+        return next.run(request).await;
+    }
+    let request_filter = content_filter::active(Stage::Request);
+    let response_filter = content_filter::active(Stage::Response);
+    if request_filter.is_none() && response_filter.is_none() {
+        return next.run(request).await;
+    }
+
+    let mut annotations = Vec::new();
+    let request = match &request_filter {
+        Some(filter) => match filter_request(filter, request, &mut annotations).await {
+            Ok(request) => request,
+            Err(rejection) => return rejection,
+        },
+        None => request,
+    };
+
+    let response = next.run(request).await;
+    let mut response = match response_filter {
+        Some(filter) => filter_response(filter, response, &mut annotations).await,
+        None => response,
+    };
+
+    if !annotations.is_empty() {
+        annotations.sort();
+        annotations.dedup();
+        if let Ok(value) = HeaderValue::from_str(&annotations.join(",")) {
+            response.headers_mut().insert(ANNOTATION_HEADER, value);
+        }
+    }
+    response
+}
@@ -0,0 +1,34 @@
+// 单账号试运行作用域：解析 X-Antigravity-Dry-Run 请求头，将请求锁定到指定账号，并回写上游状态码
+use axum::{extract::Request, http::HeaderValue, middleware::Next, response::Response};
+use std::sync::Arc;
+
+use crate::proxy::dry_run::{self, DryRunScope, DRY_RUN_HEADER, UPSTREAM_STATUS_HEADER};
+
+pub async fn dry_run_middleware(mut request: Request, next: Next) -> Response {
+    let Some(value) = request.headers_mut().remove(DRY_RUN_HEADER) else {
+        return next.run(request).await;
+    };
+    let Some(account_id) = value.to_str().ok().and_then(dry_run::parse_header) else {
+        tracing::warn!("[DryRun] Header ignored: invalid secret");
+        return next.run(request).await;
+    };
+    tracing::info!(
+        "[DryRun] {} {} pinned to account {}",
+        request.method(),
+        request.uri().path(),
+        account_id
+    );
+
+    let scope = Arc::new(DryRunScope::new(account_id));
+    let mut response = dry_run::scope(scope.clone(), next.run(request)).await;
+    let statuses = scope
+        .upstream_statuses()
+        .iter()
+        .map(u16::to_string)
+        .collect::<Vec<_>>()
+        .join(",");
+    if let Ok(value) = HeaderValue::from_str(&statuses) {
+        response.headers_mut().insert(UPSTREAM_STATUS_HEADER, value);
+    }
+    response
+}
@@ -0,0 +1,48 @@
+// 故障注入作用域：开发者模式下解析 X-Antigravity-Fault 请求头，供上游客户端按计划模拟故障
+use axum::{
+    extract::Request,
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use crate::proxy::fault_injection::{self, FaultPlan, FAULT_HEADER};
+
+pub async fn fault_injection_middleware(mut request: Request, next: Next) -> Response {
+    let Some(value) = request.headers_mut().remove(FAULT_HEADER) else {
+        return next.run(request).await;
+    };
+    if !fault_injection::is_enabled() {
+        tracing::debug!("[FaultInjection] Header ignored: developer mode is off");
+        return next.run(request).await;
+    }
+
+    let faults = match value
+        .to_str()
+        .map_err(|e| e.to_string())
+        .and_then(fault_injection::parse)
+    {
+        Ok(faults) => faults,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": {
+                        "message": format!("Invalid {} header: {}", FAULT_HEADER, e),
+                        "type": "invalid_request_error",
+                        "code": "invalid_fault_spec"
+                    }
+                })),
+            )
+                .into_response();
+        }
+    };
+    tracing::warn!(
+        "[FaultInjection] {} {} with {} fault(s)",
+        request.method(),
+        request.uri().path(),
+        faults.len()
+    );
+    fault_injection::scope_plan(FaultPlan::new(faults), next.run(request)).await
+}
@@ -1,15 +1,27 @@
 // Middleware 模块 - Axum 中间件
 
+pub mod admission;
 pub mod auth;
+pub mod content_filter;
 pub mod cors;
+pub mod dry_run;
+pub mod fault_injection;
 pub mod logging;
 pub mod monitor;
 pub mod ip_filter;
+pub mod request_context;
+pub mod scoped_prompt;
 
 pub mod service_status;
 
 pub use cors::cors_layer;
 pub use monitor::monitor_middleware;
 pub use service_status::service_status_middleware;
+pub use admission::admission_middleware;
+pub use content_filter::content_filter_middleware;
 pub use auth::{auth_middleware, admin_auth_middleware};
 pub use ip_filter::ip_filter_middleware;
+pub use request_context::request_context_middleware;
+pub use scoped_prompt::scoped_prompt_middleware;
+pub use fault_injection::fault_injection_middleware;
+pub use dry_run::dry_run_middleware;
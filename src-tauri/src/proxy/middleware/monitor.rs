@@ -33,6 +33,31 @@ fn record_user_token_usage(
     }
 }
 
+/// 记录上游首字节时间 / 总耗时 / 响应大小 (按账号统计百分位)
+fn record_latency(log: &ProxyRequestLog, ttfb_ms: u64, duration_ms: u64, response_bytes: u64) {
+    let Some(account) = log.account_email.clone() else {
+        return;
+    };
+    let model = log
+        .mapped_model
+        .clone()
+        .or_else(|| log.model.clone())
+        .unwrap_or_else(|| "unknown".to_string());
+    let status = log.status;
+    tokio::task::spawn_blocking(move || {
+        if let Err(e) = crate::modules::token_stats::record_latency(
+            &account,
+            &model,
+            status,
+            ttfb_ms,
+            duration_ms,
+            response_bytes,
+        ) {
+            tracing::debug!("Failed to record latency stats: {}", e);
+        }
+    });
+}
+
 pub async fn monitor_middleware(
     State(state): State<AppState>,
     request: Request,
@@ -118,6 +143,13 @@ pub async fn monitor_middleware(
     
     let duration = start.elapsed().as_millis() as u64;
     let status = response.status().as_u16();
+    if status >= 500 {
+        let path = uri.split('?').next().unwrap_or_default();
+        crate::modules::event_feed::record(
+            crate::modules::event_feed::EventKind::ProxyError,
+            format!("{} {}", status, path),
+        );
+    }
     
     let content_type = response.headers().get("content-type")
         .and_then(|v| v.to_str().ok())
@@ -211,6 +243,12 @@ pub async fn monitor_middleware(
                     let _ = tx.send(Err(axum::Error::new(e))).await;
                 }
             }
+            record_latency(
+                &log,
+                duration,
+                start.elapsed().as_millis() as u64,
+                all_stream_data.len() as u64,
+            );
             
             // Parse and consolidate stream data into readable format
             if let Ok(full_response) = std::str::from_utf8(&all_stream_data) {
@@ -450,6 +488,12 @@ pub async fn monitor_middleware(
         let (parts, body) = response.into_parts();
         match axum::body::to_bytes(body, MAX_RESPONSE_LOG_SIZE).await {
             Ok(bytes) => {
+                record_latency(
+                    &log,
+                    duration,
+                    start.elapsed().as_millis() as u64,
+                    bytes.len() as u64,
+                );
                 if let Ok(s) = std::str::from_utf8(&bytes) {
                     if let Ok(json) = serde_json::from_str::<Value>(&s) {
                         // 支持 OpenAI "usage" 或 Gemini "usageMetadata"
@@ -499,6 +543,13 @@ pub async fn monitor_middleware(
             }
         }
     } else {
+        let content_length = response
+            .headers()
+            .get("content-length")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(0);
+        record_latency(&log, duration, duration, content_length);
         log.response_body = Some(format!("[{}]", content_type));
 
         // Record User Token Usage
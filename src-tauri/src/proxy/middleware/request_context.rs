@@ -0,0 +1,91 @@
+// 请求上下文中间件 - 为每个代理请求创建 tracing span
+// span 字段 (request_id / account_id) 会附加到该请求期间产生的所有日志上
+
+use axum::{
+    extract::Request,
+    http::HeaderValue,
+    middleware::Next,
+    response::Response,
+};
+use parking_lot::Mutex;
+use std::sync::Arc;
+use tracing::Instrument;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+pub const MODEL_FALLBACK_HEADER: &str = "x-model-fallback";
+
+tokio::task_local! {
+    /// handler 在降级链生效时写入 "原模型 -> 替代模型"
+    static MODEL_FALLBACK: Arc<Mutex<Option<String>>>;
+}
+
+/// 记录本次请求发生的模型降级，由中间件写入响应头 `X-Model-Fallback`
+pub fn record_model_fallback(from: &str, to: &str) {
+    let _ = MODEL_FALLBACK.try_with(|slot| {
+        *slot.lock() = Some(format!("{} -> {}", from, to));
+    });
+}
+
+/// 复用客户端传入的 request id (长度受限), 否则生成一个新的
+fn resolve_request_id(request: &Request) -> String {
+    request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty() && s.len() <= 128)
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| uuid::Uuid::new_v4().simple().to_string())
+}
+
+pub async fn request_context_middleware(request: Request, next: Next) -> Response {
+    let request_id = resolve_request_id(&request);
+
+    // account_id 在 handler 选定账号后通过 Span::current().record 填充
+    let span = tracing::info_span!(
+        "request",
+        request_id = %request_id,
+        method = %request.method(),
+        path = %request.uri().path(),
+        account_id = tracing::field::Empty,
+    );
+
+    let fallback_slot = Arc::new(Mutex::new(None));
+    let mut response = MODEL_FALLBACK
+        .scope(fallback_slot.clone(), next.run(request).instrument(span))
+        .await;
+
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    if let Some(fallback) = fallback_slot.lock().take() {
+        if let Ok(value) = HeaderValue::from_str(&fallback) {
+            response.headers_mut().insert(MODEL_FALLBACK_HEADER, value);
+        }
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+
+    #[test]
+    fn test_resolve_request_id() {
+        let req = Request::builder()
+            .header(REQUEST_ID_HEADER, "abc-123")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(resolve_request_id(&req), "abc-123");
+
+        let req = Request::builder().body(Body::empty()).unwrap();
+        assert_eq!(resolve_request_id(&req).len(), 32);
+
+        let req = Request::builder()
+            .header(REQUEST_ID_HEADER, "x".repeat(200))
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(resolve_request_id(&req).len(), 32);
+    }
+}
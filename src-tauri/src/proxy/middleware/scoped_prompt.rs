@@ -0,0 +1,19 @@
+// 系统提示词注入作用域：记录调用方 (用户令牌) 与跳过请求头，供协议转换器按规则注入
+use axum::{extract::Request, middleware::Next, response::Response};
+
+use crate::proxy::middleware::auth::UserTokenIdentity;
+use crate::proxy::scoped_prompt::{self, PromptCaller, OPT_OUT_HEADER};
+
+pub async fn scoped_prompt_middleware(request: Request, next: Next) -> Response {
+    let identity = request.extensions().get::<UserTokenIdentity>();
+    let caller = PromptCaller {
+        token_id: identity.map(|i| i.token_id.clone()),
+        username: identity.map(|i| i.username.clone()),
+        opt_out: request
+            .headers()
+            .get(OPT_OUT_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(scoped_prompt::is_opt_out),
+    };
+    scoped_prompt::scope_caller(caller, next.run(request)).await
+}
@@ -13,8 +13,14 @@ pub async fn service_status_middleware(
 ) -> Response {
     let path = request.uri().path();
     
-    // Always allow Admin API and Auth callback
-    if path.starts_with("/api/") || path == "/auth/callback" || path == "/health" {
+    // Always allow Admin API, Auth callback and API docs
+    if path.starts_with("/api/")
+        || path == "/auth/callback"
+        || path == "/health"
+        || path == "/openapi.json"
+        || path == "/docs"
+        || path.starts_with("/docs/")
+    {
         return next.run(request).await;
     }
 
@@ -31,5 +37,15 @@ pub async fn service_status_middleware(
             .into_response();
     }
 
+    // 离线模式：直接拒绝，避免请求在上游逐个超时
+    if crate::modules::offline::is_offline() {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "offline: network unavailable, the proxy is paused until connectivity is restored"
+                .to_string(),
+        )
+            .into_response();
+    }
+
     next.run(request).await
 }
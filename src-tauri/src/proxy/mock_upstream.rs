@@ -0,0 +1,527 @@
+// 内置模拟上游 (feature = "mock-upstream")
+// 模拟 OAuth 刷新 / 用户信息、loadCodeAssist、fetchAvailableModels 配额查询以及 generateContent /
+// streamGenerateContent 对话接口。配合 upstream_endpoints.base_url_override 把所有模块指向它，
+// 无需真实 Google 账号即可端到端测试账号轮换与配额保护：
+// - 每次对话请求按 cost_per_request 扣减该模型的剩余配额，耗尽后返回 429 RESOURCE_EXHAUSTED
+// - 标记为 forbidden 的账号所有接口返回 403
+//
+// 手动使用：设置环境变量 ABV_MOCK_UPSTREAM_ADDR=127.0.0.1:18090 启动 (附带一个演示账号，
+// refresh_token 为 mock-refresh-token)，再把 base_url_override 设为 http://127.0.0.1:18090
+
+use axum::{
+    body::{Body, Bytes},
+    http::{header, HeaderMap, Method, StatusCode, Uri},
+    response::{IntoResponse, Response},
+    Json, Router,
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+pub const ENV_ADDR: &str = "ABV_MOCK_UPSTREAM_ADDR";
+const DEMO_REFRESH_TOKEN: &str = "mock-refresh-token";
+const DEFAULT_COST_PER_REQUEST: f64 = 0.1;
+
+#[derive(Debug, Clone)]
+pub struct MockAccount {
+    pub email: String,
+    pub refresh_token: String,
+    pub project_id: String,
+    /// 模型 -> 剩余配额比例 (0.0 - 1.0)；未列出的模型视为不限量
+    pub quotas: BTreeMap<String, f64>,
+    pub forbidden: bool,
+}
+
+impl MockAccount {
+    pub fn new(email: &str, refresh_token: &str) -> Self {
+        Self {
+            email: email.to_string(),
+            refresh_token: refresh_token.to_string(),
+            project_id: format!("mock-project-{}", email.split('@').next().unwrap_or(email)),
+            quotas: ["gemini-3-flash", "gemini-3-pro-high", "claude-sonnet-4-5"]
+                .iter()
+                .map(|m| (m.to_string(), 1.0))
+                .collect(),
+            forbidden: false,
+        }
+    }
+}
+
+/// 模拟上游收到的一次对话请求
+#[derive(Debug, Clone, Serialize)]
+pub struct MockCall {
+    pub email: String,
+    pub method: String,
+    pub model: String,
+    pub status: u16,
+}
+
+#[derive(Default)]
+struct MockState {
+    accounts: Vec<MockAccount>,
+    /// access_token -> 账号下标
+    access_tokens: HashMap<String, usize>,
+    calls: Vec<MockCall>,
+    issued: u64,
+}
+
+#[derive(Clone)]
+pub struct MockUpstream {
+    state: Arc<Mutex<MockState>>,
+    cost_per_request: f64,
+}
+
+impl Default for MockUpstream {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockUpstream {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(MockState::default())),
+            cost_per_request: DEFAULT_COST_PER_REQUEST,
+        }
+    }
+
+    /// 每次对话请求扣减的配额比例
+    pub fn with_cost_per_request(mut self, cost: f64) -> Self {
+        self.cost_per_request = cost;
+        self
+    }
+
+    pub fn add_account(&self, account: MockAccount) {
+        self.lock().accounts.push(account);
+    }
+
+    pub fn set_quota(&self, email: &str, model: &str, remaining: f64) {
+        let mut state = self.lock();
+        if let Some(acc) = state.accounts.iter_mut().find(|a| a.email == email) {
+            acc.quotas
+                .insert(model.to_string(), remaining.clamp(0.0, 1.0));
+        }
+    }
+
+    pub fn set_forbidden(&self, email: &str, forbidden: bool) {
+        let mut state = self.lock();
+        if let Some(acc) = state.accounts.iter_mut().find(|a| a.email == email) {
+            acc.forbidden = forbidden;
+        }
+    }
+
+    pub fn quota(&self, email: &str, model: &str) -> Option<f64> {
+        let state = self.lock();
+        state
+            .accounts
+            .iter()
+            .find(|a| a.email == email)
+            .and_then(|a| a.quotas.get(model).copied())
+    }
+
+    /// 已收到的对话请求 (按时间顺序)
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.lock().calls.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn router(&self) -> Router {
+        let mock = self.clone();
+        Router::new().fallback(
+            move |method: Method, uri: Uri, headers: HeaderMap, body: Bytes| {
+                let mock = mock.clone();
+                async move { mock.dispatch(method, uri, headers, body) }
+            },
+        )
+    }
+
+    /// 在后台启动服务，返回实际监听地址 (端口为 0 时由系统分配)
+    pub async fn serve(&self, addr: SocketAddr) -> Result<SocketAddr, String> {
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .map_err(|e| format!("mock_upstream_bind_failed: {}", e))?;
+        let local = listener
+            .local_addr()
+            .map_err(|e| format!("mock_upstream_bind_failed: {}", e))?;
+        let router = self.router();
+        tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, router).await {
+                tracing::error!("[MockUpstream] Server stopped: {}", e);
+            }
+        });
+        tracing::info!("[MockUpstream] Listening on http://{}", local);
+        Ok(local)
+    }
+
+    fn dispatch(&self, method: Method, uri: Uri, headers: HeaderMap, body: Bytes) -> Response {
+        let path = uri.path();
+        match (method, path) {
+            (Method::POST, "/token") => self.handle_token(&body),
+            (Method::GET, "/oauth2/v2/userinfo") => {
+                self.with_account(&headers, |acc| json!({ "email": acc.email, "name": "Mock User" }))
+            }
+            (Method::POST, "/v1internal:loadCodeAssist") => self.with_account(&headers, |acc| {
+                json!({
+                    "cloudaicompanionProject": acc.project_id,
+                    "currentTier": { "id": "free-tier", "name": "Mock Free" }
+                })
+            }),
+            (Method::POST, "/v1internal:fetchAvailableModels") => {
+                self.with_account(&headers, |acc| {
+                    let models: serde_json::Map<String, Value> = acc
+                        .quotas
+                        .iter()
+                        .map(|(name, remaining)| {
+                            (
+                                name.clone(),
+                                json!({
+                                    "displayName": name,
+                                    "quotaInfo": {
+                                        "remainingFraction": remaining,
+                                        "resetTime": (chrono::Utc::now() + chrono::Duration::hours(5)).to_rfc3339()
+                                    }
+                                }),
+                            )
+                        })
+                        .collect();
+                    json!({ "models": models })
+                })
+            }
+            (Method::POST, "/v1internal:generateContent") => {
+                self.handle_generate(&headers, &body, false)
+            }
+            (Method::POST, "/v1internal:streamGenerateContent") => {
+                self.handle_generate(&headers, &body, true)
+            }
+            _ => error_response(StatusCode::NOT_FOUND, "NOT_FOUND", "unknown mock endpoint"),
+        }
+    }
+
+    fn handle_token(&self, body: &Bytes) -> Response {
+        let form: HashMap<String, String> =
+            url::form_urlencoded::parse(body).into_owned().collect();
+        let refresh_token = form.get("refresh_token").cloned().unwrap_or_default();
+
+        let mut state = self.lock();
+        let Some(idx) = state
+            .accounts
+            .iter()
+            .position(|a| a.refresh_token == refresh_token)
+        else {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": "invalid_grant", "error_description": "Token has been expired or revoked." })),
+            )
+                .into_response();
+        };
+        state.issued += 1;
+        let access_token = format!("mock-access-{}", state.issued);
+        state.access_tokens.insert(access_token.clone(), idx);
+        Json(json!({
+            "access_token": access_token,
+            "expires_in": 3600,
+            "token_type": "Bearer"
+        }))
+        .into_response()
+    }
+
+    fn account_index(&self, state: &MockState, headers: &HeaderMap) -> Option<usize> {
+        let token = headers
+            .get(header::AUTHORIZATION)?
+            .to_str()
+            .ok()?
+            .strip_prefix("Bearer ")?;
+        state.access_tokens.get(token).copied()
+    }
+
+    fn with_account(&self, headers: &HeaderMap, f: impl FnOnce(&MockAccount) -> Value) -> Response {
+        let state = self.lock();
+        let Some(idx) = self.account_index(&state, headers) else {
+            return error_response(
+                StatusCode::UNAUTHORIZED,
+                "UNAUTHENTICATED",
+                "invalid access token",
+            );
+        };
+        let acc = &state.accounts[idx];
+        if acc.forbidden {
+            return error_response(
+                StatusCode::FORBIDDEN,
+                "PERMISSION_DENIED",
+                "account is forbidden",
+            );
+        }
+        Json(f(acc)).into_response()
+    }
+
+    fn handle_generate(&self, headers: &HeaderMap, body: &Bytes, stream: bool) -> Response {
+        let request: Value = serde_json::from_slice(body).unwrap_or(Value::Null);
+        let model = request["model"].as_str().unwrap_or("unknown").to_string();
+        let method = if stream {
+            "streamGenerateContent"
+        } else {
+            "generateContent"
+        };
+
+        let mut state = self.lock();
+        let Some(idx) = self.account_index(&state, headers) else {
+            return error_response(
+                StatusCode::UNAUTHORIZED,
+                "UNAUTHENTICATED",
+                "invalid access token",
+            );
+        };
+        let cost = self.cost_per_request;
+        let acc = &mut state.accounts[idx];
+        let email = acc.email.clone();
+
+        let status = if acc.forbidden {
+            StatusCode::FORBIDDEN
+        } else {
+            match acc.quotas.get_mut(&model) {
+                Some(remaining) if *remaining <= 0.0 => StatusCode::TOO_MANY_REQUESTS,
+                Some(remaining) => {
+                    *remaining = (*remaining - cost).max(0.0);
+                    StatusCode::OK
+                }
+                None => StatusCode::OK,
+            }
+        };
+        state.calls.push(MockCall {
+            email: email.clone(),
+            method: method.to_string(),
+            model: model.clone(),
+            status: status.as_u16(),
+        });
+        drop(state);
+
+        match status {
+            StatusCode::FORBIDDEN => {
+                error_response(status, "PERMISSION_DENIED", "account is forbidden")
+            }
+            StatusCode::TOO_MANY_REQUESTS => quota_exhausted_response(&model),
+            _ => {
+                let text = format!("Mock reply from {} ({})", email, model);
+                if stream {
+                    sse_response(&model, &text)
+                } else {
+                    Json(json!({ "response": candidate(&model, &text, true) })).into_response()
+                }
+            }
+        }
+    }
+}
+
+fn candidate(model: &str, text: &str, last: bool) -> Value {
+    let mut value = json!({
+        "candidates": [{
+            "content": { "role": "model", "parts": [{ "text": text }] }
+        }],
+        "modelVersion": model,
+        "responseId": format!("mock-{}", uuid::Uuid::new_v4().simple())
+    });
+    if last {
+        value["candidates"][0]["finishReason"] = json!("STOP");
+        value["usageMetadata"] = json!({
+            "promptTokenCount": 8,
+            "candidatesTokenCount": 8,
+            "totalTokenCount": 16
+        });
+    }
+    value
+}
+
+fn sse_response(model: &str, text: &str) -> Response {
+    let mid = text
+        .char_indices()
+        .map(|(i, _)| i)
+        .nth(text.chars().count() / 2)
+        .unwrap_or(0);
+    let (head, tail) = text.split_at(mid);
+    let events = [candidate(model, head, false), candidate(model, tail, true)]
+        .iter()
+        .map(|chunk| format!("data: {}\n\n", json!({ "response": chunk })))
+        .collect::<String>();
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/event-stream")
+        .body(Body::from(events))
+        .unwrap_or_else(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())
+}
+
+fn error_response(status: StatusCode, code: &str, message: &str) -> Response {
+    (
+        status,
+        Json(json!({
+            "error": { "code": status.as_u16(), "message": message, "status": code }
+        })),
+    )
+        .into_response()
+}
+
+fn quota_exhausted_response(model: &str) -> Response {
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        Json(json!({
+            "error": {
+                "code": 429,
+                "message": format!("Resource has been exhausted (mock quota for {}).", model),
+                "status": "RESOURCE_EXHAUSTED",
+                "details": [
+                    {
+                        "@type": "type.googleapis.com/google.rpc.ErrorInfo",
+                        "reason": "QUOTA_EXHAUSTED",
+                        "domain": "cloudcode-pa.googleapis.com"
+                    },
+                    {
+                        "@type": "type.googleapis.com/google.rpc.RetryInfo",
+                        "retryDelay": "3600s"
+                    }
+                ]
+            }
+        })),
+    )
+        .into_response()
+}
+
+/// 设置了 ABV_MOCK_UPSTREAM_ADDR 时启动带演示账号的模拟上游
+pub fn start_from_env() {
+    let Ok(addr) = std::env::var(ENV_ADDR) else {
+        return;
+    };
+    let Ok(addr) = addr.trim().parse::<SocketAddr>() else {
+        tracing::warn!("[MockUpstream] Invalid {}: {}", ENV_ADDR, addr);
+        return;
+    };
+    crate::utils::runtime::spawn(async move {
+        let mock = MockUpstream::new();
+        mock.add_account(MockAccount::new("mock@example.com", DEMO_REFRESH_TOKEN));
+        match mock.serve(addr).await {
+            // 保持状态存活直至进程退出
+            Ok(_) => std::future::pending::<()>().await,
+            Err(e) => tracing::error!("[MockUpstream] {}", e),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn start() -> (MockUpstream, String) {
+        let mock = MockUpstream::new().with_cost_per_request(0.5);
+        mock.add_account(MockAccount::new("a@example.com", "rt-a"));
+        mock.add_account(MockAccount::new("b@example.com", "rt-b"));
+        let addr = mock.serve("127.0.0.1:0".parse().unwrap()).await.unwrap();
+        (mock, format!("http://{}", addr))
+    }
+
+    async fn access_token(base: &str, refresh_token: &str) -> String {
+        let resp: Value = reqwest::Client::new()
+            .post(format!("{}/token", base))
+            .form(&[
+                ("grant_type", "refresh_token"),
+                ("refresh_token", refresh_token),
+            ])
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        resp["access_token"].as_str().unwrap().to_string()
+    }
+
+    async fn generate(base: &str, token: &str, model: &str) -> u16 {
+        reqwest::Client::new()
+            .post(format!("{}/v1internal:generateContent", base))
+            .bearer_auth(token)
+            .json(&json!({ "model": model, "request": {} }))
+            .send()
+            .await
+            .unwrap()
+            .status()
+            .as_u16()
+    }
+
+    #[tokio::test]
+    async fn test_quota_drains_then_rotates() {
+        let (mock, base) = start().await;
+        let token_a = access_token(&base, "rt-a").await;
+        let token_b = access_token(&base, "rt-b").await;
+
+        assert_eq!(generate(&base, &token_a, "gemini-3-flash").await, 200);
+        assert_eq!(generate(&base, &token_a, "gemini-3-flash").await, 200);
+        assert_eq!(mock.quota("a@example.com", "gemini-3-flash"), Some(0.0));
+        assert_eq!(generate(&base, &token_a, "gemini-3-flash").await, 429);
+        assert_eq!(generate(&base, &token_b, "gemini-3-flash").await, 200);
+
+        let quota: Value = reqwest::Client::new()
+            .post(format!("{}/v1internal:fetchAvailableModels", base))
+            .bearer_auth(&token_a)
+            .json(&json!({}))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(
+            quota["models"]["gemini-3-flash"]["quotaInfo"]["remainingFraction"],
+            json!(0.0)
+        );
+
+        let statuses: Vec<(String, u16)> = mock
+            .calls()
+            .into_iter()
+            .map(|c| (c.email, c.status))
+            .collect();
+        assert_eq!(
+            statuses.last().unwrap(),
+            &("b@example.com".to_string(), 200)
+        );
+        assert_eq!(statuses.iter().filter(|(_, s)| *s == 429).count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_auth_and_forbidden() {
+        let (mock, base) = start().await;
+        let status = reqwest::Client::new()
+            .post(format!("{}/token", base))
+            .form(&[("grant_type", "refresh_token"), ("refresh_token", "nope")])
+            .send()
+            .await
+            .unwrap()
+            .status();
+        assert_eq!(status, 400);
+        assert_eq!(generate(&base, "bogus", "gemini-3-flash").await, 401);
+
+        let token = access_token(&base, "rt-a").await;
+        mock.set_forbidden("a@example.com", true);
+        assert_eq!(generate(&base, &token, "gemini-3-flash").await, 403);
+    }
+
+    #[tokio::test]
+    async fn test_stream_returns_sse_chunks() {
+        let (_mock, base) = start().await;
+        let token = access_token(&base, "rt-a").await;
+        let body = reqwest::Client::new()
+            .post(format!("{}/v1internal:streamGenerateContent?alt=sse", base))
+            .bearer_auth(&token)
+            .json(&json!({ "model": "claude-sonnet-4-5" }))
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        let events: Vec<&str> = body.split("\n\n").filter(|e| !e.is_empty()).collect();
+        assert_eq!(events.len(), 2);
+        assert!(events[1].contains("\"finishReason\":\"STOP\""));
+    }
+}
@@ -8,30 +8,58 @@ pub mod server;
 pub mod token_manager;
 
 // 新架构模块
+pub mod admission; // 按 API Key 优先级的准入控制
 pub mod audio; // 音频处理模块
+pub mod capacity_planner; // 按模型的最低可用账号数告警
 pub mod cli_sync; // CLI 配置同步 (v3.3.35)
 pub mod droid_sync; // Droid (Factory CLI) 配置同步
 pub mod common; // 公共工具
+pub mod content_filter; // 提示词 / 响应内容过滤
 pub mod debug_logger;
+pub mod dry_run; // 单账号试运行 (锁定账号的测试请求)
+pub mod replay; // 请求日志重放 (可锁定到其他账号并比较响应)
+pub mod fault_injection; // 开发者模式：按请求注入模拟的上游故障
 pub mod handlers; // API 端点处理器
 pub mod mappers; // 协议转换器
+pub mod mdns; // 局域网 mDNS 广播 (_abvproxy._tcp)
 pub mod middleware; // Axum 中间件
+pub mod model_availability; // 受监控模型的可用账号数 (可用数降为 0 时告警)
+#[cfg(feature = "mock-upstream")]
+pub mod mock_upstream; // 内置模拟上游 (离线集成测试)
 pub mod monitor; // 监控
+pub mod openapi; // OpenAPI 文档 (/openapi.json, /docs)
 pub mod opencode_sync; // OpenCode 配置同步
+pub mod pool_simulation; // 账号池负载模拟 (配额消耗 / 耗尽时间预测)
 pub mod providers; // Extra upstream providers (z.ai, etc.)
 pub mod proxy_pool; // 代理池管理器
 pub mod rate_limit; // 限流跟踪
+pub mod reload_debounce; // 账号重载信号合并 (防止重载风暴)
+pub mod scoped_prompt; // 按 API Key / 账号标签注入系统提示词
 pub mod model_specs; // 模型规格管理 (v4.1.28)
 pub mod session_manager; // 会话指纹管理
+pub mod session_pool; // 上游会话 ID 池 (按账号持久化 / 轮换)
 pub mod signature_cache; // Signature Cache (v3.3.16)
 pub mod sticky_config; // 粘性调度配置
+pub mod token_state; // TokenManager 内存状态快照 (排查路由问题)
 pub mod upstream; // 上游客户端
 pub mod zai_vision_mcp; // Built-in Vision MCP server state
 pub mod zai_vision_tools; // Built-in Vision MCP tools (z.ai vision API) // 调试日志
 
+pub use config::update_fallback_chains;
+pub use config::update_tier_routing;
+pub use config::update_admission_config;
+pub use config::update_session_pool_config;
+pub use config::update_circuit_breaker_config;
+pub use config::update_upstream_timeouts;
+pub use config::update_quota_staleness_minutes;
+pub use config::update_content_filter_config;
+pub use config::update_scoped_system_prompts;
+pub use config::update_request_log_config;
+pub use config::update_fault_injection_config;
 pub use config::update_global_system_prompt_config;
 pub use config::update_thinking_budget_config;
 pub use config::update_image_thinking_mode;
+pub use config::AdminRole;
 pub use config::ProxyAuthMode;
 pub use config::ProxyConfig;
 pub use config::ProxyPoolConfig;
@@ -0,0 +1,178 @@
+// 模型可用性矩阵
+// 针对配额保护的 monitored_models (以及容量阈值中的模型分组，见 capacity_planner)，统计反代账号池中当前能处理每个模型的账号数:
+// 账号在池中 (已启用且未禁用反代)、该模型未被配额保护、未处于限流冷却 / 验证封锁、且仍有配额。
+// 后台清理任务每轮重新计算，某个模型的可用账号数降为 0 时记录告警事件并通知前端，恢复后再次告警才会重新触发。
+
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use crate::proxy::rate_limit::RateLimitTracker;
+use crate::proxy::token_manager::ProxyToken;
+
+pub const WARNING_EVENT: &str = "models://availability-warning";
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ModelAvailability {
+    /// 标准模型分组 ID
+    pub model: String,
+    /// 当前可处理该模型的账号数
+    pub available: usize,
+    /// 反代账号池中的账号总数
+    pub total: usize,
+    /// 因配额保护被排除
+    pub protected: usize,
+    /// 处于限流冷却或验证封锁
+    pub cooling: usize,
+    /// 没有该模型的剩余配额
+    pub exhausted: usize,
+}
+
+/// 上一轮可用数为 0 的模型 (避免重复告警)
+static UNAVAILABLE: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// 统计每个模型的可用账号数
+pub fn compute(
+    tokens: &DashMap<String, ProxyToken>,
+    models: &[String],
+    tracker: &RateLimitTracker,
+    breaker_enabled: bool,
+    staleness_minutes: u32,
+    now: i64,
+) -> Vec<ModelAvailability> {
+    models
+        .iter()
+        .map(|model| {
+            let mut row = ModelAvailability {
+                model: model.clone(),
+                available: 0,
+                total: tokens.len(),
+                protected: 0,
+                cooling: 0,
+                exhausted: 0,
+            };
+            for entry in tokens.iter() {
+                let t = entry.value();
+                if t.protected_models.contains(model) {
+                    row.protected += 1;
+                } else if (t.validation_blocked && t.validation_blocked_until > now)
+                    || (breaker_enabled && tracker.is_rate_limited(&t.account_id, Some(model)))
+                {
+                    row.cooling += 1;
+                } else if !t
+                    .model_quotas
+                    .get(model)
+                    .is_some_and(|&pct| pct > 0 || t.is_quota_stale(staleness_minutes, now))
+                {
+                    row.exhausted += 1;
+                } else {
+                    row.available += 1;
+                }
+            }
+            row
+        })
+        .collect()
+}
+
+/// 与上一轮比较，可用数降为 0 的模型返回并记录；恢复的模型从记录中移除
+fn newly_unavailable(report: &[ModelAvailability]) -> Vec<ModelAvailability> {
+    let mut unavailable = UNAVAILABLE.lock().unwrap_or_else(|e| e.into_inner());
+    unavailable.retain(|model| {
+        report
+            .iter()
+            .any(|row| &row.model == model && row.available == 0)
+    });
+    report
+        .iter()
+        .filter(|row| row.available == 0 && unavailable.insert(row.model.clone()))
+        .cloned()
+        .collect()
+}
+
+/// 后台任务每轮调用：可用数降为 0 时发出告警
+pub fn observe(report: &[ModelAvailability]) {
+    for row in newly_unavailable(report) {
+        tracing::warn!(
+            "[Availability] No account can serve {} ({} protected, {} cooling, {} exhausted of {})",
+            row.model,
+            row.protected,
+            row.cooling,
+            row.exhausted,
+            row.total
+        );
+        crate::modules::event_feed::record(
+            crate::modules::event_feed::EventKind::ModelUnavailable,
+            format!("{}: 0/{}", row.model, row.total),
+        );
+        crate::modules::log_bridge::emit_event(WARNING_EVENT, row);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(id: &str, quotas: &[(&str, i32)], protected: &[&str]) -> ProxyToken {
+        ProxyToken {
+            account_id: id.to_string(),
+            access_token: String::new(),
+            refresh_token: String::new(),
+            expires_in: 0,
+            timestamp: 0,
+            email: format!("{}@example.com", id),
+            account_path: Default::default(),
+            project_id: None,
+            provider: Default::default(),
+            subscription_tier: None,
+            remaining_quota: None,
+            protected_models: protected.iter().map(|s| s.to_string()).collect(),
+            health_score: 1.0,
+            reset_time: None,
+            validation_blocked: false,
+            validation_blocked_until: 0,
+            validation_url: None,
+            model_quotas: quotas.iter().map(|(m, p)| (m.to_string(), *p)).collect(),
+            model_limits: Default::default(),
+            tags: Vec::new(),
+            quota_updated_at: None,
+        }
+    }
+
+    #[test]
+    fn test_compute_and_warn_once() {
+        let tokens = DashMap::new();
+        tokens.insert(
+            "a".to_string(),
+            token("a", &[("claude", 80), ("gemini-3-pro", 0)], &[]),
+        );
+        tokens.insert("b".to_string(), token("b", &[("claude", 50)], &["claude"]));
+        tokens.insert("c".to_string(), token("c", &[("claude", 50)], &[]));
+        let tracker = RateLimitTracker::new();
+        tracker.set_lockout_until(
+            "c",
+            std::time::SystemTime::now() + std::time::Duration::from_secs(60),
+            crate::proxy::rate_limit::RateLimitReason::RateLimitExceeded,
+            Some("claude".to_string()),
+        );
+        let models = vec!["claude".to_string(), "gemini-3-pro".to_string()];
+        let report = compute(&tokens, &models, &tracker, true, 0, 0);
+
+        assert_eq!(
+            report[0],
+            ModelAvailability {
+                model: "claude".to_string(),
+                available: 1,
+                total: 3,
+                protected: 1,
+                cooling: 1,
+                exhausted: 0,
+            }
+        );
+        assert_eq!((report[1].available, report[1].exhausted), (0, 3));
+
+        assert_eq!(newly_unavailable(&report).len(), 1);
+        assert!(newly_unavailable(&report).is_empty());
+    }
+}
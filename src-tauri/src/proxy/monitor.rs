@@ -1,8 +1,32 @@
 use serde::{Serialize, Deserialize};
 use std::collections::VecDeque;
 use tokio::sync::RwLock;
-use tauri::Emitter;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+
+/// 最近请求统计窗口 (秒)，托盘提示使用；与日志记录开关无关
+pub const RECENT_WINDOW_SECS: i64 = 300;
+
+static RECENT_REQUESTS: Lazy<Mutex<VecDeque<i64>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+
+fn record_recent_request(now: i64) {
+    if let Ok(mut recent) = RECENT_REQUESTS.lock() {
+        recent.push_back(now);
+        while recent.front().is_some_and(|ts| *ts <= now - RECENT_WINDOW_SECS) {
+            recent.pop_front();
+        }
+    }
+}
+
+/// 最近 `window_secs` 秒 (不超过 RECENT_WINDOW_SECS) 内经过反代的请求数
+pub fn recent_request_count(window_secs: i64) -> usize {
+    let since = chrono::Utc::now().timestamp() - window_secs.min(RECENT_WINDOW_SECS);
+    RECENT_REQUESTS
+        .lock()
+        .map(|recent| recent.iter().filter(|ts| **ts > since).count())
+        .unwrap_or(0)
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProxyRequestLog {
@@ -38,36 +62,23 @@ pub struct ProxyMonitor {
     pub stats: RwLock<ProxyStats>,
     pub max_logs: usize,
     pub enabled: AtomicBool,
-    app_handle: Option<tauri::AppHandle>,
 }
 
 impl ProxyMonitor {
-    pub fn new(max_logs: usize, app_handle: Option<tauri::AppHandle>) -> Self {
+    pub fn new(max_logs: usize) -> Self {
         // Initialize DB
         if let Err(e) = crate::modules::proxy_db::init_db() {
             tracing::error!("Failed to initialize proxy DB: {}", e);
         }
 
-        // Auto cleanup old logs (keep last 30 days)
-        tokio::spawn(async {
-            match crate::modules::proxy_db::cleanup_old_logs(30) {
-                Ok(deleted) => {
-                    if deleted > 0 {
-                        tracing::info!("Auto cleanup: removed {} old logs (>30 days)", deleted);
-                    }
-                }
-                Err(e) => {
-                    tracing::error!("Failed to cleanup old logs: {}", e);
-                }
-            }
-        });
+        // Retention / compaction per proxy.request_log (hourly, first pass immediately)
+        crate::modules::proxy_db::start_maintenance();
 
         Self {
             logs: RwLock::new(VecDeque::with_capacity(max_logs)),
             stats: RwLock::new(ProxyStats::default()),
             max_logs,
             enabled: AtomicBool::new(false), // Default to disabled
-            app_handle,
         }
     }
 
@@ -80,6 +91,8 @@ impl ProxyMonitor {
     }
 
     pub async fn log_request(&self, log: ProxyRequestLog) {
+        record_recent_request(chrono::Utc::now().timestamp());
+
         if let (Some(account), Some(input), Some(output)) = (
             &log.account_email,
             log.input_tokens,
@@ -161,29 +174,27 @@ impl ProxyMonitor {
         });
 
         // Emit event (send summary only, without body to reduce memory)
-        if let Some(app) = &self.app_handle {
-            let log_summary = ProxyRequestLog {
-                id: log.id.clone(),
-                timestamp: log.timestamp,
-                method: log.method.clone(),
-                url: log.url.clone(),
-                status: log.status,
-                duration: log.duration,
-                model: log.model.clone(),
-                mapped_model: log.mapped_model.clone(),
-                account_email: log.account_email.clone(),
-                client_ip: log.client_ip.clone(),
-                error: log.error.clone(),
-                request_body: None,  // Don't send body in event
-                response_body: None, // Don't send body in event
-                input_tokens: log.input_tokens,
-                output_tokens: log.output_tokens,
-                protocol: log.protocol.clone(),
-                username: log.username.clone(),
-                cursor_payload_kind: log.cursor_payload_kind.clone(),
-            };
-            let _ = app.emit("proxy://request", &log_summary);
-        }
+        let log_summary = ProxyRequestLog {
+            id: log.id.clone(),
+            timestamp: log.timestamp,
+            method: log.method.clone(),
+            url: log.url.clone(),
+            status: log.status,
+            duration: log.duration,
+            model: log.model.clone(),
+            mapped_model: log.mapped_model.clone(),
+            account_email: log.account_email.clone(),
+            client_ip: log.client_ip.clone(),
+            error: log.error.clone(),
+            request_body: None,  // Don't send body in event
+            response_body: None, // Don't send body in event
+            input_tokens: log.input_tokens,
+            output_tokens: log.output_tokens,
+            protocol: log.protocol.clone(),
+            username: log.username.clone(),
+            cursor_payload_kind: log.cursor_payload_kind.clone(),
+        };
+        crate::modules::log_bridge::emit_event("proxy://request", log_summary);
     }
 
     pub async fn get_logs(&self, limit: usize) -> Vec<ProxyRequestLog> {
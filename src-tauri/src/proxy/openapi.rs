@@ -0,0 +1,393 @@
+//! OpenAPI document for the proxy and admin HTTP endpoints.
+//! Served at `/openapi.json`, with a Swagger UI (assets bundled into the binary) at `/docs`.
+//!
+//! Operations are declared with `#[utoipa::path]` next to each handler; a unit test
+//! checks that every route registered in `proxy::server` shows up in the document.
+
+use crate::proxy::{handlers, server};
+use utoipa::openapi::path::{Operation, PathItem};
+use utoipa::openapi::security::{Http, HttpAuthScheme, SecurityRequirement, SecurityScheme};
+use utoipa::openapi::{ContentBuilder, Ref, ResponseBuilder};
+use utoipa::{Modify, OpenApi};
+
+/// Routes served by the same handler under a second path: (alias, documented path)
+const ROUTE_ALIASES: &[(&str, &str)] = &[
+    ("/healthz", "/health"),
+    ("/api/health", "/health"),
+    ("/v1/responses", "/v1/completions"),
+    ("/api/stats/summary", "/api/stats/token/summary"),
+    ("/api/stats/hourly", "/api/stats/token/hourly"),
+    ("/api/stats/daily", "/api/stats/token/daily"),
+    ("/api/stats/weekly", "/api/stats/token/weekly"),
+    ("/api/stats/accounts", "/api/stats/token/by-account"),
+    ("/api/stats/models", "/api/stats/token/by-model"),
+];
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Antigravity Tools API",
+        description = "AI proxy endpoints (OpenAI / Anthropic / Gemini compatible) and the admin API under /api."
+    ),
+    paths(
+        server::health_check_handler,
+        handlers::openai::handle_list_models,
+        handlers::openai::handle_chat_completions,
+        handlers::openai::handle_completions,
+        handlers::openai::handle_images_generations,
+        handlers::openai::handle_images_edits,
+        handlers::audio::handle_audio_transcription,
+        handlers::claude::handle_messages,
+        handlers::cursor::handle_cursor_chat_completions,
+        handlers::claude::handle_count_tokens,
+        handlers::claude::handle_list_models,
+        handlers::mcp::handle_web_search_prime,
+        handlers::mcp::handle_web_reader,
+        handlers::mcp::handle_zai_mcp_server,
+        handlers::gemini::handle_list_models,
+        handlers::gemini::handle_get_model,
+        handlers::gemini::handle_generate,
+        handlers::gemini::handle_count_tokens,
+        handlers::common::handle_detect_model,
+        server::admin_list_accounts,
+        server::admin_add_account,
+        server::admin_list_account_summaries,
+        server::admin_create_account_share,
+        server::admin_redeem_account_share,
+        server::admin_set_proxy_status_bulk,
+        server::admin_get_current_account,
+        server::admin_get_project_binding_history,
+        server::admin_simulate_pool_workload,
+        server::admin_get_quota_history,
+        server::admin_switch_account,
+        server::admin_switch_back,
+        server::admin_get_switch_history,
+        server::admin_get_last_switch_report,
+        server::admin_refresh_all_quotas,
+        server::admin_refresh_due_quotas,
+        server::admin_refresh_quota_for_accounts,
+        server::admin_list_operations,
+        server::admin_cancel_operation,
+        server::admin_delete_account,
+        server::admin_bind_device,
+        server::admin_verify_account_login,
+        server::admin_get_device_profiles,
+        server::admin_list_device_versions,
+        server::admin_preview_generate_profile,
+        server::admin_bind_device_profile_with_profile,
+        server::admin_restore_original_device,
+        server::admin_restore_device_version,
+        server::admin_delete_device_version,
+        server::admin_import_v1_accounts,
+        server::admin_import_from_db,
+        server::admin_import_custom_db,
+        server::admin_sync_account_from_db,
+        server::admin_get_config,
+        server::admin_save_config,
+        server::admin_export_app_config,
+        server::admin_import_app_config,
+        server::admin_reset_config_section,
+        server::admin_list_config_history,
+        server::admin_diff_config_versions,
+        server::admin_rollback_config,
+        server::admin_get_cli_sync_status,
+        server::admin_execute_cli_sync,
+        server::admin_execute_cli_restore,
+        server::admin_get_cli_config_content,
+        server::admin_get_opencode_sync_status,
+        server::admin_execute_opencode_sync,
+        server::admin_execute_opencode_restore,
+        server::admin_execute_opencode_clear,
+        server::admin_get_opencode_config_content,
+        server::admin_get_droid_sync_status,
+        server::admin_execute_droid_sync,
+        server::admin_execute_droid_restore,
+        server::admin_get_droid_config_content,
+        server::admin_get_proxy_status,
+        server::admin_get_proxy_pool_config,
+        server::admin_get_all_account_bindings,
+        server::admin_bind_account_proxy,
+        server::admin_unbind_account_proxy,
+        server::admin_get_account_proxy_binding,
+        server::admin_trigger_proxy_health_check,
+        server::admin_start_proxy_service,
+        server::admin_stop_proxy_service,
+        server::admin_update_model_mapping,
+        server::admin_generate_api_key,
+        server::admin_clear_proxy_session_bindings,
+        server::admin_clear_all_rate_limits,
+        server::admin_clear_rate_limit,
+        server::admin_get_preferred_account,
+        server::admin_set_preferred_account,
+        server::admin_get_model_availability,
+        server::admin_get_token_manager_state,
+        server::admin_get_capacity_status,
+        server::admin_prepare_oauth_url,
+        server::admin_start_oauth_login,
+        server::admin_complete_oauth_login,
+        server::admin_cancel_oauth_login,
+        server::admin_submit_oauth_code,
+        server::admin_fetch_zai_models,
+        server::admin_list_known_models,
+        server::admin_sync_model_catalog,
+        server::admin_get_monitored_model_options,
+        server::admin_update_monitored_models,
+        server::admin_set_proxy_monitor_enabled,
+        server::admin_cloudflared_get_status,
+        server::admin_cloudflared_install,
+        server::admin_cloudflared_start,
+        server::admin_cloudflared_stop,
+        server::admin_open_folder,
+        server::admin_get_proxy_stats,
+        server::admin_get_admission_stats,
+        server::admin_list_upstream_sessions,
+        server::admin_rotate_upstream_sessions,
+        server::admin_get_upstream_circuits,
+        server::admin_get_content_filter_stats,
+        server::admin_reset_content_filter_stats,
+        server::admin_get_proxy_logs_filtered,
+        server::admin_get_proxy_logs_count_filtered,
+        server::admin_clear_proxy_logs,
+        server::admin_compact_proxy_logs,
+        server::admin_get_proxy_log_detail,
+        server::admin_replay_request,
+        server::admin_enable_debug_console,
+        server::admin_disable_debug_console,
+        server::admin_is_debug_console_enabled,
+        server::admin_get_debug_console_logs,
+        server::admin_clear_debug_console_logs,
+        server::admin_tail_events,
+        server::admin_clear_token_stats,
+        server::admin_run_data_retention,
+        server::admin_run_account_hygiene,
+        server::admin_run_problem_digest,
+        server::admin_run_remote_backup,
+        server::admin_list_remote_backups,
+        server::admin_restore_remote_backup,
+        server::admin_get_backup_status,
+        server::admin_sync_accounts_now,
+        server::admin_get_sync_status,
+        server::admin_get_token_stats_hourly,
+        server::admin_get_token_stats_daily,
+        server::admin_get_token_stats_weekly,
+        server::admin_get_token_stats_by_account,
+        server::admin_get_token_stats_summary,
+        server::admin_get_token_stats_by_model,
+        server::admin_get_account_latency_stats,
+        server::admin_get_token_stats_model_trend_hourly,
+        server::admin_get_token_stats_model_trend_daily,
+        server::admin_get_token_stats_account_trend_hourly,
+        server::admin_get_token_stats_account_trend_daily,
+        server::admin_delete_accounts,
+        server::admin_export_accounts,
+        server::admin_reorder_accounts,
+        server::admin_fetch_account_quota,
+        server::admin_toggle_proxy_status,
+        server::admin_warm_up_all_accounts,
+        server::admin_warm_up_account,
+        server::admin_test_account_request,
+        server::admin_update_account_tags,
+        server::admin_update_account_ip_family,
+        server::admin_update_account_antigravity_args,
+        server::admin_update_account_antigravity_executable,
+        server::admin_list_orphan_accounts,
+        server::admin_snooze_account,
+        server::admin_adopt_orphan,
+        server::admin_list_corrupt_accounts,
+        server::admin_restore_corrupt_account,
+        server::admin_delete_corrupt_account,
+        server::admin_get_data_dir_path,
+        server::admin_get_startup_report,
+        server::admin_list_notifications,
+        server::admin_mark_notifications_read,
+        server::admin_get_update_settings,
+        server::admin_should_check_updates,
+        server::admin_check_for_updates,
+        server::admin_update_last_check_time,
+        server::admin_save_update_settings,
+        server::admin_is_auto_launch_enabled,
+        server::admin_toggle_auto_launch,
+        server::admin_get_http_api_settings,
+        server::admin_save_http_api_settings,
+        server::admin_get_antigravity_path,
+        server::admin_detect_antigravity_installations,
+        server::admin_get_antigravity_args,
+        server::admin_clear_antigravity_cache,
+        server::admin_get_antigravity_cache_paths,
+        server::admin_clear_log_cache,
+        server::admin_export_logs,
+        server::admin_query_logs,
+        server::admin_generate_diagnostics_bundle,
+        server::admin_verify_data_integrity,
+        server::admin_apply_integrity_repair,
+        server::admin_get_network_status,
+        server::admin_set_offline_mode,
+        server::admin_get_clock_skew,
+        server::admin_scheduler_status,
+        server::admin_set_scheduler_job_enabled,
+        server::admin_get_onboarding_state,
+        server::admin_complete_onboarding_step,
+        server::admin_list_available_languages,
+        server::admin_get_locale_messages,
+        server::admin_get_log_levels,
+        server::admin_set_log_levels,
+        server::admin_get_ip_access_logs,
+        server::admin_clear_ip_access_logs,
+        server::admin_get_ip_stats,
+        server::admin_get_ip_token_stats,
+        server::admin_get_ip_blacklist,
+        server::admin_add_ip_to_blacklist,
+        server::admin_remove_ip_from_blacklist,
+        server::admin_clear_ip_blacklist,
+        server::admin_check_ip_in_blacklist,
+        server::admin_get_ip_whitelist,
+        server::admin_add_ip_to_whitelist,
+        server::admin_remove_ip_from_whitelist,
+        server::admin_clear_ip_whitelist,
+        server::admin_check_ip_in_whitelist,
+        server::admin_get_security_config,
+        server::admin_update_security_config,
+        server::admin_list_user_tokens,
+        server::admin_create_user_token,
+        server::admin_get_user_token_summary,
+        server::admin_get_billing_report,
+        server::admin_export_billing_report,
+        server::admin_renew_user_token,
+        server::admin_delete_user_token,
+        server::admin_update_user_token,
+        server::admin_prepare_oauth_url_web,
+    ),
+    components(schemas(server::ErrorResponse)),
+    modifiers(&ProxyApiModifier),
+    tags(
+        (name = "OpenAI"), (name = "Claude"), (name = "Gemini"), (name = "MCP"), (name = "System"),
+        (name = "Accounts"), (name = "Stats"), (name = "Config"), (name = "Proxy"), (name = "Logs"),
+        (name = "Security"), (name = "User Tokens")
+    )
+)]
+pub struct ApiDoc;
+
+/// Adds route aliases, auth schemes and the shared default / error responses
+struct ProxyApiModifier;
+
+impl Modify for ProxyApiModifier {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)),
+        );
+        components.add_security_scheme(
+            "admin_key",
+            SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)),
+        );
+
+        let paths = &mut openapi.paths.paths;
+        for (alias, documented) in ROUTE_ALIASES {
+            if let Some(item) = paths.get(*documented).cloned() {
+                paths.insert(alias.to_string(), item);
+            }
+        }
+
+        for (path, item) in paths.iter_mut() {
+            let admin = path.starts_with("/api/");
+            let scheme = if admin { "admin_key" } else { "api_key" };
+            for op in operations_mut(item) {
+                op.security = Some(vec![SecurityRequirement::new(scheme, Vec::<String>::new())]);
+                let responses = &mut op.responses.responses;
+                responses
+                    .entry("200".to_string())
+                    .or_insert_with(|| ResponseBuilder::new().description("Success").build().into());
+                responses.entry("401".to_string()).or_insert_with(|| {
+                    ResponseBuilder::new()
+                        .description("Missing or invalid API key")
+                        .build()
+                        .into()
+                });
+                if admin {
+                    responses.entry("500".to_string()).or_insert_with(|| {
+                        ResponseBuilder::new()
+                            .description("Error")
+                            .content(
+                                "application/json",
+                                ContentBuilder::new()
+                                    .schema(Some(Ref::from_schema_name("ErrorResponse")))
+                                    .build(),
+                            )
+                            .build()
+                            .into()
+                    });
+                }
+            }
+        }
+    }
+}
+
+fn operations_mut(item: &mut PathItem) -> impl Iterator<Item = &mut Operation> {
+    [
+        &mut item.get,
+        &mut item.put,
+        &mut item.post,
+        &mut item.delete,
+        &mut item.patch,
+        &mut item.head,
+        &mut item.options,
+        &mut item.trace,
+    ]
+    .into_iter()
+    .filter_map(|op| op.as_mut())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_document_serializes_with_schemas() {
+        let doc = ApiDoc::openapi();
+        let json = doc.to_json().expect("serialize openapi");
+        assert!(json.contains("/api/accounts/{accountId}"));
+
+        let chat = doc.paths.paths["/v1/chat/completions"].post.as_ref().unwrap();
+        assert!(chat.request_body.is_some());
+        assert!(chat.security.as_ref().unwrap()[0] == SecurityRequirement::new("api_key", Vec::<String>::new()));
+
+        let schemas = &doc.components.as_ref().unwrap().schemas;
+        for name in ["OpenAIRequest", "ClaudeRequest", "AddAccountRequest", "AccountListResponse", "ErrorResponse"] {
+            assert!(schemas.contains_key(name), "schema {} missing", name);
+        }
+        assert!(doc.paths.paths["/v1beta/models/{model}"].get.is_some());
+        assert!(doc.paths.paths["/v1beta/models/{model}"].post.is_some());
+        assert!(doc.paths.paths.contains_key("/api/stats/summary"));
+    }
+
+    #[test]
+    fn test_document_covers_server_routes() {
+        // Every `.route("...")` registered in server.rs must be documented
+        let doc = ApiDoc::openapi();
+        let documented: Vec<String> = doc
+            .paths
+            .paths
+            .keys()
+            .map(|p| p.replace('{', ":").replace('}', ""))
+            .collect();
+        let source = include_str!("server.rs");
+        let internal = ["/internal/warmup", "/v1/api/event_logging", "/v1/api/event_logging/batch"];
+        let public = ["/auth/callback", "/api/quota-summary"];
+
+        for chunk in source.split(".route(").skip(1) {
+            let Some(path) = chunk.trim_start().strip_prefix('"').and_then(|s| s.split('"').next()) else {
+                continue;
+            };
+            if internal.contains(&path) || public.contains(&path) {
+                continue;
+            }
+            let admin_path = format!("/api{}", path);
+            assert!(
+                documented.iter().any(|p| p == path || *p == admin_path),
+                "route {} is missing from the OpenAPI document",
+                path
+            );
+        }
+    }
+}
@@ -0,0 +1,517 @@
+// 账号池负载模拟
+// 给定合成负载 (各模型每分钟请求数 × 持续小时数) 与当前账号池状态，按令牌管理器的
+// 轮换 / 配额保护规则逐分钟推演，预测各账号的配额消耗与耗尽时间，用于评估账号池能否撑过高峰。
+// 推演是确定性的：相同输入始终得到相同结果 (无随机数，小数请求数按分钟累加)。
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+use crate::models::{Account, QuotaProtectionConfig};
+use crate::proxy::common::model_mapping::normalize_to_standard_id;
+use crate::proxy::config::TierRoutingConfig;
+use crate::proxy::sticky_config::SchedulingMode;
+use crate::proxy::token_manager::{is_premium_model, tier_rank};
+
+/// 单次请求默认消耗的配额百分比
+const DEFAULT_COST_PCT: f64 = 0.5;
+/// 上游配额的刷新周期 (小时)
+const DEFAULT_RESET_WINDOW_HOURS: f64 = 5.0;
+const MAX_HOURS: f64 = 72.0;
+const STEP_SECS: i64 = 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkloadModel {
+    pub model: String,
+    pub requests_per_minute: f64,
+    /// 单次请求消耗的配额百分比，缺省为 0.5
+    #[serde(default)]
+    pub cost_pct: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationWorkload {
+    pub hours: f64,
+    pub models: Vec<WorkloadModel>,
+    /// 配额刷新周期，缺省为 5 小时
+    #[serde(default)]
+    pub reset_window_hours: Option<f64>,
+    /// 覆盖当前调度模式
+    #[serde(default)]
+    pub mode: Option<SchedulingMode>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelProjection {
+    pub model: String,
+    pub start_pct: f64,
+    pub end_pct: f64,
+    pub requests: u64,
+    pub consumed_pct: f64,
+    /// 首次降至 0% 的时间
+    pub exhausted_at: Option<i64>,
+    /// 首次触发配额保护的时间
+    pub protected_at: Option<i64>,
+    pub resets: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountProjection {
+    pub account_id: String,
+    pub email: String,
+    pub subscription_tier: Option<String>,
+    pub models: Vec<ModelProjection>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelOutcome {
+    pub model: String,
+    pub requested: u64,
+    pub served: u64,
+    pub failed: u64,
+    /// 首个无可用账号的时间
+    pub first_failure_at: Option<i64>,
+    pub survives: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SimulationReport {
+    pub started_at: i64,
+    pub ends_at: i64,
+    pub mode: SchedulingMode,
+    pub eligible_accounts: usize,
+    pub accounts: Vec<AccountProjection>,
+    pub models: Vec<ModelOutcome>,
+    pub survives: bool,
+}
+
+/// 推演用的账号快照
+#[derive(Debug, Clone)]
+pub struct SimAccount {
+    pub account_id: String,
+    pub email: String,
+    pub subscription_tier: Option<String>,
+    /// 标准模型 ID -> (剩余百分比, 下次刷新时间)
+    pub quotas: BTreeMap<String, (f64, Option<i64>)>,
+    pub protected_models: HashSet<String>,
+}
+
+impl SimAccount {
+    /// 不参与反代的账号返回 None (禁用 / 403 / 验证阻断)
+    pub fn from_account(account: &Account, now: i64) -> Option<Self> {
+        if account.disabled || account.proxy_disabled {
+            return None;
+        }
+        if account.validation_blocked && account.validation_blocked_until.unwrap_or(i64::MAX) > now
+        {
+            return None;
+        }
+        let quota = account.quota.as_ref()?;
+        if quota.is_forbidden {
+            return None;
+        }
+        let mut quotas: BTreeMap<String, (f64, Option<i64>)> = BTreeMap::new();
+        for model in &quota.models {
+            let std_id =
+                normalize_to_standard_id(&model.name).unwrap_or_else(|| model.name.clone());
+            let reset = chrono::DateTime::parse_from_rfc3339(&model.reset_time)
+                .ok()
+                .map(|t| t.timestamp());
+            let pct = model.percentage.clamp(0, 100) as f64;
+            // 同组取最差状态，与配额保护的判定一致
+            let entry = quotas.entry(std_id).or_insert((pct, reset));
+            if pct < entry.0 {
+                *entry = (pct, reset);
+            }
+        }
+        Some(Self {
+            account_id: account.id.clone(),
+            email: account.email.clone(),
+            subscription_tier: quota.subscription_tier.clone(),
+            quotas,
+            protected_models: account.protected_models.clone(),
+        })
+    }
+}
+
+pub struct SimulationSettings {
+    pub mode: SchedulingMode,
+    pub quota_protection: QuotaProtectionConfig,
+    pub tier_routing: TierRoutingConfig,
+}
+
+struct ModelLoad {
+    std_id: String,
+    premium: bool,
+    per_step: f64,
+    cost: f64,
+    carry: f64,
+    outcome: ModelOutcome,
+}
+
+fn validate(workload: &SimulationWorkload) -> Result<(), String> {
+    if !(workload.hours > 0.0 && workload.hours <= MAX_HOURS) {
+        return Err(format!(
+            "invalid_simulation_hours: must be within (0, {}]",
+            MAX_HOURS
+        ));
+    }
+    if workload.models.is_empty() {
+        return Err("invalid_simulation_workload: no models".to_string());
+    }
+    for m in &workload.models {
+        if m.model.trim().is_empty()
+            || !m.requests_per_minute.is_finite()
+            || m.requests_per_minute < 0.0
+        {
+            return Err(format!("invalid_simulation_workload: {}", m.model));
+        }
+        if let Some(cost) = m.cost_pct {
+            if !(cost > 0.0 && cost <= 100.0) {
+                return Err(format!("invalid_simulation_cost: {}", m.model));
+            }
+        }
+    }
+    if let Some(window) = workload.reset_window_hours {
+        if !window.is_finite() || window <= 0.0 {
+            return Err("invalid_simulation_reset_window".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// 在给定账号池上推演负载 (不含限流与重试，仅模拟配额消耗)
+pub fn simulate(
+    pool: Vec<SimAccount>,
+    workload: &SimulationWorkload,
+    settings: &SimulationSettings,
+    now: i64,
+) -> Result<SimulationReport, String> {
+    validate(workload)?;
+    let mode = workload.mode.unwrap_or(settings.mode);
+    let window_secs = (workload
+        .reset_window_hours
+        .unwrap_or(DEFAULT_RESET_WINDOW_HOURS)
+        * 3600.0) as i64;
+    let ends_at = now + (workload.hours * 3600.0) as i64;
+    let protection = &settings.quota_protection;
+    let threshold = protection.threshold_percentage as f64;
+
+    let mut loads: Vec<ModelLoad> = workload
+        .models
+        .iter()
+        .map(|m| ModelLoad {
+            std_id: normalize_to_standard_id(&m.model).unwrap_or_else(|| m.model.clone()),
+            premium: is_premium_model(&m.model, &settings.tier_routing),
+            per_step: m.requests_per_minute * STEP_SECS as f64 / 60.0,
+            cost: m.cost_pct.unwrap_or(DEFAULT_COST_PCT),
+            carry: 0.0,
+            outcome: ModelOutcome {
+                model: m.model.clone(),
+                requested: 0,
+                served: 0,
+                failed: 0,
+                first_failure_at: None,
+                survives: true,
+            },
+        })
+        .collect();
+
+    let mut pool = pool;
+    pool.sort_by(|a, b| a.email.cmp(&b.email));
+    let mut projections: Vec<AccountProjection> = pool
+        .iter()
+        .map(|acc| AccountProjection {
+            account_id: acc.account_id.clone(),
+            email: acc.email.clone(),
+            subscription_tier: acc.subscription_tier.clone(),
+            models: Vec::new(),
+        })
+        .collect();
+    for (acc, proj) in pool.iter().zip(projections.iter_mut()) {
+        for load in &loads {
+            if proj.models.iter().any(|m| m.model == load.std_id) {
+                continue;
+            }
+            if let Some((pct, _)) = acc.quotas.get(&load.std_id) {
+                proj.models.push(ModelProjection {
+                    model: load.std_id.clone(),
+                    start_pct: *pct,
+                    end_pct: *pct,
+                    requests: 0,
+                    consumed_pct: 0.0,
+                    exhausted_at: None,
+                    protected_at: None,
+                    resets: 0,
+                });
+            }
+        }
+    }
+    let mut last_used: Option<(usize, i64)> = None;
+
+    let mut t = now;
+    while t < ends_at {
+        // 1. 到达刷新时间的模型恢复满额并解除保护
+        for (acc, proj) in pool.iter_mut().zip(projections.iter_mut()) {
+            for (std_id, (pct, reset)) in acc.quotas.iter_mut() {
+                if *pct >= 100.0 {
+                    continue;
+                }
+                let next_reset = *reset.get_or_insert(t + window_secs);
+                if next_reset <= t {
+                    *pct = 100.0;
+                    *reset = Some(next_reset + window_secs);
+                    acc.protected_models.remove(std_id);
+                    if let Some(m) = proj.models.iter_mut().find(|m| &m.model == std_id) {
+                        m.resets += 1;
+                    }
+                }
+            }
+        }
+
+        // 2. 按模型分配本分钟的请求
+        for load in loads.iter_mut() {
+            load.carry += load.per_step;
+            let requests = load.carry.floor() as u64;
+            load.carry -= requests as f64;
+
+            for _ in 0..requests {
+                load.outcome.requested += 1;
+                let mut candidates: Vec<usize> = (0..pool.len())
+                    .filter(|&i| {
+                        let acc = &pool[i];
+                        let available = acc
+                            .quotas
+                            .get(&load.std_id)
+                            .is_some_and(|(pct, _)| *pct > 0.0);
+                        let protected =
+                            protection.enabled && acc.protected_models.contains(&load.std_id);
+                        available && !protected
+                    })
+                    .collect();
+                if candidates.is_empty() {
+                    load.outcome.failed += 1;
+                    load.outcome.first_failure_at.get_or_insert(t);
+                    load.outcome.survives = false;
+                    continue;
+                }
+                // 与 TokenManager 的排序一致：(高端模型) 等级 > 配额 > 等级 > 刷新时间
+                candidates.sort_by(|&a, &b| {
+                    let (a, b) = (&pool[a], &pool[b]);
+                    let tier_cmp =
+                        tier_rank(&a.subscription_tier).cmp(&tier_rank(&b.subscription_tier));
+                    if load.premium && tier_cmp.is_ne() {
+                        return tier_cmp;
+                    }
+                    let (qa, ra) = a.quotas[&load.std_id];
+                    let (qb, rb) = b.quotas[&load.std_id];
+                    qb.floor()
+                        .partial_cmp(&qa.floor())
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                        .then(tier_cmp)
+                        .then(ra.unwrap_or(i64::MAX).cmp(&rb.unwrap_or(i64::MAX)))
+                        .then_with(|| a.email.cmp(&b.email))
+                });
+                // 非性能优先模式下 60s 内复用上一个账号；否则取排序首位 (P2C 的确定性近似)
+                let chosen = match last_used {
+                    Some((idx, at))
+                        if mode != SchedulingMode::PerformanceFirst
+                            && t - at < STEP_SECS
+                            && candidates.contains(&idx) =>
+                    {
+                        idx
+                    }
+                    _ => {
+                        last_used = Some((candidates[0], t));
+                        candidates[0]
+                    }
+                };
+                let acc = &mut pool[chosen];
+                let proj = &mut projections[chosen];
+                let Some((pct, _)) = acc.quotas.get_mut(&load.std_id) else {
+                    continue;
+                };
+                let before = *pct;
+                *pct = (*pct - load.cost).max(0.0);
+                load.outcome.served += 1;
+                if let Some(m) = proj.models.iter_mut().find(|m| m.model == load.std_id) {
+                    m.requests += 1;
+                    m.consumed_pct += before - *pct;
+                    if *pct <= 0.0 {
+                        m.exhausted_at.get_or_insert(t);
+                    }
+                    if protection.enabled
+                        && protection.monitored_models.contains(&load.std_id)
+                        && *pct <= threshold
+                        && acc.protected_models.insert(load.std_id.clone())
+                    {
+                        m.protected_at.get_or_insert(t);
+                    }
+                }
+            }
+        }
+        t += STEP_SECS;
+    }
+
+    for (acc, proj) in pool.iter().zip(projections.iter_mut()) {
+        for m in proj.models.iter_mut() {
+            if let Some((pct, _)) = acc.quotas.get(&m.model) {
+                m.end_pct = *pct;
+            }
+        }
+    }
+
+    let models: Vec<ModelOutcome> = loads.into_iter().map(|l| l.outcome).collect();
+    Ok(SimulationReport {
+        started_at: now,
+        ends_at,
+        mode,
+        eligible_accounts: pool.len(),
+        accounts: projections,
+        survives: models.iter().all(|m| m.survives),
+        models,
+    })
+}
+
+/// 基于当前账号池与配置推演
+pub fn simulate_current_pool(workload: &SimulationWorkload) -> Result<SimulationReport, String> {
+    let config = crate::modules::config::load_app_config()?;
+    let now = chrono::Utc::now().timestamp();
+    let pool: Vec<SimAccount> = crate::modules::account::list_accounts()?
+        .iter()
+        .filter_map(|acc| SimAccount::from_account(acc, now))
+        .collect();
+    let settings = SimulationSettings {
+        mode: config.proxy.scheduling.mode,
+        quota_protection: config.quota_protection.clone(),
+        tier_routing: crate::proxy::config::get_tier_routing(),
+    };
+    simulate(pool, workload, &settings, now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(email: &str, tier: &str, pct: f64, reset: Option<i64>) -> SimAccount {
+        SimAccount {
+            account_id: email.to_string(),
+            email: email.to_string(),
+            subscription_tier: Some(tier.to_string()),
+            quotas: [("gemini-3-flash".to_string(), (pct, reset))]
+                .into_iter()
+                .collect(),
+            protected_models: HashSet::new(),
+        }
+    }
+
+    fn settings(mode: SchedulingMode, protection: bool) -> SimulationSettings {
+        SimulationSettings {
+            mode,
+            quota_protection: QuotaProtectionConfig {
+                enabled: protection,
+                threshold_percentage: 10,
+                monitored_models: vec!["gemini-3-flash".to_string()],
+            },
+            tier_routing: TierRoutingConfig::default(),
+        }
+    }
+
+    fn workload(rpm: f64, hours: f64) -> SimulationWorkload {
+        SimulationWorkload {
+            hours,
+            models: vec![WorkloadModel {
+                model: "gemini-3-flash".to_string(),
+                requests_per_minute: rpm,
+                cost_pct: Some(1.0),
+            }],
+            reset_window_hours: None,
+            mode: None,
+        }
+    }
+
+    #[test]
+    fn test_pool_exhausts_and_reports_failure_time() {
+        let pool = vec![
+            account("a@x.com", "FREE", 50.0, Some(10 * 3600)),
+            account("b@x.com", "FREE", 30.0, Some(10 * 3600)),
+        ];
+        let report = simulate(
+            pool,
+            &workload(1.0, 2.0),
+            &settings(SchedulingMode::Balance, false),
+            0,
+        )
+        .unwrap();
+        let outcome = &report.models[0];
+        assert_eq!(outcome.requested, 120);
+        assert_eq!(outcome.served, 80);
+        assert_eq!(outcome.first_failure_at, Some(80 * 60));
+        assert!(!report.survives);
+        // 高配额账号先被消耗，两者交替下降直至耗尽
+        assert!(report
+            .accounts
+            .iter()
+            .all(|a| a.models[0].end_pct == 0.0 && a.models[0].exhausted_at.is_some()));
+    }
+
+    #[test]
+    fn test_protection_reserves_threshold_and_reset_restores() {
+        let pool = vec![account("a@x.com", "PRO", 20.0, Some(3600))];
+        let report = simulate(
+            pool,
+            &workload(1.0, 2.0),
+            &settings(SchedulingMode::Balance, true),
+            0,
+        )
+        .unwrap();
+        let m = &report.accounts[0].models[0];
+        assert_eq!(m.protected_at, Some(9 * 60));
+        assert_eq!(m.resets, 1);
+        assert_eq!(report.models[0].first_failure_at, Some(10 * 60));
+        // 刷新后 60 分钟消耗 60%
+        assert_eq!(m.end_pct, 40.0);
+        assert_eq!(m.exhausted_at, None);
+    }
+
+    #[test]
+    fn test_balance_reuses_account_within_window() {
+        let pool = vec![
+            account("a@x.com", "FREE", 100.0, None),
+            account("b@x.com", "FREE", 100.0, None),
+        ];
+        let report = simulate(
+            pool,
+            &workload(4.0, 1.0 / 60.0),
+            &settings(SchedulingMode::Balance, false),
+            0,
+        )
+        .unwrap();
+        assert_eq!(report.accounts[0].models[0].requests, 4);
+        assert_eq!(report.accounts[1].models[0].requests, 0);
+    }
+
+    #[test]
+    fn test_deterministic_and_spreads_by_quota() {
+        let pool = vec![
+            account("a@x.com", "FREE", 100.0, None),
+            account("b@x.com", "FREE", 100.0, None),
+        ];
+        let s = settings(SchedulingMode::PerformanceFirst, false);
+        let first = simulate(pool.clone(), &workload(0.5, 1.0), &s, 0).unwrap();
+        let second = simulate(pool, &workload(0.5, 1.0), &s, 0).unwrap();
+        assert_eq!(
+            serde_json::to_value(&first).unwrap(),
+            serde_json::to_value(&second).unwrap()
+        );
+        assert!(first.survives);
+        assert_eq!(first.accounts[0].models[0].requests, 15);
+        assert_eq!(first.accounts[1].models[0].requests, 15);
+    }
+
+    #[test]
+    fn test_rejects_invalid_workload() {
+        let s = settings(SchedulingMode::Balance, false);
+        assert!(simulate(vec![], &workload(1.0, 0.0), &s, 0).is_err());
+        assert!(simulate(vec![], &workload(-1.0, 1.0), &s, 0).is_err());
+    }
+}
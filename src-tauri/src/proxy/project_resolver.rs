@@ -4,7 +4,9 @@ use serde_json::Value;
 /// 这是获取 cloudaicompanionProject 的正确方式
 pub async fn fetch_project_id(access_token: &str) -> Result<String, String> {
     // 使用 Sandbox 环境，避免 Prod 环境的 429 错误
-    let url = "https://daily-cloudcode-pa.sandbox.googleapis.com/v1internal:loadCodeAssist";
+    let url = crate::modules::endpoints::resolve(
+        "https://daily-cloudcode-pa.sandbox.googleapis.com/v1internal:loadCodeAssist",
+    );
     
     let request_body = serde_json::json!({
         "metadata": {
@@ -14,11 +16,11 @@ pub async fn fetch_project_id(access_token: &str) -> Result<String, String> {
     
     let client = crate::utils::http::get_client();
     let response = client
-        .post(url)
+        .post(&url)
         .bearer_auth(access_token)
         // .header("Host", "cloudcode-pa.googleapis.com") // 移除 Host header，因为已切换域名
 
-        .header("User-Agent", crate::constants::USER_AGENT.as_str())
+        .header("User-Agent", crate::constants::user_agent())
         .header("Content-Type", "application/json")
         .json(&request_body)
         .send()
@@ -76,59 +76,24 @@ impl ProxyPoolManager {
     /// 1. 账号显式绑定代理优先 (Account-Proxy Binding)
     /// 2. 如果无绑定，且开启了“自动全局”，取池中第一个节点
     /// 3. 如果以上均无，则检查全局上游代理 (Upstream Proxy) [由调用方 fallback]
+    ///
+    /// Client 按出口缓存于 modules::http，同一出口复用连接池
     pub async fn get_effective_client(&self, account_id: Option<&str>, timeout_secs: u64) -> Client {
-        let mut builder = Client::builder()
-            .emulation(Emulation::Chrome123)
-            .timeout(Duration::from_secs(timeout_secs));
-        
-        // 尝试获取代理配置
-        let proxy_opt = if let Some(acc_id) = account_id {
-            self.get_proxy_for_account(acc_id).await.ok().flatten()
-        } else {
-            // 没有 account_id 的通用请求，如果代理池启用，则默认从中选择节点作为出口
-            let config = self.config.read().await;
-            if config.enabled {
-                let res = self.select_proxy_from_pool(&config).await.ok().flatten();
-                if let Some(ref p) = res {
-                    tracing::info!("[Proxy] Route: Generic Request -> Proxy {} (Pool)", p.entry_id);
-                } else {
-                    // [FIX #1583] 明确记录池中无可用代理的情况
-                    tracing::warn!("[Proxy] Route: Generic Request -> No available proxy in pool, falling back to upstream or direct");
-                }
-                res
-            } else {
-                tracing::debug!("[Proxy] Route: Generic Request -> Proxy pool disabled");
-                None
-            }
-        };
-
-        if let Some(proxy_cfg) = proxy_opt {
-            builder = builder.proxy(proxy_cfg.proxy);
-            // Already logged more detail in get_proxy_for_account or pool selection
-        } else {
-            // Fallback 到应用配置的单上游代理
-            if let Ok(app_cfg) = crate::modules::config::load_app_config() {
-                let up = app_cfg.proxy.upstream_proxy;
-                if up.enabled && !up.url.is_empty() {
-                    if let Ok(p) = rquest::Proxy::all(&up.url) {
-                        tracing::info!("[Proxy] Route: {:?} -> Upstream: {} (AppConfig)", account_id.unwrap_or("Generic"), up.url);
-                        builder = builder.proxy(p);
-                    }
-                } else {
-                    tracing::info!("[Proxy] Route: {:?} -> Direct", account_id.unwrap_or("Generic"));
-                }
-            }
-        }
-
-        builder.build().unwrap_or_else(|_| Client::new())
+        let route = self.resolve_route(account_id, "").await;
+        crate::modules::http::client_for(true, timeout_secs, route)
     }
 
     /// [NEW] 为指定账号获取“最终生效”的无特征 Standard HttpClient (专门用于纯净场景，如 OAuth 退还)
     pub async fn get_effective_standard_client(&self, account_id: Option<&str>, timeout_secs: u64) -> Client {
-        let mut builder = Client::builder()
-            // 无 Emulation 设置，走纯正的基础 TLS 指纹
-            .timeout(Duration::from_secs(timeout_secs));
-        
+        // 无 Emulation 设置，走纯正的基础 TLS 指纹
+        let route = self.resolve_route(account_id, " (Standard Client)").await;
+        crate::modules::http::client_for(false, timeout_secs, route)
+    }
+
+    /// 解析请求出口：代理池 > 应用上游代理 > 直连
+    async fn resolve_route(&self, account_id: Option<&str>, label: &str) -> crate::modules::http::Route {
+        use crate::modules::http::Route;
+
         // 尝试获取代理配置
         let proxy_opt = if let Some(acc_id) = account_id {
             self.get_proxy_for_account(acc_id).await.ok().flatten()
@@ -138,35 +103,29 @@ impl ProxyPoolManager {
             if config.enabled {
                 let res = self.select_proxy_from_pool(&config).await.ok().flatten();
                 if let Some(ref p) = res {
-                    tracing::info!("[Proxy] Route: Generic Request (Standard Client) -> Proxy {} (Pool)", p.entry_id);
+                    tracing::info!("[Proxy] Route: Generic Request{} -> Proxy {} (Pool)", label, p.entry_id);
                 } else {
-                    tracing::warn!("[Proxy] Route: Generic Request (Standard Client) -> No available proxy in pool, falling back to upstream or direct");
+                    // [FIX #1583] 明确记录池中无可用代理的情况
+                    tracing::warn!("[Proxy] Route: Generic Request{} -> No available proxy in pool, falling back to upstream or direct", label);
                 }
                 res
             } else {
-                tracing::debug!("[Proxy] Route: Generic Request (Standard Client) -> Proxy pool disabled");
+                tracing::debug!("[Proxy] Route: Generic Request{} -> Proxy pool disabled", label);
                 None
             }
         };
 
         if let Some(proxy_cfg) = proxy_opt {
-            builder = builder.proxy(proxy_cfg.proxy);
-        } else {
-            // Fallback 到应用配置的单上游代理
-            if let Ok(app_cfg) = crate::modules::config::load_app_config() {
-                let up = app_cfg.proxy.upstream_proxy;
-                if up.enabled && !up.url.is_empty() {
-                    if let Ok(p) = rquest::Proxy::all(&up.url) {
-                        tracing::info!("[Proxy] Route: {:?} (Standard Client) -> Upstream: {} (AppConfig)", account_id.unwrap_or("Generic"), up.url);
-                        builder = builder.proxy(p);
-                    }
-                } else {
-                    tracing::info!("[Proxy] Route: {:?} (Standard Client) -> Direct", account_id.unwrap_or("Generic"));
-                }
-            }
+            // Already logged more detail in get_proxy_for_account or pool selection
+            return Route::pool(&proxy_cfg.entry_id, proxy_cfg.proxy).for_account(account_id);
         }
 
-        builder.build().unwrap_or_else(|_| Client::new())
+        // Fallback 到应用配置的单上游代理
+        match crate::modules::http::upstream_proxy_url() {
+            Some(url) => tracing::info!("[Proxy] Route: {:?}{} -> Upstream: {} (AppConfig)", account_id.unwrap_or("Generic"), label, url),
+            None => tracing::info!("[Proxy] Route: {:?}{} -> Direct", account_id.unwrap_or("Generic"), label),
+        }
+        Route::upstream().for_account(account_id)
     }
 
     /// 为账号获取代理
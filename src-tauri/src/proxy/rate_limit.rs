@@ -17,6 +17,13 @@ pub enum RateLimitReason {
     Unknown,
 }
 
+impl RateLimitReason {
+    /// 上游报告的是模型配额 / 容量耗尽 (而非分钟级限流或服务故障)
+    pub fn is_model_exhaustion(self) -> bool {
+        matches!(self, RateLimitReason::QuotaExhausted | RateLimitReason::ModelCapacityExhausted)
+    }
+}
+
 /// 限流信息
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
@@ -333,14 +340,8 @@ impl RateLimitTracker {
         let trimmed = body.trim();
         if trimmed.starts_with('{') || trimmed.starts_with('[') {
             if let Ok(json) = serde_json::from_str::<serde_json::Value>(trimmed) {
-                if let Some(reason_str) = json.get("error")
-                    .and_then(|e| e.get("details"))
-                    .and_then(|d| d.as_array())
-                    .and_then(|a| a.get(0))
-                    .and_then(|o| o.get("reason"))
-                    .and_then(|v| v.as_str()) {
-                    
-                    return match reason_str {
+                if let Some(reason_str) = crate::error::upstream_error_reason(trimmed) {
+                    return match reason_str.as_str() {
                         "QUOTA_EXHAUSTED" => RateLimitReason::QuotaExhausted,
                         "RATE_LIMIT_EXCEEDED" => RateLimitReason::RateLimitExceeded,
                         "MODEL_CAPACITY_EXHAUSTED" => RateLimitReason::ModelCapacityExhausted,
@@ -504,6 +505,31 @@ impl RateLimitTracker {
         self.limits.get(account_id).map(|r| r.clone())
     }
     
+    /// 账号当前生效的限流信息 (账号级优先，其次模型级)
+    pub fn active_limit(&self, account_id: &str, model: Option<&str>) -> Option<RateLimitInfo> {
+        let now = SystemTime::now();
+        let mut keys = vec![account_id.to_string()];
+        if let Some(m) = model.filter(|m| !m.is_empty()) {
+            keys.push(self.get_limit_key(account_id, Some(m)));
+        }
+        keys.iter().find_map(|key| {
+            self.limits
+                .get(key)
+                .filter(|info| info.reset_time > now)
+                .map(|info| info.clone())
+        })
+    }
+
+    /// 当前仍生效的限流记录 (限流 Key, 信息)
+    pub fn active(&self) -> Vec<(String, RateLimitInfo)> {
+        let now = SystemTime::now();
+        self.limits
+            .iter()
+            .filter(|e| e.value().reset_time > now)
+            .map(|e| (e.key().clone(), e.value().clone()))
+            .collect()
+    }
+
     /// 检查账号是否仍在限流中
     /// 检查账号是否仍在限流中 (支持模型级)
     pub fn is_rate_limited(&self, account_id: &str, model: Option<&str>) -> bool {
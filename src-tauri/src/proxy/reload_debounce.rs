@@ -0,0 +1,135 @@
+// 账号重新加载信号合并
+// 账号重载事件 (events::account_reload) 会在配额更新 / 禁用 / 删除等多处触发，批量刷新时同一账号短时间内会被反复标记，
+// TokenManager 每次 get_token 都会重新加载，形成重载风暴。
+// 这里按账号合并信号: 距上次重载不足窗口期的信号会被保留到窗口结束后统一处理一次；
+// 连续的突发会使窗口指数增长 (1s → 30s)，安静一段时间后恢复到初始窗口。
+
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+const BASE_WINDOW: Duration = Duration::from_secs(1);
+const MAX_WINDOW: Duration = Duration::from_secs(30);
+
+struct ReloadHistory {
+    last_reload: Instant,
+    window: Duration,
+}
+
+#[derive(Default)]
+pub struct ReloadDebouncer {
+    pending: HashSet<String>,
+    history: HashMap<String, ReloadHistory>,
+}
+
+impl ReloadDebouncer {
+    pub fn mark(&mut self, account_id: &str) {
+        self.pending.insert(account_id.to_string());
+    }
+
+    /// 账号已删除: 丢弃待重载信号与历史
+    pub fn forget(&mut self, account_id: &str) {
+        self.pending.remove(account_id);
+        self.history.remove(account_id);
+    }
+
+    /// 取出窗口期已过的账号；其余账号继续等待
+    pub fn take_ready(&mut self, now: Instant) -> Vec<String> {
+        let mut ready = Vec::new();
+        let history = &mut self.history;
+        self.pending.retain(|account_id| {
+            let due = history
+                .get(account_id)
+                .map(|h| now.duration_since(h.last_reload) >= h.window)
+                .unwrap_or(true);
+            if !due {
+                return true;
+            }
+            let window = match history.get(account_id) {
+                // 上一个窗口结束后很快又被标记 → 仍处于突发中，窗口翻倍
+                Some(h) if now.duration_since(h.last_reload) < h.window * 2 => {
+                    (h.window * 2).min(MAX_WINDOW)
+                }
+                _ => BASE_WINDOW,
+            };
+            history.insert(
+                account_id.clone(),
+                ReloadHistory {
+                    last_reload: now,
+                    window,
+                },
+            );
+            ready.push(account_id.clone());
+            false
+        });
+        // 长时间未再触发的账号不再保留历史
+        self.history
+            .retain(|_, h| now.duration_since(h.last_reload) < MAX_WINDOW * 2);
+        ready
+    }
+
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_burst_is_coalesced_into_one_reload_per_window() {
+        let mut d = ReloadDebouncer::default();
+        let t0 = Instant::now();
+
+        d.mark("a");
+        assert_eq!(d.take_ready(t0), vec!["a"]);
+
+        // 窗口内的重复信号被合并
+        for ms in [100, 200, 300] {
+            d.mark("a");
+            assert!(d.take_ready(t0 + Duration::from_millis(ms)).is_empty());
+        }
+        assert_eq!(d.pending_len(), 1);
+        assert_eq!(d.take_ready(t0 + BASE_WINDOW), vec!["a"]);
+        assert_eq!(d.pending_len(), 0);
+    }
+
+    #[test]
+    fn test_window_grows_during_sustained_bursts_and_resets_when_quiet() {
+        let mut d = ReloadDebouncer::default();
+        let mut now = Instant::now();
+        d.mark("a");
+        d.take_ready(now);
+
+        // 持续触发: 每次都在窗口结束时立即重载，窗口 1s → 2s → 4s
+        let mut windows = Vec::new();
+        for _ in 0..3 {
+            let window = d.history["a"].window;
+            windows.push(window);
+            now += window;
+            d.mark("a");
+            assert_eq!(d.take_ready(now), vec!["a"]);
+        }
+        assert_eq!(windows, vec![BASE_WINDOW, BASE_WINDOW * 2, BASE_WINDOW * 4]);
+
+        // 安静足够久后恢复初始窗口
+        now += MAX_WINDOW * 2;
+        d.mark("a");
+        assert_eq!(d.take_ready(now), vec!["a"]);
+        assert_eq!(d.history["a"].window, BASE_WINDOW);
+    }
+
+    #[test]
+    fn test_accounts_are_independent_and_forget_drops_pending() {
+        let mut d = ReloadDebouncer::default();
+        let t0 = Instant::now();
+        d.mark("a");
+        d.take_ready(t0);
+        d.mark("a");
+        d.mark("b");
+        assert_eq!(d.take_ready(t0), vec!["b"]);
+
+        d.forget("a");
+        assert_eq!(d.pending_len(), 0);
+    }
+}
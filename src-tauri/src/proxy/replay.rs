@@ -0,0 +1,367 @@
+// 请求重放 (request replay)
+// 从请求日志中取出已保存的请求体，经本地代理的完整管线重新发送 (可选锁定到另一个账号，复用 dry_run 的锁定请求头)，
+// 返回原响应与重放响应的文本以及逐行差异，用于复现 "这个提示词昨天在账号 A 上还能用" 一类的问题。
+// 文本从 Claude / OpenAI / Gemini 的 JSON 或 SSE 响应中提取，提取不到时按原始响应体比较。
+
+use serde::Serialize;
+use serde_json::Value;
+use std::time::{Duration, Instant};
+
+use crate::proxy::dry_run::{self, DRY_RUN_HEADER, UPSTREAM_STATUS_HEADER};
+
+const TIMEOUT_SECS: u64 = 300;
+/// 超过此规模 (行数乘积) 时不做逐行 LCS，整体标记为替换
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayResponse {
+    pub status: u16,
+    pub account_email: Option<String>,
+    pub duration_ms: u64,
+    /// 从响应中提取的文本 (SSE 已合并)
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffOp {
+    Equal,
+    Removed,
+    Added,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct DiffLine {
+    pub op: DiffOp,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayResult {
+    pub log_id: String,
+    pub url: String,
+    pub model: Option<String>,
+    /// 锁定的账号 (未指定时按正常轮换选择)
+    pub target_account: Option<String>,
+    pub original: ReplayResponse,
+    pub replay: ReplayResponse,
+    /// 重放过程中上游每次调用的状态码 (仅锁定账号时可用)
+    pub upstream_statuses: Vec<u16>,
+    pub identical: bool,
+    pub diff: Vec<DiffLine>,
+}
+
+/// 去掉查询参数中的 key= (重放统一使用 Bearer 鉴权)
+fn replay_path(url: &str) -> String {
+    let Some((path, query)) = url.split_once('?') else {
+        return url.to_string();
+    };
+    let query: Vec<&str> = query
+        .split('&')
+        .filter(|p| !p.is_empty() && !p.starts_with("key="))
+        .collect();
+    if query.is_empty() {
+        path.to_string()
+    } else {
+        format!("{}?{}", path, query.join("&"))
+    }
+}
+
+/// 收集单个响应对象 (或 SSE 事件) 中的文本
+fn collect_text(value: &Value, out: &mut String) {
+    match value {
+        Value::Array(items) => items.iter().for_each(|v| collect_text(v, out)),
+        Value::Object(obj) => {
+            if let Some(error) = obj.get("error") {
+                let message = error
+                    .get("message")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| error.to_string());
+                out.push_str(&format!("[error] {}", message));
+                return;
+            }
+            // Claude: content[].text / content_block_delta.delta.text
+            for block in obj
+                .get("content")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                if let Some(text) = block.get("text").and_then(Value::as_str) {
+                    out.push_str(text);
+                }
+            }
+            if let Some(text) = obj
+                .get("delta")
+                .and_then(|d| d.get("text"))
+                .and_then(Value::as_str)
+            {
+                out.push_str(text);
+            }
+            // OpenAI: choices[].message.content / choices[].delta.content / choices[].text
+            for choice in obj
+                .get("choices")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                let text = choice
+                    .get("message")
+                    .or_else(|| choice.get("delta"))
+                    .and_then(|m| m.get("content"))
+                    .and_then(Value::as_str)
+                    .or_else(|| choice.get("text").and_then(Value::as_str));
+                if let Some(text) = text {
+                    out.push_str(text);
+                }
+            }
+            // Gemini: candidates[].content.parts[].text (可能包在 response 中)
+            let gemini = obj.get("response").unwrap_or(value);
+            for candidate in gemini
+                .get("candidates")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                let parts = candidate
+                    .get("content")
+                    .and_then(|c| c.get("parts"))
+                    .and_then(Value::as_array);
+                for part in parts.into_iter().flatten() {
+                    if let Some(text) = part.get("text").and_then(Value::as_str) {
+                        out.push_str(text);
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 从响应体中提取可比较的文本
+pub fn extract_text(body: &str) -> String {
+    let mut out = String::new();
+    let sse_events: Vec<&str> = body
+        .lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .map(str::trim)
+        .filter(|data| !data.is_empty() && *data != "[DONE]")
+        .collect();
+    if !sse_events.is_empty() {
+        for data in sse_events {
+            if let Ok(value) = serde_json::from_str::<Value>(data) {
+                collect_text(&value, &mut out);
+            }
+        }
+    } else if let Ok(value) = serde_json::from_str::<Value>(body) {
+        collect_text(&value, &mut out);
+    }
+    if out.is_empty() {
+        body.trim().to_string()
+    } else {
+        out
+    }
+}
+
+/// 逐行差异 (LCS)
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+    let line = |op, text: &str| DiffLine {
+        op,
+        text: text.to_string(),
+    };
+
+    if a.len().saturating_mul(b.len()) > MAX_DIFF_CELLS {
+        return a
+            .iter()
+            .map(|t| line(DiffOp::Removed, t))
+            .chain(b.iter().map(|t| line(DiffOp::Added, t)))
+            .collect();
+    }
+
+    // lcs[i][j] = a[i..] 与 b[j..] 的最长公共子序列长度
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut diff = Vec::new();
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            diff.push(line(DiffOp::Equal, a[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            diff.push(line(DiffOp::Removed, a[i]));
+            i += 1;
+        } else {
+            diff.push(line(DiffOp::Added, b[j]));
+            j += 1;
+        }
+    }
+    diff.extend(a[i..].iter().map(|t| line(DiffOp::Removed, t)));
+    diff.extend(b[j..].iter().map(|t| line(DiffOp::Added, t)));
+    diff
+}
+
+/// 将账号 ID 或邮箱解析为账号 ID
+fn resolve_account(target: &str) -> Result<String, String> {
+    let target = target.trim();
+    if !target.contains('@') {
+        return crate::modules::account::load_account(target).map(|a| a.id);
+    }
+    crate::modules::account::list_accounts()?
+        .into_iter()
+        .find(|a| a.email.eq_ignore_ascii_case(target))
+        .map(|a| a.id)
+        .ok_or_else(|| format!("account_not_found: {}", target))
+}
+
+/// 重放一条请求日志
+pub async fn replay(
+    port: u16,
+    api_key: &str,
+    log_id: &str,
+    target_account: Option<&str>,
+) -> Result<ReplayResult, String> {
+    let log = tokio::task::spawn_blocking({
+        let log_id = log_id.to_string();
+        move || crate::modules::proxy_db::get_log_detail(&log_id)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| format!("log_not_found: {}", e))?;
+
+    let body = match log.request_body.as_deref() {
+        Some(body) if log.method == "POST" && body != "[Binary Request Data]" => body.to_string(),
+        _ => return Err("replay_unavailable: request body was not stored".to_string()),
+    };
+    let account_id = target_account
+        .filter(|t| !t.trim().is_empty())
+        .map(resolve_account)
+        .transpose()?;
+    let path = replay_path(&log.url);
+
+    // 本地回环请求不走系统代理
+    let client = rquest::Client::builder()
+        .timeout(Duration::from_secs(TIMEOUT_SECS))
+        .no_proxy()
+        .build()
+        .unwrap_or_else(|_| rquest::Client::new());
+
+    tracing::info!(
+        "[Replay] Replaying log {} ({}) {}",
+        log.id,
+        path,
+        account_id
+            .as_deref()
+            .map(|id| format!("pinned to {}", id))
+            .unwrap_or_else(|| "with normal rotation".to_string())
+    );
+    let mut request = client
+        .post(format!("http://127.0.0.1:{}{}", port, path))
+        .header("Content-Type", "application/json")
+        .header("anthropic-version", "2023-06-01")
+        .header("Authorization", format!("Bearer {}", api_key))
+        .body(body);
+    if let Some(id) = account_id.as_deref() {
+        request = request.header(DRY_RUN_HEADER, dry_run::header_value(id));
+    }
+
+    let start = Instant::now();
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("proxy_request_failed: {}", e))?;
+    let status = response.status().as_u16();
+    let header = |name: &str| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    let upstream_statuses = dry_run::parse_statuses(header(UPSTREAM_STATUS_HEADER).as_deref());
+    let served_by = header("x-account-email");
+    let text = response.text().await.unwrap_or_default();
+    let duration_ms = start.elapsed().as_millis() as u64;
+
+    let original = ReplayResponse {
+        status: log.status,
+        account_email: log.account_email.clone(),
+        duration_ms: log.duration,
+        text: extract_text(log.response_body.as_deref().unwrap_or_default()),
+    };
+    let replay = ReplayResponse {
+        status,
+        account_email: served_by,
+        duration_ms,
+        text: extract_text(&text),
+    };
+    let diff = diff_lines(&original.text, &replay.text);
+    let identical = original.status == replay.status && original.text == replay.text;
+    tracing::info!(
+        "[Replay] Log {}: HTTP {} -> {} ({})",
+        log.id,
+        original.status,
+        replay.status,
+        if identical { "identical" } else { "differs" }
+    );
+
+    Ok(ReplayResult {
+        log_id: log.id,
+        url: path,
+        model: log.model,
+        target_account: account_id,
+        original,
+        replay,
+        upstream_statuses,
+        identical,
+        diff,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_and_diff() {
+        assert_eq!(
+            replay_path("/v1beta/models/x:streamGenerateContent?alt=sse&key=sk-1"),
+            "/v1beta/models/x:streamGenerateContent?alt=sse"
+        );
+        assert_eq!(replay_path("/v1/messages?key=sk-1"), "/v1/messages");
+
+        let claude_sse = "event: content_block_delta\ndata: {\"type\":\"content_block_delta\",\"delta\":{\"type\":\"text_delta\",\"text\":\"Hel\"}}\n\n\
+                          data: {\"type\":\"content_block_delta\",\"delta\":{\"type\":\"text_delta\",\"text\":\"lo\"}}\n\n";
+        assert_eq!(extract_text(claude_sse), "Hello");
+        let openai = r#"{"choices":[{"message":{"role":"assistant","content":"a\nb"}}]}"#;
+        assert_eq!(extract_text(openai), "a\nb");
+        let gemini = r#"[{"candidates":[{"content":{"parts":[{"text":"x"},{"text":"y"}]}}]}]"#;
+        assert_eq!(extract_text(gemini), "xy");
+        assert_eq!(
+            extract_text(r#"{"error":{"message":"quota"}}"#),
+            "[error] quota"
+        );
+        assert_eq!(extract_text("plain"), "plain");
+
+        let diff = diff_lines("a\nb\nc", "a\nc\nd");
+        let ops: Vec<DiffOp> = diff.iter().map(|l| l.op).collect();
+        assert_eq!(
+            ops,
+            vec![DiffOp::Equal, DiffOp::Removed, DiffOp::Equal, DiffOp::Added]
+        );
+        assert_eq!(diff[1].text, "b");
+        assert_eq!(diff[3].text, "d");
+    }
+}
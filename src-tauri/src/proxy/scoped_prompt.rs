@@ -0,0 +1,234 @@
+// 网关层按 API Key / 账号标签注入系统提示词 (前缀 / 后缀)
+// - 调用方 (用户令牌) 与跳过请求头由 scoped_prompt_middleware 写入请求作用域
+// - 账号标签在选定账号后由协议转换器传入 (见 apply)
+// 仅作用于客户端请求；预热等内部请求不在作用域内，不做注入
+
+use crate::proxy::config::{ScopedPromptTarget, ScopedSystemPromptConfig, ScopedSystemPromptRule};
+use crate::proxy::token_manager::ProxyToken;
+use serde_json::{json, Value};
+
+/// 客户端携带该请求头 (值不为 false / 0) 时跳过本次注入
+pub const OPT_OUT_HEADER: &str = "x-skip-prompt-injection";
+
+/// 当前请求的调用方
+#[derive(Debug, Clone, Default)]
+pub struct PromptCaller {
+    pub token_id: Option<String>,
+    pub username: Option<String>,
+    pub opt_out: bool,
+}
+
+tokio::task_local! {
+    static CALLER: PromptCaller;
+}
+
+/// 在指定调用方的作用域内执行 future
+pub async fn scope_caller<F: std::future::Future>(caller: PromptCaller, f: F) -> F::Output {
+    CALLER.scope(caller, f).await
+}
+
+/// 解析跳过请求头的值
+pub fn is_opt_out(value: &str) -> bool {
+    !matches!(
+        value.trim().to_ascii_lowercase().as_str(),
+        "" | "0" | "false" | "no"
+    )
+}
+
+fn rule_matches(
+    rule: &ScopedSystemPromptRule,
+    caller: &PromptCaller,
+    account_tags: &[String],
+) -> bool {
+    let value = rule.value.trim();
+    if !rule.enabled || value.is_empty() {
+        return false;
+    }
+    match rule.target {
+        ScopedPromptTarget::ApiKey => {
+            caller.token_id.as_deref() == Some(value) || caller.username.as_deref() == Some(value)
+        }
+        ScopedPromptTarget::AccountTag => account_tags.iter().any(|tag| tag == value),
+    }
+}
+
+/// 命中规则的前缀与后缀 (按规则顺序)
+fn collect(
+    config: &ScopedSystemPromptConfig,
+    caller: &PromptCaller,
+    account_tags: &[String],
+) -> (Vec<String>, Vec<String>) {
+    let mut prefixes = Vec::new();
+    let mut suffixes = Vec::new();
+    if !config.enabled || caller.opt_out {
+        return (prefixes, suffixes);
+    }
+    for rule in config
+        .rules
+        .iter()
+        .filter(|rule| rule_matches(rule, caller, account_tags))
+    {
+        if !rule.prefix.trim().is_empty() {
+            prefixes.push(rule.prefix.clone());
+        }
+        if !rule.suffix.trim().is_empty() {
+            suffixes.push(rule.suffix.clone());
+        }
+    }
+    (prefixes, suffixes)
+}
+
+/// 写入 systemInstruction.parts：前缀紧跟 Antigravity 身份，后缀追加到末尾
+fn inject(inner_request: &mut Value, prefixes: Vec<String>, suffixes: Vec<String>) {
+    if prefixes.is_empty() && suffixes.is_empty() {
+        return;
+    }
+    let Some(obj) = inner_request.as_object_mut() else {
+        return;
+    };
+    let sys = obj
+        .entry("systemInstruction")
+        .or_insert_with(|| json!({ "role": "user", "parts": [] }));
+    if !sys.is_object() {
+        *sys = json!({ "role": "user", "parts": [] });
+    }
+    if !sys["parts"].is_array() {
+        sys["parts"] = json!([]);
+    }
+    let Some(parts) = sys["parts"].as_array_mut() else {
+        return;
+    };
+
+    let pos = parts
+        .first()
+        .and_then(|p| p.get("text"))
+        .and_then(|t| t.as_str())
+        .is_some_and(|t| t.contains("You are Antigravity")) as usize;
+    parts.splice(
+        pos..pos,
+        prefixes.into_iter().map(|text| json!({ "text": text })),
+    );
+    for text in suffixes {
+        parts.push(json!({ "text": text }));
+    }
+}
+
+/// 协议转换完成后调用：按当前调用方与选中账号的标签注入提示词
+pub fn apply(inner_request: &mut Value, token: Option<&ProxyToken>) {
+    let Ok(caller) = CALLER.try_with(|c| c.clone()) else {
+        return;
+    };
+    let config = crate::proxy::config::get_scoped_system_prompts();
+    let tags = token.map(|t| t.tags.as_slice()).unwrap_or(&[]);
+    let (prefixes, suffixes) = collect(&config, &caller, tags);
+    if !prefixes.is_empty() || !suffixes.is_empty() {
+        tracing::debug!(
+            "[Scoped-System-Prompt] Injecting {} prefix(es), {} suffix(es)",
+            prefixes.len(),
+            suffixes.len()
+        );
+    }
+    inject(inner_request, prefixes, suffixes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(
+        name: &str,
+        target: ScopedPromptTarget,
+        value: &str,
+        prefix: &str,
+        suffix: &str,
+    ) -> ScopedSystemPromptRule {
+        ScopedSystemPromptRule {
+            name: name.to_string(),
+            target,
+            value: value.to_string(),
+            prefix: prefix.to_string(),
+            suffix: suffix.to_string(),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_collect_matches_key_and_account_tag() {
+        let config = ScopedSystemPromptConfig {
+            enabled: true,
+            rules: vec![
+                rule("org", ScopedPromptTarget::ApiKey, "alice", "Be safe.", ""),
+                rule(
+                    "fmt",
+                    ScopedPromptTarget::AccountTag,
+                    "teamA",
+                    "",
+                    "Answer in Markdown.",
+                ),
+                rule(
+                    "other",
+                    ScopedPromptTarget::ApiKey,
+                    "bob",
+                    "Never.",
+                    "Never.",
+                ),
+            ],
+        };
+        let caller = PromptCaller {
+            token_id: Some("tok_1".to_string()),
+            username: Some("alice".to_string()),
+            opt_out: false,
+        };
+        let tags = vec!["teamA".to_string()];
+
+        let (prefixes, suffixes) = collect(&config, &caller, &tags);
+        assert_eq!(prefixes, vec!["Be safe."]);
+        assert_eq!(suffixes, vec!["Answer in Markdown."]);
+
+        let opted_out = PromptCaller {
+            opt_out: true,
+            ..caller
+        };
+        let (prefixes, suffixes) = collect(&config, &opted_out, &tags);
+        assert!(prefixes.is_empty() && suffixes.is_empty());
+    }
+
+    #[test]
+    fn test_inject_keeps_identity_first() {
+        let mut body = json!({
+            "systemInstruction": {
+                "role": "user",
+                "parts": [{"text": "You are Antigravity, ..."}, {"text": "user system"}]
+            }
+        });
+        inject(
+            &mut body,
+            vec!["P1".to_string(), "P2".to_string()],
+            vec!["S1".to_string()],
+        );
+        let texts: Vec<&str> = body["systemInstruction"]["parts"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| p["text"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            texts,
+            vec!["You are Antigravity, ...", "P1", "P2", "user system", "S1"]
+        );
+
+        // 没有 systemInstruction 时创建
+        let mut body = json!({ "contents": [] });
+        inject(&mut body, vec!["P".to_string()], Vec::new());
+        assert_eq!(body["systemInstruction"]["parts"][0]["text"], "P");
+        assert_eq!(body["systemInstruction"]["role"], "user");
+    }
+
+    #[test]
+    fn test_opt_out_header_values() {
+        assert!(is_opt_out("1"));
+        assert!(is_opt_out("true"));
+        assert!(!is_opt_out("false"));
+        assert!(!is_opt_out("0"));
+    }
+}
@@ -1,10 +1,15 @@
-use crate::proxy::config::{ProxyAuthMode, ProxyConfig, SecurityMonitorConfig};
+use crate::proxy::config::{AdminRole, AdminTokenConfig, ProxyAuthMode, ProxyConfig, SecurityMonitorConfig};
+
+/// 附加监听地址的鉴权策略 (作为请求扩展注入，优先于全局策略)
+#[derive(Debug, Clone)]
+pub struct ListenerAuthMode(pub ProxyAuthMode);
 
 #[derive(Debug, Clone)]
 pub struct ProxySecurityConfig {
     pub auth_mode: ProxyAuthMode,
     pub api_key: String,
     pub admin_password: Option<String>,
+    pub admin_tokens: Vec<AdminTokenConfig>,
     pub allow_lan_access: bool,
     pub port: u16,
     pub security_monitor: SecurityMonitorConfig,
@@ -16,6 +21,7 @@ impl ProxySecurityConfig {
             auth_mode: config.auth_mode.clone(),
             api_key: config.api_key.clone(),
             admin_password: config.admin_password.clone(),
+            admin_tokens: config.admin_tokens.clone(),
             allow_lan_access: config.allow_lan_access,
             port: config.port,
             security_monitor: config.security_monitor.clone(),
@@ -34,6 +40,24 @@ impl ProxySecurityConfig {
             ref other => other.clone(),
         }
     }
+
+    /// 管理接口凭据对应的角色；admin_password (未设置时为 api_key) 为 admin，其余查 admin_tokens
+    pub fn admin_role_for(&self, key: &str) -> Option<AdminRole> {
+        if key.is_empty() {
+            return None;
+        }
+        let master = match &self.admin_password {
+            Some(pwd) if !pwd.is_empty() => pwd,
+            _ => &self.api_key,
+        };
+        if key == master {
+            return Some(AdminRole::Admin);
+        }
+        self.admin_tokens
+            .iter()
+            .find(|t| !t.token.is_empty() && t.token == key)
+            .map(|t| t.role)
+    }
 }
 
 #[cfg(test)]
@@ -46,6 +70,7 @@ mod tests {
             auth_mode: ProxyAuthMode::Auto,
             api_key: "sk-test".to_string(),
             admin_password: None,
+            admin_tokens: Vec::new(),
             allow_lan_access: false,
             port: 8080,
             security_monitor: crate::proxy::config::SecurityMonitorConfig::default(),
@@ -53,12 +78,35 @@ mod tests {
         assert!(matches!(s.effective_auth_mode(), ProxyAuthMode::Off));
     }
 
+    #[test]
+    fn admin_role_resolution() {
+        let s = ProxySecurityConfig {
+            auth_mode: ProxyAuthMode::Strict,
+            api_key: "sk-api".to_string(),
+            admin_password: Some("admin123".to_string()),
+            admin_tokens: vec![AdminTokenConfig {
+                name: "teammate".to_string(),
+                token: "view-only".to_string(),
+                role: AdminRole::Viewer,
+            }],
+            allow_lan_access: true,
+            port: 8080,
+            security_monitor: crate::proxy::config::SecurityMonitorConfig::default(),
+        };
+        assert_eq!(s.admin_role_for("admin123"), Some(AdminRole::Admin));
+        assert_eq!(s.admin_role_for("view-only"), Some(AdminRole::Viewer));
+        // 设置了 admin_password 时 api_key 不再用于管理接口
+        assert_eq!(s.admin_role_for("sk-api"), None);
+        assert_eq!(s.admin_role_for(""), None);
+    }
+
     #[test]
     fn auto_mode_resolves_all_except_health_for_lan() {
         let s = ProxySecurityConfig {
             auth_mode: ProxyAuthMode::Auto,
             api_key: "sk-test".to_string(),
             admin_password: None,
+            admin_tokens: Vec::new(),
             allow_lan_access: true,
             port: 8080,
             security_monitor: crate::proxy::config::SecurityMonitorConfig::default(),
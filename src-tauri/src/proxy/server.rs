@@ -1,5 +1,6 @@
 use crate::models::AppConfig;
 use crate::modules::{account, config, logger, migration, proxy_db, security_db, token_stats};
+use crate::proxy::openapi::ApiDoc;
 use crate::proxy::TokenManager;
 use axum::{
     extract::{DefaultBodyLimit, Path, Query, State},
@@ -16,28 +17,46 @@ use std::sync::OnceLock;
 use tokio::sync::oneshot;
 use tokio::sync::RwLock;
 use tracing::{debug, error};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use utoipa_swagger_ui::SwaggerUi;
 
 // [FIX] 全局待重新加载账号队列
 // 当 update_account_quota 更新 protected_models 后，将账号 ID 加入此队列
-// TokenManager 在 get_token 时会检查并处理这些账号
-static PENDING_RELOAD_ACCOUNTS: OnceLock<std::sync::RwLock<HashSet<String>>> = OnceLock::new();
+// TokenManager 在 get_token 时会检查并处理这些账号 (按账号合并突发信号，见 reload_debounce)
+static PENDING_RELOAD_ACCOUNTS: OnceLock<
+    std::sync::Mutex<crate::proxy::reload_debounce::ReloadDebouncer>,
+> = OnceLock::new();
 
 // [NEW] 全局待删除账号队列 (Issue #1477)
 // 当账号被删除后，将账号 ID 加入此队列，TokenManager 在 get_token 时会检查并清理内存缓存
 static PENDING_DELETE_ACCOUNTS: OnceLock<std::sync::RwLock<HashSet<String>>> = OnceLock::new();
 
-fn get_pending_reload_accounts() -> &'static std::sync::RwLock<HashSet<String>> {
-    PENDING_RELOAD_ACCOUNTS.get_or_init(|| std::sync::RwLock::new(HashSet::new()))
+// 全局待重新绑定项目的账号队列
+// project_id 发生切换后加入此队列，TokenManager 在 get_token 时重新加载账号并解除旧的粘性会话
+static PENDING_PROJECT_REBINDS: OnceLock<std::sync::RwLock<HashSet<String>>> = OnceLock::new();
+
+fn get_pending_reload_accounts(
+) -> &'static std::sync::Mutex<crate::proxy::reload_debounce::ReloadDebouncer> {
+    PENDING_RELOAD_ACCOUNTS.get_or_init(Default::default)
 }
 
 fn get_pending_delete_accounts() -> &'static std::sync::RwLock<HashSet<String>> {
     PENDING_DELETE_ACCOUNTS.get_or_init(|| std::sync::RwLock::new(HashSet::new()))
 }
 
-/// 触发账号重新加载信号（供 update_account_quota 调用）
-pub fn trigger_account_reload(account_id: &str) {
-    if let Ok(mut pending) = get_pending_reload_accounts().write() {
-        pending.insert(account_id.to_string());
+/// 反代对内部事件总线的订阅: 账号重载 / 删除信号进入待处理队列，由 TokenManager 在 get_token 时处理
+pub(crate) fn on_event(event: &crate::modules::events::AppEvent) {
+    use crate::modules::events::AppEvent;
+    match event {
+        AppEvent::AccountReload { account_id } => queue_account_reload(account_id),
+        AppEvent::AccountDeleted { account_id } => queue_account_delete(account_id),
+        _ => {}
+    }
+}
+
+fn queue_account_reload(account_id: &str) {
+    if let Ok(mut pending) = get_pending_reload_accounts().lock() {
+        pending.mark(account_id);
         tracing::debug!(
             "[Quota] Queued account {} for TokenManager reload",
             account_id
@@ -45,8 +64,11 @@ pub fn trigger_account_reload(account_id: &str) {
     }
 }
 
-/// 触发账号删除信号 (Issue #1477)
-pub fn trigger_account_delete(account_id: &str) {
+/// 账号删除信号 (Issue #1477)
+fn queue_account_delete(account_id: &str) {
+    if let Ok(mut pending) = get_pending_reload_accounts().lock() {
+        pending.forget(account_id);
+    }
     if let Ok(mut pending) = get_pending_delete_accounts().write() {
         pending.insert(account_id.to_string());
         tracing::debug!(
@@ -54,16 +76,40 @@ pub fn trigger_account_delete(account_id: &str) {
             account_id
         );
     }
+    // 同时清理该账号持久化的上游会话 ID
+    crate::proxy::session_pool::remove(account_id);
+}
+
+/// 触发项目重新绑定信号（供 project_binding 在 project_id 切换时调用）
+pub fn trigger_project_rebind(account_id: &str) {
+    let pending = PENDING_PROJECT_REBINDS.get_or_init(|| std::sync::RwLock::new(HashSet::new()));
+    if let Ok(mut pending) = pending.write() {
+        pending.insert(account_id.to_string());
+        tracing::debug!(
+            "[ProjectBinding] Queued account {} for project rebind",
+            account_id
+        );
+    }
+}
+
+/// 获取并清空待重新绑定项目的账号列表（供 TokenManager 调用）
+pub fn take_pending_project_rebinds() -> Vec<String> {
+    PENDING_PROJECT_REBINDS
+        .get()
+        .and_then(|pending| pending.write().ok().map(|mut p| p.drain().collect()))
+        .unwrap_or_default()
 }
 
-/// 获取并清空待重新加载的账号列表（供 TokenManager 调用）
+/// 获取待重新加载的账号列表（供 TokenManager 调用）
+/// 最近刚重载过的账号会保留到其窗口期结束，突发信号只触发一次重载
 pub fn take_pending_reload_accounts() -> Vec<String> {
-    if let Ok(mut pending) = get_pending_reload_accounts().write() {
-        let accounts: Vec<String> = pending.drain().collect();
+    if let Ok(mut pending) = get_pending_reload_accounts().lock() {
+        let accounts = pending.take_ready(std::time::Instant::now());
         if !accounts.is_empty() {
             tracing::debug!(
-                "[Quota] Taking {} pending accounts for reload",
-                accounts.len()
+                "[Quota] Taking {} pending accounts for reload ({} deferred)",
+                accounts.len(),
+                pending.pending_len()
             );
         }
         accounts
@@ -106,7 +152,6 @@ pub struct AppState {
     pub monitor: Arc<crate::proxy::monitor::ProxyMonitor>,
     pub experimental: Arc<RwLock<crate::proxy::config::ExperimentalConfig>>,
     pub debug_logging: Arc<RwLock<crate::proxy::config::DebugLoggingConfig>>,
-    pub switching: Arc<RwLock<bool>>, // [NEW] 账号切换状态，用于防止并发切换
     pub integration: crate::modules::integration::SystemManager, // [NEW] 系统集成层实现
     pub account_service: Arc<crate::modules::account_service::AccountService>, // [NEW] 账号管理服务层
     pub security: Arc<RwLock<crate::proxy::ProxySecurityConfig>>,              // [NEW] 安全配置状态
@@ -124,12 +169,12 @@ impl axum::extract::FromRef<AppState> for Arc<RwLock<crate::proxy::ProxySecurity
     }
 }
 
-#[derive(Serialize)]
-struct ErrorResponse {
+#[derive(Serialize, ToSchema)]
+pub(crate) struct ErrorResponse {
     error: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct AccountResponse {
     id: String,
     email: String,
@@ -151,22 +196,24 @@ struct AccountResponse {
     last_used: i64,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct QuotaResponse {
     models: Vec<ModelQuota>,
     last_updated: i64,
     subscription_tier: Option<String>,
     is_forbidden: bool,
+    /// 配额数据已过期 (超过 quota_staleness_minutes 未刷新)
+    stale: bool,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct ModelQuota {
     name: String,
     percentage: i32,
     reset_time: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct AccountListResponse {
     accounts: Vec<AccountResponse>,
     current_account_id: Option<String>,
@@ -189,6 +236,7 @@ fn to_account_response(
         proxy_disabled_at: account.proxy_disabled_at,
         protected_models: account.protected_models.iter().cloned().collect(),
         quota: account.quota.as_ref().map(|q| QuotaResponse {
+            stale: q.is_stale(crate::proxy::config::get_quota_staleness_minutes()),
             models: q
                 .models
                 .iter()
@@ -249,6 +297,8 @@ impl AxumServer {
     pub async fn update_proxy_pool(&self, new_config: crate::proxy::config::ProxyPoolConfig) {
         let mut pool = self.proxy_pool_state.write().await;
         *pool = new_config;
+        // 节点可能已变更，丢弃按旧节点缓存的 Client
+        crate::modules::http::reset_clients();
         tracing::info!("代理池配置已热更新");
     }
 
@@ -307,6 +357,7 @@ impl AxumServer {
         integration: crate::modules::integration::SystemManager,
         cloudflared_state: Arc<crate::commands::cloudflared::CloudflaredState>,
         proxy_pool_config: crate::proxy::config::ProxyPoolConfig, // [NEW]
+        additional_listeners: Vec<crate::proxy::config::ListenerConfig>,
     ) -> Result<(Self, tokio::task::JoinHandle<()>), String> {
         let custom_mapping_state = Arc::new(tokio::sync::RwLock::new(custom_mapping));
         let proxy_state = Arc::new(tokio::sync::RwLock::new(upstream_proxy.clone()));
@@ -348,7 +399,6 @@ impl AxumServer {
             monitor: monitor.clone(),
             experimental: experimental_state.clone(),
             debug_logging: debug_logging_state.clone(),
-            switching: Arc::new(RwLock::new(false)),
             integration: integration.clone(),
             account_service: Arc::new(crate::modules::account_service::AccountService::new(
                 integration.clone(),
@@ -364,8 +414,9 @@ impl AxumServer {
         // 构建路由 - 使用新架构的 handlers！
         use crate::proxy::handlers;
         use crate::proxy::middleware::{
-            admin_auth_middleware, auth_middleware, cors_layer, ip_filter_middleware,
-            monitor_middleware, service_status_middleware,
+            admin_auth_middleware, admission_middleware, auth_middleware, content_filter_middleware,
+            cors_layer, dry_run_middleware, fault_injection_middleware, ip_filter_middleware, monitor_middleware, request_context_middleware, scoped_prompt_middleware,
+            service_status_middleware,
         };
 
         // 1. 构建主 AI 代理路由 (遵循 auth_mode 配置)
@@ -440,13 +491,21 @@ impl AxumServer {
             .route("/v1/api/event_logging", post(silent_ok_handler))
             // 应用 AI 服务特定的层
             // 注意：Axum layer 执行顺序是从下往上（洋葱模型）
-            // 请求: ip_filter -> auth -> monitor -> handler
-            // 响应: handler -> monitor -> auth -> ip_filter
-            // monitor 需要在 auth 之后执行才能获取 UserTokenIdentity
+            // 请求: request_context -> ip_filter -> auth -> admission -> content_filter -> monitor -> scoped_prompt -> fault_injection -> dry_run -> handler
+            // 响应: handler -> dry_run -> monitor -> content_filter -> admission -> auth -> ip_filter
+            // monitor / admission / scoped_prompt 需要在 auth 之后执行才能获取 UserTokenIdentity
+            .layer(axum::middleware::from_fn(dry_run_middleware))
+            .layer(axum::middleware::from_fn(fault_injection_middleware))
+            .layer(axum::middleware::from_fn(scoped_prompt_middleware))
             .layer(axum::middleware::from_fn_with_state(
                 state.clone(),
                 monitor_middleware,
             ))
+            .layer(axum::middleware::from_fn(content_filter_middleware))
+            .layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                admission_middleware,
+            ))
             .layer(axum::middleware::from_fn_with_state(
                 state.clone(),
                 auth_middleware,
@@ -454,7 +513,9 @@ impl AxumServer {
             .layer(axum::middleware::from_fn_with_state(
                 state.clone(),
                 ip_filter_middleware,
-            ));
+            ))
+            // 最外层: 创建请求 span (request_id / account_id), 内层日志均继承该上下文
+            .layer(axum::middleware::from_fn(request_context_middleware));
 
         // 2. 构建管理 API (强制鉴权)
         let admin_routes = Router::new()
@@ -463,11 +524,38 @@ impl AxumServer {
                 "/accounts",
                 get(admin_list_accounts).post(admin_add_account),
             )
+            .route("/accounts/summaries", post(admin_list_account_summaries))
+            .route("/accounts/share", post(admin_create_account_share))
+            .route("/accounts/share/redeem", post(admin_redeem_account_share))
+            .route("/accounts/bulk-toggle-proxy", post(admin_set_proxy_status_bulk))
             .route("/accounts/current", get(admin_get_current_account))
+            .route(
+                "/accounts/project-bindings",
+                get(admin_get_project_binding_history),
+            )
+            .route(
+                "/accounts/simulate",
+                post(admin_simulate_pool_workload),
+            )
+            .route("/accounts/quota-history", get(admin_get_quota_history))
             .route("/accounts/switch", post(admin_switch_account))
+            .route("/accounts/switch-back", post(admin_switch_back))
+            .route("/accounts/switch-history", get(admin_get_switch_history))
+            .route("/accounts/switch-report", get(admin_get_last_switch_report))
             .route("/accounts/refresh", post(admin_refresh_all_quotas))
+            .route("/accounts/refresh-due", post(admin_refresh_due_quotas))
+            .route(
+                "/accounts/refresh-selected",
+                post(admin_refresh_quota_for_accounts),
+            )
+            .route("/operations", get(admin_list_operations))
+            .route("/operations/:operationId/cancel", post(admin_cancel_operation))
             .route("/accounts/:accountId", delete(admin_delete_account))
             .route("/accounts/:accountId/bind-device", post(admin_bind_device))
+            .route(
+                "/accounts/:accountId/verify-login",
+                post(admin_verify_account_login),
+            )
             .route(
                 "/accounts/:accountId/device-profiles",
                 get(admin_get_device_profiles),
@@ -507,6 +595,15 @@ impl AxumServer {
             .route("/stats/accounts", get(admin_get_token_stats_by_account))
             .route("/stats/models", get(admin_get_token_stats_by_model))
             .route("/config", get(admin_get_config).post(admin_save_config))
+            .route("/config/export", get(admin_export_app_config))
+            .route("/config/import", post(admin_import_app_config))
+            .route("/config/reset-section", post(admin_reset_config_section))
+            .route("/config/history", get(admin_list_config_history))
+            .route("/config/history/diff", get(admin_diff_config_versions))
+            .route(
+                "/config/history/:version/rollback",
+                post(admin_rollback_config),
+            )
             .route("/proxy/cli/status", post(admin_get_cli_sync_status))
             .route("/proxy/cli/sync", post(admin_execute_cli_sync))
             .route("/proxy/cli/restore", post(admin_execute_cli_restore))
@@ -544,12 +641,21 @@ impl AxumServer {
                 "/proxy/preferred-account",
                 get(admin_get_preferred_account).post(admin_set_preferred_account),
             )
+            .route("/proxy/model-availability", get(admin_get_model_availability))
+            .route("/proxy/token-manager", get(admin_get_token_manager_state))
+            .route("/proxy/capacity", get(admin_get_capacity_status))
             .route("/accounts/oauth/prepare", post(admin_prepare_oauth_url))
             .route("/accounts/oauth/start", post(admin_start_oauth_login))
             .route("/accounts/oauth/complete", post(admin_complete_oauth_login))
             .route("/accounts/oauth/cancel", post(admin_cancel_oauth_login))
             .route("/accounts/oauth/submit-code", post(admin_submit_oauth_code))
             .route("/zai/models/fetch", post(admin_fetch_zai_models))
+            .route("/models/known", get(admin_list_known_models))
+            .route("/models/catalog/sync", post(admin_sync_model_catalog))
+            .route(
+                "/quota-protection/monitored-models",
+                get(admin_get_monitored_model_options).post(admin_update_monitored_models),
+            )
             .route(
                 "/proxy/monitor/toggle",
                 post(admin_set_proxy_monitor_enabled),
@@ -566,17 +672,40 @@ impl AxumServer {
             .route("/proxy/cloudflared/stop", post(admin_cloudflared_stop))
             .route("/system/open-folder", post(admin_open_folder))
             .route("/proxy/stats", get(admin_get_proxy_stats))
+            .route("/proxy/admission", get(admin_get_admission_stats))
+            .route("/proxy/sessions", get(admin_list_upstream_sessions))
+            .route("/proxy/sessions/rotate", post(admin_rotate_upstream_sessions))
+            .route("/proxy/circuits", get(admin_get_upstream_circuits))
+            .route("/proxy/content-filter/stats", get(admin_get_content_filter_stats))
+            .route(
+                "/proxy/content-filter/stats/reset",
+                post(admin_reset_content_filter_stats),
+            )
             .route("/logs", get(admin_get_proxy_logs_filtered))
             .route("/logs/count", get(admin_get_proxy_logs_count_filtered))
             .route("/logs/clear", post(admin_clear_proxy_logs))
+            .route("/logs/compact", post(admin_compact_proxy_logs))
             .route("/logs/:logId", get(admin_get_proxy_log_detail))
+            .route("/logs/:logId/replay", post(admin_replay_request))
             // Debug Console (Log Bridge)
             .route("/debug/enable", post(admin_enable_debug_console))
             .route("/debug/disable", post(admin_disable_debug_console))
             .route("/debug/enabled", get(admin_is_debug_console_enabled))
             .route("/debug/logs", get(admin_get_debug_console_logs))
             .route("/debug/logs/clear", post(admin_clear_debug_console_logs))
+            .route("/debug/events", get(admin_tail_events))
             .route("/stats/token/clear", post(admin_clear_token_stats))
+            .route("/stats/retention", post(admin_run_data_retention))
+            .route("/system/hygiene", post(admin_run_account_hygiene))
+            .route("/digest/run", post(admin_run_problem_digest))
+            // Remote backup (WebDAV / S3)
+            .route("/backup/run", post(admin_run_remote_backup))
+            .route("/backup/remote", get(admin_list_remote_backups))
+            .route("/backup/restore", post(admin_restore_remote_backup))
+            .route("/backup/status", get(admin_get_backup_status))
+            // Account pool sync
+            .route("/sync/run", post(admin_sync_accounts_now))
+            .route("/sync/status", get(admin_get_sync_status))
             .route("/stats/token/hourly", get(admin_get_token_stats_hourly))
             .route("/stats/token/daily", get(admin_get_token_stats_daily))
             .route("/stats/token/weekly", get(admin_get_token_stats_weekly))
@@ -586,6 +715,7 @@ impl AxumServer {
             )
             .route("/stats/token/summary", get(admin_get_token_stats_summary))
             .route("/stats/token/by-model", get(admin_get_token_stats_by_model))
+            .route("/stats/latency/by-account", get(admin_get_account_latency_stats))
             .route(
                 "/stats/token/model-trend/hourly",
                 get(admin_get_token_stats_model_trend_hourly),
@@ -612,7 +742,39 @@ impl AxumServer {
             )
             .route("/accounts/warmup", post(admin_warm_up_all_accounts))
             .route("/accounts/:accountId/warmup", post(admin_warm_up_account))
+            .route(
+                "/accounts/:accountId/test-request",
+                post(admin_test_account_request),
+            )
+            .route("/accounts/:accountId/tags", post(admin_update_account_tags))
+            .route(
+                "/accounts/:accountId/ip-family",
+                post(admin_update_account_ip_family),
+            )
+            .route(
+                "/accounts/:accountId/antigravity-args",
+                post(admin_update_account_antigravity_args),
+            )
+            .route(
+                "/accounts/:accountId/antigravity-executable",
+                post(admin_update_account_antigravity_executable),
+            )
+            .route("/accounts/orphans", get(admin_list_orphan_accounts))
+            .route("/accounts/:accountId/snooze", post(admin_snooze_account))
+            .route("/accounts/orphans/:accountId/adopt", post(admin_adopt_orphan))
+            .route("/accounts/corrupt", get(admin_list_corrupt_accounts))
+            .route(
+                "/accounts/corrupt/:fileName/restore",
+                post(admin_restore_corrupt_account),
+            )
+            .route(
+                "/accounts/corrupt/:fileName",
+                delete(admin_delete_corrupt_account),
+            )
             .route("/system/data-dir", get(admin_get_data_dir_path))
+            .route("/system/startup-report", get(admin_get_startup_report))
+            .route("/notifications", get(admin_list_notifications))
+            .route("/notifications/read", post(admin_mark_notifications_read))
             .route("/system/updates/settings", get(admin_get_update_settings))
             .route(
                 "/system/updates/check-status",
@@ -631,6 +793,10 @@ impl AxumServer {
                 get(admin_get_http_api_settings).post(admin_save_http_api_settings),
             )
             .route("/system/antigravity/path", get(admin_get_antigravity_path))
+            .route(
+                "/system/antigravity/installations",
+                get(admin_detect_antigravity_installations),
+            )
             .route("/system/antigravity/args", get(admin_get_antigravity_args))
             .route("/system/cache/clear", post(admin_clear_antigravity_cache))
             .route(
@@ -638,6 +804,21 @@ impl AxumServer {
                 get(admin_get_antigravity_cache_paths),
             )
             .route("/system/logs/clear-cache", post(admin_clear_log_cache))
+            .route("/system/logs/export", post(admin_export_logs))
+            .route("/system/logs/query", post(admin_query_logs))
+            .route("/system/diagnostics", post(admin_generate_diagnostics_bundle))
+            .route("/system/integrity", get(admin_verify_data_integrity))
+            .route("/system/integrity/repair", post(admin_apply_integrity_repair))
+            .route("/system/network", get(admin_get_network_status))
+            .route("/system/offline", post(admin_set_offline_mode))
+            .route("/system/clock-skew", get(admin_get_clock_skew))
+            .route("/system/scheduler", get(admin_scheduler_status))
+            .route("/system/scheduler/jobs/:jobId", post(admin_set_scheduler_job_enabled))
+            .route("/onboarding", get(admin_get_onboarding_state))
+            .route("/onboarding/steps/:step", post(admin_complete_onboarding_step))
+            .route("/system/languages", get(admin_list_available_languages))
+            .route("/system/languages/:language", get(admin_get_locale_messages))
+            .route("/system/log-levels", get(admin_get_log_levels).post(admin_set_log_levels))
             // Security / IP Monitoring
             .route("/security/logs", get(admin_get_ip_access_logs))
             .route("/security/logs/clear", post(admin_clear_ip_access_logs))
@@ -653,6 +834,8 @@ impl AxumServer {
             // User Tokens
             .route("/user-tokens", get(admin_list_user_tokens).post(admin_create_user_token))
             .route("/user-tokens/summary", get(admin_get_user_token_summary))
+            .route("/user-tokens/billing", get(admin_get_billing_report))
+            .route("/user-tokens/billing/export", get(admin_export_billing_report))
             .route("/user-tokens/:id/renew", post(admin_renew_user_token))
             .route("/user-tokens/:id", delete(admin_delete_user_token).patch(admin_update_user_token))
             // OAuth (Web) - Admin 接口
@@ -677,6 +860,7 @@ impl AxumServer {
             // 公开路由 (无需鉴权)
             .route("/auth/callback", get(handle_oauth_callback))
             .route("/api/quota-summary", get(admin_quota_summary_text))
+            .merge(SwaggerUi::new("/docs").url("/openapi.json", ApiDoc::openapi()))
             // 应用全局监控与状态层 (外层)
             .layer(axum::middleware::from_fn_with_state(
                 state.clone(),
@@ -697,16 +881,35 @@ impl AxumServer {
             app
         };
 
-        // 绑定地址
-        let addr = format!("{}:{}", host, port);
-        let listener = tokio::net::TcpListener::bind(&addr)
-            .await
-            .map_err(|e| format!("地址 {} 绑定失败: {}", addr, e))?;
+        // 绑定地址 (主端口 + 附加监听地址；任一绑定失败则整体启动失败)
+        let addr = if host.contains(':') {
+            format!("[{}]:{}", host, port)
+        } else {
+            format!("{}:{}", host, port)
+        };
+        let listener = bind_listener(&addr)?;
 
         tracing::info!("反代服务器启动在 http://{}", addr);
 
+        let mut extra_listeners = Vec::new();
+        for cfg in additional_listeners.into_iter().filter(|l| l.enabled) {
+            let extra_addr = cfg.bind_address();
+            let tls = match &cfg.tls {
+                Some(tls) => Some(load_tls_acceptor(tls)?),
+                None => None,
+            };
+            let extra = bind_listener(&extra_addr)?;
+            tracing::info!(
+                "附加监听地址启动在 {}://{} (auth: {:?})",
+                if tls.is_some() { "https" } else { "http" },
+                extra_addr,
+                cfg.resolved_auth_mode()
+            );
+            extra_listeners.push((extra, tls, cfg.resolved_auth_mode()));
+        }
+
         // 创建关闭通道
-        let (shutdown_tx, mut shutdown_rx) = oneshot::channel::<()>();
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
 
         let server_instance = Self {
             shutdown_tx: Arc::new(tokio::sync::Mutex::new(Some(shutdown_tx))),
@@ -724,50 +927,32 @@ impl AxumServer {
             proxy_pool_manager,
         };
 
-        // 在新任务中启动服务器
+        // 在新任务中启动服务器；所有监听地址共享同一个关闭信号
         let handle = tokio::spawn(async move {
-            use hyper::server::conn::http1;
-            use hyper_util::rt::TokioIo;
-            use hyper_util::service::TowerToHyperService;
-
-            loop {
-                tokio::select! {
-                    res = listener.accept() => {
-                        match res {
-                            Ok((stream, remote_addr)) => {
-                                let io = TokioIo::new(stream);
-                                
-                                // 注入 ConnectInfo (用于获取真实 IP)
-                                use tower::ServiceExt;
-                                use hyper::body::Incoming;
-                                let app_with_info = app.clone().map_request(move |mut req: axum::http::Request<Incoming>| {
-                                    req.extensions_mut().insert(axum::extract::ConnectInfo(remote_addr));
-                                    req
-                                });
-
-                                let service = TowerToHyperService::new(app_with_info);
-
-                                tokio::task::spawn(async move {
-                                    if let Err(err) = http1::Builder::new()
-                                        .serve_connection(io, service)
-                                        .with_upgrades() // 支持 WebSocket (如果以后需要)
-                                        .await
-                                    {
-                                        debug!("连接处理结束或出错: {:?}", err);
-                                    }
-                                });
-                            }
-                            Err(e) => {
-                                error!("接收连接失败: {:?}", e);
-                            }
-                        }
-                    }
-                    _ = &mut shutdown_rx => {
-                        tracing::info!("反代服务器停止监听");
-                        break;
-                    }
-                }
+            let (stop_tx, stop_rx) = tokio::sync::watch::channel(false);
+            let mut tasks = vec![tokio::spawn(serve_listener(
+                listener,
+                app.clone(),
+                None,
+                None,
+                stop_rx.clone(),
+            ))];
+            for (extra, tls, auth_mode) in extra_listeners {
+                tasks.push(tokio::spawn(serve_listener(
+                    extra,
+                    app.clone(),
+                    tls,
+                    auth_mode,
+                    stop_rx.clone(),
+                )));
+            }
+
+            let _ = shutdown_rx.await;
+            let _ = stop_tx.send(true);
+            for task in tasks {
+                let _ = task.await;
             }
+            tracing::info!("反代服务器停止监听");
         });
 
         Ok((server_instance, handle))
@@ -786,9 +971,141 @@ impl AxumServer {
     }
 }
 
+/// 绑定监听地址；`[::]` 以双栈方式绑定 (关闭 IPV6_V6ONLY，同时接受 IPv4 连接)
+fn bind_listener(addr: &str) -> Result<tokio::net::TcpListener, String> {
+    use socket2::{Domain, Protocol, Socket, Type};
+
+    use std::net::ToSocketAddrs;
+
+    let socket_addr = addr
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+        .ok_or_else(|| format!("地址 {} 无效", addr))?;
+    let bind = || -> std::io::Result<std::net::TcpListener> {
+        let socket = Socket::new(Domain::for_address(socket_addr), Type::STREAM, Some(Protocol::TCP))?;
+        if socket_addr.is_ipv6() && socket_addr.ip().is_unspecified() {
+            socket.set_only_v6(false)?;
+        }
+        #[cfg(not(windows))]
+        socket.set_reuse_address(true)?;
+        socket.bind(&socket_addr.into())?;
+        socket.listen(1024)?;
+        socket.set_nonblocking(true)?;
+        Ok(socket.into())
+    };
+    bind()
+        .and_then(tokio::net::TcpListener::from_std)
+        .map_err(|e| format!("地址 {} 绑定失败: {}", addr, e))
+}
+
+/// 读取 PEM 证书与私钥
+fn load_tls_acceptor(
+    tls: &crate::proxy::config::ListenerTlsConfig,
+) -> Result<tokio_rustls::TlsAcceptor, String> {
+    use tokio_rustls::rustls;
+    use tokio_rustls::rustls::pki_types::pem::PemObject;
+    use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+
+    let cert_pem = std::fs::read(&tls.cert_path)
+        .map_err(|e| format!("读取证书 {} 失败: {}", tls.cert_path, e))?;
+    let key_pem = std::fs::read(&tls.key_path)
+        .map_err(|e| format!("读取私钥 {} 失败: {}", tls.key_path, e))?;
+    let certs = CertificateDer::pem_slice_iter(&cert_pem)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("证书 {} 无效: {:?}", tls.cert_path, e))?;
+    let key = PrivateKeyDer::from_pem_slice(&key_pem)
+        .map_err(|e| format!("私钥 {} 无效: {:?}", tls.key_path, e))?;
+
+    let config = rustls::ServerConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()
+    .map_err(|e| format!("TLS 配置失败: {}", e))?
+    .with_no_client_auth()
+    .with_single_cert(certs, key)
+    .map_err(|e| format!("TLS 配置失败: {}", e))?;
+    Ok(tokio_rustls::TlsAcceptor::from(Arc::new(config)))
+}
+
+/// 单个监听地址的接收循环
+/// 注入 ConnectInfo (用于获取真实 IP)，以及该监听地址的鉴权策略 (未设置时沿用全局策略)
+async fn serve_listener(
+    listener: tokio::net::TcpListener,
+    app: Router,
+    tls: Option<tokio_rustls::TlsAcceptor>,
+    auth_mode: Option<crate::proxy::config::ProxyAuthMode>,
+    mut stop: tokio::sync::watch::Receiver<bool>,
+) {
+    use hyper::body::Incoming;
+    use tower::ServiceExt;
+
+    loop {
+        tokio::select! {
+            res = listener.accept() => {
+                match res {
+                    Ok((stream, remote_addr)) => {
+                        let auth_mode = auth_mode.clone();
+                        let app_with_info = app.clone().map_request(move |mut req: axum::http::Request<Incoming>| {
+                            req.extensions_mut().insert(axum::extract::ConnectInfo(remote_addr));
+                            if let Some(mode) = &auth_mode {
+                                req.extensions_mut()
+                                    .insert(crate::proxy::security::ListenerAuthMode(mode.clone()));
+                            }
+                            req
+                        });
+                        let service = hyper_util::service::TowerToHyperService::new(app_with_info);
+
+                        let tls = tls.clone();
+                        tokio::task::spawn(async move {
+                            match tls {
+                                Some(acceptor) => match acceptor.accept(stream).await {
+                                    Ok(tls_stream) => serve_connection(tls_stream, service).await,
+                                    Err(e) => debug!("TLS 握手失败 ({}): {:?}", remote_addr, e),
+                                },
+                                None => serve_connection(stream, service).await,
+                            }
+                        });
+                    }
+                    Err(e) => {
+                        error!("接收连接失败: {:?}", e);
+                    }
+                }
+            }
+            _ = stop.changed() => {
+                break;
+            }
+        }
+    }
+}
+
+async fn serve_connection<I, S>(io: I, service: S)
+where
+    I: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static,
+    S: hyper::service::Service<hyper::Request<hyper::body::Incoming>, Response = Response>
+        + Send
+        + 'static,
+    S::Future: Send + 'static,
+    S::Error: std::error::Error + Send + Sync + 'static,
+{
+    if let Err(err) = hyper::server::conn::http1::Builder::new()
+        .serve_connection(hyper_util::rt::TokioIo::new(io), service)
+        .with_upgrades() // 支持 WebSocket (如果以后需要)
+        .await
+    {
+        debug!("连接处理结束或出错: {:?}", err);
+    }
+}
+
 // ===== API 处理器 (旧代码已移除，由 src/proxy/handlers/* 接管) =====
 
 /// 健康检查处理器
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "System",
+    summary = "Health check"
+)]
 async fn health_check_handler() -> Response {
     Json(serde_json::json!({
         "status": "ok",
@@ -808,6 +1125,13 @@ async fn silent_ok_handler() -> Response {
 
 // [整合清理] 旧模型定义与映射器已上移
 
+#[utoipa::path(
+    get,
+    path = "/api/accounts",
+    tag = "Accounts",
+    summary = "List accounts",
+    responses((status = 200, body = AccountListResponse))
+)]
 async fn admin_list_accounts(
     State(state): State<AppState>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -825,6 +1149,7 @@ async fn admin_list_accounts(
         .map(|acc| {
             let is_current = current_id.as_ref().map(|id| id == &acc.id).unwrap_or(false);
             let quota = acc.quota.map(|q| QuotaResponse {
+                stale: q.is_stale(crate::proxy::config::get_quota_staleness_minutes()),
                 models: q
                     .models
                     .into_iter()
@@ -868,12 +1193,19 @@ async fn admin_list_accounts(
 }
 
 /// Export accounts with refresh tokens (for backup/migration)
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct ExportAccountsRequest {
     account_ids: Vec<String>,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/accounts/export",
+    tag = "Accounts",
+    summary = "Export accounts",
+    request_body = ExportAccountsRequest
+)]
 async fn admin_export_accounts(
     State(_state): State<AppState>,
     Json(payload): Json<ExportAccountsRequest>,
@@ -888,6 +1220,12 @@ async fn admin_export_accounts(
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/accounts/current",
+    tag = "Accounts",
+    summary = "Get current account"
+)]
 async fn admin_get_current_account(
     State(state): State<AppState>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -902,6 +1240,7 @@ async fn admin_get_current_account(
         let acc = account::load_account(&id).ok();
         acc.map(|acc| {
             let quota = acc.quota.map(|q| QuotaResponse {
+                stale: q.is_stale(crate::proxy::config::get_quota_staleness_minutes()),
                 models: q
                     .models
                     .into_iter()
@@ -943,12 +1282,20 @@ async fn admin_get_current_account(
     Ok(Json(response))
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct AddAccountRequest {
     refresh_token: String,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/accounts",
+    tag = "Accounts",
+    summary = "Add account",
+    request_body = AddAccountRequest,
+    responses((status = 200, body = AccountResponse))
+)]
 async fn admin_add_account(
     State(state): State<AppState>,
     Json(payload): Json<AddAccountRequest>,
@@ -981,19 +1328,121 @@ async fn admin_add_account(
     Ok(Json(to_account_response(&account, &current_id)))
 }
 
+fn share_error(e: String) -> (StatusCode, Json<ErrorResponse>) {
+    let status = if e.starts_with("invalid_share")
+        || e.starts_with("share_passphrase_too_short")
+        || e.starts_with("share_decrypt_failed")
+        || e.starts_with("share_expired")
+    {
+        StatusCode::BAD_REQUEST
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+    (status, Json(ErrorResponse { error: e }))
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct CreateAccountShareRequest {
+    account_id: String,
+    passphrase: String,
+    ttl: Option<u64>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/accounts/share",
+    tag = "Accounts",
+    summary = "Create a passphrase-encrypted share blob for one account",
+    request_body = CreateAccountShareRequest
+)]
+async fn admin_create_account_share(
+    Json(payload): Json<CreateAccountShareRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let share =
+        crate::commands::create_account_share(payload.account_id, payload.passphrase, payload.ttl)
+            .await
+            .map_err(share_error)?;
+    Ok(Json(share))
+}
+
+#[derive(Deserialize, ToSchema)]
+struct RedeemAccountShareRequest {
+    blob: String,
+    passphrase: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/accounts/share/redeem",
+    tag = "Accounts",
+    summary = "Redeem an account share blob and add the account",
+    request_body = RedeemAccountShareRequest,
+    responses((status = 200, body = AccountResponse))
+)]
+async fn admin_redeem_account_share(
+    State(state): State<AppState>,
+    Json(payload): Json<RedeemAccountShareRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let account = crate::modules::account_share::redeem_share(
+        &state.account_service,
+        &payload.blob,
+        &payload.passphrase,
+    )
+    .await
+    .map_err(share_error)?;
+
+    if let Err(e) = state.token_manager.load_accounts().await {
+        logger::log_error(&format!(
+            "[API] Failed to reload accounts after redeeming share: {}",
+            e
+        ));
+    }
+
+    let current_id = state.account_service.get_current_id().map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+    Ok(Json(to_account_response(&account, &current_id)))
+}
+
+#[derive(Deserialize, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct DeleteConfirmQuery {
+    #[serde(default)]
+    confirmed: bool,
+}
+
+fn delete_error(e: String) -> (StatusCode, Json<ErrorResponse>) {
+    let status = if crate::modules::domain_policy::is_confirmation_error(&e) {
+        StatusCode::CONFLICT
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+    (status, Json(ErrorResponse { error: e }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/accounts/{accountId}",
+    tag = "Accounts",
+    summary = "Delete account",
+    params(
+        ("accountId" = String, Path, description = "Account ID"),
+        DeleteConfirmQuery,
+    )
+)]
 async fn admin_delete_account(
     State(state): State<AppState>,
     Path(account_id): Path<String>,
+    Query(query): Query<DeleteConfirmQuery>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     state
         .account_service
-        .delete_account(&account_id)
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse { error: e }),
-            )
-        })?;
+        .delete_account(&account_id, query.confirmed)
+        .map_err(delete_error)?;
 
     // [FIX #1166] 账号变动后立即重新加载 TokenManager
     if let Err(e) = state.token_manager.load_accounts().await {
@@ -1006,45 +1455,31 @@ async fn admin_delete_account(
     Ok(StatusCode::NO_CONTENT)
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct SwitchRequest {
     account_id: String,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/accounts/switch",
+    tag = "Accounts",
+    summary = "Switch account",
+    request_body = SwitchRequest
+)]
 async fn admin_switch_account(
     State(state): State<AppState>,
     Json(payload): Json<SwitchRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
-    {
-        let switching = state.switching.read().await;
-        if *switching {
-            return Err((
-                StatusCode::CONFLICT,
-                Json(ErrorResponse {
-                    error: "Another switch operation is already in progress".to_string(),
-                }),
-            ));
-        }
-    }
-
-    {
-        let mut switching = state.switching.write().await;
-        *switching = true;
-    }
-
+    // 并发切换由 switch_queue 串行化: 目标相同的请求合并，不同目标返回 409
     let account_id = payload.account_id.clone();
     logger::log_info(&format!("[API] Starting account switch: {}", account_id));
 
     let result = state.account_service.switch_account(&account_id).await;
 
-    {
-        let mut switching = state.switching.write().await;
-        *switching = false;
-    }
-
     match result {
-        Ok(()) => {
+        Ok(report) => {
             logger::log_info(&format!("[API] Account switch successful: {}", account_id));
 
             // [FIX #1166] 账号切换后立即同步内存状态
@@ -1056,22 +1491,158 @@ async fn admin_switch_account(
                 ));
             }
 
-            Ok(StatusCode::OK)
+            Ok(Json(report))
         }
         Err(e) => {
             logger::log_error(&format!("[API] Account switch failed: {}", e));
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse { error: e }),
-            ))
+            let status = if crate::modules::switch_queue::is_in_progress_error(&e) {
+                StatusCode::CONFLICT
+            } else {
+                StatusCode::INTERNAL_SERVER_ERROR
+            };
+            Err((status, Json(ErrorResponse { error: e })))
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/accounts/switch-back",
+    tag = "Accounts",
+    summary = "Switch back to the previously active account"
+)]
+async fn admin_switch_back(
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let result = state.account_service.switch_back().await;
+
+    match result {
+        Ok(account_id) => {
+            logger::log_info(&format!("[API] Switched back to account: {}", account_id));
+            state.token_manager.clear_all_sessions();
+            if let Err(e) = state.token_manager.load_accounts().await {
+                logger::log_error(&format!(
+                    "[API] Failed to reload accounts after switch: {}",
+                    e
+                ));
+            }
+            Ok(Json(serde_json::json!({ "accountId": account_id })))
+        }
+        Err(e) => {
+            let status = if e == "no_previous_account" {
+                StatusCode::NOT_FOUND
+            } else if crate::modules::switch_queue::is_in_progress_error(&e) {
+                StatusCode::CONFLICT
+            } else {
+                StatusCode::INTERNAL_SERVER_ERROR
+            };
+            Err((status, Json(ErrorResponse { error: e })))
         }
     }
 }
 
-async fn admin_refresh_all_quotas() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)>
+#[utoipa::path(
+    get,
+    path = "/api/accounts/switch-history",
+    tag = "Accounts",
+    summary = "Recent account switches (newest first)"
+)]
+async fn admin_get_switch_history() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)>
 {
+    let history = crate::commands::get_switch_history().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+    Ok(Json(history))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/accounts/switch-report",
+    tag = "Accounts",
+    summary = "Phase-by-phase report of the last account switch"
+)]
+async fn admin_get_last_switch_report(
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    Ok(Json(crate::modules::account::last_switch_report()))
+}
+
+#[derive(Deserialize, Default, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct OperationRequest {
+    operation_id: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/accounts/refresh",
+    tag = "Accounts",
+    summary = "Refresh all quotas",
+    request_body = OperationRequest
+)]
+async fn admin_refresh_all_quotas(
+    payload: Option<Json<OperationRequest>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     logger::log_info("[API] Starting refresh of all account quotas");
-    let stats = account::refresh_all_quotas_logic().await.map_err(|e| {
+    let operation_id = payload.and_then(|Json(p)| p.operation_id);
+    let stats = account::refresh_all_quotas_cancellable(operation_id).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+
+    Ok(Json(stats))
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct RefreshSelectedRequest {
+    account_ids: Vec<String>,
+    operation_id: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/accounts/refresh-selected",
+    tag = "Accounts",
+    summary = "Refresh quotas of the selected accounts",
+    request_body = RefreshSelectedRequest
+)]
+async fn admin_refresh_quota_for_accounts(
+    State(state): State<AppState>,
+    Json(payload): Json<RefreshSelectedRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let stats = account::refresh_quota_for_accounts(&payload.account_ids, payload.operation_id)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse { error: e }),
+            )
+        })?;
+
+    for account_id in &payload.account_ids {
+        let _ = state.token_manager.reload_account(account_id).await;
+    }
+
+    Ok(Json(stats))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/accounts/refresh-due",
+    tag = "Accounts",
+    summary = "Refresh due quotas",
+    request_body = OperationRequest
+)]
+async fn admin_refresh_due_quotas(
+    payload: Option<Json<OperationRequest>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let operation_id = payload.and_then(|Json(p)| p.operation_id);
+    let stats = account::refresh_due_quotas_logic(operation_id).await.map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse { error: e }),
@@ -1081,8 +1652,67 @@ async fn admin_refresh_all_quotas() -> Result<impl IntoResponse, (StatusCode, Js
     Ok(Json(stats))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/system/log-levels",
+    tag = "Logs",
+    summary = "Get runtime log levels"
+)]
+async fn admin_get_log_levels() -> impl IntoResponse {
+    Json(crate::modules::logger::get_log_levels())
+}
+
+#[derive(Deserialize, ToSchema)]
+struct LogLevelsRequest {
+    #[schema(value_type = Object)]
+    levels: crate::models::LoggingConfig,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/system/log-levels",
+    tag = "Logs",
+    summary = "Set runtime log levels",
+    request_body = LogLevelsRequest
+)]
+async fn admin_set_log_levels(
+    Json(payload): Json<LogLevelsRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    crate::commands::set_log_levels(payload.levels)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: e })))?;
+    Ok(StatusCode::OK)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/operations",
+    tag = "Accounts",
+    summary = "List operations"
+)]
+async fn admin_list_operations() -> impl IntoResponse {
+    Json(crate::modules::operations::list_operations())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/operations/{operationId}/cancel",
+    tag = "Accounts",
+    summary = "Cancel operation",
+    params(("operationId" = String, Path, description = "Operation ID"))
+)]
+async fn admin_cancel_operation(Path(operation_id): Path<String>) -> impl IntoResponse {
+    Json(crate::modules::operations::cancel_operation(&operation_id))
+}
+
 // --- OAuth Handlers ---
 
+#[utoipa::path(
+    post,
+    path = "/api/accounts/oauth/prepare",
+    tag = "Accounts",
+    summary = "Prepare oauth url"
+)]
 async fn admin_prepare_oauth_url(
     State(state): State<AppState>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -1099,6 +1729,13 @@ async fn admin_prepare_oauth_url(
     Ok(Json(serde_json::json!({ "url": url })))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/accounts/oauth/start",
+    tag = "Accounts",
+    summary = "Start oauth login",
+    responses((status = 200, body = AccountResponse))
+)]
 async fn admin_start_oauth_login(
     State(state): State<AppState>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -1121,6 +1758,13 @@ async fn admin_start_oauth_login(
     Ok(Json(to_account_response(&account, &current_id)))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/accounts/oauth/complete",
+    tag = "Accounts",
+    summary = "Complete oauth login",
+    responses((status = 200, body = AccountResponse))
+)]
 async fn admin_complete_oauth_login(
     State(state): State<AppState>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -1143,6 +1787,12 @@ async fn admin_complete_oauth_login(
     Ok(Json(to_account_response(&account, &current_id)))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/accounts/oauth/cancel",
+    tag = "Accounts",
+    summary = "Cancel oauth login"
+)]
 async fn admin_cancel_oauth_login(
     State(state): State<AppState>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -1150,12 +1800,19 @@ async fn admin_cancel_oauth_login(
     Ok(StatusCode::OK)
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 struct SubmitCodeRequest {
     code: String,
     state: Option<String>,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/accounts/oauth/submit-code",
+    tag = "Accounts",
+    summary = "Submit oauth code",
+    request_body = SubmitCodeRequest
+)]
 async fn admin_submit_oauth_code(
     State(state): State<AppState>,
     Json(payload): Json<SubmitCodeRequest>,
@@ -1173,7 +1830,7 @@ async fn admin_submit_oauth_code(
     Ok(StatusCode::OK)
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 struct BindDeviceRequest {
     #[serde(default = "default_bind_mode")]
     mode: String,
@@ -1183,6 +1840,35 @@ fn default_bind_mode() -> String {
     "generate".to_string()
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/accounts/{accountId}/verify-login",
+    tag = "Accounts",
+    summary = "Dry-run the switch pipeline for an account",
+    params(("accountId" = String, Path, description = "Account ID"))
+)]
+async fn admin_verify_account_login(
+    Path(account_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let report = crate::modules::account::verify_account_login(&account_id)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse { error: e }),
+            )
+        })?;
+    Ok(Json(report))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/accounts/{accountId}/bind-device",
+    tag = "Accounts",
+    summary = "Bind device",
+    params(("accountId" = String, Path, description = "Account ID")),
+    request_body = BindDeviceRequest
+)]
 async fn admin_bind_device(
     Path(account_id): Path<String>,
     Json(payload): Json<BindDeviceRequest>,
@@ -1201,9 +1887,10 @@ async fn admin_bind_device(
     })))
 }
 
-#[derive(Deserialize, Debug, Default)]
+#[derive(Deserialize, Debug, Default, IntoParams)]
 #[serde(rename_all = "camelCase")]
 #[allow(dead_code)] // 预留日志接口结构体
+#[into_params(parameter_in = Query)]
 struct LogsRequest {
     #[serde(default)]
     limit: usize,
@@ -1242,6 +1929,12 @@ async fn admin_get_logs(
     })))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/config",
+    tag = "Config",
+    summary = "Get config"
+)]
 async fn admin_get_config() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     let cfg = config::load_app_config().map_err(|e| {
         (
@@ -1252,17 +1945,46 @@ async fn admin_get_config() -> Result<impl IntoResponse, (StatusCode, Json<Error
     Ok(Json(cfg))
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct SaveConfigWrapper {
+    #[schema(value_type = Object)]
     config: AppConfig,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/config",
+    tag = "Config",
+    summary = "Save config",
+    request_body = SaveConfigWrapper
+)]
 async fn admin_save_config(
     State(state): State<AppState>,
     Json(payload): Json<SaveConfigWrapper>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
-    let new_config = payload.config;
+    let mut new_config = payload.config;
+    crate::proxy::content_filter::validate(&new_config.proxy.content_filter)
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: e })))?;
+    new_config
+        .proxy
+        .validate_listeners()
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: e })))?;
+    crate::modules::dns::validate(&new_config.http_client.dns)
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: e })))?;
+    let previous = config::load_app_config().ok();
+    // 应用锁只能通过桌面端专用命令修改
+    new_config.app_lock = previous
+        .as_ref()
+        .map(|p| p.app_lock.clone())
+        .unwrap_or_default();
+    let monitored_changed = crate::modules::model_catalog::prepare_monitored_models(
+        previous
+            .as_ref()
+            .map(|p| p.quota_protection.monitored_models.as_slice()),
+        &mut new_config.quota_protection.monitored_models,
+    )
+    .map_err(|e| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: e })))?;
     // 1. 持久化
     config::save_app_config(&new_config).map_err(|e| {
         (
@@ -1270,6 +1992,21 @@ async fn admin_save_config(
             Json(ErrorResponse { error: e }),
         )
     })?;
+    if monitored_changed {
+        if let Err(e) =
+            crate::modules::account::reapply_quota_protection(&new_config.quota_protection)
+        {
+            logger::log_warn(&format!("[Quota] Failed to re-apply protection: {}", e));
+        }
+    }
+
+    if let Err(e) = logger::apply_log_levels_if_changed(&new_config.logging) {
+        logger::log_warn(&format!("应用日志级别失败: {}", e));
+    }
+    crate::modules::http::update_settings(new_config.http_client.clone());
+    crate::modules::offline::update_config(new_config.offline.clone());
+    crate::modules::endpoints::update_config(new_config.upstream_endpoints.clone());
+    crate::constants::update_version_sources(&new_config.version_sources);
 
     // 2. 热更新内存状态
     // 这里我们直接复用内部组件的 update 方法
@@ -1284,6 +2021,18 @@ async fn admin_save_config(
         let mut mapping = state.custom_mapping.write().await;
         *mapping = new_config.clone().proxy.custom_mapping;
     }
+    crate::proxy::update_fallback_chains(new_config.proxy.fallback_chains.clone());
+    crate::proxy::update_tier_routing(new_config.proxy.tier_routing.clone());
+    crate::proxy::update_admission_config(new_config.proxy.admission.clone());
+    crate::proxy::update_session_pool_config(new_config.proxy.session_pool.clone());
+    crate::proxy::update_circuit_breaker_config(new_config.proxy.circuit_breaker.clone());
+    crate::proxy::update_upstream_timeouts(new_config.proxy.upstream_timeouts.clone());
+    crate::proxy::update_quota_staleness_minutes(new_config.proxy.quota_staleness_minutes);
+    crate::proxy::update_content_filter_config(new_config.proxy.content_filter.clone());
+    crate::proxy::update_scoped_system_prompts(new_config.proxy.scoped_system_prompts.clone());
+    crate::proxy::update_fault_injection_config(new_config.proxy.fault_injection.clone());
+    crate::proxy::update_request_log_config(new_config.proxy.request_log.clone());
+    crate::proxy::mdns::refresh(&new_config.proxy);
 
     // 更新上游代理
     {
@@ -1318,15 +2067,181 @@ async fn admin_save_config(
     Ok(StatusCode::OK)
 }
 
-// [FIX Web Mode] Get proxy pool config
-async fn admin_get_proxy_pool_config(
-    State(state): State<AppState>,
+#[derive(Deserialize, Default, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+struct ExportConfigQuery {
+    #[serde(default)]
+    include_machine_specific: bool,
+    #[serde(default)]
+    include_secrets: bool,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/config/export",
+    tag = "Config",
+    summary = "Export configuration",
+    params(ExportConfigQuery)
+)]
+async fn admin_export_app_config(
+    Query(query): Query<ExportConfigQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let json = config::export_app_config(query.include_machine_specific, query.include_secrets).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+    Ok(Json(json))
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct ImportConfigRequest {
+    json: String,
+    #[serde(default)]
+    include_machine_specific: Option<bool>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/config/import",
+    tag = "Config",
+    summary = "Import configuration",
+    request_body = ImportConfigRequest
+)]
+async fn admin_import_app_config(
+    State(state): State<AppState>,
+    Json(payload): Json<ImportConfigRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let new_config = config::import_app_config(
+        &payload.json,
+        payload.include_machine_specific.unwrap_or(false),
+    )
+    .map_err(|e| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: e })))?;
+    admin_save_config(
+        State(state),
+        Json(SaveConfigWrapper {
+            config: new_config.clone(),
+        }),
+    )
+    .await?;
+    Ok(Json(new_config))
+}
+
+#[derive(Deserialize, ToSchema)]
+struct ResetConfigSectionRequest {
+    section: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/config/reset-section",
+    tag = "Config",
+    summary = "Reset a configuration section to defaults",
+    request_body = ResetConfigSectionRequest
+)]
+async fn admin_reset_config_section(
+    State(state): State<AppState>,
+    Json(payload): Json<ResetConfigSectionRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let new_config = config::reset_config_section(&payload.section)
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: e })))?;
+    admin_save_config(
+        State(state),
+        Json(SaveConfigWrapper {
+            config: new_config.clone(),
+        }),
+    )
+    .await?;
+    Ok(Json(new_config))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/config/history",
+    tag = "Config",
+    summary = "List saved configuration versions"
+)]
+async fn admin_list_config_history() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let versions = crate::modules::config_history::list().map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+    Ok(Json(versions))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct DiffConfigQuery {
+    from: u64,
+    #[serde(default)]
+    to: Option<u64>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/config/history/diff",
+    tag = "Config",
+    summary = "Diff two configuration versions",
+    params(DiffConfigQuery)
+)]
+async fn admin_diff_config_versions(
+    Query(query): Query<DiffConfigQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let changes = config::diff_config_versions(query.from, query.to)
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: e })))?;
+    Ok(Json(changes))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/config/history/{version}/rollback",
+    tag = "Config",
+    summary = "Roll back to a configuration version",
+    params(("version" = u64, Path, description = "Config history version"))
+)]
+async fn admin_rollback_config(
+    State(state): State<AppState>,
+    Path(version): Path<u64>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let new_config = config::rollback_config(version)
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: e })))?;
+    admin_save_config(
+        State(state),
+        Json(SaveConfigWrapper {
+            config: new_config.clone(),
+        }),
+    )
+    .await?;
+    crate::modules::logger::log_info(&format!("Config rolled back to version {}", version));
+    Ok(Json(new_config))
+}
+
+// [FIX Web Mode] Get proxy pool config
+#[utoipa::path(
+    get,
+    path = "/api/proxy/pool/config",
+    tag = "Proxy",
+    summary = "Get proxy pool config"
+)]
+async fn admin_get_proxy_pool_config(
+    State(state): State<AppState>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     let config = state.proxy_pool_state.read().await;
     Ok(Json(config.clone()))
 }
 
 // [FIX Web Mode] Get all account proxy bindings
+#[utoipa::path(
+    get,
+    path = "/api/proxy/pool/bindings",
+    tag = "Proxy",
+    summary = "Get all account bindings"
+)]
 async fn admin_get_all_account_bindings(
     State(state): State<AppState>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -1335,13 +2250,20 @@ async fn admin_get_all_account_bindings(
 }
 
 // [FIX Web Mode] Bind account to proxy
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct BindAccountProxyRequest {
     account_id: String,
     proxy_id: String,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/proxy/pool/bind",
+    tag = "Proxy",
+    summary = "Bind account proxy",
+    request_body = BindAccountProxyRequest
+)]
 async fn admin_bind_account_proxy(
     State(state): State<AppState>,
     Json(payload): Json<BindAccountProxyRequest>,
@@ -1354,12 +2276,19 @@ async fn admin_bind_account_proxy(
 }
 
 // [FIX Web Mode] Unbind account from proxy
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct UnbindAccountProxyRequest {
     account_id: String,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/proxy/pool/unbind",
+    tag = "Proxy",
+    summary = "Unbind account proxy",
+    request_body = UnbindAccountProxyRequest
+)]
 async fn admin_unbind_account_proxy(
     State(state): State<AppState>,
     Json(payload): Json<UnbindAccountProxyRequest>,
@@ -1369,6 +2298,13 @@ async fn admin_unbind_account_proxy(
 }
 
 // [FIX Web Mode] Get account proxy binding
+#[utoipa::path(
+    get,
+    path = "/api/proxy/pool/binding/{accountId}",
+    tag = "Proxy",
+    summary = "Get account proxy binding",
+    params(("accountId" = String, Path, description = "Account ID"))
+)]
 async fn admin_get_account_proxy_binding(
     State(state): State<AppState>,
     Path(account_id): Path<String>,
@@ -1378,6 +2314,12 @@ async fn admin_get_account_proxy_binding(
 }
 
 // [FIX Web Mode] Trigger proxy pool health check
+#[utoipa::path(
+    post,
+    path = "/api/proxy/health-check/trigger",
+    tag = "Proxy",
+    summary = "Trigger proxy health check"
+)]
 async fn admin_trigger_proxy_health_check(
     State(state): State<AppState>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -1397,6 +2339,12 @@ async fn admin_trigger_proxy_health_check(
     })))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/proxy/status",
+    tag = "Proxy",
+    summary = "Get proxy status"
+)]
 async fn admin_get_proxy_status(
     State(state): State<AppState>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -1412,11 +2360,18 @@ async fn admin_get_proxy_status(
     })))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/proxy/start",
+    tag = "Proxy",
+    summary = "Start proxy service"
+)]
 async fn admin_start_proxy_service(State(state): State<AppState>) -> impl IntoResponse {
     // 1. 持久化配置 (修复 #1166)
     if let Ok(mut config) = crate::modules::config::load_app_config() {
         config.proxy.auto_start = true;
         let _ = crate::modules::config::save_app_config(&config);
+        crate::proxy::mdns::start(&config.proxy);
     }
 
     // 2. 确保账号已加载 (如果是第一次启动)
@@ -1430,6 +2385,12 @@ async fn admin_start_proxy_service(State(state): State<AppState>) -> impl IntoRe
     StatusCode::OK
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/proxy/stop",
+    tag = "Proxy",
+    summary = "Stop proxy service"
+)]
 async fn admin_stop_proxy_service(State(state): State<AppState>) -> impl IntoResponse {
     // 1. 持久化配置 (修复 #1166)
     if let Ok(mut config) = crate::modules::config::load_app_config() {
@@ -1437,18 +2398,27 @@ async fn admin_stop_proxy_service(State(state): State<AppState>) -> impl IntoRes
         let _ = crate::modules::config::save_app_config(&config);
     }
 
+    crate::proxy::mdns::stop();
     let mut running = state.is_running.write().await;
     *running = false;
     logger::log_info("[API] 反代服务功能已禁用 (Axum 模式 / 持久化已同步)");
     StatusCode::OK
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct UpdateMappingWrapper {
+    #[schema(value_type = Object)]
     config: crate::proxy::config::ProxyConfig,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/proxy/mapping",
+    tag = "Proxy",
+    summary = "Update model mapping",
+    request_body = UpdateMappingWrapper
+)]
 async fn admin_update_model_mapping(
     State(state): State<AppState>,
     Json(payload): Json<UpdateMappingWrapper>,
@@ -1460,6 +2430,7 @@ async fn admin_update_model_mapping(
         let mut mapping = state.custom_mapping.write().await;
         *mapping = config.custom_mapping.clone();
     }
+    crate::proxy::update_fallback_chains(config.fallback_chains.clone());
 
     // 2. 持久化到硬盘 (修复 #1149)
     // 加载当前配置，更新 mapping，然后保存
@@ -1471,6 +2442,7 @@ async fn admin_update_model_mapping(
     })?;
 
     app_config.proxy.custom_mapping = config.custom_mapping;
+    app_config.proxy.fallback_chains = config.fallback_chains;
 
     crate::modules::config::save_app_config(&app_config).map_err(|e| {
         (
@@ -1483,23 +2455,48 @@ async fn admin_update_model_mapping(
     Ok(StatusCode::OK)
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/proxy/api-key/generate",
+    tag = "Proxy",
+    summary = "Generate api key"
+)]
 async fn admin_generate_api_key() -> impl IntoResponse {
     let new_key = format!("sk-{}", uuid::Uuid::new_v4().to_string().replace("-", ""));
     Json(new_key)
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/proxy/session-bindings/clear",
+    tag = "Proxy",
+    summary = "Clear proxy session bindings"
+)]
 async fn admin_clear_proxy_session_bindings(State(state): State<AppState>) -> impl IntoResponse {
     state.token_manager.clear_all_sessions();
     logger::log_info("[API] 已清除所有会话绑定");
     StatusCode::OK
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/proxy/rate-limits",
+    tag = "Proxy",
+    summary = "Clear all rate limits"
+)]
 async fn admin_clear_all_rate_limits(State(state): State<AppState>) -> impl IntoResponse {
     state.token_manager.clear_all_rate_limits();
     logger::log_info("[API] 已清除所有限流记录");
     StatusCode::OK
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/proxy/rate-limits/{accountId}",
+    tag = "Proxy",
+    summary = "Clear rate limit",
+    params(("accountId" = String, Path, description = "Account ID"))
+)]
 async fn admin_clear_rate_limit(
     State(state): State<AppState>,
     Path(account_id): Path<String>,
@@ -1513,17 +2510,60 @@ async fn admin_clear_rate_limit(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/proxy/preferred-account",
+    tag = "Proxy",
+    summary = "Get preferred account"
+)]
 async fn admin_get_preferred_account(State(state): State<AppState>) -> impl IntoResponse {
     let pref = state.token_manager.get_preferred_account().await;
     Json(pref)
 }
 
-#[derive(Deserialize)]
+#[utoipa::path(
+    get,
+    path = "/api/proxy/model-availability",
+    tag = "Proxy",
+    summary = "Available accounts per monitored model"
+)]
+async fn admin_get_model_availability(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.token_manager.model_availability().await)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/proxy/token-manager",
+    tag = "Proxy",
+    summary = "In-memory token manager state (no tokens)"
+)]
+async fn admin_get_token_manager_state(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.token_manager.state_snapshot().await)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/proxy/capacity",
+    tag = "Proxy",
+    summary = "Per-model capacity thresholds and current availability"
+)]
+async fn admin_get_capacity_status(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.token_manager.capacity_status().await)
+}
+
+#[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct SetPreferredAccountRequest {
     account_id: Option<String>,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/proxy/preferred-account",
+    tag = "Proxy",
+    summary = "Set preferred account",
+    request_body = SetPreferredAccountRequest
+)]
 async fn admin_set_preferred_account(
     State(state): State<AppState>,
     Json(payload): Json<SetPreferredAccountRequest>,
@@ -1535,6 +2575,13 @@ async fn admin_set_preferred_account(
     StatusCode::OK
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/zai/models/fetch",
+    tag = "Proxy",
+    summary = "Fetch zai models",
+    request_body = serde_json::Value
+)]
 async fn admin_fetch_zai_models(
     Path(_id): Path<String>,
     Json(payload): Json<serde_json::Value>, // 复用前端传来的参数
@@ -1602,6 +2649,13 @@ async fn admin_fetch_zai_models(
     Ok(Json(models))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/proxy/monitor/toggle",
+    tag = "Proxy",
+    summary = "Set proxy monitor enabled",
+    request_body = serde_json::Value
+)]
 async fn admin_set_proxy_monitor_enabled(
     State(state): State<AppState>,
     Json(payload): Json<serde_json::Value>,
@@ -1620,6 +2674,13 @@ async fn admin_set_proxy_monitor_enabled(
     StatusCode::OK
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/logs/count",
+    tag = "Logs",
+    summary = "Get proxy logs count filtered",
+    params(LogsRequest)
+)]
 async fn admin_get_proxy_logs_count_filtered(
     Query(params): Query<LogsRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -1643,6 +2704,12 @@ async fn admin_get_proxy_logs_count_filtered(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/logs/clear",
+    tag = "Logs",
+    summary = "Clear proxy logs"
+)]
 async fn admin_clear_proxy_logs() -> impl IntoResponse {
     let _ = tokio::task::spawn_blocking(|| {
         if let Err(e) = proxy_db::clear_logs() {
@@ -1654,6 +2721,70 @@ async fn admin_clear_proxy_logs() -> impl IntoResponse {
     StatusCode::OK
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/logs/compact",
+    tag = "Logs",
+    summary = "Apply log retention now and vacuum the log database"
+)]
+async fn admin_compact_proxy_logs() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let result = crate::commands::proxy::compact_proxy_logs().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+    Ok(Json(result))
+}
+
+#[derive(Deserialize, Default, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct ReplayRequestBody {
+    target_account: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/logs/{logId}/replay",
+    tag = "Logs",
+    summary = "Replay a logged request through the proxy (optionally pinned to another account) and diff the responses",
+    params(("logId" = String, Path, description = "Log ID")),
+    request_body = ReplayRequestBody
+)]
+async fn admin_replay_request(
+    State(state): State<AppState>,
+    Path(log_id): Path<String>,
+    payload: Option<Json<ReplayRequestBody>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+    let api_key = state.security.read().await.api_key.clone();
+    let result = crate::proxy::replay::replay(
+        state.port,
+        &api_key,
+        &log_id,
+        payload.target_account.as_deref(),
+    )
+    .await
+    .map_err(|e| {
+        let code = if e.starts_with("log_not_found") || e.starts_with("account_not_found") {
+            StatusCode::NOT_FOUND
+        } else if e.starts_with("replay_unavailable") {
+            StatusCode::BAD_REQUEST
+        } else {
+            StatusCode::INTERNAL_SERVER_ERROR
+        };
+        (code, Json(ErrorResponse { error: e }))
+    })?;
+    Ok(Json(result))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/logs/{logId}",
+    tag = "Logs",
+    summary = "Get proxy log detail",
+    params(("logId" = String, Path, description = "Log ID"))
+)]
 async fn admin_get_proxy_log_detail(
     Path(log_id): Path<String>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -1676,8 +2807,9 @@ async fn admin_get_proxy_log_detail(
     }
 }
 
-#[derive(Deserialize, Debug, Default)]
+#[derive(Deserialize, Debug, Default, IntoParams)]
 #[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
 struct LogsFilterQuery {
     #[serde(default)]
     filter: String,
@@ -1689,6 +2821,13 @@ struct LogsFilterQuery {
     offset: usize,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/logs",
+    tag = "Logs",
+    summary = "Get proxy logs filtered",
+    params(LogsFilterQuery)
+)]
 async fn admin_get_proxy_logs_filtered(
     Query(params): Query<LogsFilterQuery>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -1717,6 +2856,12 @@ async fn admin_get_proxy_logs_filtered(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/proxy/stats",
+    tag = "Proxy",
+    summary = "Get proxy stats"
+)]
 async fn admin_get_proxy_stats(
     State(state): State<AppState>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -1724,6 +2869,84 @@ async fn admin_get_proxy_stats(
     Ok(Json(stats))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/proxy/admission",
+    tag = "Proxy",
+    summary = "Get admission control counters per API key priority"
+)]
+async fn admin_get_admission_stats(State(state): State<AppState>) -> impl IntoResponse {
+    let eligible = state.token_manager.eligible_account_count().await;
+    Json(crate::proxy::admission::stats(eligible))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/proxy/sessions",
+    tag = "Proxy",
+    summary = "List per-account upstream session IDs"
+)]
+async fn admin_list_upstream_sessions() -> impl IntoResponse {
+    Json(crate::proxy::session_pool::list())
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct RotateSessionsRequest {
+    account_id: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/proxy/sessions/rotate",
+    tag = "Proxy",
+    summary = "Rotate upstream session IDs (one account or all)",
+    request_body = RotateSessionsRequest
+)]
+async fn admin_rotate_upstream_sessions(
+    Json(payload): Json<RotateSessionsRequest>,
+) -> impl IntoResponse {
+    let rotated = crate::proxy::session_pool::rotate(payload.account_id.as_deref());
+    Json(serde_json::json!({ "rotated": rotated }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/proxy/circuits",
+    tag = "Proxy",
+    summary = "Circuit breaker state of upstream endpoints"
+)]
+async fn admin_get_upstream_circuits() -> impl IntoResponse {
+    Json(crate::proxy::upstream::circuit_breaker::snapshot())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/proxy/content-filter/stats",
+    tag = "Proxy",
+    summary = "Per-rule content filter hit counters"
+)]
+async fn admin_get_content_filter_stats() -> impl IntoResponse {
+    Json(crate::proxy::content_filter::stats())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/proxy/content-filter/stats/reset",
+    tag = "Proxy",
+    summary = "Reset content filter hit counters"
+)]
+async fn admin_reset_content_filter_stats() -> impl IntoResponse {
+    crate::proxy::content_filter::reset_stats();
+    StatusCode::OK
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/system/data-dir",
+    tag = "System",
+    summary = "Get data dir path"
+)]
 async fn admin_get_data_dir_path() -> impl IntoResponse {
     match crate::modules::account::get_data_dir() {
         Ok(p) => Json(p.to_string_lossy().to_string()),
@@ -1731,8 +2954,75 @@ async fn admin_get_data_dir_path() -> impl IntoResponse {
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/system/startup-report",
+    tag = "System",
+    summary = "Get the startup self-test report"
+)]
+async fn admin_get_startup_report() -> impl IntoResponse {
+    Json(crate::modules::startup_report::current())
+}
+
+#[derive(Deserialize, Default, IntoParams)]
+#[serde(rename_all = "camelCase", default)]
+#[into_params(parameter_in = Query)]
+struct ListNotificationsQuery {
+    unread_only: bool,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/notifications",
+    tag = "System",
+    summary = "List notification center entries (newest first, optionally unread only)",
+    params(ListNotificationsQuery)
+)]
+async fn admin_list_notifications(
+    Query(query): Query<ListNotificationsQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let notifications = crate::modules::notify::list(query.unread_only).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+    Ok(Json(notifications))
+}
+
+#[derive(Deserialize, Default, ToSchema)]
+#[serde(default)]
+struct MarkNotificationsReadRequest {
+    ids: Option<Vec<String>>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/notifications/read",
+    tag = "System",
+    summary = "Mark notifications as read (all when no ids are given)",
+    request_body = MarkNotificationsReadRequest
+)]
+async fn admin_mark_notifications_read(
+    Json(payload): Json<MarkNotificationsReadRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let unread = crate::modules::notify::mark_read(payload.ids.as_deref()).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+    Ok(Json(unread))
+}
+
 // --- User Token Handlers ---
 
+#[utoipa::path(
+    get,
+    path = "/api/user-tokens",
+    tag = "User Tokens",
+    summary = "List user tokens"
+)]
 async fn admin_list_user_tokens() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     let tokens = crate::commands::user_token::list_user_tokens().await.map_err(|e| {
         (
@@ -1743,6 +3033,12 @@ async fn admin_list_user_tokens() -> Result<impl IntoResponse, (StatusCode, Json
     Ok(Json(tokens))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/user-tokens/summary",
+    tag = "User Tokens",
+    summary = "Get user token summary"
+)]
 async fn admin_get_user_token_summary() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     let summary = crate::commands::user_token::get_user_token_summary().await.map_err(|e| {
         (
@@ -1753,6 +3049,79 @@ async fn admin_get_user_token_summary() -> Result<impl IntoResponse, (StatusCode
     Ok(Json(summary))
 }
 
+#[derive(Deserialize, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct BillingQuery {
+    from: Option<String>,
+    to: Option<String>,
+    format: Option<String>,
+}
+
+fn billing_error(e: String) -> (StatusCode, Json<ErrorResponse>) {
+    let status = if e.starts_with("invalid_billing_month") || e.starts_with("unsupported_billing_format") {
+        StatusCode::BAD_REQUEST
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+    (status, Json(ErrorResponse { error: e }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/user-tokens/billing",
+    tag = "User Tokens",
+    summary = "Per-key monthly usage and cost report",
+    params(BillingQuery)
+)]
+async fn admin_get_billing_report(
+    Query(q): Query<BillingQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let report = crate::commands::user_token::get_billing_report(q.from, q.to)
+        .await
+        .map_err(billing_error)?;
+    Ok(Json(report))
+}
+
+/// 以附件形式下载计费报表 (CSV / JSON)
+#[utoipa::path(
+    get,
+    path = "/api/user-tokens/billing/export",
+    tag = "User Tokens",
+    summary = "Download the billing report as CSV or JSON",
+    params(BillingQuery)
+)]
+async fn admin_export_billing_report(
+    Query(q): Query<BillingQuery>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let format = crate::modules::billing::ExportFormat::parse(q.format.as_deref()).map_err(billing_error)?;
+    let body = crate::commands::user_token::export_billing_report(q.from, q.to, q.format)
+        .await
+        .map_err(billing_error)?;
+    let (content_type, ext) = match format {
+        crate::modules::billing::ExportFormat::Csv => ("text/csv; charset=utf-8", "csv"),
+        crate::modules::billing::ExportFormat::Json => ("application/json", "json"),
+    };
+    let file_name = format!("billing-{}.{}", chrono::Local::now().format("%Y%m%d-%H%M%S"), ext);
+    Ok((
+        [
+            (axum::http::header::CONTENT_TYPE, content_type.to_string()),
+            (
+                axum::http::header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", file_name),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/user-tokens",
+    tag = "User Tokens",
+    summary = "Create user token",
+    request_body = crate::commands::user_token::CreateTokenRequest
+)]
 async fn admin_create_user_token(
     Json(payload): Json<crate::commands::user_token::CreateTokenRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -1765,12 +3134,20 @@ async fn admin_create_user_token(
     Ok(Json(token))
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct RenewTokenRequest {
     expires_type: String,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/user-tokens/{id}/renew",
+    tag = "User Tokens",
+    summary = "Renew user token",
+    params(("id" = String, Path, description = "User token ID")),
+    request_body = RenewTokenRequest
+)]
 async fn admin_renew_user_token(
     Path(id): Path<String>,
     Json(payload): Json<RenewTokenRequest>,
@@ -1784,6 +3161,13 @@ async fn admin_renew_user_token(
     Ok(StatusCode::OK)
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/user-tokens/{id}",
+    tag = "User Tokens",
+    summary = "Delete user token",
+    params(("id" = String, Path, description = "User token ID"))
+)]
 async fn admin_delete_user_token(
     Path(id): Path<String>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -1796,6 +3180,14 @@ async fn admin_delete_user_token(
     Ok(StatusCode::NO_CONTENT)
 }
 
+#[utoipa::path(
+    patch,
+    path = "/api/user-tokens/{id}",
+    tag = "User Tokens",
+    summary = "Update user token",
+    params(("id" = String, Path, description = "User token ID")),
+    request_body = crate::commands::user_token::UpdateTokenRequest
+)]
 async fn admin_update_user_token(
     Path(id): Path<String>,
     Json(payload): Json<crate::commands::user_token::UpdateTokenRequest>,
@@ -1809,6 +3201,12 @@ async fn admin_update_user_token(
     Ok(StatusCode::OK)
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/system/updates/check-status",
+    tag = "System",
+    summary = "Should check updates"
+)]
 async fn admin_should_check_updates() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)>
 {
     let settings = crate::modules::update_checker::load_update_settings().map_err(|e| {
@@ -1821,6 +3219,12 @@ async fn admin_should_check_updates() -> Result<impl IntoResponse, (StatusCode,
     Ok(Json(should))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/system/antigravity/path",
+    tag = "System",
+    summary = "Get antigravity path"
+)]
 async fn admin_get_antigravity_path() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)>
 {
     let path = crate::commands::get_antigravity_path(Some(true))
@@ -1834,6 +3238,31 @@ async fn admin_get_antigravity_path() -> Result<impl IntoResponse, (StatusCode,
     Ok(Json(path))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/system/antigravity/installations",
+    tag = "System",
+    summary = "Detect antigravity installations"
+)]
+async fn admin_detect_antigravity_installations(
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let installations = crate::commands::detect_antigravity_installations()
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse { error: e }),
+            )
+        })?;
+    Ok(Json(installations))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/system/antigravity/args",
+    tag = "System",
+    summary = "Get antigravity args"
+)]
 async fn admin_get_antigravity_args() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)>
 {
     let args = crate::commands::get_antigravity_args().await.map_err(|e| {
@@ -1845,6 +3274,12 @@ async fn admin_get_antigravity_args() -> Result<impl IntoResponse, (StatusCode,
     Ok(Json(args))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/system/cache/clear",
+    tag = "System",
+    summary = "Clear antigravity cache"
+)]
 async fn admin_clear_antigravity_cache(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     let res = crate::commands::clear_antigravity_cache().await.map_err(|e| {
@@ -1856,6 +3291,12 @@ async fn admin_clear_antigravity_cache(
     Ok(Json(res))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/system/cache/paths",
+    tag = "System",
+    summary = "Get antigravity cache paths"
+)]
 async fn admin_get_antigravity_cache_paths(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     let res = crate::commands::get_antigravity_cache_paths()
@@ -1869,6 +3310,12 @@ async fn admin_get_antigravity_cache_paths(
     Ok(Json(res))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/system/logs/clear-cache",
+    tag = "System",
+    summary = "Clear log cache"
+)]
 async fn admin_clear_log_cache() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     crate::commands::clear_log_cache().await.map_err(|e| {
         (
@@ -1879,15 +3326,350 @@ async fn admin_clear_log_cache() -> Result<impl IntoResponse, (StatusCode, Json<
     Ok(StatusCode::OK)
 }
 
+#[derive(Deserialize, ToSchema)]
+struct QueryLogsRequest {
+    #[serde(default)]
+    #[schema(value_type = Object)]
+    query: crate::modules::log_store::LogQuery,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/system/logs/query",
+    tag = "Logs",
+    summary = "Query structured application logs",
+    request_body = QueryLogsRequest
+)]
+async fn admin_query_logs(
+    Json(payload): Json<QueryLogsRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let result = crate::commands::query_logs(payload.query).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+    Ok(Json(result))
+}
+
+#[derive(Deserialize, Default, ToSchema)]
+struct ExportLogsRequest {
+    #[serde(default)]
+    #[schema(value_type = Object)]
+    range: crate::modules::logger::LogRange,
+}
+
+/// 在临时目录生成 zip 并读回内容 (Web 模式下服务器上的文件路径对浏览器无意义)
+async fn build_zip_download<F>(
+    file_prefix: &'static str,
+    build: F,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)>
+where
+    F: FnOnce(std::path::PathBuf) -> Result<std::path::PathBuf, String> + Send + 'static,
+{
+    let tmp_path = std::env::temp_dir().join(format!("abv-{}-{}.zip", file_prefix, uuid::Uuid::new_v4()));
+
+    let result = tokio::task::spawn_blocking(move || {
+        let path = build(tmp_path)?;
+        let bytes = std::fs::read(&path).map_err(|e| format!("Failed to read zip file: {}", e));
+        let _ = std::fs::remove_file(&path);
+        bytes
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|r| r);
+
+    let bytes = result.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+    let file_name = format!("{}-{}.zip", file_prefix, chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    Ok((
+        [
+            (axum::http::header::CONTENT_TYPE, "application/zip".to_string()),
+            (
+                axum::http::header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", file_name),
+            ),
+        ],
+        bytes,
+    )
+        .into_response())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/system/logs/export",
+    tag = "Logs",
+    summary = "Export log files as zip",
+    request_body = ExportLogsRequest
+)]
+async fn admin_export_logs(
+    payload: Option<Json<ExportLogsRequest>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let range = payload.map(|Json(p)| p.range).unwrap_or_default();
+    build_zip_download("logs", move |path| logger::export_logs(&range, Some(path))).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/system/integrity",
+    tag = "System",
+    summary = "Verify account data integrity"
+)]
+async fn admin_verify_data_integrity() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let report = crate::commands::verify_data_integrity().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+    Ok(Json(report))
+}
+
+#[derive(Deserialize, ToSchema)]
+struct ApplyIntegrityRepairRequest {
+    #[schema(value_type = Vec<Object>)]
+    plan: Vec<crate::modules::account::RepairAction>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/system/integrity/repair",
+    tag = "System",
+    summary = "Apply integrity repair plan",
+    request_body = ApplyIntegrityRepairRequest
+)]
+async fn admin_apply_integrity_repair(
+    Json(payload): Json<ApplyIntegrityRepairRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let report = crate::commands::apply_integrity_repair(payload.plan)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse { error: e }),
+            )
+        })?;
+    Ok(Json(report))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/system/network",
+    tag = "System",
+    summary = "Get network / offline mode status"
+)]
+async fn admin_get_network_status() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let status = crate::commands::get_network_status().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+    Ok(Json(status))
+}
+
+#[derive(Deserialize, ToSchema)]
+struct SetOfflineModeRequest {
+    enabled: bool,
+}
+
+#[derive(Deserialize, ToSchema)]
+struct SetSchedulerJobEnabledRequest {
+    enabled: bool,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/system/clock-skew",
+    tag = "System",
+    summary = "Estimated offset between the local clock and upstream (from Date headers)"
+)]
+async fn admin_get_clock_skew() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let status = crate::commands::get_clock_skew().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+    Ok(Json(status))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/system/scheduler",
+    tag = "System",
+    summary = "Background job status (enabled, next check, last run result)"
+)]
+async fn admin_scheduler_status() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let status = crate::commands::scheduler_status().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+    Ok(Json(status))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/system/scheduler/jobs/{jobId}",
+    tag = "System",
+    summary = "Enable or disable a background job",
+    params(("jobId" = String, Path, description = "Background job ID")),
+    request_body = SetSchedulerJobEnabledRequest
+)]
+async fn admin_set_scheduler_job_enabled(
+    Path(job_id): Path<String>,
+    Json(payload): Json<SetSchedulerJobEnabledRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let status = crate::commands::set_scheduler_job_enabled(job_id, payload.enabled)
+        .await
+        .map_err(|e| {
+            let code = if e.starts_with("unknown_job") {
+                StatusCode::NOT_FOUND
+            } else {
+                StatusCode::INTERNAL_SERVER_ERROR
+            };
+            (code, Json(ErrorResponse { error: e }))
+        })?;
+    Ok(Json(status))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/system/offline",
+    tag = "System",
+    summary = "Toggle manual offline mode",
+    request_body = SetOfflineModeRequest
+)]
+async fn admin_set_offline_mode(
+    Json(payload): Json<SetOfflineModeRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let status = crate::commands::set_offline_mode(payload.enabled)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse { error: e }),
+            )
+        })?;
+    Ok(Json(status))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/onboarding",
+    tag = "System",
+    summary = "Get first-run onboarding state"
+)]
+async fn admin_get_onboarding_state() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let state = crate::commands::get_onboarding_state().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+    Ok(Json(state))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/onboarding/steps/{step}",
+    tag = "System",
+    summary = "Complete or skip an onboarding step",
+    params(("step" = String, Path, description = "Onboarding step")),
+    request_body = crate::modules::onboarding::OnboardingStepInput
+)]
+async fn admin_complete_onboarding_step(
+    Path(step): Path<crate::models::OnboardingStep>,
+    Json(payload): Json<crate::modules::onboarding::OnboardingStepInput>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let state = tokio::task::spawn_blocking(move || {
+        crate::modules::onboarding::complete_onboarding_step(step, payload)
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|r| r)
+    .map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+    Ok(Json(state))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/system/languages",
+    tag = "System",
+    summary = "List available UI languages (built-in and community)"
+)]
+async fn admin_list_available_languages() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let languages = crate::commands::list_available_languages().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+    Ok(Json(languages))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/system/languages/{language}",
+    tag = "System",
+    summary = "Get merged locale messages for a language",
+    params(("language" = String, Path, description = "Language code"))
+)]
+async fn admin_get_locale_messages(
+    Path(language): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let messages = crate::commands::get_locale_messages(language)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse { error: e }),
+            )
+        })?;
+    Ok(Json(messages))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/system/diagnostics",
+    tag = "System",
+    summary = "Download redacted diagnostics bundle"
+)]
+async fn admin_generate_diagnostics_bundle() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    build_zip_download("diagnostics", |path| {
+        crate::modules::diagnostics::generate_diagnostics_bundle(Some(path))
+    })
+    .await
+}
+
 // Token Stats Handlers
-#[derive(Deserialize, Debug, Default)]
+#[derive(Deserialize, Debug, Default, IntoParams)]
 #[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
 struct StatsPeriodQuery {
     hours: Option<i64>,
     days: Option<i64>,
     weeks: Option<i64>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/stats/token/hourly",
+    tag = "Stats",
+    summary = "Get token stats hourly",
+    params(StatsPeriodQuery)
+)]
 async fn admin_get_token_stats_hourly(
     Query(p): Query<StatsPeriodQuery>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -1909,6 +3691,13 @@ async fn admin_get_token_stats_hourly(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/stats/token/daily",
+    tag = "Stats",
+    summary = "Get token stats daily",
+    params(StatsPeriodQuery)
+)]
 async fn admin_get_token_stats_daily(
     Query(p): Query<StatsPeriodQuery>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -1930,6 +3719,13 @@ async fn admin_get_token_stats_daily(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/stats/token/weekly",
+    tag = "Stats",
+    summary = "Get token stats weekly",
+    params(StatsPeriodQuery)
+)]
 async fn admin_get_token_stats_weekly(
     Query(p): Query<StatsPeriodQuery>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -1951,6 +3747,13 @@ async fn admin_get_token_stats_weekly(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/stats/token/by-account",
+    tag = "Stats",
+    summary = "Get token stats by account",
+    params(StatsPeriodQuery)
+)]
 async fn admin_get_token_stats_by_account(
     Query(p): Query<StatsPeriodQuery>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -1972,19 +3775,47 @@ async fn admin_get_token_stats_by_account(
     }
 }
 
-async fn admin_get_token_stats_summary(
+#[utoipa::path(
+    get,
+    path = "/api/stats/latency/by-account",
+    tag = "Stats",
+    summary = "Get upstream latency percentiles by account",
+    params(StatsPeriodQuery)
+)]
+async fn admin_get_account_latency_stats(
     Query(p): Query<StatsPeriodQuery>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
-    let hours = p.hours.unwrap_or(168);
-    let res = tokio::task::spawn_blocking(move || token_stats::get_summary_stats(hours)).await;
-
-    match res {
-        Ok(Ok(stats)) => Ok(Json(stats)),
-        Ok(Err(e)) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse { error: e }),
-        )),
-        Err(e) => Err((
+    let stats = crate::commands::get_account_latency_stats(p.hours)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse { error: e }),
+            )
+        })?;
+    Ok(Json(stats))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/stats/token/summary",
+    tag = "Stats",
+    summary = "Get token stats summary",
+    params(StatsPeriodQuery)
+)]
+async fn admin_get_token_stats_summary(
+    Query(p): Query<StatsPeriodQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let hours = p.hours.unwrap_or(168);
+    let res = tokio::task::spawn_blocking(move || token_stats::get_summary_stats(hours)).await;
+
+    match res {
+        Ok(Ok(stats)) => Ok(Json(stats)),
+        Ok(Err(e)) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )),
+        Err(e) => Err((
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse {
                 error: e.to_string(),
@@ -1993,6 +3824,13 @@ async fn admin_get_token_stats_summary(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/stats/token/by-model",
+    tag = "Stats",
+    summary = "Get token stats by model",
+    params(StatsPeriodQuery)
+)]
 async fn admin_get_token_stats_by_model(
     Query(p): Query<StatsPeriodQuery>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -2014,6 +3852,12 @@ async fn admin_get_token_stats_by_model(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/stats/token/model-trend/hourly",
+    tag = "Stats",
+    summary = "Get token stats model trend hourly"
+)]
 async fn admin_get_token_stats_model_trend_hourly(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     let res = tokio::task::spawn_blocking(|| {
@@ -2036,6 +3880,12 @@ async fn admin_get_token_stats_model_trend_hourly(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/stats/token/model-trend/daily",
+    tag = "Stats",
+    summary = "Get token stats model trend daily"
+)]
 async fn admin_get_token_stats_model_trend_daily(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     let res = tokio::task::spawn_blocking(|| {
@@ -2058,6 +3908,12 @@ async fn admin_get_token_stats_model_trend_daily(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/stats/token/account-trend/hourly",
+    tag = "Stats",
+    summary = "Get token stats account trend hourly"
+)]
 async fn admin_get_token_stats_account_trend_hourly(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     let res = tokio::task::spawn_blocking(|| {
@@ -2080,6 +3936,12 @@ async fn admin_get_token_stats_account_trend_hourly(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/stats/token/account-trend/daily",
+    tag = "Stats",
+    summary = "Get token stats account trend daily"
+)]
 async fn admin_get_token_stats_account_trend_daily(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     let res = tokio::task::spawn_blocking(|| {
@@ -2102,6 +3964,12 @@ async fn admin_get_token_stats_account_trend_daily(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/stats/token/clear",
+    tag = "Stats",
+    summary = "Clear token stats"
+)]
 async fn admin_clear_token_stats() -> impl IntoResponse {
     let res = tokio::task::spawn_blocking(|| {
         // Clear databases (brute force)
@@ -2124,20 +3992,555 @@ async fn admin_clear_token_stats() -> impl IntoResponse {
     }
 }
 
-async fn admin_get_update_settings() -> impl IntoResponse {
-    // 從真實模組加載設置
-    match crate::modules::update_checker::load_update_settings() {
-        Ok(s) => Json(serde_json::to_value(s).unwrap_or_default()),
-        Err(_) => Json(serde_json::json!({
-            "auto_check": true,
-            "last_check_time": 0,
-            "check_interval_hours": 24
-        })),
-    }
+#[utoipa::path(
+    get,
+    path = "/api/system/updates/settings",
+    tag = "System",
+    summary = "Get update settings"
+)]
+async fn admin_get_update_settings() -> impl IntoResponse {
+    // 從真實模組加載設置
+    match crate::modules::update_checker::load_update_settings() {
+        Ok(s) => Json(serde_json::to_value(s).unwrap_or_default()),
+        Err(_) => Json(serde_json::json!({
+            "auto_check": true,
+            "last_check_time": 0,
+            "check_interval_hours": 24
+        })),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/system/updates/check",
+    tag = "System",
+    summary = "Check for updates"
+)]
+async fn admin_check_for_updates() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let info = crate::modules::update_checker::check_for_updates()
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse { error: e }),
+            )
+        })?;
+    Ok(Json(info))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/system/updates/touch",
+    tag = "System",
+    summary = "Update last check time"
+)]
+async fn admin_update_last_check_time(
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    crate::modules::update_checker::update_last_check_time().map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+    Ok(StatusCode::OK)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/system/updates/save",
+    tag = "System",
+    summary = "Save update settings",
+    request_body = serde_json::Value
+)]
+async fn admin_save_update_settings(Json(settings): Json<serde_json::Value>) -> impl IntoResponse {
+    if let Ok(s) =
+        serde_json::from_value::<crate::modules::update_checker::UpdateSettings>(settings)
+    {
+        let _ = crate::modules::update_checker::save_update_settings(&s);
+        StatusCode::OK
+    } else {
+        StatusCode::BAD_REQUEST
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/system/autostart/status",
+    tag = "System",
+    summary = "Is auto launch enabled"
+)]
+async fn admin_is_auto_launch_enabled() -> impl IntoResponse {
+    // Note: Autostart requires tauri::AppHandle, which is not available in Axum State easily.
+    // For now, return false in Web mode.
+    Json(false)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/system/autostart/toggle",
+    tag = "System",
+    summary = "Toggle auto launch",
+    request_body = serde_json::Value
+)]
+async fn admin_toggle_auto_launch(Json(_payload): Json<serde_json::Value>) -> impl IntoResponse {
+    // Note: Autostart requires tauri::AppHandle.
+    StatusCode::NOT_IMPLEMENTED
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/system/http-api/settings",
+    tag = "System",
+    summary = "Get http api settings"
+)]
+async fn admin_get_http_api_settings() -> impl IntoResponse {
+    Json(serde_json::json!({ "enabled": true, "port": 8045 }))
+}
+
+// [整合清理] 冗餘導入已移除
+
+#[derive(Deserialize, ToSchema)]
+struct BulkDeleteRequest {
+    #[serde(rename = "accountIds")]
+    account_ids: Vec<String>,
+    #[serde(default)]
+    confirmed: bool,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/accounts/bulk-delete",
+    tag = "Accounts",
+    summary = "Delete accounts",
+    request_body = BulkDeleteRequest
+)]
+async fn admin_delete_accounts(
+    Json(payload): Json<BulkDeleteRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    crate::modules::account::delete_accounts(&payload.account_ids, payload.confirmed)
+        .map_err(delete_error)?;
+    Ok(StatusCode::OK)
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct ReorderRequest {
+    account_ids: Vec<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/accounts/reorder",
+    tag = "Accounts",
+    summary = "Reorder accounts",
+    request_body = ReorderRequest
+)]
+async fn admin_reorder_accounts(
+    State(state): State<AppState>,
+    Json(payload): Json<ReorderRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    crate::modules::account::reorder_accounts(&payload.account_ids).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+
+    // [FIX #1166] 排序变动后立即重新加载 TokenManager
+    if let Err(e) = state.token_manager.load_accounts().await {
+        logger::log_error(&format!(
+            "[API] Failed to reload accounts after reorder: {}",
+            e
+        ));
+    }
+
+    Ok(StatusCode::OK)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{accountId}/quota",
+    tag = "Accounts",
+    summary = "Fetch account quota",
+    params(("accountId" = String, Path, description = "Account ID"))
+)]
+async fn admin_fetch_account_quota(
+    Path(account_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let mut account = crate::modules::load_account(&account_id).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+
+    let quota = crate::modules::account::fetch_quota_with_retry(&mut account)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+        })?;
+
+    crate::modules::update_account_quota(&account_id, quota.clone()).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+
+    Ok(Json(quota))
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct ToggleProxyRequest {
+    enable: bool,
+    reason: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/accounts/{accountId}/toggle-proxy",
+    tag = "Accounts",
+    summary = "Toggle proxy status",
+    params(("accountId" = String, Path, description = "Account ID")),
+    request_body = ToggleProxyRequest
+)]
+async fn admin_toggle_proxy_status(
+    State(state): State<AppState>,
+    Path(account_id): Path<String>,
+    Json(payload): Json<ToggleProxyRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    crate::modules::account::toggle_proxy_status(
+        &account_id,
+        payload.enable,
+        payload.reason.as_deref(),
+    )
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+
+    // 同步到运行中的反代服务
+    let _ = state.token_manager.reload_account(&account_id).await;
+
+    Ok(StatusCode::OK)
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct BulkToggleProxyRequest {
+    account_ids: Vec<String>,
+    enable: bool,
+    reason: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/accounts/bulk-toggle-proxy",
+    tag = "Accounts",
+    summary = "Enable / disable proxying for many accounts at once",
+    request_body = BulkToggleProxyRequest
+)]
+async fn admin_set_proxy_status_bulk(
+    State(state): State<AppState>,
+    Json(payload): Json<BulkToggleProxyRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let result = crate::modules::account::set_proxy_status_bulk(
+        &payload.account_ids,
+        payload.enable,
+        payload.reason.as_deref(),
+    )
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+
+    // 同步到运行中的反代服务
+    if !result.updated.is_empty() {
+        let _ = state.token_manager.reload_all_accounts().await;
+    }
+
+    Ok(Json(result))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/accounts/warmup",
+    tag = "Accounts",
+    summary = "Warm up all accounts"
+)]
+async fn admin_warm_up_all_accounts() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)>
+{
+    let result = crate::commands::warm_up_all_accounts().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+    Ok(Json(result))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/accounts/{accountId}/warmup",
+    tag = "Accounts",
+    summary = "Warm up account",
+    params(("accountId" = String, Path, description = "Account ID"))
+)]
+async fn admin_warm_up_account(
+    Path(account_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let result = crate::commands::warm_up_account(account_id)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse { error: e }),
+            )
+        })?;
+    Ok(Json(result))
+}
+
+#[derive(Deserialize, ToSchema)]
+struct TestAccountRequest {
+    model: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/accounts/{accountId}/test-request",
+    tag = "Accounts",
+    summary = "Send a dry-run request pinned to the account",
+    params(("accountId" = String, Path, description = "Account ID")),
+    request_body = TestAccountRequest
+)]
+async fn admin_test_account_request(
+    State(state): State<AppState>,
+    Path(account_id): Path<String>,
+    Json(payload): Json<TestAccountRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let api_key = state.security.read().await.api_key.clone();
+    let result = crate::proxy::dry_run::run(state.port, &api_key, &account_id, &payload.model)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse { error: e }),
+            )
+        })?;
+    Ok(Json(result))
+}
+
+#[derive(Deserialize, ToSchema)]
+struct UpdateAccountTagsRequest {
+    tags: Vec<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/accounts/{accountId}/tags",
+    tag = "Accounts",
+    summary = "Update account group tags",
+    params(("accountId" = String, Path, description = "Account ID")),
+    request_body = UpdateAccountTagsRequest
+)]
+async fn admin_update_account_tags(
+    Path(account_id): Path<String>,
+    Json(payload): Json<UpdateAccountTagsRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let tags = crate::commands::update_account_tags(account_id, payload.tags)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse { error: e }),
+            )
+        })?;
+    Ok(Json(tags))
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct UpdateAccountIpFamilyRequest {
+    ip_family: Option<crate::models::IpFamily>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/accounts/{accountId}/ip-family",
+    tag = "Accounts",
+    summary = "Set account outbound IP family",
+    params(("accountId" = String, Path, description = "Account ID")),
+    request_body = UpdateAccountIpFamilyRequest
+)]
+async fn admin_update_account_ip_family(
+    Path(account_id): Path<String>,
+    Json(payload): Json<UpdateAccountIpFamilyRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    crate::commands::update_account_ip_family(account_id, payload.ip_family)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse { error: e }),
+            )
+        })?;
+    Ok(StatusCode::OK)
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct UpdateAccountAntigravityArgsRequest {
+    antigravity_args: Option<Vec<String>>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/accounts/{accountId}/antigravity-args",
+    tag = "Accounts",
+    summary = "Set per-account Antigravity launch arguments",
+    params(("accountId" = String, Path, description = "Account ID")),
+    request_body = UpdateAccountAntigravityArgsRequest
+)]
+async fn admin_update_account_antigravity_args(
+    Path(account_id): Path<String>,
+    Json(payload): Json<UpdateAccountAntigravityArgsRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    crate::commands::update_account_antigravity_args(account_id, payload.antigravity_args)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse { error: e }),
+            )
+        })?;
+    Ok(StatusCode::OK)
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct UpdateAccountAntigravityExecutableRequest {
+    antigravity_executable: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/accounts/{accountId}/antigravity-executable",
+    tag = "Accounts",
+    summary = "Select the Antigravity installation an account switches",
+    params(("accountId" = String, Path, description = "Account ID")),
+    request_body = UpdateAccountAntigravityExecutableRequest
+)]
+async fn admin_update_account_antigravity_executable(
+    Path(account_id): Path<String>,
+    Json(payload): Json<UpdateAccountAntigravityExecutableRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    crate::commands::update_account_antigravity_executable(
+        account_id,
+        payload.antigravity_executable,
+    )
+    .await
+    .map_err(|e| {
+        let status = if e.starts_with("antigravity_executable_not_found")
+            || e.starts_with("antigravity_executable_invalid")
+        {
+            StatusCode::BAD_REQUEST
+        } else {
+            StatusCode::INTERNAL_SERVER_ERROR
+        };
+        (status, Json(ErrorResponse { error: e }))
+    })?;
+    Ok(StatusCode::OK)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/models/known",
+    tag = "Proxy",
+    summary = "List known model IDs (built-in + upstream catalog)"
+)]
+async fn admin_list_known_models() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let models = crate::commands::list_known_models().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+    Ok(Json(models))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/models/catalog/sync",
+    tag = "Proxy",
+    summary = "Sync model catalog from upstream"
+)]
+async fn admin_sync_model_catalog() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let result = crate::commands::sync_model_catalog().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+    Ok(Json(result))
+}
+
+#[derive(Deserialize, Default, IntoParams)]
+#[serde(rename_all = "camelCase", default)]
+#[into_params(parameter_in = Query)]
+struct ProjectBindingQuery {
+    account_id: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/accounts/project-bindings",
+    tag = "Accounts",
+    summary = "Google project_id binding history per account",
+    params(ProjectBindingQuery)
+)]
+async fn admin_get_project_binding_history(
+    Query(q): Query<ProjectBindingQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let histories = crate::commands::get_project_binding_history(q.account_id)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse { error: e }),
+            )
+        })?;
+    Ok(Json(histories))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+struct QuotaHistoryQuery {
+    account_id: String,
+    hours: Option<i64>,
 }
 
-async fn admin_check_for_updates() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
-    let info = crate::modules::update_checker::check_for_updates()
+#[utoipa::path(
+    get,
+    path = "/api/accounts/quota-history",
+    tag = "Accounts",
+    summary = "Quota snapshots of an account over the last N hours",
+    params(QuotaHistoryQuery)
+)]
+async fn admin_get_quota_history(
+    Query(q): Query<QuotaHistoryQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let points = crate::commands::get_quota_history(q.account_id, q.hours)
         .await
         .map_err(|e| {
             (
@@ -2145,181 +4548,433 @@ async fn admin_check_for_updates() -> Result<impl IntoResponse, (StatusCode, Jso
                 Json(ErrorResponse { error: e }),
             )
         })?;
-    Ok(Json(info))
+    Ok(Json(points))
 }
 
-async fn admin_update_last_check_time(
+#[derive(Deserialize, Default, ToSchema)]
+#[serde(rename_all = "camelCase", default)]
+struct DataRetentionRequest {
+    dry_run: bool,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/stats/retention",
+    tag = "Stats",
+    summary = "Apply data retention (or dry-run report of rows to delete)",
+    request_body = DataRetentionRequest
+)]
+async fn admin_run_data_retention(
+    Json(payload): Json<DataRetentionRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
-    crate::modules::update_checker::update_last_check_time().map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse { error: e }),
-        )
+    let report = crate::commands::run_data_retention(payload.dry_run)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse { error: e }),
+            )
+        })?;
+    Ok(Json(report))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/system/hygiene",
+    tag = "System",
+    summary = "Run account maintenance now (expired cooldowns, device history, temp files, index check)",
+    request_body = DataRetentionRequest
+)]
+async fn admin_run_account_hygiene(
+    Json(payload): Json<DataRetentionRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let report = crate::commands::run_account_hygiene(payload.dry_run)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse { error: e }),
+            )
+        })?;
+    Ok(Json(report))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/digest/run",
+    tag = "Stats",
+    summary = "Build the problem-accounts digest and deliver it to the notification channels now"
+)]
+async fn admin_run_problem_digest(
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let digest = crate::commands::run_problem_digest().await.map_err(|e| {
+        let status = if e.starts_with("digest_webhook_failed") {
+            StatusCode::BAD_GATEWAY
+        } else {
+            StatusCode::INTERNAL_SERVER_ERROR
+        };
+        (status, Json(ErrorResponse { error: e }))
     })?;
-    Ok(StatusCode::OK)
+    Ok(Json(digest))
 }
 
-async fn admin_save_update_settings(Json(settings): Json<serde_json::Value>) -> impl IntoResponse {
-    if let Ok(s) =
-        serde_json::from_value::<crate::modules::update_checker::UpdateSettings>(settings)
+fn backup_error(e: String) -> (StatusCode, Json<ErrorResponse>) {
+    let status = if e.starts_with("backup_target_not_configured")
+        || e.starts_with("backup_passphrase_required")
+        || e.starts_with("invalid_backup_name")
+        || e.starts_with("invalid_s3_endpoint")
+        || e.starts_with("backup_decrypt_failed")
     {
-        let _ = crate::modules::update_checker::save_update_settings(&s);
-        StatusCode::OK
-    } else {
         StatusCode::BAD_REQUEST
-    }
+    } else if e.starts_with("backup_already_running") {
+        StatusCode::CONFLICT
+    } else if e.starts_with("backup_remote_") {
+        StatusCode::BAD_GATEWAY
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+    (status, Json(ErrorResponse { error: e }))
 }
 
-async fn admin_is_auto_launch_enabled() -> impl IntoResponse {
-    // Note: Autostart requires tauri::AppHandle, which is not available in Axum State easily.
-    // For now, return false in Web mode.
-    Json(false)
+#[utoipa::path(
+    post,
+    path = "/api/backup/run",
+    tag = "Backup",
+    summary = "Upload an encrypted backup to the remote target now"
+)]
+async fn admin_run_remote_backup() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)>
+{
+    let result = crate::commands::run_remote_backup()
+        .await
+        .map_err(backup_error)?;
+    Ok(Json(result))
 }
 
-async fn admin_toggle_auto_launch(Json(_payload): Json<serde_json::Value>) -> impl IntoResponse {
-    // Note: Autostart requires tauri::AppHandle.
-    StatusCode::NOT_IMPLEMENTED
+#[utoipa::path(
+    get,
+    path = "/api/backup/remote",
+    tag = "Backup",
+    summary = "List backup archives on the remote target"
+)]
+async fn admin_list_remote_backups(
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let names = crate::commands::list_remote_backups()
+        .await
+        .map_err(backup_error)?;
+    Ok(Json(names))
 }
 
-async fn admin_get_http_api_settings() -> impl IntoResponse {
-    Json(serde_json::json!({ "enabled": true, "port": 8045 }))
+#[derive(Deserialize, ToSchema)]
+struct RestoreBackupRequest {
+    name: String,
 }
 
-// [整合清理] 冗餘導入已移除
+#[utoipa::path(
+    post,
+    path = "/api/backup/restore",
+    tag = "Backup",
+    summary = "Restore accounts and config from a remote backup archive",
+    request_body = RestoreBackupRequest
+)]
+async fn admin_restore_remote_backup(
+    Json(payload): Json<RestoreBackupRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let result = crate::commands::restore_remote_backup(payload.name)
+        .await
+        .map_err(backup_error)?;
+    Ok(Json(result))
+}
 
-#[derive(Deserialize)]
-struct BulkDeleteRequest {
-    #[serde(rename = "accountIds")]
-    account_ids: Vec<String>,
+#[utoipa::path(
+    get,
+    path = "/api/backup/status",
+    tag = "Backup",
+    summary = "Result of the last remote backup"
+)]
+async fn admin_get_backup_status() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)>
+{
+    let status = crate::commands::get_backup_status()
+        .await
+        .map_err(backup_error)?;
+    Ok(Json(status))
 }
 
-async fn admin_delete_accounts(
-    Json(payload): Json<BulkDeleteRequest>,
+#[utoipa::path(
+    post,
+    path = "/api/sync/run",
+    tag = "Backup",
+    summary = "Two-way sync of accounts, tags and routing with the remote target"
+)]
+async fn admin_sync_accounts_now(
+    State(state): State<AppState>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
-    crate::modules::account::delete_accounts(&payload.account_ids).map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse { error: e }),
-        )
+    let report = crate::modules::sync::run_sync().await.map_err(|e| {
+        if e.starts_with("sync_already_running") {
+            (StatusCode::CONFLICT, Json(ErrorResponse { error: e }))
+        } else {
+            backup_error(e)
+        }
     })?;
-    Ok(StatusCode::OK)
+    // 拉取到路由配置时热更新模型映射
+    if report.routing.as_deref() == Some("pulled") {
+        if let Ok(cfg) = config::load_app_config() {
+            let mut mapping = state.custom_mapping.write().await;
+            *mapping = cfg.proxy.custom_mapping;
+        }
+    }
+    Ok(Json(report))
 }
 
-#[derive(Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct ReorderRequest {
-    account_ids: Vec<String>,
+#[utoipa::path(
+    get,
+    path = "/api/sync/status",
+    tag = "Backup",
+    summary = "Account pool sync status"
+)]
+async fn admin_get_sync_status() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let status = crate::commands::get_sync_status()
+        .await
+        .map_err(backup_error)?;
+    Ok(Json(status))
 }
 
-async fn admin_reorder_accounts(
-    State(state): State<AppState>,
-    Json(payload): Json<ReorderRequest>,
+#[derive(Deserialize, ToSchema)]
+struct SimulatePoolRequest {
+    #[schema(value_type = Object)]
+    workload: crate::proxy::pool_simulation::SimulationWorkload,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/accounts/simulate",
+    tag = "Accounts",
+    summary = "Simulate a synthetic workload against the current pool",
+    request_body = SimulatePoolRequest
+)]
+async fn admin_simulate_pool_workload(
+    Json(payload): Json<SimulatePoolRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
-    crate::modules::account::reorder_accounts(&payload.account_ids).map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse { error: e }),
-        )
-    })?;
+    let report = crate::commands::simulate_pool_workload(payload.workload)
+        .await
+        .map_err(|e| {
+            let status = if e.starts_with("invalid_simulation") {
+                StatusCode::BAD_REQUEST
+            } else {
+                StatusCode::INTERNAL_SERVER_ERROR
+            };
+            (status, Json(ErrorResponse { error: e }))
+        })?;
+    Ok(Json(report))
+}
 
-    // [FIX #1166] 排序变动后立即重新加载 TokenManager
-    if let Err(e) = state.token_manager.load_accounts().await {
-        logger::log_error(&format!(
-            "[API] Failed to reload accounts after reorder: {}",
-            e
-        ));
-    }
+#[utoipa::path(
+    get,
+    path = "/api/quota-protection/monitored-models",
+    tag = "Proxy",
+    summary = "List monitorable model groups and current selection"
+)]
+async fn admin_get_monitored_model_options(
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let options = crate::commands::get_monitored_model_options()
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse { error: e }),
+            )
+        })?;
+    Ok(Json(options))
+}
 
-    Ok(StatusCode::OK)
+#[derive(Deserialize, ToSchema)]
+struct UpdateMonitoredModelsRequest {
+    models: Vec<String>,
 }
 
-async fn admin_fetch_account_quota(
-    Path(account_id): Path<String>,
+#[utoipa::path(
+    post,
+    path = "/api/quota-protection/monitored-models",
+    tag = "Proxy",
+    summary = "Validate and hot-apply monitored model groups",
+    request_body = UpdateMonitoredModelsRequest
+)]
+async fn admin_update_monitored_models(
+    Json(payload): Json<UpdateMonitoredModelsRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
-    let mut account = crate::modules::load_account(&account_id).map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse { error: e }),
-        )
-    })?;
+    let models = crate::commands::update_monitored_models(payload.models)
+        .await
+        .map_err(|e| {
+            let status = if e.starts_with("unknown_monitored_models") {
+                StatusCode::BAD_REQUEST
+            } else {
+                StatusCode::INTERNAL_SERVER_ERROR
+            };
+            (status, Json(ErrorResponse { error: e }))
+        })?;
+    Ok(Json(models))
+}
 
-    let quota = crate::modules::account::fetch_quota_with_retry(&mut account)
+#[derive(Deserialize, Default, ToSchema)]
+#[serde(rename_all = "camelCase", default)]
+struct ListAccountSummariesRequest {
+    #[schema(value_type = Option<Object>)]
+    sort_by: Option<crate::models::AccountSortBy>,
+    order: Option<crate::models::SortOrder>,
+    #[schema(value_type = Option<Object>)]
+    filter: Option<crate::models::AccountListFilter>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/accounts/summaries",
+    tag = "Accounts",
+    summary = "List account summaries with backend sorting / filtering",
+    request_body = ListAccountSummariesRequest
+)]
+async fn admin_list_account_summaries(
+    Json(payload): Json<ListAccountSummariesRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let items = crate::commands::list_account_summaries(payload.sort_by, payload.order, payload.filter)
         .await
         .map_err(|e| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    error: e.to_string(),
-                }),
+                Json(ErrorResponse { error: e }),
             )
         })?;
+    Ok(Json(items))
+}
 
-    crate::modules::update_account_quota(&account_id, quota.clone()).map_err(|e| {
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct SnoozeAccountRequest {
+    duration_secs: u64,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/accounts/{accountId}/snooze",
+    tag = "Accounts",
+    summary = "Snooze an account (auto re-enable later)",
+    params(("accountId" = String, Path, description = "Account ID")),
+    request_body = SnoozeAccountRequest
+)]
+async fn admin_snooze_account(
+    State(state): State<AppState>,
+    Path(account_id): Path<String>,
+    Json(payload): Json<SnoozeAccountRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let until = crate::commands::snooze_account(account_id.clone(), payload.duration_secs)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse { error: e }),
+            )
+        })?;
+
+    // 同步到运行中的反代服务
+    let _ = state.token_manager.reload_account(&account_id).await;
+
+    Ok(Json(serde_json::json!({ "snoozed_until": until })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/accounts/orphans",
+    tag = "Accounts",
+    summary = "List account files missing from the index"
+)]
+async fn admin_list_orphan_accounts() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let orphans = crate::commands::list_orphan_accounts().await.map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse { error: e }),
         )
     })?;
-
-    Ok(Json(quota))
-}
-
-#[derive(Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct ToggleProxyRequest {
-    enable: bool,
-    reason: Option<String>,
+    Ok(Json(orphans))
 }
 
-async fn admin_toggle_proxy_status(
-    State(state): State<AppState>,
+#[utoipa::path(
+    post,
+    path = "/api/accounts/orphans/{accountId}/adopt",
+    tag = "Accounts",
+    summary = "Adopt an orphan account file",
+    params(("accountId" = String, Path, description = "Account ID"))
+)]
+async fn admin_adopt_orphan(
     Path(account_id): Path<String>,
-    Json(payload): Json<ToggleProxyRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
-    crate::modules::account::toggle_proxy_status(
-        &account_id,
-        payload.enable,
-        payload.reason.as_deref(),
-    )
-    .map_err(|e| {
+    let summary = crate::commands::adopt_orphan(account_id).await.map_err(|e| {
         (
-            StatusCode::INTERNAL_SERVER_ERROR,
+            StatusCode::BAD_REQUEST,
             Json(ErrorResponse { error: e }),
         )
     })?;
-
-    // 同步到运行中的反代服务
-    let _ = state.token_manager.reload_account(&account_id).await;
-
-    Ok(StatusCode::OK)
+    Ok(Json(summary))
 }
 
-async fn admin_warm_up_all_accounts() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)>
-{
-    let result = crate::commands::warm_up_all_accounts().await.map_err(|e| {
+#[utoipa::path(
+    get,
+    path = "/api/accounts/corrupt",
+    tag = "Accounts",
+    summary = "List quarantined unparseable account files"
+)]
+async fn admin_list_corrupt_accounts() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let files = crate::commands::list_corrupt_accounts().await.map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse { error: e }),
         )
     })?;
-    Ok(Json(result))
+    Ok(Json(files))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/accounts/corrupt/{fileName}/restore",
+    tag = "Accounts",
+    summary = "Restore a quarantined account file",
+    params(("fileName" = String, Path, description = "Quarantined file name"))
+)]
+async fn admin_restore_corrupt_account(
+    Path(file_name): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let summary = crate::commands::restore_corrupt_account(file_name).await.map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+    Ok(Json(summary))
 }
 
-async fn admin_warm_up_account(
-    Path(account_id): Path<String>,
+#[utoipa::path(
+    delete,
+    path = "/api/accounts/corrupt/{fileName}",
+    tag = "Accounts",
+    summary = "Delete a quarantined account file",
+    params(("fileName" = String, Path, description = "Quarantined file name"))
+)]
+async fn admin_delete_corrupt_account(
+    Path(file_name): Path<String>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
-    let result = crate::commands::warm_up_account(account_id)
-        .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse { error: e }),
-            )
-        })?;
-    Ok(Json(result))
+    crate::commands::delete_corrupt_account(file_name).await.map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse { error: e }),
+        )
+    })?;
+    Ok(StatusCode::NO_CONTENT)
 }
 
-
+#[utoipa::path(
+    post,
+    path = "/api/system/http-api/settings",
+    tag = "System",
+    summary = "Save http api settings",
+    request_body = crate::modules::http_api::HttpApiSettings
+)]
 async fn admin_save_http_api_settings(
     Json(payload): Json<crate::modules::http_api::HttpApiSettings>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -2333,6 +4988,12 @@ async fn admin_save_http_api_settings(
 }
 
 // Cloudflared Handlers
+#[utoipa::path(
+    get,
+    path = "/api/proxy/cloudflared/status",
+    tag = "Proxy",
+    summary = "Cloudflared get status"
+)]
 async fn admin_cloudflared_get_status(
     State(state): State<AppState>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -2365,6 +5026,12 @@ async fn admin_cloudflared_get_status(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/proxy/cloudflared/install",
+    tag = "Proxy",
+    summary = "Cloudflared install"
+)]
 async fn admin_cloudflared_install(
     State(state): State<AppState>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -2398,12 +5065,20 @@ async fn admin_cloudflared_install(
     }
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct CloudflaredStartRequest {
+    #[schema(value_type = Object)]
     config: crate::modules::cloudflared::CloudflaredConfig,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/proxy/cloudflared/start",
+    tag = "Proxy",
+    summary = "Cloudflared start",
+    request_body = CloudflaredStartRequest
+)]
 async fn admin_cloudflared_start(
     State(state): State<AppState>,
     Json(payload): Json<CloudflaredStartRequest>,
@@ -2438,6 +5113,12 @@ async fn admin_cloudflared_start(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/proxy/cloudflared/stop",
+    tag = "Proxy",
+    summary = "Cloudflared stop"
+)]
 async fn admin_cloudflared_stop(
     State(state): State<AppState>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -2473,6 +5154,13 @@ async fn admin_cloudflared_stop(
 
 // --- Supplementary Account Handlers ---
 
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{accountId}/device-profiles",
+    tag = "Accounts",
+    summary = "Get device profiles",
+    params(("accountId" = String, Path, description = "Account ID"))
+)]
 async fn admin_get_device_profiles(
     State(_state): State<AppState>,
     Path(account_id): Path<String>,
@@ -2486,6 +5174,13 @@ async fn admin_get_device_profiles(
     Ok(Json(profiles))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{accountId}/device-versions",
+    tag = "Accounts",
+    summary = "List device versions",
+    params(("accountId" = String, Path, description = "Account ID"))
+)]
 async fn admin_list_device_versions(
     State(_state): State<AppState>,
     Path(account_id): Path<String>,
@@ -2499,13 +5194,19 @@ async fn admin_list_device_versions(
     Ok(Json(profiles))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/accounts/device-preview",
+    tag = "Accounts",
+    summary = "Preview generate profile"
+)]
 async fn admin_preview_generate_profile(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     let profile = crate::modules::device::generate_profile();
     Ok(Json(profile))
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct BindDeviceProfileWrapper {
     #[serde(default)]
@@ -2515,7 +5216,7 @@ struct BindDeviceProfileWrapper {
 }
 
 // 用于 API 的 DeviceProfile 包装器，支持 camelCase 输入
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 struct DeviceProfileApiWrapper {
     #[serde(alias = "machineId")]
     machine_id: String,
@@ -2525,6 +5226,12 @@ struct DeviceProfileApiWrapper {
     dev_device_id: String,
     #[serde(alias = "sqmId")]
     sqm_id: String,
+    #[serde(default)]
+    locale: Option<String>,
+    #[serde(default)]
+    timezone: Option<String>,
+    #[serde(default, alias = "osVersion")]
+    os_version: Option<String>,
 }
 
 impl From<DeviceProfileApiWrapper> for crate::models::account::DeviceProfile {
@@ -2534,10 +5241,21 @@ impl From<DeviceProfileApiWrapper> for crate::models::account::DeviceProfile {
             mac_machine_id: wrapper.mac_machine_id,
             dev_device_id: wrapper.dev_device_id,
             sqm_id: wrapper.sqm_id,
+            locale: wrapper.locale,
+            timezone: wrapper.timezone,
+            os_version: wrapper.os_version,
         }
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/accounts/{accountId}/bind-device-profile",
+    tag = "Accounts",
+    summary = "Bind device profile with profile",
+    params(("accountId" = String, Path, description = "Account ID")),
+    request_body = BindDeviceProfileWrapper
+)]
 async fn admin_bind_device_profile_with_profile(
     State(_state): State<AppState>,
     Path(account_id): Path<String>,
@@ -2562,6 +5280,12 @@ async fn admin_bind_device_profile_with_profile(
     Ok(Json(result))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/accounts/restore-original",
+    tag = "Accounts",
+    summary = "Restore original device"
+)]
 async fn admin_restore_original_device(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     let msg = account::restore_original_device().map_err(|e| {
@@ -2573,6 +5297,16 @@ async fn admin_restore_original_device(
     Ok(Json(msg))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/accounts/{accountId}/device-versions/{versionId}/restore",
+    tag = "Accounts",
+    summary = "Restore device version",
+    params(
+        ("accountId" = String, Path, description = "Account ID"),
+        ("versionId" = String, Path, description = "Device version ID"),
+    )
+)]
 async fn admin_restore_device_version(
     State(_state): State<AppState>,
     Path((account_id, version_id)): Path<(String, String)>,
@@ -2586,6 +5320,16 @@ async fn admin_restore_device_version(
     Ok(Json(profile))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/accounts/{accountId}/device-versions/{versionId}",
+    tag = "Accounts",
+    summary = "Delete device version",
+    params(
+        ("accountId" = String, Path, description = "Account ID"),
+        ("versionId" = String, Path, description = "Device version ID"),
+    )
+)]
 async fn admin_delete_device_version(
     State(_state): State<AppState>,
     Path((account_id, version_id)): Path<(String, String)>,
@@ -2599,6 +5343,12 @@ async fn admin_delete_device_version(
     Ok(StatusCode::NO_CONTENT)
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/system/open-folder",
+    tag = "System",
+    summary = "Open folder"
+)]
 async fn admin_open_folder() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     // Note: In Web mode, this may not actually open a local folder unless the backend handles it.
     // For ABV_Refactor, the backend should use opener to open it on the server (the desktop).
@@ -2613,6 +5363,12 @@ async fn admin_open_folder() -> Result<impl IntoResponse, (StatusCode, Json<Erro
 
 // --- Import Handlers ---
 
+#[utoipa::path(
+    post,
+    path = "/api/accounts/import/v1",
+    tag = "Accounts",
+    summary = "Import v1 accounts"
+)]
 async fn admin_import_v1_accounts(
     State(state): State<AppState>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -2639,6 +5395,13 @@ async fn admin_import_v1_accounts(
     Ok(Json(responses))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/accounts/import/db",
+    tag = "Accounts",
+    summary = "Import from db",
+    responses((status = 200, body = AccountResponse))
+)]
 async fn admin_import_from_db(
     State(state): State<AppState>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -2661,11 +5424,19 @@ async fn admin_import_from_db(
     Ok(Json(to_account_response(&account, &current_id)))
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 struct CustomDbRequest {
     path: String,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/accounts/import/db-custom",
+    tag = "Accounts",
+    summary = "Import custom db",
+    request_body = CustomDbRequest,
+    responses((status = 200, body = AccountResponse))
+)]
 async fn admin_import_custom_db(
     State(state): State<AppState>,
     Json(payload): Json<CustomDbRequest>,
@@ -2701,6 +5472,12 @@ async fn admin_import_custom_db(
     Ok(Json(to_account_response(&account, &current_id)))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/accounts/sync/db",
+    tag = "Accounts",
+    summary = "Sync account from db"
+)]
 async fn admin_sync_account_from_db(
     State(state): State<AppState>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -2745,13 +5522,20 @@ async fn admin_sync_account_from_db(
 
 // --- CLI Sync Handlers ---
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct CliSyncStatusRequest {
     app_type: crate::proxy::cli_sync::CliApp,
     proxy_url: String,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/proxy/cli/status",
+    tag = "Proxy",
+    summary = "Get cli sync status",
+    request_body = CliSyncStatusRequest
+)]
 async fn admin_get_cli_sync_status(
     Json(payload): Json<CliSyncStatusRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -2766,7 +5550,7 @@ async fn admin_get_cli_sync_status(
         })
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct CliSyncRequest {
     app_type: crate::proxy::cli_sync::CliApp,
@@ -2775,6 +5559,13 @@ struct CliSyncRequest {
     pub model: Option<String>,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/proxy/cli/sync",
+    tag = "Proxy",
+    summary = "Execute cli sync",
+    request_body = CliSyncRequest
+)]
 async fn admin_execute_cli_sync(
     Json(payload): Json<CliSyncRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -2789,12 +5580,19 @@ async fn admin_execute_cli_sync(
         })
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct CliRestoreRequest {
     app_type: crate::proxy::cli_sync::CliApp,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/proxy/cli/restore",
+    tag = "Proxy",
+    summary = "Execute cli restore",
+    request_body = CliRestoreRequest
+)]
 async fn admin_execute_cli_restore(
     Json(payload): Json<CliRestoreRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -2809,13 +5607,20 @@ async fn admin_execute_cli_restore(
         })
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct CliConfigContentRequest {
     app_type: crate::proxy::cli_sync::CliApp,
     file_name: Option<String>,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/proxy/cli/config",
+    tag = "Proxy",
+    summary = "Get cli config content",
+    request_body = CliConfigContentRequest
+)]
 async fn admin_get_cli_config_content(
     Json(payload): Json<CliConfigContentRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -2954,6 +5759,12 @@ async fn handle_oauth_callback(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/auth/url",
+    tag = "Accounts",
+    summary = "Prepare oauth url web"
+)]
 async fn admin_prepare_oauth_url_web(
     headers: HeaderMap,
     State(state): State<AppState>,
@@ -3063,8 +5874,9 @@ fn get_oauth_redirect_uri(port: u16, _host: Option<&str>, _proto: Option<&str>)
 // Security / IP Management Handlers
 // ============================================================================
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
 #[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
 struct IpAccessLogQuery {
     #[serde(default = "default_page")]
     page: usize,
@@ -3078,12 +5890,21 @@ struct IpAccessLogQuery {
 fn default_page() -> usize { 1 }
 fn default_page_size() -> usize { 50 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct IpAccessLogResponse {
+    #[schema(value_type = Vec<Object>)]
     logs: Vec<crate::modules::security_db::IpAccessLog>,
     total: usize,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/security/logs",
+    tag = "Security",
+    summary = "Get ip access logs",
+    params(IpAccessLogQuery),
+    responses((status = 200, body = IpAccessLogResponse))
+)]
 async fn admin_get_ip_access_logs(
     Query(q): Query<IpAccessLogQuery>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -3100,20 +5921,34 @@ async fn admin_get_ip_access_logs(
     Ok(Json(IpAccessLogResponse { logs, total }))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/security/logs/clear",
+    tag = "Security",
+    summary = "Clear ip access logs"
+)]
 async fn admin_clear_ip_access_logs() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     security_db::clear_ip_access_logs()
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(ErrorResponse { error: e })))?;
     Ok(StatusCode::OK)
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct IpStatsResponse {
     total_requests: usize,
     unique_ips: usize,
     blocked_requests: usize,
+    #[schema(value_type = Vec<Object>)]
     top_ips: Vec<crate::modules::security_db::IpRanking>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/security/stats",
+    tag = "Security",
+    summary = "Get ip stats",
+    responses((status = 200, body = IpStatsResponse))
+)]
 async fn admin_get_ip_stats() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     let stats = security_db::get_ip_stats()
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(ErrorResponse { error: e })))?;
@@ -3129,12 +5964,20 @@ async fn admin_get_ip_stats() -> Result<impl IntoResponse, (StatusCode, Json<Err
     Ok(Json(response))
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct IpTokenStatsQuery {
     limit: Option<usize>,
     hours: Option<i64>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/security/token-stats",
+    tag = "Security",
+    summary = "Get ip token stats",
+    params(IpTokenStatsQuery)
+)]
 async fn admin_get_ip_token_stats(
     Query(q): Query<IpTokenStatsQuery>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -3145,13 +5988,19 @@ async fn admin_get_ip_token_stats(
     Ok(Json(stats))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/security/blacklist",
+    tag = "Security",
+    summary = "Get ip blacklist"
+)]
 async fn admin_get_ip_blacklist() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     let list = security_db::get_blacklist()
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(ErrorResponse { error: e })))?;
     Ok(Json(list))
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct AddBlacklistRequest {
     ip_pattern: String,
@@ -3159,6 +6008,13 @@ struct AddBlacklistRequest {
     expires_at: Option<i64>,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/security/blacklist",
+    tag = "Security",
+    summary = "Add ip to blacklist",
+    request_body = AddBlacklistRequest
+)]
 async fn admin_add_ip_to_blacklist(
     Json(req): Json<AddBlacklistRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -3172,12 +6028,20 @@ async fn admin_add_ip_to_blacklist(
     Ok(StatusCode::CREATED)
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
 #[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
 struct RemoveIpRequest {
     ip_pattern: String,
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/security/blacklist",
+    tag = "Security",
+    summary = "Remove ip from blacklist",
+    params(RemoveIpRequest)
+)]
 async fn admin_remove_ip_from_blacklist(
     Query(q): Query<RemoveIpRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -3194,6 +6058,12 @@ async fn admin_remove_ip_from_blacklist(
     Ok(StatusCode::OK)
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/security/blacklist/clear",
+    tag = "Security",
+    summary = "Clear ip blacklist"
+)]
 async fn admin_clear_ip_blacklist() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     let entries = security_db::get_blacklist()
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(ErrorResponse { error: e })))?;
@@ -3204,12 +6074,20 @@ async fn admin_clear_ip_blacklist() -> Result<impl IntoResponse, (StatusCode, Js
     Ok(StatusCode::OK)
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
 #[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
 struct CheckIpQuery {
     ip: String,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/security/blacklist/check",
+    tag = "Security",
+    summary = "Check ip in blacklist",
+    params(CheckIpQuery)
+)]
 async fn admin_check_ip_in_blacklist(
     Query(q): Query<CheckIpQuery>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -3218,19 +6096,32 @@ async fn admin_check_ip_in_blacklist(
     Ok(Json(serde_json::json!({ "result": result })))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/security/whitelist",
+    tag = "Security",
+    summary = "Get ip whitelist"
+)]
 async fn admin_get_ip_whitelist() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     let list = security_db::get_whitelist()
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(ErrorResponse { error: e })))?;
     Ok(Json(list))
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct AddWhitelistRequest {
     ip_pattern: String,
     description: Option<String>,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/security/whitelist",
+    tag = "Security",
+    summary = "Add ip to whitelist",
+    request_body = AddWhitelistRequest
+)]
 async fn admin_add_ip_to_whitelist(
     Json(req): Json<AddWhitelistRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -3241,6 +6132,13 @@ async fn admin_add_ip_to_whitelist(
     Ok(StatusCode::CREATED)
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/security/whitelist",
+    tag = "Security",
+    summary = "Remove ip from whitelist",
+    params(RemoveIpRequest)
+)]
 async fn admin_remove_ip_from_whitelist(
     Query(q): Query<RemoveIpRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -3256,6 +6154,12 @@ async fn admin_remove_ip_from_whitelist(
     Ok(StatusCode::OK)
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/security/whitelist/clear",
+    tag = "Security",
+    summary = "Clear ip whitelist"
+)]
 async fn admin_clear_ip_whitelist() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     let entries = security_db::get_whitelist()
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(ErrorResponse { error: e })))?;
@@ -3266,6 +6170,13 @@ async fn admin_clear_ip_whitelist() -> Result<impl IntoResponse, (StatusCode, Js
     Ok(StatusCode::OK)
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/security/whitelist/check",
+    tag = "Security",
+    summary = "Check ip in whitelist",
+    params(CheckIpQuery)
+)]
 async fn admin_check_ip_in_whitelist(
     Query(q): Query<CheckIpQuery>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -3274,6 +6185,12 @@ async fn admin_check_ip_in_whitelist(
     Ok(Json(serde_json::json!({ "result": result })))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/security/config",
+    tag = "Security",
+    summary = "Get security config"
+)]
 async fn admin_get_security_config(
     State(_state): State<AppState>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -3283,11 +6200,19 @@ async fn admin_get_security_config(
     Ok(Json(app_config.proxy.security_monitor))
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 struct UpdateSecurityConfigWrapper {
+    #[schema(value_type = Object)]
     config: crate::proxy::config::SecurityMonitorConfig,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/security/config",
+    tag = "Security",
+    summary = "Update security config",
+    request_body = UpdateSecurityConfigWrapper
+)]
 async fn admin_update_security_config(
     State(state): State<AppState>,
     Json(payload): Json<UpdateSecurityConfigWrapper>,
@@ -3312,36 +6237,97 @@ async fn admin_update_security_config(
 
 // --- Debug Console Handlers ---
 
+#[utoipa::path(
+    post,
+    path = "/api/debug/enable",
+    tag = "Logs",
+    summary = "Enable debug console"
+)]
 async fn admin_enable_debug_console() -> impl IntoResponse {
     crate::modules::log_bridge::enable_log_bridge();
     StatusCode::OK
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/debug/disable",
+    tag = "Logs",
+    summary = "Disable debug console"
+)]
 async fn admin_disable_debug_console() -> impl IntoResponse {
     crate::modules::log_bridge::disable_log_bridge();
     StatusCode::OK
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/debug/enabled",
+    tag = "Logs",
+    summary = "Is debug console enabled"
+)]
 async fn admin_is_debug_console_enabled() -> impl IntoResponse {
     Json(crate::modules::log_bridge::is_log_bridge_enabled())
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/debug/logs",
+    tag = "Logs",
+    summary = "Get debug console logs"
+)]
 async fn admin_get_debug_console_logs() -> impl IntoResponse {
     let logs = crate::modules::log_bridge::get_buffered_logs();
     Json(logs)
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/debug/logs/clear",
+    tag = "Logs",
+    summary = "Clear debug console logs"
+)]
 async fn admin_clear_debug_console_logs() -> impl IntoResponse {
     crate::modules::log_bridge::clear_log_buffer();
     StatusCode::OK
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Default, IntoParams)]
+#[serde(default)]
+#[into_params(parameter_in = Query)]
+struct TailEventsQuery {
+    since: Option<u64>,
+    limit: Option<usize>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/debug/events",
+    tag = "Logs",
+    summary = "Tail the internal event bus (events after the since cursor)",
+    params(TailEventsQuery)
+)]
+async fn admin_tail_events(Query(query): Query<TailEventsQuery>) -> impl IntoResponse {
+    Json(crate::modules::events::tail(
+        query.since,
+        query
+            .limit
+            .unwrap_or(crate::modules::events::DEFAULT_TAIL_LIMIT),
+    ))
+}
+
+#[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct OpencodeSyncStatusRequest {
     proxy_url: String,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/proxy/opencode/status",
+    tag = "Proxy",
+    summary = "Get opencode sync status",
+    request_body = OpencodeSyncStatusRequest
+)]
 async fn admin_get_opencode_sync_status(
     Json(payload): Json<OpencodeSyncStatusRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -3356,7 +6342,7 @@ async fn admin_get_opencode_sync_status(
         })
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct OpencodeSyncRequest {
     proxy_url: String,
@@ -3366,6 +6352,13 @@ struct OpencodeSyncRequest {
     pub models: Option<Vec<String>>,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/proxy/opencode/sync",
+    tag = "Proxy",
+    summary = "Execute opencode sync",
+    request_body = OpencodeSyncRequest
+)]
 async fn admin_execute_opencode_sync(
     Json(payload): Json<OpencodeSyncRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -3385,6 +6378,12 @@ async fn admin_execute_opencode_sync(
     })
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/proxy/opencode/restore",
+    tag = "Proxy",
+    summary = "Execute opencode restore"
+)]
 async fn admin_execute_opencode_restore(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     crate::proxy::opencode_sync::execute_opencode_restore()
@@ -3398,12 +6397,19 @@ async fn admin_execute_opencode_restore(
         })
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct GetOpencodeConfigRequest {
     file_name: Option<String>,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/proxy/opencode/config",
+    tag = "Proxy",
+    summary = "Get opencode config content",
+    request_body = GetOpencodeConfigRequest
+)]
 async fn admin_get_opencode_config_content(
     Json(payload): Json<GetOpencodeConfigRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -3421,13 +6427,20 @@ async fn admin_get_opencode_config_content(
         ))
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct OpencodeClearRequest {
     proxy_url: Option<String>,
     clear_legacy: Option<bool>,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/proxy/opencode/clear",
+    tag = "Proxy",
+    summary = "Execute opencode clear",
+    request_body = OpencodeClearRequest
+)]
 async fn admin_execute_opencode_clear(
     Json(payload): Json<OpencodeClearRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -3442,12 +6455,19 @@ async fn admin_execute_opencode_clear(
 
 // ── Droid (Factory CLI) Sync Admin Handlers ──
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct DroidSyncStatusRequest {
     proxy_url: String,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/proxy/droid/status",
+    tag = "Proxy",
+    summary = "Get droid sync status",
+    request_body = DroidSyncStatusRequest
+)]
 async fn admin_get_droid_sync_status(
     Json(payload): Json<DroidSyncStatusRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -3460,12 +6480,19 @@ async fn admin_get_droid_sync_status(
         ))
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct DroidSyncRequest {
     custom_models: Vec<serde_json::Value>,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/proxy/droid/sync",
+    tag = "Proxy",
+    summary = "Execute droid sync",
+    request_body = DroidSyncRequest
+)]
 async fn admin_execute_droid_sync(
     Json(payload): Json<DroidSyncRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
@@ -3480,6 +6507,12 @@ async fn admin_execute_droid_sync(
         ))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/proxy/droid/restore",
+    tag = "Proxy",
+    summary = "Execute droid restore"
+)]
 async fn admin_execute_droid_restore(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     crate::proxy::droid_sync::execute_droid_restore()
@@ -3491,6 +6524,12 @@ async fn admin_execute_droid_restore(
         ))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/proxy/droid/config",
+    tag = "Proxy",
+    summary = "Get droid config content"
+)]
 async fn admin_get_droid_config_content(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     crate::proxy::droid_sync::get_droid_config_content()
@@ -0,0 +1,174 @@
+// 上游会话 ID 池 (x-vscode-sessionid)
+// 官方客户端每次启动生成一个会话 ID；反代多账号共用同一个值会把所有账号的流量关联到一起。
+// - 每个账号独立生成并持久化到 <data_dir>/upstream_sessions.json，重启后保持不变
+// - 按 session_pool.rotation_hours 定期轮换 (0 = 不轮换)
+// - 关闭 per_account 时退回到全局的每次启动一个 ID (constants::SESSION_ID)
+
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+const SESSIONS_FILE: &str = "upstream_sessions.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountSession {
+    pub session_id: String,
+    /// 生成时间 (秒)
+    pub created_at: i64,
+}
+
+static SESSIONS: Lazy<DashMap<String, AccountSession>> = Lazy::new(|| {
+    let mut map = DashMap::new();
+    if let Some(saved) = sessions_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| {
+            serde_json::from_str::<std::collections::HashMap<String, AccountSession>>(&content).ok()
+        })
+    {
+        map.extend(saved);
+    }
+    map
+});
+
+fn sessions_path() -> Option<PathBuf> {
+    crate::modules::account::get_data_dir()
+        .ok()
+        .map(|dir| dir.join(SESSIONS_FILE))
+}
+
+fn persist() {
+    let Some(path) = sessions_path() else {
+        return;
+    };
+    let snapshot: std::collections::BTreeMap<String, AccountSession> = SESSIONS
+        .iter()
+        .map(|e| (e.key().clone(), e.value().clone()))
+        .collect();
+    match serde_json::to_string_pretty(&snapshot) {
+        Ok(content) => {
+            if let Err(e) = std::fs::write(&path, content) {
+                tracing::warn!("[SessionPool] Failed to save {}: {}", path.display(), e);
+            }
+        }
+        Err(e) => tracing::warn!("[SessionPool] Failed to serialize sessions: {}", e),
+    }
+}
+
+fn is_expired(session: &AccountSession, rotation_hours: u64, now: i64) -> bool {
+    rotation_hours > 0 && now - session.created_at >= (rotation_hours * 3600) as i64
+}
+
+fn new_session(now: i64) -> AccountSession {
+    AccountSession {
+        session_id: uuid::Uuid::new_v4().to_string(),
+        created_at: now,
+    }
+}
+
+/// 账号当前的会话 ID，不存在或已到轮换周期时重新生成；返回 (会话 ID, 是否新生成)
+fn ensure_session(account_id: &str, rotation_hours: u64, now: i64) -> (String, bool) {
+    if let Some(existing) = SESSIONS.get(account_id) {
+        if !is_expired(&existing, rotation_hours, now) {
+            return (existing.session_id.clone(), false);
+        }
+    }
+    let session = new_session(now);
+    let session_id = session.session_id.clone();
+    SESSIONS.insert(account_id.to_string(), session);
+    (session_id, true)
+}
+
+/// 上游请求使用的 x-vscode-sessionid
+pub fn upstream_session_id(account_id: Option<&str>) -> String {
+    let cfg = crate::proxy::config::get_session_pool_config();
+    match account_id {
+        Some(id) if cfg.per_account => {
+            let (session_id, created) =
+                ensure_session(id, cfg.rotation_hours, chrono::Utc::now().timestamp());
+            if created {
+                tracing::debug!("[SessionPool] New upstream session for account {}", id);
+                persist();
+            }
+            session_id
+        }
+        _ => crate::constants::SESSION_ID.clone(),
+    }
+}
+
+/// 立即轮换会话 ID；account_id 为空时轮换全部账号
+pub fn rotate(account_id: Option<&str>) -> usize {
+    let now = chrono::Utc::now().timestamp();
+    let rotated = match account_id {
+        Some(id) => {
+            SESSIONS.insert(id.to_string(), new_session(now));
+            1
+        }
+        None => {
+            let count = SESSIONS.len();
+            SESSIONS.iter_mut().for_each(|mut e| *e.value_mut() = new_session(now));
+            count
+        }
+    };
+    persist();
+    rotated
+}
+
+/// 删除账号时清理其会话
+pub fn remove(account_id: &str) {
+    if SESSIONS.remove(account_id).is_some() {
+        persist();
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionEntry {
+    pub account_id: String,
+    pub session_id: String,
+    pub created_at: i64,
+}
+
+/// 当前所有账号的会话 ID
+pub fn list() -> Vec<SessionEntry> {
+    let mut sessions: Vec<SessionEntry> = SESSIONS
+        .iter()
+        .map(|e| SessionEntry {
+            account_id: e.key().clone(),
+            session_id: e.value().session_id.clone(),
+            created_at: e.value().created_at,
+        })
+        .collect();
+    sessions.sort_by(|a, b| a.account_id.cmp(&b.account_id));
+    sessions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_rotation_cadence() {
+        let session = AccountSession {
+            session_id: "s".to_string(),
+            created_at: 1_000,
+        };
+        assert!(!is_expired(&session, 0, 1_000_000));
+        assert!(!is_expired(&session, 1, 1_000 + 3599));
+        assert!(is_expired(&session, 1, 1_000 + 3600));
+    }
+
+    #[test]
+    fn test_sessions_are_per_account_and_stable() {
+        let (a, created) = ensure_session("session-pool-test-a", 1, 10_000);
+        assert!(created);
+        let (b, _) = ensure_session("session-pool-test-b", 1, 10_000);
+        assert_ne!(a, b);
+        assert_eq!(ensure_session("session-pool-test-a", 1, 10_100), (a.clone(), false));
+        // 到达轮换周期后重新生成
+        let (rotated, created) = ensure_session("session-pool-test-a", 1, 10_000 + 3600);
+        assert!(created);
+        assert_ne!(rotated, a);
+        SESSIONS.remove("session-pool-test-a");
+        SESSIONS.remove("session-pool-test-b");
+    }
+}
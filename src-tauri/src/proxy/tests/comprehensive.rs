@@ -25,6 +25,7 @@ mod tests {
             ],
             system: None,
             tools: None, // 无工具调用
+            tool_choice: None,
             stream: false,
             max_tokens: None,
             temperature: None,
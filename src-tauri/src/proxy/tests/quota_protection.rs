@@ -30,6 +30,7 @@ mod tests {
             email: email.to_string(),
             account_path: PathBuf::from(format!("/tmp/test_accounts/{}.json", account_id)),
             project_id: Some("test-project".to_string()),
+            provider: crate::models::AccountProvider::Google,
             subscription_tier: Some("PRO".to_string()),
             remaining_quota,
             protected_models: protected_models.iter().map(|s| s.to_string()).collect(),
@@ -40,6 +41,8 @@ mod tests {
             validation_url: None,
             model_quotas: std::collections::HashMap::new(),
             model_limits: std::collections::HashMap::new(),
+            tags: Vec::new(),
+            quota_updated_at: None,
         }
     }
 
@@ -788,7 +791,7 @@ mod tests {
             "内存中的账号还没被同步"
         );
 
-        // 模拟 trigger_account_reload -> reload_account 同步
+        // 模拟 AccountReload 事件 -> reload_account 同步
         tokens_in_memory[0] = account_on_disk.clone();
 
         // 验证内存数据已同步
@@ -1135,6 +1138,7 @@ mod tests {
             email: email.to_string(),
             account_path,
             project_id: Some("test-project".to_string()),
+            provider: crate::models::AccountProvider::Google,
             subscription_tier: Some("PRO".to_string()),
             remaining_quota,
             protected_models: protected_models.iter().map(|s| s.to_string()).collect(),
@@ -1145,6 +1149,8 @@ mod tests {
             validation_url: None,
             model_quotas: std::collections::HashMap::new(),
             model_limits: std::collections::HashMap::new(),
+            tags: Vec::new(),
+            quota_updated_at: None,
         }
     }
 }
@@ -45,6 +45,7 @@ fn create_test_token(
         email: email.to_string(),
         account_path: PathBuf::from("/tmp/test"),
         project_id: None,
+        provider: crate::models::AccountProvider::Google,
         subscription_tier: tier.map(|s| s.to_string()),
         remaining_quota,
         protected_models: HashSet::new(),
@@ -55,6 +56,8 @@ fn create_test_token(
         validation_url: None,
         model_quotas,
         model_limits: std::collections::HashMap::new(),
+        tags: Vec::new(),
+        quota_updated_at: None,
     }
 }
 
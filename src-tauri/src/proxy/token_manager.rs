@@ -9,6 +9,70 @@ use tokio_util::sync::CancellationToken;
 use crate::proxy::rate_limit::RateLimitTracker;
 use crate::proxy::sticky_config::StickySessionConfig;
 
+tokio::task_local! {
+    /// 当前请求 API Key 绑定的账号分组 (由鉴权中间件设置)
+    static ACCOUNT_GROUP: Option<String>;
+}
+
+/// 在指定账号分组的作用域内执行 future，期间 get_token 只会选择带有该标签的账号
+pub async fn scope_account_group<F: std::future::Future>(group: Option<String>, f: F) -> F::Output {
+    ACCOUNT_GROUP.scope(group, f).await
+}
+
+/// 获取当前请求绑定的账号分组 (未绑定或不在请求作用域内时为 None)
+pub fn current_account_group() -> Option<String> {
+    ACCOUNT_GROUP.try_with(|g| g.clone()).ok().flatten()
+}
+
+/// 订阅等级排序权重: ULTRA(0) > PRO(1) > FREE(2) > 未知(3)
+pub(crate) fn tier_rank(tier: &Option<String>) -> u8 {
+    let t = tier.as_deref().unwrap_or("").to_lowercase();
+    if t.contains("ultra") {
+        0
+    } else if t.contains("pro") {
+        1
+    } else if t.contains("free") {
+        2
+    } else {
+        3
+    }
+}
+
+/// 是否为需要优先高等级账号的高端模型 (大小写不敏感的子串匹配)
+pub(crate) fn is_premium_model(model: &str, routing: &crate::proxy::config::TierRoutingConfig) -> bool {
+    let lower = model.to_lowercase();
+    routing
+        .premium_models
+        .iter()
+        .any(|m| !m.is_empty() && lower.contains(&m.to_lowercase()))
+}
+
+/// 模型被固定到的订阅等级权重集合；未配置时返回 None
+fn pinned_tier_ranks(
+    model: &str,
+    normalized: &str,
+    routing: &crate::proxy::config::TierRoutingConfig,
+) -> Option<HashSet<u8>> {
+    use crate::proxy::common::model_mapping::wildcard_match;
+
+    let tiers = routing
+        .model_tiers
+        .get(model)
+        .or_else(|| routing.model_tiers.get(normalized))
+        .or_else(|| {
+            routing
+                .model_tiers
+                .iter()
+                .filter(|(pattern, _)| pattern.contains('*'))
+                .find(|(pattern, _)| {
+                    wildcard_match(pattern, model) || wildcard_match(pattern, normalized)
+                })
+                .map(|(_, tiers)| tiers)
+        })?;
+    let ranks: HashSet<u8> = tiers.iter().map(|t| tier_rank(&Some(t.clone()))).collect();
+    (!ranks.is_empty()).then_some(ranks)
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum OnDiskAccountState {
     Enabled,
@@ -37,19 +101,32 @@ pub struct ProxyToken {
     pub validation_url: Option<String>,    // [NEW] Validation URL (#1522)
     pub model_quotas: HashMap<String, i32>, // [OPTIMIZATION] In-memory cache for model-specific quotas
     pub model_limits: HashMap<String, u64>, // [NEW] max_output_tokens per model from quota data
+    pub tags: Vec<String>,                  // [NEW] 账号分组标签 (用于按 API Key 划分账号池)
+    pub quota_updated_at: Option<i64>,      // [NEW] 配额数据刷新时间 (用于判断配额是否过期)
+}
+
+impl ProxyToken {
+    /// 配额数据超过阈值未刷新 (视为未知)
+    pub fn is_quota_stale(&self, staleness_minutes: u32, now: i64) -> bool {
+        staleness_minutes > 0
+            && self
+                .quota_updated_at
+                .is_some_and(|t| now - t > i64::from(staleness_minutes) * 60)
+    }
 }
 
 pub struct TokenManager {
     tokens: Arc<DashMap<String, ProxyToken>>, // account_id -> ProxyToken
     current_index: Arc<AtomicUsize>,
     last_used_account: Arc<tokio::sync::Mutex<Option<(String, std::time::Instant)>>>,
-    data_dir: PathBuf,
+    data_dir: Arc<parking_lot::RwLock<PathBuf>>, // 数据目录迁移后由 set_data_dir 切换
     rate_limit_tracker: Arc<RateLimitTracker>, // 新增: 限流跟踪器
     sticky_config: Arc<tokio::sync::RwLock<StickySessionConfig>>, // 新增：调度配置
     session_accounts: Arc<DashMap<String, String>>, // 新增：会话与账号映射 (SessionID -> AccountID)
     preferred_account_id: Arc<tokio::sync::RwLock<Option<String>>>, // [FIX #820] 优先使用的账号ID（固定账号模式）
     health_scores: Arc<DashMap<String, f32>>,                       // account_id -> health_score
     circuit_breaker_config: Arc<tokio::sync::RwLock<crate::models::CircuitBreakerConfig>>, // [NEW] 熔断配置缓存
+    last_selections: Arc<DashMap<String, crate::proxy::token_state::LastSelection>>, // [NEW] 模型 -> 最近一次选中的账号
     /// 支持优雅关闭时主动 abort 后台任务
     auto_cleanup_handle: Arc<tokio::sync::Mutex<Option<tokio::task::JoinHandle<()>>>>,
     cancel_token: CancellationToken,
@@ -62,7 +139,7 @@ impl TokenManager {
             tokens: Arc::new(DashMap::new()),
             current_index: Arc::new(AtomicUsize::new(0)),
             last_used_account: Arc::new(tokio::sync::Mutex::new(None)),
-            data_dir,
+            data_dir: Arc::new(parking_lot::RwLock::new(data_dir)),
             rate_limit_tracker: Arc::new(RateLimitTracker::new()),
             sticky_config: Arc::new(tokio::sync::RwLock::new(StickySessionConfig::default())),
             session_accounts: Arc::new(DashMap::new()),
@@ -71,14 +148,26 @@ impl TokenManager {
             circuit_breaker_config: Arc::new(tokio::sync::RwLock::new(
                 crate::models::CircuitBreakerConfig::default(),
             )),
+            last_selections: Arc::new(DashMap::new()),
             auto_cleanup_handle: Arc::new(tokio::sync::Mutex::new(None)),
             cancel_token: CancellationToken::new(),
         }
     }
 
-    /// 启动限流记录自动清理后台任务（每15秒检查并清除过期记录）
+    fn data_dir(&self) -> PathBuf {
+        self.data_dir.read().clone()
+    }
+
+    /// 切换数据目录 (数据目录迁移后调用，随后需 load_accounts)
+    pub fn set_data_dir(&self, data_dir: PathBuf) {
+        *self.data_dir.write() = data_dir;
+    }
+
+    /// 启动限流记录自动清理后台任务（每15秒检查并清除过期记录，并检查受监控模型的可用性）
     pub async fn start_auto_cleanup(&self) {
         let tracker = self.rate_limit_tracker.clone();
+        let tokens = self.tokens.clone();
+        let circuit_breaker_config = self.circuit_breaker_config.clone();
         let cancel = self.cancel_token.child_token();
 
         let handle = tokio::spawn(async move {
@@ -97,6 +186,13 @@ impl TokenManager {
                                 cleaned
                             );
                         }
+
+                        // [NEW] 模型可用性: 某个受监控模型无账号可用、或低于容量阈值时告警
+                        let app_config = crate::modules::config::load_app_config().unwrap_or_default();
+                        let breaker_enabled = circuit_breaker_config.read().await.enabled;
+                        let report = Self::availability_of(&tokens, &tracker, breaker_enabled, &app_config);
+                        crate::proxy::model_availability::observe(&report);
+                        crate::proxy::capacity_planner::observe(&report, &app_config.capacity_alerts);
                     }
                 }
             }
@@ -113,9 +209,69 @@ impl TokenManager {
         tracing::info!("Rate limit auto-cleanup task started (interval: 15s)");
     }
 
+    /// 受监控模型 (配额保护的 monitored_models 与容量阈值中的模型分组) 的可用账号数
+    pub async fn model_availability(&self) -> Vec<crate::proxy::model_availability::ModelAvailability> {
+        let app_config = crate::modules::config::load_app_config().unwrap_or_default();
+        let breaker_enabled = self.circuit_breaker_config.read().await.enabled;
+        Self::availability_of(&self.tokens, &self.rate_limit_tracker, breaker_enabled, &app_config)
+    }
+
+    /// 各容量阈值的当前状态
+    pub async fn capacity_status(&self) -> Vec<crate::proxy::capacity_planner::CapacityStatus> {
+        let app_config = crate::modules::config::load_app_config().unwrap_or_default();
+        let breaker_enabled = self.circuit_breaker_config.read().await.enabled;
+        let report =
+            Self::availability_of(&self.tokens, &self.rate_limit_tracker, breaker_enabled, &app_config);
+        crate::proxy::capacity_planner::evaluate(&report, &app_config.capacity_alerts)
+    }
+
+    /// 内存状态快照 (排查路由问题，不含 token)
+    pub async fn state_snapshot(&self) -> crate::proxy::token_state::TokenManagerState {
+        use crate::proxy::token_state;
+        let now = chrono::Utc::now().timestamp();
+        let last_used_account = self
+            .last_used_account
+            .lock()
+            .await
+            .as_ref()
+            .map(|(id, at)| (id.clone(), at.elapsed().as_secs()));
+        token_state::TokenManagerState {
+            generated_at: now,
+            scheduling: self.sticky_config.read().await.clone(),
+            preferred_account_id: self.preferred_account_id.read().await.clone(),
+            last_used_account,
+            session_bindings: self.session_accounts.len(),
+            accounts: token_state::accounts(&self.tokens, &self.health_scores, now),
+            cooldowns: token_state::cooldowns(&self.rate_limit_tracker),
+            last_selections: token_state::last_selections(&self.last_selections),
+        }
+    }
+
+    fn availability_of(
+        tokens: &DashMap<String, ProxyToken>,
+        tracker: &RateLimitTracker,
+        breaker_enabled: bool,
+        app_config: &crate::models::AppConfig,
+    ) -> Vec<crate::proxy::model_availability::ModelAvailability> {
+        let mut models = app_config.quota_protection.monitored_models.clone();
+        for group in crate::proxy::capacity_planner::groups(&app_config.capacity_alerts) {
+            if !models.contains(&group) {
+                models.push(group);
+            }
+        }
+        crate::proxy::model_availability::compute(
+            tokens,
+            &models,
+            tracker,
+            breaker_enabled,
+            crate::proxy::config::get_quota_staleness_minutes(),
+            chrono::Utc::now().timestamp(),
+        )
+    }
+
     /// 从主应用账号目录加载所有账号
     pub async fn load_accounts(&self) -> Result<usize, String> {
-        let accounts_dir = self.data_dir.join("accounts");
+        let accounts_dir = self.data_dir().join("accounts");
 
         if !accounts_dir.exists() {
             return Err(format!("账号目录不存在: {:?}", accounts_dir));
@@ -164,7 +320,7 @@ impl TokenManager {
     /// 重新加载指定账号（用于配额更新后的实时同步）
     pub async fn reload_account(&self, account_id: &str) -> Result<(), String> {
         let path = self
-            .data_dir
+            .data_dir()
             .join("accounts")
             .join(format!("{}.json", account_id));
         if !path.exists() {
@@ -529,6 +685,13 @@ impl TokenManager {
             })
             .unwrap_or(crate::models::AccountProvider::Google);
 
+        // 同步账号的出站地址族覆盖 (modules::http 按账号选择 DNS 解析族)
+        let ip_family = account
+            .get("ip_family")
+            .cloned()
+            .and_then(|v| serde_json::from_value::<crate::models::IpFamily>(v).ok());
+        crate::modules::http::set_account_ip_family(&account_id, ip_family);
+
         Ok(Some(ProxyToken {
             account_id,
             access_token,
@@ -549,6 +712,20 @@ impl TokenManager {
             validation_url: account.get("validation_url").and_then(|v| v.as_str()).map(|s| s.to_string()),
             model_quotas,
             model_limits,
+            tags: account
+                .get("tags")
+                .and_then(|v| v.as_array())
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(|v| v.as_str())
+                        .map(|s| s.to_string())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            quota_updated_at: account
+                .get("quota")
+                .and_then(|q| q.get("last_updated"))
+                .and_then(|v| v.as_i64()),
         }))
     }
 
@@ -779,7 +956,7 @@ impl TokenManager {
                 .map_err(|e| format!("写入文件失败: {}", e))?;
 
             // [FIX] 触发 TokenManager 的账号重新加载信号，确保内存中的 protected_models 同步
-            crate::proxy::server::trigger_account_reload(account_id);
+            crate::modules::events::account_reload(account_id);
 
             return Ok(true);
         }
@@ -858,6 +1035,10 @@ impl TokenManager {
                     serde_json::to_string_pretty(account_json).unwrap(),
                 )
                 .map_err(|e| format!("写入文件失败: {}", e))?;
+                crate::modules::scheduler::schedule_warmup_after_reenable(
+                    account_id,
+                    "quota recovered",
+                );
                 return Ok(true);
             }
         }
@@ -1009,6 +1190,24 @@ impl TokenManager {
             );
         }
 
+        // project_id 切换：重新加载账号 (新 project_id) 并解除绑定在旧项目上的粘性会话
+        let pending_rebind = crate::proxy::server::take_pending_project_rebinds();
+        for account_id in pending_rebind {
+            self.session_accounts.retain(|_, v| v != &account_id);
+            if let Err(e) = self.reload_account(&account_id).await {
+                tracing::warn!(
+                    "[ProjectBinding] Failed to reload account {}: {}",
+                    account_id,
+                    e
+                );
+            } else {
+                tracing::info!(
+                    "[ProjectBinding] Rebound account {} to its new project",
+                    account_id
+                );
+            }
+        }
+
         // 【优化 Issue #284】添加 5 秒超时，防止死锁
         let timeout_duration = std::time::Duration::from_secs(5);
         match tokio::time::timeout(
@@ -1017,13 +1216,129 @@ impl TokenManager {
         )
         .await
         {
-            Ok(result) => result,
+            Ok(result) => {
+                if let Ok((_, _, email, account_id, _)) = result.as_ref() {
+                    self.flag_stale_quota(account_id, email);
+                    self.last_selections.insert(
+                        target_model.to_string(),
+                        crate::proxy::token_state::LastSelection {
+                            model: target_model.to_string(),
+                            account_id: account_id.clone(),
+                            email: email.clone(),
+                            selected_at: chrono::Utc::now().timestamp(),
+                        },
+                    );
+                }
+                result
+            }
             Err(_) => Err(
                 "Token acquisition timeout (5s) - system too busy or deadlock detected".to_string(),
             ),
         }
     }
 
+    /// 选中的账号配额已过期时记录告警，并排队刷新该账号配额 (刷新完成后自动重新加载)
+    fn flag_stale_quota(&self, account_id: &str, email: &str) {
+        let staleness = crate::proxy::config::get_quota_staleness_minutes();
+        let now = chrono::Utc::now().timestamp();
+        let Some(updated_at) = self
+            .tokens
+            .get(account_id)
+            .filter(|t| t.is_quota_stale(staleness, now))
+            .and_then(|t| t.quota_updated_at)
+        else {
+            return;
+        };
+        if crate::modules::account::queue_stale_quota_refresh(account_id) {
+            tracing::warn!(
+                "[Quota-Stale] {} selected with quota data {} min old, refresh queued",
+                email,
+                (now - updated_at) / 60
+            );
+        }
+    }
+
+    /// 获取 Token，若目标模型在所有账号上均被保护/耗尽，则按降级链依次尝试替代模型
+    /// 返回值第二项为实际使用的替代模型 (未降级时为 None)
+    pub async fn get_token_with_fallback(
+        &self,
+        quota_group: &str,
+        force_rotate: bool,
+        session_id: Option<&str>,
+        target_model: &str,
+        fallbacks: &[String],
+    ) -> Result<((String, String, String, String, u64), Option<String>), String> {
+        let first_error = match self
+            .get_token(quota_group, force_rotate, session_id, target_model)
+            .await
+        {
+            Ok(token) => return Ok((token, None)),
+            Err(e) => e,
+        };
+
+        if fallbacks.is_empty() || !self.is_model_unavailable(target_model) {
+            return Err(first_error);
+        }
+
+        for fallback in fallbacks {
+            match self
+                .get_token(quota_group, force_rotate, session_id, fallback)
+                .await
+            {
+                Ok(token) => {
+                    tracing::warn!(
+                        "[Fallback-Chain] {} unavailable ({}), falling back to {}",
+                        target_model,
+                        first_error,
+                        fallback
+                    );
+                    return Ok((token, Some(fallback.clone())));
+                }
+                Err(e) => {
+                    tracing::debug!("[Fallback-Chain] Fallback {} unavailable: {}", fallback, e);
+                }
+            }
+        }
+
+        Err(first_error)
+    }
+
+    /// 目标模型在当前分组的所有账号上均不可用: 被配额保护、没有剩余配额，
+    /// 或处于上游以 QUOTA_EXHAUSTED / MODEL_CAPACITY_EXHAUSTED 触发的冷却中。
+    /// 依据配额数据与限流记录中的上游原因码判断；分钟级限流、鉴权失败、空账号池都不算。
+    fn is_model_unavailable(&self, target_model: &str) -> bool {
+        let model = crate::proxy::common::model_mapping::normalize_to_standard_id(target_model)
+            .unwrap_or_else(|| target_model.to_string());
+        let account_group = current_account_group();
+        let pinned_account = crate::proxy::dry_run::pinned_account();
+        let staleness = crate::proxy::config::get_quota_staleness_minutes();
+        let now = chrono::Utc::now().timestamp();
+
+        let mut candidates = 0;
+        for entry in self.tokens.iter() {
+            let t = entry.value();
+            if account_group.as_deref().is_some_and(|g| !t.tags.iter().any(|tag| tag == g))
+                || pinned_account.as_deref().is_some_and(|id| t.account_id != id)
+            {
+                continue;
+            }
+            candidates += 1;
+            let protected = t.protected_models.contains(&model);
+            let exhausted = !t
+                .model_quotas
+                .get(&model)
+                .is_some_and(|&pct| pct > 0 || t.is_quota_stale(staleness, now));
+            let locked_out = self
+                .rate_limit_tracker
+                .active_limit(&t.account_id, Some(&model))
+                .is_some_and(|info| info.reason.is_model_exhaustion());
+            if !(protected || exhausted || locked_out) {
+                return false;
+            }
+        }
+        candidates > 0
+    }
+
     /// 内部实现：获取 Token 的核心逻辑
     async fn get_token_internal(
         &self,
@@ -1032,10 +1347,49 @@ impl TokenManager {
         session_id: Option<&str>,
         target_model: &str,
     ) -> Result<(String, String, String, String, u64), String> {
-        let mut tokens_snapshot: Vec<ProxyToken> =
-            self.tokens.iter().map(|e| e.value().clone()).collect();
+        // [NEW] 账号池分区: API Key 绑定了分组时，仅使用带有该标签的账号
+        let account_group = current_account_group();
+        // [NEW] 域名策略: exclude_from_proxy 的账号不参与轮换
+        let domain_policies = crate::modules::domain_policy::current();
+        // [NEW] 单账号试运行: 请求被锁定到指定账号时只考虑该账号
+        let pinned_account = crate::proxy::dry_run::pinned_account();
+        // [NEW] 排除时间窗口: 窗口内的账号 / 标签不参与轮换
+        let exclusion_windows = crate::modules::exclusion_windows::current();
+        let now_utc = chrono::Utc::now();
+        let in_group = |t: &ProxyToken| {
+            let grouped = match account_group.as_deref() {
+                Some(group) => t.tags.iter().any(|tag| tag == group),
+                None => true,
+            };
+            grouped
+                && pinned_account
+                    .as_deref()
+                    .is_none_or(|id| t.account_id == id)
+                && !crate::modules::domain_policy::rules_in(&domain_policies, &t.email)
+                    .exclude_from_proxy
+                && !crate::modules::exclusion_windows::excluded_in(
+                    &exclusion_windows,
+                    &t.account_id,
+                    &t.email,
+                    &t.tags,
+                    now_utc,
+                )
+        };
+
+        let mut tokens_snapshot: Vec<ProxyToken> = self
+            .tokens
+            .iter()
+            .map(|e| e.value().clone())
+            .filter(|t| in_group(t))
+            .collect();
         let mut total = tokens_snapshot.len();
         if total == 0 {
+            if let Some(account_id) = pinned_account.as_deref() {
+                return Err(format!("Pinned account not in proxy pool: {}", account_id));
+            }
+            if let Some(group) = account_group.as_deref() {
+                return Err(format!("No accounts in group: {}", group));
+            }
             return Err("Token pool is empty".to_string());
         }
 
@@ -1066,13 +1420,16 @@ impl TokenManager {
         // 如果 API 返回的配额信息不完整，可能会导致误杀，但为了严格性，我们执行此过滤
         // Codex 账号对 OpenAI 原生模型跳过 model_quotas 过滤，因为它们通过 api.openai.com 直接转发
         let pre_filter_count = tokens_snapshot.len();
+        let staleness_minutes = crate::proxy::config::get_quota_staleness_minutes();
+        let now = chrono::Utc::now().timestamp();
         tokens_snapshot.retain(|t| {
             if t.provider == crate::models::AccountProvider::Codex && is_openai_native_model {
                 return true; // Codex 账号接受所有 OpenAI 原生模型
             }
             // [FIX] 不仅检查模型 key 是否存在，还要求配额 > 0
+            // [NEW] 过期的配额视为未知，不因旧的 0% 排除 (选中后会排队刷新)
             match t.model_quotas.get(&normalized_target) {
-                Some(&pct) => pct > 0,
+                Some(&pct) => pct > 0 || t.is_quota_stale(staleness_minutes, now),
                 None => false,
             }
         });
@@ -1082,6 +1439,7 @@ impl TokenManager {
         if tokens_snapshot.is_empty() && pre_filter_count > 0 {
             tokens_snapshot = self.tokens.iter()
                 .map(|entry| entry.value().clone())
+                .filter(|t| in_group(t))
                 .filter(|t| {
                     if t.provider == crate::models::AccountProvider::Codex && is_openai_native_model {
                         return true;
@@ -1102,6 +1460,20 @@ impl TokenManager {
             }
         }
 
+        // [NEW] 订阅等级路由: 模型被固定到指定等级时，仅保留对应等级的账号
+        let tier_routing = crate::proxy::config::get_tier_routing();
+        if let Some(allowed) = pinned_tier_ranks(target_model, &normalized_target, &tier_routing) {
+            tokens_snapshot.retain(|t| allowed.contains(&tier_rank(&t.subscription_tier)));
+            if tokens_snapshot.is_empty() && candidate_count_before > 0 {
+                tracing::warn!("No accounts in pinned tier for model: {}", normalized_target);
+                return Err(format!(
+                    "No accounts available with quota for model: {} (pinned tier)",
+                    normalized_target
+                ));
+            }
+        }
+        let premium = is_premium_model(target_model, &tier_routing);
+
         if tokens_snapshot.is_empty() {
             if candidate_count_before > 0 {
                 // 如果过滤前有账号，过滤后没了，说明所有账号都没有该模型的配额
@@ -1112,21 +1484,10 @@ impl TokenManager {
         }
 
         tokens_snapshot.sort_by(|a, b| {
-            // Priority 0: 严格的订阅等级排序 (ULTRA > PRO > FREE)
-            // 用户要求：轮询应当遵循 Ultra -> Pro -> Free
-            // 既然已经过滤掉了不支持该模型的账号，剩下的都是支持的
-            // 此时我们优先使用高级订阅
-            let tier_priority = |tier: &Option<String>| {
-                let t = tier.as_deref().unwrap_or("").to_lowercase();
-                if t.contains("ultra") { 0 }
-                else if t.contains("pro") { 1 }
-                else if t.contains("free") { 2 }
-                else { 3 }
-            };
+            let tier_cmp = tier_rank(&a.subscription_tier).cmp(&tier_rank(&b.subscription_tier));
 
-            let tier_cmp = tier_priority(&a.subscription_tier)
-                .cmp(&tier_priority(&b.subscription_tier));
-            if tier_cmp != std::cmp::Ordering::Equal {
+            // Priority 0: 高端模型严格按订阅等级排序 (ULTRA > PRO > FREE)
+            if premium && tier_cmp != std::cmp::Ordering::Equal {
                 return tier_cmp;
             }
 
@@ -1147,6 +1508,11 @@ impl TokenManager {
                 return health_cmp;
             }
 
+            // Priority 2.5: 普通模型以订阅等级作为次级排序
+            if tier_cmp != std::cmp::Ordering::Equal {
+                return tier_cmp;
+            }
+
             // Priority 3: Reset time (earlier is better, but only if diff > 10 min)
             let reset_a = a.reset_time.unwrap_or(i64::MAX);
             let reset_b = b.reset_time.unwrap_or(i64::MAX);
@@ -1254,8 +1620,8 @@ impl TokenManager {
                     // 直接使用优先账号，跳过轮询逻辑
                     let mut token = preferred_token.clone();
 
-                    // 检查 token 是否过期（提前5分钟刷新）
-                    let now = chrono::Utc::now().timestamp();
+                    // 检查 token 是否过期（提前5分钟刷新，使用校正后的时间）
+                    let now = crate::modules::clock_skew::now();
                     if now >= token.timestamp - 300 {
                         tracing::debug!("账号 {} 的 token 即将过期，正在刷新...", token.email);
                         match token.provider {
@@ -1621,8 +1987,8 @@ impl TokenManager {
                 OnDiskAccountState::Enabled => {}
             }
 
-            // 3. 检查 token 是否过期（提前5分钟刷新）
-            let now = chrono::Utc::now().timestamp();
+            // 3. 检查 token 是否过期（提前5分钟刷新，使用校正后的时间）
+            let now = crate::modules::clock_skew::now();
             if now >= token.timestamp - 300 {
                 tracing::debug!("账号 {} 的 token 即将过期，正在刷新...", token.email);
 
@@ -1684,7 +2050,7 @@ impl TokenManager {
                     }
                     Err(e) => {
                         tracing::error!("Token 刷新失败 ({}): {}，尝试下一个账号", token.email, e);
-                        if e.contains("\"invalid_grant\"") || e.contains("invalid_grant") {
+                        if crate::error::is_invalid_grant(&e) {
                             tracing::error!(
                                 "Disabling account due to invalid_grant ({}): refresh_token likely revoked/expired",
                                 token.email
@@ -1767,7 +2133,7 @@ impl TokenManager {
         let path = if let Some(entry) = self.tokens.get(account_id) {
             entry.account_path.clone()
         } else {
-            self.data_dir
+            self.data_dir()
                 .join("accounts")
                 .join(format!("{}.json", account_id))
         };
@@ -1789,6 +2155,16 @@ impl TokenManager {
         self.tokens.remove(account_id);
 
         tracing::warn!("Account disabled: {} ({:?})", account_id, path);
+        crate::modules::notify::push(
+            crate::modules::notify::Severity::Error,
+            "proxy",
+            format!(
+                "Account disabled: {}",
+                content["email"].as_str().unwrap_or(account_id)
+            ),
+            truncate_reason(reason, 300),
+            Some(&format!("account_disabled:{}", account_id)),
+        );
         Ok(())
     }
 
@@ -1807,6 +2183,12 @@ impl TokenManager {
 
         std::fs::write(path, serde_json::to_string_pretty(&content).unwrap())
             .map_err(|e| format!("写入文件失败: {}", e))?;
+        crate::modules::project_binding::observe(
+            account_id,
+            &entry.email,
+            project_id,
+            "token_manager",
+        );
 
         tracing::debug!("已保存 project_id 到账号 {}", account_id);
         Ok(())
@@ -1823,7 +2205,7 @@ impl TokenManager {
             &std::fs::read_to_string(path).map_err(|e| format!("读取文件失败: {}", e))?
         ).map_err(|e| format!("解析 JSON 失败: {}", e))?;
 
-        let now = chrono::Utc::now().timestamp();
+        let now = crate::modules::clock_skew::now();
 
         content["token"]["access_token"] = serde_json::Value::String(token_response.access_token.clone());
         content["token"]["expires_in"] = serde_json::Value::Number(token_response.expires_in.into());
@@ -1881,7 +2263,7 @@ impl TokenManager {
                         token.refresh_token.clone(),
                         token.timestamp,
                         token.expires_in,
-                        chrono::Utc::now().timestamp(),
+                        crate::modules::clock_skew::now(),
                         token.project_id.clone(),
                         token.provider.clone(),
                     ));
@@ -1952,7 +2334,7 @@ impl TokenManager {
                 match crate::modules::oauth::refresh_access_token(&refresh_token, Some(&account_id)).await {
                     Ok(token_response) => {
                         tracing::info!("[Warmup] Token refresh successful for {}", email);
-                        let new_now = chrono::Utc::now().timestamp();
+                        let new_now = crate::modules::clock_skew::now();
 
                         // 更新缓存
                         if let Some(mut entry) = self.tokens.get_mut(&account_id) {
@@ -2138,6 +2520,25 @@ impl TokenManager {
         false
     }
 
+    /// 当前请求作用域 (账号分组) 内可接收请求的账号数：排除账号级限流与验证封锁
+    /// 供准入控制估算账号池容量，不区分模型
+    pub async fn eligible_account_count(&self) -> usize {
+        let account_group = current_account_group();
+        let breaker_enabled = self.circuit_breaker_config.read().await.enabled;
+        let now = chrono::Utc::now().timestamp();
+        self.tokens
+            .iter()
+            .filter(|e| {
+                let t = e.value();
+                account_group
+                    .as_deref()
+                    .is_none_or(|group| t.tags.iter().any(|tag| tag == group))
+                    && !(t.validation_blocked && t.validation_blocked_until > now)
+                    && !(breaker_enabled && self.rate_limit_tracker.is_rate_limited(&t.account_id, None))
+            })
+            .count()
+    }
+
     /// 从账号文件获取配额刷新时间
     ///
     /// 返回该账号最近的配额刷新时间字符串（ISO 8601 格式）
@@ -2146,7 +2547,7 @@ impl TokenManager {
     /// - `account_id`: 账号 ID（用于查找账号文件）
     pub fn get_quota_reset_time(&self, account_id: &str) -> Option<String> {
         // 直接用 account_id 查找账号文件（文件名是 {account_id}.json）
-        let account_path = self.data_dir.join("accounts").join(format!("{}.json", account_id));
+        let account_path = self.data_dir().join("accounts").join(format!("{}.json", account_id));
 
         let content = std::fs::read_to_string(&account_path).ok()?;
         let account: serde_json::Value = serde_json::from_str(&content).ok()?;
@@ -2281,15 +2682,20 @@ impl TokenManager {
         error_body: &str,
         model: Option<&str>, // 🆕 新增模型参数
     ) {
+        // [FIX] Convert email to account_id for consistent tracking
+        let account_id = self.email_to_account_id(email).unwrap_or_else(|| email.to_string());
+
+        // 配额类 429: 尽快刷新该账号配额，让配额保护在几秒内生效 (与熔断开关无关)
+        if is_quota_error(status, error_body) && self.tokens.contains_key(&account_id) {
+            crate::modules::account::queue_rate_limit_quota_refresh(&account_id);
+        }
+
         // [NEW] 检查熔断是否启用
         let config = self.circuit_breaker_config.read().await.clone();
         if !config.enabled {
             return;
         }
 
-        // [FIX] Convert email to account_id for consistent tracking
-        let account_id = self.email_to_account_id(email).unwrap_or_else(|| email.to_string());
-
         // 检查 API 是否返回了精确的重试时间
         let has_explicit_retry_time = retry_after_header.is_some() ||
             error_body.contains("quotaResetDelay");
@@ -2601,7 +3007,7 @@ impl TokenManager {
         }
 
         // 2. Persist to disk
-        let path = self.data_dir.join("accounts").join(format!("{}.json", account_id));
+        let path = self.data_dir().join("accounts").join(format!("{}.json", account_id));
         if !path.exists() {
              return Err(format!("Account file not found: {:?}", path));
         }
@@ -2695,6 +3101,16 @@ impl TokenManager {
 }
 
 /// 截断过长的原因字符串
+/// 429 caused by exhausted quota (not plain request-rate or capacity limits)
+fn is_quota_error(status: u16, error_body: &str) -> bool {
+    if status != 429 {
+        return false;
+    }
+    let body = error_body.to_lowercase();
+    !body.contains("model_capacity")
+        && (body.contains("quota") || body.contains("resource_exhausted"))
+}
+
 fn truncate_reason(reason: &str, max_len: usize) -> String {
     if reason.len() <= max_len {
         reason.to_string()
@@ -2715,6 +3131,14 @@ mod tests {
     use super::*;
     use std::cmp::Ordering;
 
+    #[test]
+    fn test_is_quota_error() {
+        assert!(is_quota_error(429, r#"{"error":{"status":"RESOURCE_EXHAUSTED","details":[{"quotaResetDelay":"3h"}]}}"#));
+        assert!(is_quota_error(429, "Quota exceeded for model"));
+        assert!(!is_quota_error(429, "MODEL_CAPACITY_EXHAUSTED"));
+        assert!(!is_quota_error(503, "quota"));
+    }
+
     #[tokio::test]
     async fn test_reload_account_purges_cache_when_account_becomes_proxy_disabled() {
         let tmp_root = std::env::temp_dir().join(format!(
@@ -2833,6 +3257,88 @@ mod tests {
         let _ = std::fs::remove_dir_all(&tmp_root);
     }
 
+    #[tokio::test]
+    async fn test_account_group_restricts_candidates() {
+        let tmp_root = std::env::temp_dir().join(format!(
+            "antigravity-token-manager-test-group-{}",
+            uuid::Uuid::new_v4()
+        ));
+        let accounts_dir = tmp_root.join("accounts");
+        std::fs::create_dir_all(&accounts_dir).unwrap();
+
+        let now = chrono::Utc::now().timestamp();
+        let write_account = |id: &str, tag: &str| {
+            let json = serde_json::json!({
+                "id": id,
+                "email": format!("{}@test.com", id),
+                "token": {
+                    "access_token": format!("atk-{}", id),
+                    "refresh_token": format!("rtk-{}", id),
+                    "expires_in": 3600,
+                    "expiry_timestamp": now + 3600,
+                    "project_id": format!("pid-{}", id)
+                },
+                "quota": { "models": [{ "name": "gemini-3-flash", "percentage": 100 }] },
+                "tags": [tag],
+                "created_at": now,
+                "last_used": now
+            });
+            std::fs::write(
+                accounts_dir.join(format!("{}.json", id)),
+                serde_json::to_string_pretty(&json).unwrap(),
+            )
+            .unwrap();
+        };
+        write_account("acc1", "teamA");
+        write_account("acc2", "teamB");
+
+        let manager = TokenManager::new(tmp_root.clone());
+        manager.load_accounts().await.unwrap();
+
+        for _ in 0..3 {
+            let (_, _, _, account_id, _) = scope_account_group(
+                Some("teamB".to_string()),
+                manager.get_token("gemini", true, None, "gemini-3-flash"),
+            )
+            .await
+            .unwrap();
+            assert_eq!(account_id, "acc2");
+        }
+
+        let err = scope_account_group(
+            Some("teamC".to_string()),
+            manager.get_token("gemini", false, None, "gemini-3-flash"),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err, "No accounts in group: teamC");
+
+        let _ = std::fs::remove_dir_all(&tmp_root);
+    }
+
+    #[test]
+    fn test_tier_routing_helpers() {
+        let mut routing = crate::proxy::config::TierRoutingConfig::default();
+        assert!(is_premium_model("Claude-Opus-4-6", &routing));
+        assert!(!is_premium_model("claude-sonnet-4-5", &routing));
+        assert!(pinned_tier_ranks("gemini-3-flash", "gemini-3-flash", &routing).is_none());
+
+        routing
+            .model_tiers
+            .insert("gemini-3-pro-*".to_string(), vec!["ULTRA".to_string(), "Pro".to_string()]);
+        routing
+            .model_tiers
+            .insert("claude-sonnet-4-5".to_string(), vec!["FREE".to_string()]);
+
+        let ranks = pinned_tier_ranks("gemini-3-pro-high", "gemini-3-pro-high", &routing).unwrap();
+        assert_eq!(ranks, HashSet::from([0, 1]));
+        let ranks = pinned_tier_ranks("claude-sonnet-4-5-20250929", "claude-sonnet-4-5", &routing).unwrap();
+        assert_eq!(ranks, HashSet::from([2]));
+
+        assert_eq!(tier_rank(&Some("g1-ultra-tier".to_string())), 0);
+        assert_eq!(tier_rank(&None), 3);
+    }
+
     #[tokio::test]
     async fn test_sticky_session_skips_bound_account_when_disabled_on_disk_without_reload() {
         let tmp_root = std::env::temp_dir().join(format!(
@@ -2935,7 +3441,9 @@ mod tests {
             validation_url: None,
             model_quotas: HashMap::new(),
             model_limits: HashMap::new(),
+            tags: Vec::new(),
             provider: crate::models::AccountProvider::Google,
+            quota_updated_at: None,
         }
     }
 
@@ -3194,7 +3702,9 @@ mod tests {
             validation_url: None,
             model_quotas: HashMap::new(),
             model_limits: HashMap::new(),
+            tags: Vec::new(),
             provider: crate::models::AccountProvider::Google,
+            quota_updated_at: None,
         }
     }
 
@@ -3541,4 +4051,50 @@ mod tests {
             "Sonnet should sort by quota first, then by tier as tiebreaker"
         );
     }
+
+    #[test]
+    fn test_model_unavailable_uses_quota_data_and_upstream_reason() {
+        use crate::proxy::rate_limit::RateLimitReason;
+        let manager = TokenManager::new(PathBuf::from("/tmp/test"));
+        let lockout = std::time::SystemTime::now() + std::time::Duration::from_secs(600);
+        let model = "claude-sonnet-4-6";
+
+        // 空账号池不算模型不可用
+        assert!(!manager.is_model_unavailable(model));
+
+        let mut protected = create_test_token("protected@test.com", Some("PRO"), 1.0, None, Some(50));
+        protected.model_quotas.insert("claude".to_string(), 50);
+        protected.protected_models.insert("claude".to_string());
+        let mut empty = create_test_token("empty@test.com", Some("PRO"), 1.0, None, Some(50));
+        empty.model_quotas.insert("claude".to_string(), 0);
+        let mut locked = create_test_token("locked@test.com", Some("PRO"), 1.0, None, Some(50));
+        locked.model_quotas.insert("claude".to_string(), 80);
+        for t in [protected, empty, locked] {
+            manager.tokens.insert(t.account_id.clone(), t);
+        }
+
+        // 上游返回分钟级限流: 稍后即可恢复，不触发降级
+        manager.rate_limit_tracker.set_lockout_until(
+            "locked@test.com",
+            lockout,
+            RateLimitReason::RateLimitExceeded,
+            Some("claude".to_string()),
+        );
+        assert!(!manager.is_model_unavailable(model));
+
+        // 上游返回 QUOTA_EXHAUSTED: 所有账号均不可用
+        manager.rate_limit_tracker.set_lockout_until(
+            "locked@test.com",
+            lockout,
+            RateLimitReason::QuotaExhausted,
+            Some("claude".to_string()),
+        );
+        assert!(manager.is_model_unavailable(model));
+
+        // 仍有一个可用账号时不降级
+        let mut healthy = create_test_token("healthy@test.com", Some("PRO"), 1.0, None, Some(50));
+        healthy.model_quotas.insert("claude".to_string(), 30);
+        manager.tokens.insert(healthy.account_id.clone(), healthy);
+        assert!(!manager.is_model_unavailable(model));
+    }
 }
@@ -0,0 +1,173 @@
+// TokenManager 内存状态快照
+// 路由行为异常时用于排查 (以前只能重启反代碰运气): 已加载的账号、access_token 过期时间、健康分、
+// 受保护模型、限流冷却、会话绑定数，以及每个模型最近一次选中的账号。快照不包含任何 token。
+
+use dashmap::DashMap;
+use serde::Serialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::proxy::rate_limit::RateLimitTracker;
+use crate::proxy::sticky_config::StickySessionConfig;
+use crate::proxy::token_manager::ProxyToken;
+
+/// 某个模型最近一次选号结果
+#[derive(Debug, Clone, Serialize)]
+pub struct LastSelection {
+    pub model: String,
+    pub account_id: String,
+    pub email: String,
+    pub selected_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenAccountState {
+    pub account_id: String,
+    pub email: String,
+    pub provider: crate::models::AccountProvider,
+    pub subscription_tier: Option<String>,
+    pub tags: Vec<String>,
+    /// 内存中 access_token 的过期时间戳
+    pub access_token_expires_at: i64,
+    /// 距过期的秒数 (已过期为负数，下次选中时刷新)
+    pub access_token_expires_in: i64,
+    pub health_score: f32,
+    pub protected_models: Vec<String>,
+    pub validation_blocked_until: Option<i64>,
+    pub model_quotas: std::collections::BTreeMap<String, i32>,
+    pub quota_updated_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CooldownState {
+    pub account_id: String,
+    /// None 表示账号级限流
+    pub model: Option<String>,
+    pub reason: String,
+    pub reset_at: i64,
+    pub remaining_secs: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenManagerState {
+    pub generated_at: i64,
+    pub scheduling: StickySessionConfig,
+    pub preferred_account_id: Option<String>,
+    /// 最近一次使用的账号及距今秒数
+    pub last_used_account: Option<(String, u64)>,
+    pub session_bindings: usize,
+    pub accounts: Vec<TokenAccountState>,
+    pub cooldowns: Vec<CooldownState>,
+    pub last_selections: Vec<LastSelection>,
+}
+
+fn unix_secs(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+pub fn accounts(
+    tokens: &DashMap<String, ProxyToken>,
+    health_scores: &DashMap<String, f32>,
+    now: i64,
+) -> Vec<TokenAccountState> {
+    let mut accounts: Vec<TokenAccountState> = tokens
+        .iter()
+        .map(|entry| {
+            let t = entry.value();
+            let mut protected_models: Vec<String> = t.protected_models.iter().cloned().collect();
+            protected_models.sort();
+            TokenAccountState {
+                account_id: t.account_id.clone(),
+                email: t.email.clone(),
+                provider: t.provider.clone(),
+                subscription_tier: t.subscription_tier.clone(),
+                tags: t.tags.clone(),
+                access_token_expires_at: t.timestamp,
+                access_token_expires_in: t.timestamp - now,
+                health_score: health_scores
+                    .get(&t.account_id)
+                    .map(|s| *s)
+                    .unwrap_or(t.health_score),
+                protected_models,
+                validation_blocked_until: (t.validation_blocked
+                    && t.validation_blocked_until > now)
+                    .then_some(t.validation_blocked_until),
+                model_quotas: t
+                    .model_quotas
+                    .iter()
+                    .map(|(k, v)| (k.clone(), *v))
+                    .collect(),
+                quota_updated_at: t.quota_updated_at,
+            }
+        })
+        .collect();
+    accounts.sort_by(|a, b| a.email.cmp(&b.email));
+    accounts
+}
+
+pub fn cooldowns(tracker: &RateLimitTracker) -> Vec<CooldownState> {
+    let now = SystemTime::now();
+    let mut cooldowns: Vec<CooldownState> = tracker
+        .active()
+        .into_iter()
+        .map(|(key, info)| {
+            let account_id = match info.model.as_deref() {
+                Some(model) => key
+                    .strip_suffix(model)
+                    .and_then(|k| k.strip_suffix(':'))
+                    .unwrap_or(&key)
+                    .to_string(),
+                None => key.clone(),
+            };
+            CooldownState {
+                account_id,
+                model: info.model.clone(),
+                reason: format!("{:?}", info.reason),
+                reset_at: unix_secs(info.reset_time),
+                remaining_secs: info
+                    .reset_time
+                    .duration_since(now)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0),
+            }
+        })
+        .collect();
+    cooldowns.sort_by_key(|c| std::cmp::Reverse(c.remaining_secs));
+    cooldowns
+}
+
+pub fn last_selections(selections: &DashMap<String, LastSelection>) -> Vec<LastSelection> {
+    let mut list: Vec<LastSelection> = selections.iter().map(|e| e.value().clone()).collect();
+    list.sort_by(|a, b| a.model.cmp(&b.model));
+    list
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proxy::rate_limit::RateLimitReason;
+    use std::time::Duration;
+
+    #[test]
+    fn test_cooldowns_split_model_keys() {
+        let tracker = RateLimitTracker::new();
+        let reset = SystemTime::now() + Duration::from_secs(120);
+        tracker.set_lockout_until("acc-1", reset, RateLimitReason::QuotaExhausted, None);
+        tracker.set_lockout_until(
+            "acc-2",
+            reset + Duration::from_secs(60),
+            RateLimitReason::RateLimitExceeded,
+            Some("claude".to_string()),
+        );
+
+        let list = cooldowns(&tracker);
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[0].account_id, "acc-2");
+        assert_eq!(list[0].model.as_deref(), Some("claude"));
+        assert_eq!(list[0].reason, "RateLimitExceeded");
+        assert!(list[0].remaining_secs > list[1].remaining_secs);
+        assert_eq!(list[1].account_id, "acc-1");
+        assert_eq!(list[1].model, None);
+    }
+}
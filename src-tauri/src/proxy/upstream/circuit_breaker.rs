@@ -0,0 +1,210 @@
+// 上游端点熔断器
+// 某个端点连续失败 (网络错误 / 5xx) 达到阈值后熔断 (open)，期间直接跳过该端点快速失败，
+// 避免上游故障时所有请求都卡在超时上；熔断到期后放行一个探测请求 (half-open)：
+// - 探测成功 -> 恢复 (closed)
+// - 探测失败 -> 重新熔断
+// 429 / 404 等与端点健康无关的状态不计入失败
+
+use crate::proxy::config::UpstreamCircuitBreakerConfig;
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// 熔断时返回的错误前缀，便于调用方识别
+pub const CIRCUIT_OPEN_ERROR: &str = "upstream_circuit_open";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    Closed { failures: u32 },
+    Open { until: Instant },
+    /// 探测请求进行中；探测超过熔断时长仍未返回 (例如请求被取消) 时允许重新探测
+    HalfOpen { probe_started: Instant },
+}
+
+static BREAKERS: Lazy<DashMap<String, State>> = Lazy::new(DashMap::new);
+
+/// 端点处于熔断中
+#[derive(Debug, Clone)]
+pub struct CircuitOpen {
+    pub endpoint: String,
+    pub retry_after_secs: u64,
+}
+
+impl std::fmt::Display for CircuitOpen {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} is failing, retry in {}s",
+            CIRCUIT_OPEN_ERROR, self.endpoint, self.retry_after_secs
+        )
+    }
+}
+
+fn try_acquire_at(
+    endpoint: &str,
+    cfg: &UpstreamCircuitBreakerConfig,
+    now: Instant,
+) -> Result<(), CircuitOpen> {
+    if !cfg.enabled {
+        return Ok(());
+    }
+    let open_for = Duration::from_secs(cfg.open_secs);
+    let mut state = BREAKERS
+        .entry(endpoint.to_string())
+        .or_insert(State::Closed { failures: 0 });
+    match *state {
+        State::Closed { .. } => Ok(()),
+        State::Open { until } if now < until => Err(CircuitOpen {
+            endpoint: endpoint.to_string(),
+            retry_after_secs: (until - now).as_secs().max(1),
+        }),
+        State::HalfOpen { probe_started } if now < probe_started + open_for => Err(CircuitOpen {
+            endpoint: endpoint.to_string(),
+            retry_after_secs: (probe_started + open_for - now).as_secs().max(1),
+        }),
+        // 熔断到期 (或上一次探测已失联)：放行一个探测请求
+        _ => {
+            *state = State::HalfOpen { probe_started: now };
+            tracing::info!("[CircuitBreaker] Probing {}", endpoint);
+            Ok(())
+        }
+    }
+}
+
+fn record_failure_at(endpoint: &str, cfg: &UpstreamCircuitBreakerConfig, now: Instant) {
+    if !cfg.enabled {
+        return;
+    }
+    let open = State::Open {
+        until: now + Duration::from_secs(cfg.open_secs),
+    };
+    let mut state = BREAKERS
+        .entry(endpoint.to_string())
+        .or_insert(State::Closed { failures: 0 });
+    match *state {
+        State::Closed { failures } if failures + 1 >= cfg.failure_threshold.max(1) => {
+            *state = open;
+            tracing::warn!(
+                "[CircuitBreaker] {} opened after {} consecutive failures, cooling down {}s",
+                endpoint,
+                failures + 1,
+                cfg.open_secs
+            );
+        }
+        State::Closed { failures } => *state = State::Closed { failures: failures + 1 },
+        State::HalfOpen { .. } => {
+            *state = open;
+            tracing::warn!("[CircuitBreaker] Probe to {} failed, reopening", endpoint);
+        }
+        // 熔断前已发出的请求陆续失败，不延长熔断时间
+        State::Open { .. } => {}
+    }
+}
+
+/// 发出请求前检查端点是否可用
+pub fn try_acquire(endpoint: &str) -> Result<(), CircuitOpen> {
+    try_acquire_at(
+        endpoint,
+        &crate::proxy::config::get_circuit_breaker_config(),
+        Instant::now(),
+    )
+}
+
+/// 端点返回了响应 (非 5xx)
+pub fn record_success(endpoint: &str) {
+    if let Some(mut state) = BREAKERS.get_mut(endpoint) {
+        if !matches!(*state, State::Closed { .. }) {
+            tracing::info!("[CircuitBreaker] {} recovered", endpoint);
+        }
+        *state = State::Closed { failures: 0 };
+    }
+}
+
+/// 网络错误或 5xx
+pub fn record_failure(endpoint: &str) {
+    record_failure_at(
+        endpoint,
+        &crate::proxy::config::get_circuit_breaker_config(),
+        Instant::now(),
+    );
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CircuitStatus {
+    pub endpoint: String,
+    /// closed / open / half_open
+    pub state: &'static str,
+    pub consecutive_failures: u32,
+    /// 熔断剩余秒数 (仅 open)
+    pub retry_after_secs: Option<u64>,
+}
+
+/// 各端点当前的熔断状态
+pub fn snapshot() -> Vec<CircuitStatus> {
+    let now = Instant::now();
+    let mut list: Vec<CircuitStatus> = BREAKERS
+        .iter()
+        .map(|e| {
+            let (state, failures, retry_after) = match *e.value() {
+                State::Closed { failures } => ("closed", failures, None),
+                State::Open { until } if now < until => {
+                    ("open", 0, Some((until - now).as_secs().max(1)))
+                }
+                // 已到期，下一次请求将探测
+                State::Open { .. } | State::HalfOpen { .. } => ("half_open", 0, None),
+            };
+            CircuitStatus {
+                endpoint: e.key().clone(),
+                state,
+                consecutive_failures: failures,
+                retry_after_secs: retry_after,
+            }
+        })
+        .collect();
+    list.sort_by(|a, b| a.endpoint.cmp(&b.endpoint));
+    list
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opens_after_threshold_and_probes() {
+        let cfg = UpstreamCircuitBreakerConfig {
+            enabled: true,
+            failure_threshold: 3,
+            open_secs: 30,
+        };
+        let ep = "https://circuit-breaker-test.invalid/v1internal";
+        let t0 = Instant::now();
+
+        for _ in 0..2 {
+            record_failure_at(ep, &cfg, t0);
+        }
+        assert!(try_acquire_at(ep, &cfg, t0).is_ok());
+        record_failure_at(ep, &cfg, t0);
+        let err = try_acquire_at(ep, &cfg, t0 + Duration::from_secs(10)).unwrap_err();
+        assert_eq!(err.retry_after_secs, 20);
+        assert!(err.to_string().starts_with(CIRCUIT_OPEN_ERROR));
+
+        // 到期后只放行一个探测请求
+        let t1 = t0 + Duration::from_secs(31);
+        assert!(try_acquire_at(ep, &cfg, t1).is_ok());
+        assert!(try_acquire_at(ep, &cfg, t1).is_err());
+
+        // 探测失败重新熔断
+        record_failure_at(ep, &cfg, t1);
+        assert!(try_acquire_at(ep, &cfg, t1 + Duration::from_secs(1)).is_err());
+
+        // 再次探测成功后恢复
+        let t2 = t1 + Duration::from_secs(31);
+        assert!(try_acquire_at(ep, &cfg, t2).is_ok());
+        record_success(ep);
+        assert!(try_acquire_at(ep, &cfg, t2).is_ok());
+        assert!(try_acquire_at(ep, &cfg, t2).is_ok());
+
+        BREAKERS.remove(ep);
+    }
+}
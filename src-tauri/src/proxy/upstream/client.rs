@@ -1,8 +1,10 @@
 // 上游客户端实现
 // 基于高性能通讯接口封装
 
+use super::{circuit_breaker, timeouts};
 use dashmap::DashMap;
 use rquest::{header, Client, Response, StatusCode};
+use crate::models::RetryClass;
 use serde_json::Value;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -56,10 +58,18 @@ const V1_INTERNAL_BASE_URL_FALLBACKS: [&str; 3] = [
     V1_INTERNAL_BASE_URL_PROD,    // 优先级 3: Prod (仅作为兜底)
 ];
 
+/// 默认客户端使用的连接超时 (秒)；按模型配置了其他值时另建客户端
+const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 20;
+
 pub struct UpstreamClient {
     default_client: Client,
+    /// 默认客户端的上游代理配置 (按连接超时重建客户端时复用)
+    proxy_config: Option<crate::proxy::config::UpstreamProxyConfig>,
+    /// 默认客户端的出站地址族
+    default_family: crate::models::IpFamily,
+    connect_variants: DashMap<(u64, crate::models::IpFamily), Client>, // (connect_secs, family) -> Client
     proxy_pool: Option<Arc<crate::proxy::proxy_pool::ProxyPoolManager>>,
-    client_cache: DashMap<String, Client>, // proxy_id -> Client
+    client_cache: DashMap<String, Client>, // "proxy_id:connect_secs:family" -> Client
     user_agent_override: RwLock<Option<String>>,
 }
 
@@ -68,14 +78,19 @@ impl UpstreamClient {
         proxy_config: Option<crate::proxy::config::UpstreamProxyConfig>,
         proxy_pool: Option<Arc<crate::proxy::proxy_pool::ProxyPoolManager>>,
     ) -> Self {
-        let default_client = match Self::build_client_internal(proxy_config.clone()) {
+        let default_family = crate::modules::http::settings().ip_family;
+        let default_client = match Self::build_client_internal(
+            proxy_config.clone(),
+            DEFAULT_CONNECT_TIMEOUT_SECS,
+            default_family,
+        ) {
             Ok(client) => client,
             Err(err_with_proxy) => {
                 tracing::error!(
                     error = %err_with_proxy,
                     "Failed to create default HTTP client with configured upstream proxy; retrying without proxy"
                 );
-                match Self::build_client_internal(None) {
+                match Self::build_client_internal(None, DEFAULT_CONNECT_TIMEOUT_SECS, default_family) {
                     Ok(client) => client,
                     Err(err_without_proxy) => {
                         tracing::error!(
@@ -90,6 +105,9 @@ impl UpstreamClient {
 
         Self {
             default_client,
+            default_family,
+            proxy_config,
+            connect_variants: DashMap::new(),
             proxy_pool,
             client_cache: DashMap::new(),
             user_agent_override: RwLock::new(None),
@@ -97,17 +115,21 @@ impl UpstreamClient {
     }
 
     /// Internal helper to build a client with optional upstream proxy config
+    /// 总时长不在客户端上限制：非流式请求按模型设置 read 超时，流式响应由空闲超时兜底
     fn build_client_internal(
         proxy_config: Option<crate::proxy::config::UpstreamProxyConfig>,
+        connect_secs: u64,
+        family: crate::models::IpFamily,
     ) -> Result<Client, rquest::Error> {
         let mut builder = Client::builder()
             .emulation(rquest_util::Emulation::Chrome123)
             // Connection settings (优化连接复用，减少建立开销)
-            .connect_timeout(Duration::from_secs(20))
             .pool_max_idle_per_host(16) // 每主机最多 16 个空闲连接
             .pool_idle_timeout(Duration::from_secs(90)) // 空闲连接保持 90 秒
-            .tcp_keepalive(Duration::from_secs(60)) // TCP 保活探测 60 秒
-            .timeout(Duration::from_secs(600));
+            .tcp_keepalive(Duration::from_secs(60)); // TCP 保活探测 60 秒
+        if connect_secs > 0 {
+            builder = builder.connect_timeout(Duration::from_secs(connect_secs));
+        }
 
         builder = Self::apply_default_user_agent(builder);
 
@@ -121,30 +143,34 @@ impl UpstreamClient {
             }
         }
 
-        builder.build()
+        crate::modules::http::apply_resolver(builder, family).build()
     }
 
     /// Build a client with a specific PoolProxyConfig (from ProxyPool)
     fn build_client_with_proxy(
         &self,
         proxy_config: crate::proxy::proxy_pool::PoolProxyConfig,
+        connect_secs: u64,
+        family: crate::models::IpFamily,
     ) -> Result<Client, rquest::Error> {
         // Reuse base settings similar to default client but with specific proxy
-        let builder = Client::builder()
+        let mut builder = Client::builder()
             .emulation(rquest_util::Emulation::Chrome123)
-            .connect_timeout(Duration::from_secs(20))
             .pool_max_idle_per_host(16)
             .pool_idle_timeout(Duration::from_secs(90))
             .tcp_keepalive(Duration::from_secs(60))
-            .timeout(Duration::from_secs(600))
             .proxy(proxy_config.proxy); // Apply the specific proxy
+        if connect_secs > 0 {
+            builder = builder.connect_timeout(Duration::from_secs(connect_secs));
+        }
 
-        Self::apply_default_user_agent(builder).build()
+        crate::modules::http::apply_resolver(Self::apply_default_user_agent(builder), family)
+            .build()
     }
 
     fn apply_default_user_agent(builder: rquest::ClientBuilder) -> rquest::ClientBuilder {
-        let ua = crate::constants::USER_AGENT.as_str();
-        if header::HeaderValue::from_str(ua).is_ok() {
+        let ua = crate::constants::user_agent();
+        if header::HeaderValue::from_str(&ua).is_ok() {
             builder.user_agent(ua)
         } else {
             tracing::warn!(
@@ -168,25 +194,28 @@ impl UpstreamClient {
         ua_override
             .as_ref()
             .cloned()
-            .unwrap_or_else(|| crate::constants::USER_AGENT.clone())
+            .unwrap_or_else(crate::constants::user_agent)
     }
 
     /// Get client for a specific account (or default if no proxy bound)
-    pub async fn get_client(&self, account_id: Option<&str>) -> Client {
+    /// connect_secs: 按模型解析出的连接超时
+    pub async fn get_client(&self, account_id: Option<&str>, connect_secs: u64) -> Client {
+        let family = crate::modules::http::ip_family_for(account_id);
         if let Some(pool) = &self.proxy_pool {
             if let Some(acc_id) = account_id {
                 // Try to get per-account proxy
                 match pool.get_proxy_for_account(acc_id).await {
                     Ok(Some(proxy_cfg)) => {
                         // Check cache
-                        if let Some(client) = self.client_cache.get(&proxy_cfg.entry_id) {
+                        let cache_key =
+                            format!("{}:{}:{:?}", proxy_cfg.entry_id, connect_secs, family);
+                        if let Some(client) = self.client_cache.get(&cache_key) {
                             return client.clone();
                         }
                         // Build new client and cache it
-                        match self.build_client_with_proxy(proxy_cfg.clone()) {
+                        match self.build_client_with_proxy(proxy_cfg.clone(), connect_secs, family) {
                             Ok(client) => {
-                                self.client_cache
-                                    .insert(proxy_cfg.entry_id.clone(), client.clone());
+                                self.client_cache.insert(cache_key, client.clone());
                                 tracing::info!(
                                     "Using ProxyPool proxy ID: {} for account: {}",
                                     proxy_cfg.entry_id,
@@ -213,7 +242,23 @@ impl UpstreamClient {
             }
         }
         // Fallback to default client
-        self.default_client.clone()
+        if connect_secs == DEFAULT_CONNECT_TIMEOUT_SECS && family == self.default_family {
+            return self.default_client.clone();
+        }
+        self.connect_variants
+            .entry((connect_secs, family))
+            .or_insert_with(|| {
+                Self::build_client_internal(self.proxy_config.clone(), connect_secs, family)
+                    .or_else(|_| Self::build_client_internal(None, connect_secs, family))
+                    .unwrap_or_else(|_| self.default_client.clone())
+            })
+            .clone()
+    }
+
+    /// 按请求体中的模型解析超时
+    fn timeouts_for(body: &Value) -> crate::proxy::config::ResolvedTimeouts {
+        let model = body.get("model").and_then(|v| v.as_str()).unwrap_or_default();
+        timeouts::for_model(model)
     }
 
     /// Build v1internal URL
@@ -226,11 +271,13 @@ impl UpstreamClient {
     }
 
     /// Determine if we should try next endpoint (fallback logic)
+    /// 与统一重试策略共用分类 (429 / 408 / 5xx)，另外 404 也切换端点
     fn should_try_next_endpoint(status: StatusCode) -> bool {
-        status == StatusCode::TOO_MANY_REQUESTS
-            || status == StatusCode::REQUEST_TIMEOUT
-            || status == StatusCode::NOT_FOUND
-            || status.is_server_error()
+        status == StatusCode::NOT_FOUND
+            || matches!(
+                crate::modules::retry::classify_status(status.as_u16()),
+                Some(RetryClass::RateLimited | RetryClass::ServerError | RetryClass::Timeout)
+            )
     }
 
     /// Call v1internal API (Basic Method)
@@ -266,9 +313,60 @@ impl UpstreamClient {
         query_string: Option<&str>,
         extra_headers: std::collections::HashMap<String, String>,
         account_id: Option<&str>, // [NEW] Account ID
+    ) -> Result<UpstreamCallResult, String> {
+        use crate::proxy::fault_injection::{self, FaultKind};
+
+        // 开发者模式的故障注入 (X-Antigravity-Fault)，不在作用域内时为空
+        let faults = fault_injection::take();
+        let mut disconnect_after = None;
+        for fault in &faults {
+            match *fault {
+                FaultKind::SlowFirstByte { delay_ms } => {
+                    tracing::warn!("[FaultInjection] Delaying upstream {} by {}ms", method, delay_ms);
+                    tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+                }
+                FaultKind::Disconnect { after_bytes } => disconnect_after = Some(after_bytes),
+                FaultKind::Status { .. } => {}
+            }
+        }
+        if let Some(FaultKind::Status { code, retry_after }) = faults
+            .iter()
+            .find(|f| matches!(f, FaultKind::Status { .. }))
+        {
+            tracing::warn!("[FaultInjection] Returning synthetic {} for upstream {}", code, method);
+            return Ok(UpstreamCallResult {
+                response: fault_injection::synthetic_response(*code, *retry_after),
+                fallback_attempts: Vec::new(),
+            });
+        }
+
+        let mut result = self
+            .send_v1_internal(method, access_token, body, query_string, extra_headers, account_id)
+            .await?;
+        if let Some(after_bytes) = disconnect_after.filter(|_| result.response.status().is_success()) {
+            tracing::warn!(
+                "[FaultInjection] Upstream {} response will disconnect after {} bytes",
+                method,
+                after_bytes
+            );
+            result.response = fault_injection::disconnect_after(result.response, after_bytes);
+        }
+        Ok(result)
+    }
+
+    async fn send_v1_internal(
+        &self,
+        method: &str,
+        access_token: &str,
+        body: Value,
+        query_string: Option<&str>,
+        extra_headers: std::collections::HashMap<String, String>,
+        account_id: Option<&str>,
     ) -> Result<UpstreamCallResult, String> {
         // [NEW] Get client based on account (cached in proxy pool manager)
-        let client = self.get_client(account_id).await;
+        let timeouts = Self::timeouts_for(&body);
+        let streaming = query_string.is_some_and(|q| q.contains("alt=sse"));
+        let client = self.get_client(account_id, timeouts.connect_secs).await;
 
         // 构建 Headers (所有端点复用)
         let mut headers = header::HeaderMap::new();
@@ -290,13 +388,34 @@ impl UpstreamClient {
             }),
         );
 
+        // [NEW] 账号绑定的设备指纹若带有模拟的系统版本 / 语言，同步体现在请求头中
+        if let Some(id) = account_id {
+            let hints = crate::modules::device::header_hints_for_account(id);
+            if let Some(os_version) = hints.os_version.as_deref() {
+                let ua = crate::modules::device::apply_os_version_to_user_agent(
+                    &self.get_user_agent().await,
+                    os_version,
+                );
+                if let Ok(val) = header::HeaderValue::from_str(&ua) {
+                    headers.insert(header::USER_AGENT, val);
+                }
+            }
+            if let Some(locale) = hints.locale.as_deref() {
+                if let Ok(val) = header::HeaderValue::from_str(
+                    &crate::modules::device::accept_language_for(locale),
+                ) {
+                    headers.insert(header::ACCEPT_LANGUAGE, val);
+                }
+            }
+        }
+
         // [ENHANCED] 注入 Antigravity 官方客户端关键特征 Headers
         // 1. Client Identity
         headers.insert(
             "x-client-name",
             header::HeaderValue::from_static("antigravity"),
         );
-        if let Ok(ver) = header::HeaderValue::from_str(&crate::constants::CURRENT_VERSION) {
+        if let Ok(ver) = header::HeaderValue::from_str(&crate::constants::current_version()) {
             headers.insert("x-client-version", ver);
         }
 
@@ -307,8 +426,10 @@ impl UpstreamClient {
                  headers.insert("x-machine-id", mid_val);
              }
         }
-        // Session ID (Per App Launch)
-        if let Ok(sess_val) = header::HeaderValue::from_str(&crate::constants::SESSION_ID) {
+        // Session ID (按账号持久化并定期轮换，未开启时为每次启动一个)
+        if let Ok(sess_val) = header::HeaderValue::from_str(
+            &crate::proxy::session_pool::upstream_session_id(account_id),
+        ) {
             headers.insert("x-vscode-sessionid", sess_val);
         }
 
@@ -332,84 +453,135 @@ impl UpstreamClient {
         // [NEW] 收集降级尝试记录
         let mut fallback_attempts: Vec<FallbackAttemptLog> = Vec::new();
 
-        // 遍历所有端点，失败时自动切换
-        for (idx, base_url) in V1_INTERNAL_BASE_URL_FALLBACKS.iter().enumerate() {
-            let url = Self::build_url(base_url, method, query_string);
-            let has_next = idx + 1 < V1_INTERNAL_BASE_URL_FALLBACKS.len();
-
-            let response = client
-                .post(&url)
-                .headers(headers.clone())
-                .json(&body)
-                .send()
-                .await;
-
-            match response {
-                Ok(resp) => {
-                    let status = resp.status();
-                    if status.is_success() {
-                        if idx > 0 {
-                            tracing::info!(
-                                "✓ Upstream fallback succeeded | Endpoint: {} | Status: {} | Next endpoints available: {}",
-                                base_url,
-                                status,
-                                V1_INTERNAL_BASE_URL_FALLBACKS.len() - idx - 1
-                            );
+        // 429 / 5xx 响应交由调用方轮换账号；所有端点均为网络错误 (超时 / 连接失败) 时，
+        // 按统一退避策略整体再试一轮
+        let mut backoff = crate::modules::retry::RetryPolicy::current().start();
+        // 配置了上游地址覆盖 (模拟上游) 时只有一个端点
+        let base_urls = crate::modules::endpoints::resolve_all(&V1_INTERNAL_BASE_URL_FALLBACKS);
+        loop {
+            let mut last_class: Option<RetryClass> = None;
+            // 遍历所有端点，失败时自动切换
+            for (idx, base_url) in base_urls.iter().enumerate() {
+                let base_url = base_url.as_str();
+                let url = Self::build_url(base_url, method, query_string);
+                let has_next = idx + 1 < base_urls.len();
+
+                // 端点熔断中：跳过，不再等待其超时
+                if let Err(open) = circuit_breaker::try_acquire(base_url) {
+                    let msg = open.to_string();
+                    tracing::debug!("{}", msg);
+                    fallback_attempts.push(FallbackAttemptLog {
+                        endpoint_url: url.clone(),
+                        status: None,
+                        error: msg.clone(),
+                    });
+                    last_err = Some(msg);
+                    if !has_next {
+                        break;
+                    }
+                    continue;
+                }
+
+                let mut request = client.post(&url).headers(headers.clone()).json(&body);
+                // 流式响应不限总时长，由调用方的空闲超时兜底
+                if !streaming && timeouts.read_secs > 0 {
+                    request = request.timeout(Duration::from_secs(timeouts.read_secs));
+                }
+                let response = request.send().await;
+
+                match response {
+                    Ok(resp) => {
+                        crate::modules::clock_skew::observe_date_header(
+                            resp.headers().get("date").and_then(|v| v.to_str().ok()),
+                        );
+                        let status = resp.status();
+                        crate::proxy::dry_run::record_upstream_status(status.as_u16());
+                        if status.is_server_error() {
+                            circuit_breaker::record_failure(base_url);
                         } else {
-                            tracing::debug!(
-                                "✓ Upstream request succeeded | Endpoint: {} | Status: {}",
+                            circuit_breaker::record_success(base_url);
+                        }
+                        if status.is_success() {
+                            if idx > 0 {
+                                tracing::info!(
+                                    "✓ Upstream fallback succeeded | Endpoint: {} | Status: {} | Next endpoints available: {}",
+                                    base_url,
+                                    status,
+                                    base_urls.len() - idx - 1
+                                );
+                            } else {
+                                tracing::debug!(
+                                    "✓ Upstream request succeeded | Endpoint: {} | Status: {}",
+                                    base_url,
+                                    status
+                                );
+                            }
+                            return Ok(UpstreamCallResult {
+                                response: resp,
+                                fallback_attempts,
+                            });
+                        }
+
+                        // 如果有下一个端点且当前错误可重试，则切换
+                        if has_next && Self::should_try_next_endpoint(status) {
+                            let err_msg = format!("Upstream {} returned {}", base_url, status);
+                            tracing::warn!(
+                                "Upstream endpoint returned {} at {} (method={}), trying next endpoint",
+                                status,
                                 base_url,
-                                status
+                                method
                             );
+                            // [NEW] 记录降级尝试
+                            fallback_attempts.push(FallbackAttemptLog {
+                                endpoint_url: url.clone(),
+                                status: Some(status.as_u16()),
+                                error: err_msg.clone(),
+                            });
+                            last_err = Some(err_msg);
+                            continue;
                         }
+
+                        // 不可重试的错误或已是最后一个端点，直接返回
                         return Ok(UpstreamCallResult {
                             response: resp,
                             fallback_attempts,
                         });
                     }
-
-                    // 如果有下一个端点且当前错误可重试，则切换
-                    if has_next && Self::should_try_next_endpoint(status) {
-                        let err_msg = format!("Upstream {} returned {}", base_url, status);
-                        tracing::warn!(
-                            "Upstream endpoint returned {} at {} (method={}), trying next endpoint",
-                            status,
-                            base_url,
-                            method
-                        );
-                        // [NEW] 记录降级尝试
+                    Err(e) => {
+                        circuit_breaker::record_failure(base_url);
+                        let msg = format!("HTTP request failed at {}: {}", base_url, e);
+                        tracing::debug!("{}", msg);
+                        // [NEW] 记录网络错误的降级尝试
                         fallback_attempts.push(FallbackAttemptLog {
                             endpoint_url: url.clone(),
-                            status: Some(status.as_u16()),
-                            error: err_msg.clone(),
+                            status: None,
+                            error: msg.clone(),
                         });
-                        last_err = Some(err_msg);
+                        last_err = Some(msg);
+                    last_class = crate::modules::retry::classify_transport_error(&e);
+
+                        // 如果是最后一个端点，退出循环
+                        if !has_next {
+                            break;
+                        }
                         continue;
                     }
-
-                    // 不可重试的错误或已是最后一个端点，直接返回
-                    return Ok(UpstreamCallResult {
-                        response: resp,
-                        fallback_attempts,
-                    });
                 }
-                Err(e) => {
-                    let msg = format!("HTTP request failed at {}: {}", base_url, e);
-                    tracing::debug!("{}", msg);
-                    // [NEW] 记录网络错误的降级尝试
-                    fallback_attempts.push(FallbackAttemptLog {
-                        endpoint_url: url.clone(),
-                        status: None,
-                        error: msg.clone(),
-                    });
-                    last_err = Some(msg);
+            }
 
-                    // 如果是最后一个端点，退出循环
-                    if !has_next {
-                        break;
-                    }
-                    continue;
+            let attempt = backoff.attempt();
+            match backoff.next_delay(last_class) {
+                Some(delay) => {
+                    tracing::warn!(
+                        "All upstream endpoints failed (method={}, attempt {}/{}), retrying in {}ms",
+                        method,
+                        attempt,
+                        backoff.max_attempts(),
+                        delay.as_millis()
+                    );
+                    tokio::time::sleep(delay).await;
                 }
+                None => break,
             }
         }
 
@@ -470,7 +642,9 @@ impl UpstreamClient {
         body: Value,
         account_id: Option<&str>,
     ) -> Result<UpstreamCallResult, String> {
-        let client = self.get_client(account_id).await;
+        let timeouts = Self::timeouts_for(&body);
+        let streaming = body.get("stream").and_then(|v| v.as_bool()).unwrap_or(false);
+        let client = self.get_client(account_id, timeouts.connect_secs).await;
 
         let mut headers = header::HeaderMap::new();
         headers.insert(
@@ -500,14 +674,24 @@ impl UpstreamClient {
         }
 
         let url = "https://chatgpt.com/backend-api/codex/responses";
+        circuit_breaker::try_acquire(url).map_err(|open| open.to_string())?;
         tracing::debug!("[Codex] Sending request to {}", url);
-        let response = client
-            .post(url)
-            .headers(headers)
-            .json(&body)
+        let mut request = client.post(url).headers(headers).json(&body);
+        if !streaming && timeouts.read_secs > 0 {
+            request = request.timeout(Duration::from_secs(timeouts.read_secs));
+        }
+        let response = request
             .send()
             .await
-            .map_err(|e| format!("Codex request failed: {}", e))?;
+            .map_err(|e| {
+                circuit_breaker::record_failure(url);
+                format!("Codex request failed: {}", e)
+            })?;
+        if response.status().is_server_error() {
+            circuit_breaker::record_failure(url);
+        } else {
+            circuit_breaker::record_success(url);
+        }
 
         Ok(UpstreamCallResult {
             response,
@@ -1,6 +1,8 @@
 // Upstream 模块 - 上游客户端
 // 对应上游通讯接口
 
+pub mod circuit_breaker;
 pub mod client;
 pub mod retry;
+pub mod timeouts;
 pub mod models;
@@ -0,0 +1,82 @@
+// 上游超时 (按模型)
+// - connect / read 由 UpstreamClient 在发请求时应用 (read 仅限非流式请求)
+// - 流式响应不限总时长，改为空闲超时：两个数据块之间超过 stream_idle_secs 视为上游卡死并中断
+// 超时值按请求模型解析，见 UpstreamTimeoutsConfig::resolve
+
+use crate::proxy::config::ResolvedTimeouts;
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use std::pin::Pin;
+use std::time::Duration;
+
+/// 流空闲超时的错误前缀
+pub const STREAM_IDLE_TIMEOUT_ERROR: &str = "upstream_stream_idle_timeout";
+
+pub type TimedByteStream = Pin<Box<dyn Stream<Item = Result<Bytes, String>> + Send>>;
+
+/// 当前配置下某个模型的超时
+pub fn for_model(model: &str) -> ResolvedTimeouts {
+    crate::proxy::config::get_upstream_timeouts().resolve(model)
+}
+
+/// 为上游字节流加上空闲超时 (idle_secs = 0 时不限制)
+pub fn with_idle_timeout<S, E>(stream: S, idle_secs: u64) -> TimedByteStream
+where
+    S: Stream<Item = Result<Bytes, E>> + Send + 'static,
+    E: std::fmt::Display + Send + 'static,
+{
+    let stream = stream.map(|item| item.map_err(|e| e.to_string()));
+    if idle_secs == 0 {
+        return Box::pin(stream);
+    }
+    let idle = Duration::from_secs(idle_secs);
+    Box::pin(async_stream::stream! {
+        let mut stream = Box::pin(stream);
+        loop {
+            match tokio::time::timeout(idle, stream.next()).await {
+                Ok(Some(item)) => yield item,
+                Ok(None) => break,
+                Err(_) => {
+                    tracing::warn!("[Upstream] Stream idle for {}s, aborting", idle_secs);
+                    yield Err(format!(
+                        "{}: no data received for {}s",
+                        STREAM_IDLE_TIMEOUT_ERROR, idle_secs
+                    ));
+                    break;
+                }
+            }
+        }
+    })
+}
+
+/// 按模型配置为上游字节流加上空闲超时
+pub fn idle_guarded<S, E>(stream: S, model: &str) -> TimedByteStream
+where
+    S: Stream<Item = Result<Bytes, E>> + Send + 'static,
+    E: std::fmt::Display + Send + 'static,
+{
+    with_idle_timeout(stream, for_model(model).stream_idle_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_idle_timeout_aborts_stalled_stream() {
+        let stalled = futures::stream::iter(vec![Ok::<_, String>(Bytes::from_static(b"a"))])
+            .chain(futures::stream::pending());
+        let items: Vec<_> = with_idle_timeout(stalled, 1).collect().await;
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].as_deref().unwrap(), b"a");
+        assert!(items[1]
+            .as_ref()
+            .unwrap_err()
+            .starts_with(STREAM_IDLE_TIMEOUT_ERROR));
+
+        // 0 = 不限制，正常结束的流原样透传
+        let done = futures::stream::iter(vec![Ok::<_, String>(Bytes::from_static(b"b"))]);
+        let items: Vec<_> = with_idle_timeout(done, 0).collect().await;
+        assert_eq!(items.len(), 1);
+    }
+}
@@ -0,0 +1,385 @@
+//! `abv-cli service ...` - run the headless proxy as an OS service so it survives
+//! logouts and reboots: a systemd unit on Linux, a Windows service on Windows.
+//! The service always runs `abv-cli service run --data-dir <dir>` so it uses the same
+//! account store as the user who installed it, whatever account the service runs as.
+
+use std::path::{Path, PathBuf};
+
+use crate::modules;
+
+#[cfg(windows)]
+const SERVICE_NAME: &str = "AntigravityTools";
+#[cfg(target_os = "linux")]
+const UNIT_NAME: &str = "antigravity-tools.service";
+#[cfg(any(target_os = "linux", windows, test))]
+const DESCRIPTION: &str = "Antigravity Tools proxy";
+
+#[derive(Debug, Default, PartialEq)]
+pub struct ServiceOptions {
+    /// Linux: install a system-wide unit instead of a user unit
+    pub system: bool,
+    pub data_dir: Option<PathBuf>,
+}
+
+impl ServiceOptions {
+    pub fn parse(args: &[&str]) -> Result<Self, String> {
+        let mut opts = ServiceOptions::default();
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match *arg {
+                "--system" => opts.system = true,
+                "--data-dir" => {
+                    let dir = iter
+                        .next()
+                        .ok_or("invalid_service_args: --data-dir needs a path")?;
+                    opts.data_dir = Some(PathBuf::from(dir));
+                }
+                other => return Err(format!("invalid_service_args: unknown option {}", other)),
+            }
+        }
+        Ok(opts)
+    }
+
+    fn resolve_data_dir(&self) -> Result<PathBuf, String> {
+        let dir = match self.data_dir.as_ref() {
+            Some(dir) => dir.clone(),
+            None => modules::account::get_data_dir()?,
+        };
+        std::fs::create_dir_all(&dir).map_err(|e| format!("failed_to_create_data_dir: {}", e))?;
+        dir.canonicalize()
+            .map_err(|e| format!("failed_to_resolve_data_dir: {}", e))
+    }
+}
+
+fn current_exe() -> Result<PathBuf, String> {
+    std::env::current_exe().map_err(|e| format!("failed_to_get_current_exe: {}", e))
+}
+
+/// Register the service, enable it at boot and start it now
+pub fn install(opts: &ServiceOptions) -> Result<(), String> {
+    let exe = current_exe()?;
+    let data_dir = opts.resolve_data_dir()?;
+    platform::install(opts, &exe, &data_dir)
+}
+
+/// Stop and remove the service (the data directory is left untouched)
+pub fn uninstall(opts: &ServiceOptions) -> Result<(), String> {
+    platform::uninstall(opts)
+}
+
+/// Service entry point: run the proxy and all schedulers until the service manager stops us
+pub fn run(opts: &ServiceOptions) -> Result<(), String> {
+    if let Some(dir) = opts.data_dir.as_ref() {
+        std::env::set_var("ABV_DATA_DIR", dir);
+    }
+    modules::logger::init_logger();
+    modules::crash_report::install();
+    crate::init_databases();
+    platform::run()
+}
+
+/// systemd unit for `abv-cli service run`; `%` is the only character systemd expands in ExecStart
+#[cfg(any(target_os = "linux", test))]
+fn render_unit(exe: &Path, data_dir: &Path, system: bool) -> String {
+    let quote = |p: &Path| format!("\"{}\"", p.display().to_string().replace('%', "%%"));
+    format!(
+        "[Unit]\n\
+         Description={}\n\
+         After=network-online.target\n\
+         Wants=network-online.target\n\
+         \n\
+         [Service]\n\
+         Type=simple\n\
+         ExecStart={} service run --data-dir {}\n\
+         Restart=on-failure\n\
+         RestartSec=5\n\
+         \n\
+         [Install]\n\
+         WantedBy={}\n",
+        DESCRIPTION,
+        quote(exe),
+        quote(data_dir),
+        if system {
+            "multi-user.target"
+        } else {
+            "default.target"
+        }
+    )
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::*;
+    use std::process::Command;
+
+    fn unit_path(system: bool) -> Result<PathBuf, String> {
+        let dir = if system {
+            PathBuf::from("/etc/systemd/system")
+        } else {
+            dirs::config_dir()
+                .ok_or("failed_to_get_config_dir")?
+                .join("systemd")
+                .join("user")
+        };
+        Ok(dir.join(UNIT_NAME))
+    }
+
+    fn systemctl(system: bool, args: &[&str]) -> Result<(), String> {
+        let mut cmd = Command::new("systemctl");
+        if !system {
+            cmd.arg("--user");
+        }
+        let status = cmd
+            .args(args)
+            .status()
+            .map_err(|e| format!("systemctl_failed: {}", e))?;
+        if !status.success() {
+            return Err(format!(
+                "systemctl_failed: systemctl {} exited with {}",
+                args.join(" "),
+                status
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn install(opts: &ServiceOptions, exe: &Path, data_dir: &Path) -> Result<(), String> {
+        let path = unit_path(opts.system)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("failed_to_write_unit: {}", e))?;
+        }
+        std::fs::write(&path, render_unit(exe, data_dir, opts.system))
+            .map_err(|e| format!("failed_to_write_unit: {}: {}", path.display(), e))?;
+        println!("Wrote {}", path.display());
+
+        systemctl(opts.system, &["daemon-reload"])?;
+        systemctl(opts.system, &["enable", "--now", UNIT_NAME])?;
+
+        if !opts.system {
+            // User units stop at logout and only start at login unless lingering is enabled
+            let linger = std::env::var("USER").ok().map(|user| {
+                Command::new("loginctl")
+                    .args(["enable-linger", &user])
+                    .status()
+            });
+            if !matches!(linger, Some(Ok(status)) if status.success()) {
+                eprintln!(
+                    "Warning: failed to enable lingering; run `loginctl enable-linger` so the service keeps running after logout"
+                );
+            }
+        }
+        println!("Service {} installed and started", UNIT_NAME);
+        Ok(())
+    }
+
+    pub fn uninstall(opts: &ServiceOptions) -> Result<(), String> {
+        let path = unit_path(opts.system)?;
+        if !path.exists() {
+            return Err(format!("service_not_installed: {}", path.display()));
+        }
+        if let Err(e) = systemctl(opts.system, &["disable", "--now", UNIT_NAME]) {
+            eprintln!("Warning: {}", e);
+        }
+        std::fs::remove_file(&path)
+            .map_err(|e| format!("failed_to_remove_unit: {}: {}", path.display(), e))?;
+        systemctl(opts.system, &["daemon-reload"])?;
+        println!("Service {} removed", UNIT_NAME);
+        Ok(())
+    }
+
+    pub fn run() -> Result<(), String> {
+        // systemd stops services with SIGTERM, not SIGINT
+        crate::run_headless_until(async {
+            use tokio::signal::unix::{signal, SignalKind};
+            match signal(SignalKind::terminate()) {
+                Ok(mut term) => {
+                    tokio::select! {
+                        _ = tokio::signal::ctrl_c() => {}
+                        _ = term.recv() => {}
+                    }
+                }
+                Err(_) => {
+                    tokio::signal::ctrl_c().await.ok();
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::*;
+    use std::ffi::{OsStr, OsString};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use windows_service::service::{
+        ServiceAccess, ServiceControl, ServiceControlAccept, ServiceErrorControl, ServiceExitCode,
+        ServiceInfo, ServiceStartType, ServiceState, ServiceStatus, ServiceType,
+    };
+    use windows_service::service_control_handler::{self, ServiceControlHandlerResult};
+    use windows_service::service_manager::{ServiceManager, ServiceManagerAccess};
+    use windows_service::{define_windows_service, service_dispatcher};
+
+    fn manager(access: ServiceManagerAccess) -> Result<ServiceManager, String> {
+        ServiceManager::local_computer(None::<&str>, access)
+            .map_err(|e| format!("service_manager_failed: {} (run as Administrator)", e))
+    }
+
+    pub fn install(_opts: &ServiceOptions, exe: &Path, data_dir: &Path) -> Result<(), String> {
+        let manager =
+            manager(ServiceManagerAccess::CONNECT | ServiceManagerAccess::CREATE_SERVICE)?;
+        let info = ServiceInfo {
+            name: OsString::from(SERVICE_NAME),
+            display_name: OsString::from(DESCRIPTION),
+            service_type: ServiceType::OWN_PROCESS,
+            start_type: ServiceStartType::AutoStart,
+            error_control: ServiceErrorControl::Normal,
+            executable_path: exe.to_path_buf(),
+            launch_arguments: vec![
+                OsString::from("service"),
+                OsString::from("run"),
+                OsString::from("--data-dir"),
+                data_dir.as_os_str().to_os_string(),
+            ],
+            dependencies: vec![],
+            account_name: None,
+            account_password: None,
+        };
+        let service = manager
+            .create_service(&info, ServiceAccess::CHANGE_CONFIG | ServiceAccess::START)
+            .map_err(|e| format!("service_install_failed: {}", e))?;
+        let _ =
+            service.set_description("Runs the Antigravity Tools API proxy without the desktop app");
+        service
+            .start(&[] as &[&OsStr])
+            .map_err(|e| format!("service_start_failed: {}", e))?;
+        println!("Service {} installed and started", SERVICE_NAME);
+        Ok(())
+    }
+
+    pub fn uninstall(_opts: &ServiceOptions) -> Result<(), String> {
+        let manager = manager(ServiceManagerAccess::CONNECT)?;
+        let service = manager
+            .open_service(
+                SERVICE_NAME,
+                ServiceAccess::QUERY_STATUS | ServiceAccess::STOP | ServiceAccess::DELETE,
+            )
+            .map_err(|e| format!("service_not_installed: {}", e))?;
+        let stopped = service
+            .query_status()
+            .map(|s| s.current_state == ServiceState::Stopped)
+            .unwrap_or(true);
+        if !stopped {
+            if let Err(e) = service.stop() {
+                eprintln!("Warning: failed to stop service: {}", e);
+            }
+        }
+        service
+            .delete()
+            .map_err(|e| format!("service_uninstall_failed: {}", e))?;
+        println!("Service {} removed", SERVICE_NAME);
+        Ok(())
+    }
+
+    define_windows_service!(ffi_service_main, service_main);
+
+    fn service_main(_args: Vec<OsString>) {
+        if let Err(e) = run_service() {
+            modules::logger::log_error(&format!("[Service] {}", e));
+        }
+    }
+
+    fn status(state: ServiceState) -> ServiceStatus {
+        ServiceStatus {
+            service_type: ServiceType::OWN_PROCESS,
+            current_state: state,
+            controls_accepted: if state == ServiceState::Running {
+                ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN
+            } else {
+                ServiceControlAccept::empty()
+            },
+            exit_code: ServiceExitCode::Win32(0),
+            checkpoint: 0,
+            wait_hint: Duration::default(),
+            process_id: None,
+        }
+    }
+
+    fn run_service() -> windows_service::Result<()> {
+        let stop = Arc::new(tokio::sync::Notify::new());
+        let handler_stop = stop.clone();
+        let handle =
+            service_control_handler::register(SERVICE_NAME, move |control| match control {
+                ServiceControl::Stop | ServiceControl::Shutdown => {
+                    handler_stop.notify_one();
+                    ServiceControlHandlerResult::NoError
+                }
+                ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+                _ => ServiceControlHandlerResult::NotImplemented,
+            })?;
+
+        handle.set_service_status(status(ServiceState::Running))?;
+        crate::run_headless_until(async move { stop.notified().await });
+        handle.set_service_status(status(ServiceState::Stopped))?;
+        Ok(())
+    }
+
+    pub fn run() -> Result<(), String> {
+        service_dispatcher::start(SERVICE_NAME, ffi_service_main).map_err(|e| {
+            format!(
+                "service_dispatcher_failed: {} (`service run` is started by the Service Control Manager; use `abv-cli proxy run` in a console)",
+                e
+            )
+        })
+    }
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+mod platform {
+    use super::*;
+
+    const UNSUPPORTED: &str =
+        "service_unsupported_platform: use `abv-cli proxy run` under launchd or another supervisor";
+
+    pub fn install(_opts: &ServiceOptions, _exe: &Path, _data_dir: &Path) -> Result<(), String> {
+        Err(UNSUPPORTED.to_string())
+    }
+
+    pub fn uninstall(_opts: &ServiceOptions) -> Result<(), String> {
+        Err(UNSUPPORTED.to_string())
+    }
+
+    pub fn run() -> Result<(), String> {
+        Err(UNSUPPORTED.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_options_and_render_unit() {
+        assert_eq!(
+            ServiceOptions::parse(&["--system", "--data-dir", "/srv/abv"]).unwrap(),
+            ServiceOptions {
+                system: true,
+                data_dir: Some(PathBuf::from("/srv/abv")),
+            }
+        );
+        assert!(ServiceOptions::parse(&["--data-dir"]).is_err());
+        assert!(ServiceOptions::parse(&["--bogus"]).is_err());
+
+        let unit = render_unit(
+            Path::new("/opt/abv/abv-cli"),
+            Path::new("/home/me/100%/.antigravity_tools"),
+            false,
+        );
+        assert!(unit.contains(
+            "ExecStart=\"/opt/abv/abv-cli\" service run --data-dir \"/home/me/100%%/.antigravity_tools\"\n"
+        ));
+        assert!(unit.contains("WantedBy=default.target"));
+        assert!(render_unit(Path::new("/a"), Path::new("/b"), true).contains("multi-user.target"));
+    }
+}
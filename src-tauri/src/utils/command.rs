@@ -28,3 +28,24 @@ impl CommandExtWrapper for TokioCommand {
         self
     }
 }
+
+/// 用系统默认程序打开目录 / 文件 / URL
+pub fn open_with_system(target: &str) -> std::io::Result<()> {
+    #[cfg(target_os = "macos")]
+    let mut cmd = StdCommand::new("open");
+    #[cfg(target_os = "windows")]
+    let mut cmd = StdCommand::new("explorer");
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut cmd = StdCommand::new("xdg-open");
+
+    cmd.creation_flags_windows().arg(target).spawn().map(|_| ())
+}
+
+/// 在浏览器中打开 URL (opener 插件)
+#[cfg(feature = "desktop")]
+pub fn open_url(app: &tauri::AppHandle, url: &str) -> Result<(), String> {
+    use tauri_plugin_opener::OpenerExt;
+    app.opener()
+        .open_url(url, None::<&str>)
+        .map_err(|e| e.to_string())
+}
@@ -2,3 +2,4 @@ pub mod http;
 pub mod protobuf;
 pub mod crypto;
 pub mod command;
+pub mod runtime;
@@ -0,0 +1,42 @@
+// 后台任务运行时
+// 桌面端沿用 Tauri 的全局运行时；不带 desktop 特性的库构建优先使用调用方所在的 Tokio 运行时，
+// 不在运行时上下文中调用时退回到进程内共享的运行时 (与 Tauri 的行为一致)。
+
+use std::future::Future;
+
+#[cfg(feature = "desktop")]
+pub use tauri::async_runtime::JoinHandle;
+#[cfg(not(feature = "desktop"))]
+pub use tokio::task::JoinHandle;
+
+#[cfg(feature = "desktop")]
+pub fn spawn<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    crate::utils::runtime::spawn(future)
+}
+
+#[cfg(not(feature = "desktop"))]
+pub fn spawn<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    use std::sync::OnceLock;
+
+    static FALLBACK: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) => handle.spawn(future),
+        Err(_) => FALLBACK
+            .get_or_init(|| {
+                tokio::runtime::Builder::new_multi_thread()
+                    .enable_all()
+                    .build()
+                    .expect("failed to build the background runtime")
+            })
+            .spawn(future),
+    }
+}
@@ -15,11 +15,18 @@ import DebugConsole from './components/debug/DebugConsole';
 import { useEffect, useState } from 'react';
 import { useConfigStore } from './stores/useConfigStore';
 import { useAccountStore } from './stores/useAccountStore';
+import type { RefreshProgress } from './services/accountService';
+import type { CapacityStatus, ModelAvailability } from './types/account';
+import { showToast } from './components/common/ToastContainer';
 import { useTranslation } from 'react-i18next';
 import { listen } from '@tauri-apps/api/event';
 import { isTauri } from './utils/env';
 import { request as invoke } from './utils/request';
 import { AdminAuthGuard } from './components/common/AdminAuthGuard';
+import { AppLockGuard } from './components/common/AppLockGuard';
+import { loadLocaleOverrides } from './i18n';
+import { getStartupReport, listNotifications } from './services/configService';
+import type { NotificationsChanged, StartupReport } from './types/config';
 
 const router = createBrowserRouter([
   {
@@ -65,7 +72,7 @@ const router = createBrowserRouter([
 function App() {
   const { config, loadConfig } = useConfigStore();
   const { fetchCurrentAccount, fetchAccounts } = useAccountStore();
-  const { i18n } = useTranslation();
+  const { t, i18n } = useTranslation();
 
   useEffect(() => {
     loadConfig();
@@ -74,7 +81,9 @@ function App() {
   // Sync language from config
   useEffect(() => {
     if (config?.language) {
-      i18n.changeLanguage(config.language);
+      const language = config.language;
+      // 先合并 locales/ 下的用户覆盖 / 社区翻译，再切换
+      loadLocaleOverrides(language).then(() => i18n.changeLanguage(language));
       // Support RTL
       if (config.language === 'ar') {
         document.documentElement.dir = 'rtl';
@@ -116,13 +125,103 @@ function App() {
       })
     );
 
+    // 监听批量刷新进度事件
+    unlistenPromises.push(
+      listen<RefreshProgress>('quota://refresh-progress', (event) => {
+        const progress = event.payload;
+        useAccountStore.getState().setRefreshProgress(
+          progress.done >= progress.total ? null : progress
+        );
+      })
+    );
+
+    // 监听受监控模型无可用账号告警
+    unlistenPromises.push(
+      listen<ModelAvailability>('models://availability-warning', (event) => {
+        const row = event.payload;
+        showToast(
+          t('proxy.model_availability.warning', {
+            model: row.model,
+            total: row.total,
+            protected: row.protected,
+            cooling: row.cooling,
+            exhausted: row.exhausted,
+          }),
+          'warning',
+          8000
+        );
+      })
+    );
+
+    // 监听账号池容量低于阈值告警
+    unlistenPromises.push(
+      listen<CapacityStatus>('models://capacity-alert', (event) => {
+        const status = event.payload;
+        showToast(
+          t('proxy.model_availability.capacity_alert', {
+            model: status.model,
+            available: status.available,
+            required: status.required,
+          }),
+          'warning',
+          8000
+        );
+      })
+    );
+
     // Cleanup
     return () => {
       Promise.all(unlistenPromises).then(unlisteners => {
         unlisteners.forEach(unlisten => unlisten());
       });
     };
-  }, [fetchCurrentAccount, fetchAccounts]);
+  }, [fetchCurrentAccount, fetchAccounts, t]);
+
+  // 启动自检：有失败项时提示 (报告可能早于前端就绪，因此同时主动获取一次)
+  useEffect(() => {
+    if (!isTauri()) return;
+    const notified = new Set<string>();
+    const notify = (report: StartupReport | null) => {
+      const failed = report?.checks.filter(c => c.status === 'failed') ?? [];
+      const fresh = failed.filter(c => !notified.has(c.id));
+      if (fresh.length === 0) return;
+      fresh.forEach(c => notified.add(c.id));
+      showToast(
+        t('startup_report.degraded', {
+          items: fresh.map(c => `${t(`startup_report.checks.${c.id}`, c.id)}${c.detail ? ` (${c.detail})` : ''}`).join('; '),
+        }),
+        'error',
+        12000
+      );
+    };
+
+    getStartupReport().then(notify).catch(() => {});
+    const unlisten = listen<StartupReport>('app://startup-report', (event) => notify(event.payload));
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, [t]);
+
+  // 通知中心：启动时提示窗口关闭期间积累的未读通知，运行中的警告 / 错误直接弹出
+  useEffect(() => {
+    if (!isTauri()) return;
+    listNotifications(true)
+      .then(unread => {
+        if (unread.length > 0) {
+          showToast(t('notifications.unread_while_away', { count: unread.length }), 'warning', 8000);
+        }
+      })
+      .catch(() => {});
+    const unlisten = listen<NotificationsChanged>('notifications://changed', (event) => {
+      const latest = event.payload.latest;
+      if (latest && latest.severity !== 'info') {
+        showToast(`${latest.title}: ${latest.body}`, latest.severity === 'error' ? 'error' : 'warning', 8000);
+      }
+    });
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, [t]);
 
   // Update notification state
   const [showUpdateNotification, setShowUpdateNotification] = useState(false);
@@ -154,12 +253,14 @@ function App() {
 
   return (
     <AdminAuthGuard>
-      <ThemeManager />
-      <DebugConsole />
-      {showUpdateNotification && (
-        <UpdateNotification onClose={() => setShowUpdateNotification(false)} />
-      )}
-      <RouterProvider router={router} />
+      <AppLockGuard>
+        <ThemeManager />
+        <DebugConsole />
+        {showUpdateNotification && (
+          <UpdateNotification onClose={() => setShowUpdateNotification(false)} />
+        )}
+        <RouterProvider router={router} />
+      </AppLockGuard>
     </AdminAuthGuard>
   );
 }
@@ -4,6 +4,7 @@ import { Account } from '../../types/account';
 import { cn } from '../../utils/cn';
 import { useTranslation } from 'react-i18next';
 import { useConfigStore } from '../../stores/useConfigStore';
+import { formatRelativeTime } from '../../utils/format';
 import { QuotaItem } from './QuotaItem';
 import { MODEL_CONFIG, sortModels } from '../../config/modelConfig';
 
@@ -47,8 +48,8 @@ const DEFAULT_MODELS = Object.entries(MODEL_CONFIG).map(([id, config]) => ({
 }));
 
 function AccountCard({ account, selected, onSelect, isCurrent: propIsCurrent, isRefreshing, isSwitching = false, onSwitch, onRefresh, onViewDetails, onExport, onDelete, onToggleProxy, onViewDevice, onWarmup, onUpdateLabel, onViewError }: AccountCardProps) {
-    const { t } = useTranslation();
-    const { config, showAllQuotas } = useConfigStore();
+    const { t, i18n } = useTranslation();
+    const { config, showAllQuotas, networkStatus } = useConfigStore();
     const isDisabled = Boolean(account.disabled);
 
     // 自定义标签编辑状态
@@ -176,6 +177,12 @@ function AccountCard({ account, selected, onSelect, isCurrent: propIsCurrent, is
                                     {t('accounts.forbidden').toUpperCase()}
                                 </span>
                             )}
+                            {networkStatus?.offline && !!account.quota?.last_updated && (
+                                <span className="px-1.5 py-0.5 rounded-md bg-gray-100 dark:bg-white/10 text-gray-600 dark:text-gray-400 text-[9px] font-bold flex items-center gap-1 shadow-sm border border-gray-200 dark:border-white/10" title={t('accounts.quota_cached_tooltip')}>
+                                    <Clock className="w-2.5 h-2.5" />
+                                    {t('accounts.quota_cached', { age: formatRelativeTime(account.quota.last_updated, i18n.language) })}
+                                </span>
+                            )}
                             {account.validation_blocked && (
                                 <span className="px-1.5 py-0.5 rounded-md bg-amber-100 dark:bg-amber-900/40 text-amber-700 dark:text-amber-400 text-[9px] font-bold flex items-center gap-1 shadow-sm border border-amber-200/50">
                                     <Clock className="w-2.5 h-2.5" />
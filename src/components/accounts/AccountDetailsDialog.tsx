@@ -1,6 +1,8 @@
-import { X, Clock, AlertCircle, Bot } from 'lucide-react';
+import { useState } from 'react';
+import { X, Clock, AlertCircle, Bot, Send, Loader2 } from 'lucide-react';
 import { createPortal } from 'react-dom';
-import { Account } from '../../types/account';
+import { Account, AccountDryRunResult } from '../../types/account';
+import { testAccountRequest } from '../../services/accountService';
 import { formatDate } from '../../utils/format';
 import { useTranslation } from 'react-i18next';
 import { MODEL_CONFIG, sortModels } from '../../config/modelConfig';
@@ -10,6 +12,69 @@ interface AccountDetailsDialogProps {
     onClose: () => void;
 }
 
+function DryRunSection({ account }: { account: Account }) {
+    const { t } = useTranslation();
+    const models = (account.quota?.models || []).map(m => m.name);
+    const [model, setModel] = useState(() => models.find(m => m.includes('claude')) || models[0] || 'claude-sonnet-4-5');
+    const [running, setRunning] = useState(false);
+    const [result, setResult] = useState<AccountDryRunResult | null>(null);
+    const [error, setError] = useState('');
+
+    const handleRun = async () => {
+        setRunning(true);
+        setResult(null);
+        setError('');
+        try {
+            setResult(await testAccountRequest(account.id, model.trim()));
+        } catch (e) {
+            setError(String(e));
+        } finally {
+            setRunning(false);
+        }
+    };
+
+    return (
+        <div className="mt-6">
+            <h4 className="text-xs font-bold text-gray-500 dark:text-gray-400 uppercase tracking-widest mb-3">{t('accounts.details.dry_run.title')}</h4>
+            <div className="flex gap-2 items-center">
+                <input
+                    list={`dry-run-models-${account.id}`}
+                    className="input input-sm input-bordered flex-1 font-mono text-xs"
+                    value={model}
+                    onChange={(e) => setModel(e.target.value)}
+                    disabled={running}
+                />
+                <datalist id={`dry-run-models-${account.id}`}>
+                    {models.map(m => <option key={m} value={m} />)}
+                </datalist>
+                <button className="btn btn-sm btn-primary gap-1" onClick={handleRun} disabled={running || !model.trim()}>
+                    {running ? <Loader2 size={14} className="animate-spin" /> : <Send size={14} />}
+                    {t('accounts.details.dry_run.run')}
+                </button>
+            </div>
+            <p className="text-[11px] text-gray-400 mt-1">{t('accounts.details.dry_run.desc')}</p>
+            {error && <div className="mt-3 text-xs text-red-500">{error}</div>}
+            {result && (
+                <div className={`mt-3 p-3 rounded-xl border text-xs font-mono space-y-1 ${result.success ? 'border-emerald-100 bg-emerald-50/40 dark:border-emerald-900/40 dark:bg-emerald-900/10' : 'border-red-100 bg-red-50/40 dark:border-red-900/40 dark:bg-red-900/10'}`}>
+                    <div className={`font-bold ${result.success ? 'text-emerald-700 dark:text-emerald-400' : 'text-red-600 dark:text-red-400'}`}>
+                        {result.success ? t('accounts.details.dry_run.ok') : t('accounts.details.dry_run.failed')}
+                    </div>
+                    <div>{t('accounts.details.dry_run.latency')}: {result.latency_ms} ms</div>
+                    <div>{t('accounts.details.dry_run.proxy_status')}: {result.proxy_status}</div>
+                    <div>
+                        {t('accounts.details.dry_run.upstream_status')}: {result.upstream_statuses.length > 0 ? result.upstream_statuses.join(' → ') : t('accounts.details.dry_run.not_reached')}
+                    </div>
+                    {result.served_by && result.served_by !== result.email && (
+                        <div className="text-orange-600">{t('accounts.details.dry_run.served_by', { email: result.served_by })}</div>
+                    )}
+                    {result.snippet && <div className="whitespace-pre-wrap break-all text-gray-600 dark:text-gray-300">{result.snippet}</div>}
+                    {result.error && <div className="whitespace-pre-wrap break-all text-red-500">{result.error}</div>}
+                </div>
+            )}
+        </div>
+    );
+}
+
 export default function AccountDetailsDialog({ account, onClose }: AccountDetailsDialogProps) {
     const { t } = useTranslation();
     if (!account) return null;
@@ -194,6 +259,8 @@ export default function AccountDetailsDialog({ account, onClose }: AccountDetail
                                 </div>
                             )}
                     </div>
+
+                    <DryRunSection key={account.id} account={account} />
                 </div>
             </div>
             <div className="modal-backdrop bg-black/40 backdrop-blur-sm" onClick={onClose}></div>
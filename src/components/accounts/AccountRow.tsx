@@ -1,6 +1,7 @@
 import { ArrowRightLeft, RefreshCw, Trash2, Download, Info, Lock, Ban, Diamond, Gem, Circle, Clock, ToggleLeft, ToggleRight, Fingerprint } from 'lucide-react';
 import { Account } from '../../types/account';
-import { getQuotaColor, formatTimeRemaining, getTimeRemainingColor } from '../../utils/format';
+import { getQuotaColor, formatTimeRemaining, getTimeRemainingColor, formatRelativeTime } from '../../utils/format';
+import { useConfigStore } from '../../stores/useConfigStore';
 import { cn } from '../../utils/cn';
 import { useTranslation } from 'react-i18next';
 
@@ -23,7 +24,8 @@ interface AccountRowProps {
 
 
 function AccountRow({ account, selected, onSelect, isCurrent, isRefreshing, isSwitching = false, onSwitch, onRefresh, onViewDetails, onExport, onDelete, onToggleProxy, onViewDevice }: AccountRowProps) {
-    const { t } = useTranslation();
+    const { t, i18n } = useTranslation();
+    const { networkStatus } = useConfigStore();
     // [重构] 按组聚合查找逻辑，优先显示组内配额最低的型号以与锁定状态（🔒）对齐
     const geminiProModel = account.quota?.models
         .filter(m =>
@@ -128,6 +130,14 @@ function AccountRow({ account, selected, onSelect, isCurrent, isRefreshing, isSw
                             </span>
                         )}
 
+                        {/* 离线时标注配额为缓存数据及其时效 */}
+                        {networkStatus?.offline && !!account.quota?.last_updated && (
+                            <span className="px-2 py-0.5 rounded-md bg-gray-100 dark:bg-white/10 text-gray-600 dark:text-gray-400 text-[10px] font-bold flex items-center gap-1 shadow-sm border border-gray-200 dark:border-white/10" title={t('accounts.quota_cached_tooltip')}>
+                                <Clock className="w-2.5 h-2.5" />
+                                <span>{t('accounts.quota_cached', { age: formatRelativeTime(account.quota.last_updated, i18n.language) })}</span>
+                            </span>
+                        )}
+
                         {/* 订阅类型徽章 */}
                         {account.quota?.subscription_tier && (() => {
                             const tier = account.quota.subscription_tier.toLowerCase();
@@ -1,6 +1,6 @@
 import { useState, useEffect, useRef } from 'react';
 import { createPortal } from 'react-dom';
-import { Plus, Database, Globe, FileClock, Loader2, CheckCircle2, XCircle, Copy, Check, Info, Link2 } from 'lucide-react';
+import { Plus, Database, Globe, FileClock, FileJson, Loader2, CheckCircle2, XCircle, Copy, Check, Info, Link2 } from 'lucide-react';
 import { useAccountStore } from '../../stores/useAccountStore';
 import { useTranslation } from 'react-i18next';
 import { listen } from '@tauri-apps/api/event';
@@ -8,6 +8,7 @@ import { open } from '@tauri-apps/plugin-dialog';
 import { request as invoke } from '../../utils/request';
 import { isTauri } from '../../utils/env';
 import { copyToClipboard } from '../../utils/clipboard';
+import { previewAccountImport, importAccountsFromFiles, type ImportPreview } from '../../services/accountService';
 
 interface AddAccountDialogProps {
     onAdd: (email: string, refreshToken: string) => Promise<void>;
@@ -413,6 +414,52 @@ function AddAccountDialog({ onAdd, showText = true }: AddAccountDialogProps) {
         handleAction(t('accounts.add.import.btn_v1'), importV1Accounts);
     };
 
+    const handleImportFromTools = async () => {
+        try {
+            if (!isTauri()) {
+                alert(t('common.tauri_api_not_loaded') || 'Storage import only works in desktop app.');
+                return;
+            }
+            const selectedFiles = await open({
+                multiple: true,
+                filters: [{ name: 'JSON', extensions: ['json'] }],
+            });
+            if (!selectedFiles || (Array.isArray(selectedFiles) && selectedFiles.length === 0)) return;
+
+            const files = Array.isArray(selectedFiles) ? selectedFiles : [selectedFiles];
+
+            setStatus('loading');
+            const previews = await Promise.all(files.map((file) => previewAccountImport(file).catch(() => null)));
+            const detected = previews.filter((p): p is ImportPreview => p !== null);
+            const formats = Array.from(new Set(detected.map((p) => p.format))).join(', ');
+            const count = detected.reduce((sum, p) => sum + p.count, 0);
+            setMessage(t('accounts.add.import.tools_detected', { count, formats: formats || '-' }));
+
+            const result = await importAccountsFromFiles(files);
+
+            if (result.success > 0) {
+                await fetchAccounts();
+            }
+
+            if (result.failed === 0 && result.success > 0) {
+                setStatus('success');
+                setMessage(t('accounts.add.codex_batch_import_success', { count: result.success }));
+            } else if (result.success > 0 && result.failed > 0) {
+                setStatus('success');
+                setMessage(t('accounts.add.codex_batch_import_partial', { success: result.success, failed: result.failed }));
+            } else if (result.failed > 0) {
+                setStatus('error');
+                setMessage(result.errors[0] || t('accounts.add.import.tools_empty'));
+            } else {
+                setStatus('error');
+                setMessage(t('accounts.add.import.tools_empty'));
+            }
+        } catch (error) {
+            setStatus('error');
+            setMessage(String(error));
+        }
+    };
+
     const handleImportCustomDb = async () => {
         try {
             if (!isTauri()) {
@@ -801,6 +848,26 @@ function AddAccountDialog({ onAdd, showText = true }: AddAccountDialogProps) {
                                                     {t('accounts.add.import.btn_v1')}
                                                 </button>
                                             </div>
+
+                                            <div className="divider text-xs text-gray-300 dark:text-gray-600">{t('accounts.add.import.or')}</div>
+
+                                            <div className="space-y-2">
+                                                <h4 className="font-semibold flex items-center gap-2 text-gray-800 dark:text-gray-200">
+                                                    <FileJson className="w-4 h-4 text-gray-600 dark:text-gray-400" />
+                                                    {t('accounts.add.import.scheme_c')}
+                                                </h4>
+                                                <p className="text-xs text-gray-500 dark:text-gray-400">
+                                                    {t('accounts.add.import.scheme_c_desc')}
+                                                </p>
+                                                <button
+                                                    className="w-full px-4 py-3 bg-gray-50 dark:bg-base-200 text-gray-700 dark:text-gray-300 font-medium rounded-xl border border-gray-200 dark:border-base-300 hover:bg-amber-50 dark:hover:bg-amber-900/20 hover:border-amber-200 dark:hover:border-amber-800 hover:text-amber-600 dark:hover:text-amber-400 transition-all flex items-center justify-center gap-2 disabled:opacity-50 disabled:cursor-not-allowed shadow-sm"
+                                                    onClick={handleImportFromTools}
+                                                    disabled={status === 'loading' || status === 'success'}
+                                                >
+                                                    <FileJson className="w-4 h-4" />
+                                                    {t('accounts.add.import.btn_tools')}
+                                                </button>
+                                            </div>
                                         </div>
                                     )}
                                 </>
@@ -5,6 +5,7 @@ import { Account, DeviceProfile, DeviceProfileVersion } from '../../types/accoun
 import * as accountService from '../../services/accountService';
 import { useTranslation } from 'react-i18next';
 import { isTauri } from '../../utils/env';
+import { BackendError, isBackendError } from '../../utils/errors';
 
 interface DeviceFingerprintDialogProps {
     account: Account | null;
@@ -30,10 +31,9 @@ export default function DeviceFingerprintDialog({ account, onClose }: DeviceFing
             const res = await accountService.getDeviceProfiles(target.id);
             setDeviceProfiles(res);
         } catch (e: any) {
-            const errorMsg = typeof e === 'string' ? e : e.message || '';
-            const translated = errorMsg === 'storage_json_not_found'
+            const translated = isBackendError(e, 'storage_json_not_found')
                 ? t('accounts.device_fingerprint_dialog.storage_json_not_found')
-                : (typeof e === 'string' ? e : t('accounts.device_fingerprint_dialog.failed_to_load_device_info'));
+                : (e instanceof BackendError ? e.message : t('accounts.device_fingerprint_dialog.failed_to_load_device_info'));
             setActionMessage(translated);
         } finally {
             setLoadingDevice(false);
@@ -51,7 +51,7 @@ export default function DeviceFingerprintDialog({ account, onClose }: DeviceFing
             setConfirmProfile(profile);
             setConfirmType('generate');
         } catch (e: any) {
-            setActionMessage(typeof e === 'string' ? e : t('accounts.device_fingerprint_dialog.generation_failed'));
+            setActionMessage(e instanceof BackendError ? e.message : t('accounts.device_fingerprint_dialog.generation_failed'));
         } finally {
             setActionLoading(null);
         }
@@ -67,7 +67,7 @@ export default function DeviceFingerprintDialog({ account, onClose }: DeviceFing
             setConfirmType(null);
             await fetchDevice(account); // Refresh history
         } catch (e: any) {
-            setActionMessage(typeof e === 'string' ? e : t('accounts.device_fingerprint_dialog.binding_failed'));
+            setActionMessage(e instanceof BackendError ? e.message : t('accounts.device_fingerprint_dialog.binding_failed'));
         } finally {
             setActionLoading(null);
         }
@@ -92,7 +92,7 @@ export default function DeviceFingerprintDialog({ account, onClose }: DeviceFing
             setConfirmType(null);
             await fetchDevice(account);
         } catch (e: any) {
-            setActionMessage(typeof e === 'string' ? e : t('accounts.device_fingerprint_dialog.restoration_failed'));
+            setActionMessage(e instanceof BackendError ? e.message : t('accounts.device_fingerprint_dialog.restoration_failed'));
         } finally {
             setActionLoading(null);
         }
@@ -106,7 +106,7 @@ export default function DeviceFingerprintDialog({ account, onClose }: DeviceFing
             setActionMessage(t('accounts.device_fingerprint_dialog.restored'));
             await fetchDevice(account);
         } catch (e: any) {
-            setActionMessage(typeof e === 'string' ? e : t('accounts.device_fingerprint_dialog.restoration_failed'));
+            setActionMessage(e instanceof BackendError ? e.message : t('accounts.device_fingerprint_dialog.restoration_failed'));
         } finally {
             setActionLoading(null);
         }
@@ -120,7 +120,7 @@ export default function DeviceFingerprintDialog({ account, onClose }: DeviceFing
             setActionMessage(t('accounts.device_fingerprint_dialog.deleted'));
             await fetchDevice(account);
         } catch (e: any) {
-            setActionMessage(typeof e === 'string' ? e : t('accounts.device_fingerprint_dialog.deletion_failed'));
+            setActionMessage(e instanceof BackendError ? e.message : t('accounts.device_fingerprint_dialog.deletion_failed'));
         } finally {
             setActionLoading(null);
         }
@@ -132,7 +132,7 @@ export default function DeviceFingerprintDialog({ account, onClose }: DeviceFing
             await accountService.openDeviceFolder();
             setActionMessage(t('accounts.device_fingerprint_dialog.directory_opened'));
         } catch (e: any) {
-            setActionMessage(typeof e === 'string' ? e : t('accounts.device_fingerprint_dialog.directory_open_failed'));
+            setActionMessage(e instanceof BackendError ? e.message : t('accounts.device_fingerprint_dialog.directory_open_failed'));
         } finally {
             setActionLoading(null);
         }
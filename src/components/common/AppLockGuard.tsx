@@ -0,0 +1,127 @@
+import React, { useState, useEffect, useCallback } from 'react';
+import { Lock, Key, AlertCircle, Loader2 } from 'lucide-react';
+import { useTranslation } from 'react-i18next';
+import { isTauri } from '../../utils/env';
+import { getAppLockStatus, reportAppLockActivity, unlockApp } from '../../services/configService';
+
+const STATUS_POLL_MS = 15_000;
+const ACTIVITY_THROTTLE_MS = 30_000;
+
+/**
+ * AppLockGuard
+ * 桌面端主密码应用锁。锁定状态由后端决定 (命令层同样拦截)，这里只负责显示解锁界面、
+ * 上报用户活动心跳，并定时轮询状态以便空闲超时后及时盖住界面。
+ */
+export const AppLockGuard: React.FC<{ children: React.ReactNode }> = ({ children }) => {
+    const { t } = useTranslation();
+    const [locked, setLocked] = useState(false);
+    const [password, setPassword] = useState('');
+    const [isLoading, setIsLoading] = useState(false);
+    const [error, setError] = useState('');
+
+    const refreshStatus = useCallback(async () => {
+        try {
+            const status = await getAppLockStatus();
+            setLocked(status.locked);
+        } catch (err) {
+            console.error('[AppLock] Failed to get status:', err);
+        }
+    }, []);
+
+    useEffect(() => {
+        if (!isTauri()) return;
+        refreshStatus();
+        const timer = setInterval(refreshStatus, STATUS_POLL_MS);
+        return () => clearInterval(timer);
+    }, [refreshStatus]);
+
+    // 用户活动心跳 (节流)
+    useEffect(() => {
+        if (!isTauri() || locked) return;
+        let lastReport = 0;
+        const onActivity = () => {
+            const now = Date.now();
+            if (now - lastReport < ACTIVITY_THROTTLE_MS) return;
+            lastReport = now;
+            reportAppLockActivity()
+                .then((status) => setLocked(status.locked))
+                .catch(() => { });
+        };
+        const events = ['mousedown', 'keydown', 'wheel', 'touchstart'];
+        events.forEach((e) => window.addEventListener(e, onActivity, { passive: true }));
+        return () => events.forEach((e) => window.removeEventListener(e, onActivity));
+    }, [locked]);
+
+    const handleUnlock = async (e: React.FormEvent) => {
+        e.preventDefault();
+        if (!password) return;
+
+        setIsLoading(true);
+        setError('');
+        try {
+            await unlockApp(password);
+            setPassword('');
+            // 锁定期间的数据请求都被拒绝过，重新加载以恢复界面状态
+            window.location.reload();
+        } catch (err) {
+            setError(String(err));
+        } finally {
+            setIsLoading(false);
+        }
+    };
+
+    return (
+        <>
+            {children}
+            {locked && (
+                <div className="fixed inset-0 z-[9999] bg-slate-50 dark:bg-base-300 flex items-center justify-center p-4">
+                    <div className="max-w-md w-full bg-white dark:bg-base-100 rounded-3xl shadow-xl overflow-hidden border border-slate-100 dark:border-white/5">
+                        <div className="p-8">
+                            <div className="w-16 h-16 bg-blue-50 dark:bg-blue-900/20 rounded-2xl flex items-center justify-center mb-6 mx-auto">
+                                <Lock className="w-8 h-8 text-blue-500" />
+                            </div>
+                            <h2 className="text-2xl font-bold text-center text-slate-900 dark:text-slate-100 mb-2 font-display">
+                                {t('app_lock.title', { defaultValue: 'App Locked' })}
+                            </h2>
+                            <p className="text-center text-slate-500 dark:text-slate-400 mb-8 text-sm">
+                                {t('app_lock.desc', { defaultValue: 'Enter the master password to continue.' })}
+                            </p>
+
+                            <form onSubmit={handleUnlock} className="space-y-6">
+                                <div className="relative">
+                                    <Key className="absolute left-4 top-1/2 -translate-y-1/2 w-5 h-5 text-slate-400" />
+                                    <input
+                                        type="password"
+                                        placeholder={t('app_lock.placeholder', { defaultValue: 'Master password' })}
+                                        className={`w-full pl-12 pr-4 py-4 bg-slate-50 dark:bg-base-200 border-2 rounded-2xl focus:ring-2 focus:ring-blue-500 transition-all outline-none text-slate-900 dark:text-white ${error ? 'border-red-400' : 'border-transparent'}`}
+                                        value={password}
+                                        onChange={(e) => { setPassword(e.target.value); setError(''); }}
+                                        autoFocus
+                                        disabled={isLoading}
+                                    />
+                                </div>
+                                {error && (
+                                    <div className="flex items-center gap-2 text-red-500 text-sm">
+                                        <AlertCircle className="w-4 h-4" />
+                                        <span>{error}</span>
+                                    </div>
+                                )}
+                                <button
+                                    type="submit"
+                                    disabled={isLoading || !password}
+                                    className="w-full py-4 bg-blue-500 hover:bg-blue-600 disabled:bg-blue-300 disabled:cursor-not-allowed text-white font-bold rounded-2xl shadow-lg shadow-blue-500/30 transition-all active:scale-[0.98] flex items-center justify-center gap-2"
+                                >
+                                    {isLoading ? (
+                                        <Loader2 className="w-5 h-5 animate-spin" />
+                                    ) : (
+                                        t('app_lock.btn_unlock', { defaultValue: 'Unlock' })
+                                    )}
+                                </button>
+                            </form>
+                        </div>
+                    </div>
+                </div>
+            )}
+        </>
+    );
+};
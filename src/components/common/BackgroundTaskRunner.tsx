@@ -3,8 +3,8 @@ import { useConfigStore } from '../../stores/useConfigStore';
 import { useAccountStore } from '../../stores/useAccountStore';
 
 function BackgroundTaskRunner() {
-    const { config } = useConfigStore();
-    const { refreshAllQuotas } = useAccountStore();
+    const { config, refreshNetworkStatus } = useConfigStore();
+    const { refreshAllQuotas, refreshDueQuotas } = useAccountStore();
 
     // Use refs to track previous state to detect "off -> on" transitions
     const prevAutoRefreshRef = useRef(false);
@@ -25,11 +25,11 @@ function BackgroundTaskRunner() {
         prevAutoRefreshRef.current = auto_refresh;
 
         if (auto_refresh && refresh_interval > 0) {
-            console.log(`[BackgroundTask] Starting auto-refresh quota timer: ${refresh_interval} mins`);
+            // 后端按账号自适应间隔 + 抖动判断是否到期，这里只需定期轮询
+            console.log(`[BackgroundTask] Starting adaptive auto-refresh timer (base: ${refresh_interval} mins)`);
             intervalId = setInterval(() => {
-                console.log('[BackgroundTask] Auto-refreshing all quotas...');
-                refreshAllQuotas();
-            }, Math.min(refresh_interval * 60 * 1000, 2147483647));
+                refreshDueQuotas().catch((e) => console.warn('[BackgroundTask] Scheduled refresh failed:', e));
+            }, 60 * 1000);
         }
 
         return () => {
@@ -71,6 +71,13 @@ function BackgroundTaskRunner() {
         };
     }, [config?.auto_sync, config?.sync_interval]);
 
+    // Network / Offline Status Effect (后端自动探测，这里只轮询状态用于界面提示)
+    useEffect(() => {
+        refreshNetworkStatus();
+        const intervalId = setInterval(refreshNetworkStatus, 30 * 1000);
+        return () => clearInterval(intervalId);
+    }, [config?.offline?.manual]);
+
     // Render nothing
     return null;
 }
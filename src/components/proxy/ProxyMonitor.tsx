@@ -3,13 +3,15 @@ import { listen } from '@tauri-apps/api/event';
 import ModalDialog from '../common/ModalDialog';
 import { useTranslation } from 'react-i18next';
 import { request as invoke } from '../../utils/request';
-import { Trash2, Search, X, Copy, CheckCircle, ChevronLeft, ChevronRight, RefreshCw, User } from 'lucide-react';
+import { Trash2, Search, X, Copy, CheckCircle, ChevronLeft, ChevronRight, RefreshCw, User, Loader2, RotateCcw } from 'lucide-react';
 
 import { AppConfig } from '../../types/config';
 import { formatCompactNumber } from '../../utils/format';
 import { useAccountStore } from '../../stores/useAccountStore';
 import { isTauri } from '../../utils/env';
 import { copyToClipboard } from '../../utils/clipboard';
+import { replayRequest } from '../../services/accountService';
+import { Account, ReplayResult } from '../../types/account';
 
 
 interface ProxyRequestLog {
@@ -40,6 +42,84 @@ interface ProxyMonitorProps {
     className?: string;
 }
 
+// 重放请求日志并显示与原响应的逐行差异
+function ReplaySection({ log, accounts }: { log: ProxyRequestLog; accounts: Account[] }) {
+    const { t } = useTranslation();
+    const [target, setTarget] = useState('');
+    const [running, setRunning] = useState(false);
+    const [result, setResult] = useState<ReplayResult | null>(null);
+    const [error, setError] = useState('');
+
+    const handleReplay = async () => {
+        setRunning(true);
+        setResult(null);
+        setError('');
+        try {
+            setResult(await replayRequest(log.id, target || undefined));
+        } catch (e) {
+            setError(String(e));
+        } finally {
+            setRunning(false);
+        }
+    };
+
+    const lineClass = (op: string) =>
+        op === 'added' ? 'bg-emerald-50 text-emerald-700 dark:bg-emerald-900/20 dark:text-emerald-400' :
+            op === 'removed' ? 'bg-red-50 text-red-600 dark:bg-red-900/20 dark:text-red-400' :
+                'text-gray-600 dark:text-gray-300';
+
+    return (
+        <div>
+            <h3 className="text-xs font-bold uppercase text-gray-400 mb-2">{t('monitor.replay.title')}</h3>
+            <div className="flex gap-2 items-center">
+                <select
+                    className="select select-sm select-bordered flex-1 text-xs"
+                    value={target}
+                    onChange={(e) => setTarget(e.target.value)}
+                    disabled={running}
+                >
+                    <option value="">{t('monitor.replay.normal_rotation')}</option>
+                    {accounts.map(acc => <option key={acc.id} value={acc.id}>{acc.email}</option>)}
+                </select>
+                <button className="btn btn-sm btn-primary gap-1" onClick={handleReplay} disabled={running || !log.request_body}>
+                    {running ? <Loader2 size={14} className="animate-spin" /> : <RotateCcw size={14} />}
+                    {t('monitor.replay.run')}
+                </button>
+            </div>
+            <p className="text-[11px] text-gray-400 mt-1">{t('monitor.replay.desc')}</p>
+            {error && <div className="mt-3 text-xs text-red-500">{error}</div>}
+            {result && (
+                <div className="mt-3 space-y-2 text-xs font-mono">
+                    <div className="grid grid-cols-2 gap-2">
+                        {([['original', result.original], ['replay', result.replay]] as const).map(([key, side]) => (
+                            <div key={key} className="p-2 rounded-lg border border-gray-100 dark:border-base-300 bg-gray-50 dark:bg-base-300">
+                                <div className="font-bold text-gray-500 dark:text-gray-400">{t(`monitor.replay.${key}`)}</div>
+                                <div>HTTP {side.status} · {side.duration_ms} ms</div>
+                                <div className="truncate">{side.account_email || '-'}</div>
+                            </div>
+                        ))}
+                    </div>
+                    {result.upstream_statuses.length > 0 && (
+                        <div>{t('monitor.replay.upstream_status')}: {result.upstream_statuses.join(' → ')}</div>
+                    )}
+                    <div className={`font-bold ${result.identical ? 'text-emerald-600' : 'text-orange-600'}`}>
+                        {result.identical ? t('monitor.replay.identical') : t('monitor.replay.differs')}
+                    </div>
+                    {!result.identical && (
+                        <div className="max-h-80 overflow-auto rounded-lg border border-gray-100 dark:border-base-300">
+                            {result.diff.map((line, i) => (
+                                <div key={i} className={`px-2 whitespace-pre-wrap break-all ${lineClass(line.op)}`}>
+                                    {line.op === 'added' ? '+ ' : line.op === 'removed' ? '- ' : '  '}{line.text}
+                                </div>
+                            ))}
+                        </div>
+                    )}
+                </div>
+            )}
+        </div>
+    );
+}
+
 // Log Table Component
 interface LogTableProps {
     logs: ProxyRequestLog[];
@@ -711,6 +791,9 @@ export const ProxyMonitor: React.FC<ProxyMonitorProps> = ({ className }) => {
                                     </div>
                                     <div className="bg-gray-50 dark:bg-base-300 rounded-lg p-3 border border-gray-100 dark:border-base-300 overflow-hidden">{formatBody(selectedLog.response_body)}</div>
                                 </div>
+                                {selectedLog.method === 'POST' && (
+                                    <ReplaySection key={selectedLog.id} log={selectedLog} accounts={accounts} />
+                                )}
                             </div>
                         </div>
                     </div>
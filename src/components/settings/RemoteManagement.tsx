@@ -0,0 +1,93 @@
+import { useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { Server, PlugZap } from 'lucide-react';
+import { request as invoke } from '../../utils/request';
+import { showToast } from '../common/ToastContainer';
+import { RemoteConfig } from '../../types/config';
+
+interface RemoteManagementProps {
+    config: RemoteConfig;
+    onChange: (config: RemoteConfig) => void;
+}
+
+// 远程管理模式：连接到另一台 (通常是无界面) 实例的管理 API
+// 令牌在配置文件中加密保存，回传给后端时会自动解密
+export default function RemoteManagement({ config, onChange }: RemoteManagementProps) {
+    const { t } = useTranslation();
+    const [isTesting, setIsTesting] = useState(false);
+
+    const handleTest = async () => {
+        if (!config.url.trim()) {
+            showToast(t('settings.remote.url_required'), 'error');
+            return;
+        }
+        setIsTesting(true);
+        try {
+            await invoke('test_remote_connection', { remote: { ...config, enabled: true } });
+            showToast(t('settings.remote.test_success'), 'success');
+        } catch (error) {
+            showToast(`${t('settings.remote.test_failed')}: ${error}`, 'error');
+        } finally {
+            setIsTesting(false);
+        }
+    };
+
+    return (
+        <div className="space-y-4">
+            <div className="flex items-center justify-between">
+                <div className="flex items-center gap-3">
+                    <Server className="w-5 h-5 text-blue-500" />
+                    <div>
+                        <div className="text-sm font-medium text-gray-900 dark:text-base-content">{t('settings.remote.title')}</div>
+                        <p className="text-xs text-gray-500 dark:text-gray-400 mt-0.5">{t('settings.remote.desc')}</p>
+                    </div>
+                </div>
+                <label className="relative inline-flex items-center cursor-pointer">
+                    <input
+                        type="checkbox"
+                        className="sr-only peer"
+                        checked={config.enabled}
+                        onChange={(e) => onChange({ ...config, enabled: e.target.checked })}
+                    />
+                    <div className="w-11 h-6 bg-gray-200 dark:bg-base-300 peer-focus:outline-none peer-focus:ring-4 peer-focus:ring-blue-300 dark:peer-focus:ring-blue-800 rounded-full peer peer-checked:after:translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-[2px] after:left-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all peer-checked:bg-blue-500"></div>
+                </label>
+            </div>
+
+            <div className="grid grid-cols-1 md:grid-cols-2 gap-3">
+                <div>
+                    <label className="block text-xs font-medium text-gray-700 dark:text-gray-300 mb-1">{t('settings.remote.url')}</label>
+                    <input
+                        type="text"
+                        className="w-full px-4 py-3 border border-gray-200 dark:border-base-300 rounded-lg bg-gray-50 dark:bg-base-200 text-gray-900 dark:text-base-content font-medium"
+                        placeholder="http://192.168.1.10:8045"
+                        value={config.url}
+                        onChange={(e) => onChange({ ...config, url: e.target.value })}
+                    />
+                </div>
+                <div>
+                    <label className="block text-xs font-medium text-gray-700 dark:text-gray-300 mb-1">{t('settings.remote.token')}</label>
+                    <input
+                        type="password"
+                        autoComplete="off"
+                        className="w-full px-4 py-3 border border-gray-200 dark:border-base-300 rounded-lg bg-gray-50 dark:bg-base-200 text-gray-900 dark:text-base-content font-medium"
+                        placeholder={t('settings.remote.token_placeholder')}
+                        value={config.token}
+                        onChange={(e) => onChange({ ...config, token: e.target.value })}
+                    />
+                </div>
+            </div>
+
+            <div className="flex items-center justify-between gap-3">
+                <p className="text-xs text-gray-500 dark:text-gray-400">{t('settings.remote.save_hint')}</p>
+                <button
+                    className="px-4 py-2 border border-gray-200 dark:border-base-300 text-gray-700 dark:text-gray-300 rounded-lg hover:bg-gray-50 dark:hover:bg-base-200 transition-colors flex items-center gap-2 shrink-0 disabled:opacity-50"
+                    onClick={handleTest}
+                    disabled={isTesting}
+                >
+                    <PlugZap size={14} className={isTesting ? 'animate-pulse' : ''} />
+                    {t('settings.remote.test_btn')}
+                </button>
+            </div>
+        </div>
+    );
+}
@@ -50,6 +50,18 @@ const SmartWarmup: React.FC<SmartWarmupProps> = ({ config, onChange }) => {
         onChange({ ...config, monitored_models: newModels });
     };
 
+    const pacingFields = [
+        { key: 'window_secs', fallback: 300, unit: 's' },
+        { key: 'per_account_interval_secs', fallback: 30, unit: 's' },
+        { key: 'max_per_minute', fallback: 6, unit: '/min' },
+    ] as const;
+
+    const handlePacingChange = (key: typeof pacingFields[number]['key'], value: string) => {
+        const num = parseInt(value);
+        if (isNaN(num) || num < 0) return;
+        onChange({ ...config, [key]: num });
+    };
+
     return (
         <div className="space-y-4">
             <div className="flex items-center justify-between">
@@ -118,6 +130,33 @@ const SmartWarmup: React.FC<SmartWarmupProps> = ({ config, onChange }) => {
                                 {t('settings.quota_protection.monitored_models_desc', '勾选需要监控的模型。当选中的任一模型利用率跌破阈值时，将触发保护')}
                             </p>
                         </div>
+                        <div>
+                            <label className="text-[10px] font-bold text-gray-400 dark:text-gray-500 uppercase tracking-widest block mb-2">
+                                {t('settings.warmup.pacing_label', '预热节流')}
+                            </label>
+                            <div className="grid grid-cols-3 gap-2">
+                                {pacingFields.map(({ key, fallback, unit }) => (
+                                    <div key={key}>
+                                        <span className="text-[11px] text-gray-500 dark:text-gray-400 block mb-1">
+                                            {t(`settings.warmup.${key}`)}
+                                        </span>
+                                        <div className="relative">
+                                            <input
+                                                type="number"
+                                                min="0"
+                                                value={config[key] ?? fallback}
+                                                onChange={(e) => handlePacingChange(key, e.target.value)}
+                                                className="w-full bg-white dark:bg-base-100 border border-gray-200 dark:border-gray-700 rounded-lg px-3 py-1.5 text-sm font-mono focus:ring-2 focus:ring-orange-500/20 outline-none transition-all [appearance:textfield] [&::-webkit-outer-spin-button]:appearance-none [&::-webkit-inner-spin-button]:appearance-none"
+                                            />
+                                            <span className="absolute right-3 top-1/2 -translate-y-1/2 text-[10px] font-bold opacity-30 select-none pointer-events-none">{unit}</span>
+                                        </div>
+                                    </div>
+                                ))}
+                            </div>
+                            <p className="text-[10px] text-gray-400 dark:text-gray-500 mt-2 leading-relaxed">
+                                {t('settings.warmup.pacing_desc')}
+                            </p>
+                        </div>
                     </div>
                 </div>
             )}
@@ -14,6 +14,7 @@ import ko from "./locales/ko.json";
 import ar from "./locales/ar.json";
 import es from "./locales/es.json";
 import my from "./locales/my.json";
+import { getLocaleMessages } from "./services/configService";
 
 i18n
     // detect user language
@@ -92,4 +93,19 @@ i18n
         },
     });
 
+/**
+ * 加载数据目录 locales/ 下的语言包 (用户覆盖 / 社区翻译)
+ * 后端返回内置字符串与用户文件合并后的结果，失败时保持内置资源不变
+ */
+export async function loadLocaleOverrides(language: string): Promise<void> {
+    try {
+        const messages = await getLocaleMessages(language);
+        if (messages && Object.keys(messages).length > 0) {
+            i18n.addResourceBundle(language, "translation", messages, true, true);
+        }
+    } catch (error) {
+        console.warn(`[i18n] Failed to load locale overrides for ${language}:`, error);
+    }
+}
+
 export default i18n;
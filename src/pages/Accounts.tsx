@@ -29,6 +29,7 @@ import { Account } from "../types/account";
 import { cn } from "../utils/cn";
 import { isTauri } from "../utils/env";
 import { request as invoke } from "../utils/request";
+import { isBackendError } from "../utils/errors";
 import { useTranslation } from "react-i18next";
 
 type FilterType = "all" | "pro" | "ultra" | "free" | "codex";
@@ -48,6 +49,7 @@ function Accounts() {
     loading,
     refreshQuota,
     toggleProxyStatus,
+    setProxyStatusBulk,
     reorderAccounts,
     warmUpAccounts,
     warmUpAccount,
@@ -380,12 +382,24 @@ function Accounts() {
     setIsBatchDelete(true);
   };
 
+  // 域名策略要求二次确认的账号: 后端返回 delete_requires_confirmation 时询问后重试
+  const withDomainPolicyConfirm = async (run: (confirmed?: boolean) => Promise<void>): Promise<boolean> => {
+    try {
+      await run();
+    } catch (error) {
+      if (!isBackendError(error, "delete_requires_confirmation")) throw error;
+      if (!confirm(t("accounts.confirm_policy_delete", { defaultValue: "{{detail}}\n\nDelete anyway?", detail: String(error) }))) return false;
+      await run(true);
+    }
+    return true;
+  };
+
   const executeBatchDelete = async () => {
     setIsBatchDelete(false);
     try {
       const ids = Array.from(selectedIds);
       console.log("[Accounts] Batch deleting:", ids);
-      await deleteAccounts(ids);
+      if (!(await withDomainPolicyConfirm((confirmed) => deleteAccounts(ids, confirmed)))) return;
       setSelectedIds(new Set());
       console.log("[Accounts] Batch delete success");
       showToast(t("common.success"), "success");
@@ -405,7 +419,8 @@ function Accounts() {
 
     try {
       console.log("[Accounts] Executing delete for:", deleteConfirmId);
-      await deleteAccount(deleteConfirmId);
+      const accountId = deleteConfirmId;
+      if (!(await withDomainPolicyConfirm((confirmed) => deleteAccount(accountId, confirmed)))) return;
       console.log("[Accounts] Delete success");
       showToast(t("common.success"), "success");
     } catch (error) {
@@ -444,19 +459,19 @@ function Accounts() {
     if (selectedIds.size === 0) return;
 
     try {
-      const promises = Array.from(selectedIds).map((id) =>
-        toggleProxyStatus(
-          id,
-          enable,
-          enable ? undefined : t("accounts.proxy_disabled_reason_batch"),
-        ),
+      const result = await setProxyStatusBulk(
+        Array.from(selectedIds),
+        enable,
+        enable ? undefined : t("accounts.proxy_disabled_reason_batch"),
       );
-      await Promise.all(promises);
+      if (result.failed.length > 0) {
+        console.warn("[Accounts] Batch toggle proxy partially failed:", result.failed);
+      }
       showToast(
         enable
-          ? t("accounts.toast.proxy_enabled", { count: selectedIds.size })
-          : t("accounts.toast.proxy_disabled", { count: selectedIds.size }),
-        "success",
+          ? t("accounts.toast.proxy_enabled", { count: result.updated.length })
+          : t("accounts.toast.proxy_disabled", { count: result.updated.length }),
+        result.failed.length > 0 ? "warning" : "success",
       );
       setSelectedIds(new Set());
     } catch (error) {
@@ -466,6 +481,7 @@ function Accounts() {
   };
 
   const [isRefreshing, setIsRefreshing] = useState(false);
+  const refreshProgress = useAccountStore((state) => state.refreshProgress);
   const [isRefreshConfirmOpen, setIsRefreshConfirmOpen] = useState(false);
 
   const handleRefreshClick = () => {
@@ -1005,7 +1021,9 @@ function Accounts() {
             />
             <span className="hidden xl:inline">
               {isRefreshing
-                ? t("common.loading")
+                ? refreshProgress
+                  ? `${refreshProgress.done}/${refreshProgress.total}`
+                  : t("common.loading")
                 : selectedIds.size > 0
                   ? t("accounts.refresh_selected", { count: selectedIds.size })
                   : t("accounts.refresh_all")}
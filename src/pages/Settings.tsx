@@ -3,7 +3,9 @@ import { Save, Github, User, MessageCircle, ExternalLink, RefreshCw, Heart, Coff
 import { request as invoke } from '../utils/request';
 import { open } from '@tauri-apps/plugin-dialog';
 import { useConfigStore } from '../stores/useConfigStore';
-import { AppConfig } from '../types/config';
+import { AntigravityInstallation, AppConfig, LanguageInfo, MenuBarTitle } from '../types/config';
+import { detectAntigravityInstallations, listAvailableLanguages, moveDataDir } from '../services/configService';
+import { loadLocaleOverrides } from '../i18n';
 import ModalDialog from '../components/common/ModalDialog';
 import { showToast } from '../components/common/ToastContainer';
 import QuotaProtection from '../components/settings/QuotaProtection';
@@ -17,11 +19,12 @@ import { relaunch } from '@tauri-apps/plugin-process';
 
 import DebugConsole from '../components/debug/DebugConsole';
 import ProxyPoolSettings from '../components/settings/ProxyPoolSettings';
+import RemoteManagement from '../components/settings/RemoteManagement';
 
 
 function Settings() {
     const { t, i18n } = useTranslation();
-    const { config, loadConfig, saveConfig, updateLanguage, updateTheme } = useConfigStore();
+    const { config, loadConfig, saveConfig, updateLanguage, updateTheme, networkStatus, setOfflineMode } = useConfigStore();
     const { enable, disable, isEnabled } = useDebugConsole();
     const [activeTab, setActiveTab] = useState<'general' | 'account' | 'proxy' | 'advanced' | 'debug' | 'about'>('general');
     const [formData, setFormData] = useState<AppConfig>({
@@ -57,7 +60,10 @@ function Settings() {
         },
         scheduled_warmup: {
             enabled: false,
-            monitored_models: []
+            monitored_models: [],
+            window_secs: 300,
+            per_account_interval_secs: 30,
+            max_per_minute: 6
         },
         quota_protection: {
             enabled: false,
@@ -84,6 +90,9 @@ function Settings() {
     // Dialog state
     // Dialog state
     const [isClearLogsOpen, setIsClearLogsOpen] = useState(false);
+    const [moveDataDirTarget, setMoveDataDirTarget] = useState<string | null>(null);
+    const [isMovingDataDir, setIsMovingDataDir] = useState(false);
+    const [installations, setInstallations] = useState<AntigravityInstallation[]>([]);
     const [isSupportModalOpen, setIsSupportModalOpen] = useState(false);
     const [dataDirPath, setDataDirPath] = useState<string>('~/.antigravity_tools/');
 
@@ -108,10 +117,30 @@ function Settings() {
     const [isBrewConfirmOpen, setIsBrewConfirmOpen] = useState(false);
     const [isBrewSuccessOpen, setIsBrewSuccessOpen] = useState(false);
 
+    // 界面语言列表 (内置 + 数据目录 locales/ 下的社区翻译)
+    const [languages, setLanguages] = useState<LanguageInfo[]>([
+        { code: 'zh', name: '简体中文', builtin: true, user_provided: false },
+        { code: 'zh-TW', name: '繁體中文', builtin: true, user_provided: false },
+        { code: 'en', name: 'English', builtin: true, user_provided: false },
+        { code: 'ja', name: '日本語', builtin: true, user_provided: false },
+        { code: 'tr', name: 'Türkçe', builtin: true, user_provided: false },
+        { code: 'vi', name: 'Tiếng Việt', builtin: true, user_provided: false },
+        { code: 'pt', name: 'Português', builtin: true, user_provided: false },
+        { code: 'ko', name: '한국어', builtin: true, user_provided: false },
+        { code: 'ru', name: 'Русский', builtin: true, user_provided: false },
+        { code: 'ar', name: 'العربية', builtin: true, user_provided: false },
+    ]);
+
 
     useEffect(() => {
         loadConfig();
 
+        listAvailableLanguages()
+            .then(list => {
+                if (list.length > 0) setLanguages(list);
+            })
+            .catch(err => console.error('Failed to list languages:', err));
+
         // 获取真实数据目录路径
         invoke<string>('get_data_dir_path')
             .then(path => setDataDirPath(path))
@@ -162,9 +191,16 @@ function Settings() {
                 return;
             }
 
+            const remoteChanged = JSON.stringify(config?.remote ?? null) !== JSON.stringify(formData.remote ?? null);
             await saveConfig(formData);
             showToast(t('common.saved'), 'success');
 
+            // 切换了远程实例 (或开关远程模式)：重新加载页面，让各页面从新的端点拉取数据
+            if (remoteChanged) {
+                window.location.reload();
+                return;
+            }
+
             // 如果修改了代理配置，提示用户需要重启
             if (proxyEnabled && proxyUrl) {
                 showToast(t('proxy.config.upstream_proxy.restart_hint'), 'info');
@@ -192,6 +228,36 @@ function Settings() {
         }
     };
 
+    const handleSelectDataDirTarget = async () => {
+        try {
+            const selected = await open({
+                directory: true,
+                multiple: false,
+                title: t('settings.advanced.move_data_dir_title'),
+            });
+            if (selected && typeof selected === 'string') {
+                setMoveDataDirTarget(selected);
+            }
+        } catch (error) {
+            showToast(`${t('common.error')}: ${error}`, 'error');
+        }
+    };
+
+    const confirmMoveDataDir = async () => {
+        if (!moveDataDirTarget) return;
+        setIsMovingDataDir(true);
+        try {
+            const report = await moveDataDir(moveDataDirTarget);
+            setDataDirPath(report.to);
+            showToast(t('settings.advanced.data_dir_moved', { count: report.files }), 'success');
+        } catch (error) {
+            showToast(`${t('common.error')}: ${error}`, 'error');
+        } finally {
+            setIsMovingDataDir(false);
+            setMoveDataDirTarget(null);
+        }
+    };
+
     const handleSelectExportPath = async () => {
         try {
             // @ts-ignore
@@ -249,9 +315,15 @@ function Settings() {
 
     const handleDetectAntigravityPath = async () => {
         try {
-            const command = isTauri() ? 'get_antigravity_path' : 'get_antigravity_path'; // 后端已统一
-            const path = await invoke<string>(command, { bypassConfig: true });
-            setFormData({ ...formData, antigravity_executable: path });
+            const found = await detectAntigravityInstallations();
+            if (found.length === 0) {
+                setInstallations([]);
+                showToast(t('settings.advanced.antigravity_not_found'), 'error');
+                return;
+            }
+            // 多个安装时展示候选列表供选择，只有一个时直接填入
+            setInstallations(found.length > 1 ? found : []);
+            setFormData({ ...formData, antigravity_executable: found[0].path });
             showToast(t('settings.advanced.antigravity_path_detected'), 'success');
         } catch (error) {
             showToast(`${t('common.error')}: ${error}`, 'error');
@@ -435,21 +507,17 @@ function Settings() {
                                     onChange={(e) => {
                                         const newLang = e.target.value;
                                         setFormData({ ...formData, language: newLang });
-                                        i18n.changeLanguage(newLang);
+                                        loadLocaleOverrides(newLang).then(() => i18n.changeLanguage(newLang));
                                         updateLanguage(newLang);
                                     }}
                                 >
-                                    <option value="zh">简体中文</option>
-                                    <option value="zh-TW">繁體中文</option>
-                                    <option value="en">English</option>
-                                    <option value="ja">日本語</option>
-                                    <option value="tr">Türkçe</option>
-                                    <option value="vi">Tiếng Việt</option>
-                                    <option value="pt">Português</option>
-                                    <option value="ko">한국어</option>
-                                    <option value="ru">Русский</option>
-                                    <option value="ar">العربية</option>
+                                    {languages.map(lang => (
+                                        <option key={lang.code} value={lang.code}>
+                                            {lang.builtin ? lang.name : `${lang.name} (${t('settings.general.community_language')})`}
+                                        </option>
+                                    ))}
                                 </select>
+                                <p className="mt-1 text-xs text-gray-500 dark:text-gray-400">{t('settings.general.language_hint')}</p>
                             </div>
 
                             {/* 主题选择 */}
@@ -501,6 +569,30 @@ function Settings() {
                                 <p className="text-sm text-gray-500 dark:text-gray-400 mt-2">{t('settings.general.auto_launch_desc')}</p>
                             </div>
 
+                            {/* macOS 菜单栏标题 */}
+                            <div>
+                                <label className="block text-sm font-medium text-gray-900 dark:text-base-content mb-2">{t('settings.general.menu_bar_title')}</label>
+                                <select
+                                    className="w-full px-4 py-4 border border-gray-200 dark:border-base-300 rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-transparent text-gray-900 dark:text-base-content bg-gray-50 dark:bg-base-200"
+                                    value={formData.tray?.menu_bar_title ?? 'off'}
+                                    onChange={(e) => setFormData({
+                                        ...formData,
+                                        tray: {
+                                            max_text_width: 40,
+                                            email_middle_ellipsis: true,
+                                            ...formData.tray,
+                                            menu_bar_title: e.target.value as MenuBarTitle,
+                                        },
+                                    })}
+                                >
+                                    <option value="off">{t('settings.general.menu_bar_title_off')}</option>
+                                    <option value="account">{t('settings.general.menu_bar_title_account')}</option>
+                                    <option value="lowest_quota">{t('settings.general.menu_bar_title_lowest_quota')}</option>
+                                    <option value="both">{t('settings.general.menu_bar_title_both')}</option>
+                                </select>
+                                <p className="text-sm text-gray-500 dark:text-gray-400 mt-2">{t('settings.general.menu_bar_title_desc')}</p>
+                            </div>
+
                             {/* 自动检查更新 */}
                             <>
                                 <div className="flex items-center justify-between p-4 bg-gray-50 dark:bg-base-200 rounded-lg border border-gray-100 dark:border-base-300">
@@ -564,6 +656,37 @@ function Settings() {
                                     </div>
                                 )}
 
+                                {/* 离线模式 */}
+                                <div className="flex items-center justify-between p-4 bg-gray-50 dark:bg-base-200 rounded-lg border border-gray-100 dark:border-base-300">
+                                    <div>
+                                        <div className="font-medium text-gray-900 dark:text-base-content">{t('settings.general.offline_mode')}</div>
+                                        <p className="text-sm text-gray-600 dark:text-gray-400 mt-1">{t('settings.general.offline_mode_desc')}</p>
+                                        {networkStatus?.auto_detected && (
+                                            <p className="text-sm text-amber-600 dark:text-amber-400 mt-1">{t('settings.general.offline_mode_auto')}</p>
+                                        )}
+                                    </div>
+                                    <label className="relative inline-flex items-center cursor-pointer">
+                                        <input
+                                            type="checkbox"
+                                            className="sr-only peer"
+                                            checked={formData.offline?.manual ?? false}
+                                            onChange={async (e) => {
+                                                const enabled = e.target.checked;
+                                                try {
+                                                    await setOfflineMode(enabled);
+                                                    if (formData.offline) {
+                                                        setFormData({ ...formData, offline: { ...formData.offline, manual: enabled } });
+                                                    }
+                                                    showToast(enabled ? t('settings.general.offline_mode_enabled') : t('settings.general.offline_mode_disabled'), 'success');
+                                                } catch (error) {
+                                                    showToast(`${t('common.error')}: ${error}`, 'error');
+                                                }
+                                            }}
+                                        />
+                                        <div className="w-11 h-6 bg-gray-200 dark:bg-base-300 peer-focus:outline-none peer-focus:ring-4 peer-focus:ring-blue-300 dark:peer-focus:ring-blue-800 rounded-full peer peer-checked:after:translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-[2px] after:left-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all peer-checked:bg-blue-500"></div>
+                                    </label>
+                                </div>
+
                                 {/* 菜单显示设置 */}
                                 <div className="border-t border-gray-200 dark:border-base-200 pt-6 mt-6">
                                     <h3 className="font-medium text-gray-900 dark:text-base-content mb-3">{t('settings.menu.title')}</h3>
@@ -874,6 +997,14 @@ function Settings() {
                                                 {t('settings.web_mode_limitation', '(Web 模式不支持)')}
                                             </span>
                                         )}
+                                        {isTauri() && (
+                                            <button
+                                                className="px-4 py-2 border border-gray-200 dark:border-base-300 text-gray-700 dark:text-gray-300 rounded-lg hover:bg-gray-50 dark:hover:bg-base-200 hover:text-gray-900 dark:hover:text-base-content transition-colors"
+                                                onClick={handleSelectDataDirTarget}
+                                            >
+                                                {t('settings.advanced.move_btn')}
+                                            </button>
+                                        )}
                                     </div>
                                     <p className="text-sm text-gray-500 dark:text-gray-400 mt-2">{t('settings.advanced.data_dir_desc')}</p>
                                 </div>
@@ -918,6 +1049,24 @@ function Settings() {
                                             </span>
                                         )}
                                     </div>
+                                    {installations.length > 0 && (
+                                        <div className="mt-2 border border-gray-200 dark:border-base-300 rounded-lg divide-y divide-gray-100 dark:divide-base-300">
+                                            {installations.map((inst) => (
+                                                <button
+                                                    key={inst.path}
+                                                    className={`w-full text-left px-4 py-2 text-sm hover:bg-gray-50 dark:hover:bg-base-200 transition-colors ${formData.antigravity_executable === inst.path ? 'bg-blue-50 dark:bg-blue-900/10' : ''}`}
+                                                    onClick={() => setFormData({ ...formData, antigravity_executable: inst.path })}
+                                                >
+                                                    <div className="font-mono text-gray-900 dark:text-base-content break-all">{inst.path}</div>
+                                                    <div className="text-xs text-gray-500 dark:text-gray-400 mt-0.5">
+                                                        {inst.version ? `v${inst.version}` : t('settings.advanced.antigravity_version_unknown')}
+                                                        {' · '}
+                                                        {inst.sources.map((source) => t(`settings.advanced.antigravity_source.${source}`)).join(', ')}
+                                                    </div>
+                                                </button>
+                                            ))}
+                                        </div>
+                                    )}
                                     <p className="text-sm text-gray-500 dark:text-gray-400 mt-2">
                                         {t('settings.advanced.antigravity_path_desc')}
                                     </p>
@@ -1072,6 +1221,16 @@ function Settings() {
                                     </div>
                                 </div>
 
+                                {/* 远程管理模式 (仅桌面端) */}
+                                {isTauri() && (
+                                    <div className="border-t border-gray-200 dark:border-base-300 pt-4">
+                                        <RemoteManagement
+                                            config={formData.remote ?? { enabled: false, url: '', token: '' }}
+                                            onChange={(remote) => setFormData({ ...formData, remote })}
+                                        />
+                                    </div>
+                                )}
+
                             </div>
                         </>
                     )}
@@ -1424,6 +1583,17 @@ function Settings() {
                     onCancel={() => setIsClearLogsOpen(false)}
                 />
 
+                <ModalDialog
+                    isOpen={moveDataDirTarget !== null}
+                    title={t('settings.advanced.move_data_dir_title')}
+                    message={t('settings.advanced.move_data_dir_msg', { from: dataDirPath, to: moveDataDirTarget ?? '' })}
+                    type="confirm"
+                    confirmText={isMovingDataDir ? t('settings.advanced.moving') : t('settings.advanced.move_btn')}
+                    cancelText={t('common.cancel')}
+                    onConfirm={confirmMoveDataDir}
+                    onCancel={() => !isMovingDataDir && setMoveDataDirTarget(null)}
+                />
+
                 {/* Antigravity Cache Clear Modal */}
                 <ModalDialog
                     isOpen={isClearCacheOpen}
@@ -22,8 +22,12 @@ interface UserToken {
     last_used_at?: number;
     total_requests: number;
     total_tokens_used: number;
+    account_group?: string;
+    priority?: TokenPriority;
 }
 
+type TokenPriority = 'high' | 'normal' | 'low';
+
 interface UserTokenStats {
     total_tokens: number;
     active_tokens: number;
@@ -49,6 +53,8 @@ const UserToken: React.FC = () => {
     const [editMaxIps, setEditMaxIps] = useState(0);
     const [editCurfewStart, setEditCurfewStart] = useState('');
     const [editCurfewEnd, setEditCurfewEnd] = useState('');
+    const [editAccountGroup, setEditAccountGroup] = useState('');
+    const [editPriority, setEditPriority] = useState<TokenPriority>('normal');
     const [updating, setUpdating] = useState(false);
 
     // Create Form State
@@ -59,6 +65,8 @@ const UserToken: React.FC = () => {
     const [newCurfewStart, setNewCurfewStart] = useState('');
     const [newCurfewEnd, setNewCurfewEnd] = useState('');
     const [newCustomExpires, setNewCustomExpires] = useState(''); // datetime-local value
+    const [newAccountGroup, setNewAccountGroup] = useState('');
+    const [newPriority, setNewPriority] = useState<TokenPriority>('normal');
 
     const loadData = async () => {
         setLoading(true);
@@ -108,7 +116,9 @@ const UserToken: React.FC = () => {
                     max_ips: newMaxIps,
                     curfew_start: newCurfewStart || null,
                     curfew_end: newCurfewEnd || null,
-                    custom_expires_at: customExpiresAt || null
+                    custom_expires_at: customExpiresAt || null,
+                    account_group: newAccountGroup.trim() || null,
+                    priority: newPriority
                 }
             });
             showToast(t('common.create_success') || 'Created successfully', 'success');
@@ -120,6 +130,8 @@ const UserToken: React.FC = () => {
             setNewCurfewStart('');
             setNewCurfewEnd('');
             setNewCustomExpires('');
+            setNewAccountGroup('');
+            setNewPriority('normal');
             loadData();
         } catch (e) {
             console.error('Failed to create token', e);
@@ -147,6 +159,8 @@ const UserToken: React.FC = () => {
         setEditMaxIps(token.max_ips ?? 0);  // 使用 ?? 确保 null/undefined 变为 0
         setEditCurfewStart(token.curfew_start ?? '');
         setEditCurfewEnd(token.curfew_end ?? '');
+        setEditAccountGroup(token.account_group ?? '');
+        setEditPriority(token.priority ?? 'normal');
         setShowEditModal(true);
     };
 
@@ -167,7 +181,10 @@ const UserToken: React.FC = () => {
                     max_ips: editMaxIps,
                     // 使用双层包装: undefined = 不更新, null = 清空, string = 设置值
                     curfew_start: editCurfewStart === '' ? null : editCurfewStart,
-                    curfew_end: editCurfewEnd === '' ? null : editCurfewEnd
+                    curfew_end: editCurfewEnd === '' ? null : editCurfewEnd,
+                    // 空字符串 = 解除分组绑定
+                    account_group: editAccountGroup.trim(),
+                    priority: editPriority
                 }
             });
             showToast(t('common.update_success') || 'Updated successfully', 'success');
@@ -487,6 +504,37 @@ const UserToken: React.FC = () => {
                             />
                         </div>
 
+                        <div className="form-control w-full mb-3">
+                            <label className="label">
+                                <span className="label-text">{t('user_token.account_group', { defaultValue: 'Account Group' })}</span>
+                            </label>
+                            <input
+                                type="text"
+                                className="input input-bordered w-full"
+                                value={newAccountGroup}
+                                onChange={e => setNewAccountGroup(e.target.value)}
+                                placeholder={t('user_token.placeholder_account_group', { defaultValue: 'Account tag, empty = all accounts' })}
+                            />
+                        </div>
+
+                        <div className="form-control w-full mb-3">
+                            <label className="label">
+                                <span className="label-text">{t('user_token.priority', { defaultValue: 'Priority' })}</span>
+                            </label>
+                            <select
+                                className="select select-bordered w-full"
+                                value={newPriority}
+                                onChange={e => setNewPriority(e.target.value as TokenPriority)}
+                            >
+                                <option value="high">{t('user_token.priority_high', { defaultValue: 'High' })}</option>
+                                <option value="normal">{t('user_token.priority_normal', { defaultValue: 'Normal' })}</option>
+                                <option value="low">{t('user_token.priority_low', { defaultValue: 'Low' })}</option>
+                            </select>
+                            <label className="label">
+                                <span className="label-text-alt text-gray-500">{t('user_token.hint_priority', { defaultValue: 'When the account pool is saturated, low-priority keys are queued or rejected first' })}</span>
+                            </label>
+                        </div>
+
                         <div className="grid grid-cols-2 gap-4 mb-3">
                             <div className="form-control w-full">
                                 <label className="label">
@@ -615,6 +663,37 @@ const UserToken: React.FC = () => {
                             />
                         </div>
 
+                        <div className="form-control w-full mb-3">
+                            <label className="label">
+                                <span className="label-text">{t('user_token.account_group', { defaultValue: 'Account Group' })}</span>
+                            </label>
+                            <input
+                                type="text"
+                                className="input input-bordered w-full"
+                                value={editAccountGroup}
+                                onChange={e => setEditAccountGroup(e.target.value)}
+                                placeholder={t('user_token.placeholder_account_group', { defaultValue: 'Account tag, empty = all accounts' })}
+                            />
+                        </div>
+
+                        <div className="form-control w-full mb-3">
+                            <label className="label">
+                                <span className="label-text">{t('user_token.priority', { defaultValue: 'Priority' })}</span>
+                            </label>
+                            <select
+                                className="select select-bordered w-full"
+                                value={editPriority}
+                                onChange={e => setEditPriority(e.target.value as TokenPriority)}
+                            >
+                                <option value="high">{t('user_token.priority_high', { defaultValue: 'High' })}</option>
+                                <option value="normal">{t('user_token.priority_normal', { defaultValue: 'Normal' })}</option>
+                                <option value="low">{t('user_token.priority_low', { defaultValue: 'Low' })}</option>
+                            </select>
+                            <label className="label">
+                                <span className="label-text-alt text-gray-500">{t('user_token.hint_priority', { defaultValue: 'When the account pool is saturated, low-priority keys are queued or rejected first' })}</span>
+                            </label>
+                        </div>
+
                         <div className="form-control w-full mb-3">
                             <label className="label">
                                 <span className="label-text">{t('user_token.ip_limit', { defaultValue: 'Max IPs' })}</span>
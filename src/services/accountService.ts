@@ -1,6 +1,7 @@
 import i18n from '../i18n';
-import { Account, DeviceProfile, DeviceProfileVersion, QuotaData } from '../types/account';
+import { Account, AccountDryRunResult, AccountListFilter, CapacityStatus, ModelAvailability, AccountListItem, AccountSortBy, DeviceProfile, DeviceProfileVersion, QuotaData, ReplayResult, SortOrder, TokenManagerState } from '../types/account';
 import { request as invoke } from '../utils/request';
+import { BackendError } from '../utils/errors';
 
 // 检查环境 (可选)
 function ensureTauriEnvironment() {
@@ -20,6 +21,15 @@ export async function listAccounts(): Promise<Account[]> {
     return response || [];
 }
 
+// 后端排序 / 筛选的账号摘要列表，避免为排序加载全部完整账号
+export async function listAccountSummaries(
+    sortBy?: AccountSortBy,
+    order?: SortOrder,
+    filter?: AccountListFilter
+): Promise<AccountListItem[]> {
+    return await invoke('list_account_summaries', { sortBy, order, filter });
+}
+
 export async function getCurrentAccount(): Promise<Account | null> {
     return await invoke('get_current_account');
 }
@@ -28,12 +38,13 @@ export async function addAccount(email: string, refreshToken: string): Promise<A
     return await invoke('add_account', { email, refreshToken });
 }
 
-export async function deleteAccount(accountId: string): Promise<void> {
-    return await invoke('delete_account', { accountId });
+// confirmed: 域名策略要求确认删除时 (错误前缀 delete_requires_confirmation) 由用户确认后重试
+export async function deleteAccount(accountId: string, confirmed?: boolean): Promise<void> {
+    return await invoke('delete_account', { accountId, confirmed });
 }
 
-export async function deleteAccounts(accountIds: string[]): Promise<void> {
-    return await invoke('delete_accounts', { accountIds });
+export async function deleteAccounts(accountIds: string[], confirmed?: boolean): Promise<void> {
+    return await invoke('delete_accounts', { accountIds, confirmed });
 }
 
 export async function switchAccount(accountId: string): Promise<void> {
@@ -49,10 +60,46 @@ export interface RefreshStats {
     success: number;
     failed: number;
     details: string[];
+    skipped: number; // 取消后未处理的账号数
+    cancelled: boolean;
+    operation_id?: string;
+}
+
+// operationId 可由调用方预先生成，用于在完成前调用 cancelOperation
+export async function refreshAllQuotas(operationId?: string): Promise<RefreshStats> {
+    return await invoke('refresh_all_quotas', { operationId });
+}
+
+// 仅刷新到期账号 (自适应间隔 + 随机抖动，由后端决定)
+export async function refreshDueQuotas(operationId?: string): Promise<RefreshStats> {
+    return await invoke('refresh_due_quotas', { operationId });
+}
+
+// 仅刷新选中账号；同一账号已在刷新时后端会合并请求
+export async function refreshQuotaForAccounts(accountIds: string[], operationId?: string): Promise<RefreshStats> {
+    return await invoke('refresh_quota_for_accounts', { accountIds, operationId });
+}
+
+// quota://refresh-progress 事件负载
+export interface RefreshProgress {
+    operation_id: string;
+    done: number;
+    total: number;
+    current_email?: string;
+}
+
+export interface OperationInfo {
+    id: string;
+    kind: string;
+    started_at: number;
 }
 
-export async function refreshAllQuotas(): Promise<RefreshStats> {
-    return await invoke('refresh_all_quotas');
+export async function cancelOperation(operationId: string): Promise<boolean> {
+    return await invoke('cancel_operation', { operationId });
+}
+
+export async function listOperations(): Promise<OperationInfo[]> {
+    return await invoke('list_operations');
 }
 
 // OAuth
@@ -63,13 +110,13 @@ export async function startOAuthLogin(): Promise<Account> {
         return await invoke('start_oauth_login');
     } catch (error) {
         // 增强错误信息
-        if (typeof error === 'string') {
+        if (error instanceof BackendError) {
             // 如果是 refresh_token 缺失错误,保持原样(已包含详细说明)
-            if (error.includes('Refresh Token') || error.includes('refresh_token')) {
+            if (error.technical.includes('Refresh Token') || error.technical.includes('refresh_token')) {
                 throw error;
             }
             // 其他错误添加上下文
-            throw i18n.t('accounts.add.oauth_error', { error });
+            throw i18n.t('accounts.add.oauth_error', { error: error.message });
         }
         throw error;
     }
@@ -80,11 +127,11 @@ export async function completeOAuthLogin(): Promise<Account> {
     try {
         return await invoke('complete_oauth_login');
     } catch (error) {
-        if (typeof error === 'string') {
-            if (error.includes('Refresh Token') || error.includes('refresh_token')) {
+        if (error instanceof BackendError) {
+            if (error.technical.includes('Refresh Token') || error.technical.includes('refresh_token')) {
                 throw error;
             }
-            throw i18n.t('accounts.add.oauth_error', { error });
+            throw i18n.t('accounts.add.oauth_error', { error: error.message });
         }
         throw error;
     }
@@ -108,6 +155,29 @@ export async function importFromCustomDb(path: string): Promise<Account> {
     return await invoke('import_custom_db', { path });
 }
 
+export interface ImportPreview {
+    format: string;
+    count: number;
+    emails: (string | null)[];
+}
+
+export interface BatchImportResult {
+    success: number;
+    failed: number;
+    errors: string[];
+    skipped: number;
+    cancelled: boolean;
+    operation_id: string;
+}
+
+export async function previewAccountImport(filePath: string): Promise<ImportPreview> {
+    return await invoke('preview_account_import', { filePath });
+}
+
+export async function importAccountsFromFiles(files: string[], operationId?: string): Promise<BatchImportResult> {
+    return await invoke('import_accounts_from_files', { files, operationId });
+}
+
 export async function syncAccountFromDb(): Promise<Account | null> {
     return await invoke('sync_account_from_db');
 }
@@ -116,6 +186,35 @@ export async function toggleProxyStatus(accountId: string, enable: boolean, reas
     return await invoke('toggle_proxy_status', { accountId, enable, reason });
 }
 
+export interface BulkProxyStatusResult {
+    updated: string[];
+    failed: { account_id: string; error: string }[];
+}
+
+// 批量切换反代状态 (后端单次写索引)
+export async function setProxyStatusBulk(accountIds: string[], enable: boolean, reason?: string): Promise<BulkProxyStatusResult> {
+    return await invoke('set_proxy_status_bulk', { accountIds, enable, reason });
+}
+
+export interface LoginVerificationStep {
+    name: 'load_account' | 'refresh_token' | 'resolve_project' | 'fetch_quota' | 'device_profile';
+    status: 'ok' | 'warning' | 'failed' | 'skipped';
+    message: string | null;
+    duration_ms: number;
+}
+
+export interface LoginVerification {
+    account_id: string;
+    email: string | null;
+    passed: boolean;
+    steps: LoginVerificationStep[];
+}
+
+// 验证账号登录 (只读演练切换流程，不改动当前账号)
+export async function verifyAccountLogin(accountId: string): Promise<LoginVerification> {
+    return await invoke('verify_account_login', { accountId });
+}
+
 /**
  * 重新排序账号列表
  * @param accountIds 按新顺序排列的账号ID数组
@@ -176,6 +275,31 @@ export async function warmUpAccount(accountId: string): Promise<string> {
     return await invoke('warm_up_account', { accountId });
 }
 
+// 受监控模型的可用账号数 (反代运行时)
+export async function getModelAvailability(): Promise<ModelAvailability[]> {
+    return await invoke('get_model_availability');
+}
+
+// TokenManager 内存状态快照 (反代运行时，排查路由问题)
+export async function getTokenManagerState(): Promise<TokenManagerState> {
+    return await invoke('get_token_manager_state');
+}
+
+// 各模型容量阈值的当前状态 (反代运行时)
+export async function getCapacityStatus(): Promise<CapacityStatus[]> {
+    return await invoke('get_capacity_status');
+}
+
+// 单账号试运行: 经完整代理管线发送固定的小请求，锁定到指定账号
+export async function testAccountRequest(accountId: string, model: string): Promise<AccountDryRunResult> {
+    return await invoke('test_account_request', { accountId, model });
+}
+
+// 重放请求日志，可锁定到指定账号 (ID 或邮箱)，返回与原响应的差异
+export async function replayRequest(logId: string, targetAccount?: string): Promise<ReplayResult> {
+    return await invoke('replay_request', { logId, targetAccount });
+}
+
 // 导出账号相关
 export interface ExportAccountItem {
     email: string;
@@ -184,6 +308,7 @@ export interface ExportAccountItem {
 
 export interface ExportAccountsResponse {
     accounts: ExportAccountItem[];
+    withheld?: string[]; // 因域名策略未导出的账号邮箱
 }
 
 export async function exportAccounts(accountIds: string[]): Promise<ExportAccountsResponse> {
@@ -195,6 +320,16 @@ export async function updateAccountLabel(accountId: string, label: string): Prom
     return await invoke('update_account_label', { accountId, label });
 }
 
+// 账号分组标签 (按 API Key 划分账号池)
+export async function updateAccountTags(accountId: string, tags: string[]): Promise<string[]> {
+    return await invoke('update_account_tags', { accountId, tags });
+}
+
+// 暂停账号 (snooze)，到期后自动恢复反代；返回恢复时间戳 (秒)
+export async function snoozeAccount(accountId: string, durationSecs: number): Promise<number> {
+    return await invoke('snooze_account', { accountId, durationSecs });
+}
+
 // Codex account management
 export async function addCodexAccountManual(token: string, refreshToken?: string): Promise<Account> {
     return await invoke('add_codex_account_manual', { token, refreshToken });
@@ -1,5 +1,5 @@
 import { request as invoke } from '../utils/request';
-import { AppConfig } from '../types/config';
+import { AntigravityInstallation, AppConfig, AppLockStatus, AppNotification, BusEventEnvelope, ConfigChange, ConfigVersionInfo, LanguageInfo, NetworkStatus, OnboardingState, OnboardingStep, SchedulerStatus, StartupReport } from '../types/config';
 
 export async function loadConfig(): Promise<AppConfig> {
     return await invoke('load_config');
@@ -8,3 +8,153 @@ export async function loadConfig(): Promise<AppConfig> {
 export async function saveConfig(config: AppConfig): Promise<void> {
     return await invoke('save_config', { config });
 }
+
+// 导出配置 JSON (默认不含本机路径和凭据)
+export async function exportAppConfig(includeMachineSpecific = false, includeSecrets = false): Promise<string> {
+    return await invoke('export_app_config', { includeMachineSpecific, includeSecrets });
+}
+
+// 导入配置 JSON，返回合并后的新配置
+export async function importAppConfig(json: string, includeMachineSpecific = false): Promise<AppConfig> {
+    return await invoke('import_app_config', { json, includeMachineSpecific });
+}
+
+// 将配置分区恢复默认 (例如 'quota_protection' / 'proxy')
+export async function resetConfigSection(section: string): Promise<AppConfig> {
+    return await invoke('reset_config_section', { section });
+}
+
+// 配置历史版本 (新的在前)
+export async function listConfigHistory(): Promise<ConfigVersionInfo[]> {
+    return await invoke('list_config_history');
+}
+
+// 对比两个配置版本，to 省略时与当前配置对比
+export async function diffConfigVersions(from: number, to?: number): Promise<ConfigChange[]> {
+    return await invoke('diff_config_versions', { from, to });
+}
+
+// 回滚到指定版本并立即生效，返回新配置
+export async function rollbackConfig(version: number): Promise<AppConfig> {
+    return await invoke('rollback_config', { version });
+}
+
+// 启动自检结果，自检尚未完成时为 null
+export async function getStartupReport(): Promise<StartupReport | null> {
+    return await invoke('get_startup_report');
+}
+
+// 通知中心 (窗口关闭期间产生的警告也会保留)
+export async function listNotifications(unreadOnly = false): Promise<AppNotification[]> {
+    return await invoke('list_notifications', { unreadOnly });
+}
+
+// ids 为空时全部标记为已读，返回剩余未读数
+export async function markNotificationsRead(ids?: string[]): Promise<number> {
+    return await invoke('mark_notifications_read', { ids });
+}
+
+// 调试: 查看内部事件总线 (since 为上次返回的最后一个 seq)
+export async function tailEvents(since?: number, limit?: number): Promise<BusEventEnvelope[]> {
+    return await invoke('tail_events', { since, limit });
+}
+
+// 网络 / 离线模式状态
+export async function getNetworkStatus(): Promise<NetworkStatus> {
+    return await invoke('get_network_status');
+}
+
+// 手动开启 / 关闭离线模式
+export async function setOfflineMode(enabled: boolean): Promise<NetworkStatus> {
+    return await invoke('set_offline_mode', { enabled });
+}
+
+// 后台定时任务状态
+export async function getSchedulerStatus(): Promise<SchedulerStatus> {
+    return await invoke('scheduler_status');
+}
+
+// 启用 / 停用单个后台任务
+export async function setSchedulerJobEnabled(jobId: string, enabled: boolean): Promise<SchedulerStatus> {
+    return await invoke('set_scheduler_job_enabled', { jobId, enabled });
+}
+
+// 首次运行引导状态
+export async function getOnboardingState(): Promise<OnboardingState> {
+    return await invoke('get_onboarding_state');
+}
+
+// 完成 / 跳过引导步骤 (path: 可执行文件路径, port: 反代端口)
+export async function completeOnboardingStep(
+    step: OnboardingStep,
+    options: { path?: string; port?: number; skip?: boolean } = {}
+): Promise<OnboardingState> {
+    return await invoke('complete_onboarding_step', { step, ...options });
+}
+
+// 探测本机所有 Antigravity 安装 (已按可信度排序)
+export async function detectAntigravityInstallations(): Promise<AntigravityInstallation[]> {
+    return await invoke('detect_antigravity_installations');
+}
+
+export interface MoveDataDirReport {
+    from: string;
+    to: string;
+    files: number;
+    bytes: number;
+}
+
+// 迁移数据目录 (复制并校验后切换到新位置，原目录保留)，无需重启
+export async function moveDataDir(newPath: string): Promise<MoveDataDirReport> {
+    return await invoke('move_data_dir', { newPath });
+}
+
+// 可用界面语言 (内置 + 社区翻译)
+export async function listAvailableLanguages(): Promise<LanguageInfo[]> {
+    return await invoke('list_available_languages');
+}
+
+// 合并后的语言包 (内置 + 用户覆盖)
+export async function getLocaleMessages(language: string): Promise<Record<string, any>> {
+    return await invoke('get_locale_messages', { language });
+}
+
+export interface WipeReport {
+    files_removed: number;
+    bytes_overwritten: number;
+    pending: string[]; // 仍被占用的文件，下次启动时删除
+    location_records_removed: string[]; // 删除的数据目录记录文件
+}
+
+// 清除全部本地数据，confirmPhrase 必须为 "WIPE ALL DATA"；完成后应用自动重启
+export async function wipeAllData(confirmPhrase: string): Promise<WipeReport> {
+    return await invoke('wipe_all_data', { confirmPhrase });
+}
+
+// 应用锁 (仅桌面端)：锁定期间修改类命令与暴露 Token 的命令会返回 app_locked
+export async function getAppLockStatus(): Promise<AppLockStatus> {
+    return await invoke('get_app_lock_status');
+}
+
+export async function lockApp(): Promise<void> {
+    return await invoke('lock_app');
+}
+
+export async function unlockApp(password: string): Promise<void> {
+    return await invoke('unlock_app', { password });
+}
+
+// 用户活动心跳，推迟空闲自动锁定
+export async function reportAppLockActivity(): Promise<AppLockStatus> {
+    return await invoke('app_lock_activity');
+}
+
+// newPassword 为空时清除主密码并关闭应用锁
+export async function setAppLockPassword(currentPassword: string | null, newPassword: string | null): Promise<void> {
+    return await invoke('set_app_lock_password', { currentPassword, newPassword });
+}
+
+// 关闭应用锁时需要提供当前密码
+export async function setAppLockSettings(enabled: boolean, idleMinutes: number, password?: string): Promise<void> {
+    return await invoke('set_app_lock_settings', { enabled, idleMinutes, password });
+}
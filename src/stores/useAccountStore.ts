@@ -7,17 +7,20 @@ interface AccountState {
     currentAccount: Account | null;
     loading: boolean;
     error: string | null;
+    refreshProgress: accountService.RefreshProgress | null; // 批量刷新进度 (quota://refresh-progress)
 
     // Actions
     fetchAccounts: () => Promise<void>;
     fetchCurrentAccount: () => Promise<void>;
     addAccount: (email: string, refreshToken: string) => Promise<void>;
-    deleteAccount: (accountId: string) => Promise<void>;
-    deleteAccounts: (accountIds: string[]) => Promise<void>;
+    deleteAccount: (accountId: string, confirmed?: boolean) => Promise<void>;
+    deleteAccounts: (accountIds: string[], confirmed?: boolean) => Promise<void>;
     switchAccount: (accountId: string) => Promise<void>;
     refreshQuota: (accountId: string) => Promise<void>;
     refreshAllQuotas: () => Promise<accountService.RefreshStats>;
+    refreshDueQuotas: () => Promise<accountService.RefreshStats>;
     reorderAccounts: (accountIds: string[]) => Promise<void>;
+    setRefreshProgress: (progress: accountService.RefreshProgress | null) => void;
 
     // 新增 actions
     startOAuthLogin: () => Promise<void>;
@@ -28,6 +31,7 @@ interface AccountState {
     importFromCustomDb: (path: string) => Promise<void>;
     syncAccountFromDb: () => Promise<void>;
     toggleProxyStatus: (accountId: string, enable: boolean, reason?: string) => Promise<void>;
+    setProxyStatusBulk: (accountIds: string[], enable: boolean, reason?: string) => Promise<accountService.BulkProxyStatusResult>;
     warmUpAccounts: () => Promise<string>;
     warmUpAccount: (accountId: string) => Promise<string>;
     updateAccountLabel: (accountId: string, label: string) => Promise<void>;
@@ -41,6 +45,9 @@ export const useAccountStore = create<AccountState>((set, get) => ({
     currentAccount: null,
     loading: false,
     error: null,
+    refreshProgress: null,
+
+    setRefreshProgress: (progress) => set({ refreshProgress: progress }),
 
     fetchAccounts: async () => {
         set({ loading: true, error: null });
@@ -76,10 +83,10 @@ export const useAccountStore = create<AccountState>((set, get) => ({
         }
     },
 
-    deleteAccount: async (accountId: string) => {
+    deleteAccount: async (accountId: string, confirmed?: boolean) => {
         set({ loading: true, error: null });
         try {
-            await accountService.deleteAccount(accountId);
+            await accountService.deleteAccount(accountId, confirmed);
             await Promise.all([
                 get().fetchAccounts(),
                 get().fetchCurrentAccount()
@@ -91,10 +98,10 @@ export const useAccountStore = create<AccountState>((set, get) => ({
         }
     },
 
-    deleteAccounts: async (accountIds: string[]) => {
+    deleteAccounts: async (accountIds: string[], confirmed?: boolean) => {
         set({ loading: true, error: null });
         try {
-            await accountService.deleteAccounts(accountIds);
+            await accountService.deleteAccounts(accountIds, confirmed);
             await Promise.all([
                 get().fetchAccounts(),
                 get().fetchCurrentAccount()
@@ -140,9 +147,20 @@ export const useAccountStore = create<AccountState>((set, get) => ({
         } catch (error) {
             set({ error: String(error), loading: false });
             throw error;
+        } finally {
+            set({ refreshProgress: null });
         }
     },
 
+    // 后台定时刷新：不切换 loading 状态，只在有账号被刷新时重新拉取列表
+    refreshDueQuotas: async () => {
+        const stats = await accountService.refreshDueQuotas();
+        if (stats.total > 0) {
+            await get().fetchAccounts();
+        }
+        return stats;
+    },
+
     /**
      * 重新排序账号列表
      * 采用乐观更新策略：先更新本地状态再调用后端持久化，以提供流畅的拖拽体验
@@ -273,6 +291,17 @@ export const useAccountStore = create<AccountState>((set, get) => ({
         }
     },
 
+    setProxyStatusBulk: async (accountIds: string[], enable: boolean, reason?: string) => {
+        try {
+            const result = await accountService.setProxyStatusBulk(accountIds, enable, reason);
+            await get().fetchAccounts();
+            return result;
+        } catch (error) {
+            console.error('[AccountStore] Bulk toggle proxy status failed:', error);
+            throw error;
+        }
+    },
+
     warmUpAccounts: async () => {
         set({ loading: true, error: null });
         try {
@@ -1,6 +1,7 @@
 import { create } from 'zustand';
-import { AppConfig } from '../types/config';
+import { AppConfig, NetworkStatus } from '../types/config';
 import * as configService from '../services/configService';
+import { setRemoteModeEnabled } from '../utils/request';
 
 interface ConfigState {
     config: AppConfig | null;
@@ -16,6 +17,10 @@ interface ConfigState {
     showAllQuotas: boolean;
     toggleMenuItem: (path: string) => Promise<void>;
     isMenuItemHidden: (path: string) => boolean;
+    // 离线模式
+    networkStatus: NetworkStatus | null;
+    refreshNetworkStatus: () => Promise<void>;
+    setOfflineMode: (enabled: boolean) => Promise<void>;
 }
 
 export const useConfigStore = create<ConfigState>((set, get) => ({
@@ -23,11 +28,13 @@ export const useConfigStore = create<ConfigState>((set, get) => ({
     loading: false,
     error: null,
     showAllQuotas: localStorage.getItem('antigravity_show_all_quotas') === 'true',
+    networkStatus: null,
 
     loadConfig: async () => {
         set({ loading: true, error: null });
         try {
             const config = await configService.loadConfig();
+            setRemoteModeEnabled(!!config.remote?.enabled);
             set({ config, loading: false });
         } catch (error) {
             set({ error: String(error), loading: false });
@@ -38,6 +45,7 @@ export const useConfigStore = create<ConfigState>((set, get) => ({
         if (!silent) set({ loading: true, error: null });
         try {
             await configService.saveConfig(config);
+            setRemoteModeEnabled(!!config.remote?.enabled);
             set({ config, loading: false });
             const { isTauri } = await import('../utils/env');
             if (isTauri()) {
@@ -67,6 +75,25 @@ export const useConfigStore = create<ConfigState>((set, get) => ({
         await get().saveConfig(newConfig, true);
     },
 
+    refreshNetworkStatus: async () => {
+        try {
+            const networkStatus = await configService.getNetworkStatus();
+            set({ networkStatus });
+        } catch (error) {
+            console.warn('[Config] Failed to load network status:', error);
+        }
+    },
+
+    setOfflineMode: async (enabled: boolean) => {
+        const networkStatus = await configService.setOfflineMode(enabled);
+        // 后端已持久化，同步本地配置，避免后续保存覆盖
+        const { config } = get();
+        set({
+            networkStatus,
+            config: config?.offline ? { ...config, offline: { ...config.offline, manual: enabled } } : config,
+        });
+    },
+
     toggleShowAllQuotas: () => {
         const current = get().showAllQuotas;
         const next = !current;
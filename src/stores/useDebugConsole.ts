@@ -15,6 +15,24 @@ export interface LogEntry {
 
 export type LogLevel = 'ERROR' | 'WARN' | 'INFO' | 'DEBUG' | 'TRACE';
 
+export interface LogQuery {
+    level?: string; // 最低级别，例如 'warn' 返回 WARN + ERROR
+    module?: string; // tracing target 子串，例如 'proxy'
+    text?: string;
+    accountId?: string;
+    requestId?: string;
+    range?: { start?: number; end?: number }; // 毫秒时间戳
+    page?: number; // 从 1 开始
+    pageSize?: number;
+}
+
+export interface LogQueryResult {
+    total: number;
+    page: number;
+    pageSize: number;
+    items: LogEntry[]; // 按时间倒序
+}
+
 interface DebugConsoleState {
     isOpen: boolean;
     isEnabled: boolean;
@@ -42,6 +60,7 @@ interface DebugConsoleState {
     startPolling: () => void;
     stopPolling: () => void;
     checkEnabled: () => Promise<void>;
+    queryLogs: (query: LogQuery) => Promise<LogQueryResult>;
 }
 
 const MAX_LOGS = 5000;
@@ -205,4 +224,9 @@ export const useDebugConsole = create<DebugConsoleState>((set, get) => ({
             console.error('Failed to check debug console status:', error);
         }
     },
+
+    // 查询持久化的结构化日志 (不依赖调试控制台是否开启)
+    queryLogs: async (query: LogQuery) => {
+        return await request<LogQueryResult>('query_logs', { query });
+    },
 }));
@@ -1,3 +1,5 @@
+import type { IpFamily, SchedulingMode } from './config';
+
 export type AccountProvider = 'google' | 'codex';
 
 export interface Account {
@@ -16,7 +18,11 @@ export interface Account {
     proxy_disabled_at?: number;
     protected_models?: string[];
     custom_label?: string;  // 用户自定义标签
+    tags?: string[];        // 账号分组标签 (API Key 分区)
     provider?: AccountProvider;  // 默认 'google'
+    ip_family?: IpFamily | null; // 出站地址族覆盖 (未设置时使用全局设置)
+    antigravity_args?: string[] | null; // 切换到此账号时的启动参数，同名标志覆盖全局参数
+    antigravity_executable?: string | null; // 切换到此账号时使用的安装 (如第二个 .app 副本)
     validation_blocked?: boolean;
     validation_blocked_until?: number;
     validation_blocked_reason?: string;
@@ -41,6 +47,7 @@ export interface QuotaData {
     forbidden_reason?: string;
     subscription_tier?: string;  // 订阅类型: FREE/PRO/ULTRA
     model_forwarding_rules?: Record<string, string>; // 废弃模型转发表
+    stale?: boolean; // 管理 API 返回: 超过 quota_staleness_minutes 未刷新
 }
 
 export interface ModelQuota {
@@ -62,6 +69,9 @@ export interface DeviceProfile {
     mac_machine_id: string;
     dev_device_id: string;
     sqm_id: string;
+    locale?: string;      // 模拟的系统语言，如 de-DE
+    timezone?: string;    // 模拟的时区，如 Europe/Berlin
+    os_version?: string;  // 模拟的 User-Agent 平台段
 }
 
 export interface DeviceProfileVersion {
@@ -72,3 +82,278 @@ export interface DeviceProfileVersion {
     is_current?: boolean;
 }
 
+
+// 账号列表排序字段 (后端排序)
+export type AccountSortBy =
+    | { field: 'custom' }
+    | { field: 'quota'; model: string } // 模型名或标准 ID (如 "claude")
+    | { field: 'last_used' }
+    | { field: 'created_at' }
+    | { field: 'tier' }
+    | { field: 'email' };
+
+export type SortOrder = 'asc' | 'desc';
+
+export interface AccountListFilter {
+    disabled?: boolean; // 账号禁用或反代禁用
+    protected?: boolean; // 存在受配额保护的模型
+    tagged?: boolean;
+    tags?: string[]; // 带有其中任一标签
+    search?: string; // 邮箱 / 名称 / 自定义标签
+}
+
+// 账号列表行 (不含 Token / 设备指纹)
+export interface AccountListItem {
+    id: string;
+    email: string;
+    name?: string;
+    custom_label?: string;
+    provider: AccountProvider;
+    disabled: boolean;
+    proxy_disabled: boolean;
+    protected_models: string[];
+    tags: string[];
+    subscription_tier?: string;
+    is_forbidden: boolean;
+    created_at: number;
+    last_used: number;
+    quota_updated_at?: number;
+    stale: boolean; // 配额数据已过期 (超过 quota_staleness_minutes 未刷新)
+    quota_percentage?: number; // 按额度排序时所选模型的剩余百分比
+}
+
+export interface ProjectBindingEntry {
+    project_id: string;
+    first_seen: number;
+    last_seen: number;
+    source: string; // quota_refresh / token_manager
+}
+
+// 账号的 Google project_id 绑定历史
+export interface ProjectBindingHistory {
+    account_id: string;
+    email: string;
+    current?: string;
+    entries: ProjectBindingEntry[];
+    switches: number[]; // 24 小时内的切换时间
+    total_switches: number;
+    churning: boolean; // 24 小时内频繁切换
+}
+
+export interface QuotaSnapshotPoint {
+    timestamp: number;
+    model: string;
+    percentage: number;
+}
+
+export interface AccountLatencyStats {
+    account_email: string;
+    request_count: number; // 成功请求数 (百分位只统计成功请求)
+    error_count: number;
+    ttfb_p50_ms: number;
+    ttfb_p90_ms: number;
+    ttfb_p99_ms: number;
+    duration_p50_ms: number;
+    duration_p90_ms: number;
+    duration_p99_ms: number;
+    total_bytes: number;
+    avg_bytes: number;
+    throughput_p50_bps: number; // 首字节之后的传输速率中位数
+}
+
+export interface WorkloadModel {
+    model: string;
+    requests_per_minute: number;
+    cost_pct?: number; // 单次请求消耗的配额百分比 (默认 0.5)
+}
+
+export interface SimulationWorkload {
+    hours: number;
+    models: WorkloadModel[];
+    reset_window_hours?: number; // 配额刷新周期 (默认 5 小时)
+    mode?: SchedulingMode; // 覆盖当前调度模式
+}
+
+export interface ModelProjection {
+    model: string;
+    start_pct: number;
+    end_pct: number;
+    requests: number;
+    consumed_pct: number;
+    exhausted_at?: number;
+    protected_at?: number;
+    resets: number;
+}
+
+export interface AccountProjection {
+    account_id: string;
+    email: string;
+    subscription_tier?: string;
+    models: ModelProjection[];
+}
+
+export interface ModelOutcome {
+    model: string;
+    requested: number;
+    served: number;
+    failed: number;
+    first_failure_at?: number; // 首个无可用账号的时间
+    survives: boolean;
+}
+
+export interface SimulationReport {
+    started_at: number;
+    ends_at: number;
+    mode: SchedulingMode;
+    eligible_accounts: number;
+    accounts: AccountProjection[];
+    models: ModelOutcome[];
+    survives: boolean;
+}
+
+export interface AccountShare {
+    blob: string; // 分享文本，同时作为二维码内容
+    email: string;
+    expires_at: number;
+}
+
+export interface SwitchHistoryEntry {
+    from_id?: string;
+    to_id: string;
+    timestamp: number;
+}
+
+export type SwitchPhase =
+    | 'refresh_token'
+    | 'device_profile'
+    | 'snapshot'
+    | 'close_process'
+    | 'write_profile'
+    | 'inject_token'
+    | 'start_process'
+    | 'external_command'
+    | 'update_index'
+    | 'rollback';
+
+export interface SwitchPhaseResult {
+    phase: SwitchPhase;
+    status: 'done' | 'skipped' | 'failed';
+    detail?: string;
+}
+
+// 账号切换的分阶段结果 (失败时 rolled_back 表示已恢复到切换前状态)
+export interface SwitchReport {
+    account_id: string;
+    email: string;
+    previous_account_id?: string;
+    started_at: number;
+    finished_at?: number;
+    success: boolean;
+    rolled_back: boolean;
+    phases: SwitchPhaseResult[];
+}
+
+/** 单账号试运行结果 (test_account_request) */
+export interface AccountDryRunResult {
+    account_id: string;
+    email: string;
+    model: string;
+    success: boolean;
+    proxy_status: number;
+    upstream_status?: number | null;
+    upstream_statuses: number[];
+    latency_ms: number;
+    served_by?: string | null;
+    snippet: string;
+    error?: string | null;
+}
+
+/** 受监控模型的可用账号数 (get_model_availability) */
+export interface ModelAvailability {
+    model: string;
+    available: number;
+    total: number;
+    protected: number;
+    cooling: number;
+    exhausted: number;
+}
+
+/** 模型容量阈值状态 (get_capacity_status) */
+export interface CapacityStatus {
+    model: string;
+    group: string; // 统计所用的配额分组
+    required: number;
+    available: number;
+    breached: boolean;
+}
+
+/** TokenManager 中单个账号的内存状态 */
+export interface TokenAccountState {
+    account_id: string;
+    email: string;
+    provider: string;
+    subscription_tier?: string | null;
+    tags: string[];
+    access_token_expires_at: number;
+    access_token_expires_in: number; // 距过期秒数 (负数为已过期)
+    health_score: number;
+    protected_models: string[];
+    validation_blocked_until?: number | null;
+    model_quotas: Record<string, number>;
+    quota_updated_at?: number | null;
+}
+
+/** 限流冷却记录 */
+export interface TokenCooldownState {
+    account_id: string;
+    model?: string | null; // 为空表示账号级限流
+    reason: string;
+    reset_at: number;
+    remaining_secs: number;
+}
+
+/** 模型最近一次选号 */
+export interface TokenLastSelection {
+    model: string;
+    account_id: string;
+    email: string;
+    selected_at: number;
+}
+
+/** TokenManager 内存状态快照 (get_token_manager_state) */
+export interface TokenManagerState {
+    generated_at: number;
+    scheduling: { mode: string; max_wait_seconds: number };
+    preferred_account_id?: string | null;
+    last_used_account?: [string, number] | null; // [账号 ID, 距今秒数]
+    session_bindings: number;
+    accounts: TokenAccountState[];
+    cooldowns: TokenCooldownState[];
+    last_selections: TokenLastSelection[];
+}
+
+/** 请求重放中的一侧响应 */
+export interface ReplayResponse {
+    status: number;
+    account_email?: string | null;
+    duration_ms: number;
+    text: string; // 从响应中提取的文本 (SSE 已合并)
+}
+
+export interface ReplayDiffLine {
+    op: 'equal' | 'removed' | 'added';
+    text: string;
+}
+
+/** 请求日志重放结果 (replay_request) */
+export interface ReplayResult {
+    log_id: string;
+    url: string;
+    model?: string | null;
+    target_account?: string | null; // 锁定的账号 ID (未指定时按正常轮换)
+    original: ReplayResponse;
+    replay: ReplayResponse;
+    upstream_statuses: number[];
+    identical: boolean;
+    diff: ReplayDiffLine[];
+}
@@ -3,17 +3,59 @@ export interface UpstreamProxyConfig {
     url: string;
 }
 
+export type AdminRole = 'viewer' | 'operator' | 'admin';
+
+export interface AdminTokenConfig {
+    name: string;
+    token: string;
+    role: AdminRole;
+}
+
+export interface ListenerTlsConfig {
+    cert_path: string;
+    key_path: string;
+}
+
+export interface ListenerConfig {
+    enabled: boolean;
+    host: string;
+    port: number;
+    auth_mode?: 'off' | 'strict' | 'all_except_health' | 'auto' | null; // 未设置时沿用全局 auth_mode
+    tls?: ListenerTlsConfig | null;
+}
+
+export interface MdnsConfig {
+    enabled: boolean;
+    instance_name?: string | null; // 未设置时使用本机主机名
+}
+
 export interface ProxyConfig {
     enabled: boolean;
     allow_lan_access?: boolean;
+    listen_ipv6?: boolean; // 局域网访问时监听双栈地址 ::
     auth_mode?: 'off' | 'strict' | 'all_except_health' | 'auto';
     port: number;
     api_key: string;
     admin_password?: string;
+    admin_tokens?: AdminTokenConfig[]; // 按角色授权的附加管理令牌
+    additional_listeners?: ListenerConfig[]; // 附加监听地址 (各自独立的鉴权 / TLS 设置)
+    mdns?: MdnsConfig; // 局域网 mDNS 广播 (_abvproxy._tcp)
     auto_start: boolean;
     custom_mapping?: Record<string, string>;
+    fallback_chains?: Record<string, string[]>; // 模型降级链 (请求模型 -> 替代模型列表)
+    tier_routing?: TierRoutingConfig; // 订阅等级路由 (高端模型优先高等级 / 模型固定等级)
+    admission?: AdmissionConfig; // 按 API Key 优先级的准入控制
+    session_pool?: SessionPoolConfig; // 上游会话 ID 池 (按账号隔离 / 定期轮换)
+    circuit_breaker?: UpstreamCircuitBreakerConfig; // 上游端点熔断 (连续失败后快速失败)
+    upstream_timeouts?: UpstreamTimeoutsConfig; // 上游超时 (可按模型覆盖)
+    content_filter?: ContentFilterConfig; // 提示词 / 响应内容过滤
+    scoped_system_prompts?: ScopedSystemPromptConfig; // 按 API Key / 账号标签注入系统提示词
+    billing?: BillingConfig; // 用量计费导出的模型单价表
+    fault_injection?: FaultInjectionConfig; // 开发者故障注入 (X-Antigravity-Fault 请求头)
+    quota_staleness_minutes?: number; // 配额数据过期阈值 (分钟，0 = 不过期)
     request_timeout: number;
     enable_logging: boolean;
+    request_log?: RequestLogConfig; // 请求日志保留 / 压缩策略
     debug_logging?: DebugLoggingConfig;
     upstream_proxy: UpstreamProxyConfig;
     zai?: ZaiConfig;
@@ -38,6 +80,151 @@ export type ThinkingBudgetMode = 'auto' | 'passthrough' | 'custom' | 'adaptive';
 export type ThinkingEffort = 'low' | 'medium' | 'high';
 
 /** Thinking Budget 配置 */
+export interface TierRoutingConfig {
+    premium_models: string[]; // 高端模型关键字 (子串匹配)，命中时优先 ULTRA > PRO > FREE
+    model_tiers: Record<string, string[]>; // 模型 -> 允许的订阅等级 (支持 * 通配)
+}
+
+export interface AdmissionConfig {
+    enabled: boolean;
+    max_concurrent_per_account: number; // 单个可用账号的并发上限 (容量 = 可用账号数 × 该值)
+    low_priority_share: number; // low 优先级放行的容量比例 (0-1)
+    normal_priority_share: number; // normal 优先级放行的容量比例 (0-1)，high 始终放行
+    queue_timeout_ms: number; // 超出份额时的最长排队时间，0 = 直接拒绝
+}
+
+export interface SessionPoolConfig {
+    per_account: boolean; // 每个账号独立的 x-vscode-sessionid，关闭时共用每次启动一个
+    rotation_hours: number; // 轮换周期 (小时)，0 = 不轮换
+}
+
+export interface UpstreamCircuitBreakerConfig {
+    enabled: boolean;
+    failure_threshold: number; // 连续失败 (网络错误 / 5xx) 多少次后熔断端点
+    open_secs: number; // 熔断时长 (秒)，到期后放行一个探测请求
+}
+
+export interface ModelTimeoutOverride {
+    connect_secs?: number;
+    read_secs?: number;
+    stream_idle_secs?: number;
+}
+
+export interface UpstreamTimeoutsConfig {
+    connect_secs: number; // 建立连接超时 (秒)
+    read_secs: number; // 非流式请求总超时 (秒)，0 = 不限
+    stream_idle_secs: number; // 流式响应两个数据块之间的最长间隔 (秒)，0 = 不限
+    per_model: Record<string, ModelTimeoutOverride>; // 键为模型名或标准模型 ID
+}
+
+export type ContentFilterAction = 'block' | 'redact' | 'annotate';
+export type ContentFilterDirection = 'request' | 'response' | 'both';
+
+export interface ContentFilterRule {
+    name: string; // 规则名 (唯一)
+    pattern: string; // 关键词 (不区分大小写) 或正则
+    is_regex?: boolean;
+    action?: ContentFilterAction; // 默认 redact
+    direction?: ContentFilterDirection; // 默认 request
+    replacement?: string; // redact 替换文本，默认 "[FILTERED]"
+    enabled?: boolean;
+}
+
+export interface ContentFilterConfig {
+    enabled: boolean;
+    rules: ContentFilterRule[];
+}
+
+export type ScopedPromptTarget = 'api_key' | 'account_tag';
+
+export interface ScopedSystemPromptRule {
+    name: string;
+    target?: ScopedPromptTarget; // 默认 api_key
+    value: string; // 令牌 ID / 用户名，或账号标签
+    prefix?: string; // 插入到系统指令开头
+    suffix?: string; // 追加到系统指令末尾
+    enabled?: boolean;
+}
+
+/** 客户端可通过请求头 X-Skip-Prompt-Injection: true 跳过 */
+export interface ScopedSystemPromptConfig {
+    enabled: boolean;
+    rules: ScopedSystemPromptRule[];
+}
+
+export interface RequestLogConfig {
+    retention_days: number; // 删除早于 N 天的日志，0 = 不按时间清理
+    max_rows: number; // 最多保留行数，0 = 不限
+    vacuum_interval_hours: number; // 两次 VACUUM 的最短间隔，0 = 从不
+}
+
+export interface LogCompactionResult {
+    deleted_by_age: number;
+    deleted_by_count: number;
+    vacuumed: boolean;
+    remaining: number;
+}
+
+export interface ModelPrice {
+    input_per_million?: number; // 每百万输入 token
+    output_per_million?: number; // 每百万输出 token
+    per_request?: number; // 每次请求固定费用
+}
+
+// 开发者模式：允许通过 X-Antigravity-Fault 请求头模拟上游故障 (429 / 断流 / 首字节延迟)
+export interface FaultInjectionConfig {
+    enabled: boolean;
+}
+
+export interface BillingConfig {
+    currency: string; // 默认 USD
+    prices: Record<string, ModelPrice>; // 键为模型名，支持 * 通配
+}
+
+export interface BillingLine {
+    month: string; // YYYY-MM (UTC)
+    token_id: string;
+    username: string;
+    model: string;
+    requests: number;
+    input_tokens: number;
+    output_tokens: number;
+    total_tokens: number;
+    cost: number;
+    unpriced: boolean; // 单价表中没有匹配的模型
+}
+
+export interface BillingKeyTotal {
+    month: string;
+    token_id: string;
+    username: string;
+    requests: number;
+    input_tokens: number;
+    output_tokens: number;
+    total_tokens: number;
+    cost: number;
+}
+
+export interface BillingReport {
+    currency: string;
+    from_month: string | null;
+    to_month: string | null;
+    generated_at: number;
+    lines: BillingLine[];
+    totals: BillingKeyTotal[];
+    total_cost: number;
+}
+
+export interface ContentFilterRuleStats {
+    name: string;
+    request_hits: number;
+    response_hits: number;
+    blocked: number;
+    redacted: number;
+    annotated: number;
+    last_hit_at: number | null;
+}
+
 export interface ThinkingBudgetConfig {
     /** 模式选择 */
     mode: ThinkingBudgetMode;
@@ -99,6 +286,9 @@ export interface ZaiConfig {
 export interface ScheduledWarmupConfig {
     enabled: boolean;
     monitored_models: string[];
+    window_secs?: number; // 每批预热铺开的时间窗口 (秒)
+    per_account_interval_secs?: number; // 同一账号两次预热的最小间隔 (秒)
+    max_per_minute?: number; // 全局每分钟预热请求上限 (0 = 不限制)
 }
 
 export interface QuotaProtectionConfig {
@@ -107,6 +297,11 @@ export interface QuotaProtectionConfig {
     monitored_models: string[];
 }
 
+export interface MonitoredModelOptions {
+    standard_ids: string[]; // 可监控的标准分组 ID
+    monitored_models: string[];
+}
+
 export interface PinnedQuotaModelsConfig {
     models: string[];
 }
@@ -123,7 +318,428 @@ export interface CircuitBreakerConfig {
     backoff_steps: number[];
 }
 
+export interface QuietHoursConfig {
+    enabled: boolean;
+    start: string; // "HH:MM" 本地时间
+    end: string; // "HH:MM"，早于 start 时跨越午夜
+}
+
+export interface RemoteConfig {
+    enabled: boolean;
+    url: string; // 远程实例地址，例如 http://192.168.1.10:8045
+    token: string; // 远程实例的 API Key / Web UI 密码 (配置文件中加密保存，加载后为密文)
+}
+
+export interface LoggingConfig {
+    default_level: string; // off | error | warn | info | debug | trace
+    module_levels: Record<string, string>; // 按 tracing target 覆盖，例如 { "antigravity_tools_lib::proxy": "debug" }
+    rotation?: 'hourly' | 'daily'; // 按时间轮转 (重启后生效)
+    max_file_size_mb?: number; // 单个日志文件上限，0 表示不限
+    retention_days?: number; // 保留天数
+    max_total_size_mb?: number; // 日志目录总大小上限
+    redact_secrets?: boolean; // 日志与错误信息中屏蔽令牌 / 密钥 (默认开启，仅本地调试时关闭)
+    store_enabled?: boolean; // 写入结构化日志库 (query_logs)，默认开启
+    forward?: LogForwardConfig; // 转发到系统日志 (syslog / journald / Windows 事件日志)
+}
+
+// get_log_levels 返回值: 配置中的级别 + 实际生效的过滤规则
+export interface LogLevelsStatus extends LoggingConfig {
+    effective_filter: string; // 实际生效的过滤规则，如 "info,antigravity_tools_lib::proxy=debug"
+    env_override?: string | null; // 启动时 RUST_LOG 覆盖了配置的级别 (调用 set_log_levels 后失效)
+}
+
+export interface LogForwardConfig {
+    enabled: boolean;
+    sink: 'syslog' | 'journald' | 'eventlog';
+    address: string; // syslog: 留空使用本地 /dev/log，或 udp://host:514 / tcp://host:601
+    facility: string; // syslog facility，例如 daemon / local0
+    app_name: string; // syslog APP-NAME / journald SYSLOG_IDENTIFIER / 事件来源
+    min_level: string; // 低于此级别的日志不转发
+}
+
+export interface OfflineConfig {
+    manual: boolean; // 手动离线
+    auto_detect: boolean; // 自动探测网络连通性
+    probe_interval_secs: number;
+    probe_url: string;
+}
+
+export interface DataRetentionConfig {
+    quota_history_days: number; // 配额快照保留天数，0 = 永久
+    usage_stats_days: number; // 用量统计保留天数，0 = 永久
+    audit_events_days: number; // 审计记录 (IP 访问 / API Key 使用) 保留天数，0 = 永久
+    cleanup_hour: number; // 夜间清理的本地小时 (0-23)
+}
+
+export interface RetentionTargetResult {
+    category: 'quota_history' | 'usage_stats' | 'request_logs' | 'audit_events';
+    table: string;
+    retention_days: number;
+    cutoff?: number;
+    rows: number; // dry_run 时为将被删除的行数
+}
+
+export interface RetentionReport {
+    dry_run: boolean;
+    ran_at: number;
+    targets: RetentionTargetResult[];
+    total_rows: number;
+    errors: string[];
+}
+
+export type NotificationSeverity = 'info' | 'warning' | 'error';
+
+export interface AppNotification {
+    id: string;
+    severity: NotificationSeverity;
+    source: string; // 产生通知的模块，如 account / proxy / digest / hygiene
+    title: string;
+    body: string;
+    dedupe_key?: string;
+    created_at: number;
+    updated_at: number; // 最近一次推送时间 (合并重复通知时更新)
+    count: number; // 未读期间合并的次数
+    read: boolean;
+}
+
+export interface NotificationsChanged {
+    unread: number;
+    latest?: AppNotification | null;
+}
+
+// 内部事件总线上的事件 (调试用)
+export type BusEvent =
+    | { type: 'account_reload'; account_id: string }
+    | { type: 'account_deleted'; account_id: string }
+    | { type: 'accounts_refreshed' }
+    | { type: 'config_updated' };
+
+export interface BusEventEnvelope {
+    seq: number; // 进程内递增序号，作为下一次 tail 的游标
+    at: number; // 毫秒时间戳
+    event: BusEvent;
+}
+
+export interface DeepLinkConfig {
+    enabled: boolean; // 是否处理 abv:// 链接
+    allow_switch: boolean; // 是否允许链接切换当前账号 (刷新配额不受影响)
+}
+
+export interface HygieneConfig {
+    enabled: boolean;
+    weekday: number; // 每周执行的星期 (1 = 周一 .. 7 = 周日)
+    hour: number; // 执行的本地小时 (0-23)
+    device_history_keep: number; // 每个账号保留的设备指纹历史条数 (当前版本始终保留)
+    temp_file_min_age_hours: number; // 超过此时长的 *.tmp.* 残留文件会被删除
+}
+
+export interface HygieneReport {
+    dry_run: boolean;
+    ran_at: number;
+    expired_cooldowns: string[]; // 清除了过期验证阻止的账号 ID
+    device_history_pruned: number;
+    temp_files: string[]; // 相对数据目录
+    integrity?: unknown;
+    errors: string[];
+}
+
+export type RemoteBackupTarget =
+    | { type: 'none' }
+    | { type: 'webdav'; url: string; username: string; password: string }
+    | {
+        type: 's3';
+        endpoint: string;
+        region: string;
+        bucket: string;
+        prefix: string;
+        access_key_id: string;
+        secret_access_key: string;
+        path_style: boolean; // MinIO 等自建服务通常需要 path-style
+    };
+
+export interface BackupConfig {
+    enabled: boolean;
+    interval_hours: number; // 自动备份间隔 (小时)
+    keep_last: number; // 远程只保留最近 N 份，0 = 不清理
+    passphrase: string; // 归档加密口令
+    target: RemoteBackupTarget;
+}
+
+export interface SyncConfig {
+    enabled: boolean;
+    interval_minutes: number; // 自动同步间隔 (分钟)
+    include_routing: boolean; // 同时同步模型映射 / 降级链 / 等级路由
+}
+
+export interface DigestConfig {
+    enabled: boolean;
+    hour: number; // 每日发送时间 (本地小时 0-23)
+    quota_threshold: number; // 最低模型配额低于此百分比时列出
+    unauthorized_threshold: number; // 24 小时内 401 次数达到此值时列出
+    webhook_url: string; // 可选 webhook (Slack / Discord 等)
+    send_when_empty: boolean; // 没有问题账号时也发送
+}
+
+export interface CapacityAlertConfig {
+    enabled: boolean;
+    min_available: Record<string, number>; // 模型 -> 至少需要的可用账号数 (按配额分组统计)
+    webhook_url: string; // 可选 webhook (Slack / Discord 等)
+}
+
+export interface SchedulerConfig {
+    disabled_jobs: string[]; // 已停用的后台任务 ID
+}
+
+export interface SwitchCommandConfig {
+    enabled: boolean;
+    command: string; // 外部脚本 / 程序，账号 JSON 通过 stdin 传入
+    args: string[];
+    timeout_secs: number; // 超时秒数
+}
+
+export interface DomainPolicy {
+    domain: string; // 如 corp.com 或 @corp.com (仅精确匹配)
+    no_export?: boolean; // 禁止导出 refresh_token
+    exclude_from_proxy?: boolean; // 不参与反代轮换
+    confirm_delete?: boolean; // 删除前需要确认
+}
+
+export interface ExclusionWindow {
+    enabled: boolean;
+    accounts?: string[]; // 账号 ID 或邮箱
+    tags?: string[]; // 带有任一标签的账号
+    start: string; // HH:MM，结束早于开始时跨午夜
+    end: string; // HH:MM
+    days?: number[]; // 开始当天的星期 (1 = 周一 ... 7 = 周日)，空 = 每天
+    timezone?: string; // local / UTC / 固定偏移如 +08:00
+}
+
+export interface AppLockConfig {
+    enabled: boolean;
+    idle_minutes: number; // 空闲多少分钟后自动锁定 (0 = 仅手动锁定)
+    password_hash?: string; // 只读，通过应用锁命令修改
+}
+
+export interface AppLockStatus {
+    enabled: boolean;
+    has_password: boolean;
+    locked: boolean;
+    idle_minutes: number;
+}
+
+// 启动自检
+export type StartupCheckStatus = 'ok' | 'warning' | 'failed' | 'skipped';
+
+export interface StartupCheck {
+    id: string; // data_dir | config | proxy_port | antigravity | keychain
+    status: StartupCheckStatus;
+    detail: string | null;
+}
+
+export interface StartupReport {
+    generated_at: number;
+    degraded: boolean; // 存在失败项
+    checks: StartupCheck[];
+}
+
+// 配置历史版本
+export interface ConfigVersionInfo {
+    version: number;
+    saved_at: number;
+    changed: string[]; // 相对上一版本变更的设置路径，如 quota_protection.threshold_percentage
+}
+
+export interface ConfigChange {
+    path: string;
+    before: unknown | null; // null 表示该版本中不存在此设置
+    after: unknown | null;
+}
+
+export interface DigestAccount {
+    account_id: string;
+    email: string;
+    detail: string;
+}
+
+export interface ProblemDigest {
+    generated_at: number;
+    total_accounts: number;
+    disabled: DigestAccount[];
+    forbidden: DigestAccount[];
+    low_quota: DigestAccount[];
+    repeated_unauthorized: DigestAccount[];
+    fingerprint_collisions: { field: string; value: string; emails: string[] }[];
+}
+
+export interface SyncReport {
+    synced_at: number;
+    pulled: string[];
+    pushed: string[];
+    deleted_locally: string[];
+    deleted_remotely: string[];
+    conflicts: string[]; // 本地已有同邮箱的其他账号，未应用
+    routing?: 'pulled' | 'pushed';
+    uploaded: boolean;
+}
+
+export interface SyncStatus {
+    device_id: string;
+    enabled: boolean;
+    last_sync_at?: number;
+    last_attempt_at?: number;
+    last_error?: string;
+    last_report?: SyncReport;
+}
+
+export interface BackupStatus {
+    last_backup_at?: number;
+    last_archive?: string;
+    last_error?: string;
+    last_attempt_at?: number;
+}
+
+export interface BackupResult {
+    archive: string;
+    files: number;
+    size: number;
+    pruned: string[];
+}
+
+export interface RestoreResult {
+    archive: string;
+    files_restored: number;
+    safety_copy: string; // 恢复前本地数据的快照路径
+    restart_required: boolean;
+}
+
+export interface UpstreamEndpointsConfig {
+    base_url_override?: string; // 上游基础地址覆盖 (如内置模拟上游 http://127.0.0.1:18090)
+}
+
+export interface NetworkStatus {
+    offline: boolean;
+    manual: boolean;
+    auto_detected: boolean;
+    auto_detect: boolean;
+    last_checked_at?: number;
+    last_online_at?: number;
+}
+
+export interface SchedulerJobRun {
+    started_at: number;
+    duration_ms: number;
+    success: boolean;
+    message: string; // 成功时为摘要，失败时为错误信息
+}
+
+export interface SchedulerJobStatus {
+    id: string; // 如 backup / sync / digest / model_catalog_sync / retention_cleanup / account_hygiene / snooze_wake
+    enabled: boolean;
+    interval_secs: number; // 检查间隔
+    last_check_at?: number;
+    next_check_at?: number;
+    last_run?: SchedulerJobRun;
+}
+
+export interface SchedulerStatus {
+    quiet_hours_active: boolean;
+    offline: boolean;
+    jobs: SchedulerJobStatus[];
+}
+
+// 事件 clock://skew 的载荷同为此结构
+export interface ClockSkewStatus {
+    offset_secs: number; // 上游时间 - 本机时间 (秒)，正数表示本机时钟偏慢
+    samples: number;
+    last_sample_at?: number;
+    threshold_secs: number;
+    warning: boolean;
+}
+
+export interface TrayConfig {
+    max_text_width: number; // 托盘菜单单行最大显示宽度 (列，中日韩 / 全角字符计 2)
+    email_middle_ellipsis: boolean; // 邮箱从本地部分中间省略，保留域名
+    menu_bar_title?: MenuBarTitle; // macOS 菜单栏图标旁显示的短标题
+}
+
+export type MenuBarTitle = 'off' | 'account' | 'lowest_quota' | 'both';
+
+export type OnboardingStep = 'locate_executable' | 'capture_device_baseline' | 'add_account' | 'configure_proxy_port';
+
+export interface OnboardingProgress {
+    completed_steps: OnboardingStep[];
+    skipped_steps: OnboardingStep[];
+    finished_at?: number | null;
+}
+
+export interface OnboardingStepState {
+    step: OnboardingStep;
+    status: 'done' | 'skipped' | 'pending';
+    detail: string | null; // 检测到的当前值 (可执行文件路径 / 账号数 / 端口)
+}
+
+export interface OnboardingState {
+    steps: OnboardingStepState[];
+    current_step: OnboardingStep | null;
+    finished: boolean;
+}
+
+export type InstallationSource = 'configured' | 'running_process' | 'standard_location' | 'path_lookup' | 'desktop_entry' | 'registry';
+
+export interface AntigravityInstallation {
+    path: string;
+    sources: InstallationSource[];
+    version: string | null;
+    running: boolean;
+    configured: boolean;
+    score: number; // 排序用可信度，越高越优先
+}
+
+export interface LanguageInfo {
+    code: string;
+    name: string;
+    builtin: boolean; // 随应用内置
+    user_provided: boolean; // 数据目录 locales/ 下有对应文件 (覆盖或社区翻译)
+}
+
+export interface VersionSourcesConfig {
+    pinned_version?: string; // 固定指纹版本 (X.Y.Z)，优先于本地 / 远程解析
+    source_urls: string[]; // 额外的版本来源 URL，优先于内置端点
+    runtime_mappings?: RuntimeVersionMapping[]; // 版本 → Electron / Chrome 映射 (覆盖内置表)
+}
+
+export interface RuntimeVersionMapping {
+    min_version: string;
+    electron: string;
+    chrome: string;
+}
+
+export type RetryClass = 'unauthorized' | 'rate_limited' | 'server_error' | 'timeout';
+
+export type IpFamily = 'auto' | 'ipv4' | 'ipv6';
+
+export interface DnsOverrideConfig {
+    hosts?: Record<string, string[]>; // 主机名 (或 *.example.com) -> IP 列表
+    doh_url?: string | null; // DNS-over-HTTPS JSON 接口，如 https://dns.google/resolve
+}
+
+export interface HttpClientConfig {
+    timeout_secs: number; // 普通请求超时
+    long_timeout_secs: number; // OAuth 等长请求超时
+    connect_timeout_secs: number;
+    pool_idle_timeout_secs: number;
+    pool_max_idle_per_host: number;
+    max_attempts: number; // 配额拉取最大尝试次数
+    retry_base_delay_ms: number;
+    retry_max_delay_ms?: number; // 单次退避上限
+    retry_on?: RetryClass[]; // 允许重试的失败类型
+    max_concurrent_fetches: number; // 批量刷新配额并发数
+    ip_family?: IpFamily; // 出站地址族 (账号可单独覆盖)
+    dns?: DnsOverrideConfig; // 上游主机静态解析 / DoH
+}
+
 export interface AppConfig {
+    config_version?: number; // 配置文件格式版本 (后端自动迁移)
     language: string;
     theme: string;
     auto_refresh: boolean;
@@ -144,6 +760,27 @@ export interface AppConfig {
     circuit_breaker: CircuitBreakerConfig; // [NEW] 熔断器配置
     proxy: ProxyConfig;
     cloudflared: CloudflaredConfig; // [NEW] Cloudflared 配置
+    quiet_hours?: QuietHoursConfig; // [NEW] 静默时段 (暂停后台任务)
+    remote?: RemoteConfig; // [NEW] 远程管理模式
+    logging?: LoggingConfig; // [NEW] 日志级别 (运行时可调)
+    http_client?: HttpClientConfig; // [NEW] 共享 HTTP 客户端 (超时 / 连接池 / 重试)
+    offline?: OfflineConfig; // [NEW] 离线模式
+    upstream_endpoints?: UpstreamEndpointsConfig; // [NEW] 上游端点覆盖 (离线集成测试)
+    data_retention?: DataRetentionConfig; // [NEW] 数据保留策略 (夜间清理)
+    backup?: BackupConfig; // [NEW] 定时加密备份到远程目标 (WebDAV / S3)
+    sync?: SyncConfig; // [NEW] 通过备份目标与其他实例双向同步账号池
+    digest?: DigestConfig; // [NEW] 问题账号日报
+    capacity_alerts?: CapacityAlertConfig; // [NEW] 按模型的最低可用账号数告警
+    switch_command?: SwitchCommandConfig; // [NEW] 外部命令接管账号切换
+    domain_policies?: DomainPolicy[]; // [NEW] 按邮箱域名的导出 / 轮换 / 删除策略
+    app_lock?: AppLockConfig; // [NEW] 主密码应用锁 (空闲自动锁定)
+    scheduler?: SchedulerConfig; // [NEW] 单独停用的后台任务
+    exclusion_windows?: ExclusionWindow[]; // [NEW] 账号 / 标签在指定时间段内不参与反代轮换
+    hygiene?: HygieneConfig; // [NEW] 每周账号数据维护
+    deep_links?: DeepLinkConfig; // [NEW] abv:// 链接 (切换账号 / 刷新配额)
+    version_sources?: VersionSourcesConfig; // [NEW] 版本来源 / 固定版本
+    tray?: TrayConfig; // [NEW] 托盘菜单文本截断
+    onboarding?: OnboardingProgress; // [NEW] 首次运行引导进度
 }
 
 // ============================================================================
@@ -0,0 +1,51 @@
+import i18n from '../i18n';
+
+/**
+ * 后端命令错误
+ * 后端返回 `{ code, params, message }` (CommandError) 或 `code: detail` 字符串，这里统一为带错误码的 Error，
+ * message / toString() 为本地化后的提示 (errors.backend.<code>)，technical 保留原始错误文本。
+ */
+export class BackendError extends Error {
+    code: string;
+    params: Record<string, string>;
+    technical: string;
+
+    constructor(code: string, params: Record<string, string>, technical: string) {
+        super(i18n.t(`errors.backend.${code}`, { ...params, defaultValue: technical }));
+        this.name = 'BackendError';
+        this.code = code;
+        this.params = params;
+        this.technical = technical;
+    }
+
+    toString() {
+        return this.message;
+    }
+}
+
+const CODE_RE = /^[a-z][a-z0-9_]+$/;
+
+/** 与后端 error::split_error_code 保持一致 */
+function splitErrorCode(error: string): [string, string] {
+    const idx = error.indexOf(':');
+    const head = (idx >= 0 ? error.slice(0, idx) : error).trim();
+    const detail = idx >= 0 ? error.slice(idx + 1).trim() : '';
+    return CODE_RE.test(head) ? [head, detail] : ['unknown', error.trim()];
+}
+
+export function toBackendError(error: unknown): unknown {
+    if (error instanceof BackendError) return error;
+    if (typeof error === 'string') {
+        const [code, detail] = splitErrorCode(error);
+        return new BackendError(code, { detail }, error);
+    }
+    if (error && typeof error === 'object' && typeof (error as any).code === 'string' && typeof (error as any).message === 'string') {
+        const { code, params, message } = error as { code: string; params?: Record<string, string>; message: string };
+        return new BackendError(code, params || {}, message);
+    }
+    return error;
+}
+
+export function isBackendError(error: unknown, code: string): boolean {
+    return error instanceof BackendError && error.code === code;
+}
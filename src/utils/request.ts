@@ -1,3 +1,5 @@
+import { toBackendError } from './errors';
+
 // 探测环境
 const isTauri = typeof window !== 'undefined' && (!!(window as any).__TAURI_INTERNALS__ || !!(window as any).__TAURI__);
 
@@ -5,20 +7,55 @@ const isTauri = typeof window !== 'undefined' && (!!(window as any).__TAURI_INTE
 const COMMAND_MAPPING: Record<string, { url: string; method: 'GET' | 'POST' | 'DELETE' | 'PATCH' }> = {
   // Accounts
   'list_accounts': { url: '/api/accounts', method: 'GET' },
+  'list_account_summaries': { url: '/api/accounts/summaries', method: 'POST' },
   'get_current_account': { url: '/api/accounts/current', method: 'GET' },
+  'get_project_binding_history': { url: '/api/accounts/project-bindings', method: 'GET' },
+  'simulate_pool_workload': { url: '/api/accounts/simulate', method: 'POST' },
+  'get_quota_history': { url: '/api/accounts/quota-history', method: 'GET' },
+  'run_data_retention': { url: '/api/stats/retention', method: 'POST' },
+  'run_account_hygiene': { url: '/api/system/hygiene', method: 'POST' },
+  'run_problem_digest': { url: '/api/digest/run', method: 'POST' },
+  'run_remote_backup': { url: '/api/backup/run', method: 'POST' },
+  'list_remote_backups': { url: '/api/backup/remote', method: 'GET' },
+  'restore_remote_backup': { url: '/api/backup/restore', method: 'POST' },
+  'get_backup_status': { url: '/api/backup/status', method: 'GET' },
+  'sync_accounts_now': { url: '/api/sync/run', method: 'POST' },
+  'get_sync_status': { url: '/api/sync/status', method: 'GET' },
   'switch_account': { url: '/api/accounts/switch', method: 'POST' },
+  'switch_back': { url: '/api/accounts/switch-back', method: 'POST' },
+  'get_switch_history': { url: '/api/accounts/switch-history', method: 'GET' },
+  'get_last_switch_report': { url: '/api/accounts/switch-report', method: 'GET' },
   'add_account': { url: '/api/accounts', method: 'POST' },
+  'create_account_share': { url: '/api/accounts/share', method: 'POST' },
+  'redeem_account_share': { url: '/api/accounts/share/redeem', method: 'POST' },
   'delete_account': { url: '/api/accounts/:accountId', method: 'DELETE' },
   'delete_accounts': { url: '/api/accounts/bulk-delete', method: 'POST' },
   'fetch_account_quota': { url: '/api/accounts/:accountId/quota', method: 'GET' },
   'refresh_account_quota': { url: '/api/accounts/:accountId/quota', method: 'GET' },
   'refresh_all_quotas': { url: '/api/accounts/refresh', method: 'POST' },
+  'refresh_due_quotas': { url: '/api/accounts/refresh-due', method: 'POST' },
+  'refresh_quota_for_accounts': { url: '/api/accounts/refresh-selected', method: 'POST' },
+  'list_operations': { url: '/api/operations', method: 'GET' },
+  'cancel_operation': { url: '/api/operations/:operationId/cancel', method: 'POST' },
   'reorder_accounts': { url: '/api/accounts/reorder', method: 'POST' },
   'toggle_proxy_status': { url: '/api/accounts/:accountId/toggle-proxy', method: 'POST' },
+  'set_proxy_status_bulk': { url: '/api/accounts/bulk-toggle-proxy', method: 'POST' },
+  'verify_account_login': { url: '/api/accounts/:accountId/verify-login', method: 'POST' },
   'warm_up_accounts': { url: '/api/accounts/warmup', method: 'POST' },
   'warm_up_all_accounts': { url: '/api/accounts/warmup', method: 'POST' },
   'warm_up_account': { url: '/api/accounts/:accountId/warmup', method: 'POST' },
+  'test_account_request': { url: '/api/accounts/:accountId/test-request', method: 'POST' },
   'update_account_label': { url: '/api/accounts/:accountId/label', method: 'POST' },
+  'update_account_tags': { url: '/api/accounts/:accountId/tags', method: 'POST' },
+  'update_account_ip_family': { url: '/api/accounts/:accountId/ip-family', method: 'POST' },
+  'update_account_antigravity_args': { url: '/api/accounts/:accountId/antigravity-args', method: 'POST' },
+  'update_account_antigravity_executable': { url: '/api/accounts/:accountId/antigravity-executable', method: 'POST' },
+  'list_orphan_accounts': { url: '/api/accounts/orphans', method: 'GET' },
+  'snooze_account': { url: '/api/accounts/:accountId/snooze', method: 'POST' },
+  'adopt_orphan': { url: '/api/accounts/orphans/:accountId/adopt', method: 'POST' },
+  'list_corrupt_accounts': { url: '/api/accounts/corrupt', method: 'GET' },
+  'restore_corrupt_account': { url: '/api/accounts/corrupt/:fileName/restore', method: 'POST' },
+  'delete_corrupt_account': { url: '/api/accounts/corrupt/:fileName', method: 'DELETE' },
   'export_accounts': { url: '/api/accounts/export', method: 'POST' },
   'bind_device_profile': { url: '/api/accounts/:accountId/bind-device', method: 'POST' },
   'get_device_profiles': { url: '/api/accounts/:accountId/device-profiles', method: 'GET' },
@@ -42,17 +79,38 @@ const COMMAND_MAPPING: Record<string, { url: string; method: 'GET' | 'POST' | 'D
   'check_proxy_health': { url: '/api/proxy/health-check/trigger', method: 'POST' },
   'get_preferred_account': { url: '/api/proxy/preferred-account', method: 'GET' },
   'set_preferred_account': { url: '/api/proxy/preferred-account', method: 'POST' },
+  'get_model_availability': { url: '/api/proxy/model-availability', method: 'GET' },
+  'get_token_manager_state': { url: '/api/proxy/token-manager', method: 'GET' },
+  'get_capacity_status': { url: '/api/proxy/capacity', method: 'GET' },
   'fetch_zai_models': { url: '/api/zai/models/fetch', method: 'POST' },
+  'list_known_models': { url: '/api/models/known', method: 'GET' },
+  'sync_model_catalog': { url: '/api/models/catalog/sync', method: 'POST' },
+  'get_monitored_model_options': { url: '/api/quota-protection/monitored-models', method: 'GET' },
+  'update_monitored_models': { url: '/api/quota-protection/monitored-models', method: 'POST' },
   'load_config': { url: '/api/config', method: 'GET' },
   'save_config': { url: '/api/config', method: 'POST' },
+  'export_app_config': { url: '/api/config/export', method: 'GET' },
+  'import_app_config': { url: '/api/config/import', method: 'POST' },
+  'reset_config_section': { url: '/api/config/reset-section', method: 'POST' },
+  'list_config_history': { url: '/api/config/history', method: 'GET' },
+  'diff_config_versions': { url: '/api/config/history/diff', method: 'GET' },
+  'rollback_config': { url: '/api/config/history/:version/rollback', method: 'POST' },
   'get_proxy_stats': { url: '/api/proxy/stats', method: 'GET' },
+  'get_admission_stats': { url: '/api/proxy/admission', method: 'GET' },
+  'list_upstream_sessions': { url: '/api/proxy/sessions', method: 'GET' },
+  'rotate_upstream_sessions': { url: '/api/proxy/sessions/rotate', method: 'POST' },
+  'get_upstream_circuits': { url: '/api/proxy/circuits', method: 'GET' },
+  'get_content_filter_stats': { url: '/api/proxy/content-filter/stats', method: 'GET' },
+  'reset_content_filter_stats': { url: '/api/proxy/content-filter/stats/reset', method: 'POST' },
   'set_proxy_monitor_enabled': { url: '/api/proxy/monitor/toggle', method: 'POST' },
 
   // Logs & Monitoring
   'get_proxy_logs_filtered': { url: '/api/logs', method: 'GET' },
   'get_proxy_logs_count_filtered': { url: '/api/logs/count', method: 'GET' },
   'clear_proxy_logs': { url: '/api/logs/clear', method: 'POST' },
+  'compact_proxy_logs': { url: '/api/logs/compact', method: 'POST' },
   'get_proxy_log_detail': { url: '/api/logs/:logId', method: 'GET' },
+  'replay_request': { url: '/api/logs/:logId/replay', method: 'POST' },
 
   // Debug Console
   'enable_debug_console': { url: '/api/debug/enable', method: 'POST' },
@@ -60,6 +118,9 @@ const COMMAND_MAPPING: Record<string, { url: string; method: 'GET' | 'POST' | 'D
   'is_debug_console_enabled': { url: '/api/debug/enabled', method: 'GET' },
   'get_debug_console_logs': { url: '/api/debug/logs', method: 'GET' },
   'clear_debug_console_logs': { url: '/api/debug/logs/clear', method: 'POST' },
+  'tail_events': { url: '/api/debug/events', method: 'GET' },
+  'get_log_levels': { url: '/api/system/log-levels', method: 'GET' },
+  'set_log_levels': { url: '/api/system/log-levels', method: 'POST' },
 
   // CLI Sync
   'get_cli_sync_status': { url: '/api/proxy/cli/status', method: 'POST' },
@@ -81,6 +142,7 @@ const COMMAND_MAPPING: Record<string, { url: string; method: 'GET' | 'POST' | 'D
   'get_token_stats_by_account': { url: '/api/stats/token/by-account', method: 'GET' },
   'get_token_stats_summary': { url: '/api/stats/token/summary', method: 'GET' },
   'get_token_stats_by_model': { url: '/api/stats/token/by-model', method: 'GET' },
+  'get_account_latency_stats': { url: '/api/stats/latency/by-account', method: 'GET' },
   'get_token_stats_model_trend_hourly': { url: '/api/stats/token/model-trend/hourly', method: 'GET' },
   'get_token_stats_model_trend_daily': { url: '/api/stats/token/model-trend/daily', method: 'GET' },
   'get_token_stats_account_trend_hourly': { url: '/api/stats/token/account-trend/hourly', method: 'GET' },
@@ -89,6 +151,9 @@ const COMMAND_MAPPING: Record<string, { url: string; method: 'GET' | 'POST' | 'D
 
   // System
   'get_data_dir_path': { url: '/api/system/data-dir', method: 'GET' },
+  'get_startup_report': { url: '/api/system/startup-report', method: 'GET' },
+  'list_notifications': { url: '/api/notifications', method: 'GET' },
+  'mark_notifications_read': { url: '/api/notifications/read', method: 'POST' },
   'get_update_settings': { url: '/api/system/updates/settings', method: 'GET' },
   'save_update_settings': { url: '/api/system/updates/save', method: 'POST' },
   'is_auto_launch_enabled': { url: '/api/system/autostart/status', method: 'GET' },
@@ -96,6 +161,7 @@ const COMMAND_MAPPING: Record<string, { url: string; method: 'GET' | 'POST' | 'D
   'get_http_api_settings': { url: '/api/system/http-api/settings', method: 'GET' },
   'save_http_api_settings': { url: '/api/system/http-api/settings', method: 'POST' },
   'get_antigravity_path': { url: '/api/system/antigravity/path', method: 'GET' },
+  'detect_antigravity_installations': { url: '/api/system/antigravity/installations', method: 'GET' },
   'get_antigravity_args': { url: '/api/system/antigravity/args', method: 'GET' },
 
   // Cloudflared
@@ -127,6 +193,18 @@ const COMMAND_MAPPING: Record<string, { url: string; method: 'GET' | 'POST' | 'D
   'clear_antigravity_cache': { url: '/api/system/cache/clear', method: 'POST' },
   'get_antigravity_cache_paths': { url: '/api/system/cache/paths', method: 'GET' },
   'clear_log_cache': { url: '/api/system/logs/clear-cache', method: 'POST' },
+  'query_logs': { url: '/api/system/logs/query', method: 'POST' },
+  'verify_data_integrity': { url: '/api/system/integrity', method: 'GET' },
+  'apply_integrity_repair': { url: '/api/system/integrity/repair', method: 'POST' },
+  'get_network_status': { url: '/api/system/network', method: 'GET' },
+  'set_offline_mode': { url: '/api/system/offline', method: 'POST' },
+  'get_clock_skew': { url: '/api/system/clock-skew', method: 'GET' },
+  'scheduler_status': { url: '/api/system/scheduler', method: 'GET' },
+  'set_scheduler_job_enabled': { url: '/api/system/scheduler/jobs/:jobId', method: 'POST' },
+  'get_onboarding_state': { url: '/api/onboarding', method: 'GET' },
+  'complete_onboarding_step': { url: '/api/onboarding/steps/:step', method: 'POST' },
+  'list_available_languages': { url: '/api/system/languages', method: 'GET' },
+  'get_locale_messages': { url: '/api/system/languages/:language', method: 'GET' },
 
   // Security / IP Management
   'get_ip_access_logs': { url: '/api/security/logs', method: 'GET' },
@@ -148,6 +226,8 @@ const COMMAND_MAPPING: Record<string, { url: string; method: 'GET' | 'POST' | 'D
   // User Tokens
   'list_user_tokens': { url: '/api/user-tokens', method: 'GET' },
   'get_user_token_summary': { url: '/api/user-tokens/summary', method: 'GET' },
+  'get_billing_report': { url: '/api/user-tokens/billing', method: 'GET' },
+  'export_billing_report': { url: '/api/user-tokens/billing/export', method: 'GET' },
   'create_user_token': { url: '/api/user-tokens', method: 'POST' },
   'renew_user_token': { url: '/api/user-tokens/:id/renew', method: 'POST' },
   'delete_user_token': { url: '/api/user-tokens/:id', method: 'DELETE' },
@@ -161,25 +241,18 @@ const COMMAND_MAPPING: Record<string, { url: string; method: 'GET' | 'POST' | 'D
   'get_account_proxy_binding': { url: '/api/proxy/pool/binding/:accountId', method: 'GET' },
 };
 
-export async function request<T>(cmd: string, args?: any): Promise<T> {
-  // 1. Tauri 环境：直接使用 invoke ...
-  if (isTauri) {
-    try {
-      const { invoke } = await import('@tauri-apps/api/core');
-      return await invoke<T>(cmd, args);
-    } catch (error) {
-      console.error(`Tauri Invoke Error [${cmd}]:`, error);
-      throw error;
-    }
-  }
+// 远程管理模式：由 useConfigStore 在加载/保存配置时同步
+let remoteModeEnabled = false;
 
-  // 2. Web 环境：映射到 HTTP API
-  const mapping = COMMAND_MAPPING[cmd];
-  if (!mapping) {
-    console.error(`Command [${cmd}] is not yet mapped for Web mode. Failing.`);
-    throw new Error(`Command [${cmd}] not supported in Web mode.`);
-  }
+export function setRemoteModeEnabled(enabled: boolean) {
+  remoteModeEnabled = enabled;
+}
 
+// 远程模式下仍在本地执行的命令 (远程连接配置本身保存在本地)
+const LOCAL_ONLY_COMMANDS = new Set(['load_config', 'save_config']);
+
+// 根据映射解析请求路径（含路径/查询参数）和请求体
+function buildHttpRequest(mapping: { url: string; method: string }, args?: any): { url: string; body?: any } {
   let url = mapping.url;
   // [FIX] 创建 args 副本，用于移除已使用的路径参数
   let bodyArgs = args ? { ...args } : undefined;
@@ -198,6 +271,56 @@ export async function request<T>(cmd: string, args?: any): Promise<T> {
     });
   }
 
+  if ((mapping.method === 'GET' || mapping.method === 'DELETE') && args) {
+    const params = new URLSearchParams();
+    Object.entries(args).forEach(([key, value]) => {
+      // [FIX] 跳过已用于路径替换的参数
+      if (url.includes(encodeURIComponent(String(value)))) return;
+      if (value !== undefined && value !== null) {
+        params.append(key, String(value));
+      }
+    });
+    const qs = params.toString();
+    if (qs) url += `?${qs}`;
+    return { url };
+  }
+
+  if ((mapping.method === 'POST' || mapping.method === 'PATCH') && bodyArgs) {
+    // [FIX] 如果有 request 包装，提取其内容作为 body
+    return { url, body: bodyArgs.request !== undefined ? bodyArgs.request : bodyArgs };
+  }
+
+  return { url };
+}
+
+export async function request<T>(cmd: string, args?: any): Promise<T> {
+  // 1. Tauri 环境：直接使用 invoke ...
+  if (isTauri) {
+    try {
+      const { invoke } = await import('@tauri-apps/api/core');
+      const remoteMapping = COMMAND_MAPPING[cmd];
+      // 远程模式：已映射的管理命令通过后端 HTTP 适配层转发到远程实例
+      if (remoteModeEnabled && remoteMapping && !LOCAL_ONLY_COMMANDS.has(cmd)) {
+        const { url, body } = buildHttpRequest(remoteMapping, args);
+        return await invoke<T>('remote_request', { method: remoteMapping.method, path: url, body });
+      }
+      return await invoke<T>(cmd, args);
+    } catch (error) {
+      console.error(`Tauri Invoke Error [${cmd}]:`, error);
+      // 统一为带错误码的本地化错误
+      throw toBackendError(error);
+    }
+  }
+
+  // 2. Web 环境：映射到 HTTP API
+  const mapping = COMMAND_MAPPING[cmd];
+  if (!mapping) {
+    console.error(`Command [${cmd}] is not yet mapped for Web mode. Failing.`);
+    throw new Error(`Command [${cmd}] not supported in Web mode.`);
+  }
+
+  const { url, body } = buildHttpRequest(mapping, args);
+
   const apiKey = typeof window !== 'undefined' ? sessionStorage.getItem('abv_admin_api_key') : null;
 
   const options: RequestInit = {
@@ -211,20 +334,7 @@ export async function request<T>(cmd: string, args?: any): Promise<T> {
     },
   };
 
-  if ((mapping.method === 'GET' || mapping.method === 'DELETE') && args) {
-    const params = new URLSearchParams();
-    Object.entries(args).forEach(([key, value]) => {
-      // [FIX] 跳过已用于路径替换的参数
-      if (url.includes(encodeURIComponent(String(value)))) return;
-      if (value !== undefined && value !== null) {
-        params.append(key, String(value));
-      }
-    });
-    const qs = params.toString();
-    if (qs) url += `?${qs}`;
-  } else if ((mapping.method === 'POST' || mapping.method === 'PATCH') && bodyArgs) {
-    // [FIX] 如果有 request 包装，提取其内容作为 body
-    const body = bodyArgs.request !== undefined ? bodyArgs.request : bodyArgs;
+  if (body !== undefined) {
     options.body = JSON.stringify(body);
   }
 
@@ -262,6 +372,6 @@ export async function request<T>(cmd: string, args?: any): Promise<T> {
     }
   } catch (error) {
     console.error(`Web Fetch Error [${cmd}]:`, error);
-    throw error;
+    throw toBackendError(error);
   }
 }